@@ -0,0 +1,221 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::Bytes;
+use http::{Method, Uri, header::HOST};
+use rustfs_utils::crypto::hex_sha256;
+use s3s::Body;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::error::{Error, Result};
+
+/// Path prefix of the admin API.
+pub const ADMIN_PREFIX: &str = "/rustfs/admin";
+
+const DEFAULT_REGION: &str = "us-east-1";
+
+/// Client of the admin API of a RustFS deployment.
+///
+/// Cloning is cheap, clones share the connection pool.
+#[derive(Debug, Clone)]
+pub struct AdminClient {
+    endpoint: String,
+    access_key: String,
+    secret_key: String,
+    session_token: String,
+    region: String,
+    http: reqwest::Client,
+}
+
+impl AdminClient {
+    /// A client of `endpoint`, `http(s)://host[:port]`, signing requests with an access key.
+    pub fn new(endpoint: impl Into<String>, access_key: impl Into<String>, secret_key: impl Into<String>) -> Result<Self> {
+        let endpoint = endpoint.into().trim_end_matches('/').to_string();
+        let Ok(uri) = endpoint.parse::<Uri>() else {
+            return Err(Error::InvalidEndpoint(endpoint));
+        };
+        let valid_scheme = matches!(uri.scheme_str(), Some("http") | Some("https"));
+        if !valid_scheme || uri.authority().is_none() || !matches!(uri.path(), "" | "/") || uri.query().is_some() {
+            return Err(Error::InvalidEndpoint(endpoint));
+        }
+
+        Ok(Self {
+            endpoint,
+            access_key: access_key.into(),
+            secret_key: secret_key.into(),
+            session_token: String::new(),
+            region: DEFAULT_REGION.to_string(),
+            http: reqwest::Client::new(),
+        })
+    }
+
+    /// Signs requests with the session token of temporary credentials.
+    pub fn with_session_token(mut self, session_token: impl Into<String>) -> Self {
+        self.session_token = session_token.into();
+        self
+    }
+
+    /// Signs requests for `region` instead of `us-east-1`.
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.region = region.into();
+        self
+    }
+
+    /// Sends requests with `http`, for custom timeouts, proxies or TLS roots.
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// The URL of the admin API `path`, such as `/tier`, with the query parameters whose value is not empty.
+    pub(crate) fn url(&self, path: &str, query: &[(&str, String)]) -> String {
+        let mut url = format!("{}{ADMIN_PREFIX}/v3{path}", self.endpoint);
+        let query: Vec<String> = query
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(key, value)| format!("{key}={}", urlencoding::encode(value)))
+            .collect();
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query.join("&"));
+        }
+        url
+    }
+
+    /// A signed request of `url`, the body is sent separately.
+    pub(crate) fn sign(&self, method: Method, url: &str, body: &[u8]) -> Result<http::Request<Body>> {
+        let Ok(uri) = url.parse::<Uri>() else {
+            return Err(Error::InvalidEndpoint(url.to_string()));
+        };
+        let host = uri.authority().map(|authority| authority.to_string()).unwrap_or_default();
+        let request = http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(HOST, host)
+            .header("X-Amz-Content-Sha256", hex_sha256(body, |hash| hash.to_string()))
+            .body(Body::empty())?;
+
+        Ok(rustfs_signer::sign_v4(
+            request,
+            body.len() as i64,
+            &self.access_key,
+            &self.secret_key,
+            &self.session_token,
+            &self.region,
+        ))
+    }
+
+    /// Sends a request, returning the body of a successful response.
+    pub(crate) async fn send(&self, method: Method, path: &str, query: &[(&str, String)], body: Vec<u8>) -> Result<Bytes> {
+        let signed = self.sign(method, &self.url(path, query), &body)?;
+
+        let mut request = self.http.request(signed.method().clone(), signed.uri().to_string());
+        for (name, value) in signed.headers() {
+            request = request.header(name, value);
+        }
+        let response = request.body(body).send().await?;
+
+        let status = response.status();
+        let data = response.bytes().await?;
+        if !status.is_success() {
+            return Err(Error::from_response(status, &data));
+        }
+        Ok(data)
+    }
+
+    /// Sends a request without a body, decoding the JSON response.
+    pub(crate) async fn json<T: DeserializeOwned>(&self, method: Method, path: &str, query: &[(&str, String)]) -> Result<T> {
+        let data = self.send(method, path, query, Vec::new()).await?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Sends a JSON body, decoding the JSON response.
+    pub(crate) async fn send_json<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: &B,
+    ) -> Result<T> {
+        let data = self.send(method, path, query, serde_json::to_vec(body)?).await?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+}
+
+/// A path segment, percent-encoded.
+pub(crate) fn segment(value: &str) -> String {
+    urlencoding::encode(value).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert!(AdminClient::new("http://127.0.0.1:9000", "ak", "sk").is_ok());
+        assert_eq!(
+            AdminClient::new("https://rustfs.example.com/", "ak", "sk")
+                .unwrap()
+                .endpoint(),
+            "https://rustfs.example.com"
+        );
+        assert!(AdminClient::new("127.0.0.1:9000", "ak", "sk").is_err());
+        assert!(AdminClient::new("ftp://127.0.0.1", "ak", "sk").is_err());
+        assert!(AdminClient::new("http://127.0.0.1:9000/minio", "ak", "sk").is_err());
+    }
+
+    #[test]
+    fn test_url() {
+        let client = AdminClient::new("http://127.0.0.1:9000", "ak", "sk").unwrap();
+        assert_eq!(client.url("/tier", &[]), "http://127.0.0.1:9000/rustfs/admin/v3/tier");
+        assert_eq!(
+            client.url(
+                "/event-backfill",
+                &[
+                    ("bucket", "photos".to_string()),
+                    ("prefix", String::new()),
+                    ("since", "2024-05-01T00:00:00Z".to_string())
+                ]
+            ),
+            "http://127.0.0.1:9000/rustfs/admin/v3/event-backfill?bucket=photos&since=2024-05-01T00%3A00%3A00Z"
+        );
+        assert_eq!(segment("2024/a b"), "2024%2Fa%20b");
+    }
+
+    #[test]
+    fn test_sign() {
+        let client = AdminClient::new("http://127.0.0.1:9000", "ak", "sk")
+            .unwrap()
+            .with_session_token("token");
+        let request = client
+            .sign(Method::PUT, &client.url("/add-user", &[("accessKey", "alice".to_string())]), b"{}")
+            .unwrap();
+
+        let headers = request.headers();
+        assert_eq!(headers[HOST], "127.0.0.1:9000");
+        assert_eq!(headers["X-Amz-Security-Token"], "token");
+        assert_eq!(
+            headers["X-Amz-Content-Sha256"],
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+        let authorization = headers["Authorization"].to_str().unwrap();
+        assert!(authorization.starts_with("AWS4-HMAC-SHA256 Credential=ak/"));
+        assert!(authorization.contains("/us-east-1/s3/aws4_request"));
+    }
+}
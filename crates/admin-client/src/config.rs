@@ -0,0 +1,112 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backups of the server configuration and notification targets.
+
+use http::Method;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::client::segment;
+use crate::{AdminClient, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBackupInfo {
+    pub id: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    pub size: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    /// Backup that was restored.
+    pub backup: String,
+    /// Backup of the state before the restore.
+    pub previous: Option<String>,
+    pub restored: usize,
+    /// Objects that could not be written back.
+    pub failed: Vec<String>,
+    /// Buckets whose metadata was restored.
+    pub buckets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationEndpoint {
+    pub account_id: String,
+    pub service: String,
+    pub status: String,
+}
+
+#[derive(Deserialize)]
+struct NotificationEndpointsResponse {
+    notification_endpoints: Vec<NotificationEndpoint>,
+}
+
+#[derive(Serialize)]
+struct KeyValue<'a> {
+    key: &'a str,
+    value: &'a str,
+}
+
+#[derive(Serialize)]
+struct NotificationTargetBody<'a> {
+    key_values: Vec<KeyValue<'a>>,
+}
+
+fn target_path(target_type: &str, target_name: &str) -> String {
+    format!("/target/{}/{}", segment(target_type), segment(target_name))
+}
+
+impl AdminClient {
+    /// The configuration backups, oldest first.
+    pub async fn list_config_backups(&self) -> Result<Vec<ConfigBackupInfo>> {
+        self.json(Method::GET, "/config-backups", &[]).await
+    }
+
+    pub async fn create_config_backup(&self) -> Result<ConfigBackupInfo> {
+        self.json(Method::POST, "/config-backups", &[]).await
+    }
+
+    /// Restores the last backup taken at or before `at`.
+    pub async fn restore_config_backup(&self, at: OffsetDateTime) -> Result<RestoreReport> {
+        let at = at.format(&Rfc3339).unwrap_or_default();
+        self.json(Method::POST, "/config-backups/restore", &[("time", at)]).await
+    }
+
+    /// The active notification targets.
+    pub async fn list_notification_targets(&self) -> Result<Vec<NotificationEndpoint>> {
+        let response: NotificationEndpointsResponse = self.json(Method::GET, "/target/list", &[]).await?;
+        Ok(response.notification_endpoints)
+    }
+
+    /// Creates or replaces a notification target, such as `notify_webhook` `1`, from its settings.
+    pub async fn set_notification_target(&self, target_type: &str, target_name: &str, key_values: &[(&str, &str)]) -> Result<()> {
+        let body = NotificationTargetBody {
+            key_values: key_values.iter().map(|&(key, value)| KeyValue { key, value }).collect(),
+        };
+        self.send(Method::PUT, &target_path(target_type, target_name), &[], serde_json::to_vec(&body)?)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_notification_target(&self, target_type: &str, target_name: &str) -> Result<()> {
+        let path = format!("{}/reset", target_path(target_type, target_name));
+        self.send(Method::DELETE, &path, &[], Vec::new()).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::StatusCode;
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("invalid endpoint '{0}', expected http(s)://host[:port]")]
+    InvalidEndpoint(String),
+
+    #[error("build request failed: {0}")]
+    Request(#[from] http::Error),
+
+    #[error("send request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The server answered with an error status.
+    #[error("admin API returned {status}: {code} {message}")]
+    Api {
+        status: StatusCode,
+        code: String,
+        message: String,
+    },
+}
+
+impl Error {
+    /// The error code of the server, for example `NoSuchKey`, if the server answered with an error.
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Error::Api { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Builds the error of a response, from the S3 style XML error document of its body.
+    pub(crate) fn from_response(status: StatusCode, body: &[u8]) -> Self {
+        let body = String::from_utf8_lossy(body);
+        let element = |name: &str| {
+            let start = body.find(&format!("<{name}>"))? + name.len() + 2;
+            let len = body[start..].find(&format!("</{name}>"))?;
+            Some(body[start..start + len].to_string())
+        };
+
+        let code = element("Code").unwrap_or_else(|| status.canonical_reason().unwrap_or_default().to_string());
+        let message = element("Message").unwrap_or_else(|| body.trim().to_string());
+        Error::Api { status, code, message }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_response() {
+        let body =
+            b"<?xml version=\"1.0\" encoding=\"UTF-8\"?><Error><Code>NoSuchKey</Code><Message>no job abc</Message></Error>";
+        let err = Error::from_response(StatusCode::NOT_FOUND, body);
+        assert_eq!(err.code(), Some("NoSuchKey"));
+        assert_eq!(err.to_string(), "admin API returned 404 Not Found: NoSuchKey no job abc");
+
+        let err = Error::from_response(StatusCode::BAD_GATEWAY, b"upstream down");
+        assert_eq!(err.code(), Some("Bad Gateway"));
+        assert!(err.to_string().ends_with("upstream down"));
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Healing of buckets and objects.
+
+use bytes::Bytes;
+use http::Method;
+use rustfs_common::heal_channel::HealOpts;
+
+use crate::client::segment;
+use crate::{AdminClient, Result};
+
+fn heal_path(bucket: &str, prefix: &str) -> String {
+    format!("/heal/{}/{}", segment(bucket), segment(prefix))
+}
+
+impl AdminClient {
+    /// Starts healing the objects of `bucket` under `prefix`. With `force_start`, a heal already
+    /// running on the same path is replaced.
+    pub async fn heal_start(&self, bucket: &str, prefix: &str, opts: &HealOpts, force_start: bool) -> Result<()> {
+        let query = [("forceStart", if force_start { "true".to_string() } else { String::new() })];
+        self.send(Method::POST, &heal_path(bucket, prefix), &query, serde_json::to_vec(opts)?)
+            .await?;
+        Ok(())
+    }
+
+    /// The status of the heal started with `client_token`, as reported by the server.
+    pub async fn heal_status(&self, bucket: &str, prefix: &str, client_token: &str) -> Result<Bytes> {
+        let query = [("clientToken", client_token.to_string())];
+        self.send(Method::POST, &heal_path(bucket, prefix), &query, Vec::new()).await
+    }
+
+    /// Stops the heal running on the objects of `bucket` under `prefix`.
+    pub async fn heal_stop(&self, bucket: &str, prefix: &str) -> Result<()> {
+        let query = [("forceStop", "true".to_string())];
+        // Requests without a client token carry heal options, even to stop a heal
+        let body = serde_json::to_vec(&HealOpts::default())?;
+        self.send(Method::POST, &heal_path(bucket, prefix), &query, body).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Users, groups, policies and service accounts.
+
+use std::collections::HashMap;
+
+use http::Method;
+use rustfs_madmin::group::{GroupAddRemove, GroupDesc, GroupStatus};
+use rustfs_madmin::user::{
+    AccountStatus, AddOrUpdateUserReq, AddServiceAccountReq, ListServiceAccountsResp, ServiceAccountInfo,
+    UpdateServiceAccountReq, UserInfo,
+};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::{AdminClient, Result};
+
+/// Credentials of a new service account.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Credentials {
+    pub access_key: String,
+    pub secret_key: String,
+    #[serde(default)]
+    pub session_token: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub expiration: Option<OffsetDateTime>,
+}
+
+#[derive(Deserialize)]
+struct AddServiceAccountResp {
+    credentials: Credentials,
+}
+
+fn access_key_query(access_key: &str) -> [(&'static str, String); 1] {
+    [("accessKey", access_key.to_string())]
+}
+
+impl AdminClient {
+    /// The users, by access key.
+    pub async fn list_users(&self) -> Result<HashMap<String, UserInfo>> {
+        self.json(Method::GET, "/list-users", &[]).await
+    }
+
+    pub async fn user_info(&self, access_key: &str) -> Result<UserInfo> {
+        self.json(Method::GET, "/user-info", &access_key_query(access_key)).await
+    }
+
+    /// Creates a user, or updates the secret key, policy and status of an existing one.
+    pub async fn add_user(&self, access_key: &str, user: &AddOrUpdateUserReq) -> Result<()> {
+        let body = serde_json::to_vec(user)?;
+        self.send(Method::PUT, "/add-user", &access_key_query(access_key), body)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_user(&self, access_key: &str) -> Result<()> {
+        self.send(Method::DELETE, "/remove-user", &access_key_query(access_key), Vec::new())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_user_status(&self, access_key: &str, status: AccountStatus) -> Result<()> {
+        let query = [("accessKey", access_key.to_string()), ("status", status.as_ref().to_string())];
+        self.send(Method::PUT, "/set-user-status", &query, Vec::new()).await?;
+        Ok(())
+    }
+
+    pub async fn list_groups(&self) -> Result<Vec<String>> {
+        self.json(Method::GET, "/groups", &[]).await
+    }
+
+    pub async fn group_info(&self, group: &str) -> Result<GroupDesc> {
+        self.json(Method::GET, "/group", &[("group", group.to_string())]).await
+    }
+
+    /// Adds members to a group, creating it, or removes members from it.
+    pub async fn update_group_members(&self, update: &GroupAddRemove) -> Result<()> {
+        let body = serde_json::to_vec(update)?;
+        self.send(Method::PUT, "/update-group-members", &[], body).await?;
+        Ok(())
+    }
+
+    pub async fn set_group_status(&self, group: &str, status: GroupStatus) -> Result<()> {
+        let status = match status {
+            GroupStatus::Enabled => "enabled",
+            GroupStatus::Disabled => "disabled",
+        };
+        let query = [("group", group.to_string()), ("status", status.to_string())];
+        self.send(Method::PUT, "/set-group-status", &query, Vec::new()).await?;
+        Ok(())
+    }
+
+    /// Removes a group. Without `force`, only a group without members can be removed.
+    pub async fn remove_group(&self, group: &str, force: bool) -> Result<()> {
+        let query = [
+            ("group", group.to_string()),
+            ("force", if force { "true".to_string() } else { String::new() }),
+        ];
+        self.send(Method::DELETE, "/group", &query, Vec::new()).await?;
+        Ok(())
+    }
+
+    /// The canned policies, by name.
+    pub async fn list_canned_policies(&self) -> Result<HashMap<String, serde_json::Value>> {
+        self.json(Method::GET, "/list-canned-policies", &[]).await
+    }
+
+    pub async fn canned_policy_info(&self, name: &str) -> Result<serde_json::Value> {
+        self.json(Method::GET, "/info-canned-policy", &[("name", name.to_string())])
+            .await
+    }
+
+    /// Creates or replaces a canned policy from its JSON policy document.
+    pub async fn add_canned_policy(&self, name: &str, policy: &serde_json::Value) -> Result<()> {
+        let body = serde_json::to_vec(policy)?;
+        self.send(Method::PUT, "/add-canned-policy", &[("name", name.to_string())], body)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_canned_policy(&self, name: &str) -> Result<()> {
+        self.send(Method::DELETE, "/remove-canned-policy", &[("name", name.to_string())], Vec::new())
+            .await?;
+        Ok(())
+    }
+
+    /// Attaches policies, a comma separated list of names, to a user or a group.
+    pub async fn set_policy(&self, policy_name: &str, user_or_group: &str, is_group: bool) -> Result<()> {
+        let query = [
+            ("policyName", policy_name.to_string()),
+            ("userOrGroup", user_or_group.to_string()),
+            ("isGroup", is_group.to_string()),
+        ];
+        self.send(Method::PUT, "/set-user-or-group-policy", &query, Vec::new())
+            .await?;
+        Ok(())
+    }
+
+    /// The service accounts of `user`, or of the requesting user when empty.
+    pub async fn list_service_accounts(&self, user: &str) -> Result<Vec<ServiceAccountInfo>> {
+        let response: ListServiceAccountsResp = self
+            .json(Method::GET, "/list-service-accounts", &[("user", user.to_string())])
+            .await?;
+        Ok(response.accounts)
+    }
+
+    pub async fn add_service_account(&self, account: &AddServiceAccountReq) -> Result<Credentials> {
+        let response: AddServiceAccountResp = self.send_json(Method::PUT, "/add-service-accounts", &[], account).await?;
+        Ok(response.credentials)
+    }
+
+    pub async fn update_service_account(&self, access_key: &str, update: &UpdateServiceAccountReq) -> Result<()> {
+        let body = serde_json::to_vec(update)?;
+        self.send(Method::POST, "/update-service-account", &access_key_query(access_key), body)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_service_account(&self, access_key: &str) -> Result<()> {
+        self.send(Method::DELETE, "/delete-service-accounts", &access_key_query(access_key), Vec::new())
+            .await?;
+        Ok(())
+    }
+}
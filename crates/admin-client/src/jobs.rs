@@ -0,0 +1,179 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Background jobs: event backfills, KMS key rotations, bucket backups, rebalancing and pool
+//! decommissioning.
+//!
+//! Job reports are returned as JSON values, their layout is owned by the server.
+
+use http::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::{AdminClient, Result};
+
+/// Schedule of incremental backups of a bucket to a remote target.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSchedule {
+    pub bucket: String,
+    pub prefix: String,
+    /// ARN of the remote target of the bucket the objects are copied to
+    pub arn: String,
+    /// Cron expression of the runs, in UTC
+    pub cron: String,
+    pub enabled: bool,
+}
+
+/// Objects whose data keys are rewrapped by a key rotation job.
+#[derive(Debug, Clone, Default)]
+pub struct KeyRotation {
+    /// Key the data keys are wrapped with.
+    pub key_id: String,
+    /// Only data keys wrapped with this version of the key.
+    pub source_version: Option<u32>,
+    /// Key to rewrap the data keys with, the current version of `key_id` when empty.
+    pub target_key_id: String,
+    pub bucket: String,
+    pub prefix: String,
+}
+
+#[derive(Deserialize)]
+struct RebalanceResp {
+    id: String,
+}
+
+fn rfc3339(t: Option<OffsetDateTime>) -> String {
+    t.and_then(|t| t.format(&Rfc3339).ok()).unwrap_or_default()
+}
+
+fn id_query(id: &str) -> [(&'static str, String); 1] {
+    [("id", id.to_string())]
+}
+
+impl AdminClient {
+    /// Sends `ObjectCreated` events for the objects of `bucket` under `prefix` modified in
+    /// `[since, until)`.
+    pub async fn start_event_backfill(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        since: Option<OffsetDateTime>,
+        until: Option<OffsetDateTime>,
+    ) -> Result<Value> {
+        let query = [
+            ("bucket", bucket.to_string()),
+            ("prefix", prefix.to_string()),
+            ("since", rfc3339(since)),
+            ("until", rfc3339(until)),
+        ];
+        self.json(Method::POST, "/event-backfill", &query).await
+    }
+
+    /// The report of a backfill job, or the list of jobs when `id` is empty.
+    pub async fn event_backfill_status(&self, id: &str) -> Result<Value> {
+        self.json(Method::GET, "/event-backfill", &id_query(id)).await
+    }
+
+    pub async fn cancel_event_backfill(&self, id: &str) -> Result<()> {
+        self.send(Method::DELETE, "/event-backfill", &id_query(id), Vec::new())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn start_key_rotation(&self, rotation: &KeyRotation) -> Result<Value> {
+        let query = [
+            ("keyId", rotation.key_id.clone()),
+            ("sourceVersion", rotation.source_version.map(|v| v.to_string()).unwrap_or_default()),
+            ("targetKeyId", rotation.target_key_id.clone()),
+            ("bucket", rotation.bucket.clone()),
+            ("prefix", rotation.prefix.clone()),
+        ];
+        self.json(Method::POST, "/kms/key-rotation", &query).await
+    }
+
+    /// The report of a key rotation job, or the list of jobs when `id` is empty.
+    pub async fn key_rotation_status(&self, id: &str) -> Result<Value> {
+        self.json(Method::GET, "/kms/key-rotation", &id_query(id)).await
+    }
+
+    pub async fn cancel_key_rotation(&self, id: &str) -> Result<()> {
+        self.send(Method::DELETE, "/kms/key-rotation", &id_query(id), Vec::new())
+            .await?;
+        Ok(())
+    }
+
+    /// Creates a bucket backup schedule, or replaces the schedule `id` when it is not empty.
+    pub async fn set_bucket_backup(&self, id: &str, schedule: &BackupSchedule) -> Result<Value> {
+        self.send_json(Method::PUT, "/bucket-backup", &id_query(id), schedule).await
+    }
+
+    /// A schedule with the history of its runs, or the list of schedules when `id` is empty.
+    pub async fn bucket_backup_status(&self, id: &str) -> Result<Value> {
+        self.json(Method::GET, "/bucket-backup", &id_query(id)).await
+    }
+
+    pub async fn remove_bucket_backup(&self, id: &str) -> Result<()> {
+        self.send(Method::DELETE, "/bucket-backup", &id_query(id), Vec::new()).await?;
+        Ok(())
+    }
+
+    /// Runs a backup schedule now.
+    pub async fn run_bucket_backup(&self, id: &str) -> Result<Value> {
+        self.json(Method::POST, "/bucket-backup/run", &id_query(id)).await
+    }
+
+    /// Cancels the run of a backup schedule on the node receiving the request.
+    pub async fn cancel_bucket_backup_run(&self, id: &str) -> Result<()> {
+        self.send(Method::DELETE, "/bucket-backup/run", &id_query(id), Vec::new())
+            .await?;
+        Ok(())
+    }
+
+    /// Starts rebalancing the pools, returning the id of the rebalance.
+    pub async fn start_rebalance(&self) -> Result<String> {
+        let response: RebalanceResp = self.json(Method::POST, "/rebalance/start", &[]).await?;
+        Ok(response.id)
+    }
+
+    pub async fn rebalance_status(&self) -> Result<Value> {
+        self.json(Method::GET, "/rebalance/status", &[]).await
+    }
+
+    pub async fn stop_rebalance(&self) -> Result<()> {
+        self.send(Method::POST, "/rebalance/stop", &[], Vec::new()).await?;
+        Ok(())
+    }
+
+    /// The decommissioning status of every pool.
+    pub async fn list_pools(&self) -> Result<Value> {
+        self.json(Method::GET, "/pools/list", &[]).await
+    }
+
+    /// Starts decommissioning pools, given by their index.
+    pub async fn start_decommission(&self, pools: &[usize]) -> Result<()> {
+        let pools: Vec<String> = pools.iter().map(|idx| idx.to_string()).collect();
+        let query = [("pool", pools.join(",")), ("by-id", "true".to_string())];
+        self.send(Method::POST, "/pools/decommission", &query, Vec::new()).await?;
+        Ok(())
+    }
+
+    pub async fn cancel_decommission(&self, pool: usize) -> Result<()> {
+        let query = [("pool", pool.to_string()), ("by-id", "true".to_string())];
+        self.send(Method::POST, "/pools/cancel", &query, Vec::new()).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed async client for the RustFS admin API.
+//!
+//! Every request is signed with AWS Signature Version 4, like the S3 API. The operations are
+//! grouped by area, healing, configuration, IAM, tiering, background jobs and request traces, as
+//! methods of [`AdminClient`].
+//!
+//! ```no_run
+//! # async fn run() -> rustfs_admin_client::Result<()> {
+//! let client = rustfs_admin_client::AdminClient::new("http://127.0.0.1:9000", "rustfsadmin", "rustfsadmin")?;
+//! for (name, user) in client.list_users().await? {
+//!     println!("{name}: {:?}", user.status);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod client;
+mod error;
+
+pub mod config;
+pub mod heal;
+pub mod iam;
+pub mod jobs;
+pub mod tier;
+pub mod trace;
+
+pub use client::{ADMIN_PREFIX, AdminClient};
+pub use error::{Error, Result};
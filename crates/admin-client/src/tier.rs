@@ -0,0 +1,72 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Remote tiers objects transition to.
+//!
+//! The tier configurations are generic, callers decode them into the `TierConfig` of
+//! `rustfs-ecstore` or into `serde_json::Value`.
+
+use http::Method;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::client::segment;
+use crate::{AdminClient, Result};
+
+/// New credentials of a tier.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TierCredentials {
+    pub access_key: String,
+    pub secret_key: String,
+    pub aws_role: bool,
+    pub aws_role_web_identity_token_file: String,
+    pub aws_role_arn: String,
+}
+
+fn force_query(force: bool) -> [(&'static str, String); 1] {
+    [("force", if force { "true".to_string() } else { String::new() })]
+}
+
+impl AdminClient {
+    pub async fn list_tiers<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+        self.json(Method::GET, "/tier", &[]).await
+    }
+
+    /// The configuration of a tier, `None` if there is no tier of this name.
+    pub async fn tier_info<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>> {
+        self.json(Method::GET, "/tier-stats", &[("tier", name.to_string())]).await
+    }
+
+    /// Adds a tier. With `force`, the tier is added even if its remote is already in use.
+    pub async fn add_tier<T: Serialize + ?Sized>(&self, config: &T, force: bool) -> Result<()> {
+        let body = serde_json::to_vec(config)?;
+        self.send(Method::PUT, "/tier", &force_query(force), body).await?;
+        Ok(())
+    }
+
+    /// Replaces the credentials of a tier.
+    pub async fn edit_tier(&self, name: &str, creds: &TierCredentials) -> Result<()> {
+        let body = serde_json::to_vec(creds)?;
+        self.send(Method::POST, &format!("/tier/{}", segment(name)), &[], body)
+            .await?;
+        Ok(())
+    }
+
+    /// Removes a tier. With `force`, the tier is removed without checking whether it is in use.
+    pub async fn remove_tier(&self, name: &str, force: bool) -> Result<()> {
+        self.send(Method::DELETE, &format!("/tier/{}", segment(name)), &force_query(force), Vec::new())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traces of recent requests.
+
+use http::Method;
+use serde_json::Value;
+
+use crate::{AdminClient, Result};
+
+impl AdminClient {
+    /// The record of a recent request, by its `x-amz-request-id`, with its audit entry and I/O.
+    /// Only the node that served the request knows it.
+    pub async fn request_trace(&self, request_id: &str) -> Result<Value> {
+        self.json(Method::GET, "/request", &[("id", request_id.to_string())]).await
+    }
+}
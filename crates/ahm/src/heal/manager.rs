@@ -18,9 +18,11 @@ use crate::heal::{
     task::{HealOptions, HealPriority, HealRequest, HealTask, HealTaskStatus, HealType},
 };
 use crate::{Error, Result};
+use rustfs_common::heal_channel::record_erasure_set_heal;
 use rustfs_ecstore::disk::DiskAPI;
 use rustfs_ecstore::disk::error::DiskError;
 use rustfs_ecstore::global::GLOBAL_LOCAL_DISK_MAP;
+use rustfs_ecstore::maintenance::GLOBAL_MAINTENANCE_SYS;
 use std::{
     collections::{BinaryHeap, HashMap, HashSet},
     sync::Arc,
@@ -164,6 +166,17 @@ impl PriorityHealQueue {
         }
     }
 
+    /// Queued object heals per bucket
+    fn object_heals_by_bucket(&self) -> HashMap<String, u64> {
+        let mut counts = HashMap::new();
+        for item in &self.heap {
+            if let Some(bucket) = item.request.heal_type.object_bucket() {
+                *counts.entry(bucket.to_string()).or_default() += 1;
+            }
+        }
+        counts
+    }
+
     /// Check if a request with the same key already exists in the queue
     #[allow(dead_code)]
     fn contains_key(&self, request: &HealRequest) -> bool {
@@ -411,6 +424,20 @@ impl HealManager {
         active_heals.len()
     }
 
+    /// Object heals queued or running, per bucket
+    pub async fn get_heal_backlog_by_bucket(&self) -> HashMap<String, u64> {
+        // Same lock order as the scheduler: queue first, then active_heals
+        let queue = self.heal_queue.lock().await;
+        let mut backlog = queue.object_heals_by_bucket();
+        let active_heals = self.active_heals.lock().await;
+        for task in active_heals.values() {
+            if let Some(bucket) = task.heal_type.object_bucket() {
+                *backlog.entry(bucket.to_string()).or_default() += 1;
+            }
+        }
+        backlog
+    }
+
     /// Get queue length
     pub async fn get_queue_length(&self) -> usize {
         let queue = self.heal_queue.lock().await;
@@ -463,6 +490,10 @@ impl HealManager {
                         break;
                     }
                     _ = interval.tick() => {
+                        if GLOBAL_MAINTENANCE_SYS.is_local_node_in_maintenance() {
+                            continue;
+                        }
+
                         // Build list of endpoints that need healing
                         let mut endpoints = Vec::new();
                         for (_, disk_opt) in GLOBAL_LOCAL_DISK_MAP.read().await.iter() {
@@ -603,6 +634,9 @@ impl HealManager {
                             error!("Heal task failed: {} - {}", task_id, e);
                         }
                     }
+                    if let HealType::ErasureSet { set_disk_id, .. } = &task.heal_type {
+                        record_erasure_set_heal(set_disk_id, result.is_ok());
+                    }
                     let mut active_heals_guard = active_heals_clone.lock().await;
                     if let Some(completed_task) = active_heals_guard.remove(&task_id) {
                         // update statistics
@@ -610,6 +644,9 @@ impl HealManager {
                         match completed_task.get_status().await {
                             HealTaskStatus::Completed => {
                                 stats.update_task_completion(true);
+                                if let Some(bucket) = completed_task.heal_type.object_bucket() {
+                                    stats.add_bucket_object_healed(bucket);
+                                }
                             }
                             _ => {
                                 stats.update_task_completion(false);
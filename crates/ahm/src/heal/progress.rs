@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::SystemTime;
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -94,6 +95,9 @@ pub struct HealStatistics {
     pub total_objects_healed: u64,
     /// Total healed bytes
     pub total_bytes_healed: u64,
+    /// Object heals completed, per bucket
+    #[serde(default)]
+    pub objects_healed_by_bucket: HashMap<String, u64>,
     /// Last update time
     pub last_update_time: SystemTime,
 }
@@ -113,6 +117,7 @@ impl HealStatistics {
             running_tasks: 0,
             total_objects_healed: 0,
             total_bytes_healed: 0,
+            objects_healed_by_bucket: HashMap::new(),
             last_update_time: SystemTime::now(),
         }
     }
@@ -137,6 +142,11 @@ impl HealStatistics {
         self.last_update_time = SystemTime::now();
     }
 
+    pub fn add_bucket_object_healed(&mut self, bucket: &str) {
+        *self.objects_healed_by_bucket.entry(bucket.to_string()).or_default() += 1;
+        self.last_update_time = SystemTime::now();
+    }
+
     pub fn get_success_rate(&self) -> f64 {
         let total = self.successful_tasks + self.failed_tasks;
         if total > 0 {
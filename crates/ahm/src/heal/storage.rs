@@ -283,6 +283,7 @@ impl HealStorageAPI for ECStoreHealStorage {
             no_lock: false,
             pool: None,
             set: None,
+            rewrite: false,
         };
 
         match self.heal_object(bucket, object, None, &heal_opts).await {
@@ -367,6 +368,7 @@ impl HealStorageAPI for ECStoreHealStorage {
             no_lock: false,
             pool: None,
             set: None,
+            rewrite: false,
         };
 
         match self.heal_bucket(bucket, &heal_opts).await {
@@ -480,7 +482,7 @@ impl HealStorageAPI for ECStoreHealStorage {
 
         match self.ecstore.heal_format(dry_run).await {
             Ok((result, ecstore_error)) => {
-                let error = ecstore_error.map(Error::other);
+                let error = ecstore_error.map(Error::Storage);
                 info!("Heal format completed - result: {:?}, error: {:?}", result, error);
                 Ok((result, error))
             }
@@ -498,7 +500,7 @@ impl HealStorageAPI for ECStoreHealStorage {
         match self
             .ecstore
             .clone()
-            .list_objects_v2(bucket, prefix, None, None, 1000, false, None, false)
+            .list_objects_v2(bucket, prefix, None, None, 1000, false, None, false, None)
             .await
         {
             Ok(list_info) => {
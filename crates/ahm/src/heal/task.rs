@@ -15,6 +15,7 @@
 use crate::heal::{ErasureSetHealer, progress::HealProgress, storage::HealStorageAPI};
 use crate::{Error, Result};
 use rustfs_common::heal_channel::{HealOpts, HealScanMode};
+use rustfs_ecstore::error::StorageError;
 use serde::{Deserialize, Serialize};
 use std::{
     future::Future,
@@ -50,6 +51,18 @@ pub enum HealType {
     },
 }
 
+impl HealType {
+    /// Bucket of the object a heal repairs, `None` for heals not targeting a single object
+    pub fn object_bucket(&self) -> Option<&str> {
+        match self {
+            HealType::Object { bucket, .. } | HealType::Metadata { bucket, .. } | HealType::ECDecode { bucket, .. } => {
+                Some(bucket)
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Heal priority
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum HealPriority {
@@ -399,6 +412,7 @@ impl HealTask {
             no_lock: false,
             pool: self.options.pool_index,
             set: self.options.set_index,
+            rewrite: false,
         };
 
         let heal_result = self
@@ -517,6 +531,7 @@ impl HealTask {
             no_lock: false,
             pool: None,
             set: None,
+            rewrite: false,
         };
 
         match self
@@ -589,6 +604,7 @@ impl HealTask {
             no_lock: false,
             pool: self.options.pool_index,
             set: self.options.set_index,
+            rewrite: false,
         };
 
         let heal_result = self.await_with_control(self.storage.heal_bucket(bucket, &heal_opts)).await;
@@ -656,6 +672,7 @@ impl HealTask {
             no_lock: false,
             pool: self.options.pool_index,
             set: self.options.set_index,
+            rewrite: false,
         };
 
         let heal_result = self
@@ -736,6 +753,7 @@ impl HealTask {
             no_lock: false,
             pool: None,
             set: None,
+            rewrite: false,
         };
 
         let heal_result = self
@@ -816,6 +834,7 @@ impl HealTask {
             no_lock: false,
             pool: None,
             set: None,
+            rewrite: false,
         };
 
         let heal_result = self
@@ -889,7 +908,8 @@ impl HealTask {
 
         match format_result {
             Ok((result, error)) => {
-                if let Some(e) = error {
+                // Drives formatted beforehand, e.g. by a disk replacement, leave nothing to do here
+                if let Some(e) = error.filter(|e| !matches!(e, Error::Storage(StorageError::NoHealRequired))) {
                     error!("Disk format heal failed: {} - {}", set_disk_id, e);
                     {
                         let mut progress = self.progress.write().await;
@@ -0,0 +1,153 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-bucket breakdown of the background work of a scanner cycle.
+//!
+//! After each cycle the heal backlog, the heals queued and completed, the lifecycle actions applied
+//! and the time spent on every scanned bucket are exported as gauges labelled with the bucket. Only
+//! the largest buckets get their own label so the number of series stays bounded, the others are
+//! summed under [`OTHER_BUCKETS_LABEL`]. The series of a bucket that drops out are reset to zero.
+
+use metrics::gauge;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Label of the series summing the buckets past the cardinality cap.
+pub const OTHER_BUCKETS_LABEL: &str = "other";
+
+/// Background work one cycle spent on a bucket.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BucketCycleStats {
+    /// Size of the bucket, decides which buckets get their own label
+    pub size: u64,
+    /// Object heals queued or running when the cycle finished
+    pub heal_backlog: u64,
+    /// Objects the cycle queued for healing
+    pub heals_queued: u64,
+    /// Object heals completed since the previous cycle
+    pub objects_healed: u64,
+    /// Lifecycle actions the cycle applied
+    pub lifecycle_actions: u64,
+    /// Time spent walking, evaluating and verifying the bucket
+    pub scan_duration: Duration,
+}
+
+impl BucketCycleStats {
+    fn merge(&mut self, other: &BucketCycleStats) {
+        self.size = self.size.saturating_add(other.size);
+        self.heal_backlog = self.heal_backlog.saturating_add(other.heal_backlog);
+        self.heals_queued = self.heals_queued.saturating_add(other.heals_queued);
+        self.objects_healed = self.objects_healed.saturating_add(other.objects_healed);
+        self.lifecycle_actions = self.lifecycle_actions.saturating_add(other.lifecycle_actions);
+        self.scan_duration += other.scan_duration;
+    }
+}
+
+/// Exports the per-bucket gauges and remembers which labels it set.
+#[derive(Debug, Default)]
+pub struct BucketMetricsExporter {
+    exported: HashSet<String>,
+}
+
+impl BucketMetricsExporter {
+    /// Export the stats of a cycle, at most `limit` buckets with their own label.
+    pub fn export(&mut self, stats: &HashMap<String, BucketCycleStats>, limit: usize) {
+        let series = bucket_series(stats, limit);
+        let labels: HashSet<String> = series.iter().map(|(label, _)| label.clone()).collect();
+
+        for stale in self.exported.difference(&labels) {
+            set_gauges(stale, &BucketCycleStats::default());
+        }
+        for (label, stats) in &series {
+            set_gauges(label, stats);
+        }
+        self.exported = labels;
+    }
+}
+
+fn set_gauges(label: &str, stats: &BucketCycleStats) {
+    let bucket = label.to_string();
+    gauge!("rustfs_scanner_bucket_heal_backlog", "bucket" => bucket.clone()).set(stats.heal_backlog as f64);
+    gauge!("rustfs_scanner_bucket_heals_queued", "bucket" => bucket.clone()).set(stats.heals_queued as f64);
+    gauge!("rustfs_scanner_bucket_objects_healed", "bucket" => bucket.clone()).set(stats.objects_healed as f64);
+    gauge!("rustfs_scanner_bucket_lifecycle_actions", "bucket" => bucket.clone()).set(stats.lifecycle_actions as f64);
+    gauge!("rustfs_scanner_bucket_scan_duration_seconds", "bucket" => bucket).set(stats.scan_duration.as_secs_f64());
+}
+
+/// The `limit` largest buckets under their own label, the rest summed under [`OTHER_BUCKETS_LABEL`].
+fn bucket_series(stats: &HashMap<String, BucketCycleStats>, limit: usize) -> Vec<(String, BucketCycleStats)> {
+    let mut buckets: Vec<(&String, &BucketCycleStats)> = stats.iter().collect();
+    buckets.sort_by(|(a_name, a), (b_name, b)| b.size.cmp(&a.size).then_with(|| a_name.cmp(b_name)));
+
+    let mut series: Vec<(String, BucketCycleStats)> = Vec::with_capacity(limit.min(buckets.len()) + 1);
+    let mut other: Option<BucketCycleStats> = None;
+    for (index, (bucket, stats)) in buckets.into_iter().enumerate() {
+        // A bucket named like the aggregate label is counted in the aggregate
+        if index < limit && bucket != OTHER_BUCKETS_LABEL {
+            series.push((bucket.clone(), stats.clone()));
+        } else {
+            other.get_or_insert_with(BucketCycleStats::default).merge(stats);
+        }
+    }
+    if let Some(other) = other {
+        series.push((OTHER_BUCKETS_LABEL.to_string(), other));
+    }
+    series
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(size: u64, heals_queued: u64) -> BucketCycleStats {
+        BucketCycleStats {
+            size,
+            heals_queued,
+            scan_duration: Duration::from_secs(1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bucket_series_keeps_largest_buckets() {
+        let buckets = HashMap::from([
+            ("small".to_string(), stats(10, 1)),
+            ("large".to_string(), stats(1000, 2)),
+            ("medium".to_string(), stats(100, 3)),
+            ("tiny".to_string(), stats(1, 4)),
+        ]);
+
+        let series = bucket_series(&buckets, 2);
+        let labels: Vec<&str> = series.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(labels, vec!["large", "medium", OTHER_BUCKETS_LABEL]);
+
+        let other = &series[2].1;
+        assert_eq!(other.size, 11);
+        assert_eq!(other.heals_queued, 5);
+        assert_eq!(other.scan_duration, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_bucket_series_without_overflow() {
+        let buckets = HashMap::from([("a".to_string(), stats(1, 0)), ("b".to_string(), stats(1, 0))]);
+
+        let series = bucket_series(&buckets, 10);
+        let labels: Vec<&str> = series.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(labels, vec!["a", "b"]);
+
+        let series = bucket_series(&buckets, 0);
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].0, OTHER_BUCKETS_LABEL);
+    }
+}
@@ -17,29 +17,36 @@ use crate::{
     Error, HealRequest, Result, get_ahm_services_cancel_token,
     heal::HealManager,
     scanner::{
-        BucketMetrics, DecentralizedStatsAggregator, DecentralizedStatsAggregatorConfig, DiskMetrics, MetricsCollector,
-        NodeScanner, NodeScannerConfig, ScannerMetrics,
-        lifecycle::ScannerItem,
+        BucketMetrics, DecentralizedStatsAggregator, DecentralizedStatsAggregatorConfig, DiskMetrics, DiskWalkers,
+        MetricsCollector, NodeScanner, NodeScannerConfig, ScannerMetrics,
+        bucket_metrics::{BucketCycleStats, BucketMetricsExporter},
+        lifecycle::{LifecyclePriority, ObjectAgeHistogram, ScannerItem, select_lifecycle_buckets},
         local_scan::{self, LocalObjectRecord, LocalScanOutcome},
     },
 };
-use rustfs_common::data_usage::{DataUsageInfo, SizeSummary};
+use rustfs_common::data_usage::{BucketUsageInfo, DataUsageInfo, SizeSummary};
 use rustfs_common::metrics::{Metric, Metrics, global_metrics};
+use rustfs_common::scanner_events::{BucketCycleSummary, publish_bucket_cycle};
 use rustfs_ecstore::{
     self as ecstore, StorageAPI,
+    bucket::metadata_sys::get_replication_config,
+    bucket::replication::{check_replicated_object, sample_window},
     bucket::versioning::VersioningApi,
     bucket::versioning_sys::BucketVersioningSys,
     data_usage::{aggregate_local_snapshots, store_data_usage_in_backend},
-    disk::{Disk, DiskAPI, DiskStore, RUSTFS_META_BUCKET, WalkDirOptions},
+    disk::{
+        Disk, DiskAPI, DiskStore, RUSTFS_META_BUCKET, WalkDirOptions,
+        walk::{EntryStream, WalkDirStream},
+    },
     set_disk::SetDisks,
     store_api::ObjectInfo,
 };
-use rustfs_filemeta::{MetacacheReader, VersionType};
+use rustfs_filemeta::VersionType;
 use s3s::dto::{BucketVersioningStatus, VersioningConfiguration};
 use std::{
     collections::HashMap,
     sync::Arc,
-    time::{Duration, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 use time::OffsetDateTime;
 use tokio::sync::{Mutex, RwLock};
@@ -66,6 +73,8 @@ pub struct ScannerConfig {
     pub deep_scan_interval: Duration,
     /// Maximum concurrent scans
     pub max_concurrent_scans: usize,
+    /// Walks reading one physical disk at a time, 0 for no limit
+    pub walkers_per_disk: usize,
     /// Whether to enable healing
     pub enable_healing: bool,
     /// Whether to enable metrics collection
@@ -74,6 +83,18 @@ pub struct ScannerConfig {
     pub scan_mode: ScanMode,
     /// Whether to enable data usage statistics collection
     pub enable_data_usage_stats: bool,
+    /// Buckets whose lifecycle rules act within this horizon, or that hold objects about to reach
+    /// it, have their lifecycle evaluated every cycle
+    pub lifecycle_urgent_horizon: Duration,
+    /// Maximum number of other buckets evaluated for lifecycle per cycle, 0 for all of them
+    pub lifecycle_buckets_per_cycle: usize,
+    /// Objects per replicated bucket whose target copies are verified each cycle, 0 to disable
+    pub replication_check_sample: usize,
+    /// Queue objects whose target copy drifted for re-replication
+    pub replication_check_requeue: bool,
+    /// Largest buckets exported under their own label in the per-bucket metrics, the others are
+    /// summed under `other`
+    pub bucket_metrics_limit: usize,
 }
 
 impl Default for ScannerConfig {
@@ -82,10 +103,16 @@ impl Default for ScannerConfig {
             scan_interval: Duration::from_secs(300),       // 5 minutes
             deep_scan_interval: Duration::from_secs(3600), // 1 hour
             max_concurrent_scans: 20,
+            walkers_per_disk: 1,
             enable_healing: true,
             enable_metrics: true,
             scan_mode: ScanMode::Normal,
             enable_data_usage_stats: true,
+            lifecycle_urgent_horizon: Duration::from_secs(7 * 24 * 3600), // 7 days
+            lifecycle_buckets_per_cycle: 0,
+            replication_check_sample: 32,
+            replication_check_requeue: false,
+            bucket_metrics_limit: 100,
         }
     }
 }
@@ -139,10 +166,20 @@ pub struct Scanner {
     last_data_usage_collection: Arc<RwLock<Option<SystemTime>>>,
     /// Heal manager for auto-heal integration
     heal_manager: Option<Arc<HealManager>>,
+    /// Cycle each bucket last had its lifecycle evaluated in
+    lifecycle_evaluated: Arc<Mutex<HashMap<String, u64>>>,
+    /// Objects of each bucket queued for healing in the running cycle
+    cycle_heals: Arc<Mutex<HashMap<String, u64>>>,
+    /// Object heals completed per bucket as of the previous cycle
+    healed_at_last_cycle: Arc<Mutex<HashMap<String, u64>>>,
+    /// Per-bucket gauges exported after each cycle
+    bucket_metrics_exporter: Arc<Mutex<BucketMetricsExporter>>,
 
     // NEW: Optimized scanner components
     /// Node scanner for local disk scanning
     node_scanner: Arc<NodeScanner>,
+    /// Walks allowed per physical disk, shared with the node scanner
+    disk_walkers: Arc<DiskWalkers>,
     /// Statistics aggregator for global view
     stats_aggregator: Arc<DecentralizedStatsAggregator>,
     /// Node ID for this scanner instance
@@ -170,8 +207,9 @@ impl Scanner {
             max_retry_attempts: 3,
         };
 
-        // Create node scanner
-        let node_scanner = Arc::new(NodeScanner::new(node_id.clone(), node_config));
+        // Create node scanner, walking the disks under the same per-disk limit as the local scan
+        let disk_walkers = Arc::new(DiskWalkers::new(config.walkers_per_disk));
+        let node_scanner = Arc::new(NodeScanner::new(node_id.clone(), node_config).with_disk_walkers(disk_walkers.clone()));
 
         // Create stats aggregator configuration
         let aggregator_config = DecentralizedStatsAggregatorConfig {
@@ -193,7 +231,12 @@ impl Scanner {
             data_usage_stats: Arc::new(Mutex::new(HashMap::new())),
             last_data_usage_collection: Arc::new(RwLock::new(None)),
             heal_manager,
+            lifecycle_evaluated: Arc::new(Mutex::new(HashMap::new())),
+            cycle_heals: Arc::new(Mutex::new(HashMap::new())),
+            healed_at_last_cycle: Arc::new(Mutex::new(HashMap::new())),
+            bucket_metrics_exporter: Arc::new(Mutex::new(BucketMetricsExporter::default())),
             node_scanner,
+            disk_walkers,
             stats_aggregator,
             node_id,
         }
@@ -260,7 +303,7 @@ impl Scanner {
             let enable_healing = config.enable_healing;
             drop(config);
 
-            let scan_outcome = match local_scan::scan_and_persist_local_usage(ecstore.clone()).await {
+            let scan_outcome = match local_scan::scan_and_persist_local_usage(ecstore.clone(), self.disk_walkers.clone()).await {
                 Ok(outcome) => outcome,
                 Err(err) => {
                     warn!("Local usage scan failed: {}", err);
@@ -268,6 +311,13 @@ impl Scanner {
                 }
             };
             let bucket_objects_map = &scan_outcome.bucket_objects;
+            self.cycle_heals.lock().await.clear();
+
+            // Objects written while drives were offline go ahead of routine work, they stay at
+            // reduced parity until healed
+            if enable_healing {
+                self.heal_degraded_objects(&ecstore, &scan_outcome.degraded_objects).await;
+            }
 
             // List all buckets
             debug!("Listing buckets");
@@ -277,6 +327,28 @@ impl Scanner {
             {
                 Ok(buckets) => {
                     debug!("Found {} buckets", buckets.len());
+                    let config = self.config.read().await;
+                    let urgent_days = config.lifecycle_urgent_horizon.as_secs() / 86_400;
+                    let max_other = config.lifecycle_buckets_per_cycle;
+                    let replication_check_sample = config.replication_check_sample;
+                    let replication_check_requeue = config.replication_check_requeue;
+                    drop(config);
+
+                    let now = OffsetDateTime::now_utc();
+                    let mut bucket_work: HashMap<String, BucketCycleStats> = scan_outcome
+                        .bucket_walk_durations
+                        .iter()
+                        .map(|(bucket, elapsed)| {
+                            let stats = BucketCycleStats {
+                                scan_duration: *elapsed,
+                                ..Default::default()
+                            };
+                            (bucket.clone(), stats)
+                        })
+                        .collect();
+                    let mut scanned_buckets = Vec::new();
+                    let mut lifecycle_buckets = HashMap::new();
+                    let mut priorities = Vec::new();
                     for bucket_info in buckets {
                         let bucket_name = &bucket_info.name;
 
@@ -317,41 +389,99 @@ impl Scanner {
                         total_objects_scanned = total_objects_scanned.saturating_add(live_objects);
                         debug!("Counted {} objects in bucket {} using local snapshots", live_objects, bucket_name);
 
-                        // Process objects for lifecycle actions
-                        if let Some(lifecycle_config) = &lifecycle_config {
-                            debug!("Processing lifecycle actions for bucket: {}", bucket_name);
-                            let mut scanner_item = ScannerItem::new(
-                                bucket_name.to_string(),
-                                Some(lifecycle_config.clone()),
-                                Some(versioning_config.clone()),
-                            );
+                        if let Some(lifecycle_config) = lifecycle_config {
+                            let ages = Self::object_age_histogram(records, now);
+                            priorities.push(LifecyclePriority::new(bucket_name, &lifecycle_config, &ages, urgent_days, now));
+                            lifecycle_buckets.insert(bucket_name.clone(), (lifecycle_config, versioning_config));
+                        }
+                        scanned_buckets.push(bucket_name.clone());
+                    }
 
-                            match self
-                                .process_bucket_objects_for_lifecycle(bucket_name, &mut scanner_item, records)
-                                .await
-                            {
-                                Ok(processed_count) => {
-                                    debug!("Processed {} objects for lifecycle in bucket {}", processed_count, bucket_name);
-                                }
-                                Err(e) => {
-                                    warn!("Failed to process lifecycle actions for bucket {}: {}", bucket_name, e);
-                                }
+                    // Process objects for lifecycle actions, buckets about to act first so short retention
+                    // buckets are evaluated every cycle
+                    let cycle = self.state.read().await.current_cycle;
+                    let selected = {
+                        let last_evaluated = self.lifecycle_evaluated.lock().await;
+                        select_lifecycle_buckets(priorities, &last_evaluated, max_other)
+                    };
+                    for priority in selected {
+                        let bucket_name = &priority.bucket;
+                        let (Some((lifecycle_config, versioning_config)), Some(records)) =
+                            (lifecycle_buckets.get(bucket_name), bucket_objects_map.get(bucket_name))
+                        else {
+                            continue;
+                        };
+
+                        debug!(
+                            "Processing lifecycle actions for bucket: {} (horizon {:?} days, {} objects due, urgent {})",
+                            bucket_name, priority.horizon_days, priority.objects_due, priority.urgent
+                        );
+                        let mut scanner_item = ScannerItem::new(
+                            bucket_name.to_string(),
+                            Some(lifecycle_config.clone()),
+                            Some(versioning_config.clone()),
+                        );
+
+                        let started = Instant::now();
+                        match self
+                            .process_bucket_objects_for_lifecycle(bucket_name, &mut scanner_item, records)
+                            .await
+                        {
+                            Ok(processed_count) => {
+                                debug!("Processed {} objects for lifecycle in bucket {}", processed_count, bucket_name);
+                            }
+                            Err(e) => {
+                                warn!("Failed to process lifecycle actions for bucket {}: {}", bucket_name, e);
                             }
                         }
+                        let work = bucket_work.entry(bucket_name.clone()).or_default();
+                        work.scan_duration += started.elapsed();
+                        work.lifecycle_actions = work.lifecycle_actions.saturating_add(scanner_item.actions_applied);
+                        self.lifecycle_evaluated.lock().await.insert(bucket_name.clone(), cycle);
+                    }
 
-                        // If deep scan is enabled, verify each object's integrity
-                        if enable_deep_scan && enable_healing {
+                    // Verify a rotating sample of replicated objects against their target copies
+                    if replication_check_sample > 0 {
+                        for bucket_name in &scanned_buckets {
+                            let Some(records) = bucket_objects_map.get(bucket_name) else {
+                                continue;
+                            };
+                            let started = Instant::now();
+                            self.check_bucket_replication(
+                                &ecstore,
+                                bucket_name,
+                                records,
+                                replication_check_sample,
+                                replication_check_requeue,
+                                cycle,
+                            )
+                            .await;
+                            bucket_work.entry(bucket_name.clone()).or_default().scan_duration += started.elapsed();
+                        }
+                    }
+
+                    // If deep scan is enabled, verify each object's integrity
+                    if enable_deep_scan && enable_healing {
+                        for bucket_name in &scanned_buckets {
+                            let Some(records) = bucket_objects_map.get(bucket_name) else {
+                                continue;
+                            };
                             debug!("Deep scan enabled, verifying object integrity in bucket {}", bucket_name);
+                            let started = Instant::now();
                             if let Err(e) = self
                                 .deep_scan_bucket_objects_with_records(&ecstore, bucket_name, records)
                                 .await
                             {
                                 warn!("Deep scan failed for bucket {}: {}", bucket_name, e);
                             }
+                            bucket_work.entry(bucket_name.clone()).or_default().scan_duration += started.elapsed();
                         }
                     }
 
+                    let previous_usage = self.bucket_usages().await;
                     self.update_data_usage_statistics(&scan_outcome, &ecstore).await;
+                    self.publish_bucket_cycles(cycle, &scanned_buckets, &previous_usage, bucket_work)
+                        .await;
                 }
                 Err(e) => {
                     error!("Failed to list buckets: {}", e);
@@ -460,6 +590,66 @@ impl Scanner {
         });
     }
 
+    /// Usage of each bucket as of the last refresh of the data usage statistics
+    async fn bucket_usages(&self) -> HashMap<String, BucketUsageInfo> {
+        let guard = self.data_usage_stats.lock().await;
+        guard
+            .iter()
+            .filter_map(|(bucket, info)| info.buckets_usage.get(bucket).map(|usage| (bucket.clone(), usage.clone())))
+            .collect()
+    }
+
+    async fn count_cycle_heal(&self, bucket: &str) {
+        *self.cycle_heals.lock().await.entry(bucket.to_string()).or_default() += 1;
+    }
+
+    /// Tell subscribers which buckets the cycle is done with and what changed in them since the
+    /// previous cycle, so they can skip their own sync when nothing did, and export the background
+    /// work the cycle spent on each bucket
+    async fn publish_bucket_cycles(
+        &self,
+        cycle: u64,
+        buckets: &[String],
+        previous: &HashMap<String, BucketUsageInfo>,
+        mut work: HashMap<String, BucketCycleStats>,
+    ) {
+        let current = self.bucket_usages().await;
+        let heals = std::mem::take(&mut *self.cycle_heals.lock().await);
+        for bucket in buckets {
+            let Some(usage) = current.get(bucket) else {
+                continue;
+            };
+            let heals_queued = heals.get(bucket).copied().unwrap_or_default();
+            publish_bucket_cycle(BucketCycleSummary::new(bucket, cycle, usage, previous.get(bucket), heals_queued));
+        }
+
+        let (backlog, healed) = match &self.heal_manager {
+            Some(heal_manager) => (
+                heal_manager.get_heal_backlog_by_bucket().await,
+                heal_manager.get_statistics().await.objects_healed_by_bucket,
+            ),
+            None => (HashMap::new(), HashMap::new()),
+        };
+        let healed_before = std::mem::replace(&mut *self.healed_at_last_cycle.lock().await, healed.clone());
+
+        let mut stats = HashMap::with_capacity(buckets.len());
+        for bucket in buckets {
+            let mut bucket_stats = work.remove(bucket).unwrap_or_default();
+            bucket_stats.size = current.get(bucket).map(|usage| usage.size).unwrap_or_default();
+            bucket_stats.heals_queued = heals.get(bucket).copied().unwrap_or_default();
+            bucket_stats.heal_backlog = backlog.get(bucket).copied().unwrap_or_default();
+            bucket_stats.objects_healed = healed
+                .get(bucket)
+                .copied()
+                .unwrap_or_default()
+                .saturating_sub(healed_before.get(bucket).copied().unwrap_or_default());
+            stats.insert(bucket.clone(), bucket_stats);
+        }
+
+        let limit = self.config.read().await.bucket_metrics_limit;
+        self.bucket_metrics_exporter.lock().await.export(&stats, limit);
+    }
+
     fn convert_record_to_object_info(record: &LocalObjectRecord) -> ObjectInfo {
         if let Some(info) = &record.object_info {
             return info.clone();
@@ -481,6 +671,17 @@ impl Scanner {
         OffsetDateTime::from_unix_timestamp_nanos(ns).ok()
     }
 
+    /// Age histogram of the live objects of a bucket, used to rank buckets for lifecycle evaluation
+    fn object_age_histogram(records: &[LocalObjectRecord], now: OffsetDateTime) -> ObjectAgeHistogram {
+        let mut ages = ObjectAgeHistogram::default();
+        for record in records.iter().filter(|record| record.usage.has_live_object) {
+            if let Some(mod_time) = record.usage.last_modified_ns.and_then(Self::ns_to_offset_datetime) {
+                ages.add((now - mod_time).whole_days().max(0) as u64);
+            }
+        }
+        ages
+    }
+
     async fn deep_scan_bucket_objects_with_records(
         &self,
         ecstore: &std::sync::Arc<rustfs_ecstore::store::ECStore>,
@@ -556,6 +757,95 @@ impl Scanner {
         Ok(())
     }
 
+    /// Queue heals restoring full parity of objects written while drives were offline, once every
+    /// drive of their set is back online.
+    async fn heal_degraded_objects(&self, ecstore: &Arc<ecstore::store::ECStore>, objects: &[local_scan::DegradedObject]) {
+        let Some(heal_manager) = &self.heal_manager else {
+            return;
+        };
+
+        let mut set_online: HashMap<(usize, usize), bool> = HashMap::new();
+        let mut queued = 0usize;
+        for degraded in objects {
+            let key = (degraded.pool_index, degraded.set_index);
+            let online = match set_online.get(&key) {
+                Some(online) => *online,
+                None => {
+                    let online = match ecstore
+                        .pools
+                        .get(degraded.pool_index)
+                        .and_then(|pool| pool.disk_set.get(degraded.set_index))
+                    {
+                        Some(set_disks) => {
+                            let (disks, healing) = set_disks.get_online_disks_with_healing(false).await;
+                            !healing && disks.len() == set_disks.set_drive_count
+                        }
+                        None => false,
+                    };
+                    set_online.insert(key, online);
+                    online
+                }
+            };
+            if !online {
+                continue;
+            }
+
+            let req = HealRequest::new(
+                crate::heal::task::HealType::Object {
+                    bucket: degraded.bucket.clone(),
+                    object: degraded.object.clone(),
+                    version_id: None,
+                },
+                crate::heal::task::HealOptions::default(),
+                crate::heal::task::HealPriority::High,
+            );
+            match heal_manager.submit_heal_request(req).await {
+                Ok(_) => {
+                    queued += 1;
+                    self.count_cycle_heal(&degraded.bucket).await;
+                }
+                Err(e) => warn!("Failed to submit parity upgrade of {}/{}: {}", degraded.bucket, degraded.object, e),
+            }
+        }
+
+        if queued > 0 {
+            info!("Queued {} objects written at reduced parity for healing", queued);
+        }
+    }
+
+    /// Verify the target copies of `sample` replicated objects of a bucket, a different window of
+    /// the bucket every cycle
+    async fn check_bucket_replication(
+        &self,
+        ecstore: &Arc<rustfs_ecstore::store::ECStore>,
+        bucket_name: &str,
+        records: &[LocalObjectRecord],
+        sample: usize,
+        requeue: bool,
+        cycle: u64,
+    ) {
+        if get_replication_config(bucket_name).await.is_err() {
+            return;
+        }
+
+        let live: Vec<&LocalObjectRecord> = records.iter().filter(|record| record.usage.has_live_object).collect();
+        let mut drifted = 0usize;
+        for idx in sample_window(live.len(), sample, cycle) {
+            let object = &live[idx].usage.object;
+            let Ok(info) = ecstore.get_object_info(bucket_name, object, &Default::default()).await else {
+                continue;
+            };
+            let states = check_replicated_object(&info, requeue).await;
+            if states.iter().any(|(_, state)| state.is_drift()) {
+                drifted += 1;
+            }
+        }
+
+        if drifted > 0 {
+            warn!("{} sampled objects of bucket {} drifted from their replicas", drifted, bucket_name);
+        }
+    }
+
     /// Process bucket objects for lifecycle actions
     async fn process_bucket_objects_for_lifecycle(
         &self,
@@ -872,6 +1162,23 @@ impl Scanner {
             if let Err(e) = self.minimal_ec_verification(&ecstore).await {
                 error!("Minimal EC verification failed: {}", e);
             }
+
+            // Phase 3: Drop trashed objects whose retention has passed
+            if let Err(e) = rustfs_ecstore::bucket::trash::purge_expired_trash(ecstore.clone()).await {
+                warn!("Purging expired trash failed: {}", e);
+            }
+
+            // Phase 4: Read the next batch of objects into the metadata search index
+            match rustfs_ecstore::metadata_index::backfill_metadata_index(ecstore).await {
+                Ok(read) if read > 0 => debug!("Metadata index backfill read {} objects", read),
+                Ok(_) => {}
+                Err(e) => warn!("Metadata index backfill failed: {}", e),
+            }
+        }
+
+        // Phase 5: Look for data directories no version references
+        if self.config.read().await.scan_mode == ScanMode::Deep {
+            self.scan_orphaned_data().await;
         }
 
         // Update scan duration
@@ -901,7 +1208,7 @@ impl Scanner {
         };
 
         // Run local usage scan and aggregate snapshots; fall back to on-demand build when necessary.
-        let mut data_usage = match local_scan::scan_and_persist_local_usage(ecstore.clone()).await {
+        let mut data_usage = match local_scan::scan_and_persist_local_usage(ecstore.clone(), self.disk_walkers.clone()).await {
             Ok(outcome) => {
                 info!(
                     "Local usage scan completed: {} disks with {} snapshot entries",
@@ -1006,6 +1313,7 @@ impl Scanner {
                             false, // fetch_owner
                             None,  // start_after
                             false, // incl_deleted
+                            None,  // consistency
                         )
                         .await
                     {
@@ -1196,6 +1504,7 @@ impl Scanner {
                         error!("Failed to submit heal task for {}/{}: {}", bucket, object, e);
                     } else {
                         debug!("Successfully submitted heal request for {}/{}", bucket, object);
+                        self.count_cycle_heal(bucket).await;
                     }
                 } else {
                     debug!("No heal manager available for {}/{}", bucket, object);
@@ -1874,24 +2183,25 @@ impl Scanner {
             forward_to: None,
             limit: 0,
             disk_id: String::new(),
+            per_dir_limit: 0,
         };
 
-        // Use a buffer to collect scan results for processing
-        let mut scan_buffer = Vec::new();
-
-        if let Err(e) = disk.walk_dir(walk_opts, &mut scan_buffer).await {
-            error!("Failed to walk directory for bucket {}: {}", bucket, e);
-            return Err(Error::Storage(e.into()));
-        }
-
-        // Process the scan results using MetacacheReader
-        let mut reader = MetacacheReader::new(std::io::Cursor::new(scan_buffer));
+        // Stream the walk instead of buffering the whole bucket in memory
+        let mut stream = WalkDirStream::new(disk.clone(), walk_opts);
         let mut objects_scanned = 0u64;
         let mut objects_with_issues = 0u64;
         let mut object_metadata = HashMap::new();
 
         // Process each object entry
-        while let Ok(Some(mut entry)) = reader.peek().await {
+        loop {
+            let mut entry = match stream.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(e) => {
+                    error!("Failed to walk directory for bucket {}: {}", bucket, e);
+                    return Err(Error::Storage(e.into()));
+                }
+            };
             objects_scanned += 1;
             // Check if this is an actual object (not just a directory)
             if entry.is_object() {
@@ -1935,6 +2245,7 @@ impl Scanner {
                                     object_name: entry.name.clone(),
                                     lifecycle: Some(lifecycle_config.clone()),
                                     versioning: versioning_config.clone(),
+                                    actions_applied: 0,
                                 };
                                 //ScannerItem::new(bucket.to_string(), Some(lifecycle_config.clone()), versioning_config.clone());
                                 let fivs = match entry.clone().file_info_versions(&scanner_item.bucket) {
@@ -2483,9 +2794,9 @@ impl Scanner {
                 }
             }
 
-            let (enable_data_usage_stats, scan_interval) = {
+            let (enable_data_usage_stats, scan_interval, deep_scan_interval) = {
                 let config = self.config.read().await;
-                (config.enable_data_usage_stats, config.scan_interval)
+                (config.enable_data_usage_stats, config.scan_interval, config.deep_scan_interval)
             };
 
             if enable_data_usage_stats {
@@ -2498,6 +2809,21 @@ impl Scanner {
             let local_stats = self.node_scanner.get_stats_summary().await;
             self.stats_aggregator.set_local_stats(local_stats).await;
 
+            // The first deep pass waits a full interval so startup is not slowed by walking every drive
+            let deep_scan_due = {
+                let mut state = self.state.write().await;
+                let now = SystemTime::now();
+                let last_deep_scan_time = *state.last_deep_scan_time.get_or_insert(now);
+                let due = now.duration_since(last_deep_scan_time).unwrap_or(Duration::ZERO) >= deep_scan_interval;
+                if due {
+                    state.last_deep_scan_time = Some(now);
+                }
+                due
+            };
+            if deep_scan_due {
+                self.scan_orphaned_data().await;
+            }
+
             match get_ahm_services_cancel_token() {
                 Some(token) => {
                     tokio::select! {
@@ -2515,6 +2841,18 @@ impl Scanner {
         Ok(())
     }
 
+    /// Report, and remove when enabled, the data directories of the local drives no version references.
+    async fn scan_orphaned_data(&self) {
+        let Some(ecstore) = rustfs_ecstore::new_object_layer_fn() else {
+            return;
+        };
+
+        let reclaim = rustfs_ecstore::orphaned_data::reclaim_enabled();
+        if let Err(e) = rustfs_ecstore::orphaned_data::scan_orphaned_data(ecstore, reclaim).await {
+            warn!("Orphaned data scan failed: {}", e);
+        }
+    }
+
     /// Update legacy metrics from aggregated statistics
     async fn update_legacy_metrics_from_aggregated(&self, aggregated: &super::stats_aggregator::AggregatedStats) {
         // Update metrics collector with aggregated data
@@ -2569,7 +2907,12 @@ impl Scanner {
             data_usage_stats: Arc::clone(&self.data_usage_stats),
             last_data_usage_collection: Arc::clone(&self.last_data_usage_collection),
             heal_manager: self.heal_manager.clone(),
+            lifecycle_evaluated: Arc::clone(&self.lifecycle_evaluated),
+            cycle_heals: Arc::clone(&self.cycle_heals),
+            healed_at_last_cycle: Arc::clone(&self.healed_at_last_cycle),
+            bucket_metrics_exporter: Arc::clone(&self.bucket_metrics_exporter),
             node_scanner: Arc::clone(&self.node_scanner),
+            disk_walkers: Arc::clone(&self.disk_walkers),
             stats_aggregator: Arc::clone(&self.stats_aggregator),
             node_id: self.node_id.clone(),
         }
@@ -0,0 +1,169 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Walkers per physical disk.
+//!
+//! A walk of a drive holds a permit of the device the drive lives on, so at most `per_disk`
+//! walks of the local scan and the node scanner read one spindle at a time, however many drive
+//! directories share it. Walks queue behind each other on a busy device instead of interleaving
+//! their seeks with client IO, and drives are visited round-robin over devices so every device
+//! is kept busy with one walk.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+#[derive(Debug)]
+pub struct DiskWalkers {
+    per_disk: usize,
+    devices: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl Default for DiskWalkers {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+/// Device a path lives on, the path itself where the device cannot be told.
+pub fn device_key(path: &Path) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if let Ok(meta) = std::fs::metadata(path) {
+            return format!("dev:{}", meta.dev());
+        }
+    }
+    path.to_string_lossy().into_owned()
+}
+
+/// Order `items` so consecutive items belong to different devices where possible, keeping the
+/// order of the items of each device.
+pub fn round_robin<T>(items: Vec<T>, key: impl Fn(&T) -> String) -> Vec<T> {
+    let mut order: Vec<String> = Vec::new();
+    let mut queues: HashMap<String, Vec<T>> = HashMap::new();
+    for item in items {
+        let key = key(&item);
+        if !queues.contains_key(&key) {
+            order.push(key.clone());
+        }
+        queues.entry(key).or_default().push(item);
+    }
+
+    let mut queues: Vec<std::vec::IntoIter<T>> = order
+        .iter()
+        .filter_map(|key| queues.remove(key))
+        .map(Vec::into_iter)
+        .collect();
+    let mut out = Vec::new();
+    loop {
+        let before = out.len();
+        for queue in queues.iter_mut() {
+            out.extend(queue.next());
+        }
+        if out.len() == before {
+            return out;
+        }
+    }
+}
+
+impl DiskWalkers {
+    /// At most `per_disk` concurrent walks per device, 0 for no limit.
+    pub fn new(per_disk: usize) -> Self {
+        Self {
+            per_disk,
+            devices: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn device(&self, key: &str) -> Arc<Semaphore> {
+        let mut devices = self.devices.lock().unwrap_or_else(|e| e.into_inner());
+        devices
+            .entry(key.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.per_disk)))
+            .clone()
+    }
+
+    /// Wait for a walk of the device `key` to be allowed, the walk lasts while the permit is held.
+    /// None when walks are not limited.
+    pub async fn acquire_key(&self, key: &str) -> Option<OwnedSemaphorePermit> {
+        if self.per_disk == 0 {
+            return None;
+        }
+        self.device(key).acquire_owned().await.ok()
+    }
+
+    /// Wait for a walk of the drive at `path` to be allowed.
+    pub async fn acquire(&self, path: &Path) -> Option<OwnedSemaphorePermit> {
+        if self.per_disk == 0 {
+            return None;
+        }
+        self.acquire_key(&device_key(path)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[test]
+    fn test_round_robin() {
+        let items = vec![("a", 1), ("a", 2), ("a", 3), ("b", 1), ("c", 1), ("b", 2)];
+        let ordered = round_robin(items, |(dev, _)| dev.to_string());
+        assert_eq!(ordered, vec![("a", 1), ("b", 1), ("c", 1), ("a", 2), ("b", 2), ("a", 3)]);
+
+        assert!(round_robin(Vec::<u8>::new(), |_| String::new()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_one_walker_per_disk() {
+        let walkers = DiskWalkers::new(1);
+
+        let first = walkers.acquire_key("dev:1").await;
+        assert!(first.is_some());
+        // Another device walks meanwhile
+        assert!(walkers.acquire_key("dev:2").await.is_some());
+        // The same device waits for the first walk
+        assert!(
+            timeout(Duration::from_millis(20), walkers.acquire_key("dev:1"))
+                .await
+                .is_err()
+        );
+
+        drop(first);
+        assert!(walkers.acquire_key("dev:1").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_walkers() {
+        let walkers = DiskWalkers::new(0);
+        assert!(walkers.acquire_key("dev:1").await.is_none());
+        assert!(walkers.acquire(Path::new("/")).await.is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_device_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        std::fs::create_dir_all(&a).unwrap();
+        std::fs::create_dir_all(&b).unwrap();
+        // Directories of one file system share a device
+        assert_eq!(device_key(&a), device_key(&b));
+    }
+}
@@ -29,7 +29,8 @@ use rustfs_ecstore::bucket::{
 };
 use rustfs_ecstore::store_api::{ObjectInfo, ObjectToDelete};
 use rustfs_filemeta::FileInfo;
-use s3s::dto::{BucketLifecycleConfiguration as LifecycleConfig, VersioningConfiguration};
+use s3s::dto::{BucketLifecycleConfiguration as LifecycleConfig, ExpirationStatus, VersioningConfiguration};
+use std::collections::HashMap;
 use std::sync::{
     Arc,
     atomic::{AtomicU64, Ordering},
@@ -40,12 +41,123 @@ use tracing::info;
 static SCANNER_EXCESS_OBJECT_VERSIONS: AtomicU64 = AtomicU64::new(100);
 static SCANNER_EXCESS_OBJECT_VERSIONS_TOTAL_SIZE: AtomicU64 = AtomicU64::new(1024 * 1024 * 1024 * 1024); // 1 TB
 
+/// Upper bounds, in days, of the bins of [`ObjectAgeHistogram`].
+pub const OBJECT_AGE_BOUNDS_DAYS: [u64; 6] = [1, 7, 30, 90, 365, u64::MAX];
+
+/// Number of objects of a bucket by age since last modification.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectAgeHistogram(pub [u64; OBJECT_AGE_BOUNDS_DAYS.len()]);
+
+impl ObjectAgeHistogram {
+    pub fn add(&mut self, age_days: u64) {
+        let bin = OBJECT_AGE_BOUNDS_DAYS
+            .iter()
+            .position(|bound| age_days < *bound)
+            .unwrap_or(OBJECT_AGE_BOUNDS_DAYS.len() - 1);
+        self.0[bin] += 1;
+    }
+
+    /// Objects that may be `days` old or older. Bins are coarse, so a bin counts as soon as its
+    /// upper bound exceeds `days`.
+    pub fn count_older_than(&self, days: u64) -> u64 {
+        OBJECT_AGE_BOUNDS_DAYS
+            .iter()
+            .zip(self.0.iter())
+            .filter(|(bound, _)| **bound > days)
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+/// Days until the earliest action of the enabled rules of `lc` can fire, counted from object
+/// creation for relative rules and from `now` for dated ones.
+pub fn lifecycle_horizon_days(lc: &LifecycleConfig, now: OffsetDateTime) -> Option<u64> {
+    let until = |date: &s3s::dto::Timestamp| (OffsetDateTime::from(date.clone()) - now).whole_days().max(0) as u64;
+    let days = |d: Option<i32>| d.filter(|d| *d >= 0).map(|d| d as u64);
+
+    lc.rules
+        .iter()
+        .filter(|rule| rule.status.as_str() != ExpirationStatus::DISABLED)
+        .flat_map(|rule| {
+            let mut horizons = Vec::new();
+            if let Some(expiration) = &rule.expiration {
+                horizons.extend(days(expiration.days));
+                horizons.extend(expiration.date.as_ref().map(until));
+            }
+            if let Some(noncurrent) = &rule.noncurrent_version_expiration {
+                horizons.extend(days(noncurrent.noncurrent_days));
+            }
+            for transition in rule.transitions.iter().flatten() {
+                horizons.extend(days(transition.days));
+                horizons.extend(transition.date.as_ref().map(until));
+            }
+            horizons
+        })
+        .min()
+}
+
+/// How urgently the lifecycle rules of a bucket need to be evaluated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifecyclePriority {
+    pub bucket: String,
+    pub horizon_days: Option<u64>,
+    /// Objects old enough that a rule is about to act on them, estimated from the age histogram.
+    pub objects_due: u64,
+    /// Short-retention buckets and buckets with objects due are evaluated every cycle.
+    pub urgent: bool,
+}
+
+impl LifecyclePriority {
+    pub fn new(bucket: &str, lc: &LifecycleConfig, ages: &ObjectAgeHistogram, urgent_days: u64, now: OffsetDateTime) -> Self {
+        let horizon_days = lifecycle_horizon_days(lc, now);
+        let objects_due = horizon_days.map_or(0, |h| ages.count_older_than(h.saturating_sub(urgent_days)));
+        Self {
+            bucket: bucket.to_string(),
+            horizon_days,
+            objects_due,
+            urgent: horizon_days.is_some_and(|h| h <= urgent_days) || objects_due > 0,
+        }
+    }
+}
+
+/// Order buckets for lifecycle evaluation and keep the ones to evaluate this cycle: every urgent
+/// bucket, most objects due first, then up to `max_other` of the remaining buckets, least recently
+/// evaluated first. `last_evaluated` maps buckets to the cycle they were last evaluated in and a
+/// `max_other` of 0 keeps all buckets.
+pub fn select_lifecycle_buckets(
+    mut priorities: Vec<LifecyclePriority>,
+    last_evaluated: &HashMap<String, u64>,
+    max_other: usize,
+) -> Vec<LifecyclePriority> {
+    let last = |p: &LifecyclePriority| last_evaluated.get(&p.bucket).copied().unwrap_or(0);
+    priorities.sort_by(|a, b| {
+        b.urgent
+            .cmp(&a.urgent)
+            .then_with(|| {
+                if a.urgent {
+                    b.objects_due.cmp(&a.objects_due).then(a.horizon_days.cmp(&b.horizon_days))
+                } else {
+                    last(a).cmp(&last(b))
+                }
+            })
+            .then_with(|| a.bucket.cmp(&b.bucket))
+    });
+
+    if max_other > 0 {
+        let urgent = priorities.iter().filter(|p| p.urgent).count();
+        priorities.truncate(urgent + max_other);
+    }
+    priorities
+}
+
 #[derive(Clone)]
 pub struct ScannerItem {
     pub bucket: String,
     pub object_name: String,
     pub lifecycle: Option<Arc<LifecycleConfig>>,
     pub versioning: Option<Arc<VersioningConfiguration>>,
+    /// Lifecycle actions applied through this item
+    pub actions_applied: u64,
 }
 
 impl ScannerItem {
@@ -59,6 +171,7 @@ impl ScannerItem {
             object_name: "".to_string(),
             lifecycle,
             versioning,
+            actions_applied: 0,
         }
     }
 
@@ -260,6 +373,7 @@ impl ScannerItem {
         if lc_evt.action != IlmAction::NoneAction {
             info!("apply_lifecycle: Applying lifecycle action {:?} for object {}", lc_evt.action, oi.name);
             apply_lifecycle_action(&lc_evt, &LcEventSrc::Scanner, oi).await;
+            self.actions_applied = self.actions_applied.saturating_add(1);
         } else {
             info!("apply_lifecycle: Skipping lifecycle action for object {} as no action is needed", oi.name);
         }
@@ -267,3 +381,74 @@ impl ScannerItem {
         (lc_evt.action, new_size)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustfs_ecstore::bucket::utils::deserialize;
+
+    fn expiring_after(days: i32) -> LifecycleConfig {
+        let xml = format!(
+            "<LifecycleConfiguration><Rule><ID>expire</ID><Status>Enabled</Status><Filter><Prefix></Prefix></Filter>\
+             <Expiration><Days>{days}</Days></Expiration></Rule></LifecycleConfiguration>"
+        );
+        deserialize(xml.as_bytes()).expect("valid lifecycle configuration")
+    }
+
+    fn priority(bucket: &str, urgent: bool, objects_due: u64) -> LifecyclePriority {
+        LifecyclePriority {
+            bucket: bucket.to_string(),
+            horizon_days: Some(30),
+            objects_due,
+            urgent,
+        }
+    }
+
+    #[test]
+    fn test_object_age_histogram() {
+        let mut ages = ObjectAgeHistogram::default();
+        for days in [0, 3, 10, 400] {
+            ages.add(days);
+        }
+        assert_eq!(ages.0, [1, 1, 1, 0, 0, 1]);
+        assert_eq!(ages.count_older_than(0), 4);
+        assert_eq!(ages.count_older_than(30), 1);
+    }
+
+    #[test]
+    fn test_lifecycle_priority() {
+        let now = OffsetDateTime::now_utc();
+        let mut young = ObjectAgeHistogram::default();
+        young.add(0);
+
+        let short = LifecyclePriority::new("logs", &expiring_after(1), &young, 7, now);
+        assert_eq!(short.horizon_days, Some(1));
+        assert!(short.urgent);
+
+        let long = LifecyclePriority::new("archive", &expiring_after(365), &young, 7, now);
+        assert!(!long.urgent);
+
+        let mut old = ObjectAgeHistogram::default();
+        old.add(400);
+        let due = LifecyclePriority::new("archive", &expiring_after(365), &old, 7, now);
+        assert_eq!(due.objects_due, 1);
+        assert!(due.urgent);
+    }
+
+    #[test]
+    fn test_select_lifecycle_buckets() {
+        let priorities = vec![
+            priority("a", false, 0),
+            priority("b", true, 1),
+            priority("c", false, 0),
+            priority("d", true, 5),
+        ];
+        let last_evaluated = HashMap::from([("a".to_string(), 3), ("c".to_string(), 1)]);
+
+        let selected = select_lifecycle_buckets(priorities.clone(), &last_evaluated, 1);
+        let buckets: Vec<_> = selected.iter().map(|p| p.bucket.as_str()).collect();
+        assert_eq!(buckets, vec!["d", "b", "c"]);
+
+        assert_eq!(select_lifecycle_buckets(priorities, &last_evaluated, 0).len(), 4);
+    }
+}
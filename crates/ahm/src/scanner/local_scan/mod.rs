@@ -12,13 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::scanner::disk_walkers::{DiskWalkers, device_key, round_robin};
 use crate::{Error, Result};
+use futures::future::join_all;
 use rustfs_common::data_usage::DiskUsageStatus;
 use rustfs_ecstore::data_usage::{
     LocalUsageSnapshot, LocalUsageSnapshotMeta, data_usage_state_dir, ensure_data_usage_layout, snapshot_file_name,
     write_local_snapshot,
 };
-use rustfs_ecstore::disk::DiskAPI;
+use rustfs_ecstore::disk::{DiskAPI, DiskStore};
 use rustfs_ecstore::store::ECStore;
 use rustfs_ecstore::store_api::ObjectInfo;
 use rustfs_filemeta::{FileInfo, FileMeta, FileMetaVersion, VersionType};
@@ -27,7 +29,7 @@ use serde_json::{from_slice, to_vec};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::{fs, task};
 use tracing::warn;
 use walkdir::WalkDir;
@@ -43,6 +45,9 @@ pub struct LocalObjectUsage {
     pub delete_markers_count: u64,
     pub total_size: u64,
     pub has_live_object: bool,
+    /// The latest version was written while drives of its set were offline.
+    #[serde(default)]
+    pub degraded: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -55,6 +60,7 @@ struct DiskScanResult {
     snapshot: LocalUsageSnapshot,
     state: IncrementalScanState,
     objects_by_bucket: HashMap<String, Vec<LocalObjectRecord>>,
+    walk_durations: HashMap<String, Duration>,
     status: DiskUsageStatus,
 }
 
@@ -64,19 +70,30 @@ pub struct LocalObjectRecord {
     pub object_info: Option<rustfs_ecstore::store_api::ObjectInfo>,
 }
 
+/// An object whose latest version is stored at reduced parity, with the set holding it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DegradedObject {
+    pub pool_index: usize,
+    pub set_index: usize,
+    pub bucket: String,
+    pub object: String,
+}
+
 #[derive(Debug, Default)]
 pub struct LocalScanOutcome {
     pub snapshots: Vec<LocalUsageSnapshot>,
     pub bucket_objects: HashMap<String, Vec<LocalObjectRecord>>,
     pub disk_status: Vec<DiskUsageStatus>,
+    pub degraded_objects: Vec<DegradedObject>,
+    /// Time spent walking each bucket, summed over the disks
+    pub bucket_walk_durations: HashMap<String, Duration>,
 }
 
 /// Scan all local primary disks and persist refreshed usage snapshots.
-pub async fn scan_and_persist_local_usage(store: Arc<ECStore>) -> Result<LocalScanOutcome> {
-    let mut snapshots = Vec::new();
-    let mut bucket_objects: HashMap<String, Vec<LocalObjectRecord>> = HashMap::new();
-    let mut disk_status = Vec::new();
-
+///
+/// Disks are walked concurrently, at most `walkers` walks per physical disk at a time.
+pub async fn scan_and_persist_local_usage(store: Arc<ECStore>, walkers: Arc<DiskWalkers>) -> Result<LocalScanOutcome> {
+    let mut jobs = Vec::new();
     for (pool_idx, pool) in store.pools.iter().enumerate() {
         for set_disks in pool.disk_set.iter() {
             let disks = {
@@ -84,74 +101,105 @@ pub async fn scan_and_persist_local_usage(store: Arc<ECStore>) -> Result<LocalSc
                 guard.clone()
             };
 
-            for (disk_index, disk_opt) in disks.into_iter().enumerate() {
-                let Some(disk) = disk_opt else {
-                    continue;
-                };
+            // Count objects once by scanning only disk index zero from each set.
+            if let Some(Some(disk)) = disks.into_iter().next()
+                && disk.is_local()
+            {
+                jobs.push((pool_idx, set_disks.set_index, disk));
+            }
+        }
+    }
 
-                if !disk.is_local() {
-                    continue;
-                }
+    // Start the walks of different physical disks first so no device waits while another is busy
+    let jobs = round_robin(jobs, |(_, _, disk)| device_key(&disk.path()));
+    let results = join_all(
+        jobs.into_iter()
+            .map(|(pool_idx, set_index, disk)| scan_local_disk(pool_idx, set_index, disk, walkers.clone())),
+    )
+    .await;
+
+    let mut outcome = LocalScanOutcome::default();
+    for result in results {
+        let Some((pool_idx, set_index, result)) = result? else {
+            continue;
+        };
 
-                // Count objects once by scanning only disk index zero from each set.
-                if disk_index != 0 {
-                    continue;
-                }
+        outcome.snapshots.push(result.snapshot);
+        outcome.degraded_objects.extend(
+            result
+                .objects_by_bucket
+                .values()
+                .flatten()
+                .filter(|record| record.usage.degraded)
+                .map(|record| DegradedObject {
+                    pool_index: pool_idx,
+                    set_index,
+                    bucket: record.usage.bucket.clone(),
+                    object: record.usage.object.clone(),
+                }),
+        );
+        for (bucket, records) in result.objects_by_bucket {
+            outcome.bucket_objects.entry(bucket).or_default().extend(records.into_iter());
+        }
+        for (bucket, elapsed) in result.walk_durations {
+            *outcome.bucket_walk_durations.entry(bucket).or_default() += elapsed;
+        }
+        outcome.disk_status.push(result.status);
+    }
 
-                let disk_id = match disk.get_disk_id().await.map_err(Error::from)? {
-                    Some(id) => id.to_string(),
-                    None => {
-                        warn!("Skipping disk without ID: {}", disk.to_string());
-                        continue;
-                    }
-                };
+    Ok(outcome)
+}
 
-                let root = disk.path();
-                ensure_data_usage_layout(root.as_path()).await.map_err(Error::from)?;
+/// Walk one disk once a walker of its device is free and persist its snapshot.
+async fn scan_local_disk(
+    pool_idx: usize,
+    set_index: usize,
+    disk: DiskStore,
+    walkers: Arc<DiskWalkers>,
+) -> Result<Option<(usize, usize, DiskScanResult)>> {
+    let disk_id = match disk.get_disk_id().await.map_err(Error::from)? {
+        Some(id) => id.to_string(),
+        None => {
+            warn!("Skipping disk without ID: {}", disk.to_string());
+            return Ok(None);
+        }
+    };
 
-                let meta = LocalUsageSnapshotMeta {
-                    disk_id: disk_id.clone(),
-                    pool_index: Some(pool_idx),
-                    set_index: Some(set_disks.set_index),
-                    disk_index: Some(disk_index),
-                };
+    let root = disk.path();
+    ensure_data_usage_layout(root.as_path()).await.map_err(Error::from)?;
 
-                let state_path = state_file_path(root.as_path(), &disk_id);
-                let state = read_scan_state(&state_path).await?;
+    let meta = LocalUsageSnapshotMeta {
+        disk_id: disk_id.clone(),
+        pool_index: Some(pool_idx),
+        set_index: Some(set_index),
+        disk_index: Some(0),
+    };
 
-                let root_clone = root.clone();
-                let meta_clone = meta.clone();
+    let state_path = state_file_path(root.as_path(), &disk_id);
+    let state = read_scan_state(&state_path).await?;
 
-                let handle = task::spawn_blocking(move || scan_disk_blocking(root_clone, meta_clone, state));
+    let _walker = walkers.acquire(root.as_path()).await;
 
-                match handle.await {
-                    Ok(Ok(result)) => {
-                        write_local_snapshot(root.as_path(), &disk_id, &result.snapshot)
-                            .await
-                            .map_err(Error::from)?;
-                        write_scan_state(&state_path, &result.state).await?;
-                        snapshots.push(result.snapshot);
-                        for (bucket, records) in result.objects_by_bucket {
-                            bucket_objects.entry(bucket).or_default().extend(records.into_iter());
-                        }
-                        disk_status.push(result.status);
-                    }
-                    Ok(Err(err)) => {
-                        warn!("Failed to scan disk {}: {}", disk.to_string(), err);
-                    }
-                    Err(join_err) => {
-                        warn!("Disk scan task panicked for disk {}: {}", disk.to_string(), join_err);
-                    }
-                }
-            }
+    let root_clone = root.clone();
+    let handle = task::spawn_blocking(move || scan_disk_blocking(root_clone, meta, state));
+
+    match handle.await {
+        Ok(Ok(result)) => {
+            write_local_snapshot(root.as_path(), &disk_id, &result.snapshot)
+                .await
+                .map_err(Error::from)?;
+            write_scan_state(&state_path, &result.state).await?;
+            Ok(Some((pool_idx, set_index, result)))
+        }
+        Ok(Err(err)) => {
+            warn!("Failed to scan disk {}: {}", disk.to_string(), err);
+            Ok(None)
+        }
+        Err(join_err) => {
+            warn!("Disk scan task panicked for disk {}: {}", disk.to_string(), join_err);
+            Ok(None)
         }
     }
-
-    Ok(LocalScanOutcome {
-        snapshots,
-        bucket_objects,
-        disk_status,
-    })
 }
 
 fn scan_disk_blocking(root: PathBuf, meta: LocalUsageSnapshotMeta, mut state: IncrementalScanState) -> Result<DiskScanResult> {
@@ -160,6 +208,9 @@ fn scan_disk_blocking(root: PathBuf, meta: LocalUsageSnapshotMeta, mut state: In
     let mut visited: HashSet<String> = HashSet::new();
     let mut emitted: HashSet<String> = HashSet::new();
     let mut objects_by_bucket: HashMap<String, Vec<LocalObjectRecord>> = HashMap::new();
+    // The walk is depth first, each bucket is walked in one go
+    let mut walk_durations: HashMap<String, Duration> = HashMap::new();
+    let mut walking: Option<(String, Instant)> = None;
     let mut status = DiskUsageStatus {
         disk_id: meta.disk_id.clone(),
         pool_index: meta.pool_index,
@@ -196,6 +247,13 @@ fn scan_disk_blocking(root: PathBuf, meta: LocalUsageSnapshotMeta, mut state: In
             continue;
         }
 
+        if walking.as_ref().is_none_or(|(bucket, _)| bucket != bucket_name) {
+            if let Some((bucket, started)) = walking.take() {
+                *walk_durations.entry(bucket).or_default() += started.elapsed();
+            }
+            walking = Some((bucket_name.to_string(), Instant::now()));
+        }
+
         let object_key = components.collect::<Vec<_>>().join("/");
 
         visited.insert(rel_path.clone());
@@ -243,6 +301,10 @@ fn scan_disk_blocking(root: PathBuf, meta: LocalUsageSnapshotMeta, mut state: In
         }
     }
 
+    if let Some((bucket, started)) = walking {
+        *walk_durations.entry(bucket).or_default() += started.elapsed();
+    }
+
     state.objects.retain(|key, _| visited.contains(key));
     state.last_scan_ns = Some(now_ns);
 
@@ -267,6 +329,7 @@ fn scan_disk_blocking(root: PathBuf, meta: LocalUsageSnapshotMeta, mut state: In
         snapshot,
         state,
         objects_by_bucket,
+        walk_durations,
         status,
     })
 }
@@ -315,6 +378,7 @@ fn compute_object_usage(bucket: &str, object: &str, file_meta: &FileMeta) -> Res
         return Ok(None);
     }
 
+    let degraded = latest_file_info.as_ref().is_some_and(|fi| fi.degraded_write().is_some());
     let object_info = latest_file_info.as_ref().map(|fi| {
         let versioned = fi.version_id.is_some();
         ObjectInfo::from_file_info(fi, bucket, object, versioned)
@@ -329,6 +393,7 @@ fn compute_object_usage(bucket: &str, object: &str, file_meta: &FileMeta) -> Res
             delete_markers_count,
             total_size,
             has_live_object,
+            degraded,
         },
         object_info,
     }))
@@ -493,6 +558,27 @@ mod tests {
         assert!(record.usage.has_live_object);
     }
 
+    #[test]
+    fn compute_object_usage_reports_degraded_write() {
+        let file_meta = build_file_meta_with_object(0, 1024);
+        let record = compute_object_usage("bucket", "healthy", &file_meta)
+            .expect("compute")
+            .expect("record");
+        assert!(!record.usage.degraded);
+
+        let mut file_meta = FileMeta::default();
+        let mut fi = FileInfo::new("bucket/degraded", 2, 2);
+        fi.version_id = Some(Uuid::new_v4());
+        fi.mod_time = Some(OffsetDateTime::now_utc());
+        fi.set_degraded_write(3);
+        file_meta.add_version(fi).expect("add version");
+
+        let record = compute_object_usage("bucket", "degraded", &file_meta)
+            .expect("compute")
+            .expect("record");
+        assert!(record.usage.degraded);
+    }
+
     #[test]
     fn compute_object_usage_reports_delete_marker() {
         let file_meta = build_file_meta_with_delete_marker();
@@ -518,6 +604,7 @@ mod tests {
                 delete_markers_count: 1,
                 total_size: 512,
                 has_live_object: true,
+                degraded: false,
             },
         );
 
@@ -615,6 +702,7 @@ mod tests {
                 delete_markers_count: 0,
                 total_size: 512,
                 has_live_object: true,
+                degraded: false,
             },
         );
         stale_state.last_scan_ns = Some(99);
@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod bucket_metrics;
 pub mod checkpoint;
 pub mod data_scanner;
+pub mod disk_walkers;
 pub mod histogram;
 pub mod io_monitor;
 pub mod io_throttler;
@@ -26,6 +28,7 @@ pub mod stats_aggregator;
 
 pub use checkpoint::{CheckpointData, CheckpointInfo, CheckpointManager};
 pub use data_scanner::{ScanMode, Scanner, ScannerConfig, ScannerState};
+pub use disk_walkers::DiskWalkers;
 pub use io_monitor::{AdvancedIOMonitor, IOMetrics, IOMonitorConfig};
 pub use io_throttler::{AdvancedIOThrottler, IOThrottlerConfig, MetricsSnapshot, ResourceAllocation, ThrottleDecision};
 pub use local_stats::{BatchScanResult, LocalStatsManager, ScanResultEntry, StatsSummary};
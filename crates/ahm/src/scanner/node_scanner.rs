@@ -16,10 +16,12 @@ use crate::Result;
 use crate::scanner::{
     AdvancedIOMonitor, AdvancedIOThrottler, BatchScanResult, CheckpointManager, IOMonitorConfig, IOThrottlerConfig,
     LocalStatsManager, MetricsSnapshot, ScanResultEntry,
+    disk_walkers::{DiskWalkers, device_key, round_robin},
 };
 use rustfs_common::data_usage::DataUsageInfo;
 use rustfs_ecstore::StorageAPI;
 use rustfs_ecstore::disk::{DiskAPI, DiskStore};
+use rustfs_ecstore::maintenance::{GLOBAL_MAINTENANCE_SYS, MAINTENANCE_REFRESH_INTERVAL};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
@@ -430,6 +432,8 @@ pub struct NodeScanner {
     checkpoint_managers: Arc<RwLock<HashMap<String, Arc<CheckpointManager>>>>,
     /// cancel token
     cancel_token: CancellationToken,
+    /// walks allowed per physical disk
+    disk_walkers: Arc<DiskWalkers>,
 }
 
 impl NodeScanner {
@@ -473,9 +477,16 @@ impl NodeScanner {
             scan_progress: Arc::new(RwLock::new(ScanProgress::default())),
             checkpoint_managers: Arc::new(RwLock::new(HashMap::new())),
             cancel_token: CancellationToken::new(),
+            disk_walkers: Arc::new(DiskWalkers::default()),
         }
     }
 
+    /// Share the per-disk walk limit with other scanners of this node.
+    pub fn with_disk_walkers(mut self, disk_walkers: Arc<DiskWalkers>) -> Self {
+        self.disk_walkers = disk_walkers;
+        self
+    }
+
     /// add local disk and create checkpoint manager for it
     pub async fn add_local_disk(&self, disk: Arc<DiskStore>) {
         // get disk path and create corresponding scanner directory
@@ -740,6 +751,7 @@ impl NodeScanner {
             scan_progress: self.scan_progress.clone(),
             checkpoint_managers: self.checkpoint_managers.clone(),
             cancel_token: self.cancel_token.clone(),
+            disk_walkers: self.disk_walkers.clone(),
         }
     }
 
@@ -786,6 +798,15 @@ impl NodeScanner {
                 continue;
             }
 
+            // local drives of a node in maintenance are left alone until it is cleared
+            if GLOBAL_MAINTENANCE_SYS.is_local_node_in_maintenance() {
+                debug!("node {} is in maintenance, skip scanning local disks", self.node_id);
+                tokio::select! {
+                    _ = self.cancel_token.cancelled() => break,
+                    _ = tokio::time::sleep(MAINTENANCE_REFRESH_INTERVAL) => continue,
+                }
+            }
+
             // execute serial disk scanning
             if let Err(e) = self.scan_all_disks_serially().await {
                 error!("disk scanning failed: {}", e);
@@ -821,7 +842,8 @@ impl NodeScanner {
 
     /// serial scanning all local disks
     async fn scan_all_disks_serially(&self) -> Result<()> {
-        let local_disks = self.local_disks.read().await;
+        // Alternate between physical disks so consecutive walks do not queue on one device
+        let local_disks = round_robin(self.local_disks.read().await.clone(), |disk| device_key(&disk.path()));
         info!("start serial scanning node {} of {} disks", self.node_id, local_disks.len());
 
         for (index, disk) in local_disks.iter().enumerate() {
@@ -863,6 +885,7 @@ impl NodeScanner {
     async fn scan_single_disk(&self, disk: Arc<DiskStore>) -> Result<()> {
         info!("scan disk: path={:?}", disk.path());
 
+        let _walker = self.disk_walkers.acquire(&disk.path()).await;
         let scan_start = SystemTime::now();
         let mut scan_entries = Vec::new();
 
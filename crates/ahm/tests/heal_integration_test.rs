@@ -399,6 +399,7 @@ mod serial_tests {
             no_lock: false,
             pool: None,
             set: None,
+            rewrite: false,
         };
 
         let bucket_result = heal_storage.heal_bucket(bucket_name, &heal_opts).await;
@@ -420,6 +421,7 @@ mod serial_tests {
             no_lock: false,
             pool: None,
             set: None,
+            rewrite: false,
         };
 
         let object_result = heal_storage
@@ -404,7 +404,7 @@ mod serial_tests {
         assert!(object_exists(&ecstore, bucket_name.as_str(), object_name).await);
         println!("✅ Object exists before lifecycle processing");
 
-        let scan_outcome = match local_scan::scan_and_persist_local_usage(ecstore.clone()).await {
+        let scan_outcome = match local_scan::scan_and_persist_local_usage(ecstore.clone(), Default::default()).await {
             Ok(outcome) => outcome,
             Err(err) => {
                 warn!("Local usage scan failed: {}", err);
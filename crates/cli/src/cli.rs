@@ -0,0 +1,342 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+#[derive(Debug, Parser)]
+#[command(name = "rustfs-cli", version, about = "Command-line management tool of RustFS")]
+pub struct Cli {
+    /// Alias of the deployment to manage
+    #[arg(short, long, global = true, env = "RUSTFS_CLI_ALIAS", default_value = "local")]
+    pub alias: String,
+
+    /// Print results as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Manage the aliases of deployments
+    #[command(subcommand)]
+    Alias(AliasCommand),
+    /// Manage users
+    #[command(subcommand)]
+    User(UserCommand),
+    /// Manage canned policies and attach them to users and groups
+    #[command(subcommand)]
+    Policy(PolicyCommand),
+    /// Heal buckets and objects
+    #[command(subcommand)]
+    Heal(HealCommand),
+    /// Manage notification targets and configuration backups
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Manage remote tiers
+    #[command(subcommand)]
+    Tier(TierCommand),
+    /// Submit, follow and cancel background jobs
+    #[command(subcommand)]
+    Job(JobCommand),
+    /// Show the trace of a recent request, by its x-amz-request-id
+    Trace { request_id: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AliasCommand {
+    /// Add or replace an alias
+    Set {
+        name: String,
+        /// Endpoint, http(s)://host[:port]
+        url: String,
+        access_key: String,
+        secret_key: String,
+        #[arg(long)]
+        session_token: Option<String>,
+    },
+    /// List the aliases
+    List,
+    /// Remove an alias
+    Remove { name: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum UserCommand {
+    /// Add a user, or update the secret key of an existing one
+    Add {
+        access_key: String,
+        secret_key: String,
+        /// Policies of the user, a comma separated list
+        #[arg(long)]
+        policy: Option<String>,
+    },
+    Remove {
+        access_key: String,
+    },
+    List,
+    Info {
+        access_key: String,
+    },
+    Enable {
+        access_key: String,
+    },
+    Disable {
+        access_key: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PolicyCommand {
+    /// Create or replace a canned policy from a JSON policy document
+    Create {
+        name: String,
+        file: PathBuf,
+    },
+    Remove {
+        name: String,
+    },
+    List,
+    Info {
+        name: String,
+    },
+    /// Attach policies, a comma separated list, to a user or a group
+    Attach {
+        policy: String,
+        #[arg(long, conflicts_with = "group", required_unless_present = "group")]
+        user: Option<String>,
+        #[arg(long)]
+        group: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum HealCommand {
+    /// Start healing a bucket, or every bucket
+    Start {
+        #[command(flatten)]
+        path: HealPath,
+        /// Heal the objects under the prefix too
+        #[arg(short, long)]
+        recursive: bool,
+        /// Only report what would be healed
+        #[arg(long)]
+        dry_run: bool,
+        /// Remove dangling objects
+        #[arg(long)]
+        remove: bool,
+        /// Verify the bitrot checksums of the shards
+        #[arg(long)]
+        deep: bool,
+        /// Replace a heal already running on the same path
+        #[arg(long)]
+        force: bool,
+    },
+    /// Stop the heal running on a path
+    Stop {
+        #[command(flatten)]
+        path: HealPath,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct HealPath {
+    #[arg(default_value = "")]
+    pub bucket: String,
+    #[arg(default_value = "")]
+    pub prefix: String,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Create or replace a notification target, such as `notify_webhook 1 endpoint=http://...`
+    Set {
+        target_type: String,
+        target_name: String,
+        #[arg(required = true, value_parser = parse_key_value)]
+        key_values: Vec<(String, String)>,
+    },
+    /// Remove a notification target
+    Remove { target_type: String, target_name: String },
+    /// List the active notification targets
+    Targets,
+    /// Back up the configuration now
+    Backup,
+    /// List the configuration backups
+    Backups,
+    /// Restore the last configuration backup taken at or before a time, RFC 3339
+    Restore {
+        #[arg(value_parser = parse_time)]
+        time: OffsetDateTime,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TierCommand {
+    /// Add a tier from a JSON tier configuration
+    Add {
+        file: PathBuf,
+        /// Add the tier even if its remote is already in use
+        #[arg(long)]
+        force: bool,
+    },
+    List,
+    Info {
+        name: String,
+    },
+    /// Replace the credentials of a tier
+    Edit {
+        name: String,
+        access_key: String,
+        secret_key: String,
+    },
+    Remove {
+        name: String,
+        /// Remove the tier without checking whether it is in use
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum JobCommand {
+    /// Start a job
+    #[command(subcommand)]
+    Submit(JobSubmit),
+    /// Report on a job, or list the jobs of a kind when no id is given
+    Status { kind: JobKind, id: Option<String> },
+    /// Cancel a job, the id of a decommission is the index of the pool
+    Cancel { kind: JobKind, id: Option<String> },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum JobSubmit {
+    /// Send ObjectCreated events for the existing objects of a bucket
+    Backfill {
+        bucket: String,
+        #[arg(long, default_value = "")]
+        prefix: String,
+        /// Only objects modified at or after this time, RFC 3339
+        #[arg(long, value_parser = parse_time)]
+        since: Option<OffsetDateTime>,
+        /// Only objects modified before this time, RFC 3339
+        #[arg(long, value_parser = parse_time)]
+        until: Option<OffsetDateTime>,
+    },
+    /// Rewrap the data keys of objects with another KMS key or key version
+    KeyRotation {
+        key_id: String,
+        #[arg(long)]
+        source_version: Option<u32>,
+        #[arg(long, default_value = "")]
+        target_key_id: String,
+        #[arg(long, default_value = "")]
+        bucket: String,
+        #[arg(long, default_value = "")]
+        prefix: String,
+    },
+    /// Create or replace a scheduled backup of a bucket to a remote target
+    BucketBackup {
+        bucket: String,
+        /// ARN of the remote target
+        #[arg(long)]
+        arn: String,
+        /// Cron expression of the runs, in UTC
+        #[arg(long)]
+        cron: String,
+        #[arg(long, default_value = "")]
+        prefix: String,
+        /// Schedule to replace
+        #[arg(long, default_value = "")]
+        id: String,
+        #[arg(long)]
+        disabled: bool,
+    },
+    /// Run a bucket backup schedule now
+    BucketBackupRun { id: String },
+    /// Rebalance the pools
+    Rebalance,
+    /// Decommission pools, given by their index
+    Decommission {
+        #[arg(required = true)]
+        pools: Vec<usize>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum JobKind {
+    Backfill,
+    KeyRotation,
+    BucketBackup,
+    Rebalance,
+    Decommission,
+}
+
+fn parse_key_value(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{value}'")),
+    }
+}
+
+fn parse_time(value: &str) -> Result<OffsetDateTime, String> {
+    OffsetDateTime::parse(value, &Rfc3339).map_err(|e| format!("expected an RFC 3339 time: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let cli = Cli::try_parse_from(["rustfs-cli", "user", "add", "alice", "secret123", "--policy", "readwrite"]).unwrap();
+        assert_eq!(cli.alias, "local");
+        assert!(matches!(
+            cli.command,
+            Command::User(UserCommand::Add { policy: Some(ref policy), .. }) if policy == "readwrite"
+        ));
+
+        let cli = Cli::try_parse_from([
+            "rustfs-cli",
+            "-a",
+            "prod",
+            "config",
+            "set",
+            "notify_webhook",
+            "1",
+            "endpoint=http://h/x",
+        ])
+        .unwrap();
+        assert_eq!(cli.alias, "prod");
+        let Command::Config(ConfigCommand::Set { key_values, .. }) = cli.command else {
+            panic!("expected config set");
+        };
+        assert_eq!(key_values, vec![("endpoint".to_string(), "http://h/x".to_string())]);
+
+        let cli = Cli::try_parse_from(["rustfs-cli", "heal", "start", "photos", "--recursive"]).unwrap();
+        let Command::Heal(HealCommand::Start { path, recursive, .. }) = cli.command else {
+            panic!("expected heal start");
+        };
+        assert_eq!((path.bucket.as_str(), path.prefix.as_str(), recursive), ("photos", "", true));
+
+        assert!(Cli::try_parse_from(["rustfs-cli", "policy", "attach", "readonly"]).is_err());
+        assert!(Cli::try_parse_from(["rustfs-cli", "config", "set", "notify_webhook", "1", "endpoint"]).is_err());
+        assert!(Cli::try_parse_from(["rustfs-cli", "job", "submit", "backfill", "photos", "--since", "yesterday"]).is_err());
+    }
+}
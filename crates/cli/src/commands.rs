@@ -0,0 +1,401 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{Context, Result, anyhow, bail};
+use rustfs_admin_client::AdminClient;
+use rustfs_admin_client::jobs::{BackupSchedule, KeyRotation};
+use rustfs_admin_client::tier::TierCredentials;
+use rustfs_common::heal_channel::{HealOpts, HealScanMode};
+use rustfs_madmin::user::{AccountStatus, AddOrUpdateUserReq};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::alias::{Alias, AliasFile};
+use crate::cli::{
+    AliasCommand, Cli, Command, ConfigCommand, HealCommand, JobCommand, JobKind, JobSubmit, PolicyCommand, TierCommand,
+    UserCommand,
+};
+
+/// Prints the outcome of a command that returns nothing.
+struct Output {
+    json: bool,
+}
+
+impl Output {
+    fn done(&self, message: String) {
+        if self.json {
+            println!("{}", json!({ "status": "success", "message": message }));
+        } else {
+            println!("{message}");
+        }
+    }
+
+    /// Results without a tabular form are printed as JSON in both modes.
+    fn value<T: Serialize>(&self, value: &T) -> Result<()> {
+        let data = if self.json {
+            serde_json::to_string(value)?
+        } else {
+            serde_json::to_string_pretty(value)?
+        };
+        println!("{data}");
+        Ok(())
+    }
+
+    fn lines<T: Serialize>(&self, value: &T, lines: impl IntoIterator<Item = String>) -> Result<()> {
+        if self.json {
+            return self.value(value);
+        }
+        for line in lines {
+            println!("{line}");
+        }
+        Ok(())
+    }
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    let data = std::fs::read(path).with_context(|| format!("read {}", path.display()))?;
+    serde_json::from_slice(&data).with_context(|| format!("parse {}", path.display()))
+}
+
+pub async fn run(cli: Cli) -> Result<()> {
+    let out = Output { json: cli.json };
+    let path = AliasFile::path()?;
+    let aliases = AliasFile::load(&path)?;
+    let connect = || aliases.resolve(&cli.alias)?.client();
+
+    match cli.command {
+        Command::Alias(command) => alias(&out, &path, aliases.clone(), command),
+        Command::User(command) => user(&out, &connect()?, command).await,
+        Command::Policy(command) => policy(&out, &connect()?, command).await,
+        Command::Heal(command) => heal(&out, &connect()?, command).await,
+        Command::Config(command) => config(&out, &connect()?, command).await,
+        Command::Tier(command) => tier(&out, &connect()?, command).await,
+        Command::Job(command) => job(&out, &connect()?, command).await,
+        Command::Trace { request_id } => out.value(&connect()?.request_trace(&request_id).await?),
+    }
+}
+
+fn alias(out: &Output, path: &Path, mut aliases: AliasFile, command: AliasCommand) -> Result<()> {
+    match command {
+        AliasCommand::Set {
+            name,
+            url,
+            access_key,
+            secret_key,
+            session_token,
+        } => {
+            let alias = Alias {
+                url: url.trim_end_matches('/').to_string(),
+                access_key,
+                secret_key,
+                session_token: session_token.unwrap_or_default(),
+            };
+            // Catch a malformed endpoint before saving it
+            alias.client()?;
+            aliases.aliases.insert(name.clone(), alias);
+            aliases.save(path)?;
+            out.done(format!("Added alias `{name}`."));
+        }
+        AliasCommand::List => {
+            let lines = aliases
+                .aliases
+                .iter()
+                .map(|(name, alias)| format!("{name}\t{}\t{}", alias.url, alias.access_key));
+            // Secret keys are not printed
+            let listed: Vec<Value> = aliases
+                .aliases
+                .iter()
+                .map(|(name, alias)| json!({ "alias": name, "url": alias.url, "accessKey": alias.access_key }))
+                .collect();
+            out.lines(&listed, lines)?;
+        }
+        AliasCommand::Remove { name } => {
+            if aliases.aliases.remove(&name).is_none() {
+                bail!("alias '{name}' is not defined");
+            }
+            aliases.save(path)?;
+            out.done(format!("Removed alias `{name}`."));
+        }
+    }
+    Ok(())
+}
+
+async fn user(out: &Output, client: &AdminClient, command: UserCommand) -> Result<()> {
+    match command {
+        UserCommand::Add {
+            access_key,
+            secret_key,
+            policy,
+        } => {
+            let user = AddOrUpdateUserReq {
+                secret_key,
+                policy,
+                status: AccountStatus::Enabled,
+            };
+            client.add_user(&access_key, &user).await?;
+            out.done(format!("Added user `{access_key}`."));
+        }
+        UserCommand::Remove { access_key } => {
+            client.remove_user(&access_key).await?;
+            out.done(format!("Removed user `{access_key}`."));
+        }
+        UserCommand::List => {
+            let users: BTreeMap<_, _> = client.list_users().await?.into_iter().collect();
+            let lines = users.iter().map(|(name, user)| {
+                format!("{name}\t{}\t{}", user.status.as_ref(), user.policy_name.as_deref().unwrap_or_default())
+            });
+            out.lines(&users, lines)?;
+        }
+        UserCommand::Info { access_key } => out.value(&client.user_info(&access_key).await?)?,
+        UserCommand::Enable { access_key } => {
+            client.set_user_status(&access_key, AccountStatus::Enabled).await?;
+            out.done(format!("Enabled user `{access_key}`."));
+        }
+        UserCommand::Disable { access_key } => {
+            client.set_user_status(&access_key, AccountStatus::Disabled).await?;
+            out.done(format!("Disabled user `{access_key}`."));
+        }
+    }
+    Ok(())
+}
+
+async fn policy(out: &Output, client: &AdminClient, command: PolicyCommand) -> Result<()> {
+    match command {
+        PolicyCommand::Create { name, file } => {
+            client.add_canned_policy(&name, &read_json(&file)?).await?;
+            out.done(format!("Created policy `{name}`."));
+        }
+        PolicyCommand::Remove { name } => {
+            client.remove_canned_policy(&name).await?;
+            out.done(format!("Removed policy `{name}`."));
+        }
+        PolicyCommand::List => {
+            let policies: BTreeMap<_, _> = client.list_canned_policies().await?.into_iter().collect();
+            let names: Vec<String> = policies.keys().cloned().collect();
+            out.lines(&policies, names)?;
+        }
+        PolicyCommand::Info { name } => out.value(&client.canned_policy_info(&name).await?)?,
+        PolicyCommand::Attach { policy, user, group } => {
+            let (target, is_group) = match (user, group) {
+                (Some(user), _) => (user, false),
+                (None, Some(group)) => (group, true),
+                (None, None) => bail!("--user or --group is required"),
+            };
+            client.set_policy(&policy, &target, is_group).await?;
+            let kind = if is_group { "group" } else { "user" };
+            out.done(format!("Attached `{policy}` to {kind} `{target}`."));
+        }
+    }
+    Ok(())
+}
+
+async fn heal(out: &Output, client: &AdminClient, command: HealCommand) -> Result<()> {
+    match command {
+        HealCommand::Start {
+            path,
+            recursive,
+            dry_run,
+            remove,
+            deep,
+            force,
+        } => {
+            let opts = HealOpts {
+                recursive,
+                dry_run,
+                remove,
+                scan_mode: if deep { HealScanMode::Deep } else { HealScanMode::Normal },
+                ..Default::default()
+            };
+            client.heal_start(&path.bucket, &path.prefix, &opts, force).await?;
+            out.done(format!("Started healing `{}/{}`.", path.bucket, path.prefix));
+        }
+        HealCommand::Stop { path } => {
+            client.heal_stop(&path.bucket, &path.prefix).await?;
+            out.done(format!("Stopped healing `{}/{}`.", path.bucket, path.prefix));
+        }
+    }
+    Ok(())
+}
+
+async fn config(out: &Output, client: &AdminClient, command: ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::Set {
+            target_type,
+            target_name,
+            key_values,
+        } => {
+            let key_values: Vec<(&str, &str)> = key_values.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+            client
+                .set_notification_target(&target_type, &target_name, &key_values)
+                .await?;
+            out.done(format!("Set notification target `{target_type}:{target_name}`."));
+        }
+        ConfigCommand::Remove {
+            target_type,
+            target_name,
+        } => {
+            client.remove_notification_target(&target_type, &target_name).await?;
+            out.done(format!("Removed notification target `{target_type}:{target_name}`."));
+        }
+        ConfigCommand::Targets => {
+            let targets = client.list_notification_targets().await?;
+            let lines: Vec<String> = targets
+                .iter()
+                .map(|target| format!("{}:{}\t{}", target.service, target.account_id, target.status))
+                .collect();
+            out.lines(&targets, lines)?;
+        }
+        ConfigCommand::Backup => {
+            let backup = client.create_config_backup().await?;
+            out.done(format!("Created configuration backup `{}`.", backup.id));
+        }
+        ConfigCommand::Backups => {
+            let backups = client.list_config_backups().await?;
+            let lines: Vec<String> = backups
+                .iter()
+                .map(|backup| format!("{}\t{}\t{}", backup.id, backup.created, backup.size))
+                .collect();
+            out.lines(&backups, lines)?;
+        }
+        ConfigCommand::Restore { time } => {
+            let report = client.restore_config_backup(time).await?;
+            if !report.failed.is_empty() {
+                eprintln!("Objects not restored: {}", report.failed.join(", "));
+            }
+            out.done(format!("Restored configuration backup `{}`, {} objects.", report.backup, report.restored));
+        }
+    }
+    Ok(())
+}
+
+async fn tier(out: &Output, client: &AdminClient, command: TierCommand) -> Result<()> {
+    match command {
+        TierCommand::Add { file, force } => {
+            client.add_tier(&read_json(&file)?, force).await?;
+            out.done(format!("Added tier from `{}`.", file.display()));
+        }
+        TierCommand::List => out.value(&client.list_tiers::<Value>().await?)?,
+        TierCommand::Info { name } => {
+            let tier = client
+                .tier_info::<Value>(&name)
+                .await?
+                .ok_or_else(|| anyhow!("tier '{name}' does not exist"))?;
+            out.value(&tier)?;
+        }
+        TierCommand::Edit {
+            name,
+            access_key,
+            secret_key,
+        } => {
+            let creds = TierCredentials {
+                access_key,
+                secret_key,
+                ..Default::default()
+            };
+            client.edit_tier(&name, &creds).await?;
+            out.done(format!("Updated the credentials of tier `{name}`."));
+        }
+        TierCommand::Remove { name, force } => {
+            client.remove_tier(&name, force).await?;
+            out.done(format!("Removed tier `{name}`."));
+        }
+    }
+    Ok(())
+}
+
+async fn job(out: &Output, client: &AdminClient, command: JobCommand) -> Result<()> {
+    match command {
+        JobCommand::Submit(submit) => match submit {
+            JobSubmit::Backfill {
+                bucket,
+                prefix,
+                since,
+                until,
+            } => out.value(&client.start_event_backfill(&bucket, &prefix, since, until).await?)?,
+            JobSubmit::KeyRotation {
+                key_id,
+                source_version,
+                target_key_id,
+                bucket,
+                prefix,
+            } => {
+                let rotation = KeyRotation {
+                    key_id,
+                    source_version,
+                    target_key_id,
+                    bucket,
+                    prefix,
+                };
+                out.value(&client.start_key_rotation(&rotation).await?)?;
+            }
+            JobSubmit::BucketBackup {
+                bucket,
+                arn,
+                cron,
+                prefix,
+                id,
+                disabled,
+            } => {
+                let schedule = BackupSchedule {
+                    bucket,
+                    prefix,
+                    arn,
+                    cron,
+                    enabled: !disabled,
+                };
+                out.value(&client.set_bucket_backup(&id, &schedule).await?)?;
+            }
+            JobSubmit::BucketBackupRun { id } => out.value(&client.run_bucket_backup(&id).await?)?,
+            JobSubmit::Rebalance => {
+                let id = client.start_rebalance().await?;
+                out.done(format!("Started rebalance `{id}`."));
+            }
+            JobSubmit::Decommission { pools } => {
+                client.start_decommission(&pools).await?;
+                out.done(format!("Started decommissioning pools {pools:?}."));
+            }
+        },
+        JobCommand::Status { kind, id } => {
+            let id = id.unwrap_or_default();
+            let status = match kind {
+                JobKind::Backfill => client.event_backfill_status(&id).await?,
+                JobKind::KeyRotation => client.key_rotation_status(&id).await?,
+                JobKind::BucketBackup => client.bucket_backup_status(&id).await?,
+                JobKind::Rebalance => client.rebalance_status().await?,
+                JobKind::Decommission => client.list_pools().await?,
+            };
+            out.value(&status)?;
+        }
+        JobCommand::Cancel { kind, id } => {
+            let id = id.unwrap_or_default();
+            if id.is_empty() && kind != JobKind::Rebalance {
+                bail!("the id of the job to cancel is required");
+            }
+            match kind {
+                JobKind::Backfill => client.cancel_event_backfill(&id).await?,
+                JobKind::KeyRotation => client.cancel_key_rotation(&id).await?,
+                JobKind::BucketBackup => client.cancel_bucket_backup_run(&id).await?,
+                JobKind::Rebalance => client.stop_rebalance().await?,
+                JobKind::Decommission => {
+                    let pool = id.parse().with_context(|| format!("invalid pool index '{id}'"))?;
+                    client.cancel_decommission(pool).await?;
+                }
+            }
+            out.done("Cancelled the job.".to_string());
+        }
+    }
+    Ok(())
+}
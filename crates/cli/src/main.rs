@@ -0,0 +1,36 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `rustfs-cli`, the command-line management tool of RustFS.
+//!
+//! ```text
+//! rustfs-cli alias set local http://127.0.0.1:9000 rustfsadmin rustfsadmin
+//! rustfs-cli user add alice secret123 --policy readwrite
+//! rustfs-cli heal start photos --recursive
+//! ```
+
+mod alias;
+mod cli;
+mod commands;
+
+use clap::Parser;
+
+#[tokio::main]
+async fn main() {
+    let cli = cli::Cli::parse();
+    if let Err(e) = commands::run(cli).await {
+        eprintln!("rustfs-cli: {e:#}");
+        std::process::exit(1);
+    }
+}
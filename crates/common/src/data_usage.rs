@@ -112,6 +112,9 @@ pub struct BucketUsageInfo {
     pub replica_size: u64,
     pub replica_count: u64,
     pub replication_info: HashMap<String, BucketTargetUsageInfo>,
+    /// Cost-allocation tags of the bucket, filled in from the bucket tagging configuration
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, String>,
 }
 
 /// DataUsageInfo represents data usage stats of the underlying storage
@@ -898,6 +901,20 @@ impl DataUsageInfo {
         Self::default()
     }
 
+    /// Group bucket usage by the value of the cost-allocation tag `key`
+    ///
+    /// Buckets without the tag are accounted under an empty value.
+    pub fn usage_by_tag(&self, key: &str) -> HashMap<String, BucketUsageInfo> {
+        let mut groups: HashMap<String, BucketUsageInfo> = HashMap::new();
+        for usage in self.buckets_usage.values() {
+            let value = usage.tags.get(key).cloned().unwrap_or_default();
+            let group = groups.entry(value.clone()).or_default();
+            group.merge(usage);
+            group.tags.entry(key.to_string()).or_insert(value);
+        }
+        groups
+    }
+
     /// Add object metadata to data usage statistics
     pub fn add_object(&mut self, object_path: &str, meta_object: &rustfs_filemeta::MetaObject) {
         // This method is kept for backward compatibility
@@ -1260,6 +1277,34 @@ mod tests {
         assert!(info.last_update.is_some());
     }
 
+    #[test]
+    fn test_usage_by_tag() {
+        let mut info = DataUsageInfo::new();
+        for (bucket, size, team) in [
+            ("a", 100, Some("web")),
+            ("b", 200, Some("web")),
+            ("c", 50, Some("ml")),
+            ("d", 7, None),
+        ] {
+            let mut usage = BucketUsageInfo {
+                size,
+                objects_count: 1,
+                ..Default::default()
+            };
+            if let Some(team) = team {
+                usage.tags.insert("team".to_string(), team.to_string());
+            }
+            info.buckets_usage.insert(bucket.to_string(), usage);
+        }
+
+        let groups = info.usage_by_tag("team");
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups["web"].size, 300);
+        assert_eq!(groups["web"].objects_count, 2);
+        assert_eq!(groups["ml"].size, 50);
+        assert_eq!(groups[""].size, 7);
+    }
+
     #[test]
     fn test_bucket_usage_info_merge() {
         let mut usage1 = BucketUsageInfo::new();
@@ -0,0 +1,85 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Request deadlines carried in task-local state.
+//!
+//! The HTTP layer opens a scope with the deadline of the API class being served, disk calls and
+//! internode RPCs issued from the same task pick it up so work for an expired request stops
+//! instead of holding locks and disk queues.
+
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+tokio::task_local! {
+    static DEADLINE: Instant;
+}
+
+/// Run `fut` with `deadline` as the current deadline, an earlier outer deadline is kept.
+pub async fn scope<F: Future>(deadline: Option<Instant>, fut: F) -> F::Output {
+    let effective = match (deadline, current()) {
+        (Some(deadline), Some(outer)) => Some(deadline.min(outer)),
+        (deadline, outer) => deadline.or(outer),
+    };
+
+    match effective {
+        Some(deadline) => DEADLINE.scope(deadline, fut).await,
+        None => fut.await,
+    }
+}
+
+/// Deadline of the request served by the current task, if any.
+pub fn current() -> Option<Instant> {
+    DEADLINE.try_with(|deadline| *deadline).ok()
+}
+
+/// Time left until the current deadline, zero once it has passed.
+pub fn remaining() -> Option<Duration> {
+    current().map(|deadline| deadline.saturating_duration_since(Instant::now()))
+}
+
+/// Run `fut` until the current deadline, returning `None` if it expires first.
+pub async fn bounded<F: Future>(fut: F) -> Option<F::Output> {
+    match current() {
+        Some(deadline) => tokio::time::timeout_at(deadline, fut).await.ok(),
+        None => Some(fut.await),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scope_keeps_earliest_deadline() {
+        assert!(current().is_none());
+        assert_eq!(bounded(async { 1 }).await, Some(1));
+
+        let outer = Instant::now() + Duration::from_millis(20);
+        scope(Some(outer), async move {
+            assert_eq!(current(), Some(outer));
+
+            scope(Some(outer + Duration::from_secs(60)), async move {
+                assert_eq!(current(), Some(outer));
+            })
+            .await;
+
+            assert!(remaining().is_some_and(|d| d <= Duration::from_millis(20)));
+            assert_eq!(bounded(tokio::time::sleep(Duration::from_secs(5))).await, None);
+        })
+        .await;
+
+        assert!(current().is_none());
+    }
+}
@@ -15,8 +15,10 @@
 use s3s::dto::{BucketLifecycleConfiguration, ExpirationStatus, LifecycleRule, ReplicationConfiguration, ReplicationRuleStatus};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     fmt::{self, Display},
-    sync::OnceLock,
+    sync::{LazyLock, Mutex, OnceLock},
+    time::SystemTime,
 };
 use tokio::sync::{broadcast, mpsc};
 use uuid::Uuid;
@@ -108,6 +110,9 @@ pub struct HealOpts {
     pub no_lock: bool,
     pub pool: Option<usize>,
     pub set: Option<usize>,
+    /// Rewrite the shards of every drive, not only of the drives found outdated or corrupt.
+    #[serde(default)]
+    pub rewrite: bool,
 }
 
 /// Heal channel command type
@@ -438,6 +443,36 @@ pub async fn send_heal_disk(set_disk_id: String, priority: Option<HealChannelPri
     send_heal_request(req).await
 }
 
+/// When the last erasure set heal of a set finished and whether it succeeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErasureSetHealOutcome {
+    pub finished: SystemTime,
+    pub success: bool,
+}
+
+static ERASURE_SET_HEAL_OUTCOMES: LazyLock<Mutex<HashMap<String, ErasureSetHealOutcome>>> = LazyLock::new(Default::default);
+
+/// Record that a heal of the erasure set `set_disk_id` (`pool_<p>_set_<s>`) finished on this node.
+pub fn record_erasure_set_heal(set_disk_id: &str, success: bool) {
+    let outcome = ErasureSetHealOutcome {
+        finished: SystemTime::now(),
+        success,
+    };
+    ERASURE_SET_HEAL_OUTCOMES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(set_disk_id.to_string(), outcome);
+}
+
+/// The last heal of the erasure set `set_disk_id` that finished on this node.
+pub fn last_erasure_set_heal(set_disk_id: &str) -> Option<ErasureSetHealOutcome> {
+    ERASURE_SET_HEAL_OUTCOMES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(set_disk_id)
+        .copied()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -15,10 +15,13 @@
 pub mod bucket_stats;
 // pub mod error;
 pub mod data_usage;
+pub mod deadline;
 pub mod globals;
 pub mod heal_channel;
 pub mod last_minute;
 pub mod metrics;
+pub mod perf_monitor;
+pub mod scanner_events;
 
 // is ','
 pub static DEFAULT_DELIMITER: u8 = 44;
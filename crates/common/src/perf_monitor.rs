@@ -0,0 +1,196 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per API in-flight request and latency tracking of this node.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub static GLOBAL_PERF_MONITOR: LazyLock<Arc<PerfMonitor>> = LazyLock::new(|| Arc::new(PerfMonitor::default()));
+
+/// Request count and summed latency of each of the last 60 seconds, at millisecond precision.
+struct LatencyWindow {
+    /// `(second, requests, total latency in ms)`, indexed by second modulo 60.
+    slots: [(u64, u64, u64); 60],
+}
+
+impl Default for LatencyWindow {
+    fn default() -> Self {
+        Self { slots: [(0, 0, 0); 60] }
+    }
+}
+
+impl LatencyWindow {
+    fn now_sec() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_secs()
+    }
+
+    fn add(&mut self, latency: Duration) {
+        let sec = Self::now_sec();
+        let slot = &mut self.slots[(sec % 60) as usize];
+        if slot.0 != sec {
+            *slot = (sec, 0, 0);
+        }
+        slot.1 += 1;
+        slot.2 += latency.as_millis() as u64;
+    }
+
+    /// Requests and average latency in ms over the last minute.
+    fn total(&self) -> (u64, u64) {
+        let now = Self::now_sec();
+        let (n, total) = self
+            .slots
+            .iter()
+            .filter(|(sec, _, _)| now.saturating_sub(*sec) < 60)
+            .fold((0, 0), |(n, total), (_, sn, st)| (n + sn, total + st));
+        (n, if n > 0 { total / n } else { 0 })
+    }
+}
+
+#[derive(Default)]
+struct ApiState {
+    /// Start time of every in-flight request, by request id.
+    in_flight: HashMap<u64, SystemTime>,
+    total: u64,
+    last_minute: LatencyWindow,
+}
+
+/// Snapshot of one API on one node.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiPerfStats {
+    pub api: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub node: String,
+    pub in_flight: u64,
+    /// Age of the oldest in-flight request.
+    pub oldest_in_flight_ms: u64,
+    pub total: u64,
+    pub last_minute_requests: u64,
+    pub last_minute_avg_latency_ms: u64,
+}
+
+#[derive(Default)]
+pub struct PerfMonitor {
+    next_id: AtomicU64,
+    apis: Mutex<HashMap<&'static str, ApiState>>,
+}
+
+/// Tracks one request from creation until it is dropped.
+pub struct ApiRequestGuard {
+    monitor: Arc<PerfMonitor>,
+    api: &'static str,
+    id: u64,
+    start: SystemTime,
+}
+
+impl Drop for ApiRequestGuard {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed().unwrap_or(Duration::ZERO);
+        let mut apis = self.monitor.apis.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(state) = apis.get_mut(self.api) {
+            state.in_flight.remove(&self.id);
+            state.last_minute.add(elapsed);
+        }
+    }
+}
+
+impl PerfMonitor {
+    /// Register the start of a request of `api`, it stays in flight until the guard is dropped.
+    pub fn start(self: &Arc<Self>, api: &'static str) -> ApiRequestGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let start = SystemTime::now();
+        let mut apis = self.apis.lock().unwrap_or_else(|e| e.into_inner());
+        let state = apis.entry(api).or_default();
+        state.in_flight.insert(id, start);
+        state.total += 1;
+
+        ApiRequestGuard {
+            monitor: self.clone(),
+            api,
+            id,
+            start,
+        }
+    }
+
+    /// Snapshot of every API seen so far.
+    pub fn stats(&self) -> Vec<ApiPerfStats> {
+        let now = SystemTime::now();
+        let apis = self.apis.lock().unwrap_or_else(|e| e.into_inner());
+        apis.iter()
+            .map(|(api, state)| {
+                let oldest = state.in_flight.values().min().copied();
+                let (last_minute_requests, last_minute_avg_latency_ms) = state.last_minute.total();
+                ApiPerfStats {
+                    api: api.to_string(),
+                    node: String::new(),
+                    in_flight: state.in_flight.len() as u64,
+                    oldest_in_flight_ms: oldest
+                        .and_then(|t| now.duration_since(t).ok())
+                        .map_or(0, |d| d.as_millis() as u64),
+                    total: state.total,
+                    last_minute_requests,
+                    last_minute_avg_latency_ms,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Order APIs so the most likely stuck ones come first: most in flight, then oldest in-flight
+/// request, then slowest over the last minute. Keeps at most `count` entries.
+pub fn sort_top_apis(stats: &mut Vec<ApiPerfStats>, count: usize) {
+    stats.sort_by(|a, b| {
+        b.in_flight
+            .cmp(&a.in_flight)
+            .then(b.oldest_in_flight_ms.cmp(&a.oldest_in_flight_ms))
+            .then(b.last_minute_avg_latency_ms.cmp(&a.last_minute_avg_latency_ms))
+            .then(a.api.cmp(&b.api))
+    });
+    stats.truncate(count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perf_monitor_in_flight() {
+        let monitor = Arc::new(PerfMonitor::default());
+        let get1 = monitor.start("GetObject");
+        let _get2 = monitor.start("GetObject");
+        drop(monitor.start("PutObject"));
+        drop(get1);
+
+        let mut stats = monitor.stats();
+        sort_top_apis(&mut stats, 10);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].api, "GetObject");
+        assert_eq!(stats[0].in_flight, 1);
+        assert_eq!(stats[0].total, 2);
+        assert_eq!(stats[0].last_minute_requests, 1);
+        assert_eq!(stats[1].api, "PutObject");
+        assert_eq!(stats[1].in_flight, 0);
+        assert_eq!(stats[1].oldest_in_flight_ms, 0);
+
+        sort_top_apis(&mut stats, 1);
+        assert_eq!(stats.len(), 1);
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::data_usage::BucketUsageInfo;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use tokio::sync::broadcast;
+
+/// What a scanner cycle of this node found in one bucket, published once the cycle is done with it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BucketCycleSummary {
+    pub bucket: String,
+    pub cycle: u64,
+    pub objects_count: u64,
+    pub versions_count: u64,
+    pub delete_markers_count: u64,
+    pub size: u64,
+    /// Changes since the previous cycle, zero for the first cycle after a start
+    pub objects_delta: i64,
+    pub versions_delta: i64,
+    pub size_delta: i64,
+    /// Objects the cycle queued for healing
+    pub heals_queued: u64,
+}
+
+impl BucketCycleSummary {
+    pub fn new(bucket: &str, cycle: u64, usage: &BucketUsageInfo, previous: Option<&BucketUsageInfo>, heals_queued: u64) -> Self {
+        let delta = |now: u64, before: u64| (now as i64).saturating_sub(before as i64);
+        let previous = previous.unwrap_or(usage);
+        Self {
+            bucket: bucket.to_string(),
+            cycle,
+            objects_count: usage.objects_count,
+            versions_count: usage.versions_count,
+            delete_markers_count: usage.delete_markers_count,
+            size: usage.size,
+            objects_delta: delta(usage.objects_count, previous.objects_count),
+            versions_delta: delta(usage.versions_count, previous.versions_count),
+            size_delta: delta(usage.size, previous.size),
+            heals_queued,
+        }
+    }
+
+    /// Whether anything changed since the previous cycle.
+    pub fn has_changes(&self) -> bool {
+        self.objects_delta != 0 || self.versions_delta != 0 || self.size_delta != 0 || self.heals_queued > 0
+    }
+}
+
+static GLOBAL_BUCKET_CYCLE_SENDER: OnceLock<broadcast::Sender<BucketCycleSummary>> = OnceLock::new();
+
+fn bucket_cycle_sender() -> &'static broadcast::Sender<BucketCycleSummary> {
+    GLOBAL_BUCKET_CYCLE_SENDER.get_or_init(|| {
+        let (tx, _rx) = broadcast::channel(1024);
+        tx
+    })
+}
+
+/// Publish the summary of a finished bucket, dropped when nobody subscribed.
+pub fn publish_bucket_cycle(summary: BucketCycleSummary) {
+    let _ = bucket_cycle_sender().send(summary);
+}
+
+/// Subscribe to the summaries of finished buckets.
+pub fn subscribe_bucket_cycles() -> broadcast::Receiver<BucketCycleSummary> {
+    bucket_cycle_sender().subscribe()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(objects: u64, size: u64) -> BucketUsageInfo {
+        BucketUsageInfo {
+            objects_count: objects,
+            versions_count: objects,
+            size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bucket_cycle_summary_deltas() {
+        let first = BucketCycleSummary::new("bucket", 1, &usage(10, 1000), None, 0);
+        assert_eq!(first.objects_delta, 0);
+        assert_eq!(first.size_delta, 0);
+        assert!(!first.has_changes());
+
+        let second = BucketCycleSummary::new("bucket", 2, &usage(7, 1500), Some(&usage(10, 1000)), 0);
+        assert_eq!(second.objects_delta, -3);
+        assert_eq!(second.versions_delta, -3);
+        assert_eq!(second.size_delta, 500);
+        assert!(second.has_changes());
+
+        let healed = BucketCycleSummary::new("bucket", 3, &usage(7, 1500), Some(&usage(7, 1500)), 2);
+        assert!(healed.has_changes());
+    }
+
+    #[tokio::test]
+    async fn test_bucket_cycle_broadcast_reaches_subscriber() {
+        let mut receiver = subscribe_bucket_cycles();
+        let summary = BucketCycleSummary::new("bucket", 4, &usage(1, 1), None, 0);
+
+        publish_bucket_cycle(summary.clone());
+
+        assert_eq!(receiver.recv().await.expect("should receive summary"), summary);
+    }
+}
@@ -0,0 +1,58 @@
+//  Copyright 2024 RustFS Team
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+/// Timeout in seconds for listing requests (ListBuckets, ListObjects, ListObjectVersions, ...)
+pub const ENV_API_TIMEOUT_LIST: &str = "RUSTFS_API_TIMEOUT_LIST";
+/// Timeout in seconds for read requests until the response starts (GetObject, HeadObject, ...)
+pub const ENV_API_TIMEOUT_READ: &str = "RUSTFS_API_TIMEOUT_READ";
+/// Timeout in seconds for write requests, including the time to receive the body (PutObject, UploadPart, ...)
+pub const ENV_API_TIMEOUT_WRITE: &str = "RUSTFS_API_TIMEOUT_WRITE";
+/// Timeout in seconds for admin API requests
+pub const ENV_API_TIMEOUT_ADMIN: &str = "RUSTFS_API_TIMEOUT_ADMIN";
+
+// Default API timeouts in seconds, 0 disables the timeout of the class
+pub const DEFAULT_API_TIMEOUT_LIST: u64 = 300;
+pub const DEFAULT_API_TIMEOUT_READ: u64 = 300;
+pub const DEFAULT_API_TIMEOUT_WRITE: u64 = 0;
+pub const DEFAULT_API_TIMEOUT_ADMIN: u64 = 0;
+
+/// Initial HTTP/2 flow-control window of a stream in bytes
+pub const ENV_HTTP2_STREAM_WINDOW_SIZE: &str = "RUSTFS_HTTP2_STREAM_WINDOW_SIZE";
+/// Initial HTTP/2 flow-control window of a connection in bytes
+pub const ENV_HTTP2_CONNECTION_WINDOW_SIZE: &str = "RUSTFS_HTTP2_CONNECTION_WINDOW_SIZE";
+/// Size the HTTP/2 windows from the measured bandwidth-delay product instead of the fixed sizes
+pub const ENV_HTTP2_ADAPTIVE_WINDOW: &str = "RUSTFS_HTTP2_ADAPTIVE_WINDOW";
+/// Maximum number of concurrent HTTP/2 streams of a connection
+pub const ENV_HTTP2_MAX_CONCURRENT_STREAMS: &str = "RUSTFS_HTTP2_MAX_CONCURRENT_STREAMS";
+/// Maximum HTTP/2 frame size in bytes, between 16 KiB and 16 MiB
+pub const ENV_HTTP2_MAX_FRAME_SIZE: &str = "RUSTFS_HTTP2_MAX_FRAME_SIZE";
+/// Maximum bytes buffered per HTTP/2 stream while sending a response
+pub const ENV_HTTP2_MAX_SEND_BUF_SIZE: &str = "RUSTFS_HTTP2_MAX_SEND_BUF_SIZE";
+
+// Windows sized for multi-megabyte object bodies rather than the 64 KiB of the protocol default
+pub const DEFAULT_HTTP2_STREAM_WINDOW_SIZE: u32 = 4 * 1024 * 1024;
+pub const DEFAULT_HTTP2_CONNECTION_WINDOW_SIZE: u32 = 16 * 1024 * 1024;
+pub const DEFAULT_HTTP2_ADAPTIVE_WINDOW: bool = false;
+pub const DEFAULT_HTTP2_MAX_CONCURRENT_STREAMS: u32 = 256;
+pub const DEFAULT_HTTP2_MAX_FRAME_SIZE: u32 = 1024 * 1024;
+pub const DEFAULT_HTTP2_MAX_SEND_BUF_SIZE: usize = 4 * 1024 * 1024;
+
+/// UDP address of the experimental HTTP/3 listener, e.g. ":9000", empty disables it.
+/// Needs TLS and a build with the `http3` feature.
+pub const ENV_HTTP3_ADDRESS: &str = "RUSTFS_HTTP3_ADDRESS";
+pub const DEFAULT_HTTP3_ADDRESS: &str = "";
+
+/// Number of recent requests kept by request id for the admin request lookup, 0 disables it
+pub const ENV_REQUEST_LOG_SIZE: &str = "RUSTFS_REQUEST_LOG_SIZE";
+pub const DEFAULT_REQUEST_LOG_SIZE: usize = 10_000;
@@ -0,0 +1,37 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// Config sub-system of the request authentication chain.
+pub const AUTH_CHAIN_SUB_SYS: &str = "auth_chain";
+
+/// Comma separated names of the authenticators S3 requests go through, in order. `sigv4`, the
+/// AWS signature check, comes last and is implied when missing.
+pub const AUTH_CHAIN_ORDER: &str = "order";
+
+/// Environment variable overriding the `order` of the authentication chain.
+pub const ENV_AUTH_CHAIN_ORDER: &str = "RUSTFS_AUTH_CHAIN_ORDER";
+
+pub const DEFAULT_AUTH_CHAIN_ORDER: &str = "sigv4";
+
+/// Name of the AWS signature check in the authentication chain.
+pub const AUTH_CHAIN_SIGV4: &str = "sigv4";
+
+/// Name of the authenticator accepting signed `x-rustfs-auth` tokens.
+pub const AUTH_CHAIN_HMAC_TOKEN: &str = "hmac_token";
+
+/// Environment variable for how often, in seconds, each node persists when its access keys were
+/// last used and reloads what the other nodes recorded. Set to 0 to only keep them in memory.
+pub const ENV_IAM_LAST_USED_INTERVAL: &str = "RUSTFS_IAM_LAST_USED_INTERVAL";
+
+pub const DEFAULT_IAM_LAST_USED_INTERVAL: u64 = 5 * 60;
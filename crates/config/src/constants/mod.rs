@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub(crate) mod api;
 pub(crate) mod app;
+pub(crate) mod auth;
 pub(crate) mod console;
 pub(crate) mod env;
 pub(crate) mod profiler;
 pub(crate) mod runtime;
+pub(crate) mod storage;
 pub(crate) mod targets;
 pub(crate) mod tls;
@@ -0,0 +1,217 @@
+//  Copyright 2024 RustFS Team
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+/// Environment variable for the reserved free-space watermark of a drive, in percent.
+/// Once free space on a drive drops below this value, new object writes are refused.
+/// Set to 0 to disable the watermark check.
+pub const ENV_DRIVE_RESERVED_WATERMARK: &str = "RUSTFS_DRIVE_RESERVED_WATERMARK";
+
+/// Environment variable for the free-space percentage at which a read-only drive accepts writes again.
+/// Values below the reserved watermark are raised to the reserved watermark.
+pub const ENV_DRIVE_RESUME_WATERMARK: &str = "RUSTFS_DRIVE_RESUME_WATERMARK";
+
+/// Environment variable for the interval, in seconds, at which local drives re-evaluate their watermark state.
+pub const ENV_DRIVE_WATERMARK_CHECK_INTERVAL: &str = "RUSTFS_DRIVE_WATERMARK_CHECK_INTERVAL";
+
+pub const DEFAULT_DRIVE_RESERVED_WATERMARK: f64 = 2.0;
+pub const DEFAULT_DRIVE_RESUME_WATERMARK: f64 = 5.0;
+pub const DEFAULT_DRIVE_WATERMARK_CHECK_INTERVAL: u64 = 10;
+
+/// Environment variable that makes single-part uploads report the MD5 of the uploaded data as ETag
+/// even when the object is stored compressed or encrypted.
+pub const ENV_ETAG_FULL_OBJECT_MD5: &str = "RUSTFS_ETAG_FULL_OBJECT_MD5";
+
+pub const DEFAULT_ETAG_FULL_OBJECT_MD5: bool = false;
+
+/// Environment variable holding disk fault injection rules as a JSON array, read in debug builds only.
+pub const ENV_DISK_FAULTS: &str = "RUSTFS_DISK_FAULTS";
+
+/// Environment variable for the time, in seconds, listing results are served from the listing cache.
+/// Set to 0 to disable the listing cache.
+pub const ENV_LIST_CACHE_TTL: &str = "RUSTFS_LIST_CACHE_TTL";
+
+/// Environment variable for the interval, in milliseconds, at which invalidations of the listing cache
+/// caused by writes are sent to the other nodes. Bounds how long a peer may serve a listing that misses a write.
+pub const ENV_LIST_CACHE_INVALIDATION_INTERVAL: &str = "RUSTFS_LIST_CACHE_INVALIDATION_INTERVAL";
+
+pub const DEFAULT_LIST_CACHE_TTL: u64 = 0;
+pub const DEFAULT_LIST_CACHE_INVALIDATION_INTERVAL: u64 = 100;
+
+/// Environment variable for the number of erasure blocks a large GET reads and decodes ahead of
+/// the block being sent to the client. Set to 0 to disable read-ahead.
+pub const ENV_GET_PREFETCH_DEPTH: &str = "RUSTFS_GET_PREFETCH_DEPTH";
+
+/// Environment variable for the memory, in MiB, all GETs together may hold in read-ahead blocks.
+/// A GET that does not fit in the remaining budget reads without read-ahead.
+pub const ENV_GET_PREFETCH_MEMORY: &str = "RUSTFS_GET_PREFETCH_MEMORY";
+
+pub const DEFAULT_GET_PREFETCH_DEPTH: u64 = 2;
+pub const DEFAULT_GET_PREFETCH_MEMORY: u64 = 256;
+
+/// Environment variable for the memory, in MiB, the erasure blocks of all in-flight PUTs and GETs of
+/// this node may hold together. Transfers that do not fit wait for memory to be released, then fail
+/// with SlowDown. Set to 0 to disable the budget.
+pub const ENV_MEMORY_BUDGET: &str = "RUSTFS_MEMORY_BUDGET";
+
+/// Environment variable for how long, in milliseconds, a transfer waits for memory of the budget
+/// before failing with SlowDown. Set to 0 to fail at once.
+pub const ENV_MEMORY_BUDGET_WAIT: &str = "RUSTFS_MEMORY_BUDGET_WAIT";
+
+pub const DEFAULT_MEMORY_BUDGET: u64 = 0;
+pub const DEFAULT_MEMORY_BUDGET_WAIT: u64 = 1000;
+
+/// Environment variable for the memory, in MiB, the cache of raw xl.meta read by listings may hold.
+/// Set to 0 to disable the cache.
+pub const ENV_XL_META_CACHE_SIZE: &str = "RUSTFS_XL_META_CACHE_SIZE";
+
+pub const DEFAULT_XL_META_CACHE_SIZE: u64 = 64;
+
+/// Environment variable for the memory, in MiB, this node may hold in copies of hot objects served
+/// to GETs without reading the drives. Set to 0 to disable the hot object cache.
+pub const ENV_HOT_OBJECT_CACHE_SIZE: &str = "RUSTFS_HOT_OBJECT_CACHE_SIZE";
+
+/// Environment variable for the size, in MiB, above which a hot object is not held in memory.
+pub const ENV_HOT_OBJECT_MAX_SIZE: &str = "RUSTFS_HOT_OBJECT_MAX_SIZE";
+
+/// Environment variable for the number of reads of one object within `RUSTFS_HOT_OBJECT_WINDOW`
+/// that make it hot.
+pub const ENV_HOT_OBJECT_THRESHOLD: &str = "RUSTFS_HOT_OBJECT_THRESHOLD";
+
+/// Environment variable for the window, in seconds, reads of an object are counted over.
+pub const ENV_HOT_OBJECT_WINDOW: &str = "RUSTFS_HOT_OBJECT_WINDOW";
+
+pub const DEFAULT_HOT_OBJECT_CACHE_SIZE: u64 = 0;
+pub const DEFAULT_HOT_OBJECT_MAX_SIZE: u64 = 8;
+pub const DEFAULT_HOT_OBJECT_THRESHOLD: u64 = 100;
+pub const DEFAULT_HOT_OBJECT_WINDOW: u64 = 10;
+
+/// Environment variable enabling the background migration of drives to the format version of this
+/// build. Turn it off during a rolling upgrade until every node runs a build supporting the new version,
+/// nodes that do not support the format of a drive refuse to start.
+pub const ENV_FORMAT_MIGRATION_ENABLE: &str = "RUSTFS_FORMAT_MIGRATION_ENABLE";
+
+pub const DEFAULT_FORMAT_MIGRATION_ENABLE: bool = true;
+
+/// Environment variable for the directory of the metadata search index of this node, which indexes
+/// the user metadata and tags of objects. The index is disabled when empty.
+pub const ENV_METADATA_INDEX_DIR: &str = "RUSTFS_METADATA_INDEX_DIR";
+
+/// Environment variable for the largest size, in MiB, the metadata search index may grow to.
+pub const ENV_METADATA_INDEX_MAX_SIZE: &str = "RUSTFS_METADATA_INDEX_MAX_SIZE";
+
+/// Environment variable for the number of objects each scanner cycle reads into the metadata search
+/// index, picking up objects written through other nodes or before the index was enabled.
+pub const ENV_METADATA_INDEX_BACKFILL_BATCH: &str = "RUSTFS_METADATA_INDEX_BACKFILL_BATCH";
+
+pub const DEFAULT_METADATA_INDEX_DIR: &str = "";
+pub const DEFAULT_METADATA_INDEX_MAX_SIZE: u64 = 10 * 1024;
+pub const DEFAULT_METADATA_INDEX_BACKFILL_BATCH: u64 = 10_000;
+
+/// Environment variable for the number of nodes the DNS names of `dns://` and `dns+srv://` volumes
+/// must resolve to before the node starts. When 0, the first answer seen twice in a row is used.
+pub const ENV_DNS_DISCOVERY_NODES: &str = "RUSTFS_DNS_DISCOVERY_NODES";
+
+/// Environment variable for how long, in seconds, startup waits for the DNS names of the volumes to
+/// resolve to the expected nodes.
+pub const ENV_DNS_DISCOVERY_TIMEOUT: &str = "RUSTFS_DNS_DISCOVERY_TIMEOUT";
+
+/// Environment variable for how often, in seconds, the DNS names of the volumes are resolved again
+/// to follow replaced nodes.
+pub const ENV_DNS_DISCOVERY_INTERVAL: &str = "RUSTFS_DNS_DISCOVERY_INTERVAL";
+
+pub const DEFAULT_DNS_DISCOVERY_NODES: usize = 0;
+pub const DEFAULT_DNS_DISCOVERY_TIMEOUT: u64 = 300;
+pub const DEFAULT_DNS_DISCOVERY_INTERVAL: u64 = 30;
+
+/// Environment variable enabling the replicated log of IAM config changes, which orders
+/// the changes cluster-wide and brings them to the caches of every node.
+pub const ENV_CONFIG_LOG_ENABLE: &str = "RUSTFS_CONFIG_LOG_ENABLE";
+
+/// Environment variable for how often, in seconds, each node reads the new entries of the config log.
+pub const ENV_CONFIG_LOG_POLL_INTERVAL: &str = "RUSTFS_CONFIG_LOG_POLL_INTERVAL";
+
+/// Environment variable for the number of entries the config log keeps when it is compacted. Nodes
+/// that fall further behind reload their caches entirely.
+pub const ENV_CONFIG_LOG_RETAIN: &str = "RUSTFS_CONFIG_LOG_RETAIN";
+
+pub const DEFAULT_CONFIG_LOG_ENABLE: bool = true;
+pub const DEFAULT_CONFIG_LOG_POLL_INTERVAL: u64 = 2;
+pub const DEFAULT_CONFIG_LOG_RETAIN: u64 = 100;
+
+/// Environment variable for how old, in seconds, a data directory without a referencing xl.meta
+/// version must be before the deep scan reports it as orphaned. Younger ones may belong to writes
+/// still in flight.
+pub const ENV_ORPHANED_DATA_MIN_AGE: &str = "RUSTFS_ORPHANED_DATA_MIN_AGE";
+
+/// Environment variable enabling the deep scan to remove the orphaned data directories it finds
+/// instead of only reporting them.
+pub const ENV_ORPHANED_DATA_RECLAIM: &str = "RUSTFS_ORPHANED_DATA_RECLAIM";
+
+pub const DEFAULT_ORPHANED_DATA_MIN_AGE: u64 = 24 * 60 * 60;
+pub const DEFAULT_ORPHANED_DATA_RECLAIM: bool = false;
+
+/// Environment variable for the bandwidth, in MiB per second, heal may use on this node to read
+/// and write shards. Set to 0 for no limit. Limits set through the admin API take precedence.
+pub const ENV_BANDWIDTH_HEAL: &str = "RUSTFS_BANDWIDTH_HEAL";
+
+/// Environment variable for the bandwidth, in MiB per second, rebalancing may use on this node to
+/// move objects between pools. Set to 0 for no limit.
+pub const ENV_BANDWIDTH_REBALANCE: &str = "RUSTFS_BANDWIDTH_REBALANCE";
+
+/// Environment variable for the bandwidth, in MiB per second, decommissioning may use on this node
+/// to move objects off a pool. Set to 0 for no limit.
+pub const ENV_BANDWIDTH_DECOMMISSION: &str = "RUSTFS_BANDWIDTH_DECOMMISSION";
+
+/// Environment variable for the bandwidth, in MiB per second, replication may use on this node to
+/// send objects to remote targets. Set to 0 for no limit.
+pub const ENV_BANDWIDTH_REPLICATION: &str = "RUSTFS_BANDWIDTH_REPLICATION";
+
+pub const DEFAULT_BANDWIDTH_HEAL: u64 = 0;
+pub const DEFAULT_BANDWIDTH_REBALANCE: u64 = 0;
+pub const DEFAULT_BANDWIDTH_DECOMMISSION: u64 = 0;
+pub const DEFAULT_BANDWIDTH_REPLICATION: u64 = 0;
+
+/// Environment variable for how often, in seconds, the cluster takes a backup of the system
+/// configuration: IAM, bucket metadata and the server config. Set to 0 to only take backups
+/// through the admin API.
+pub const ENV_CONFIG_BACKUP_INTERVAL: &str = "RUSTFS_CONFIG_BACKUP_INTERVAL";
+
+/// Environment variable for the number of configuration backups kept, older ones are deleted.
+pub const ENV_CONFIG_BACKUP_RETAIN: &str = "RUSTFS_CONFIG_BACKUP_RETAIN";
+
+pub const DEFAULT_CONFIG_BACKUP_INTERVAL: u64 = 24 * 60 * 60;
+pub const DEFAULT_CONFIG_BACKUP_RETAIN: u64 = 14;
+
+/// Environment variable with the comma separated etcd endpoints, e.g. `http://etcd-1:2379`, of the
+/// directory federated clusters share to own each bucket name once. Empty disables the directory.
+pub const ENV_FEDERATION_ETCD_ENDPOINTS: &str = "RUSTFS_FEDERATION_ETCD_ENDPOINTS";
+
+/// Environment variable for the etcd key prefix under which the bucket owners are registered.
+pub const ENV_FEDERATION_ETCD_PREFIX: &str = "RUSTFS_FEDERATION_ETCD_PREFIX";
+
+/// Environment variable for the `scheme://host[:port]` clients reach this cluster at, registered
+/// as the owner of the buckets created here and the target of redirects from the other clusters.
+pub const ENV_FEDERATION_ENDPOINT: &str = "RUSTFS_FEDERATION_ENDPOINT";
+
+pub const DEFAULT_FEDERATION_ETCD_PREFIX: &str = "/rustfs/buckets/";
+
+/// Environment variable for how often, in seconds, the SMART health of the local drives is
+/// collected with smartctl. Set to 0 to disable the collection.
+pub const ENV_DRIVE_SMART_INTERVAL: &str = "RUSTFS_DRIVE_SMART_INTERVAL";
+
+/// Environment variable for the smartctl binary, looked up in `PATH` unless it is a path.
+pub const ENV_DRIVE_SMARTCTL: &str = "RUSTFS_DRIVE_SMARTCTL";
+
+pub const DEFAULT_DRIVE_SMART_INTERVAL: u64 = 60 * 60;
+pub const DEFAULT_DRIVE_SMARTCTL: &str = "smartctl";
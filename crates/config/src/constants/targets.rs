@@ -32,3 +32,39 @@ pub const MQTT_RECONNECT_INTERVAL: &str = "reconnect_interval";
 pub const MQTT_KEEP_ALIVE_INTERVAL: &str = "keep_alive_interval";
 pub const MQTT_QUEUE_DIR: &str = "queue_dir";
 pub const MQTT_QUEUE_LIMIT: &str = "queue_limit";
+
+pub const POSTGRES_CONNECTION_STRING: &str = "connection_string";
+pub const POSTGRES_TABLE: &str = "table";
+pub const POSTGRES_FORMAT: &str = "format";
+pub const POSTGRES_MAX_OPEN_CONNECTIONS: &str = "max_open_connections";
+pub const POSTGRES_BATCH_SIZE: &str = "batch_size";
+pub const POSTGRES_QUEUE_DIR: &str = "queue_dir";
+pub const POSTGRES_QUEUE_LIMIT: &str = "queue_limit";
+
+pub const MYSQL_DSN_STRING: &str = "dsn_string";
+pub const MYSQL_TABLE: &str = "table";
+pub const MYSQL_FORMAT: &str = "format";
+pub const MYSQL_MAX_OPEN_CONNECTIONS: &str = "max_open_connections";
+pub const MYSQL_BATCH_SIZE: &str = "batch_size";
+pub const MYSQL_QUEUE_DIR: &str = "queue_dir";
+pub const MYSQL_QUEUE_LIMIT: &str = "queue_limit";
+
+/// Layouts of the SQL and Elasticsearch targets: `namespace` keeps one row or document per object, `access` appends one
+/// per event.
+pub const TARGET_FORMAT_NAMESPACE: &str = "namespace";
+pub const TARGET_FORMAT_ACCESS: &str = "access";
+pub const DEFAULT_SQL_MAX_OPEN_CONNECTIONS: u32 = 2;
+pub const DEFAULT_SQL_BATCH_SIZE: usize = 1;
+
+pub const ELASTICSEARCH_URL: &str = "url";
+pub const ELASTICSEARCH_INDEX: &str = "index";
+pub const ELASTICSEARCH_FORMAT: &str = "format";
+pub const ELASTICSEARCH_USERNAME: &str = "username";
+pub const ELASTICSEARCH_PASSWORD: &str = "password";
+pub const ELASTICSEARCH_INCLUDE_METADATA: &str = "include_metadata";
+pub const ELASTICSEARCH_BATCH_SIZE: &str = "batch_size";
+pub const ELASTICSEARCH_MAX_RETRY: &str = "max_retry";
+pub const ELASTICSEARCH_QUEUE_DIR: &str = "queue_dir";
+pub const ELASTICSEARCH_QUEUE_LIMIT: &str = "queue_limit";
+pub const DEFAULT_ELASTICSEARCH_BATCH_SIZE: usize = 1;
+pub const DEFAULT_ELASTICSEARCH_MAX_RETRY: u32 = 3;
@@ -0,0 +1,56 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// A list of all valid configuration keys for an Elasticsearch target.
+pub const NOTIFY_ELASTICSEARCH_KEYS: &[&str] = &[
+    crate::ENABLE_KEY,
+    crate::ELASTICSEARCH_URL,
+    crate::ELASTICSEARCH_INDEX,
+    crate::ELASTICSEARCH_FORMAT,
+    crate::ELASTICSEARCH_USERNAME,
+    crate::ELASTICSEARCH_PASSWORD,
+    crate::ELASTICSEARCH_INCLUDE_METADATA,
+    crate::ELASTICSEARCH_BATCH_SIZE,
+    crate::ELASTICSEARCH_MAX_RETRY,
+    crate::ELASTICSEARCH_QUEUE_DIR,
+    crate::ELASTICSEARCH_QUEUE_LIMIT,
+    crate::COMMENT_KEY,
+];
+
+// Elasticsearch Environment Variables
+pub const ENV_NOTIFY_ELASTICSEARCH_ENABLE: &str = "RUSTFS_NOTIFY_ELASTICSEARCH_ENABLE";
+pub const ENV_NOTIFY_ELASTICSEARCH_URL: &str = "RUSTFS_NOTIFY_ELASTICSEARCH_URL";
+pub const ENV_NOTIFY_ELASTICSEARCH_INDEX: &str = "RUSTFS_NOTIFY_ELASTICSEARCH_INDEX";
+pub const ENV_NOTIFY_ELASTICSEARCH_FORMAT: &str = "RUSTFS_NOTIFY_ELASTICSEARCH_FORMAT";
+pub const ENV_NOTIFY_ELASTICSEARCH_USERNAME: &str = "RUSTFS_NOTIFY_ELASTICSEARCH_USERNAME";
+pub const ENV_NOTIFY_ELASTICSEARCH_PASSWORD: &str = "RUSTFS_NOTIFY_ELASTICSEARCH_PASSWORD";
+pub const ENV_NOTIFY_ELASTICSEARCH_INCLUDE_METADATA: &str = "RUSTFS_NOTIFY_ELASTICSEARCH_INCLUDE_METADATA";
+pub const ENV_NOTIFY_ELASTICSEARCH_BATCH_SIZE: &str = "RUSTFS_NOTIFY_ELASTICSEARCH_BATCH_SIZE";
+pub const ENV_NOTIFY_ELASTICSEARCH_MAX_RETRY: &str = "RUSTFS_NOTIFY_ELASTICSEARCH_MAX_RETRY";
+pub const ENV_NOTIFY_ELASTICSEARCH_QUEUE_DIR: &str = "RUSTFS_NOTIFY_ELASTICSEARCH_QUEUE_DIR";
+pub const ENV_NOTIFY_ELASTICSEARCH_QUEUE_LIMIT: &str = "RUSTFS_NOTIFY_ELASTICSEARCH_QUEUE_LIMIT";
+
+pub const ENV_NOTIFY_ELASTICSEARCH_KEYS: &[&str; 11] = &[
+    ENV_NOTIFY_ELASTICSEARCH_ENABLE,
+    ENV_NOTIFY_ELASTICSEARCH_URL,
+    ENV_NOTIFY_ELASTICSEARCH_INDEX,
+    ENV_NOTIFY_ELASTICSEARCH_FORMAT,
+    ENV_NOTIFY_ELASTICSEARCH_USERNAME,
+    ENV_NOTIFY_ELASTICSEARCH_PASSWORD,
+    ENV_NOTIFY_ELASTICSEARCH_INCLUDE_METADATA,
+    ENV_NOTIFY_ELASTICSEARCH_BATCH_SIZE,
+    ENV_NOTIFY_ELASTICSEARCH_MAX_RETRY,
+    ENV_NOTIFY_ELASTICSEARCH_QUEUE_DIR,
+    ENV_NOTIFY_ELASTICSEARCH_QUEUE_LIMIT,
+];
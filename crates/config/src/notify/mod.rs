@@ -13,12 +13,18 @@
 // limitations under the License.
 
 mod arn;
+mod elasticsearch;
 mod mqtt;
+mod mysql;
+mod postgres;
 mod store;
 mod webhook;
 
 pub use arn::*;
+pub use elasticsearch::*;
 pub use mqtt::*;
+pub use mysql::*;
+pub use postgres::*;
 pub use store::*;
 pub use webhook::*;
 
@@ -31,23 +37,40 @@ pub const NOTIFY_PREFIX: &str = "notify";
 
 pub const NOTIFY_ROUTE_PREFIX: &str = const_str::concat!(NOTIFY_PREFIX, DEFAULT_DELIMITER);
 
+/// Whether event records carry the `rustfs` extension block (content type, user metadata, source)
+/// next to the standard S3 event fields.
+pub const ENV_NOTIFY_EXTENDED_METADATA: &str = "RUSTFS_NOTIFY_EXTENDED_METADATA";
+pub const DEFAULT_NOTIFY_EXTENDED_METADATA: bool = true;
+
+/// Directory of the write-ahead journal of dispatched events, one subdirectory per target.
+/// Empty disables the journal.
+pub const ENV_NOTIFY_JOURNAL_DIR: &str = "RUSTFS_NOTIFY_JOURNAL_DIR";
+pub const DEFAULT_NOTIFY_JOURNAL_DIR: &str = "";
+
+/// Hours delivered events stay in the journal and can be replayed.
+pub const ENV_NOTIFY_JOURNAL_RETENTION_HOURS: &str = "RUSTFS_NOTIFY_JOURNAL_RETENTION_HOURS";
+pub const DEFAULT_NOTIFY_JOURNAL_RETENTION_HOURS: u64 = 24;
+
 #[allow(dead_code)]
-pub const NOTIFY_SUB_SYSTEMS: &[&str] = &[NOTIFY_MQTT_SUB_SYS, NOTIFY_WEBHOOK_SUB_SYS];
+pub const NOTIFY_SUB_SYSTEMS: &[&str] = &[
+    NOTIFY_ES_SUB_SYS,
+    NOTIFY_MQTT_SUB_SYS,
+    NOTIFY_MY_SQL_SUB_SYS,
+    NOTIFY_POSTGRES_SUB_SYS,
+    NOTIFY_WEBHOOK_SUB_SYS,
+];
 
 #[allow(dead_code)]
 pub const NOTIFY_KAFKA_SUB_SYS: &str = "notify_kafka";
 pub const NOTIFY_MQTT_SUB_SYS: &str = "notify_mqtt";
-#[allow(dead_code)]
 pub const NOTIFY_MY_SQL_SUB_SYS: &str = "notify_mysql";
 #[allow(dead_code)]
 pub const NOTIFY_NATS_SUB_SYS: &str = "notify_nats";
 #[allow(dead_code)]
 pub const NOTIFY_NSQ_SUB_SYS: &str = "notify_nsq";
-#[allow(dead_code)]
 pub const NOTIFY_ES_SUB_SYS: &str = "notify_elasticsearch";
 #[allow(dead_code)]
 pub const NOTIFY_AMQP_SUB_SYS: &str = "notify_amqp";
-#[allow(dead_code)]
 pub const NOTIFY_POSTGRES_SUB_SYS: &str = "notify_postgres";
 #[allow(dead_code)]
 pub const NOTIFY_REDIS_SUB_SYS: &str = "notify_redis";
@@ -0,0 +1,51 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! OpenID Connect identity provider env vars.
+//!
+//! The default provider is configured by the plain keys below. Additional providers are
+//! configured by appending `_<NAME>` to every key, e.g. `RUSTFS_IDENTITY_OPENID_CONFIG_URL_KEYCLOAK`.
+
+pub const IDENTITY_OPENID_SUB_SYS: &str = "identity_openid";
+
+/// Discovery document of the provider (`.../.well-known/openid-configuration`).
+pub const ENV_IDENTITY_OPENID_CONFIG_URL: &str = "RUSTFS_IDENTITY_OPENID_CONFIG_URL";
+/// Comma separated client ids accepted in the `aud` claim.
+pub const ENV_IDENTITY_OPENID_CLIENT_ID: &str = "RUSTFS_IDENTITY_OPENID_CLIENT_ID";
+/// Overrides the issuer announced by the discovery document.
+pub const ENV_IDENTITY_OPENID_ISSUER: &str = "RUSTFS_IDENTITY_OPENID_ISSUER";
+/// Overrides the `jwks_uri` announced by the discovery document.
+pub const ENV_IDENTITY_OPENID_JWKS_URL: &str = "RUSTFS_IDENTITY_OPENID_JWKS_URL";
+/// Claim holding the policies (or groups/roles) of the user, defaults to `policy`.
+pub const ENV_IDENTITY_OPENID_CLAIM_NAME: &str = "RUSTFS_IDENTITY_OPENID_CLAIM_NAME";
+/// Prefix prepended to every claim value to form the policy name.
+pub const ENV_IDENTITY_OPENID_CLAIM_PREFIX: &str = "RUSTFS_IDENTITY_OPENID_CLAIM_PREFIX";
+/// Comma separated policies granted to every user of the provider, ignoring the claim.
+pub const ENV_IDENTITY_OPENID_ROLE_POLICY: &str = "RUSTFS_IDENTITY_OPENID_ROLE_POLICY";
+/// Seconds a fetched key set is trusted before it is fetched again.
+pub const ENV_IDENTITY_OPENID_JWKS_TTL: &str = "RUSTFS_IDENTITY_OPENID_JWKS_TTL";
+
+pub const ENV_IDENTITY_OPENID_KEYS: &[&str] = &[
+    ENV_IDENTITY_OPENID_CONFIG_URL,
+    ENV_IDENTITY_OPENID_CLIENT_ID,
+    ENV_IDENTITY_OPENID_ISSUER,
+    ENV_IDENTITY_OPENID_JWKS_URL,
+    ENV_IDENTITY_OPENID_CLAIM_NAME,
+    ENV_IDENTITY_OPENID_CLAIM_PREFIX,
+    ENV_IDENTITY_OPENID_ROLE_POLICY,
+    ENV_IDENTITY_OPENID_JWKS_TTL,
+];
+
+pub const DEFAULT_IDENTITY_OPENID_CLAIM_NAME: &str = "policy";
+pub const DEFAULT_IDENTITY_OPENID_JWKS_TTL: u64 = 3600;
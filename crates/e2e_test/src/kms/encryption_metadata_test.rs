@@ -29,6 +29,7 @@ use tracing::info;
 fn assert_encryption_metadata(metadata: &HashMap<String, String>, expected_size: usize) {
     for key in [
         "x-rustfs-encryption-key",
+        "x-rustfs-encryption-key-version",
         "x-rustfs-encryption-iv",
         "x-rustfs-encryption-context",
         "x-rustfs-encryption-original-size",
@@ -21,3 +21,7 @@ pub mod common;
 // KMS-specific test modules
 #[cfg(test)]
 mod kms;
+
+// S3 compatibility suite, run with `--features s3compat`
+#[cfg(all(test, feature = "s3compat"))]
+mod s3compat;
@@ -0,0 +1,671 @@
+#![cfg(test)]
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Consistency checks of versioned writes under concurrency.
+//!
+//! Several clients, spread over the nodes of a cluster, run random conditional writes, versioned
+//! deletes, reads and listings against a small set of keys. Every operation is recorded with the
+//! time it was invoked and the time it returned, and the history of each key is then checked for
+//! linearizability against a model of a versioned object: some order of the operations, consistent
+//! with real time, must explain every result that was observed.
+//!
+//! Operations that failed in transit may or may not have taken effect. They stay open until the
+//! end of the history, so the checker may place them anywhere after their invocation or drop them.
+//!
+//! The endpoints come from `RUSTFS_CONSISTENCY_ENDPOINTS`, comma separated, and default to a single
+//! local node.
+
+use aws_config::meta::region::RegionProviderChain;
+use aws_sdk_s3::Client;
+use aws_sdk_s3::config::{Credentials, Region};
+use aws_sdk_s3::error::SdkError;
+use aws_sdk_s3::types::{BucketVersioningStatus, VersioningConfiguration};
+use bytes::Bytes;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serial_test::serial;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+const ACCESS_KEY: &str = "rustfsadmin";
+const SECRET_KEY: &str = "rustfsadmin";
+const BUCKET: &str = "consistency-test";
+const ENV_ENDPOINTS: &str = "RUSTFS_CONSISTENCY_ENDPOINTS";
+
+/// Return time of operations whose outcome is unknown.
+const NEVER: u64 = u64::MAX;
+
+/// Operation on one key, as the model sees it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Op {
+    Put {
+        value: String,
+    },
+    /// Put with `If-None-Match: *`.
+    PutIfAbsent {
+        value: String,
+    },
+    /// Put with `If-Match` on the ETag of `expected`.
+    PutIfMatch {
+        expected: String,
+        value: String,
+    },
+    /// Delete without version, which adds a delete marker.
+    Delete,
+    /// Delete of one version.
+    DeleteVersion {
+        version_id: String,
+    },
+    /// Read of the latest version, by GET or by listing.
+    Read,
+}
+
+/// What the client observed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Outcome {
+    /// A write succeeded and created this version.
+    Written { version_id: Option<String> },
+    /// A conditional write was refused.
+    Refused,
+    /// A read saw this value, None when the key had no current version.
+    Read(Option<String>),
+    /// The request failed in transit, it may or may not have happened.
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+struct Event {
+    key: String,
+    op: Op,
+    outcome: Outcome,
+    call: u64,
+    ret: u64,
+}
+
+/// Versions of a key, oldest first. A version without value is a delete marker, a version
+/// without id was written by an operation whose response got lost.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+struct VersionedKey {
+    versions: Vec<(Option<String>, Option<String>)>,
+}
+
+impl VersionedKey {
+    fn latest(&self) -> Option<&String> {
+        self.versions.last().and_then(|(_, value)| value.as_ref())
+    }
+
+    /// Apply `op` if it can have produced `outcome` in this state.
+    fn step(&self, op: &Op, outcome: &Outcome) -> Option<Self> {
+        let unknown = *outcome == Outcome::Unknown;
+        let written_id = match outcome {
+            Outcome::Written { version_id } => Some(version_id.clone()),
+            Outcome::Unknown => Some(None),
+            _ => None,
+        };
+
+        let mut next = self.clone();
+        match op {
+            Op::Put { value } => {
+                next.versions.push((written_id?, Some(value.clone())));
+                Some(next)
+            }
+            Op::PutIfAbsent { value } => {
+                let allowed = self.latest().is_none();
+                if allowed && (unknown || written_id.is_some()) {
+                    next.versions.push((written_id.flatten(), Some(value.clone())));
+                    Some(next)
+                } else if !allowed && (unknown || *outcome == Outcome::Refused) {
+                    Some(next)
+                } else {
+                    None
+                }
+            }
+            Op::PutIfMatch { expected, value } => {
+                let allowed = self.latest() == Some(expected);
+                if allowed && (unknown || written_id.is_some()) {
+                    next.versions.push((written_id.flatten(), Some(value.clone())));
+                    Some(next)
+                } else if !allowed && (unknown || *outcome == Outcome::Refused) {
+                    Some(next)
+                } else {
+                    None
+                }
+            }
+            Op::Delete => {
+                next.versions.push((written_id?, None));
+                Some(next)
+            }
+            Op::DeleteVersion { version_id } => {
+                next.versions.retain(|(id, _)| id.as_ref() != Some(version_id));
+                Some(next)
+            }
+            Op::Read => match outcome {
+                Outcome::Read(value) if value.as_ref() == self.latest() => Some(next),
+                Outcome::Unknown => Some(next),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Check the events of one key for linearizability with the Wing and Gong search: repeatedly
+/// pick an operation that no pending operation returned before, apply it to the model, and
+/// backtrack when the model rejects every choice. Returns the number of linearized operations,
+/// or the events of the key when no order explains them.
+fn check_key(events: &[Event]) -> Result<usize, Vec<Event>> {
+    let required: usize = events.iter().filter(|e| e.ret != NEVER).count();
+    let mut visited: HashSet<(Vec<bool>, VersionedKey)> = HashSet::new();
+    let mut stack = vec![(vec![false; events.len()], VersionedKey::default())];
+
+    while let Some((done, state)) = stack.pop() {
+        let linearized = done.iter().zip(events).filter(|(d, e)| **d && e.ret != NEVER).count();
+        if linearized == required {
+            return Ok(linearized);
+        }
+        if !visited.insert((done.clone(), state.clone())) {
+            continue;
+        }
+
+        // Every candidate has to start before the earliest return of an open operation
+        let horizon = events
+            .iter()
+            .zip(&done)
+            .filter(|(_, d)| !**d)
+            .map(|(e, _)| e.ret)
+            .min()
+            .unwrap_or(NEVER);
+
+        for (i, event) in events.iter().enumerate() {
+            if done[i] || event.call > horizon {
+                continue;
+            }
+            if let Some(next) = state.step(&event.op, &event.outcome) {
+                let mut done = done.clone();
+                done[i] = true;
+                stack.push((done, next));
+            }
+        }
+    }
+
+    Err(events.to_vec())
+}
+
+/// Check every key of `history`, returning the histories of the keys that are not linearizable.
+fn check_history(history: &[Event]) -> Vec<(String, Vec<Event>)> {
+    let mut by_key: HashMap<&str, Vec<Event>> = HashMap::new();
+    for event in history {
+        by_key.entry(&event.key).or_default().push(event.clone());
+    }
+
+    let mut failures = Vec::new();
+    for (key, mut events) in by_key {
+        events.sort_by_key(|e| e.call);
+        if let Err(events) = check_key(&events) {
+            failures.push((key.to_string(), events));
+        }
+    }
+    failures.sort_by(|a, b| a.0.cmp(&b.0));
+    failures
+}
+
+#[derive(Debug, Clone)]
+struct Recorder {
+    start: Instant,
+    events: Arc<Mutex<Vec<Event>>>,
+    /// Values sent by any process, by ETag.
+    values: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl Recorder {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            events: Arc::new(Mutex::new(Vec::new())),
+            values: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Remember `value` before it is sent, so listings can map its ETag back.
+    fn sending(&self, value: &str) {
+        self.values.lock().unwrap().insert(etag_of(value), value.to_string());
+    }
+
+    fn value_of(&self, etag: &str) -> String {
+        self.values
+            .lock()
+            .unwrap()
+            .get(etag)
+            .cloned()
+            .unwrap_or_else(|| format!("unknown etag {etag}"))
+    }
+
+    fn now(&self) -> u64 {
+        self.start.elapsed().as_nanos() as u64
+    }
+
+    fn record(&self, key: &str, op: Op, outcome: Outcome, call: u64) {
+        let ret = if outcome == Outcome::Unknown { NEVER } else { self.now() };
+        self.events.lock().unwrap().push(Event {
+            key: key.to_string(),
+            op,
+            outcome,
+            call,
+            ret,
+        });
+    }
+
+    fn history(&self) -> Vec<Event> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+fn etag_of(value: &str) -> String {
+    format!("\"{:x}\"", md5::compute(value.as_bytes()))
+}
+
+fn service_code<E: aws_sdk_s3::error::ProvideErrorMetadata, R>(err: &SdkError<E, R>) -> Option<String> {
+    match err {
+        SdkError::ServiceError(e) => e.err().code().map(str::to_string),
+        _ => None,
+    }
+}
+
+fn is_refusal(code: Option<&str>) -> bool {
+    matches!(code, Some("PreconditionFailed") | Some("ConditionalRequestConflict") | Some("NoSuchKey"))
+}
+
+async fn create_client(endpoint: &str) -> Client {
+    let region_provider = RegionProviderChain::default_provider().or_else(Region::new("us-east-1"));
+    let shared_config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+        .region(region_provider)
+        .credentials_provider(Credentials::new(ACCESS_KEY, SECRET_KEY, None, None, "static"))
+        .endpoint_url(endpoint)
+        .load()
+        .await;
+
+    Client::from_conf(
+        aws_sdk_s3::Config::from(&shared_config)
+            .to_builder()
+            .force_path_style(true)
+            .build(),
+    )
+}
+
+async fn setup_bucket(client: &Client) -> Result<(), Box<dyn Error>> {
+    if let Err(err) = client.create_bucket().bucket(BUCKET).send().await {
+        let code = service_code(&err);
+        if !matches!(code.as_deref(), Some("BucketAlreadyExists") | Some("BucketAlreadyOwnedByYou")) {
+            return Err(err.into());
+        }
+    }
+    client
+        .put_bucket_versioning()
+        .bucket(BUCKET)
+        .versioning_configuration(
+            VersioningConfiguration::builder()
+                .status(BucketVersioningStatus::Enabled)
+                .build(),
+        )
+        .send()
+        .await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Workload {
+    processes: usize,
+    ops_per_process: usize,
+    keys: usize,
+}
+
+/// Run one client process: random operations on `keys`, every result recorded.
+async fn run_process(client: Client, recorder: Recorder, process: usize, workload: Workload, prefix: String) {
+    let mut rng = StdRng::seed_from_u64(process as u64);
+    let mut seen: HashMap<String, String> = HashMap::new();
+    let mut versions: HashMap<String, Vec<String>> = HashMap::new();
+
+    for n in 0..workload.ops_per_process {
+        let key = format!("{prefix}k{}", rng.random_range(0..workload.keys));
+        let value = format!("p{process}-{n}");
+        recorder.sending(&value);
+        let call = recorder.now();
+
+        match rng.random_range(0..100) {
+            0..20 => {
+                let res = client
+                    .put_object()
+                    .bucket(BUCKET)
+                    .key(&key)
+                    .body(Bytes::from(value.clone()).into())
+                    .send()
+                    .await;
+                let outcome = match res {
+                    Ok(out) => {
+                        if let Some(vid) = out.version_id() {
+                            versions.entry(key.clone()).or_default().push(vid.to_string());
+                        }
+                        Outcome::Written {
+                            version_id: out.version_id().map(str::to_string),
+                        }
+                    }
+                    Err(_) => Outcome::Unknown,
+                };
+                recorder.record(&key, Op::Put { value }, outcome, call);
+            }
+            20..35 => {
+                let res = client
+                    .put_object()
+                    .bucket(BUCKET)
+                    .key(&key)
+                    .if_none_match("*")
+                    .body(Bytes::from(value.clone()).into())
+                    .send()
+                    .await;
+                let outcome = match res {
+                    Ok(out) => Outcome::Written {
+                        version_id: out.version_id().map(str::to_string),
+                    },
+                    Err(err) if is_refusal(service_code(&err).as_deref()) => Outcome::Refused,
+                    Err(_) => Outcome::Unknown,
+                };
+                recorder.record(&key, Op::PutIfAbsent { value }, outcome, call);
+            }
+            35..50 => {
+                let Some(expected) = seen.get(&key).cloned() else {
+                    continue;
+                };
+                let res = client
+                    .put_object()
+                    .bucket(BUCKET)
+                    .key(&key)
+                    .if_match(etag_of(&expected))
+                    .body(Bytes::from(value.clone()).into())
+                    .send()
+                    .await;
+                let outcome = match res {
+                    Ok(out) => Outcome::Written {
+                        version_id: out.version_id().map(str::to_string),
+                    },
+                    Err(err) if is_refusal(service_code(&err).as_deref()) => Outcome::Refused,
+                    Err(_) => Outcome::Unknown,
+                };
+                recorder.record(&key, Op::PutIfMatch { expected, value }, outcome, call);
+            }
+            50..60 => {
+                let outcome = match client.delete_object().bucket(BUCKET).key(&key).send().await {
+                    Ok(out) => Outcome::Written {
+                        version_id: out.version_id().map(str::to_string),
+                    },
+                    Err(_) => Outcome::Unknown,
+                };
+                recorder.record(&key, Op::Delete, outcome, call);
+            }
+            60..70 => {
+                let Some(version_id) = versions.get_mut(&key).and_then(|v| v.pop()) else {
+                    continue;
+                };
+                let outcome = match client
+                    .delete_object()
+                    .bucket(BUCKET)
+                    .key(&key)
+                    .version_id(&version_id)
+                    .send()
+                    .await
+                {
+                    Ok(out) => Outcome::Written {
+                        version_id: out.version_id().map(str::to_string),
+                    },
+                    Err(_) => Outcome::Unknown,
+                };
+                recorder.record(&key, Op::DeleteVersion { version_id }, outcome, call);
+            }
+            70..90 => {
+                let outcome = match client.get_object().bucket(BUCKET).key(&key).send().await {
+                    Ok(out) => match out.body.collect().await {
+                        Ok(body) => {
+                            let value = String::from_utf8_lossy(&body.into_bytes()).to_string();
+                            seen.insert(key.clone(), value.clone());
+                            Outcome::Read(Some(value))
+                        }
+                        Err(_) => Outcome::Unknown,
+                    },
+                    Err(err) if service_code(&err).as_deref() == Some("NoSuchKey") => {
+                        seen.remove(&key);
+                        Outcome::Read(None)
+                    }
+                    Err(_) => Outcome::Unknown,
+                };
+                recorder.record(&key, Op::Read, outcome, call);
+            }
+            _ => {
+                let Ok(out) = client.list_objects_v2().bucket(BUCKET).prefix(&prefix).send().await else {
+                    continue;
+                };
+                let listed: HashMap<String, String> = out
+                    .contents()
+                    .iter()
+                    .filter_map(|o| Some((o.key()?.to_string(), o.e_tag()?.to_string())))
+                    .collect();
+                // A listing reads every key of the workload at once
+                for k in 0..workload.keys {
+                    let key = format!("{prefix}k{k}");
+                    let value = listed.get(&key).map(|etag| recorder.value_of(etag));
+                    recorder.record(&key, Op::Read, Outcome::Read(value), call);
+                }
+            }
+        }
+    }
+}
+
+/// Run `workload` spread over `clients` and return the recorded history.
+async fn run_workload(clients: &[Client], workload: Workload) -> Vec<Event> {
+    let recorder = Recorder::new();
+    let prefix = format!("run-{}/", uuid::Uuid::new_v4());
+
+    let mut handles = Vec::with_capacity(workload.processes);
+    for process in 0..workload.processes {
+        let client = clients[process % clients.len()].clone();
+        handles.push(tokio::spawn(run_process(client, recorder.clone(), process, workload, prefix.clone())));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    recorder.history()
+}
+
+#[tokio::test]
+#[serial]
+#[ignore = "requires running RustFS cluster, see RUSTFS_CONSISTENCY_ENDPOINTS"]
+async fn test_versioned_writes_are_linearizable() -> Result<(), Box<dyn Error>> {
+    let endpoints = std::env::var(ENV_ENDPOINTS).unwrap_or_else(|_| "http://localhost:9000".to_string());
+    let mut clients = Vec::new();
+    for endpoint in endpoints.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        clients.push(create_client(endpoint).await);
+    }
+    setup_bucket(&clients[0]).await?;
+
+    let started = Instant::now();
+    let history = run_workload(
+        &clients,
+        Workload {
+            processes: 8,
+            ops_per_process: 60,
+            keys: 4,
+        },
+    )
+    .await;
+    println!("recorded {} operations in {:?}", history.len(), started.elapsed());
+
+    let failures = check_history(&history);
+    for (key, events) in &failures {
+        println!("history of {key} is not linearizable:");
+        for e in events {
+            let ret = if e.ret == NEVER { "?".to_string() } else { e.ret.to_string() };
+            println!("  [{}, {}] {:?} -> {:?}", e.call, ret, e.op, e.outcome);
+        }
+    }
+    assert!(failures.is_empty(), "{} keys are not linearizable", failures.len());
+    Ok(())
+}
+
+mod checker {
+    use super::*;
+
+    fn ev(op: Op, outcome: Outcome, call: u64, ret: u64) -> Event {
+        Event {
+            key: "k".to_string(),
+            op,
+            outcome,
+            call,
+            ret,
+        }
+    }
+
+    fn put(value: &str, vid: &str) -> (Op, Outcome) {
+        (
+            Op::Put {
+                value: value.to_string(),
+            },
+            Outcome::Written {
+                version_id: Some(vid.to_string()),
+            },
+        )
+    }
+
+    fn read(value: Option<&str>) -> (Op, Outcome) {
+        (Op::Read, Outcome::Read(value.map(str::to_string)))
+    }
+
+    #[test]
+    fn test_sequential_history() {
+        let (p1, o1) = put("a", "v1");
+        let (r1, ro1) = read(Some("a"));
+        let (r2, ro2) = read(None);
+        let events = vec![
+            ev(p1, o1, 0, 10),
+            ev(r1, ro1, 20, 30),
+            ev(Op::Delete, Outcome::Written { version_id: None }, 40, 50),
+            ev(r2, ro2, 60, 70),
+            ev(
+                Op::DeleteVersion { version_id: "v1".into() },
+                Outcome::Written { version_id: None },
+                80,
+                90,
+            ),
+        ];
+        assert_eq!(check_key(&events), Ok(5));
+    }
+
+    #[test]
+    fn test_stale_read_is_rejected() {
+        let (p1, o1) = put("a", "v1");
+        let (p2, o2) = put("b", "v2");
+        let (r1, ro1) = read(Some("a"));
+        let events = vec![ev(p1, o1, 0, 10), ev(p2, o2, 20, 30), ev(r1, ro1, 40, 50)];
+        assert!(check_key(&events).is_err());
+    }
+
+    #[test]
+    fn test_concurrent_operations_may_reorder() {
+        let (p1, o1) = put("a", "v1");
+        let (p2, o2) = put("b", "v2");
+        let (r1, ro1) = read(Some("a"));
+        // The second put overlaps the first, so it may have gone first
+        let events = vec![ev(p1, o1, 0, 30), ev(p2, o2, 10, 20), ev(r1, ro1, 40, 50)];
+        assert!(check_key(&events).is_ok());
+    }
+
+    #[test]
+    fn test_conditional_writes() {
+        let ok = Outcome::Written { version_id: None };
+        let both_created = vec![
+            ev(Op::PutIfAbsent { value: "a".into() }, ok.clone(), 0, 30),
+            ev(Op::PutIfAbsent { value: "b".into() }, ok.clone(), 10, 20),
+        ];
+        assert!(check_key(&both_created).is_err());
+
+        let one_refused = vec![
+            ev(Op::PutIfAbsent { value: "a".into() }, ok.clone(), 0, 30),
+            ev(Op::PutIfAbsent { value: "b".into() }, Outcome::Refused, 10, 20),
+            ev(
+                Op::PutIfMatch {
+                    expected: "a".into(),
+                    value: "c".into(),
+                },
+                ok.clone(),
+                40,
+                50,
+            ),
+        ];
+        assert!(check_key(&one_refused).is_ok());
+
+        let lost_update = vec![
+            ev(Op::PutIfAbsent { value: "a".into() }, ok.clone(), 0, 10),
+            ev(
+                Op::PutIfMatch {
+                    expected: "a".into(),
+                    value: "b".into(),
+                },
+                ok.clone(),
+                20,
+                40,
+            ),
+            ev(
+                Op::PutIfMatch {
+                    expected: "a".into(),
+                    value: "c".into(),
+                },
+                ok,
+                25,
+                35,
+            ),
+        ];
+        assert!(check_key(&lost_update).is_err());
+    }
+
+    #[test]
+    fn test_unknown_outcomes() {
+        let (r1, ro1) = read(Some("a"));
+        let (r2, ro2) = read(None);
+        // A lost put may take effect any time after it was sent, or never
+        let events = vec![
+            ev(Op::Put { value: "a".into() }, Outcome::Unknown, 0, NEVER),
+            ev(r2, ro2, 10, 20),
+            ev(r1, ro1, 30, 40),
+        ];
+        assert!(check_key(&events).is_ok());
+
+        let (r3, ro3) = read(None);
+        let never_happened = vec![
+            ev(Op::Put { value: "a".into() }, Outcome::Unknown, 0, NEVER),
+            ev(r3, ro3, 10, 20),
+        ];
+        assert!(check_key(&never_happened).is_ok());
+    }
+
+    #[test]
+    fn test_check_history_groups_by_key() {
+        let (p1, o1) = put("a", "v1");
+        let (r1, ro1) = read(Some("b"));
+        let mut bad = ev(r1, ro1, 20, 30);
+        bad.key = "other".to_string();
+        let failures = check_history(&[ev(p1, o1, 0, 10), bad]);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "other");
+    }
+}
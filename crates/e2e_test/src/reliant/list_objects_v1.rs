@@ -0,0 +1,144 @@
+#![cfg(test)]
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Marker based paging of the legacy ListObjects (v1) API.
+
+use aws_config::meta::region::RegionProviderChain;
+use aws_sdk_s3::Client;
+use aws_sdk_s3::config::{Credentials, Region};
+use aws_sdk_s3::error::SdkError;
+use bytes::Bytes;
+use serial_test::serial;
+use std::error::Error;
+
+const ENDPOINT: &str = "http://localhost:9000";
+const ACCESS_KEY: &str = "rustfsadmin";
+const SECRET_KEY: &str = "rustfsadmin";
+const BUCKET: &str = "list-v1-test";
+
+async fn create_aws_s3_client() -> Result<Client, Box<dyn Error>> {
+    let region_provider = RegionProviderChain::default_provider().or_else(Region::new("us-east-1"));
+    let shared_config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+        .region(region_provider)
+        .credentials_provider(Credentials::new(ACCESS_KEY, SECRET_KEY, None, None, "static"))
+        .endpoint_url(ENDPOINT)
+        .load()
+        .await;
+
+    let client = Client::from_conf(
+        aws_sdk_s3::Config::from(&shared_config)
+            .to_builder()
+            .force_path_style(true)
+            .build(),
+    );
+    Ok(client)
+}
+
+async fn setup_test_bucket(client: &Client, keys: &[&str]) -> Result<(), Box<dyn Error>> {
+    match client.create_bucket().bucket(BUCKET).send().await {
+        Ok(_) => {}
+        Err(SdkError::ServiceError(e)) => {
+            let e = e.into_err();
+            let error_code = e.meta().code().unwrap_or("");
+            if !error_code.eq("BucketAlreadyExists") && !error_code.eq("BucketAlreadyOwnedByYou") {
+                return Err(e.into());
+            }
+        }
+        Err(e) => {
+            return Err(e.into());
+        }
+    }
+
+    for key in keys {
+        client
+            .put_object()
+            .bucket(BUCKET)
+            .key(*key)
+            .body(Bytes::from_static(b"data").into())
+            .send()
+            .await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+#[ignore = "requires running RustFS server at localhost:9000"]
+async fn test_list_objects_v1_marker_paging() -> Result<(), Box<dyn Error>> {
+    let client = create_aws_s3_client().await?;
+    let keys = ["a.txt", "b.txt", "c.txt", "d.txt", "e.txt"];
+    setup_test_bucket(&client, &keys).await?;
+
+    // Without a delimiter the next page starts after the last key of the previous one
+    let mut listed = Vec::new();
+    let mut marker: Option<String> = None;
+    loop {
+        let page = client
+            .list_objects()
+            .bucket(BUCKET)
+            .max_keys(2)
+            .set_marker(marker.clone())
+            .send()
+            .await?;
+        assert_eq!(page.marker().unwrap_or_default(), marker.as_deref().unwrap_or_default());
+        assert!(page.next_marker().is_none());
+
+        let page_keys: Vec<String> = page.contents().iter().filter_map(|o| o.key().map(str::to_string)).collect();
+        assert!(page_keys.len() <= 2);
+        listed.extend(page_keys.iter().cloned());
+
+        if !page.is_truncated().unwrap_or_default() {
+            break;
+        }
+        marker = page_keys.last().cloned();
+    }
+    assert_eq!(listed, keys);
+
+    let after = client.list_objects().bucket(BUCKET).marker("c.txt").send().await?;
+    let after_keys: Vec<&str> = after.contents().iter().filter_map(|o| o.key()).collect();
+    assert_eq!(after_keys, vec!["d.txt", "e.txt"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+#[ignore = "requires running RustFS server at localhost:9000"]
+async fn test_list_objects_v1_delimiter_next_marker() -> Result<(), Box<dyn Error>> {
+    let client = create_aws_s3_client().await?;
+    let keys = ["dir1/a.txt", "dir1/b.txt", "dir2/a.txt", "file1.txt", "file2.txt"];
+    setup_test_bucket(&client, &keys).await?;
+
+    let first = client.list_objects().bucket(BUCKET).delimiter("/").max_keys(2).send().await?;
+    assert!(first.is_truncated().unwrap_or_default());
+    let prefixes: Vec<&str> = first.common_prefixes().iter().filter_map(|p| p.prefix()).collect();
+    assert_eq!(prefixes, vec!["dir1/", "dir2/"]);
+    assert_eq!(first.next_marker(), Some("dir2/"));
+
+    let second = client
+        .list_objects()
+        .bucket(BUCKET)
+        .delimiter("/")
+        .max_keys(2)
+        .marker("dir2/")
+        .send()
+        .await?;
+    assert!(second.common_prefixes().is_empty());
+    let second_keys: Vec<&str> = second.contents().iter().filter_map(|o| o.key()).collect();
+    assert_eq!(second_keys, vec!["file1.txt", "file2.txt"]);
+    assert!(!second.is_truncated().unwrap_or_default());
+
+    Ok(())
+}
@@ -13,7 +13,10 @@
 // limitations under the License.
 
 mod conditional_writes;
+mod consistency;
+mod head_bucket;
 mod lifecycle;
+mod list_objects_v1;
 mod lock;
 mod node_interact_test;
 mod sql;
@@ -0,0 +1,35 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! s3-tests expected to fail, with the reason. Remove an entry once the test passes.
+
+pub const KNOWN_FAILURES: &[(&str, &str)] = &[
+    // Bucket CORS configuration is not implemented
+    ("test_set_cors", "PutBucketCors is not implemented"),
+    ("test_cors_origin_response", "PutBucketCors is not implemented"),
+    ("test_cors_origin_wildcard", "PutBucketCors is not implemented"),
+    ("test_cors_header_option", "PutBucketCors is not implemented"),
+    // Public access block configuration is not implemented
+    ("test_get_default_public_block", "GetPublicAccessBlock is not implemented"),
+    ("test_put_public_block", "PutPublicAccessBlock is not implemented"),
+    ("test_block_public_put_bucket_acls", "PutPublicAccessBlock is not implemented"),
+    ("test_block_public_object_canned_acls", "PutPublicAccessBlock is not implemented"),
+    ("test_block_public_policy", "PutPublicAccessBlock is not implemented"),
+    ("test_ignore_public_acls", "PutPublicAccessBlock is not implemented"),
+    // ACLs only take canned values, grants to other accounts are rejected
+    ("test_bucket_acl_grant_userid_fullcontrol", "ACL grants are not supported"),
+    ("test_bucket_acl_grant_userid_read", "ACL grants are not supported"),
+    ("test_bucket_acl_grant_userid_write", "ACL grants are not supported"),
+    ("test_object_acl_full_control_verify_owner", "ACL grants are not supported"),
+];
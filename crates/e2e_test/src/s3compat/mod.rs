@@ -0,0 +1,317 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! S3 compatibility tests
+//!
+//! Runs the ceph s3-tests suite against a RustFS server started for the test:
+//!
+//! ```text
+//! git clone https://github.com/ceph/s3-tests && pip install -r s3-tests/requirements.txt
+//! pip install awscurl
+//! S3_TESTS_DIR=$PWD/s3-tests cargo test -p e2e_test --features s3compat -- --nocapture
+//! ```
+//!
+//! Only `CURATED_TESTS` run unless `S3_TESTS_FULL=1` is set. A test failing that is not in
+//! `KNOWN_FAILURES` fails the run; a known failure that passes is reported so it can be dropped
+//! from the list.
+
+mod known_failures;
+
+use crate::common::{RustFSTestEnvironment, execute_awscurl, init_logging};
+use known_failures::KNOWN_FAILURES;
+use serial_test::serial;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{info, warn};
+
+type TestResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+const ALT_ACCESS_KEY: &str = "s3testsalt";
+const ALT_SECRET_KEY: &str = "s3testsaltsecret";
+const TENANT_ACCESS_KEY: &str = "s3teststenant";
+const TENANT_SECRET_KEY: &str = "s3teststenantsecret";
+
+/// Tests of the core S3 API every build must pass.
+const CURATED_TESTS: &[&str] = &[
+    "test_bucket_list_empty",
+    "test_bucket_list_distinct",
+    "test_bucket_list_many",
+    "test_bucket_listv2_many",
+    "test_bucket_listv2_delimiter_basic",
+    "test_bucket_listv2_encoding_basic",
+    "test_bucket_listv2_fetchowner_notempty",
+    "test_bucket_listv2_fetchowner_defaultempty",
+    "test_bucket_listv2_fetchowner_empty",
+    "test_bucket_listv2_startafter_basic",
+    "test_bucket_listv2_startafter_not_in_list",
+    "test_bucket_listv2_startafter_after_list",
+    "test_bucket_listv2_both_continuationtoken_startafter",
+    "test_bucket_list_delimiter_basic",
+    "test_bucket_list_prefix_basic",
+    "test_bucket_create_exists",
+    "test_bucket_delete_notexist",
+    "test_bucket_delete_nonempty",
+    "test_object_write_read_update_read_delete",
+    "test_object_head_zero_bytes",
+    "test_object_write_check_etag",
+    "test_object_write_cache_control",
+    "test_object_read_not_exist",
+    "test_object_metadata_replaced_on_put",
+    "test_multi_object_delete",
+    "test_multi_objectv2_delete",
+    "test_object_copy_zero_size",
+    "test_object_copy_same_bucket",
+    "test_object_copy_diff_bucket",
+    "test_multipart_upload_empty",
+    "test_multipart_upload_small",
+    "test_multipart_upload",
+    "test_multipart_upload_size_too_small",
+    "test_abort_multipart_upload",
+    "test_list_multipart_upload",
+    "test_ranged_request_response_code",
+    "test_ranged_request_skip_leading_bytes_response_code",
+    "test_ranged_request_return_trailing_bytes_response_code",
+    "test_get_object_ifmatch_good",
+    "test_get_object_ifmatch_failed",
+    "test_get_object_ifnonematch_good",
+    "test_get_object_ifnonematch_failed",
+    "test_versioning_bucket_create_suspend",
+    "test_versioning_obj_create_read_remove",
+    "test_set_bucket_tagging",
+    "test_put_obj_tagging",
+    "test_get_obj_tagging",
+    "test_delete_obj_tagging",
+    "test_lifecycle_set",
+    "test_lifecycle_get",
+    "test_bucket_policy",
+    "test_object_lock_put_obj_lock",
+    "test_object_lock_get_obj_lock",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Passed,
+    Failed,
+}
+
+/// The outcome of each test from the `-rA` summary of pytest, keyed by test name.
+fn parse_pytest_summary(output: &str) -> Vec<(String, Outcome)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (outcome, rest) = if let Some(rest) = line.strip_prefix("PASSED ") {
+                (Outcome::Passed, rest)
+            } else if let Some(rest) = line.strip_prefix("FAILED ").or_else(|| line.strip_prefix("ERROR ")) {
+                (Outcome::Failed, rest)
+            } else {
+                return None;
+            };
+            let node_id = rest.split(" - ").next().unwrap_or(rest).trim();
+            let name = node_id.rsplit("::").next().unwrap_or(node_id);
+            Some((name.to_string(), outcome))
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Verdict {
+    passed: usize,
+    /// Failures that are not known, which fail the run
+    regressions: BTreeSet<String>,
+    /// Known failures that failed again
+    expected: BTreeSet<String>,
+    /// Known failures that passed, to be removed from the list
+    fixed: BTreeSet<String>,
+}
+
+fn judge(outcomes: &[(String, Outcome)], known_failures: &[(&str, &str)]) -> Verdict {
+    let known: BTreeSet<&str> = known_failures.iter().map(|(name, _)| *name).collect();
+    let mut verdict = Verdict::default();
+    for (name, outcome) in outcomes {
+        match (outcome, known.contains(name.as_str())) {
+            (Outcome::Passed, false) => verdict.passed += 1,
+            (Outcome::Passed, true) => {
+                verdict.passed += 1;
+                verdict.fixed.insert(name.clone());
+            }
+            (Outcome::Failed, false) => {
+                verdict.regressions.insert(name.clone());
+            }
+            (Outcome::Failed, true) => {
+                verdict.expected.insert(name.clone());
+            }
+        }
+    }
+    verdict
+}
+
+fn s3tests_config(env: &RustFSTestEnvironment) -> String {
+    let (host, port) = env.address.rsplit_once(':').unwrap_or((env.address.as_str(), "9000"));
+    let main = (env.access_key.as_str(), env.secret_key.as_str());
+    let alt = (ALT_ACCESS_KEY, ALT_SECRET_KEY);
+    let tenant = (TENANT_ACCESS_KEY, TENANT_SECRET_KEY);
+
+    let user = |section: &str, name: &str, (access_key, secret_key): (&str, &str), extra: &str| {
+        format!(
+            "[{section}]\ndisplay_name = {name}\nuser_id = {access_key}\nemail = {name}@example.com\n\
+             access_key = {access_key}\nsecret_key = {secret_key}\n{extra}\n"
+        )
+    };
+
+    let mut conf = format!(
+        "[DEFAULT]\nhost = {host}\nport = {port}\nis_secure = False\nssl_verify = False\n\n\
+         [fixtures]\nbucket prefix = rustfs-s3compat-{{random}}-\n\n"
+    );
+    conf.push_str(&user("s3 main", "main", main, ""));
+    conf.push_str(&user("s3 alt", "alt", alt, ""));
+    conf.push_str(&user("s3 tenant", "tenant", tenant, "tenant = testx\n"));
+    conf.push_str(&user("iam", "main", main, ""));
+    conf.push_str(&user("iam root", "main", main, ""));
+    conf.push_str(&user("iam alt root", "alt", alt, ""));
+    conf
+}
+
+/// Add a user with full access for the suite to act as a second account.
+async fn add_s3tests_user(env: &RustFSTestEnvironment, access_key: &str, secret_key: &str) -> TestResult {
+    let body = format!(r#"{{"secretKey":"{secret_key}","status":"enabled"}}"#);
+    execute_awscurl(
+        &format!("{}/rustfs/admin/v3/add-user?accessKey={access_key}", env.url),
+        "PUT",
+        Some(&body),
+        &env.access_key,
+        &env.secret_key,
+    )
+    .await?;
+    execute_awscurl(
+        &format!(
+            "{}/rustfs/admin/v3/set-user-or-group-policy?policyName=readwrite&userOrGroup={access_key}&isGroup=false",
+            env.url
+        ),
+        "PUT",
+        None,
+        &env.access_key,
+        &env.secret_key,
+    )
+    .await?;
+    Ok(())
+}
+
+/// The module of the suite, which moved from `s3tests_boto3` to `s3tests` upstream.
+fn suite_module(dir: &Path) -> Option<&'static str> {
+    ["s3tests_boto3", "s3tests"]
+        .into_iter()
+        .find(|module| dir.join(module).join("functional").join("test_s3.py").exists())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_s3_compatibility_suite() -> TestResult {
+    init_logging();
+
+    let Some(dir) = std::env::var_os("S3_TESTS_DIR").map(PathBuf::from) else {
+        warn!("S3_TESTS_DIR is not set, skipping the s3-tests suite");
+        return Ok(());
+    };
+    let Some(module) = suite_module(&dir) else {
+        return Err(format!("no s3-tests checkout found in {}", dir.display()).into());
+    };
+    let full = std::env::var("S3_TESTS_FULL").is_ok_and(|v| v == "1" || v == "true");
+
+    let mut env = RustFSTestEnvironment::new().await?;
+    env.start_rustfs_server(vec![]).await?;
+    add_s3tests_user(&env, ALT_ACCESS_KEY, ALT_SECRET_KEY).await?;
+    add_s3tests_user(&env, TENANT_ACCESS_KEY, TENANT_SECRET_KEY).await?;
+
+    let conf_path = PathBuf::from(&env.temp_dir).with_extension("s3tests.conf");
+    std::fs::write(&conf_path, s3tests_config(&env))?;
+
+    let suite = format!("{module}/functional/test_s3.py");
+    let mut args = vec!["-m".to_string(), "pytest".to_string(), "-rA".to_string(), "-q".to_string()];
+    args.push("-p".to_string());
+    args.push("no:cacheprovider".to_string());
+    if full {
+        args.push(suite);
+    } else {
+        args.extend(CURATED_TESTS.iter().map(|name| format!("{suite}::{name}")));
+    }
+
+    let python = std::env::var("S3_TESTS_PYTHON").unwrap_or_else(|_| "python3".to_string());
+    info!("Running s3-tests ({}) from {}", if full { "full" } else { "curated" }, dir.display());
+    let output = Command::new(python)
+        .current_dir(&dir)
+        .env("S3TEST_CONF", &conf_path)
+        .args(&args)
+        .output()?;
+    let _ = std::fs::remove_file(&conf_path);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let outcomes = parse_pytest_summary(&stdout);
+    if outcomes.is_empty() {
+        return Err(format!("s3-tests reported no results:\n{}\n{}", stdout, String::from_utf8_lossy(&output.stderr)).into());
+    }
+
+    let verdict = judge(&outcomes, KNOWN_FAILURES);
+    info!(
+        "s3-tests: {} passed, {} known failures, {} regressions",
+        verdict.passed,
+        verdict.expected.len(),
+        verdict.regressions.len()
+    );
+    if !verdict.fixed.is_empty() {
+        warn!("known failures that now pass, remove them from KNOWN_FAILURES: {:?}", verdict.fixed);
+    }
+    assert!(
+        verdict.regressions.is_empty(),
+        "s3-tests failing outside KNOWN_FAILURES: {:?}",
+        verdict.regressions
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_pytest_summary() {
+    let output = "\
+..F
+=========================== short test summary info ============================
+PASSED s3tests_boto3/functional/test_s3.py::test_bucket_list_empty
+FAILED s3tests_boto3/functional/test_s3.py::test_set_cors - botocore.exceptions.ClientError: NotImplemented
+ERROR s3tests/functional/test_s3.py::test_bucket_list_many
+1 failed, 1 passed, 1 error in 3.21s
+";
+    assert_eq!(
+        parse_pytest_summary(output),
+        vec![
+            ("test_bucket_list_empty".to_string(), Outcome::Passed),
+            ("test_set_cors".to_string(), Outcome::Failed),
+            ("test_bucket_list_many".to_string(), Outcome::Failed),
+        ]
+    );
+}
+
+#[test]
+fn test_judge_against_known_failures() {
+    let outcomes = vec![
+        ("test_a".to_string(), Outcome::Passed),
+        ("test_b".to_string(), Outcome::Failed),
+        ("test_c".to_string(), Outcome::Failed),
+        ("test_d".to_string(), Outcome::Passed),
+    ];
+    let verdict = judge(&outcomes, &[("test_c", "not supported"), ("test_d", "not supported")]);
+    assert_eq!(verdict.passed, 2);
+    assert_eq!(verdict.regressions, BTreeSet::from(["test_b".to_string()]));
+    assert_eq!(verdict.expected, BTreeSet::from(["test_c".to_string()]));
+    assert_eq!(verdict.fixed, BTreeSet::from(["test_d".to_string()]));
+}
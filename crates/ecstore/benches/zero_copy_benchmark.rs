@@ -0,0 +1,84 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Plain object read benchmarks
+//!
+//! Compares streaming a stored object through a buffered file reader, which copies every chunk,
+//! with handing out slices of the mapped file.
+//!
+//! ## Running
+//!
+//! ```bash
+//! cargo bench --bench zero_copy_benchmark
+//! ```
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use futures::StreamExt;
+use rustfs_ecstore::{fs_objects::map_file, set_disk::DEFAULT_READ_BUFFER_SIZE};
+use rustfs_utils::bytes_chunks;
+use std::hint::black_box;
+use std::io::Write;
+use tokio::runtime::Runtime;
+use tokio_util::io::ReaderStream;
+
+fn bench_plain_read(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("plain_read");
+
+    for (size, size_name) in [(1024 * 1024, "1MB"), (16 * 1024 * 1024, "16MB"), (128 * 1024 * 1024, "128MB")] {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(&vec![0x5a; size]).unwrap();
+        file.flush().unwrap();
+        let path = file.path().to_path_buf();
+
+        group.throughput(Throughput::Bytes(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("buffered", size_name), &path, |b, path| {
+            b.iter(|| {
+                rt.block_on(async {
+                    let file = tokio::fs::File::open(path).await.unwrap();
+                    let mut stream = ReaderStream::with_capacity(file, DEFAULT_READ_BUFFER_SIZE);
+                    let mut total = 0;
+                    while let Some(chunk) = stream.next().await {
+                        total += black_box(chunk.unwrap()).len();
+                    }
+                    assert_eq!(total, size);
+                })
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("mapped", size_name), &path, |b, path| {
+            b.iter(|| {
+                rt.block_on(async {
+                    let file = std::fs::File::open(path).unwrap();
+                    let data = map_file(&file, size).unwrap();
+                    let mut stream = Box::pin(bytes_chunks::<std::io::Error>(data, DEFAULT_READ_BUFFER_SIZE));
+                    let mut total = 0;
+                    while let Some(chunk) = stream.next().await {
+                        // Touch every page so the mapped read pays for its faults
+                        let chunk = chunk.unwrap();
+                        black_box(chunk.iter().step_by(4096).map(|b| *b as usize).sum::<usize>());
+                        total += chunk.len();
+                    }
+                    assert_eq!(total, size);
+                })
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_plain_read);
+criterion_main!(benches);
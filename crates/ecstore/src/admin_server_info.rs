@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::data_usage::{DATA_USAGE_CACHE_NAME, DATA_USAGE_ROOT, load_data_usage_from_backend};
+use crate::data_usage::{DATA_USAGE_CACHE_NAME, DATA_USAGE_ROOT, attach_bucket_tags, load_data_usage_from_backend};
 use crate::error::{Error, Result};
 use crate::{
     disk::endpoint::Endpoint,
@@ -234,7 +234,14 @@ pub async fn get_server_info(get_pools: bool) -> InfoMessage {
     if let Some(store) = new_object_layer_fn() {
         mode = ITEM_ONLINE;
         match load_data_usage_from_backend(store.clone()).await {
-            Ok(res) => {
+            Ok(mut res) => {
+                attach_bucket_tags(&mut res).await;
+                buckets.tags = res
+                    .buckets_usage
+                    .iter()
+                    .filter(|(_, usage)| !usage.tags.is_empty())
+                    .map(|(bucket, usage)| (bucket.clone(), usage.tags.clone()))
+                    .collect();
                 buckets.count = res.buckets_count;
                 objects.count = res.objects_total_count;
                 versions.count = res.versions_total_count;
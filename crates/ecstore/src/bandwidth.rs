@@ -0,0 +1,366 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bandwidth limits of background traffic.
+//!
+//! Heal, rebalance, decommission and replication each draw from a token bucket of their own before
+//! moving data, so one of them can be slowed down without touching the others or client traffic.
+//! A transfer larger than what is left in its bucket goes ahead once the bucket has refilled by the
+//! missing amount, so each class averages out at its limit. The limits apply per node. They start
+//! from the `RUSTFS_BANDWIDTH_*` environment variables, and once set through the admin API they are
+//! persisted in the cluster config and picked up by every node.
+
+use crate::config::com::{read_config, save_config};
+use crate::error::{Error, Result};
+use crate::store::ECStore;
+use metrics::{counter, gauge};
+use parking_lot::Mutex;
+use rustfs_config::{
+    DEFAULT_BANDWIDTH_DECOMMISSION, DEFAULT_BANDWIDTH_HEAL, DEFAULT_BANDWIDTH_REBALANCE, DEFAULT_BANDWIDTH_REPLICATION,
+    ENV_BANDWIDTH_DECOMMISSION, ENV_BANDWIDTH_HEAL, ENV_BANDWIDTH_REBALANCE, ENV_BANDWIDTH_REPLICATION,
+};
+use rustfs_utils::get_env_u64;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+pub const BANDWIDTH_CONFIG_PATH: &str = "config/bandwidth.json";
+
+/// How often a node reloads the persisted limits to pick up changes made on peers.
+pub const BANDWIDTH_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+pub static GLOBAL_BANDWIDTH_SYS: LazyLock<BandwidthSys> = LazyLock::new(|| BandwidthSys::new(BandwidthLimits::from_env()));
+
+/// Background traffic with a bandwidth limit of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrafficClass {
+    Heal,
+    Rebalance,
+    Decommission,
+    Replication,
+}
+
+impl TrafficClass {
+    pub const ALL: [TrafficClass; 4] = [
+        TrafficClass::Heal,
+        TrafficClass::Rebalance,
+        TrafficClass::Decommission,
+        TrafficClass::Replication,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrafficClass::Heal => "heal",
+            TrafficClass::Rebalance => "rebalance",
+            TrafficClass::Decommission => "decommission",
+            TrafficClass::Replication => "replication",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Limits in bytes per second, 0 for no limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BandwidthLimits {
+    pub heal: u64,
+    pub rebalance: u64,
+    pub decommission: u64,
+    pub replication: u64,
+}
+
+impl BandwidthLimits {
+    pub fn from_env() -> Self {
+        let mib = |key, default| get_env_u64(key, default).saturating_mul(1024 * 1024);
+        Self {
+            heal: mib(ENV_BANDWIDTH_HEAL, DEFAULT_BANDWIDTH_HEAL),
+            rebalance: mib(ENV_BANDWIDTH_REBALANCE, DEFAULT_BANDWIDTH_REBALANCE),
+            decommission: mib(ENV_BANDWIDTH_DECOMMISSION, DEFAULT_BANDWIDTH_DECOMMISSION),
+            replication: mib(ENV_BANDWIDTH_REPLICATION, DEFAULT_BANDWIDTH_REPLICATION),
+        }
+    }
+
+    pub fn unmarshal(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data).map_err(Error::other)
+    }
+
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(Error::other)
+    }
+
+    pub fn get(&self, class: TrafficClass) -> u64 {
+        match class {
+            TrafficClass::Heal => self.heal,
+            TrafficClass::Rebalance => self.rebalance,
+            TrafficClass::Decommission => self.decommission,
+            TrafficClass::Replication => self.replication,
+        }
+    }
+}
+
+/// What a class moved and how long it was held back, since the node started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrafficStats {
+    pub limit: u64,
+    pub bytes: u64,
+    pub throttled: u64,
+    pub throttled_ms: u64,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    /// Bytes that may go without waiting, negative while transfers wait for their share.
+    tokens: f64,
+    last: Instant,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate: AtomicU64,
+    state: Mutex<BucketState>,
+    bytes: AtomicU64,
+    throttled: AtomicU64,
+    throttled_ms: AtomicU64,
+}
+
+impl TokenBucket {
+    fn new(rate: u64) -> Self {
+        Self {
+            rate: AtomicU64::new(rate),
+            state: Mutex::new(BucketState {
+                tokens: rate as f64,
+                last: Instant::now(),
+            }),
+            bytes: AtomicU64::new(0),
+            throttled: AtomicU64::new(0),
+            throttled_ms: AtomicU64::new(0),
+        }
+    }
+
+    fn set_rate(&self, rate: u64) {
+        if self.rate.swap(rate, Ordering::Relaxed) != rate {
+            // Start over with a full second of the new rate, debts of the old one are forgiven
+            let mut state = self.state.lock();
+            state.tokens = rate as f64;
+            state.last = Instant::now();
+        }
+    }
+
+    /// Take `bytes` from the bucket, returning how long the caller must wait before sending them.
+    /// The bucket holds at most one second of its rate.
+    fn reserve(&self, bytes: u64, now: Instant) -> Duration {
+        let rate = self.rate.load(Ordering::Relaxed);
+        if rate == 0 {
+            return Duration::ZERO;
+        }
+
+        let rate = rate as f64;
+        let mut state = self.state.lock();
+        let elapsed = now.saturating_duration_since(state.last).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * rate).min(rate);
+        state.last = now.max(state.last);
+        state.tokens -= bytes as f64;
+
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / rate)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BandwidthSys {
+    limits: Mutex<BandwidthLimits>,
+    buckets: [TokenBucket; 4],
+}
+
+impl BandwidthSys {
+    pub fn new(limits: BandwidthLimits) -> Self {
+        let sys = Self {
+            limits: Mutex::new(limits),
+            buckets: TrafficClass::ALL.map(|class| TokenBucket::new(limits.get(class))),
+        };
+        sys.report_limits(&limits);
+        sys
+    }
+
+    pub fn limits(&self) -> BandwidthLimits {
+        *self.limits.lock()
+    }
+
+    fn replace(&self, limits: BandwidthLimits) {
+        let mut current = self.limits.lock();
+        if *current == limits {
+            return;
+        }
+
+        for class in TrafficClass::ALL {
+            self.buckets[class.index()].set_rate(limits.get(class));
+        }
+        *current = limits;
+        self.report_limits(&limits);
+        info!(?limits, "background bandwidth limits changed");
+    }
+
+    fn report_limits(&self, limits: &BandwidthLimits) {
+        for class in TrafficClass::ALL {
+            gauge!("rustfs_background_bandwidth_limit_bytes", "class" => class.as_str()).set(limits.get(class) as f64);
+        }
+    }
+
+    /// Load the persisted limits, keeping the current ones when none were set through the admin API.
+    pub async fn load(&self, store: Arc<ECStore>) -> Result<()> {
+        match read_config(store, BANDWIDTH_CONFIG_PATH).await {
+            Ok(data) => self.replace(BandwidthLimits::unmarshal(&data)?),
+            Err(Error::ConfigNotFound) => {}
+            Err(err) => return Err(err),
+        }
+        Ok(())
+    }
+
+    /// Apply `limits` on this node and persist them for the others.
+    pub async fn set(&self, store: Arc<ECStore>, limits: BandwidthLimits) -> Result<()> {
+        save_config(store, BANDWIDTH_CONFIG_PATH, limits.marshal()?).await?;
+        self.replace(limits);
+        Ok(())
+    }
+
+    /// Wait until `bytes` of `class` traffic fit in its limit.
+    pub async fn throttle(&self, class: TrafficClass, bytes: u64) {
+        let bucket = &self.buckets[class.index()];
+        bucket.bytes.fetch_add(bytes, Ordering::Relaxed);
+        counter!("rustfs_background_bandwidth_bytes_total", "class" => class.as_str()).increment(bytes);
+
+        let wait = bucket.reserve(bytes, Instant::now());
+        if wait.is_zero() {
+            return;
+        }
+
+        let wait_ms = u64::try_from(wait.as_millis()).unwrap_or(u64::MAX);
+        bucket.throttled.fetch_add(1, Ordering::Relaxed);
+        bucket.throttled_ms.fetch_add(wait_ms, Ordering::Relaxed);
+        counter!("rustfs_background_bandwidth_throttled_total", "class" => class.as_str()).increment(1);
+        counter!("rustfs_background_bandwidth_throttled_ms_total", "class" => class.as_str()).increment(wait_ms);
+
+        tokio::time::sleep(wait).await;
+    }
+
+    pub fn stats(&self, class: TrafficClass) -> TrafficStats {
+        let bucket = &self.buckets[class.index()];
+        TrafficStats {
+            limit: bucket.rate.load(Ordering::Relaxed),
+            bytes: bucket.bytes.load(Ordering::Relaxed),
+            throttled: bucket.throttled.load(Ordering::Relaxed),
+            throttled_ms: bucket.throttled_ms.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Load the persisted limits and keep them in sync with changes made on peers.
+pub async fn init_bandwidth_sys(store: Arc<ECStore>, cancel: CancellationToken) {
+    if let Err(err) = GLOBAL_BANDWIDTH_SYS.load(store.clone()).await {
+        warn!("load background bandwidth limits failed: {:?}", err);
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(BANDWIDTH_REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = interval.tick() => {
+                    if let Err(err) = GLOBAL_BANDWIDTH_SYS.load(store.clone()).await {
+                        warn!("refresh background bandwidth limits failed: {:?}", err);
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_reserve() {
+        let bucket = TokenBucket::new(1000);
+        let start = Instant::now();
+
+        // A full second of the rate goes at once, the rest waits for the refill
+        assert_eq!(bucket.reserve(1000, start), Duration::ZERO);
+        assert_eq!(bucket.reserve(500, start), Duration::from_millis(500));
+        assert_eq!(bucket.reserve(500, start), Duration::from_secs(1));
+
+        // Refilled after the debt is paid
+        let later = start + Duration::from_secs(2);
+        assert_eq!(bucket.reserve(1000, later), Duration::ZERO);
+
+        // Idle time never adds more than one second of the rate
+        let idle = later + Duration::from_secs(60);
+        assert_eq!(bucket.reserve(1000, idle), Duration::ZERO);
+        assert_eq!(bucket.reserve(100, idle), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_unlimited_and_rate_change() {
+        let bucket = TokenBucket::new(0);
+        let now = Instant::now();
+        assert_eq!(bucket.reserve(u64::MAX, now), Duration::ZERO);
+
+        bucket.set_rate(100);
+        let wait = bucket.reserve(200, Instant::now());
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+        bucket.set_rate(0);
+        assert_eq!(bucket.reserve(200, Instant::now()), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_classes_are_limited_independently() {
+        let sys = BandwidthSys::new(BandwidthLimits {
+            heal: 1000,
+            ..Default::default()
+        });
+
+        sys.throttle(TrafficClass::Heal, 1000).await;
+        sys.throttle(TrafficClass::Replication, 1 << 30).await;
+        assert_eq!(sys.stats(TrafficClass::Heal).throttled, 0);
+        assert_eq!(sys.stats(TrafficClass::Replication).bytes, 1 << 30);
+
+        let wait = sys.buckets[TrafficClass::Heal.index()].reserve(10, Instant::now());
+        assert!(wait > Duration::ZERO);
+        assert_eq!(sys.stats(TrafficClass::Heal).limit, 1000);
+        assert_eq!(sys.stats(TrafficClass::Rebalance).limit, 0);
+    }
+
+    #[test]
+    fn test_limits_round_trip() {
+        let limits = BandwidthLimits {
+            heal: 1 << 20,
+            replication: 1 << 30,
+            ..Default::default()
+        };
+        assert_eq!(BandwidthLimits::unmarshal(&limits.marshal().unwrap()).unwrap(), limits);
+        assert_eq!(BandwidthLimits::unmarshal(b"{\"heal\":5}").unwrap().heal, 5);
+    }
+}
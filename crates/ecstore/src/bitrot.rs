@@ -15,6 +15,7 @@
 use crate::disk::error::DiskError;
 use crate::disk::{self, DiskAPI as _, DiskStore};
 use crate::erasure_coding::{BitrotReader, BitrotWriterWrapper, CustomWriter};
+use crate::request_io;
 use rustfs_utils::HashAlgorithm;
 use std::io::Cursor;
 use tokio::io::AsyncRead;
@@ -54,6 +55,7 @@ pub async fn create_bitrot_reader(
         // Read from disk
         match disk.read_file_stream(bucket, path, offset, length - offset).await {
             Ok(rd) => {
+                let rd = request_io::wrap_reader(&disk.to_string(), rd);
                 let reader = BitrotReader::new(rd, shard_size, checksum_algo);
                 Ok(Some(reader))
             }
@@ -98,7 +100,7 @@ pub async fn create_bitrot_writer(
         };
 
         let file = disk.create_file("", volume, path, length).await?;
-        CustomWriter::new_tokio_writer(file)
+        CustomWriter::new_tokio_writer(request_io::wrap_writer(&disk.to_string(), file))
     } else {
         return Err(DiskError::DiskNotFound);
     };
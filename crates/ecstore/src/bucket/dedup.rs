@@ -0,0 +1,541 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Content-hash deduplication of object data.
+//!
+//! In a bucket with dedup enabled, a PutObject streams its data into a blob of the system bucket
+//! while computing its SHA-256. When a blob with the same digest is already indexed the new blob is
+//! dropped and the object is linked to the existing one, otherwise the new blob is indexed. The
+//! object version itself is stored without data and names its blob in internal metadata; reads are
+//! served from the blob. Object versions are immutable, so changing an object writes a new version
+//! that is deduplicated on its own, which makes the sharing copy-on-write.
+//!
+//! Every link is recorded as a reference next to the blob. A background sweep drops references
+//! whose object version is gone and reclaims blobs nothing refers to anymore. Only references and
+//! blobs older than a grace period are considered, so writes in flight are never reclaimed.
+
+use super::metadata_sys;
+use super::utils::is_meta_bucketname;
+use crate::disk::RUSTFS_META_BUCKET;
+use crate::error::{Error, Result, is_err_bucket_not_found, is_err_object_not_found};
+use crate::new_object_layer_fn;
+use crate::store::ECStore;
+use crate::store_api::{
+    GetObjectReader, HTTPRangeSpec, ObjectIO, ObjectInfo, ObjectOptions, PutObjReader, StorageAPI, WalkOptions,
+};
+use http::HeaderMap;
+use rustfs_utils::http::headers::RESERVED_METADATA_PREFIX_LOWER;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Prefix of the dedup store inside the system bucket.
+pub const DEDUP_PREFIX: &str = "dedup";
+
+/// Objects smaller than this are stored as they are unless configured otherwise.
+pub const DEFAULT_DEDUP_MIN_SIZE: i64 = 128 * 1024;
+
+/// Age below which references and blobs are left alone by the sweep.
+pub const DEDUP_GRACE_PERIOD: Duration = Duration::from_secs(24 * 3600);
+
+const DEDUP_SWEEP_INTERVAL: Duration = Duration::from_secs(3600);
+
+const DEDUP_META_BLOB: &str = "dedup-blob";
+const DEDUP_META_REF: &str = "dedup-ref";
+const DEDUP_META_SIZE: &str = "dedup-size";
+const DEDUP_META_BUCKET: &str = "dedup-bucket";
+const DEDUP_META_OBJECT: &str = "dedup-object";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketDedup {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Objects smaller than this many bytes are not deduplicated.
+    #[serde(default = "default_min_size")]
+    pub min_size: i64,
+}
+
+fn default_min_size() -> i64 {
+    DEFAULT_DEDUP_MIN_SIZE
+}
+
+impl Default for BucketDedup {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_size: DEFAULT_DEDUP_MIN_SIZE,
+        }
+    }
+}
+
+impl BucketDedup {
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(buf)?)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.min_size < 0 {
+            return Err(Error::other("dedup min size must not be negative"));
+        }
+        Ok(())
+    }
+
+    /// Whether an upload of `size` bytes, negative when unknown, is deduplicated.
+    pub fn applies_to(&self, size: i64) -> bool {
+        self.enabled && (size < 0 || size >= self.min_size)
+    }
+}
+
+/// Dedup configuration of `bucket`, disabled for buckets without one.
+pub async fn bucket_dedup(bucket: &str) -> BucketDedup {
+    if is_meta_bucketname(bucket) {
+        return BucketDedup::default();
+    }
+
+    metadata_sys::get_dedup_config(bucket)
+        .await
+        .map(|(cfg, _)| cfg)
+        .unwrap_or_default()
+}
+
+fn meta_key(key: &str) -> String {
+    format!("{RESERVED_METADATA_PREFIX_LOWER}{key}")
+}
+
+fn blob_path(blob: &str) -> String {
+    format!("{DEDUP_PREFIX}/blobs/{blob}")
+}
+
+fn digest_prefix(digest: &str) -> String {
+    format!("{DEDUP_PREFIX}/digests/{digest}/")
+}
+
+fn marker_path(digest: &str, blob: &str) -> String {
+    format!("{}{blob}", digest_prefix(digest))
+}
+
+fn refs_prefix(blob: &str) -> String {
+    format!("{DEDUP_PREFIX}/refs/{blob}/")
+}
+
+fn ref_path(blob: &str, ref_id: &str) -> String {
+    format!("{}{ref_id}", refs_prefix(blob))
+}
+
+/// Last element of a path in the dedup store.
+fn base_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or_default()
+}
+
+/// Blob holding the data of an object version, None if the version is not deduplicated.
+pub fn dedup_blob(user_defined: &HashMap<String, String>) -> Option<&str> {
+    user_defined.get(&meta_key(DEDUP_META_BLOB)).map(String::as_str)
+}
+
+/// Size of the data of a deduplicated object version, stored without data of its own.
+pub fn dedup_size(metadata: &HashMap<String, String>) -> Option<i64> {
+    metadata.get(&meta_key(DEDUP_META_SIZE)).and_then(|v| v.parse().ok())
+}
+
+/// Metadata linking an object version to a blob, which does not carry over to a copy of its data.
+pub fn strip_dedup_metadata(user_defined: &mut HashMap<String, String>) {
+    for key in [DEDUP_META_BLOB, DEDUP_META_REF, DEDUP_META_SIZE] {
+        user_defined.remove(&meta_key(key));
+    }
+}
+
+/// Data stored compressed or encrypted differs for equal content and is not deduplicated.
+fn is_transformed(user_defined: &HashMap<String, String>) -> bool {
+    user_defined.keys().any(|k| {
+        *k == meta_key("compression") || k.starts_with("x-amz-server-side-encryption") || k.starts_with("x-rustfs-encryption-")
+    })
+}
+
+fn is_past_grace(mod_time: Option<OffsetDateTime>, now: OffsetDateTime) -> bool {
+    mod_time.is_some_and(|t| now - t >= DEDUP_GRACE_PERIOD)
+}
+
+async fn put_empty(store: &Arc<ECStore>, path: &str, user_defined: HashMap<String, String>) -> Result<ObjectInfo> {
+    let opts = ObjectOptions {
+        user_defined,
+        ..Default::default()
+    };
+    store
+        .put_object(RUSTFS_META_BUCKET, path, &mut PutObjReader::from_vec(Vec::new()), &opts)
+        .await
+}
+
+/// Delete a path of the dedup store, gone already is fine.
+async fn remove(store: &Arc<ECStore>, path: &str) -> Result<()> {
+    match store.delete_object(RUSTFS_META_BUCKET, path, ObjectOptions::default()).await {
+        Ok(_) => Ok(()),
+        Err(err) if is_err_object_not_found(&err) => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+async fn exists(store: &Arc<ECStore>, path: &str) -> Result<bool> {
+    match store
+        .get_object_info(RUSTFS_META_BUCKET, path, &ObjectOptions::default())
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(err) if is_err_object_not_found(&err) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// The indexed blob with `digest`, the earliest indexed one if several raced.
+async fn indexed_blob(store: &Arc<ECStore>, digest: &str) -> Result<Option<String>> {
+    let markers = store
+        .clone()
+        .walk_collect(RUSTFS_META_BUCKET, &digest_prefix(digest), WalkOptions::default())
+        .await?;
+    Ok(markers
+        .iter()
+        .min_by(|a, b| a.mod_time.cmp(&b.mod_time).then(a.name.cmp(&b.name)))
+        .map(|info| base_name(&info.name).to_string()))
+}
+
+async fn put_ref(store: &Arc<ECStore>, blob: &str, ref_id: &str, bucket: &str, object: &str) -> Result<()> {
+    let user_defined = HashMap::from([
+        (meta_key(DEDUP_META_BUCKET), bucket.to_string()),
+        (meta_key(DEDUP_META_OBJECT), object.to_string()),
+    ]);
+    put_empty(store, &ref_path(blob, ref_id), user_defined).await?;
+    Ok(())
+}
+
+/// Store the data of a PutObject of `bucket/object` in the dedup store when the bucket deduplicates.
+///
+/// Returns the options to write the object version with, without data, or None when the object is
+/// written as usual.
+pub(crate) async fn deduplicate(
+    bucket: &str,
+    object: &str,
+    data: &mut PutObjReader,
+    opts: &ObjectOptions,
+) -> Result<Option<ObjectOptions>> {
+    if opts.data_movement || is_meta_bucketname(bucket) || is_transformed(&opts.user_defined) {
+        return Ok(None);
+    }
+    if !bucket_dedup(bucket).await.applies_to(data.size()) {
+        return Ok(None);
+    }
+    let Some(store) = new_object_layer_fn() else {
+        return Ok(None);
+    };
+
+    let own = Uuid::new_v4().to_string();
+    data.stream.compute_digest();
+    let stored = store
+        .put_object(RUSTFS_META_BUCKET, &blob_path(&own), data, &ObjectOptions::default())
+        .await?;
+    let Some(digest) = data.stream.digest().map(str::to_string) else {
+        remove(&store, &blob_path(&own)).await?;
+        return Err(Error::other(format!("no content digest computed for {bucket}/{object}")));
+    };
+
+    let ref_id = Uuid::new_v4().to_string();
+    let mut blob = None;
+    if let Some(existing) = indexed_blob(&store, &digest).await? {
+        // The reference is recorded before the index is checked again, a sweep reclaiming the blob
+        // meanwhile either sees the reference or has dropped the index entry by then
+        put_ref(&store, &existing, &ref_id, bucket, object).await?;
+        if exists(&store, &marker_path(&digest, &existing)).await? {
+            remove(&store, &blob_path(&own)).await?;
+            blob = Some(existing);
+        } else {
+            remove(&store, &ref_path(&existing, &ref_id)).await?;
+        }
+    }
+    let blob = match blob {
+        Some(blob) => {
+            debug!("dedup: {}/{} linked to blob {} ({})", bucket, object, blob, digest);
+            blob
+        }
+        None => {
+            put_empty(&store, &marker_path(&digest, &own), HashMap::new()).await?;
+            put_ref(&store, &own, &ref_id, bucket, object).await?;
+            own
+        }
+    };
+
+    let mut user_defined = opts.user_defined.clone();
+    user_defined.insert(meta_key(DEDUP_META_BLOB), blob);
+    user_defined.insert(meta_key(DEDUP_META_REF), ref_id);
+    user_defined.insert(meta_key(DEDUP_META_SIZE), stored.size.to_string());
+
+    Ok(Some(ObjectOptions {
+        user_defined,
+        preserve_etag: stored.etag,
+        ..opts.clone()
+    }))
+}
+
+/// Read a deduplicated object version from its blob.
+pub async fn get_deduplicated_object_reader(
+    range: Option<HTTPRangeSpec>,
+    h: HeaderMap,
+    oi: &ObjectInfo,
+    opts: &ObjectOptions,
+) -> Result<GetObjectReader> {
+    let Some(blob) = dedup_blob(&oi.user_defined) else {
+        return Err(Error::other(format!("{}/{} is not deduplicated", oi.bucket, oi.name)));
+    };
+    let Some(store) = new_object_layer_fn() else {
+        return Err(Error::other("errServerNotInitialized"));
+    };
+
+    let blob_opts = ObjectOptions {
+        part_number: opts.part_number,
+        ..Default::default()
+    };
+    let mut reader = match store
+        .get_object_reader(RUSTFS_META_BUCKET, &blob_path(blob), range, h, &blob_opts)
+        .await
+    {
+        Ok(reader) => reader,
+        Err(err) if is_err_object_not_found(&err) => {
+            return Err(Error::other(format!("data of {}/{} is missing from blob {}", oi.bucket, oi.name, blob)));
+        }
+        Err(err) => return Err(err),
+    };
+    reader.object_info = oi.clone();
+    Ok(reader)
+}
+
+/// Whether a version of `bucket/object` still links the reference `ref_id`.
+async fn is_ref_live(store: &Arc<ECStore>, bucket: &str, object: &str, ref_id: &str) -> Result<bool> {
+    let mut marker = None;
+    let mut version_marker = None;
+    loop {
+        let page = match store
+            .clone()
+            .list_object_versions(bucket, object, marker, version_marker, None, 1000)
+            .await
+        {
+            Ok(page) => page,
+            Err(err) if is_err_bucket_not_found(&err) => return Ok(false),
+            Err(err) => return Err(err),
+        };
+
+        if page
+            .objects
+            .iter()
+            .filter(|o| o.name == object)
+            .any(|o| o.user_defined.get(&meta_key(DEDUP_META_REF)).is_some_and(|r| r == ref_id))
+        {
+            return Ok(true);
+        }
+        if !page.is_truncated || page.next_marker.as_deref() != Some(object) {
+            return Ok(false);
+        }
+        marker = page.next_marker;
+        version_marker = page.next_version_idmarker;
+    }
+}
+
+/// Drop the dead references of `blob`, returns whether any reference is left.
+async fn prune_refs(store: &Arc<ECStore>, blob: &str, now: OffsetDateTime) -> Result<bool> {
+    let mut referenced = false;
+    for info in store
+        .clone()
+        .walk_collect(RUSTFS_META_BUCKET, &refs_prefix(blob), WalkOptions::default())
+        .await?
+    {
+        let (Some(bucket), Some(object)) = (
+            info.user_defined.get(&meta_key(DEDUP_META_BUCKET)),
+            info.user_defined.get(&meta_key(DEDUP_META_OBJECT)),
+        ) else {
+            referenced = true;
+            continue;
+        };
+        // The object version of a recent reference may still be being written
+        if !is_past_grace(info.mod_time, now) {
+            referenced = true;
+            continue;
+        }
+
+        match is_ref_live(store, bucket, object, base_name(&info.name)).await {
+            Ok(true) => referenced = true,
+            Ok(false) => {
+                remove(store, &info.name).await?;
+            }
+            Err(err) => {
+                warn!("dedup: check reference {} failed: {:?}", info.name, err);
+                referenced = true;
+            }
+        }
+    }
+    Ok(referenced)
+}
+
+/// Drop dead references and reclaim blobs nothing refers to, returns how many blobs were reclaimed.
+pub async fn sweep_dedup_store(store: Arc<ECStore>) -> Result<usize> {
+    let now = OffsetDateTime::now_utc();
+
+    // Index entries by blob, the digest is part of their path
+    let markers: HashMap<String, String> = store
+        .clone()
+        .walk_collect(RUSTFS_META_BUCKET, &format!("{DEDUP_PREFIX}/digests/"), WalkOptions::default())
+        .await?
+        .into_iter()
+        .map(|info| (base_name(&info.name).to_string(), info.name))
+        .collect();
+    let blobs = store
+        .clone()
+        .walk_collect(RUSTFS_META_BUCKET, &format!("{DEDUP_PREFIX}/blobs/"), WalkOptions::default())
+        .await?;
+
+    let mut reclaimed = 0;
+    let mut present = HashSet::with_capacity(blobs.len());
+    for info in blobs {
+        let blob = base_name(&info.name).to_string();
+        present.insert(blob.clone());
+        if !is_past_grace(info.mod_time, now) {
+            continue;
+        }
+
+        match prune_refs(&store, &blob, now).await {
+            Ok(false) => {}
+            Ok(true) => continue,
+            Err(err) => {
+                warn!("dedup: prune references of blob {} failed: {:?}", blob, err);
+                continue;
+            }
+        }
+
+        if let Some(marker) = markers.get(&blob) {
+            // Unindex first, a write linking the blob meanwhile has recorded its reference by now
+            remove(&store, marker).await?;
+            let linked = !store
+                .clone()
+                .walk_collect(RUSTFS_META_BUCKET, &refs_prefix(&blob), WalkOptions::default())
+                .await?
+                .is_empty();
+            if linked {
+                put_empty(&store, marker, HashMap::new()).await?;
+                continue;
+            }
+        }
+
+        remove(&store, &info.name).await?;
+        reclaimed += 1;
+    }
+
+    // Index entries of blobs already gone
+    for (blob, marker) in markers {
+        if !present.contains(&blob) {
+            remove(&store, &marker).await?;
+        }
+    }
+
+    if reclaimed > 0 {
+        info!("dedup: reclaimed {} unreferenced blobs", reclaimed);
+    }
+    Ok(reclaimed)
+}
+
+pub async fn init_dedup_sys(store: Arc<ECStore>, cancel: CancellationToken) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DEDUP_SWEEP_INTERVAL);
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = interval.tick() => {
+                    if let Err(err) = sweep_dedup_store(store.clone()).await {
+                        warn!("dedup: sweep failed: {:?}", err);
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_config() {
+        let cfg = BucketDedup::unmarshal(br#"{"enabled":true}"#).unwrap();
+        assert!(cfg.enabled);
+        assert_eq!(cfg.min_size, DEFAULT_DEDUP_MIN_SIZE);
+        assert!(cfg.validate().is_ok());
+        assert_eq!(BucketDedup::unmarshal(&cfg.marshal().unwrap()).unwrap(), cfg);
+
+        assert!(cfg.applies_to(DEFAULT_DEDUP_MIN_SIZE));
+        assert!(cfg.applies_to(-1));
+        assert!(!cfg.applies_to(DEFAULT_DEDUP_MIN_SIZE - 1));
+        assert!(!BucketDedup::default().applies_to(1 << 30));
+
+        assert!(
+            BucketDedup {
+                enabled: true,
+                min_size: -1
+            }
+            .validate()
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_dedup_metadata() {
+        let mut meta = HashMap::from([
+            (meta_key(DEDUP_META_BLOB), "b1".to_string()),
+            (meta_key(DEDUP_META_REF), "r1".to_string()),
+            (meta_key(DEDUP_META_SIZE), "1048576".to_string()),
+            ("content-type".to_string(), "application/octet-stream".to_string()),
+        ]);
+        assert_eq!(dedup_blob(&meta), Some("b1"));
+        assert_eq!(dedup_size(&meta), Some(1 << 20));
+
+        strip_dedup_metadata(&mut meta);
+        assert_eq!(dedup_blob(&meta), None);
+        assert_eq!(dedup_size(&meta), None);
+        assert_eq!(meta.len(), 1);
+
+        assert!(is_transformed(&HashMap::from([(meta_key("compression"), "zstd".to_string())])));
+        assert!(is_transformed(&HashMap::from([(
+            "x-amz-server-side-encryption".to_string(),
+            "AES256".to_string()
+        )])));
+        assert!(!is_transformed(&meta));
+    }
+
+    #[test]
+    fn test_dedup_paths() {
+        assert_eq!(blob_path("b1"), "dedup/blobs/b1");
+        assert_eq!(marker_path("abc", "b1"), "dedup/digests/abc/b1");
+        assert_eq!(ref_path("b1", "r1"), "dedup/refs/b1/r1");
+        assert_eq!(base_name(&ref_path("b1", "r1")), "r1");
+
+        let now = OffsetDateTime::now_utc();
+        assert!(!is_past_grace(None, now));
+        assert!(!is_past_grace(Some(now), now));
+        assert!(is_past_grace(Some(now - time::Duration::days(2)), now));
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-bucket erasure block size.
+//!
+//! Objects are erasure coded one block at a time, each block split into one shard per drive of
+//! the set. Large blocks favour the throughput of spinning drives, small ones cut the latency and
+//! memory of small objects on NVMe drives. The size of a bucket applies to objects written after
+//! it is set, existing objects keep the block size recorded in their metadata.
+
+use super::metadata_sys;
+use super::utils::is_meta_bucketname;
+use crate::error::{Error, Result};
+use rustfs_filemeta::BLOCK_SIZE_V2;
+use serde::{Deserialize, Serialize};
+
+/// Block sizes a bucket may use, 64 KiB to 4 MiB in powers of two.
+pub const ALLOWED_BLOCK_SIZES: &[usize] = &[
+    64 * 1024,
+    128 * 1024,
+    256 * 1024,
+    512 * 1024,
+    1024 * 1024,
+    2 * 1024 * 1024,
+    4 * 1024 * 1024,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketErasure {
+    /// Bytes of object data coded at once, one of [`ALLOWED_BLOCK_SIZES`].
+    #[serde(default = "default_block_size")]
+    pub block_size: usize,
+}
+
+fn default_block_size() -> usize {
+    BLOCK_SIZE_V2
+}
+
+impl Default for BucketErasure {
+    fn default() -> Self {
+        Self {
+            block_size: BLOCK_SIZE_V2,
+        }
+    }
+}
+
+impl BucketErasure {
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(buf)?)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !ALLOWED_BLOCK_SIZES.contains(&self.block_size) {
+            return Err(Error::other(format!(
+                "block size {} is not allowed, expected one of {:?}",
+                self.block_size, ALLOWED_BLOCK_SIZES
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Block size of new objects of `bucket`.
+pub async fn bucket_block_size(bucket: &str) -> usize {
+    if is_meta_bucketname(bucket) {
+        return BLOCK_SIZE_V2;
+    }
+
+    metadata_sys::get_erasure_config(bucket)
+        .await
+        .map(|(cfg, _)| cfg)
+        .unwrap_or_default()
+        .block_size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erasure_roundtrip() {
+        let erasure = BucketErasure::unmarshal(br#"{"blockSize":131072}"#).unwrap();
+        assert_eq!(erasure.block_size, 128 * 1024);
+        assert!(erasure.validate().is_ok());
+        assert_eq!(BucketErasure::unmarshal(&erasure.marshal().unwrap()).unwrap(), erasure);
+
+        assert_eq!(BucketErasure::unmarshal(b"{}").unwrap(), BucketErasure::default());
+    }
+
+    #[test]
+    fn test_erasure_validate() {
+        for block_size in [0, 1000, 32 * 1024, 3 * 1024 * 1024, 8 * 1024 * 1024] {
+            assert!(BucketErasure { block_size }.validate().is_err(), "{block_size}");
+        }
+        assert!(BucketErasure::default().validate().is_ok());
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Payload integrity requirements of a bucket.
+//!
+//! A Content-MD5 or x-amz-checksum sent with a write is always verified against the received data.
+//! A bucket requiring digests additionally rejects PutObject and UploadPart requests that send
+//! neither, so every byte stored in it was checked end to end against what the client meant to send.
+
+use super::metadata_sys;
+use super::utils::is_meta_bucketname;
+use crate::error::Result;
+use http::HeaderMap;
+use rustfs_rio::get_content_checksum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketIntegrity {
+    /// Reject writes sending neither Content-MD5 nor an x-amz-checksum.
+    #[serde(default)]
+    pub require_digest: bool,
+}
+
+impl BucketIntegrity {
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(buf)?)
+    }
+
+    /// Whether a write with these headers and Content-MD5 may be stored.
+    pub fn allows(&self, headers: &HeaderMap, content_md5: Option<&str>) -> bool {
+        !self.require_digest || has_payload_digest(headers, content_md5)
+    }
+}
+
+/// Whether a write sends a digest of its payload, as Content-MD5 or as an x-amz-checksum header
+/// or trailer.
+pub fn has_payload_digest(headers: &HeaderMap, content_md5: Option<&str>) -> bool {
+    content_md5.is_some_and(|md5| !md5.is_empty()) || matches!(get_content_checksum(headers), Ok(Some(_)))
+}
+
+/// Integrity requirements of `bucket`, none for buckets without a configuration.
+pub async fn bucket_integrity(bucket: &str) -> BucketIntegrity {
+    if is_meta_bucketname(bucket) {
+        return BucketIntegrity::default();
+    }
+
+    metadata_sys::get_integrity_config(bucket)
+        .await
+        .map(|(cfg, _)| cfg)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn test_integrity_roundtrip() {
+        let cfg = BucketIntegrity::unmarshal(br#"{"requireDigest":true}"#).unwrap();
+        assert!(cfg.require_digest);
+        assert_eq!(BucketIntegrity::unmarshal(&cfg.marshal().unwrap()).unwrap(), cfg);
+        assert_eq!(BucketIntegrity::unmarshal(b"{}").unwrap(), BucketIntegrity::default());
+    }
+
+    #[test]
+    fn test_required_digest() {
+        let required = BucketIntegrity { require_digest: true };
+        let none = HeaderMap::new();
+        assert!(!required.allows(&none, None));
+        assert!(!required.allows(&none, Some("")));
+        assert!(required.allows(&none, Some("XrY7u+Ae7tCTyyK7j1rNww==")));
+        assert!(BucketIntegrity::default().allows(&none, None));
+
+        let mut checksum = HeaderMap::new();
+        checksum.insert("x-amz-checksum-crc32", HeaderValue::from_static("AAAAAA=="));
+        assert!(required.allows(&checksum, None));
+
+        let mut trailer = HeaderMap::new();
+        trailer.insert("x-amz-trailer", HeaderValue::from_static("x-amz-checksum-crc32c"));
+        assert!(required.allows(&trailer, None));
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Binding of a bucket to a KMS key.
+//!
+//! In deployments where tenants share a KMS, each tenant bucket can be bound to the key of its
+//! tenant. Writes encrypted with SSE-KMS then use that key when they name none and are refused when
+//! they name another one, whether the key comes from the request or from the default encryption of
+//! the bucket. Policies can narrow this further with the `s3:x-amz-server-side-encryption-aws-kms-key-id`
+//! condition.
+
+use super::metadata_sys;
+use super::utils::is_meta_bucketname;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketKmsScope {
+    /// The only KMS key objects of the bucket may be encrypted with.
+    #[serde(default)]
+    pub key_id: String,
+}
+
+/// A write named a KMS key other than the one its bucket is bound to.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("KMS key {requested} is not allowed in bucket {bucket}, which is bound to {bound}")]
+pub struct KmsKeyNotAllowed {
+    pub bucket: String,
+    pub requested: String,
+    pub bound: String,
+}
+
+impl BucketKmsScope {
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(buf)?)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.key_id.trim().is_empty() {
+            return Err(Error::other("kms scope key id must not be empty"));
+        }
+        Ok(())
+    }
+
+    /// The KMS key a write to `bucket` uses given the key it asks for, `sse_kms` telling whether it
+    /// is encrypted with SSE-KMS.
+    pub fn resolve(
+        &self,
+        bucket: &str,
+        sse_kms: bool,
+        requested: Option<String>,
+    ) -> std::result::Result<Option<String>, KmsKeyNotAllowed> {
+        match requested {
+            Some(requested) if !same_key(&requested, &self.key_id) => Err(KmsKeyNotAllowed {
+                bucket: bucket.to_string(),
+                requested,
+                bound: self.key_id.clone(),
+            }),
+            Some(requested) => Ok(Some(requested)),
+            None if sse_kms => Ok(Some(self.key_id.clone())),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Whether two references name the same key, a bare key id matching the ARN of the key.
+fn same_key(a: &str, b: &str) -> bool {
+    fn key_id(key: &str) -> &str {
+        if key.starts_with("arn:") {
+            key.rsplit_once(":key/").map_or(key, |(_, id)| id)
+        } else {
+            key
+        }
+    }
+    key_id(a) == key_id(b)
+}
+
+/// KMS key `bucket` is bound to, if any.
+pub async fn bucket_kms_scope(bucket: &str) -> Option<BucketKmsScope> {
+    if is_meta_bucketname(bucket) {
+        return None;
+    }
+
+    metadata_sys::get_kms_scope_config(bucket).await.ok().map(|(cfg, _)| cfg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kms_scope_roundtrip() {
+        let scope = BucketKmsScope::unmarshal(br#"{"keyId":"tenant-a"}"#).unwrap();
+        assert_eq!(scope.key_id, "tenant-a");
+        assert!(scope.validate().is_ok());
+        assert_eq!(BucketKmsScope::unmarshal(&scope.marshal().unwrap()).unwrap(), scope);
+        assert!(BucketKmsScope::unmarshal(b"{}").unwrap().validate().is_err());
+    }
+
+    #[test]
+    fn test_kms_scope_resolve() {
+        let scope = BucketKmsScope {
+            key_id: "tenant-a".to_string(),
+        };
+
+        assert_eq!(scope.resolve("b", true, None), Ok(Some("tenant-a".to_string())));
+        assert_eq!(scope.resolve("b", false, None), Ok(None));
+        assert_eq!(scope.resolve("b", true, Some("tenant-a".to_string())), Ok(Some("tenant-a".to_string())));
+
+        let arn = "arn:aws:kms:us-east-1:123456789012:key/tenant-a".to_string();
+        assert_eq!(scope.resolve("b", true, Some(arn.clone())), Ok(Some(arn)));
+
+        let err = scope.resolve("b", true, Some("tenant-b".to_string())).unwrap_err();
+        assert_eq!(err.requested, "tenant-b");
+        assert_eq!(err.bound, "tenant-a");
+        assert!(scope.resolve("b", false, Some("tenant-b".to_string())).is_err());
+    }
+}
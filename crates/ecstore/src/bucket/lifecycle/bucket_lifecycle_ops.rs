@@ -25,6 +25,7 @@ use crate::bucket::lifecycle::tier_sweeper::{Jentry, delete_object_from_remote_t
 use crate::bucket::object_lock::objectlock_sys::enforce_retention_for_deletion;
 use crate::bucket::{metadata_sys::get_lifecycle_config, versioning_sys::BucketVersioningSys};
 use crate::client::object_api_utils::new_getobjectreader;
+use crate::client::object_handlers_common::delete_object_versions;
 use crate::error::Error;
 use crate::error::StorageError;
 use crate::error::{error_resp_to_object_err, is_err_object_not_found, is_err_version_not_found, is_network_or_host_down};
@@ -45,6 +46,7 @@ use rustfs_common::data_usage::TierStats;
 use rustfs_common::heal_channel::rep_has_active_rules;
 use rustfs_common::metrics::{IlmAction, Metrics};
 use rustfs_filemeta::{NULL_VERSION_ID, RestoreStatusOps, is_restored_object_on_disk};
+use rustfs_lock::MAX_DELETE_LIST;
 use rustfs_utils::path::encode_dir_object;
 use rustfs_utils::string::strings_has_prefix_fold;
 use s3s::Body;
@@ -372,35 +374,59 @@ impl ExpiryState {
                         let _ = rx;
                         return;
                     }
-                    let v = v.expect("err!");
-                    if v.as_any().is::<ExpiryTask>() {
-                        let v = v.as_any().downcast_ref::<ExpiryTask>().expect("err!");
-                        if v.obj_info.transitioned_object.status != "" {
-                            apply_expiry_on_transitioned_object(api.clone(), &v.obj_info, &v.event, &v.src).await;
-                        } else {
-                            apply_expiry_on_non_transitioned_objects(api.clone(), &v.obj_info, &v.event, &v.src).await;
+                    let mut next = v;
+                    while let Some(v) = next.take() {
+                        let Some(task) = v.as_any().downcast_ref::<NewerNoncurrentTask>() else {
+                            ExpiryState::run_task(api.clone(), v).await;
+                            continue;
+                        };
+
+                        // Coalesce the queued version deletes of the bucket into one batch delete
+                        let mut versions = task.versions.clone();
+                        let mut shutdown = false;
+                        while versions.len() < MAX_DELETE_LIST {
+                            match rx.try_recv() {
+                                Ok(Some(more)) => match more.as_any().downcast_ref::<NewerNoncurrentTask>() {
+                                    Some(more) if more.bucket == task.bucket => versions.extend(more.versions.iter().cloned()),
+                                    _ => {
+                                        next = Some(more);
+                                        break;
+                                    }
+                                },
+                                Ok(None) => {
+                                    shutdown = true;
+                                    break;
+                                }
+                                Err(_) => break,
+                            }
                         }
-                    }
-                    else if v.as_any().is::<NewerNoncurrentTask>() {
-                        let _v = v.as_any().downcast_ref::<NewerNoncurrentTask>().expect("err!");
-                        //delete_object_versions(api, &v.bucket, &v.versions, v.event).await;
-                    }
-                    else if v.as_any().is::<Jentry>() {
-                        //transitionLogIf(es.ctx, deleteObjectFromRemoteTier(es.ctx, v.ObjName, v.VersionID, v.TierName))
-                    }
-                    else if v.as_any().is::<FreeVersionTask>() {
-                        let v = v.as_any().downcast_ref::<FreeVersionTask>().expect("err!");
-                        let _oi = v.0.clone();
 
-                    }
-                    else {
-                        //info!("Invalid work type - {:?}", v);
-                        todo!();
+                        delete_object_versions(api.clone(), &task.bucket, &versions, task.event.clone()).await;
+                        if shutdown {
+                            return;
+                        }
                     }
                 }
             }
         }
     }
+
+    async fn run_task(api: Arc<ECStore>, v: ExpiryOpType) {
+        if let Some(v) = v.as_any().downcast_ref::<ExpiryTask>() {
+            if v.obj_info.transitioned_object.status != "" {
+                apply_expiry_on_transitioned_object(api, &v.obj_info, &v.event, &v.src).await;
+            } else {
+                apply_expiry_on_non_transitioned_objects(api, &v.obj_info, &v.event, &v.src).await;
+            }
+        } else if v.as_any().is::<Jentry>() {
+            //transitionLogIf(es.ctx, deleteObjectFromRemoteTier(es.ctx, v.ObjName, v.VersionID, v.TierName))
+        } else if let Some(v) = v.as_any().downcast_ref::<FreeVersionTask>() {
+            let _oi = v.0.clone();
+        } else {
+            //info!("Invalid work type - {:?}", v);
+            todo!();
+        }
+    }
 }
 
 struct TransitionTask {
@@ -1024,7 +1050,18 @@ pub async fn eval_action_from_lifecycle(
     rcfg: Option<(ReplicationConfiguration, OffsetDateTime)>,
     oi: &ObjectInfo,
 ) -> lifecycle::Event {
-    let event = lc.eval(&oi.to_lifecycle_opts()).await;
+    eval_action_from_lifecycle_at(lc, lr, rcfg, oi, OffsetDateTime::now_utc()).await
+}
+
+/// Lifecycle action due on `oi` at the time `now`.
+pub async fn eval_action_from_lifecycle_at(
+    lc: &BucketLifecycleConfiguration,
+    lr: Option<DefaultRetention>,
+    rcfg: Option<(ReplicationConfiguration, OffsetDateTime)>,
+    oi: &ObjectInfo,
+    now: OffsetDateTime,
+) -> lifecycle::Event {
+    let event = lc.eval_inner(&oi.to_lifecycle_opts(), now).await;
     //if serverDebugLog {
     info!("lifecycle: Secondary scan: {}", event.action);
     //}
@@ -0,0 +1,101 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-bucket listing consistency.
+//!
+//! A `strict` listing never reads the listing cache and walks the drives, so it sees every write
+//! acknowledged before it started. An `eventual` listing may be served from the listing cache
+//! while the cached page is younger than the cache TTL, and is the default. A request picks its
+//! level with the `x-rustfs-list-consistency` header, the configuration of its bucket applies
+//! otherwise. Both levels ask the same drives, they only differ while the listing cache is enabled.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListingConsistency {
+    Strict,
+    #[default]
+    Eventual,
+}
+
+impl ListingConsistency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Strict => "strict",
+            Self::Eventual => "eventual",
+        }
+    }
+}
+
+impl fmt::Display for ListingConsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ListingConsistency {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "strict" => Ok(Self::Strict),
+            "eventual" => Ok(Self::Eventual),
+            _ => Err(format!("unknown listing consistency '{s}', expected 'strict' or 'eventual'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketListing {
+    /// Consistency of listings that do not ask for one.
+    #[serde(default)]
+    pub consistency: ListingConsistency,
+}
+
+impl BucketListing {
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(buf)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listing_roundtrip() {
+        let listing = BucketListing::unmarshal(br#"{"consistency":"strict"}"#).unwrap();
+        assert_eq!(listing.consistency, ListingConsistency::Strict);
+        assert_eq!(BucketListing::unmarshal(&listing.marshal().unwrap()).unwrap(), listing);
+
+        assert_eq!(BucketListing::unmarshal(b"{}").unwrap().consistency, ListingConsistency::Eventual);
+        assert!(BucketListing::unmarshal(br#"{"consistency":"linearizable"}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_listing_consistency() {
+        assert_eq!(" Strict ".parse::<ListingConsistency>(), Ok(ListingConsistency::Strict));
+        assert_eq!("eventual".parse::<ListingConsistency>(), Ok(ListingConsistency::Eventual));
+        assert!("".parse::<ListingConsistency>().is_err());
+    }
+}
@@ -0,0 +1,445 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cleanup of orphaned delete markers in versioned buckets.
+//!
+//! A delete marker that is the only version left of a key hides nothing, yet it is listed and
+//! kept forever. A cleanup job walks the versions of a bucket, optionally below a prefix, and
+//! removes such markers, optionally only those older than a given age. A dry run reports the
+//! markers it would remove without touching them. Jobs run in the background on the node that
+//! accepted the request; the report of a finished job is saved in the cluster config.
+
+use crate::config::com::{read_config, save_config};
+use crate::error::{Error, Result, StorageError};
+use crate::store::ECStore;
+use crate::store_api::{BucketOptions, ObjectInfo, ObjectOptions, ObjectToDelete, StorageAPI};
+use parking_lot::RwLock;
+use rustfs_common::globals::GLOBAL_Local_Node_Name;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use time::OffsetDateTime;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+const MARKER_CLEANUP_REPORT_PREFIX: &str = "config/delete-marker-cleanup";
+
+/// Number of object versions listed, and most markers deleted, per batch.
+const CLEANUP_BATCH_SIZE: usize = 1000;
+
+/// Most removed markers listed in a report; the counters keep counting past it.
+const MAX_REPORT_ENTRIES: usize = 10_000;
+
+/// Most finished jobs kept in memory, older ones are only in the saved reports.
+const MAX_FINISHED_JOBS: usize = 100;
+
+pub static GLOBAL_MARKER_CLEANUP_SYS: LazyLock<MarkerCleanupSys> = LazyLock::new(MarkerCleanupSys::default);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MarkerCleanupRequest {
+    pub bucket: String,
+    pub prefix: String,
+    /// Only remove markers at least this many seconds old
+    pub older_than_secs: Option<u64>,
+    /// Report the markers without removing them
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkerCleanupState {
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovedMarker {
+    pub object: String,
+    pub version_id: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub mod_time: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerCleanupJob {
+    pub id: String,
+    pub request: MarkerCleanupRequest,
+    /// The node running the job.
+    pub node: String,
+    pub state: MarkerCleanupState,
+    #[serde(with = "time::serde::rfc3339")]
+    pub started: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated: OffsetDateTime,
+    pub versions_scanned: u64,
+    /// Markers removed, or found for a dry run.
+    pub markers_removed: u64,
+    pub markers_failed: u64,
+    pub removed: Vec<RemovedMarker>,
+    /// More markers were removed than `removed` lists.
+    pub report_truncated: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+impl MarkerCleanupJob {
+    fn new(request: MarkerCleanupRequest, node: &str) -> Self {
+        let now = OffsetDateTime::now_utc();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            request,
+            node: node.to_string(),
+            state: MarkerCleanupState::Running,
+            started: now,
+            updated: now,
+            versions_scanned: 0,
+            markers_removed: 0,
+            markers_failed: 0,
+            removed: Vec::new(),
+            report_truncated: false,
+            error: String::new(),
+        }
+    }
+
+    fn record_removed(&mut self, marker: &ObjectInfo) {
+        self.markers_removed += 1;
+        if self.removed.len() < MAX_REPORT_ENTRIES {
+            self.removed.push(RemovedMarker {
+                object: marker.name.clone(),
+                version_id: marker.version_id.map(|v| v.to_string()),
+                mod_time: marker.mod_time,
+            });
+        } else {
+            self.report_truncated = true;
+        }
+    }
+}
+
+/// The versions of one key seen so far, in listing order.
+#[derive(Default)]
+struct KeyVersions {
+    name: String,
+    versions: usize,
+    marker: Option<ObjectInfo>,
+}
+
+impl KeyVersions {
+    /// The marker of the key when it is its only version and old enough.
+    fn orphaned_marker(self, cutoff: Option<OffsetDateTime>) -> Option<ObjectInfo> {
+        if self.versions != 1 {
+            return None;
+        }
+
+        let marker = self.marker?;
+        match (cutoff, marker.mod_time) {
+            (None, _) => Some(marker),
+            (Some(cutoff), Some(mod_time)) if mod_time <= cutoff => Some(marker),
+            _ => None,
+        }
+    }
+}
+
+/// Feeds the listed versions of a bucket in order and hands out the orphaned markers.
+struct OrphanFinder {
+    cutoff: Option<OffsetDateTime>,
+    current: Option<KeyVersions>,
+}
+
+impl OrphanFinder {
+    fn new(cutoff: Option<OffsetDateTime>) -> Self {
+        Self { cutoff, current: None }
+    }
+
+    /// Add the next version; returns the orphaned marker of the previous key once it is complete.
+    fn push(&mut self, version: ObjectInfo) -> Option<ObjectInfo> {
+        let mut done = None;
+        if self.current.as_ref().is_none_or(|k| k.name != version.name) {
+            done = self.current.take().and_then(|k| k.orphaned_marker(self.cutoff));
+            self.current = Some(KeyVersions {
+                name: version.name.clone(),
+                ..Default::default()
+            });
+        }
+
+        if let Some(key) = self.current.as_mut() {
+            key.versions += 1;
+            if version.delete_marker {
+                key.marker = Some(version);
+            }
+        }
+        done
+    }
+
+    fn finish(&mut self) -> Option<ObjectInfo> {
+        self.current.take().and_then(|k| k.orphaned_marker(self.cutoff))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MarkerCleanupSys {
+    jobs: RwLock<BTreeMap<String, MarkerCleanupJob>>,
+}
+
+impl MarkerCleanupSys {
+    fn report_path(id: &str) -> String {
+        format!("{MARKER_CLEANUP_REPORT_PREFIX}/{id}.json")
+    }
+
+    /// Start a cleanup job in the background.
+    pub async fn start(&'static self, store: Arc<ECStore>, request: MarkerCleanupRequest) -> Result<MarkerCleanupJob> {
+        store.get_bucket_info(&request.bucket, &BucketOptions::default()).await?;
+
+        let node = GLOBAL_Local_Node_Name.read().await.clone();
+        let job = MarkerCleanupJob::new(request, &node);
+        self.jobs.write().insert(job.id.clone(), job.clone());
+
+        info!(
+            bucket = job.request.bucket,
+            id = job.id,
+            dry_run = job.request.dry_run,
+            "delete marker cleanup started"
+        );
+        let status = job.clone();
+        tokio::spawn(async move {
+            let id = job.id.clone();
+            let job = self.run(store.clone(), job).await;
+            if job.state == MarkerCleanupState::Failed {
+                error!(id, "delete marker cleanup failed: {}", job.error);
+            }
+            match serde_json::to_vec(&job) {
+                Ok(data) => {
+                    if let Err(err) = save_config(store, &Self::report_path(&id), data).await {
+                        warn!(id, "save delete marker cleanup report failed: {:?}", err);
+                    }
+                }
+                Err(err) => warn!(id, "marshal delete marker cleanup report failed: {:?}", err),
+            }
+            self.update(job);
+            self.prune();
+        });
+
+        Ok(status)
+    }
+
+    /// The job with `id`, from memory or from its saved report.
+    pub async fn status(&self, store: Arc<ECStore>, id: &str) -> Result<Option<MarkerCleanupJob>> {
+        if let Some(job) = self.jobs.read().get(id) {
+            return Ok(Some(job.clone()));
+        }
+        if Uuid::parse_str(id).is_err() {
+            return Ok(None);
+        }
+
+        match read_config(store, &Self::report_path(id)).await {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data).map_err(Error::other)?)),
+            Err(Error::ConfigNotFound) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Jobs run or running on this node since it started, without their lists of markers.
+    pub fn list(&self) -> Vec<MarkerCleanupJob> {
+        self.jobs
+            .read()
+            .values()
+            .map(|job| MarkerCleanupJob {
+                removed: Vec::new(),
+                ..job.clone()
+            })
+            .collect()
+    }
+
+    fn update(&self, mut job: MarkerCleanupJob) {
+        job.updated = OffsetDateTime::now_utc();
+        self.jobs.write().insert(job.id.clone(), job);
+    }
+
+    fn prune(&self) {
+        let mut jobs = self.jobs.write();
+        let mut finished: Vec<(OffsetDateTime, String)> = jobs
+            .values()
+            .filter(|job| job.state != MarkerCleanupState::Running)
+            .map(|job| (job.updated, job.id.clone()))
+            .collect();
+        if finished.len() > MAX_FINISHED_JOBS {
+            finished.sort();
+            for (_, id) in finished.iter().take(finished.len() - MAX_FINISHED_JOBS) {
+                jobs.remove(id);
+            }
+        }
+    }
+
+    async fn run(&self, store: Arc<ECStore>, mut job: MarkerCleanupJob) -> MarkerCleanupJob {
+        match self.cleanup(store, &mut job).await {
+            Ok(()) => job.state = MarkerCleanupState::Completed,
+            Err(err) => {
+                job.state = MarkerCleanupState::Failed;
+                job.error = err.to_string();
+            }
+        }
+        info!(
+            id = job.id,
+            scanned = job.versions_scanned,
+            removed = job.markers_removed,
+            failed = job.markers_failed,
+            "delete marker cleanup finished"
+        );
+        job
+    }
+
+    async fn cleanup(&self, store: Arc<ECStore>, job: &mut MarkerCleanupJob) -> Result<()> {
+        let bucket = job.request.bucket.clone();
+        let cutoff = job
+            .request
+            .older_than_secs
+            .map(|secs| OffsetDateTime::now_utc() - Duration::from_secs(secs));
+        let mut finder = OrphanFinder::new(cutoff);
+        let mut pending = Vec::new();
+        let mut marker = None;
+        let mut version_marker = None;
+
+        loop {
+            let listing = store
+                .clone()
+                .list_object_versions(
+                    &bucket,
+                    &job.request.prefix,
+                    marker.take(),
+                    version_marker.take(),
+                    None,
+                    CLEANUP_BATCH_SIZE as i32,
+                )
+                .await?;
+
+            job.versions_scanned += listing.objects.len() as u64;
+            let last_page = !listing.is_truncated || listing.objects.is_empty();
+            for version in listing.objects {
+                pending.extend(finder.push(version));
+            }
+            if last_page {
+                pending.extend(finder.finish());
+            }
+
+            if pending.len() >= CLEANUP_BATCH_SIZE || last_page {
+                self.remove_markers(&store, job, std::mem::take(&mut pending)).await;
+            }
+            self.update(job.clone());
+
+            if last_page {
+                return Ok(());
+            }
+            marker = listing.next_marker;
+            version_marker = listing.next_version_idmarker;
+            if marker.is_none() {
+                return Err(StorageError::other("version listing truncated without a marker"));
+            }
+        }
+    }
+
+    async fn remove_markers(&self, store: &Arc<ECStore>, job: &mut MarkerCleanupJob, markers: Vec<ObjectInfo>) {
+        if markers.is_empty() {
+            return;
+        }
+
+        if job.request.dry_run {
+            for marker in &markers {
+                job.record_removed(marker);
+            }
+            return;
+        }
+
+        let objects = markers
+            .iter()
+            .map(|marker| ObjectToDelete {
+                object_name: marker.name.clone(),
+                version_id: marker.version_id,
+                ..Default::default()
+            })
+            .collect();
+        let (_, errs) = store
+            .delete_objects(&job.request.bucket, objects, ObjectOptions::default())
+            .await;
+
+        for (marker, err) in markers.iter().zip(errs) {
+            match err {
+                None => job.record_removed(marker),
+                Some(err) => {
+                    job.markers_failed += 1;
+                    warn!(
+                        bucket = job.request.bucket,
+                        object = marker.name,
+                        "remove delete marker failed: {:?}",
+                        err
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(name: &str, delete_marker: bool, age_days: i64) -> ObjectInfo {
+        ObjectInfo {
+            name: name.to_string(),
+            version_id: Some(Uuid::new_v4()),
+            delete_marker,
+            mod_time: Some(OffsetDateTime::now_utc() - time::Duration::days(age_days)),
+            ..Default::default()
+        }
+    }
+
+    fn orphans(versions: Vec<ObjectInfo>, cutoff: Option<OffsetDateTime>) -> Vec<String> {
+        let mut finder = OrphanFinder::new(cutoff);
+        let mut found: Vec<ObjectInfo> = versions.into_iter().filter_map(|v| finder.push(v)).collect();
+        found.extend(finder.finish());
+        found.into_iter().map(|m| m.name).collect()
+    }
+
+    #[test]
+    fn test_orphan_finder() {
+        let versions = vec![
+            version("a", true, 40),
+            version("b", true, 40),
+            version("b", false, 50),
+            version("c", false, 40),
+            version("d", true, 2),
+            version("e", true, 60),
+        ];
+
+        assert_eq!(orphans(versions.clone(), None), vec!["a", "d", "e"]);
+
+        let cutoff = OffsetDateTime::now_utc() - time::Duration::days(30);
+        assert_eq!(orphans(versions, Some(cutoff)), vec!["a", "e"]);
+    }
+
+    #[test]
+    fn test_report_truncated() {
+        let mut job = MarkerCleanupJob::new(MarkerCleanupRequest::default(), "node1:9000");
+        let marker = version("a", true, 1);
+        for _ in 0..MAX_REPORT_ENTRIES + 2 {
+            job.record_removed(&marker);
+        }
+        assert_eq!(job.markers_removed, MAX_REPORT_ENTRIES as u64 + 2);
+        assert_eq!(job.removed.len(), MAX_REPORT_ENTRIES);
+        assert!(job.report_truncated);
+    }
+}
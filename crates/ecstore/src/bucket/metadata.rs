@@ -12,7 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{quota::BucketQuota, target::BucketTargets};
+use super::{
+    dedup::BucketDedup, erasure::BucketErasure, integrity::BucketIntegrity, kms_scope::BucketKmsScope, listing::BucketListing,
+    mode::BucketMode, naming::BucketNaming, placement::BucketPlacement, quota::BucketQuota, target::BucketTargets,
+    transform::BucketTransform, trash::BucketTrash,
+};
 
 use super::object_lock::ObjectLockApi;
 use super::versioning::VersioningApi;
@@ -51,6 +55,16 @@ pub const OBJECT_LOCK_CONFIG: &str = "object-lock.xml";
 pub const BUCKET_VERSIONING_CONFIG: &str = "versioning.xml";
 pub const BUCKET_REPLICATION_CONFIG: &str = "replication.xml";
 pub const BUCKET_TARGETS_FILE: &str = "bucket-targets.json";
+pub const BUCKET_PLACEMENT_CONFIG: &str = "placement.json";
+pub const BUCKET_TRANSFORM_CONFIG: &str = "transform.json";
+pub const BUCKET_TRASH_CONFIG: &str = "trash.json";
+pub const BUCKET_NAMING_CONFIG: &str = "naming.json";
+pub const BUCKET_LISTING_CONFIG: &str = "listing.json";
+pub const BUCKET_ERASURE_CONFIG: &str = "erasure.json";
+pub const BUCKET_MODE_CONFIG: &str = "mode.json";
+pub const BUCKET_DEDUP_CONFIG: &str = "dedup.json";
+pub const BUCKET_INTEGRITY_CONFIG: &str = "integrity.json";
+pub const BUCKET_KMS_SCOPE_CONFIG: &str = "kms-scope.json";
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "PascalCase", default)]
@@ -69,6 +83,16 @@ pub struct BucketMetadata {
     pub replication_config_xml: Vec<u8>,
     pub bucket_targets_config_json: Vec<u8>,
     pub bucket_targets_config_meta_json: Vec<u8>,
+    pub placement_config_json: Vec<u8>,
+    pub transform_config_json: Vec<u8>,
+    pub trash_config_json: Vec<u8>,
+    pub naming_config_json: Vec<u8>,
+    pub listing_config_json: Vec<u8>,
+    pub erasure_config_json: Vec<u8>,
+    pub mode_config_json: Vec<u8>,
+    pub dedup_config_json: Vec<u8>,
+    pub integrity_config_json: Vec<u8>,
+    pub kms_scope_config_json: Vec<u8>,
 
     pub policy_config_updated_at: OffsetDateTime,
     pub object_lock_config_updated_at: OffsetDateTime,
@@ -81,6 +105,19 @@ pub struct BucketMetadata {
     pub notification_config_updated_at: OffsetDateTime,
     pub bucket_targets_config_updated_at: OffsetDateTime,
     pub bucket_targets_config_meta_updated_at: OffsetDateTime,
+    pub placement_config_updated_at: OffsetDateTime,
+    pub transform_config_updated_at: OffsetDateTime,
+    pub trash_config_updated_at: OffsetDateTime,
+    pub naming_config_updated_at: OffsetDateTime,
+    pub listing_config_updated_at: OffsetDateTime,
+    pub erasure_config_updated_at: OffsetDateTime,
+    pub mode_config_updated_at: OffsetDateTime,
+    pub dedup_config_updated_at: OffsetDateTime,
+    pub integrity_config_updated_at: OffsetDateTime,
+    pub kms_scope_config_updated_at: OffsetDateTime,
+
+    /// Incremented on every configuration change, the basis of the metadata ETag.
+    pub revision: u64,
 
     #[serde(skip)]
     pub new_field_updated_at: OffsetDateTime,
@@ -107,6 +144,26 @@ pub struct BucketMetadata {
     pub bucket_target_config: Option<BucketTargets>,
     #[serde(skip)]
     pub bucket_target_config_meta: Option<HashMap<String, String>>,
+    #[serde(skip)]
+    pub placement_config: Option<BucketPlacement>,
+    #[serde(skip)]
+    pub transform_config: Option<BucketTransform>,
+    #[serde(skip)]
+    pub trash_config: Option<BucketTrash>,
+    #[serde(skip)]
+    pub naming_config: Option<BucketNaming>,
+    #[serde(skip)]
+    pub listing_config: Option<BucketListing>,
+    #[serde(skip)]
+    pub erasure_config: Option<BucketErasure>,
+    #[serde(skip)]
+    pub mode_config: Option<BucketMode>,
+    #[serde(skip)]
+    pub dedup_config: Option<BucketDedup>,
+    #[serde(skip)]
+    pub integrity_config: Option<BucketIntegrity>,
+    #[serde(skip)]
+    pub kms_scope_config: Option<BucketKmsScope>,
 }
 
 impl Default for BucketMetadata {
@@ -126,6 +183,16 @@ impl Default for BucketMetadata {
             replication_config_xml: Default::default(),
             bucket_targets_config_json: Default::default(),
             bucket_targets_config_meta_json: Default::default(),
+            placement_config_json: Default::default(),
+            transform_config_json: Default::default(),
+            trash_config_json: Default::default(),
+            naming_config_json: Default::default(),
+            listing_config_json: Default::default(),
+            erasure_config_json: Default::default(),
+            mode_config_json: Default::default(),
+            dedup_config_json: Default::default(),
+            integrity_config_json: Default::default(),
+            kms_scope_config_json: Default::default(),
             policy_config_updated_at: OffsetDateTime::UNIX_EPOCH,
             object_lock_config_updated_at: OffsetDateTime::UNIX_EPOCH,
             encryption_config_updated_at: OffsetDateTime::UNIX_EPOCH,
@@ -137,6 +204,17 @@ impl Default for BucketMetadata {
             notification_config_updated_at: OffsetDateTime::UNIX_EPOCH,
             bucket_targets_config_updated_at: OffsetDateTime::UNIX_EPOCH,
             bucket_targets_config_meta_updated_at: OffsetDateTime::UNIX_EPOCH,
+            placement_config_updated_at: OffsetDateTime::UNIX_EPOCH,
+            transform_config_updated_at: OffsetDateTime::UNIX_EPOCH,
+            trash_config_updated_at: OffsetDateTime::UNIX_EPOCH,
+            naming_config_updated_at: OffsetDateTime::UNIX_EPOCH,
+            listing_config_updated_at: OffsetDateTime::UNIX_EPOCH,
+            erasure_config_updated_at: OffsetDateTime::UNIX_EPOCH,
+            mode_config_updated_at: OffsetDateTime::UNIX_EPOCH,
+            dedup_config_updated_at: OffsetDateTime::UNIX_EPOCH,
+            integrity_config_updated_at: OffsetDateTime::UNIX_EPOCH,
+            kms_scope_config_updated_at: OffsetDateTime::UNIX_EPOCH,
+            revision: 0,
             new_field_updated_at: OffsetDateTime::UNIX_EPOCH,
             policy_config: Default::default(),
             notification_config: Default::default(),
@@ -149,6 +227,16 @@ impl Default for BucketMetadata {
             replication_config: Default::default(),
             bucket_target_config: Default::default(),
             bucket_target_config_meta: Default::default(),
+            placement_config: Default::default(),
+            transform_config: Default::default(),
+            trash_config: Default::default(),
+            naming_config: Default::default(),
+            listing_config: Default::default(),
+            erasure_config: Default::default(),
+            mode_config: Default::default(),
+            dedup_config: Default::default(),
+            integrity_config: Default::default(),
+            kms_scope_config: Default::default(),
         }
     }
 }
@@ -165,6 +253,46 @@ impl BucketMetadata {
         format!("{}/{}/{}", BUCKET_META_PREFIX, self.name.as_str(), BUCKET_METADATA_FILE)
     }
 
+    /// Opaque entity tag of the current revision, used for `If-Match` on configuration updates.
+    pub fn etag(&self) -> String {
+        format!("\"{:016x}\"", self.revision)
+    }
+
+    /// Whether an `If-Match` value matches the current revision, `*` matches any revision.
+    pub fn etag_matches(&self, if_match: &str) -> bool {
+        let if_match = if_match.trim();
+        if_match == "*" || if_match.trim_matches('"') == self.etag().trim_matches('"')
+    }
+
+    /// The raw content stored for `config_file`, empty if it is not set.
+    pub fn config_data(&self, config_file: &str) -> Result<&[u8]> {
+        let data = match config_file {
+            BUCKET_POLICY_CONFIG => &self.policy_config_json,
+            BUCKET_NOTIFICATION_CONFIG => &self.notification_config_xml,
+            BUCKET_LIFECYCLE_CONFIG => &self.lifecycle_config_xml,
+            BUCKET_SSECONFIG => &self.encryption_config_xml,
+            BUCKET_TAGGING_CONFIG => &self.tagging_config_xml,
+            BUCKET_QUOTA_CONFIG_FILE => &self.quota_config_json,
+            OBJECT_LOCK_CONFIG => &self.object_lock_config_xml,
+            BUCKET_VERSIONING_CONFIG => &self.versioning_config_xml,
+            BUCKET_REPLICATION_CONFIG => &self.replication_config_xml,
+            BUCKET_TARGETS_FILE => &self.bucket_targets_config_json,
+            BUCKET_PLACEMENT_CONFIG => &self.placement_config_json,
+            BUCKET_TRANSFORM_CONFIG => &self.transform_config_json,
+            BUCKET_TRASH_CONFIG => &self.trash_config_json,
+            BUCKET_NAMING_CONFIG => &self.naming_config_json,
+            BUCKET_LISTING_CONFIG => &self.listing_config_json,
+            BUCKET_ERASURE_CONFIG => &self.erasure_config_json,
+            BUCKET_MODE_CONFIG => &self.mode_config_json,
+            BUCKET_DEDUP_CONFIG => &self.dedup_config_json,
+            BUCKET_INTEGRITY_CONFIG => &self.integrity_config_json,
+            BUCKET_KMS_SCOPE_CONFIG => &self.kms_scope_config_json,
+            _ => return Err(Error::other(format!("config file not found : {config_file}"))),
+        };
+
+        Ok(data.as_slice())
+    }
+
     pub fn versioning(&self) -> bool {
         self.lock_enabled
             || (self.object_lock_config.as_ref().is_some_and(|v| v.enabled())
@@ -248,6 +376,36 @@ impl BucketMetadata {
         if self.bucket_targets_config_meta_updated_at == OffsetDateTime::UNIX_EPOCH {
             self.bucket_targets_config_meta_updated_at = self.created
         }
+        if self.placement_config_updated_at == OffsetDateTime::UNIX_EPOCH {
+            self.placement_config_updated_at = self.created
+        }
+        if self.transform_config_updated_at == OffsetDateTime::UNIX_EPOCH {
+            self.transform_config_updated_at = self.created
+        }
+        if self.trash_config_updated_at == OffsetDateTime::UNIX_EPOCH {
+            self.trash_config_updated_at = self.created
+        }
+        if self.naming_config_updated_at == OffsetDateTime::UNIX_EPOCH {
+            self.naming_config_updated_at = self.created
+        }
+        if self.listing_config_updated_at == OffsetDateTime::UNIX_EPOCH {
+            self.listing_config_updated_at = self.created
+        }
+        if self.erasure_config_updated_at == OffsetDateTime::UNIX_EPOCH {
+            self.erasure_config_updated_at = self.created
+        }
+        if self.mode_config_updated_at == OffsetDateTime::UNIX_EPOCH {
+            self.mode_config_updated_at = self.created
+        }
+        if self.dedup_config_updated_at == OffsetDateTime::UNIX_EPOCH {
+            self.dedup_config_updated_at = self.created
+        }
+        if self.integrity_config_updated_at == OffsetDateTime::UNIX_EPOCH {
+            self.integrity_config_updated_at = self.created
+        }
+        if self.kms_scope_config_updated_at == OffsetDateTime::UNIX_EPOCH {
+            self.kms_scope_config_updated_at = self.created
+        }
     }
 
     pub fn update_config(&mut self, config_file: &str, data: Vec<u8>) -> Result<OffsetDateTime> {
@@ -297,6 +455,46 @@ impl BucketMetadata {
                 self.bucket_targets_config_json = data.clone();
                 self.bucket_targets_config_updated_at = updated;
             }
+            BUCKET_PLACEMENT_CONFIG => {
+                self.placement_config_json = data;
+                self.placement_config_updated_at = updated;
+            }
+            BUCKET_TRANSFORM_CONFIG => {
+                self.transform_config_json = data;
+                self.transform_config_updated_at = updated;
+            }
+            BUCKET_TRASH_CONFIG => {
+                self.trash_config_json = data;
+                self.trash_config_updated_at = updated;
+            }
+            BUCKET_NAMING_CONFIG => {
+                self.naming_config_json = data;
+                self.naming_config_updated_at = updated;
+            }
+            BUCKET_LISTING_CONFIG => {
+                self.listing_config_json = data;
+                self.listing_config_updated_at = updated;
+            }
+            BUCKET_ERASURE_CONFIG => {
+                self.erasure_config_json = data;
+                self.erasure_config_updated_at = updated;
+            }
+            BUCKET_MODE_CONFIG => {
+                self.mode_config_json = data;
+                self.mode_config_updated_at = updated;
+            }
+            BUCKET_DEDUP_CONFIG => {
+                self.dedup_config_json = data;
+                self.dedup_config_updated_at = updated;
+            }
+            BUCKET_INTEGRITY_CONFIG => {
+                self.integrity_config_json = data;
+                self.integrity_config_updated_at = updated;
+            }
+            BUCKET_KMS_SCOPE_CONFIG => {
+                self.kms_scope_config_json = data;
+                self.kms_scope_config_updated_at = updated;
+            }
             _ => return Err(Error::other(format!("config file not found : {config_file}"))),
         }
 
@@ -353,6 +551,8 @@ impl BucketMetadata {
         }
         if !self.tagging_config_xml.is_empty() {
             self.tagging_config = Some(deserialize::<Tagging>(&self.tagging_config_xml)?);
+        } else {
+            self.tagging_config = None;
         }
         if !self.quota_config_json.is_empty() {
             self.quota_config = Some(BucketQuota::unmarshal(&self.quota_config_json)?);
@@ -360,6 +560,56 @@ impl BucketMetadata {
         if !self.replication_config_xml.is_empty() {
             self.replication_config = Some(deserialize::<ReplicationConfiguration>(&self.replication_config_xml)?);
         }
+        if !self.placement_config_json.is_empty() {
+            self.placement_config = Some(BucketPlacement::unmarshal(&self.placement_config_json)?);
+        } else {
+            self.placement_config = None;
+        }
+        if !self.transform_config_json.is_empty() {
+            self.transform_config = Some(BucketTransform::unmarshal(&self.transform_config_json)?);
+        } else {
+            self.transform_config = None;
+        }
+        if !self.trash_config_json.is_empty() {
+            self.trash_config = Some(BucketTrash::unmarshal(&self.trash_config_json)?);
+        } else {
+            self.trash_config = None;
+        }
+        if !self.naming_config_json.is_empty() {
+            self.naming_config = Some(BucketNaming::unmarshal(&self.naming_config_json)?);
+        } else {
+            self.naming_config = None;
+        }
+        if !self.listing_config_json.is_empty() {
+            self.listing_config = Some(BucketListing::unmarshal(&self.listing_config_json)?);
+        } else {
+            self.listing_config = None;
+        }
+        if !self.erasure_config_json.is_empty() {
+            self.erasure_config = Some(BucketErasure::unmarshal(&self.erasure_config_json)?);
+        } else {
+            self.erasure_config = None;
+        }
+        if !self.mode_config_json.is_empty() {
+            self.mode_config = Some(BucketMode::unmarshal(&self.mode_config_json)?);
+        } else {
+            self.mode_config = None;
+        }
+        if !self.dedup_config_json.is_empty() {
+            self.dedup_config = Some(BucketDedup::unmarshal(&self.dedup_config_json)?);
+        } else {
+            self.dedup_config = None;
+        }
+        if !self.integrity_config_json.is_empty() {
+            self.integrity_config = Some(BucketIntegrity::unmarshal(&self.integrity_config_json)?);
+        } else {
+            self.integrity_config = None;
+        }
+        if !self.kms_scope_config_json.is_empty() {
+            self.kms_scope_config = Some(BucketKmsScope::unmarshal(&self.kms_scope_config_json)?);
+        } else {
+            self.kms_scope_config = None;
+        }
         //let temp = self.bucket_targets_config_json.clone();
         if !self.bucket_targets_config_json.is_empty() {
             let bucket_targets: BucketTargets = serde_json::from_slice(&self.bucket_targets_config_json)?;
@@ -601,4 +851,22 @@ mod test {
         println!("   - Lifecycle config size: {} bytes", deserialized_bm.lifecycle_config_xml.len());
         println!("   - Serialized buffer size: {} bytes", buf.len());
     }
+
+    #[test]
+    fn revision_etag_round_trip() {
+        let mut bm = BucketMetadata::new("test-bucket");
+        assert_eq!(bm.etag(), "\"0000000000000000\"");
+
+        bm.update_config(BUCKET_TAGGING_CONFIG, b"<Tagging/>".to_vec()).unwrap();
+        bm.revision += 1;
+        assert_eq!(bm.config_data(BUCKET_TAGGING_CONFIG).unwrap(), b"<Tagging/>");
+        assert!(bm.config_data("unknown.xml").is_err());
+
+        let new = BucketMetadata::unmarshal(&bm.marshal_msg().unwrap()).unwrap();
+        assert_eq!(new.revision, 1);
+        assert!(new.etag_matches(&bm.etag()));
+        assert!(new.etag_matches("0000000000000001"));
+        assert!(new.etag_matches("*"));
+        assert!(!new.etag_matches("\"0000000000000000\""));
+    }
 }
@@ -0,0 +1,132 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bounded change history of bucket metadata.
+//!
+//! Every configuration change applied through the bucket metadata system bumps the metadata
+//! revision and appends an entry here, together with the content the configuration had before
+//! the change, so operators can audit and roll back recent updates.
+
+use crate::config::com::{read_config, save_config};
+use crate::disk::BUCKET_META_PREFIX;
+use crate::error::{Error, Result};
+use crate::store::ECStore;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use time::OffsetDateTime;
+
+pub const BUCKET_METADATA_HISTORY_FILE: &str = ".metadata-history.json";
+
+/// Number of changes kept per bucket, older entries are dropped first.
+pub const BUCKET_METADATA_HISTORY_LIMIT: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataChange {
+    /// Metadata revision produced by this change.
+    pub revision: u64,
+    pub config_file: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated: OffsetDateTime,
+    #[serde(default)]
+    pub deleted: bool,
+    /// Content of the configuration before this change, empty if it was not set.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub previous: String,
+}
+
+/// Recent changes of a bucket's metadata, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BucketMetadataHistory {
+    #[serde(default)]
+    pub changes: Vec<MetadataChange>,
+}
+
+impl BucketMetadataHistory {
+    pub fn unmarshal(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data).map_err(Error::other)
+    }
+
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(Error::other)
+    }
+
+    /// Append a change, dropping the oldest entries beyond `BUCKET_METADATA_HISTORY_LIMIT`.
+    pub fn push(&mut self, change: MetadataChange) {
+        self.changes.push(change);
+        if self.changes.len() > BUCKET_METADATA_HISTORY_LIMIT {
+            let excess = self.changes.len() - BUCKET_METADATA_HISTORY_LIMIT;
+            self.changes.drain(..excess);
+        }
+    }
+}
+
+pub fn history_file_path(bucket: &str) -> String {
+    format!("{BUCKET_META_PREFIX}/{bucket}/{BUCKET_METADATA_HISTORY_FILE}")
+}
+
+/// Load the change history of `bucket`, treating a missing file as no recorded change.
+pub async fn load_history(api: Arc<ECStore>, bucket: &str) -> Result<BucketMetadataHistory> {
+    match read_config(api, &history_file_path(bucket)).await {
+        Ok(data) => BucketMetadataHistory::unmarshal(&data),
+        Err(Error::ConfigNotFound) => Ok(BucketMetadataHistory::default()),
+        Err(err) => Err(err),
+    }
+}
+
+pub async fn record_change(api: Arc<ECStore>, bucket: &str, change: MetadataChange) -> Result<()> {
+    let mut history = load_history(api.clone(), bucket).await?;
+    history.push(change);
+    save_config(api, &history_file_path(bucket), history.marshal()?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(revision: u64) -> MetadataChange {
+        MetadataChange {
+            revision,
+            config_file: "policy.json".to_string(),
+            updated: OffsetDateTime::UNIX_EPOCH,
+            deleted: false,
+            previous: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_history_push_is_bounded() {
+        let mut history = BucketMetadataHistory::default();
+        for revision in 1..=(BUCKET_METADATA_HISTORY_LIMIT as u64 + 5) {
+            history.push(change(revision));
+        }
+
+        assert_eq!(history.changes.len(), BUCKET_METADATA_HISTORY_LIMIT);
+        assert_eq!(history.changes[0].revision, 6);
+        assert_eq!(history.changes.last().map(|c| c.revision), Some(BUCKET_METADATA_HISTORY_LIMIT as u64 + 5));
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let mut history = BucketMetadataHistory::default();
+        let mut c = change(3);
+        c.deleted = true;
+        c.previous = "{\"Version\":\"2012-10-17\"}".to_string();
+        history.push(c);
+
+        let decoded = BucketMetadataHistory::unmarshal(&history.marshal().unwrap()).unwrap();
+        assert_eq!(decoded, history);
+        assert_eq!(BucketMetadataHistory::unmarshal(b"{}").unwrap(), BucketMetadataHistory::default());
+    }
+}
@@ -18,6 +18,7 @@ use crate::bucket::metadata::{BUCKET_LIFECYCLE_CONFIG, load_bucket_metadata_pars
 use crate::bucket::utils::{deserialize, is_meta_bucketname};
 use crate::error::{Error, Result, is_err_bucket_not_found};
 use crate::global::{GLOBAL_Endpoints, is_dist_erasure, is_erasure, new_object_layer_fn};
+use crate::notification_sys::get_global_notification_sys;
 use crate::store::ECStore;
 use futures::future::join_all;
 use rustfs_common::heal_channel::HealOpts;
@@ -34,11 +35,22 @@ use std::{collections::HashMap, sync::Arc};
 use time::OffsetDateTime;
 use tokio::sync::RwLock;
 use tokio::time::sleep;
-use tracing::error;
+use tracing::{error, warn};
 
+use super::dedup::BucketDedup;
+use super::erasure::BucketErasure;
+use super::integrity::BucketIntegrity;
+use super::kms_scope::BucketKmsScope;
+use super::listing::BucketListing;
 use super::metadata::{BucketMetadata, load_bucket_metadata};
+use super::metadata_history::{MetadataChange, record_change};
+use super::mode::BucketMode;
+use super::naming::BucketNaming;
+use super::placement::BucketPlacement;
 use super::quota::BucketQuota;
 use super::target::BucketTargets;
+use super::transform::BucketTransform;
+use super::trash::BucketTrash;
 
 use lazy_static::lazy_static;
 
@@ -78,17 +90,66 @@ pub async fn get(bucket: &str) -> Result<Arc<BucketMetadata>> {
 }
 
 pub async fn update(bucket: &str, config_file: &str, data: Vec<u8>) -> Result<OffsetDateTime> {
-    let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
-    let mut bucket_meta_sys = bucket_meta_sys_lock.write().await;
-
-    bucket_meta_sys.update(bucket, config_file, data).await
+    Ok(update_if_match(bucket, config_file, data, None).await?.updated)
 }
 
 pub async fn delete(bucket: &str, config_file: &str) -> Result<OffsetDateTime> {
-    let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
-    let mut bucket_meta_sys = bucket_meta_sys_lock.write().await;
+    Ok(delete_if_match(bucket, config_file, None).await?.updated)
+}
+
+/// Update a bucket configuration only if the metadata still matches `if_match`.
+///
+/// On success the new revision is pushed to all peers so their cached metadata is refreshed.
+pub async fn update_if_match(
+    bucket: &str,
+    config_file: &str,
+    data: Vec<u8>,
+    if_match: Option<&str>,
+) -> Result<BucketConfigUpdate> {
+    let res = {
+        let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
+        let mut bucket_meta_sys = bucket_meta_sys_lock.write().await;
+
+        bucket_meta_sys.update_if_match(bucket, config_file, data, if_match).await?
+    };
+
+    notify_peers(bucket).await;
+    Ok(res)
+}
+
+/// Delete a bucket configuration only if the metadata still matches `if_match`.
+pub async fn delete_if_match(bucket: &str, config_file: &str, if_match: Option<&str>) -> Result<BucketConfigUpdate> {
+    let res = {
+        let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
+        let mut bucket_meta_sys = bucket_meta_sys_lock.write().await;
+
+        bucket_meta_sys.delete_if_match(bucket, config_file, if_match).await?
+    };
 
-    bucket_meta_sys.delete(bucket, config_file).await
+    notify_peers(bucket).await;
+    Ok(res)
+}
+
+/// Ask all peers to reload the metadata of `bucket` instead of waiting for their periodic refresh.
+async fn notify_peers(bucket: &str) {
+    let Some(notification_sys) = get_global_notification_sys() else {
+        return;
+    };
+
+    for peer_err in notification_sys.load_bucket_metadata(bucket).await {
+        if let Some(err) = peer_err.err {
+            warn!("push bucket metadata of {} to peer {} failed: {}", bucket, peer_err.host, err);
+        }
+    }
+}
+
+/// Result of a bucket configuration change.
+#[derive(Debug, Clone)]
+pub struct BucketConfigUpdate {
+    pub updated: OffsetDateTime,
+    pub revision: u64,
+    /// ETag of the metadata after the change, to be sent back as `If-Match` on the next update.
+    pub etag: String,
 }
 
 pub async fn get_bucket_policy(bucket: &str) -> Result<(BucketPolicy, OffsetDateTime)> {
@@ -105,6 +166,76 @@ pub async fn get_quota_config(bucket: &str) -> Result<(BucketQuota, OffsetDateTi
     bucket_meta_sys.get_quota_config(bucket).await
 }
 
+pub async fn get_placement_config(bucket: &str) -> Result<(BucketPlacement, OffsetDateTime)> {
+    let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
+    let bucket_meta_sys = bucket_meta_sys_lock.read().await;
+
+    bucket_meta_sys.get_placement_config(bucket).await
+}
+
+pub async fn get_transform_config(bucket: &str) -> Result<(BucketTransform, OffsetDateTime)> {
+    let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
+    let bucket_meta_sys = bucket_meta_sys_lock.read().await;
+
+    bucket_meta_sys.get_transform_config(bucket).await
+}
+
+pub async fn get_trash_config(bucket: &str) -> Result<(BucketTrash, OffsetDateTime)> {
+    let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
+    let bucket_meta_sys = bucket_meta_sys_lock.read().await;
+
+    bucket_meta_sys.get_trash_config(bucket).await
+}
+
+pub async fn get_naming_config(bucket: &str) -> Result<(BucketNaming, OffsetDateTime)> {
+    let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
+    let bucket_meta_sys = bucket_meta_sys_lock.read().await;
+
+    bucket_meta_sys.get_naming_config(bucket).await
+}
+
+pub async fn get_listing_config(bucket: &str) -> Result<(BucketListing, OffsetDateTime)> {
+    let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
+    let bucket_meta_sys = bucket_meta_sys_lock.read().await;
+
+    bucket_meta_sys.get_listing_config(bucket).await
+}
+
+pub async fn get_erasure_config(bucket: &str) -> Result<(BucketErasure, OffsetDateTime)> {
+    let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
+    let bucket_meta_sys = bucket_meta_sys_lock.read().await;
+
+    bucket_meta_sys.get_erasure_config(bucket).await
+}
+
+pub async fn get_mode_config(bucket: &str) -> Result<(BucketMode, OffsetDateTime)> {
+    let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
+    let bucket_meta_sys = bucket_meta_sys_lock.read().await;
+
+    bucket_meta_sys.get_mode_config(bucket).await
+}
+
+pub async fn get_dedup_config(bucket: &str) -> Result<(BucketDedup, OffsetDateTime)> {
+    let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
+    let bucket_meta_sys = bucket_meta_sys_lock.read().await;
+
+    bucket_meta_sys.get_dedup_config(bucket).await
+}
+
+pub async fn get_integrity_config(bucket: &str) -> Result<(BucketIntegrity, OffsetDateTime)> {
+    let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
+    let bucket_meta_sys = bucket_meta_sys_lock.read().await;
+
+    bucket_meta_sys.get_integrity_config(bucket).await
+}
+
+pub async fn get_kms_scope_config(bucket: &str) -> Result<(BucketKmsScope, OffsetDateTime)> {
+    let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
+    let bucket_meta_sys = bucket_meta_sys_lock.read().await;
+
+    bucket_meta_sys.get_kms_scope_config(bucket).await
+}
+
 pub async fn get_bucket_targets_config(bucket: &str) -> Result<BucketTargets> {
     let bucket_meta_sys_lock = get_bucket_metadata_sys()?;
     let bucket_meta_sys = bucket_meta_sys_lock.read().await;
@@ -304,10 +435,29 @@ impl BucketMetadataSys {
     }
 
     pub async fn update(&mut self, bucket: &str, config_file: &str, data: Vec<u8>) -> Result<OffsetDateTime> {
-        self.update_and_parse(bucket, config_file, data, true).await
+        Ok(self.update_if_match(bucket, config_file, data, None).await?.updated)
+    }
+
+    pub async fn update_if_match(
+        &mut self,
+        bucket: &str,
+        config_file: &str,
+        data: Vec<u8>,
+        if_match: Option<&str>,
+    ) -> Result<BucketConfigUpdate> {
+        self.update_and_parse(bucket, config_file, data, true, if_match).await
     }
 
     pub async fn delete(&mut self, bucket: &str, config_file: &str) -> Result<OffsetDateTime> {
+        Ok(self.delete_if_match(bucket, config_file, None).await?.updated)
+    }
+
+    pub async fn delete_if_match(
+        &mut self,
+        bucket: &str,
+        config_file: &str,
+        if_match: Option<&str>,
+    ) -> Result<BucketConfigUpdate> {
         if config_file == BUCKET_LIFECYCLE_CONFIG {
             let meta = match self.get_config_from_disk(bucket).await {
                 Ok(res) => res,
@@ -332,10 +482,21 @@ impl BucketMetadataSys {
             // TODO: other lifecycle handle
         }
 
-        self.update_and_parse(bucket, config_file, Vec::new(), false).await
+        self.update_and_parse(bucket, config_file, Vec::new(), false, if_match).await
     }
 
-    async fn update_and_parse(&mut self, bucket: &str, config_file: &str, data: Vec<u8>, parse: bool) -> Result<OffsetDateTime> {
+    /// Apply a configuration change on top of the metadata persisted on disk.
+    ///
+    /// The persisted copy is re-read so the `if_match` precondition is checked against the latest
+    /// revision written by any node, not against a possibly stale cache.
+    async fn update_and_parse(
+        &mut self,
+        bucket: &str,
+        config_file: &str,
+        data: Vec<u8>,
+        parse: bool,
+        if_match: Option<&str>,
+    ) -> Result<BucketConfigUpdate> {
         let Some(store) = new_object_layer_fn() else {
             return Err(Error::other("errServerNotInitialized"));
         };
@@ -344,7 +505,7 @@ impl BucketMetadataSys {
             return Err(Error::other("errInvalidArgument"));
         }
 
-        let mut bm = match load_bucket_metadata_parse(store, bucket, parse).await {
+        let mut bm = match load_bucket_metadata_parse(store.clone(), bucket, parse).await {
             Ok(res) => res,
             Err(err) => {
                 if !is_erasure().await && !is_dist_erasure().await && is_err_bucket_not_found(&err) {
@@ -356,11 +517,37 @@ impl BucketMetadataSys {
             }
         };
 
+        if let Some(if_match) = if_match
+            && !bm.etag_matches(if_match)
+        {
+            return Err(Error::PreconditionFailed);
+        }
+
+        let deleted = data.is_empty();
+        let previous = String::from_utf8_lossy(bm.config_data(config_file)?).into_owned();
         let updated = bm.update_config(config_file, data)?;
+        bm.revision += 1;
+
+        let res = BucketConfigUpdate {
+            updated,
+            revision: bm.revision,
+            etag: bm.etag(),
+        };
 
         self.save(bm).await?;
 
-        Ok(updated)
+        let change = MetadataChange {
+            revision: res.revision,
+            config_file: config_file.to_string(),
+            updated,
+            deleted,
+            previous,
+        };
+        if let Err(err) = record_change(store, bucket, change).await {
+            warn!("record metadata history of bucket {} failed: {}", bucket, err);
+        }
+
+        Ok(res)
     }
 
     async fn save(&self, bm: BucketMetadata) -> Result<()> {
@@ -523,6 +710,106 @@ impl BucketMetadataSys {
         }
     }
 
+    pub async fn get_placement_config(&self, bucket: &str) -> Result<(BucketPlacement, OffsetDateTime)> {
+        let (bm, _) = self.get_config(bucket).await?;
+
+        if let Some(config) = &bm.placement_config {
+            Ok((*config, bm.placement_config_updated_at))
+        } else {
+            Err(Error::ConfigNotFound)
+        }
+    }
+
+    pub async fn get_transform_config(&self, bucket: &str) -> Result<(BucketTransform, OffsetDateTime)> {
+        let (bm, _) = self.get_config(bucket).await?;
+
+        if let Some(config) = &bm.transform_config {
+            Ok((config.clone(), bm.transform_config_updated_at))
+        } else {
+            Err(Error::ConfigNotFound)
+        }
+    }
+
+    pub async fn get_trash_config(&self, bucket: &str) -> Result<(BucketTrash, OffsetDateTime)> {
+        let (bm, _) = self.get_config(bucket).await?;
+
+        if let Some(config) = &bm.trash_config {
+            Ok((*config, bm.trash_config_updated_at))
+        } else {
+            Err(Error::ConfigNotFound)
+        }
+    }
+
+    pub async fn get_naming_config(&self, bucket: &str) -> Result<(BucketNaming, OffsetDateTime)> {
+        let (bm, _) = self.get_config(bucket).await?;
+
+        if let Some(config) = &bm.naming_config {
+            Ok((*config, bm.naming_config_updated_at))
+        } else {
+            Err(Error::ConfigNotFound)
+        }
+    }
+
+    pub async fn get_listing_config(&self, bucket: &str) -> Result<(BucketListing, OffsetDateTime)> {
+        let (bm, _) = self.get_config(bucket).await?;
+
+        if let Some(config) = &bm.listing_config {
+            Ok((*config, bm.listing_config_updated_at))
+        } else {
+            Err(Error::ConfigNotFound)
+        }
+    }
+
+    pub async fn get_erasure_config(&self, bucket: &str) -> Result<(BucketErasure, OffsetDateTime)> {
+        let (bm, _) = self.get_config(bucket).await?;
+
+        if let Some(config) = &bm.erasure_config {
+            Ok((*config, bm.erasure_config_updated_at))
+        } else {
+            Err(Error::ConfigNotFound)
+        }
+    }
+
+    pub async fn get_mode_config(&self, bucket: &str) -> Result<(BucketMode, OffsetDateTime)> {
+        let (bm, _) = self.get_config(bucket).await?;
+
+        if let Some(config) = &bm.mode_config {
+            Ok((*config, bm.mode_config_updated_at))
+        } else {
+            Err(Error::ConfigNotFound)
+        }
+    }
+
+    pub async fn get_dedup_config(&self, bucket: &str) -> Result<(BucketDedup, OffsetDateTime)> {
+        let (bm, _) = self.get_config(bucket).await?;
+
+        if let Some(config) = &bm.dedup_config {
+            Ok((*config, bm.dedup_config_updated_at))
+        } else {
+            Err(Error::ConfigNotFound)
+        }
+    }
+
+    pub async fn get_integrity_config(&self, bucket: &str) -> Result<(BucketIntegrity, OffsetDateTime)> {
+        let (bm, _) = self.get_config(bucket).await?;
+
+        if let Some(config) = &bm.integrity_config {
+            Ok((*config, bm.integrity_config_updated_at))
+        } else {
+            Err(Error::ConfigNotFound)
+        }
+    }
+
+    pub async fn get_kms_scope_config(&self, bucket: &str) -> Result<(BucketKmsScope, OffsetDateTime)> {
+        let (bm, _) = self.get_config(bucket).await?;
+
+        if let Some(config) = &bm.kms_scope_config {
+            Ok((config.clone(), bm.kms_scope_config_updated_at))
+        } else {
+            Err(Error::ConfigNotFound)
+        }
+    }
+
     pub async fn get_replication_config(&self, bucket: &str) -> Result<(ReplicationConfiguration, OffsetDateTime)> {
         let (bm, reload) = self.get_config(bucket).await?;
 
@@ -13,16 +13,31 @@
 // limitations under the License.
 
 pub mod bucket_target_sys;
+pub mod dedup;
+pub mod erasure;
 pub mod error;
+pub mod integrity;
+pub mod kms_scope;
 pub mod lifecycle;
+pub mod listing;
+pub mod marker_cleanup;
 pub mod metadata;
+pub mod metadata_history;
 pub mod metadata_sys;
+pub mod mode;
+pub mod naming;
 pub mod object_lock;
+pub mod placement;
 pub mod policy_sys;
+pub mod purge;
 pub mod quota;
 pub mod replication;
+pub mod rule_eval;
+pub mod snapshot;
 pub mod tagging;
 pub mod target;
+pub mod transform;
+pub mod trash;
 pub mod utils;
 pub mod versioning;
 pub mod versioning_sys;
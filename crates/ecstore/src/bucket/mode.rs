@@ -0,0 +1,162 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Administrative bucket modes.
+//!
+//! A read-only bucket rejects every change to its objects, a write-once bucket accepts new objects
+//! but never overwrites or deletes one. Modes are enforced by the object layer, beneath policy
+//! evaluation, so no policy or credential can lift them; only an administrator changing the mode
+//! of the bucket can. Internal data movement such as decommission and rebalance is not affected.
+
+use super::metadata_sys;
+use super::utils::is_meta_bucketname;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BucketAccessMode {
+    #[default]
+    Normal,
+    ReadOnly,
+    WriteOnce,
+}
+
+impl fmt::Display for BucketAccessMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BucketAccessMode::Normal => write!(f, "normal"),
+            BucketAccessMode::ReadOnly => write!(f, "read-only"),
+            BucketAccessMode::WriteOnce => write!(f, "write-once"),
+        }
+    }
+}
+
+/// Changes to the objects of a bucket a mode may forbid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketMutation {
+    /// Write an object that does not exist yet.
+    Create,
+    /// Write over an existing object.
+    Overwrite,
+    Delete,
+    /// Change the tags or metadata of an object, or upload a part of one.
+    Update,
+}
+
+impl fmt::Display for BucketMutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BucketMutation::Create => write!(f, "writing a new object"),
+            BucketMutation::Overwrite => write!(f, "overwriting an object"),
+            BucketMutation::Delete => write!(f, "deleting an object"),
+            BucketMutation::Update => write!(f, "updating an object"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketMode {
+    #[serde(default)]
+    pub mode: BucketAccessMode,
+}
+
+impl BucketMode {
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(buf)?)
+    }
+
+    /// Whether a write must be told apart from an overwrite to be checked.
+    pub fn tells_overwrites(&self) -> bool {
+        self.mode == BucketAccessMode::WriteOnce
+    }
+
+    /// Check a change to an object of `bucket` against the mode.
+    pub fn check(&self, bucket: &str, mutation: BucketMutation) -> Result<()> {
+        let allowed = match self.mode {
+            BucketAccessMode::Normal => true,
+            BucketAccessMode::ReadOnly => false,
+            BucketAccessMode::WriteOnce => matches!(mutation, BucketMutation::Create | BucketMutation::Update),
+        };
+        if allowed {
+            return Ok(());
+        }
+        Err(Error::BucketModeViolation(
+            bucket.to_string(),
+            self.mode.to_string(),
+            mutation.to_string(),
+        ))
+    }
+}
+
+/// Mode of `bucket`, normal for buckets without one.
+pub async fn bucket_mode(bucket: &str) -> BucketMode {
+    if is_meta_bucketname(bucket) {
+        return BucketMode::default();
+    }
+
+    metadata_sys::get_mode_config(bucket)
+        .await
+        .map(|(cfg, _)| cfg)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_roundtrip() {
+        let mode = BucketMode::unmarshal(br#"{"mode":"write-once"}"#).unwrap();
+        assert_eq!(mode.mode, BucketAccessMode::WriteOnce);
+        assert_eq!(BucketMode::unmarshal(&mode.marshal().unwrap()).unwrap(), mode);
+
+        assert_eq!(BucketMode::unmarshal(b"{}").unwrap(), BucketMode::default());
+        assert!(BucketMode::unmarshal(br#"{"mode":"append-only"}"#).is_err());
+    }
+
+    #[test]
+    fn test_mode_check() {
+        let all = [
+            BucketMutation::Create,
+            BucketMutation::Overwrite,
+            BucketMutation::Delete,
+            BucketMutation::Update,
+        ];
+
+        let normal = BucketMode::default();
+        assert!(all.iter().all(|m| normal.check("bucket", *m).is_ok()));
+
+        let read_only = BucketMode {
+            mode: BucketAccessMode::ReadOnly,
+        };
+        for m in all {
+            assert!(matches!(read_only.check("bucket", m), Err(Error::BucketModeViolation(..))), "{m}");
+        }
+
+        let write_once = BucketMode {
+            mode: BucketAccessMode::WriteOnce,
+        };
+        assert!(write_once.check("bucket", BucketMutation::Create).is_ok());
+        assert!(write_once.check("bucket", BucketMutation::Update).is_ok());
+        assert!(write_once.check("bucket", BucketMutation::Overwrite).is_err());
+        assert!(write_once.check("bucket", BucketMutation::Delete).is_err());
+    }
+}
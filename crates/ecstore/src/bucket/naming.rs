@@ -0,0 +1,154 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-bucket object name policy.
+//!
+//! New object names are checked against the policy of their bucket before any data is written, so
+//! names the disks cannot store are rejected with `InvalidObjectName` up front. Buckets without a
+//! configuration use the default policy, which only rejects control characters and names longer
+//! than the S3 limit. Names reach this layer as UTF-8, the request parser rejects anything else.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// Longest object name S3 accepts, in bytes.
+pub const MAX_OBJECT_NAME_LENGTH: usize = 1024;
+
+/// Characters Windows does not allow in file names.
+const WINDOWS_RESERVED_CHARS: &[char] = &['\\', ':', '*', '?', '"', '<', '>', '|'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketNaming {
+    /// Reject names that cannot be stored on Windows file systems.
+    #[serde(default)]
+    pub windows_compat: bool,
+    /// Longest name in bytes, at most [`MAX_OBJECT_NAME_LENGTH`].
+    #[serde(default = "default_max_key_length")]
+    pub max_key_length: usize,
+    /// Most `/` separated components a name may have, 0 for no limit.
+    #[serde(default)]
+    pub max_path_depth: usize,
+}
+
+fn default_max_key_length() -> usize {
+    MAX_OBJECT_NAME_LENGTH
+}
+
+impl Default for BucketNaming {
+    fn default() -> Self {
+        Self {
+            windows_compat: false,
+            max_key_length: MAX_OBJECT_NAME_LENGTH,
+            max_path_depth: 0,
+        }
+    }
+}
+
+impl BucketNaming {
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(buf)?)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.max_key_length == 0 || self.max_key_length > MAX_OBJECT_NAME_LENGTH {
+            return Err(Error::other(format!(
+                "max key length must be between 1 and {MAX_OBJECT_NAME_LENGTH} bytes"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check a new object name of `bucket`, the error tells which rule it breaks.
+    pub fn check_object_name(&self, bucket: &str, object: &str) -> Result<()> {
+        let invalid = |reason: String| Err(Error::InvalidObjectName(bucket.to_string(), object.to_string(), reason));
+
+        if object.len() > self.max_key_length {
+            return invalid(format!("name is longer than {} bytes", self.max_key_length));
+        }
+
+        if let Some(c) = object.chars().find(|c| c.is_control()) {
+            return invalid(format!("name contains control character U+{:04X}", c as u32));
+        }
+
+        if self.windows_compat
+            && let Some(c) = object.chars().find(|c| WINDOWS_RESERVED_CHARS.contains(c))
+        {
+            return invalid(format!("name contains '{c}', which is not allowed in this bucket"));
+        }
+
+        if self.max_path_depth > 0 {
+            let depth = object.trim_end_matches('/').split('/').count();
+            if depth > self.max_path_depth {
+                return invalid(format!("name has {depth} path components, at most {} are allowed", self.max_path_depth));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_naming_roundtrip() {
+        let naming = BucketNaming::unmarshal(br#"{"windowsCompat":true}"#).unwrap();
+        assert!(naming.windows_compat);
+        assert_eq!(naming.max_key_length, MAX_OBJECT_NAME_LENGTH);
+        assert_eq!(naming.max_path_depth, 0);
+        assert_eq!(BucketNaming::unmarshal(&naming.marshal().unwrap()).unwrap(), naming);
+
+        assert!(BucketNaming::unmarshal(br#"{"maxKeyLength":0}"#).unwrap().validate().is_err());
+        assert!(
+            BucketNaming::unmarshal(br#"{"maxKeyLength":2048}"#)
+                .unwrap()
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_check_object_name() {
+        let default = BucketNaming::default();
+        assert!(default.check_object_name("bucket", "dir/a:b*c?.txt").is_ok());
+        assert!(matches!(
+            default.check_object_name("bucket", "a\u{0}b"),
+            Err(Error::InvalidObjectName(_, _, _))
+        ));
+        assert!(default.check_object_name("bucket", "tab\there").is_err());
+        assert!(
+            default
+                .check_object_name("bucket", &"a".repeat(MAX_OBJECT_NAME_LENGTH + 1))
+                .is_err()
+        );
+
+        let strict = BucketNaming {
+            windows_compat: true,
+            max_key_length: 16,
+            max_path_depth: 2,
+        };
+        assert!(strict.check_object_name("bucket", "dir/file.txt").is_ok());
+        assert!(strict.check_object_name("bucket", "dir/sub/").is_ok());
+        assert!(strict.check_object_name("bucket", "a\\b").is_err());
+        assert!(strict.check_object_name("bucket", "a:b").is_err());
+        assert!(strict.check_object_name("bucket", "a/b/c").is_err());
+        assert!(strict.check_object_name("bucket", "a-much-longer-name").is_err());
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bucket placement configuration, pinning new objects of a bucket to a class of pools.
+
+use crate::config::storageclass;
+use crate::error::Result;
+use crate::pools::PoolClass;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BucketPlacement {
+    pub class: PoolClass,
+}
+
+impl BucketPlacement {
+    pub fn new(class: PoolClass) -> Self {
+        Self { class }
+    }
+
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(buf)?)
+    }
+}
+
+/// Map an S3 storage class to the pool class that should hold it, if it has a preference.
+pub fn pool_class_for_storage_class(storage_class: &str) -> Option<PoolClass> {
+    match storage_class {
+        storageclass::STANDARD_IA
+        | storageclass::ONEZONE_IA
+        | storageclass::GLACIER
+        | storageclass::GLACIER_IR
+        | storageclass::DEEP_ARCHIVE => Some(PoolClass::Cold),
+        storageclass::EXPRESS_ONEZONE => Some(PoolClass::Hot),
+        _ => None,
+    }
+}
+
+/// Resolve the pool class a new object should land on.
+///
+/// The bucket placement configuration takes precedence over the storage class of the request.
+pub fn resolve_pool_class(placement: Option<&BucketPlacement>, storage_class: Option<&str>) -> Option<PoolClass> {
+    placement
+        .map(|p| p.class)
+        .or_else(|| storage_class.and_then(pool_class_for_storage_class))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placement_roundtrip() {
+        let placement = BucketPlacement::new(PoolClass::Cold);
+        let data = placement.marshal().unwrap();
+        assert_eq!(std::str::from_utf8(&data).unwrap(), r#"{"class":"cold"}"#);
+        assert_eq!(BucketPlacement::unmarshal(&data).unwrap(), placement);
+    }
+
+    #[test]
+    fn test_resolve_pool_class() {
+        assert_eq!(resolve_pool_class(None, None), None);
+        assert_eq!(resolve_pool_class(None, Some(storageclass::STANDARD)), None);
+        assert_eq!(resolve_pool_class(None, Some(storageclass::GLACIER)), Some(PoolClass::Cold));
+        assert_eq!(resolve_pool_class(None, Some(storageclass::EXPRESS_ONEZONE)), Some(PoolClass::Hot));
+
+        let hot = BucketPlacement::new(PoolClass::Hot);
+        assert_eq!(resolve_pool_class(Some(&hot), Some(storageclass::DEEP_ARCHIVE)), Some(PoolClass::Hot));
+    }
+}
@@ -0,0 +1,401 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Force deletion of non-empty buckets.
+//!
+//! A force-deleted bucket is recorded in the cluster config right away, which hides it from the
+//! S3 API, while a background job on the node that accepted the request deletes its object
+//! versions in batches and finally removes the bucket itself. Progress is persisted after every
+//! batch, so the job resumes after a restart and can be polled through the admin API.
+
+use crate::bucket::metadata_sys;
+use crate::bucket::object_lock::ObjectLockApi;
+use crate::config::com::{read_config, save_config};
+use crate::error::{Error, Result, StorageError};
+use crate::store::ECStore;
+use crate::store_api::{BucketOptions, DeleteBucketOptions, ObjectOptions, ObjectToDelete, StorageAPI};
+use parking_lot::RwLock;
+use rustfs_common::globals::GLOBAL_Local_Node_Name;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+pub const BUCKET_PURGE_CONFIG_PATH: &str = "config/bucket-purge.json";
+
+/// How often a node reloads the purge state to learn about buckets force-deleted on peers.
+pub const BUCKET_PURGE_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Number of object versions listed and deleted per batch.
+const PURGE_BATCH_SIZE: i32 = 1000;
+
+pub static GLOBAL_BUCKET_PURGE_SYS: LazyLock<BucketPurgeSys> = LazyLock::new(BucketPurgeSys::default);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PurgeState {
+    Running,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketPurgeStatus {
+    pub bucket: String,
+    /// The node running the purge job.
+    pub node: String,
+    pub state: PurgeState,
+    #[serde(with = "time::serde::rfc3339")]
+    pub started: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated: OffsetDateTime,
+    pub versions_deleted: u64,
+    pub bytes_deleted: u64,
+    pub versions_failed: u64,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+impl BucketPurgeStatus {
+    fn new(bucket: &str, node: &str) -> Self {
+        let now = OffsetDateTime::now_utc();
+        Self {
+            bucket: bucket.to_string(),
+            node: node.to_string(),
+            state: PurgeState::Running,
+            started: now,
+            updated: now,
+            versions_deleted: 0,
+            bytes_deleted: 0,
+            versions_failed: 0,
+            error: String::new(),
+        }
+    }
+
+    fn fail(&mut self, err: impl std::fmt::Display) {
+        self.state = PurgeState::Failed;
+        self.error = err.to_string();
+        self.updated = OffsetDateTime::now_utc();
+    }
+}
+
+/// Buckets pending purge, keyed by bucket name. Entries are removed once the bucket is gone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BucketPurgeState {
+    #[serde(default)]
+    pub buckets: BTreeMap<String, BucketPurgeStatus>,
+}
+
+impl BucketPurgeState {
+    pub fn unmarshal(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data).map_err(Error::other)
+    }
+
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(Error::other)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BucketPurgeSys {
+    state: RwLock<BucketPurgeState>,
+    /// Serializes read-modify-write cycles of the persisted state on this node.
+    write_lock: Mutex<()>,
+}
+
+impl BucketPurgeSys {
+    async fn read_state(store: Arc<ECStore>) -> Result<BucketPurgeState> {
+        match read_config(store, BUCKET_PURGE_CONFIG_PATH).await {
+            Ok(data) => BucketPurgeState::unmarshal(&data),
+            Err(Error::ConfigNotFound) => Ok(BucketPurgeState::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Reload the persisted state.
+    pub async fn load(&self, store: Arc<ECStore>) -> Result<()> {
+        let state = Self::read_state(store).await?;
+        *self.state.write() = state;
+        Ok(())
+    }
+
+    /// Apply `f` to the persisted state and save it.
+    async fn modify(&self, store: Arc<ECStore>, f: impl FnOnce(&mut BucketPurgeState)) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+
+        let mut state = Self::read_state(store.clone()).await?;
+        f(&mut state);
+        save_config(store, BUCKET_PURGE_CONFIG_PATH, state.marshal()?).await?;
+
+        *self.state.write() = state;
+        Ok(())
+    }
+
+    /// Whether `bucket` is force-deleted and must be hidden from clients.
+    pub fn is_purging(&self, bucket: &str) -> bool {
+        self.state.read().buckets.contains_key(bucket)
+    }
+
+    pub fn status(&self, bucket: &str) -> Option<BucketPurgeStatus> {
+        self.state.read().buckets.get(bucket).cloned()
+    }
+
+    pub fn list(&self) -> Vec<BucketPurgeStatus> {
+        self.state.read().buckets.values().cloned().collect()
+    }
+
+    /// Mark `bucket` deleted and start purging it in the background.
+    ///
+    /// Starting a purge for a bucket that is already being purged returns its current status;
+    /// a failed purge is restarted on this node.
+    pub async fn start(&'static self, store: Arc<ECStore>, bucket: &str) -> Result<BucketPurgeStatus> {
+        if let Some(status) = self.status(bucket)
+            && status.state == PurgeState::Running
+        {
+            return Ok(status);
+        }
+
+        if !self.is_purging(bucket) {
+            store.get_bucket_info(bucket, &BucketOptions::default()).await?;
+
+            // Locked objects must outlive their retention, so such buckets are never force-deleted
+            if let Ok((cfg, _)) = metadata_sys::get_object_lock_config(bucket).await
+                && cfg.enabled()
+            {
+                return Err(StorageError::other(format!(
+                    "bucket {bucket} has object lock enabled and cannot be force deleted"
+                )));
+            }
+        }
+
+        let node = GLOBAL_Local_Node_Name.read().await.clone();
+        let status = match self.status(bucket) {
+            // Restart a failed purge, keeping its counters
+            Some(mut status) => {
+                status.node.clone_from(&node);
+                status.state = PurgeState::Running;
+                status.error.clear();
+                status.updated = OffsetDateTime::now_utc();
+                status
+            }
+            None => BucketPurgeStatus::new(bucket, &node),
+        };
+        {
+            let status = status.clone();
+            self.modify(store.clone(), move |state| {
+                state.buckets.insert(status.bucket.clone(), status);
+            })
+            .await?;
+        }
+
+        info!(bucket, node, "bucket marked deleted, purging in the background");
+        self.spawn(store, status.clone());
+        Ok(status)
+    }
+
+    /// Resume the running purge jobs owned by this node.
+    pub async fn resume(&'static self, store: Arc<ECStore>) {
+        let node = GLOBAL_Local_Node_Name.read().await.clone();
+        for status in self.list() {
+            if status.state == PurgeState::Running && status.node == node {
+                info!(bucket = status.bucket, "resuming bucket purge");
+                self.spawn(store.clone(), status);
+            }
+        }
+    }
+
+    fn spawn(&'static self, store: Arc<ECStore>, status: BucketPurgeStatus) {
+        tokio::spawn(async move {
+            let bucket = status.bucket.clone();
+            if let Err(err) = self.run(store, status).await {
+                error!(bucket, "bucket purge failed: {:?}", err);
+            }
+        });
+    }
+
+    async fn run(&self, store: Arc<ECStore>, mut status: BucketPurgeStatus) -> Result<()> {
+        let bucket = status.bucket.clone();
+
+        loop {
+            let listing = match store
+                .clone()
+                .list_object_versions(&bucket, "", None, None, None, PURGE_BATCH_SIZE)
+                .await
+            {
+                Ok(listing) => listing,
+                Err(StorageError::BucketNotFound(_)) | Err(StorageError::VolumeNotFound) => break,
+                Err(err) => {
+                    status.fail(&err);
+                    self.save_status(store, status).await?;
+                    return Err(err);
+                }
+            };
+
+            if listing.objects.is_empty() {
+                break;
+            }
+
+            let sizes: Vec<i64> = listing.objects.iter().map(|obj| obj.size).collect();
+            let objects = listing
+                .objects
+                .into_iter()
+                .map(|obj| ObjectToDelete {
+                    object_name: obj.name,
+                    // Always name the version, otherwise versioned buckets would only get a delete marker
+                    version_id: Some(obj.version_id.unwrap_or(Uuid::nil())),
+                    ..Default::default()
+                })
+                .collect();
+
+            let (_, errs) = store.delete_objects(&bucket, objects, ObjectOptions::default()).await;
+
+            let mut deleted = 0;
+            for (err, size) in errs.iter().zip(sizes) {
+                match err {
+                    None => {
+                        deleted += 1;
+                        status.bytes_deleted += size.max(0) as u64;
+                    }
+                    Some(err) => {
+                        status.versions_failed += 1;
+                        warn!(bucket, "purge object version failed: {:?}", err);
+                    }
+                }
+            }
+            status.versions_deleted += deleted;
+            status.updated = OffsetDateTime::now_utc();
+
+            // The listing restarts from the beginning every round, so a batch that deletes
+            // nothing would be listed again forever.
+            if deleted == 0 {
+                status.fail("no object version of the batch could be deleted");
+                self.save_status(store, status).await?;
+                return Err(Error::other("bucket purge stalled"));
+            }
+
+            self.save_status(store.clone(), status.clone()).await?;
+        }
+
+        match store
+            .delete_bucket(
+                &bucket,
+                &DeleteBucketOptions {
+                    force: true,
+                    no_recreate: true,
+                    ..Default::default()
+                },
+            )
+            .await
+        {
+            Ok(_) | Err(StorageError::BucketNotFound(_)) | Err(StorageError::VolumeNotFound) => {}
+            Err(err) => {
+                status.fail(&err);
+                self.save_status(store, status).await?;
+                return Err(err);
+            }
+        }
+
+        self.modify(store, |state| {
+            state.buckets.remove(&bucket);
+        })
+        .await?;
+
+        info!(
+            bucket,
+            versions = status.versions_deleted,
+            bytes = status.bytes_deleted,
+            "bucket purge completed"
+        );
+        Ok(())
+    }
+
+    async fn save_status(&self, store: Arc<ECStore>, status: BucketPurgeStatus) -> Result<()> {
+        self.modify(store, move |state| {
+            state.buckets.insert(status.bucket.clone(), status);
+        })
+        .await
+    }
+}
+
+/// Load the purge state, resume the jobs owned by this node and keep the state in sync with peers.
+pub async fn init_bucket_purge_sys(store: Arc<ECStore>, cancel: CancellationToken) {
+    if let Err(err) = GLOBAL_BUCKET_PURGE_SYS.load(store.clone()).await {
+        warn!("load bucket purge state failed: {:?}", err);
+    }
+
+    GLOBAL_BUCKET_PURGE_SYS.resume(store.clone()).await;
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(BUCKET_PURGE_REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = interval.tick() => {
+                    if let Err(err) = GLOBAL_BUCKET_PURGE_SYS.load(store.clone()).await {
+                        warn!("refresh bucket purge state failed: {:?}", err);
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_purge_state_round_trip() {
+        let mut state = BucketPurgeState::default();
+        let mut status = BucketPurgeStatus::new("photos", "node1:9000");
+        status.versions_deleted = 10;
+        state.buckets.insert(status.bucket.clone(), status);
+
+        let data = state.marshal().unwrap();
+        let text = String::from_utf8(data.clone()).unwrap();
+        assert!(text.contains("\"state\":\"running\""));
+        assert!(text.contains("\"versionsDeleted\":10"));
+        assert!(!text.contains("\"error\""));
+
+        assert_eq!(BucketPurgeState::unmarshal(&data).unwrap(), state);
+        assert_eq!(BucketPurgeState::unmarshal(b"{}").unwrap(), BucketPurgeState::default());
+    }
+
+    #[test]
+    fn test_purge_status_fail() {
+        let mut status = BucketPurgeStatus::new("photos", "node1:9000");
+        status.fail("disk offline");
+        assert_eq!(status.state, PurgeState::Failed);
+        assert_eq!(status.error, "disk offline");
+    }
+
+    #[test]
+    fn test_is_purging() {
+        let sys = BucketPurgeSys::default();
+        assert!(!sys.is_purging("photos"));
+
+        sys.state
+            .write()
+            .buckets
+            .insert("photos".to_string(), BucketPurgeStatus::new("photos", "node1:9000"));
+        assert!(sys.is_purging("photos"));
+        assert_eq!(sys.list().len(), 1);
+    }
+}
@@ -14,6 +14,7 @@
 
 mod config;
 pub mod datatypes;
+mod replication_checker;
 mod replication_pool;
 mod replication_resyncer;
 mod replication_state;
@@ -21,6 +22,7 @@ mod rule;
 
 pub use config::*;
 pub use datatypes::*;
+pub use replication_checker::*;
 pub use replication_pool::*;
 pub use replication_resyncer::*;
 pub use rule::*;
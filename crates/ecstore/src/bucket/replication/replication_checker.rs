@@ -0,0 +1,216 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification that replicated objects still match their copies on the targets.
+//!
+//! The scanner hands over a sample of objects whose replication completed, and every target they
+//! were replicated to is asked for its copy. A missing copy, or one whose data or metadata differ,
+//! counts as drift for that target. Drifted objects can be queued again through the heal path,
+//! which compares the copies once more and only sends what differs.
+
+use crate::bucket::bucket_target_sys::BucketTargetSys;
+use crate::bucket::metadata_sys;
+use crate::bucket::replication::{GLOBAL_REPLICATION_POOL, ReplicationConfig, get_heal_replicate_object_info};
+use crate::store_api::ObjectInfo;
+use rustfs_filemeta::{ReplicationAction, ReplicationStatusType, ReplicationType, replication_statuses_map};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use time::OffsetDateTime;
+use tracing::{debug, warn};
+
+use super::replication_resyncer::get_replication_action;
+
+pub static GLOBAL_REPLICATION_DRIFT: LazyLock<ReplicationDrift> = LazyLock::new(ReplicationDrift::default);
+
+/// Outcome of comparing an object with its copy on one target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplicaState {
+    InSync,
+    Missing,
+    /// Data, size or version differ.
+    ContentDrift,
+    /// Only metadata or tags differ.
+    MetadataDrift,
+    /// The target could not be asked.
+    Unreachable(String),
+}
+
+impl ReplicaState {
+    pub fn is_drift(&self) -> bool {
+        matches!(self, Self::Missing | Self::ContentDrift | Self::MetadataDrift)
+    }
+}
+
+/// Check results of one target since the node started.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetDriftStats {
+    pub checked: u64,
+    pub in_sync: u64,
+    pub missing: u64,
+    pub content_drift: u64,
+    pub metadata_drift: u64,
+    pub errors: u64,
+    pub requeued: u64,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub last_drift: Option<OffsetDateTime>,
+}
+
+impl TargetDriftStats {
+    fn record(&mut self, state: &ReplicaState, requeued: bool) {
+        self.checked += 1;
+        match state {
+            ReplicaState::InSync => self.in_sync += 1,
+            ReplicaState::Missing => self.missing += 1,
+            ReplicaState::ContentDrift => self.content_drift += 1,
+            ReplicaState::MetadataDrift => self.metadata_drift += 1,
+            ReplicaState::Unreachable(_) => self.errors += 1,
+        }
+        if state.is_drift() {
+            self.last_drift = Some(OffsetDateTime::now_utc());
+        }
+        if requeued {
+            self.requeued += 1;
+        }
+    }
+}
+
+/// Drift counters of this node, by target ARN.
+#[derive(Debug, Default)]
+pub struct ReplicationDrift {
+    targets: Mutex<HashMap<String, TargetDriftStats>>,
+}
+
+impl ReplicationDrift {
+    fn record(&self, arn: &str, state: &ReplicaState, requeued: bool) {
+        let mut targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+        targets.entry(arn.to_string()).or_default().record(state, requeued);
+    }
+
+    pub fn stats(&self) -> HashMap<String, TargetDriftStats> {
+        self.targets.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Compare `oi` with its copy on the target `arn`.
+pub async fn check_replica(oi: &ObjectInfo, arn: &str) -> ReplicaState {
+    let Some(tgt_client) = BucketTargetSys::get().get_remote_target_client(&oi.bucket, arn).await else {
+        return ReplicaState::Unreachable(format!("no client for target {arn}"));
+    };
+
+    match tgt_client
+        .head_object(&tgt_client.bucket, &oi.name, oi.version_id.map(|v| v.to_string()))
+        .await
+    {
+        Ok(head) => match get_replication_action(oi, &head, ReplicationType::Heal) {
+            ReplicationAction::None => ReplicaState::InSync,
+            ReplicationAction::Metadata => ReplicaState::MetadataDrift,
+            ReplicationAction::All => ReplicaState::ContentDrift,
+        },
+        Err(err) => {
+            if err.as_service_error().is_some_and(|e| e.is_not_found()) {
+                ReplicaState::Missing
+            } else {
+                ReplicaState::Unreachable(err.to_string())
+            }
+        }
+    }
+}
+
+/// Verify the copies of `oi` on every target its replication completed to, and queue it for
+/// re-replication when `requeue` is set and a copy drifted. Returns the state of each target.
+pub async fn check_replicated_object(oi: &ObjectInfo, requeue: bool) -> Vec<(String, ReplicaState)> {
+    if oi.delete_marker {
+        return Vec::new();
+    }
+
+    let targets: Vec<String> = replication_statuses_map(oi.replication_status_internal.as_deref().unwrap_or_default())
+        .into_iter()
+        .filter(|(_, status)| *status == ReplicationStatusType::Completed)
+        .map(|(arn, _)| arn)
+        .collect();
+
+    let mut states = Vec::with_capacity(targets.len());
+    for arn in targets {
+        let state = check_replica(oi, &arn).await;
+        if state.is_drift() {
+            warn!("replica of {}/{} on {} drifted: {:?}", oi.bucket, oi.name, arn, state);
+        } else if let ReplicaState::Unreachable(err) = &state {
+            debug!("replica of {}/{} on {} not checked: {}", oi.bucket, oi.name, arn, err);
+        }
+        states.push((arn, state));
+    }
+
+    let requeued = requeue && states.iter().any(|(_, state)| state.is_drift()) && requeue_object(oi).await;
+    for (arn, state) in &states {
+        GLOBAL_REPLICATION_DRIFT.record(arn, state, requeued && state.is_drift());
+    }
+
+    states
+}
+
+async fn requeue_object(oi: &ObjectInfo) -> bool {
+    let Some(pool) = GLOBAL_REPLICATION_POOL.get() else {
+        return false;
+    };
+    let Ok((config, _)) = metadata_sys::get_replication_config(&oi.bucket).await else {
+        return false;
+    };
+    let remotes = BucketTargetSys::get().list_bucket_targets(&oi.bucket).await.ok();
+
+    let roi = get_heal_replicate_object_info(oi, &ReplicationConfig::new(Some(config), remotes)).await;
+    pool.queue_replica_task(roi).await;
+    true
+}
+
+/// Pick up to `count` of `len` items to check this cycle, rotating through all of them over
+/// successive cycles.
+pub fn sample_window(len: usize, count: usize, cycle: u64) -> impl Iterator<Item = usize> {
+    let count = count.min(len);
+    let start = if len == 0 {
+        0
+    } else {
+        ((cycle as u128 * count as u128) % len as u128) as usize
+    };
+    (0..count).map(move |i| (start + i) % len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drift_stats() {
+        let drift = ReplicationDrift::default();
+        drift.record("arn1", &ReplicaState::InSync, false);
+        drift.record("arn1", &ReplicaState::Missing, true);
+        drift.record("arn1", &ReplicaState::Unreachable("timeout".to_string()), false);
+        drift.record("arn2", &ReplicaState::MetadataDrift, false);
+
+        let stats = drift.stats();
+        let arn1 = &stats["arn1"];
+        assert_eq!((arn1.checked, arn1.in_sync, arn1.missing, arn1.errors, arn1.requeued), (3, 1, 1, 1, 1));
+        assert!(arn1.last_drift.is_some());
+        assert_eq!(stats["arn2"].metadata_drift, 1);
+    }
+
+    #[test]
+    fn test_sample_window() {
+        assert_eq!(sample_window(5, 2, 0).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(sample_window(5, 2, 2).collect::<Vec<_>>(), vec![4, 0]);
+        assert_eq!(sample_window(3, 10, 7).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(sample_window(0, 4, 1).count(), 0);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::bandwidth::{GLOBAL_BANDWIDTH_SYS, TrafficClass};
 use crate::bucket::bucket_target_sys::{
     AdvancedPutOptions, BucketTargetSys, PutObjectOptions, PutObjectPartOptions, RemoveObjectOptions, TargetClient,
 };
@@ -1062,7 +1063,7 @@ pub async fn check_replicate_delete(
 }
 
 /// Check if the user-defined metadata contains SSEC encryption headers
-fn is_ssec_encrypted(user_defined: &std::collections::HashMap<String, String>) -> bool {
+pub(crate) fn is_ssec_encrypted(user_defined: &std::collections::HashMap<String, String>) -> bool {
     user_defined.contains_key(SSEC_ALGORITHM_HEADER)
         || user_defined.contains_key(SSEC_KEY_HEADER)
         || user_defined.contains_key(SSEC_KEY_MD5_HEADER)
@@ -1789,7 +1790,9 @@ impl ReplicateObjectInfoExt for ReplicateObjectInfo {
             }
         };
 
-        // TODO:bandwidth
+        GLOBAL_BANDWIDTH_SYS
+            .throttle(TrafficClass::Replication, u64::try_from(size).unwrap_or_default())
+            .await;
 
         if let Some(err) = if is_multipart {
             replicate_object_with_multipart(tgt_client.clone(), &tgt_client.bucket, &object, gr.stream, &object_info, put_opts)
@@ -2348,7 +2351,7 @@ async fn replicate_object_with_multipart(
     Ok(())
 }
 
-fn get_replication_action(oi1: &ObjectInfo, oi2: &HeadObjectOutput, op_type: ReplicationType) -> ReplicationAction {
+pub(crate) fn get_replication_action(oi1: &ObjectInfo, oi2: &HeadObjectOutput, op_type: ReplicationType) -> ReplicationAction {
     if op_type == ReplicationType::ExistingObject
         && oi1.mod_time
             > oi2
@@ -0,0 +1,233 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dry-run evaluation of the lifecycle and replication rules of a bucket.
+//!
+//! An object is run through the same evaluation the scanner and the replication pool use, and the
+//! rules it matches, the lifecycle action due on it and whether it would be replicated are
+//! reported. Nothing is expired, transitioned or replicated. Lifecycle rules may be evaluated at a
+//! later time to tell when an action becomes due.
+
+use crate::bucket::lifecycle::bucket_lifecycle_ops::eval_action_from_lifecycle_at;
+use crate::bucket::lifecycle::lifecycle::{IlmAction, Lifecycle, ObjectOpts as LifecycleObjectOpts};
+use crate::bucket::replication::{ObjectOpts, ReplicationConfigurationExt, is_ssec_encrypted};
+use crate::store_api::ObjectInfo;
+use rustfs_filemeta::{ReplicationStatusType, ReplicationType};
+use s3s::dto::{BucketLifecycleConfiguration, DefaultRetention, ReplicationConfiguration};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleEvaluation {
+    /// Enabled rules whose filter matches the object.
+    pub matched_rules: Vec<String>,
+    /// Action the scanner would take, "NoneAction" when nothing is due.
+    pub action: String,
+    /// Rule the action comes from.
+    pub rule_id: String,
+    /// When the action became due, None when no action is due.
+    #[serde(with = "time::serde::rfc3339::option", default)]
+    pub due: Option<OffsetDateTime>,
+    /// Tier of a transition.
+    pub storage_class: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicationRuleMatch {
+    pub rule_id: String,
+    pub priority: i32,
+    /// ARN of the target bucket.
+    pub destination: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicationEvaluation {
+    /// Enabled rules whose filter matches the object, in the order they are applied.
+    pub matched_rules: Vec<ReplicationRuleMatch>,
+    /// Targets the object would be replicated to.
+    pub targets: Vec<String>,
+    pub replicate: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectRuleEvaluation {
+    pub object: String,
+    pub version_id: String,
+    pub is_latest: bool,
+    pub delete_marker: bool,
+    /// None when the bucket has no lifecycle configuration.
+    pub lifecycle: Option<LifecycleEvaluation>,
+    /// None when the bucket has no replication configuration.
+    pub replication: Option<ReplicationEvaluation>,
+}
+
+/// Evaluate the lifecycle rules of a bucket against one object version as a scan at `now` would.
+pub async fn evaluate_lifecycle(
+    lc: &BucketLifecycleConfiguration,
+    retention: Option<DefaultRetention>,
+    rcfg: Option<(ReplicationConfiguration, OffsetDateTime)>,
+    oi: &ObjectInfo,
+    now: OffsetDateTime,
+) -> LifecycleEvaluation {
+    let opts = LifecycleObjectOpts {
+        name: oi.name.clone(),
+        user_tags: oi.user_tags.clone(),
+        ..Default::default()
+    };
+    let matched_rules = lc
+        .filter_rules(&opts)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|rule| rule.id.unwrap_or_default())
+        .collect();
+
+    // Lifecycle evaluation needs a modification time to count days from
+    if oi.mod_time.is_none() {
+        return LifecycleEvaluation {
+            matched_rules,
+            action: IlmAction::NoneAction.to_string(),
+            ..Default::default()
+        };
+    }
+
+    let event = eval_action_from_lifecycle_at(lc, retention, rcfg, oi, now).await;
+    let due = (event.action != IlmAction::NoneAction).then_some(event.due).flatten();
+    LifecycleEvaluation {
+        matched_rules,
+        action: event.action.to_string(),
+        rule_id: event.rule_id,
+        due,
+        storage_class: event.storage_class,
+    }
+}
+
+/// Evaluate the replication rules of a bucket against one object version as a PUT or DELETE would.
+pub fn evaluate_replication(cfg: &ReplicationConfiguration, oi: &ObjectInfo) -> ReplicationEvaluation {
+    let opts = ObjectOpts {
+        name: oi.name.clone(),
+        user_tags: oi.user_tags.clone(),
+        version_id: oi.version_id,
+        delete_marker: oi.delete_marker,
+        ssec: is_ssec_encrypted(&oi.user_defined),
+        op_type: if oi.delete_marker {
+            ReplicationType::Delete
+        } else {
+            ReplicationType::Object
+        },
+        replica: oi.replication_status == ReplicationStatusType::Replica,
+        ..Default::default()
+    };
+
+    let matched_rules = cfg
+        .filter_actionable_rules(&opts)
+        .into_iter()
+        .map(|rule| ReplicationRuleMatch {
+            rule_id: rule.id.unwrap_or_default(),
+            priority: rule.priority.unwrap_or_default(),
+            destination: rule.destination.bucket,
+        })
+        .collect();
+
+    let targets = cfg.filter_target_arns(&opts);
+    let replicate = !targets.is_empty()
+        && targets.iter().any(|arn| {
+            cfg.replicate(&ObjectOpts {
+                target_arn: arn.clone(),
+                ..opts.clone()
+            })
+        });
+
+    ReplicationEvaluation {
+        matched_rules,
+        targets,
+        replicate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bucket::utils::deserialize;
+
+    fn object(name: &str, age_days: i64) -> ObjectInfo {
+        ObjectInfo {
+            bucket: "bucket".to_string(),
+            name: name.to_string(),
+            mod_time: Some(OffsetDateTime::now_utc() - time::Duration::days(age_days)),
+            is_latest: true,
+            num_versions: 1,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_lifecycle() {
+        let lc = deserialize::<BucketLifecycleConfiguration>(
+            br#"<LifecycleConfiguration>
+    <Rule><ID>logs</ID><Status>Enabled</Status><Prefix>logs/</Prefix><Expiration><Days>1</Days></Expiration></Rule>
+    <Rule><ID>tmp</ID><Status>Enabled</Status><Prefix>tmp/</Prefix><Expiration><Days>1</Days></Expiration></Rule>
+</LifecycleConfiguration>"#,
+        )
+        .unwrap();
+
+        let expired = evaluate_lifecycle(&lc, None, None, &object("logs/a", 10), OffsetDateTime::now_utc()).await;
+        assert_eq!(expired.matched_rules, vec!["logs".to_string()]);
+        assert_eq!(expired.action, IlmAction::DeleteAction.to_string());
+        assert_eq!(expired.rule_id, "logs");
+        assert!(expired.due.is_some());
+
+        let fresh = object("logs/b", 0);
+        let now = evaluate_lifecycle(&lc, None, None, &fresh, OffsetDateTime::now_utc()).await;
+        assert_eq!(now.action, IlmAction::NoneAction.to_string());
+        assert!(now.due.is_none());
+        // Due once the rule's days have passed
+        let later = evaluate_lifecycle(&lc, None, None, &fresh, OffsetDateTime::now_utc() + time::Duration::days(3)).await;
+        assert_eq!(later.action, IlmAction::DeleteAction.to_string());
+
+        let unmatched = evaluate_lifecycle(&lc, None, None, &object("data/c", 10), OffsetDateTime::now_utc()).await;
+        assert!(unmatched.matched_rules.is_empty());
+        assert_eq!(unmatched.action, IlmAction::NoneAction.to_string());
+    }
+
+    #[test]
+    fn test_evaluate_replication() {
+        let cfg = deserialize::<ReplicationConfiguration>(
+            br#"<ReplicationConfiguration>
+    <Role></Role>
+    <Rule>
+        <ID>docs</ID><Priority>1</Priority><Status>Enabled</Status>
+        <Filter><Prefix>docs/</Prefix></Filter>
+        <DeleteMarkerReplication><Status>Disabled</Status></DeleteMarkerReplication>
+        <Destination><Bucket>arn:rustfs:replication::target:remote</Bucket></Destination>
+    </Rule>
+</ReplicationConfiguration>"#,
+        )
+        .unwrap();
+
+        let matched = evaluate_replication(&cfg, &object("docs/a", 0));
+        assert_eq!(matched.matched_rules.len(), 1);
+        assert_eq!(matched.matched_rules[0].rule_id, "docs");
+        assert_eq!(matched.targets, vec!["arn:rustfs:replication::target:remote".to_string()]);
+        assert!(matched.replicate);
+
+        let unmatched = evaluate_replication(&cfg, &object("images/a", 0));
+        assert!(unmatched.matched_rules.is_empty());
+        assert!(!unmatched.replicate);
+    }
+}
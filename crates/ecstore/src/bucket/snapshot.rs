@@ -0,0 +1,342 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Point-in-time snapshots of versioned buckets.
+//!
+//! A snapshot is a manifest of the version that was current for every object of the bucket when
+//! it was taken. Versions referenced by a snapshot cannot be deleted, so the snapshot stays
+//! readable through the snapshot header and two snapshots can be diffed to find changed keys.
+
+use crate::bucket::versioning_sys::BucketVersioningSys;
+use crate::config::com::{delete_config, read_config, save_config};
+use crate::disk::BUCKET_META_PREFIX;
+use crate::error::{Error, Result};
+use crate::store::ECStore;
+use crate::store_api::WalkOptions;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tracing::info;
+
+/// Request header naming the snapshot a GET or HEAD reads through.
+pub const SNAPSHOT_HEADER: &str = "x-rustfs-snapshot";
+
+pub const BUCKET_SNAPSHOTS_DIR: &str = ".snapshots";
+pub const BUCKET_SNAPSHOT_INDEX_FILE: &str = ".snapshots.json";
+
+pub const MAX_SNAPSHOT_NAME_LEN: usize = 64;
+
+/// Version of an object captured by a snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotEntry {
+    /// Captured version, `None` for the null version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    #[serde(default)]
+    pub etag: String,
+    #[serde(default)]
+    pub size: i64,
+}
+
+impl SnapshotEntry {
+    /// Version id to request from the object layer.
+    pub fn version_id_or_null(&self) -> String {
+        self.version_id.clone().unwrap_or_else(|| "null".to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotManifest {
+    pub name: String,
+    pub bucket: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    #[serde(default)]
+    pub entries: BTreeMap<String, SnapshotEntry>,
+}
+
+impl SnapshotManifest {
+    pub fn unmarshal(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data).map_err(Error::other)
+    }
+
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(Error::other)
+    }
+
+    pub fn info(&self) -> SnapshotInfo {
+        SnapshotInfo {
+            name: self.name.clone(),
+            created: self.created,
+            objects: self.entries.len(),
+            size: self.entries.values().map(|e| e.size).sum(),
+        }
+    }
+}
+
+/// Summary of a snapshot kept in the bucket's snapshot index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub name: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    pub objects: usize,
+    pub size: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotIndex {
+    #[serde(default)]
+    pub snapshots: Vec<SnapshotInfo>,
+}
+
+impl SnapshotIndex {
+    pub fn unmarshal(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data).map_err(Error::other)
+    }
+
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(Error::other)
+    }
+}
+
+/// Keys that differ between two snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<String>,
+}
+
+/// Compare the manifests `from` and `to`, keys are reported in lexical order.
+pub fn diff(from: &SnapshotManifest, to: &SnapshotManifest) -> SnapshotDiff {
+    let mut result = SnapshotDiff::default();
+    for (key, entry) in to.entries.iter() {
+        match from.entries.get(key) {
+            None => result.added.push(key.clone()),
+            Some(prev) if prev != entry => result.modified.push(key.clone()),
+            Some(_) => {}
+        }
+    }
+    result.removed = from
+        .entries
+        .keys()
+        .filter(|key| !to.entries.contains_key(*key))
+        .cloned()
+        .collect();
+    result
+}
+
+pub fn validate_snapshot_name(bucket: &str, name: &str) -> Result<()> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if name.is_empty() || name.len() > MAX_SNAPSHOT_NAME_LEN || !valid_chars || name.starts_with('.') {
+        return Err(Error::InvalidArgument(
+            bucket.to_string(),
+            name.to_string(),
+            format!("invalid snapshot name, use up to {MAX_SNAPSHOT_NAME_LEN} letters, digits, '-', '_' or '.'"),
+        ));
+    }
+    Ok(())
+}
+
+fn index_file_path(bucket: &str) -> String {
+    format!("{BUCKET_META_PREFIX}/{bucket}/{BUCKET_SNAPSHOT_INDEX_FILE}")
+}
+
+fn manifest_file_path(bucket: &str, name: &str) -> String {
+    format!("{BUCKET_META_PREFIX}/{bucket}/{BUCKET_SNAPSHOTS_DIR}/{name}.json")
+}
+
+/// Snapshots of `bucket`, oldest first. A missing index means no snapshot was taken.
+pub async fn list_snapshots(api: Arc<ECStore>, bucket: &str) -> Result<Vec<SnapshotInfo>> {
+    match read_config(api, &index_file_path(bucket)).await {
+        Ok(data) => Ok(SnapshotIndex::unmarshal(&data)?.snapshots),
+        Err(Error::ConfigNotFound) => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+async fn save_index(api: Arc<ECStore>, bucket: &str, snapshots: Vec<SnapshotInfo>) -> Result<()> {
+    save_config(api, &index_file_path(bucket), SnapshotIndex { snapshots }.marshal()?).await
+}
+
+/// Load the manifest of snapshot `name`, `Error::ConfigNotFound` if there is none.
+pub async fn get_snapshot(api: Arc<ECStore>, bucket: &str, name: &str) -> Result<SnapshotManifest> {
+    validate_snapshot_name(bucket, name)?;
+    let data = read_config(api, &manifest_file_path(bucket, name)).await?;
+    SnapshotManifest::unmarshal(&data)
+}
+
+/// Capture the current version of every object in `bucket` as snapshot `name`.
+///
+/// The bucket must have versioning enabled, otherwise the captured versions could be
+/// overwritten in place.
+pub async fn create_snapshot(api: Arc<ECStore>, bucket: &str, name: &str) -> Result<SnapshotInfo> {
+    validate_snapshot_name(bucket, name)?;
+    if !BucketVersioningSys::enabled(bucket).await {
+        return Err(Error::InvalidArgument(
+            bucket.to_string(),
+            name.to_string(),
+            "snapshots require versioning to be enabled".to_string(),
+        ));
+    }
+
+    let mut snapshots = list_snapshots(api.clone(), bucket).await?;
+    if snapshots.iter().any(|s| s.name == name) {
+        return Err(Error::InvalidArgument(
+            bucket.to_string(),
+            name.to_string(),
+            "snapshot already exists".to_string(),
+        ));
+    }
+
+    let opts = WalkOptions {
+        latest_only: true,
+        ..Default::default()
+    };
+    let objects = api.clone().walk_collect(bucket, "", opts).await?;
+
+    let entries = objects
+        .into_iter()
+        .filter(|info| !info.delete_marker && !info.is_dir)
+        .map(|info| {
+            let entry = SnapshotEntry {
+                version_id: info.version_id.map(|v| v.to_string()),
+                etag: info.etag.unwrap_or_default(),
+                size: info.size,
+            };
+            (info.name, entry)
+        })
+        .collect();
+
+    let manifest = SnapshotManifest {
+        name: name.to_string(),
+        bucket: bucket.to_string(),
+        created: OffsetDateTime::now_utc(),
+        entries,
+    };
+    save_config(api.clone(), &manifest_file_path(bucket, name), manifest.marshal()?).await?;
+
+    let info = manifest.info();
+    snapshots.push(info.clone());
+    save_index(api, bucket, snapshots).await?;
+
+    info!("created snapshot {} of bucket {} with {} objects", name, bucket, info.objects);
+    Ok(info)
+}
+
+/// Delete snapshot `name`, releasing the versions only it referenced.
+pub async fn delete_snapshot(api: Arc<ECStore>, bucket: &str, name: &str) -> Result<()> {
+    validate_snapshot_name(bucket, name)?;
+    let mut snapshots = list_snapshots(api.clone(), bucket).await?;
+    let before = snapshots.len();
+    snapshots.retain(|s| s.name != name);
+    if snapshots.len() == before {
+        return Err(Error::ConfigNotFound);
+    }
+
+    save_index(api.clone(), bucket, snapshots).await?;
+    match delete_config(api, &manifest_file_path(bucket, name)).await {
+        Ok(()) | Err(Error::ConfigNotFound) => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Diff snapshot `from` against snapshot `to` of `bucket`.
+pub async fn diff_snapshots(api: Arc<ECStore>, bucket: &str, from: &str, to: &str) -> Result<SnapshotDiff> {
+    let from = get_snapshot(api.clone(), bucket, from).await?;
+    let to = get_snapshot(api, bucket, to).await?;
+    Ok(diff(&from, &to))
+}
+
+/// Name of a snapshot of `bucket` that still references `version_id` of `object`, if any.
+pub async fn snapshot_referencing(api: Arc<ECStore>, bucket: &str, object: &str, version_id: &str) -> Result<Option<String>> {
+    for info in list_snapshots(api.clone(), bucket).await? {
+        let manifest = match get_snapshot(api.clone(), bucket, &info.name).await {
+            Ok(manifest) => manifest,
+            Err(Error::ConfigNotFound) => continue,
+            Err(err) => return Err(err),
+        };
+        if manifest
+            .entries
+            .get(object)
+            .is_some_and(|entry| entry.version_id_or_null() == version_id)
+        {
+            return Ok(Some(info.name));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(entries: &[(&str, &str, &str)]) -> SnapshotManifest {
+        SnapshotManifest {
+            name: "s".to_string(),
+            bucket: "b".to_string(),
+            created: OffsetDateTime::UNIX_EPOCH,
+            entries: entries
+                .iter()
+                .map(|(key, vid, etag)| {
+                    let entry = SnapshotEntry {
+                        version_id: Some(vid.to_string()),
+                        etag: etag.to_string(),
+                        size: 1,
+                    };
+                    (key.to_string(), entry)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let from = manifest(&[("a", "v1", "e1"), ("b", "v1", "e1"), ("c", "v1", "e1")]);
+        let to = manifest(&[("a", "v1", "e1"), ("b", "v2", "e2"), ("d", "v1", "e1")]);
+
+        let d = diff(&from, &to);
+        assert_eq!(d.added, vec!["d".to_string()]);
+        assert_eq!(d.removed, vec!["c".to_string()]);
+        assert_eq!(d.modified, vec!["b".to_string()]);
+        assert_eq!(diff(&from, &from), SnapshotDiff::default());
+    }
+
+    #[test]
+    fn test_validate_snapshot_name() {
+        assert!(validate_snapshot_name("b", "nightly-2024.01.01_a").is_ok());
+        assert!(validate_snapshot_name("b", "").is_err());
+        assert!(validate_snapshot_name("b", ".hidden").is_err());
+        assert!(validate_snapshot_name("b", "a/b").is_err());
+        assert!(validate_snapshot_name("b", &"a".repeat(MAX_SNAPSHOT_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let mut m = manifest(&[("a", "v1", "e1")]);
+        m.entries.insert("null".to_string(), SnapshotEntry::default());
+        let decoded = SnapshotManifest::unmarshal(&m.marshal().unwrap()).unwrap();
+        assert_eq!(decoded, m);
+        assert_eq!(decoded.entries["null"].version_id_or_null(), "null");
+        assert_eq!(m.info().objects, 2);
+    }
+}
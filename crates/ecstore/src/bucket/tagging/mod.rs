@@ -14,7 +14,7 @@
 
 use std::collections::HashMap;
 
-use s3s::dto::Tag;
+use s3s::dto::{Tag, Tagging};
 use url::form_urlencoded;
 
 pub fn decode_tags(tags: &str) -> Vec<Tag> {
@@ -62,3 +62,14 @@ pub fn encode_tags(tags: Vec<Tag>) -> String {
 
     encoded.finish()
 }
+
+pub fn tags_to_map(tagging: &Tagging) -> HashMap<String, String> {
+    tagging
+        .tag_set
+        .iter()
+        .filter_map(|tag| match (tag.key.as_ref(), tag.value.as_ref()) {
+            (Some(k), Some(v)) if !k.is_empty() => Some((k.clone(), v.clone())),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,222 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bucket GET transformation configuration.
+//!
+//! A bucket can register named transformers and route GetObject responses for matching keys
+//! through one of them, so the payload is rewritten (e.g. PII redacted, formats converted) before
+//! it reaches the client. This module only holds the configuration; the transformers themselves
+//! run in the S3 front end.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// Timeout applied to a transformer that does not configure its own.
+pub const DEFAULT_TRANSFORM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How a transformer is invoked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TransformerKind {
+    /// Stream the object to an external HTTP endpoint and return its response body.
+    Http { endpoint: String },
+    /// Run the object through a WASM module.
+    Wasm { module: String },
+}
+
+/// A transformer registered on a bucket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transformer {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: TransformerKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl Transformer {
+    pub fn timeout(&self) -> Duration {
+        self.timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TRANSFORM_TIMEOUT)
+    }
+}
+
+/// Routes objects under `prefix` through the transformer named `transformer`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransformRule {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub prefix: String,
+    pub transformer: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BucketTransform {
+    #[serde(default)]
+    pub transformers: Vec<Transformer>,
+    #[serde(default)]
+    pub rules: Vec<TransformRule>,
+}
+
+impl BucketTransform {
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(buf)?)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for t in self.transformers.iter() {
+            if t.name.is_empty() {
+                return Err(Error::other("transformer name is required"));
+            }
+            if !names.insert(t.name.as_str()) {
+                return Err(Error::other(format!("duplicate transformer {}", t.name)));
+            }
+            if t.timeout_secs == Some(0) {
+                return Err(Error::other(format!("transformer {} has a zero timeout", t.name)));
+            }
+
+            match &t.kind {
+                TransformerKind::Http { endpoint } => {
+                    let url = url::Url::parse(endpoint)
+                        .map_err(|e| Error::other(format!("transformer {} has an invalid endpoint: {e}", t.name)))?;
+                    if url.scheme() != "http" && url.scheme() != "https" {
+                        return Err(Error::other(format!("transformer {} endpoint must be http or https", t.name)));
+                    }
+                }
+                TransformerKind::Wasm { .. } => {
+                    return Err(Error::other(format!(
+                        "transformer {}: WASM transformers are not supported by this server",
+                        t.name
+                    )));
+                }
+            }
+        }
+
+        for rule in self.rules.iter() {
+            if !names.contains(rule.transformer.as_str()) {
+                return Err(Error::other(format!("rule references unknown transformer {}", rule.transformer)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The transformer GetObject responses for `object` go through; the longest matching prefix wins.
+    pub fn transformer_for(&self, object: &str) -> Option<&Transformer> {
+        let rule = self
+            .rules
+            .iter()
+            .filter(|r| object.starts_with(&r.prefix))
+            .max_by_key(|r| r.prefix.len())?;
+
+        self.transformers.iter().find(|t| t.name == rule.transformer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http(name: &str, endpoint: &str) -> Transformer {
+        Transformer {
+            name: name.to_string(),
+            kind: TransformerKind::Http {
+                endpoint: endpoint.to_string(),
+            },
+            timeout_secs: None,
+        }
+    }
+
+    fn rule(prefix: &str, transformer: &str) -> TransformRule {
+        TransformRule {
+            prefix: prefix.to_string(),
+            transformer: transformer.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_transform_roundtrip() {
+        let data = r#"{"transformers":[{"name":"redact","type":"http","endpoint":"http://127.0.0.1:8080/redact","timeout_secs":5}],"rules":[{"prefix":"users/","transformer":"redact"}]}"#;
+        let cfg = BucketTransform::unmarshal(data.as_bytes()).unwrap();
+        assert_eq!(cfg.transformers[0].timeout(), Duration::from_secs(5));
+        assert!(cfg.validate().is_ok());
+        assert_eq!(BucketTransform::unmarshal(&cfg.marshal().unwrap()).unwrap(), cfg);
+    }
+
+    #[test]
+    fn test_transform_validate() {
+        let valid = BucketTransform {
+            transformers: vec![http("redact", "https://lambda.local/redact")],
+            rules: vec![rule("", "redact")],
+        };
+        assert!(valid.validate().is_ok());
+
+        let unknown = BucketTransform {
+            rules: vec![rule("", "missing")],
+            ..valid.clone()
+        };
+        assert!(unknown.validate().is_err());
+
+        let duplicate = BucketTransform {
+            transformers: vec![http("redact", "http://a"), http("redact", "http://b")],
+            rules: Vec::new(),
+        };
+        assert!(duplicate.validate().is_err());
+
+        let bad_scheme = BucketTransform {
+            transformers: vec![http("redact", "ftp://a")],
+            rules: Vec::new(),
+        };
+        assert!(bad_scheme.validate().is_err());
+
+        let wasm = BucketTransform {
+            transformers: vec![Transformer {
+                name: "csv".to_string(),
+                kind: TransformerKind::Wasm {
+                    module: "csv.wasm".to_string(),
+                },
+                timeout_secs: None,
+            }],
+            rules: Vec::new(),
+        };
+        assert!(wasm.validate().is_err());
+    }
+
+    #[test]
+    fn test_transformer_for_longest_prefix() {
+        let cfg = BucketTransform {
+            transformers: vec![http("all", "http://a"), http("users", "http://b")],
+            rules: vec![rule("", "all"), rule("users/", "users")],
+        };
+
+        assert_eq!(cfg.transformer_for("users/1.json").map(|t| t.name.as_str()), Some("users"));
+        assert_eq!(cfg.transformer_for("logs/1.json").map(|t| t.name.as_str()), Some("all"));
+
+        let scoped = BucketTransform {
+            rules: vec![rule("users/", "users")],
+            ..cfg
+        };
+        assert!(scoped.transformer_for("logs/1.json").is_none());
+    }
+}
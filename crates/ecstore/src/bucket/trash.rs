@@ -0,0 +1,365 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-bucket trash for unversioned buckets.
+//!
+//! With trash enabled, deleting the current object of a bucket without versioning first moves
+//! the object into a hidden namespace of the system bucket, where it stays restorable for the
+//! configured retention. The scanner purges entries whose retention has passed.
+
+use crate::bucket::versioning_sys::BucketVersioningSys;
+use crate::disk::RUSTFS_META_BUCKET;
+use crate::error::{Error, Result, is_err_object_not_found};
+use crate::store::ECStore;
+use crate::store_api::{ObjectIO, ObjectInfo, ObjectOptions, PutObjReader, StorageAPI, WalkOptions};
+use http::HeaderMap;
+use rustfs_rio::{HashReader, WarpReader};
+use rustfs_utils::http::headers::RESERVED_METADATA_PREFIX_LOWER;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Prefix of the trash namespace inside the system bucket.
+pub const TRASH_PREFIX: &str = "trash";
+
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 7;
+pub const MAX_TRASH_RETENTION_DAYS: u32 = 3650;
+
+const TRASH_META_OBJECT: &str = "trash-object";
+const TRASH_META_DELETED_AT: &str = "trash-deleted-at";
+const TRASH_META_EXPIRES_AT: &str = "trash-expires-at";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketTrash {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_retention_days")]
+    pub retention_days: u32,
+}
+
+fn default_retention_days() -> u32 {
+    DEFAULT_TRASH_RETENTION_DAYS
+}
+
+impl Default for BucketTrash {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: DEFAULT_TRASH_RETENTION_DAYS,
+        }
+    }
+}
+
+impl BucketTrash {
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(buf)?)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.retention_days == 0 || self.retention_days > MAX_TRASH_RETENTION_DAYS {
+            return Err(Error::other(format!(
+                "trash retention must be between 1 and {MAX_TRASH_RETENTION_DAYS} days"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn retention(&self) -> Duration {
+        Duration::from_secs(u64::from(self.retention_days) * 24 * 3600)
+    }
+}
+
+/// An object held in the trash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub id: String,
+    pub bucket: String,
+    pub object: String,
+    pub size: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub deleted_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: OffsetDateTime,
+}
+
+impl TrashEntry {
+    fn from_object_info(bucket: &str, info: &ObjectInfo) -> Option<Self> {
+        let meta = |key: &str| info.user_defined.get(&format!("{RESERVED_METADATA_PREFIX_LOWER}{key}"));
+        let timestamp = |key: &str| {
+            meta(key)
+                .and_then(|v| v.parse::<i64>().ok())
+                .and_then(|v| OffsetDateTime::from_unix_timestamp(v).ok())
+        };
+
+        Some(Self {
+            id: info.name.rsplit('/').next()?.to_string(),
+            bucket: bucket.to_string(),
+            object: meta(TRASH_META_OBJECT)?.clone(),
+            size: info.size,
+            deleted_at: timestamp(TRASH_META_DELETED_AT)?,
+            expires_at: timestamp(TRASH_META_EXPIRES_AT)?,
+        })
+    }
+
+    pub fn is_expired(&self, now: OffsetDateTime) -> bool {
+        self.expires_at <= now
+    }
+}
+
+fn trash_bucket_prefix(bucket: &str) -> String {
+    format!("{TRASH_PREFIX}/{bucket}/")
+}
+
+fn trash_path(bucket: &str, id: &str) -> String {
+    format!("{TRASH_PREFIX}/{bucket}/{id}")
+}
+
+fn is_trash_meta_key(key: &str) -> bool {
+    [TRASH_META_OBJECT, TRASH_META_DELETED_AT, TRASH_META_EXPIRES_AT]
+        .iter()
+        .any(|k| key == format!("{RESERVED_METADATA_PREFIX_LOWER}{k}"))
+}
+
+/// Metadata describing the stored encoding, which does not carry over when the data is rewritten.
+fn is_encoding_meta_key(key: &str) -> bool {
+    ["compression", "compression-size", "actual-size"]
+        .iter()
+        .any(|k| key == format!("{RESERVED_METADATA_PREFIX_LOWER}{k}"))
+}
+
+/// Copy the current version of `bucket/object` to `dst_bucket/dst_object` with `user_defined`.
+async fn copy_object_data(
+    store: &Arc<ECStore>,
+    bucket: &str,
+    object: &str,
+    dst_bucket: &str,
+    dst_object: &str,
+    update_meta: impl FnOnce(&mut HashMap<String, String>),
+    dst_opts: ObjectOptions,
+) -> Result<ObjectInfo> {
+    let reader = store
+        .get_object_reader(bucket, object, None, HeaderMap::new(), &ObjectOptions::default())
+        .await?;
+    let size = reader.object_info.size;
+
+    let mut user_defined = reader.object_info.user_defined.clone();
+    user_defined.retain(|k, _| !is_encoding_meta_key(k));
+    update_meta(&mut user_defined);
+
+    let hash_reader = HashReader::new(Box::new(WarpReader::new(reader.stream)), size, size, None, None, false)?;
+    let opts = ObjectOptions {
+        user_defined,
+        ..dst_opts
+    };
+
+    store
+        .put_object(dst_bucket, dst_object, &mut PutObjReader::new(hash_reader), &opts)
+        .await
+}
+
+/// Move the current version of `bucket/object` into the trash, `None` if there is no object.
+///
+/// The caller deletes the original once this returns.
+pub async fn move_to_trash(store: Arc<ECStore>, bucket: &str, object: &str, config: &BucketTrash) -> Result<Option<TrashEntry>> {
+    let now = OffsetDateTime::now_utc();
+    let expires_at = now + config.retention();
+    let id = Uuid::new_v4().to_string();
+
+    let info = match copy_object_data(
+        &store,
+        bucket,
+        object,
+        RUSTFS_META_BUCKET,
+        &trash_path(bucket, &id),
+        |meta| {
+            let key = |k: &str| format!("{RESERVED_METADATA_PREFIX_LOWER}{k}");
+            meta.insert(key(TRASH_META_OBJECT), object.to_string());
+            meta.insert(key(TRASH_META_DELETED_AT), now.unix_timestamp().to_string());
+            meta.insert(key(TRASH_META_EXPIRES_AT), expires_at.unix_timestamp().to_string());
+        },
+        ObjectOptions::default(),
+    )
+    .await
+    {
+        Ok(info) => info,
+        Err(err) if is_err_object_not_found(&err) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    Ok(TrashEntry::from_object_info(bucket, &info))
+}
+
+/// Trashed objects of `bucket`, optionally only those whose original key starts with `prefix`.
+pub async fn list_trash(store: Arc<ECStore>, bucket: &str, prefix: &str) -> Result<Vec<TrashEntry>> {
+    let mut entries: Vec<TrashEntry> = store
+        .walk_collect(RUSTFS_META_BUCKET, &trash_bucket_prefix(bucket), WalkOptions::default())
+        .await?
+        .iter()
+        .filter_map(|info| TrashEntry::from_object_info(bucket, info))
+        .filter(|entry| entry.object.starts_with(prefix))
+        .collect();
+
+    entries.sort_by(|a, b| a.object.cmp(&b.object).then(b.deleted_at.cmp(&a.deleted_at)));
+    Ok(entries)
+}
+
+/// Restore a trashed object to its original key and drop it from the trash.
+///
+/// Fails if an object exists at the key unless `overwrite` is set.
+pub async fn restore_from_trash(store: Arc<ECStore>, bucket: &str, id: &str, overwrite: bool) -> Result<TrashEntry> {
+    let path = trash_path(bucket, id);
+    let info = store
+        .get_object_info(RUSTFS_META_BUCKET, &path, &ObjectOptions::default())
+        .await?;
+    let entry = TrashEntry::from_object_info(bucket, &info).ok_or_else(|| Error::other(format!("invalid trash entry {id}")))?;
+
+    if !overwrite {
+        match store.get_object_info(bucket, &entry.object, &ObjectOptions::default()).await {
+            Ok(existing) if !existing.delete_marker => {
+                return Err(Error::other(format!("object {} already exists", entry.object)));
+            }
+            Ok(_) => {}
+            Err(err) if is_err_object_not_found(&err) => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    let versioned = BucketVersioningSys::prefix_enabled(bucket, &entry.object).await;
+    copy_object_data(
+        &store,
+        RUSTFS_META_BUCKET,
+        &path,
+        bucket,
+        &entry.object,
+        |meta| meta.retain(|k, _| !is_trash_meta_key(k)),
+        ObjectOptions {
+            versioned,
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    store
+        .delete_object(RUSTFS_META_BUCKET, &path, ObjectOptions::default())
+        .await?;
+
+    Ok(entry)
+}
+
+/// Delete trash entries of every bucket whose retention has passed, returns how many were purged.
+pub async fn purge_expired_trash(store: Arc<ECStore>) -> Result<usize> {
+    let now = OffsetDateTime::now_utc();
+    let mut purged = 0;
+
+    for info in store
+        .clone()
+        .walk_collect(RUSTFS_META_BUCKET, &format!("{TRASH_PREFIX}/"), WalkOptions::default())
+        .await?
+    {
+        let bucket = info
+            .name
+            .trim_start_matches(TRASH_PREFIX)
+            .trim_start_matches('/')
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let Some(entry) = TrashEntry::from_object_info(&bucket, &info) else {
+            continue;
+        };
+        if !entry.is_expired(now) {
+            continue;
+        }
+
+        match store
+            .delete_object(RUSTFS_META_BUCKET, &info.name, ObjectOptions::default())
+            .await
+        {
+            Ok(_) => purged += 1,
+            Err(err) if is_err_object_not_found(&err) => {}
+            Err(err) => warn!("purge trash entry {} failed: {:?}", info.name, err),
+        }
+    }
+
+    if purged > 0 {
+        info!("purged {} expired trash entries", purged);
+    }
+    Ok(purged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trash_config_validate() {
+        let cfg = BucketTrash::unmarshal(br#"{"enabled":true}"#).unwrap();
+        assert!(cfg.enabled);
+        assert_eq!(cfg.retention_days, DEFAULT_TRASH_RETENTION_DAYS);
+        assert!(cfg.validate().is_ok());
+        assert_eq!(cfg.retention(), Duration::from_secs(7 * 24 * 3600));
+
+        assert!(
+            BucketTrash {
+                enabled: true,
+                retention_days: 0
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            BucketTrash {
+                enabled: true,
+                retention_days: MAX_TRASH_RETENTION_DAYS + 1
+            }
+            .validate()
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_trash_entry_from_object_info() {
+        let mut info = ObjectInfo {
+            name: trash_path("photos", "0d7c"),
+            size: 42,
+            ..Default::default()
+        };
+        let key = |k: &str| format!("{RESERVED_METADATA_PREFIX_LOWER}{k}");
+        info.user_defined.insert(key(TRASH_META_OBJECT), "2024/cat.jpg".to_string());
+        info.user_defined.insert(key(TRASH_META_DELETED_AT), "1700000000".to_string());
+        info.user_defined.insert(key(TRASH_META_EXPIRES_AT), "1700604800".to_string());
+
+        let entry = TrashEntry::from_object_info("photos", &info).unwrap();
+        assert_eq!(entry.id, "0d7c");
+        assert_eq!(entry.object, "2024/cat.jpg");
+        assert_eq!(entry.size, 42);
+        assert!(!entry.is_expired(OffsetDateTime::from_unix_timestamp(1700000001).unwrap()));
+        assert!(entry.is_expired(OffsetDateTime::from_unix_timestamp(1700604800).unwrap()));
+
+        info.user_defined.remove(&key(TRASH_META_OBJECT));
+        assert!(TrashEntry::from_object_info("photos", &info).is_none());
+        assert!(is_trash_meta_key(&key(TRASH_META_DELETED_AT)));
+        assert!(!is_trash_meta_key("content-type"));
+    }
+}
@@ -0,0 +1,423 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Capacity planning of pool expansions and decommissions.
+//!
+//! Proposed pools are split into erasure sets the way the server splits the drives of its command
+//! line, and take the parity of the first pool, as every pool of a cluster does. The plan reports
+//! the usable capacity of every pool, how many drives and nodes each of its sets may lose, the
+//! share of new objects it would receive, and whether the data of the pools to decommission fits
+//! in the pools that stay.
+
+use crate::config::storageclass::validate_parity;
+use crate::disks_layout::plan_pool_sets;
+use crate::error::{Error, Result};
+use crate::global::DISK_RESERVE_FRACTION;
+use crate::store::ECStore;
+use crate::store_init::ec_drives_no_config;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A pool to add, of `nodes` nodes with `drives_per_node` drives of `drive_capacity` bytes each.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProposedPool {
+    pub nodes: usize,
+    pub drives_per_node: usize,
+    pub drive_capacity: u64,
+    /// Parity of a fresh cluster, the pools of an existing one share the parity of its first pool
+    pub parity: Option<usize>,
+    /// Drives per erasure set, 0 to pick it like the server does
+    pub set_drive_count: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CapacityPlanRequest {
+    pub pools: Vec<ProposedPool>,
+    /// Indexes of the existing pools to decommission
+    pub decommission: Vec<usize>,
+    /// Plan a new cluster rather than changes to this one
+    pub ignore_existing: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolPlan {
+    pub index: usize,
+    pub existing: bool,
+    pub nodes: usize,
+    pub sets: usize,
+    pub set_drive_count: usize,
+    pub parity: usize,
+    /// Usable bytes, parity left out
+    pub total_capacity: u64,
+    pub used_capacity: u64,
+    pub free_capacity: u64,
+    /// Drives every set may lose and still serve reads
+    pub read_drive_tolerance: usize,
+    /// Drives every set may lose and still take writes
+    pub write_drive_tolerance: usize,
+    pub read_node_tolerance: usize,
+    pub write_node_tolerance: usize,
+    /// Suspended for a decommission, in progress or planned
+    pub decommissioned: bool,
+    /// The fraction of new objects placed in the pool
+    pub write_share: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecommissionPlan {
+    pub pools: Vec<usize>,
+    /// Bytes to move to the pools that stay
+    pub data_to_move: u64,
+    /// Whether the data fits below the fill limit of the pools that stay
+    pub fits: bool,
+    /// Usable bytes left in the cluster once the data moved
+    pub free_after: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapacityPlan {
+    pub pools: Vec<PoolPlan>,
+    pub total_capacity: u64,
+    pub used_capacity: u64,
+    pub free_capacity: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decommission: Option<DecommissionPlan>,
+    pub warnings: Vec<String>,
+}
+
+/// The drives a set may lose and still take writes, with the write quorum of the server.
+fn write_drive_tolerance(set_drive_count: usize, parity: usize) -> usize {
+    let data = set_drive_count - parity;
+    let quorum = if data == parity { data + 1 } else { data };
+    set_drive_count.saturating_sub(quorum)
+}
+
+/// The nodes that may fail together while every set loses at most `drives` drives.
+fn node_tolerance(sets: &[Vec<usize>], drives: usize) -> usize {
+    sets.iter()
+        .map(|set| {
+            let mut per_node: HashMap<usize, usize> = HashMap::new();
+            for node in set {
+                *per_node.entry(*node).or_default() += 1;
+            }
+            let mut counts: Vec<usize> = per_node.into_values().collect();
+            counts.sort_unstable_by(|a, b| b.cmp(a));
+
+            let mut lost = 0;
+            counts
+                .iter()
+                .take_while(|count| {
+                    lost += **count;
+                    lost <= drives
+                })
+                .count()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// The plan of a pool whose sets have their drives on the nodes in `sets`.
+fn pool_plan(index: usize, existing: bool, sets: &[Vec<usize>], parity: usize, total: u64, used: u64) -> PoolPlan {
+    let set_drive_count = sets.first().map_or(0, Vec::len);
+    let write_drive_tolerance = write_drive_tolerance(set_drive_count, parity);
+    let nodes = sets.iter().flatten().max().map_or(0, |node| node + 1);
+    PoolPlan {
+        index,
+        existing,
+        nodes,
+        sets: sets.len(),
+        set_drive_count,
+        parity,
+        total_capacity: total,
+        used_capacity: used,
+        free_capacity: total.saturating_sub(used),
+        read_drive_tolerance: parity,
+        write_drive_tolerance,
+        read_node_tolerance: node_tolerance(sets, parity),
+        write_node_tolerance: node_tolerance(sets, write_drive_tolerance),
+        ..Default::default()
+    }
+}
+
+/// The usable bytes a pool may hold before it stops taking new objects.
+fn fill_limit(pool: &PoolPlan) -> u64 {
+    (pool.total_capacity as f64 * (1.0 - DISK_RESERVE_FRACTION)) as u64
+}
+
+/// Plan `request` on top of the `existing` pools, whose first one sets the parity of all.
+pub fn simulate(mut pools: Vec<PoolPlan>, request: &CapacityPlanRequest) -> Result<CapacityPlan> {
+    let mut warnings = Vec::new();
+    let mut parity = pools.first().map(|pool| pool.parity);
+
+    for proposed in request.pools.iter() {
+        let index = pools.len();
+        if proposed.drive_capacity == 0 {
+            return Err(Error::other(format!("pool {index}: the drive capacity is required")));
+        }
+        let sets = plan_pool_sets(proposed.nodes, proposed.drives_per_node, proposed.set_drive_count)
+            .map_err(|e| Error::other(format!("pool {index}: {e}")))?;
+        let set_drive_count = sets.first().map_or(0, Vec::len);
+
+        let pool_parity = match parity {
+            Some(common) => {
+                if proposed.parity.is_some_and(|p| p != common) {
+                    warnings.push(format!("pool {index}: pools share the parity of the first pool, {common}"));
+                }
+                common
+            }
+            None => match proposed.parity {
+                Some(p) => p,
+                None => ec_drives_no_config(set_drive_count)?,
+            },
+        };
+        validate_parity(pool_parity, set_drive_count).map_err(|e| Error::other(format!("pool {index}: {e}")))?;
+        parity = Some(pool_parity);
+
+        let total = (set_drive_count - pool_parity) as u64 * sets.len() as u64 * proposed.drive_capacity;
+        pools.push(pool_plan(index, false, &sets, pool_parity, total, 0));
+    }
+
+    if pools.is_empty() {
+        return Err(Error::other("no pools to plan"));
+    }
+
+    let mut decommission = None;
+    if !request.decommission.is_empty() {
+        for idx in request.decommission.iter() {
+            match pools.get_mut(*idx) {
+                Some(pool) if pool.existing => pool.decommissioned = true,
+                _ => return Err(Error::other(format!("no existing pool {idx} to decommission"))),
+            }
+        }
+        if pools.iter().all(|pool| pool.decommissioned) {
+            return Err(Error::other("at least one pool has to stay"));
+        }
+
+        let data_to_move: u64 = pools
+            .iter()
+            .filter(|pool| request.decommission.contains(&pool.index))
+            .map(|pool| pool.used_capacity)
+            .sum();
+        let staying = pools.iter().filter(|pool| !pool.decommissioned);
+        let room: u64 = staying
+            .clone()
+            .map(|pool| fill_limit(pool).saturating_sub(pool.used_capacity))
+            .sum();
+        let free: u64 = staying.map(|pool| pool.free_capacity).sum();
+        let fits = data_to_move <= room;
+        if !fits {
+            warnings.push(format!(
+                "the {data_to_move} bytes to decommission exceed the {room} bytes the other pools take before they are full"
+            ));
+        }
+        decommission = Some(DecommissionPlan {
+            pools: request.decommission.clone(),
+            data_to_move,
+            fits,
+            free_after: free.saturating_sub(data_to_move),
+        });
+    }
+
+    // New objects go to a pool picked at random, weighted by the raw free space of its drives,
+    // among the pools not suspended and below the fill limit
+    let weights: Vec<f64> = pools
+        .iter()
+        .map(|pool| {
+            if pool.decommissioned || pool.used_capacity >= fill_limit(pool) || pool.set_drive_count == pool.parity {
+                return 0.0;
+            }
+            pool.free_capacity as f64 * pool.set_drive_count as f64 / (pool.set_drive_count - pool.parity) as f64
+        })
+        .collect();
+    let total_weight: f64 = weights.iter().sum();
+    for (pool, weight) in pools.iter_mut().zip(weights) {
+        if total_weight > 0.0 {
+            pool.write_share = weight / total_weight;
+        }
+    }
+    if total_weight == 0.0 {
+        warnings.push("no pool takes new objects".to_string());
+    }
+
+    for pool in pools.iter().filter(|pool| pool.nodes > 1 && !pool.decommissioned) {
+        if pool.write_node_tolerance == 0 {
+            warnings.push(format!("pool {}: losing any node stops writes", pool.index));
+        }
+    }
+
+    let active = pools.iter().filter(|pool| !pool.decommissioned);
+    Ok(CapacityPlan {
+        total_capacity: active.clone().map(|pool| pool.total_capacity).sum(),
+        used_capacity: pools.iter().map(|pool| pool.used_capacity).sum(),
+        free_capacity: active.map(|pool| pool.free_capacity).sum(),
+        pools,
+        decommission,
+        warnings,
+    })
+}
+
+/// The pools of `store` as they are now.
+pub async fn existing_pools(store: &ECStore) -> Result<Vec<PoolPlan>> {
+    let mut pools = Vec::with_capacity(store.pools.len());
+    for (idx, pool) in store.pools.iter().enumerate() {
+        let space = store.get_decommission_pool_space_info(idx).await?;
+
+        let mut hosts: Vec<String> = Vec::new();
+        let mut sets = vec![Vec::new(); pool.set_count];
+        for ep in pool.endpoints.endpoints.as_ref() {
+            let host = ep.host_port();
+            let node = match hosts.iter().position(|h| *h == host) {
+                Some(node) => node,
+                None => {
+                    hosts.push(host);
+                    hosts.len() - 1
+                }
+            };
+            if let Some(set) = usize::try_from(ep.set_idx).ok().and_then(|set| sets.get_mut(set)) {
+                set.push(node);
+            }
+        }
+
+        let mut plan = pool_plan(idx, true, &sets, pool.default_parity_count, space.total as u64, space.used as u64);
+        plan.decommissioned = store.pool_meta.read().await.is_suspended(idx);
+        pools.push(plan);
+    }
+    Ok(pools)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TB: u64 = 1_000_000_000_000;
+
+    fn existing(index: usize, total: u64, used: u64) -> PoolPlan {
+        // 4 nodes with 4 drives each, in one set of 16 with parity 4
+        let sets = vec![(0..16).map(|drive| drive % 4).collect::<Vec<_>>()];
+        pool_plan(index, true, &sets, 4, total, used)
+    }
+
+    #[test]
+    fn test_tolerance() {
+        assert_eq!(write_drive_tolerance(16, 4), 4);
+        assert_eq!(write_drive_tolerance(8, 4), 3);
+        assert_eq!(write_drive_tolerance(2, 1), 0);
+
+        let pool = existing(0, 100 * TB, 0);
+        assert_eq!(pool.nodes, 4);
+        assert_eq!(pool.read_drive_tolerance, 4);
+        assert_eq!(pool.read_node_tolerance, 1);
+        assert_eq!(pool.write_node_tolerance, 1);
+
+        // Two nodes holding 4 drives each of a set of 8 with parity 4
+        let sets = vec![vec![0, 1, 0, 1, 0, 1, 0, 1]];
+        assert_eq!(node_tolerance(&sets, 4), 1);
+        assert_eq!(node_tolerance(&sets, 3), 0);
+    }
+
+    #[test]
+    fn test_simulate_expansion() {
+        let request = CapacityPlanRequest {
+            pools: vec![ProposedPool {
+                nodes: 4,
+                drives_per_node: 4,
+                drive_capacity: 10 * TB,
+                parity: Some(2),
+                set_drive_count: 0,
+            }],
+            ..Default::default()
+        };
+        let plan = simulate(vec![existing(0, 120 * TB, 60 * TB)], &request).unwrap();
+
+        let added = &plan.pools[1];
+        assert!(!added.existing);
+        assert_eq!(added.sets, 1);
+        assert_eq!(added.set_drive_count, 16);
+        assert_eq!(added.parity, 4);
+        assert_eq!(added.total_capacity, 120 * TB);
+        assert_eq!(plan.total_capacity, 240 * TB);
+        assert_eq!(plan.free_capacity, 180 * TB);
+        assert_eq!(plan.warnings, vec!["pool 1: pools share the parity of the first pool, 4".to_string()]);
+
+        // Twice the free space, twice the new objects
+        assert!((plan.pools[0].write_share - 1.0 / 3.0).abs() < 1e-9);
+        assert!((added.write_share - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_simulate_decommission() {
+        let pools = vec![existing(0, 100 * TB, 50 * TB), existing(1, 100 * TB, 20 * TB)];
+        let request = CapacityPlanRequest {
+            decommission: vec![0],
+            ..Default::default()
+        };
+        let plan = simulate(pools.clone(), &request).unwrap();
+        let decommission = plan.decommission.unwrap();
+        assert_eq!(decommission.data_to_move, 50 * TB);
+        assert!(decommission.fits);
+        assert_eq!(decommission.free_after, 30 * TB);
+        assert_eq!(plan.pools[0].write_share, 0.0);
+        assert_eq!(plan.pools[1].write_share, 1.0);
+
+        let crowded = vec![existing(0, 100 * TB, 50 * TB), existing(1, 100 * TB, 60 * TB)];
+        let plan = simulate(crowded, &request).unwrap();
+        assert!(!plan.decommission.unwrap().fits);
+
+        let all = CapacityPlanRequest {
+            decommission: vec![0, 1],
+            ..Default::default()
+        };
+        assert!(simulate(pools.clone(), &all).is_err());
+        let missing = CapacityPlanRequest {
+            decommission: vec![2],
+            ..Default::default()
+        };
+        assert!(simulate(pools, &missing).is_err());
+    }
+
+    #[test]
+    fn test_simulate_new_cluster() {
+        let request = CapacityPlanRequest {
+            pools: vec![ProposedPool {
+                nodes: 4,
+                drives_per_node: 8,
+                drive_capacity: TB,
+                parity: Some(2),
+                set_drive_count: 8,
+            }],
+            ignore_existing: true,
+            ..Default::default()
+        };
+        let plan = simulate(Vec::new(), &request).unwrap();
+        let pool = &plan.pools[0];
+        assert_eq!(pool.sets, 4);
+        assert_eq!(pool.parity, 2);
+        assert_eq!(pool.total_capacity, 24 * TB);
+        assert!(plan.warnings.is_empty(), "{:?}", plan.warnings);
+
+        let too_much_parity = CapacityPlanRequest {
+            pools: vec![ProposedPool {
+                parity: Some(5),
+                ..request.pools[0].clone()
+            }],
+            ..request
+        };
+        assert!(simulate(Vec::new(), &too_much_parity).is_err());
+    }
+}
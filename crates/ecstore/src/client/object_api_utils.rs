@@ -144,6 +144,7 @@ pub fn new_getobjectreader<'a>(
         let r = GetObjectReader {
             object_info: oi.clone(),
             stream: Box::new(input_reader),
+            body: None,
         };
         r
         //})
@@ -19,26 +19,36 @@ use crate::bucket::versioning_sys::BucketVersioningSys;
 use crate::store::ECStore;
 use crate::store_api::{ObjectOptions, ObjectToDelete};
 use rustfs_lock::MAX_DELETE_LIST;
+use std::sync::Arc;
+use tracing::warn;
 
-pub async fn delete_object_versions(api: ECStore, bucket: &str, to_del: &[ObjectToDelete], _lc_event: lifecycle::Event) {
-    let mut remaining = to_del;
-    loop {
-        let mut to_del = remaining;
-        if to_del.len() > MAX_DELETE_LIST {
-            remaining = &to_del[MAX_DELETE_LIST..];
-            to_del = &to_del[..MAX_DELETE_LIST];
-        } else {
-            remaining = &[];
+/// Delete object versions expired by lifecycle, up to `MAX_DELETE_LIST` of them per call so the
+/// versions of an erasure set go to each of its drives in one request.
+pub async fn delete_object_versions(api: Arc<ECStore>, bucket: &str, to_del: &[ObjectToDelete], _lc_event: lifecycle::Event) {
+    let version_suspended = match BucketVersioningSys::get(bucket).await {
+        Ok(vc) => vc.suspended(),
+        Err(err) => {
+            warn!("lifecycle: get versioning config of {} failed: {}", bucket, err);
+            return;
+        }
+    };
+
+    for chunk in to_del.chunks(MAX_DELETE_LIST) {
+        let (_, errs) = api
+            .delete_objects(
+                bucket,
+                chunk.to_vec(),
+                ObjectOptions {
+                    version_suspended,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        for (obj, err) in chunk.iter().zip(errs.iter()) {
+            if let Some(err) = err {
+                warn!("lifecycle: delete {}/{} {:?} failed: {}", bucket, obj.object_name, obj.version_id, err);
+            }
         }
-        let vc = BucketVersioningSys::get(bucket).await.expect("err!");
-        let _deleted_objs = api.delete_objects(
-            bucket,
-            to_del.to_vec(),
-            ObjectOptions {
-                //prefix_enabled_fn:  vc.prefix_enabled(""),
-                version_suspended: vc.suspended(),
-                ..Default::default()
-            },
-        );
     }
 }
@@ -0,0 +1,172 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cluster wide switches for legacy client compatibility.
+//!
+//! The switches are off by default, persisted in the cluster config when changed through the
+//! admin API, and reloaded periodically by every node to learn about changes made on peers.
+
+use crate::config::com::{read_config, save_config};
+use crate::error::{Error, Result};
+use crate::store::ECStore;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+pub const COMPAT_CONFIG_PATH: &str = "config/compat.json";
+
+/// How often a node reloads the persisted switches to pick up changes made on peers.
+pub const COMPAT_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+pub static GLOBAL_COMPAT_SYS: LazyLock<CompatSys> = LazyLock::new(CompatSys::default);
+
+/// CreateMultipartUpload request header, `hidden` keeps the upload out of prefix listings.
+pub const UPLOAD_VISIBILITY_HEADER: &str = "x-rustfs-upload-visibility";
+/// Upload metadata key recording the object name, used to list uploads by prefix.
+pub const MULTIPART_OBJECT_KEY: &str = "x-rustfs-internal-multipart-object";
+/// Upload metadata key set on uploads created with `x-rustfs-upload-visibility: hidden`.
+pub const MULTIPART_HIDDEN_KEY: &str = "x-rustfs-internal-multipart-hidden";
+
+/// What happens to the empty directory markers above a newly written object.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DirectoryMarkers {
+    /// Leave them in place, the S3A `keep` marker policy.
+    #[default]
+    Keep,
+    /// Remove them once the object is written, the S3A `delete` marker policy applied server side.
+    Delete,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatConfig {
+    /// Accept requests signed with AWS Signature Version 2, in header or presigned form.
+    #[serde(default)]
+    pub signature_v2: bool,
+    /// Behave the way the Hadoop S3A magic committer expects: ListMultipartUploads returns the
+    /// uploads of every key under the prefix instead of only those of the exact key.
+    #[serde(default)]
+    pub s3a_magic_committer: bool,
+    /// Handling of the directory markers above newly written objects, in unversioned buckets.
+    #[serde(default)]
+    pub directory_markers: DirectoryMarkers,
+}
+
+impl CompatConfig {
+    pub fn unmarshal(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data).map_err(Error::other)
+    }
+
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(Error::other)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CompatSys {
+    config: RwLock<CompatConfig>,
+}
+
+impl CompatSys {
+    /// Load the persisted switches, treating a missing config as all switches off.
+    pub async fn load(&self, store: Arc<ECStore>) -> Result<()> {
+        let config = match read_config(store, COMPAT_CONFIG_PATH).await {
+            Ok(data) => CompatConfig::unmarshal(&data)?,
+            Err(Error::ConfigNotFound) => CompatConfig::default(),
+            Err(err) => return Err(err),
+        };
+
+        *self.config.write() = config;
+        Ok(())
+    }
+
+    pub async fn set(&self, store: Arc<ECStore>, config: CompatConfig) -> Result<CompatConfig> {
+        save_config(store, COMPAT_CONFIG_PATH, config.marshal()?).await?;
+        info!(
+            signature_v2 = config.signature_v2,
+            s3a_magic_committer = config.s3a_magic_committer,
+            directory_markers = ?config.directory_markers,
+            "compatibility switches changed"
+        );
+
+        *self.config.write() = config.clone();
+        Ok(config)
+    }
+
+    pub fn config(&self) -> CompatConfig {
+        self.config.read().clone()
+    }
+
+    pub fn signature_v2_enabled(&self) -> bool {
+        self.config.read().signature_v2
+    }
+
+    pub fn s3a_magic_committer_enabled(&self) -> bool {
+        self.config.read().s3a_magic_committer
+    }
+
+    pub fn directory_markers(&self) -> DirectoryMarkers {
+        self.config.read().directory_markers
+    }
+}
+
+/// Load the persisted compatibility switches and keep them in sync with changes made on peers.
+pub async fn init_compat_sys(store: Arc<ECStore>, cancel: CancellationToken) {
+    if let Err(err) = GLOBAL_COMPAT_SYS.load(store.clone()).await {
+        warn!("load compatibility switches failed: {:?}", err);
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(COMPAT_REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = interval.tick() => {
+                    if let Err(err) = GLOBAL_COMPAT_SYS.load(store.clone()).await {
+                        warn!("refresh compatibility switches failed: {:?}", err);
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_round_trip() {
+        let config = CompatConfig {
+            signature_v2: true,
+            s3a_magic_committer: true,
+            directory_markers: DirectoryMarkers::Delete,
+        };
+        assert_eq!(CompatConfig::unmarshal(&config.marshal().unwrap()).unwrap(), config);
+        assert_eq!(CompatConfig::unmarshal(b"{}").unwrap(), CompatConfig::default());
+        assert_eq!(
+            CompatConfig::unmarshal(br#"{"directoryMarkers":"delete"}"#)
+                .unwrap()
+                .directory_markers,
+            DirectoryMarkers::Delete
+        );
+        assert!(!CompatSys::default().signature_v2_enabled());
+        assert!(!CompatSys::default().s3a_magic_committer_enabled());
+    }
+}
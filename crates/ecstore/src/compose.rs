@@ -0,0 +1,183 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Server-side concatenation of objects.
+//!
+//! Composing writes a new object holding the data of up to `MAX_COMPOSE_SOURCES` objects of the
+//! same bucket, one after the other, without the client reading or uploading any of it. The
+//! sources are read one at a time, each from its data shards, and streamed into the erasure
+//! encoder of the destination, so memory use does not depend on the size or number of sources.
+
+use crate::bucket::versioning_sys::BucketVersioningSys;
+use crate::error::{Error, Result, StorageError};
+use crate::store::ECStore;
+use crate::store_api::{ObjectIO, ObjectInfo, ObjectOptions, PutObjReader, StorageAPI};
+use http::HeaderMap;
+use rustfs_rio::{HashReader, WarpReader};
+use rustfs_utils::http::headers::SSEC_ALGORITHM_HEADER;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+/// Most objects one compose may concatenate.
+pub const MAX_COMPOSE_SOURCES: usize = 32;
+
+// Data buffered between the reading of the sources and the encoder
+const COMPOSE_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+
+const CONTENT_TYPE: &str = "content-type";
+
+/// An object to append to the composed object.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposeSource {
+    pub object: String,
+    /// The version to read instead of the latest one
+    #[serde(default)]
+    pub version_id: Option<String>,
+    /// Fail unless the source has this ETag
+    #[serde(default)]
+    pub etag: Option<String>,
+}
+
+/// Write `bucket/object` as the concatenation of `sources`, with `user_defined` as its metadata.
+/// The content type of the first source is kept unless `user_defined` sets one.
+pub async fn compose_object(
+    store: Arc<ECStore>,
+    bucket: &str,
+    object: &str,
+    sources: &[ComposeSource],
+    mut user_defined: HashMap<String, String>,
+) -> Result<ObjectInfo> {
+    if sources.is_empty() || sources.len() > MAX_COMPOSE_SOURCES {
+        return Err(StorageError::InvalidArgument(
+            bucket.to_string(),
+            object.to_string(),
+            format!("compose takes 1 to {MAX_COMPOSE_SOURCES} sources, got {}", sources.len()),
+        ));
+    }
+
+    // Pin every source to the version found now, so a concurrent overwrite cannot change the sizes
+    let mut pinned = Vec::with_capacity(sources.len());
+    let mut total_size = 0i64;
+    for source in sources {
+        let opts = ObjectOptions {
+            version_id: source.version_id.clone(),
+            ..Default::default()
+        };
+        let info = store.get_object_info(bucket, &source.object, &opts).await?;
+        check_source(bucket, source, &info)?;
+
+        total_size += info.get_actual_size()?;
+        pinned.push(ObjectOptions {
+            version_id: info.version_id.map(|v| v.to_string()),
+            ..Default::default()
+        });
+        if pinned.len() == 1
+            && !user_defined.contains_key(CONTENT_TYPE)
+            && let Some(content_type) = info.content_type.clone()
+        {
+            user_defined.insert(CONTENT_TYPE.to_string(), content_type);
+        }
+    }
+
+    let (mut writer, reader) = tokio::io::duplex(COMPOSE_BUFFER_SIZE);
+    let read_store = store.clone();
+    let read_bucket = bucket.to_string();
+    let objects: Vec<String> = sources.iter().map(|s| s.object.clone()).collect();
+    let copier = tokio::spawn(async move {
+        for (object, opts) in objects.iter().zip(pinned.iter()) {
+            let mut source = read_store
+                .get_object_reader(&read_bucket, object, None, HeaderMap::new(), opts)
+                .await?;
+            tokio::io::copy(&mut source.stream, &mut writer).await?;
+        }
+        writer.shutdown().await?;
+        Ok::<(), Error>(())
+    });
+
+    // A source failing to read ends the stream short, which fails the write against the size
+    let hash_reader = HashReader::new(Box::new(WarpReader::new(reader)), total_size, total_size, None, None, false)?;
+    let opts = ObjectOptions {
+        versioned: BucketVersioningSys::prefix_enabled(bucket, object).await,
+        version_suspended: BucketVersioningSys::prefix_suspended(bucket, object).await,
+        user_defined,
+        ..Default::default()
+    };
+    let written = store
+        .put_object(bucket, object, &mut PutObjReader::new(hash_reader), &opts)
+        .await;
+
+    // The write got every byte when it succeeded, otherwise the read error tells why it did not
+    match (written, copier.await?) {
+        (Ok(info), _) => Ok(info),
+        (Err(_), Err(err)) => {
+            warn!("compose of {}/{} failed reading its sources: {}", bucket, object, err);
+            Err(err)
+        }
+        (Err(err), Ok(())) => Err(err),
+    }
+}
+
+fn check_source(bucket: &str, source: &ComposeSource, info: &ObjectInfo) -> Result<()> {
+    if info.delete_marker {
+        return Err(StorageError::ObjectNotFound(bucket.to_string(), source.object.clone()));
+    }
+
+    if let Some(etag) = &source.etag
+        && info.etag.as_deref().map(|e| e.trim_matches('"')) != Some(etag.trim_matches('"'))
+    {
+        return Err(StorageError::PreconditionFailed);
+    }
+
+    // The stored data of encrypted objects is ciphertext under a key of its own
+    if info.user_defined.contains_key(SSEC_ALGORITHM_HEADER) || info.user_defined.contains_key("x-rustfs-encryption-key") {
+        return Err(StorageError::InvalidArgument(
+            bucket.to_string(),
+            source.object.clone(),
+            "encrypted objects cannot be composed".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_source() {
+        let source = ComposeSource {
+            object: "part-1".to_string(),
+            etag: Some("\"abc\"".to_string()),
+            ..Default::default()
+        };
+        let mut info = ObjectInfo {
+            etag: Some("abc".to_string()),
+            ..Default::default()
+        };
+        assert!(check_source("bucket", &source, &info).is_ok());
+
+        info.etag = Some("def".to_string());
+        assert_eq!(check_source("bucket", &source, &info), Err(StorageError::PreconditionFailed));
+
+        info.etag = Some("abc".to_string());
+        info.user_defined
+            .insert(SSEC_ALGORITHM_HEADER.to_string(), "AES256".to_string());
+        assert!(check_source("bucket", &source, &info).is_err());
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config::{Config, KV, KVS};
+use rustfs_config::{AUTH_CHAIN_ORDER, AUTH_CHAIN_SUB_SYS, DEFAULT_AUTH_CHAIN_ORDER, DEFAULT_DELIMITER, ENV_AUTH_CHAIN_ORDER};
+use std::sync::LazyLock;
+
+/// Default KVS for the authentication chain.
+pub static DEFAULT_AUTH_CHAIN_KVS: LazyLock<KVS> = LazyLock::new(|| {
+    KVS(vec![KV {
+        key: AUTH_CHAIN_ORDER.to_owned(),
+        value: DEFAULT_AUTH_CHAIN_ORDER.to_owned(),
+        hidden_if_empty: false,
+    }])
+});
+
+/// Names of the authenticators of the chain in order, the environment overrides the stored config.
+pub fn lookup_order(cfg: &Config) -> Vec<String> {
+    let order = match std::env::var(ENV_AUTH_CHAIN_ORDER) {
+        Ok(order) => order,
+        Err(_) => cfg
+            .get_value(AUTH_CHAIN_SUB_SYS, DEFAULT_DELIMITER)
+            .and_then(|kvs| kvs.lookup(AUTH_CHAIN_ORDER))
+            .unwrap_or_else(|| DEFAULT_AUTH_CHAIN_ORDER.to_owned()),
+    };
+
+    order
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
@@ -0,0 +1,389 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backups of the system configuration.
+//!
+//! A backup holds the config objects of the system bucket (IAM, the server config and the other
+//! subsystem configs) and the metadata of every bucket in a single object, written like any config
+//! object with the highest parity the sets allow. Backups are taken on a schedule and through the
+//! admin API. Every node runs the schedule, the backup of an interval is named after its start and
+//! written create-only, so one node takes it and the others find it taken.
+//!
+//! Restoring writes the objects of a backup back in place, through the config log so the IAM
+//! caches of every node pick the changes up, and reloads the metadata of the restored buckets on
+//! all nodes. Objects created after the backup are left alone, and a backup of the current state is
+//! taken first so a restore can be undone.
+
+use crate::bucket::metadata::{BUCKET_METADATA_FILE, load_bucket_metadata};
+use crate::bucket::metadata_sys;
+use crate::config::change_log::{ConfigChange, GLOBAL_CONFIG_LOG, base64_data};
+use crate::config::com::{CONFIG_PREFIX, delete_config, read_config, save_config_with_opts};
+use crate::disk::{BUCKET_META_PREFIX, RUSTFS_META_BUCKET};
+use crate::error::{Error, Result, StorageError};
+use crate::notification_sys::get_global_notification_sys;
+use crate::store::ECStore;
+use crate::store_api::{HTTPPreconditions, ObjectInfoOrErr, ObjectOptions, StorageAPI, WalkOptions};
+use rustfs_common::globals::GLOBAL_Local_Node_Name;
+use rustfs_config::{
+    DEFAULT_CONFIG_BACKUP_INTERVAL, DEFAULT_CONFIG_BACKUP_RETAIN, ENV_CONFIG_BACKUP_INTERVAL, ENV_CONFIG_BACKUP_RETAIN,
+};
+use rustfs_utils::get_env_u64;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+const CONFIG_BACKUP_PREFIX: &str = "config-backups";
+
+// Entries of the config log are replayed by the log itself, restoring them would replay them twice
+const CONFIG_LOG_PREFIX: &str = "config/log/";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupObject {
+    pub path: String,
+    #[serde(with = "base64_data")]
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBackup {
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    pub node: String,
+    pub objects: Vec<BackupObject>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBackupInfo {
+    pub id: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    pub size: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    /// Backup that was restored.
+    pub backup: String,
+    /// Backup of the state before the restore.
+    pub previous: Option<String>,
+    pub restored: usize,
+    /// Objects that could not be written back.
+    pub failed: Vec<String>,
+    /// Buckets whose metadata was restored.
+    pub buckets: Vec<String>,
+}
+
+/// Backups are named after the second they were taken at, so names sort by time.
+fn backup_id(created: OffsetDateTime) -> String {
+    format!("{:020}", created.unix_timestamp().max(0))
+}
+
+fn backup_path(id: &str) -> String {
+    format!("{CONFIG_BACKUP_PREFIX}/{id}.json")
+}
+
+fn parse_backup_name(name: &str) -> Option<(String, OffsetDateTime)> {
+    let id = name
+        .strip_prefix(CONFIG_BACKUP_PREFIX)?
+        .strip_prefix('/')?
+        .strip_suffix(".json")?;
+    let created = OffsetDateTime::from_unix_timestamp(id.parse().ok()?).ok()?;
+    Some((id.to_string(), created))
+}
+
+/// Whether a system bucket object is part of a backup.
+fn is_backed_up(path: &str) -> bool {
+    if path.starts_with(CONFIG_LOG_PREFIX) {
+        return false;
+    }
+    if path.starts_with(&format!("{CONFIG_PREFIX}/")) {
+        return true;
+    }
+    path.starts_with(&format!("{BUCKET_META_PREFIX}/")) && path.ends_with(&format!("/{BUCKET_METADATA_FILE}"))
+}
+
+/// Bucket a restored object holds the metadata of.
+fn metadata_bucket(path: &str) -> Option<&str> {
+    path.strip_prefix(BUCKET_META_PREFIX)?
+        .strip_prefix('/')?
+        .strip_suffix(BUCKET_METADATA_FILE)?
+        .strip_suffix('/')
+        .filter(|bucket| !bucket.is_empty() && !bucket.contains('/'))
+}
+
+async fn list_objects(store: Arc<ECStore>, prefix: &str) -> Result<Vec<(String, i64)>> {
+    let (tx, mut rx) = mpsc::channel::<ObjectInfoOrErr>(100);
+    let cancel = CancellationToken::new();
+    let walk = {
+        let store = store.clone();
+        let prefix = prefix.to_string();
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            store
+                .walk(cancel, RUSTFS_META_BUCKET, &prefix, tx, WalkOptions::default())
+                .await
+        })
+    };
+
+    let mut objects = Vec::new();
+    while let Some(item) = rx.recv().await {
+        if let Some(err) = item.err {
+            cancel.cancel();
+            return Err(err);
+        }
+        if let Some(info) = item.item {
+            objects.push((info.name, info.size));
+        }
+    }
+
+    match walk.await {
+        Ok(Ok(())) => Ok(objects),
+        Ok(Err(err)) => Err(err),
+        Err(err) => Err(Error::other(err)),
+    }
+}
+
+async fn collect(store: Arc<ECStore>) -> Result<Vec<BackupObject>> {
+    let mut objects = Vec::new();
+    for prefix in [CONFIG_PREFIX, BUCKET_META_PREFIX] {
+        for (path, _) in list_objects(store.clone(), &format!("{prefix}/")).await? {
+            if !is_backed_up(&path) {
+                continue;
+            }
+            match read_config(store.clone(), &path).await {
+                Ok(data) => objects.push(BackupObject { path, data }),
+                // Deleted while listing
+                Err(Error::ConfigNotFound) => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    Ok(objects)
+}
+
+/// Take a backup named after `created`, None when a backup with that name exists already.
+pub async fn create_backup(store: Arc<ECStore>, created: OffsetDateTime) -> Result<Option<ConfigBackupInfo>> {
+    let id = backup_id(created);
+    let backup = ConfigBackup {
+        created,
+        node: GLOBAL_Local_Node_Name.read().await.clone(),
+        objects: collect(store.clone()).await?,
+    };
+    let data = serde_json::to_vec(&backup).map_err(Error::other)?;
+    let size = data.len() as i64;
+
+    let opts = ObjectOptions {
+        max_parity: true,
+        http_preconditions: Some(HTTPPreconditions {
+            if_none_match: Some("*".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    match save_config_with_opts(store, &backup_path(&id), data, &opts).await {
+        Ok(()) => {}
+        Err(StorageError::PreconditionFailed) => return Ok(None),
+        Err(err) => return Err(err),
+    }
+
+    info!(backup = %id, objects = backup.objects.len(), "config backup taken");
+    Ok(Some(ConfigBackupInfo { id, created, size }))
+}
+
+/// Backups in the order they were taken.
+pub async fn list_backups(store: Arc<ECStore>) -> Result<Vec<ConfigBackupInfo>> {
+    let mut backups: Vec<ConfigBackupInfo> = list_objects(store, &format!("{CONFIG_BACKUP_PREFIX}/"))
+        .await?
+        .into_iter()
+        .filter_map(|(name, size)| parse_backup_name(&name).map(|(id, created)| ConfigBackupInfo { id, created, size }))
+        .collect();
+    backups.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(backups)
+}
+
+pub async fn read_backup(store: Arc<ECStore>, id: &str) -> Result<ConfigBackup> {
+    let data = read_config(store, &backup_path(id)).await?;
+    serde_json::from_slice(&data).map_err(Error::other)
+}
+
+/// Delete the oldest backups to keep `retain`.
+pub async fn prune_backups(store: Arc<ECStore>, retain: usize) -> Result<()> {
+    let backups = list_backups(store.clone()).await?;
+    let excess = backups.len().saturating_sub(retain.max(1));
+    for backup in &backups[..excess] {
+        match delete_config(store.clone(), &backup_path(&backup.id)).await {
+            Ok(()) | Err(Error::ConfigNotFound) => {}
+            Err(err) => warn!(backup = %backup.id, "delete expired config backup failed: {:?}", err),
+        }
+    }
+    Ok(())
+}
+
+/// Restore the last backup taken at or before `at`, ConfigNotFound when there is none.
+pub async fn restore_backup(store: Arc<ECStore>, at: OffsetDateTime) -> Result<RestoreReport> {
+    let Some(target) = list_backups(store.clone())
+        .await?
+        .into_iter()
+        .rev()
+        .find(|backup| backup.created <= at)
+    else {
+        return Err(Error::ConfigNotFound);
+    };
+    let backup = read_backup(store.clone(), &target.id).await?;
+
+    let now = OffsetDateTime::now_utc();
+    let previous = match create_backup(store.clone(), now).await? {
+        Some(info) => Some(info.id),
+        // Taken within the same second, by the schedule or another restore
+        None => Some(backup_id(now)),
+    };
+
+    let mut report = RestoreReport {
+        backup: target.id,
+        previous,
+        ..Default::default()
+    };
+    let mut buckets = BTreeSet::new();
+    for object in backup.objects {
+        let change = ConfigChange::Put {
+            path: object.path.clone(),
+            data: object.data,
+        };
+        match GLOBAL_CONFIG_LOG.append(store.clone(), change).await {
+            Ok(_) => {
+                report.restored += 1;
+                if let Some(bucket) = metadata_bucket(&object.path) {
+                    buckets.insert(bucket.to_string());
+                }
+            }
+            Err(err) => {
+                warn!(path = %object.path, "restore config object failed: {:?}", err);
+                report.failed.push(object.path);
+            }
+        }
+    }
+
+    for bucket in &buckets {
+        match load_bucket_metadata(store.clone(), bucket).await {
+            Ok(meta) => {
+                if let Err(err) = metadata_sys::set_bucket_metadata(bucket.clone(), meta).await {
+                    warn!(bucket = %bucket, "reload restored bucket metadata failed: {:?}", err);
+                }
+            }
+            Err(err) => warn!(bucket = %bucket, "load restored bucket metadata failed: {:?}", err),
+        }
+        if let Some(notification_sys) = get_global_notification_sys() {
+            for peer in notification_sys.load_bucket_metadata(bucket).await {
+                if let Some(err) = peer.err {
+                    warn!(bucket = %bucket, host = %peer.host, "peer reload of restored bucket metadata failed: {:?}", err);
+                }
+            }
+        }
+    }
+    report.buckets = buckets.into_iter().collect();
+
+    info!(
+        backup = %report.backup,
+        restored = report.restored,
+        failed = report.failed.len(),
+        "config backup restored"
+    );
+    Ok(report)
+}
+
+/// Take a backup every `RUSTFS_CONFIG_BACKUP_INTERVAL` and keep the last `RUSTFS_CONFIG_BACKUP_RETAIN`.
+pub async fn init_config_backup(store: Arc<ECStore>, cancel: CancellationToken) {
+    let interval_secs = get_env_u64(ENV_CONFIG_BACKUP_INTERVAL, DEFAULT_CONFIG_BACKUP_INTERVAL);
+    if interval_secs == 0 {
+        return;
+    }
+    let retain = get_env_u64(ENV_CONFIG_BACKUP_RETAIN, DEFAULT_CONFIG_BACKUP_RETAIN) as usize;
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = interval.tick() => {
+                    let now = OffsetDateTime::now_utc().unix_timestamp();
+                    let slot = now - now.rem_euclid(interval_secs as i64);
+                    let Ok(created) = OffsetDateTime::from_unix_timestamp(slot) else {
+                        continue;
+                    };
+                    match create_backup(store.clone(), created).await {
+                        Ok(Some(_)) => {
+                            if let Err(err) = prune_backups(store.clone(), retain).await {
+                                warn!("prune config backups failed: {:?}", err);
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(err) => warn!("take config backup failed: {:?}", err),
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backed_up_objects() {
+        assert!(is_backed_up("config/config.json"));
+        assert!(is_backed_up("config/iam/users/alice/identity.json"));
+        assert!(!is_backed_up("config/log/entries/00000000000000000001.json"));
+        assert!(is_backed_up("buckets/photos/.metadata.bin"));
+        assert!(!is_backed_up("buckets/photos/.usage-cache.bin"));
+        assert!(!is_backed_up("config-backups/00000000001700000000.json"));
+
+        assert_eq!(metadata_bucket("buckets/photos/.metadata.bin"), Some("photos"));
+        assert_eq!(metadata_bucket("config/config.json"), None);
+    }
+
+    #[test]
+    fn test_backup_names_sort_by_time() {
+        let early = OffsetDateTime::from_unix_timestamp(999).unwrap();
+        let late = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        assert!(backup_id(early) < backup_id(late));
+
+        let (id, created) = parse_backup_name(&backup_path(&backup_id(late))).unwrap();
+        assert_eq!(id, backup_id(late));
+        assert_eq!(created, late);
+        assert!(parse_backup_name("config-backups/notes.txt").is_none());
+    }
+
+    #[test]
+    fn test_backup_round_trip() {
+        let backup = ConfigBackup {
+            created: OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap(),
+            node: "node1:9000".to_string(),
+            objects: vec![BackupObject {
+                path: "buckets/photos/.metadata.bin".to_string(),
+                data: vec![0, 1, 2, 255],
+            }],
+        };
+        let data = serde_json::to_vec(&backup).unwrap();
+        assert_eq!(serde_json::from_slice::<ConfigBackup>(&data).unwrap(), backup);
+    }
+}
@@ -0,0 +1,399 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Replicated, ordered log of cluster configuration changes.
+//!
+//! Changes to config objects that every node caches, such as the IAM users, groups and policies,
+//! go through this log instead of being written to their config objects directly. Each change
+//! becomes an entry with the next sequence number, created with a create-only write at erasure
+//! quorum and read back, so a number belongs to a single change and every node sees the changes in
+//! the same order. The entry carries the new data and is then applied to the config object it
+//! targets, which is what readers keep reading.
+//!
+//! Every node tails the log and tells the subscribers which config objects changed, so a policy or
+//! user change made on one node reaches the caches of all nodes within a poll interval, without
+//! depending on peer notifications. A node that was down catches up from the log when it returns.
+//!
+//! The log is compacted by moving the snapshot point, up to which the config objects are known to
+//! hold every change, and deleting the entries before it. A node that fell behind the snapshot
+//! reloads its caches entirely.
+
+use crate::config::com::{delete_config, read_config, save_config, save_config_with_opts};
+use crate::error::{Error, Result, StorageError};
+use crate::store::ECStore;
+use crate::store_api::{HTTPPreconditions, ObjectOptions};
+use rustfs_common::globals::GLOBAL_Local_Node_Name;
+use rustfs_config::{
+    DEFAULT_CONFIG_LOG_ENABLE, DEFAULT_CONFIG_LOG_POLL_INTERVAL, DEFAULT_CONFIG_LOG_RETAIN, ENV_CONFIG_LOG_ENABLE,
+    ENV_CONFIG_LOG_POLL_INTERVAL, ENV_CONFIG_LOG_RETAIN,
+};
+use rustfs_utils::{get_env_bool, get_env_u64};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+const CONFIG_LOG_PREFIX: &str = "config/log";
+
+// Attempts to claim a sequence number before giving up on a change
+const MAX_APPEND_ATTEMPTS: usize = 32;
+
+// How often the log is compacted
+const COMPACT_INTERVAL: Duration = Duration::from_secs(600);
+
+pub static GLOBAL_CONFIG_LOG: LazyLock<ConfigLog> = LazyLock::new(ConfigLog::default);
+
+/// A change of a config object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum ConfigChange {
+    Put {
+        path: String,
+        #[serde(with = "base64_data")]
+        data: Vec<u8>,
+    },
+    Delete {
+        path: String,
+    },
+}
+
+impl ConfigChange {
+    pub fn path(&self) -> &str {
+        match self {
+            ConfigChange::Put { path, .. } | ConfigChange::Delete { path } => path,
+        }
+    }
+}
+
+pub(crate) mod base64_data {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&base64_simd::STANDARD.encode_to_string(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(d)?;
+        base64_simd::STANDARD.decode_to_vec(s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogEntry {
+    seq: u64,
+    /// Tells the writer whether the entry it reads back is its own.
+    id: Uuid,
+    node: String,
+    #[serde(with = "time::serde::rfc3339")]
+    time: OffsetDateTime,
+    change: ConfigChange,
+}
+
+/// Point up to which the config objects hold every change; the entries before it are deleted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Snapshot {
+    seq: u64,
+}
+
+/// What a subscriber learns from the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigLogEvent {
+    /// The config object at the path changed.
+    Changed { seq: u64, path: String },
+    /// Changes were missed, everything has to be reloaded.
+    Resync,
+}
+
+pub struct ConfigLog {
+    enabled: AtomicBool,
+    /// Last sequence number applied to the subscribers of this node.
+    applied: AtomicU64,
+    events: broadcast::Sender<ConfigLogEvent>,
+}
+
+impl Default for ConfigLog {
+    fn default() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            applied: AtomicU64::new(0),
+            events: broadcast::channel(1024).0,
+        }
+    }
+}
+
+fn entry_path(seq: u64) -> String {
+    format!("{CONFIG_LOG_PREFIX}/entries/{seq:020}.json")
+}
+
+fn snapshot_path() -> String {
+    format!("{CONFIG_LOG_PREFIX}/snapshot.json")
+}
+
+async fn read_entry(store: Arc<ECStore>, seq: u64) -> Result<Option<LogEntry>> {
+    match read_config(store, &entry_path(seq)).await {
+        Ok(data) => Ok(Some(serde_json::from_slice(&data).map_err(Error::other)?)),
+        Err(Error::ConfigNotFound) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+async fn read_snapshot(store: Arc<ECStore>) -> Result<Snapshot> {
+    match read_config(store, &snapshot_path()).await {
+        Ok(data) => serde_json::from_slice(&data).map_err(Error::other),
+        Err(Error::ConfigNotFound) => Ok(Snapshot::default()),
+        Err(err) => Err(err),
+    }
+}
+
+async fn apply_change(store: Arc<ECStore>, change: &ConfigChange) -> Result<()> {
+    match change {
+        ConfigChange::Put { path, data } => save_config(store, path, data.clone()).await,
+        ConfigChange::Delete { path } => match delete_config(store, path).await {
+            Ok(()) | Err(Error::ConfigNotFound) => Ok(()),
+            Err(err) => Err(err),
+        },
+    }
+}
+
+impl ConfigLog {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ConfigLogEvent> {
+        self.events.subscribe()
+    }
+
+    /// The sequence number after the last entry, found from the snapshot and what this node applied.
+    async fn next_seq(&self, store: Arc<ECStore>) -> Result<u64> {
+        let mut seq = self
+            .applied
+            .load(Ordering::Relaxed)
+            .max(read_snapshot(store.clone()).await?.seq)
+            + 1;
+        while read_entry(store.clone(), seq).await?.is_some() {
+            seq += 1;
+        }
+        Ok(seq)
+    }
+
+    /// Record `change` in the log and apply it, returning its sequence number. Without the log the
+    /// change is only applied.
+    pub async fn append(&self, store: Arc<ECStore>, change: ConfigChange) -> Result<u64> {
+        if !self.is_enabled() {
+            match &change {
+                ConfigChange::Put { path, data } => save_config(store, path, data.clone()).await?,
+                ConfigChange::Delete { path } => delete_config(store, path).await?,
+            }
+            return Ok(0);
+        }
+
+        // Deleting what does not exist reports it, as deleting the config object directly does
+        if let ConfigChange::Delete { path } = &change {
+            read_config(store.clone(), path).await?;
+        }
+
+        let node = GLOBAL_Local_Node_Name.read().await.clone();
+        let mut seq = self.next_seq(store.clone()).await?;
+        for _ in 0..MAX_APPEND_ATTEMPTS {
+            let entry = LogEntry {
+                seq,
+                id: Uuid::new_v4(),
+                node: node.clone(),
+                time: OffsetDateTime::now_utc(),
+                change: change.clone(),
+            };
+            let data = serde_json::to_vec(&entry).map_err(Error::other)?;
+
+            let opts = ObjectOptions {
+                max_parity: true,
+                http_preconditions: Some(HTTPPreconditions {
+                    if_none_match: Some("*".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            match save_config_with_opts(store.clone(), &entry_path(seq), data, &opts).await {
+                Ok(()) => {}
+                Err(StorageError::PreconditionFailed) => {
+                    seq += 1;
+                    continue;
+                }
+                Err(err) => return Err(err),
+            }
+
+            // A write racing ours on another node may have taken the number after all
+            if read_entry(store.clone(), seq).await?.is_none_or(|e| e.id != entry.id) {
+                seq += 1;
+                continue;
+            }
+
+            apply_change(store.clone(), &change).await?;
+            self.reapply_later_change(store, seq, change.path()).await?;
+            return Ok(seq);
+        }
+
+        Err(Error::other("config log: no free sequence number"))
+    }
+
+    /// A change of the same path logged after `seq` may have been applied before ours, so apply the
+    /// latest of them again to leave the config object as the log says.
+    async fn reapply_later_change(&self, store: Arc<ECStore>, seq: u64, path: &str) -> Result<()> {
+        let mut latest = None;
+        let mut next = seq + 1;
+        while let Some(entry) = read_entry(store.clone(), next).await? {
+            if entry.change.path() == path {
+                latest = Some(entry.change);
+            }
+            next += 1;
+        }
+
+        if let Some(change) = latest {
+            apply_change(store, &change).await?;
+        }
+        Ok(())
+    }
+
+    /// Hand the entries this node has not seen yet to the subscribers.
+    async fn poll(&self, store: Arc<ECStore>) -> Result<()> {
+        let snapshot = read_snapshot(store.clone()).await?;
+        let mut applied = self.applied.load(Ordering::Relaxed);
+        if applied < snapshot.seq {
+            // The entries up to the snapshot may be gone already
+            applied = snapshot.seq;
+            let _ = self.events.send(ConfigLogEvent::Resync);
+        }
+
+        loop {
+            let seq = applied + 1;
+            let Some(entry) = read_entry(store.clone(), seq).await? else {
+                // A number whose write failed stays empty, skip it once later entries exist
+                if read_entry(store.clone(), seq + 1).await?.is_some() {
+                    warn!(seq, "config log entry missing, reloading everything");
+                    let _ = self.events.send(ConfigLogEvent::Resync);
+                    applied = seq;
+                    continue;
+                }
+                break;
+            };
+
+            let _ = self.events.send(ConfigLogEvent::Changed {
+                seq,
+                path: entry.change.path().to_string(),
+            });
+            applied = seq;
+        }
+
+        self.applied.store(applied, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Move the snapshot point to keep `retain` entries and delete the ones before it.
+    async fn compact(&self, store: Arc<ECStore>, retain: u64) -> Result<()> {
+        let snapshot = read_snapshot(store.clone()).await?;
+        let last = self.applied.load(Ordering::Relaxed);
+        if last <= snapshot.seq + retain {
+            return Ok(());
+        }
+
+        let new_snapshot = Snapshot { seq: last - retain };
+        save_config(store.clone(), &snapshot_path(), serde_json::to_vec(&new_snapshot).map_err(Error::other)?).await?;
+
+        for seq in snapshot.seq + 1..=new_snapshot.seq {
+            match delete_config(store.clone(), &entry_path(seq)).await {
+                Ok(()) | Err(Error::ConfigNotFound) => {}
+                Err(err) => warn!(seq, "delete compacted config log entry failed: {:?}", err),
+            }
+        }
+        info!(snapshot = new_snapshot.seq, "config log compacted");
+        Ok(())
+    }
+}
+
+/// Start the config log: catch up with the entries written so far, then tail and compact it.
+pub async fn init_config_log(store: Arc<ECStore>, cancel: CancellationToken) {
+    if !get_env_bool(ENV_CONFIG_LOG_ENABLE, DEFAULT_CONFIG_LOG_ENABLE) {
+        return;
+    }
+
+    // Changes before this node started are already in the config objects it loads
+    match GLOBAL_CONFIG_LOG.next_seq(store.clone()).await {
+        Ok(next) => GLOBAL_CONFIG_LOG.applied.store(next - 1, Ordering::Relaxed),
+        Err(err) => {
+            warn!("config log disabled, reading it failed: {:?}", err);
+            return;
+        }
+    }
+    GLOBAL_CONFIG_LOG.enabled.store(true, Ordering::Relaxed);
+
+    let poll_interval = Duration::from_secs(get_env_u64(ENV_CONFIG_LOG_POLL_INTERVAL, DEFAULT_CONFIG_LOG_POLL_INTERVAL).max(1));
+    let retain = get_env_u64(ENV_CONFIG_LOG_RETAIN, DEFAULT_CONFIG_LOG_RETAIN).max(1);
+    tokio::spawn(async move {
+        let mut poll = tokio::time::interval(poll_interval);
+        let mut compact = tokio::time::interval(COMPACT_INTERVAL);
+        compact.tick().await;
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = poll.tick() => {
+                    if let Err(err) = GLOBAL_CONFIG_LOG.poll(store.clone()).await {
+                        warn!("poll config log failed: {:?}", err);
+                    }
+                }
+                _ = compact.tick() => {
+                    if let Err(err) = GLOBAL_CONFIG_LOG.compact(store.clone(), retain).await {
+                        warn!("compact config log failed: {:?}", err);
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_round_trip() {
+        let entry = LogEntry {
+            seq: 7,
+            id: Uuid::new_v4(),
+            node: "node1:9000".to_string(),
+            time: OffsetDateTime::now_utc().replace_nanosecond(0).unwrap(),
+            change: ConfigChange::Put {
+                path: "config/iam/policies/readonly/policy.json".to_string(),
+                data: vec![0, 1, 2, 255],
+            },
+        };
+
+        let data = serde_json::to_vec(&entry).unwrap();
+        let text = String::from_utf8(data.clone()).unwrap();
+        assert!(text.contains("\"op\":\"put\""));
+        assert!(text.contains("\"data\":\"AAEC/w==\""));
+        assert_eq!(serde_json::from_slice::<LogEntry>(&data).unwrap(), entry);
+    }
+
+    #[test]
+    fn test_entry_path_order() {
+        assert!(entry_path(9) < entry_path(10));
+        assert_eq!(ConfigChange::Delete { path: "a".to_string() }.path(), "a");
+    }
+}
@@ -13,6 +13,9 @@
 // limitations under the License.
 
 mod audit;
+pub mod auth_chain;
+pub mod backup;
+pub mod change_log;
 pub mod com;
 #[allow(dead_code)]
 pub mod heal;
@@ -22,10 +25,13 @@ pub mod storageclass;
 use crate::error::Result;
 use crate::store::ECStore;
 use com::{STORAGE_CLASS_SUB_SYS, lookup_configs, read_config_without_migrate};
+use rustfs_config::AUTH_CHAIN_SUB_SYS;
 use rustfs_config::COMMENT_KEY;
 use rustfs_config::DEFAULT_DELIMITER;
 use rustfs_config::audit::{AUDIT_MQTT_SUB_SYS, AUDIT_WEBHOOK_SUB_SYS};
-use rustfs_config::notify::{NOTIFY_MQTT_SUB_SYS, NOTIFY_WEBHOOK_SUB_SYS};
+use rustfs_config::notify::{
+    NOTIFY_ES_SUB_SYS, NOTIFY_MQTT_SUB_SYS, NOTIFY_MY_SQL_SUB_SYS, NOTIFY_POSTGRES_SUB_SYS, NOTIFY_WEBHOOK_SUB_SYS,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::LazyLock;
@@ -226,6 +232,10 @@ pub fn init() {
     kvs.insert(AUDIT_WEBHOOK_SUB_SYS.to_owned(), audit::DEFAULT_AUDIT_WEBHOOK_KVS.clone());
     kvs.insert(NOTIFY_MQTT_SUB_SYS.to_owned(), notify::DEFAULT_NOTIFY_MQTT_KVS.clone());
     kvs.insert(AUDIT_MQTT_SUB_SYS.to_owned(), audit::DEFAULT_AUDIT_MQTT_KVS.clone());
+    kvs.insert(NOTIFY_POSTGRES_SUB_SYS.to_owned(), notify::DEFAULT_NOTIFY_POSTGRES_KVS.clone());
+    kvs.insert(NOTIFY_MY_SQL_SUB_SYS.to_owned(), notify::DEFAULT_NOTIFY_MYSQL_KVS.clone());
+    kvs.insert(NOTIFY_ES_SUB_SYS.to_owned(), notify::DEFAULT_NOTIFY_ELASTICSEARCH_KVS.clone());
+    kvs.insert(AUTH_CHAIN_SUB_SYS.to_owned(), auth_chain::DEFAULT_AUTH_CHAIN_KVS.clone());
 
     // Register all default configurations
     register_default_kvs(kvs)
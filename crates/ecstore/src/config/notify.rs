@@ -14,9 +14,15 @@
 
 use crate::config::{KV, KVS};
 use rustfs_config::{
-    COMMENT_KEY, DEFAULT_DIR, DEFAULT_LIMIT, ENABLE_KEY, EnableState, MQTT_BROKER, MQTT_KEEP_ALIVE_INTERVAL, MQTT_PASSWORD,
-    MQTT_QOS, MQTT_QUEUE_DIR, MQTT_QUEUE_LIMIT, MQTT_RECONNECT_INTERVAL, MQTT_TOPIC, MQTT_USERNAME, WEBHOOK_AUTH_TOKEN,
-    WEBHOOK_CLIENT_CERT, WEBHOOK_CLIENT_KEY, WEBHOOK_ENDPOINT, WEBHOOK_QUEUE_DIR, WEBHOOK_QUEUE_LIMIT,
+    COMMENT_KEY, DEFAULT_DIR, DEFAULT_ELASTICSEARCH_BATCH_SIZE, DEFAULT_ELASTICSEARCH_MAX_RETRY, DEFAULT_LIMIT,
+    DEFAULT_SQL_BATCH_SIZE, DEFAULT_SQL_MAX_OPEN_CONNECTIONS, ELASTICSEARCH_BATCH_SIZE, ELASTICSEARCH_FORMAT,
+    ELASTICSEARCH_INCLUDE_METADATA, ELASTICSEARCH_INDEX, ELASTICSEARCH_MAX_RETRY, ELASTICSEARCH_PASSWORD,
+    ELASTICSEARCH_QUEUE_DIR, ELASTICSEARCH_QUEUE_LIMIT, ELASTICSEARCH_URL, ELASTICSEARCH_USERNAME, ENABLE_KEY, EnableState,
+    MQTT_BROKER, MQTT_KEEP_ALIVE_INTERVAL, MQTT_PASSWORD, MQTT_QOS, MQTT_QUEUE_DIR, MQTT_QUEUE_LIMIT, MQTT_RECONNECT_INTERVAL,
+    MQTT_TOPIC, MQTT_USERNAME, MYSQL_BATCH_SIZE, MYSQL_DSN_STRING, MYSQL_FORMAT, MYSQL_MAX_OPEN_CONNECTIONS, MYSQL_QUEUE_DIR,
+    MYSQL_QUEUE_LIMIT, MYSQL_TABLE, POSTGRES_BATCH_SIZE, POSTGRES_CONNECTION_STRING, POSTGRES_FORMAT,
+    POSTGRES_MAX_OPEN_CONNECTIONS, POSTGRES_QUEUE_DIR, POSTGRES_QUEUE_LIMIT, POSTGRES_TABLE, TARGET_FORMAT_NAMESPACE,
+    WEBHOOK_AUTH_TOKEN, WEBHOOK_CLIENT_CERT, WEBHOOK_CLIENT_KEY, WEBHOOK_ENDPOINT, WEBHOOK_QUEUE_DIR, WEBHOOK_QUEUE_LIMIT,
 };
 use std::sync::LazyLock;
 
@@ -129,3 +135,174 @@ pub static DEFAULT_NOTIFY_MQTT_KVS: LazyLock<KVS> = LazyLock::new(|| {
         },
     ])
 });
+
+/// PostgreSQL's default configuration collection
+pub static DEFAULT_NOTIFY_POSTGRES_KVS: LazyLock<KVS> = LazyLock::new(|| {
+    KVS(vec![
+        KV {
+            key: ENABLE_KEY.to_owned(),
+            value: EnableState::Off.to_string(),
+            hidden_if_empty: false,
+        },
+        // Connection strings carry credentials and are hidden when the value is empty
+        KV {
+            key: POSTGRES_CONNECTION_STRING.to_owned(),
+            value: "".to_owned(),
+            hidden_if_empty: true,
+        },
+        KV {
+            key: POSTGRES_TABLE.to_owned(),
+            value: "".to_owned(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: POSTGRES_FORMAT.to_owned(),
+            value: TARGET_FORMAT_NAMESPACE.to_owned(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: POSTGRES_MAX_OPEN_CONNECTIONS.to_owned(),
+            value: DEFAULT_SQL_MAX_OPEN_CONNECTIONS.to_string(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: POSTGRES_BATCH_SIZE.to_owned(),
+            value: DEFAULT_SQL_BATCH_SIZE.to_string(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: POSTGRES_QUEUE_DIR.to_owned(),
+            value: DEFAULT_DIR.to_owned(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: POSTGRES_QUEUE_LIMIT.to_owned(),
+            value: DEFAULT_LIMIT.to_string(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: COMMENT_KEY.to_owned(),
+            value: "".to_owned(),
+            hidden_if_empty: false,
+        },
+    ])
+});
+
+/// MySQL's default configuration collection
+pub static DEFAULT_NOTIFY_MYSQL_KVS: LazyLock<KVS> = LazyLock::new(|| {
+    KVS(vec![
+        KV {
+            key: ENABLE_KEY.to_owned(),
+            value: EnableState::Off.to_string(),
+            hidden_if_empty: false,
+        },
+        // Connection strings carry credentials and are hidden when the value is empty
+        KV {
+            key: MYSQL_DSN_STRING.to_owned(),
+            value: "".to_owned(),
+            hidden_if_empty: true,
+        },
+        KV {
+            key: MYSQL_TABLE.to_owned(),
+            value: "".to_owned(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: MYSQL_FORMAT.to_owned(),
+            value: TARGET_FORMAT_NAMESPACE.to_owned(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: MYSQL_MAX_OPEN_CONNECTIONS.to_owned(),
+            value: DEFAULT_SQL_MAX_OPEN_CONNECTIONS.to_string(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: MYSQL_BATCH_SIZE.to_owned(),
+            value: DEFAULT_SQL_BATCH_SIZE.to_string(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: MYSQL_QUEUE_DIR.to_owned(),
+            value: DEFAULT_DIR.to_owned(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: MYSQL_QUEUE_LIMIT.to_owned(),
+            value: DEFAULT_LIMIT.to_string(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: COMMENT_KEY.to_owned(),
+            value: "".to_owned(),
+            hidden_if_empty: false,
+        },
+    ])
+});
+
+/// Elasticsearch's default configuration collection
+pub static DEFAULT_NOTIFY_ELASTICSEARCH_KVS: LazyLock<KVS> = LazyLock::new(|| {
+    KVS(vec![
+        KV {
+            key: ENABLE_KEY.to_owned(),
+            value: EnableState::Off.to_string(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: ELASTICSEARCH_URL.to_owned(),
+            value: "".to_owned(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: ELASTICSEARCH_INDEX.to_owned(),
+            value: "".to_owned(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: ELASTICSEARCH_FORMAT.to_owned(),
+            value: TARGET_FORMAT_NAMESPACE.to_owned(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: ELASTICSEARCH_USERNAME.to_owned(),
+            value: "".to_owned(),
+            hidden_if_empty: false,
+        },
+        // Sensitive information such as passwords are hidden when the value is empty
+        KV {
+            key: ELASTICSEARCH_PASSWORD.to_owned(),
+            value: "".to_owned(),
+            hidden_if_empty: true,
+        },
+        KV {
+            key: ELASTICSEARCH_INCLUDE_METADATA.to_owned(),
+            value: EnableState::Off.to_string(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: ELASTICSEARCH_BATCH_SIZE.to_owned(),
+            value: DEFAULT_ELASTICSEARCH_BATCH_SIZE.to_string(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: ELASTICSEARCH_MAX_RETRY.to_owned(),
+            value: DEFAULT_ELASTICSEARCH_MAX_RETRY.to_string(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: ELASTICSEARCH_QUEUE_DIR.to_owned(),
+            value: DEFAULT_DIR.to_owned(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: ELASTICSEARCH_QUEUE_LIMIT.to_owned(),
+            value: DEFAULT_LIMIT.to_string(),
+            hidden_if_empty: false,
+        },
+        KV {
+            key: COMMENT_KEY.to_owned(),
+            value: "".to_owned(),
+            hidden_if_empty: false,
+        },
+    ])
+});
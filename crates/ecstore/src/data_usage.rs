@@ -14,8 +14,8 @@
 
 use std::{
     collections::{HashMap, hash_map::Entry},
-    sync::Arc,
-    time::SystemTime,
+    sync::{Arc, LazyLock},
+    time::{Duration, SystemTime},
 };
 
 pub mod local_snapshot;
@@ -26,12 +26,22 @@ pub use local_snapshot::{
 };
 
 use crate::{
-    bucket::metadata_sys::get_replication_config, config::com::read_config, disk::DiskAPI, store::ECStore, store_api::StorageAPI,
+    bucket::{
+        metadata_sys::{get_replication_config, get_tagging_config},
+        tagging::tags_to_map,
+    },
+    config::com::read_config,
+    disk::DiskAPI,
+    store::ECStore,
+    store_api::StorageAPI,
 };
+use parking_lot::RwLock;
 use rustfs_common::data_usage::{
     BucketTargetUsageInfo, BucketUsageInfo, DataUsageCache, DataUsageEntry, DataUsageInfo, DiskUsageStatus, SizeSummary,
 };
 use rustfs_utils::path::SLASH_SEPARATOR;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 use tracing::{error, info, warn};
 
 use crate::error::Error;
@@ -74,6 +84,18 @@ pub async fn store_data_usage_in_backend(data_usage_info: DataUsageInfo, store:
     Ok(())
 }
 
+/// Fill in the cost-allocation tags of every bucket in `info` from its bucket tagging configuration
+///
+/// Tags are not persisted with the usage snapshot, so exports always reflect the current tagging.
+pub async fn attach_bucket_tags(info: &mut DataUsageInfo) {
+    for (bucket, usage) in info.buckets_usage.iter_mut() {
+        usage.tags = match get_tagging_config(bucket).await {
+            Ok((tagging, _)) => tags_to_map(&tagging),
+            Err(_) => HashMap::new(),
+        };
+    }
+}
+
 /// Load data usage info from backend storage
 pub async fn load_data_usage_from_backend(store: Arc<ECStore>) -> Result<DataUsageInfo, Error> {
     let buf: Vec<u8> = match read_config(store.clone(), &DATA_USAGE_OBJ_NAME_PATH).await {
@@ -260,6 +282,11 @@ pub async fn aggregate_local_snapshots(store: Arc<ECStore>) -> Result<(Vec<DiskU
 
 /// Calculate accurate bucket usage statistics by enumerating objects through the object layer.
 pub async fn compute_bucket_usage(store: Arc<ECStore>, bucket_name: &str) -> Result<BucketUsageInfo, Error> {
+    compute_prefix_usage(store, bucket_name, "").await
+}
+
+/// Calculate usage statistics of the objects of a bucket whose key starts with `prefix`.
+pub async fn compute_prefix_usage(store: Arc<ECStore>, bucket_name: &str, prefix: &str) -> Result<BucketUsageInfo, Error> {
     let mut continuation: Option<String> = None;
     let mut objects_count: u64 = 0;
     let mut versions_count: u64 = 0;
@@ -271,13 +298,14 @@ pub async fn compute_bucket_usage(store: Arc<ECStore>, bucket_name: &str) -> Res
             .clone()
             .list_objects_v2(
                 bucket_name,
-                "", // prefix
+                prefix,
                 continuation.clone(),
                 None,  // delimiter
                 1000,  // max_keys
                 false, // fetch_owner
                 None,  // start_after
                 false, // incl_deleted
+                None,  // consistency
             )
             .await?;
 
@@ -332,6 +360,97 @@ pub async fn compute_bucket_usage(store: Arc<ECStore>, bucket_name: &str) -> Res
     Ok(usage)
 }
 
+/// How long a usage computed by listing is served before it is computed again.
+pub const LISTED_USAGE_TTL: Duration = Duration::from_secs(300);
+
+/// Where a usage report comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageSource {
+    /// The data usage the scanner last stored.
+    Scanner,
+    /// A listing of the objects, for prefixes, buckets the scanner has not seen yet and refreshes.
+    Listing,
+}
+
+/// Usage of a bucket or of the objects of a bucket under a prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub bucket: String,
+    pub prefix: String,
+    pub size: u64,
+    pub objects_count: u64,
+    pub versions_count: u64,
+    pub delete_markers_count: u64,
+    /// When the numbers were collected.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_update: Option<OffsetDateTime>,
+    pub source: UsageSource,
+}
+
+impl UsageReport {
+    fn new(
+        bucket: &str,
+        prefix: &str,
+        usage: &BucketUsageInfo,
+        last_update: Option<OffsetDateTime>,
+        source: UsageSource,
+    ) -> Self {
+        Self {
+            bucket: bucket.to_string(),
+            prefix: prefix.to_string(),
+            size: usage.size,
+            objects_count: usage.objects_count,
+            versions_count: usage.versions_count,
+            delete_markers_count: usage.delete_markers_count,
+            last_update,
+            source,
+        }
+    }
+
+    fn is_fresh(&self, now: OffsetDateTime) -> bool {
+        self.last_update.is_some_and(|t| now - t < LISTED_USAGE_TTL)
+    }
+}
+
+/// Usage computed by listing, keyed by bucket and prefix.
+static LISTED_USAGE: LazyLock<RwLock<HashMap<(String, String), UsageReport>>> = LazyLock::new(Default::default);
+
+/// Usage of `bucket`, or of its objects under `prefix` when not empty.
+///
+/// A bucket is reported from the data usage the scanner stored, prefixes are computed by listing
+/// and served from memory for a while. `refresh` computes the usage by listing right away.
+pub async fn query_usage(store: Arc<ECStore>, bucket: &str, prefix: &str, refresh: bool) -> Result<UsageReport, Error> {
+    let key = (bucket.to_string(), prefix.to_string());
+    if !refresh {
+        if prefix.is_empty() {
+            match load_data_usage_from_backend(store.clone()).await {
+                Ok(info) => {
+                    if let Some(usage) = info.buckets_usage.get(bucket) {
+                        let last_update = info.last_update.map(OffsetDateTime::from);
+                        return Ok(UsageReport::new(bucket, prefix, usage, last_update, UsageSource::Scanner));
+                    }
+                }
+                Err(err) => warn!("load data usage failed, listing {} instead: {}", bucket, err),
+            }
+        }
+
+        if let Some(report) = LISTED_USAGE.read().get(&key)
+            && report.is_fresh(OffsetDateTime::now_utc())
+        {
+            return Ok(report.clone());
+        }
+    }
+
+    let usage = compute_prefix_usage(store, bucket, prefix).await?;
+    let report = UsageReport::new(bucket, prefix, &usage, Some(OffsetDateTime::now_utc()), UsageSource::Listing);
+    let mut listed = LISTED_USAGE.write();
+    listed.retain(|_, r| r.is_fresh(OffsetDateTime::now_utc()));
+    listed.insert(key, report.clone());
+    Ok(report)
+}
+
 /// Build basic data usage info with real object counts
 async fn build_basic_data_usage_info(store: Arc<ECStore>) -> Result<DataUsageInfo, Error> {
     let mut data_usage_info = DataUsageInfo::default();
@@ -549,3 +668,32 @@ pub async fn save_data_usage_cache(cache: &DataUsageCache, name: &str) -> crate:
     save_config(store, &name, buf).await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_report() {
+        let usage = BucketUsageInfo {
+            size: 1024,
+            objects_count: 3,
+            versions_count: 4,
+            delete_markers_count: 1,
+            ..Default::default()
+        };
+        let now = OffsetDateTime::now_utc();
+        let report = UsageReport::new("bucket", "logs/", &usage, Some(now), UsageSource::Listing);
+        assert_eq!(report.size, 1024);
+        assert_eq!(report.versions_count, 4);
+        assert!(report.is_fresh(now));
+        assert!(!report.is_fresh(now + LISTED_USAGE_TTL));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["source"], "listing");
+        assert_eq!(json["objectsCount"], 3);
+
+        let unknown = UsageReport::new("bucket", "", &usage, None, UsageSource::Scanner);
+        assert!(!unknown.is_fresh(now));
+    }
+}
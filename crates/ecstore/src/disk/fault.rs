@@ -0,0 +1,424 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deterministic fault injection for the disk layer.
+//!
+//! Rules select drives, operations and paths, and inject latency, IO errors, partial writes or
+//! torn `xl.meta` reads into matching calls. There is no randomness: a rule fires on every match
+//! after skipping the first `skip` ones, at most `times` times, so a test sees the same failures on
+//! every run. Rules come from the environment and the admin API in debug builds only, tests add
+//! them directly. With no rules installed a disk call pays a single atomic load.
+
+use super::error::{DiskError, Result};
+use super::{FileWriter, STORAGE_FORMAT_FILE};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::AsyncWrite;
+use tracing::{debug, warn};
+
+/// Fault rules applied to the drives of this process.
+pub static GLOBAL_DISK_FAULTS: LazyLock<DiskFaults> = LazyLock::new(DiskFaults::default);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiskOp {
+    ReadAll,
+    WriteAll,
+    ReadVersion,
+    ReadXl,
+    WriteMetadata,
+    RenameData,
+    ReadFile,
+    CreateFile,
+    AppendFile,
+    Delete,
+}
+
+impl DiskOp {
+    fn is_write(self) -> bool {
+        matches!(self, Self::WriteAll | Self::CreateFile | Self::AppendFile)
+    }
+
+    fn reads_meta(self) -> bool {
+        matches!(self, Self::ReadAll | Self::ReadXl | Self::ReadVersion)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FaultKind {
+    /// Delay the call, then let it proceed.
+    Latency { millis: u64 },
+    /// Fail the call with a faulty disk error.
+    IoError,
+    /// Write only the first `bytes` bytes, then fail. Applies to write operations.
+    PartialWrite { bytes: usize },
+    /// Return `xl.meta` cut in half, or a corrupt file error where no raw bytes are returned.
+    /// Applies to metadata reads of `xl.meta`.
+    TornMeta,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaultRule {
+    /// Drive as printed by the disk, every drive when empty.
+    #[serde(default)]
+    pub disk: String,
+    /// Operations to fault, all of them when empty.
+    #[serde(default)]
+    pub ops: Vec<DiskOp>,
+    /// Prefix of `<volume>/<path>` to fault, every path when empty.
+    #[serde(default)]
+    pub path_prefix: String,
+    pub kind: FaultKind,
+    /// Matching calls let through before the rule fires.
+    #[serde(default)]
+    pub skip: u64,
+    /// Times the rule fires before it goes quiet, 0 for no limit.
+    #[serde(default)]
+    pub times: u64,
+}
+
+impl FaultRule {
+    pub fn new(kind: FaultKind) -> Self {
+        Self {
+            disk: String::new(),
+            ops: Vec::new(),
+            path_prefix: String::new(),
+            kind,
+            skip: 0,
+            times: 0,
+        }
+    }
+
+    pub fn on_disk(mut self, disk: impl Into<String>) -> Self {
+        self.disk = disk.into();
+        self
+    }
+
+    pub fn on_ops(mut self, ops: &[DiskOp]) -> Self {
+        self.ops = ops.to_vec();
+        self
+    }
+
+    pub fn on_path(mut self, prefix: impl Into<String>) -> Self {
+        self.path_prefix = prefix.into();
+        self
+    }
+
+    pub fn skip(mut self, skip: u64) -> Self {
+        self.skip = skip;
+        self
+    }
+
+    pub fn times(mut self, times: u64) -> Self {
+        self.times = times;
+        self
+    }
+
+    fn matches(&self, disk: &str, op: DiskOp, volume: &str, path: &str) -> bool {
+        if !self.disk.is_empty() && self.disk != disk {
+            return false;
+        }
+        if !self.ops.is_empty() && !self.ops.contains(&op) {
+            return false;
+        }
+        if !self.path_prefix.is_empty() && !format!("{volume}/{path}").starts_with(&self.path_prefix) {
+            return false;
+        }
+        match self.kind {
+            FaultKind::PartialWrite { .. } => op.is_write(),
+            FaultKind::TornMeta => op.reads_meta() && (op != DiskOp::ReadAll || path.ends_with(STORAGE_FORMAT_FILE)),
+            FaultKind::Latency { .. } | FaultKind::IoError => true,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct InstalledRule {
+    id: u64,
+    rule: FaultRule,
+    seen: AtomicU64,
+    fired: AtomicU64,
+}
+
+/// A rule together with how often it fired.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaultRuleStatus {
+    pub id: u64,
+    pub rule: FaultRule,
+    pub fired: u64,
+}
+
+/// What the caller has to do to the data of a call that proceeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Injected {
+    PartialWrite(usize),
+    TornMeta,
+}
+
+#[derive(Debug, Default)]
+pub struct DiskFaults {
+    active: AtomicBool,
+    next_id: AtomicU64,
+    rules: RwLock<Vec<InstalledRule>>,
+}
+
+impl DiskFaults {
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Install `rule`, returning its id.
+    pub fn add(&self, rule: FaultRule) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut rules = self.rules.write();
+        rules.push(InstalledRule {
+            id,
+            rule,
+            seen: AtomicU64::new(0),
+            fired: AtomicU64::new(0),
+        });
+        self.active.store(true, Ordering::Release);
+        id
+    }
+
+    /// Install `rule` until the returned guard is dropped.
+    pub fn scoped(&self, rule: FaultRule) -> FaultGuard<'_> {
+        FaultGuard {
+            faults: self,
+            id: self.add(rule),
+        }
+    }
+
+    pub fn remove(&self, id: u64) -> bool {
+        let mut rules = self.rules.write();
+        let len = rules.len();
+        rules.retain(|r| r.id != id);
+        self.active.store(!rules.is_empty(), Ordering::Release);
+        rules.len() != len
+    }
+
+    pub fn clear(&self) {
+        self.rules.write().clear();
+        self.active.store(false, Ordering::Release);
+    }
+
+    pub fn list(&self) -> Vec<FaultRuleStatus> {
+        self.rules
+            .read()
+            .iter()
+            .map(|r| FaultRuleStatus {
+                id: r.id,
+                rule: r.rule.clone(),
+                fired: r.fired.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    /// Times the rule `id` fired, None when it is not installed.
+    pub fn fired(&self, id: u64) -> Option<u64> {
+        self.rules
+            .read()
+            .iter()
+            .find(|r| r.id == id)
+            .map(|r| r.fired.load(Ordering::Relaxed))
+    }
+
+    /// Install the rules of the environment. Only debug builds look at it.
+    pub fn load_env(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        let Ok(value) = std::env::var(rustfs_config::ENV_DISK_FAULTS) else {
+            return;
+        };
+        match serde_json::from_str::<Vec<FaultRule>>(&value) {
+            Ok(rules) => {
+                for rule in rules {
+                    warn!("disk fault injection enabled: {:?}", rule);
+                    self.add(rule);
+                }
+            }
+            Err(err) => warn!("invalid {}: {}", rustfs_config::ENV_DISK_FAULTS, err),
+        }
+    }
+
+    /// Count the call against the matching rules and apply the first that fires: sleep for
+    /// latency, fail for IO errors, or tell the caller how to damage its data.
+    pub async fn inject(&self, disk: &str, op: DiskOp, volume: &str, path: &str) -> Result<Option<Injected>> {
+        if !self.is_active() {
+            return Ok(None);
+        }
+
+        let kind = {
+            let rules = self.rules.read();
+            rules.iter().filter(|r| r.rule.matches(disk, op, volume, path)).find_map(|r| {
+                let seen = r.seen.fetch_add(1, Ordering::Relaxed);
+                if seen < r.rule.skip {
+                    return None;
+                }
+                if r.rule.times > 0 && r.fired.load(Ordering::Relaxed) >= r.rule.times {
+                    return None;
+                }
+                r.fired.fetch_add(1, Ordering::Relaxed);
+                Some(r.rule.kind.clone())
+            })
+        };
+
+        let Some(kind) = kind else {
+            return Ok(None);
+        };
+        debug!("inject {:?} into {:?} {}/{} on {}", kind, op, volume, path, disk);
+
+        match kind {
+            FaultKind::Latency { millis } => {
+                tokio::time::sleep(Duration::from_millis(millis)).await;
+                Ok(None)
+            }
+            FaultKind::IoError => Err(DiskError::FaultyDisk),
+            FaultKind::PartialWrite { bytes } => Ok(Some(Injected::PartialWrite(bytes))),
+            FaultKind::TornMeta => Ok(Some(Injected::TornMeta)),
+        }
+    }
+}
+
+/// Removes its rule when dropped.
+#[derive(Debug)]
+pub struct FaultGuard<'a> {
+    faults: &'a DiskFaults,
+    id: u64,
+}
+
+impl FaultGuard<'_> {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn fired(&self) -> u64 {
+        self.faults.fired(self.id).unwrap_or_default()
+    }
+}
+
+impl Drop for FaultGuard<'_> {
+    fn drop(&mut self) {
+        self.faults.remove(self.id);
+    }
+}
+
+/// Cut `xl.meta` bytes in half so they no longer decode.
+pub fn tear(buf: &[u8]) -> Vec<u8> {
+    buf[..buf.len() / 2].to_vec()
+}
+
+/// Writer that passes `remaining` bytes through, then fails every write.
+pub struct PartialWriter {
+    inner: FileWriter,
+    remaining: usize,
+}
+
+impl PartialWriter {
+    pub fn new(inner: FileWriter, bytes: usize) -> Self {
+        Self { inner, remaining: bytes }
+    }
+}
+
+impl AsyncWrite for PartialWriter {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        if this.remaining == 0 {
+            return Poll::Ready(Err(std::io::Error::other("injected partial write")));
+        }
+        let len = buf.len().min(this.remaining);
+        match Pin::new(&mut this.inner).poll_write(cx, &buf[..len]) {
+            Poll::Ready(Ok(n)) => {
+                this.remaining -= n;
+                Poll::Ready(Ok(n))
+            }
+            other => other,
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+
+    #[tokio::test]
+    async fn test_rules_are_deterministic() {
+        let faults = DiskFaults::default();
+        let id = faults.add(
+            FaultRule::new(FaultKind::IoError)
+                .on_disk("/data/d1")
+                .on_ops(&[DiskOp::ReadVersion])
+                .on_path("bucket/obj")
+                .skip(1)
+                .times(2),
+        );
+
+        let call = |disk: &'static str, op, path: &'static str| faults.inject(disk, op, "bucket", path);
+        assert!(call("/data/d1", DiskOp::ReadVersion, "obj").await.is_ok());
+        assert!(matches!(call("/data/d1", DiskOp::ReadVersion, "obj").await, Err(DiskError::FaultyDisk)));
+        assert!(call("/data/d2", DiskOp::ReadVersion, "obj").await.is_ok());
+        assert!(call("/data/d1", DiskOp::ReadXl, "obj").await.is_ok());
+        assert!(call("/data/d1", DiskOp::ReadVersion, "other").await.is_ok());
+        assert!(call("/data/d1", DiskOp::ReadVersion, "obj/part.1").await.is_err());
+        assert!(call("/data/d1", DiskOp::ReadVersion, "obj").await.is_ok());
+        assert_eq!(faults.fired(id), Some(2));
+
+        {
+            let guard = faults.scoped(FaultRule::new(FaultKind::TornMeta));
+            assert_eq!(call("/data/d3", DiskOp::ReadAll, "obj/xl.meta").await.unwrap(), Some(Injected::TornMeta));
+            assert_eq!(call("/data/d3", DiskOp::ReadAll, "obj/part.1").await.unwrap(), None);
+            assert_eq!(call("/data/d3", DiskOp::WriteAll, "obj/xl.meta").await.unwrap(), None);
+            assert_eq!(guard.fired(), 1);
+        }
+        assert_eq!(faults.list().len(), 1);
+        faults.clear();
+        assert!(call("/data/d1", DiskOp::ReadVersion, "obj").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_partial_writer() {
+        let mut writer = PartialWriter::new(Box::new(Vec::new()), 4);
+        assert!(writer.write_all(b"abcdefgh").await.is_err());
+        assert_eq!(tear(b"abcd"), b"ab");
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let rules: Vec<FaultRule> =
+            serde_json::from_str(r#"[{"disk":"/data/d1","ops":["create_file"],"kind":{"type":"partial_write","bytes":10}}]"#)
+                .unwrap();
+        assert_eq!(
+            rules[0],
+            FaultRule::new(FaultKind::PartialWrite { bytes: 10 })
+                .on_disk("/data/d1")
+                .on_ops(&[DiskOp::CreateFile])
+        );
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-drive intent log for metadata updates spanning several filesystem steps.
+//!
+//! Renaming a new version into place and deleting a version with its data directory each take
+//! several renames and writes. An intent is made durable before the first step and dropped after
+//! the last one, so a crash in between leaves a record telling the drive, when it is opened again,
+//! which operation to finish or undo. Without it the drive keeps a half-applied update and the
+//! quorum of its set has to be reconstructed from the other drives.
+
+use super::error::{Error, Result};
+use super::error_conv::to_file_error;
+use rustfs_filemeta::FileInfo;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::{AsyncWriteExt, ErrorKind};
+use tracing::warn;
+use uuid::Uuid;
+
+const INTENT_FILE_EXTENSION: &str = "json";
+
+/// A multi-step metadata update in progress on a drive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "kebab-case")]
+pub enum Intent {
+    /// A version written under `src_path` moves to `dst_path`. Renaming `xl.meta` is the commit
+    /// point, the data directory and the copy of the previous metadata are moved before it.
+    RenameData {
+        src_volume: String,
+        src_path: String,
+        dst_volume: String,
+        dst_path: String,
+        /// Data directory moved along, `None` for inline and remote data.
+        data_dir: Option<Uuid>,
+        /// Data directory of the replaced version, which receives a copy of the previous metadata.
+        old_data_dir: Option<Uuid>,
+    },
+    /// A version is removed from `xl.meta` and its data directory moved to the trash first.
+    DeleteVersion {
+        volume: String,
+        path: String,
+        data_dir: Uuid,
+        fi: FileInfo,
+        undo_write: bool,
+        old_data_dir: Option<Uuid>,
+    },
+}
+
+/// Intents of one drive, one file each so concurrent operations never share a record.
+#[derive(Debug, Clone)]
+pub struct IntentLog {
+    dir: PathBuf,
+}
+
+impl IntentLog {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn intent_path(&self, id: &Uuid) -> PathBuf {
+        self.dir.join(format!("{id}.{INTENT_FILE_EXTENSION}"))
+    }
+
+    /// Durably record `intent`, returning the id to drop it with once the operation is complete.
+    pub async fn begin(&self, intent: &Intent) -> Result<Uuid> {
+        let id = Uuid::new_v4();
+        let buf = serde_json::to_vec(intent).map_err(Error::other)?;
+
+        let mut file = fs::File::create(self.intent_path(&id)).await.map_err(to_file_error)?;
+        file.write_all(&buf).await.map_err(to_file_error)?;
+        file.sync_all().await.map_err(to_file_error)?;
+        Ok(id)
+    }
+
+    /// Drop the intent of a complete operation. A leftover record is harmless, replaying a
+    /// complete operation changes nothing, so failures are only logged.
+    pub async fn end(&self, id: Uuid) {
+        if let Err(err) = fs::remove_file(self.intent_path(&id)).await
+            && err.kind() != ErrorKind::NotFound
+        {
+            warn!("failed to remove intent {} from {:?}: {}", id, self.dir, err);
+        }
+    }
+
+    /// Intents left behind by operations that did not complete.
+    ///
+    /// A record that cannot be decoded was torn while being written, before the first step of its
+    /// operation, and is dropped.
+    pub async fn pending(&self) -> Result<Vec<(Uuid, Intent)>> {
+        let mut entries = match fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(to_file_error(err).into()),
+        };
+
+        let mut pending = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(to_file_error)? {
+            let path = entry.path();
+            let Some(id) = intent_id(&path) else {
+                continue;
+            };
+            let buf = fs::read(&path).await.map_err(to_file_error)?;
+            match serde_json::from_slice::<Intent>(&buf) {
+                Ok(intent) => pending.push((id, intent)),
+                Err(err) => {
+                    warn!("dropping torn intent {:?}: {}", path, err);
+                    self.end(id).await;
+                }
+            }
+        }
+        Ok(pending)
+    }
+}
+
+fn intent_id(path: &Path) -> Option<Uuid> {
+    if path.extension()? != INTENT_FILE_EXTENSION {
+        return None;
+    }
+    Uuid::parse_str(path.file_stem()?.to_str()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_intent_log_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = IntentLog::new(dir.path().to_path_buf());
+
+        let rename = Intent::RenameData {
+            src_volume: ".rustfs.sys/tmp".to_string(),
+            src_path: "tmp-id".to_string(),
+            dst_volume: "bucket".to_string(),
+            dst_path: "object".to_string(),
+            data_dir: Some(Uuid::new_v4()),
+            old_data_dir: None,
+        };
+        let id = log.begin(&rename).await.unwrap();
+        fs::write(dir.path().join(format!("{}.json", Uuid::new_v4())), b"{\"op\":")
+            .await
+            .unwrap();
+        fs::write(dir.path().join("unrelated"), b"x").await.unwrap();
+
+        assert_eq!(log.pending().await.unwrap(), vec![(id, rename)]);
+        // The torn record was dropped while reading
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        log.end(id).await;
+        log.end(id).await;
+        assert!(log.pending().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_missing_log_dir_has_no_intents() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = IntentLog::new(dir.path().join("missing"));
+        assert!(log.pending().await.unwrap().is_empty());
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use super::error::{Error, Result};
+use super::intent_log::{Intent, IntentLog};
 use super::os::{is_root_disk, rename_all};
 use super::{
     BUCKET_META_PREFIX, CheckPartsResp, DeleteOptions, DiskAPI, DiskInfo, DiskInfoOptions, DiskLocation, DiskMetrics,
@@ -28,9 +29,11 @@ use crate::disk::fs::{
     O_APPEND, O_CREATE, O_RDONLY, O_TRUNC, O_WRONLY, access, lstat, lstat_std, remove, remove_all_std, remove_std, rename,
 };
 use crate::disk::os::{check_path_length, is_empty_dir};
+use crate::disk::watermark::{GLOBAL_DISK_SPACE_TRACKER, get_disk_watermark};
+use crate::disk::xl_meta_cache::{FileStamp, GLOBAL_XL_META_CACHE};
 use crate::disk::{
     CHECK_PART_FILE_CORRUPT, CHECK_PART_FILE_NOT_FOUND, CHECK_PART_SUCCESS, CHECK_PART_UNKNOWN, CHECK_PART_VOLUME_NOT_FOUND,
-    FileReader, RUSTFS_META_TMP_DELETED_BUCKET, conv_part_err_to_int,
+    FileReader, RUSTFS_META_INTENTS_BUCKET, RUSTFS_META_TMP_DELETED_BUCKET, conv_part_err_to_int,
 };
 use crate::disk::{FileWriter, STORAGE_FORMAT_FILE};
 use crate::global::{GLOBAL_IsErasureSD, GLOBAL_RootDiskThreshold};
@@ -45,11 +48,13 @@ use bytes::Bytes;
 // use path_absolutize::Absolutize;  // Replaced with direct path operations for better performance
 use crate::file_cache::{get_global_file_cache, prefetch_metadata_patterns, read_metadata_cached};
 use parking_lot::RwLock as ParkingLotRwLock;
+use rustfs_config::{DEFAULT_DRIVE_WATERMARK_CHECK_INTERVAL, ENV_DRIVE_WATERMARK_CHECK_INTERVAL};
 use rustfs_filemeta::{
     Cache, FileInfo, FileInfoOpts, FileMeta, MetaCacheEntry, MetacacheWriter, ObjectPartInfo, Opts, RawFileInfo, UpdateFn,
     get_file_info, read_xl_meta_no_data,
 };
 use rustfs_utils::HashAlgorithm;
+use rustfs_utils::get_env_u64;
 use rustfs_utils::os::get_info;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -108,6 +113,7 @@ pub struct LocalDisk {
     // Performance optimization fields
     path_cache: Arc<ParkingLotRwLock<HashMap<String, PathBuf>>>,
     current_dir: Arc<OnceLock<PathBuf>>,
+    intents: IntentLog,
     // pub id: Mutex<Option<Uuid>>,
     // pub format_data: Mutex<Vec<u8>>,
     // pub format_file_info: Mutex<Option<Metadata>>,
@@ -237,6 +243,7 @@ impl LocalDisk {
             // format_last_check: Mutex::new(format_last_check),
             path_cache: Arc::new(ParkingLotRwLock::new(HashMap::with_capacity(2048))),
             current_dir: Arc::new(OnceLock::new()),
+            intents: IntentLog::new(root.join(RUSTFS_META_INTENTS_BUCKET)),
             exit_signal: None,
         };
         let (info, _root) = get_disk_info(root).await?;
@@ -257,12 +264,14 @@ impl LocalDisk {
         }
 
         disk.make_meta_volumes().await?;
+        disk.replay_intents().await;
 
         let (exit_tx, exit_rx) = tokio::sync::broadcast::channel(1);
-        disk.exit_signal = Some(exit_tx);
 
         let root = disk.root.clone();
+        tokio::spawn(Self::watch_disk_space_loop(root.clone(), ep.to_string(), exit_tx.subscribe()));
         tokio::spawn(Self::cleanup_deleted_objects_loop(root, exit_rx));
+        disk.exit_signal = Some(exit_tx);
         debug!("LocalDisk created: {:?}", disk);
         Ok(disk)
     }
@@ -284,6 +293,37 @@ impl LocalDisk {
         }
     }
 
+    /// Periodically re-evaluate the reserved space watermark, so a full drive turns read-only
+    /// and resumes writes even when nothing else queries its disk info.
+    async fn watch_disk_space_loop(root: PathBuf, endpoint: String, mut exit_rx: tokio::sync::broadcast::Receiver<()>) {
+        let watermark = get_disk_watermark();
+        if !watermark.is_enabled() {
+            return;
+        }
+
+        let mut interval = interval(Duration::from_secs(
+            get_env_u64(ENV_DRIVE_WATERMARK_CHECK_INTERVAL, DEFAULT_DRIVE_WATERMARK_CHECK_INTERVAL).max(1),
+        ));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    match get_disk_info(root.clone()).await {
+                        Ok((info, _)) => {
+                            GLOBAL_DISK_SPACE_TRACKER.observe(&endpoint, info.total, info.free, watermark);
+                        }
+                        Err(err) => {
+                            debug!("watch_disk_space_loop: get_disk_info {:?} failed: {:?}", root, err);
+                        }
+                    }
+                }
+                _ = exit_rx.recv() => {
+                    debug!("watch_disk_space_loop exit");
+                    break;
+                }
+            }
+        }
+    }
+
     async fn cleanup_deleted_objects(root: PathBuf) -> Result<()> {
         let trash = path_join(&[root, RUSTFS_META_TMP_DELETED_BUCKET.into()]);
         let mut entries = fs::read_dir(&trash).await?;
@@ -344,6 +384,99 @@ impl LocalDisk {
         let md = std::fs::metadata(&self.format_path).map_err(to_unformatted_disk_error)?;
         Ok(md)
     }
+    /// Finish or undo the multi-step metadata updates left incomplete by a crash or a failed step.
+    async fn replay_intents(&self) {
+        let pending = match self.intents.pending().await {
+            Ok(pending) => pending,
+            Err(err) => {
+                warn!("failed to read the intent log of {}: {:?}", self.endpoint, err);
+                return;
+            }
+        };
+
+        for (id, intent) in pending {
+            match self.replay_intent(&intent).await {
+                Ok(()) => {
+                    info!("replayed interrupted metadata update {:?} on {}", intent, self.endpoint);
+                    self.intents.end(id).await;
+                }
+                Err(err) => warn!("failed to replay intent {} on {}: {:?}", id, self.endpoint, err),
+            }
+        }
+    }
+
+    async fn replay_intent(&self, intent: &Intent) -> Result<()> {
+        match intent {
+            Intent::RenameData {
+                src_volume,
+                src_path,
+                dst_volume,
+                dst_path,
+                data_dir,
+                old_data_dir,
+            } => {
+                let src_volume_dir = self.get_bucket_path(src_volume)?;
+                let dst_volume_dir = self.get_bucket_path(dst_volume)?;
+                let src_dir = self.get_object_path(src_volume, src_path)?;
+                let dst_dir = self.get_object_path(dst_volume, dst_path)?;
+
+                if !src_dir.join(STORAGE_FORMAT_FILE).exists() {
+                    // xl.meta was renamed, the version is in place and only the source is left to remove
+                    if src_volume != super::RUSTFS_META_MULTIPART_BUCKET {
+                        let _ = remove_std(&src_dir);
+                    } else {
+                        self.delete_file(&src_volume_dir, &src_dir, true, false).await?;
+                    }
+                    return Ok(());
+                }
+
+                // The version never became visible, move its data back so the write can be retried
+                if let Some(data_dir) = data_dir {
+                    let src_data_path = src_dir.join(data_dir.to_string());
+                    let dst_data_path = dst_dir.join(data_dir.to_string());
+                    if dst_data_path.exists() && !src_data_path.exists() {
+                        rename_all(&dst_data_path, &src_data_path, &src_volume_dir).await?;
+                    }
+                }
+                if let Some(old_data_dir) = old_data_dir {
+                    let copy_path = dst_dir.join(old_data_dir.to_string()).join(STORAGE_FORMAT_FILE);
+                    self.delete_file(&dst_volume_dir, &copy_path, false, false).await?;
+                }
+                Ok(())
+            }
+            Intent::DeleteVersion {
+                volume,
+                path,
+                data_dir,
+                fi,
+                undo_write,
+                old_data_dir,
+            } => {
+                let file_path = self.get_object_path(volume, path)?;
+                // The data was not moved to the trash yet, nothing changed
+                if file_path.join(data_dir.to_string()).exists() {
+                    return Ok(());
+                }
+
+                let buf = match fs::read(file_path.join(STORAGE_FORMAT_FILE)).await {
+                    Ok(buf) => buf,
+                    Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+                    Err(err) => return Err(to_file_error(err).into()),
+                };
+                if !FileMeta::load(&buf)?.get_data_dirs()?.contains(&Some(*data_dir)) {
+                    return Ok(());
+                }
+
+                let opts = DeleteOptions {
+                    undo_write: *undo_write,
+                    old_data_dir: *old_data_dir,
+                    ..Default::default()
+                };
+                self.delete_version(volume, path, fi.clone(), false, opts).await
+            }
+        }
+    }
+
     async fn make_meta_volumes(&self) -> Result<()> {
         let buckets = format!("{RUSTFS_META_BUCKET}/{BUCKET_META_PREFIX}");
         let multipart = format!("{}/{}", RUSTFS_META_BUCKET, "multipart");
@@ -356,6 +489,7 @@ impl LocalDisk {
             config.as_str(),
             tmp.as_str(),
             RUSTFS_META_TMP_DELETED_BUCKET,
+            RUSTFS_META_INTENTS_BUCKET,
         ];
 
         self.make_volumes(defaults).await
@@ -718,16 +852,29 @@ impl LocalDisk {
     }
 
     async fn read_metadata(&self, file_path: impl AsRef<Path>) -> Result<Vec<u8>> {
-        // Try to use cached file content reading for better performance, with safe fallback
-        let path = file_path.as_ref().to_path_buf();
+        let path = file_path.as_ref();
+        if !GLOBAL_XL_META_CACHE.is_enabled() {
+            let (data, _) = self.read_metadata_with_dmtime(path).await?;
+            return Ok(data);
+        }
+
+        check_path_length(path.to_string_lossy().as_ref())?;
 
-        // First, try the cache
-        if let Ok(bytes) = get_global_file_cache().get_file_content(path.clone()).await {
-            return Ok(bytes.to_vec());
+        // The stamp is taken before the read, a write racing the read leaves a stamp that no longer
+        // matches and the next read goes to the disk again
+        let meta = fs::metadata(path).await.map_err(to_file_error)?;
+        if meta.is_dir() {
+            return Err(Error::FileNotFound);
+        }
+        let stamp = FileStamp::new(&meta);
+        if let Some(data) = GLOBAL_XL_META_CACHE.get(path, &stamp).await {
+            return Ok(data.to_vec());
         }
 
-        // Fallback to direct read if cache fails
-        let (data, _) = self.read_metadata_with_dmtime(file_path.as_ref()).await?;
+        let (data, _) = self.read_metadata_with_dmtime(path).await?;
+        GLOBAL_XL_META_CACHE
+            .insert(path.to_path_buf(), stamp, Bytes::copy_from_slice(&data))
+            .await;
         Ok(data)
     }
 
@@ -1125,11 +1272,18 @@ impl LocalDisk {
         let mut dir_stack: Vec<String> = Vec::with_capacity(5);
         prefix = "".to_owned();
 
+        // Entries emitted from this directory, bounded by opts.per_dir_limit
+        let mut dir_returned = 0;
+
         for entry in entries.iter() {
             if opts.limit > 0 && *objs_returned >= opts.limit {
                 return Ok(());
             }
 
+            if opts.per_dir_limit > 0 && dir_returned >= opts.per_dir_limit {
+                break;
+            }
+
             if entry.is_empty() {
                 continue;
             }
@@ -1144,6 +1298,7 @@ impl LocalDisk {
                     ..Default::default()
                 })
                 .await?;
+                dir_returned += 1;
 
                 if opts.recursive {
                     if let Err(er) = Box::pin(self.scan_dir(pop, prefix.clone(), opts, out, objs_returned)).await {
@@ -1179,6 +1334,7 @@ impl LocalDisk {
                     meta.metadata = res;
 
                     out.write_obj(&meta).await?;
+                    dir_returned += 1;
 
                     // if let Ok(meta) = FileMeta::load(&meta.metadata)
                     //     && !meta.all_hidden(true)
@@ -1206,6 +1362,11 @@ impl LocalDisk {
                 return Ok(());
             }
 
+            if opts.per_dir_limit > 0 && dir_returned >= opts.per_dir_limit {
+                return Ok(());
+            }
+            dir_returned += 1;
+
             out.write_obj(&MetaCacheEntry {
                 name: dir.clone(),
                 ..Default::default()
@@ -2026,10 +2187,30 @@ impl DiskAPI for LocalDisk {
             new_dst_buf.len()
         );
 
+        let no_inline = fi.data.is_none() && fi.size > 0;
+        let moved_data_dir = if no_inline && has_data_dir_path.is_some() {
+            fi.data_dir
+        } else {
+            None
+        };
+        // Left in place when a step fails, the update is then finished or undone when the drive is opened again
+        let intent_id = if moved_data_dir.is_some() || has_old_data_dir.is_some() {
+            let intent = Intent::RenameData {
+                src_volume: src_volume.to_string(),
+                src_path: src_path.to_string(),
+                dst_volume: dst_volume.to_string(),
+                dst_path: dst_path.to_string(),
+                data_dir: moved_data_dir,
+                old_data_dir: has_old_data_dir,
+            };
+            Some(self.intents.begin(&intent).await?)
+        } else {
+            None
+        };
+
         self.write_all(src_volume, format!("{}/{}", &src_path, STORAGE_FORMAT_FILE).as_str(), new_dst_buf.into())
             .await?;
         if let Some((src_data_path, dst_data_path)) = has_data_dir_path.as_ref() {
-            let no_inline = fi.data.is_none() && fi.size > 0;
             if no_inline {
                 if let Err(err) = rename_all(&src_data_path, &dst_data_path, &skip_parent).await {
                     let _ = self.delete_file(&dst_volume_dir, dst_data_path, false, false).await;
@@ -2079,6 +2260,10 @@ impl DiskAPI for LocalDisk {
             }
         }
 
+        if let Some(id) = intent_id {
+            self.intents.end(id).await;
+        }
+
         Ok(RenameDataResp {
             old_data_dir: has_old_data_dir,
             sign: None, // TODO:
@@ -2319,6 +2504,7 @@ impl DiskAPI for LocalDisk {
         let mut meta = FileMeta::load(&buf)?;
         let old_dir = meta.delete_version(&fi)?;
 
+        let mut intent_id = None;
         if let Some(uuid) = old_dir {
             let vid = fi.version_id.unwrap_or_default();
             let _ = meta.data.remove(vec![vid, uuid])?;
@@ -2326,6 +2512,17 @@ impl DiskAPI for LocalDisk {
             let old_path = file_path.join(Path::new(uuid.to_string().as_str()));
             check_path_length(old_path.to_string_lossy().as_ref())?;
 
+            // Without the data, the version must leave xl.meta even if the drive stops before the write below
+            let intent = Intent::DeleteVersion {
+                volume: volume.to_string(),
+                path: path.to_string(),
+                data_dir: uuid,
+                fi: fi.clone(),
+                undo_write: opts.undo_write,
+                old_data_dir: opts.old_data_dir,
+            };
+            intent_id = Some(self.intents.begin(&intent).await?);
+
             if let Err(err) = self.move_to_trash(&old_path, true, false).await {
                 if err != DiskError::FileNotFound && err != DiskError::VolumeNotFound {
                     return Err(err);
@@ -2333,24 +2530,25 @@ impl DiskAPI for LocalDisk {
             }
         }
 
-        if !meta.versions.is_empty() {
+        let result = if !meta.versions.is_empty() {
             let buf = meta.marshal_msg()?;
-            return self
-                .write_all_meta(volume, format!("{path}{SLASH_SEPARATOR}{STORAGE_FORMAT_FILE}").as_str(), &buf, true)
-                .await;
-        }
+            self.write_all_meta(volume, format!("{path}{SLASH_SEPARATOR}{STORAGE_FORMAT_FILE}").as_str(), &buf, true)
+                .await
+        } else if let Some(old_data_dir) = opts.old_data_dir.filter(|_| opts.undo_write) {
+            let src_path =
+                file_path.join(Path::new(format!("{old_data_dir}{SLASH_SEPARATOR}{STORAGE_FORMAT_FILE_BACKUP}").as_str()));
+            let dst_path = file_path.join(Path::new(format!("{path}{SLASH_SEPARATOR}{STORAGE_FORMAT_FILE}").as_str()));
+            rename_all(src_path, dst_path, file_path).await
+        } else {
+            self.delete_file(&volume_dir, &xl_path, true, false).await
+        };
 
-        // opts.undo_write && opts.old_data_dir.is_some_and(f)
-        if let Some(old_data_dir) = opts.old_data_dir {
-            if opts.undo_write {
-                let src_path =
-                    file_path.join(Path::new(format!("{old_data_dir}{SLASH_SEPARATOR}{STORAGE_FORMAT_FILE_BACKUP}").as_str()));
-                let dst_path = file_path.join(Path::new(format!("{path}{SLASH_SEPARATOR}{STORAGE_FORMAT_FILE}").as_str()));
-                return rename_all(src_path, dst_path, file_path).await;
-            }
+        if let Some(id) = intent_id
+            && result.is_ok()
+        {
+            self.intents.end(id).await;
         }
-
-        self.delete_file(&volume_dir, &xl_path, true, false).await
+        result
     }
     #[tracing::instrument(level = "debug", skip(self))]
     async fn delete_versions(&self, volume: &str, versions: Vec<FileInfoVersions>, _opts: DeleteOptions) -> Vec<Option<Error>> {
@@ -2455,6 +2653,7 @@ impl DiskAPI for LocalDisk {
         info.mount_path = self.path().to_str().unwrap().to_string();
         info.endpoint = self.endpoint.to_string();
         info.scanning = self.scanning.load(Ordering::SeqCst) == 1;
+        info.read_only = GLOBAL_DISK_SPACE_TRACKER.observe(&info.endpoint, info.total, info.free, get_disk_watermark());
 
         Ok(info)
     }
@@ -2829,4 +3028,71 @@ mod test {
         #[cfg(not(windows))]
         assert!(!is_root_path("\\"));
     }
+
+    #[tokio::test]
+    async fn test_replay_undoes_uncommitted_rename() {
+        let dir = tempfile::tempdir().unwrap();
+        let endpoint = Endpoint::try_from(dir.path().to_str().unwrap()).unwrap();
+        let disk = LocalDisk::new(&endpoint, false).await.unwrap();
+        disk.make_volume("bucket").await.unwrap();
+
+        // The data directory was moved into place but xl.meta was not renamed yet
+        let data_dir = Uuid::new_v4();
+        let src = dir.path().join(super::super::RUSTFS_META_TMP_BUCKET).join("upload");
+        let dst = dir.path().join("bucket").join("object");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join(STORAGE_FORMAT_FILE), b"meta").unwrap();
+        std::fs::create_dir_all(dst.join(data_dir.to_string())).unwrap();
+        std::fs::write(dst.join(data_dir.to_string()).join("part.1"), b"shard").unwrap();
+
+        let intent = Intent::RenameData {
+            src_volume: super::super::RUSTFS_META_TMP_BUCKET.to_string(),
+            src_path: "upload".to_string(),
+            dst_volume: "bucket".to_string(),
+            dst_path: "object".to_string(),
+            data_dir: Some(data_dir),
+            old_data_dir: None,
+        };
+        disk.intents.begin(&intent).await.unwrap();
+        disk.replay_intents().await;
+
+        assert!(src.join(data_dir.to_string()).join("part.1").exists());
+        assert!(!dst.join(data_dir.to_string()).exists());
+        assert!(disk.intents.pending().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replay_finishes_interrupted_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let endpoint = Endpoint::try_from(dir.path().to_str().unwrap()).unwrap();
+        let disk = LocalDisk::new(&endpoint, false).await.unwrap();
+        disk.make_volume("bucket").await.unwrap();
+
+        let data_dir = Uuid::new_v4();
+        let mut fi = FileInfo::new("object", 2, 1);
+        fi.version_id = Some(Uuid::new_v4());
+        fi.data_dir = Some(data_dir);
+        fi.mod_time = Some(OffsetDateTime::now_utc());
+        let mut meta = FileMeta::new();
+        meta.add_version(fi.clone()).unwrap();
+
+        // The data directory went to the trash but xl.meta still lists the version
+        let object_dir = dir.path().join("bucket").join("object");
+        std::fs::create_dir_all(&object_dir).unwrap();
+        std::fs::write(object_dir.join(STORAGE_FORMAT_FILE), meta.marshal_msg().unwrap()).unwrap();
+
+        let intent = Intent::DeleteVersion {
+            volume: "bucket".to_string(),
+            path: "object".to_string(),
+            data_dir,
+            fi,
+            undo_write: false,
+            old_data_dir: None,
+        };
+        disk.intents.begin(&intent).await.unwrap();
+        disk.replay_intents().await;
+
+        assert!(!object_dir.join(STORAGE_FORMAT_FILE).exists());
+        assert!(disk.intents.pending().await.unwrap().is_empty());
+    }
 }
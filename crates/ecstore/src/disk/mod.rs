@@ -16,15 +16,21 @@ pub mod endpoint;
 pub mod error;
 pub mod error_conv;
 pub mod error_reduce;
+pub mod fault;
 pub mod format;
 pub mod fs;
+pub mod intent_log;
 pub mod local;
 pub mod os;
+pub mod walk;
+pub mod watermark;
+pub mod xl_meta_cache;
 
 pub const RUSTFS_META_BUCKET: &str = ".rustfs.sys";
 pub const RUSTFS_META_MULTIPART_BUCKET: &str = ".rustfs.sys/multipart";
 pub const RUSTFS_META_TMP_BUCKET: &str = ".rustfs.sys/tmp";
 pub const RUSTFS_META_TMP_DELETED_BUCKET: &str = ".rustfs.sys/tmp/.trash";
+pub const RUSTFS_META_INTENTS_BUCKET: &str = ".rustfs.sys/intents";
 pub const BUCKET_META_PREFIX: &str = "buckets";
 pub const FORMAT_CONFIG_FILE: &str = "format.json";
 pub const STORAGE_FORMAT_FILE: &str = "xl.meta";
@@ -35,7 +41,9 @@ use bytes::Bytes;
 use endpoint::Endpoint;
 use error::DiskError;
 use error::{Error, Result};
+use fault::{DiskOp, GLOBAL_DISK_FAULTS, Injected, PartialWriter};
 use local::LocalDisk;
+use rustfs_common::deadline;
 use rustfs_filemeta::{FileInfo, ObjectPartInfo, RawFileInfo};
 use rustfs_madmin::info_commands::DiskMetrics;
 use serde::{Deserialize, Serialize};
@@ -55,6 +63,15 @@ pub enum Disk {
     Remote(Box<RemoteDisk>),
 }
 
+impl Disk {
+    async fn inject_fault(&self, op: DiskOp, volume: &str, path: &str) -> Result<Option<Injected>> {
+        if !GLOBAL_DISK_FAULTS.is_active() {
+            return Ok(None);
+        }
+        GLOBAL_DISK_FAULTS.inject(&DiskAPI::to_string(self), op, volume, path).await
+    }
+}
+
 #[async_trait::async_trait]
 impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
@@ -218,6 +235,7 @@ impl DiskAPI for Disk {
 
     #[tracing::instrument(skip(self))]
     async fn write_metadata(&self, _org_volume: &str, volume: &str, path: &str, fi: FileInfo) -> Result<()> {
+        self.inject_fault(DiskOp::WriteMetadata, volume, path).await?;
         match self {
             Disk::Local(local_disk) => local_disk.write_metadata(_org_volume, volume, path, fi).await,
             Disk::Remote(remote_disk) => remote_disk.write_metadata(_org_volume, volume, path, fi).await,
@@ -241,18 +259,34 @@ impl DiskAPI for Disk {
         version_id: &str,
         opts: &ReadOptions,
     ) -> Result<FileInfo> {
-        match self {
-            Disk::Local(local_disk) => local_disk.read_version(_org_volume, volume, path, version_id, opts).await,
-            Disk::Remote(remote_disk) => remote_disk.read_version(_org_volume, volume, path, version_id, opts).await,
+        if self.inject_fault(DiskOp::ReadVersion, volume, path).await? == Some(Injected::TornMeta) {
+            return Err(DiskError::FileCorrupt);
         }
+        with_deadline(async {
+            match self {
+                Disk::Local(local_disk) => local_disk.read_version(_org_volume, volume, path, version_id, opts).await,
+                Disk::Remote(remote_disk) => remote_disk.read_version(_org_volume, volume, path, version_id, opts).await,
+            }
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self))]
     async fn read_xl(&self, volume: &str, path: &str, read_data: bool) -> Result<RawFileInfo> {
-        match self {
-            Disk::Local(local_disk) => local_disk.read_xl(volume, path, read_data).await,
-            Disk::Remote(remote_disk) => remote_disk.read_xl(volume, path, read_data).await,
+        let injected = self.inject_fault(DiskOp::ReadXl, volume, path).await?;
+        let raw = with_deadline(async {
+            match self {
+                Disk::Local(local_disk) => local_disk.read_xl(volume, path, read_data).await,
+                Disk::Remote(remote_disk) => remote_disk.read_xl(volume, path, read_data).await,
+            }
+        })
+        .await?;
+        if injected == Some(Injected::TornMeta) {
+            return Ok(RawFileInfo {
+                buf: fault::tear(&raw.buf),
+            });
         }
+        Ok(raw)
     }
 
     #[tracing::instrument(skip(self, fi))]
@@ -264,6 +298,7 @@ impl DiskAPI for Disk {
         dst_volume: &str,
         dst_path: &str,
     ) -> Result<RenameDataResp> {
+        self.inject_fault(DiskOp::RenameData, src_volume, src_path).await?;
         match self {
             Disk::Local(local_disk) => local_disk.rename_data(src_volume, src_path, fi, dst_volume, dst_path).await,
             Disk::Remote(remote_disk) => remote_disk.rename_data(src_volume, src_path, fi, dst_volume, dst_path).await,
@@ -272,41 +307,62 @@ impl DiskAPI for Disk {
 
     #[tracing::instrument(skip(self))]
     async fn list_dir(&self, _origvolume: &str, volume: &str, _dir_path: &str, _count: i32) -> Result<Vec<String>> {
-        match self {
-            Disk::Local(local_disk) => local_disk.list_dir(_origvolume, volume, _dir_path, _count).await,
-            Disk::Remote(remote_disk) => remote_disk.list_dir(_origvolume, volume, _dir_path, _count).await,
-        }
+        with_deadline(async {
+            match self {
+                Disk::Local(local_disk) => local_disk.list_dir(_origvolume, volume, _dir_path, _count).await,
+                Disk::Remote(remote_disk) => remote_disk.list_dir(_origvolume, volume, _dir_path, _count).await,
+            }
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self))]
     async fn read_file(&self, volume: &str, path: &str) -> Result<FileReader> {
-        match self {
-            Disk::Local(local_disk) => local_disk.read_file(volume, path).await,
-            Disk::Remote(remote_disk) => remote_disk.read_file(volume, path).await,
-        }
+        self.inject_fault(DiskOp::ReadFile, volume, path).await?;
+        with_deadline(async {
+            match self {
+                Disk::Local(local_disk) => local_disk.read_file(volume, path).await,
+                Disk::Remote(remote_disk) => remote_disk.read_file(volume, path).await,
+            }
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self))]
     async fn read_file_stream(&self, volume: &str, path: &str, offset: usize, length: usize) -> Result<FileReader> {
-        match self {
-            Disk::Local(local_disk) => local_disk.read_file_stream(volume, path, offset, length).await,
-            Disk::Remote(remote_disk) => remote_disk.read_file_stream(volume, path, offset, length).await,
-        }
+        self.inject_fault(DiskOp::ReadFile, volume, path).await?;
+        with_deadline(async {
+            match self {
+                Disk::Local(local_disk) => local_disk.read_file_stream(volume, path, offset, length).await,
+                Disk::Remote(remote_disk) => remote_disk.read_file_stream(volume, path, offset, length).await,
+            }
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self))]
     async fn append_file(&self, volume: &str, path: &str) -> Result<FileWriter> {
-        match self {
-            Disk::Local(local_disk) => local_disk.append_file(volume, path).await,
-            Disk::Remote(remote_disk) => remote_disk.append_file(volume, path).await,
+        let injected = self.inject_fault(DiskOp::AppendFile, volume, path).await?;
+        let writer = match self {
+            Disk::Local(local_disk) => local_disk.append_file(volume, path).await?,
+            Disk::Remote(remote_disk) => remote_disk.append_file(volume, path).await?,
+        };
+        match injected {
+            Some(Injected::PartialWrite(bytes)) => Ok(Box::new(PartialWriter::new(writer, bytes))),
+            _ => Ok(writer),
         }
     }
 
     #[tracing::instrument(skip(self))]
     async fn create_file(&self, _origvolume: &str, volume: &str, path: &str, _file_size: i64) -> Result<FileWriter> {
-        match self {
-            Disk::Local(local_disk) => local_disk.create_file(_origvolume, volume, path, _file_size).await,
-            Disk::Remote(remote_disk) => remote_disk.create_file(_origvolume, volume, path, _file_size).await,
+        let injected = self.inject_fault(DiskOp::CreateFile, volume, path).await?;
+        let writer = match self {
+            Disk::Local(local_disk) => local_disk.create_file(_origvolume, volume, path, _file_size).await?,
+            Disk::Remote(remote_disk) => remote_disk.create_file(_origvolume, volume, path, _file_size).await?,
+        };
+        match injected {
+            Some(Injected::PartialWrite(bytes)) => Ok(Box::new(PartialWriter::new(writer, bytes))),
+            _ => Ok(writer),
         }
     }
 
@@ -320,10 +376,13 @@ impl DiskAPI for Disk {
 
     #[tracing::instrument(skip(self))]
     async fn read_parts(&self, bucket: &str, paths: &[String]) -> Result<Vec<ObjectPartInfo>> {
-        match self {
-            Disk::Local(local_disk) => local_disk.read_parts(bucket, paths).await,
-            Disk::Remote(remote_disk) => remote_disk.read_parts(bucket, paths).await,
-        }
+        with_deadline(async {
+            match self {
+                Disk::Local(local_disk) => local_disk.read_parts(bucket, paths).await,
+                Disk::Remote(remote_disk) => remote_disk.read_parts(bucket, paths).await,
+            }
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self))]
@@ -340,6 +399,7 @@ impl DiskAPI for Disk {
 
     #[tracing::instrument(skip(self))]
     async fn delete(&self, volume: &str, path: &str, opt: DeleteOptions) -> Result<()> {
+        self.inject_fault(DiskOp::Delete, volume, path).await?;
         match self {
             Disk::Local(local_disk) => local_disk.delete(volume, path, opt).await,
             Disk::Remote(remote_disk) => remote_disk.delete(volume, path, opt).await,
@@ -356,22 +416,36 @@ impl DiskAPI for Disk {
 
     #[tracing::instrument(skip(self))]
     async fn check_parts(&self, volume: &str, path: &str, fi: &FileInfo) -> Result<CheckPartsResp> {
-        match self {
-            Disk::Local(local_disk) => local_disk.check_parts(volume, path, fi).await,
-            Disk::Remote(remote_disk) => remote_disk.check_parts(volume, path, fi).await,
-        }
+        with_deadline(async {
+            match self {
+                Disk::Local(local_disk) => local_disk.check_parts(volume, path, fi).await,
+                Disk::Remote(remote_disk) => remote_disk.check_parts(volume, path, fi).await,
+            }
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self))]
     async fn read_multiple(&self, req: ReadMultipleReq) -> Result<Vec<ReadMultipleResp>> {
-        match self {
-            Disk::Local(local_disk) => local_disk.read_multiple(req).await,
-            Disk::Remote(remote_disk) => remote_disk.read_multiple(req).await,
-        }
+        with_deadline(async {
+            match self {
+                Disk::Local(local_disk) => local_disk.read_multiple(req).await,
+                Disk::Remote(remote_disk) => remote_disk.read_multiple(req).await,
+            }
+        })
+        .await
     }
 
     #[tracing::instrument(skip(self))]
     async fn write_all(&self, volume: &str, path: &str, data: Bytes) -> Result<()> {
+        if let Some(Injected::PartialWrite(bytes)) = self.inject_fault(DiskOp::WriteAll, volume, path).await? {
+            let data = data.slice(..bytes.min(data.len()));
+            match self {
+                Disk::Local(local_disk) => local_disk.write_all(volume, path, data).await?,
+                Disk::Remote(remote_disk) => remote_disk.write_all(volume, path, data).await?,
+            }
+            return Err(DiskError::ShortWrite);
+        }
         match self {
             Disk::Local(local_disk) => local_disk.write_all(volume, path, data).await,
             Disk::Remote(remote_disk) => remote_disk.write_all(volume, path, data).await,
@@ -380,21 +454,39 @@ impl DiskAPI for Disk {
 
     #[tracing::instrument(skip(self))]
     async fn read_all(&self, volume: &str, path: &str) -> Result<Bytes> {
-        match self {
-            Disk::Local(local_disk) => local_disk.read_all(volume, path).await,
-            Disk::Remote(remote_disk) => remote_disk.read_all(volume, path).await,
+        let injected = self.inject_fault(DiskOp::ReadAll, volume, path).await?;
+        let data = with_deadline(async {
+            match self {
+                Disk::Local(local_disk) => local_disk.read_all(volume, path).await,
+                Disk::Remote(remote_disk) => remote_disk.read_all(volume, path).await,
+            }
+        })
+        .await?;
+        if injected == Some(Injected::TornMeta) {
+            return Ok(Bytes::from(fault::tear(&data)));
         }
+        Ok(data)
     }
 
     #[tracing::instrument(skip(self))]
     async fn disk_info(&self, opts: &DiskInfoOptions) -> Result<DiskInfo> {
         match self {
             Disk::Local(local_disk) => local_disk.disk_info(opts).await,
-            Disk::Remote(remote_disk) => remote_disk.disk_info(opts).await,
+            Disk::Remote(remote_disk) => {
+                let info = remote_disk.disk_info(opts).await?;
+                // Remote drives evaluate their own watermark, mirror the reported state locally
+                watermark::GLOBAL_DISK_SPACE_TRACKER.set_read_only(&remote_disk.to_string(), info.read_only);
+                Ok(info)
+            }
         }
     }
 }
 
+/// Bound a disk call by the deadline of the request being served, see [`rustfs_common::deadline`].
+async fn with_deadline<T>(fut: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+    deadline::bounded(fut).await.unwrap_or(Err(DiskError::Timeout))
+}
+
 pub async fn new_disk(ep: &Endpoint, opt: &DiskOption) -> Result<DiskStore> {
     if ep.is_local {
         let s = LocalDisk::new(ep, opt.cleanup).await?;
@@ -538,6 +630,9 @@ pub struct DiskInfo {
     pub rotational: bool,
     pub metrics: DiskMetrics,
     pub error: String,
+    /// Free space is below the reserved watermark and new writes are refused
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -608,6 +703,10 @@ pub struct WalkDirOptions {
     // DiskID contains the disk ID of the disk.
     // Leave empty to not check disk ID.
     pub disk_id: String,
+
+    // Limit the number of entries returned from a single directory if > 0.
+    #[serde(default)]
+    pub per_dir_limit: i32,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -798,6 +897,7 @@ mod tests {
             forward_to: Some("object/path".to_string()),
             limit: 100,
             disk_id: "disk-123".to_string(),
+            per_dir_limit: 10,
         };
 
         assert_eq!(opts.bucket, "test-bucket");
@@ -808,6 +908,7 @@ mod tests {
         assert_eq!(opts.forward_to, Some("object/path".to_string()));
         assert_eq!(opts.limit, 100);
         assert_eq!(opts.disk_id, "disk-123");
+        assert_eq!(opts.per_dir_limit, 10);
     }
 
     /// Test DeleteOptions structure
@@ -0,0 +1,268 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Streaming directory walks.
+//!
+//! [`WalkDirStream`] runs `walk_dir` on a single drive and hands out its entries one at a time,
+//! in lexical order, through a fixed size pipe so memory stays bounded however large the
+//! directory tree is. [`MergedEntryStream`] combines several sorted streams into one, folding
+//! entries that share a name into a single entry.
+
+use super::error::{DiskError, Result};
+use super::{DiskAPI, DiskStore, WalkDirOptions};
+use rustfs_filemeta::{MetaCacheEntries, MetaCacheEntry, MetacacheReader, MetadataResolutionParams, is_io_eof};
+use tokio::io::{AsyncWriteExt, DuplexStream};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Size of the pipe between the drive walk and its reader.
+pub const WALK_DIR_STREAM_BUFFER: usize = 64 * 1024;
+
+/// A source of `MetaCacheEntry` values sorted by name.
+#[async_trait::async_trait]
+pub trait EntryStream: Send {
+    /// Next entry, or `None` once the stream is exhausted.
+    async fn next_entry(&mut self) -> Result<Option<MetaCacheEntry>>;
+}
+
+/// Entries of a single drive walk, streamed in lexical order.
+pub struct WalkDirStream {
+    reader: MetacacheReader<DuplexStream>,
+    job: Option<JoinHandle<Result<()>>>,
+    done: bool,
+}
+
+impl WalkDirStream {
+    pub fn new(disk: DiskStore, opts: WalkDirOptions) -> Self {
+        Self::with_buffer(disk, opts, WALK_DIR_STREAM_BUFFER)
+    }
+
+    pub fn with_buffer(disk: DiskStore, opts: WalkDirOptions, buffer: usize) -> Self {
+        let (rd, mut wr) = tokio::io::duplex(buffer);
+        let job = tokio::spawn(async move {
+            let res = disk.walk_dir(opts, &mut wr).await;
+            // Let the reader see the end of the stream
+            let _ = wr.shutdown().await;
+            res
+        });
+
+        Self {
+            reader: MetacacheReader::new(rd),
+            job: Some(job),
+            done: false,
+        }
+    }
+
+    async fn finish(&mut self) -> Result<Option<MetaCacheEntry>> {
+        self.done = true;
+        if let Some(job) = self.job.take() {
+            job.await??;
+        }
+        Ok(None)
+    }
+}
+
+#[async_trait::async_trait]
+impl EntryStream for WalkDirStream {
+    async fn next_entry(&mut self) -> Result<Option<MetaCacheEntry>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        match self.reader.peek().await {
+            Ok(Some(entry)) => Ok(Some(entry)),
+            Ok(None) => self.finish().await,
+            Err(err) if is_io_eof(&err) || err == rustfs_filemeta::Error::Unexpected => self.finish().await,
+            Err(err) => {
+                self.done = true;
+                Err(err.into())
+            }
+        }
+    }
+}
+
+impl Drop for WalkDirStream {
+    fn drop(&mut self) {
+        if let Some(job) = self.job.take() {
+            job.abort();
+        }
+    }
+}
+
+/// N-way merge of sorted entry streams into a single sorted stream.
+///
+/// Entries sharing a name are emitted once. When the copies disagree they are resolved with
+/// the given resolution parameters, or the first copy is kept if none were given. A source
+/// failing mid-walk is dropped from the merge; the error is only returned if every source fails.
+pub struct MergedEntryStream<S: EntryStream> {
+    sources: Vec<S>,
+    heads: Vec<Option<MetaCacheEntry>>,
+    errs: Vec<Option<DiskError>>,
+    resolver: Option<MetadataResolutionParams>,
+    started: bool,
+}
+
+impl<S: EntryStream> MergedEntryStream<S> {
+    pub fn new(sources: Vec<S>, resolver: Option<MetadataResolutionParams>) -> Self {
+        let n = sources.len();
+        Self {
+            sources,
+            heads: vec![None; n],
+            errs: vec![None; n],
+            resolver,
+            started: false,
+        }
+    }
+
+    async fn advance(&mut self, idx: usize) {
+        self.heads[idx] = match self.sources[idx].next_entry().await {
+            Ok(entry) => entry,
+            Err(err) => {
+                warn!("merged walk: source {} failed: {:?}", idx, &err);
+                self.errs[idx] = Some(err);
+                None
+            }
+        };
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: EntryStream> EntryStream for MergedEntryStream<S> {
+    async fn next_entry(&mut self) -> Result<Option<MetaCacheEntry>> {
+        if !self.started {
+            self.started = true;
+            for idx in 0..self.sources.len() {
+                self.advance(idx).await;
+            }
+        }
+
+        loop {
+            let Some(name) = self.heads.iter().flatten().map(|e| e.name.clone()).min() else {
+                if !self.errs.is_empty() && self.errs.iter().all(|e| e.is_some()) {
+                    return Err(self.errs[0].clone().unwrap_or(DiskError::Unexpected));
+                }
+                return Ok(None);
+            };
+
+            let mut group: Vec<Option<MetaCacheEntry>> = vec![None; self.sources.len()];
+            for idx in 0..self.sources.len() {
+                if self.heads[idx].as_ref().is_some_and(|e| e.name == name) {
+                    group[idx] = self.heads[idx].take();
+                    self.advance(idx).await;
+                }
+            }
+
+            let mut copies = group.iter().flatten();
+            let Some(first) = copies.next() else {
+                continue;
+            };
+
+            if copies.all(|e| e.metadata == first.metadata) {
+                return Ok(Some(first.clone()));
+            }
+
+            let entry = match &self.resolver {
+                Some(resolver) => MetaCacheEntries(group.clone()).resolve(resolver.clone()),
+                None => Some(first.clone()),
+            };
+
+            if let Some(entry) = entry {
+                return Ok(Some(entry));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    struct VecStream(VecDeque<Result<MetaCacheEntry>>);
+
+    impl VecStream {
+        fn new(names: &[&str]) -> Self {
+            Self(names.iter().map(|n| Ok(entry(n, n.as_bytes()))).collect())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EntryStream for VecStream {
+        async fn next_entry(&mut self) -> Result<Option<MetaCacheEntry>> {
+            self.0.pop_front().transpose()
+        }
+    }
+
+    fn entry(name: &str, metadata: &[u8]) -> MetaCacheEntry {
+        MetaCacheEntry {
+            name: name.to_owned(),
+            metadata: metadata.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    async fn collect<S: EntryStream>(mut stream: S) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        while let Some(entry) = stream.next_entry().await? {
+            names.push(entry.name);
+        }
+        Ok(names)
+    }
+
+    #[tokio::test]
+    async fn test_merged_stream_sorted_and_deduplicated() {
+        let merged = MergedEntryStream::new(
+            vec![
+                VecStream::new(&["a", "c", "e"]),
+                VecStream::new(&["b", "c", "f"]),
+                VecStream::new(&["a", "d"]),
+            ],
+            None,
+        );
+
+        assert_eq!(collect(merged).await.unwrap(), vec!["a", "b", "c", "d", "e", "f"]);
+    }
+
+    #[tokio::test]
+    async fn test_merged_stream_keeps_first_copy_without_resolver() {
+        let mut merged = MergedEntryStream::new(
+            vec![
+                VecStream(VecDeque::from(vec![Ok(entry("a", b"one"))])),
+                VecStream(VecDeque::from(vec![Ok(entry("a", b"two"))])),
+            ],
+            None,
+        );
+
+        let first = merged.next_entry().await.unwrap().unwrap();
+        assert_eq!(first.metadata, b"one");
+        assert!(merged.next_entry().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_merged_stream_survives_failing_source() {
+        let failing = VecStream(VecDeque::from(vec![Ok(entry("a", b"a")), Err(DiskError::FaultyDisk)]));
+        let merged = MergedEntryStream::new(vec![failing, VecStream::new(&["b", "c"])], None);
+
+        assert_eq!(collect(merged).await.unwrap(), vec!["a", "b", "c"]);
+
+        let merged = MergedEntryStream::new(vec![VecStream(VecDeque::from(vec![Err(DiskError::FaultyDisk)]))], None);
+        assert!(collect(merged).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_merged_stream_empty() {
+        let merged: MergedEntryStream<VecStream> = MergedEntryStream::new(Vec::new(), None);
+        assert!(collect(merged).await.unwrap().is_empty());
+    }
+}
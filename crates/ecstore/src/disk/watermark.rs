@@ -0,0 +1,257 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reserved free-space watermarks for drives.
+//!
+//! A drive whose free space drops below the reserved watermark is flagged read-only: new object
+//! writes targeting its erasure set are refused with `StorageFull`, while reads, deletes and
+//! healing keep working. The flag is cleared once free space climbs back above the resume
+//! watermark, so a drive does not flap around a single threshold.
+
+use parking_lot::RwLock;
+use rustfs_config::{
+    DEFAULT_DRIVE_RESERVED_WATERMARK, DEFAULT_DRIVE_RESUME_WATERMARK, ENV_DRIVE_RESERVED_WATERMARK, ENV_DRIVE_RESUME_WATERMARK,
+};
+use rustfs_utils::get_env_f64;
+use std::collections::HashMap;
+use std::sync::{LazyLock, OnceLock};
+use time::OffsetDateTime;
+use tracing::{info, warn};
+
+static GLOBAL_DISK_WATERMARK: OnceLock<DiskWatermark> = OnceLock::new();
+
+/// Process-wide view of which drives are currently refusing writes.
+pub static GLOBAL_DISK_SPACE_TRACKER: LazyLock<DiskSpaceTracker> = LazyLock::new(DiskSpaceTracker::default);
+
+/// Get the drive watermark configured through the environment.
+pub fn get_disk_watermark() -> &'static DiskWatermark {
+    GLOBAL_DISK_WATERMARK.get_or_init(DiskWatermark::from_env)
+}
+
+/// Free-space thresholds, expressed as the percentage of drive capacity that must stay free.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiskWatermark {
+    /// Writes are refused once free space drops below this percentage.
+    pub reserved_pct: f64,
+    /// Writes resume once free space is back at or above this percentage.
+    pub resume_pct: f64,
+}
+
+impl Default for DiskWatermark {
+    fn default() -> Self {
+        Self::new(DEFAULT_DRIVE_RESERVED_WATERMARK, DEFAULT_DRIVE_RESUME_WATERMARK)
+    }
+}
+
+impl DiskWatermark {
+    pub fn new(reserved_pct: f64, resume_pct: f64) -> Self {
+        let reserved_pct = reserved_pct.clamp(0.0, 100.0);
+        let resume_pct = resume_pct.clamp(0.0, 100.0).max(reserved_pct);
+        Self {
+            reserved_pct,
+            resume_pct,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(
+            get_env_f64(ENV_DRIVE_RESERVED_WATERMARK, DEFAULT_DRIVE_RESERVED_WATERMARK),
+            get_env_f64(ENV_DRIVE_RESUME_WATERMARK, DEFAULT_DRIVE_RESUME_WATERMARK),
+        )
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.reserved_pct > 0.0
+    }
+
+    /// Compute whether a drive should be read-only given its previous state and current usage.
+    ///
+    /// Drives reporting zero capacity keep their previous state, since the numbers are not trustworthy.
+    pub fn evaluate(&self, was_read_only: bool, total: u64, free: u64) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+
+        if total == 0 {
+            return was_read_only;
+        }
+
+        let free_pct = free.min(total) as f64 * 100.0 / total as f64;
+        if was_read_only {
+            free_pct < self.resume_pct
+        } else {
+            free_pct < self.reserved_pct
+        }
+    }
+}
+
+/// A change of a drive's write state caused by crossing a watermark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpaceTransition {
+    ReadOnly,
+    Writable,
+}
+
+#[derive(Debug, Clone)]
+struct DriveSpaceState {
+    read_only: bool,
+    since: OffsetDateTime,
+}
+
+/// Tracks the read-only state of drives, keyed by endpoint string.
+///
+/// Local drives are evaluated against the watermark directly; remote drives report the state
+/// computed by their owning node through `DiskInfo::read_only`.
+#[derive(Debug, Default)]
+pub struct DiskSpaceTracker {
+    states: RwLock<HashMap<String, DriveSpaceState>>,
+}
+
+impl DiskSpaceTracker {
+    /// Evaluate a drive's usage against `watermark` and record the resulting state.
+    ///
+    /// Returns whether the drive is read-only after the evaluation.
+    pub fn observe(&self, endpoint: &str, total: u64, free: u64, watermark: &DiskWatermark) -> bool {
+        let read_only = watermark.evaluate(self.is_read_only(endpoint), total, free);
+        if let Some(transition) = self.set_read_only(endpoint, read_only) {
+            let free_pct = if total > 0 { free as f64 * 100.0 / total as f64 } else { 0.0 };
+            match transition {
+                SpaceTransition::ReadOnly => warn!(
+                    endpoint,
+                    free_pct,
+                    reserved_pct = watermark.reserved_pct,
+                    "drive free space dropped below the reserved watermark, refusing new writes"
+                ),
+                SpaceTransition::Writable => info!(
+                    endpoint,
+                    free_pct,
+                    resume_pct = watermark.resume_pct,
+                    "drive free space reclaimed above the resume watermark, accepting writes again"
+                ),
+            }
+        }
+        read_only
+    }
+
+    /// Record a drive's read-only state, returning the transition if the state changed.
+    pub fn set_read_only(&self, endpoint: &str, read_only: bool) -> Option<SpaceTransition> {
+        let mut states = self.states.write();
+        let previous = states.get(endpoint).is_some_and(|s| s.read_only);
+        if previous == read_only {
+            return None;
+        }
+
+        states.insert(
+            endpoint.to_string(),
+            DriveSpaceState {
+                read_only,
+                since: OffsetDateTime::now_utc(),
+            },
+        );
+
+        Some(if read_only {
+            SpaceTransition::ReadOnly
+        } else {
+            SpaceTransition::Writable
+        })
+    }
+
+    pub fn is_read_only(&self, endpoint: &str) -> bool {
+        self.states.read().get(endpoint).is_some_and(|s| s.read_only)
+    }
+
+    /// List the drives currently refusing writes along with the time they turned read-only.
+    pub fn read_only_drives(&self) -> Vec<(String, OffsetDateTime)> {
+        self.states
+            .read()
+            .iter()
+            .filter(|(_, s)| s.read_only)
+            .map(|(ep, s)| (ep.clone(), s.since))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1 << 30;
+
+    #[test]
+    fn test_watermark_new_clamps_resume_to_reserved() {
+        let wm = DiskWatermark::new(10.0, 3.0);
+        assert_eq!(wm.reserved_pct, 10.0);
+        assert_eq!(wm.resume_pct, 10.0);
+
+        let wm = DiskWatermark::new(-1.0, 200.0);
+        assert_eq!(wm.reserved_pct, 0.0);
+        assert_eq!(wm.resume_pct, 100.0);
+        assert!(!wm.is_enabled());
+    }
+
+    #[test]
+    fn test_watermark_evaluate_hysteresis() {
+        let wm = DiskWatermark::new(5.0, 10.0);
+
+        assert!(!wm.evaluate(false, 100 * GIB, 20 * GIB));
+        assert!(wm.evaluate(false, 100 * GIB, 4 * GIB));
+        // Still read-only between the two thresholds
+        assert!(wm.evaluate(true, 100 * GIB, 7 * GIB));
+        assert!(!wm.evaluate(false, 100 * GIB, 7 * GIB));
+        // Resumes at the resume threshold
+        assert!(!wm.evaluate(true, 100 * GIB, 10 * GIB));
+    }
+
+    #[test]
+    fn test_watermark_evaluate_unknown_capacity_keeps_state() {
+        let wm = DiskWatermark::new(5.0, 10.0);
+        assert!(wm.evaluate(true, 0, 0));
+        assert!(!wm.evaluate(false, 0, 0));
+    }
+
+    #[test]
+    fn test_watermark_disabled_never_read_only() {
+        let wm = DiskWatermark::new(0.0, 0.0);
+        assert!(!wm.evaluate(true, 100 * GIB, 0));
+    }
+
+    #[test]
+    fn test_tracker_transitions() {
+        let tracker = DiskSpaceTracker::default();
+        let wm = DiskWatermark::new(5.0, 10.0);
+        let ep = "http://node1:9000/data1";
+
+        assert!(!tracker.observe(ep, 100 * GIB, 50 * GIB, &wm));
+        assert!(tracker.read_only_drives().is_empty());
+
+        assert!(tracker.observe(ep, 100 * GIB, GIB, &wm));
+        assert!(tracker.is_read_only(ep));
+        assert_eq!(tracker.read_only_drives().len(), 1);
+
+        assert!(tracker.observe(ep, 100 * GIB, 8 * GIB, &wm));
+        assert!(!tracker.observe(ep, 100 * GIB, 30 * GIB, &wm));
+        assert!(!tracker.is_read_only(ep));
+    }
+
+    #[test]
+    fn test_tracker_set_read_only_reports_changes_only() {
+        let tracker = DiskSpaceTracker::default();
+        let ep = "http://node2:9000/data1";
+
+        assert_eq!(tracker.set_read_only(ep, false), None);
+        assert_eq!(tracker.set_read_only(ep, true), Some(SpaceTransition::ReadOnly));
+        assert_eq!(tracker.set_read_only(ep, true), None);
+        assert_eq!(tracker.set_read_only(ep, false), Some(SpaceTransition::Writable));
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read-through cache of the raw xl.meta read by listings.
+//!
+//! Listings of directory-heavy buckets resolve the same parent prefixes again and again and read
+//! the xl.meta of their entries each time. The xl.meta read without inline data are kept by their
+//! absolute path, which names both the disk and the object, up to `RUSTFS_XL_META_CACHE_SIZE` MiB.
+//! A cached copy is only served while the file still has the size, modification time and inode it
+//! was read with, so it never hides a write, including one made behind the back of the node.
+
+use bytes::Bytes;
+use metrics::{counter, gauge};
+use moka::future::Cache;
+use rustfs_config::{DEFAULT_XL_META_CACHE_SIZE, ENV_XL_META_CACHE_SIZE};
+use rustfs_utils::get_env_u64;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::SystemTime;
+
+pub static GLOBAL_XL_META_CACHE: LazyLock<XlMetaCache> =
+    LazyLock::new(|| XlMetaCache::new(get_env_u64(ENV_XL_META_CACHE_SIZE, DEFAULT_XL_META_CACHE_SIZE) * 1024 * 1024));
+
+/// What identifies the version of a file the cached copy was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    size: u64,
+    modified: Option<SystemTime>,
+    inode: u64,
+}
+
+impl FileStamp {
+    pub fn new(meta: &Metadata) -> Self {
+        #[cfg(unix)]
+        let inode = std::os::unix::fs::MetadataExt::ino(meta);
+        #[cfg(not(unix))]
+        let inode = 0;
+
+        Self {
+            size: meta.len(),
+            modified: meta.modified().ok(),
+            inode,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedMeta {
+    stamp: FileStamp,
+    data: Bytes,
+}
+
+pub struct XlMetaCache {
+    cache: Option<Cache<PathBuf, CachedMeta>>,
+}
+
+impl XlMetaCache {
+    /// A cache holding up to `capacity` bytes, disabled when 0.
+    pub fn new(capacity: u64) -> Self {
+        let cache = (capacity > 0).then(|| {
+            Cache::builder()
+                .max_capacity(capacity)
+                .weigher(|path: &PathBuf, meta: &CachedMeta| {
+                    u32::try_from(path.as_os_str().len() + meta.data.len()).unwrap_or(u32::MAX)
+                })
+                .build()
+        });
+        Self { cache }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.cache.is_some()
+    }
+
+    /// The cached xl.meta of `path` if the file is still the one it was read from.
+    pub async fn get(&self, path: &Path, stamp: &FileStamp) -> Option<Bytes> {
+        let cache = self.cache.as_ref()?;
+        match cache.get(path).await {
+            Some(cached) if cached.stamp == *stamp => {
+                counter!("rustfs_xl_meta_cache_hits_total").increment(1);
+                Some(cached.data)
+            }
+            Some(_) => {
+                counter!("rustfs_xl_meta_cache_stale_total").increment(1);
+                cache.invalidate(path).await;
+                None
+            }
+            None => {
+                counter!("rustfs_xl_meta_cache_misses_total").increment(1);
+                None
+            }
+        }
+    }
+
+    /// Keep `data`, read from `path` while it had `stamp`.
+    pub async fn insert(&self, path: PathBuf, stamp: FileStamp, data: Bytes) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+        cache.insert(path, CachedMeta { stamp, data }).await;
+        gauge!("rustfs_xl_meta_cache_bytes").set(cache.weighted_size() as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cached_copy_follows_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("xl.meta");
+        std::fs::write(&path, b"v1").unwrap();
+
+        let cache = XlMetaCache::new(1024 * 1024);
+        let stamp = FileStamp::new(&std::fs::metadata(&path).unwrap());
+        assert!(cache.get(&path, &stamp).await.is_none());
+
+        cache.insert(path.clone(), stamp, Bytes::from_static(b"v1")).await;
+        assert_eq!(cache.get(&path, &stamp).await, Some(Bytes::from_static(b"v1")));
+
+        std::fs::write(&path, b"v2-longer").unwrap();
+        let changed = FileStamp::new(&std::fs::metadata(&path).unwrap());
+        assert!(cache.get(&path, &changed).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_cache() {
+        let cache = XlMetaCache::new(0);
+        assert!(!cache.is_enabled());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("xl.meta");
+        std::fs::write(&path, b"v1").unwrap();
+        let stamp = FileStamp::new(&std::fs::metadata(&path).unwrap());
+        cache.insert(path.clone(), stamp, Bytes::from_static(b"v1")).await;
+        assert!(cache.get(&path, &stamp).await.is_none());
+    }
+}
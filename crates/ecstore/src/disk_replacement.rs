@@ -0,0 +1,509 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Guided replacement of a drive while the cluster stays online.
+//!
+//! A drive marked for replacement is no longer used as a source by heals and listings. Once the
+//! other drives of its erasure set hold write quorum without it, it is reported safe to pull.
+//! When a blank drive shows up at the same path it is formatted into the slot of the old one, and
+//! a heal of its erasure set is started, the replacement being complete once that heal finished.
+//!
+//! Replacements are persisted in the cluster config, so every node knows which drives drain. The
+//! node serving a drive is the one moving its replacement from step to step.
+
+use crate::config::com::{read_config, save_config};
+use crate::disk::endpoint::Endpoint;
+use crate::disk::error::DiskError;
+use crate::disk::{DiskAPI, DiskInfoOptions, DiskOption, new_disk};
+use crate::error::{Error, Result, StorageError};
+use crate::global::get_global_endpoints;
+use crate::store::ECStore;
+use crate::store_api::StorageAPI;
+use parking_lot::RwLock;
+use rustfs_common::heal_channel::{HealChannelPriority, last_erasure_set_heal, send_heal_disk};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+pub const DISK_REPLACEMENT_CONFIG_PATH: &str = "config/disk-replacement.json";
+
+/// How often replacements are moved forward and the persisted state reloaded.
+pub const DISK_REPLACEMENT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Heals of the set requested at most before the replacement is given up.
+const MAX_HEAL_ATTEMPTS: u32 = 3;
+
+pub static GLOBAL_DISK_REPLACEMENT_SYS: LazyLock<DiskReplacementSys> = LazyLock::new(DiskReplacementSys::default);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplacementStep {
+    /// Marked, waiting for the rest of the set to hold write quorum without the drive.
+    Draining,
+    /// The drive may be pulled, waiting for a blank drive at its path.
+    SafeToRemove,
+    /// A blank drive was found and is being formatted.
+    Formatting,
+    /// The heal of the erasure set is running.
+    Healing,
+    Completed,
+    Failed,
+}
+
+impl ReplacementStep {
+    /// Whether the drive in the slot is not to be read from.
+    pub fn is_draining(&self) -> bool {
+        matches!(self, Self::Draining | Self::SafeToRemove | Self::Formatting)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskReplacement {
+    pub pool_idx: usize,
+    pub set_idx: usize,
+    pub disk_idx: usize,
+    pub step: ReplacementStep,
+    /// The rest of the set held write quorum without the drive when last checked.
+    #[serde(default)]
+    pub safe_to_remove: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_disk_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_disk_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub reason: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub error: String,
+    #[serde(default)]
+    pub heal_attempts: u32,
+    #[serde(default, with = "time::serde::rfc3339::option", skip_serializing_if = "Option::is_none")]
+    pub heal_requested: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub since: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated: OffsetDateTime,
+}
+
+/// What the node serving a drive under replacement found in its slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriveProbe {
+    Formatted(Uuid),
+    Unformatted,
+    Unreachable,
+}
+
+/// Work a replacement needs done before its next step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplacementAction {
+    None,
+    Format,
+    Heal,
+}
+
+impl DiskReplacement {
+    /// Move to the step matching what was observed, returning the work to do next.
+    ///
+    /// `last_heal` is when the last heal of the set finished and whether it succeeded.
+    pub fn advance(
+        &mut self,
+        probe: DriveProbe,
+        safe_to_remove: bool,
+        last_heal: Option<(OffsetDateTime, bool)>,
+        now: OffsetDateTime,
+    ) -> ReplacementAction {
+        self.safe_to_remove = safe_to_remove;
+        let old_disk_id = self.old_disk_id;
+        let (step, action) = match (self.step, probe) {
+            (step, _) if step.is_finished() => (step, ReplacementAction::None),
+            // Whatever was asked before, a blank drive in the slot is formatted first
+            (step, DriveProbe::Unformatted) if step != ReplacementStep::Healing => {
+                (ReplacementStep::Formatting, ReplacementAction::Format)
+            }
+            (
+                ReplacementStep::Draining | ReplacementStep::SafeToRemove | ReplacementStep::Formatting,
+                DriveProbe::Formatted(id),
+            ) if old_disk_id != Some(id) => {
+                self.new_disk_id = Some(id);
+                (ReplacementStep::Healing, ReplacementAction::Heal)
+            }
+            (ReplacementStep::Draining | ReplacementStep::SafeToRemove, _) if safe_to_remove => {
+                (ReplacementStep::SafeToRemove, ReplacementAction::None)
+            }
+            (ReplacementStep::Draining | ReplacementStep::SafeToRemove, _) => {
+                (ReplacementStep::Draining, ReplacementAction::None)
+            }
+            (ReplacementStep::Healing, _) => match (self.heal_requested, last_heal) {
+                (Some(requested), Some((finished, success))) if finished >= requested => {
+                    if success {
+                        (ReplacementStep::Completed, ReplacementAction::None)
+                    } else if self.heal_attempts >= MAX_HEAL_ATTEMPTS {
+                        self.error = format!("heal of the erasure set failed {} times", self.heal_attempts);
+                        (ReplacementStep::Failed, ReplacementAction::None)
+                    } else {
+                        (ReplacementStep::Healing, ReplacementAction::Heal)
+                    }
+                }
+                (None, _) => (ReplacementStep::Healing, ReplacementAction::Heal),
+                _ => (ReplacementStep::Healing, ReplacementAction::None),
+            },
+            (step, _) => (step, ReplacementAction::None),
+        };
+
+        if step != self.step {
+            info!(
+                "disk replacement of pool {} set {} disk {}: {:?} -> {:?}",
+                self.pool_idx, self.set_idx, self.disk_idx, self.step, step
+            );
+            self.step = step;
+            self.updated = now;
+        }
+        action
+    }
+}
+
+/// Replacements keyed by the endpoint of their drive, as shown by `Endpoint`'s `Display`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskReplacementState {
+    #[serde(default)]
+    pub disks: BTreeMap<String, DiskReplacement>,
+}
+
+impl DiskReplacementState {
+    pub fn unmarshal(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data).map_err(Error::other)
+    }
+
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(Error::other)
+    }
+}
+
+fn find_endpoint(disk: &str) -> Option<Endpoint> {
+    get_global_endpoints()
+        .as_ref()
+        .iter()
+        .flat_map(|pool| pool.endpoints.as_ref().iter())
+        .find(|ep| ep.to_string() == disk)
+        .cloned()
+}
+
+#[derive(Debug, Default)]
+pub struct DiskReplacementSys {
+    state: RwLock<DiskReplacementState>,
+}
+
+impl DiskReplacementSys {
+    /// Load the persisted state, treating a missing config as no replacement.
+    pub async fn load(&self, store: Arc<ECStore>) -> Result<()> {
+        let state = match read_config(store, DISK_REPLACEMENT_CONFIG_PATH).await {
+            Ok(data) => DiskReplacementState::unmarshal(&data)?,
+            Err(Error::ConfigNotFound) => DiskReplacementState::default(),
+            Err(err) => return Err(err),
+        };
+
+        *self.state.write() = state;
+        Ok(())
+    }
+
+    /// Apply `change` to the persisted state, re-read first so changes made on peers are not lost.
+    async fn update<T>(&self, store: Arc<ECStore>, change: impl FnOnce(&mut DiskReplacementState) -> Result<T>) -> Result<T> {
+        self.load(store.clone()).await?;
+
+        let mut state = self.state();
+        let res = change(&mut state)?;
+        save_config(store, DISK_REPLACEMENT_CONFIG_PATH, state.marshal()?).await?;
+
+        *self.state.write() = state;
+        Ok(res)
+    }
+
+    pub fn state(&self) -> DiskReplacementState {
+        self.state.read().clone()
+    }
+
+    /// Whether `ep` is a drive under replacement that is not to be read from.
+    pub fn is_draining(&self, ep: &Endpoint) -> bool {
+        let state = self.state.read();
+        !state.disks.is_empty() && state.disks.get(&ep.to_string()).is_some_and(|r| r.step.is_draining())
+    }
+
+    /// Mark the drive at endpoint `disk` for replacement.
+    pub async fn mark(&self, store: Arc<ECStore>, disk: &str, reason: &str) -> Result<DiskReplacement> {
+        let ep = find_endpoint(disk).ok_or_else(|| Error::other(format!("no drive at {disk}")))?;
+        let (Ok(pool_idx), Ok(set_idx), Ok(disk_idx)) =
+            (usize::try_from(ep.pool_idx), usize::try_from(ep.set_idx), usize::try_from(ep.disk_idx))
+        else {
+            return Err(Error::other(format!("drive {disk} is not part of an erasure set")));
+        };
+
+        let old_disk_id = match store.get_disks(pool_idx, set_idx).await?.get(disk_idx) {
+            Some(Some(current)) => current.get_disk_id().await.ok().flatten(),
+            _ => None,
+        };
+
+        let now = OffsetDateTime::now_utc();
+        let replacement = DiskReplacement {
+            pool_idx,
+            set_idx,
+            disk_idx,
+            step: ReplacementStep::Draining,
+            safe_to_remove: false,
+            old_disk_id,
+            new_disk_id: None,
+            reason: reason.to_string(),
+            error: String::new(),
+            heal_attempts: 0,
+            heal_requested: None,
+            since: now,
+            updated: now,
+        };
+
+        let disk = ep.to_string();
+        self.update(store, |state| {
+            if let Some(current) = state.disks.get(&disk)
+                && !current.step.is_finished()
+            {
+                return Err(Error::other(format!("drive {disk} is already being replaced")));
+            }
+            state.disks.insert(disk.clone(), replacement.clone());
+            Ok(())
+        })
+        .await?;
+
+        info!(disk, reason, "drive marked for replacement");
+        Ok(replacement)
+    }
+
+    /// Forget the replacement of the drive at `disk`, cancelling it if it is not finished.
+    pub async fn clear(&self, store: Arc<ECStore>, disk: &str) -> Result<bool> {
+        let removed = self.update(store, |state| Ok(state.disks.remove(disk).is_some())).await?;
+        if removed {
+            info!(disk, "drive replacement cleared");
+        }
+        Ok(removed)
+    }
+
+    /// Move the replacements of the drives this node serves forward.
+    async fn advance_local(&self, store: Arc<ECStore>) {
+        let state = self.state();
+        for (disk, replacement) in state.disks {
+            if replacement.step.is_finished() {
+                continue;
+            }
+            let Some(ep) = find_endpoint(&disk).filter(|ep| ep.is_local) else {
+                continue;
+            };
+
+            let mut next = replacement.clone();
+            let probe = probe_drive(&ep).await;
+            let safe = is_safe_to_remove(&store, &next).await;
+            let set_disk_id = format!("pool_{}_set_{}", next.pool_idx, next.set_idx);
+            let last_heal =
+                last_erasure_set_heal(&set_disk_id).map(|outcome| (OffsetDateTime::from(outcome.finished), outcome.success));
+
+            match next.advance(probe, safe, last_heal, OffsetDateTime::now_utc()) {
+                ReplacementAction::None => {}
+                ReplacementAction::Format => match store.pools.get(next.pool_idx) {
+                    Some(pool) => match pool.heal_format(false).await {
+                        Ok((_, None | Some(StorageError::NoHealRequired))) => next.error.clear(),
+                        Ok((_, Some(err))) | Err(err) => next.error = format!("format drive: {err}"),
+                    },
+                    None => next.error = format!("no pool {}", next.pool_idx),
+                },
+                ReplacementAction::Heal => match send_heal_disk(set_disk_id, Some(HealChannelPriority::High)).await {
+                    Ok(()) => {
+                        next.heal_attempts += 1;
+                        next.heal_requested = Some(OffsetDateTime::now_utc());
+                        next.error.clear();
+                    }
+                    Err(err) => next.error = format!("request heal: {err}"),
+                },
+            }
+
+            if next == replacement {
+                continue;
+            }
+            let res = self
+                .update(store.clone(), |state| {
+                    // Cleared or marked again meanwhile
+                    if state.disks.get(&disk).is_some_and(|r| r.since == next.since) {
+                        state.disks.insert(disk.clone(), next);
+                    }
+                    Ok(())
+                })
+                .await;
+            if let Err(err) = res {
+                warn!("save disk replacement of {} failed: {:?}", disk, err);
+            }
+        }
+    }
+}
+
+async fn probe_drive(ep: &Endpoint) -> DriveProbe {
+    let opt = DiskOption {
+        cleanup: false,
+        health_check: false,
+    };
+    let Ok(disk) = new_disk(ep, &opt).await else {
+        return DriveProbe::Unreachable;
+    };
+    match disk.get_disk_id().await {
+        Ok(Some(id)) => DriveProbe::Formatted(id),
+        Err(DiskError::UnformattedDisk) => DriveProbe::Unformatted,
+        _ => DriveProbe::Unreachable,
+    }
+}
+
+/// Whether the other drives of the set of `replacement` hold write quorum without it.
+async fn is_safe_to_remove(store: &ECStore, replacement: &DiskReplacement) -> bool {
+    let Some(set) = store
+        .pools
+        .get(replacement.pool_idx)
+        .and_then(|pool| pool.disk_set.get(replacement.set_idx))
+    else {
+        return false;
+    };
+
+    let disks = set.disks.read().await.clone();
+    let mut online = 0;
+    for (idx, disk) in disks.iter().enumerate() {
+        if idx == replacement.disk_idx {
+            continue;
+        }
+        if let Some(disk) = disk
+            && disk.disk_info(&DiskInfoOptions::default()).await.is_ok()
+        {
+            online += 1;
+        }
+    }
+    online >= set.default_write_quorum()
+}
+
+/// Load the persisted replacements and keep moving those of local drives forward.
+pub async fn init_disk_replacement_sys(store: Arc<ECStore>, cancel: CancellationToken) {
+    if let Err(err) = GLOBAL_DISK_REPLACEMENT_SYS.load(store.clone()).await {
+        warn!("load disk replacement state failed: {:?}", err);
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DISK_REPLACEMENT_INTERVAL);
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = interval.tick() => {
+                    if let Err(err) = GLOBAL_DISK_REPLACEMENT_SYS.load(store.clone()).await {
+                        warn!("refresh disk replacement state failed: {:?}", err);
+                        continue;
+                    }
+                    GLOBAL_DISK_REPLACEMENT_SYS.advance_local(store.clone()).await;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacement() -> DiskReplacement {
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        DiskReplacement {
+            pool_idx: 0,
+            set_idx: 1,
+            disk_idx: 2,
+            step: ReplacementStep::Draining,
+            safe_to_remove: false,
+            old_disk_id: Some(Uuid::from_u128(1)),
+            new_disk_id: None,
+            reason: String::new(),
+            error: String::new(),
+            heal_attempts: 0,
+            heal_requested: None,
+            since: now,
+            updated: now,
+        }
+    }
+
+    #[test]
+    fn test_advance_through_replacement() {
+        let mut r = replacement();
+        let now = r.since;
+        let old = DriveProbe::Formatted(Uuid::from_u128(1));
+
+        assert_eq!(r.advance(old, false, None, now), ReplacementAction::None);
+        assert_eq!(r.step, ReplacementStep::Draining);
+        assert_eq!(r.advance(old, true, None, now), ReplacementAction::None);
+        assert_eq!(r.step, ReplacementStep::SafeToRemove);
+
+        // Pulled, then a blank drive shows up
+        assert_eq!(r.advance(DriveProbe::Unreachable, true, None, now), ReplacementAction::None);
+        assert_eq!(r.step, ReplacementStep::SafeToRemove);
+        assert_eq!(r.advance(DriveProbe::Unformatted, true, None, now), ReplacementAction::Format);
+        assert_eq!(r.step, ReplacementStep::Formatting);
+
+        let new = DriveProbe::Formatted(Uuid::from_u128(2));
+        assert_eq!(r.advance(new, true, None, now), ReplacementAction::Heal);
+        assert_eq!(r.step, ReplacementStep::Healing);
+        assert_eq!(r.new_disk_id, Some(Uuid::from_u128(2)));
+
+        r.heal_attempts = 1;
+        r.heal_requested = Some(now);
+        // A heal that finished before this one was requested does not count
+        let earlier = now - time::Duration::minutes(1);
+        assert_eq!(r.advance(new, true, Some((earlier, true)), now), ReplacementAction::None);
+        assert_eq!(r.step, ReplacementStep::Healing);
+
+        let later = now + time::Duration::minutes(1);
+        assert_eq!(r.advance(new, true, Some((later, false)), later), ReplacementAction::Heal);
+        assert_eq!(r.advance(new, true, Some((later, true)), later), ReplacementAction::None);
+        assert_eq!(r.step, ReplacementStep::Completed);
+        assert_eq!(r.updated, later);
+    }
+
+    #[test]
+    fn test_advance_gives_up_failing_heals() {
+        let mut r = replacement();
+        let now = r.since;
+        r.step = ReplacementStep::Healing;
+        r.heal_requested = Some(now);
+        r.heal_attempts = MAX_HEAL_ATTEMPTS;
+
+        let new = DriveProbe::Formatted(Uuid::from_u128(2));
+        assert_eq!(r.advance(new, true, Some((now, false)), now), ReplacementAction::None);
+        assert_eq!(r.step, ReplacementStep::Failed);
+        assert!(!r.error.is_empty());
+        assert!(!r.step.is_draining());
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let mut state = DiskReplacementState::default();
+        state.disks.insert("/data/disk3".to_string(), replacement());
+        let decoded = DiskReplacementState::unmarshal(&state.marshal().unwrap()).unwrap();
+        assert_eq!(decoded, state);
+
+        assert_eq!(DiskReplacementState::unmarshal(b"{}").unwrap(), DiskReplacementState::default());
+    }
+}
@@ -108,11 +108,7 @@ impl DisksLayout {
 
         let is_ellipses = args.iter().any(|v| has_ellipses(&[v]));
 
-        let set_drive_count_env = env::var(ENV_RUSTFS_ERASURE_SET_DRIVE_COUNT).unwrap_or_else(|err| {
-            debug!("{} not set use default:0, {:?}", ENV_RUSTFS_ERASURE_SET_DRIVE_COUNT, err);
-            "0".to_string()
-        });
-        let set_drive_count: usize = set_drive_count_env.parse().map_err(Error::other)?;
+        let set_drive_count = set_drive_count_from_env()?;
 
         // None of the args have ellipses use the old style.
         if !is_ellipses {
@@ -146,6 +142,31 @@ impl DisksLayout {
         })
     }
 
+    /// Layout of pools whose drives are listed one by one rather than by ellipses, such as the
+    /// drives of the nodes found through DNS, one pool per `(cmd_line, drives)`.
+    pub fn from_pool_drives(pools: Vec<(String, Vec<String>)>) -> Result<Self> {
+        if pools.is_empty() {
+            return Err(Error::other("Invalid argument"));
+        }
+
+        let set_drive_count = set_drive_count_from_env()?;
+
+        let mut layout = Vec::with_capacity(pools.len());
+        for (cmd_line, drives) in pools {
+            if drives.is_empty() {
+                return Err(Error::other(format!("no drives found for {cmd_line}")));
+            }
+
+            let set_args = get_all_sets(set_drive_count, false, &drives)?;
+            layout.push(PoolDisksLayout::new(cmd_line, set_args));
+        }
+
+        Ok(DisksLayout {
+            legacy: false,
+            pools: layout,
+        })
+    }
+
     pub fn is_empty_layout(&self) -> bool {
         self.pools.is_empty()
             || self.pools[0].layout.is_empty()
@@ -177,12 +198,63 @@ impl DisksLayout {
     }
 }
 
+/// The erasure sets a pool of `nodes` nodes with `drives_per_node` drives each is split into, as
+/// the node, from 0, of every drive of every set. The sets are formed as for the command line
+/// `http://node{1...nodes}/disk{1...drives_per_node}`, and a `set_drive_count` of 0 picks the set
+/// size the way the server does.
+pub fn plan_pool_sets(nodes: usize, drives_per_node: usize, set_drive_count: usize) -> Result<Vec<Vec<usize>>> {
+    if nodes == 0 || drives_per_node == 0 {
+        return Err(Error::other("a pool needs at least one node with one drive"));
+    }
+    if nodes * drives_per_node == 1 {
+        return Ok(vec![vec![0]]);
+    }
+
+    let set_drive_count = if set_drive_count == 0 {
+        set_drive_count_from_env()?
+    } else {
+        set_drive_count
+    };
+    let range = |count: usize| {
+        if count > 1 {
+            format!("{{1...{count}}}")
+        } else {
+            "1".to_string()
+        }
+    };
+    let arg = format!("http://node{}/disk{}", range(nodes), range(drives_per_node));
+
+    get_all_sets(set_drive_count, true, &[arg])?
+        .iter()
+        .map(|set| {
+            set.iter()
+                .map(|drive| {
+                    drive
+                        .strip_prefix("http://node")
+                        .and_then(|rest| rest.split('/').next())
+                        .and_then(|node| node.parse::<usize>().ok())
+                        .map(|node| node - 1)
+                        .ok_or_else(|| Error::other(format!("unexpected drive {drive}")))
+                })
+                .collect()
+        })
+        .collect()
+}
+
 /// parses all ellipses input arguments, expands them into
 /// corresponding list of endpoints chunked evenly in accordance with a
 /// specific set size.
 ///
 /// For example: {1...64} is divided into 4 sets each of size 16.
 /// This applies to even distributed setup syntax as well.
+fn set_drive_count_from_env() -> Result<usize> {
+    let set_drive_count_env = env::var(ENV_RUSTFS_ERASURE_SET_DRIVE_COUNT).unwrap_or_else(|err| {
+        debug!("{} not set use default:0, {:?}", ENV_RUSTFS_ERASURE_SET_DRIVE_COUNT, err);
+        "0".to_string()
+    });
+    set_drive_count_env.parse().map_err(Error::other)
+}
+
 fn get_all_sets<T: AsRef<str>>(set_drive_count: usize, is_ellipses: bool, args: &[T]) -> Result<Vec<Vec<String>>> {
     let endpoint_set = if is_ellipses {
         EndpointSet::from_volumes(args, set_drive_count)?
@@ -499,6 +571,25 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_plan_pool_sets() {
+        let sets = plan_pool_sets(4, 4, 0).unwrap();
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].len(), 16);
+        for node in 0..4 {
+            assert_eq!(sets[0].iter().filter(|n| **n == node).count(), 4);
+        }
+
+        let sets = plan_pool_sets(4, 8, 8).unwrap();
+        assert_eq!(sets.len(), 4);
+        assert!(sets.iter().all(|set| set.len() == 8));
+
+        assert_eq!(plan_pool_sets(1, 1, 0).unwrap(), vec![vec![0]]);
+        assert_eq!(plan_pool_sets(6, 1, 0).unwrap(), vec![vec![0, 1, 2, 3, 4, 5]]);
+        assert!(plan_pool_sets(0, 4, 0).is_err());
+        assert!(plan_pool_sets(1, 17, 0).is_err());
+    }
+
     #[test]
     fn test_get_set_indexes() {
         #[derive(Default)]
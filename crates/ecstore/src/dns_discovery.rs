@@ -0,0 +1,393 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Discovery of the nodes of a pool through DNS.
+//!
+//! Instead of listing every node, a volume argument can name a DNS record holding them:
+//!
+//! - `dns+srv://_rustfs._tcp.rustfs.default.svc.cluster.local/data/rustfs{0...3}` takes the targets
+//!   and ports of an SRV record, such as the one of a headless Kubernetes service, which names each
+//!   pod of a StatefulSet by a stable host name.
+//! - `dns://rustfs.default.svc.cluster.local:9000/data/rustfs{0...3}` takes the addresses of an
+//!   A/AAAA record.
+//!
+//! `dns+srv+https://` and `dns+https://` reach the nodes over TLS. At startup the record is resolved
+//! until it names the expected number of nodes, so a node never forms a cluster from a partial
+//! answer. The members are sorted, which gives every node the same drive order. Afterwards the
+//! record is resolved again periodically: a node that keeps its name but moved to a new address has
+//! its cached connections dropped, while nodes leaving or joining the record are reported along with
+//! the erasure sets that lost their quorum, since the drive layout cannot change while running.
+
+use crate::disk::endpoint::Endpoint;
+use crate::endpoints::EndpointServerPools;
+use hickory_resolver::TokioResolver;
+use rustfs_common::globals::GLOBAL_Conn_Map;
+use rustfs_config::{
+    DEFAULT_DNS_DISCOVERY_INTERVAL, DEFAULT_DNS_DISCOVERY_NODES, DEFAULT_DNS_DISCOVERY_TIMEOUT, ENV_DNS_DISCOVERY_INTERVAL,
+    ENV_DNS_DISCOVERY_NODES, ENV_DNS_DISCOVERY_TIMEOUT,
+};
+use rustfs_utils::string::{find_ellipses_patterns, has_ellipses};
+use rustfs_utils::{get_env_u64, get_env_usize};
+use std::collections::{BTreeSet, HashMap};
+use std::io::{Error, Result};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+use tokio::net::lookup_host;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+// Pause between resolutions while waiting for the members at startup
+const BOOTSTRAP_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RecordKind {
+    Srv,
+    Address,
+}
+
+/// A volume argument naming the nodes of a pool by a DNS record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsVolume {
+    arg: String,
+    kind: RecordKind,
+    scheme: &'static str,
+    name: String,
+    port: Option<u16>,
+    path: String,
+}
+
+impl DnsVolume {
+    pub fn is_dns_volume(arg: &str) -> bool {
+        arg.starts_with("dns://") || arg.starts_with("dns+")
+    }
+
+    pub fn parse(arg: &str) -> Result<Self> {
+        let invalid = |msg: &str| Error::other(format!("invalid DNS volume {arg}: {msg}"));
+
+        let (scheme_part, rest) = arg.split_once("://").ok_or_else(|| invalid("missing ://"))?;
+        let (kind, scheme) = match scheme_part {
+            "dns+srv" => (RecordKind::Srv, "http"),
+            "dns+srv+https" => (RecordKind::Srv, "https"),
+            "dns" => (RecordKind::Address, "http"),
+            "dns+https" => (RecordKind::Address, "https"),
+            _ => return Err(invalid("unknown scheme")),
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => return Err(invalid("missing drive path")),
+        };
+        if path.len() < 2 {
+            return Err(invalid("missing drive path"));
+        }
+
+        let (name, port) = match authority.rsplit_once(':') {
+            Some((name, port)) => (name, Some(port.parse::<u16>().map_err(|_| invalid("bad port"))?)),
+            None => (authority, None),
+        };
+        if name.is_empty() {
+            return Err(invalid("missing DNS name"));
+        }
+        if kind == RecordKind::Address && port.is_none() {
+            return Err(invalid("a port is required for address records"));
+        }
+        if kind == RecordKind::Srv && port.is_some() {
+            return Err(invalid("SRV records carry their own ports"));
+        }
+
+        Ok(Self {
+            arg: arg.to_string(),
+            kind,
+            scheme,
+            name: name.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    /// The members the record names now, as sorted `host:port`.
+    async fn resolve(&self, resolver: &TokioResolver) -> Result<Vec<String>> {
+        let members: BTreeSet<String> = match self.kind {
+            RecordKind::Srv => resolver
+                .srv_lookup(self.name.as_str())
+                .await
+                .map_err(Error::other)?
+                .iter()
+                .map(|srv| format!("{}:{}", srv.target().to_utf8().trim_end_matches('.'), srv.port()))
+                .collect(),
+            RecordKind::Address => {
+                let port = self.port.unwrap_or_default();
+                lookup_host((self.name.as_str(), port))
+                    .await?
+                    .map(|addr| addr.to_string())
+                    .collect()
+            }
+        };
+
+        Ok(members.into_iter().collect())
+    }
+
+    /// The drives of the pool, the members varying fastest so each erasure set spans the nodes.
+    pub fn drives(&self, members: &[String]) -> Result<Vec<String>> {
+        let paths = if has_ellipses(&[&self.path]) {
+            find_ellipses_patterns(&self.path)?
+                .expand()
+                .into_iter()
+                .map(|parts| parts.join(""))
+                .collect()
+        } else {
+            vec![self.path.clone()]
+        };
+
+        Ok(paths
+            .iter()
+            .flat_map(|path| {
+                members
+                    .iter()
+                    .map(move |member| format!("{}://{}{}", self.scheme, member, path))
+            })
+            .collect())
+    }
+}
+
+/// Resolve the record of `volume` until it names `expected` members, or the same members twice in
+/// a row when `expected` is 0.
+async fn bootstrap_members(
+    volume: &DnsVolume,
+    resolver: &TokioResolver,
+    expected: usize,
+    timeout: Duration,
+) -> Result<Vec<String>> {
+    let deadline = Instant::now() + timeout;
+    let mut previous: Option<Vec<String>> = None;
+
+    loop {
+        match volume.resolve(resolver).await {
+            Ok(members) if !members.is_empty() => {
+                let settled = if expected > 0 {
+                    members.len() == expected
+                } else {
+                    previous.as_ref() == Some(&members)
+                };
+                if settled {
+                    return Ok(members);
+                }
+                info!(
+                    volume = volume.arg,
+                    found = members.len(),
+                    expected,
+                    "waiting for the cluster members to resolve"
+                );
+                previous = Some(members);
+            }
+            Ok(_) => info!(volume = volume.arg, "no cluster members resolve yet"),
+            Err(err) => warn!(volume = volume.arg, "resolve cluster members failed: {}", err),
+        }
+
+        if Instant::now() >= deadline {
+            return Err(Error::other(format!(
+                "timed out waiting for {} to resolve to the cluster members",
+                volume.arg
+            )));
+        }
+        tokio::time::sleep(BOOTSTRAP_RETRY_INTERVAL).await;
+    }
+}
+
+fn new_resolver() -> Result<TokioResolver> {
+    Ok(TokioResolver::builder_tokio().map_err(Error::other)?.build())
+}
+
+/// Resolve the DNS volumes into the drives of their pools, one `(volume, drives)` per argument.
+pub async fn resolve_volume_pools(args: &[String]) -> Result<Vec<(String, Vec<String>)>> {
+    let resolver = new_resolver()?;
+    let expected = get_env_usize(ENV_DNS_DISCOVERY_NODES, DEFAULT_DNS_DISCOVERY_NODES);
+    let timeout = Duration::from_secs(get_env_u64(ENV_DNS_DISCOVERY_TIMEOUT, DEFAULT_DNS_DISCOVERY_TIMEOUT));
+
+    let mut pools = Vec::with_capacity(args.len());
+    for arg in args {
+        let volume = DnsVolume::parse(arg)?;
+        let members = bootstrap_members(&volume, &resolver, expected, timeout).await?;
+        info!(volume = arg, ?members, "cluster members resolved");
+        pools.push((arg.clone(), volume.drives(&members)?));
+    }
+
+    Ok(pools)
+}
+
+/// The erasure sets of the pool of `cmd_line` holding more than half their drives on `members`,
+/// which cannot serve reads or writes while those members are gone.
+fn sets_without_quorum(pools: &EndpointServerPools, cmd_line: &str, members: &BTreeSet<String>) -> Vec<usize> {
+    let Some(pool) = pools.as_ref().iter().find(|pool| pool.cmd_line == cmd_line) else {
+        return Vec::new();
+    };
+
+    let mut lost: HashMap<i32, usize> = HashMap::new();
+    for ep in pool.endpoints.as_ref().iter().filter(|ep| members.contains(&ep.host_port())) {
+        *lost.entry(ep.set_idx).or_default() += 1;
+    }
+
+    let mut sets: Vec<usize> = lost
+        .into_iter()
+        .filter(|(_, count)| *count > pool.drives_per_set / 2)
+        .map(|(set_idx, _)| set_idx.max(0) as usize)
+        .collect();
+    sets.sort_unstable();
+    sets
+}
+
+/// Drop the cached RPC connections to `member`, so the next request connects to its new address.
+async fn drop_connections(member: &str) {
+    let suffix = format!("://{member}");
+    GLOBAL_Conn_Map.write().await.retain(|addr, _| !addr.ends_with(&suffix));
+}
+
+async fn member_addrs(member: &str) -> Vec<IpAddr> {
+    let mut addrs: Vec<IpAddr> = match lookup_host(member).await {
+        Ok(addrs) => addrs.map(|addr| addr.ip()).collect(),
+        Err(_) => Vec::new(),
+    };
+    addrs.sort_unstable();
+    addrs.dedup();
+    addrs
+}
+
+/// Resolve the DNS volumes again every interval until `cancel` fires, following nodes that move to
+/// new addresses and reporting nodes that leave or join.
+pub fn start_dns_discovery_watch(args: Vec<String>, pools: EndpointServerPools, cancel: CancellationToken) {
+    let volumes: Vec<DnsVolume> = args
+        .iter()
+        .filter(|arg| DnsVolume::is_dns_volume(arg))
+        .filter_map(|arg| DnsVolume::parse(arg).ok())
+        .collect();
+    if volumes.is_empty() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let resolver = match new_resolver() {
+            Ok(resolver) => resolver,
+            Err(err) => {
+                warn!("DNS discovery watch not started: {}", err);
+                return;
+            }
+        };
+
+        // The members each pool started with, and the addresses of their names
+        let mut started: Vec<BTreeSet<String>> = Vec::with_capacity(volumes.len());
+        for volume in &volumes {
+            let members = pools
+                .as_ref()
+                .iter()
+                .find(|pool| pool.cmd_line == volume.arg)
+                .map(|pool| pool.endpoints.as_ref().iter().map(Endpoint::host_port).collect())
+                .unwrap_or_default();
+            started.push(members);
+        }
+        let mut addrs: HashMap<String, Vec<IpAddr>> = HashMap::new();
+        for member in started.iter().flatten() {
+            addrs.insert(member.clone(), member_addrs(member).await);
+        }
+
+        let interval = Duration::from_secs(get_env_u64(ENV_DNS_DISCOVERY_INTERVAL, DEFAULT_DNS_DISCOVERY_INTERVAL).max(1));
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = ticker.tick() => {}
+            }
+
+            for (volume, started) in volumes.iter().zip(started.iter()) {
+                let current: BTreeSet<String> = match volume.resolve(&resolver).await {
+                    Ok(members) => members.into_iter().collect(),
+                    Err(err) => {
+                        warn!(volume = volume.arg, "resolve cluster members failed: {}", err);
+                        continue;
+                    }
+                };
+
+                let left: BTreeSet<String> = started.difference(&current).cloned().collect();
+                let joined: Vec<&String> = current.difference(started).collect();
+                if !left.is_empty() {
+                    let sets = sets_without_quorum(&pools, &volume.arg, &left);
+                    warn!(
+                        volume = volume.arg,
+                        ?left,
+                        ?sets,
+                        "cluster members no longer resolve, the listed erasure sets are without quorum until they return"
+                    );
+                }
+                if !joined.is_empty() {
+                    warn!(
+                        volume = volume.arg,
+                        ?joined,
+                        "new members resolve, they are only used after a restart of every node while the drive layout stays the same"
+                    );
+                }
+
+                // Members that kept their name but moved, such as a rescheduled pod
+                for member in started.intersection(&current) {
+                    let now = member_addrs(member).await;
+                    if now.is_empty() {
+                        continue;
+                    }
+                    if addrs.get(member).is_some_and(|before| *before != now) {
+                        info!(member, ?now, "cluster member moved to a new address, reconnecting");
+                        drop_connections(member).await;
+                    }
+                    addrs.insert(member.clone(), now);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dns_volume() {
+        let v = DnsVolume::parse("dns+srv://_rustfs._tcp.rustfs.svc/data/rustfs{0...1}").unwrap();
+        assert_eq!(
+            (v.kind, v.scheme, v.name.as_str(), v.port),
+            (RecordKind::Srv, "http", "_rustfs._tcp.rustfs.svc", None)
+        );
+
+        let v = DnsVolume::parse("dns+https://rustfs.svc:9000/data").unwrap();
+        assert_eq!((v.kind, v.scheme, v.port), (RecordKind::Address, "https", Some(9000)));
+
+        assert!(DnsVolume::parse("dns://rustfs.svc/data").is_err());
+        assert!(DnsVolume::parse("dns+srv://_rustfs._tcp.rustfs.svc:9000/data").is_err());
+        assert!(DnsVolume::parse("dns+srv://_rustfs._tcp.rustfs.svc").is_err());
+        assert!(DnsVolume::is_dns_volume("dns+srv://x/data"));
+        assert!(!DnsVolume::is_dns_volume("http://node{1...4}/data"));
+    }
+
+    #[test]
+    fn test_drives_order() {
+        let v = DnsVolume::parse("dns+srv://_rustfs._tcp.rustfs.svc/data{1...2}").unwrap();
+        let members = vec!["a:9000".to_string(), "b:9000".to_string()];
+        assert_eq!(
+            v.drives(&members).unwrap(),
+            vec![
+                "http://a:9000/data1",
+                "http://b:9000/data1",
+                "http://a:9000/data2",
+                "http://b:9000/data2",
+            ]
+        );
+    }
+}
@@ -0,0 +1,477 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! SMART health of the local drives.
+//!
+//! Every node runs smartctl against the devices behind its drives on a schedule. The counters of
+//! failing media, reallocated, pending and uncorrectable sectors and NVMe media errors, are kept
+//! for a week, and a drive whose counters went up within that week is reported with warnings in
+//! the server info. Every collection finding a counter higher than the one before, or the device
+//! failing its self-assessment, raises an alert, so the drive can be replaced before it fails in
+//! the middle of a heal.
+
+use crate::disk::endpoint::Endpoint;
+use crate::global::get_global_endpoints;
+use parking_lot::RwLock;
+use rustfs_config::{DEFAULT_DRIVE_SMART_INTERVAL, DEFAULT_DRIVE_SMARTCTL, ENV_DRIVE_SMART_INTERVAL, ENV_DRIVE_SMARTCTL};
+use rustfs_madmin::DriveSmartHealth;
+use rustfs_utils::{get_env_str, get_env_u64};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io;
+use std::sync::{LazyLock, OnceLock};
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::process::Command;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// How far back the counters of a drive are compared with to find a trend.
+const TREND_WINDOW: time::Duration = time::Duration::days(7);
+
+/// How long smartctl may take for one device.
+const SMARTCTL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The counters of failing media, by the name used in warnings.
+const COUNTERS: [(&str, fn(&DriveSmartHealth) -> Option<u64>); 4] = [
+    ("reallocated sectors", |health| health.reallocated_sectors),
+    ("pending sectors", |health| health.pending_sectors),
+    ("uncorrectable sectors", |health| health.uncorrectable_sectors),
+    ("media errors", |health| health.media_errors),
+];
+
+pub static GLOBAL_DRIVE_HEALTH_SYS: LazyLock<DriveHealthSys> = LazyLock::new(DriveHealthSys::default);
+
+/// A drive found degrading by a collection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriveHealthAlert {
+    pub endpoint: String,
+    /// What changed since the collection before
+    pub reasons: Vec<String>,
+    pub health: DriveSmartHealth,
+}
+
+static GLOBAL_DRIVE_HEALTH_ALERT_SENDER: OnceLock<broadcast::Sender<DriveHealthAlert>> = OnceLock::new();
+
+fn drive_health_alert_sender() -> &'static broadcast::Sender<DriveHealthAlert> {
+    GLOBAL_DRIVE_HEALTH_ALERT_SENDER.get_or_init(|| {
+        let (tx, _rx) = broadcast::channel(128);
+        tx
+    })
+}
+
+/// Subscribe to the alerts of the local drives.
+pub fn subscribe_drive_health_alerts() -> broadcast::Receiver<DriveHealthAlert> {
+    drive_health_alert_sender().subscribe()
+}
+
+#[derive(Debug, Default)]
+struct DriveRecord {
+    health: Option<DriveSmartHealth>,
+    /// The successful collections within the trend window, oldest first
+    history: Vec<DriveSmartHealth>,
+}
+
+#[derive(Debug, Default)]
+pub struct DriveHealthSys {
+    drives: RwLock<HashMap<String, DriveRecord>>,
+}
+
+impl DriveHealthSys {
+    /// The last collected health of a local drive.
+    pub fn get(&self, endpoint: &str) -> Option<DriveSmartHealth> {
+        self.drives.read().get(endpoint).and_then(|drive| drive.health.clone())
+    }
+
+    /// Keep a collection of a drive, returning an alert when the drive degraded since the one before.
+    fn record(
+        &self,
+        endpoint: &str,
+        device: &str,
+        sample: Result<DriveSmartHealth, String>,
+        now: OffsetDateTime,
+    ) -> Option<DriveHealthAlert> {
+        let mut drives = self.drives.write();
+        let drive = drives.entry(endpoint.to_string()).or_default();
+
+        let mut sample = match sample {
+            Ok(sample) => sample,
+            Err(err) => {
+                let mut health = drive.health.clone().unwrap_or_else(|| DriveSmartHealth {
+                    device: device.to_string(),
+                    ..Default::default()
+                });
+                health.error = err;
+                drive.health = Some(health);
+                return None;
+            }
+        };
+        sample.collected = Some(now);
+
+        // Another device now serves the drive, its counters start over
+        if drive
+            .history
+            .last()
+            .is_some_and(|last| last.device != sample.device || last.serial != sample.serial)
+        {
+            drive.history.clear();
+        }
+        let previous = drive.history.last().cloned();
+        drive
+            .history
+            .retain(|health| health.collected.is_some_and(|collected| now - collected <= TREND_WINDOW));
+
+        let mut reasons = previous
+            .as_ref()
+            .map(|previous| rising_counters(previous, &sample))
+            .unwrap_or_default();
+        let failed = sample.passed == Some(false);
+        if failed && previous.as_ref().is_none_or(|previous| previous.passed != Some(false)) {
+            reasons.insert(0, "SMART self-assessment failed".to_string());
+        }
+
+        if let Some(baseline) = drive.history.first().or(previous.as_ref()) {
+            sample.warnings = rising_counters(baseline, &sample);
+        }
+        if failed {
+            sample.warnings.insert(0, "SMART self-assessment failed".to_string());
+        }
+
+        drive.history.push(sample.clone());
+        drive.health = Some(sample.clone());
+
+        (!reasons.is_empty()).then(|| DriveHealthAlert {
+            endpoint: endpoint.to_string(),
+            reasons,
+            health: sample,
+        })
+    }
+
+    /// Collect the health of every local drive. Returns false when smartctl is not installed.
+    async fn collect(&self, smartctl: &str) -> bool {
+        let now = OffsetDateTime::now_utc();
+        // Drives sharing a device are collected once
+        let mut samples: HashMap<String, Result<DriveSmartHealth, String>> = HashMap::new();
+
+        for ep in local_endpoints() {
+            let endpoint = ep.to_string();
+            let device = match block_device(ep.get_file_path()) {
+                Ok(device) => device,
+                Err(err) => {
+                    self.record(&endpoint, "", Err(format!("find the device of the drive: {err}")), now);
+                    continue;
+                }
+            };
+
+            let sample = match samples.get(&device) {
+                Some(sample) => sample.clone(),
+                None => {
+                    let sample = match run_smartctl(smartctl, &device).await {
+                        Ok(output) => parse_smartctl(&device, &output),
+                        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                            info!("{} not found, the SMART health of the drives is not collected", smartctl);
+                            return false;
+                        }
+                        Err(err) => Err(format!("run {smartctl}: {err}")),
+                    };
+                    samples.insert(device.clone(), sample.clone());
+                    sample
+                }
+            };
+            if let Err(err) = &sample {
+                debug!(endpoint = %endpoint, device = %device, "collect SMART health failed: {}", err);
+            }
+
+            if let Some(alert) = self.record(&endpoint, &device, sample, now) {
+                warn!(
+                    endpoint = %alert.endpoint,
+                    device = %alert.health.device,
+                    serial = %alert.health.serial,
+                    "drive health degraded: {}",
+                    alert.reasons.join(", ")
+                );
+                let _ = drive_health_alert_sender().send(alert);
+            }
+        }
+        true
+    }
+}
+
+/// The counters that rose from `before` to `after`, as warnings.
+fn rising_counters(before: &DriveSmartHealth, after: &DriveSmartHealth) -> Vec<String> {
+    COUNTERS
+        .iter()
+        .filter_map(|(name, counter)| match (counter(before), counter(after)) {
+            (Some(before), Some(after)) if after > before => Some(format!("{name} rose from {before} to {after}")),
+            _ => None,
+        })
+        .collect()
+}
+
+fn local_endpoints() -> Vec<Endpoint> {
+    get_global_endpoints()
+        .as_ref()
+        .iter()
+        .flat_map(|pool| pool.endpoints.as_ref().iter())
+        .filter(|ep| ep.is_local)
+        .cloned()
+        .collect()
+}
+
+/// The whole device, e.g. `/dev/sda` for a drive on `/dev/sda1`, the drive at `path` is on.
+#[cfg(target_os = "linux")]
+fn block_device(path: &str) -> io::Result<String> {
+    let info = rustfs_utils::os::get_info(path)?;
+    let sys = std::path::PathBuf::from(format!("/sys/dev/block/{}:{}", info.major, info.minor));
+    let target = std::fs::canonicalize(&sys)?;
+    whole_device(&target, sys.join("partition").exists())
+        .map(|name| format!("/dev/{name}"))
+        .ok_or_else(|| io::Error::other(format!("no device name in {}", target.display())))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn block_device(_path: &str) -> io::Result<String> {
+    Err(io::Error::other("SMART health is only collected on Linux"))
+}
+
+/// The name of the device of a `/sys/devices/.../block/<disk>[/<partition>]` directory.
+#[cfg(target_os = "linux")]
+fn whole_device(target: &std::path::Path, partition: bool) -> Option<String> {
+    let device = if partition { target.parent()? } else { target };
+    device.file_name()?.to_str().map(str::to_string)
+}
+
+async fn run_smartctl(smartctl: &str, device: &str) -> io::Result<Vec<u8>> {
+    let output = Command::new(smartctl)
+        // Do not spin up drives in standby only to read their counters
+        .args(["--json=c", "--info", "--health", "--attributes", "--nocheck=standby", device])
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(SMARTCTL_TIMEOUT, output)
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "smartctl timed out"))??;
+    Ok(output.stdout)
+}
+
+/// Read the health of `device` from the JSON output of smartctl.
+fn parse_smartctl(device: &str, output: &[u8]) -> Result<DriveSmartHealth, String> {
+    let json: Value = serde_json::from_slice(output).map_err(|e| format!("parse smartctl output: {e}"))?;
+
+    // Bits 0 and 1 of the exit status: the command line was invalid or the device could not be opened
+    let status = json.pointer("/smartctl/exit_status").and_then(Value::as_u64).unwrap_or(0);
+    if status & 0b11 != 0 {
+        let messages: Vec<&str> = json
+            .pointer("/smartctl/messages")
+            .and_then(Value::as_array)
+            .map(|messages| {
+                messages
+                    .iter()
+                    .filter_map(|m| m.get("string").and_then(Value::as_str))
+                    .collect()
+            })
+            .unwrap_or_default();
+        return Err(if messages.is_empty() {
+            format!("smartctl exited with status {status}")
+        } else {
+            messages.join("; ")
+        });
+    }
+
+    let attribute = |id: u64| {
+        json.pointer("/ata_smart_attributes/table")
+            .and_then(Value::as_array)
+            .and_then(|table| table.iter().find(|attr| attr.get("id").and_then(Value::as_u64) == Some(id)))
+            .and_then(|attr| attr.pointer("/raw/value"))
+            .and_then(Value::as_u64)
+    };
+    let string = |name: &str| json.get(name).and_then(Value::as_str).unwrap_or_default().to_string();
+
+    Ok(DriveSmartHealth {
+        device: device.to_string(),
+        model: string("model_name"),
+        serial: string("serial_number"),
+        passed: json.pointer("/smart_status/passed").and_then(Value::as_bool),
+        temperature: json.pointer("/temperature/current").and_then(Value::as_i64),
+        power_on_hours: json.pointer("/power_on_time/hours").and_then(Value::as_u64),
+        // SCSI drives report the sectors they remapped as grown defects
+        reallocated_sectors: attribute(5).or_else(|| json.get("scsi_grown_defect_list").and_then(Value::as_u64)),
+        pending_sectors: attribute(197),
+        uncorrectable_sectors: attribute(198),
+        media_errors: json
+            .pointer("/nvme_smart_health_information_log/media_errors")
+            .and_then(Value::as_u64),
+        ..Default::default()
+    })
+}
+
+/// Collect the SMART health of the local drives every `RUSTFS_DRIVE_SMART_INTERVAL`.
+pub async fn init_drive_health(cancel: CancellationToken) {
+    let interval_secs = get_env_u64(ENV_DRIVE_SMART_INTERVAL, DEFAULT_DRIVE_SMART_INTERVAL);
+    if interval_secs == 0 {
+        return;
+    }
+    let smartctl = get_env_str(ENV_DRIVE_SMARTCTL, DEFAULT_DRIVE_SMARTCTL);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = interval.tick() => {
+                    if !GLOBAL_DRIVE_HEALTH_SYS.collect(&smartctl).await {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ATA_OUTPUT: &str = r#"{
+        "smartctl": {"exit_status": 0},
+        "model_name": "ST8000NM000A",
+        "serial_number": "ZA1B2C3D",
+        "smart_status": {"passed": true},
+        "temperature": {"current": 34},
+        "power_on_time": {"hours": 12000},
+        "ata_smart_attributes": {"table": [
+            {"id": 5, "name": "Reallocated_Sector_Ct", "raw": {"value": 8, "string": "8"}},
+            {"id": 9, "name": "Power_On_Hours", "raw": {"value": 12000, "string": "12000"}},
+            {"id": 197, "name": "Current_Pending_Sector", "raw": {"value": 2, "string": "2"}},
+            {"id": 198, "name": "Offline_Uncorrectable", "raw": {"value": 0, "string": "0"}}
+        ]}
+    }"#;
+
+    fn sample(reallocated: u64, passed: bool) -> DriveSmartHealth {
+        DriveSmartHealth {
+            device: "/dev/sda".to_string(),
+            serial: "ZA1B2C3D".to_string(),
+            passed: Some(passed),
+            reallocated_sectors: Some(reallocated),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_smartctl() {
+        let health = parse_smartctl("/dev/sda", ATA_OUTPUT.as_bytes()).unwrap();
+        assert_eq!(health.model, "ST8000NM000A");
+        assert_eq!(health.passed, Some(true));
+        assert_eq!(health.temperature, Some(34));
+        assert_eq!(health.power_on_hours, Some(12000));
+        assert_eq!(health.reallocated_sectors, Some(8));
+        assert_eq!(health.pending_sectors, Some(2));
+        assert_eq!(health.uncorrectable_sectors, Some(0));
+        assert_eq!(health.media_errors, None);
+
+        let nvme = r#"{
+            "smartctl": {"exit_status": 4},
+            "model_name": "Samsung SSD 980",
+            "smart_status": {"passed": false},
+            "nvme_smart_health_information_log": {"critical_warning": 4, "media_errors": 17}
+        }"#;
+        let health = parse_smartctl("/dev/nvme0n1", nvme.as_bytes()).unwrap();
+        assert_eq!(health.passed, Some(false));
+        assert_eq!(health.media_errors, Some(17));
+        assert_eq!(health.reallocated_sectors, None);
+
+        let standby = r#"{"smartctl": {"exit_status": 2, "messages": [{"string": "Device is in STANDBY mode, exit(2)"}]}}"#;
+        assert_eq!(
+            parse_smartctl("/dev/sdb", standby.as_bytes()),
+            Err("Device is in STANDBY mode, exit(2)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_record_trend() {
+        let sys = DriveHealthSys::default();
+        let start = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let hour = time::Duration::hours(1);
+
+        assert!(sys.record("/data1", "/dev/sda", Ok(sample(8, true)), start).is_none());
+        assert!(sys.get("/data1").unwrap().warnings.is_empty());
+
+        let alert = sys.record("/data1", "/dev/sda", Ok(sample(10, true)), start + hour).unwrap();
+        assert_eq!(alert.reasons, vec!["reallocated sectors rose from 8 to 10".to_string()]);
+
+        // No new alert while the counters hold, the warning stays for the trend window
+        assert!(
+            sys.record("/data1", "/dev/sda", Ok(sample(10, true)), start + hour * 2)
+                .is_none()
+        );
+        assert_eq!(
+            sys.get("/data1").unwrap().warnings,
+            vec!["reallocated sectors rose from 8 to 10".to_string()]
+        );
+
+        // A failed collection keeps the counters
+        assert!(
+            sys.record("/data1", "/dev/sda", Err("timed out".to_string()), start + hour * 3)
+                .is_none()
+        );
+        let health = sys.get("/data1").unwrap();
+        assert_eq!(health.error, "timed out");
+        assert_eq!(health.reallocated_sectors, Some(10));
+
+        let alert = sys
+            .record("/data1", "/dev/sda", Ok(sample(10, false)), start + hour * 4)
+            .unwrap();
+        assert_eq!(alert.reasons, vec!["SMART self-assessment failed".to_string()]);
+        assert!(
+            sys.record("/data1", "/dev/sda", Ok(sample(10, false)), start + hour * 5)
+                .is_none()
+        );
+
+        // Past the window the drive looks healthy again
+        let later = start + TREND_WINDOW + hour * 6;
+        assert!(sys.record("/data1", "/dev/sda", Ok(sample(10, true)), later).is_none());
+        assert!(sys.get("/data1").unwrap().warnings.is_empty());
+    }
+
+    #[test]
+    fn test_record_replaced_device() {
+        let sys = DriveHealthSys::default();
+        let start = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        assert!(sys.record("/data1", "/dev/sda", Ok(sample(100, true)), start).is_none());
+
+        let mut replacement = sample(0, true);
+        replacement.serial = "ZA9Z8Y7X".to_string();
+        assert!(
+            sys.record("/data1", "/dev/sda", Ok(replacement.clone()), start + time::Duration::hours(1))
+                .is_none()
+        );
+
+        replacement.reallocated_sectors = Some(1);
+        let alert = sys
+            .record("/data1", "/dev/sda", Ok(replacement), start + time::Duration::hours(2))
+            .unwrap();
+        assert_eq!(alert.reasons, vec!["reallocated sectors rose from 0 to 1".to_string()]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_whole_device() {
+        let disk = std::path::Path::new("/sys/devices/pci0000:00/0000:00:17.0/ata1/host0/target0:0:0/0:0:0:0/block/sda");
+        assert_eq!(whole_device(disk, false), Some("sda".to_string()));
+        assert_eq!(whole_device(&disk.join("sda1"), true), Some("sda".to_string()));
+
+        let nvme = std::path::Path::new("/sys/devices/pci0000:00/0000:00:1d.0/nvme/nvme0/nvme0n1/nvme0n1p2");
+        assert_eq!(whole_device(nvme, true), Some("nvme0n1".to_string()));
+    }
+}
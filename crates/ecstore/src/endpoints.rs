@@ -18,6 +18,7 @@ use tracing::{error, info, instrument, warn};
 use crate::{
     disk::endpoint::{Endpoint, EndpointType},
     disks_layout::DisksLayout,
+    dns_discovery::{DnsVolume, resolve_volume_pools},
     global::global_rustfs_port,
 };
 use std::io::{Error, Result};
@@ -483,7 +484,14 @@ impl EndpointServerPools {
         None
     }
     pub async fn from_volumes(server_addr: &str, endpoints: Vec<String>) -> Result<(EndpointServerPools, SetupType)> {
-        let layouts = DisksLayout::from_volumes(endpoints.as_slice())?;
+        let dns_volumes = endpoints.iter().filter(|v| DnsVolume::is_dns_volume(v)).count();
+        let layouts = if dns_volumes == 0 {
+            DisksLayout::from_volumes(endpoints.as_slice())?
+        } else if dns_volumes == endpoints.len() {
+            DisksLayout::from_pool_drives(resolve_volume_pools(&endpoints).await?)?
+        } else {
+            return Err(Error::other("DNS volumes cannot be mixed with other volumes"));
+        };
 
         Self::create_server_endpoints(server_addr, &layouts).await
     }
@@ -14,6 +14,7 @@
 
 use super::BitrotReader;
 use super::Erasure;
+use super::prefetch::{GLOBAL_PREFETCH, PrefetchWindow};
 use crate::disk::error::Error;
 use crate::disk::error_reduce::reduce_errs;
 use futures::stream::{FuturesUnordered, StreamExt};
@@ -23,9 +24,17 @@ use std::io::ErrorKind;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncWrite;
 use tokio::io::AsyncWriteExt;
-use tracing::error;
+use tokio::sync::mpsc;
+use tracing::{error, warn};
 
 pin_project! {
+/// Reads one block worth of shards per call, from the first readers able to serve it.
+///
+/// Every reader tracks how many blocks it has consumed. A reader that was not needed for some
+/// blocks is caught up by reading and discarding the skipped shards the first time it is used,
+/// so a shard failing mid-stream is replaced by parity for the following blocks without
+/// restarting the read. A reader that fails once is not used again and its index is remembered
+/// so the caller can heal it.
 pub(crate) struct ParallelReader<R> {
     #[pin]
     readers: Vec<Option<BitrotReader<R>>>,
@@ -34,6 +43,12 @@ pub(crate) struct ParallelReader<R> {
     shard_file_size: usize,
     data_shards: usize,
     total_shards: usize,
+    // Index of the block the next read returns
+    block: usize,
+    // Blocks consumed by each reader
+    positions: Vec<usize>,
+    // Readers that failed mid-stream
+    failed: Vec<usize>,
 }
 }
 
@@ -50,6 +65,7 @@ where
 
         // Ensure offset does not exceed shard_file_size
 
+        let positions = vec![0; readers.len()];
         ParallelReader {
             readers,
             offset,
@@ -57,8 +73,34 @@ where
             shard_file_size,
             data_shards: e.data_shards,
             total_shards: e.data_shards + e.parity_shards,
+            block: 0,
+            positions,
+            failed: Vec::new(),
         }
     }
+
+    /// Indices of the readers that failed after the read started.
+    pub fn failed_shards(&self) -> &[usize] {
+        &self.failed
+    }
+}
+
+/// Read `skip` full shards into the void, then the shard of the current block.
+async fn read_shard<R>(reader: &mut BitrotReader<R>, skip: usize, full_size: usize, shard_size: usize) -> Result<Vec<u8>, Error>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+{
+    if skip > 0 {
+        let mut scratch = vec![0u8; full_size];
+        for _ in 0..skip {
+            reader.read(&mut scratch).await.map_err(Error::from)?;
+        }
+    }
+
+    let mut buf = vec![0u8; shard_size];
+    let n = reader.read(&mut buf).await.map_err(Error::from)?;
+    buf.truncate(n);
+    Ok(buf)
 }
 
 impl<R> ParallelReader<R>
@@ -72,7 +114,7 @@ where
         let num_readers = self.readers.len();
 
         let shard_size = if self.offset + self.shard_size > self.shard_file_size {
-            self.shard_file_size - self.offset
+            self.shard_file_size.saturating_sub(self.offset)
         } else {
             self.shard_size
         };
@@ -83,61 +125,76 @@ where
 
         let mut shards: Vec<Option<Vec<u8>>> = vec![None; num_readers];
         let mut errs = vec![None; num_readers];
-
-        let mut futures = Vec::with_capacity(self.total_shards);
-        let reader_iter: std::slice::IterMut<'_, Option<BitrotReader<R>>> = self.readers.iter_mut();
-        for (i, reader) in reader_iter.enumerate() {
-            let future = if let Some(reader) = reader {
-                Box::pin(async move {
-                    let mut buf = vec![0u8; shard_size];
-                    match reader.read(&mut buf).await {
-                        Ok(n) => {
-                            buf.truncate(n);
-                            (i, Ok(buf))
-                        }
-                        Err(e) => (i, Err(Error::from(e))),
+        let block = self.block;
+        let full_size = self.shard_size;
+
+        {
+            // Readers already in step with the current block are preferred, lagging ones have to catch up first
+            let mut order: Vec<usize> = (0..num_readers).collect();
+            order.sort_by_key(|&i| (self.positions[i] != block, i));
+
+            let mut futures: Vec<_> = Vec::with_capacity(self.total_shards);
+            let mut slots: Vec<Option<&mut Option<BitrotReader<R>>>> = self.readers.iter_mut().map(Some).collect();
+            for i in order {
+                let skip = block.saturating_sub(self.positions[i]);
+                let reader = slots[i].take();
+                let future = Box::pin(async move {
+                    match reader {
+                        Some(Some(reader)) => (i, read_shard(reader, skip, full_size, shard_size).await),
+                        // Return FileNotFound error when reader is None
+                        _ => (i, Err(Error::FileNotFound)),
                     }
-                }) as std::pin::Pin<Box<dyn std::future::Future<Output = (usize, Result<Vec<u8>, Error>)> + Send>>
-            } else {
-                // Return FileNotFound error when reader is None
-                Box::pin(async move { (i, Err(Error::FileNotFound)) })
-                    as std::pin::Pin<Box<dyn std::future::Future<Output = (usize, Result<Vec<u8>, Error>)> + Send>>
-            };
-
-            futures.push(future);
-        }
+                })
+                    as std::pin::Pin<Box<dyn std::future::Future<Output = (usize, Result<Vec<u8>, Error>)> + Send + '_>>;
 
-        if futures.len() >= self.data_shards {
-            let mut fut_iter = futures.into_iter();
-            let mut sets = FuturesUnordered::new();
-            for _ in 0..self.data_shards {
-                if let Some(future) = fut_iter.next() {
-                    sets.push(future);
-                }
+                futures.push(future);
             }
 
-            let mut success = 0;
-            while let Some((i, result)) = sets.next().await {
-                match result {
-                    Ok(v) => {
-                        shards[i] = Some(v);
-                        success += 1;
+            if futures.len() >= self.data_shards {
+                let mut fut_iter = futures.into_iter();
+                let mut sets = FuturesUnordered::new();
+                for _ in 0..self.data_shards {
+                    if let Some(future) = fut_iter.next() {
+                        sets.push(future);
                     }
-                    Err(e) => {
-                        errs[i] = Some(e);
+                }
+
+                let mut success = 0;
+                while let Some((i, result)) = sets.next().await {
+                    match result {
+                        Ok(v) => {
+                            shards[i] = Some(v);
+                            success += 1;
+                        }
+                        Err(e) => {
+                            errs[i] = Some(e);
 
-                        if let Some(future) = fut_iter.next() {
-                            sets.push(future);
+                            if let Some(future) = fut_iter.next() {
+                                sets.push(future);
+                            }
                         }
                     }
-                }
 
-                if success >= self.data_shards {
-                    break;
+                    if success >= self.data_shards {
+                        break;
+                    }
                 }
             }
         }
 
+        for i in 0..num_readers {
+            if shards[i].is_some() {
+                self.positions[i] = block + 1;
+            } else if errs[i].is_some() && self.readers[i].take().is_some() {
+                // The reader is in an unknown position now, substitute it for the rest of the read
+                warn!("erasure shard {} failed at block {}: {:?}", i, block, errs[i]);
+                self.failed.push(i);
+            }
+        }
+
+        self.block += 1;
+        self.offset += shard_size;
+
         (shards, errs)
     }
 
@@ -217,6 +274,10 @@ where
 }
 
 impl Erasure {
+    /// Decode `length` bytes from `offset` into `writer`.
+    ///
+    /// Returns the bytes written, the error that ended or degraded the read, and the indices of the
+    /// shards whose reader failed mid-stream and were substituted by parity for the remaining blocks.
     pub async fn decode<W, R>(
         &self,
         writer: &mut W,
@@ -224,23 +285,27 @@ impl Erasure {
         offset: usize,
         length: usize,
         total_length: usize,
-    ) -> (usize, Option<std::io::Error>)
+    ) -> (usize, Option<std::io::Error>, Vec<usize>)
     where
         W: AsyncWrite + Send + Sync + Unpin,
         R: AsyncRead + Unpin + Send + Sync,
     {
         if readers.len() != self.data_shards + self.parity_shards {
-            return (0, Some(io::Error::new(ErrorKind::InvalidInput, "Invalid number of readers")));
+            return (0, Some(io::Error::new(ErrorKind::InvalidInput, "Invalid number of readers")), Vec::new());
         }
 
         if offset + length > total_length {
-            return (0, Some(io::Error::new(ErrorKind::InvalidInput, "offset + length exceeds total length")));
+            return (
+                0,
+                Some(io::Error::new(ErrorKind::InvalidInput, "offset + length exceeds total length")),
+                Vec::new(),
+            );
         }
 
         let mut ret_err = None;
 
         if length == 0 {
-            return (0, ret_err);
+            return (0, ret_err, Vec::new());
         }
 
         let mut written = 0;
@@ -250,16 +315,12 @@ impl Erasure {
         let start = offset / self.block_size;
         let end = (offset + length) / self.block_size;
 
+        if let Some(window) = GLOBAL_PREFETCH.reserve(self.block_size, end - start + 1) {
+            return self.decode_prefetch(writer, reader, offset, length, window).await;
+        }
+
         for i in start..=end {
-            let (block_offset, block_length) = if start == end {
-                (offset % self.block_size, length)
-            } else if i == start {
-                (offset % self.block_size, self.block_size - (offset % self.block_size))
-            } else if i == end {
-                (0, (offset + length) % self.block_size)
-            } else {
-                (0, self.block_size)
-            };
+            let (block_offset, block_length) = self.block_span(i, offset, length);
 
             if block_length == 0 {
                 // error!("erasure decode decode block_length == 0");
@@ -301,15 +362,128 @@ impl Erasure {
             written += n;
         }
 
+        let failed = reader.failed_shards().to_vec();
+
         if ret_err.is_some() {
-            return (written, ret_err);
+            return (written, ret_err, failed);
         }
 
         if written < length {
             ret_err = Some(Error::LessData.into());
         }
 
-        (written, ret_err)
+        (written, ret_err, failed)
+    }
+
+    /// Offset and length of the part of block `i` a read of `length` bytes from `offset` covers.
+    fn block_span(&self, i: usize, offset: usize, length: usize) -> (usize, usize) {
+        let start = offset / self.block_size;
+        let end = (offset + length) / self.block_size;
+
+        if start == end {
+            (offset % self.block_size, length)
+        } else if i == start {
+            (offset % self.block_size, self.block_size - (offset % self.block_size))
+        } else if i == end {
+            (0, (offset + length) % self.block_size)
+        } else {
+            (0, self.block_size)
+        }
+    }
+
+    /// [`Erasure::decode`] reading and decoding up to `window.depth` blocks ahead of the block being
+    /// written, the decoding runs on the blocking pool so consecutive blocks decode in parallel.
+    async fn decode_prefetch<W, R>(
+        &self,
+        writer: &mut W,
+        mut reader: ParallelReader<R>,
+        offset: usize,
+        length: usize,
+        window: PrefetchWindow,
+    ) -> (usize, Option<std::io::Error>, Vec<usize>)
+    where
+        W: AsyncWrite + Send + Sync + Unpin,
+        R: AsyncRead + Unpin + Send + Sync,
+    {
+        let start = offset / self.block_size;
+        let end = (offset + length) / self.block_size;
+        let (tx, mut rx) = mpsc::channel(window.depth);
+
+        let read = async move {
+            let mut ret_err = None;
+            for i in start..=end {
+                let (block_offset, block_length) = self.block_span(i, offset, length);
+                if block_length == 0 {
+                    break;
+                }
+
+                let (mut shards, errs) = reader.read().await;
+
+                if ret_err.is_none() {
+                    if let (_, Some(err)) = reduce_errs(&errs, &[]) {
+                        if err == Error::FileNotFound || err == Error::FileCorrupt {
+                            ret_err = Some(err.into());
+                        }
+                    }
+                }
+
+                if !reader.can_decode(&shards) {
+                    error!("erasure decode can_decode errs: {:?}", &errs);
+                    ret_err = Some(Error::ErasureReadQuorum.into());
+                    break;
+                }
+
+                let erasure = self.clone();
+                let decoded = tokio::task::spawn_blocking(move || erasure.decode_data(&mut shards).map(|_| shards));
+
+                // The writer stopped, nothing more to read
+                if tx.send((decoded, block_offset, block_length)).await.is_err() {
+                    break;
+                }
+            }
+
+            (ret_err, reader.failed_shards().to_vec())
+        };
+
+        let write = async move {
+            let mut written = 0;
+            while let Some((decoded, block_offset, block_length)) = rx.recv().await {
+                let shards = match decoded.await {
+                    Ok(Ok(shards)) => shards,
+                    Ok(Err(e)) => {
+                        error!("erasure decode decode_data err: {:?}", e);
+                        return (written, Some(e));
+                    }
+                    Err(e) => {
+                        error!("erasure decode decode_data join err: {:?}", e);
+                        return (written, Some(io::Error::other(e)));
+                    }
+                };
+
+                match write_data_blocks(writer, &shards, self.data_shards, block_offset, block_length).await {
+                    Ok(n) => written += n,
+                    Err(e) => {
+                        error!("erasure decode write_data_blocks err: {:?}", e);
+                        return (written, Some(e));
+                    }
+                }
+            }
+
+            (written, None)
+        };
+
+        let ((read_err, failed), (written, write_err)) = tokio::join!(read, write);
+        drop(window);
+
+        if let Some(err) = write_err.or(read_err) {
+            return (written, Some(err), failed);
+        }
+
+        if written < length {
+            return (written, Some(Error::LessData.into()), failed);
+        }
+
+        (written, None, failed)
     }
 }
 
@@ -317,7 +491,10 @@ impl Erasure {
 mod tests {
     use rustfs_utils::HashAlgorithm;
 
-    use crate::{disk::error::DiskError, erasure_coding::BitrotWriter};
+    use crate::{
+        disk::error::DiskError,
+        erasure_coding::{BitrotWriter, prefetch::Prefetch},
+    };
 
     use super::*;
     use std::io::Cursor;
@@ -412,12 +589,13 @@ mod tests {
         let erausre = Erasure::new(DATA_SHARDS, PARITY_SHARDS, BLOCK_SIZE);
         let mut parallel_reader = ParallelReader::new(readers, erausre, reader_offset, NUM_SHARDS * BLOCK_SIZE);
 
-        for _ in 0..NUM_SHARDS {
+        for block in 0..NUM_SHARDS {
             let (bufs, errs) = parallel_reader.read().await;
 
             assert_eq!(DATA_SHARDS, bufs.iter().filter(|buf| buf.is_some()).count());
+            // Rotten readers are dropped after their first failure, later blocks read parity directly
             assert_eq!(
-                BITROT_DISKS,
+                if block == 0 { BITROT_DISKS } else { 0 },
                 errs.iter()
                     .filter(|err| {
                         match err {
@@ -432,6 +610,90 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_parallel_reader_substitutes_parity_mid_stream() {
+        const NUM_SHARDS: usize = 3;
+        const BLOCK_SIZE: usize = 64;
+        const DATA_SHARDS: usize = 8;
+        const PARITY_SHARDS: usize = 4;
+        const SHARD_SIZE: usize = BLOCK_SIZE / DATA_SHARDS;
+        let hash_algo = HashAlgorithm::HighwayHash256;
+
+        let mut readers = vec![];
+        for i in 0..(DATA_SHARDS + PARITY_SHARDS) {
+            if i == 0 {
+                // The first data shard only rots in the second block
+                let mut writer = BitrotWriter::new(Cursor::new(Vec::new()), SHARD_SIZE, hash_algo.clone());
+                for _ in 0..NUM_SHARDS {
+                    writer.write(vec![0u8; SHARD_SIZE].as_slice()).await.unwrap();
+                }
+                let mut buf = writer.into_inner().into_inner();
+                buf[hash_algo.size() + SHARD_SIZE] ^= 1;
+                readers.push(Some(BitrotReader::new(Cursor::new(buf), SHARD_SIZE, hash_algo.clone())));
+            } else {
+                readers.push(Some(create_reader(SHARD_SIZE, NUM_SHARDS, i as u8, &hash_algo, false).await));
+            }
+        }
+
+        let erasure = Erasure::new(DATA_SHARDS, PARITY_SHARDS, BLOCK_SIZE);
+        let mut parallel_reader = ParallelReader::new(readers, erasure, 0, NUM_SHARDS * BLOCK_SIZE);
+
+        let (bufs, errs) = parallel_reader.read().await;
+        assert!(bufs[..DATA_SHARDS].iter().all(|buf| buf.is_some()));
+        assert!(errs.iter().all(|err| err.is_none()));
+        assert!(parallel_reader.failed_shards().is_empty());
+
+        // The first parity shard skips the block it was not needed for and replaces the rotten shard
+        let (bufs, errs) = parallel_reader.read().await;
+        assert!(bufs[0].is_none());
+        assert!(errs[0].is_some());
+        assert_eq!(Some(vec![DATA_SHARDS as u8; SHARD_SIZE]), bufs[DATA_SHARDS]);
+        assert_eq!(parallel_reader.failed_shards(), &[0]);
+
+        let (bufs, errs) = parallel_reader.read().await;
+        assert!(bufs[0].is_none());
+        assert!(errs.iter().all(|err| err.is_none()));
+        for (index, buf) in bufs.iter().enumerate().take(DATA_SHARDS + 1).skip(1) {
+            assert_eq!(Some(vec![index as u8; SHARD_SIZE]), *buf);
+        }
+        assert_eq!(parallel_reader.failed_shards(), &[0]);
+    }
+
+    #[tokio::test]
+    async fn test_decode_prefetch() {
+        const NUM_BLOCKS: usize = 6;
+        const BLOCK_SIZE: usize = 64;
+        const DATA_SHARDS: usize = 8;
+        const PARITY_SHARDS: usize = 4;
+        const SHARD_SIZE: usize = BLOCK_SIZE / DATA_SHARDS;
+        let hash_algo = HashAlgorithm::HighwayHash256;
+
+        let block: Vec<u8> = (0..DATA_SHARDS).flat_map(|i| vec![i as u8; SHARD_SIZE]).collect();
+        let object = block.repeat(NUM_BLOCKS);
+        let erasure = Erasure::new(DATA_SHARDS, PARITY_SHARDS, BLOCK_SIZE);
+
+        for (offset, length) in [(0, object.len()), (10, BLOCK_SIZE * 4 + 3)] {
+            let mut readers = vec![];
+            for i in 0..(DATA_SHARDS + PARITY_SHARDS) {
+                if i < DATA_SHARDS {
+                    readers.push(Some(create_reader(SHARD_SIZE, NUM_BLOCKS, i as u8, &hash_algo, false).await));
+                } else {
+                    readers.push(None);
+                }
+            }
+
+            let reader = ParallelReader::new(readers, erasure.clone(), offset, object.len());
+            let window = Prefetch::new(2, 1024 * 1024).reserve(BLOCK_SIZE, NUM_BLOCKS).unwrap();
+
+            let mut out = Vec::new();
+            let (written, err, failed) = erasure.decode_prefetch(&mut out, reader, offset, length, window).await;
+            assert!(err.is_none(), "{err:?}");
+            assert!(failed.is_empty());
+            assert_eq!(written, length);
+            assert_eq!(out, &object[offset..offset + length]);
+        }
+    }
+
     async fn create_reader(
         shard_size: usize,
         num_shards: usize,
@@ -16,6 +16,7 @@ pub mod decode;
 pub mod encode;
 pub mod erasure;
 pub mod heal;
+mod prefetch;
 
 mod bitrot;
 pub use bitrot::*;
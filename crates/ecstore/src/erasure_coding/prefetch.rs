@@ -0,0 +1,102 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read-ahead budget of large GETs.
+//!
+//! A read spanning enough blocks reads and decodes up to `depth` blocks ahead of the one being
+//! written. Every read reserves the memory of its whole window from a budget shared by all reads
+//! before starting, and one that does not fit reads block by block instead of waiting.
+
+use rustfs_config::{DEFAULT_GET_PREFETCH_DEPTH, DEFAULT_GET_PREFETCH_MEMORY, ENV_GET_PREFETCH_DEPTH, ENV_GET_PREFETCH_MEMORY};
+use rustfs_utils::get_env_u64;
+use std::sync::{Arc, LazyLock};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+pub(crate) static GLOBAL_PREFETCH: LazyLock<Prefetch> = LazyLock::new(|| {
+    Prefetch::new(
+        get_env_u64(ENV_GET_PREFETCH_DEPTH, DEFAULT_GET_PREFETCH_DEPTH) as usize,
+        get_env_u64(ENV_GET_PREFETCH_MEMORY, DEFAULT_GET_PREFETCH_MEMORY) as usize * 1024 * 1024,
+    )
+});
+
+/// Reads spanning fewer blocks gain nothing from read-ahead.
+const MIN_PREFETCH_BLOCKS: usize = 4;
+
+// The budget is counted in KiB to keep the permit count small
+const PERMIT_UNIT: usize = 1024;
+
+#[derive(Debug)]
+pub(crate) struct Prefetch {
+    depth: usize,
+    budget: Arc<Semaphore>,
+}
+
+/// Memory reserved for the blocks one read holds, released when dropped.
+#[derive(Debug)]
+pub(crate) struct PrefetchWindow {
+    pub depth: usize,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Prefetch {
+    pub fn new(depth: usize, memory: usize) -> Self {
+        Self {
+            depth,
+            budget: Arc::new(Semaphore::new((memory / PERMIT_UNIT).min(Semaphore::MAX_PERMITS))),
+        }
+    }
+
+    /// Reserve a window for a read of `blocks` blocks of `block_size` bytes, None when the read is
+    /// too short, read-ahead is disabled or the budget is used up.
+    pub fn reserve(&self, block_size: usize, blocks: usize) -> Option<PrefetchWindow> {
+        if self.depth == 0 || blocks < MIN_PREFETCH_BLOCKS {
+            return None;
+        }
+
+        let depth = self.depth.min(blocks - 1);
+        // The block being written is held as well
+        let permits = (depth + 1) * block_size.div_ceil(PERMIT_UNIT);
+        let permit = self
+            .budget
+            .clone()
+            .try_acquire_many_owned(u32::try_from(permits).ok()?)
+            .ok()?;
+
+        Some(PrefetchWindow { depth, _permit: permit })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefetch_reserve() {
+        const BLOCK_SIZE: usize = 1024 * 1024;
+
+        let prefetch = Prefetch::new(2, 8 * BLOCK_SIZE);
+        assert!(prefetch.reserve(BLOCK_SIZE, MIN_PREFETCH_BLOCKS - 1).is_none());
+
+        let first = prefetch.reserve(BLOCK_SIZE, 100).unwrap();
+        assert_eq!(first.depth, 2);
+        let second = prefetch.reserve(BLOCK_SIZE, 100).unwrap();
+        assert!(prefetch.reserve(BLOCK_SIZE, 100).is_none());
+
+        drop(first);
+        assert!(prefetch.reserve(BLOCK_SIZE, 100).is_some());
+        drop(second);
+
+        assert!(Prefetch::new(0, 8 * BLOCK_SIZE).reserve(BLOCK_SIZE, 100).is_none());
+    }
+}
@@ -165,6 +165,15 @@ pub enum StorageError {
     #[error("Storage resources are insufficient for the write operation: {0}/{1}")]
     InsufficientWriteQuorum(String, String),
 
+    #[error("Invalid object name {0}/{1}: {2}")]
+    InvalidObjectName(String, String, String),
+
+    #[error("Bucket {0} is {1}: {2} is not allowed")]
+    BucketModeViolation(String, String, String),
+
+    #[error("Write quorum not reached for {0}/{1}: {2} disks succeeded, {3} failed, {4} required")]
+    WriteQuorumNotReached(String, String, usize, usize, usize),
+
     #[error("Decommission not started")]
     DecommissionNotStarted,
     #[error("Decommission already running")]
@@ -266,6 +275,7 @@ impl From<StorageError> for DiskError {
             StorageError::SlowDown => DiskError::TooManyOpenFiles,
             StorageError::ErasureReadQuorum => DiskError::ErasureReadQuorum,
             StorageError::ErasureWriteQuorum => DiskError::ErasureWriteQuorum,
+            StorageError::WriteQuorumNotReached(_, _, _, _, _) => DiskError::ErasureWriteQuorum,
             StorageError::TooManyOpenFiles => DiskError::TooManyOpenFiles,
             StorageError::NoHealRequired => DiskError::NoHealRequired,
             StorageError::CorruptedFormat => DiskError::CorruptedFormat,
@@ -426,6 +436,11 @@ impl Clone for StorageError {
             StorageError::Lock(e) => StorageError::Lock(e.clone()),
             StorageError::InsufficientReadQuorum(a, b) => StorageError::InsufficientReadQuorum(a.clone(), b.clone()),
             StorageError::InsufficientWriteQuorum(a, b) => StorageError::InsufficientWriteQuorum(a.clone(), b.clone()),
+            StorageError::InvalidObjectName(a, b, c) => StorageError::InvalidObjectName(a.clone(), b.clone(), c.clone()),
+            StorageError::BucketModeViolation(a, b, c) => StorageError::BucketModeViolation(a.clone(), b.clone(), c.clone()),
+            StorageError::WriteQuorumNotReached(a, b, c, d, e) => {
+                StorageError::WriteQuorumNotReached(a.clone(), b.clone(), *c, *d, *e)
+            }
             StorageError::PreconditionFailed => StorageError::PreconditionFailed,
             StorageError::InvalidRangeSpec(a) => StorageError::InvalidRangeSpec(a.clone()),
         }
@@ -496,6 +511,9 @@ impl StorageError {
             StorageError::PreconditionFailed => 0x3B,
             StorageError::EntityTooSmall(_, _, _) => 0x3C,
             StorageError::InvalidRangeSpec(_) => 0x3D,
+            StorageError::WriteQuorumNotReached(_, _, _, _, _) => 0x3E,
+            StorageError::InvalidObjectName(_, _, _) => 0x3F,
+            StorageError::BucketModeViolation(_, _, _) => 0x40,
         }
     }
 
@@ -566,6 +584,23 @@ impl StorageError {
             0x3B => Some(StorageError::PreconditionFailed),
             0x3C => Some(StorageError::EntityTooSmall(Default::default(), Default::default(), Default::default())),
             0x3D => Some(StorageError::InvalidRangeSpec(Default::default())),
+            0x3E => Some(StorageError::WriteQuorumNotReached(
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            )),
+            0x3F => Some(StorageError::InvalidObjectName(
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            )),
+            0x40 => Some(StorageError::BucketModeViolation(
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            )),
             _ => None,
         }
     }
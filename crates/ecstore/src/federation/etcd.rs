@@ -0,0 +1,378 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bucket directory kept in etcd, spoken to through the JSON gateway of the etcd v3 API.
+
+use super::{BucketDirectory, BucketRecord, DirectoryEvent};
+use crate::error::{Error, Result};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Timeout of the requests other than watches.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn b64(data: &[u8]) -> String {
+    base64_simd::STANDARD.encode_to_string(data)
+}
+
+fn de_b64<'de, D: Deserializer<'de>>(d: D) -> std::result::Result<Vec<u8>, D::Error> {
+    let s = String::deserialize(d)?;
+    base64_simd::STANDARD.decode_to_vec(s).map_err(serde::de::Error::custom)
+}
+
+/// The gateway encodes int64 as strings.
+fn de_i64<'de, D: Deserializer<'de>>(d: D) -> std::result::Result<i64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum I64 {
+        Num(i64),
+        Str(String),
+    }
+    match I64::deserialize(d)? {
+        I64::Num(n) => Ok(n),
+        I64::Str(s) => s.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+/// End of the key range holding every key starting with `prefix`.
+fn prefix_range_end(prefix: &[u8]) -> Vec<u8> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < 0xff {
+            end.push(last + 1);
+            return end;
+        }
+    }
+    // Every byte is 0xff, "\0" means up to the end of the key space
+    vec![0]
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ResponseHeader {
+    #[serde(default, deserialize_with = "de_i64")]
+    revision: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyValue {
+    #[serde(deserialize_with = "de_b64")]
+    key: Vec<u8>,
+    #[serde(default, deserialize_with = "de_b64")]
+    value: Vec<u8>,
+    #[serde(default, deserialize_with = "de_i64")]
+    mod_revision: i64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RangeResponse {
+    #[serde(default)]
+    header: ResponseHeader,
+    #[serde(default)]
+    kvs: Vec<KeyValue>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TxnResponse {
+    #[serde(default)]
+    succeeded: bool,
+    #[serde(default)]
+    responses: Vec<TxnOpResponse>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TxnOpResponse {
+    #[serde(default)]
+    response_range: Option<RangeResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WatchMessage {
+    #[serde(default)]
+    result: Option<WatchResponse>,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WatchResponse {
+    #[serde(default)]
+    canceled: bool,
+    #[serde(default)]
+    cancel_reason: String,
+    #[serde(default)]
+    events: Vec<WatchEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WatchEvent {
+    /// `PUT` is the default and left out by the gateway.
+    #[serde(default, rename = "type")]
+    kind: String,
+    kv: KeyValue,
+}
+
+#[derive(Debug)]
+pub struct EtcdDirectory {
+    endpoints: Vec<String>,
+    prefix: String,
+    client: reqwest::Client,
+}
+
+impl EtcdDirectory {
+    pub fn new(endpoints: Vec<String>, prefix: String) -> Result<Self> {
+        let endpoints: Vec<String> = endpoints
+            .into_iter()
+            .map(|ep| ep.trim().trim_end_matches('/').to_string())
+            .filter(|ep| !ep.is_empty())
+            .collect();
+        if endpoints.is_empty() {
+            return Err(Error::other("no etcd endpoints"));
+        }
+        Ok(Self {
+            endpoints,
+            prefix,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    fn key(&self, bucket: &str) -> Vec<u8> {
+        format!("{}{}", self.prefix, bucket).into_bytes()
+    }
+
+    fn bucket_of<'a>(&self, key: &'a [u8]) -> Option<&'a str> {
+        std::str::from_utf8(key).ok()?.strip_prefix(self.prefix.as_str())
+    }
+
+    /// POST `body` to `path` of the first endpoint answering.
+    async fn call<T: DeserializeOwned>(&self, path: &str, body: &serde_json::Value) -> Result<T> {
+        let mut last_err = None;
+        for endpoint in &self.endpoints {
+            let res = self
+                .client
+                .post(format!("{endpoint}{path}"))
+                .json(body)
+                .timeout(REQUEST_TIMEOUT)
+                .send()
+                .await
+                .and_then(|res| res.error_for_status());
+            match res {
+                Ok(res) => return res.json::<T>().await.map_err(Error::other),
+                Err(err) => {
+                    warn!("etcd request to {} failed: {}", endpoint, err);
+                    last_err = Some(err);
+                }
+            }
+        }
+        Err(Error::other(format!("etcd unavailable: {:?}", last_err)))
+    }
+
+    fn record_of(&self, kv: &KeyValue) -> Option<(String, BucketRecord)> {
+        let bucket = self.bucket_of(&kv.key)?;
+        match serde_json::from_slice::<BucketRecord>(&kv.value) {
+            Ok(record) => Some((bucket.to_string(), record)),
+            Err(err) => {
+                warn!("ignoring bucket {} with an invalid directory record: {}", bucket, err);
+                None
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BucketDirectory for EtcdDirectory {
+    async fn register(&self, bucket: &str, record: &BucketRecord) -> Result<Option<BucketRecord>> {
+        let key = b64(&self.key(bucket));
+        let value = b64(&serde_json::to_vec(record).map_err(Error::other)?);
+        // Put only when the key was never created, read the current owner otherwise
+        let body = json!({
+            "compare": [{ "key": key, "target": "CREATE", "result": "EQUAL", "create_revision": "0" }],
+            "success": [{ "request_put": { "key": key, "value": value } }],
+            "failure": [{ "request_range": { "key": key } }],
+        });
+        let res: TxnResponse = self.call("/v3/kv/txn", &body).await?;
+        if res.succeeded {
+            return Ok(None);
+        }
+
+        let Some(kv) = res
+            .responses
+            .iter()
+            .filter_map(|r| r.response_range.as_ref())
+            .flat_map(|r| r.kvs.iter())
+            .next()
+        else {
+            // Deleted in between, try again
+            return self.register(bucket, record).await;
+        };
+        match self.record_of(kv) {
+            Some((_, owner)) => Ok(Some(owner)),
+            None => Err(Error::other(format!("bucket {bucket} has an invalid directory record"))),
+        }
+    }
+
+    async fn unregister(&self, bucket: &str, record: &BucketRecord) -> Result<()> {
+        let key = b64(&self.key(bucket));
+        let res: RangeResponse = self.call("/v3/kv/range", &json!({ "key": key })).await?;
+        let Some(kv) = res.kvs.first() else {
+            return Ok(());
+        };
+        if self
+            .record_of(kv)
+            .is_none_or(|(_, owner)| owner.route.endpoint != record.route.endpoint)
+        {
+            return Ok(());
+        }
+
+        // Delete only the version read, a cluster may have claimed the name in between
+        let body = json!({
+            "compare": [{ "key": key, "target": "MOD", "result": "EQUAL", "mod_revision": kv.mod_revision.to_string() }],
+            "success": [{ "request_delete_range": { "key": key } }],
+        });
+        let _: TxnResponse = self.call("/v3/kv/txn", &body).await?;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<(HashMap<String, BucketRecord>, i64)> {
+        let prefix = self.prefix.as_bytes();
+        let body = json!({ "key": b64(prefix), "range_end": b64(&prefix_range_end(prefix)) });
+        let res: RangeResponse = self.call("/v3/kv/range", &body).await?;
+        let records = res.kvs.iter().filter_map(|kv| self.record_of(kv)).collect();
+        Ok((records, res.header.revision))
+    }
+
+    async fn watch(&self, revision: i64, tx: mpsc::Sender<DirectoryEvent>) -> Result<()> {
+        let prefix = self.prefix.as_bytes();
+        let body = json!({
+            "create_request": {
+                "key": b64(prefix),
+                "range_end": b64(&prefix_range_end(prefix)),
+                "start_revision": (revision + 1).to_string(),
+            }
+        });
+
+        let mut res = None;
+        for endpoint in &self.endpoints {
+            match self
+                .client
+                .post(format!("{endpoint}/v3/watch"))
+                .json(&body)
+                .send()
+                .await
+                .and_then(|res| res.error_for_status())
+            {
+                Ok(r) => {
+                    res = Some(r);
+                    break;
+                }
+                Err(err) => warn!("etcd watch on {} failed: {}", endpoint, err),
+            }
+        }
+        let Some(res) = res else {
+            return Err(Error::other("etcd unavailable"));
+        };
+
+        // The gateway streams one JSON message per line
+        let mut stream = res.bytes_stream();
+        let mut buf = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            buf.extend_from_slice(&chunk.map_err(Error::other)?);
+            while let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buf.drain(..=pos).collect();
+                if line.iter().all(|b| b.is_ascii_whitespace()) {
+                    continue;
+                }
+
+                let msg: WatchMessage = serde_json::from_slice(&line).map_err(Error::other)?;
+                if let Some(err) = msg.error {
+                    return Err(Error::other(format!("etcd watch failed: {err}")));
+                }
+                let Some(result) = msg.result else {
+                    continue;
+                };
+                if result.canceled {
+                    return Err(Error::other(format!("etcd watch canceled: {}", result.cancel_reason)));
+                }
+
+                for event in result.events {
+                    let Some(bucket) = self.bucket_of(&event.kv.key) else {
+                        continue;
+                    };
+                    let event = if event.kind == "DELETE" {
+                        DirectoryEvent::Delete(bucket.to_string())
+                    } else if let Some((bucket, record)) = self.record_of(&event.kv) {
+                        DirectoryEvent::Put(bucket, record)
+                    } else {
+                        continue;
+                    };
+                    if tx.send(event).await.is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_range_end() {
+        assert_eq!(prefix_range_end(b"/rustfs/buckets/"), b"/rustfs/buckets0".to_vec());
+        assert_eq!(prefix_range_end(b"a\xff"), b"b".to_vec());
+        assert_eq!(prefix_range_end(b"\xff\xff"), vec![0]);
+    }
+
+    #[test]
+    fn test_parse_gateway_responses() {
+        let directory = EtcdDirectory::new(vec!["http://etcd:2379/".to_string()], "/rustfs/buckets/".to_string()).unwrap();
+        assert_eq!(directory.endpoints, vec!["http://etcd:2379".to_string()]);
+
+        let record = br#"{"endpoint":"https://cluster-b:9000","region":"eu-west-1"}"#;
+        let body = format!(
+            r#"{{"header":{{"revision":"42"}},"kvs":[{{"key":"{}","value":"{}","mod_revision":"40"}}],"count":"1"}}"#,
+            b64(b"/rustfs/buckets/photos"),
+            b64(record)
+        );
+        let res: RangeResponse = serde_json::from_str(&body).unwrap();
+        assert_eq!(res.header.revision, 42);
+        assert_eq!(res.kvs[0].mod_revision, 40);
+
+        let (bucket, record) = directory.record_of(&res.kvs[0]).unwrap();
+        assert_eq!(bucket, "photos");
+        assert_eq!(record.route.endpoint, "https://cluster-b:9000");
+        assert_eq!(record.route.region.as_deref(), Some("eu-west-1"));
+
+        let txn: TxnResponse = serde_json::from_str(r#"{"header":{"revision":"43"}}"#).unwrap();
+        assert!(!txn.succeeded);
+
+        let watch = format!(
+            r#"{{"result":{{"header":{{"revision":"44"}},"events":[{{"type":"DELETE","kv":{{"key":"{}","mod_revision":"44"}}}}]}}}}"#,
+            b64(b"/rustfs/buckets/photos")
+        );
+        let msg: WatchMessage = serde_json::from_str(&watch).unwrap();
+        let event = &msg.result.unwrap().events[0];
+        assert_eq!(event.kind, "DELETE");
+        assert_eq!(directory.bucket_of(&event.kv.key), Some("photos"));
+    }
+}
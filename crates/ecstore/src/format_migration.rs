@@ -0,0 +1,366 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Format versions of drives and their online migration.
+//!
+//! Every drive records the version of its on-disk format, for the metadata and layout of the data
+//! it holds, in `FORMAT_VERSION_FILE` next to `format.json`. A build supports the versions up to
+//! the one its registered migrations lead to, and a node refuses to start when a drive of its
+//! pools has a newer version or is being migrated to one.
+//!
+//! Migrations move a drive from one version to the next while it is serving. Each checks the
+//! drive before starting and after finishing, and works in steps, the position reached is saved
+//! after every step so an interrupted migration continues where it stopped.
+
+use crate::disk::{DiskAPI, DiskStore, RUSTFS_META_BUCKET, error::DiskError};
+use crate::error::{Error, Result};
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, LazyLock};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+pub const FORMAT_VERSION_FILE: &str = "format-version.json";
+
+/// Version of drives formatted before format versions were recorded.
+pub const BASE_FORMAT_VERSION: u32 = 1;
+
+/// Migrations known to this build.
+pub static GLOBAL_FORMAT_MIGRATIONS: LazyLock<MigrationRegistry> = LazyLock::new(MigrationRegistry::default);
+
+/// Format version of a drive, and the migration running on it if any.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormatVersion {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub migration: Option<MigrationProgress>,
+}
+
+impl Default for FormatVersion {
+    fn default() -> Self {
+        Self {
+            version: BASE_FORMAT_VERSION,
+            migration: None,
+        }
+    }
+}
+
+impl FormatVersion {
+    /// The highest version the drive may hold data of.
+    pub fn highest(&self) -> u32 {
+        self.migration.as_ref().map_or(self.version, |m| m.target.max(self.version))
+    }
+}
+
+/// Position of a migration on a drive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationProgress {
+    pub name: String,
+    pub target: u32,
+    /// Where the next step starts, set by the last completed step
+    pub checkpoint: Option<String>,
+    pub steps: u64,
+}
+
+/// Moves a drive from `from_version` to the version after it.
+#[async_trait::async_trait]
+pub trait FormatMigration: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn from_version(&self) -> u32;
+
+    /// Checks the drive can be migrated, before the first step.
+    async fn pre_check(&self, disk: &DiskStore) -> Result<()>;
+
+    /// Migrates the part of the drive after `checkpoint`, returns the checkpoint to continue from
+    /// or None when the drive is done. Steps must be safe to repeat from their checkpoint.
+    async fn step(&self, disk: &DiskStore, checkpoint: Option<String>) -> Result<Option<String>>;
+
+    /// Checks the drive is consistent, after the last step.
+    async fn post_check(&self, disk: &DiskStore) -> Result<()>;
+}
+
+/// Migrations ordered by the version they start from.
+#[derive(Default)]
+pub struct MigrationRegistry {
+    migrations: Vec<Arc<dyn FormatMigration>>,
+}
+
+impl MigrationRegistry {
+    /// Adds the migration from the latest version, migrations must be registered in version order.
+    pub fn register(&mut self, migration: Arc<dyn FormatMigration>) -> Result<()> {
+        if migration.from_version() != self.latest() {
+            return Err(Error::other(format!(
+                "migration {} starts from format version {}, expected {}",
+                migration.name(),
+                migration.from_version(),
+                self.latest()
+            )));
+        }
+
+        self.migrations.push(migration);
+        Ok(())
+    }
+
+    /// The format version drives are migrated to.
+    pub fn latest(&self) -> u32 {
+        BASE_FORMAT_VERSION + self.migrations.len() as u32
+    }
+
+    fn get(&self, from_version: u32) -> Option<&Arc<dyn FormatMigration>> {
+        let idx = from_version.checked_sub(BASE_FORMAT_VERSION)?;
+        self.migrations.get(idx as usize)
+    }
+
+    /// Checks a drive holds no data newer than this build supports.
+    pub fn check_compatible(&self, endpoint: &str, version: &FormatVersion) -> Result<()> {
+        if version.highest() > self.latest() {
+            return Err(Error::other(format!(
+                "drive {endpoint} has format version {}, this node supports up to {}, upgrade it before joining",
+                version.highest(),
+                self.latest()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Runs the pending migrations of a drive, resuming the one interrupted, and returns the
+    /// version the drive reached. Stops after the current step when cancelled.
+    pub async fn migrate_disk(&self, disk: &DiskStore, cancel: &CancellationToken) -> Result<u32> {
+        let mut state = load_format_version(disk).await?;
+        self.check_compatible(&disk.to_string(), &state)?;
+
+        while state.version < self.latest() {
+            let Some(migration) = self.get(state.version) else {
+                return Err(Error::other(format!("no migration from format version {}", state.version)));
+            };
+
+            let target = state.version + 1;
+            let mut progress = match state.migration.take() {
+                Some(progress) if progress.target == target => progress,
+                _ => {
+                    migration.pre_check(disk).await?;
+                    info!(
+                        "migrating drive {} to format version {} with {}",
+                        disk.to_string(),
+                        target,
+                        migration.name()
+                    );
+                    MigrationProgress {
+                        name: migration.name().to_string(),
+                        target,
+                        checkpoint: None,
+                        steps: 0,
+                    }
+                }
+            };
+
+            loop {
+                if cancel.is_cancelled() {
+                    return Ok(state.version);
+                }
+
+                let Some(checkpoint) = migration.step(disk, progress.checkpoint.clone()).await? else {
+                    break;
+                };
+                progress.checkpoint = Some(checkpoint);
+                progress.steps += 1;
+
+                state.migration = Some(progress.clone());
+                save_format_version(disk, &state).await?;
+            }
+
+            migration.post_check(disk).await?;
+
+            state = FormatVersion {
+                version: target,
+                migration: None,
+            };
+            save_format_version(disk, &state).await?;
+            info!("drive {} migrated to format version {}", disk.to_string(), target);
+        }
+
+        Ok(state.version)
+    }
+}
+
+/// Reads the format version of a drive, drives without one have the base version.
+pub async fn load_format_version(disk: &DiskStore) -> Result<FormatVersion> {
+    match disk.read_all(RUSTFS_META_BUCKET, FORMAT_VERSION_FILE).await {
+        Ok(data) => Ok(serde_json::from_slice(&data)?),
+        Err(DiskError::FileNotFound) => Ok(FormatVersion::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn save_format_version(disk: &DiskStore, version: &FormatVersion) -> Result<()> {
+    let data = serde_json::to_vec(version)?;
+    let tmpfile = Uuid::new_v4().to_string();
+
+    disk.write_all(RUSTFS_META_BUCKET, &tmpfile, data.into()).await?;
+    disk.rename_file(RUSTFS_META_BUCKET, &tmpfile, RUSTFS_META_BUCKET, FORMAT_VERSION_FILE)
+        .await?;
+
+    Ok(())
+}
+
+/// Checks every reachable drive of a pool is supported by this build.
+pub async fn check_format_versions(disks: &[Option<DiskStore>]) -> Result<()> {
+    let versions = join_all(disks.iter().flatten().map(|disk| async move {
+        let version = load_format_version(disk).await;
+        (disk.to_string(), version)
+    }))
+    .await;
+
+    for (endpoint, version) in versions {
+        match version {
+            Ok(version) => GLOBAL_FORMAT_MIGRATIONS.check_compatible(&endpoint, &version)?,
+            // Offline drives are checked when they come back
+            Err(err) => warn!("reading the format version of drive {} failed: {}", endpoint, err),
+        }
+    }
+
+    Ok(())
+}
+
+/// Migrates the local drives in the background, one task per drive.
+pub fn start_format_migrations(disks: Vec<DiskStore>, cancel: CancellationToken) {
+    if GLOBAL_FORMAT_MIGRATIONS.latest() == BASE_FORMAT_VERSION {
+        return;
+    }
+
+    for disk in disks {
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            if let Err(err) = GLOBAL_FORMAT_MIGRATIONS.migrate_disk(&disk, &cancel).await {
+                error!("format migration of drive {} failed: {}", disk.to_string(), err);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disk::{DiskOption, endpoint::Endpoint, new_disk};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Counts to `steps` in the checkpoint, failing once after `fail_at` steps.
+    struct CountingMigration {
+        from: u32,
+        steps: u32,
+        fail_at: AtomicU32,
+        post_checks: AtomicU32,
+    }
+
+    impl CountingMigration {
+        fn new(from: u32, steps: u32, fail_at: u32) -> Arc<Self> {
+            Arc::new(Self {
+                from,
+                steps,
+                fail_at: AtomicU32::new(fail_at),
+                post_checks: AtomicU32::new(0),
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FormatMigration for CountingMigration {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        fn from_version(&self) -> u32 {
+            self.from
+        }
+
+        async fn pre_check(&self, _disk: &DiskStore) -> Result<()> {
+            Ok(())
+        }
+
+        async fn step(&self, _disk: &DiskStore, checkpoint: Option<String>) -> Result<Option<String>> {
+            let done: u32 = checkpoint.map_or(0, |c| c.parse().unwrap());
+            if done == self.fail_at.load(Ordering::SeqCst) {
+                self.fail_at.store(u32::MAX, Ordering::SeqCst);
+                return Err(Error::other("interrupted"));
+            }
+            Ok((done < self.steps).then(|| (done + 1).to_string()))
+        }
+
+        async fn post_check(&self, _disk: &DiskStore) -> Result<()> {
+            self.post_checks.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_register_in_order() {
+        let mut registry = MigrationRegistry::default();
+        assert_eq!(registry.latest(), BASE_FORMAT_VERSION);
+        assert!(registry.register(CountingMigration::new(2, 1, u32::MAX)).is_err());
+        registry.register(CountingMigration::new(1, 1, u32::MAX)).unwrap();
+        registry.register(CountingMigration::new(2, 1, u32::MAX)).unwrap();
+        assert_eq!(registry.latest(), 3);
+
+        assert!(registry.check_compatible("d", &FormatVersion::default()).is_ok());
+        let migrating = FormatVersion {
+            version: 3,
+            migration: Some(MigrationProgress {
+                name: "next".to_string(),
+                target: 4,
+                checkpoint: None,
+                steps: 0,
+            }),
+        };
+        assert!(registry.check_compatible("d", &migrating).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_disk_resumes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(RUSTFS_META_BUCKET)).unwrap();
+        let endpoint = Endpoint::try_from(dir.path().to_str().unwrap()).unwrap();
+        let disk = new_disk(
+            &endpoint,
+            &DiskOption {
+                cleanup: false,
+                health_check: false,
+            },
+        )
+        .await
+        .unwrap();
+
+        let migration = CountingMigration::new(1, 5, 3);
+        let mut registry = MigrationRegistry::default();
+        registry.register(migration.clone()).unwrap();
+
+        let cancel = CancellationToken::new();
+        assert!(registry.migrate_disk(&disk, &cancel).await.is_err());
+        let state = load_format_version(&disk).await.unwrap();
+        assert_eq!(state.version, 1);
+        assert_eq!(state.migration.unwrap().checkpoint.as_deref(), Some("3"));
+
+        assert_eq!(registry.migrate_disk(&disk, &cancel).await.unwrap(), 2);
+        assert_eq!(
+            load_format_version(&disk).await.unwrap(),
+            FormatVersion {
+                version: 2,
+                migration: None
+            }
+        );
+        assert_eq!(migration.post_checks.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,661 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Filesystem backend: every object is a plain file at `<drive>/<bucket>/<key>`.
+//!
+//! The metadata S3 needs that a file cannot carry (ETag, user metadata, content type) is kept in a
+//! JSON sidecar under the system bucket of the drive. Files put on the drive by other means are
+//! served as well, with metadata derived from the file itself. Writes go to the system tmp
+//! directory first and are renamed into place, so readers never see partial objects.
+//!
+//! A key cannot be both an object and the parent of other objects, as on any filesystem.
+
+use crate::disk::{RUSTFS_META_BUCKET, RUSTFS_META_TMP_BUCKET};
+use crate::error::{Error, Result, StorageError};
+use crate::object_layer::ObjectLayer;
+use crate::store_api::{GetObjectReader, HTTPRangeSpec, ListObjectsV2Info, ObjectInfo, ObjectOptions, PutObjReader};
+use bytes::Bytes;
+use http::HeaderMap;
+use rustfs_rio::EtagResolvable;
+use rustfs_utils::http::headers::RESERVED_METADATA_PREFIX_LOWER;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{ErrorKind, SeekFrom};
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Sidecar metadata lives under `<drive>/.rustfs.sys/fs/<bucket>/<key>.meta.json`.
+const FS_META_DIR: &str = "fs";
+const FS_META_SUFFIX: &str = ".meta.json";
+
+const DEFAULT_MAX_KEYS: i32 = 1000;
+
+/// Smaller objects are read, mapping them costs more than the copy it saves.
+const MIN_MAPPED_SIZE: i64 = 64 * 1024;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FsObjectMeta {
+    etag: String,
+    size: i64,
+    actual_size: i64,
+    mod_time_nanos: i64,
+    #[serde(default)]
+    user_defined: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FsObjects {
+    root: PathBuf,
+}
+
+/// Map `file` into memory when it holds exactly `size` bytes, None when it cannot be mapped.
+///
+/// Objects are replaced by renaming a new file over the old one and never written in place, so a
+/// mapping keeps the content it was made with for as long as it is held.
+pub fn map_file(file: &std::fs::File, size: usize) -> Option<Bytes> {
+    // SAFETY: the file is never truncated or modified while mapped, see above
+    let map = unsafe { memmap2::Mmap::map(file) }.ok()?;
+    if map.len() != size {
+        return None;
+    }
+    Some(Bytes::from_owner(map))
+}
+
+fn not_found(bucket: &str, object: &str) -> Error {
+    StorageError::ObjectNotFound(bucket.to_owned(), object.to_owned())
+}
+
+impl FsObjects {
+    /// Serve objects from the drive mounted at `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn bucket_path(&self, bucket: &str) -> PathBuf {
+        self.root.join(bucket)
+    }
+
+    /// Path of `object`, rejecting keys a filesystem cannot represent.
+    fn object_path(&self, bucket: &str, object: &str) -> Result<PathBuf> {
+        let trimmed = object.strip_suffix('/').unwrap_or(object);
+        if trimmed.is_empty() || trimmed.split('/').any(|c| c.is_empty() || c == "." || c == "..") || object.contains('\\') {
+            return Err(StorageError::ObjectNameInvalid(bucket.to_owned(), object.to_owned()));
+        }
+        Ok(self.bucket_path(bucket).join(trimmed))
+    }
+
+    fn meta_path(&self, bucket: &str, object: &str) -> PathBuf {
+        self.root
+            .join(RUSTFS_META_BUCKET)
+            .join(FS_META_DIR)
+            .join(bucket)
+            .join(format!("{object}{FS_META_SUFFIX}"))
+    }
+
+    fn tmp_path(&self) -> PathBuf {
+        self.root.join(RUSTFS_META_TMP_BUCKET).join(format!("fs-{}", Uuid::new_v4()))
+    }
+
+    async fn check_bucket(&self, bucket: &str) -> Result<()> {
+        match fs::metadata(self.bucket_path(bucket)).await {
+            Ok(meta) if meta.is_dir() => Ok(()),
+            _ => Err(StorageError::BucketNotFound(bucket.to_owned())),
+        }
+    }
+
+    async fn write_meta(&self, bucket: &str, object: &str, meta: &FsObjectMeta) -> Result<()> {
+        let data = serde_json::to_vec(meta).map_err(Error::other)?;
+        let tmp = self.tmp_path();
+        if let Some(parent) = tmp.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&tmp, data).await?;
+
+        let path = self.meta_path(bucket, object);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    /// Sidecar metadata of `object`, or metadata derived from the file when it has none.
+    async fn read_meta(&self, bucket: &str, object: &str) -> Result<(FsObjectMeta, bool)> {
+        let path = self.object_path(bucket, object)?;
+        let stat = match fs::metadata(&path).await {
+            Ok(stat) => stat,
+            Err(err) if err.kind() == ErrorKind::NotFound || err.kind() == ErrorKind::NotADirectory => {
+                return Err(not_found(bucket, object));
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let is_dir = object.ends_with('/');
+        if stat.is_dir() != is_dir {
+            return Err(not_found(bucket, object));
+        }
+
+        if let Ok(data) = fs::read(self.meta_path(bucket, object)).await
+            && let Ok(meta) = serde_json::from_slice::<FsObjectMeta>(&data)
+        {
+            return Ok((meta, is_dir));
+        }
+
+        // Only directories created as objects are objects, plain parents of files are not
+        if is_dir && fs::read_dir(&path).await?.next_entry().await?.is_some() {
+            return Err(not_found(bucket, object));
+        }
+
+        let mod_time_nanos = stat
+            .modified()
+            .ok()
+            .map(|t| OffsetDateTime::from(t).unix_timestamp_nanos() as i64)
+            .unwrap_or_default();
+        let size = if is_dir { 0 } else { stat.len() as i64 };
+        Ok((
+            FsObjectMeta {
+                etag: format!("{:016x}{:016x}", mod_time_nanos, size),
+                size,
+                actual_size: size,
+                mod_time_nanos,
+                user_defined: HashMap::new(),
+            },
+            is_dir,
+        ))
+    }
+
+    fn object_info(bucket: &str, object: &str, meta: FsObjectMeta, is_dir: bool) -> ObjectInfo {
+        ObjectInfo {
+            bucket: bucket.to_owned(),
+            name: object.to_owned(),
+            mod_time: OffsetDateTime::from_unix_timestamp_nanos(meta.mod_time_nanos as i128).ok(),
+            size: meta.size,
+            actual_size: meta.actual_size,
+            is_dir,
+            content_type: meta.user_defined.get("content-type").cloned(),
+            content_encoding: meta.user_defined.get("content-encoding").cloned(),
+            etag: Some(meta.etag),
+            user_defined: meta.user_defined,
+            is_latest: true,
+            num_versions: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Remove the directories left empty between `path` and `stop`, `stop` itself excluded.
+    async fn remove_empty_parents(path: &Path, stop: &Path) {
+        let mut dir = path.parent();
+        while let Some(d) = dir {
+            if d == stop || !d.starts_with(stop) || fs::remove_dir(d).await.is_err() {
+                break;
+            }
+            dir = d.parent();
+        }
+    }
+
+    /// All object keys of `bucket` under the directory of `prefix`, sorted.
+    fn walk_keys(bucket_dir: &Path, meta_dir: &Path, prefix: &str) -> Vec<String> {
+        let base = prefix.rfind('/').map(|i| &prefix[..=i]).unwrap_or("");
+        let mut keys = Vec::new();
+        let mut stack = vec![base.to_owned()];
+
+        while let Some(dir_key) = stack.pop() {
+            let Ok(entries) = std::fs::read_dir(bucket_dir.join(&dir_key)) else {
+                continue;
+            };
+            let mut empty = true;
+            for entry in entries.flatten() {
+                empty = false;
+                let Ok(name) = entry.file_name().into_string() else {
+                    continue;
+                };
+                let key = format!("{dir_key}{name}");
+                match entry.file_type() {
+                    Ok(ft) if ft.is_dir() => stack.push(format!("{key}/")),
+                    Ok(ft) if ft.is_file() => keys.push(key),
+                    _ => {}
+                }
+            }
+            if !dir_key.is_empty() && dir_key != base && (empty || meta_dir.join(format!("{dir_key}{FS_META_SUFFIX}")).is_file())
+            {
+                keys.push(dir_key);
+            }
+        }
+
+        keys.retain(|k| k.starts_with(prefix));
+        keys.sort();
+        keys
+    }
+}
+
+/// Page through sorted `keys` the way ListObjectsV2 does: keys after `marker`, rolled up into
+/// common prefixes at `delimiter`, at most `max_keys` entries in total. Returns the object keys,
+/// the common prefixes, and the marker to continue from when more remain.
+pub(crate) fn paginate_keys(
+    keys: &[String],
+    prefix: &str,
+    marker: Option<&str>,
+    delimiter: Option<&str>,
+    max_keys: usize,
+) -> (Vec<String>, Vec<String>, Option<String>) {
+    let mut objects = Vec::new();
+    let mut prefixes: Vec<String> = Vec::new();
+    let mut last = None;
+
+    for key in keys {
+        if marker.is_some_and(|m| key.as_str() <= m) {
+            continue;
+        }
+
+        let entry = match delimiter.filter(|d| !d.is_empty()) {
+            Some(d) => match key[prefix.len()..].find(d) {
+                Some(i) => {
+                    let common = &key[..prefix.len() + i + d.len()];
+                    if prefixes.last().is_some_and(|p| p == common) || marker.is_some_and(|m| common <= m) {
+                        continue;
+                    }
+                    Err(common.to_owned())
+                }
+                None => Ok(key.clone()),
+            },
+            None => Ok(key.clone()),
+        };
+
+        if objects.len() + prefixes.len() >= max_keys {
+            return (objects, prefixes, last);
+        }
+
+        match entry {
+            Ok(key) => {
+                last = Some(key.clone());
+                objects.push(key);
+            }
+            Err(common) => {
+                last = Some(common.clone());
+                prefixes.push(common);
+            }
+        }
+    }
+
+    (objects, prefixes, None)
+}
+
+#[async_trait::async_trait]
+impl ObjectLayer for FsObjects {
+    async fn get_object_reader(
+        &self,
+        bucket: &str,
+        object: &str,
+        range: Option<HTTPRangeSpec>,
+        h: HeaderMap,
+        opts: &ObjectOptions,
+    ) -> Result<GetObjectReader> {
+        let oi = self.get_object_info(bucket, object, opts).await?;
+        if oi.is_dir {
+            let (reader, _, _) = GetObjectReader::new(Box::new(tokio::io::empty()), None, &oi, opts, &h)?;
+            return Ok(reader);
+        }
+
+        // Compressed objects are read whole, the decompressing reader skips to the range itself
+        let (off, length) = match &range {
+            Some(rs) if !oi.is_compressed() => rs.get_offset_length(oi.size)?,
+            _ => (0, oi.size),
+        };
+
+        let mut file = fs::File::open(self.object_path(bucket, object)?).await.map_err(|err| {
+            if err.kind() == ErrorKind::NotFound {
+                not_found(bucket, object)
+            } else {
+                err.into()
+            }
+        })?;
+
+        if range.is_none() && opts.part_number.is_none() && !oi.is_compressed() && oi.size >= MIN_MAPPED_SIZE {
+            let std_file = file.into_std().await;
+            if let Some(data) = map_file(&std_file, oi.size as usize) {
+                return Ok(GetObjectReader::from_bytes(data, oi));
+            }
+            file = fs::File::from_std(std_file);
+        }
+
+        file.seek(SeekFrom::Start(off as u64)).await?;
+
+        let (reader, _, _) = GetObjectReader::new(Box::new(file.take(length.max(0) as u64)), range, &oi, opts, &h)?;
+        Ok(reader)
+    }
+
+    async fn put_object(&self, bucket: &str, object: &str, data: &mut PutObjReader, opts: &ObjectOptions) -> Result<ObjectInfo> {
+        self.check_bucket(bucket).await?;
+        let path = self.object_path(bucket, object)?;
+        let is_dir = object.ends_with('/');
+
+        let size = if is_dir {
+            fs::create_dir_all(&path)
+                .await
+                .map_err(|_| StorageError::ObjectExistsAsDirectory(bucket.to_owned(), object.to_owned()))?;
+            0
+        } else {
+            if fs::metadata(&path).await.is_ok_and(|m| m.is_dir()) {
+                return Err(StorageError::ObjectExistsAsDirectory(bucket.to_owned(), object.to_owned()));
+            }
+
+            let tmp = self.tmp_path();
+            if let Some(parent) = tmp.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            let written = async {
+                let mut file = fs::File::create(&tmp).await?;
+                let written = tokio::io::copy(&mut data.stream, &mut file).await?;
+                file.flush().await?;
+                file.sync_all().await?;
+                Ok::<_, std::io::Error>(written as i64)
+            }
+            .await;
+            let written = match written {
+                Ok(written) if written >= data.size() => written,
+                Ok(written) => {
+                    let _ = fs::remove_file(&tmp).await;
+                    return Err(Error::other(format!("incomplete body, got {written} of {} bytes", data.size())));
+                }
+                Err(err) => {
+                    let _ = fs::remove_file(&tmp).await;
+                    return Err(err.into());
+                }
+            };
+
+            let placed = async {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).await?;
+                }
+                fs::rename(&tmp, &path).await
+            }
+            .await;
+            if let Err(err) = placed {
+                let _ = fs::remove_file(&tmp).await;
+                warn!("place {}/{} failed: {}", bucket, object, err);
+                // Some parent of the key is an object already
+                return Err(StorageError::ObjectExistsAsDirectory(bucket.to_owned(), object.to_owned()));
+            }
+            written
+        };
+
+        let mut user_defined = opts.user_defined.clone();
+        if user_defined.contains_key(&format!("{RESERVED_METADATA_PREFIX_LOWER}compression")) {
+            user_defined.insert(format!("{RESERVED_METADATA_PREFIX_LOWER}compression-size"), size.to_string());
+        }
+        let etag = data.stream.try_resolve_etag().unwrap_or_default();
+        user_defined.insert("etag".to_owned(), etag.clone());
+
+        let actual_size = if data.actual_size() >= 0 { data.actual_size() } else { size };
+        let mod_time = opts.mod_time.unwrap_or_else(OffsetDateTime::now_utc);
+        let meta = FsObjectMeta {
+            etag,
+            size,
+            actual_size,
+            mod_time_nanos: mod_time.unix_timestamp_nanos() as i64,
+            user_defined,
+        };
+        self.write_meta(bucket, object, &meta).await?;
+
+        Ok(Self::object_info(bucket, object, meta, is_dir))
+    }
+
+    async fn get_object_info(&self, bucket: &str, object: &str, _opts: &ObjectOptions) -> Result<ObjectInfo> {
+        self.check_bucket(bucket).await?;
+        let (meta, is_dir) = self.read_meta(bucket, object).await?;
+        Ok(Self::object_info(bucket, object, meta, is_dir))
+    }
+
+    async fn copy_object(
+        &self,
+        src_bucket: &str,
+        src_object: &str,
+        dst_bucket: &str,
+        dst_object: &str,
+        src_info: &mut ObjectInfo,
+        dst_opts: &ObjectOptions,
+    ) -> Result<ObjectInfo> {
+        if src_bucket == dst_bucket && src_object == dst_object {
+            let (mut meta, is_dir) = self.read_meta(src_bucket, src_object).await?;
+            let etag = meta.user_defined.get("etag").cloned();
+            meta.user_defined = src_info.user_defined.clone();
+            if let Some(etag) = etag {
+                meta.user_defined.insert("etag".to_owned(), etag);
+            }
+            if let Some(mod_time) = dst_opts.mod_time {
+                meta.mod_time_nanos = mod_time.unix_timestamp_nanos() as i64;
+            }
+            self.write_meta(dst_bucket, dst_object, &meta).await?;
+            return Ok(Self::object_info(dst_bucket, dst_object, meta, is_dir));
+        }
+
+        let put_opts = ObjectOptions {
+            user_defined: src_info.user_defined.clone(),
+            mod_time: dst_opts.mod_time,
+            ..Default::default()
+        };
+        let Some(reader) = src_info.put_object_reader.as_mut() else {
+            return Err(StorageError::InvalidArgument(
+                src_bucket.to_owned(),
+                src_object.to_owned(),
+                "put_object_reader is none".to_owned(),
+            ));
+        };
+        self.put_object(dst_bucket, dst_object, reader, &put_opts).await
+    }
+
+    async fn delete_object(&self, bucket: &str, object: &str, _opts: &ObjectOptions) -> Result<ObjectInfo> {
+        self.check_bucket(bucket).await?;
+        let (meta, is_dir) = self.read_meta(bucket, object).await?;
+        let path = self.object_path(bucket, object)?;
+
+        if is_dir {
+            // A directory object with objects below it only loses its own metadata
+            let _ = fs::remove_dir(&path).await;
+        } else {
+            match fs::remove_file(&path).await {
+                Ok(()) => {}
+                Err(err) if err.kind() == ErrorKind::NotFound => return Err(not_found(bucket, object)),
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Self::remove_empty_parents(&path, &self.bucket_path(bucket)).await;
+
+        let meta_path = self.meta_path(bucket, object);
+        if fs::remove_file(&meta_path).await.is_ok() {
+            let meta_bucket = self.root.join(RUSTFS_META_BUCKET).join(FS_META_DIR).join(bucket);
+            Self::remove_empty_parents(&meta_path, &meta_bucket).await;
+        }
+
+        Ok(Self::object_info(bucket, object, meta, is_dir))
+    }
+
+    async fn list_objects_v2(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        continuation_token: Option<String>,
+        delimiter: Option<String>,
+        max_keys: i32,
+        start_after: Option<String>,
+    ) -> Result<ListObjectsV2Info> {
+        self.check_bucket(bucket).await?;
+
+        let bucket_dir = self.bucket_path(bucket);
+        let meta_dir = self.root.join(RUSTFS_META_BUCKET).join(FS_META_DIR).join(bucket);
+        let walk_prefix = prefix.to_owned();
+        let keys = tokio::task::spawn_blocking(move || Self::walk_keys(&bucket_dir, &meta_dir, &walk_prefix))
+            .await
+            .map_err(Error::other)?;
+
+        let marker = continuation_token.clone().or(start_after);
+        let max_keys = if max_keys < 0 { DEFAULT_MAX_KEYS } else { max_keys } as usize;
+        let (names, prefixes, next_marker) = paginate_keys(&keys, prefix, marker.as_deref(), delimiter.as_deref(), max_keys);
+
+        let mut objects = Vec::with_capacity(names.len());
+        for name in names {
+            match self.read_meta(bucket, &name).await {
+                Ok((meta, is_dir)) => objects.push(Self::object_info(bucket, &name, meta, is_dir)),
+                // Removed while listing
+                Err(StorageError::ObjectNotFound(_, _)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(ListObjectsV2Info {
+            is_truncated: next_marker.is_some(),
+            continuation_token,
+            next_continuation_token: next_marker,
+            objects,
+            prefixes,
+        })
+    }
+
+    async fn delete_bucket(&self, bucket: &str) -> Result<()> {
+        let meta_dir = self.root.join(RUSTFS_META_BUCKET).join(FS_META_DIR).join(bucket);
+        match fs::remove_dir_all(&meta_dir).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(list: &[&str]) -> Vec<String> {
+        list.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn test_paginate_keys() {
+        let all = keys(&["a/1", "a/2", "b", "c/d/e", "c/f"]);
+
+        let (objects, prefixes, next) = paginate_keys(&all, "", None, Some("/"), 10);
+        assert_eq!(objects, keys(&["b"]));
+        assert_eq!(prefixes, keys(&["a/", "c/"]));
+        assert_eq!(next, None);
+
+        let (objects, prefixes, next) = paginate_keys(&all, "", None, Some("/"), 2);
+        assert_eq!((objects, prefixes), (keys(&["b"]), keys(&["a/"])));
+        assert_eq!(next.as_deref(), Some("b"));
+        let (objects, prefixes, next) = paginate_keys(&all, "", Some("b"), Some("/"), 2);
+        assert_eq!((objects, prefixes, next), (vec![], keys(&["c/"]), None));
+
+        let (objects, _, next) = paginate_keys(&all, "", Some("a/2"), None, 2);
+        assert_eq!(objects, keys(&["b", "c/d/e"]));
+        assert_eq!(next.as_deref(), Some("c/d/e"));
+    }
+
+    #[tokio::test]
+    async fn test_fs_objects_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let layer = FsObjects::new(dir.path());
+        std::fs::create_dir_all(dir.path().join("bucket")).unwrap();
+        let opts = ObjectOptions::default();
+
+        let mut data = PutObjReader::from_vec(b"hello world".to_vec());
+        let oi = layer.put_object("bucket", "dir/a.txt", &mut data, &opts).await.unwrap();
+        assert_eq!(oi.size, 11);
+        assert_eq!(std::fs::read(dir.path().join("bucket/dir/a.txt")).unwrap(), b"hello world");
+
+        let mut reader = layer
+            .get_object_reader(
+                "bucket",
+                "dir/a.txt",
+                Some(HTTPRangeSpec {
+                    is_suffix_length: false,
+                    start: 6,
+                    end: 10,
+                }),
+                HeaderMap::new(),
+                &opts,
+            )
+            .await
+            .unwrap();
+        assert_eq!(reader.read_all().await.unwrap(), b"world");
+
+        // Files placed on the drive directly are objects as well
+        std::fs::write(dir.path().join("bucket/plain"), b"abc").unwrap();
+        let info = layer.get_object_info("bucket", "plain", &opts).await.unwrap();
+        assert_eq!(info.size, 3);
+
+        let list = layer
+            .list_objects_v2("bucket", "", None, Some("/".to_string()), 1000, None)
+            .await
+            .unwrap();
+        assert_eq!(list.prefixes, keys(&["dir/"]));
+        assert_eq!(list.objects.iter().map(|o| o.name.as_str()).collect::<Vec<_>>(), vec!["plain"]);
+
+        let mut data = PutObjReader::from_vec(b"x".to_vec());
+        assert!(matches!(
+            layer.put_object("bucket", "dir", &mut data, &opts).await,
+            Err(StorageError::ObjectExistsAsDirectory(_, _))
+        ));
+
+        layer.delete_object("bucket", "dir/a.txt", &opts).await.unwrap();
+        assert!(!dir.path().join("bucket/dir").exists());
+        assert!(matches!(
+            layer.get_object_info("bucket", "dir/a.txt", &opts).await,
+            Err(StorageError::ObjectNotFound(_, _))
+        ));
+        assert!(matches!(
+            layer.get_object_info("bucket", "../x", &opts).await,
+            Err(StorageError::ObjectNameInvalid(_, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fs_objects_mapped_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let layer = FsObjects::new(dir.path());
+        std::fs::create_dir_all(dir.path().join("bucket")).unwrap();
+        let opts = ObjectOptions::default();
+
+        let content = vec![7u8; MIN_MAPPED_SIZE as usize];
+        let mut data = PutObjReader::from_vec(content.clone());
+        layer.put_object("bucket", "big", &mut data, &opts).await.unwrap();
+
+        let mut reader = layer
+            .get_object_reader("bucket", "big", None, HeaderMap::new(), &opts)
+            .await
+            .unwrap();
+        let body = reader.body.clone().unwrap();
+        assert_eq!(body, content);
+        assert_eq!(reader.read_all().await.unwrap(), content);
+
+        // Overwriting replaces the file, the mapping keeps the old content
+        let mut data = PutObjReader::from_vec(vec![8u8; MIN_MAPPED_SIZE as usize]);
+        layer.put_object("bucket", "big", &mut data, &opts).await.unwrap();
+        assert_eq!(body, content);
+
+        let range = HTTPRangeSpec {
+            is_suffix_length: false,
+            start: 0,
+            end: 9,
+        };
+        let reader = layer
+            .get_object_reader("bucket", "big", Some(range), HeaderMap::new(), &opts)
+            .await
+            .unwrap();
+        assert!(reader.body.is_none());
+    }
+}
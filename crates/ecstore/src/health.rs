@@ -0,0 +1,263 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Erasure set and peer health, backing the readiness and cluster health probes.
+
+use crate::disk::DiskAPI;
+use crate::maintenance::GLOBAL_MAINTENANCE_SYS;
+use crate::store::ECStore;
+use futures::future::join_all;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HealthOptions {
+    /// Evaluate the cluster as if the drives of this node and of the nodes already in maintenance
+    /// were offline, to tell whether the node can be taken down without losing quorum.
+    pub maintenance: bool,
+    /// Only evaluate the erasure sets that have a drive on this node.
+    pub local_only: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SetHealth {
+    pub pool_index: usize,
+    pub set_index: usize,
+    pub online_drives: usize,
+    pub total_drives: usize,
+    pub read_quorum: usize,
+    pub write_quorum: usize,
+}
+
+impl SetHealth {
+    pub fn has_read_quorum(&self) -> bool {
+        self.online_drives >= self.read_quorum
+    }
+
+    pub fn has_write_quorum(&self) -> bool {
+        self.online_drives >= self.write_quorum
+    }
+}
+
+/// A node, online when any of its drives answers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PeerHealth {
+    pub host: String,
+    pub online: bool,
+    pub online_drives: usize,
+    pub total_drives: usize,
+    /// The node is in maintenance mode and draining.
+    pub maintenance: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HealthResult {
+    /// Every evaluated set has write quorum.
+    pub healthy: bool,
+    /// Every evaluated set has read quorum.
+    pub healthy_read: bool,
+    pub maintenance: bool,
+    /// This node is in maintenance mode and should not receive new requests.
+    pub draining: bool,
+    pub sets: Vec<SetHealth>,
+    pub peers: Vec<PeerHealth>,
+}
+
+/// State of one drive slot of an erasure set.
+#[derive(Debug, Clone)]
+struct DriveState {
+    host: String,
+    is_local: bool,
+    online: bool,
+    in_maintenance: bool,
+}
+
+impl DriveState {
+    fn counts_online(&self, maintenance: bool) -> bool {
+        self.online && !(maintenance && (self.is_local || self.in_maintenance))
+    }
+}
+
+fn evaluate(sets: Vec<(SetHealth, Vec<DriveState>)>, maintenance: bool) -> HealthResult {
+    let mut peers: BTreeMap<String, PeerHealth> = BTreeMap::new();
+    let mut result = HealthResult {
+        healthy: true,
+        healthy_read: true,
+        maintenance,
+        ..Default::default()
+    };
+
+    for (mut set, drives) in sets {
+        set.total_drives = drives.len();
+        set.online_drives = drives.iter().filter(|d| d.counts_online(maintenance)).count();
+
+        for drive in drives {
+            let peer = peers.entry(drive.host.clone()).or_insert_with(|| PeerHealth {
+                host: drive.host.clone(),
+                maintenance: drive.in_maintenance,
+                ..Default::default()
+            });
+            peer.total_drives += 1;
+            if drive.counts_online(maintenance) {
+                peer.online_drives += 1;
+                peer.online = true;
+            }
+        }
+
+        result.healthy &= set.has_write_quorum();
+        result.healthy_read &= set.has_read_quorum();
+        result.sets.push(set);
+    }
+
+    result.peers = peers.into_values().collect();
+    result
+}
+
+impl ECStore {
+    /// Evaluate the quorum of every erasure set and the reachability of every node.
+    pub async fn health(&self, opts: HealthOptions) -> HealthResult {
+        let mut sets = Vec::new();
+        for pool in self.pools.iter() {
+            for set in pool.disk_set.iter() {
+                if opts.local_only && !set.set_endpoints.iter().any(|ep| ep.is_local) {
+                    continue;
+                }
+
+                let disks = set.disks.read().await.clone();
+                let online = join_all(disks.iter().map(|disk| async move {
+                    match disk {
+                        Some(disk) => disk.is_online().await,
+                        None => false,
+                    }
+                }))
+                .await;
+
+                let drives = set
+                    .set_endpoints
+                    .iter()
+                    .zip(online)
+                    .map(|(ep, online)| DriveState {
+                        host: ep.host_port(),
+                        is_local: ep.is_local,
+                        online,
+                        in_maintenance: GLOBAL_MAINTENANCE_SYS.is_endpoint_in_maintenance(ep),
+                    })
+                    .collect();
+
+                let health = SetHealth {
+                    pool_index: set.pool_index,
+                    set_index: set.set_index,
+                    read_quorum: set.default_read_quorum(),
+                    write_quorum: set.default_write_quorum(),
+                    ..Default::default()
+                };
+                sets.push((health, drives));
+            }
+        }
+
+        let mut result = evaluate(sets, opts.maintenance);
+        result.draining = GLOBAL_MAINTENANCE_SYS.is_local_node_in_maintenance();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drive(host: &str, is_local: bool, online: bool) -> DriveState {
+        DriveState {
+            host: host.to_string(),
+            is_local,
+            online,
+            in_maintenance: false,
+        }
+    }
+
+    fn set(read_quorum: usize, write_quorum: usize) -> SetHealth {
+        SetHealth {
+            read_quorum,
+            write_quorum,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_evaluate_quorum() {
+        let drives = vec![
+            drive("n1:9000", true, true),
+            drive("n2:9000", false, true),
+            drive("n3:9000", false, true),
+            drive("n4:9000", false, false),
+        ];
+
+        let result = evaluate(vec![(set(2, 3), drives.clone())], false);
+        assert!(result.healthy);
+        assert!(result.healthy_read);
+        assert_eq!(result.sets[0].online_drives, 3);
+        assert_eq!(result.peers.len(), 4);
+        assert!(!result.peers.iter().find(|p| p.host == "n4:9000").unwrap().online);
+
+        // Taking n1 down would leave only two drives: readable but not writable
+        let result = evaluate(vec![(set(2, 3), drives)], true);
+        assert!(!result.healthy);
+        assert!(result.healthy_read);
+        assert!(result.maintenance);
+        assert!(!result.peers.iter().find(|p| p.host == "n1:9000").unwrap().online);
+    }
+
+    #[test]
+    fn test_evaluate_peer_online_with_any_drive() {
+        let drives = vec![drive("n1:9000", false, false), drive("n1:9000", false, true)];
+        let result = evaluate(vec![(set(1, 2), drives)], false);
+        assert!(!result.healthy);
+        assert_eq!(
+            result.peers,
+            vec![PeerHealth {
+                host: "n1:9000".to_string(),
+                online: true,
+                online_drives: 1,
+                total_drives: 2,
+                maintenance: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_counts_peers_in_maintenance_as_gone() {
+        let mut drives = vec![
+            drive("n1:9000", true, true),
+            drive("n2:9000", false, true),
+            drive("n3:9000", false, true),
+            drive("n4:9000", false, true),
+        ];
+        drives[1].in_maintenance = true;
+
+        let result = evaluate(vec![(set(2, 3), drives.clone())], false);
+        assert!(result.healthy);
+        assert!(result.peers.iter().find(|p| p.host == "n2:9000").unwrap().maintenance);
+
+        // n2 is already draining, so n1 cannot be taken down as well
+        let result = evaluate(vec![(set(2, 3), drives)], true);
+        assert!(!result.healthy);
+        assert_eq!(result.sets[0].online_drives, 2);
+    }
+
+    #[test]
+    fn test_evaluate_empty_is_healthy() {
+        let result = evaluate(Vec::new(), false);
+        assert!(result.healthy);
+        assert!(result.healthy_read);
+    }
+}
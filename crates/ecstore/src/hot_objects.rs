@@ -0,0 +1,315 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! In-memory copies of hot objects.
+//!
+//! An object the lock manager of its pool sees read `RUSTFS_HOT_OBJECT_THRESHOLD` times within
+//! `RUSTFS_HOT_OBJECT_WINDOW` is read whole into memory once, concurrent GETs wait for that read,
+//! and later GETs are served from the copy instead of reading and decoding its shards. Every node
+//! holds its own copies of the objects it sees hot, so a flash crowd spread over the nodes is served
+//! from memory on each of them. The cache is disabled unless `RUSTFS_HOT_OBJECT_CACHE_SIZE` is set.
+//!
+//! GETs still read the object metadata, a copy is only served while it belongs to the version,
+//! modification time and data directory the metadata names. A write to an object drops the copy
+//! of this node and, when this node held one, tells the other nodes to drop theirs.
+
+use crate::error::{Error, Result};
+use crate::notification_sys::get_global_notification_sys;
+use crate::rpc::build_auth_headers;
+use bytes::Bytes;
+use futures::future::join_all;
+use http::{HeaderMap, Method};
+use moka::future::Cache;
+use rustfs_config::{
+    DEFAULT_HOT_OBJECT_CACHE_SIZE, DEFAULT_HOT_OBJECT_MAX_SIZE, DEFAULT_HOT_OBJECT_THRESHOLD, DEFAULT_HOT_OBJECT_WINDOW,
+    ENV_HOT_OBJECT_CACHE_SIZE, ENV_HOT_OBJECT_MAX_SIZE, ENV_HOT_OBJECT_THRESHOLD, ENV_HOT_OBJECT_WINDOW,
+};
+use rustfs_filemeta::FileInfo;
+use rustfs_lock::LockConfig;
+use rustfs_utils::get_env_u64;
+use std::future::Future;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use time::OffsetDateTime;
+use tracing::debug;
+use uuid::Uuid;
+
+pub static GLOBAL_HOT_OBJECTS: LazyLock<HotObjectCache> = LazyLock::new(|| {
+    HotObjectCache::new(
+        get_env_u64(ENV_HOT_OBJECT_CACHE_SIZE, DEFAULT_HOT_OBJECT_CACHE_SIZE) << 20,
+        get_env_u64(ENV_HOT_OBJECT_MAX_SIZE, DEFAULT_HOT_OBJECT_MAX_SIZE) << 20,
+        get_env_u64(ENV_HOT_OBJECT_THRESHOLD, DEFAULT_HOT_OBJECT_THRESHOLD).min(u32::MAX as u64) as u32,
+        Duration::from_secs(get_env_u64(ENV_HOT_OBJECT_WINDOW, DEFAULT_HOT_OBJECT_WINDOW)),
+    )
+});
+
+const PEER_TIMEOUT: Duration = Duration::from_secs(5);
+
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct HotObjectKey {
+    bucket: String,
+    object: String,
+}
+
+/// The whole of one object version as stored, with what identifies the write it came from.
+#[derive(Debug)]
+struct HotObject {
+    version_id: Option<Uuid>,
+    mod_time: Option<OffsetDateTime>,
+    data_dir: Option<Uuid>,
+    data: Bytes,
+}
+
+impl HotObject {
+    fn matches(&self, fi: &FileInfo) -> bool {
+        self.version_id == fi.version_id && self.mod_time == fi.mod_time && self.data_dir == fi.data_dir
+    }
+}
+
+pub struct HotObjectCache {
+    objects: Option<Cache<HotObjectKey, Arc<HotObject>>>,
+    max_object_size: u64,
+    threshold: u32,
+    window: Duration,
+}
+
+impl HotObjectCache {
+    pub fn new(capacity: u64, max_object_size: u64, threshold: u32, window: Duration) -> Self {
+        let enabled = capacity > 0 && max_object_size > 0 && threshold > 0 && !window.is_zero();
+        let objects = enabled.then(|| {
+            Cache::builder()
+                .max_capacity(capacity)
+                .weigher(|_: &HotObjectKey, object: &Arc<HotObject>| object.data.len().try_into().unwrap_or(u32::MAX))
+                .build()
+        });
+
+        Self {
+            objects,
+            max_object_size,
+            threshold,
+            window,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.objects.is_some()
+    }
+
+    /// Config of the lock managers, tracking hot objects when the cache is enabled.
+    pub fn lock_config(&self) -> LockConfig {
+        let mut config = LockConfig::default();
+        if self.is_enabled() {
+            config.hot_key_threshold = self.threshold;
+            config.hot_key_window = self.window;
+        }
+        config
+    }
+
+    /// Whether an object stored as `size` bytes may be held in memory.
+    pub fn fits(&self, size: i64) -> bool {
+        self.is_enabled() && size > 0 && (size as u64) <= self.max_object_size
+    }
+
+    /// The stored bytes of the version `fi` of `bucket/object`, read with `load` unless this node
+    /// holds them already. None when `load` fails, the caller then reads the object as usual.
+    pub async fn get_or_load<F>(&self, bucket: &str, object: &str, fi: &FileInfo, load: F) -> Option<Bytes>
+    where
+        F: Future<Output = Result<Bytes>>,
+    {
+        let objects = self.objects.as_ref()?;
+        let key = HotObjectKey {
+            bucket: bucket.to_owned(),
+            object: object.to_owned(),
+        };
+
+        if let Some(held) = objects.get(&key).await {
+            if held.matches(fi) {
+                return Some(held.data.clone());
+            }
+            objects.invalidate(&key).await;
+        }
+
+        let expected = fi.size;
+        let loaded = objects
+            .try_get_with(key.clone(), async {
+                let data = load.await?;
+                if data.len() as i64 != expected {
+                    return Err(Error::other(format!("read {} bytes of {expected}", data.len())));
+                }
+                Ok::<_, Error>(Arc::new(HotObject {
+                    version_id: fi.version_id,
+                    mod_time: fi.mod_time,
+                    data_dir: fi.data_dir,
+                    data,
+                }))
+            })
+            .await;
+
+        match loaded {
+            // A GET of another version may have loaded it meanwhile
+            Ok(held) if held.matches(fi) => Some(held.data.clone()),
+            Ok(_) => None,
+            Err(err) => {
+                debug!("load hot object {bucket}/{object} err {:?}", err);
+                None
+            }
+        }
+    }
+
+    /// Drop the copy of `bucket/object` held by this node, an empty `object` drops the whole bucket.
+    /// Returns whether a copy was dropped.
+    pub async fn invalidate(&self, bucket: &str, object: &str) -> bool {
+        let Some(objects) = self.objects.as_ref() else {
+            return false;
+        };
+
+        if object.is_empty() {
+            let keys: Vec<_> = objects
+                .iter()
+                .filter(|(key, _)| key.bucket == bucket)
+                .map(|(key, _)| key.as_ref().clone())
+                .collect();
+            for key in keys.iter() {
+                objects.invalidate(key).await;
+            }
+            return !keys.is_empty();
+        }
+
+        objects
+            .remove(&HotObjectKey {
+                bucket: bucket.to_owned(),
+                object: object.to_owned(),
+            })
+            .await
+            .is_some()
+    }
+
+    /// Drop the copies of `bucket/object` after a write to it, here and, if this node held one, on the
+    /// other nodes.
+    pub async fn object_changed(&self, bucket: &str, object: &str) {
+        if !self.invalidate(bucket, object).await {
+            return;
+        }
+
+        let bucket = bucket.to_owned();
+        let object = object.to_owned();
+        tokio::spawn(async move {
+            invalidate_peers(&bucket, &object).await;
+        });
+    }
+}
+
+async fn invalidate_peer(grid_host: &str, bucket: &str, object: &str) -> Result<()> {
+    let query = serde_urlencoded::to_string([("bucket", bucket), ("object", object)]).map_err(Error::other)?;
+    let url = format!("{grid_host}/rustfs/rpc/hot_object_invalidate?{query}");
+    let mut headers = HeaderMap::new();
+    build_auth_headers(&url, &Method::POST, &mut headers);
+    HTTP_CLIENT
+        .post(&url)
+        .headers(headers)
+        .timeout(PEER_TIMEOUT)
+        .send()
+        .await
+        .map_err(Error::other)?
+        .error_for_status()
+        .map_err(Error::other)?;
+    Ok(())
+}
+
+async fn invalidate_peers(bucket: &str, object: &str) {
+    let Some(sys) = get_global_notification_sys() else {
+        return;
+    };
+    let peers: Vec<(String, String)> = sys
+        .peer_clients
+        .iter()
+        .flatten()
+        .map(|client| (client.host.to_string(), client.grid_host.clone()))
+        .collect();
+
+    let results = join_all(peers.iter().map(|(_, grid_host)| invalidate_peer(grid_host, bucket, object))).await;
+    for ((host, _), result) in peers.into_iter().zip(results) {
+        if let Err(err) = result {
+            debug!("invalidate hot object {bucket}/{object} on {host} err {:?}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_info(mod_time: i64, size: i64) -> FileInfo {
+        FileInfo {
+            mod_time: Some(OffsetDateTime::from_unix_timestamp(mod_time).unwrap()),
+            size,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_or_load() {
+        let cache = HotObjectCache::new(1 << 20, 1 << 10, 1, Duration::from_secs(10));
+        let fi = file_info(1, 4);
+
+        let data = cache
+            .get_or_load("bucket", "hot", &fi, async { Ok(Bytes::from_static(b"data")) })
+            .await;
+        assert_eq!(data.as_deref(), Some(&b"data"[..]));
+
+        // Served from memory while the metadata names the same write
+        let data = cache
+            .get_or_load("bucket", "hot", &fi, async { Err(Error::other("drives read")) })
+            .await;
+        assert_eq!(data.as_deref(), Some(&b"data"[..]));
+
+        // An overwrite seen in the metadata is read again
+        let data = cache
+            .get_or_load("bucket", "hot", &file_info(2, 3), async { Ok(Bytes::from_static(b"new")) })
+            .await;
+        assert_eq!(data.as_deref(), Some(&b"new"[..]));
+
+        // A short read is not kept
+        let data = cache
+            .get_or_load("bucket", "short", &fi, async { Ok(Bytes::from_static(b"da")) })
+            .await;
+        assert!(data.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalidate() {
+        let cache = HotObjectCache::new(1 << 20, 1 << 10, 1, Duration::from_secs(10));
+        let fi = file_info(1, 1);
+        for object in ["a", "b"] {
+            cache
+                .get_or_load("bucket", object, &fi, async { Ok(Bytes::from_static(b"x")) })
+                .await;
+        }
+
+        assert!(cache.invalidate("bucket", "a").await);
+        assert!(!cache.invalidate("bucket", "a").await);
+        assert!(cache.invalidate("bucket", "").await);
+        assert!(!cache.invalidate("bucket", "b").await);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let cache = HotObjectCache::new(0, 1 << 10, 1, Duration::from_secs(10));
+        assert!(!cache.is_enabled());
+        assert!(!cache.fits(1));
+        assert_eq!(cache.lock_config().hot_key_threshold, 0);
+    }
+}
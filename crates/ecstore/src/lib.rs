@@ -16,33 +16,59 @@
 extern crate core;
 
 pub mod admin_server_info;
+pub mod bandwidth;
 pub mod batch_processor;
 pub mod bitrot;
 pub mod bucket;
 pub mod cache_value;
+pub mod capacity_plan;
 mod chunk_stream;
+pub mod compat;
+pub mod compose;
 pub mod compress;
 pub mod config;
 pub mod data_usage;
 pub mod disk;
+pub mod disk_replacement;
 pub mod disks_layout;
+pub mod dns_discovery;
+pub mod drive_health;
 pub mod endpoints;
 pub mod erasure_coding;
 pub mod error;
+pub mod federation;
 pub mod file_cache;
+pub mod format_migration;
+pub mod fs_objects;
 pub mod global;
+pub mod health;
+pub mod hot_objects;
+pub mod list_cache;
+pub mod list_token;
+pub mod listing_metrics;
+pub mod maintenance;
+pub mod mem_objects;
+pub mod memory_budget;
+pub mod metadata_index;
 pub mod metrics_realtime;
 pub mod notification_sys;
+pub mod object_layer;
+pub mod orphaned_data;
 pub mod pools;
+pub mod presign;
 pub mod rebalance;
+pub mod request_io;
 pub mod rpc;
 pub mod set_disk;
 mod sets;
+pub mod speedtest;
 pub mod store;
 pub mod store_api;
 mod store_init;
 pub mod store_list_objects;
 pub mod store_utils;
+pub mod tmp_cleanup;
+pub mod top;
 
 // pub mod checksum;
 pub mod client;
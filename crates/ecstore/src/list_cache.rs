@@ -0,0 +1,599 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cache of listing results, shared between nodes.
+//!
+//! A cached listing holds the metacache entries a walk of the drives returned and is served until
+//! its TTL expires or a write touches an object under its prefix. Writes invalidate the local cache
+//! right away and are batched for the other nodes, which are told every
+//! `RUSTFS_LIST_CACHE_INVALIDATION_INTERVAL` milliseconds, so a peer serves a listing missing a write
+//! for about that long at most. The cache is disabled unless `RUSTFS_LIST_CACHE_TTL` is set.
+//!
+//! The same batches announce the listings a node walked. The other nodes remember the owner of each
+//! listing id and stream its entries from the owner instead of walking the drives again. The owner
+//! only answers for the listing id it still holds, with the protocol version the requester speaks,
+//! anything else is a miss and the requester walks the drives.
+
+use crate::error::{Error, Result};
+use crate::global::get_global_endpoints;
+use crate::notification_sys::get_global_notification_sys;
+use bytes::Bytes;
+use rustfs_config::{
+    DEFAULT_LIST_CACHE_INVALIDATION_INTERVAL, DEFAULT_LIST_CACHE_TTL, ENV_LIST_CACHE_INVALIDATION_INTERVAL, ENV_LIST_CACHE_TTL,
+};
+use rustfs_filemeta::MetaCacheEntry;
+use rustfs_utils::get_env_u64;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+use uuid::Uuid;
+
+pub static GLOBAL_LIST_CACHE: LazyLock<ListCache> =
+    LazyLock::new(|| ListCache::new(Duration::from_secs(get_env_u64(ENV_LIST_CACHE_TTL, DEFAULT_LIST_CACHE_TTL))));
+
+/// Version of the messages nodes exchange about cached listings.
+pub const LIST_CACHE_PROTOCOL_VERSION: u32 = 1;
+
+const MAX_CACHED_LISTINGS: usize = 1024;
+
+/// Pending prefixes of a bucket above which the whole bucket is invalidated instead.
+const MAX_PENDING_PREFIXES: usize = 256;
+
+/// Entries per message when streaming a listing to another node.
+const FETCH_CHUNK_ENTRIES: usize = 256;
+
+/// Arguments a listing result depends on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ListCacheKey {
+    pub bucket: String,
+    pub prefix: String,
+    pub marker: Option<String>,
+    pub delimiter: Option<String>,
+    pub max_keys: i32,
+    pub incl_deleted: bool,
+}
+
+/// Entries of a listing page past its marker, as the drives returned them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CachedListing {
+    pub entries: Vec<MetaCacheEntry>,
+    // The walk reached the end of the prefix.
+    pub eof: bool,
+}
+
+/// Node holding the listing `id` for `key`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListCacheLocation {
+    pub key: ListCacheKey,
+    pub id: String,
+    pub owner: String,
+}
+
+/// Batch sent to the other nodes: prefixes written per bucket, an empty prefix stands for the whole
+/// bucket, and listings walked since the last batch.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListCacheUpdate {
+    pub changes: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub listings: Vec<ListCacheLocation>,
+}
+
+impl ListCacheUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.listings.is_empty()
+    }
+
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(Error::other)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        serde_json::from_slice(buf).map_err(Error::other)
+    }
+}
+
+/// Request for the entries of a listing held by another node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListCacheFetch {
+    pub version: u32,
+    pub id: String,
+    pub key: ListCacheKey,
+}
+
+impl ListCacheFetch {
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        rmp_serde::to_vec(self).map_err(Error::other)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        rmp_serde::from_slice(buf).map_err(Error::other)
+    }
+}
+
+/// One message of a streamed listing, `last` is set on the final one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ListCacheChunk {
+    version: u32,
+    id: String,
+    entries: Vec<MetaCacheEntry>,
+    last: bool,
+    eof: bool,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    id: String,
+    cached_at: Instant,
+    listing: CachedListing,
+}
+
+#[derive(Debug, Default)]
+struct ListCacheState {
+    entries: HashMap<ListCacheKey, CacheEntry>,
+    locations: HashMap<ListCacheKey, (Instant, ListCacheLocation)>,
+    // Bumped by every invalidation, a listing started before one is not cached.
+    generations: HashMap<String, u64>,
+    pending: HashMap<String, BTreeSet<String>>,
+    pending_listings: Vec<ListCacheLocation>,
+}
+
+#[derive(Debug)]
+pub struct ListCache {
+    ttl: Duration,
+    state: Mutex<ListCacheState>,
+    flusher_started: AtomicBool,
+}
+
+fn key_includes(key: &ListCacheKey, bucket: &str, changed: &str) -> bool {
+    key.bucket == bucket && (changed.starts_with(&key.prefix) || key.prefix.starts_with(changed))
+}
+
+/// Name other nodes know this node by, empty unless the deployment is distributed.
+fn local_node() -> String {
+    get_global_endpoints().peers().1
+}
+
+impl ListCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            state: Mutex::new(ListCacheState::default()),
+            flusher_started: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, ListCacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn get(&self, key: &ListCacheKey) -> Option<CachedListing> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let mut state = self.state();
+        match state.entries.get(key) {
+            Some(entry) if entry.cached_at.elapsed() < self.ttl => Some(entry.listing.clone()),
+            Some(_) => {
+                state.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Generation of `bucket`, to be taken before listing and passed to [`ListCache::put`].
+    pub fn generation(&self, bucket: &str) -> u64 {
+        self.state().generations.get(bucket).copied().unwrap_or_default()
+    }
+
+    /// Cache `listing` unless `key.bucket` was invalidated since `generation` was taken. A listing
+    /// walked by this node is `shared`, it is announced to the other nodes with the next batch.
+    pub fn put(&'static self, key: ListCacheKey, generation: u64, listing: CachedListing, shared: bool) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let owner = if shared { local_node() } else { String::new() };
+        {
+            let mut state = self.state();
+            if state.generations.get(&key.bucket).copied().unwrap_or_default() != generation {
+                return;
+            }
+
+            if state.entries.len() >= MAX_CACHED_LISTINGS {
+                let ttl = self.ttl;
+                state.entries.retain(|_, entry| entry.cached_at.elapsed() < ttl);
+            }
+            if state.entries.len() >= MAX_CACHED_LISTINGS
+                && let Some(oldest) = state
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.cached_at)
+                    .map(|(k, _)| k.clone())
+            {
+                state.entries.remove(&oldest);
+            }
+
+            if !owner.is_empty() {
+                state.pending_listings.push(ListCacheLocation {
+                    key: key.clone(),
+                    id: id.clone(),
+                    owner: owner.clone(),
+                });
+            }
+            state.entries.insert(
+                key,
+                CacheEntry {
+                    id,
+                    cached_at: Instant::now(),
+                    listing,
+                },
+            );
+        }
+
+        if !owner.is_empty() {
+            self.start_flusher();
+        }
+    }
+
+    /// Drop the cached listings of `bucket` that may include `changed`, an object name or a prefix.
+    pub fn invalidate(&self, bucket: &str, changed: &str) {
+        let mut state = self.state();
+        *state.generations.entry(bucket.to_owned()).or_default() += 1;
+        state.entries.retain(|key, _| !key_includes(key, bucket, changed));
+        state.locations.retain(|key, _| !key_includes(key, bucket, changed));
+        state.pending_listings.retain(|loc| !key_includes(&loc.key, bucket, changed));
+    }
+
+    /// Apply a batch received from another node.
+    pub fn apply(&self, update: &ListCacheUpdate) {
+        for (bucket, prefixes) in update.changes.iter() {
+            for prefix in prefixes {
+                self.invalidate(bucket, prefix);
+            }
+        }
+
+        if !self.is_enabled() || update.listings.is_empty() {
+            return;
+        }
+
+        let mut state = self.state();
+        for loc in update.listings.iter() {
+            if state.locations.len() >= MAX_CACHED_LISTINGS {
+                let ttl = self.ttl;
+                state.locations.retain(|_, (seen_at, _)| seen_at.elapsed() < ttl);
+            }
+            if state.locations.len() >= MAX_CACHED_LISTINGS {
+                break;
+            }
+            state.locations.insert(loc.key.clone(), (Instant::now(), loc.clone()));
+        }
+    }
+
+    /// Node holding a listing for `key`, if one was announced within the TTL.
+    pub fn locate(&self, key: &ListCacheKey) -> Option<ListCacheLocation> {
+        let mut state = self.state();
+        match state.locations.get(key) {
+            Some((seen_at, loc)) if seen_at.elapsed() < self.ttl => Some(loc.clone()),
+            Some(_) => {
+                state.locations.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn forget(&self, loc: &ListCacheLocation) {
+        let mut state = self.state();
+        if state.locations.get(&loc.key).is_some_and(|(_, known)| known.id == loc.id) {
+            state.locations.remove(&loc.key);
+        }
+    }
+
+    /// Messages answering `fetch` from another node, in order.
+    pub fn serve(&self, fetch: &ListCacheFetch) -> Result<Vec<Bytes>> {
+        if fetch.version != LIST_CACHE_PROTOCOL_VERSION {
+            return Err(Error::other(format!(
+                "list cache protocol version {} not supported, expected {}",
+                fetch.version, LIST_CACHE_PROTOCOL_VERSION
+            )));
+        }
+
+        let listing = {
+            let state = self.state();
+            match state.entries.get(&fetch.key) {
+                Some(entry) if entry.id == fetch.id && entry.cached_at.elapsed() < self.ttl => entry.listing.clone(),
+                _ => return Err(Error::other(format!("listing {} not cached", fetch.id))),
+            }
+        };
+
+        let mut chunks: Vec<Vec<MetaCacheEntry>> = listing
+            .entries
+            .chunks(FETCH_CHUNK_ENTRIES)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+        if chunks.is_empty() {
+            chunks.push(Vec::new());
+        }
+
+        let count = chunks.len();
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, entries)| {
+                let chunk = ListCacheChunk {
+                    version: LIST_CACHE_PROTOCOL_VERSION,
+                    id: fetch.id.clone(),
+                    entries,
+                    last: i + 1 == count,
+                    eof: listing.eof,
+                };
+                rmp_serde::to_vec(&chunk).map(Bytes::from).map_err(Error::other)
+            })
+            .collect()
+    }
+
+    /// Fetch the listing for `key` from the node that walked it, None when no node holds it.
+    pub async fn fetch(&self, key: &ListCacheKey) -> Option<CachedListing> {
+        let loc = self.locate(key)?;
+        let notification_sys = get_global_notification_sys()?;
+
+        let fetch = ListCacheFetch {
+            version: LIST_CACHE_PROTOCOL_VERSION,
+            id: loc.id.clone(),
+            key: key.clone(),
+        };
+        let result = match fetch.marshal() {
+            Ok(opts) => match notification_sys.get_metacache_listing(&loc.owner, Bytes::from(opts)).await {
+                Ok(chunks) => read_chunks(&loc.id, &chunks),
+                Err(err) => Err(err),
+            },
+            Err(err) => Err(err),
+        };
+
+        match result {
+            Ok(listing) => Some(listing),
+            Err(err) => {
+                debug!("fetch listing {} from {} err {:?}", loc.id, loc.owner, err);
+                self.forget(&loc);
+                None
+            }
+        }
+    }
+
+    /// Invalidate listings including `object` here and, within the invalidation interval, on the other nodes.
+    pub fn object_changed(&'static self, bucket: &str, object: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        self.invalidate(bucket, object);
+
+        {
+            let mut state = self.state();
+            let pending = state.pending.entry(bucket.to_owned()).or_default();
+            if !pending.contains("") {
+                pending.insert(object.to_owned());
+                if pending.len() > MAX_PENDING_PREFIXES {
+                    pending.clear();
+                    pending.insert(String::new());
+                }
+            }
+        }
+
+        self.start_flusher();
+    }
+
+    fn take_pending(&self) -> ListCacheUpdate {
+        let mut state = self.state();
+        let pending = std::mem::take(&mut state.pending);
+        ListCacheUpdate {
+            changes: pending
+                .into_iter()
+                .map(|(bucket, prefixes)| (bucket, prefixes.into_iter().collect()))
+                .collect(),
+            listings: std::mem::take(&mut state.pending_listings),
+        }
+    }
+
+    fn start_flusher(&'static self) {
+        if self.flusher_started.load(Ordering::Relaxed) {
+            return;
+        }
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        if self.flusher_started.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let interval = Duration::from_millis(
+            get_env_u64(ENV_LIST_CACHE_INVALIDATION_INTERVAL, DEFAULT_LIST_CACHE_INVALIDATION_INTERVAL).max(1),
+        );
+        handle.spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+
+                let update = self.take_pending();
+                if update.is_empty() {
+                    continue;
+                }
+                let Some(notification_sys) = get_global_notification_sys() else {
+                    continue;
+                };
+                match update.marshal() {
+                    Ok(buf) => notification_sys.update_metacache_listing(Bytes::from(buf)).await,
+                    Err(err) => debug!("marshal list cache update err {:?}", err),
+                }
+            }
+        });
+    }
+}
+
+/// Reassemble a listing streamed by [`ListCache::serve`], checking it is complete and the one asked for.
+fn read_chunks(id: &str, chunks: &[Bytes]) -> Result<CachedListing> {
+    let mut listing = CachedListing::default();
+    for (i, buf) in chunks.iter().enumerate() {
+        let chunk: ListCacheChunk = rmp_serde::from_slice(buf).map_err(Error::other)?;
+        if chunk.version != LIST_CACHE_PROTOCOL_VERSION || chunk.id != id {
+            return Err(Error::other(format!(
+                "unexpected listing {} version {}, want {} version {}",
+                chunk.id, chunk.version, id, LIST_CACHE_PROTOCOL_VERSION
+            )));
+        }
+
+        listing.entries.extend(chunk.entries);
+        if chunk.last {
+            if i + 1 != chunks.len() {
+                return Err(Error::other("listing stream continues past its end"));
+            }
+            listing.eof = chunk.eof;
+            return Ok(listing);
+        }
+    }
+
+    Err(Error::other("listing stream ended early"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static CACHE: LazyLock<ListCache> = LazyLock::new(|| ListCache::new(Duration::from_secs(60)));
+
+    fn key(bucket: &str, prefix: &str) -> ListCacheKey {
+        ListCacheKey {
+            bucket: bucket.to_string(),
+            prefix: prefix.to_string(),
+            marker: None,
+            delimiter: Some("/".to_string()),
+            max_keys: 1000,
+            incl_deleted: false,
+        }
+    }
+
+    fn listing(names: &[&str]) -> CachedListing {
+        CachedListing {
+            entries: names
+                .iter()
+                .map(|name| MetaCacheEntry {
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            eof: true,
+        }
+    }
+
+    #[test]
+    fn test_list_cache_invalidate() {
+        let cache = &*CACHE;
+
+        for prefix in ["", "a/", "a/b/", "c/"] {
+            let generation = cache.generation("invalidate");
+            cache.put(key("invalidate", prefix), generation, listing(&[prefix]), false);
+        }
+        assert!(cache.get(&key("invalidate", "a/")).is_some());
+
+        cache.invalidate("invalidate", "a/b/obj");
+        assert!(cache.get(&key("invalidate", "")).is_none());
+        assert!(cache.get(&key("invalidate", "a/")).is_none());
+        assert!(cache.get(&key("invalidate", "a/b/")).is_none());
+        assert!(cache.get(&key("invalidate", "c/")).is_some());
+
+        cache.invalidate("other", "");
+        assert!(cache.get(&key("invalidate", "c/")).is_some());
+        cache.apply(&ListCacheUpdate {
+            changes: HashMap::from([("invalidate".to_string(), vec![String::new()])]),
+            ..Default::default()
+        });
+        assert!(cache.get(&key("invalidate", "c/")).is_none());
+    }
+
+    #[test]
+    fn test_list_cache_skips_listing_raced_by_write() {
+        let cache = &*CACHE;
+
+        let generation = cache.generation("raced");
+        cache.invalidate("raced", "obj");
+        cache.put(key("raced", ""), generation, listing(&["obj"]), false);
+        assert!(cache.get(&key("raced", "")).is_none());
+
+        let disabled = ListCache::new(Duration::ZERO);
+        assert!(disabled.get(&key("raced", "")).is_none());
+    }
+
+    #[test]
+    fn test_list_cache_locate() {
+        let cache = &*CACHE;
+        let loc = ListCacheLocation {
+            key: key("locate", "a/"),
+            id: "id".to_string(),
+            owner: "node2:9000".to_string(),
+        };
+
+        cache.apply(&ListCacheUpdate {
+            listings: vec![loc.clone()],
+            ..Default::default()
+        });
+        assert_eq!(cache.locate(&loc.key), Some(loc.clone()));
+
+        cache.invalidate("locate", "a/obj");
+        assert_eq!(cache.locate(&loc.key), None);
+
+        let update = ListCacheUpdate {
+            changes: HashMap::from([("locate".to_string(), vec!["a/".to_string()])]),
+            listings: vec![loc],
+        };
+        assert_eq!(ListCacheUpdate::unmarshal(&update.marshal().unwrap()).unwrap(), update);
+    }
+
+    #[test]
+    fn test_list_cache_serve() {
+        let cache = &*CACHE;
+        let names: Vec<String> = (0..FETCH_CHUNK_ENTRIES * 2 + 1).map(|i| format!("serve/{i:04}")).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+        let generation = cache.generation("serve");
+        cache.put(key("serve", "serve/"), generation, listing(&names), false);
+        let id = cache.state().entries[&key("serve", "serve/")].id.clone();
+
+        let mut fetch = ListCacheFetch {
+            version: LIST_CACHE_PROTOCOL_VERSION,
+            id: id.clone(),
+            key: key("serve", "serve/"),
+        };
+        let chunks = cache.serve(&fetch).unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(read_chunks(&id, &chunks).unwrap(), listing(&names));
+        assert!(read_chunks(&id, &chunks[..2]).is_err());
+        assert!(read_chunks("other", &chunks).is_err());
+
+        fetch.version += 1;
+        assert!(cache.serve(&fetch).is_err());
+        fetch.version -= 1;
+        fetch.id = "other".to_string();
+        assert!(cache.serve(&fetch).is_err());
+    }
+}
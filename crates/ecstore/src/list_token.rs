@@ -0,0 +1,215 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opaque continuation tokens of object listings.
+//!
+//! A token holds what is needed to resume a listing: the last key returned, the version marker,
+//! the id and location of the cached listing behind it, and the list cache generation it was taken
+//! at. The state is signed with an HMAC keyed by the cluster credentials, so clients cannot forge
+//! or alter it, and is bound to the bucket and prefix it was issued for.
+//!
+//! A token whose generation is no longer the current one of this node, because the bucket changed
+//! or the token comes from another node or from before a restart, still resumes after its last key,
+//! only as a fresh listing instead of from the cached one.
+
+use crate::error::{Error, Result};
+use crate::global::get_global_action_cred;
+use crate::list_cache::GLOBAL_LIST_CACHE;
+use crate::store_list_objects::ListPathOptions;
+use hmac::{Hmac, KeyInit, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const TOKEN_VERSION: u8 = 1;
+
+// Keeps signatures of tokens apart from other uses of the same key
+const TOKEN_DOMAIN: &[u8] = b"rustfs-list-continuation";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct TokenState {
+    #[serde(rename = "v")]
+    version: u8,
+    #[serde(rename = "b")]
+    bucket: String,
+    #[serde(rename = "p")]
+    prefix: String,
+    #[serde(rename = "k")]
+    last_key: String,
+    #[serde(rename = "vm", default, skip_serializing_if = "Option::is_none")]
+    version_marker: Option<String>,
+    #[serde(rename = "id", default, skip_serializing_if = "Option::is_none")]
+    listing_id: Option<String>,
+    #[serde(rename = "pl", default, skip_serializing_if = "Option::is_none")]
+    pool_idx: Option<usize>,
+    #[serde(rename = "st", default, skip_serializing_if = "Option::is_none")]
+    set_idx: Option<usize>,
+    #[serde(rename = "g")]
+    generation: u64,
+}
+
+fn signing_key() -> Result<String> {
+    get_global_action_cred()
+        .map(|cred| cred.secret_key)
+        .ok_or_else(|| Error::other("continuation tokens need the cluster credentials"))
+}
+
+fn sign(key: &[u8], payload: &[u8]) -> Result<HmacSha256> {
+    let mut mac = HmacSha256::new_from_slice(key).map_err(Error::other)?;
+    mac.update(TOKEN_DOMAIN);
+    mac.update(payload);
+    Ok(mac)
+}
+
+fn encode_state(key: &[u8], state: &TokenState) -> Result<String> {
+    let payload = serde_json::to_vec(state).map_err(Error::other)?;
+    let signature = sign(key, &payload)?.finalize().into_bytes();
+    Ok(format!(
+        "{}.{}",
+        base64_simd::URL_SAFE_NO_PAD.encode_to_string(&payload),
+        base64_simd::URL_SAFE_NO_PAD.encode_to_string(signature)
+    ))
+}
+
+fn decode_state(key: &[u8], token: &str) -> Result<TokenState> {
+    let invalid = || Error::other("invalid continuation token");
+
+    let (payload, signature) = token.split_once('.').ok_or_else(invalid)?;
+    let payload = base64_simd::URL_SAFE_NO_PAD
+        .decode_to_vec(payload.as_bytes())
+        .map_err(|_| invalid())?;
+    let signature = base64_simd::URL_SAFE_NO_PAD
+        .decode_to_vec(signature.as_bytes())
+        .map_err(|_| invalid())?;
+    sign(key, &payload)?.verify_slice(&signature).map_err(|_| invalid())?;
+
+    let state: TokenState = serde_json::from_slice(&payload).map_err(|_| invalid())?;
+    if state.version != TOKEN_VERSION {
+        return Err(invalid());
+    }
+    Ok(state)
+}
+
+/// The token resuming a listing of `bucket` under `prefix` after `marker`, the next marker
+/// returned by the listing.
+pub fn encode_continuation_token(bucket: &str, prefix: &str, marker: &str, version_marker: Option<&str>) -> Result<String> {
+    let mut opts = ListPathOptions {
+        marker: Some(marker.to_owned()),
+        ..Default::default()
+    };
+    opts.parse_marker();
+
+    // A marker asking for a new listing names an id that was never cached
+    let cached = !opts.create;
+    let state = TokenState {
+        version: TOKEN_VERSION,
+        bucket: bucket.to_owned(),
+        prefix: prefix.to_owned(),
+        last_key: opts.marker.unwrap_or_default(),
+        version_marker: version_marker.map(str::to_owned),
+        listing_id: opts.id.filter(|_| cached),
+        pool_idx: opts.pool_idx.filter(|_| cached),
+        set_idx: opts.set_idx.filter(|_| cached),
+        generation: GLOBAL_LIST_CACHE.generation(bucket),
+    };
+
+    encode_state(signing_key()?.as_bytes(), &state)
+}
+
+/// A decoded continuation token: the marker to resume the listing from, and its version marker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListContinuation {
+    pub marker: String,
+    pub version_marker: Option<String>,
+}
+
+/// Check `token` was issued for a listing of `bucket` under `prefix` and return where it resumes.
+pub fn decode_continuation_token(bucket: &str, prefix: &str, token: &str) -> Result<ListContinuation> {
+    let state = decode_state(signing_key()?.as_bytes(), token)?;
+    resume(state, bucket, prefix, GLOBAL_LIST_CACHE.generation(bucket))
+}
+
+fn resume(state: TokenState, bucket: &str, prefix: &str, generation: u64) -> Result<ListContinuation> {
+    if state.bucket != bucket || state.prefix != prefix {
+        return Err(Error::other("continuation token belongs to another listing"));
+    }
+
+    let marker = match state.listing_id {
+        Some(id) if state.generation == generation => {
+            let mut opts = ListPathOptions {
+                id: Some(id),
+                pool_idx: state.pool_idx,
+                set_idx: state.set_idx,
+                ..Default::default()
+            };
+            opts.encode_marker(&state.last_key)
+        }
+        _ => state.last_key,
+    };
+
+    Ok(ListContinuation {
+        marker,
+        version_marker: state.version_marker,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"secret";
+
+    fn state() -> TokenState {
+        TokenState {
+            version: TOKEN_VERSION,
+            bucket: "bucket".to_string(),
+            prefix: "logs/".to_string(),
+            last_key: "logs/2024/01.json".to_string(),
+            listing_id: Some("5f1c".to_string()),
+            pool_idx: Some(0),
+            set_idx: Some(0),
+            generation: 3,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_token_round_trip_and_tamper() {
+        let token = encode_state(KEY, &state()).unwrap();
+        assert_eq!(decode_state(KEY, &token).unwrap(), state());
+
+        assert!(decode_state(b"other", &token).is_err());
+
+        let mut forged = state();
+        forged.last_key = "logs/2099".to_string();
+        let forged_payload = base64_simd::URL_SAFE_NO_PAD.encode_to_string(serde_json::to_vec(&forged).unwrap());
+        let signature = token.split_once('.').unwrap().1;
+        assert!(decode_state(KEY, &format!("{forged_payload}.{signature}")).is_err());
+        assert!(decode_state(KEY, "bG9ncy8").is_err());
+    }
+
+    #[test]
+    fn test_resume() {
+        let cont = resume(state(), "bucket", "logs/", 3).unwrap();
+        assert_eq!(cont.marker, "logs/2024/01.json[rustfs_cache:v1,id:5f1c,p:0,s:0]");
+
+        // A stale generation restarts after the last key without the cached listing
+        let cont = resume(state(), "bucket", "logs/", 4).unwrap();
+        assert_eq!(cont.marker, "logs/2024/01.json");
+
+        assert!(resume(state(), "bucket", "other/", 3).is_err());
+        assert!(resume(state(), "other", "logs/", 3).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Effectiveness of the listing cache.
+//!
+//! A listing served from the listing cache or continued with the cache id of its marker is a hit,
+//! a listing without one is a miss, and a marker that asks for a fresh listing or carries unusable
+//! cache tags forces a rescan.
+//! Entries the drives disagreed on are resolved from the versions the quorum saw and may be stale,
+//! they are counted as served stale. Everything is exported through the metrics recorder, and the
+//! totals since startup are kept for a snapshot.
+
+use metrics::{counter, histogram};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub static GLOBAL_LISTING_METRICS: LazyLock<ListingMetrics> = LazyLock::new(ListingMetrics::default);
+
+/// How a listing used the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListingCacheUse {
+    Hit,
+    Miss,
+    Rescan,
+}
+
+#[derive(Debug, Default)]
+pub struct ListingMetrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    rescans: AtomicU64,
+    stale_entries: AtomicU64,
+    listings: AtomicU64,
+    entries: AtomicU64,
+}
+
+/// Totals since the node started.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingMetricsSnapshot {
+    pub hits: u64,
+    pub misses: u64,
+    pub forced_rescans: u64,
+    pub stale_entries_served: u64,
+    pub listings: u64,
+    pub entries: u64,
+    pub hit_ratio: f64,
+    pub avg_entries_per_listing: f64,
+}
+
+impl ListingMetrics {
+    /// Record a listing that returned `entries` entries.
+    pub fn record_listing(&self, cache_use: ListingCacheUse, entries: usize) {
+        match cache_use {
+            ListingCacheUse::Hit => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                counter!("rustfs_listing_cache_hits_total").increment(1);
+            }
+            ListingCacheUse::Miss => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                counter!("rustfs_listing_cache_misses_total").increment(1);
+            }
+            ListingCacheUse::Rescan => {
+                self.rescans.fetch_add(1, Ordering::Relaxed);
+                counter!("rustfs_listing_cache_forced_rescans_total").increment(1);
+            }
+        }
+        self.listings.fetch_add(1, Ordering::Relaxed);
+        self.entries.fetch_add(entries as u64, Ordering::Relaxed);
+        histogram!("rustfs_listing_entries").record(entries as f64);
+    }
+
+    /// Record an entry resolved from drives that disagreed on it.
+    pub fn record_stale_entry(&self) {
+        self.stale_entries.fetch_add(1, Ordering::Relaxed);
+        counter!("rustfs_listing_stale_entries_served_total").increment(1);
+    }
+
+    pub fn snapshot(&self) -> ListingMetricsSnapshot {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let forced_rescans = self.rescans.load(Ordering::Relaxed);
+        let listings = self.listings.load(Ordering::Relaxed);
+        let entries = self.entries.load(Ordering::Relaxed);
+
+        let lookups = hits + misses + forced_rescans;
+        ListingMetricsSnapshot {
+            hits,
+            misses,
+            forced_rescans,
+            stale_entries_served: self.stale_entries.load(Ordering::Relaxed),
+            listings,
+            entries,
+            hit_ratio: if lookups == 0 { 0.0 } else { hits as f64 / lookups as f64 },
+            avg_entries_per_listing: if listings == 0 {
+                0.0
+            } else {
+                entries as f64 / listings as f64
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listing_metrics_snapshot() {
+        let metrics = ListingMetrics::default();
+        assert_eq!(metrics.snapshot().hit_ratio, 0.0);
+
+        metrics.record_listing(ListingCacheUse::Miss, 10);
+        metrics.record_listing(ListingCacheUse::Hit, 20);
+        metrics.record_listing(ListingCacheUse::Hit, 0);
+        metrics.record_listing(ListingCacheUse::Rescan, 2);
+        metrics.record_stale_entry();
+
+        let snapshot = metrics.snapshot();
+        assert_eq!((snapshot.hits, snapshot.misses, snapshot.forced_rescans), (2, 1, 1));
+        assert_eq!(snapshot.stale_entries_served, 1);
+        assert_eq!(snapshot.hit_ratio, 0.5);
+        assert_eq!(snapshot.avg_entries_per_listing, 8.0);
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-node maintenance mode.
+//!
+//! A node in maintenance keeps serving the requests it already accepted, but its readiness probe
+//! reports it as draining, its drives are only used as a fallback when picking listing drives,
+//! and background heal scanning of its local drives is paused. The set of nodes in maintenance is
+//! persisted in the cluster config, so the mode survives restarts until it is explicitly cleared,
+//! and every node reloads it periodically to learn about its peers.
+
+use crate::config::com::{read_config, save_config};
+use crate::disk::endpoint::Endpoint;
+use crate::error::{Error, Result};
+use crate::global::get_global_endpoints;
+use crate::store::ECStore;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+pub const MAINTENANCE_CONFIG_PATH: &str = "config/maintenance.json";
+
+/// How often a node reloads the persisted maintenance state to pick up changes made on peers.
+pub const MAINTENANCE_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+pub static GLOBAL_MAINTENANCE_SYS: LazyLock<MaintenanceSys> = LazyLock::new(MaintenanceSys::default);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceEntry {
+    #[serde(with = "time::serde::rfc3339")]
+    pub since: OffsetDateTime,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub reason: String,
+}
+
+/// Nodes in maintenance, keyed by `host:port` as reported by `Endpoint::host_port`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceState {
+    #[serde(default)]
+    pub nodes: BTreeMap<String, MaintenanceEntry>,
+}
+
+impl MaintenanceState {
+    pub fn unmarshal(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data).map_err(Error::other)
+    }
+
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(Error::other)
+    }
+
+    /// Enable or clear maintenance for `node`, returning whether the state changed.
+    pub fn set(&mut self, node: &str, enable: bool, reason: &str) -> bool {
+        if !enable {
+            return self.nodes.remove(node).is_some();
+        }
+
+        if self.nodes.contains_key(node) {
+            return false;
+        }
+
+        self.nodes.insert(
+            node.to_string(),
+            MaintenanceEntry {
+                since: OffsetDateTime::now_utc(),
+                reason: reason.to_string(),
+            },
+        );
+        true
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MaintenanceSys {
+    state: RwLock<MaintenanceState>,
+    local_nodes: RwLock<HashSet<String>>,
+}
+
+impl MaintenanceSys {
+    /// Load the persisted state, treating a missing config as no node in maintenance.
+    pub async fn load(&self, store: Arc<ECStore>) -> Result<()> {
+        let state = match read_config(store, MAINTENANCE_CONFIG_PATH).await {
+            Ok(data) => MaintenanceState::unmarshal(&data)?,
+            Err(Error::ConfigNotFound) => MaintenanceState::default(),
+            Err(err) => return Err(err),
+        };
+
+        self.replace(state);
+        Ok(())
+    }
+
+    /// Enable or clear maintenance for `node` and persist the result.
+    ///
+    /// The persisted state is re-read first so concurrent changes made on peers are not lost.
+    pub async fn set(&self, store: Arc<ECStore>, node: &str, enable: bool, reason: &str) -> Result<MaintenanceState> {
+        self.load(store.clone()).await?;
+
+        let mut state = self.state();
+        if state.set(node, enable, reason) {
+            save_config(store, MAINTENANCE_CONFIG_PATH, state.marshal()?).await?;
+            info!(node, enable, "maintenance mode changed");
+        }
+
+        self.replace(state.clone());
+        Ok(state)
+    }
+
+    fn replace(&self, state: MaintenanceState) {
+        *self.state.write() = state;
+    }
+
+    pub fn state(&self) -> MaintenanceState {
+        self.state.read().clone()
+    }
+
+    pub fn is_node_in_maintenance(&self, node: &str) -> bool {
+        self.state.read().nodes.contains_key(node)
+    }
+
+    pub fn is_endpoint_in_maintenance(&self, ep: &Endpoint) -> bool {
+        self.is_node_in_maintenance(&ep.host_port())
+    }
+
+    /// The `host:port` names this node serves drives under.
+    pub fn local_nodes(&self) -> HashSet<String> {
+        {
+            let local = self.local_nodes.read();
+            if !local.is_empty() {
+                return local.clone();
+            }
+        }
+
+        let local: HashSet<String> = get_global_endpoints()
+            .as_ref()
+            .iter()
+            .flat_map(|pool| pool.endpoints.as_ref().iter())
+            .filter(|ep| ep.is_local)
+            .map(|ep| ep.host_port())
+            .collect();
+
+        if !local.is_empty() {
+            *self.local_nodes.write() = local.clone();
+        }
+        local
+    }
+
+    pub fn is_local_node_in_maintenance(&self) -> bool {
+        let state = self.state.read();
+        if state.nodes.is_empty() {
+            return false;
+        }
+        self.local_nodes().iter().any(|node| state.nodes.contains_key(node))
+    }
+}
+
+/// Load the persisted maintenance state and keep it in sync with changes made on peers.
+pub async fn init_maintenance_sys(store: Arc<ECStore>, cancel: CancellationToken) {
+    if let Err(err) = GLOBAL_MAINTENANCE_SYS.load(store.clone()).await {
+        warn!("load maintenance state failed: {:?}", err);
+    }
+
+    if GLOBAL_MAINTENANCE_SYS.is_local_node_in_maintenance() {
+        warn!("this node is in maintenance mode, clear it through the admin API to resume normal operation");
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(MAINTENANCE_REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = interval.tick() => {
+                    if let Err(err) = GLOBAL_MAINTENANCE_SYS.load(store.clone()).await {
+                        warn!("refresh maintenance state failed: {:?}", err);
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Move the drives of nodes in maintenance behind all other drives, keeping the relative order
+/// within each group, so they are only asked when the other drives are not enough.
+pub fn deprioritize_maintenance<T>(items: &mut [T], in_maintenance: impl Fn(&T) -> bool) {
+    items.sort_by_key(|item| in_maintenance(item));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_set_and_clear() {
+        let mut state = MaintenanceState::default();
+        assert!(state.set("node1:9000", true, "disk swap"));
+        assert!(!state.set("node1:9000", true, "again"));
+        assert_eq!(state.nodes["node1:9000"].reason, "disk swap");
+
+        assert!(!state.set("node2:9000", false, ""));
+        assert!(state.set("node1:9000", false, ""));
+        assert!(state.nodes.is_empty());
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let mut state = MaintenanceState::default();
+        state.set("node1:9000", true, "");
+        let decoded = MaintenanceState::unmarshal(&state.marshal().unwrap()).unwrap();
+        assert_eq!(decoded, state);
+
+        assert_eq!(MaintenanceState::unmarshal(b"{}").unwrap(), MaintenanceState::default());
+    }
+
+    #[test]
+    fn test_deprioritize_maintenance_is_stable() {
+        let mut hosts = vec!["a", "m1", "b", "m2", "c"];
+        deprioritize_maintenance(&mut hosts, |h| h.starts_with('m'));
+        assert_eq!(hosts, vec!["a", "b", "c", "m1", "m2"]);
+    }
+}
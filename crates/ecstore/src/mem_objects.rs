@@ -0,0 +1,311 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! In-memory object layer for tests and benchmarks.
+//!
+//! Objects live in a map per bucket and vanish with the value, no drives or temporary
+//! directories are involved. Versioning, multipart uploads and locking are out of scope.
+
+use crate::error::{Error, Result, StorageError};
+use crate::fs_objects::paginate_keys;
+use crate::object_layer::ObjectLayer;
+use crate::store_api::{GetObjectReader, HTTPRangeSpec, ListObjectsV2Info, ObjectInfo, ObjectOptions, PutObjReader};
+use bytes::Bytes;
+use http::HeaderMap;
+use rustfs_rio::EtagResolvable;
+use rustfs_utils::http::headers::RESERVED_METADATA_PREFIX_LOWER;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Cursor;
+use std::sync::RwLock;
+use time::OffsetDateTime;
+use tokio::io::AsyncReadExt;
+
+const DEFAULT_MAX_KEYS: i32 = 1000;
+
+#[derive(Debug, Clone)]
+struct MemObject {
+    data: Bytes,
+    info: ObjectInfo,
+}
+
+#[derive(Debug, Default)]
+pub struct MemObjects {
+    buckets: RwLock<HashMap<String, BTreeMap<String, MemObject>>>,
+}
+
+impl MemObjects {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create `bucket`, keeping its objects when it exists already.
+    pub fn make_bucket(&self, bucket: &str) {
+        self.buckets
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(bucket.to_owned())
+            .or_default();
+    }
+
+    pub fn bucket_exists(&self, bucket: &str) -> bool {
+        self.buckets.read().unwrap_or_else(|e| e.into_inner()).contains_key(bucket)
+    }
+
+    /// Number of objects in `bucket`, None when it does not exist.
+    pub fn object_count(&self, bucket: &str) -> Option<usize> {
+        self.buckets
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(bucket)
+            .map(BTreeMap::len)
+    }
+
+    fn get(&self, bucket: &str, object: &str) -> Result<MemObject> {
+        let buckets = self.buckets.read().unwrap_or_else(|e| e.into_inner());
+        let objects = buckets
+            .get(bucket)
+            .ok_or_else(|| StorageError::BucketNotFound(bucket.to_owned()))?;
+        objects
+            .get(object)
+            .cloned()
+            .ok_or_else(|| StorageError::ObjectNotFound(bucket.to_owned(), object.to_owned()))
+    }
+
+    fn insert(&self, bucket: &str, object: &str, obj: MemObject) -> Result<()> {
+        let mut buckets = self.buckets.write().unwrap_or_else(|e| e.into_inner());
+        let objects = buckets
+            .get_mut(bucket)
+            .ok_or_else(|| StorageError::BucketNotFound(bucket.to_owned()))?;
+        objects.insert(object.to_owned(), obj);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectLayer for MemObjects {
+    async fn get_object_reader(
+        &self,
+        bucket: &str,
+        object: &str,
+        range: Option<HTTPRangeSpec>,
+        h: HeaderMap,
+        opts: &ObjectOptions,
+    ) -> Result<GetObjectReader> {
+        let obj = self.get(bucket, object)?;
+
+        if range.is_none() && opts.part_number.is_none() && !obj.info.is_compressed() {
+            return Ok(GetObjectReader::from_bytes(obj.data, obj.info));
+        }
+
+        let data = match &range {
+            Some(rs) if !obj.info.is_compressed() => {
+                let (off, length) = rs.get_offset_length(obj.info.size)?;
+                obj.data.slice(off..off + length.max(0) as usize)
+            }
+            _ => obj.data,
+        };
+
+        let (reader, _, _) = GetObjectReader::new(Box::new(Cursor::new(data)), range, &obj.info, opts, &h)?;
+        Ok(reader)
+    }
+
+    async fn put_object(&self, bucket: &str, object: &str, data: &mut PutObjReader, opts: &ObjectOptions) -> Result<ObjectInfo> {
+        if !self.bucket_exists(bucket) {
+            return Err(StorageError::BucketNotFound(bucket.to_owned()));
+        }
+
+        let mut buf = Vec::new();
+        data.stream.read_to_end(&mut buf).await?;
+        if (buf.len() as i64) < data.size() {
+            return Err(Error::other(format!("incomplete body, got {} of {} bytes", buf.len(), data.size())));
+        }
+        let size = buf.len() as i64;
+
+        let mut user_defined = opts.user_defined.clone();
+        if user_defined.contains_key(&format!("{RESERVED_METADATA_PREFIX_LOWER}compression")) {
+            user_defined.insert(format!("{RESERVED_METADATA_PREFIX_LOWER}compression-size"), size.to_string());
+        }
+        let etag = data.stream.try_resolve_etag().unwrap_or_default();
+        user_defined.insert("etag".to_owned(), etag.clone());
+
+        let info = ObjectInfo {
+            bucket: bucket.to_owned(),
+            name: object.to_owned(),
+            mod_time: Some(opts.mod_time.unwrap_or_else(OffsetDateTime::now_utc)),
+            size,
+            actual_size: if data.actual_size() >= 0 { data.actual_size() } else { size },
+            is_dir: object.ends_with('/'),
+            content_type: user_defined.get("content-type").cloned(),
+            content_encoding: user_defined.get("content-encoding").cloned(),
+            etag: Some(etag),
+            user_defined,
+            is_latest: true,
+            num_versions: 1,
+            ..Default::default()
+        };
+
+        self.insert(
+            bucket,
+            object,
+            MemObject {
+                data: Bytes::from(buf),
+                info: info.clone(),
+            },
+        )?;
+        Ok(info)
+    }
+
+    async fn get_object_info(&self, bucket: &str, object: &str, _opts: &ObjectOptions) -> Result<ObjectInfo> {
+        Ok(self.get(bucket, object)?.info)
+    }
+
+    async fn copy_object(
+        &self,
+        src_bucket: &str,
+        src_object: &str,
+        dst_bucket: &str,
+        dst_object: &str,
+        src_info: &mut ObjectInfo,
+        dst_opts: &ObjectOptions,
+    ) -> Result<ObjectInfo> {
+        let mut obj = self.get(src_bucket, src_object)?;
+
+        let etag = obj.info.user_defined.get("etag").cloned();
+        obj.info.user_defined = src_info.user_defined.clone();
+        if let Some(etag) = etag {
+            obj.info.user_defined.insert("etag".to_owned(), etag);
+        }
+        obj.info.content_type = obj.info.user_defined.get("content-type").cloned();
+        obj.info.content_encoding = obj.info.user_defined.get("content-encoding").cloned();
+        obj.info.bucket = dst_bucket.to_owned();
+        obj.info.name = dst_object.to_owned();
+        obj.info.mod_time = Some(dst_opts.mod_time.unwrap_or_else(OffsetDateTime::now_utc));
+
+        let info = obj.info.clone();
+        self.insert(dst_bucket, dst_object, obj)?;
+        Ok(info)
+    }
+
+    async fn delete_object(&self, bucket: &str, object: &str, _opts: &ObjectOptions) -> Result<ObjectInfo> {
+        let mut buckets = self.buckets.write().unwrap_or_else(|e| e.into_inner());
+        let objects = buckets
+            .get_mut(bucket)
+            .ok_or_else(|| StorageError::BucketNotFound(bucket.to_owned()))?;
+        objects
+            .remove(object)
+            .map(|obj| obj.info)
+            .ok_or_else(|| StorageError::ObjectNotFound(bucket.to_owned(), object.to_owned()))
+    }
+
+    async fn list_objects_v2(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        continuation_token: Option<String>,
+        delimiter: Option<String>,
+        max_keys: i32,
+        start_after: Option<String>,
+    ) -> Result<ListObjectsV2Info> {
+        let buckets = self.buckets.read().unwrap_or_else(|e| e.into_inner());
+        let objects = buckets
+            .get(bucket)
+            .ok_or_else(|| StorageError::BucketNotFound(bucket.to_owned()))?;
+
+        let keys: Vec<String> = objects
+            .range(prefix.to_owned()..)
+            .map(|(k, _)| k)
+            .take_while(|k| k.starts_with(prefix))
+            .cloned()
+            .collect();
+
+        let marker = continuation_token.clone().or(start_after);
+        let max_keys = if max_keys < 0 { DEFAULT_MAX_KEYS } else { max_keys } as usize;
+        let (names, prefixes, next_marker) = paginate_keys(&keys, prefix, marker.as_deref(), delimiter.as_deref(), max_keys);
+
+        Ok(ListObjectsV2Info {
+            is_truncated: next_marker.is_some(),
+            continuation_token,
+            next_continuation_token: next_marker,
+            objects: names
+                .iter()
+                .filter_map(|name| objects.get(name))
+                .map(|obj| obj.info.clone())
+                .collect(),
+            prefixes,
+        })
+    }
+
+    async fn delete_bucket(&self, bucket: &str) -> Result<()> {
+        self.buckets.write().unwrap_or_else(|e| e.into_inner()).remove(bucket);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mem_objects() {
+        let layer = MemObjects::new();
+        let opts = ObjectOptions::default();
+
+        let mut data = PutObjReader::from_vec(b"hello world".to_vec());
+        assert!(matches!(
+            layer.put_object("bucket", "a", &mut data, &opts).await,
+            Err(StorageError::BucketNotFound(_))
+        ));
+
+        layer.make_bucket("bucket");
+        for key in ["a/1", "a/2", "b"] {
+            let mut data = PutObjReader::from_vec(b"hello world".to_vec());
+            layer.put_object("bucket", key, &mut data, &opts).await.unwrap();
+        }
+        assert_eq!(layer.object_count("bucket"), Some(3));
+
+        let range = HTTPRangeSpec {
+            is_suffix_length: false,
+            start: 0,
+            end: 4,
+        };
+        let mut reader = layer
+            .get_object_reader("bucket", "b", Some(range), HeaderMap::new(), &opts)
+            .await
+            .unwrap();
+        assert_eq!(reader.read_all().await.unwrap(), b"hello");
+
+        let list = layer
+            .list_objects_v2("bucket", "", None, Some("/".to_string()), 1000, None)
+            .await
+            .unwrap();
+        assert_eq!(list.prefixes, vec!["a/".to_string()]);
+        assert_eq!(list.objects.len(), 1);
+
+        let mut src = layer.get_object_info("bucket", "b", &opts).await.unwrap();
+        src.user_defined.insert("content-type".to_string(), "text/plain".to_string());
+        let copied = layer
+            .copy_object("bucket", "b", "bucket", "c", &mut src, &opts)
+            .await
+            .unwrap();
+        assert_eq!(copied.content_type.as_deref(), Some("text/plain"));
+
+        layer.delete_object("bucket", "a/1", &opts).await.unwrap();
+        assert!(matches!(
+            layer.get_object_info("bucket", "a/1", &opts).await,
+            Err(StorageError::ObjectNotFound(_, _))
+        ));
+        layer.delete_bucket("bucket").await.unwrap();
+        assert!(!layer.bucket_exists("bucket"));
+    }
+}
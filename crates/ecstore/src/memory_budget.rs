@@ -0,0 +1,168 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Memory budget of in-flight transfers.
+//!
+//! Every PUT, part upload and GET reserves the memory its erasure blocks hold before it moves any
+//! data, and keeps the reservation until the transfer ends. A transfer that does not fit in what
+//! is left of `RUSTFS_MEMORY_BUDGET` waits up to `RUSTFS_MEMORY_BUDGET_WAIT` for other transfers to
+//! finish and fails with SlowDown after that, so a burst of large transfers is turned away before
+//! the node runs out of memory. The budget is disabled unless `RUSTFS_MEMORY_BUDGET` is set.
+
+use crate::error::{Error, Result};
+use crate::request_io;
+use rustfs_config::{DEFAULT_MEMORY_BUDGET, DEFAULT_MEMORY_BUDGET_WAIT, ENV_MEMORY_BUDGET, ENV_MEMORY_BUDGET_WAIT};
+use rustfs_utils::get_env_u64;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::warn;
+
+pub static GLOBAL_MEMORY_BUDGET: LazyLock<MemoryBudget> = LazyLock::new(|| {
+    MemoryBudget::new(
+        get_env_u64(ENV_MEMORY_BUDGET, DEFAULT_MEMORY_BUDGET) as usize * 1024 * 1024,
+        Duration::from_millis(get_env_u64(ENV_MEMORY_BUDGET_WAIT, DEFAULT_MEMORY_BUDGET_WAIT)),
+    )
+});
+
+// The budget is counted in KiB to keep the permit count small
+const PERMIT_UNIT: usize = 1024;
+
+#[derive(Debug)]
+pub struct MemoryBudget {
+    budget: Option<Arc<Semaphore>>,
+    permits: usize,
+    wait: Duration,
+}
+
+/// Memory reserved for one transfer, released when dropped.
+#[derive(Debug)]
+pub struct MemoryReservation {
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Bytes an erasure transfer of `size` bytes holds at once: the block being encoded or decoded and
+/// its shards. A negative `size` stands for a stream of unknown length.
+pub fn transfer_memory(data_blocks: usize, parity_blocks: usize, block_size: usize, size: i64) -> usize {
+    let block = match usize::try_from(size) {
+        Ok(size) => size.min(block_size),
+        Err(_) => block_size,
+    };
+    block + block.div_ceil(data_blocks.max(1)) * (data_blocks + parity_blocks)
+}
+
+impl MemoryBudget {
+    pub fn new(memory: usize, wait: Duration) -> Self {
+        let permits = (memory / PERMIT_UNIT).min(Semaphore::MAX_PERMITS);
+        Self {
+            budget: (permits > 0).then(|| Arc::new(Semaphore::new(permits))),
+            permits,
+            wait,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.budget.is_some()
+    }
+
+    /// Bytes reserved by the transfers in flight.
+    pub fn used(&self) -> usize {
+        self.budget
+            .as_ref()
+            .map_or(0, |budget| (self.permits - budget.available_permits()) * PERMIT_UNIT)
+    }
+
+    /// Reserve `bytes` for a transfer, waiting for other transfers to release memory when the budget
+    /// is used up. None when the budget is disabled, SlowDown when no memory was released in time.
+    /// A transfer larger than the whole budget waits for the budget to be free.
+    pub async fn admit(&self, bytes: usize) -> Result<Option<MemoryReservation>> {
+        let Some(budget) = &self.budget else {
+            return Ok(None);
+        };
+
+        let permits = bytes.div_ceil(PERMIT_UNIT).clamp(1, self.permits);
+        let permits = u32::try_from(permits).unwrap_or(u32::MAX);
+        let permit = match budget.clone().try_acquire_many_owned(permits) {
+            Ok(permit) => permit,
+            Err(_) if self.wait.is_zero() => return Err(self.exhausted(bytes)),
+            Err(_) => match tokio::time::timeout(self.wait, budget.clone().acquire_many_owned(permits)).await {
+                Ok(Ok(permit)) => permit,
+                Ok(Err(err)) => return Err(Error::other(err)),
+                Err(_) => return Err(self.exhausted(bytes)),
+            },
+        };
+
+        request_io::record_memory_reserved(bytes);
+        Ok(Some(MemoryReservation { _permit: permit }))
+    }
+
+    fn exhausted(&self, bytes: usize) -> Error {
+        warn!(
+            "memory budget exhausted, {} of {} bytes in use, turning away a transfer of {} bytes",
+            self.used(),
+            self.permits * PERMIT_UNIT,
+            bytes
+        );
+        Error::SlowDown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: usize = 1024 * 1024;
+
+    #[test]
+    fn test_transfer_memory() {
+        // A block of 1 MiB split over 4 data and 2 parity shards
+        assert_eq!(transfer_memory(4, 2, MIB, 10 * MIB as i64), MIB + 6 * (MIB / 4));
+        assert_eq!(transfer_memory(4, 2, MIB, -1), MIB + 6 * (MIB / 4));
+        // Small objects hold only their own size
+        assert_eq!(transfer_memory(4, 2, MIB, 4096), 4096 + 6 * 1024);
+    }
+
+    #[tokio::test]
+    async fn test_admit() {
+        let budget = MemoryBudget::new(4 * MIB, Duration::from_millis(20));
+        assert!(budget.is_enabled());
+
+        let first = budget.admit(3 * MIB).await.unwrap();
+        assert!(first.is_some());
+        assert_eq!(budget.used(), 3 * MIB);
+
+        assert!(matches!(budget.admit(2 * MIB).await, Err(Error::SlowDown)));
+
+        // Waiting transfers are admitted once memory is released
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            drop(first);
+        });
+        let second = budget.admit(2 * MIB).await.unwrap();
+        assert!(second.is_some());
+        release.await.unwrap();
+        drop(second);
+
+        // Larger than the whole budget is clamped to it
+        assert!(budget.admit(16 * MIB).await.unwrap().is_some());
+        assert_eq!(budget.used(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_disabled() {
+        let budget = MemoryBudget::new(0, Duration::ZERO);
+        assert!(!budget.is_enabled());
+        assert!(budget.admit(MIB).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,783 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Search index over the user metadata and tags of objects.
+//!
+//! Every node keeps its own index in an LMDB environment under `ENV_METADATA_INDEX_DIR`. Writes
+//! served by the node are queued to the index as they complete, and the scanner reads a batch of
+//! objects of the whole namespace into it every cycle, which picks up writes served by other nodes
+//! and drops the objects deleted through them. Queries are answered from the index of the node
+//! receiving them, so a write through another node shows up after the next backfill pass.
+//!
+//! Metadata keys are matched case insensitively, tag keys and all values as stored.
+
+use crate::bucket::tagging::decode_tags_to_map;
+use crate::bucket::utils::is_meta_bucketname;
+use crate::error::{Error, Result};
+use crate::store::ECStore;
+use crate::store_api::{BucketOptions, ObjectInfo, StorageAPI};
+use heed::types::{Bytes, DecodeIgnore, Str, Unit};
+use heed::{Database, Env, EnvOpenOptions, RoTxn, RwTxn};
+use rustfs_config::{
+    DEFAULT_METADATA_INDEX_BACKFILL_BATCH, DEFAULT_METADATA_INDEX_DIR, DEFAULT_METADATA_INDEX_MAX_SIZE,
+    ENV_METADATA_INDEX_BACKFILL_BATCH, ENV_METADATA_INDEX_DIR, ENV_METADATA_INDEX_MAX_SIZE,
+};
+use rustfs_utils::http::headers::RESERVED_METADATA_PREFIX_LOWER;
+use rustfs_utils::{get_env_str, get_env_u64};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use time::OffsetDateTime;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+static GLOBAL_METADATA_INDEX: OnceLock<Arc<MetadataIndex>> = OnceLock::new();
+
+static DROPPED_UPDATES: AtomicU64 = AtomicU64::new(0);
+
+/// Updates queued for the index writer, writes beyond it are left to the backfill.
+const UPDATE_QUEUE_SIZE: usize = 16 * 1024;
+
+// Updates applied in one write transaction
+const WRITE_BATCH: usize = 1024;
+
+// Objects listed per page while backfilling
+const BACKFILL_PAGE: usize = 1000;
+
+const BACKFILL_CURSOR_KEY: &str = "backfill";
+
+// Separates the parts of index keys, it appears in neither bucket names nor metadata
+const SEP: char = '\0';
+
+/// The index of this node, None when it is disabled.
+pub fn get_metadata_index() -> Option<Arc<MetadataIndex>> {
+    GLOBAL_METADATA_INDEX.get().cloned()
+}
+
+/// Number of index updates dropped because the writer fell behind.
+pub fn dropped_updates() -> u64 {
+    DROPPED_UPDATES.load(Ordering::Relaxed)
+}
+
+/// Open the index when `ENV_METADATA_INDEX_DIR` is set and start applying queued updates.
+pub fn init_metadata_index(cancel: CancellationToken) {
+    let dir = get_env_str(ENV_METADATA_INDEX_DIR, DEFAULT_METADATA_INDEX_DIR);
+    if dir.is_empty() {
+        return;
+    }
+
+    let max_size = get_env_u64(ENV_METADATA_INDEX_MAX_SIZE, DEFAULT_METADATA_INDEX_MAX_SIZE) as usize * 1024 * 1024;
+    let (index, rx) = match MetadataIndex::open(Path::new(&dir), max_size) {
+        Ok(opened) => opened,
+        Err(err) => {
+            error!("opening the metadata index at {} failed: {}", dir, err);
+            return;
+        }
+    };
+
+    let index = Arc::new(index);
+    if GLOBAL_METADATA_INDEX.set(index.clone()).is_err() {
+        return;
+    }
+    info!("metadata index available at {}", dir);
+
+    tokio::spawn(run_writer(index, rx, cancel));
+}
+
+/// Queue the object written by a request for indexing.
+pub fn index_object_written(bucket: &str, info: &ObjectInfo) {
+    if info.is_dir || is_meta_bucketname(bucket) {
+        return;
+    }
+    if let Some(index) = GLOBAL_METADATA_INDEX.get() {
+        index.queue(IndexUpdate::Put(IndexedObject::new(bucket, info)));
+    }
+}
+
+/// Queue the removal of a deleted object, or of one whose latest version is a delete marker.
+pub fn index_object_deleted(bucket: &str, object: &str) {
+    if is_meta_bucketname(bucket) {
+        return;
+    }
+    if let Some(index) = GLOBAL_METADATA_INDEX.get() {
+        index.queue(IndexUpdate::Remove(bucket.to_string(), object.to_string()));
+    }
+}
+
+pub fn index_bucket_deleted(bucket: &str) {
+    if let Some(index) = GLOBAL_METADATA_INDEX.get() {
+        index.queue(IndexUpdate::RemoveBucket(bucket.to_string()));
+    }
+}
+
+/// An object as kept in the index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedObject {
+    pub bucket: String,
+    pub name: String,
+    pub size: i64,
+    pub etag: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option", default)]
+    pub mod_time: Option<OffsetDateTime>,
+    pub version_id: Option<String>,
+    pub metadata: BTreeMap<String, String>,
+    pub tags: BTreeMap<String, String>,
+}
+
+impl IndexedObject {
+    pub fn new(bucket: &str, info: &ObjectInfo) -> Self {
+        Self {
+            bucket: bucket.to_string(),
+            name: info.name.clone(),
+            size: info.size,
+            etag: info.etag.clone(),
+            mod_time: info.mod_time,
+            version_id: info.version_id.map(|v| v.to_string()),
+            metadata: user_metadata(&info.user_defined),
+            tags: decode_tags_to_map(&info.user_tags)
+                .into_iter()
+                .filter(|(k, v)| !k.contains(SEP) && !v.contains(SEP))
+                .collect(),
+        }
+    }
+
+    fn attrs(&self) -> impl Iterator<Item = (AttrKind, &String, &String)> {
+        let metadata = self.metadata.iter().map(|(k, v)| (AttrKind::Metadata, k, v));
+        let tags = self.tags.iter().map(|(k, v)| (AttrKind::Tag, k, v));
+        metadata.chain(tags)
+    }
+
+    fn attr(&self, kind: AttrKind, key: &str) -> Option<&String> {
+        match kind {
+            AttrKind::Metadata => self.metadata.get(key),
+            AttrKind::Tag => self.tags.get(key),
+        }
+    }
+}
+
+/// The metadata of an object a client may search, without the internal keys and the meta prefixes.
+fn user_metadata(user_defined: &std::collections::HashMap<String, String>) -> BTreeMap<String, String> {
+    user_defined
+        .iter()
+        .filter_map(|(k, v)| {
+            let key = k.to_ascii_lowercase();
+            if key.starts_with(RESERVED_METADATA_PREFIX_LOWER) || key.contains(SEP) || v.contains(SEP) {
+                return None;
+            }
+            let key = match key.strip_prefix("x-amz-meta-").or_else(|| key.strip_prefix("x-rustfs-meta-")) {
+                Some(key) => key.to_string(),
+                None if key.starts_with("x-amz-") || key.starts_with("x-rustfs-") => return None,
+                None => key,
+            };
+            Some((key, v.clone()))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttrKind {
+    Metadata,
+    Tag,
+}
+
+impl AttrKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AttrKind::Metadata => "m",
+            AttrKind::Tag => "t",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PredicateOp {
+    /// The value equals the predicate value
+    #[default]
+    Eq,
+    /// The value starts with the predicate value
+    Prefix,
+    /// The key is set, whatever its value
+    Exists,
+}
+
+/// A condition on one metadata key or tag of an object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Predicate {
+    pub kind: AttrKind,
+    pub key: String,
+    #[serde(default)]
+    pub op: PredicateOp,
+    #[serde(default)]
+    pub value: String,
+}
+
+impl Predicate {
+    fn normalized(&self) -> Self {
+        let mut p = self.clone();
+        if p.kind == AttrKind::Metadata {
+            p.key = p.key.to_ascii_lowercase();
+        }
+        p
+    }
+
+    fn matches(&self, object: &IndexedObject) -> bool {
+        match (object.attr(self.kind, &self.key), self.op) {
+            (None, _) => false,
+            (Some(_), PredicateOp::Exists) => true,
+            (Some(v), PredicateOp::Eq) => *v == self.value,
+            (Some(v), PredicateOp::Prefix) => v.starts_with(&self.value),
+        }
+    }
+
+    /// Prefix of the attribute keys of the objects that may match.
+    fn attr_prefix(&self, bucket: &str) -> String {
+        let base = format!("{bucket}{SEP}{}{SEP}{}{SEP}", self.kind.as_str(), self.key);
+        match self.op {
+            PredicateOp::Exists => base,
+            PredicateOp::Prefix => format!("{base}{}", self.value),
+            PredicateOp::Eq => format!("{base}{}{SEP}", self.value),
+        }
+    }
+}
+
+/// Objects of `bucket` under `prefix` matching all `predicates`, in name order after `marker`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MetadataQuery {
+    pub bucket: String,
+    pub prefix: String,
+    pub predicates: Vec<Predicate>,
+    pub marker: String,
+    pub max_keys: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataQueryResult {
+    pub objects: Vec<IndexedObject>,
+    pub is_truncated: bool,
+    pub next_marker: Option<String>,
+}
+
+#[derive(Debug)]
+enum IndexUpdate {
+    Put(IndexedObject),
+    Remove(String, String),
+    RemoveBucket(String),
+}
+
+/// Where the backfill continues, the object after `marker` in `bucket`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct BackfillCursor {
+    bucket: String,
+    marker: String,
+}
+
+pub struct MetadataIndex {
+    env: Env,
+    /// `bucket SEP object` to the JSON of the indexed object
+    objects: Database<Str, Bytes>,
+    /// `bucket SEP kind SEP key SEP value SEP object` for every metadata key and tag of an object
+    attrs: Database<Str, Unit>,
+    state: Database<Str, Bytes>,
+    tx: mpsc::Sender<IndexUpdate>,
+}
+
+impl MetadataIndex {
+    fn open(dir: &Path, max_size: usize) -> Result<(Self, mpsc::Receiver<IndexUpdate>)> {
+        std::fs::create_dir_all(dir)?;
+
+        // SAFETY: the environment is opened once per process and its files are not changed by
+        // anything else while it is open.
+        let env = unsafe { EnvOpenOptions::new().map_size(max_size).max_dbs(3).open(dir) }.map_err(Error::other)?;
+
+        let mut wtxn = env.write_txn().map_err(Error::other)?;
+        let objects = env.create_database(&mut wtxn, Some("objects")).map_err(Error::other)?;
+        let attrs = env.create_database(&mut wtxn, Some("attrs")).map_err(Error::other)?;
+        let state = env.create_database(&mut wtxn, Some("state")).map_err(Error::other)?;
+        wtxn.commit().map_err(Error::other)?;
+
+        let (tx, rx) = mpsc::channel(UPDATE_QUEUE_SIZE);
+        Ok((
+            Self {
+                env,
+                objects,
+                attrs,
+                state,
+                tx,
+            },
+            rx,
+        ))
+    }
+
+    fn queue(&self, update: IndexUpdate) {
+        if self.tx.try_send(update).is_err() {
+            DROPPED_UPDATES.fetch_add(1, Ordering::Relaxed);
+            debug!("metadata index update dropped, the backfill will pick it up");
+        }
+    }
+
+    fn apply(&self, updates: Vec<IndexUpdate>) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(Error::other)?;
+        for update in updates {
+            match update {
+                IndexUpdate::Put(object) => self.put(&mut wtxn, &object)?,
+                IndexUpdate::Remove(bucket, object) => self.remove(&mut wtxn, &bucket, &object)?,
+                IndexUpdate::RemoveBucket(bucket) => self.remove_bucket(&mut wtxn, &bucket)?,
+            }
+        }
+        wtxn.commit().map_err(Error::other)
+    }
+
+    fn get(&self, rtxn: &RoTxn, bucket: &str, object: &str) -> Result<Option<IndexedObject>> {
+        let key = format!("{bucket}{SEP}{object}");
+        match self.objects.get(rtxn, &key).map_err(Error::other)? {
+            Some(data) => Ok(Some(serde_json::from_slice(data)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn put(&self, wtxn: &mut RwTxn, object: &IndexedObject) -> Result<()> {
+        self.remove(wtxn, &object.bucket, &object.name)?;
+
+        let key = format!("{}{SEP}{}", object.bucket, object.name);
+        self.objects
+            .put(wtxn, &key, &serde_json::to_vec(object)?)
+            .map_err(Error::other)?;
+        for attr in attr_keys(object) {
+            self.attrs.put(wtxn, &attr, &()).map_err(Error::other)?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, wtxn: &mut RwTxn, bucket: &str, name: &str) -> Result<()> {
+        let Some(object) = self.get(wtxn, bucket, name)? else {
+            return Ok(());
+        };
+
+        for attr in attr_keys(&object) {
+            self.attrs.delete(wtxn, &attr).map_err(Error::other)?;
+        }
+        self.objects
+            .delete(wtxn, &format!("{bucket}{SEP}{name}"))
+            .map_err(Error::other)?;
+        Ok(())
+    }
+
+    fn remove_bucket(&self, wtxn: &mut RwTxn, bucket: &str) -> Result<()> {
+        let prefix = format!("{bucket}{SEP}");
+        for db in [
+            self.objects.remap_data_type::<DecodeIgnore>(),
+            self.attrs.remap_data_type::<DecodeIgnore>(),
+        ] {
+            let keys = db
+                .prefix_iter(wtxn, &prefix)
+                .map_err(Error::other)?
+                .map(|item| item.map(|(k, _)| k.to_string()))
+                .collect::<heed::Result<Vec<_>>>()
+                .map_err(Error::other)?;
+            for key in keys {
+                db.delete(wtxn, &key).map_err(Error::other)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Names of the indexed objects of `bucket` after `after`, up to and including `upto`.
+    fn names_between(&self, rtxn: &RoTxn, bucket: &str, after: &str, upto: Option<&str>) -> Result<Vec<String>> {
+        let prefix = format!("{bucket}{SEP}");
+        let mut names = Vec::new();
+        for item in self.objects.prefix_iter(rtxn, &prefix).map_err(Error::other)? {
+            let (key, _) = item.map_err(Error::other)?;
+            let name = &key[prefix.len()..];
+            if name <= after {
+                continue;
+            }
+            if upto.is_some_and(|upto| name > upto) {
+                break;
+            }
+            names.push(name.to_string());
+        }
+        Ok(names)
+    }
+
+    /// Buckets with objects in the index.
+    fn buckets(&self) -> Result<Vec<String>> {
+        let rtxn = self.env.read_txn().map_err(Error::other)?;
+        let mut buckets = Vec::new();
+        let mut from = String::new();
+        loop {
+            let range = (Bound::Included(from.as_str()), Bound::Unbounded);
+            let Some(item) = self.objects.range(&rtxn, &range).map_err(Error::other)?.next() else {
+                break;
+            };
+            let (key, _) = item.map_err(Error::other)?;
+            let bucket = key.split(SEP).next().unwrap_or_default().to_string();
+            // The smallest key after every key of the bucket
+            from = format!("{bucket}\u{1}");
+            buckets.push(bucket);
+        }
+        Ok(buckets)
+    }
+
+    /// Replace the indexed objects of `bucket` after `after` and up to `upto` by `listed`, the
+    /// objects a listing returned for that range.
+    fn reconcile(&self, bucket: &str, after: &str, upto: Option<&str>, listed: Vec<IndexedObject>) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(Error::other)?;
+        let names: HashSet<&str> = listed.iter().map(|o| o.name.as_str()).collect();
+        for name in self.names_between(&wtxn, bucket, after, upto)? {
+            if !names.contains(name.as_str()) {
+                self.remove(&mut wtxn, bucket, &name)?;
+            }
+        }
+        for object in listed.iter() {
+            if self.get(&wtxn, bucket, &object.name)?.as_ref() != Some(object) {
+                self.put(&mut wtxn, object)?;
+            }
+        }
+        wtxn.commit().map_err(Error::other)
+    }
+
+    fn load_cursor(&self) -> Result<BackfillCursor> {
+        let rtxn = self.env.read_txn().map_err(Error::other)?;
+        match self.state.get(&rtxn, BACKFILL_CURSOR_KEY).map_err(Error::other)? {
+            Some(data) => Ok(serde_json::from_slice(data)?),
+            None => Ok(BackfillCursor::default()),
+        }
+    }
+
+    fn save_cursor(&self, cursor: &BackfillCursor) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(Error::other)?;
+        self.state
+            .put(&mut wtxn, BACKFILL_CURSOR_KEY, &serde_json::to_vec(cursor)?)
+            .map_err(Error::other)?;
+        wtxn.commit().map_err(Error::other)
+    }
+
+    /// Run a query, reading only the objects matching the first predicate when there is one.
+    pub fn query(&self, query: &MetadataQuery) -> Result<MetadataQueryResult> {
+        let rtxn = self.env.read_txn().map_err(Error::other)?;
+        let predicates: Vec<Predicate> = query.predicates.iter().map(Predicate::normalized).collect();
+        let max_keys = query.max_keys.max(1);
+        let in_range = |name: &str| name.starts_with(&query.prefix) && name > query.marker.as_str();
+
+        let candidates: BTreeSet<String> = match predicates.first() {
+            Some(first) => {
+                let mut names = BTreeSet::new();
+                for item in self
+                    .attrs
+                    .prefix_iter(&rtxn, &first.attr_prefix(&query.bucket))
+                    .map_err(Error::other)?
+                {
+                    let (key, _) = item.map_err(Error::other)?;
+                    if let Some(name) = key.splitn(5, SEP).nth(4)
+                        && in_range(name)
+                    {
+                        names.insert(name.to_string());
+                    }
+                }
+                names
+            }
+            None => {
+                let prefix = format!("{}{SEP}{}", query.bucket, query.prefix);
+                let mut names = BTreeSet::new();
+                for item in self.objects.prefix_iter(&rtxn, &prefix).map_err(Error::other)? {
+                    let (key, _) = item.map_err(Error::other)?;
+                    let name = &key[query.bucket.len() + 1..];
+                    if in_range(name) {
+                        names.insert(name.to_string());
+                        if names.len() > max_keys {
+                            break;
+                        }
+                    }
+                }
+                names
+            }
+        };
+
+        let mut result = MetadataQueryResult::default();
+        for name in candidates {
+            let Some(object) = self.get(&rtxn, &query.bucket, &name)? else {
+                continue;
+            };
+            if !predicates.iter().all(|p| p.matches(&object)) {
+                continue;
+            }
+            if result.objects.len() == max_keys {
+                result.is_truncated = true;
+                result.next_marker = result.objects.last().map(|o| o.name.clone());
+                break;
+            }
+            result.objects.push(object);
+        }
+        Ok(result)
+    }
+}
+
+fn attr_keys(object: &IndexedObject) -> Vec<String> {
+    object
+        .attrs()
+        .map(|(kind, k, v)| format!("{}{SEP}{}{SEP}{k}{SEP}{v}{SEP}{}", object.bucket, kind.as_str(), object.name))
+        .collect()
+}
+
+async fn run_writer(index: Arc<MetadataIndex>, mut rx: mpsc::Receiver<IndexUpdate>, cancel: CancellationToken) {
+    loop {
+        let update = tokio::select! {
+            _ = cancel.cancelled() => return,
+            update = rx.recv() => match update {
+                Some(update) => update,
+                None => return,
+            },
+        };
+
+        let mut updates = vec![update];
+        while updates.len() < WRITE_BATCH
+            && let Ok(update) = rx.try_recv()
+        {
+            updates.push(update);
+        }
+
+        let index = index.clone();
+        match tokio::task::spawn_blocking(move || index.apply(updates)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => warn!("updating the metadata index failed: {}", err),
+            Err(err) => warn!("updating the metadata index failed: {}", err),
+        }
+    }
+}
+
+/// Read the next batch of objects into the index, continuing from where the last call stopped.
+/// Returns the number of objects read.
+pub async fn backfill_metadata_index(store: Arc<ECStore>) -> Result<usize> {
+    let Some(index) = get_metadata_index() else {
+        return Ok(0);
+    };
+    let batch = get_env_u64(ENV_METADATA_INDEX_BACKFILL_BATCH, DEFAULT_METADATA_INDEX_BACKFILL_BATCH) as usize;
+
+    let mut buckets: Vec<String> = store
+        .list_bucket(&BucketOptions {
+            no_metadata: true,
+            ..Default::default()
+        })
+        .await?
+        .into_iter()
+        .map(|b| b.name)
+        .filter(|name| !is_meta_bucketname(name))
+        .collect();
+    buckets.sort();
+
+    let mut cursor = index.load_cursor()?;
+    if cursor.bucket.is_empty() {
+        // A new pass starts, drop the buckets deleted through other nodes
+        for bucket in index.buckets()? {
+            if buckets.binary_search(&bucket).is_err() {
+                index.queue(IndexUpdate::RemoveBucket(bucket));
+            }
+        }
+    }
+
+    let mut read = 0;
+    while read < batch {
+        let Some(bucket) = buckets.iter().find(|b| **b >= cursor.bucket).cloned() else {
+            cursor = BackfillCursor::default();
+            break;
+        };
+        if bucket != cursor.bucket {
+            cursor = BackfillCursor {
+                bucket: bucket.clone(),
+                marker: String::new(),
+            };
+        }
+
+        let page = store
+            .clone()
+            .list_objects_v2(
+                &bucket,
+                "",
+                None,
+                None,
+                BACKFILL_PAGE.min(batch - read) as i32,
+                false,
+                (!cursor.marker.is_empty()).then(|| cursor.marker.clone()),
+                false,
+                None,
+            )
+            .await?;
+
+        let listed: Vec<IndexedObject> = page
+            .objects
+            .iter()
+            .filter(|o| !o.is_dir && !o.delete_marker)
+            .map(|o| IndexedObject::new(&bucket, o))
+            .collect();
+        read += page.objects.len();
+
+        let last = page.objects.last().map(|o| o.name.clone());
+        let upto = if page.is_truncated { last.clone() } else { None };
+        let after = cursor.marker.clone();
+        let idx = index.clone();
+        let reconcile_bucket = bucket.clone();
+        tokio::task::spawn_blocking(move || idx.reconcile(&reconcile_bucket, &after, upto.as_deref(), listed)).await??;
+
+        match (page.is_truncated, last) {
+            (true, Some(last)) => cursor.marker = last,
+            // The smallest bucket name after this one
+            _ => {
+                cursor = BackfillCursor {
+                    bucket: format!("{bucket}{SEP}"),
+                    marker: String::new(),
+                }
+            }
+        }
+    }
+
+    index.save_cursor(&cursor)?;
+    Ok(read)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(name: &str, metadata: &[(&str, &str)], tags: &[(&str, &str)]) -> IndexedObject {
+        IndexedObject {
+            bucket: "bucket".to_string(),
+            name: name.to_string(),
+            size: 1,
+            etag: None,
+            mod_time: None,
+            version_id: None,
+            metadata: metadata.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            tags: tags.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+
+    fn predicate(kind: AttrKind, key: &str, op: PredicateOp, value: &str) -> Predicate {
+        Predicate {
+            kind,
+            key: key.to_string(),
+            op,
+            value: value.to_string(),
+        }
+    }
+
+    fn names(result: &MetadataQueryResult) -> Vec<&str> {
+        result.objects.iter().map(|o| o.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_user_metadata() {
+        let user_defined = [
+            ("X-Amz-Meta-Color", "red"),
+            ("content-type", "image/png"),
+            ("x-rustfs-internal-actual-size", "1"),
+            ("X-Amz-Storage-Class", "STANDARD"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let metadata = user_metadata(&user_defined);
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata["color"], "red");
+        assert_eq!(metadata["content-type"], "image/png");
+    }
+
+    #[test]
+    fn test_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let (index, _rx) = MetadataIndex::open(dir.path(), 16 * 1024 * 1024).unwrap();
+
+        index
+            .apply(vec![
+                IndexUpdate::Put(object("a/1", &[("color", "red")], &[("env", "prod")])),
+                IndexUpdate::Put(object("a/2", &[("color", "reddish")], &[("env", "dev")])),
+                IndexUpdate::Put(object("b/3", &[("color", "red")], &[])),
+            ])
+            .unwrap();
+
+        let mut query = MetadataQuery {
+            bucket: "bucket".to_string(),
+            predicates: vec![predicate(AttrKind::Metadata, "Color", PredicateOp::Eq, "red")],
+            max_keys: 10,
+            ..Default::default()
+        };
+        assert_eq!(names(&index.query(&query).unwrap()), ["a/1", "b/3"]);
+
+        query.predicates[0].op = PredicateOp::Prefix;
+        query.prefix = "a/".to_string();
+        assert_eq!(names(&index.query(&query).unwrap()), ["a/1", "a/2"]);
+
+        query.predicates.push(predicate(AttrKind::Tag, "env", PredicateOp::Eq, "dev"));
+        assert_eq!(names(&index.query(&query).unwrap()), ["a/2"]);
+
+        let query = MetadataQuery {
+            bucket: "bucket".to_string(),
+            predicates: vec![predicate(AttrKind::Tag, "env", PredicateOp::Exists, "")],
+            max_keys: 1,
+            ..Default::default()
+        };
+        let result = index.query(&query).unwrap();
+        assert_eq!(names(&result), ["a/1"]);
+        assert!(result.is_truncated);
+        assert_eq!(result.next_marker.as_deref(), Some("a/1"));
+
+        // Updating an object drops its old attributes
+        index
+            .apply(vec![IndexUpdate::Put(object("a/1", &[("color", "blue")], &[]))])
+            .unwrap();
+        let result = index.query(&query).unwrap();
+        assert_eq!(names(&result), ["a/2"]);
+        assert!(!result.is_truncated);
+    }
+
+    #[test]
+    fn test_reconcile_and_remove_bucket() {
+        let dir = tempfile::tempdir().unwrap();
+        let (index, _rx) = MetadataIndex::open(dir.path(), 16 * 1024 * 1024).unwrap();
+
+        index
+            .apply(vec![
+                IndexUpdate::Put(object("1", &[("k", "v")], &[])),
+                IndexUpdate::Put(object("2", &[("k", "v")], &[])),
+                IndexUpdate::Put(object("3", &[("k", "v")], &[])),
+            ])
+            .unwrap();
+
+        // "2" was deleted through another node and "4" written through it
+        index
+            .reconcile("bucket", "1", None, vec![object("3", &[("k", "v")], &[]), object("4", &[], &[])])
+            .unwrap();
+
+        let query = MetadataQuery {
+            bucket: "bucket".to_string(),
+            max_keys: 10,
+            ..Default::default()
+        };
+        assert_eq!(names(&index.query(&query).unwrap()), ["1", "3", "4"]);
+        assert_eq!(index.buckets().unwrap(), ["bucket"]);
+
+        index.apply(vec![IndexUpdate::RemoveBucket("bucket".to_string())]).unwrap();
+        assert!(index.query(&query).unwrap().objects.is_empty());
+        assert!(index.buckets().unwrap().is_empty());
+    }
+}
@@ -19,6 +19,7 @@ use crate::global::{GLOBAL_BOOT_TIME, get_global_endpoints};
 use crate::metrics_realtime::{CollectMetricsOpts, MetricType};
 use crate::rpc::PeerRestClient;
 use crate::{endpoints::EndpointServerPools, new_object_layer_fn};
+use bytes::Bytes;
 use futures::future::join_all;
 use lazy_static::lazy_static;
 use rustfs_madmin::health::{Cpus, MemInfo, OsInfo, Partitions, ProcInfo, SysConfig, SysErrors, SysService};
@@ -417,6 +418,34 @@ impl NotificationSys {
         warn!("notification stop_rebalance stop_rebalance done");
     }
 
+    /// Stream a cached listing from `owner`, the name the peer is known by.
+    pub async fn get_metacache_listing(&self, owner: &str, opts: Bytes) -> Result<Vec<Bytes>> {
+        let Some(client) = self
+            .peer_clients
+            .iter()
+            .flatten()
+            .find(|client| client.host.to_string() == owner)
+        else {
+            return Err(Error::other(format!("peer {owner} not found")));
+        };
+
+        client.get_metacache_listing(opts).await
+    }
+
+    pub async fn update_metacache_listing(&self, metacache: Bytes) {
+        let mut futures = Vec::with_capacity(self.peer_clients.len());
+        for client in self.peer_clients.iter().flatten() {
+            futures.push(client.update_metacache_listing(metacache.clone()));
+        }
+
+        let results = join_all(futures).await;
+        for result in results {
+            if let Err(err) = result {
+                error!("notification update_metacache_listing err {:?}", err);
+            }
+        }
+    }
+
     pub async fn load_bucket_metadata(&self, bucket: &str) -> Vec<NotificationPeerErr> {
         let mut futures = Vec::with_capacity(self.peer_clients.len());
         for client in self.peer_clients.iter() {
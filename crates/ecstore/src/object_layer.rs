@@ -0,0 +1,115 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Object operations a storage backend has to provide, and the choice of backend at startup.
+//!
+//! Erasure coding over the configured drives is the default. The filesystem backend stores every
+//! object as a plain file on a single drive, for development machines and edge deployments where
+//! erasure coding buys nothing. Buckets, the system bucket and all the subsystems built on it stay
+//! on the erasure layer either way, only the objects of user buckets go through the backend.
+
+use crate::error::{Error, Result};
+use crate::store_api::{GetObjectReader, HTTPRangeSpec, ListObjectsV2Info, ObjectInfo, ObjectOptions, PutObjReader};
+use http::HeaderMap;
+use std::fmt::Debug;
+use std::str::FromStr;
+
+/// Storage backend, `erasure` or `fs`.
+pub const ENV_STORAGE_BACKEND: &str = "RUSTFS_STORAGE_BACKEND";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageBackend {
+    #[default]
+    Erasure,
+    /// Plain files on a single drive, no erasure coding.
+    Fs,
+}
+
+impl FromStr for StorageBackend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "erasure" => Ok(Self::Erasure),
+            "fs" | "filesystem" => Ok(Self::Fs),
+            other => Err(Error::other(format!("unknown storage backend {other}, expected erasure or fs"))),
+        }
+    }
+}
+
+impl StorageBackend {
+    pub fn from_env() -> Result<Self> {
+        std::env::var(ENV_STORAGE_BACKEND).unwrap_or_default().parse()
+    }
+}
+
+/// Object operations on user buckets, implemented by storage backends other than erasure coding.
+///
+/// Callers have validated bucket and object names already, and the bucket is known to exist in
+/// the bucket metadata.
+#[async_trait::async_trait]
+pub trait ObjectLayer: Send + Sync + Debug {
+    async fn get_object_reader(
+        &self,
+        bucket: &str,
+        object: &str,
+        range: Option<HTTPRangeSpec>,
+        h: HeaderMap,
+        opts: &ObjectOptions,
+    ) -> Result<GetObjectReader>;
+
+    async fn put_object(&self, bucket: &str, object: &str, data: &mut PutObjReader, opts: &ObjectOptions) -> Result<ObjectInfo>;
+
+    async fn get_object_info(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<ObjectInfo>;
+
+    /// Copy `src_info`, whose data is in its `put_object_reader`, to the destination. Copying an
+    /// object onto itself replaces its metadata.
+    async fn copy_object(
+        &self,
+        src_bucket: &str,
+        src_object: &str,
+        dst_bucket: &str,
+        dst_object: &str,
+        src_info: &mut ObjectInfo,
+        dst_opts: &ObjectOptions,
+    ) -> Result<ObjectInfo>;
+
+    async fn delete_object(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<ObjectInfo>;
+
+    async fn list_objects_v2(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        continuation_token: Option<String>,
+        delimiter: Option<String>,
+        max_keys: i32,
+        start_after: Option<String>,
+    ) -> Result<ListObjectsV2Info>;
+
+    /// Drop whatever the backend keeps for `bucket` once the bucket is deleted.
+    async fn delete_bucket(&self, bucket: &str) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_storage_backend() {
+        assert_eq!("".parse::<StorageBackend>().unwrap(), StorageBackend::Erasure);
+        assert_eq!("FS".parse::<StorageBackend>().unwrap(), StorageBackend::Fs);
+        assert_eq!("filesystem".parse::<StorageBackend>().unwrap(), StorageBackend::Fs);
+        assert!("raid".parse::<StorageBackend>().is_err());
+    }
+}
@@ -0,0 +1,485 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Detection of orphaned object data on the local drives.
+//!
+//! The shards of an object version live in a data directory named after a UUID next to the
+//! `xl.meta` of the object. A crash between moving a data directory into place and writing the
+//! metadata, or an overwrite whose old data directory could not be removed, leaves data directories
+//! that no version references. Nothing reads or deletes them again, so the deep scan walks the
+//! drives to report them and, when enabled, to remove them.
+
+use crate::disk::{DeleteOptions, DiskAPI, DiskStore, STORAGE_FORMAT_FILE, error::DiskError};
+use crate::error::{Error, Result};
+use crate::store::ECStore;
+use futures::future::join_all;
+use parking_lot::RwLock;
+use rustfs_config::{
+    DEFAULT_ORPHANED_DATA_MIN_AGE, DEFAULT_ORPHANED_DATA_RECLAIM, ENV_ORPHANED_DATA_MIN_AGE, ENV_ORPHANED_DATA_RECLAIM,
+};
+use rustfs_filemeta::FileMeta;
+use rustfs_utils::{get_env_bool, get_env_u64};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, SystemTime};
+use time::OffsetDateTime;
+use tokio::task;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Orphans listed in a report, the counters keep covering the ones beyond.
+const MAX_REPORTED_ORPHANS: usize = 10_000;
+
+static LAST_REPORT: LazyLock<RwLock<Option<OrphanedDataReport>>> = LazyLock::new(|| RwLock::new(None));
+
+/// Serializes scans so a reclaim never races another walk of the same drives.
+static SCAN_LOCK: LazyLock<tokio::sync::Mutex<()>> = LazyLock::new(|| tokio::sync::Mutex::new(()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OrphanReason {
+    /// The object directory holds no `xl.meta` at all.
+    NoMetadata,
+    /// The `xl.meta` of the object has no version stored in the directory.
+    Unreferenced,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedDataDir {
+    pub disk: String,
+    pub bucket: String,
+    pub object: String,
+    pub data_dir: Uuid,
+    pub reason: OrphanReason,
+    pub parts: usize,
+    pub size: u64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub mod_time: OffsetDateTime,
+    #[serde(default)]
+    pub reclaimed: bool,
+}
+
+/// Orphaned data found on the local drives of this node by the last scan.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedDataReport {
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub scanned_at: Option<OffsetDateTime>,
+    pub min_age_secs: u64,
+    pub disks_scanned: usize,
+    pub orphans_count: u64,
+    pub total_size: u64,
+    pub reclaimed_count: u64,
+    pub reclaimed_size: u64,
+    /// More orphans were found than are listed in `orphans`.
+    pub truncated: bool,
+    pub orphans: Vec<OrphanedDataDir>,
+}
+
+impl OrphanedDataReport {
+    fn add(&mut self, orphan: OrphanedDataDir) {
+        self.orphans_count += 1;
+        self.total_size = self.total_size.saturating_add(orphan.size);
+        if orphan.reclaimed {
+            self.reclaimed_count += 1;
+            self.reclaimed_size = self.reclaimed_size.saturating_add(orphan.size);
+        }
+        if self.orphans.len() < MAX_REPORTED_ORPHANS {
+            self.orphans.push(orphan);
+        } else {
+            self.truncated = true;
+        }
+    }
+}
+
+/// How long a data directory must have gone unchanged before it may be reported.
+pub fn min_age() -> Duration {
+    Duration::from_secs(get_env_u64(ENV_ORPHANED_DATA_MIN_AGE, DEFAULT_ORPHANED_DATA_MIN_AGE))
+}
+
+/// Whether the deep scan removes the orphaned data it finds.
+pub fn reclaim_enabled() -> bool {
+    get_env_bool(ENV_ORPHANED_DATA_RECLAIM, DEFAULT_ORPHANED_DATA_RECLAIM)
+}
+
+/// Report of the last scan run on this node, if any.
+pub fn last_report() -> Option<OrphanedDataReport> {
+    LAST_REPORT.read().clone()
+}
+
+/// Walk every local drive for orphaned data directories, removing them when `reclaim` is set.
+///
+/// Every drive of a set holds its own shards, so unlike usage collection all local drives are
+/// walked, not only the first one of each set.
+pub async fn scan_orphaned_data(store: Arc<ECStore>, reclaim: bool) -> Result<OrphanedDataReport> {
+    let _guard = SCAN_LOCK.lock().await;
+
+    let min_age = min_age();
+    let mut disks = Vec::new();
+    for pool in store.pools.iter() {
+        for set_disks in pool.disk_set.iter() {
+            let set = set_disks.disks.read().await;
+            disks.extend(set.iter().flatten().filter(|disk| disk.is_local()).cloned());
+        }
+    }
+
+    let results = join_all(disks.iter().map(|disk| scan_disk(disk.clone(), min_age, reclaim))).await;
+
+    let mut report = OrphanedDataReport {
+        scanned_at: Some(OffsetDateTime::now_utc()),
+        min_age_secs: min_age.as_secs(),
+        ..Default::default()
+    };
+    for (disk, result) in disks.iter().zip(results) {
+        match result {
+            Ok(orphans) => {
+                report.disks_scanned += 1;
+                orphans.into_iter().for_each(|orphan| report.add(orphan));
+            }
+            Err(err) => warn!("orphaned data scan of {} failed: {}", disk.to_string(), err),
+        }
+    }
+
+    if report.orphans_count > 0 {
+        info!(
+            "found {} orphaned data directories ({} bytes), reclaimed {}",
+            report.orphans_count, report.total_size, report.reclaimed_count
+        );
+    }
+
+    *LAST_REPORT.write() = Some(report.clone());
+    Ok(report)
+}
+
+async fn scan_disk(disk: DiskStore, min_age: Duration, reclaim: bool) -> Result<Vec<OrphanedDataDir>> {
+    let root = disk.path();
+    let walk_root = root.clone();
+    let found = task::spawn_blocking(move || find_orphans(&walk_root, min_age, SystemTime::now()))
+        .await
+        .map_err(|e| Error::other(format!("orphaned data walk panicked: {e}")))?;
+
+    let name = disk.to_string();
+    let mut orphans = Vec::with_capacity(found.len());
+    for orphan in found {
+        let reclaimed = reclaim && reclaim_orphan(&disk, &root, &orphan, min_age).await;
+        orphans.push(OrphanedDataDir {
+            disk: name.clone(),
+            bucket: orphan.bucket,
+            object: orphan.object,
+            data_dir: orphan.data_dir,
+            reason: orphan.reason,
+            parts: orphan.parts,
+            size: orphan.size,
+            mod_time: OffsetDateTime::from(orphan.mod_time),
+            reclaimed,
+        });
+    }
+    Ok(orphans)
+}
+
+/// Remove one orphan after checking it is still unreferenced and untouched.
+async fn reclaim_orphan(disk: &DiskStore, root: &Path, orphan: &FoundOrphan, min_age: Duration) -> bool {
+    let object_dir = object_dir(root, &orphan.bucket, &orphan.object);
+    let data_dir = orphan.data_dir;
+    let still_orphaned = task::spawn_blocking(move || {
+        let meta = read_meta_state(&object_dir);
+        inspect_data_dir(&object_dir, &meta, data_dir, min_age, SystemTime::now()).is_some()
+    })
+    .await
+    .unwrap_or(false);
+    if !still_orphaned {
+        debug!(
+            "orphaned data {}/{}/{} changed since the walk, kept",
+            orphan.bucket, orphan.object, data_dir
+        );
+        return false;
+    }
+
+    let path = if orphan.object.is_empty() {
+        data_dir.to_string()
+    } else {
+        format!("{}/{}", orphan.object, data_dir)
+    };
+    let opts = DeleteOptions {
+        recursive: true,
+        ..Default::default()
+    };
+    match disk.delete(&orphan.bucket, &path, opts).await {
+        Ok(()) | Err(DiskError::FileNotFound) => true,
+        Err(err) => {
+            warn!(
+                "failed to remove orphaned data {}/{} on {}: {}",
+                orphan.bucket,
+                path,
+                disk.to_string(),
+                err
+            );
+            false
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FoundOrphan {
+    bucket: String,
+    object: String,
+    data_dir: Uuid,
+    reason: OrphanReason,
+    parts: usize,
+    size: u64,
+    mod_time: SystemTime,
+}
+
+/// What the `xl.meta` of a directory says about the data directories next to it.
+enum MetaState {
+    Missing,
+    Referenced(HashSet<Uuid>),
+    /// The metadata could not be read, nothing next to it is judged.
+    Unreadable,
+}
+
+fn read_meta_state(dir: &Path) -> MetaState {
+    let buf = match std::fs::read(dir.join(STORAGE_FORMAT_FILE)) {
+        Ok(buf) => buf,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return MetaState::Missing,
+        Err(err) => {
+            debug!("failed to read {:?}: {}", dir.join(STORAGE_FORMAT_FILE), err);
+            return MetaState::Unreadable;
+        }
+    };
+    match FileMeta::load(&buf).and_then(|meta| meta.get_data_dirs()) {
+        Ok(dirs) => MetaState::Referenced(dirs.into_iter().flatten().collect()),
+        Err(err) => {
+            debug!("failed to decode {:?}: {}", dir.join(STORAGE_FORMAT_FILE), err);
+            MetaState::Unreadable
+        }
+    }
+}
+
+fn object_dir(root: &Path, bucket: &str, object: &str) -> PathBuf {
+    let dir = root.join(bucket);
+    if object.is_empty() { dir } else { dir.join(object) }
+}
+
+/// Walk the buckets under `root`, system buckets excluded, for data directories no version references.
+fn find_orphans(root: &Path, min_age: Duration, now: SystemTime) -> Vec<FoundOrphan> {
+    let mut found = Vec::new();
+    let Ok(buckets) = std::fs::read_dir(root) else {
+        return found;
+    };
+
+    for bucket in buckets.flatten() {
+        let bucket_name = bucket.file_name().to_string_lossy().to_string();
+        if bucket_name.starts_with('.') || !bucket.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+
+        let mut pending = vec![String::new()];
+        while let Some(object) = pending.pop() {
+            let dir = object_dir(root, &bucket_name, &object);
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            let meta = read_meta_state(&dir);
+
+            for entry in entries.flatten() {
+                if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    continue;
+                }
+                let name = entry.file_name().to_string_lossy().to_string();
+                let child = if object.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{object}/{name}")
+                };
+
+                if let Ok(data_dir) = Uuid::parse_str(&name)
+                    && is_data_dir(&entry.path())
+                {
+                    if let Some(mut orphan) = inspect_data_dir(&dir, &meta, data_dir, min_age, now) {
+                        orphan.bucket = bucket_name.clone();
+                        orphan.object = object.clone();
+                        found.push(orphan);
+                    }
+                    continue;
+                }
+
+                pending.push(child);
+            }
+        }
+    }
+
+    found
+}
+
+/// A directory holding part files and no metadata of its own, as opposed to an object or prefix.
+fn is_data_dir(dir: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    let mut has_parts = false;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name == STORAGE_FORMAT_FILE {
+            return false;
+        }
+        has_parts |= name.starts_with("part.");
+    }
+    has_parts
+}
+
+/// Judge the data directory `data_dir` of `object_dir`, returning it when orphaned and old enough.
+fn inspect_data_dir(
+    object_dir: &Path,
+    meta: &MetaState,
+    data_dir: Uuid,
+    min_age: Duration,
+    now: SystemTime,
+) -> Option<FoundOrphan> {
+    let reason = match meta {
+        MetaState::Missing => OrphanReason::NoMetadata,
+        MetaState::Referenced(dirs) if !dirs.contains(&data_dir) => OrphanReason::Unreferenced,
+        _ => return None,
+    };
+
+    let dir = object_dir.join(data_dir.to_string());
+    // Moving data into place touches the directory, so its own time counts along with the parts
+    let mut mod_time = std::fs::metadata(&dir).and_then(|m| m.modified()).ok()?;
+    let mut parts = 0;
+    let mut size = 0u64;
+    for entry in std::fs::read_dir(&dir).ok()?.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if let Ok(modified) = metadata.modified() {
+            mod_time = mod_time.max(modified);
+        }
+        if metadata.is_file() && entry.file_name().to_string_lossy().starts_with("part.") {
+            parts += 1;
+            size = size.saturating_add(metadata.len());
+        }
+    }
+
+    if parts == 0 || now.duration_since(mod_time).ok().is_none_or(|age| age < min_age) {
+        return None;
+    }
+
+    Some(FoundOrphan {
+        bucket: String::new(),
+        object: String::new(),
+        data_dir,
+        reason,
+        parts,
+        size,
+        mod_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustfs_filemeta::FileInfo;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    fn write_parts(dir: &Path, data_dir: Uuid) {
+        let dir = dir.join(data_dir.to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("part.1"), b"shard").unwrap();
+    }
+
+    fn write_meta(dir: &Path, data_dirs: &[Uuid]) {
+        let mut meta = FileMeta::new();
+        for data_dir in data_dirs {
+            let mut fi = FileInfo::new("object", 2, 1);
+            fi.version_id = Some(Uuid::new_v4());
+            fi.data_dir = Some(*data_dir);
+            fi.mod_time = Some(OffsetDateTime::now_utc());
+            meta.add_version(fi).unwrap();
+        }
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join(STORAGE_FORMAT_FILE), meta.marshal_msg().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_find_orphans() {
+        let root = tempfile::tempdir().unwrap();
+        let bucket = root.path().join("bucket");
+
+        let (live, stale, lost) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        write_meta(&bucket.join("obj"), &[live]);
+        write_parts(&bucket.join("obj"), live);
+        write_parts(&bucket.join("obj"), stale);
+        write_parts(&bucket.join("gone"), lost);
+
+        // An object named like a UUID is walked, not taken for a data directory
+        let nested = Uuid::new_v4();
+        let nested_object = bucket.join("prefix").join(Uuid::new_v4().to_string());
+        write_meta(&nested_object, &[nested]);
+        write_parts(&nested_object, nested);
+
+        write_parts(&root.path().join(".rustfs.sys").join("tmp"), Uuid::new_v4());
+
+        let later = SystemTime::now() + 2 * HOUR;
+        let mut found = find_orphans(root.path(), HOUR, later);
+        found.sort_by(|a, b| a.object.cmp(&b.object));
+
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].object.as_str(), found[0].data_dir), ("gone", lost));
+        assert_eq!(found[0].reason, OrphanReason::NoMetadata);
+        assert_eq!((found[1].object.as_str(), found[1].data_dir), ("obj", stale));
+        assert_eq!(found[1].reason, OrphanReason::Unreferenced);
+        assert_eq!((found[1].parts, found[1].size), (1, 5));
+
+        // Recent data may belong to a write in flight
+        assert!(find_orphans(root.path(), HOUR, SystemTime::now()).is_empty());
+    }
+
+    #[test]
+    fn test_unreadable_meta_keeps_data() {
+        let root = tempfile::tempdir().unwrap();
+        let object = root.path().join("bucket").join("obj");
+        write_parts(&object, Uuid::new_v4());
+        std::fs::write(object.join(STORAGE_FORMAT_FILE), b"garbage").unwrap();
+
+        assert!(find_orphans(root.path(), Duration::ZERO, SystemTime::now() + HOUR).is_empty());
+    }
+
+    #[test]
+    fn test_report_truncates_listing() {
+        let mut report = OrphanedDataReport::default();
+        for _ in 0..MAX_REPORTED_ORPHANS + 1 {
+            report.add(OrphanedDataDir {
+                disk: "disk".to_string(),
+                bucket: "bucket".to_string(),
+                object: "obj".to_string(),
+                data_dir: Uuid::new_v4(),
+                reason: OrphanReason::Unreferenced,
+                parts: 1,
+                size: 2,
+                mod_time: OffsetDateTime::now_utc(),
+                reclaimed: true,
+            });
+        }
+
+        assert!(report.truncated);
+        assert_eq!(report.orphans.len(), MAX_REPORTED_ORPHANS);
+        assert_eq!(report.orphans_count, MAX_REPORTED_ORPHANS as u64 + 1);
+        assert_eq!(report.reclaimed_size, 2 * (MAX_REPORTED_ORPHANS as u64 + 1));
+    }
+}
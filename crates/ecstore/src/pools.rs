@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::bandwidth::{GLOBAL_BANDWIDTH_SYS, TrafficClass};
 use crate::bucket::versioning_sys::BucketVersioningSys;
 use crate::cache_value::metacache_set::{ListPathRawOptions, list_path_raw};
 use crate::config::com::{CONFIG_PREFIX, read_config, save_config};
@@ -45,6 +46,7 @@ use std::collections::HashMap;
 use std::fmt::Display;
 use std::io::{Cursor, Write};
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use time::{Duration, OffsetDateTime};
 use tokio::io::{AsyncReadExt, BufReader};
@@ -65,6 +67,54 @@ pub struct PoolStatus {
     pub last_update: OffsetDateTime,
     #[serde(rename = "decommissionInfo")]
     pub decommission: Option<PoolDecommissionInfo>,
+    #[serde(rename = "class", default)]
+    pub class: PoolClass,
+}
+
+/// Tier served by a pool, used to place new objects by storage class or bucket configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PoolClass {
+    #[default]
+    Standard,
+    Hot,
+    Cold,
+}
+
+impl PoolClass {
+    /// Whether a pool of this class may take objects that prefer `wanted`.
+    ///
+    /// Without an explicit preference, cold pools are kept for data that asks for them.
+    pub fn accepts(&self, wanted: Option<PoolClass>) -> bool {
+        match wanted {
+            Some(class) => *self == class,
+            None => *self != PoolClass::Cold,
+        }
+    }
+}
+
+impl Display for PoolClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            PoolClass::Standard => "standard",
+            PoolClass::Hot => "hot",
+            PoolClass::Cold => "cold",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for PoolClass {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "standard" => Ok(PoolClass::Standard),
+            "hot" => Ok(PoolClass::Hot),
+            "cold" => Ok(PoolClass::Cold),
+            _ => Err(Error::other(format!("invalid pool class: {s}"))),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -102,6 +152,7 @@ impl PoolMeta {
                 id: idx,
                 last_update: OffsetDateTime::now_utc(),
                 decommission: None,
+                class: PoolClass::default(),
             });
         }
 
@@ -116,6 +167,22 @@ impl PoolMeta {
         self.pools[idx].decommission.is_some()
     }
 
+    pub fn class(&self, idx: usize) -> PoolClass {
+        self.pools.get(idx).map(|p| p.class).unwrap_or_default()
+    }
+
+    pub fn set_class(&mut self, idx: usize, class: PoolClass) -> bool {
+        if let Some(pool) = self.pools.get_mut(idx)
+            && pool.class != class
+        {
+            pool.class = class;
+            pool.last_update = OffsetDateTime::now_utc();
+            return true;
+        }
+
+        false
+    }
+
     pub async fn load(&mut self, pool: Arc<Sets>, _pools: Vec<Arc<Sets>>) -> Result<()> {
         let data = match read_config(pool, POOL_META_NAME).await {
             Ok(data) => {
@@ -592,7 +659,7 @@ impl ECStore {
         Ok(pool_info)
     }
 
-    async fn get_decommission_pool_space_info(&self, idx: usize) -> Result<PoolSpaceInfo> {
+    pub(crate) async fn get_decommission_pool_space_info(&self, idx: usize) -> Result<PoolSpaceInfo> {
         if let Some(sets) = self.pools.get(idx) {
             let mut info = sets.storage_info().await;
             info.backend = self.backend_info().await;
@@ -1187,6 +1254,89 @@ impl ECStore {
         Ok(ret)
     }
 
+    pub async fn pool_class(&self, idx: usize) -> PoolClass {
+        self.pool_meta.read().await.class(idx)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn set_pool_class(&self, idx: usize, class: PoolClass) -> Result<()> {
+        if idx >= self.pools.len() {
+            return Err(Error::other("InvalidArgument"));
+        }
+
+        let mut pool_meta = self.pool_meta.write().await;
+        if pool_meta.set_class(idx, class) {
+            pool_meta.save(self.pools.clone()).await?;
+            drop(pool_meta);
+            if let Some(notification_sys) = get_global_notification_sys() {
+                notification_sys.reload_pool_meta().await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move the objects of `bucket` off every pool whose class is not `class`.
+    ///
+    /// Objects are relocated in the background through the decommission data path; the
+    /// destination pool is picked by the bucket placement, so it must be set beforehand.
+    #[tracing::instrument(skip(self, rx))]
+    pub async fn migrate_bucket_pool_class(
+        self: &Arc<Self>,
+        rx: CancellationToken,
+        bucket: &str,
+        class: PoolClass,
+    ) -> Result<()> {
+        if self.single_pool() {
+            return Err(Error::other("InvalidArgument"));
+        }
+
+        if self.is_decommission_running().await {
+            return Err(StorageError::DecommissionAlreadyRunning);
+        }
+
+        self.get_bucket_info(bucket, &BucketOptions::default()).await?;
+
+        let (targets, sources): (Vec<usize>, Vec<usize>) = {
+            let pool_meta = self.pool_meta.read().await;
+            (0..self.pools.len())
+                .filter(|idx| !pool_meta.is_suspended(*idx))
+                .partition(|idx| pool_meta.class(*idx) == class)
+        };
+
+        if targets.is_empty() {
+            return Err(Error::other(format!("no {class} pool available to migrate bucket {bucket}")));
+        }
+
+        let _ = self.heal_bucket(bucket, &HealOpts::default()).await;
+
+        let bi = DecomBucketInfo {
+            name: bucket.to_owned(),
+            ..Default::default()
+        };
+        let store = self.clone();
+        tokio::spawn(async move {
+            for idx in sources {
+                if rx.is_cancelled() {
+                    warn!("migrate: canceled moving bucket {}", &bi.name);
+                    return;
+                }
+
+                info!("migrate: moving bucket {} from pool {} to {} pools", &bi.name, idx, class);
+                if let Err(err) = store
+                    .decommission_pool(rx.clone(), idx, store.pools[idx].clone(), bi.clone())
+                    .await
+                {
+                    error!("migrate: moving bucket {} from pool {} err {:?}", &bi.name, idx, &err);
+                }
+            }
+
+            info!("migrate: bucket {} moved to {} pools", &bi.name, class);
+        });
+
+        Ok(())
+    }
+
     #[tracing::instrument(skip(self, rd))]
     async fn decommission_object(self: Arc<Self>, pool_idx: usize, bucket: String, rd: GetObjectReader) -> Result<()> {
         warn!("decommission_object: start {} {}", &bucket, &rd.object_info.name);
@@ -1194,6 +1344,9 @@ impl ECStore {
 
         // TODO: check : use size or actual_size ?
         let _actual_size = object_info.get_actual_size()?;
+        GLOBAL_BANDWIDTH_SYS
+            .throttle(TrafficClass::Decommission, u64::try_from(object_info.size).unwrap_or_default())
+            .await;
 
         if object_info.is_multipart() {
             let res = match self
@@ -1409,3 +1562,46 @@ pub fn get_total_usable_capacity_free(disks: &[rustfs_madmin::Disk], info: &rust
     }
     capacity
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_class_parse() {
+        assert_eq!("hot".parse::<PoolClass>().unwrap(), PoolClass::Hot);
+        assert_eq!("COLD".parse::<PoolClass>().unwrap(), PoolClass::Cold);
+        assert_eq!(PoolClass::Standard.to_string(), "standard");
+        assert!("warm".parse::<PoolClass>().is_err());
+    }
+
+    #[test]
+    fn test_pool_class_accepts() {
+        assert!(PoolClass::Standard.accepts(None));
+        assert!(PoolClass::Hot.accepts(None));
+        assert!(!PoolClass::Cold.accepts(None));
+        assert!(PoolClass::Cold.accepts(Some(PoolClass::Cold)));
+        assert!(!PoolClass::Hot.accepts(Some(PoolClass::Cold)));
+    }
+
+    #[test]
+    fn test_pool_meta_set_class() {
+        let mut meta = PoolMeta {
+            version: POOL_META_VERSION,
+            pools: vec![PoolStatus {
+                id: 0,
+                cmd_line: "http://server{1...4}/disk{1...4}".to_string(),
+                last_update: OffsetDateTime::UNIX_EPOCH,
+                decommission: None,
+                class: PoolClass::default(),
+            }],
+            dont_save: true,
+        };
+
+        assert!(meta.set_class(0, PoolClass::Cold));
+        assert!(!meta.set_class(0, PoolClass::Cold));
+        assert!(!meta.set_class(3, PoolClass::Hot));
+        assert_eq!(meta.class(0), PoolClass::Cold);
+        assert_eq!(meta.class(3), PoolClass::Standard);
+    }
+}
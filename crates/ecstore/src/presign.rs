@@ -0,0 +1,164 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cluster wide guardrails for presigned URLs and presigned POST policies.
+//!
+//! The limits apply on top of whatever the signer asked for, so holders of credentials cannot
+//! hand out URLs or upload forms that outlive or outgrow what the administrator allows. They are
+//! persisted in the cluster config and reloaded periodically by every node, like the
+//! compatibility switches.
+
+use crate::config::com::{read_config, save_config};
+use crate::error::{Error, Result};
+use crate::store::ECStore;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+pub const PRESIGN_CONFIG_PATH: &str = "config/presign.json";
+
+/// How often a node reloads the persisted limits to pick up changes made on peers.
+pub const PRESIGN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Longest validity S3 accepts for a presigned URL.
+pub const MAX_PRESIGN_EXPIRY_SECS: u64 = 7 * 24 * 60 * 60;
+
+pub static GLOBAL_PRESIGN_SYS: LazyLock<PresignSys> = LazyLock::new(PresignSys::default);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresignLimits {
+    /// Longest validity a presigned URL may have, in seconds, 0 for the S3 limit of 7 days.
+    #[serde(default)]
+    pub max_expiry_secs: u64,
+    /// Largest body accepted through a presigned URL or POST policy upload, 0 for no limit.
+    #[serde(default)]
+    pub max_content_length: u64,
+    /// Presigned and POST policy uploads must request server side encryption.
+    #[serde(default)]
+    pub require_sse: bool,
+}
+
+impl PresignLimits {
+    pub fn unmarshal(data: &[u8]) -> Result<Self> {
+        serde_json::from_slice(data).map_err(Error::other)
+    }
+
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(Error::other)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.max_expiry_secs > MAX_PRESIGN_EXPIRY_SECS {
+            return Err(Error::other(format!("max expiry must be at most {MAX_PRESIGN_EXPIRY_SECS} seconds")));
+        }
+        Ok(())
+    }
+
+    /// Longest validity in seconds a presigned URL may have.
+    pub fn effective_max_expiry_secs(&self) -> u64 {
+        if self.max_expiry_secs == 0 {
+            MAX_PRESIGN_EXPIRY_SECS
+        } else {
+            self.max_expiry_secs
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PresignSys {
+    limits: RwLock<PresignLimits>,
+}
+
+impl PresignSys {
+    /// Load the persisted limits, treating a missing config as no limits beyond the S3 ones.
+    pub async fn load(&self, store: Arc<ECStore>) -> Result<()> {
+        let limits = match read_config(store, PRESIGN_CONFIG_PATH).await {
+            Ok(data) => PresignLimits::unmarshal(&data)?,
+            Err(Error::ConfigNotFound) => PresignLimits::default(),
+            Err(err) => return Err(err),
+        };
+
+        *self.limits.write() = limits;
+        Ok(())
+    }
+
+    pub async fn set(&self, store: Arc<ECStore>, limits: PresignLimits) -> Result<PresignLimits> {
+        limits.validate()?;
+        save_config(store, PRESIGN_CONFIG_PATH, limits.marshal()?).await?;
+        info!(
+            max_expiry_secs = limits.max_expiry_secs,
+            max_content_length = limits.max_content_length,
+            require_sse = limits.require_sse,
+            "presign limits changed"
+        );
+
+        *self.limits.write() = limits;
+        Ok(limits)
+    }
+
+    pub fn limits(&self) -> PresignLimits {
+        *self.limits.read()
+    }
+}
+
+/// Load the persisted presign limits and keep them in sync with changes made on peers.
+pub async fn init_presign_sys(store: Arc<ECStore>, cancel: CancellationToken) {
+    if let Err(err) = GLOBAL_PRESIGN_SYS.load(store.clone()).await {
+        warn!("load presign limits failed: {:?}", err);
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PRESIGN_REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = interval.tick() => {
+                    if let Err(err) = GLOBAL_PRESIGN_SYS.load(store.clone()).await {
+                        warn!("refresh presign limits failed: {:?}", err);
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_round_trip() {
+        let limits = PresignLimits {
+            max_expiry_secs: 3600,
+            max_content_length: 1 << 20,
+            require_sse: true,
+        };
+        assert_eq!(PresignLimits::unmarshal(&limits.marshal().unwrap()).unwrap(), limits);
+        assert_eq!(PresignLimits::unmarshal(b"{}").unwrap(), PresignLimits::default());
+        assert_eq!(PresignLimits::default().effective_max_expiry_secs(), MAX_PRESIGN_EXPIRY_SECS);
+        assert!(
+            PresignLimits {
+                max_expiry_secs: MAX_PRESIGN_EXPIRY_SECS + 1,
+                ..Default::default()
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
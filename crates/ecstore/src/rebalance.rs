@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::StorageAPI;
+use crate::bandwidth::{GLOBAL_BANDWIDTH_SYS, TrafficClass};
 use crate::cache_value::metacache_set::{ListPathRawOptions, list_path_raw};
 use crate::config::com::{read_config_with_metadata, save_config_with_opts};
 use crate::disk::error::DiskError;
@@ -909,6 +910,9 @@ impl ECStore {
 
         // TODO: check : use size or actual_size ?
         let _actual_size = object_info.get_actual_size()?;
+        GLOBAL_BANDWIDTH_SYS
+            .throttle(TrafficClass::Rebalance, u64::try_from(object_info.size).unwrap_or_default())
+            .await;
 
         if object_info.is_multipart() {
             let res = match self
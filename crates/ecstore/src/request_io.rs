@@ -0,0 +1,277 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Disk IO and transfer memory attributed to the request being served.
+//!
+//! The HTTP server runs every S3 request in [`scope`]. Shard files opened while it runs count the
+//! bytes read from and written to each disk, and memory budget reservations count the memory the
+//! transfer holds. Work a request hands to a spawned task keeps the attribution when the task is
+//! spawned through [`inherit`]. The totals go to the request log, and once the last reader or
+//! writer of the request is closed they are recorded on the tracing span of the request.
+
+use crate::disk::{FileReader, FileWriter};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tracing::Span;
+
+tokio::task_local! {
+    static REQUEST_IO: Arc<RequestIo>;
+}
+
+/// Bytes moved to and from one disk.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskIo {
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+}
+
+/// Disk IO and memory of a request so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestIoSnapshot {
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    /// Memory budget reserved for the transfers of the request
+    pub memory_reserved: u64,
+    /// IO per disk, by disk endpoint
+    pub disks: BTreeMap<String, DiskIo>,
+}
+
+impl RequestIoSnapshot {
+    /// `disk=read/written` pairs of the disks the request touched, for span fields and logs.
+    pub fn disks_summary(&self) -> String {
+        self.disks
+            .iter()
+            .map(|(disk, io)| format!("{}={}/{}", disk, io.bytes_read, io.bytes_written))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+#[derive(Debug, Default)]
+struct DiskCounters {
+    read: AtomicU64,
+    written: AtomicU64,
+}
+
+/// Counters of a request, kept by the request log after the request is done.
+#[derive(Debug, Default)]
+pub struct RequestIoStats {
+    disks: Mutex<BTreeMap<String, Arc<DiskCounters>>>,
+    memory_reserved: AtomicU64,
+}
+
+impl RequestIoStats {
+    fn disk(&self, disk: &str) -> Arc<DiskCounters> {
+        let mut disks = self.disks.lock().unwrap_or_else(|e| e.into_inner());
+        disks.entry(disk.to_string()).or_default().clone()
+    }
+
+    pub fn snapshot(&self) -> RequestIoSnapshot {
+        let disks = self.disks.lock().unwrap_or_else(|e| e.into_inner());
+        let mut snapshot = RequestIoSnapshot {
+            memory_reserved: self.memory_reserved.load(Ordering::Relaxed),
+            ..Default::default()
+        };
+        for (disk, counters) in disks.iter() {
+            let io = DiskIo {
+                bytes_read: counters.read.load(Ordering::Relaxed),
+                bytes_written: counters.written.load(Ordering::Relaxed),
+            };
+            snapshot.bytes_read = snapshot.bytes_read.saturating_add(io.bytes_read);
+            snapshot.bytes_written = snapshot.bytes_written.saturating_add(io.bytes_written);
+            snapshot.disks.insert(disk.clone(), io);
+        }
+        snapshot
+    }
+}
+
+/// Attribution of a request, shared by the tasks, readers and writers working for it.
+#[derive(Debug, Default)]
+pub struct RequestIo {
+    stats: Arc<RequestIoStats>,
+    span: OnceLock<Span>,
+}
+
+impl RequestIo {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn stats(&self) -> Arc<RequestIoStats> {
+        self.stats.clone()
+    }
+
+    /// Record the totals on `span` once the request is done with the disks. The span must declare
+    /// the `disk_read_bytes`, `disk_written_bytes`, `disk_io` and `memory_reserved` fields.
+    pub fn attach_span(&self, span: Span) {
+        let _ = self.span.set(span);
+    }
+}
+
+impl Drop for RequestIo {
+    fn drop(&mut self) {
+        let Some(span) = self.span.get() else {
+            return;
+        };
+        let snapshot = self.stats.snapshot();
+        span.record("disk_read_bytes", snapshot.bytes_read);
+        span.record("disk_written_bytes", snapshot.bytes_written);
+        span.record("disk_io", tracing::field::display(snapshot.disks_summary()));
+        span.record("memory_reserved", snapshot.memory_reserved);
+    }
+}
+
+/// Attribution of the request the current task works for.
+pub fn current() -> Option<Arc<RequestIo>> {
+    REQUEST_IO.try_with(|io| io.clone()).ok()
+}
+
+/// Run `fut` attributing its IO to `io`.
+pub async fn scope<F: Future>(io: Arc<RequestIo>, fut: F) -> F::Output {
+    REQUEST_IO.scope(io, fut).await
+}
+
+/// Keep the attribution of the current request for `fut`, which is about to be spawned.
+pub fn inherit<F: Future>(fut: F) -> impl Future<Output = F::Output> {
+    let io = current();
+    async move {
+        match io {
+            Some(io) => REQUEST_IO.scope(io, fut).await,
+            None => fut.await,
+        }
+    }
+}
+
+/// Count memory the current request reserved from the memory budget.
+pub fn record_memory_reserved(bytes: usize) {
+    let _ = REQUEST_IO.try_with(|io| io.stats.memory_reserved.fetch_add(bytes as u64, Ordering::Relaxed));
+}
+
+/// Count what is read through `reader` against `disk` for the current request.
+pub fn wrap_reader(disk: &str, reader: FileReader) -> FileReader {
+    match current() {
+        Some(io) => Box::new(CountingReader {
+            counters: io.stats.disk(disk),
+            _io: io,
+            inner: reader,
+        }),
+        None => reader,
+    }
+}
+
+/// Count what is written through `writer` against `disk` for the current request.
+pub fn wrap_writer(disk: &str, writer: FileWriter) -> FileWriter {
+    match current() {
+        Some(io) => Box::new(CountingWriter {
+            counters: io.stats.disk(disk),
+            _io: io,
+            inner: writer,
+        }),
+        None => writer,
+    }
+}
+
+struct CountingReader {
+    counters: Arc<DiskCounters>,
+    // Keeps the request open until the reader is done
+    _io: Arc<RequestIo>,
+    inner: FileReader,
+}
+
+impl AsyncRead for CountingReader {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let before = buf.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = &poll {
+            let read = (buf.filled().len() - before) as u64;
+            self.counters.read.fetch_add(read, Ordering::Relaxed);
+        }
+        poll
+    }
+}
+
+struct CountingWriter {
+    counters: Arc<DiskCounters>,
+    // Keeps the request open until the writer is done
+    _io: Arc<RequestIo>,
+    inner: FileWriter,
+}
+
+impl AsyncWrite for CountingWriter {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = &poll {
+            self.counters.written.fetch_add(*written as u64, Ordering::Relaxed);
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_io_is_counted_per_disk() {
+        let io = RequestIo::new();
+        let stats = io.stats();
+
+        scope(io, async {
+            let mut reader = wrap_reader("disk1", Box::new(std::io::Cursor::new(vec![0u8; 100])));
+            let mut data = Vec::new();
+            reader.read_to_end(&mut data).await.unwrap();
+
+            let handle = tokio::spawn(inherit(async {
+                let mut writer = wrap_writer("disk2", Box::new(tokio::io::sink()));
+                writer.write_all(&[1u8; 40]).await.unwrap();
+                record_memory_reserved(1024);
+            }));
+            handle.await.unwrap();
+        })
+        .await;
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.bytes_read, 100);
+        assert_eq!(snapshot.bytes_written, 40);
+        assert_eq!(snapshot.memory_reserved, 1024);
+        assert_eq!(snapshot.disks_summary(), "disk1=100/0,disk2=0/40");
+    }
+
+    #[tokio::test]
+    async fn test_io_outside_request_is_not_counted() {
+        assert!(current().is_none());
+        let mut reader = wrap_reader("disk1", Box::new(std::io::Cursor::new(vec![0u8; 10])));
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await.unwrap();
+        record_memory_reserved(10);
+    }
+}
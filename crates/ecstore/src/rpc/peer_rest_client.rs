@@ -18,6 +18,7 @@ use crate::{
     global::is_dist_erasure,
     metrics_realtime::{CollectMetricsOpts, MetricType},
 };
+use bytes::Bytes;
 use rmp_serde::{Deserializer, Serializer};
 use rustfs_madmin::{
     ServerProperties,
@@ -29,11 +30,12 @@ use rustfs_protos::{
     node_service_time_out_client,
     proto_gen::node_service::{
         DeleteBucketMetadataRequest, DeletePolicyRequest, DeleteServiceAccountRequest, DeleteUserRequest, GetCpusRequest,
-        GetMemInfoRequest, GetMetricsRequest, GetNetInfoRequest, GetOsInfoRequest, GetPartitionsRequest, GetProcInfoRequest,
-        GetSeLinuxInfoRequest, GetSysConfigRequest, GetSysErrorsRequest, LoadBucketMetadataRequest, LoadGroupRequest,
-        LoadPolicyMappingRequest, LoadPolicyRequest, LoadRebalanceMetaRequest, LoadServiceAccountRequest,
-        LoadTransitionTierConfigRequest, LoadUserRequest, LocalStorageInfoRequest, Mss, ReloadPoolMetaRequest,
-        ReloadSiteReplicationConfigRequest, ServerInfoRequest, SignalServiceRequest, StartProfilingRequest, StopRebalanceRequest,
+        GetMemInfoRequest, GetMetacacheListingRequest, GetMetricsRequest, GetNetInfoRequest, GetOsInfoRequest,
+        GetPartitionsRequest, GetProcInfoRequest, GetSeLinuxInfoRequest, GetSysConfigRequest, GetSysErrorsRequest,
+        LoadBucketMetadataRequest, LoadGroupRequest, LoadPolicyMappingRequest, LoadPolicyRequest, LoadRebalanceMetaRequest,
+        LoadServiceAccountRequest, LoadTransitionTierConfigRequest, LoadUserRequest, LocalStorageInfoRequest, Mss,
+        ReloadPoolMetaRequest, ReloadSiteReplicationConfigRequest, ServerInfoRequest, SignalServiceRequest,
+        StartProfilingRequest, StopRebalanceRequest, UpdateMetacacheListingRequest,
     },
 };
 use rustfs_utils::XHost;
@@ -599,18 +601,42 @@ impl PeerRestClient {
         Ok(())
     }
 
-    pub async fn get_metacache_listing(&self) -> Result<()> {
-        let _client = node_service_time_out_client(&self.grid_host)
+    /// Stream a listing cached by the peer, `opts` is encoded by `ListCacheFetch::marshal`.
+    pub async fn get_metacache_listing(&self, opts: Bytes) -> Result<Vec<Bytes>> {
+        let mut client = node_service_time_out_client(&self.grid_host)
             .await
             .map_err(|err| Error::other(err.to_string()))?;
-        todo!()
+        let request = Request::new(GetMetacacheListingRequest { opts });
+
+        let mut response = client.get_metacache_listing(request).await?.into_inner();
+        let mut chunks = Vec::new();
+        while let Some(resp) = response.message().await? {
+            if !resp.success {
+                if let Some(msg) = resp.error_info {
+                    return Err(Error::other(msg));
+                }
+                return Err(Error::other(""));
+            }
+            chunks.push(resp.metacache);
+        }
+        Ok(chunks)
     }
 
-    pub async fn update_metacache_listing(&self) -> Result<()> {
-        let _client = node_service_time_out_client(&self.grid_host)
+    /// Send a batch of listing cache updates, encoded by `ListCacheUpdate::marshal`.
+    pub async fn update_metacache_listing(&self, metacache: Bytes) -> Result<()> {
+        let mut client = node_service_time_out_client(&self.grid_host)
             .await
             .map_err(|err| Error::other(err.to_string()))?;
-        todo!()
+        let request = Request::new(UpdateMetacacheListingRequest { metacache });
+
+        let response = client.update_metacache_listing(request).await?.into_inner();
+        if !response.success {
+            if let Some(msg) = response.error_info {
+                return Err(Error::other(msg));
+            }
+            return Err(Error::other(""));
+        }
+        Ok(())
     }
 
     pub async fn reload_pool_meta(&self) -> Result<()> {
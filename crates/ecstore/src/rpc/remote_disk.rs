@@ -42,7 +42,7 @@ use rustfs_protos::proto_gen::node_service::RenamePartRequest;
 use rustfs_rio::{HttpReader, HttpWriter};
 use tokio::{io::AsyncWrite, net::TcpStream, time::timeout};
 use tonic::Request;
-use tracing::info;
+use tracing::{debug, info};
 use uuid::Uuid;
 
 #[derive(Debug)]
@@ -356,7 +356,7 @@ impl DiskAPI for RemoteDisk {
 
     #[tracing::instrument(skip(self))]
     async fn delete_versions(&self, volume: &str, versions: Vec<FileInfoVersions>, opts: DeleteOptions) -> Vec<Option<Error>> {
-        info!("delete_versions");
+        debug!("delete_versions {} versions", versions.len());
 
         let opts = match serde_json::to_string(&opts) {
             Ok(opts) => opts,
@@ -420,6 +420,14 @@ impl DiskAPI for RemoteDisk {
             }
             return errors;
         }
+        // Peers without typed errors only send the messages
+        if response.disk_errors.len() == response.errors.len() {
+            return response
+                .disk_errors
+                .into_iter()
+                .map(|error| if error.code == 0 { None } else { Some(error.into()) })
+                .collect();
+        }
         response
             .errors
             .iter()
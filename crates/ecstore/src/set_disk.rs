@@ -15,27 +15,40 @@
 #![allow(unused_imports)]
 #![allow(unused_variables)]
 
+use crate::bandwidth::{GLOBAL_BANDWIDTH_SYS, TrafficClass};
 use crate::batch_processor::{AsyncBatchProcessor, get_global_processors};
 use crate::bitrot::{create_bitrot_reader, create_bitrot_writer};
+use crate::bucket::dedup::{dedup_blob, get_deduplicated_object_reader};
+use crate::bucket::erasure::bucket_block_size;
 use crate::bucket::lifecycle::lifecycle::TRANSITION_COMPLETE;
+use crate::bucket::listing::ListingConsistency;
 use crate::bucket::replication::check_replicate_delete;
+use crate::bucket::utils::is_meta_bucketname;
 use crate::bucket::versioning::VersioningApi;
 use crate::bucket::versioning_sys::BucketVersioningSys;
 use crate::client::{object_api_utils::get_raw_etag, transition_api::ReaderImpl};
+use crate::compat::{GLOBAL_COMPAT_SYS, MULTIPART_HIDDEN_KEY, MULTIPART_OBJECT_KEY};
 use crate::disk::STORAGE_FORMAT_FILE;
 use crate::disk::error_reduce::{OBJECT_OP_IGNORED_ERRS, reduce_read_quorum_errs, reduce_write_quorum_errs};
+use crate::disk::watermark::GLOBAL_DISK_SPACE_TRACKER;
 use crate::disk::{
     self, CHECK_PART_DISK_NOT_FOUND, CHECK_PART_FILE_CORRUPT, CHECK_PART_FILE_NOT_FOUND, CHECK_PART_SUCCESS,
     conv_part_err_to_int, has_part_err,
 };
+use crate::disk_replacement::GLOBAL_DISK_REPLACEMENT_SYS;
+use crate::drive_health::GLOBAL_DRIVE_HEALTH_SYS;
 use crate::erasure_coding;
 use crate::erasure_coding::bitrot_verify;
 use crate::error::{Error, Result, is_err_version_not_found};
 use crate::error::{GenericError, ObjectApiError, is_err_object_not_found};
 use crate::global::{GLOBAL_LocalNodeName, GLOBAL_TierConfigMgr};
+use crate::hot_objects::GLOBAL_HOT_OBJECTS;
+use crate::memory_budget::{GLOBAL_MEMORY_BUDGET, transfer_memory};
+use crate::request_io;
 use crate::store_api::ListObjectVersionsInfo;
 use crate::store_api::{ListPartsInfo, ObjectOptions, ObjectToDelete};
 use crate::store_api::{ObjectInfoOrErr, WalkOptions};
+use crate::tmp_cleanup::TmpCleanupGuard;
 use crate::{
     bucket::lifecycle::bucket_lifecycle_ops::{
         LifecycleOps, gen_transition_objname, get_transitioned_object_reader, put_restore_opts,
@@ -70,8 +83,8 @@ use regex::Regex;
 use rustfs_common::heal_channel::{DriveState, HealChannelPriority, HealItemType, HealOpts, HealScanMode, send_heal_disk};
 use rustfs_config::MI_B;
 use rustfs_filemeta::{
-    FileInfo, FileMeta, FileMetaShallowVersion, MetaCacheEntries, MetaCacheEntry, MetadataResolutionParams, ObjectPartInfo,
-    RawFileInfo, ReplicationStatusType, VersionPurgeStatusType, file_info_from_raw, merge_file_meta_versions,
+    DEGRADED_WRITE, FileInfo, FileMeta, FileMetaShallowVersion, MetaCacheEntries, MetaCacheEntry, MetadataResolutionParams,
+    ObjectPartInfo, RawFileInfo, ReplicationStatusType, VersionPurgeStatusType, file_info_from_raw, merge_file_meta_versions,
 };
 use rustfs_lock::fast_lock::types::LockResult;
 use rustfs_madmin::heal_commands::{HealDriveInfo, HealResultItem};
@@ -224,6 +237,29 @@ impl SetDisks {
 
         (filtered, online_count)
     }
+
+    /// Refuse new object writes while any drive of this set sits below its reserved space watermark.
+    /// Reads, deletes and healing are not gated, so space can still be reclaimed.
+    fn check_disk_watermark(&self, bucket: &str, object: &str) -> Result<()> {
+        if is_meta_bucketname(bucket) {
+            return Ok(());
+        }
+
+        if let Some(ep) = self
+            .set_endpoints
+            .iter()
+            .find(|ep| GLOBAL_DISK_SPACE_TRACKER.is_read_only(&ep.to_string()))
+        {
+            warn!(
+                "refusing write to {}/{}: drive {} is below its reserved space watermark (pool {}, set {})",
+                bucket, object, ep, self.pool_index, self.set_index
+            );
+            return Err(StorageError::StorageFull);
+        }
+
+        Ok(())
+    }
+
     fn format_lock_error(&self, bucket: &str, object: &str, mode: &str, err: &LockResult) -> String {
         match err {
             LockResult::Timeout => {
@@ -340,7 +376,12 @@ impl SetDisks {
                 continue;
             }
 
-            if info.healing {
+            // Drives being replaced are left out like healing ones, they are about to go
+            if info.healing
+                || disk
+                    .as_ref()
+                    .is_some_and(|d| GLOBAL_DISK_REPLACEMENT_SYS.is_draining(&d.endpoint()))
+            {
                 healing += 1;
                 if incl_healing {
                     healing_disks.push(disk.unwrap());
@@ -378,10 +419,10 @@ impl SetDisks {
             .filter(|v| v.as_ref().is_some_and(|d| d.is_local()))
             .collect()
     }
-    fn default_read_quorum(&self) -> usize {
+    pub(crate) fn default_read_quorum(&self) -> usize {
         self.set_drive_count - self.default_parity_count
     }
-    fn default_write_quorum(&self) -> usize {
+    pub(crate) fn default_write_quorum(&self) -> usize {
         let mut data_count = self.set_drive_count - self.default_parity_count;
         if data_count == self.default_parity_count {
             data_count += 1
@@ -400,7 +441,7 @@ impl SetDisks {
         dst_bucket: &str,
         dst_object: &str,
         write_quorum: usize,
-    ) -> disk::error::Result<(Vec<Option<DiskStore>>, Option<Vec<u8>>, Option<Uuid>)> {
+    ) -> Result<(Vec<Option<DiskStore>>, Option<Vec<u8>>, Option<Uuid>)> {
         let mut futures = Vec::with_capacity(disks.len());
 
         // let mut ress = Vec::with_capacity(disks.len());
@@ -457,7 +498,8 @@ impl SetDisks {
 
         let mut futures = Vec::with_capacity(disks.len());
         if let Some(ret_err) = reduce_write_quorum_errs(&errs, OBJECT_OP_IGNORED_ERRS, write_quorum) {
-            // TODO: add concurrency
+            // Undo the renames that went through, a version present on less than a quorum of
+            // disks would otherwise confuse later quorum resolution
             for (i, err) in errs.iter().enumerate() {
                 if err.is_some() {
                     continue;
@@ -467,13 +509,13 @@ impl SetDisks {
                     let fi = file_infos[i].clone();
                     let old_data_dir = data_dirs[i];
                     let disk = disk.clone();
-                    let src_bucket = src_bucket.clone();
-                    let src_object = src_object.clone();
+                    let dst_bucket = dst_bucket.clone();
+                    let dst_object = dst_object.clone();
                     futures.push(tokio::spawn(async move {
                         let _ = disk
                             .delete_version(
-                                &src_bucket,
-                                &src_object,
+                                &dst_bucket,
+                                &dst_object,
                                 fi,
                                 false,
                                 DeleteOptions {
@@ -492,7 +534,7 @@ impl SetDisks {
             }
 
             let _ = join_all(futures).await;
-            return Err(ret_err);
+            return Err(write_quorum_err(ret_err, &dst_bucket, &dst_object, &errs, write_quorum));
         }
 
         let versions = None;
@@ -819,7 +861,7 @@ impl SetDisks {
         dst_object: &str,
         meta: Bytes,
         write_quorum: usize,
-    ) -> disk::error::Result<Vec<Option<DiskStore>>> {
+    ) -> Result<Vec<Option<DiskStore>>> {
         let src_bucket = Arc::new(src_bucket.to_string());
         let src_object = Arc::new(src_object.to_string());
         let dst_bucket = Arc::new(dst_bucket.to_string());
@@ -859,7 +901,7 @@ impl SetDisks {
         if let Some(err) = reduce_write_quorum_errs(&errs, OBJECT_OP_IGNORED_ERRS, write_quorum) {
             warn!("rename_part errs {:?}", &errs);
             Self::cleanup_multipart_path(disks, &[dst_object.to_string(), format!("{dst_object}.meta")]).await;
-            return Err(err);
+            return Err(write_quorum_err(err, &dst_bucket, &dst_object, &errs, write_quorum));
         }
 
         let disks = Self::eval_disks(disks, &errs);
@@ -1238,6 +1280,134 @@ impl SetDisks {
         (new_disk, mod_time, etag)
     }
 
+    /// Disks to list multipart uploads from, local ones first.
+    async fn multipart_list_disks(&self) -> Vec<Option<DiskStore>> {
+        let disks = self.get_online_local_disks().await;
+        if disks.is_empty() {
+            // TODO: getOnlineDisksWithHealing
+            self.get_online_disks().await
+        } else {
+            disks
+        }
+    }
+
+    fn multipart_info(bucket: &str, object: &str, upload_uuid: &str) -> Result<MultipartInfo> {
+        let mut initiated = OffsetDateTime::now_utc();
+        let splits: Vec<&str> = upload_uuid.split("x").collect();
+        if splits.len() == 2
+            && let Ok(unix) = splits[1].parse::<i128>()
+        {
+            initiated = OffsetDateTime::from_unix_timestamp_nanos(unix)?;
+        }
+
+        Ok(MultipartInfo {
+            bucket: bucket.to_owned(),
+            object: object.to_owned(),
+            upload_id: base64_simd::URL_SAFE_NO_PAD
+                .encode_to_string(format!("{}.{}", get_global_deployment_id().unwrap_or_default(), upload_uuid).as_bytes()),
+            initiated: Some(initiated),
+            ..Default::default()
+        })
+    }
+
+    /// The uploads of exactly `object`.
+    async fn list_object_uploads(&self, bucket: &str, object: &str) -> Result<Vec<MultipartInfo>> {
+        let mut upload_ids: Vec<String> = Vec::new();
+
+        for disk in self.multipart_list_disks().await.iter().flatten() {
+            if !disk.is_online().await {
+                continue;
+            }
+
+            match disk
+                .list_dir(
+                    bucket,
+                    RUSTFS_META_MULTIPART_BUCKET,
+                    Self::get_multipart_sha_dir(bucket, object).as_str(),
+                    -1,
+                )
+                .await
+            {
+                Ok(ids) => {
+                    upload_ids = ids;
+                    break;
+                }
+                Err(DiskError::DiskNotFound) => continue,
+                Err(DiskError::FileNotFound) => return Ok(Vec::new()),
+                Err(err) => return Err(to_object_err(err.into(), vec![bucket, object])),
+            }
+        }
+
+        let mut populated_upload_ids = HashSet::new();
+        let mut uploads = Vec::new();
+        for upload_id in upload_ids.iter() {
+            let upload_id = upload_id.trim_end_matches(SLASH_SEPARATOR);
+            if populated_upload_ids.insert(upload_id.to_string()) {
+                uploads.push(Self::multipart_info(bucket, object, upload_id)?);
+            }
+        }
+
+        Ok(uploads)
+    }
+
+    /// The uploads of every object of `bucket` whose name starts with `prefix`. Upload directories
+    /// are named after a hash of the object, so the name is read back from the upload metadata;
+    /// uploads created before the name was recorded are only found by their exact object name.
+    async fn list_uploads_under_prefix(&self, bucket: &str, prefix: &str) -> Result<Vec<MultipartInfo>> {
+        let Some(disk) = self.multipart_list_disks().await.into_iter().flatten().next() else {
+            return Err(Error::ErasureReadQuorum);
+        };
+
+        let sha_dirs = match disk.list_dir("", RUSTFS_META_MULTIPART_BUCKET, "", -1).await {
+            Ok(dirs) => dirs,
+            Err(DiskError::FileNotFound | DiskError::VolumeNotFound) => return Ok(Vec::new()),
+            Err(err) => return Err(to_object_err(err.into(), vec![bucket, prefix])),
+        };
+
+        let exact_sha_dir = Self::get_multipart_sha_dir(bucket, prefix);
+        let mut uploads = Vec::new();
+        for sha_dir in sha_dirs {
+            let sha_dir = sha_dir.trim_end_matches(SLASH_SEPARATOR);
+            let Ok(upload_ids) = disk.list_dir("", RUSTFS_META_MULTIPART_BUCKET, sha_dir, -1).await else {
+                continue;
+            };
+
+            for upload_id in upload_ids {
+                let upload_id = upload_id.trim_end_matches(SLASH_SEPARATOR);
+                let Ok(fi) = disk
+                    .read_version(
+                        bucket,
+                        RUSTFS_META_MULTIPART_BUCKET,
+                        &format!("{sha_dir}/{upload_id}"),
+                        "",
+                        &ReadOptions::default(),
+                    )
+                    .await
+                else {
+                    continue;
+                };
+
+                let object = match fi.metadata.get(MULTIPART_OBJECT_KEY) {
+                    Some(object) if Self::get_multipart_sha_dir(bucket, object) == sha_dir => object.clone(),
+                    Some(_) => continue,
+                    None if sha_dir == exact_sha_dir => prefix.to_owned(),
+                    None => continue,
+                };
+                if !object.starts_with(prefix) {
+                    continue;
+                }
+                // Hidden uploads are still listed when asked for by their exact object name, so they can be aborted.
+                if object != prefix && fi.metadata.contains_key(MULTIPART_HIDDEN_KEY) {
+                    continue;
+                }
+
+                uploads.push(Self::multipart_info(bucket, &object, upload_id)?);
+            }
+        }
+
+        Ok(uploads)
+    }
+
     #[tracing::instrument(level = "debug", skip(self))]
     async fn check_upload_id_exists(
         &self,
@@ -2409,7 +2579,7 @@ impl SetDisks {
             //     "read part {} part_offset {},part_length {},part_size {}  ",
             //     part_number, part_offset, part_length, part_size
             // );
-            let (written, err) = erasure.decode(writer, readers, part_offset, part_length, part_size).await;
+            let (written, err, failed_shards) = erasure.decode(writer, readers, part_offset, part_length, part_size).await;
             debug!(
                 bucket,
                 object,
@@ -2419,6 +2589,44 @@ impl SetDisks {
                 bytes_written = written,
                 "Finished decoding multipart part"
             );
+            if !failed_shards.is_empty() && err.is_none() {
+                // The read survived on parity, heal the drives that dropped out mid-stream
+                let failed_disks: Vec<String> = failed_shards
+                    .iter()
+                    .map(|&i| {
+                        disks
+                            .get(i)
+                            .and_then(|d| d.as_ref())
+                            .map_or_else(|| format!("shard {i}"), |d| d.to_string())
+                    })
+                    .collect();
+                warn!(
+                    bucket,
+                    object,
+                    part_number,
+                    ?failed_disks,
+                    "Shard reads failed mid-stream, substituted parity shards"
+                );
+                if let Err(e) =
+                    rustfs_common::heal_channel::send_heal_request(rustfs_common::heal_channel::create_heal_request_with_options(
+                        bucket.to_string(),
+                        Some(object.to_string()),
+                        false,
+                        Some(HealChannelPriority::Normal),
+                        Some(pool_index),
+                        Some(set_index),
+                    ))
+                    .await
+                {
+                    warn!(
+                        bucket,
+                        object,
+                        part_number,
+                        error = %e,
+                        "Failed to enqueue heal request for failed shards"
+                    );
+                }
+            }
             if let Some(e) = err {
                 let de_err: DiskError = e.into();
                 let mut has_err = true;
@@ -2471,6 +2679,38 @@ impl SetDisks {
         Ok(())
     }
 
+    /// Drop the degraded write marker of a version once every drive of the set holds it.
+    async fn clear_degraded_write(
+        &self,
+        bucket: &str,
+        object: &str,
+        latest_meta: &FileInfo,
+        result: &HealResultItem,
+        disks: &[Option<DiskStore>],
+    ) {
+        if latest_meta.deleted || latest_meta.degraded_write().is_none() {
+            return;
+        }
+        let all_ok =
+            result.after.drives.len() == disks.len() && result.after.drives.iter().all(|d| d.state == DriveState::Ok.to_string());
+        if !all_ok {
+            return;
+        }
+
+        let mut fi = FileInfo {
+            version_id: latest_meta.version_id,
+            ..Default::default()
+        };
+        // An empty internal value removes the key from the stored version
+        fi.metadata
+            .insert(format!("{RESERVED_METADATA_PREFIX_LOWER}{DEGRADED_WRITE}"), String::new());
+
+        match self.update_object_meta(bucket, object, fi, disks).await {
+            Ok(()) => info!("{bucket}/{object} is back at full parity"),
+            Err(err) => warn!("clear degraded write marker of {bucket}/{object} err {:?}", err),
+        }
+    }
+
     async fn update_object_meta(
         &self,
         bucket: &str,
@@ -2558,7 +2798,11 @@ impl SetDisks {
 
         infos.iter().zip(self.disks.write().await.iter()).for_each(|(info, disk)| {
             if info.error.is_empty() {
-                if info.healing {
+                if info.healing
+                    || disk
+                        .as_ref()
+                        .is_some_and(|d| GLOBAL_DISK_REPLACEMENT_SYS.is_draining(&d.endpoint()))
+                {
                     healing += 1;
                     if incl_healing {
                         healing_disks.push(disk.clone());
@@ -2836,8 +3080,11 @@ impl SetDisks {
                             ));
                         }
 
-                        if disks_to_heal_count == 0 {
+                        if disks_to_heal_count == 0 && !opts.rewrite {
                             info!("No disks to heal, returning early");
+                            if !opts.dry_run {
+                                self.clear_degraded_write(bucket, object, &latest_meta, &result, &disks).await;
+                            }
                             return Ok((result, None));
                         }
 
@@ -2910,6 +3157,17 @@ impl SetDisks {
                             };
                         }
 
+                        // The quorum check above only counts the drives that are actually damaged,
+                        // the healthy ones are rewritten from the same shards they are read from.
+                        if opts.rewrite {
+                            for index in 0..available_disks.len() {
+                                if outdate_disks[index].is_none() && available_disks[index].is_some() {
+                                    outdate_disks[index] = disks[index].clone();
+                                    disks_to_heal_count += 1;
+                                }
+                            }
+                        }
+
                         if !latest_meta.deleted && latest_meta.erasure.distribution.len() != available_disks.len() {
                             let err_str = format!(
                                 "unexpected file distribution ({:?}) from available disks ({:?}), looks like backend disks have been manually modified refusing to heal {}/{}({})",
@@ -2993,6 +3251,7 @@ impl SetDisks {
                         if !latest_meta.deleted && !latest_meta.is_remote() {
                             let erasure_info = latest_meta.erasure;
                             for part in latest_meta.parts.iter() {
+                                GLOBAL_BANDWIDTH_SYS.throttle(TrafficClass::Heal, part.size as u64).await;
                                 let till_offset = erasure.shard_file_offset(0, part.size, part.size);
                                 let checksum_algo = erasure_info.get_checksum_info(part.number).algorithm;
                                 let mut readers = Vec::with_capacity(latest_disks.len());
@@ -3182,6 +3441,34 @@ impl SetDisks {
                                 // Attempt a rename now from healed data to final location.
                                 parts_metadata[index].set_healing();
 
+                                // The data directory of a rewritten drive may still be in place, and the
+                                // healed one cannot be renamed over it.
+                                if opts.rewrite
+                                    && !latest_meta.deleted
+                                    && !latest_meta.is_remote()
+                                    && !parts_metadata[index].inline_data()
+                                {
+                                    let d_path = Path::new(&encode_dir_object(object)).join(dst_data_dir.to_string());
+                                    if let Err(err) = disk
+                                        .delete(
+                                            bucket,
+                                            &d_path.to_string_lossy(),
+                                            DeleteOptions {
+                                                recursive: true,
+                                                immediate: true,
+                                                ..Default::default()
+                                            },
+                                        )
+                                        .await
+                                        && err != DiskError::FileNotFound
+                                    {
+                                        warn!(
+                                            "heal_object remove data dir {:?} on disk {} before rewrite failed: {:?}",
+                                            d_path, self.set_endpoints[index], err
+                                        );
+                                    }
+                                }
+
                                 info!(
                                     "Renaming healed data for disk {} (endpoint={}): src_volume={}, src_path={}, dst_volume={}, dst_path={}",
                                     index, self.set_endpoints[index], RUSTFS_META_TMP_BUCKET, tmp_id, bucket, object
@@ -3275,6 +3562,8 @@ impl SetDisks {
                             }
                         }
 
+                        self.clear_degraded_write(bucket, object, &latest_meta, &result, &disks).await;
+
                         Ok((result, None))
                     }
                     Err(err) => {
@@ -3727,6 +4016,10 @@ impl ObjectIO for SetDisks {
             return Err(to_object_err(Error::MethodNotAllowed, vec![bucket, object]));
         }
 
+        if dedup_blob(&object_info.user_defined).is_some() {
+            return get_deduplicated_object_reader(range, h, &object_info, opts).await;
+        }
+
         // if object_info.size == 0 {
         //     let empty_rd: Box<dyn AsyncRead> = Box::new(Bytes::new());
 
@@ -3744,6 +4037,7 @@ impl ObjectIO for SetDisks {
             let reader = GetObjectReader {
                 stream: Box::new(Cursor::new(Vec::new())),
                 object_info,
+                body: None,
             };
             return Ok(reader);
         }
@@ -3753,6 +4047,42 @@ impl ObjectIO for SetDisks {
             return Ok(gr);
         }
 
+        if range.is_none()
+            && opts.part_number.is_none()
+            && !object_info.is_compressed()
+            && GLOBAL_HOT_OBJECTS.fits(object_info.size)
+        {
+            let hot_keys = self.fast_lock_manager.hot_keys();
+            let key = rustfs_lock::fast_lock::types::ObjectKey::new(bucket, object);
+            if opts.no_lock {
+                // The caller holds the lock, count the read here
+                hot_keys.record(&key);
+            }
+
+            if hot_keys.is_hot(&key) {
+                let load = async {
+                    let mut data = Vec::with_capacity(object_info.size as usize);
+                    Self::get_object_with_fileinfo(
+                        bucket,
+                        object,
+                        0,
+                        object_info.size,
+                        &mut data,
+                        fi.clone(),
+                        files.clone(),
+                        &disks,
+                        self.set_index,
+                        self.pool_index,
+                    )
+                    .await?;
+                    Ok(Bytes::from(data))
+                };
+                if let Some(data) = GLOBAL_HOT_OBJECTS.get_or_load(bucket, object, &fi, load).await {
+                    return Ok(GetObjectReader::from_bytes(data, object_info));
+                }
+            }
+        }
+
         let (rd, wd) = tokio::io::duplex(DEFAULT_READ_BUFFER_SIZE);
 
         let (reader, offset, length) = GetObjectReader::new(Box::new(rd), range, &object_info, opts, &h)?;
@@ -3762,10 +4092,20 @@ impl ObjectIO for SetDisks {
         let object = object.to_owned();
         let set_index = self.set_index;
         let pool_index = self.pool_index;
+        let memory = GLOBAL_MEMORY_BUDGET
+            .admit(transfer_memory(
+                fi.erasure.data_blocks,
+                fi.erasure.parity_blocks,
+                fi.erasure.block_size,
+                length,
+            ))
+            .await?;
+
         // Move the read-lock guard into the task so it lives for the duration of the read
         // let _guard_to_hold = _read_lock_guard; // moved into closure below
-        tokio::spawn(async move {
+        tokio::spawn(request_io::inherit(async move {
             let _guard = read_lock_guard; // keep guard alive until task ends
+            let _memory = memory;
             let mut writer = wd;
             if let Err(e) = Self::get_object_with_fileinfo(
                 &bucket,
@@ -3785,13 +4125,15 @@ impl ObjectIO for SetDisks {
             };
 
             // error!("get_object_with_fileinfo end {}/{}", bucket, object);
-        });
+        }));
 
         Ok(reader)
     }
 
     #[tracing::instrument(level = "debug", skip(self, data,))]
     async fn put_object(&self, bucket: &str, object: &str, data: &mut PutObjReader, opts: &ObjectOptions) -> Result<ObjectInfo> {
+        self.check_disk_watermark(bucket, object)?;
+
         let disks_snapshot = self.get_disks_internal().await;
         let (disks, filtered_online) = self.filter_online_disks(disks_snapshot).await;
 
@@ -3843,6 +4185,7 @@ impl ObjectIO for SetDisks {
         }
 
         let mut fi = FileInfo::new([bucket, object].join("/").as_str(), data_drives, parity_drives);
+        fi.erasure.block_size = bucket_block_size(bucket).await;
 
         fi.version_id = {
             if let Some(ref vid) = opts.version_id {
@@ -3866,6 +4209,19 @@ impl ObjectIO for SetDisks {
 
         let tmp_object = format!("{}/{}/part.1", tmp_dir, fi.data_dir.unwrap());
 
+        // Held until the erasure blocks of the upload are written
+        let _memory = GLOBAL_MEMORY_BUDGET
+            .admit(transfer_memory(
+                fi.erasure.data_blocks,
+                fi.erasure.parity_blocks,
+                fi.erasure.block_size,
+                data.size(),
+            ))
+            .await?;
+
+        // Removes the temporary shards if the write fails or the client disconnects before it completes
+        let tmp_guard = TmpCleanupGuard::new(&shuffle_disks, tmp_dir.clone());
+
         let erasure = erasure_coding::Erasure::new(fi.erasure.data_blocks, fi.erasure.parity_blocks, fi.erasure.block_size);
 
         let is_inline_buffer = {
@@ -3940,9 +4296,11 @@ impl ObjectIO for SetDisks {
             Ok((r, w)) => (r, w),
             Err(e) => {
                 error!("encode err {:?}", e);
+                drop(writers);
+                tmp_guard.rollback().await;
                 return Err(e.into());
             }
-        }; // TODO: delete temporary directory on error
+        };
 
         let _ = mem::replace(&mut data.stream, reader);
         // if let Err(err) = close_bitrot_writers(&mut writers).await {
@@ -3951,6 +4309,8 @@ impl ObjectIO for SetDisks {
 
         if (w_size as i64) < data.size() {
             warn!("put_object write size < data.size(), w_size={}, data.size={}", w_size, data.size());
+            drop(writers);
+            tmp_guard.rollback().await;
             return Err(Error::other(format!(
                 "put_object write size < data.size(), w_size={}, data.size={}",
                 w_size,
@@ -3966,7 +4326,11 @@ impl ObjectIO for SetDisks {
 
         //TODO: userDefined
 
-        let etag = data.stream.try_resolve_etag().unwrap_or_default();
+        let mut etag = data.stream.try_resolve_etag().unwrap_or_default();
+
+        if let Some(ref tag) = opts.preserve_etag {
+            etag = tag.clone();
+        }
 
         user_defined.insert("etag".to_owned(), etag.clone());
 
@@ -4019,11 +4383,18 @@ impl ObjectIO for SetDisks {
             if opts.data_movement {
                 pfi.set_data_moved();
             }
+
+            // Drives offline for the write leave it at reduced parity until the scanner heals it
+            if nil_count < shuffle_disks.len() {
+                pfi.set_degraded_write(nil_count);
+            } else {
+                pfi.clear_degraded_write();
+            }
         }
 
         drop(writers); // drop writers to close all files, this is to prevent FileAccessDenied errors when renaming data
 
-        let (online_disks, _, op_old_dir) = Self::rename_data(
+        let (online_disks, _, op_old_dir) = match Self::rename_data(
             &shuffle_disks,
             RUSTFS_META_TMP_BUCKET,
             tmp_dir.as_str(),
@@ -4032,13 +4403,22 @@ impl ObjectIO for SetDisks {
             object,
             write_quorum,
         )
-        .await?;
+        .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                // Shards that reached their destination were undone, drop the ones left in tmp
+                tmp_guard.rollback().await;
+                return Err(e);
+            }
+        };
 
         if let Some(old_dir) = op_old_dir {
             self.commit_rename_data_dir(&shuffle_disks, bucket, object, &old_dir.to_string(), write_quorum)
                 .await?;
         }
 
+        tmp_guard.disarm();
         self.delete_all(RUSTFS_META_TMP_BUCKET, &tmp_dir).await?;
 
         for (i, op_disk) in online_disks.iter().enumerate() {
@@ -4621,6 +5001,7 @@ impl StorageAPI for SetDisks {
         _fetch_owner: bool,
         _start_after: Option<String>,
         _incl_deleted: bool,
+        _consistency: Option<ListingConsistency>,
     ) -> Result<ListObjectsV2Info> {
         unimplemented!()
     }
@@ -4651,6 +5032,19 @@ impl StorageAPI for SetDisks {
 
     #[tracing::instrument(skip(self))]
     async fn get_object_info(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<ObjectInfo> {
+        // A given version only changes when it is deleted or its metadata updated, so read it
+        // without the lock and keep the result unless a write of the object overlapped the read
+        if !opts.no_lock
+            && opts.version_id.is_some()
+            && let Some(snapshot) = self.fast_lock_manager.read_snapshot(bucket, object)
+        {
+            let result = self.get_object_fileinfo(bucket, object, opts, false).await;
+            if self.fast_lock_manager.validate_read(bucket, object, snapshot) {
+                let (fi, _, _) = result.map_err(|e| to_object_err(e, vec![bucket, object]))?;
+                return Ok(ObjectInfo::from_file_info(&fi, bucket, object, opts.versioned || opts.version_suspended));
+            }
+        }
+
         // Acquire a shared read-lock to protect consistency during info fetch
         let _read_lock_guard = if !opts.no_lock {
             Some(
@@ -4769,12 +5163,20 @@ impl StorageAPI for SetDisks {
             fi.metadata.insert(k, v);
         }
 
+        if opts.replace_metadata {
+            fi.set_replace_metadata();
+        }
+
         if let Some(mt) = &opts.eval_metadata {
             for (k, v) in mt {
                 fi.metadata.insert(k.clone(), v.clone());
             }
         }
 
+        if opts.replace_metadata {
+            fi.bump_metadata_revision();
+        }
+
         fi.mod_time = opts.mod_time;
         if let Some(ref version_id) = opts.version_id {
             fi.version_id = Uuid::parse_str(version_id).ok();
@@ -4859,6 +5261,7 @@ impl StorageAPI for SetDisks {
         let reader = ReaderImpl::ObjectBody(GetObjectReader {
             stream: Box::new(pr),
             object_info: oi,
+            body: None,
         });
 
         let cloned_bucket = bucket.to_string();
@@ -5115,6 +5518,8 @@ impl StorageAPI for SetDisks {
         data: &mut PutObjReader,
         opts: &ObjectOptions,
     ) -> Result<PartInfo> {
+        self.check_disk_watermark(bucket, object)?;
+
         let upload_id_path = Self::get_upload_id_dir(bucket, object, upload_id);
 
         let (fi, _) = self.check_upload_id_exists(bucket, object, upload_id, true).await?;
@@ -5148,6 +5553,19 @@ impl StorageAPI for SetDisks {
         let tmp_part = format!("{}x{}", Uuid::new_v4(), OffsetDateTime::now_utc().unix_timestamp());
         let tmp_part_path = Arc::new(format!("{tmp_part}/{part_suffix}"));
 
+        // Held until the erasure blocks of the upload are written
+        let _memory = GLOBAL_MEMORY_BUDGET
+            .admit(transfer_memory(
+                fi.erasure.data_blocks,
+                fi.erasure.parity_blocks,
+                fi.erasure.block_size,
+                data.size(),
+            ))
+            .await?;
+
+        // Removes the temporary part if the upload fails or the client disconnects before it completes
+        let tmp_guard = TmpCleanupGuard::new(&shuffle_disks, tmp_part.clone());
+
         let erasure = erasure_coding::Erasure::new(fi.erasure.data_blocks, fi.erasure.parity_blocks, fi.erasure.block_size);
 
         let mut writers = Vec::with_capacity(shuffle_disks.len());
@@ -5203,12 +5621,21 @@ impl StorageAPI for SetDisks {
             HashReader::new(Box::new(WarpReader::new(Cursor::new(Vec::new()))), 0, 0, None, None, false)?,
         );
 
-        let (reader, w_size) = Arc::new(erasure).encode(stream, &mut writers, write_quorum).await?; // TODO: delete temporary directory on error
+        let (reader, w_size) = match Arc::new(erasure).encode(stream, &mut writers, write_quorum).await {
+            Ok(v) => v,
+            Err(e) => {
+                drop(writers);
+                tmp_guard.rollback().await;
+                return Err(e.into());
+            }
+        };
 
         let _ = mem::replace(&mut data.stream, reader);
 
         if (w_size as i64) < data.size() {
             warn!("put_object_part write size < data.size(), w_size={}, data.size={}", w_size, data.size());
+            drop(writers);
+            tmp_guard.rollback().await;
             return Err(Error::other(format!(
                 "put_object_part write size < data.size(), w_size={}, data.size={}",
                 w_size,
@@ -5250,7 +5677,7 @@ impl StorageAPI for SetDisks {
         drop(writers); // drop writers to close all files
 
         let part_path = format!("{}/{}/{}", upload_id_path, fi.data_dir.unwrap_or_default(), part_suffix);
-        let _ = Self::rename_part(
+        if let Err(e) = Self::rename_part(
             &disks,
             RUSTFS_META_TMP_BUCKET,
             &tmp_part_path,
@@ -5259,7 +5686,12 @@ impl StorageAPI for SetDisks {
             part_info_buff.into(),
             write_quorum,
         )
-        .await?;
+        .await
+        {
+            tmp_guard.rollback().await;
+            return Err(e);
+        }
+        tmp_guard.disarm();
 
         let ret: PartInfo = PartInfo {
             etag: Some(etag.clone()),
@@ -5409,144 +5841,26 @@ impl StorageAPI for SetDisks {
         delimiter: Option<String>,
         max_uploads: usize,
     ) -> Result<ListMultipartsInfo> {
-        let disks = {
-            let disks = self.get_online_local_disks().await;
-            if disks.is_empty() {
-                // TODO: getOnlineDisksWithHealing
-                self.get_online_disks().await
-            } else {
-                disks
-            }
+        let uploads = if GLOBAL_COMPAT_SYS.s3a_magic_committer_enabled() {
+            self.list_uploads_under_prefix(bucket, object).await?
+        } else {
+            self.list_object_uploads(bucket, object).await?
         };
 
-        let mut upload_ids: Vec<String> = Vec::new();
-
-        for disk in disks.iter().flatten() {
-            if !disk.is_online().await {
-                continue;
-            }
-
-            let has_uoload_ids = match disk
-                .list_dir(
-                    bucket,
-                    RUSTFS_META_MULTIPART_BUCKET,
-                    Self::get_multipart_sha_dir(bucket, object).as_str(),
-                    -1,
-                )
-                .await
-            {
-                Ok(res) => Some(res),
-                Err(err) => {
-                    if err == DiskError::DiskNotFound {
-                        None
-                    } else if err == DiskError::FileNotFound {
-                        return Ok(ListMultipartsInfo {
-                            key_marker: key_marker.to_owned(),
-                            max_uploads,
-                            prefix: object.to_owned(),
-                            delimiter: delimiter.to_owned(),
-                            ..Default::default()
-                        });
-                    } else {
-                        return Err(to_object_err(err.into(), vec![bucket, object]));
-                    }
-                }
-            };
-
-            if let Some(ids) = has_uoload_ids {
-                upload_ids = ids;
-                break;
-            }
-        }
-
-        let mut uploads = Vec::new();
-
-        let mut populated_upload_ids = HashSet::new();
-
-        for upload_id in upload_ids.iter() {
-            let upload_id = upload_id.trim_end_matches(SLASH_SEPARATOR).to_string();
-            if populated_upload_ids.contains(&upload_id) {
-                continue;
-            }
-
-            let start_time = {
-                let now = OffsetDateTime::now_utc();
-
-                let splits: Vec<&str> = upload_id.split("x").collect();
-                if splits.len() == 2 {
-                    if let Ok(unix) = splits[1].parse::<i128>() {
-                        OffsetDateTime::from_unix_timestamp_nanos(unix)?
-                    } else {
-                        now
-                    }
-                } else {
-                    now
-                }
-            };
-
-            uploads.push(MultipartInfo {
-                bucket: bucket.to_owned(),
-                object: object.to_owned(),
-                upload_id: base64_simd::URL_SAFE_NO_PAD
-                    .encode_to_string(format!("{}.{}", get_global_deployment_id().unwrap_or_default(), upload_id).as_bytes()),
-                initiated: Some(start_time),
-                ..Default::default()
-            });
-
-            populated_upload_ids.insert(upload_id);
-        }
-
-        uploads.sort_by(|a, b| a.initiated.cmp(&b.initiated));
-
-        let mut upload_idx = 0;
-        if let Some(upload_id_marker) = &upload_id_marker {
-            while upload_idx < uploads.len() {
-                if &uploads[upload_idx].upload_id != upload_id_marker {
-                    upload_idx += 1;
-                    continue;
-                }
-
-                if &uploads[upload_idx].upload_id == upload_id_marker {
-                    upload_idx += 1;
-                    break;
-                }
-
-                upload_idx += 1;
-            }
-        }
-
-        let mut ret_uploads = Vec::new();
-        let mut next_upload_id_marker = None;
-        while upload_idx < uploads.len() {
-            ret_uploads.push(uploads[upload_idx].clone());
-            next_upload_id_marker = Some(uploads[upload_idx].upload_id.clone());
-            upload_idx += 1;
-
-            if ret_uploads.len() > max_uploads {
-                break;
-            }
-        }
-
-        let is_truncated = ret_uploads.len() < uploads.len();
-
-        if !is_truncated {
-            next_upload_id_marker = None;
-        }
-
-        Ok(ListMultipartsInfo {
-            key_marker: key_marker.to_owned(),
-            next_upload_id_marker,
+        Ok(ListMultipartsInfo::paginate(
+            object,
+            uploads,
+            key_marker,
+            upload_id_marker,
+            delimiter,
             max_uploads,
-            is_truncated,
-            uploads: ret_uploads,
-            prefix: object.to_owned(),
-            delimiter: delimiter.to_owned(),
-            ..Default::default()
-        })
+        ))
     }
 
     #[tracing::instrument(skip(self))]
     async fn new_multipart_upload(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<MultipartUploadResult> {
+        self.check_disk_watermark(bucket, object)?;
+
         let disks = self.disks.read().await;
 
         let disks = disks.clone();
@@ -5583,6 +5897,7 @@ impl StorageAPI for SetDisks {
         }
 
         let mut fi = FileInfo::new([bucket, object].join("/").as_str(), data_drives, parity_drives);
+        fi.erasure.block_size = bucket_block_size(bucket).await;
 
         fi.version_id = if let Some(vid) = &opts.version_id {
             Some(Uuid::parse_str(vid)?)
@@ -5615,6 +5930,8 @@ impl StorageAPI for SetDisks {
             }
         }
 
+        user_defined.insert(MULTIPART_OBJECT_KEY.to_owned(), object.to_owned());
+
         if let Some(checksum) = &opts.want_checksum {
             user_defined.insert(rustfs_rio::RUSTFS_MULTIPART_CHECKSUM.to_string(), checksum.checksum_type.to_string());
             user_defined.insert(
@@ -5988,6 +6305,8 @@ impl StorageAPI for SetDisks {
 
         fi.metadata.remove(rustfs_rio::RUSTFS_MULTIPART_CHECKSUM);
         fi.metadata.remove(rustfs_rio::RUSTFS_MULTIPART_CHECKSUM_TYPE);
+        fi.metadata.remove(MULTIPART_OBJECT_KEY);
+        fi.metadata.remove(MULTIPART_HIDDEN_KEY);
 
         fi.size = object_size as i64;
         fi.mod_time = opts.mod_time;
@@ -6419,6 +6738,18 @@ fn is_object_dir_dang_ling(errs: &[Option<DiskError>]) -> bool {
     found < not_found && found > 0
 }
 
+/// Error of a write that missed its quorum, with the number of disks that succeeded and failed
+/// when the quorum was lost rather than a common disk error being hit.
+fn write_quorum_err(err: DiskError, bucket: &str, object: &str, errs: &[Option<DiskError>], write_quorum: usize) -> Error {
+    if err != DiskError::ErasureWriteQuorum {
+        return err.into();
+    }
+
+    let succeeded = errs.iter().filter(|e| e.is_none()).count();
+    error!(bucket, object, succeeded, write_quorum, "write quorum not reached: {:?}", errs);
+    Error::WriteQuorumNotReached(bucket.to_string(), object.to_string(), succeeded, errs.len() - succeeded, write_quorum)
+}
+
 fn join_errs(errs: &[Option<DiskError>]) -> String {
     let errs = errs
         .iter()
@@ -6663,6 +6994,11 @@ async fn get_disks_info(disks: &[Option<DiskStore>], eps: &[Endpoint]) -> Vec<ru
 
     for (i, pool) in disks.iter().enumerate() {
         if let Some(disk) = pool {
+            let smart = if eps[i].is_local {
+                GLOBAL_DRIVE_HEALTH_SYS.get(&eps[i].to_string())
+            } else {
+                None
+            };
             match disk.disk_info(&DiskInfoOptions::default()).await {
                 Ok(res) => ret.push(rustfs_madmin::Disk {
                     endpoint: eps[i].to_string(),
@@ -6680,7 +7016,10 @@ async fn get_disks_info(disks: &[Option<DiskStore>], eps: &[Endpoint]) -> Vec<ru
                     uuid: res.id.clone(),
                     major: res.major as u32,
                     minor: res.minor as u32,
-                    model: None,
+                    model: smart
+                        .as_ref()
+                        .map(|smart| smart.model.clone())
+                        .filter(|model| !model.is_empty()),
                     total_space: res.total,
                     used_space: res.used,
                     available_space: res.free,
@@ -6693,6 +7032,7 @@ async fn get_disks_info(disks: &[Option<DiskStore>], eps: &[Endpoint]) -> Vec<ru
                     },
                     used_inodes: res.used_inodes,
                     free_inodes: res.free_inodes,
+                    smart,
                     ..Default::default()
                 }),
                 Err(err) => ret.push(rustfs_madmin::Disk {
@@ -6765,11 +7105,14 @@ fn is_min_allowed_part_size(size: i64) -> bool {
     size >= GLOBAL_MIN_PART_SIZE.as_u64() as i64
 }
 
+/// ETag of a completed multipart upload: the MD5 of the concatenated binary part MD5s followed
+/// by `-<part count>`, as S3 computes it. Clients may send the part ETags quoted.
 fn get_complete_multipart_md5(parts: &[CompletePart]) -> String {
     let mut buf = Vec::new();
 
     for part in parts.iter() {
         if let Some(etag) = &part.etag {
+            let etag = rustfs_utils::path::trim_etag(etag);
             if let Ok(etag_bytes) = hex_simd::decode_to_vec(etag.as_bytes()) {
                 buf.extend(etag_bytes);
             } else {
@@ -6929,8 +7272,17 @@ mod tests {
         ];
 
         let md5 = get_complete_multipart_md5(&parts);
-        assert!(md5.ends_with("-2")); // Should end with part count
-        assert!(md5.len() > 10); // Should have reasonable length
+        assert_eq!(md5, "f666c301a43456b66b9f3a47f696d391-2");
+
+        // Quoted part ETags produce the same result
+        let quoted: Vec<CompletePart> = parts
+            .iter()
+            .map(|p| CompletePart {
+                etag: p.etag.as_ref().map(|e| format!("\"{e}\"")),
+                ..p.clone()
+            })
+            .collect();
+        assert_eq!(get_complete_multipart_md5(&quoted), md5);
 
         // Test with empty parts
         let empty_parts = vec![];
@@ -7425,4 +7777,16 @@ mod tests {
         assert!(!is_infrequent_access_class(storageclass::DEEP_ARCHIVE));
         assert!(!is_infrequent_access_class(storageclass::EXPRESS_ONEZONE));
     }
+
+    #[test]
+    fn test_write_quorum_err() {
+        let errs = vec![None, Some(DiskError::DiskNotFound), Some(DiskError::FaultyDisk), None];
+        let err = write_quorum_err(DiskError::ErasureWriteQuorum, "bucket", "object", &errs, 3);
+        assert!(matches!(err, Error::WriteQuorumNotReached(ref b, ref o, 2, 2, 3) if b == "bucket" && o == "object"));
+        assert_eq!(DiskError::from(err), DiskError::ErasureWriteQuorum);
+
+        // A common disk error reaching quorum is reported as is
+        let err = write_quorum_err(DiskError::DiskFull, "bucket", "object", &errs, 3);
+        assert_eq!(err, Error::DiskFull);
+    }
 }
@@ -15,8 +15,11 @@
 
 use std::{collections::HashMap, sync::Arc};
 
+use crate::bucket::listing::ListingConsistency;
+use crate::compat::GLOBAL_COMPAT_SYS;
 use crate::disk::error_reduce::count_errs;
 use crate::error::{Error, Result};
+use crate::hot_objects::GLOBAL_HOT_OBJECTS;
 use crate::store_api::{ListPartsInfo, ObjectInfoOrErr, WalkOptions};
 use crate::{
     disk::{
@@ -112,7 +115,7 @@ impl Sets {
         let mut disk_set = Vec::with_capacity(set_count);
 
         // Create fast lock manager for high performance
-        let fast_lock_manager = Arc::new(rustfs_lock::FastObjectLockManager::new());
+        let fast_lock_manager = Arc::new(rustfs_lock::FastObjectLockManager::with_config(GLOBAL_HOT_OBJECTS.lock_config()));
 
         for i in 0..set_count {
             let mut set_drive = Vec::with_capacity(set_drive_count);
@@ -441,6 +444,7 @@ impl StorageAPI for Sets {
         _fetch_owner: bool,
         _start_after: Option<String>,
         _incl_deleted: bool,
+        _consistency: Option<ListingConsistency>,
     ) -> Result<ListObjectsV2Info> {
         unimplemented!()
     }
@@ -586,18 +590,21 @@ impl StorageAPI for Sets {
             }
         }
 
-        // TODO: concurrency
-        for (k, v) in set_obj_map {
+        // Each erasure set deletes its share in one batch, all sets at once
+        let futures = set_obj_map.into_iter().map(|(k, v)| {
             let disks = self.get_disks(k);
-            let objs: Vec<ObjectToDelete> = v.iter().map(|v| v.obj.clone()).collect();
-            let (dobjects, errs) = disks.delete_objects(bucket, objs, opts.clone()).await;
-
-            for (i, err) in errs.into_iter().enumerate() {
-                let obj = v.get(i).unwrap();
+            let opts = opts.clone();
+            async move {
+                let objs: Vec<ObjectToDelete> = v.iter().map(|v| v.obj.clone()).collect();
+                let (dobjects, errs) = disks.delete_objects(bucket, objs, opts).await;
+                (v, dobjects, errs)
+            }
+        });
 
+        for (v, dobjects, errs) in join_all(futures).await {
+            for ((obj, dobject), err) in v.iter().zip(dobjects).zip(errs) {
                 del_errs[obj.orig_idx] = err;
-
-                del_objects[obj.orig_idx] = dobjects.get(i).unwrap().clone();
+                del_objects[obj.orig_idx] = dobject;
             }
         }
 
@@ -628,9 +635,35 @@ impl StorageAPI for Sets {
         delimiter: Option<String>,
         max_uploads: usize,
     ) -> Result<ListMultipartsInfo> {
-        self.get_disks_by_key(prefix)
-            .list_multipart_uploads(bucket, prefix, key_marker, upload_id_marker, delimiter, max_uploads)
-            .await
+        if !GLOBAL_COMPAT_SYS.s3a_magic_committer_enabled() {
+            return self
+                .get_disks_by_key(prefix)
+                .list_multipart_uploads(bucket, prefix, key_marker, upload_id_marker, delimiter, max_uploads)
+                .await;
+        }
+
+        // Uploads under a prefix are spread over all sets, each set returns all of its own so the
+        // page is cut once over the merged list.
+        let results = join_all(
+            self.disk_set
+                .iter()
+                .map(|set| set.list_multipart_uploads(bucket, prefix, None, None, None, usize::MAX)),
+        )
+        .await;
+
+        let mut uploads = Vec::new();
+        for res in results {
+            uploads.extend(res?.uploads);
+        }
+
+        Ok(ListMultipartsInfo::paginate(
+            prefix,
+            uploads,
+            key_marker,
+            upload_id_marker,
+            delimiter,
+            max_uploads,
+        ))
     }
     #[tracing::instrument(skip(self))]
     async fn new_multipart_upload(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<MultipartUploadResult> {
@@ -0,0 +1,621 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Controlled performance tests of drives, the internode network and the object layer.
+//!
+//! Drive tests run on the node owning each drive, so remote drives are measured through the
+//! internode RPC endpoint rather than over the network. Network tests stream generated data to
+//! and from every peer. Object tests PUT and then GET objects in the system bucket for a fixed
+//! duration. All test data is removed afterwards, and only one test runs at a time.
+
+use crate::disk::{DeleteOptions, DiskAPI, DiskStore, RUSTFS_META_BUCKET, RUSTFS_META_TMP_BUCKET};
+use crate::error::{Error, Result};
+use crate::rpc::build_auth_headers;
+use crate::store::ECStore;
+use crate::store_api::{ObjectIO, ObjectOptions, PutObjReader, StorageAPI};
+use bytes::Bytes;
+use futures::future::join_all;
+use futures::{StreamExt, stream};
+use http::{HeaderMap, Method};
+use rand::{Rng, RngCore};
+use rustfs_rio::{HashReader, WarpReader};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Prefix of speed test data in the system buckets.
+pub const SPEEDTEST_PREFIX: &str = "speedtest";
+
+pub const MAX_SPEEDTEST_OBJECT_SIZE: u64 = 1 << 30;
+pub const MAX_SPEEDTEST_CONCURRENCY: usize = 256;
+pub const MAX_SPEEDTEST_DURATION_SECS: u64 = 300;
+
+const MIN_BLOCK_SIZE: u64 = 4 << 10;
+const MAX_BLOCK_SIZE: u64 = 64 << 20;
+const MAX_DRIVE_FILE_SIZE: u64 = 4 << 30;
+const MAX_RANDOM_READS: u64 = 10_000;
+const NET_CHUNK_SIZE: usize = 1 << 20;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
+/// Held while a speed test runs, so tests do not skew each other's numbers.
+struct RunGuard;
+
+impl RunGuard {
+    fn acquire() -> Result<Self> {
+        if RUNNING.swap(true, Ordering::AcqRel) {
+            return Err(Error::other("a speed test is already running"));
+        }
+        Ok(Self)
+    }
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        RUNNING.store(false, Ordering::Release);
+    }
+}
+
+fn bytes_per_sec(bytes: u64, elapsed: Duration) -> u64 {
+    let secs = elapsed.as_secs_f64();
+    if secs <= 0.0 {
+        return 0;
+    }
+    (bytes as f64 / secs) as u64
+}
+
+fn random_bytes(len: usize) -> Bytes {
+    let mut buf = vec![0u8; len];
+    rand::rng().fill_bytes(&mut buf);
+    Bytes::from(buf)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DrivePerfOptions {
+    /// Size of the file written and read sequentially on each drive.
+    pub file_size: u64,
+    /// Size of every write and random read.
+    pub block_size: u64,
+    /// Number of block-sized reads at random offsets.
+    pub random_reads: u64,
+    /// Test one drive after the other instead of all at once.
+    pub serial: bool,
+}
+
+impl Default for DrivePerfOptions {
+    fn default() -> Self {
+        Self {
+            file_size: 256 << 20,
+            block_size: 4 << 20,
+            random_reads: 256,
+            serial: false,
+        }
+    }
+}
+
+impl DrivePerfOptions {
+    pub fn validate(&self) -> Result<()> {
+        if !(MIN_BLOCK_SIZE..=MAX_BLOCK_SIZE).contains(&self.block_size) {
+            return Err(Error::other(format!(
+                "block size must be between {MIN_BLOCK_SIZE} and {MAX_BLOCK_SIZE} bytes"
+            )));
+        }
+        if self.file_size < self.block_size || self.file_size > MAX_DRIVE_FILE_SIZE {
+            return Err(Error::other(format!(
+                "file size must be between the block size and {MAX_DRIVE_FILE_SIZE} bytes"
+            )));
+        }
+        if self.random_reads > MAX_RANDOM_READS {
+            return Err(Error::other(format!("at most {MAX_RANDOM_READS} random reads are allowed")));
+        }
+        Ok(())
+    }
+
+    fn blocks(&self) -> u64 {
+        self.file_size / self.block_size
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrivePerfResult {
+    pub endpoint: String,
+    pub write_bytes_per_sec: u64,
+    pub read_bytes_per_sec: u64,
+    pub random_read_iops: u64,
+    pub random_read_bytes_per_sec: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+async fn run_drive_perf(disk: &DiskStore, path: &str, opts: &DrivePerfOptions) -> Result<DrivePerfResult> {
+    let block = random_bytes(opts.block_size as usize);
+    let blocks = opts.blocks();
+    let file_size = blocks * opts.block_size;
+
+    let start = Instant::now();
+    let mut writer = disk.create_file("", RUSTFS_META_TMP_BUCKET, path, file_size as i64).await?;
+    for _ in 0..blocks {
+        writer.write_all(&block).await?;
+    }
+    writer.shutdown().await?;
+    let write_elapsed = start.elapsed();
+
+    let mut buf = vec![0u8; opts.block_size as usize];
+    let start = Instant::now();
+    let mut reader = disk
+        .read_file_stream(RUSTFS_META_TMP_BUCKET, path, 0, file_size as usize)
+        .await?;
+    let mut read = 0u64;
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        read += n as u64;
+    }
+    let read_elapsed = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..opts.random_reads {
+        let offset = rand::rng().random_range(0..blocks) * opts.block_size;
+        let mut reader = disk
+            .read_file_stream(RUSTFS_META_TMP_BUCKET, path, offset as usize, opts.block_size as usize)
+            .await?;
+        reader.read_exact(&mut buf).await?;
+    }
+    let random_elapsed = start.elapsed();
+
+    Ok(DrivePerfResult {
+        endpoint: disk.to_string(),
+        write_bytes_per_sec: bytes_per_sec(file_size, write_elapsed),
+        read_bytes_per_sec: bytes_per_sec(read, read_elapsed),
+        random_read_iops: bytes_per_sec(opts.random_reads, random_elapsed),
+        random_read_bytes_per_sec: bytes_per_sec(opts.random_reads * opts.block_size, random_elapsed),
+        error: None,
+    })
+}
+
+/// Measure sequential and random throughput of a drive of this node.
+pub async fn local_drive_perf(disk: &DiskStore, opts: &DrivePerfOptions) -> DrivePerfResult {
+    let path = format!("{SPEEDTEST_PREFIX}/{}", Uuid::new_v4());
+    let result = run_drive_perf(disk, &path, opts).await;
+
+    let delete_opts = DeleteOptions {
+        immediate: true,
+        ..Default::default()
+    };
+    if let Err(err) = disk.delete(RUSTFS_META_TMP_BUCKET, &path, delete_opts).await {
+        warn!("failed to remove drive speed test file {} on {}: {:?}", path, disk.to_string(), err);
+    }
+
+    result.unwrap_or_else(|err| DrivePerfResult {
+        endpoint: disk.to_string(),
+        error: Some(err.to_string()),
+        ..Default::default()
+    })
+}
+
+async fn remote_drive_perf(disk: &DiskStore, opts: &DrivePerfOptions) -> Result<DrivePerfResult> {
+    let endpoint = disk.endpoint();
+    let url = format!(
+        "{}/rustfs/rpc/drive_perf?disk={}&file_size={}&block_size={}&random_reads={}",
+        endpoint.grid_host(),
+        urlencoding::encode(endpoint.to_string().as_str()),
+        opts.file_size,
+        opts.block_size,
+        opts.random_reads
+    );
+
+    let mut headers = HeaderMap::new();
+    build_auth_headers(&url, &Method::GET, &mut headers);
+    let resp = HTTP_CLIENT
+        .get(&url)
+        .headers(headers)
+        .send()
+        .await
+        .map_err(Error::other)?
+        .error_for_status()
+        .map_err(Error::other)?;
+    let body = resp.bytes().await.map_err(Error::other)?;
+    serde_json::from_slice(&body).map_err(Error::other)
+}
+
+/// Measure every drive of the cluster, each one on the node it belongs to.
+pub async fn drive_perf(store: &ECStore, opts: DrivePerfOptions) -> Result<Vec<DrivePerfResult>> {
+    opts.validate()?;
+    let _guard = RunGuard::acquire()?;
+
+    let disks: Vec<DiskStore> = store.disk_map.values().flatten().flatten().cloned().collect();
+    info!("running drive speed test on {} drives with {:?}", disks.len(), opts);
+
+    let run_one = |disk: DiskStore| async move {
+        if disk.is_local() {
+            return local_drive_perf(&disk, &opts).await;
+        }
+        remote_drive_perf(&disk, &opts).await.unwrap_or_else(|err| DrivePerfResult {
+            endpoint: disk.to_string(),
+            error: Some(err.to_string()),
+            ..Default::default()
+        })
+    };
+
+    if opts.serial {
+        let mut results = Vec::with_capacity(disks.len());
+        for disk in disks {
+            results.push(run_one(disk).await);
+        }
+        return Ok(results);
+    }
+    Ok(join_all(disks.into_iter().map(run_one)).await)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetPerfResult {
+    pub peer: String,
+    /// Throughput from this node to the peer.
+    pub tx_bytes_per_sec: u64,
+    /// Throughput from the peer to this node.
+    pub rx_bytes_per_sec: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn net_perf_url(peer: &str, size: u64) -> String {
+    format!("{peer}/rustfs/rpc/net_perf?size={size}")
+}
+
+async fn peer_net_perf(peer: &str, size: u64) -> Result<NetPerfResult> {
+    let chunk = random_bytes(NET_CHUNK_SIZE);
+    let chunks = size.div_ceil(NET_CHUNK_SIZE as u64) as usize;
+
+    let url = net_perf_url(peer, size);
+    let mut headers = HeaderMap::new();
+    build_auth_headers(&url, &Method::PUT, &mut headers);
+    let body = stream::iter((0..chunks).map(move |_| Ok::<_, std::io::Error>(chunk.clone())));
+    let start = Instant::now();
+    HTTP_CLIENT
+        .put(&url)
+        .headers(headers)
+        .body(reqwest::Body::wrap_stream(body))
+        .send()
+        .await
+        .map_err(Error::other)?
+        .error_for_status()
+        .map_err(Error::other)?;
+    let tx = bytes_per_sec((chunks * NET_CHUNK_SIZE) as u64, start.elapsed());
+
+    let mut headers = HeaderMap::new();
+    build_auth_headers(&url, &Method::GET, &mut headers);
+    let start = Instant::now();
+    let resp = HTTP_CLIENT
+        .get(&url)
+        .headers(headers)
+        .send()
+        .await
+        .map_err(Error::other)?
+        .error_for_status()
+        .map_err(Error::other)?;
+    let mut received = 0u64;
+    let mut body = resp.bytes_stream();
+    while let Some(chunk) = body.next().await {
+        received += chunk.map_err(Error::other)?.len() as u64;
+    }
+    let rx = bytes_per_sec(received, start.elapsed());
+
+    Ok(NetPerfResult {
+        peer: peer.to_string(),
+        tx_bytes_per_sec: tx,
+        rx_bytes_per_sec: rx,
+        error: None,
+    })
+}
+
+/// Measure throughput between this node and every peer, one peer at a time.
+pub async fn net_perf(store: &ECStore, size: u64) -> Result<Vec<NetPerfResult>> {
+    if size == 0 || size > MAX_SPEEDTEST_OBJECT_SIZE {
+        return Err(Error::other(format!("size must be between 1 and {MAX_SPEEDTEST_OBJECT_SIZE} bytes")));
+    }
+    let _guard = RunGuard::acquire()?;
+
+    let peers: BTreeSet<String> = store
+        .disk_map
+        .values()
+        .flatten()
+        .flatten()
+        .filter(|disk| !disk.is_local())
+        .map(|disk| disk.endpoint().grid_host())
+        .collect();
+
+    let mut results = Vec::with_capacity(peers.len());
+    for peer in peers {
+        let result = peer_net_perf(&peer, size).await.unwrap_or_else(|err| NetPerfResult {
+            peer: peer.clone(),
+            error: Some(err.to_string()),
+            ..Default::default()
+        });
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// Body served to a peer measuring download throughput from this node.
+pub fn net_perf_payload(size: u64) -> impl futures::Stream<Item = std::io::Result<Bytes>> + Send + 'static {
+    let chunk = Bytes::from(vec![0u8; NET_CHUNK_SIZE]);
+    let chunks = size.div_ceil(NET_CHUNK_SIZE as u64);
+    stream::iter((0..chunks).map(move |i| {
+        let remaining = size - i * NET_CHUNK_SIZE as u64;
+        Ok(chunk.slice(..remaining.min(NET_CHUNK_SIZE as u64) as usize))
+    }))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ObjectPerfOptions {
+    pub size: u64,
+    pub concurrency: usize,
+    /// Duration of each of the PUT and GET phases, in seconds.
+    pub duration: u64,
+}
+
+impl Default for ObjectPerfOptions {
+    fn default() -> Self {
+        Self {
+            size: 64 << 20,
+            concurrency: 32,
+            duration: 10,
+        }
+    }
+}
+
+impl ObjectPerfOptions {
+    pub fn validate(&self) -> Result<()> {
+        if self.size == 0 || self.size > MAX_SPEEDTEST_OBJECT_SIZE {
+            return Err(Error::other(format!("size must be between 1 and {MAX_SPEEDTEST_OBJECT_SIZE} bytes")));
+        }
+        if self.concurrency == 0 || self.concurrency > MAX_SPEEDTEST_CONCURRENCY {
+            return Err(Error::other(format!("concurrency must be between 1 and {MAX_SPEEDTEST_CONCURRENCY}")));
+        }
+        if self.duration == 0 || self.duration > MAX_SPEEDTEST_DURATION_SECS {
+            return Err(Error::other(format!(
+                "duration must be between 1 and {MAX_SPEEDTEST_DURATION_SECS} seconds"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectPerfStats {
+    pub objects: u64,
+    pub errors: u64,
+    pub bytes_per_sec: u64,
+    pub objects_per_sec: f64,
+    pub latency_avg_ms: f64,
+    pub latency_p50_ms: f64,
+    pub latency_p99_ms: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectPerfResult {
+    pub size: u64,
+    pub concurrency: usize,
+    pub put: ObjectPerfStats,
+    pub get: ObjectPerfStats,
+}
+
+fn summarize(mut latencies: Vec<Duration>, errors: u64, size: u64, elapsed: Duration) -> ObjectPerfStats {
+    latencies.sort_unstable();
+    let objects = latencies.len() as u64;
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    let percentile = |p: f64| {
+        if latencies.is_empty() {
+            return 0.0;
+        }
+        let idx = ((latencies.len() as f64 * p).ceil() as usize).clamp(1, latencies.len()) - 1;
+        ms(latencies[idx])
+    };
+    let secs = elapsed.as_secs_f64();
+
+    ObjectPerfStats {
+        objects,
+        errors,
+        bytes_per_sec: bytes_per_sec(objects * size, elapsed),
+        objects_per_sec: if secs > 0.0 { objects as f64 / secs } else { 0.0 },
+        latency_avg_ms: if objects > 0 {
+            latencies.iter().map(|d| ms(*d)).sum::<f64>() / objects as f64
+        } else {
+            0.0
+        },
+        latency_p50_ms: percentile(0.5),
+        latency_p99_ms: percentile(0.99),
+    }
+}
+
+#[derive(Default)]
+struct WorkerStats {
+    latencies: Vec<Duration>,
+    errors: u64,
+    objects: Vec<String>,
+}
+
+async fn put_worker(store: Arc<ECStore>, prefix: String, data: Bytes, deadline: Instant) -> WorkerStats {
+    let mut stats = WorkerStats::default();
+    let size = data.len() as i64;
+    while Instant::now() < deadline {
+        let object = format!("{prefix}{}", stats.objects.len() + stats.errors as usize);
+        let start = Instant::now();
+        let reader = match HashReader::new(Box::new(WarpReader::new(Cursor::new(data.clone()))), size, size, None, None, false) {
+            Ok(reader) => reader,
+            Err(_) => {
+                stats.errors += 1;
+                continue;
+            }
+        };
+        match store
+            .put_object(RUSTFS_META_BUCKET, &object, &mut PutObjReader::new(reader), &ObjectOptions::default())
+            .await
+        {
+            Ok(_) => {
+                stats.latencies.push(start.elapsed());
+                stats.objects.push(object);
+            }
+            Err(_) => stats.errors += 1,
+        }
+    }
+    stats
+}
+
+async fn get_worker(store: Arc<ECStore>, objects: Vec<String>, deadline: Instant) -> WorkerStats {
+    let mut stats = WorkerStats::default();
+    if objects.is_empty() {
+        return stats;
+    }
+    let mut sink = tokio::io::sink();
+    for object in objects.iter().cycle() {
+        if Instant::now() >= deadline {
+            break;
+        }
+        let start = Instant::now();
+        let result = match store
+            .get_object_reader(RUSTFS_META_BUCKET, object, None, HeaderMap::new(), &ObjectOptions::default())
+            .await
+        {
+            Ok(mut reader) => tokio::io::copy(&mut reader.stream, &mut sink).await.map_err(Error::from),
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(_) => stats.latencies.push(start.elapsed()),
+            Err(_) => stats.errors += 1,
+        }
+    }
+    stats
+}
+
+fn merge(stats: Vec<std::result::Result<WorkerStats, tokio::task::JoinError>>) -> WorkerStats {
+    let mut merged = WorkerStats::default();
+    for s in stats.into_iter().flatten() {
+        merged.latencies.extend(s.latencies);
+        merged.errors += s.errors;
+        merged.objects.extend(s.objects);
+    }
+    merged
+}
+
+/// PUT and then GET objects of `opts.size` with `opts.concurrency` workers, each phase for
+/// `opts.duration` seconds.
+pub async fn object_perf(store: Arc<ECStore>, opts: ObjectPerfOptions) -> Result<ObjectPerfResult> {
+    opts.validate()?;
+    let _guard = RunGuard::acquire()?;
+
+    let run_prefix = format!("{SPEEDTEST_PREFIX}/{}/", Uuid::new_v4());
+    let data = random_bytes(opts.size as usize);
+    info!("running object speed test {} with {:?}", run_prefix, opts);
+
+    let duration = Duration::from_secs(opts.duration);
+    let start = Instant::now();
+    let deadline = start + duration;
+    let workers = (0..opts.concurrency)
+        .map(|worker| tokio::spawn(put_worker(store.clone(), format!("{run_prefix}{worker}/"), data.clone(), deadline)));
+    let puts = merge(join_all(workers).await);
+    let put_elapsed = start.elapsed();
+
+    let mut per_worker: Vec<Vec<String>> = vec![Vec::new(); opts.concurrency];
+    for (i, object) in puts.objects.iter().enumerate() {
+        per_worker[i % opts.concurrency].push(object.clone());
+    }
+    let start = Instant::now();
+    let deadline = start + duration;
+    let workers = per_worker
+        .into_iter()
+        .map(|objects| tokio::spawn(get_worker(store.clone(), objects, deadline)));
+    let gets = merge(join_all(workers).await);
+    let get_elapsed = start.elapsed();
+
+    let delete_opts = ObjectOptions {
+        delete_prefix: true,
+        ..Default::default()
+    };
+    if let Err(err) = store.delete_object(RUSTFS_META_BUCKET, &run_prefix, delete_opts).await {
+        warn!("failed to remove object speed test data {}: {:?}", run_prefix, err);
+    }
+
+    Ok(ObjectPerfResult {
+        size: opts.size,
+        concurrency: opts.concurrency,
+        put: summarize(puts.latencies, puts.errors, opts.size, put_elapsed),
+        get: summarize(gets.latencies, gets.errors, opts.size, get_elapsed),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_validate() {
+        assert!(DrivePerfOptions::default().validate().is_ok());
+        let small = DrivePerfOptions {
+            file_size: 1 << 10,
+            ..Default::default()
+        };
+        assert!(small.validate().is_err());
+
+        assert!(ObjectPerfOptions::default().validate().is_ok());
+        let idle = ObjectPerfOptions {
+            concurrency: 0,
+            ..Default::default()
+        };
+        assert!(idle.validate().is_err());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let latencies = (1..=100).map(Duration::from_millis).collect();
+        let stats = summarize(latencies, 2, 1 << 20, Duration::from_secs(10));
+        assert_eq!(stats.objects, 100);
+        assert_eq!(stats.errors, 2);
+        assert_eq!(stats.bytes_per_sec, 10 << 20);
+        assert_eq!(stats.objects_per_sec, 10.0);
+        assert_eq!(stats.latency_p50_ms, 50.0);
+        assert_eq!(stats.latency_p99_ms, 99.0);
+        assert_eq!(stats.latency_avg_ms, 50.5);
+
+        assert_eq!(summarize(Vec::new(), 0, 1, Duration::from_secs(1)), ObjectPerfStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_net_perf_payload() {
+        let size = NET_CHUNK_SIZE as u64 * 2 + 10;
+        let total: usize = net_perf_payload(size)
+            .map(|c| c.unwrap().len())
+            .collect::<Vec<_>>()
+            .await
+            .iter()
+            .sum();
+        assert_eq!(total as u64, size);
+    }
+}
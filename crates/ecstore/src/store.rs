@@ -14,25 +14,37 @@
 
 #![allow(clippy::map_entry)]
 
+use crate::bucket::dedup;
 use crate::bucket::lifecycle::bucket_lifecycle_ops::init_background_expiry;
+use crate::bucket::listing::ListingConsistency;
 use crate::bucket::metadata_sys::{self, set_bucket_metadata};
+use crate::bucket::mode::{BucketMutation, bucket_mode};
+use crate::bucket::placement::resolve_pool_class;
 use crate::bucket::utils::{check_valid_bucket_name, check_valid_bucket_name_strict, is_meta_bucketname};
+use crate::compat::{DirectoryMarkers, GLOBAL_COMPAT_SYS};
 use crate::config::GLOBAL_STORAGE_CLASS;
 use crate::config::storageclass;
 use crate::disk::endpoint::{Endpoint, EndpointType};
+use crate::disk::fault::GLOBAL_DISK_FAULTS;
 use crate::disk::{DiskAPI, DiskInfo, DiskInfoOptions};
 use crate::error::{Error, Result};
 use crate::error::{
     StorageError, is_err_bucket_exists, is_err_invalid_upload_id, is_err_object_not_found, is_err_read_quorum,
     is_err_version_not_found, to_object_err,
 };
+use crate::format_migration::{BASE_FORMAT_VERSION, GLOBAL_FORMAT_MIGRATIONS, check_format_versions, start_format_migrations};
+use crate::fs_objects::FsObjects;
 use crate::global::{
     DISK_ASSUME_UNKNOWN_SIZE, DISK_FILL_FRACTION, DISK_MIN_INODES, DISK_RESERVE_FRACTION, GLOBAL_BOOT_TIME,
     GLOBAL_LOCAL_DISK_MAP, GLOBAL_LOCAL_DISK_SET_DRIVES, GLOBAL_TierConfigMgr, get_global_deployment_id, get_global_endpoints,
     is_dist_erasure, is_erasure_sd, set_global_deployment_id, set_object_layer,
 };
+use crate::hot_objects::GLOBAL_HOT_OBJECTS;
+use crate::list_cache::GLOBAL_LIST_CACHE;
+use crate::metadata_index::{index_bucket_deleted, index_object_deleted, index_object_written};
 use crate::notification_sys::get_global_notification_sys;
-use crate::pools::PoolMeta;
+use crate::object_layer::{ObjectLayer, StorageBackend};
+use crate::pools::{PoolClass, PoolMeta};
 use crate::rebalance::RebalanceMeta;
 use crate::store_api::{
     ListMultipartsInfo, ListObjectVersionsInfo, ListPartsInfo, MultipartInfo, ObjectIO, ObjectInfoOrErr, WalkOptions,
@@ -57,8 +69,11 @@ use lazy_static::lazy_static;
 use rand::Rng as _;
 use rustfs_common::globals::{GLOBAL_Local_Node_Name, GLOBAL_Rustfs_Host, GLOBAL_Rustfs_Port};
 use rustfs_common::heal_channel::{HealItemType, HealOpts};
+use rustfs_config::{DEFAULT_FORMAT_MIGRATION_ENABLE, ENV_FORMAT_MIGRATION_ENABLE};
 use rustfs_filemeta::FileInfo;
 use rustfs_madmin::heal_commands::HealResultItem;
+use rustfs_utils::get_env_bool;
+use rustfs_utils::http::headers::AMZ_STORAGE_CLASS;
 use rustfs_utils::path::{SLASH_SEPARATOR, decode_dir_object, encode_dir_object, path_join_buf};
 use s3s::dto::{BucketVersioningStatus, ObjectLockConfiguration, ObjectLockEnabled, VersioningConfiguration};
 use std::cmp::Ordering;
@@ -88,6 +103,8 @@ pub struct ECStore {
     pub pool_meta: RwLock<PoolMeta>,
     pub rebalance_meta: RwLock<Option<RebalanceMeta>>,
     pub decommission_cancelers: Vec<Option<usize>>,
+    /// Backend for the objects of user buckets, None when they are erasure coded.
+    pub plain_layer: Option<Arc<dyn ObjectLayer>>,
 }
 
 // impl Clone for ECStore {
@@ -108,6 +125,32 @@ pub struct ECStore {
 // }
 
 impl ECStore {
+    /// The backend serving the objects of `bucket` when it is not erasure coded.
+    fn plain_layer(&self, bucket: &str) -> Option<&Arc<dyn ObjectLayer>> {
+        self.plain_layer.as_ref().filter(|_| !is_meta_bucketname(bucket))
+    }
+
+    /// Check a change to `object` against the mode of its bucket. A write to a write-once bucket
+    /// is an overwrite when the object exists.
+    async fn check_bucket_mode(&self, bucket: &str, object: &str, mutation: BucketMutation, opts: &ObjectOptions) -> Result<()> {
+        if opts.data_movement {
+            return Ok(());
+        }
+
+        let mode = bucket_mode(bucket).await;
+        let mutation = if mutation == BucketMutation::Create && mode.tells_overwrites() {
+            match self.get_object_info(bucket, object, &ObjectOptions::default()).await {
+                Ok(info) if !info.delete_marker => BucketMutation::Overwrite,
+                Ok(_) => mutation,
+                Err(err) if is_err_object_not_found(&err) || is_err_version_not_found(&err) => mutation,
+                Err(err) => return Err(err),
+            }
+        } else {
+            mutation
+        };
+        mode.check(bucket, mutation)
+    }
+
     #[allow(clippy::new_ret_no_self)]
     #[instrument(level = "debug", skip(endpoint_pools))]
     pub async fn new(address: SocketAddr, endpoint_pools: EndpointServerPools, ctx: CancellationToken) -> Result<Arc<Self>> {
@@ -115,6 +158,8 @@ impl ECStore {
 
         let mut deployment_id = None;
 
+        GLOBAL_DISK_FAULTS.load_env();
+
         // let (endpoint_pools, _) = EndpointServerPools::create_server_endpoints(address.as_str(), &layouts)?;
 
         let mut pools = Vec::with_capacity(endpoint_pools.as_ref().len());
@@ -194,6 +239,8 @@ impl ECStore {
                 }
             };
 
+            check_format_versions(&disks).await?;
+
             if deployment_id.is_none() {
                 deployment_id = Some(fm.id);
             }
@@ -218,6 +265,22 @@ impl ECStore {
             disk_map.insert(i, disks);
         }
 
+        let plain_layer: Option<Arc<dyn ObjectLayer>> = match StorageBackend::from_env()? {
+            StorageBackend::Erasure => None,
+            StorageBackend::Fs => {
+                let drives: usize = disk_map.values().map(Vec::len).sum();
+                if drives != 1 || local_disks.len() != 1 {
+                    return Err(Error::other(format!(
+                        "the fs storage backend needs exactly one local drive, {drives} configured"
+                    )));
+                }
+                info!("serving objects as plain files from {}", local_disks[0].path().display());
+                Some(Arc::new(FsObjects::new(local_disks[0].path())))
+            }
+        };
+
+        let migrate_disks = local_disks.clone();
+
         // Replace the local disk
         if !is_dist_erasure().await {
             let mut global_local_disk_map = GLOBAL_LOCAL_DISK_MAP.write().await;
@@ -240,6 +303,7 @@ impl ECStore {
             pool_meta: RwLock::new(pool_meta),
             rebalance_meta: RwLock::new(None),
             decommission_cancelers,
+            plain_layer,
         });
 
         // Only set it when the global deployment ID is not yet configured
@@ -271,6 +335,12 @@ impl ECStore {
 
         set_object_layer(ec.clone()).await;
 
+        if get_env_bool(ENV_FORMAT_MIGRATION_ENABLE, DEFAULT_FORMAT_MIGRATION_ENABLE) {
+            start_format_migrations(migrate_disks, ctx);
+        } else if GLOBAL_FORMAT_MIGRATIONS.latest() != BASE_FORMAT_VERSION {
+            info!("format migration disabled, drives stay at their format version");
+        }
+
         Ok(ec)
     }
 
@@ -516,11 +586,20 @@ impl ECStore {
         Ok(())
     }
 
-    async fn get_available_pool_idx(&self, bucket: &str, object: &str, size: i64) -> Option<usize> {
+    async fn get_available_pool_idx(&self, bucket: &str, object: &str, size: i64, class: Option<PoolClass>) -> Option<usize> {
         // // Return a random one first
 
         let mut server_pools = self.get_server_pools_available_space(bucket, object, size).await;
         server_pools.filter_max_used(100 - (100_f64 * DISK_RESERVE_FRACTION) as u64);
+
+        if !is_meta_bucketname(bucket) {
+            let classes: Vec<PoolClass> = {
+                let pool_meta = self.pool_meta.read().await;
+                (0..self.pools.len()).map(|idx| pool_meta.class(idx)).collect()
+            };
+            server_pools.filter_class(&classes, class);
+        }
+
         let total = server_pools.total_available();
 
         if total == 0 {
@@ -572,7 +651,10 @@ impl ECStore {
                 continue;
             }
 
-            if !is_meta_bucketname(bucket) && !has_space_for(zinfo, size).await.unwrap_or_default() {
+            // Pools with a drive below its reserved space watermark do not take new objects
+            if !is_meta_bucketname(bucket)
+                && (zinfo.iter().flatten().any(|di| di.read_only) || !has_space_for(zinfo, size).await.unwrap_or_default())
+            {
                 server_pools[i] = PoolAvailableSpace {
                     index: i,
                     ..Default::default()
@@ -617,7 +699,232 @@ impl ECStore {
         pool_meta.is_suspended(idx)
     }
 
-    async fn get_pool_idx(&self, bucket: &str, object: &str, size: i64) -> Result<usize> {
+    /// Resolve the pool class new data for `bucket` should land on.
+    async fn placement_pool_class(&self, bucket: &str, opts: &ObjectOptions) -> Option<PoolClass> {
+        if is_meta_bucketname(bucket) {
+            return None;
+        }
+
+        let placement = metadata_sys::get_placement_config(bucket).await.ok().map(|(p, _)| p);
+        resolve_pool_class(placement.as_ref(), opts.user_defined.get(AMZ_STORAGE_CLASS).map(String::as_str))
+    }
+
+    /// Remove the empty directory markers above a newly written object when the cluster is set to
+    /// the `delete` marker policy. Versioned buckets keep them, removing would add delete markers.
+    async fn remove_parent_dir_markers(&self, bucket: &str, object: &str, opts: &ObjectOptions) {
+        if GLOBAL_COMPAT_SYS.directory_markers() != DirectoryMarkers::Delete
+            || opts.data_movement
+            || is_meta_bucketname(bucket)
+            || object.ends_with(SLASH_SEPARATOR)
+            || opts.versioned
+            || opts.version_suspended
+        {
+            return;
+        }
+
+        for marker in parent_dir_markers(object) {
+            let Ok(info) = self.get_object_info(bucket, &marker, &ObjectOptions::default()).await else {
+                continue;
+            };
+            if info.delete_marker || info.size > 0 {
+                continue;
+            }
+            if let Err(err) = self.delete_object(bucket, &marker, ObjectOptions::default()).await
+                && !is_err_object_not_found(&err)
+            {
+                warn!("remove directory marker {bucket}/{marker} failed: {err}");
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn copy_object_inner(
+        &self,
+        src_bucket: &str,
+        src_object: &str,
+        dst_bucket: &str,
+        dst_object: &str,
+        src_info: &mut ObjectInfo,
+        src_opts: &ObjectOptions,
+        dst_opts: &ObjectOptions,
+    ) -> Result<ObjectInfo> {
+        check_copy_obj_args(src_bucket, src_object)?;
+        check_copy_obj_args(dst_bucket, dst_object)?;
+        let self_copy = src_bucket == dst_bucket && src_object == dst_object;
+        if !self_copy {
+            // Copying onto itself only updates metadata, existing names stay usable
+            check_object_naming(dst_bucket, dst_object).await?;
+        }
+        let mutation = if self_copy {
+            BucketMutation::Update
+        } else {
+            BucketMutation::Create
+        };
+        self.check_bucket_mode(dst_bucket, dst_object, mutation, dst_opts).await?;
+
+        if let Some(plain) = self.plain_layer(dst_bucket) {
+            if self.plain_layer(src_bucket).is_none() {
+                return Err(StorageError::NotImplemented);
+            }
+            return plain
+                .copy_object(src_bucket, src_object, dst_bucket, dst_object, src_info, dst_opts)
+                .await;
+        }
+
+        let src_object = encode_dir_object(src_object);
+        let dst_object = encode_dir_object(dst_object);
+
+        let cp_src_dst_same = path_join_buf(&[src_bucket, &src_object]) == path_join_buf(&[dst_bucket, &dst_object]);
+
+        // TODO: nslock
+
+        let pool_idx = self
+            .get_pool_idx_no_lock(src_bucket, &src_object, src_info.size, None)
+            .await?;
+
+        if cp_src_dst_same {
+            if let (Some(src_vid), Some(dst_vid)) = (&src_opts.version_id, &dst_opts.version_id) {
+                if src_vid == dst_vid {
+                    return self.pools[pool_idx]
+                        .copy_object(src_bucket, &src_object, dst_bucket, &dst_object, src_info, src_opts, dst_opts)
+                        .await;
+                }
+            }
+
+            if !dst_opts.versioned && src_opts.version_id.is_none() {
+                return self.pools[pool_idx]
+                    .copy_object(src_bucket, &src_object, dst_bucket, &dst_object, src_info, src_opts, dst_opts)
+                    .await;
+            }
+
+            if dst_opts.versioned && src_opts.version_id != dst_opts.version_id {
+                src_info.version_only = true;
+                return self.pools[pool_idx]
+                    .copy_object(src_bucket, &src_object, dst_bucket, &dst_object, src_info, src_opts, dst_opts)
+                    .await;
+            }
+        }
+
+        let mut user_defined = src_info.user_defined.clone();
+        // The data is copied, the copy does not share the blob of a deduplicated source
+        dedup::strip_dedup_metadata(&mut user_defined);
+        let put_opts = ObjectOptions {
+            user_defined,
+            versioned: dst_opts.versioned,
+            version_id: dst_opts.version_id.clone(),
+            no_lock: true,
+            mod_time: dst_opts.mod_time,
+            ..Default::default()
+        };
+
+        if let Some(put_object_reader) = src_info.put_object_reader.as_mut() {
+            return self.pools[pool_idx]
+                .put_object(dst_bucket, &dst_object, put_object_reader, &put_opts)
+                .await;
+        }
+
+        Err(StorageError::InvalidArgument(
+            src_bucket.to_owned(),
+            src_object.to_owned(),
+            "put_object_reader is none".to_owned(),
+        ))
+    }
+
+    async fn delete_object_inner(&self, bucket: &str, object: &str, opts: ObjectOptions) -> Result<ObjectInfo> {
+        check_del_obj_args(bucket, object)?;
+        self.check_bucket_mode(bucket, object, BucketMutation::Delete, &opts).await?;
+
+        if opts.delete_prefix {
+            self.delete_prefix(bucket, object).await?;
+            return Ok(ObjectInfo::default());
+        }
+
+        if let Some(plain) = self.plain_layer(bucket) {
+            return plain.delete_object(bucket, object, &opts).await;
+        }
+
+        // TODO: nslock
+
+        let object = encode_dir_object(object);
+        let object = object.as_str();
+
+        let mut gopts = opts.clone();
+        gopts.no_lock = true;
+
+        // Determine which pool contains it
+        let (mut pinfo, errs) = self
+            .get_pool_info_existing_with_opts(bucket, object, &gopts)
+            .await
+            .map_err(|e| {
+                if is_err_read_quorum(&e) {
+                    StorageError::ErasureWriteQuorum
+                } else {
+                    e
+                }
+            })?;
+
+        if pinfo.object_info.delete_marker && opts.version_id.is_none() {
+            pinfo.object_info.name = decode_dir_object(object);
+            return Ok(pinfo.object_info);
+        }
+
+        if opts.data_movement && opts.src_pool_idx == pinfo.index {
+            return Err(StorageError::DataMovementOverwriteErr(
+                bucket.to_owned(),
+                object.to_owned(),
+                opts.version_id.unwrap_or_default(),
+            ));
+        }
+
+        if opts.data_movement {
+            let mut obj = self.pools[pinfo.index].delete_object(bucket, object, opts).await?;
+            obj.name = decode_dir_object(obj.name.as_str());
+            return Ok(obj);
+        }
+
+        if !errs.is_empty() && !opts.versioned && !opts.version_suspended {
+            return self.delete_object_from_all_pools(bucket, object, &opts, errs).await;
+        }
+
+        for pool in self.pools.iter() {
+            match pool.delete_object(bucket, object, opts.clone()).await {
+                Ok(res) => {
+                    let mut obj = res;
+                    obj.name = decode_dir_object(object);
+                    return Ok(obj);
+                }
+                Err(err) => {
+                    if !is_err_object_not_found(&err) && !is_err_version_not_found(&err) {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+
+        if let Some(ver) = opts.version_id {
+            return Err(StorageError::VersionNotFound(bucket.to_owned(), object.to_owned(), ver));
+        }
+
+        Err(StorageError::ObjectNotFound(bucket.to_owned(), object.to_owned()))
+    }
+
+    /// Select the pool for a new write, honoring pool classes.
+    ///
+    /// Data moved off a pool whose class does not fit is not pinned to the pool it currently lives on.
+    async fn get_pool_idx_for_write(&self, bucket: &str, object: &str, size: i64, opts: &ObjectOptions) -> Result<usize> {
+        let class = self.placement_pool_class(bucket, opts).await;
+
+        if opts.data_movement && !self.pool_class(opts.src_pool_idx).await.accepts(class) {
+            return self
+                .get_available_pool_idx(bucket, object, size, class)
+                .await
+                .ok_or(Error::DiskFull);
+        }
+
+        self.get_pool_idx(bucket, object, size, class).await
+    }
+
+    async fn get_pool_idx(&self, bucket: &str, object: &str, size: i64, class: Option<PoolClass>) -> Result<usize> {
         let idx = match self
             .get_pool_idx_existing_with_opts(
                 bucket,
@@ -636,7 +943,7 @@ impl ECStore {
                     return Err(err);
                 }
 
-                if let Some(hit_idx) = self.get_available_pool_idx(bucket, object, size).await {
+                if let Some(hit_idx) = self.get_available_pool_idx(bucket, object, size, class).await {
                     hit_idx
                 } else {
                     return Err(Error::DiskFull);
@@ -647,7 +954,7 @@ impl ECStore {
         Ok(idx)
     }
 
-    async fn get_pool_idx_no_lock(&self, bucket: &str, object: &str, size: i64) -> Result<usize> {
+    async fn get_pool_idx_no_lock(&self, bucket: &str, object: &str, size: i64, class: Option<PoolClass>) -> Result<usize> {
         let idx = match self.get_pool_idx_existing_no_lock(bucket, object).await {
             Ok(res) => res,
             Err(err) => {
@@ -655,7 +962,7 @@ impl ECStore {
                     return Err(err);
                 }
 
-                if let Some(idx) = self.get_available_pool_idx(bucket, object, size).await {
+                if let Some(idx) = self.get_available_pool_idx(bucket, object, size, class).await {
                     idx
                 } else {
                     warn!("get_pool_idx_no_lock: disk full {}/{}", bucket, object);
@@ -1089,6 +1396,10 @@ impl ObjectIO for ECStore {
     ) -> Result<GetObjectReader> {
         check_get_obj_args(bucket, object)?;
 
+        if let Some(plain) = self.plain_layer(bucket) {
+            return plain.get_object_reader(bucket, object, range, h, opts).await;
+        }
+
         let object = encode_dir_object(object);
 
         if self.single_pool() {
@@ -1111,24 +1422,47 @@ impl ObjectIO for ECStore {
     #[instrument(level = "debug", skip(self, data))]
     async fn put_object(&self, bucket: &str, object: &str, data: &mut PutObjReader, opts: &ObjectOptions) -> Result<ObjectInfo> {
         check_put_object_args(bucket, object)?;
+        check_object_naming(bucket, object).await?;
+        self.check_bucket_mode(bucket, object, BucketMutation::Create, opts).await?;
 
-        let object = encode_dir_object(object);
-
-        if self.single_pool() {
-            return self.pools[0].put_object(bucket, object.as_str(), data, opts).await;
+        if let Some(plain) = self.plain_layer(bucket) {
+            return plain.put_object(bucket, object, data, opts).await;
         }
 
-        let idx = self.get_pool_idx(bucket, &object, data.size()).await?;
+        // A deduplicated object version is written without data, its data lives in the dedup store
+        let dedup_opts;
+        let mut no_data;
+        let (data, opts) = match dedup::deduplicate(bucket, object, data, opts).await? {
+            Some(ref_opts) => {
+                dedup_opts = ref_opts;
+                no_data = PutObjReader::from_vec(Vec::new());
+                (&mut no_data, &dedup_opts)
+            }
+            None => (data, opts),
+        };
 
-        if opts.data_movement && idx == opts.src_pool_idx {
-            return Err(StorageError::DataMovementOverwriteErr(
-                bucket.to_owned(),
-                object.to_owned(),
-                opts.version_id.clone().unwrap_or_default(),
-            ));
-        }
+        let encoded = encode_dir_object(object);
+
+        let info = if self.single_pool() {
+            self.pools[0].put_object(bucket, encoded.as_str(), data, opts).await?
+        } else {
+            let idx = self.get_pool_idx_for_write(bucket, &encoded, data.size(), opts).await?;
 
-        self.pools[idx].put_object(bucket, &object, data, opts).await
+            if opts.data_movement && idx == opts.src_pool_idx {
+                return Err(StorageError::DataMovementOverwriteErr(
+                    bucket.to_owned(),
+                    encoded.to_owned(),
+                    opts.version_id.clone().unwrap_or_default(),
+                ));
+            }
+
+            self.pools[idx].put_object(bucket, &encoded, data, opts).await?
+        };
+
+        self.remove_parent_dir_markers(bucket, object, opts).await;
+        caches_object_changed(bucket, object).await;
+        index_object_written(bucket, &info);
+        Ok(info)
     }
 }
 
@@ -1316,6 +1650,12 @@ impl StorageAPI for ECStore {
             .await
             .map_err(|e| to_object_err(e.into(), vec![bucket]))?;
 
+        if let Some(plain) = self.plain_layer(bucket) {
+            plain.delete_bucket(bucket).await?;
+        }
+        caches_object_changed(bucket, "").await;
+        index_bucket_deleted(bucket);
+
         // TODO: replication opts.srdelete_op
 
         // Delete the metadata
@@ -1339,7 +1679,15 @@ impl StorageAPI for ECStore {
         fetch_owner: bool,
         start_after: Option<String>,
         incl_deleted: bool,
+        consistency: Option<ListingConsistency>,
     ) -> Result<ListObjectsV2Info> {
+        if let Some(plain) = self.plain_layer(bucket) {
+            self.get_bucket_info(bucket, &BucketOptions::default()).await?;
+            return plain
+                .list_objects_v2(bucket, prefix, continuation_token, delimiter, max_keys, start_after)
+                .await;
+        }
+
         self.inner_list_objects_v2(
             bucket,
             prefix,
@@ -1349,6 +1697,7 @@ impl StorageAPI for ECStore {
             fetch_owner,
             start_after,
             incl_deleted,
+            consistency,
         )
         .await
     }
@@ -1382,6 +1731,10 @@ impl StorageAPI for ECStore {
     async fn get_object_info(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<ObjectInfo> {
         check_object_args(bucket, object)?;
 
+        if let Some(plain) = self.plain_layer(bucket) {
+            return plain.get_object_info(bucket, object, opts).await;
+        }
+
         let object = encode_dir_object(object);
 
         if self.single_pool() {
@@ -1407,134 +1760,19 @@ impl StorageAPI for ECStore {
         src_opts: &ObjectOptions,
         dst_opts: &ObjectOptions,
     ) -> Result<ObjectInfo> {
-        check_copy_obj_args(src_bucket, src_object)?;
-        check_copy_obj_args(dst_bucket, dst_object)?;
-
-        let src_object = encode_dir_object(src_object);
-        let dst_object = encode_dir_object(dst_object);
-
-        let cp_src_dst_same = path_join_buf(&[src_bucket, &src_object]) == path_join_buf(&[dst_bucket, &dst_object]);
-
-        // TODO: nslock
-
-        let pool_idx = self.get_pool_idx_no_lock(src_bucket, &src_object, src_info.size).await?;
-
-        if cp_src_dst_same {
-            if let (Some(src_vid), Some(dst_vid)) = (&src_opts.version_id, &dst_opts.version_id) {
-                if src_vid == dst_vid {
-                    return self.pools[pool_idx]
-                        .copy_object(src_bucket, &src_object, dst_bucket, &dst_object, src_info, src_opts, dst_opts)
-                        .await;
-                }
-            }
-
-            if !dst_opts.versioned && src_opts.version_id.is_none() {
-                return self.pools[pool_idx]
-                    .copy_object(src_bucket, &src_object, dst_bucket, &dst_object, src_info, src_opts, dst_opts)
-                    .await;
-            }
-
-            if dst_opts.versioned && src_opts.version_id != dst_opts.version_id {
-                src_info.version_only = true;
-                return self.pools[pool_idx]
-                    .copy_object(src_bucket, &src_object, dst_bucket, &dst_object, src_info, src_opts, dst_opts)
-                    .await;
-            }
-        }
-
-        let put_opts = ObjectOptions {
-            user_defined: src_info.user_defined.clone(),
-            versioned: dst_opts.versioned,
-            version_id: dst_opts.version_id.clone(),
-            no_lock: true,
-            mod_time: dst_opts.mod_time,
-            ..Default::default()
-        };
-
-        if let Some(put_object_reader) = src_info.put_object_reader.as_mut() {
-            return self.pools[pool_idx]
-                .put_object(dst_bucket, &dst_object, put_object_reader, &put_opts)
-                .await;
-        }
-
-        Err(StorageError::InvalidArgument(
-            src_bucket.to_owned(),
-            src_object.to_owned(),
-            "put_object_reader is none".to_owned(),
-        ))
+        let info = self
+            .copy_object_inner(src_bucket, src_object, dst_bucket, dst_object, src_info, src_opts, dst_opts)
+            .await?;
+        caches_object_changed(dst_bucket, dst_object).await;
+        index_object_written(dst_bucket, &info);
+        Ok(info)
     }
     #[instrument(skip(self))]
     async fn delete_object(&self, bucket: &str, object: &str, opts: ObjectOptions) -> Result<ObjectInfo> {
-        check_del_obj_args(bucket, object)?;
-
-        if opts.delete_prefix {
-            self.delete_prefix(bucket, object).await?;
-            return Ok(ObjectInfo::default());
-        }
-
-        // TODO: nslock
-
-        let object = encode_dir_object(object);
-        let object = object.as_str();
-
-        let mut gopts = opts.clone();
-        gopts.no_lock = true;
-
-        // Determine which pool contains it
-        let (mut pinfo, errs) = self
-            .get_pool_info_existing_with_opts(bucket, object, &gopts)
-            .await
-            .map_err(|e| {
-                if is_err_read_quorum(&e) {
-                    StorageError::ErasureWriteQuorum
-                } else {
-                    e
-                }
-            })?;
-
-        if pinfo.object_info.delete_marker && opts.version_id.is_none() {
-            pinfo.object_info.name = decode_dir_object(object);
-            return Ok(pinfo.object_info);
-        }
-
-        if opts.data_movement && opts.src_pool_idx == pinfo.index {
-            return Err(StorageError::DataMovementOverwriteErr(
-                bucket.to_owned(),
-                object.to_owned(),
-                opts.version_id.unwrap_or_default(),
-            ));
-        }
-
-        if opts.data_movement {
-            let mut obj = self.pools[pinfo.index].delete_object(bucket, object, opts).await?;
-            obj.name = decode_dir_object(obj.name.as_str());
-            return Ok(obj);
-        }
-
-        if !errs.is_empty() && !opts.versioned && !opts.version_suspended {
-            return self.delete_object_from_all_pools(bucket, object, &opts, errs).await;
-        }
-
-        for pool in self.pools.iter() {
-            match pool.delete_object(bucket, object, opts.clone()).await {
-                Ok(res) => {
-                    let mut obj = res;
-                    obj.name = decode_dir_object(object);
-                    return Ok(obj);
-                }
-                Err(err) => {
-                    if !is_err_object_not_found(&err) && !is_err_version_not_found(&err) {
-                        return Err(err);
-                    }
-                }
-            }
-        }
-
-        if let Some(ver) = opts.version_id {
-            return Err(StorageError::VersionNotFound(bucket.to_owned(), object.to_owned(), ver));
-        }
-
-        Err(StorageError::ObjectNotFound(bucket.to_owned(), object.to_owned()))
+        let info = self.delete_object_inner(bucket, object, opts).await?;
+        caches_object_changed(bucket, object).await;
+        index_object_deleted(bucket, object);
+        Ok(info)
     }
     // TODO: review
     #[instrument(skip(self))]
@@ -1544,6 +1782,39 @@ impl StorageAPI for ECStore {
         objects: Vec<ObjectToDelete>,
         opts: ObjectOptions,
     ) -> (Vec<DeletedObject>, Vec<Option<Error>>) {
+        // Nothing of a bucket whose mode forbids deletes is deleted
+        if let Err(err) = self.check_bucket_mode(bucket, "", BucketMutation::Delete, &opts).await {
+            let del_objects = objects
+                .iter()
+                .map(|obj| DeletedObject {
+                    object_name: obj.object_name.clone(),
+                    ..Default::default()
+                })
+                .collect();
+            return (del_objects, vec![Some(err); objects.len()]);
+        }
+
+        if let Some(plain) = self.plain_layer(bucket) {
+            let mut del_objects = Vec::with_capacity(objects.len());
+            let mut del_errs = Vec::with_capacity(objects.len());
+            for obj in objects {
+                let mut deleted = DeletedObject {
+                    object_name: obj.object_name.clone(),
+                    ..Default::default()
+                };
+                match plain.delete_object(bucket, &obj.object_name, &opts).await {
+                    Ok(_) => {
+                        deleted.found = true;
+                        del_errs.push(None);
+                    }
+                    Err(err) if is_err_object_not_found(&err) => del_errs.push(None),
+                    Err(err) => del_errs.push(Some(err)),
+                }
+                del_objects.push(deleted);
+            }
+            return (del_objects, del_errs);
+        }
+
         // encode object name
         let objects: Vec<ObjectToDelete> = objects
             .iter()
@@ -1590,6 +1861,12 @@ impl StorageAPI for ECStore {
         del_objects.iter_mut().for_each(|v| {
             v.object_name = decode_dir_object(&v.object_name);
         });
+        for (deleted, err) in del_objects.iter().zip(del_errs.iter()) {
+            if err.is_none() && deleted.found {
+                caches_object_changed(bucket, &deleted.object_name).await;
+                index_object_deleted(bucket, &deleted.object_name);
+            }
+        }
 
         (del_objects, del_errs)
 
@@ -1789,33 +2066,32 @@ impl StorageAPI for ECStore {
             if self.is_suspended(pool.pool_idx).await {
                 continue;
             }
+            // Every pool returns all of its uploads so the page is cut once over the merged list.
             let res = pool
-                .list_multipart_uploads(
-                    bucket,
-                    prefix,
-                    key_marker.clone(),
-                    upload_id_marker.clone(),
-                    delimiter.clone(),
-                    max_uploads,
-                )
+                .list_multipart_uploads(bucket, prefix, None, None, None, usize::MAX)
                 .await?;
             uploads.extend(res.uploads);
         }
 
-        Ok(ListMultipartsInfo {
+        Ok(ListMultipartsInfo::paginate(
+            prefix,
+            uploads,
             key_marker,
             upload_id_marker,
+            delimiter,
             max_uploads,
-            uploads,
-            prefix: prefix.to_owned(),
-            delimiter: delimiter.to_owned(),
-            ..Default::default()
-        })
+        ))
     }
 
     #[instrument(skip(self))]
     async fn new_multipart_upload(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<MultipartUploadResult> {
         check_new_multipart_args(bucket, object)?;
+        check_object_naming(bucket, object).await?;
+        self.check_bucket_mode(bucket, object, BucketMutation::Create, opts).await?;
+
+        if self.plain_layer(bucket).is_some() {
+            return Err(StorageError::NotImplemented);
+        }
 
         if self.single_pool() {
             return self.pools[0].new_multipart_upload(bucket, object, opts).await;
@@ -1829,11 +2105,11 @@ impl StorageAPI for ECStore {
                 .list_multipart_uploads(bucket, object, None, None, None, MAX_UPLOADS_LIST)
                 .await?;
 
-            if !res.uploads.is_empty() {
+            if res.uploads.iter().any(|u| u.object == object) {
                 return self.pools[idx].new_multipart_upload(bucket, object, opts).await;
             }
         }
-        let idx = self.get_pool_idx(bucket, object, -1).await?;
+        let idx = self.get_pool_idx_for_write(bucket, object, -1, opts).await?;
         if opts.data_movement && idx == opts.src_pool_idx {
             return Err(StorageError::DataMovementOverwriteErr(
                 bucket.to_owned(),
@@ -1924,6 +2200,7 @@ impl StorageAPI for ECStore {
         opts: &ObjectOptions,
     ) -> Result<PartInfo> {
         check_put_object_part_args(bucket, object, upload_id)?;
+        self.check_bucket_mode(bucket, object, BucketMutation::Update, opts).await?;
 
         if self.single_pool() {
             return self.pools[0]
@@ -2028,12 +2305,18 @@ impl StorageAPI for ECStore {
         opts: &ObjectOptions,
     ) -> Result<ObjectInfo> {
         check_complete_multipart_args(bucket, object, upload_id)?;
+        // The object may have been written since the upload started
+        self.check_bucket_mode(bucket, object, BucketMutation::Create, opts).await?;
 
         if self.single_pool() {
-            return self.pools[0]
+            let info = self.pools[0]
                 .clone()
                 .complete_multipart_upload(bucket, object, upload_id, uploaded_parts, opts)
-                .await;
+                .await?;
+            self.remove_parent_dir_markers(bucket, object, opts).await;
+            caches_object_changed(bucket, object).await;
+            index_object_written(bucket, &info);
+            return Ok(info);
         }
 
         for pool in self.pools.iter() {
@@ -2046,7 +2329,12 @@ impl StorageAPI for ECStore {
                 .complete_multipart_upload(bucket, object, upload_id, uploaded_parts.clone(), opts)
                 .await
             {
-                Ok(res) => return Ok(res),
+                Ok(res) => {
+                    self.remove_parent_dir_markers(bucket, object, opts).await;
+                    caches_object_changed(bucket, object).await;
+                    index_object_written(bucket, &res);
+                    return Ok(res);
+                }
                 Err(err) => {
                     //
                     if is_err_invalid_upload_id(&err) { None } else { Some(err) }
@@ -2081,17 +2369,23 @@ impl StorageAPI for ECStore {
     }
     #[instrument(skip(self))]
     async fn put_object_metadata(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<ObjectInfo> {
+        self.check_bucket_mode(bucket, object, BucketMutation::Update, opts).await?;
         let object = encode_dir_object(object);
-        if self.single_pool() {
-            return self.pools[0].put_object_metadata(bucket, object.as_str(), opts).await;
-        }
+        let info = if self.single_pool() {
+            self.pools[0].put_object_metadata(bucket, object.as_str(), opts).await?
+        } else {
+            let mut opts = opts.clone();
+            opts.metadata_chg = true;
 
-        let mut opts = opts.clone();
-        opts.metadata_chg = true;
+            let idx = self.get_pool_idx_existing_with_opts(bucket, object.as_str(), &opts).await?;
 
-        let idx = self.get_pool_idx_existing_with_opts(bucket, object.as_str(), &opts).await?;
+            self.pools[idx].put_object_metadata(bucket, object.as_str(), &opts).await?
+        };
 
-        self.pools[idx].put_object_metadata(bucket, object.as_str(), &opts).await
+        if opts.version_id.is_none() {
+            index_object_written(bucket, &info);
+        }
+        Ok(info)
     }
     #[instrument(skip(self))]
     async fn get_object_tags(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<String> {
@@ -2108,15 +2402,22 @@ impl StorageAPI for ECStore {
 
     #[instrument(level = "debug", skip(self))]
     async fn put_object_tags(&self, bucket: &str, object: &str, tags: &str, opts: &ObjectOptions) -> Result<ObjectInfo> {
+        self.check_bucket_mode(bucket, object, BucketMutation::Update, opts).await?;
         let object = encode_dir_object(object);
 
-        if self.single_pool() {
-            return self.pools[0].put_object_tags(bucket, object.as_str(), tags, opts).await;
-        }
+        let info = if self.single_pool() {
+            self.pools[0].put_object_tags(bucket, object.as_str(), tags, opts).await?
+        } else {
+            let idx = self.get_pool_idx_existing_with_opts(bucket, object.as_str(), opts).await?;
 
-        let idx = self.get_pool_idx_existing_with_opts(bucket, object.as_str(), opts).await?;
+            self.pools[idx].put_object_tags(bucket, object.as_str(), tags, opts).await?
+        };
 
-        self.pools[idx].put_object_tags(bucket, object.as_str(), tags, opts).await
+        // Tags of older versions are not searchable
+        if opts.version_id.is_none() {
+            index_object_written(bucket, &info);
+        }
+        Ok(info)
     }
 
     #[instrument(skip(self))]
@@ -2135,15 +2436,21 @@ impl StorageAPI for ECStore {
 
     #[instrument(skip(self))]
     async fn delete_object_tags(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<ObjectInfo> {
+        self.check_bucket_mode(bucket, object, BucketMutation::Update, opts).await?;
         let object = encode_dir_object(object);
 
-        if self.single_pool() {
-            return self.pools[0].delete_object_tags(bucket, object.as_str(), opts).await;
-        }
+        let info = if self.single_pool() {
+            self.pools[0].delete_object_tags(bucket, object.as_str(), opts).await?
+        } else {
+            let idx = self.get_pool_idx_existing_with_opts(bucket, object.as_str(), opts).await?;
 
-        let idx = self.get_pool_idx_existing_with_opts(bucket, object.as_str(), opts).await?;
+            self.pools[idx].delete_object_tags(bucket, object.as_str(), opts).await?
+        };
 
-        self.pools[idx].delete_object_tags(bucket, object.as_str(), opts).await
+        if opts.version_id.is_none() {
+            index_object_written(bucket, &info);
+        }
+        Ok(info)
     }
 
     #[instrument(skip(self))]
@@ -2333,6 +2640,14 @@ async fn init_local_peer(endpoint_pools: &EndpointServerPools, host: &String, po
     *GLOBAL_Local_Node_Name.write().await = peer_set[0].clone();
 }
 
+/// Invalidate cached listings that may include `object` and in-memory copies of it after a write to it.
+async fn caches_object_changed(bucket: &str, object: &str) {
+    if !is_meta_bucketname(bucket) {
+        GLOBAL_LIST_CACHE.object_changed(bucket, object);
+        GLOBAL_HOT_OBJECTS.object_changed(bucket, object).await;
+    }
+}
+
 pub fn is_valid_object_prefix(_object: &str) -> bool {
     // Implement object prefix validation
     // !object.is_empty() // Placeholder
@@ -2484,6 +2799,28 @@ fn check_abort_multipart_args(bucket: &str, object: &str, upload_id: &str) -> Re
     check_multipart_object_args(bucket, object, upload_id)
 }
 
+/// Check the name of a new object against the naming policy of its bucket.
+async fn check_object_naming(bucket: &str, object: &str) -> Result<()> {
+    if is_meta_bucketname(bucket) {
+        return Ok(());
+    }
+
+    let naming = metadata_sys::get_naming_config(bucket)
+        .await
+        .map(|(cfg, _)| cfg)
+        .unwrap_or_default();
+    naming.check_object_name(bucket, object)
+}
+
+/// The directory markers a write of `object` may leave redundant, nearest first.
+fn parent_dir_markers(object: &str) -> Vec<String> {
+    object
+        .match_indices(SLASH_SEPARATOR)
+        .map(|(idx, sep)| object[..idx + sep.len()].to_owned())
+        .rev()
+        .collect()
+}
+
 #[instrument(level = "debug")]
 fn check_put_object_args(bucket: &str, object: &str) -> Result<()> {
     if !is_meta_bucketname(bucket) && check_valid_bucket_name_strict(bucket).is_err() {
@@ -2536,6 +2873,24 @@ impl ServerPoolsAvailableSpace {
         total
     }
 
+    /// Filter out pools whose class does not accept `wanted`, unless none of them has space left.
+    pub fn filter_class(&mut self, classes: &[PoolClass], wanted: Option<PoolClass>) {
+        let accepts = |pool: &PoolAvailableSpace| classes.get(pool.index).copied().unwrap_or_default().accepts(wanted);
+
+        if !self.0.iter().any(|pool| pool.available > 0 && accepts(pool)) {
+            if wanted.is_some() {
+                warn!("no {:?} pool has space available, placing on any pool", wanted);
+            }
+            return;
+        }
+
+        for pool in self.0.iter_mut() {
+            if !accepts(pool) {
+                pool.available = 0;
+            }
+        }
+    }
+
     // FilterMaxUsed will filter out any pools that has used percent bigger than max,
     // unless all have that, in which case all are preserved.
     pub fn filter_max_used(&mut self, max: u64) {
@@ -2614,6 +2969,12 @@ pub async fn has_space_for(dis: &[Option<DiskInfo>], size: i64) -> Result<bool>
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parent_dir_markers() {
+        assert_eq!(parent_dir_markers("a/b/c.txt"), vec!["a/b/".to_string(), "a/".to_string()]);
+        assert!(parent_dir_markers("c.txt").is_empty());
+    }
+
     // Test validation functions
     #[test]
     fn test_is_valid_object_name() {
@@ -2712,6 +3073,39 @@ mod tests {
         assert_eq!(spaces.total_available(), 1000); // Only first pool contributes to total
     }
 
+    #[test]
+    fn test_server_pools_filter_class() {
+        let pools = || {
+            ServerPoolsAvailableSpace(vec![
+                PoolAvailableSpace {
+                    index: 0,
+                    available: 1000,
+                    max_used_pct: 50,
+                },
+                PoolAvailableSpace {
+                    index: 1,
+                    available: 2000,
+                    max_used_pct: 50,
+                },
+            ])
+        };
+        let classes = [PoolClass::Hot, PoolClass::Cold];
+
+        let mut spaces = pools();
+        spaces.filter_class(&classes, Some(PoolClass::Cold));
+        assert_eq!(spaces.total_available(), 2000);
+
+        // Without a preference cold pools are skipped
+        let mut spaces = pools();
+        spaces.filter_class(&classes, None);
+        assert_eq!(spaces.total_available(), 1000);
+
+        // No pool of the wanted class, keep every pool
+        let mut spaces = pools();
+        spaces.filter_class(&classes, Some(PoolClass::Standard));
+        assert_eq!(spaces.total_available(), 3000);
+    }
+
     #[tokio::test]
     async fn test_find_local_disk() {
         let result = find_local_disk(&"/nonexistent/path".to_string()).await;
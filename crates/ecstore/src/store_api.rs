@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::bucket::dedup::dedup_size;
+use crate::bucket::listing::ListingConsistency;
 use crate::bucket::metadata_sys::get_versioning_config;
 use crate::bucket::versioning::VersioningApi as _;
 use crate::config::storageclass;
@@ -28,7 +30,8 @@ use http::{HeaderMap, HeaderValue};
 use rustfs_common::heal_channel::HealOpts;
 use rustfs_filemeta::{
     FileInfo, MetaCacheEntriesSorted, ObjectPartInfo, REPLICATION_RESET, REPLICATION_STATUS, ReplicateDecision, ReplicationState,
-    ReplicationStatusType, VersionPurgeStatusType, replication_statuses_map, version_purge_statuses_map,
+    ReplicationStatusType, RestoreStatusOps as _, TRANSITION_COMPLETE, VersionPurgeStatusType, replication_statuses_map,
+    restore_status_from_metadata, version_purge_statuses_map,
 };
 use rustfs_madmin::heal_commands::HealResultItem;
 use rustfs_rio::Checksum;
@@ -131,6 +134,9 @@ impl PutObjReader {
 pub struct GetObjectReader {
     pub stream: Box<dyn AsyncRead + Unpin + Send + Sync>,
     pub object_info: ObjectInfo,
+    // The whole object as stored, in memory or mapped from its file. Set on full reads of objects
+    // that are not compressed, so the response can be sent without copying through `stream`.
+    pub body: Option<Bytes>,
 }
 
 impl GetObjectReader {
@@ -203,6 +209,7 @@ impl GetObjectReader {
                 GetObjectReader {
                     stream: final_reader,
                     object_info: oi,
+                    body: None,
                 },
                 off,
                 length,
@@ -216,6 +223,7 @@ impl GetObjectReader {
                 GetObjectReader {
                     stream: reader,
                     object_info: oi.clone(),
+                    body: None,
                 },
                 off,
                 length,
@@ -225,12 +233,22 @@ impl GetObjectReader {
                 GetObjectReader {
                     stream: reader,
                     object_info: oi.clone(),
+                    body: None,
                 },
                 0,
                 oi.size,
             ))
         }
     }
+    /// Reader over `data`, the whole of an uncompressed object, that also hands it out as `body`.
+    pub fn from_bytes(data: Bytes, object_info: ObjectInfo) -> Self {
+        Self {
+            stream: Box::new(Cursor::new(data.clone())),
+            object_info,
+            body: Some(data),
+        }
+    }
+
     pub async fn read_all(&mut self) -> Result<Vec<u8>> {
         let mut data = Vec::new();
         self.stream.read_to_end(&mut data).await?;
@@ -393,6 +411,8 @@ pub struct ObjectOptions {
     pub lifecycle_audit_event: LcAuditEvent,
 
     pub eval_metadata: Option<HashMap<String, String>>,
+    /// put_object_metadata replaces the user metadata of the version with eval_metadata
+    pub replace_metadata: bool,
 
     pub want_checksum: Option<Checksum>,
 }
@@ -529,6 +549,8 @@ pub struct ObjectInfo {
     pub user_defined: HashMap<String, String>,
     pub parity_blocks: usize,
     pub data_blocks: usize,
+    /// Bytes of data erasure coded at once.
+    pub block_size: usize,
     pub version_id: Option<Uuid>,
     pub delete_marker: bool,
     pub transitioned_object: TransitionedObject,
@@ -568,6 +590,7 @@ impl Clone for ObjectInfo {
             user_defined: self.user_defined.clone(),
             parity_blocks: self.parity_blocks,
             data_blocks: self.data_blocks,
+            block_size: self.block_size,
             version_id: self.version_id,
             delete_marker: self.delete_marker,
             transitioned_object: self.transitioned_object.clone(),
@@ -693,11 +716,20 @@ impl ObjectInfo {
             v
         };
 
-        // Extract storage class from metadata, default to STANDARD if not found
-        let storage_class = metadata
-            .get(AMZ_STORAGE_CLASS)
-            .cloned()
-            .or_else(|| Some(storageclass::STANDARD.to_string()));
+        // Extract storage class from metadata, default to STANDARD if not found.
+        // A transitioned object reports the tier it was moved to.
+        let storage_class = if fi.transition_status == TRANSITION_COMPLETE && !fi.transition_tier.is_empty() {
+            Some(fi.transition_tier.clone())
+        } else {
+            metadata
+                .get(AMZ_STORAGE_CLASS)
+                .cloned()
+                .or_else(|| Some(storageclass::STANDARD.to_string()))
+        };
+
+        let restore_status = restore_status_from_metadata(&fi.metadata);
+        let restore_ongoing = restore_status.as_ref().is_some_and(|status| status.on_going());
+        let restore_expires = restore_status.as_ref().and_then(|status| status.expiry());
 
         // Convert parts from rustfs_filemeta::ObjectPartInfo to store_api::ObjectPartInfo
         let parts = fi
@@ -721,10 +753,12 @@ impl ObjectInfo {
             is_dir: object.starts_with('/'),
             parity_blocks: fi.erasure.parity_blocks,
             data_blocks: fi.erasure.data_blocks,
+            block_size: fi.erasure.block_size,
             version_id,
             delete_marker: fi.deleted,
             mod_time: fi.mod_time,
-            size: fi.size,
+            // A deduplicated version is stored without data, its size is that of its blob
+            size: dedup_size(&fi.metadata).unwrap_or(fi.size),
             parts,
             is_latest: fi.is_latest,
             user_tags,
@@ -738,6 +772,8 @@ impl ObjectInfo {
             transitioned_object,
             checksum: fi.checksum.clone(),
             storage_class,
+            restore_ongoing,
+            restore_expires,
             ..Default::default()
         }
     }
@@ -947,7 +983,7 @@ impl ObjectInfo {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct ListObjectsInfo {
     // Indicates whether the returned list objects response is truncated. A
     // value of true indicates that the list was truncated. The list can be truncated
@@ -1056,6 +1092,92 @@ pub struct ListMultipartsInfo {
     // encoding_type: String, // Not supported yet.
 }
 
+impl ListMultipartsInfo {
+    /// Build one page out of all `uploads` under `prefix`, in key then initiation order. Pagination
+    /// follows S3: a key marker alone skips every upload of that key, together with an upload id
+    /// marker it skips the uploads of that key up to and including the marked one.
+    pub fn paginate(
+        prefix: &str,
+        mut uploads: Vec<MultipartInfo>,
+        key_marker: Option<String>,
+        upload_id_marker: Option<String>,
+        delimiter: Option<String>,
+        max_uploads: usize,
+    ) -> Self {
+        uploads.sort_by(|a, b| {
+            a.object
+                .cmp(&b.object)
+                .then(a.initiated.cmp(&b.initiated))
+                .then(a.upload_id.cmp(&b.upload_id))
+        });
+        uploads.dedup_by(|a, b| a.object == b.object && a.upload_id == b.upload_id);
+
+        let start = match key_marker.as_deref() {
+            None => 0,
+            Some(key) => {
+                // A common prefix handed out as the marker stands for every key under it.
+                let rolled_up = delimiter.as_deref().is_some_and(|d| !d.is_empty() && key.ends_with(d));
+                let after_key = uploads.partition_point(|u| u.object.as_str() <= key || (rolled_up && u.object.starts_with(key)));
+                match upload_id_marker.as_deref() {
+                    Some(id) => uploads
+                        .iter()
+                        .position(|u| u.object == key && u.upload_id == id)
+                        .map_or(after_key, |idx| idx + 1),
+                    None => after_key,
+                }
+            }
+        };
+
+        let mut ret = ListMultipartsInfo {
+            key_marker,
+            upload_id_marker,
+            max_uploads,
+            prefix: prefix.to_owned(),
+            delimiter: delimiter.clone(),
+            ..Default::default()
+        };
+
+        let delimiter = delimiter.filter(|d| !d.is_empty());
+        let mut count = 0;
+        for upload in uploads.into_iter().skip(start) {
+            let common_prefix = delimiter.as_deref().and_then(|d| {
+                let rest = upload.object.strip_prefix(prefix)?;
+                rest.find(d).map(|idx| format!("{prefix}{}", &rest[..idx + d.len()]))
+            });
+            if let Some(common_prefix) = &common_prefix
+                && ret.common_prefixes.last() == Some(common_prefix)
+            {
+                continue;
+            }
+
+            if count == max_uploads {
+                ret.is_truncated = true;
+                break;
+            }
+            count += 1;
+
+            match common_prefix {
+                Some(common_prefix) => {
+                    ret.next_key_marker = Some(common_prefix.clone());
+                    ret.next_upload_id_marker = None;
+                    ret.common_prefixes.push(common_prefix);
+                }
+                None => {
+                    ret.next_key_marker = Some(upload.object.clone());
+                    ret.next_upload_id_marker = Some(upload.upload_id.clone());
+                    ret.uploads.push(upload);
+                }
+            }
+        }
+
+        if !ret.is_truncated {
+            ret.next_key_marker = None;
+            ret.next_upload_id_marker = None;
+        }
+        ret
+    }
+}
+
 /// ListPartsInfo - represents list of all parts.
 #[derive(Debug, Clone, Default)]
 pub struct ListPartsInfo {
@@ -1225,6 +1347,7 @@ pub trait StorageAPI: ObjectIO + Debug {
         fetch_owner: bool,
         start_after: Option<String>,
         incl_deleted: bool,
+        consistency: Option<ListingConsistency>,
     ) -> Result<ListObjectsV2Info>;
     // ListObjectVersions TODO: FIXME:
     async fn list_object_versions(
@@ -1578,6 +1701,80 @@ mod tests {
     use std::io::Cursor;
     use tokio::io::AsyncReadExt;
 
+    fn upload(object: &str, upload_id: &str, initiated: i64) -> MultipartInfo {
+        MultipartInfo {
+            bucket: "bucket".to_string(),
+            object: object.to_string(),
+            upload_id: upload_id.to_string(),
+            initiated: Some(OffsetDateTime::from_unix_timestamp(initiated).unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_object_info_transition_state() {
+        let mut fi = FileInfo::default();
+        let info = ObjectInfo::from_file_info(&fi, "bucket", "object", false);
+        assert_eq!(info.storage_class.as_deref(), Some(storageclass::STANDARD));
+        assert!(!info.restore_ongoing);
+        assert!(info.restore_expires.is_none());
+
+        fi.transition_status = TRANSITION_COMPLETE.to_string();
+        fi.transition_tier = "WARM-TIER".to_string();
+        fi.metadata
+            .insert("x-amz-restore".to_string(), "ongoing-request=\"true\"".to_string());
+        let info = ObjectInfo::from_file_info(&fi, "bucket", "object", false);
+        assert_eq!(info.storage_class.as_deref(), Some("WARM-TIER"));
+        assert!(info.restore_ongoing);
+        assert!(info.restore_expires.is_none());
+
+        fi.metadata.insert(
+            "x-amz-restore".to_string(),
+            "ongoing-request=\"false\", expiry-date=\"2030-01-02T00:00:00Z\"".to_string(),
+        );
+        let info = ObjectInfo::from_file_info(&fi, "bucket", "object", false);
+        assert!(!info.restore_ongoing);
+        assert_eq!(info.restore_expires.map(|t| t.unix_timestamp()), Some(1_893_542_400));
+    }
+
+    #[test]
+    fn test_paginate_multipart_uploads() {
+        let uploads = vec![
+            upload("out/b", "u3", 3),
+            upload("out/a", "u2", 2),
+            upload("out/a", "u1", 1),
+            upload("out/dir/c", "u4", 4),
+            upload("out/dir/d", "u5", 5),
+        ];
+
+        let page = ListMultipartsInfo::paginate("out/", uploads.clone(), None, None, None, 2);
+        let ids: Vec<_> = page.uploads.iter().map(|u| u.upload_id.as_str()).collect();
+        assert_eq!(ids, vec!["u1", "u2"]);
+        assert!(page.is_truncated);
+        assert_eq!(page.next_key_marker.as_deref(), Some("out/a"));
+        assert_eq!(page.next_upload_id_marker.as_deref(), Some("u2"));
+
+        let page =
+            ListMultipartsInfo::paginate("out/", uploads.clone(), page.next_key_marker, page.next_upload_id_marker, None, 10);
+        let ids: Vec<_> = page.uploads.iter().map(|u| u.upload_id.as_str()).collect();
+        assert_eq!(ids, vec!["u3", "u4", "u5"]);
+        assert!(!page.is_truncated);
+        assert!(page.next_key_marker.is_none());
+
+        let page = ListMultipartsInfo::paginate("out/", uploads.clone(), Some("out/a".to_string()), None, None, 10);
+        assert_eq!(page.uploads.len(), 3);
+
+        let page = ListMultipartsInfo::paginate("out/", uploads.clone(), None, None, Some("/".to_string()), 3);
+        let ids: Vec<_> = page.uploads.iter().map(|u| u.upload_id.as_str()).collect();
+        assert_eq!(ids, vec!["u1", "u2", "u3"]);
+        assert!(page.is_truncated);
+
+        let page = ListMultipartsInfo::paginate("out/", uploads, Some("out/b".to_string()), None, Some("/".to_string()), 3);
+        assert!(page.uploads.is_empty());
+        assert_eq!(page.common_prefixes, vec!["out/dir/".to_string()]);
+        assert!(!page.is_truncated);
+    }
+
     #[tokio::test]
     async fn test_ranged_decompress_reader() {
         // Create test data
@@ -16,6 +16,7 @@ use crate::config::{KVS, storageclass};
 use crate::disk::error_reduce::{count_errs, reduce_write_quorum_errs};
 use crate::disk::{self, DiskAPI};
 use crate::error::{Error, Result};
+use crate::format_migration::{FormatVersion, GLOBAL_FORMAT_MIGRATIONS, save_format_version};
 use crate::{
     disk::{
         DiskInfoOptions, DiskOption, DiskStore, FORMAT_CONFIG_FILE, RUSTFS_META_BUCKET,
@@ -149,6 +150,17 @@ async fn init_format_erasure(
 
     save_format_file_all(disks, &fms).await?;
 
+    // Fresh drives start at the latest format version, there is nothing to migrate
+    let version = FormatVersion {
+        version: GLOBAL_FORMAT_MIGRATIONS.latest(),
+        migration: None,
+    };
+    for disk in disks.iter().flatten() {
+        if let Err(err) = save_format_version(disk, &version).await {
+            warn!("saving the format version of drive {} failed: {}", disk.to_string(), err);
+        }
+    }
+
     get_format_erasure_in_quorum(&fms)
 }
 
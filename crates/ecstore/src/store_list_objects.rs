@@ -13,14 +13,18 @@
 // limitations under the License.
 
 use crate::StorageAPI;
-use crate::bucket::metadata_sys::get_versioning_config;
+use crate::bucket::listing::ListingConsistency;
+use crate::bucket::metadata_sys::{get_listing_config, get_versioning_config};
 use crate::bucket::versioning::VersioningApi;
 use crate::cache_value::metacache_set::{ListPathRawOptions, list_path_raw};
 use crate::disk::error::DiskError;
-use crate::disk::{DiskInfo, DiskStore};
+use crate::disk::{DiskAPI, DiskInfo, DiskStore};
 use crate::error::{
     Error, Result, StorageError, is_all_not_found, is_all_volume_not_found, is_err_bucket_not_found, to_object_err,
 };
+use crate::list_cache::{CachedListing, GLOBAL_LIST_CACHE, ListCacheKey};
+use crate::listing_metrics::{GLOBAL_LISTING_METRICS, ListingCacheUse};
+use crate::maintenance::{GLOBAL_MAINTENANCE_SYS, deprioritize_maintenance};
 use crate::set_disk::SetDisks;
 use crate::store::check_list_objs_args;
 use crate::store_api::{
@@ -32,17 +36,24 @@ use futures::future::join_all;
 use rand::seq::SliceRandom;
 use rustfs_filemeta::{
     MetaCacheEntries, MetaCacheEntriesSorted, MetaCacheEntriesSortedResult, MetaCacheEntry, MetadataResolutionParams,
-    merge_file_meta_versions,
+    merge_pool_versions,
 };
 use rustfs_utils::path::{self, SLASH_SEPARATOR, base_dir_from_prefix};
 use std::collections::HashMap;
 use std::sync::Arc;
+use time::OffsetDateTime;
 use tokio::sync::broadcast::{self};
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 use uuid::Uuid;
 
+/// Content type of directory objects.
+pub const DIRECTORY_CONTENT_TYPE: &str = "application/x-directory";
+
+/// MD5 of empty content, the ETag of every zero byte object written in a single part.
+const EMPTY_CONTENT_ETAG: &str = "d41d8cd98f00b204e9800998ecf8427e";
+
 const MAX_OBJECT_LIST: i32 = 1000;
 // const MAX_DELETE_LIST: i32 = 1000;
 // const MAX_UPLOADS_LIST: i32 = 10000;
@@ -202,7 +213,7 @@ impl ListPathOptions {
                 MARKER_TAG_VERSION,
                 id.to_owned(),
                 self.pool_idx.unwrap_or_default(),
-                self.pool_idx.unwrap_or_default(),
+                self.set_idx.unwrap_or_default(),
             )
         } else {
             format!("{marker}[rustfs_cache:{MARKER_TAG_VERSION},return:]")
@@ -226,6 +237,7 @@ impl ECStore {
         _fetch_owner: bool,
         start_after: Option<String>,
         incl_deleted: bool,
+        consistency: Option<ListingConsistency>,
     ) -> Result<ListObjectsV2Info> {
         let marker = {
             if continuation_token.is_none() {
@@ -236,7 +248,7 @@ impl ECStore {
         };
 
         let loi = self
-            .list_objects_generic(bucket, prefix, marker, delimiter, max_keys, incl_deleted)
+            .list_objects_generic(bucket, prefix, marker, delimiter, max_keys, incl_deleted, consistency)
             .await?;
         Ok(ListObjectsV2Info {
             is_truncated: loi.is_truncated,
@@ -247,6 +259,7 @@ impl ECStore {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn list_objects_generic(
         self: Arc<Self>,
         bucket: &str,
@@ -255,6 +268,7 @@ impl ECStore {
         delimiter: Option<String>,
         max_keys: i32,
         incl_deleted: bool,
+        consistency: Option<ListingConsistency>,
     ) -> Result<ListObjectsInfo> {
         let opts = ListPathOptions {
             bucket: bucket.to_owned(),
@@ -294,40 +308,62 @@ impl ECStore {
             };
         };
 
-        let mut list_result = self
-            .list_path(&opts)
-            .await
-            .unwrap_or_else(|err| MetaCacheEntriesSortedResult {
-                err: Some(err.into()),
-                ..Default::default()
-            });
+        let cached = GLOBAL_LIST_CACHE.is_enabled() && !is_reserved_or_invalid_bucket(bucket, false);
+        let consistency = match consistency {
+            Some(consistency) => consistency,
+            None if cached => get_listing_config(bucket)
+                .await
+                .map(|(cfg, _)| cfg.consistency)
+                .unwrap_or_default(),
+            None => ListingConsistency::default(),
+        };
 
-        if let Some(err) = list_result.err.clone() {
-            if err != rustfs_filemeta::Error::Unexpected {
-                return Err(to_object_err(err.into(), vec![bucket, prefix]));
+        let listing = if cached {
+            let key = ListCacheKey {
+                bucket: bucket.to_owned(),
+                prefix: prefix.to_owned(),
+                marker: opts.marker.clone(),
+                delimiter: delimiter.clone(),
+                max_keys,
+                incl_deleted,
+            };
+            let generation = GLOBAL_LIST_CACHE.generation(bucket);
+
+            if consistency == ListingConsistency::Strict {
+                // Walk the drives whatever is cached, the fresh page still serves later eventual listings
+                let listing = self.clone().walk_listing(&opts).await?;
+                GLOBAL_LIST_CACHE.put(key, generation, listing.clone(), true);
+                listing
+            } else if let Some(listing) = GLOBAL_LIST_CACHE.get(&key) {
+                GLOBAL_LISTING_METRICS.record_listing(ListingCacheUse::Hit, listing.entries.len());
+                listing
+            } else if let Some(listing) = GLOBAL_LIST_CACHE.fetch(&key).await {
+                GLOBAL_LISTING_METRICS.record_listing(ListingCacheUse::Hit, listing.entries.len());
+                GLOBAL_LIST_CACHE.put(key, generation, listing.clone(), false);
+                listing
+            } else {
+                let listing = self.clone().walk_listing(&opts).await?;
+                GLOBAL_LIST_CACHE.put(key, generation, listing.clone(), true);
+                listing
             }
-        }
-
-        if let Some(result) = list_result.entries.as_mut() {
-            result.forward_past(opts.marker);
-        }
+        } else {
+            self.clone().walk_listing(&opts).await?
+        };
 
         // contextCanceled
 
-        let mut get_objects = ObjectInfo::from_meta_cache_entries_sorted_infos(
-            &list_result.entries.unwrap_or_default(),
-            bucket,
-            prefix,
-            delimiter.clone(),
-        )
-        .await;
+        let entries = MetaCacheEntriesSorted {
+            o: MetaCacheEntries(listing.entries.into_iter().map(Some).collect()),
+            ..Default::default()
+        };
+        let mut get_objects = ObjectInfo::from_meta_cache_entries_sorted_infos(&entries, bucket, prefix, delimiter.clone()).await;
 
         let is_truncated = {
             if max_keys > 0 && get_objects.len() > max_keys as usize {
                 get_objects.truncate(max_keys as usize);
                 true
             } else {
-                list_result.err.is_none() && !get_objects.is_empty()
+                !listing.eof && !get_objects.is_empty()
             }
         };
 
@@ -373,6 +409,32 @@ impl ECStore {
         })
     }
 
+    /// Walk the drives for the entries of a listing page past its marker.
+    async fn walk_listing(self: Arc<Self>, opts: &ListPathOptions) -> Result<CachedListing> {
+        let mut list_result = self.list_path(opts).await.unwrap_or_else(|err| MetaCacheEntriesSortedResult {
+            err: Some(err.into()),
+            ..Default::default()
+        });
+
+        if let Some(err) = list_result.err.clone() {
+            if err != rustfs_filemeta::Error::Unexpected {
+                return Err(to_object_err(err.into(), vec![opts.bucket.as_str(), opts.prefix.as_str()]));
+            }
+        }
+
+        if let Some(result) = list_result.entries.as_mut() {
+            result.forward_past(opts.marker.clone());
+        }
+
+        Ok(CachedListing {
+            entries: list_result
+                .entries
+                .map(|entries| entries.o.0.into_iter().flatten().collect())
+                .unwrap_or_default(),
+            eof: list_result.err.is_some(),
+        })
+    }
+
     pub async fn inner_list_object_versions(
         self: Arc<Self>,
         bucket: &str,
@@ -524,6 +586,13 @@ impl ECStore {
         }
 
         o.parse_marker();
+        let cache_use = if o.create {
+            ListingCacheUse::Rescan
+        } else if o.id.is_some() {
+            ListingCacheUse::Hit
+        } else {
+            ListingCacheUse::Miss
+        };
 
         if o.base_dir.is_empty() {
             o.base_dir = base_dir_from_prefix(&o.prefix);
@@ -617,9 +686,24 @@ impl ECStore {
             }
         }
 
+        GLOBAL_LISTING_METRICS.record_listing(cache_use, result.entries.as_ref().map_or(0, |e| e.o.0.len()));
+
         Ok(result)
     }
 
+    /// Authority of a pool when the same object is listed from several pools, lower wins.
+    ///
+    /// Pools being drained by decommission or rebalance rank after every other pool, since the
+    /// copy already moved off them is the one that will survive.
+    async fn list_pool_priority(&self, pool_idx: usize) -> usize {
+        let suspended = self.pool_meta.read().await.is_suspended(pool_idx);
+        if suspended || self.is_pool_rebalancing(pool_idx).await {
+            self.pools.len() + pool_idx
+        } else {
+            pool_idx
+        }
+    }
+
     // Read all
     async fn list_merged(
         &self,
@@ -632,12 +716,15 @@ impl ECStore {
         let mut futures = Vec::new();
 
         let mut inputs = Vec::new();
+        let mut priority = Vec::new();
 
-        for sets in self.pools.iter() {
+        for (pool_idx, sets) in self.pools.iter().enumerate() {
+            let pool_priority = self.list_pool_priority(pool_idx).await;
             for set in sets.disk_set.iter() {
                 let (send, recv) = mpsc::channel(100);
 
                 inputs.push(recv);
+                priority.push(pool_priority);
                 let opts = opts.clone();
                 let rx_clone = rx.clone();
                 futures.push(set.list_path(rx_clone, opts, send));
@@ -645,7 +732,7 @@ impl ECStore {
         }
 
         tokio::spawn(async move {
-            if let Err(err) = merge_entry_channels(rx, inputs, sender.clone(), 1).await {
+            if let Err(err) = merge_entry_channels(rx, inputs, sender.clone(), priority).await {
                 error!("merge_entry_channels err {:?}", err)
             }
         });
@@ -701,6 +788,53 @@ impl ECStore {
         Ok(Vec::new())
     }
 
+    /// Walk `bucket` under `prefix` and collect every returned object, stopping at the first error.
+    pub async fn walk_collect(self: Arc<Self>, bucket: &str, prefix: &str, opts: WalkOptions) -> Result<Vec<ObjectInfo>> {
+        let (tx, mut rx) = mpsc::channel::<ObjectInfoOrErr>(100);
+        let ctx = CancellationToken::new();
+
+        let walk_ctx = ctx.clone();
+        let bucket_name = bucket.to_owned();
+        let walk_prefix = prefix.to_owned();
+        tokio::spawn(async move {
+            if let Err(err) = self.walk(walk_ctx, &bucket_name, &walk_prefix, tx, opts).await {
+                error!("walk {}/{} failed: {:?}", bucket_name, walk_prefix, err);
+            }
+        });
+
+        let mut items = Vec::new();
+        while let Some(v) = rx.recv().await {
+            if let Some(err) = v.err {
+                ctx.cancel();
+                return Err(err);
+            }
+            if let Some(info) = v.item {
+                items.push(info);
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Synthesized directory metadata for `prefix` when no `prefix/` marker object exists but
+    /// objects are stored below it, so a HEAD on the prefix behaves like on an explicit marker.
+    pub async fn implicit_dir_info(self: Arc<Self>, bucket: &str, prefix: &str) -> Result<Option<ObjectInfo>> {
+        if !prefix.ends_with(SLASH_SEPARATOR) {
+            return Ok(None);
+        }
+
+        // With a delimiter a single child object or sub prefix is enough to tell the prefix is in use
+        let loi = self
+            .inner_list_objects_v2(bucket, prefix, None, Some(SLASH_SEPARATOR.to_string()), 2, false, None, false, None)
+            .await?;
+        let child = loi.objects.iter().find(|o| o.name != prefix);
+        if child.is_none() && loi.prefixes.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(implicit_dir_object_info(bucket, prefix, child.and_then(|o| o.mod_time))))
+    }
+
     #[allow(unused_assignments)]
     pub async fn walk_internal(
         self: Arc<Self>,
@@ -714,14 +848,17 @@ impl ECStore {
 
         let mut futures = Vec::new();
         let mut inputs = Vec::new();
+        let mut priority = Vec::new();
 
-        for eset in self.pools.iter() {
+        for (pool_idx, eset) in self.pools.iter().enumerate() {
+            let pool_priority = self.list_pool_priority(pool_idx).await;
             for set in eset.disk_set.iter() {
                 let (mut disks, infos, _) = set.get_online_disks_with_healing_and_info(true).await;
                 let opts = opts.clone();
 
                 let (sender, list_out_rx) = mpsc::channel::<MetaCacheEntry>(1);
                 inputs.push(list_out_rx);
+                priority.push(pool_priority);
                 let rx_clone = rx.clone();
                 futures.push(async move {
                     let mut ask_disks = get_list_quorum(&opts.ask_disks, set.set_drive_count as i32);
@@ -742,6 +879,9 @@ impl ECStore {
                         if ask_disks > 0 && disks.len() > ask_disks as usize {
                             let mut rand = rand::rng();
                             disks.shuffle(&mut rand);
+                            deprioritize_maintenance(&mut disks, |disk| {
+                                GLOBAL_MAINTENANCE_SYS.is_endpoint_in_maintenance(&disk.endpoint())
+                            });
                             disks.split_off(ask_disks as usize)
                         } else {
                             Vec::new()
@@ -935,7 +1075,7 @@ impl ECStore {
             }
         });
 
-        tokio::spawn(async move { merge_entry_channels(rx, inputs, merge_tx, 1).await });
+        tokio::spawn(async move { merge_entry_channels(rx, inputs, merge_tx, priority).await });
 
         join_all(futures).await;
 
@@ -1054,11 +1194,16 @@ async fn select_from(
 }
 
 // TODO: exit when cancel
+/// Merge the sorted entries of several sets into one sorted stream.
+///
+/// `priority` holds, per input channel, the authority of the pool it lists; lower is more
+/// authoritative. When an object is found in several pools, as happens while data is moved by
+/// decommission or rebalance, its versions are deduplicated in favor of the authoritative pool.
 async fn merge_entry_channels(
     rx: CancellationToken,
     in_channels: Vec<Receiver<MetaCacheEntry>>,
     out_channel: Sender<MetaCacheEntry>,
-    read_quorum: usize,
+    priority: Vec<usize>,
 ) -> Result<()> {
     if in_channels.is_empty() {
         return Ok(());
@@ -1115,7 +1260,7 @@ async fn merge_entry_channels(
                     // println!("get other_entry {:?}", other_entry.name);
 
                     if path::clean(&best_entry.name) == path::clean(&other_entry.name) {
-                        let dir_matches = best_entry.is_dir() && other_entry.is_dir();
+                        let dir_matches = best_entry.is_dir() == other_entry.is_dir();
                         let suffix_matches =
                             best_entry.name.ends_with(SLASH_SEPARATOR) == other_entry.name.ends_with(SLASH_SEPARATOR);
 
@@ -1159,7 +1304,7 @@ async fn merge_entry_channels(
                 let mut has_xl = { entry.clone().xl_meta().ok() };
 
                 if let Some(x) = &has_xl {
-                    versions.push(x.versions.clone());
+                    versions.push((priority.get(best_idx).copied().unwrap_or_default(), x.versions.clone()));
                 }
 
                 for &idx in to_merge.iter() {
@@ -1175,7 +1320,7 @@ async fn merge_entry_channels(
                             }
                         };
 
-                        versions.push(xl2.versions.clone());
+                        versions.push((priority.get(idx).copied().unwrap_or_default(), xl2.versions.clone()));
 
                         if has_xl.is_none() {
                             select_from(&mut in_channels, best_idx, &mut top, &mut n_done).await?;
@@ -1184,14 +1329,16 @@ async fn merge_entry_channels(
                             best = Some(entry.clone());
                             has_xl = Some(xl2);
                         } else {
-                            select_from(&mut in_channels, best_idx, &mut top, &mut n_done).await?;
+                            select_from(&mut in_channels, idx, &mut top, &mut n_done).await?;
                         }
                     }
                 }
 
                 if let Some(xl) = has_xl.as_mut() {
                     if !versions.is_empty() {
-                        xl.versions = merge_file_meta_versions(read_quorum, true, 0, &versions);
+                        versions.sort_by_key(|(priority, _)| *priority);
+                        let versions: Vec<_> = versions.into_iter().map(|(_, v)| v).collect();
+                        xl.versions = merge_pool_versions(&versions);
 
                         if let Ok(meta) = xl.marshal_msg() {
                             if let Some(b) = best.as_mut() {
@@ -1217,6 +1364,20 @@ async fn merge_entry_channels(
     }
 }
 
+/// Metadata of a directory that only exists through the objects below it: a zero byte object
+/// with the ETag of empty content, the way explicit `prefix/` markers are usually written.
+pub fn implicit_dir_object_info(bucket: &str, prefix: &str, mod_time: Option<OffsetDateTime>) -> ObjectInfo {
+    ObjectInfo {
+        bucket: bucket.to_string(),
+        name: prefix.to_string(),
+        is_dir: true,
+        mod_time,
+        content_type: Some(DIRECTORY_CONTENT_TYPE.to_string()),
+        etag: Some(EMPTY_CONTENT_ETAG.to_string()),
+        ..Default::default()
+    }
+}
+
 impl SetDisks {
     pub async fn list_path(&self, rx: CancellationToken, opts: ListPathOptions, sender: Sender<MetaCacheEntry>) -> Result<()> {
         let (mut disks, infos, _) = self.get_online_disks_with_healing_and_info(true).await;
@@ -1243,6 +1404,7 @@ impl SetDisks {
         if ask_disks > 0 && disks.len() > ask_disks as usize {
             let mut rand = rand::rng();
             disks.shuffle(&mut rand);
+            deprioritize_maintenance(&mut disks, |disk| GLOBAL_MAINTENANCE_SYS.is_endpoint_in_maintenance(&disk.endpoint()));
 
             fallback_disks = disks.split_off(ask_disks as usize);
         }
@@ -1297,6 +1459,7 @@ impl SetDisks {
                         let resolver = resolver.clone();
                         async move {
                             if let Some(entry) = entries.resolve(resolver) {
+                                GLOBAL_LISTING_METRICS.record_stale_entry();
                                 if let Err(err) = value.send(entry).await {
                                     error!("list_path send fail {:?}", err);
                                 }
@@ -1662,4 +1825,113 @@ mod test {
     //         println!("get entry {:?}", entry)
     //     }
     // }
+
+    use super::{DIRECTORY_CONTENT_TYPE, implicit_dir_object_info, merge_entry_channels};
+    use rustfs_filemeta::{FileMeta, FileMetaShallowVersion, FileMetaVersion, MetaCacheEntry, MetaObject, VersionType};
+    use time::OffsetDateTime;
+    use tokio::sync::mpsc;
+    use tokio_util::sync::CancellationToken;
+    use uuid::Uuid;
+
+    fn object_entry(name: &str, versions: &[(Uuid, i64, usize)]) -> MetaCacheEntry {
+        let mut fm = FileMeta::new();
+        for (version_id, mod_time, erasure_n) in versions {
+            let version = FileMetaVersion {
+                version_type: VersionType::Object,
+                object: Some(MetaObject {
+                    version_id: Some(*version_id),
+                    erasure_m: 2,
+                    erasure_n: *erasure_n,
+                    mod_time: Some(OffsetDateTime::from_unix_timestamp(*mod_time).unwrap()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            fm.versions.push(FileMetaShallowVersion::try_from(version).unwrap());
+        }
+
+        MetaCacheEntry {
+            name: name.to_owned(),
+            metadata: fm.marshal_msg().unwrap(),
+            ..Default::default()
+        }
+    }
+
+    async fn merge(inputs: Vec<Vec<MetaCacheEntry>>, priority: Vec<usize>) -> Vec<MetaCacheEntry> {
+        let mut receivers = Vec::new();
+        for entries in inputs {
+            let (tx, rx) = mpsc::channel(entries.len().max(1));
+            for entry in entries {
+                tx.send(entry).await.unwrap();
+            }
+            receivers.push(rx);
+        }
+
+        let (out_tx, mut out_rx) = mpsc::channel(100);
+        merge_entry_channels(CancellationToken::new(), receivers, out_tx, priority)
+            .await
+            .unwrap();
+
+        let mut out = Vec::new();
+        while let Some(entry) = out_rx.recv().await {
+            out.push(entry);
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn test_merge_entry_channels_mid_migration() {
+        let v1 = Uuid::new_v4();
+        let v2 = Uuid::new_v4();
+
+        // Pool 0 already received v1 of "a"; pool 1 is being drained and still holds both versions
+        let destination = vec![
+            object_entry("a", &[(v1, 100, 4)]),
+            object_entry("c", &[(Uuid::new_v4(), 100, 4)]),
+        ];
+        let source = vec![
+            object_entry("a", &[(v2, 200, 2), (v1, 100, 2)]),
+            object_entry("b", &[(Uuid::new_v4(), 100, 2)]),
+        ];
+
+        let out = merge(vec![destination, source], vec![0, 3]).await;
+        let names: Vec<&str> = out.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+
+        let mut a = out[0].clone();
+        let xl = a.xl_meta().unwrap();
+        assert_eq!(xl.versions.len(), 2);
+        assert_eq!(xl.versions[0].header.version_id, Some(v2));
+        assert_eq!(xl.versions[1].header.version_id, Some(v1));
+        assert_eq!(xl.versions[1].header.ec_n, 4);
+    }
+
+    #[tokio::test]
+    async fn test_merge_entry_channels_prefers_priority_over_input_order() {
+        let v1 = Uuid::new_v4();
+
+        // The draining pool comes first in input order but must not win
+        let source = vec![object_entry("a", &[(v1, 100, 2)])];
+        let destination = vec![object_entry("a", &[(v1, 100, 4)])];
+
+        let out = merge(vec![source, destination], vec![2, 1]).await;
+        assert_eq!(out.len(), 1);
+
+        let mut a = out[0].clone();
+        let xl = a.xl_meta().unwrap();
+        assert_eq!(xl.versions.len(), 1);
+        assert_eq!(xl.versions[0].header.ec_n, 4);
+    }
+
+    #[test]
+    fn test_implicit_dir_object_info() {
+        let mod_time = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let info = implicit_dir_object_info("bucket", "dir/", Some(mod_time));
+        assert!(info.is_dir);
+        assert_eq!(info.name, "dir/");
+        assert_eq!(info.size, 0);
+        assert_eq!(info.mod_time, Some(mod_time));
+        assert_eq!(info.content_type.as_deref(), Some(DIRECTORY_CONTENT_TYPE));
+        assert_eq!(info.etag.as_deref(), Some("d41d8cd98f00b204e9800998ecf8427e"));
+    }
 }
@@ -0,0 +1,131 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cleanup of temporary data left behind by interrupted writes.
+//!
+//! Object and part uploads are erasure coded into `RUSTFS_META_TMP_BUCKET` first and renamed
+//! into place once complete. When the write fails, or the request future is dropped because the
+//! client went away, the temporary shards must not outlive the request.
+
+use crate::disk::{DeleteOptions, DiskAPI, DiskStore, RUSTFS_META_TMP_BUCKET, error::DiskError};
+use futures::future::join_all;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{debug, warn};
+
+static ABANDONED_WRITES: AtomicU64 = AtomicU64::new(0);
+
+/// Number of writes whose temporary data was removed because they did not complete.
+pub fn abandoned_writes() -> u64 {
+    ABANDONED_WRITES.load(Ordering::Relaxed)
+}
+
+/// Removes a temporary upload path from every disk when dropped, unless disarmed.
+///
+/// Dropping happens on error returns as well as on cancellation, the removal runs in a
+/// background task so it also completes when the owning future is dropped.
+pub(crate) struct TmpCleanupGuard {
+    disks: Vec<Option<DiskStore>>,
+    path: String,
+    armed: bool,
+}
+
+impl TmpCleanupGuard {
+    pub(crate) fn new(disks: &[Option<DiskStore>], path: String) -> Self {
+        Self {
+            disks: disks.to_vec(),
+            path,
+            armed: true,
+        }
+    }
+
+    /// Keep the temporary data, the write completed and took care of it.
+    pub(crate) fn disarm(mut self) {
+        self.armed = false;
+    }
+
+    /// Remove the temporary data before returning, for writes that failed while the request is
+    /// still being served so no shards are left behind once the error reaches the client.
+    pub(crate) async fn rollback(mut self) {
+        self.armed = false;
+        ABANDONED_WRITES.fetch_add(1, Ordering::Relaxed);
+        let disks = std::mem::take(&mut self.disks);
+        let path = std::mem::take(&mut self.path);
+        remove_tmp(disks, path).await;
+    }
+}
+
+async fn remove_tmp(disks: Vec<Option<DiskStore>>, path: String) {
+    let futures = disks.iter().flatten().map(|disk| {
+        disk.delete(
+            RUSTFS_META_TMP_BUCKET,
+            &path,
+            DeleteOptions {
+                recursive: true,
+                ..Default::default()
+            },
+        )
+    });
+    // Inline writes never create files, nothing to remove is fine
+    let failed = join_all(futures)
+        .await
+        .into_iter()
+        .filter(|r| r.as_ref().is_err_and(|e| *e != DiskError::FileNotFound))
+        .count();
+    if failed > 0 {
+        warn!("failed to remove temporary data {} of an interrupted write on {} disks", path, failed);
+    } else {
+        debug!("removed temporary data {} of an interrupted write", path);
+    }
+}
+
+impl Drop for TmpCleanupGuard {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+
+        ABANDONED_WRITES.fetch_add(1, Ordering::Relaxed);
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            warn!("no runtime to remove temporary data {}", self.path);
+            return;
+        };
+
+        let disks = std::mem::take(&mut self.disks);
+        let path = std::mem::take(&mut self.path);
+        handle.spawn(remove_tmp(disks, path));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_guard_counts_only_armed_drops() {
+        let before = abandoned_writes();
+
+        TmpCleanupGuard::new(&[None], "tmp-ok".to_string()).disarm();
+        assert_eq!(abandoned_writes(), before);
+
+        drop(TmpCleanupGuard::new(&[None, None], "tmp-abandoned".to_string()));
+        assert!(abandoned_writes() > before);
+    }
+
+    #[tokio::test]
+    async fn test_guard_rollback_counts_abandoned() {
+        let before = abandoned_writes();
+        TmpCleanupGuard::new(&[None], "tmp-rollback".to_string()).rollback().await;
+        assert!(abandoned_writes() > before);
+    }
+}
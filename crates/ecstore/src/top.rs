@@ -0,0 +1,200 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cluster-wide views of the oldest held locks and of the busiest APIs.
+//!
+//! Every node reports its own entries through the internode RPC endpoint, the node serving the
+//! admin request merges them. Unreachable nodes are reported instead of failing the whole view.
+
+use crate::error::{Error, Result};
+use crate::notification_sys::get_global_notification_sys;
+use crate::rpc::build_auth_headers;
+use futures::future::join_all;
+use http::{HeaderMap, Method};
+use rustfs_common::globals::GLOBAL_Local_Node_Name;
+use rustfs_common::perf_monitor::{ApiPerfStats, GLOBAL_PERF_MONITOR, sort_top_apis};
+use rustfs_lock::{LockManager, LockMode, get_global_lock_manager};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime};
+use time::OffsetDateTime;
+
+pub const DEFAULT_TOP_COUNT: usize = 10;
+pub const MAX_TOP_COUNT: usize = 1000;
+
+const PEER_TIMEOUT: Duration = Duration::from_secs(10);
+
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockEntry {
+    pub node: String,
+    pub bucket: String,
+    pub object: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    /// `read` or `write`.
+    pub mode: String,
+    pub owner: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub acquired: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires: OffsetDateTime,
+    pub held_ms: u64,
+}
+
+/// Merged view over all nodes, with the error of every node that could not be asked.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopReport<T> {
+    pub entries: Vec<T>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, String>,
+}
+
+async fn local_node_name() -> String {
+    GLOBAL_Local_Node_Name.read().await.clone()
+}
+
+fn sort_top_locks(locks: &mut Vec<LockEntry>, count: usize) {
+    locks.sort_by(|a, b| b.held_ms.cmp(&a.held_ms).then_with(|| a.object.cmp(&b.object)));
+    locks.truncate(count);
+}
+
+/// The `count` longest held locks of this node.
+pub async fn local_top_locks(count: usize) -> Vec<LockEntry> {
+    let node = local_node_name().await;
+    let now = SystemTime::now();
+    let mut locks: Vec<LockEntry> = get_global_lock_manager()
+        .list_locks()
+        .into_iter()
+        .map(|info| LockEntry {
+            node: node.clone(),
+            bucket: info.key.bucket.to_string(),
+            object: info.key.object.to_string(),
+            version_id: info.key.version.as_ref().map(|v| v.to_string()),
+            mode: match info.mode {
+                LockMode::Shared => "read".to_string(),
+                LockMode::Exclusive => "write".to_string(),
+            },
+            owner: info.owner.to_string(),
+            acquired: info.acquired_at.into(),
+            expires: info.expires_at.into(),
+            held_ms: now.duration_since(info.acquired_at).map_or(0, |d| d.as_millis() as u64),
+        })
+        .collect();
+    sort_top_locks(&mut locks, count);
+    locks
+}
+
+/// The `count` busiest APIs of this node.
+pub async fn local_top_apis(count: usize) -> Vec<ApiPerfStats> {
+    let node = local_node_name().await;
+    let mut stats = GLOBAL_PERF_MONITOR.stats();
+    for s in stats.iter_mut() {
+        s.node = node.clone();
+    }
+    sort_top_apis(&mut stats, count);
+    stats
+}
+
+async fn peer_top<T: DeserializeOwned>(grid_host: &str, view: &str, count: usize) -> Result<Vec<T>> {
+    let url = format!("{grid_host}/rustfs/rpc/{view}?count={count}");
+    let mut headers = HeaderMap::new();
+    build_auth_headers(&url, &Method::GET, &mut headers);
+    let resp = HTTP_CLIENT
+        .get(&url)
+        .headers(headers)
+        .timeout(PEER_TIMEOUT)
+        .send()
+        .await
+        .map_err(Error::other)?
+        .error_for_status()
+        .map_err(Error::other)?;
+    let body = resp.bytes().await.map_err(Error::other)?;
+    serde_json::from_slice(&body).map_err(Error::other)
+}
+
+async fn collect_peers<T: DeserializeOwned>(view: &str, count: usize, report: &mut TopReport<T>) {
+    let Some(sys) = get_global_notification_sys() else {
+        return;
+    };
+    let peers: Vec<(String, String)> = sys
+        .peer_clients
+        .iter()
+        .flatten()
+        .map(|client| (client.host.to_string(), client.grid_host.clone()))
+        .collect();
+
+    let results = join_all(peers.iter().map(|(_, grid_host)| peer_top::<T>(grid_host, view, count))).await;
+    for ((host, _), result) in peers.into_iter().zip(results) {
+        match result {
+            Ok(entries) => report.entries.extend(entries),
+            Err(err) => {
+                report.errors.insert(host, err.to_string());
+            }
+        }
+    }
+}
+
+/// The `count` longest held locks over all nodes.
+pub async fn top_locks(count: usize) -> TopReport<LockEntry> {
+    let mut report = TopReport {
+        entries: local_top_locks(count).await,
+        errors: BTreeMap::new(),
+    };
+    collect_peers("top_locks", count, &mut report).await;
+    sort_top_locks(&mut report.entries, count);
+    report
+}
+
+/// The `count` busiest APIs over all nodes, one entry per API and node.
+pub async fn top_apis(count: usize) -> TopReport<ApiPerfStats> {
+    let mut report = TopReport {
+        entries: local_top_apis(count).await,
+        errors: BTreeMap::new(),
+    };
+    collect_peers("top_apis", count, &mut report).await;
+    sort_top_apis(&mut report.entries, count);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock(object: &str, held_ms: u64) -> LockEntry {
+        LockEntry {
+            node: "node1".to_string(),
+            bucket: "bucket".to_string(),
+            object: object.to_string(),
+            version_id: None,
+            mode: "write".to_string(),
+            owner: "owner".to_string(),
+            acquired: OffsetDateTime::UNIX_EPOCH,
+            expires: OffsetDateTime::UNIX_EPOCH,
+            held_ms,
+        }
+    }
+
+    #[test]
+    fn test_sort_top_locks() {
+        let mut locks = vec![lock("a", 10), lock("b", 300), lock("c", 20)];
+        sort_top_locks(&mut locks, 2);
+        let objects: Vec<_> = locks.iter().map(|l| l.object.as_str()).collect();
+        assert_eq!(objects, vec!["b", "c"]);
+    }
+}
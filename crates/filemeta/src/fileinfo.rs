@@ -34,6 +34,35 @@ pub const NULL_VERSION_ID: &str = "null";
 pub const TIER_FV_ID: &str = "tier-free-versionID";
 pub const TIER_FV_MARKER: &str = "tier-free-marker";
 pub const TIER_SKIP_FV_ID: &str = "tier-skip-fvid";
+pub const DEGRADED_WRITE: &str = "degraded-write";
+pub const METADATA_REVISION: &str = "metadata-revision";
+/// Internal key asking a metadata update to drop the user metadata of the version before applying
+/// its own, it is never stored.
+pub const REPLACE_METADATA: &str = "replace-metadata";
+
+/// Keys the server keeps with an object outside of the `x-amz-`/`x-rustfs-` namespaces.
+const SYSTEM_METADATA_KEYS: &[&str] = &["etag", "md5sum", "last-modified"];
+
+/// Whether `key` is metadata the user set: the content headers and the user defined keys, stored
+/// with or without their `x-amz-meta-` prefix.
+pub fn is_replaceable_metadata(key: &str) -> bool {
+    let key = key.to_lowercase();
+    if key.starts_with("x-amz-meta-") || key.starts_with("x-rustfs-meta-") {
+        return true;
+    }
+    !(key.starts_with("x-amz-")
+        || key.starts_with("x-rustfs-")
+        || key.starts_with("x-minio-")
+        || SYSTEM_METADATA_KEYS.contains(&key.as_str()))
+}
+
+/// Number of in-place metadata updates the version carrying `metadata` went through.
+pub fn metadata_revision(metadata: &HashMap<String, String>) -> u64 {
+    metadata
+        .get(&format!("{RESERVED_METADATA_PREFIX_LOWER}{METADATA_REVISION}"))
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_default()
+}
 
 const ERR_RESTORE_HDR_MALFORMED: &str = "x-amz-restore header malformed";
 
@@ -386,6 +415,40 @@ impl FileInfo {
             .insert(format!("{RESERVED_METADATA_PREFIX_LOWER}data-moved").to_owned(), "true".to_owned());
     }
 
+    /// Record that only `shards` drives of the erasure set took the write, the others were offline.
+    pub fn set_degraded_write(&mut self, shards: usize) {
+        self.metadata
+            .insert(format!("{RESERVED_METADATA_PREFIX_LOWER}{DEGRADED_WRITE}"), shards.to_string());
+    }
+
+    /// Number of drives that took a write made while drives were offline, None once the object
+    /// is stored at full parity again.
+    pub fn degraded_write(&self) -> Option<usize> {
+        self.metadata
+            .get(&format!("{RESERVED_METADATA_PREFIX_LOWER}{DEGRADED_WRITE}"))
+            .and_then(|v| v.parse().ok())
+    }
+
+    pub fn clear_degraded_write(&mut self) {
+        self.metadata
+            .remove(&format!("{RESERVED_METADATA_PREFIX_LOWER}{DEGRADED_WRITE}"));
+    }
+
+    /// Replace the user metadata of the version with what `self` carries when updated in place.
+    pub fn set_replace_metadata(&mut self) {
+        self.metadata.retain(|k, _| !is_replaceable_metadata(k));
+        self.metadata
+            .insert(format!("{RESERVED_METADATA_PREFIX_LOWER}{REPLACE_METADATA}"), "true".to_string());
+    }
+
+    /// Count one more in-place metadata update, returning the new revision.
+    pub fn bump_metadata_revision(&mut self) -> u64 {
+        let revision = metadata_revision(&self.metadata) + 1;
+        self.metadata
+            .insert(format!("{RESERVED_METADATA_PREFIX_LOWER}{METADATA_REVISION}"), revision.to_string());
+        revision
+    }
+
     pub fn inline_data(&self) -> bool {
         self.metadata
             .contains_key(format!("{RESERVED_METADATA_PREFIX_LOWER}inline-data").as_str())
@@ -620,10 +683,8 @@ fn parse_restore_obj_status(restore_hdr: &str) -> Result<RestoreStatus> {
             if expiry_tokens[0].trim() != "expiry-date" {
                 return Err(Error::other(ERR_RESTORE_HDR_MALFORMED));
             }
-            let expiry = OffsetDateTime::parse(expiry_tokens[1].trim_matches('"'), &Rfc3339).unwrap();
-            /*if err != nil {
-                return Err(Error::other(ERR_RESTORE_HDR_MALFORMED));
-            }*/
+            let expiry = OffsetDateTime::parse(expiry_tokens[1].trim_matches('"'), &Rfc3339)
+                .map_err(|_| Error::other(ERR_RESTORE_HDR_MALFORMED))?;
             return Ok(RestoreStatus {
                 is_restore_in_progress: Some(false),
                 restore_expiry_date: Some(Timestamp::from(expiry)),
@@ -634,11 +695,12 @@ fn parse_restore_obj_status(restore_hdr: &str) -> Result<RestoreStatus> {
     Err(Error::other(ERR_RESTORE_HDR_MALFORMED))
 }
 
+/// Restore state RestoreObject recorded in the metadata of a transitioned object, a malformed record counts as none.
+pub fn restore_status_from_metadata(meta: &HashMap<String, String>) -> Option<RestoreStatus> {
+    meta.get(X_AMZ_RESTORE.as_str())
+        .and_then(|restore_hdr| parse_restore_obj_status(restore_hdr).ok())
+}
+
 pub fn is_restored_object_on_disk(meta: &HashMap<String, String>) -> bool {
-    if let Some(restore_hdr) = meta.get(X_AMZ_RESTORE.as_str()) {
-        if let Ok(restore_status) = parse_restore_obj_status(restore_hdr) {
-            return restore_status.on_disk();
-        }
-    }
-    false
+    restore_status_from_metadata(meta).is_some_and(|restore_status| restore_status.on_disk())
 }
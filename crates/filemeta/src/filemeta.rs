@@ -13,8 +13,9 @@
 // limitations under the License.
 
 use crate::{
-    ErasureAlgo, ErasureInfo, Error, FileInfo, FileInfoVersions, InlineData, ObjectPartInfo, RawFileInfo, ReplicationState,
-    ReplicationStatusType, Result, VersionPurgeStatusType, replication_statuses_map, version_purge_statuses_map,
+    ErasureAlgo, ErasureInfo, Error, FileInfo, FileInfoVersions, InlineData, ObjectPartInfo, REPLACE_METADATA, RawFileInfo,
+    ReplicationState, ReplicationStatusType, Result, VersionPurgeStatusType, is_replaceable_metadata, replication_statuses_map,
+    version_purge_statuses_map,
 };
 use byteorder::ByteOrder;
 use bytes::Bytes;
@@ -473,7 +474,21 @@ impl FileMeta {
                         let mut ver = FileMetaVersion::try_from(version.meta.as_slice())?;
 
                         if let Some(ref mut obj) = ver.object {
+                            let replace_key = format!("{RESERVED_METADATA_PREFIX_LOWER}{REPLACE_METADATA}");
+                            if fi.metadata.contains_key(&replace_key) {
+                                obj.meta_user.retain(|k, _| !is_replaceable_metadata(k));
+                            }
+
                             for (k, v) in fi.metadata.iter() {
+                                if *k == replace_key {
+                                    continue;
+                                }
+                                // An empty internal value removes the key, wherever the version keeps it
+                                if v.is_empty() && k.to_lowercase().starts_with(RESERVED_METADATA_PREFIX_LOWER) {
+                                    obj.meta_user.remove(k);
+                                    obj.meta_sys.remove(k);
+                                    continue;
+                                }
                                 obj.meta_user.insert(k.clone(), v.clone());
                             }
 
@@ -3232,6 +3247,75 @@ mod test {
         assert!(fm.validate_integrity().is_ok());
     }
 
+    #[test]
+    fn test_update_object_version_clears_internal_key() {
+        let mut fm = FileMeta::new();
+
+        let mut fi = crate::fileinfo::FileInfo::new("test", 2, 1);
+        fi.version_id = Some(Uuid::new_v4());
+        fi.mod_time = Some(OffsetDateTime::now_utc());
+        fi.set_degraded_write(2);
+        fm.add_version(fi.clone()).unwrap();
+
+        let vid = fi.version_id.unwrap().to_string();
+        let stored = fm.into_fileinfo("bucket", "object", &vid, false, false).unwrap();
+        assert_eq!(stored.degraded_write(), Some(2));
+
+        let mut update = crate::fileinfo::FileInfo {
+            version_id: fi.version_id,
+            ..Default::default()
+        };
+        update.metadata.insert(
+            format!("{RESERVED_METADATA_PREFIX_LOWER}{}", crate::fileinfo::DEGRADED_WRITE),
+            String::new(),
+        );
+        update.metadata.insert("x-amz-meta-kept".to_string(), "yes".to_string());
+        fm.update_object_version(update).unwrap();
+
+        let stored = fm.into_fileinfo("bucket", "object", &vid, false, false).unwrap();
+        assert_eq!(stored.degraded_write(), None);
+        assert_eq!(stored.metadata.get("x-amz-meta-kept").map(String::as_str), Some("yes"));
+    }
+
+    #[test]
+    fn test_update_object_version_replaces_user_metadata() {
+        let mut fm = FileMeta::new();
+
+        let mut fi = crate::fileinfo::FileInfo::new("test", 2, 1);
+        fi.version_id = Some(Uuid::new_v4());
+        fi.mod_time = Some(OffsetDateTime::now_utc());
+        fi.metadata.insert("content-type".to_string(), "text/plain".to_string());
+        fi.metadata.insert("old".to_string(), "1".to_string());
+        fi.metadata.insert(AMZ_STORAGE_CLASS.to_string(), "STANDARD_IA".to_string());
+        fm.add_version(fi.clone()).unwrap();
+
+        let mut update = crate::fileinfo::FileInfo {
+            version_id: fi.version_id,
+            ..Default::default()
+        };
+        update.set_replace_metadata();
+        update
+            .metadata
+            .insert("content-type".to_string(), "application/json".to_string());
+        update.metadata.insert("new".to_string(), "2".to_string());
+        assert_eq!(update.bump_metadata_revision(), 1);
+        fm.update_object_version(update).unwrap();
+
+        let vid = fi.version_id.unwrap().to_string();
+        let stored = fm.into_fileinfo("bucket", "object", &vid, false, false).unwrap();
+        assert_eq!(stored.metadata.get("content-type").map(String::as_str), Some("application/json"));
+        assert_eq!(stored.metadata.get("new").map(String::as_str), Some("2"));
+        assert!(!stored.metadata.contains_key("old"));
+        // Metadata that is not the user's is kept
+        assert_eq!(stored.metadata.get(AMZ_STORAGE_CLASS).map(String::as_str), Some("STANDARD_IA"));
+        assert_eq!(crate::fileinfo::metadata_revision(&stored.metadata), 1);
+        assert!(
+            !stored
+                .metadata
+                .contains_key(&format!("{RESERVED_METADATA_PREFIX_LOWER}{REPLACE_METADATA}"))
+        );
+    }
+
     #[test]
     fn test_version_merge_scenarios() {
         // Test various version merge scenarios
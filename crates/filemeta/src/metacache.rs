@@ -18,6 +18,7 @@ use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::str::from_utf8;
 use std::{
+    collections::HashSet,
     fmt::Debug,
     future::Future,
     pin::Pin,
@@ -450,6 +451,33 @@ impl MetaCacheEntriesSorted {
     }
 }
 
+/// Merge the versions of one object listed from several pools.
+///
+/// `versions` must be ordered from the most to the least authoritative pool. While data moves
+/// between pools the same version can exist on both with a different erasure layout; only the
+/// copy from the most authoritative pool is kept, so the version is neither duplicated nor lost.
+pub fn merge_pool_versions(versions: &[Vec<FileMetaShallowVersion>]) -> Vec<FileMetaShallowVersion> {
+    let mut seen = HashSet::new();
+    let mut merged: Vec<FileMetaShallowVersion> = versions
+        .iter()
+        .flatten()
+        .filter(|ver| seen.insert(ver.header.version_id))
+        .cloned()
+        .collect();
+
+    merged.sort_by(|a, b| {
+        if a.header.sorts_before(&b.header) {
+            Ordering::Less
+        } else if b.header.sorts_before(&a.header) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    });
+
+    merged
+}
+
 const METACACHE_STREAM_VERSION: u8 = 2;
 
 #[derive(Debug)]
@@ -858,6 +886,7 @@ impl<T: Clone + Debug + Send + 'static> Cache<T> {
 mod tests {
     use super::*;
     use std::io::Cursor;
+    use uuid::Uuid;
 
     #[tokio::test]
     async fn test_writer() {
@@ -886,4 +915,41 @@ mod tests {
 
         assert_eq!(objs, nobjs);
     }
+
+    fn object_version(version_id: Uuid, mod_time: i64, erasure_n: usize) -> FileMetaShallowVersion {
+        FileMetaShallowVersion::try_from(crate::FileMetaVersion {
+            version_type: VersionType::Object,
+            object: Some(crate::MetaObject {
+                version_id: Some(version_id),
+                erasure_m: 2,
+                erasure_n,
+                mod_time: Some(OffsetDateTime::from_unix_timestamp(mod_time).unwrap()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_merge_pool_versions_prefers_authoritative_copy() {
+        let v1 = Uuid::new_v4();
+        let v2 = Uuid::new_v4();
+
+        // v1 was already copied to the destination pool with a different layout
+        let destination = vec![object_version(v1, 100, 4)];
+        let source = vec![object_version(v2, 200, 2), object_version(v1, 100, 2)];
+
+        let merged = merge_pool_versions(&[destination.clone(), source]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].header.version_id, Some(v2));
+        assert_eq!(merged[1], destination[0]);
+    }
+
+    #[test]
+    fn test_merge_pool_versions_single_pool() {
+        let versions = vec![object_version(Uuid::new_v4(), 200, 2), object_version(Uuid::new_v4(), 100, 2)];
+        assert_eq!(merge_pool_versions(std::slice::from_ref(&versions)), versions);
+        assert!(merge_pool_versions(&[]).is_empty());
+    }
 }
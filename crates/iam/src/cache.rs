@@ -27,7 +27,7 @@ use rustfs_policy::{
 use time::OffsetDateTime;
 use tracing::warn;
 
-use crate::store::{GroupInfo, MappedPolicy};
+use crate::store::{GroupInfo, MappedPolicy, StsRevocationList};
 
 pub struct Cache {
     pub policy_docs: ArcSwap<CacheEntity<PolicyDoc>>,
@@ -38,6 +38,7 @@ pub struct Cache {
     pub groups: ArcSwap<CacheEntity<GroupInfo>>,
     pub user_group_memberships: ArcSwap<CacheEntity<HashSet<String>>>,
     pub group_policies: ArcSwap<CacheEntity<MappedPolicy>>,
+    pub sts_revocations: ArcSwap<StsRevocationList>,
 }
 
 impl Default for Cache {
@@ -51,6 +52,7 @@ impl Default for Cache {
             groups: ArcSwap::new(Arc::new(CacheEntity::default())),
             user_group_memberships: ArcSwap::new(Arc::new(CacheEntity::default())),
             group_policies: ArcSwap::new(Arc::new(CacheEntity::default())),
+            sts_revocations: ArcSwap::new(Arc::new(StsRevocationList::default())),
         }
     }
 }
@@ -42,6 +42,9 @@ pub enum Error {
     #[error("group '{0}' does not exist")]
     NoSuchGroup(String),
 
+    #[error("identity provider '{0}' is not configured")]
+    NoSuchIdentityProvider(String),
+
     #[error("policy does not exist")]
     NoSuchPolicy,
 
@@ -120,6 +123,7 @@ impl PartialEq for Error {
             (Error::NoSuchServiceAccount(a), Error::NoSuchServiceAccount(b)) => a == b,
             (Error::NoSuchTempAccount(a), Error::NoSuchTempAccount(b)) => a == b,
             (Error::NoSuchGroup(a), Error::NoSuchGroup(b)) => a == b,
+            (Error::NoSuchIdentityProvider(a), Error::NoSuchIdentityProvider(b)) => a == b,
             (Error::InvalidServiceType(a), Error::InvalidServiceType(b)) => a == b,
             (Error::Io(a), Error::Io(b)) => a.kind() == b.kind() && a.to_string() == b.to_string(),
             // For complex types like PolicyError, CryptoError, JWTError, compare string representations
@@ -139,6 +143,7 @@ impl Clone for Error {
             Error::NoSuchServiceAccount(s) => Error::NoSuchServiceAccount(s.clone()),
             Error::NoSuchTempAccount(s) => Error::NoSuchTempAccount(s.clone()),
             Error::NoSuchGroup(s) => Error::NoSuchGroup(s.clone()),
+            Error::NoSuchIdentityProvider(s) => Error::NoSuchIdentityProvider(s.clone()),
             Error::NoSuchPolicy => Error::NoSuchPolicy,
             Error::PolicyInUse => Error::PolicyInUse,
             Error::GroupNotEmpty => Error::GroupNotEmpty,
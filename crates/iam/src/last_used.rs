@@ -0,0 +1,308 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tracks when and from where each access key last authenticated a request.
+//!
+//! Requests only touch memory, and a key is recorded again at most once per
+//! [`RECORD_RESOLUTION`] unless its source IP changes. Each node persists its
+//! own records to a single object every `RUSTFS_IAM_LAST_USED_INTERVAL` seconds
+//! and reloads those of the other nodes, so the write load does not grow with
+//! the request rate.
+
+use crate::store::object::IAM_CONFIG_PREFIX;
+use parking_lot::RwLock;
+use rustfs_config::{DEFAULT_IAM_LAST_USED_INTERVAL, ENV_IAM_LAST_USED_INTERVAL};
+use rustfs_ecstore::StorageAPI as _;
+use rustfs_ecstore::config::com::{read_config, save_config};
+use rustfs_ecstore::disk::RUSTFS_META_BUCKET;
+use rustfs_ecstore::error::{Error as StorageError, Result as StorageResult};
+use rustfs_ecstore::store::ECStore;
+use rustfs_ecstore::store_api::{ObjectInfoOrErr, WalkOptions};
+use rustfs_madmin::AccessKeyLastUsed;
+use rustfs_utils::get_env_u64;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+/// Uses of the same key from the same IP closer together than this are not recorded.
+pub const RECORD_RESOLUTION: Duration = Duration::from_secs(60);
+
+pub static GLOBAL_KEY_USAGE: LazyLock<KeyUsageTracker> = LazyLock::new(KeyUsageTracker::default);
+
+fn last_used_prefix() -> String {
+    format!("{}/last-used/", IAM_CONFIG_PREFIX.as_str())
+}
+
+fn last_used_path(node: &str) -> String {
+    let node: String = node
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}{}.json", last_used_prefix(), if node.is_empty() { "local" } else { node.as_str() })
+}
+
+#[derive(Debug, Default)]
+pub struct KeyUsageTracker {
+    /// Records of this node, persisted by it
+    local: RwLock<HashMap<String, AccessKeyLastUsed>>,
+    /// Newest records of the other nodes, as of the last reload
+    remote: RwLock<HashMap<String, AccessKeyLastUsed>>,
+    dirty: AtomicBool,
+}
+
+impl KeyUsageTracker {
+    /// Record that `access_key` authenticated a request coming from `source_ip`.
+    pub fn record(&self, access_key: &str, source_ip: &str) {
+        self.record_at(access_key, source_ip, OffsetDateTime::now_utc());
+    }
+
+    fn record_at(&self, access_key: &str, source_ip: &str, now: OffsetDateTime) -> bool {
+        if access_key.is_empty() {
+            return false;
+        }
+
+        let is_recent = |entry: Option<&AccessKeyLastUsed>| {
+            entry.is_some_and(|last| last.source_ip == source_ip && now - last.time < RECORD_RESOLUTION)
+        };
+        if is_recent(self.local.read().get(access_key)) {
+            return false;
+        }
+
+        let mut local = self.local.write();
+        if is_recent(local.get(access_key)) {
+            return false;
+        }
+        local.insert(
+            access_key.to_string(),
+            AccessKeyLastUsed {
+                time: now,
+                source_ip: source_ip.to_string(),
+            },
+        );
+        self.dirty.store(true, Ordering::Relaxed);
+        true
+    }
+
+    /// The most recent use of `access_key` seen by any node.
+    pub fn last_used(&self, access_key: &str) -> Option<AccessKeyLastUsed> {
+        let local = self.local.read().get(access_key).cloned();
+        let remote = self.remote.read().get(access_key).cloned();
+        match (local, remote) {
+            (Some(local), Some(remote)) => Some(if remote.time > local.time { remote } else { local }),
+            (local, remote) => local.or(remote),
+        }
+    }
+
+    /// Forget the records of a deleted or expired access key.
+    pub fn forget(&self, access_key: &str) {
+        if self.local.write().remove(access_key).is_some() {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        self.remote.write().remove(access_key);
+    }
+
+    async fn load_local(&self, store: Arc<ECStore>, node: &str) -> StorageResult<()> {
+        let records = read_records(store, &last_used_path(node)).await?;
+        let mut local = self.local.write();
+        for (access_key, last) in records {
+            merge_newer(&mut local, access_key, last);
+        }
+        Ok(())
+    }
+
+    async fn persist(&self, store: Arc<ECStore>, node: &str) -> StorageResult<()> {
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let data = serde_json::to_vec(&*self.local.read()).map_err(StorageError::other)?;
+        if let Err(err) = save_config(store, &last_used_path(node), data).await {
+            self.dirty.store(true, Ordering::Relaxed);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    async fn reload_remote(&self, store: Arc<ECStore>, node: &str) -> StorageResult<()> {
+        let own = last_used_path(node);
+        let mut remote = HashMap::new();
+        for path in list_record_objects(store.clone()).await? {
+            if path == own {
+                continue;
+            }
+            match read_records(store.clone(), &path).await {
+                Ok(records) => {
+                    for (access_key, last) in records {
+                        merge_newer(&mut remote, access_key, last);
+                    }
+                }
+                Err(err) => warn!("load access key usage {} failed: {:?}", path, err),
+            }
+        }
+        *self.remote.write() = remote;
+        Ok(())
+    }
+
+    /// Drop the local records of access keys IAM no longer knows.
+    async fn prune(&self) {
+        let Ok(iam_sys) = crate::get() else {
+            return;
+        };
+
+        let access_keys: Vec<String> = self.local.read().keys().cloned().collect();
+        for access_key in access_keys {
+            if iam_sys.get_user(&access_key).await.is_none() {
+                self.forget(&access_key);
+            }
+        }
+    }
+}
+
+fn merge_newer(records: &mut HashMap<String, AccessKeyLastUsed>, access_key: String, last: AccessKeyLastUsed) {
+    if records.get(&access_key).is_none_or(|current| current.time < last.time) {
+        records.insert(access_key, last);
+    }
+}
+
+async fn read_records(store: Arc<ECStore>, path: &str) -> StorageResult<HashMap<String, AccessKeyLastUsed>> {
+    match read_config(store, path).await {
+        Ok(data) => serde_json::from_slice(&data).map_err(StorageError::other),
+        Err(StorageError::ConfigNotFound) => Ok(HashMap::new()),
+        Err(err) => Err(err),
+    }
+}
+
+async fn list_record_objects(store: Arc<ECStore>) -> StorageResult<Vec<String>> {
+    let (tx, mut rx) = mpsc::channel::<ObjectInfoOrErr>(100);
+    let cancel = CancellationToken::new();
+    let walk = {
+        let store = store.clone();
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            store
+                .walk(cancel, RUSTFS_META_BUCKET, &last_used_prefix(), tx, WalkOptions::default())
+                .await
+        })
+    };
+
+    let mut paths = Vec::new();
+    while let Some(item) = rx.recv().await {
+        if let Some(err) = item.err {
+            cancel.cancel();
+            return Err(err);
+        }
+        if let Some(info) = item.item {
+            paths.push(info.name.replace('\\', "/"));
+        }
+    }
+
+    match walk.await {
+        Ok(Ok(())) => Ok(paths),
+        Ok(Err(err)) => Err(err),
+        Err(err) => Err(StorageError::other(err)),
+    }
+}
+
+/// Load the records of this node and start persisting them every `RUSTFS_IAM_LAST_USED_INTERVAL` seconds.
+pub async fn init_key_usage_tracker(store: Arc<ECStore>, node: String, cancel: CancellationToken) {
+    let interval_secs = get_env_u64(ENV_IAM_LAST_USED_INTERVAL, DEFAULT_IAM_LAST_USED_INTERVAL);
+    if interval_secs == 0 {
+        return;
+    }
+
+    if let Err(err) = GLOBAL_KEY_USAGE.load_local(store.clone(), &node).await {
+        warn!("load access key usage failed: {:?}", err);
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => {
+                    if let Err(err) = GLOBAL_KEY_USAGE.persist(store.clone(), &node).await {
+                        warn!("persist access key usage failed: {:?}", err);
+                    }
+                    return;
+                }
+                _ = interval.tick() => {
+                    GLOBAL_KEY_USAGE.prune().await;
+                    if let Err(err) = GLOBAL_KEY_USAGE.persist(store.clone(), &node).await {
+                        warn!("persist access key usage failed: {:?}", err);
+                    }
+                    if let Err(err) = GLOBAL_KEY_USAGE.reload_remote(store.clone(), &node).await {
+                        warn!("reload access key usage failed: {:?}", err);
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_is_sampled_per_key_and_ip() {
+        let tracker = KeyUsageTracker::default();
+        let start = OffsetDateTime::now_utc();
+
+        assert!(tracker.record_at("AKIA1", "10.0.0.1", start));
+        assert!(!tracker.record_at("AKIA1", "10.0.0.1", start + Duration::from_secs(10)));
+        assert!(tracker.record_at("AKIA1", "10.0.0.2", start + Duration::from_secs(20)));
+        assert!(tracker.record_at("AKIA1", "10.0.0.2", start + RECORD_RESOLUTION + Duration::from_secs(20)));
+        assert!(!tracker.record_at("", "10.0.0.1", start));
+
+        let last = tracker.last_used("AKIA1").expect("key should be recorded");
+        assert_eq!(last.source_ip, "10.0.0.2");
+        assert_eq!(last.time, start + RECORD_RESOLUTION + Duration::from_secs(20));
+        assert!(tracker.last_used("AKIA2").is_none());
+    }
+
+    #[test]
+    fn test_last_used_prefers_newest_node() {
+        let tracker = KeyUsageTracker::default();
+        let start = OffsetDateTime::now_utc();
+        tracker.record_at("AKIA1", "10.0.0.1", start);
+        tracker.remote.write().insert(
+            "AKIA1".to_string(),
+            AccessKeyLastUsed {
+                time: start + Duration::from_secs(5),
+                source_ip: "10.0.0.9".to_string(),
+            },
+        );
+
+        assert_eq!(tracker.last_used("AKIA1").map(|last| last.source_ip), Some("10.0.0.9".to_string()));
+
+        tracker.forget("AKIA1");
+        assert!(tracker.last_used("AKIA1").is_none());
+    }
+
+    #[test]
+    fn test_last_used_path_is_safe() {
+        assert!(last_used_path("node1:9000").ends_with("/last-used/node1_9000.json"));
+        assert!(last_used_path("").ends_with("/last-used/local.json"));
+    }
+}
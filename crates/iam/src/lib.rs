@@ -22,7 +22,9 @@ use tracing::{debug, instrument};
 
 pub mod cache;
 pub mod error;
+pub mod last_used;
 pub mod manager;
+pub mod oidc;
 pub mod store;
 pub mod utils;
 
@@ -13,16 +13,18 @@
 // limitations under the License.
 
 use crate::error::{Error, Result, is_err_config_not_found};
+use crate::last_used::GLOBAL_KEY_USAGE;
 use crate::sys::get_claims_from_token_with_secret;
 use crate::{
     cache::{Cache, CacheEntity},
     error::{Error as IamError, is_err_no_such_group, is_err_no_such_policy, is_err_no_such_user},
-    store::{GroupInfo, MappedPolicy, Store, UserType, object::IAM_CONFIG_PREFIX},
+    store::{GroupInfo, MappedPolicy, RevokedSession, Store, StsRevocationList, UserType, object::IAM_CONFIG_PREFIX},
     sys::{
         MAX_SVCSESSION_POLICY_SIZE, SESSION_POLICY_NAME, SESSION_POLICY_NAME_EXTRACTED, STATUS_DISABLED, STATUS_ENABLED,
         UpdateServiceAccountOpts,
     },
 };
+use rustfs_ecstore::config::change_log::{ConfigLogEvent, GLOBAL_CONFIG_LOG};
 use rustfs_ecstore::global::get_global_action_cred;
 use rustfs_madmin::{AccountStatus, AddOrUpdateUserReq, GroupDesc};
 use rustfs_policy::{
@@ -48,7 +50,7 @@ use time::OffsetDateTime;
 use tokio::{
     select,
     sync::{
-        mpsc,
+        broadcast, mpsc,
         mpsc::{Receiver, Sender},
     },
 };
@@ -56,6 +58,7 @@ use tracing::warn;
 use tracing::{error, info};
 
 const IAM_FORMAT_FILE: &str = "format.json";
+const IAM_STS_REVOCATION_FILE: &str = "sts-revoked.json";
 const IAM_FORMAT_VERSION_1: i32 = 1;
 
 #[derive(Serialize, Deserialize)]
@@ -75,6 +78,10 @@ fn get_iam_format_file_path() -> String {
     path_join_buf(&[&IAM_CONFIG_PREFIX, IAM_FORMAT_FILE])
 }
 
+fn get_sts_revocation_file_path() -> String {
+    path_join_buf(&[&IAM_CONFIG_PREFIX, IAM_STS_REVOCATION_FILE])
+}
+
 pub struct IamCache<T> {
     pub cache: Cache,
     pub api: T,
@@ -117,6 +124,7 @@ where
                 let s = Arc::clone(&self);
                 async move {
                     let ticker = tokio::time::interval(Duration::from_secs(120));
+                    let mut changes = GLOBAL_CONFIG_LOG.subscribe();
                     tokio::pin!(ticker, receiver);
                     loop {
                         select! {
@@ -142,6 +150,22 @@ where
                                     None => return,
                                 }
                             }
+                            event = changes.recv() => {
+                                let reload = match event {
+                                    Ok(ConfigLogEvent::Changed { path, .. }) => path.starts_with(IAM_CONFIG_PREFIX.as_str()),
+                                    Ok(ConfigLogEvent::Resync) | Err(broadcast::error::RecvError::Lagged(_)) => true,
+                                    Err(broadcast::error::RecvError::Closed) => return,
+                                };
+                                if reload {
+                                    // One reload covers the changes already queued behind this one
+                                    while changes.try_recv().is_ok() {}
+                                    info!("iam load config log change");
+                                    if let Err(err) = s.clone().load().await {
+                                        error!("iam load err {:?}", err);
+                                    }
+                                    ticker.reset();
+                                }
+                            }
                         }
                     }
                 }
@@ -158,6 +182,7 @@ where
     async fn load(self: Arc<Self>) -> Result<()> {
         // debug!("load iam to cache");
         self.api.load_all(&self.cache).await?;
+        self.load_sts_revocations().await?;
         self.last_timestamp
             .store(OffsetDateTime::now_utc().unix_timestamp(), Ordering::Relaxed);
         Ok(())
@@ -240,6 +265,108 @@ where
         Ok(())
     }
 
+    async fn read_sts_revocations(&self) -> Result<StsRevocationList> {
+        match self
+            .api
+            .load_iam_config::<StsRevocationList>(get_sts_revocation_file_path())
+            .await
+        {
+            Ok(list) => Ok(list),
+            Err(err) if is_err_config_not_found(&err) => Ok(StsRevocationList::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Reload the revoked STS sessions shared by all nodes.
+    pub async fn load_sts_revocations(&self) -> Result<()> {
+        let mut list = self.read_sts_revocations().await?;
+        list.prune(OffsetDateTime::now_utc());
+        self.cache.sts_revocations.store(Arc::new(list));
+        Ok(())
+    }
+
+    pub fn is_sts_revoked(&self, access_key: &str) -> bool {
+        self.cache.sts_revocations.load().sessions.contains_key(access_key)
+    }
+
+    /// Currently valid STS sessions of `parent_user`, or of every user if it is empty.
+    pub fn list_sts_sessions(&self, parent_user: &str) -> Vec<Credentials> {
+        let revocations = self.cache.sts_revocations.load();
+        let mut sessions: Vec<Credentials> = self
+            .cache
+            .sts_accounts
+            .load()
+            .values()
+            .filter(|u| u.credentials.is_temp() && !u.credentials.is_expired())
+            .filter(|u| parent_user.is_empty() || u.credentials.parent_user == parent_user)
+            .filter(|u| !revocations.sessions.contains_key(&u.credentials.access_key))
+            .map(|u| {
+                let mut c = u.credentials.clone();
+                c.secret_key = String::new();
+                c.session_token = String::new();
+                c
+            })
+            .collect();
+        sessions.sort_by(|a, b| a.access_key.cmp(&b.access_key));
+        sessions
+    }
+
+    /// Revoke the session `access_key` of `parent_user`, or all its sessions if `access_key` is
+    /// empty, returning the revoked access keys.
+    pub async fn revoke_sts_sessions(&self, parent_user: &str, access_key: &str) -> Result<Vec<String>> {
+        if parent_user.is_empty() && access_key.is_empty() {
+            return Err(Error::InvalidArgument);
+        }
+
+        let targets: Vec<Credentials> = self
+            .cache
+            .sts_accounts
+            .load()
+            .values()
+            .map(|u| &u.credentials)
+            .filter(|c| c.is_temp())
+            .filter(|c| access_key.is_empty() || c.access_key == access_key)
+            .filter(|c| parent_user.is_empty() || c.parent_user == parent_user)
+            .cloned()
+            .collect();
+
+        if !access_key.is_empty() && targets.is_empty() {
+            return Err(Error::NoSuchTempAccount(access_key.to_string()));
+        }
+        if targets.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let now = OffsetDateTime::now_utc();
+        let mut list = self.read_sts_revocations().await?;
+        list.prune(now);
+        for c in targets.iter() {
+            list.sessions.insert(
+                c.access_key.clone(),
+                RevokedSession {
+                    parent_user: c.parent_user.clone(),
+                    expiration: c.expiration,
+                    revoked_at: now,
+                },
+            );
+        }
+        self.api.save_iam_config(list.clone(), get_sts_revocation_file_path()).await?;
+        self.cache.sts_revocations.store(Arc::new(list));
+
+        let mut revoked = Vec::with_capacity(targets.len());
+        for c in targets {
+            if let Err(err) = self.api.delete_user_identity(&c.access_key, UserType::Sts).await
+                && !is_err_no_such_user(&err)
+            {
+                warn!("delete revoked sts identity {} failed: {}", c.access_key, err);
+            }
+            Cache::delete(&self.cache.sts_accounts, &c.access_key, OffsetDateTime::now_utc());
+            revoked.push(c.access_key);
+        }
+
+        Ok(revoked)
+    }
+
     // TODO: Check if exists, whether retry is possible
     #[tracing::instrument(level = "debug", skip(self))]
     async fn save_iam_formatter(self: Arc<Self>) -> Result<()> {
@@ -772,7 +899,8 @@ where
                     .filter(|v| v.status == STATUS_DISABLED)
                     .is_some()
                 {
-                    return Ok((Vec::new(), OffsetDateTime::now_utc()));
+                    // A disabled group grants nothing but must not revoke the member's own policies.
+                    continue;
                 }
 
                 let mp = match self.cache.group_policies.load().get(group) {
@@ -818,7 +946,7 @@ where
                 .filter(|v| v.status == STATUS_DISABLED)
                 .is_some()
             {
-                return Ok((Vec::new(), OffsetDateTime::now_utc()));
+                continue;
             }
 
             let mp = match self.cache.group_policies.load().get(group) {
@@ -950,6 +1078,7 @@ where
                 AccountStatus::Disabled
             },
             updated_at: u.update_at,
+            last_used: GLOBAL_KEY_USAGE.last_used(name),
             ..Default::default()
         };
 
@@ -985,6 +1114,7 @@ where
                     AccountStatus::Disabled
                 },
                 updated_at: v.update_at,
+                last_used: GLOBAL_KEY_USAGE.last_used(k),
                 ..Default::default()
             };
 
@@ -1041,6 +1171,7 @@ where
                     AccountStatus::Disabled
                 },
                 updated_at: v.update_at,
+                last_used: GLOBAL_KEY_USAGE.last_used(k),
                 ..Default::default()
             };
 
@@ -1280,11 +1411,9 @@ where
 
         let user_group_memberships = self.cache.user_group_memberships.load();
         members.iter().for_each(|member| {
-            if let Some(m) = user_group_memberships.get(member) {
-                let mut m = m.clone();
-                m.insert(group.to_string());
-                Cache::add_or_update(&self.cache.user_group_memberships, member, &m, OffsetDateTime::now_utc());
-            }
+            let mut m = user_group_memberships.get(member).cloned().unwrap_or_default();
+            m.insert(group.to_string());
+            Cache::add_or_update(&self.cache.user_group_memberships, member, &m, OffsetDateTime::now_utc());
         });
 
         Ok(OffsetDateTime::now_utc())
@@ -1335,6 +1464,19 @@ where
         })
     }
 
+    /// Groups `name` is a member of, sorted by name.
+    pub fn get_user_groups(&self, name: &str) -> Vec<String> {
+        let mut groups: Vec<String> = self
+            .cache
+            .user_group_memberships
+            .load()
+            .get(name)
+            .map(|m| m.iter().cloned().collect())
+            .unwrap_or_default();
+        groups.sort();
+        groups
+    }
+
     pub async fn list_groups(&self) -> Result<Vec<String>> {
         Ok(self.cache.groups.load().keys().cloned().collect())
     }
@@ -1439,6 +1581,7 @@ where
 
             Cache::delete(&self.cache.groups, group, OffsetDateTime::now_utc());
             Cache::delete(&self.cache.group_policies, group, OffsetDateTime::now_utc());
+            self.remove_group_from_memberships_map(group);
 
             return Ok(OffsetDateTime::now_utc());
         }
@@ -1460,11 +1603,9 @@ where
     fn update_group_memberships_map(&self, group: &str, gi: &GroupInfo) {
         let user_group_memberships = self.cache.user_group_memberships.load();
         for member in gi.members.iter() {
-            if let Some(m) = user_group_memberships.get(member) {
-                let mut m = m.clone();
-                m.insert(group.to_string());
-                Cache::add_or_update(&self.cache.user_group_memberships, member, &m, OffsetDateTime::now_utc());
-            }
+            let mut m = user_group_memberships.get(member).cloned().unwrap_or_default();
+            m.insert(group.to_string());
+            Cache::add_or_update(&self.cache.user_group_memberships, member, &m, OffsetDateTime::now_utc());
         }
     }
 
@@ -1577,6 +1718,10 @@ where
 
             if user_type == UserType::Sts {
                 Cache::delete(&self.cache.sts_accounts, name, OffsetDateTime::now_utc());
+                // The session may have been revoked on another node.
+                if let Err(err) = self.load_sts_revocations().await {
+                    warn!("reload sts revocations failed: {}", err);
+                }
             } else {
                 Cache::delete(&self.cache.users, name, OffsetDateTime::now_utc());
             }
@@ -0,0 +1,565 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! OpenID Connect identity providers used by STS `AssumeRoleWithWebIdentity`.
+//!
+//! Each provider validates ID tokens against the keys published at its JWKS endpoint. Keys are
+//! cached for the configured TTL and fetched again as soon as a token is signed with an unknown
+//! key id, so key rotation on the provider side is picked up without a restart.
+
+use crate::error::{Error, Result};
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{DecodingKey, Validation, dangerous::insecure_decode, decode, decode_header};
+use rustfs_config::oidc::*;
+use rustfs_policy::arn::ARN;
+use rustfs_policy::policy::get_policies_from_claims;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+use tokio::sync::{OnceCell, RwLock};
+use tracing::{info, warn};
+
+/// Name of the provider configured without a `_<NAME>` suffix.
+pub const DEFAULT_PROVIDER_NAME: &str = "default";
+
+/// Prefix of the parent user of credentials issued for a web identity.
+pub const OPENID_PARENT_PREFIX: &str = "openid:";
+
+/// Minimum delay between two key set fetches triggered by unknown key ids.
+const JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderConfig {
+    pub name: String,
+    pub config_url: String,
+    pub client_ids: Vec<String>,
+    pub issuer: String,
+    pub jwks_url: String,
+    pub claim_name: String,
+    pub claim_prefix: String,
+    /// Policies granted to every user of the provider. When set the provider is addressed by
+    /// its role ARN and the policy claim is ignored.
+    pub role_policy: Vec<String>,
+    pub jwks_ttl: Duration,
+}
+
+impl ProviderConfig {
+    /// Role ARN of a provider configured with a role policy.
+    pub fn role_arn(&self) -> Option<ARN> {
+        if self.role_policy.is_empty() {
+            return None;
+        }
+        ARN::new_iam_role_arn(&format!("openid-{}", self.name), "").ok()
+    }
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parse the provider configurations from `(key, value)` environment pairs.
+pub fn lookup_providers_from<I>(vars: I) -> Result<Vec<ProviderConfig>>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let vars: HashMap<String, String> = vars
+        .into_iter()
+        .filter(|(k, _)| ENV_IDENTITY_OPENID_KEYS.iter().any(|prefix| k.starts_with(prefix)))
+        .collect();
+
+    // A provider exists for every suffix of the config url or issuer key.
+    let mut suffixes = BTreeMap::new();
+    for key in vars.keys() {
+        for base in [ENV_IDENTITY_OPENID_CONFIG_URL, ENV_IDENTITY_OPENID_ISSUER] {
+            if let Some(suffix) = key.strip_prefix(base)
+                && (suffix.is_empty() || suffix.starts_with('_'))
+            {
+                let name = suffix.trim_start_matches('_').to_lowercase();
+                let name = if name.is_empty() {
+                    DEFAULT_PROVIDER_NAME.to_string()
+                } else {
+                    name
+                };
+                suffixes.insert(name, suffix.to_string());
+            }
+        }
+    }
+
+    let mut providers = Vec::with_capacity(suffixes.len());
+    for (name, suffix) in suffixes {
+        let get = |base: &str| {
+            vars.get(&format!("{base}{suffix}"))
+                .map(|v| v.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        let jwks_ttl = match get(ENV_IDENTITY_OPENID_JWKS_TTL) {
+            v if v.is_empty() => DEFAULT_IDENTITY_OPENID_JWKS_TTL,
+            v => v
+                .parse::<u64>()
+                .map_err(|_| Error::other(format!("openid provider {name}: invalid jwks ttl '{v}'")))?,
+        };
+
+        let claim_name = match get(ENV_IDENTITY_OPENID_CLAIM_NAME) {
+            v if v.is_empty() => DEFAULT_IDENTITY_OPENID_CLAIM_NAME.to_string(),
+            v => v,
+        };
+
+        let config = ProviderConfig {
+            config_url: get(ENV_IDENTITY_OPENID_CONFIG_URL),
+            client_ids: split_list(&get(ENV_IDENTITY_OPENID_CLIENT_ID)),
+            issuer: get(ENV_IDENTITY_OPENID_ISSUER),
+            jwks_url: get(ENV_IDENTITY_OPENID_JWKS_URL),
+            claim_name,
+            claim_prefix: get(ENV_IDENTITY_OPENID_CLAIM_PREFIX),
+            role_policy: split_list(&get(ENV_IDENTITY_OPENID_ROLE_POLICY)),
+            jwks_ttl: Duration::from_secs(jwks_ttl),
+            name,
+        };
+
+        if config.client_ids.is_empty() {
+            return Err(Error::other(format!("openid provider {}: client id is required", config.name)));
+        }
+        if config.config_url.is_empty() && (config.issuer.is_empty() || config.jwks_url.is_empty()) {
+            return Err(Error::other(format!(
+                "openid provider {}: either the config url or both issuer and jwks url are required",
+                config.name
+            )));
+        }
+        if !config.role_policy.is_empty() && config.role_arn().is_none() {
+            return Err(Error::other(format!("openid provider {}: name is not valid in a role ARN", config.name)));
+        }
+
+        providers.push(config);
+    }
+
+    Ok(providers)
+}
+
+pub fn lookup_providers() -> Result<Vec<ProviderConfig>> {
+    lookup_providers_from(std::env::vars())
+}
+
+#[derive(Debug, Clone)]
+struct CachedKeys {
+    set: JwkSet,
+    fetched_at: Instant,
+}
+
+/// Key set of a provider, refreshed when it expires or when an unknown key id is seen.
+#[derive(Debug)]
+pub struct JwksCache {
+    url: String,
+    ttl: Duration,
+    keys: RwLock<Option<CachedKeys>>,
+}
+
+impl JwksCache {
+    pub fn new(url: &str, ttl: Duration) -> Self {
+        Self {
+            url: url.to_string(),
+            ttl,
+            keys: RwLock::new(None),
+        }
+    }
+
+    pub async fn store(&self, set: JwkSet) {
+        *self.keys.write().await = Some(CachedKeys {
+            set,
+            fetched_at: Instant::now(),
+        });
+    }
+
+    fn find(set: &JwkSet, kid: Option<&str>) -> Option<Jwk> {
+        match kid {
+            Some(kid) => set.find(kid).cloned(),
+            // Without a key id the token can only be matched against a single published key.
+            None if set.keys.len() == 1 => set.keys.first().cloned(),
+            None => None,
+        }
+    }
+
+    async fn fetch(&self, client: &reqwest::Client) -> Result<JwkSet> {
+        let resp = client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| Error::other(format!("fetch jwks from {} failed: {e}", self.url)))?;
+
+        resp.json::<JwkSet>()
+            .await
+            .map_err(|e| Error::other(format!("parse jwks from {} failed: {e}", self.url)))
+    }
+
+    pub async fn get_key(&self, client: &reqwest::Client, kid: Option<&str>) -> Result<Jwk> {
+        let cached = self.keys.read().await.clone();
+        if let Some(cached) = &cached {
+            let age = cached.fetched_at.elapsed();
+            match Self::find(&cached.set, kid) {
+                Some(key) if age < self.ttl => return Ok(key),
+                None if age < JWKS_MIN_REFRESH_INTERVAL => return Err(Error::InvalidToken),
+                _ => {}
+            }
+        }
+
+        match self.fetch(client).await {
+            Ok(set) => {
+                let key = Self::find(&set, kid);
+                self.store(set).await;
+                key.ok_or(Error::InvalidToken)
+            }
+            Err(err) => {
+                // Keep serving a known key while the provider is unreachable.
+                if let Some(key) = cached.as_ref().and_then(|c| Self::find(&c.set, kid)) {
+                    warn!("{err}, using the cached key set");
+                    return Ok(key);
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscoveryDocument {
+    issuer: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug)]
+struct Endpoints {
+    issuer: String,
+    jwks: JwksCache,
+}
+
+/// A configured provider, resolving its endpoints from the discovery document on first use.
+#[derive(Debug)]
+pub struct OpenIdProvider {
+    config: ProviderConfig,
+    client: reqwest::Client,
+    endpoints: OnceCell<Endpoints>,
+}
+
+impl OpenIdProvider {
+    pub fn new(config: ProviderConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(DISCOVERY_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            config,
+            client,
+            endpoints: OnceCell::new(),
+        }
+    }
+
+    pub fn config(&self) -> &ProviderConfig {
+        &self.config
+    }
+
+    async fn endpoints(&self) -> Result<&Endpoints> {
+        self.endpoints
+            .get_or_try_init(|| async {
+                let (mut issuer, mut jwks_url) = (self.config.issuer.clone(), self.config.jwks_url.clone());
+                if issuer.is_empty() || jwks_url.is_empty() {
+                    let doc = self
+                        .client
+                        .get(&self.config.config_url)
+                        .send()
+                        .await
+                        .and_then(|resp| resp.error_for_status())
+                        .map_err(|e| Error::other(format!("fetch {} failed: {e}", self.config.config_url)))?
+                        .json::<DiscoveryDocument>()
+                        .await
+                        .map_err(|e| Error::other(format!("parse {} failed: {e}", self.config.config_url)))?;
+                    if issuer.is_empty() {
+                        issuer = doc.issuer;
+                    }
+                    if jwks_url.is_empty() {
+                        jwks_url = doc.jwks_uri;
+                    }
+                }
+
+                Ok::<_, Error>(Endpoints {
+                    jwks: JwksCache::new(&jwks_url, self.config.jwks_ttl),
+                    issuer,
+                })
+            })
+            .await
+    }
+
+    pub async fn issuer(&self) -> Result<String> {
+        Ok(self.endpoints().await?.issuer.clone())
+    }
+
+    pub async fn jwks(&self) -> Result<&JwksCache> {
+        Ok(&self.endpoints().await?.jwks)
+    }
+
+    /// Verify the signature, issuer, audience and expiry of `token` and return its claims.
+    pub async fn validate(&self, token: &str) -> Result<HashMap<String, Value>> {
+        let header = decode_header(token).map_err(Error::JWTError)?;
+        let endpoints = self.endpoints().await?;
+
+        let jwk = endpoints.jwks.get_key(&self.client, header.kid.as_deref()).await?;
+        let key = DecodingKey::from_jwk(&jwk).map_err(Error::JWTError)?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&self.config.client_ids);
+        validation.set_issuer(&[&endpoints.issuer]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let data = decode::<HashMap<String, Value>>(token, &key, &validation).map_err(Error::JWTError)?;
+        Ok(data.claims)
+    }
+
+    /// Policies granted by validated `claims`: the role policy, else the values of the
+    /// configured claim with the claim prefix prepended.
+    pub fn policies(&self, claims: &HashMap<String, Value>) -> Result<Vec<String>> {
+        if !self.config.role_policy.is_empty() {
+            return Ok(self.config.role_policy.clone());
+        }
+
+        let (values, _) = get_policies_from_claims(claims, &self.config.claim_name);
+        let mut policies: Vec<String> = values
+            .into_iter()
+            .map(|v| format!("{}{v}", self.config.claim_prefix))
+            .collect();
+        policies.sort();
+        policies.dedup();
+
+        if policies.is_empty() {
+            return Err(Error::NoSuchPolicy);
+        }
+        Ok(policies)
+    }
+
+    /// Parent user of the credentials issued for the subject of `claims`.
+    pub fn parent_user(&self, claims: &HashMap<String, Value>) -> String {
+        let sub = claims.get("sub").and_then(Value::as_str).unwrap_or_default();
+        format!("{OPENID_PARENT_PREFIX}{}:{sub}", self.config.name)
+    }
+}
+
+/// All configured providers.
+#[derive(Debug, Default)]
+pub struct OpenIdSys {
+    providers: Vec<OpenIdProvider>,
+}
+
+impl OpenIdSys {
+    pub fn new(configs: Vec<ProviderConfig>) -> Self {
+        Self {
+            providers: configs.into_iter().map(OpenIdProvider::new).collect(),
+        }
+    }
+
+    /// Load the providers from the environment, disabling web identities on a bad configuration.
+    pub fn from_env() -> Self {
+        match lookup_providers() {
+            Ok(configs) => {
+                for config in configs.iter() {
+                    info!("openid provider {} enabled", config.name);
+                }
+                Self::new(configs)
+            }
+            Err(err) => {
+                warn!("load openid configuration failed: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.providers.is_empty()
+    }
+
+    pub fn providers(&self) -> &[OpenIdProvider] {
+        &self.providers
+    }
+
+    /// Role ARNs of the providers configured with a role policy, with their policies.
+    pub fn roles(&self) -> Vec<(ARN, String)> {
+        self.providers
+            .iter()
+            .filter_map(|p| p.config.role_arn().map(|arn| (arn, p.config.role_policy.join(","))))
+            .collect()
+    }
+
+    /// Pick the provider for a login: the one owning `role_arn` if given, else the claim based
+    /// provider whose issuer matches the (not yet verified) `iss` claim of `token`.
+    pub async fn provider_for(&self, role_arn: &str, token: &str) -> Result<&OpenIdProvider> {
+        if !role_arn.is_empty() {
+            let arn = ARN::parse(role_arn).map_err(|_| Error::NoSuchIdentityProvider(role_arn.to_string()))?;
+            return self
+                .providers
+                .iter()
+                .find(|p| p.config.role_arn().is_some_and(|a| a == arn))
+                .ok_or_else(|| Error::NoSuchIdentityProvider(role_arn.to_string()));
+        }
+
+        let unverified = insecure_decode::<HashMap<String, Value>>(token).map_err(Error::JWTError)?;
+        let iss = unverified.claims.get("iss").and_then(Value::as_str).unwrap_or_default();
+
+        let mut candidates = self.providers.iter().filter(|p| p.config.role_policy.is_empty());
+        if self.providers.len() == 1 {
+            return candidates
+                .next()
+                .ok_or_else(|| Error::NoSuchIdentityProvider(iss.to_string()));
+        }
+        for provider in candidates {
+            if provider.issuer().await.is_ok_and(|issuer| issuer == iss) {
+                return Ok(provider);
+            }
+        }
+
+        Err(Error::NoSuchIdentityProvider(iss.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+    use serde_json::json;
+
+    const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn static_provider(role_policy: &str) -> OpenIdProvider {
+        let configs = lookup_providers_from(vars(&[
+            (ENV_IDENTITY_OPENID_ISSUER, "https://idp.example.com"),
+            (ENV_IDENTITY_OPENID_JWKS_URL, "http://127.0.0.1:1/jwks"),
+            (ENV_IDENTITY_OPENID_CLIENT_ID, "rustfs"),
+            (ENV_IDENTITY_OPENID_CLAIM_PREFIX, "oidc-"),
+            (ENV_IDENTITY_OPENID_ROLE_POLICY, role_policy),
+        ]))
+        .unwrap();
+        OpenIdProvider::new(configs.into_iter().next().unwrap())
+    }
+
+    fn key_set(kid: &str) -> JwkSet {
+        serde_json::from_value(json!({
+            "keys": [{
+                "kty": "oct",
+                "kid": kid,
+                "alg": "HS256",
+                "k": base64_simd::URL_SAFE_NO_PAD.encode_to_string(SECRET),
+            }]
+        }))
+        .unwrap()
+    }
+
+    fn token(kid: &str, claims: Value) -> String {
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some(kid.to_string());
+        encode(&header, &claims, &EncodingKey::from_secret(SECRET)).unwrap()
+    }
+
+    fn claims(aud: &str, exp_offset: i64) -> Value {
+        json!({
+            "iss": "https://idp.example.com",
+            "aud": aud,
+            "sub": "alice",
+            "exp": jsonwebtoken::get_current_timestamp() as i64 + exp_offset,
+            "policy": ["readonly", "diagnostics,readwrite"],
+        })
+    }
+
+    #[test]
+    fn test_lookup_multiple_providers() {
+        let configs = lookup_providers_from(vars(&[
+            (ENV_IDENTITY_OPENID_CONFIG_URL, "https://a.example.com/.well-known/openid-configuration"),
+            (ENV_IDENTITY_OPENID_CLIENT_ID, "a1, a2"),
+            (
+                "RUSTFS_IDENTITY_OPENID_CONFIG_URL_KEYCLOAK",
+                "https://k.example.com/.well-known/openid-configuration",
+            ),
+            ("RUSTFS_IDENTITY_OPENID_CLIENT_ID_KEYCLOAK", "k"),
+            ("RUSTFS_IDENTITY_OPENID_CLAIM_NAME_KEYCLOAK", "groups"),
+            ("RUSTFS_IDENTITY_OPENID_ROLE_POLICY_KEYCLOAK", "readonly"),
+            ("RUSTFS_UNRELATED", "x"),
+        ]))
+        .unwrap();
+
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].name, DEFAULT_PROVIDER_NAME);
+        assert_eq!(configs[0].client_ids, vec!["a1".to_string(), "a2".to_string()]);
+        assert_eq!(configs[0].claim_name, DEFAULT_IDENTITY_OPENID_CLAIM_NAME);
+        assert!(configs[0].role_arn().is_none());
+        assert_eq!(configs[1].name, "keycloak");
+        assert_eq!(configs[1].claim_name, "groups");
+        assert_eq!(
+            configs[1].role_arn().map(|a| a.to_string()),
+            Some("arn:rustfs:iam:::role/openid-keycloak".to_string())
+        );
+
+        assert!(lookup_providers_from(vars(&[(ENV_IDENTITY_OPENID_CONFIG_URL, "https://a.example.com")])).is_err());
+        assert!(lookup_providers_from(vars(&[(ENV_IDENTITY_OPENID_ISSUER, "https://a.example.com")])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_token() {
+        let provider = static_provider("");
+        provider.jwks().await.unwrap().store(key_set("k1")).await;
+
+        let claims = provider.validate(&token("k1", claims("rustfs", 600))).await.unwrap();
+        assert_eq!(claims.get("sub"), Some(&json!("alice")));
+        assert_eq!(
+            provider.policies(&claims).unwrap(),
+            vec![
+                "oidc-diagnostics".to_string(),
+                "oidc-readonly".to_string(),
+                "oidc-readwrite".to_string()
+            ]
+        );
+        assert_eq!(provider.parent_user(&claims), "openid:default:alice");
+
+        // Wrong audience, expired token and unknown key id are rejected.
+        assert!(provider.validate(&token("k1", claims("other", 600))).await.is_err());
+        assert!(provider.validate(&token("k1", claims("rustfs", -600))).await.is_err());
+        assert!(provider.validate(&token("k2", claims("rustfs", 600))).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_role_policy_provider() {
+        let provider = static_provider("readonly,diagnostics");
+        assert_eq!(
+            provider.policies(&HashMap::new()).unwrap(),
+            vec!["readonly".to_string(), "diagnostics".to_string()]
+        );
+
+        let sys = OpenIdSys {
+            providers: vec![provider],
+        };
+        let roles = sys.roles();
+        assert_eq!(roles.len(), 1);
+        assert_eq!(roles[0].1, "readonly,diagnostics");
+        assert!(sys.provider_for(&roles[0].0.to_string(), "").await.is_ok());
+        assert!(sys.provider_for("arn:rustfs:iam:::role/openid-other", "").await.is_err());
+    }
+}
@@ -16,6 +16,7 @@ pub mod object;
 
 use crate::cache::Cache;
 use crate::error::Result;
+use rustfs_madmin::{IAMGroupInfo, IAMPolicyMapping};
 use rustfs_policy::{auth::UserIdentity, policy::PolicyDoc};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::collections::{HashMap, HashSet};
@@ -171,3 +172,83 @@ impl GroupInfo {
         }
     }
 }
+
+impl From<MappedPolicy> for IAMPolicyMapping {
+    fn from(mp: MappedPolicy) -> Self {
+        Self {
+            version: mp.version,
+            policy: mp.policies,
+            updated_at: Some(mp.update_at),
+        }
+    }
+}
+
+impl From<GroupInfo> for IAMGroupInfo {
+    fn from(gi: GroupInfo) -> Self {
+        Self {
+            version: gi.version,
+            status: gi.status,
+            members: gi.members,
+            updated_at: gi.update_at,
+        }
+    }
+}
+
+/// A revoked STS session, kept until the credentials would have expired anyway.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RevokedSession {
+    pub parent_user: String,
+    pub expiration: Option<OffsetDateTime>,
+    pub revoked_at: OffsetDateTime,
+}
+
+impl RevokedSession {
+    pub fn is_expired(&self, now: OffsetDateTime) -> bool {
+        self.expiration.is_some_and(|exp| exp < now)
+    }
+}
+
+/// Revoked STS sessions keyed by access key, shared by all nodes through the IAM config.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StsRevocationList {
+    #[serde(default)]
+    pub sessions: HashMap<String, RevokedSession>,
+}
+
+impl StsRevocationList {
+    /// Drop the entries whose credentials have expired since they were revoked.
+    pub fn prune(&mut self, now: OffsetDateTime) {
+        self.sessions.retain(|_, s| !s.is_expired(now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+
+    #[test]
+    fn test_revocation_list_prune() {
+        let now = OffsetDateTime::now_utc();
+        let session = |expiration: Option<OffsetDateTime>| RevokedSession {
+            parent_user: "alice".to_string(),
+            expiration,
+            revoked_at: now,
+        };
+
+        let mut list = StsRevocationList::default();
+        list.sessions
+            .insert("expired".to_string(), session(Some(now - Duration::minutes(1))));
+        list.sessions
+            .insert("valid".to_string(), session(Some(now + Duration::minutes(1))));
+        list.sessions.insert("no-expiry".to_string(), session(None));
+        list.prune(now);
+
+        let mut keys: Vec<&String> = list.sessions.keys().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["no-expiry", "valid"]);
+
+        let decoded: StsRevocationList = serde_json::from_slice(&serde_json::to_vec(&list).unwrap()).unwrap();
+        assert_eq!(decoded, list);
+    }
+}
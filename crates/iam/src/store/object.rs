@@ -25,7 +25,8 @@ use rustfs_ecstore::store_api::{ObjectInfoOrErr, WalkOptions};
 use rustfs_ecstore::{
     config::{
         RUSTFS_CONFIG_PREFIX,
-        com::{delete_config, read_config, read_config_with_metadata, save_config},
+        change_log::{ConfigChange, GLOBAL_CONFIG_LOG},
+        com::{read_config, read_config_with_metadata},
     },
     global::get_global_action_cred,
     store::ECStore,
@@ -403,11 +404,26 @@ impl Store for ObjectStore {
         let mut data = serde_json::to_vec(&item)?;
         data = Self::encrypt_data(&data)?;
 
-        save_config(self.object_api.clone(), path.as_ref(), data).await?;
+        GLOBAL_CONFIG_LOG
+            .append(
+                self.object_api.clone(),
+                ConfigChange::Put {
+                    path: path.as_ref().to_string(),
+                    data,
+                },
+            )
+            .await?;
         Ok(())
     }
     async fn delete_iam_config(&self, path: impl AsRef<str> + Send) -> Result<()> {
-        delete_config(self.object_api.clone(), path.as_ref()).await?;
+        GLOBAL_CONFIG_LOG
+            .append(
+                self.object_api.clone(),
+                ConfigChange::Delete {
+                    path: path.as_ref().to_string(),
+                },
+            )
+            .await?;
         Ok(())
     }
 
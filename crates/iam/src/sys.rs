@@ -19,6 +19,7 @@ use crate::error::{Error, Result};
 use crate::manager::IamCache;
 use crate::manager::extract_jwt_claims;
 use crate::manager::get_default_policyes;
+use crate::oidc::{OPENID_PARENT_PREFIX, OpenIdSys};
 use crate::store::GroupInfo;
 use crate::store::MappedPolicy;
 use crate::store::Store;
@@ -34,12 +35,15 @@ use rustfs_policy::auth::{
     ACCOUNT_ON, UserIdentity, contains_reserved_chars, create_new_credentials_with_metadata, generate_credentials,
     is_access_key_valid, is_secret_key_valid,
 };
-use rustfs_policy::policy::Args;
 use rustfs_policy::policy::opa;
-use rustfs_policy::policy::{EMBEDDED_POLICY_TYPE, INHERITED_POLICY_TYPE, Policy, PolicyDoc, iam_policy_claim_name_sa};
+use rustfs_policy::policy::{Args, Evaluation, Statement};
+use rustfs_policy::policy::{
+    BUCKET_ACCESS_ACTIONS, EMBEDDED_POLICY_TYPE, INHERITED_POLICY_TYPE, Policy, PolicyDoc, iam_policy_claim_name_sa,
+};
+use serde::Serialize;
 use serde_json::Value;
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 use std::sync::OnceLock;
 use time::OffsetDateTime;
@@ -64,6 +68,7 @@ fn get_policy_plugin_client() -> Arc<RwLock<Option<rustfs_policy::policy::opa::A
 pub struct IamSys<T> {
     store: Arc<IamCache<T>>,
     roles_map: HashMap<ARN, String>,
+    openid: Arc<OpenIdSys>,
 }
 
 impl<T: Store> IamSys<T> {
@@ -82,11 +87,20 @@ impl<T: Store> IamSys<T> {
             };
         });
 
+        let openid = Arc::new(OpenIdSys::from_env());
+        let roles_map = openid.roles().into_iter().collect();
+
         Self {
             store,
-            roles_map: HashMap::new(),
+            roles_map,
+            openid,
         }
     }
+
+    pub fn openid(&self) -> Arc<OpenIdSys> {
+        self.openid.clone()
+    }
+
     pub fn has_watcher(&self) -> bool {
         self.store.api.has_watcher()
     }
@@ -429,6 +443,23 @@ impl<T: Store> IamSys<T> {
         self.store.list_sts_accounts(access_key).await
     }
 
+    /// Currently valid STS sessions of `parent_user`, or of every user if it is empty.
+    pub fn list_sts_sessions(&self, parent_user: &str) -> Vec<Credentials> {
+        self.store.list_sts_sessions(parent_user)
+    }
+
+    /// Revoke one STS session, or all sessions of `parent_user` when `access_key` is empty.
+    /// Other nodes drop the revoked sessions from their cache and reload the revocation list.
+    pub async fn revoke_sts_sessions(&self, parent_user: &str, access_key: &str) -> Result<Vec<String>> {
+        let revoked = self.store.revoke_sts_sessions(parent_user, access_key).await?;
+
+        for access_key in revoked.iter() {
+            self.notify_for_user(access_key, true).await;
+        }
+
+        Ok(revoked)
+    }
+
     pub async fn get_service_account(&self, access_key: &str) -> Result<(Credentials, Option<Policy>)> {
         let (mut da, policy) = self.get_service_account_internal(access_key).await?;
 
@@ -620,6 +651,10 @@ impl<T: Store> IamSys<T> {
             }
         }
 
+        if self.store.is_sts_revoked(access_key) {
+            return Ok((None, false));
+        }
+
         match self.store.get_user(access_key).await {
             Some(res) => {
                 let ok = res.credentials.is_valid();
@@ -666,6 +701,23 @@ impl<T: Store> IamSys<T> {
         Ok(updated_at)
     }
 
+    /// Delete a group and its policy mapping; a group with members is only deleted when `force` is set.
+    pub async fn delete_group(&self, group: &str, force: bool) -> Result<OffsetDateTime> {
+        if force {
+            let members = self.store.get_group_description(group).await?.members;
+            if !members.is_empty() {
+                self.store.remove_members_from_group(group, members, false).await?;
+            }
+        }
+
+        self.remove_users_from_group(group, Vec::new()).await
+    }
+
+    /// Groups `name` is a member of, sorted by name.
+    pub fn get_user_groups(&self, name: &str) -> Vec<String> {
+        self.store.get_user_groups(name)
+    }
+
     pub async fn set_group_status(&self, group: &str, enable: bool) -> Result<OffsetDateTime> {
         let updated_at = self.store.set_group_status(group, enable).await?;
 
@@ -716,11 +768,14 @@ impl<T: Store> IamSys<T> {
                 let Ok(arn) = ARN::parse(role_arn.unwrap_or_default()) else { return false };
 
                 MappedPolicy::new(self.roles_map.get(&arn).map_or_else(String::default, |v| v.clone()).as_str()).to_slice()
+            } else if parent_user.starts_with(OPENID_PARENT_PREFIX) {
+                // Web identities have no stored mapping, the policies come with the signed claims.
+                let (p, _) = args.get_policies(POLICYNAME);
+                p.into_iter().collect()
             } else {
                 let Ok(p) = self.policy_db_get(parent_user, args.groups).await else { return false };
 
                 p
-                //TODO: FROM JWT
             }
         };
 
@@ -846,6 +901,185 @@ impl<T: Store> IamSys<T> {
 
         self.get_combined_policy(&policies).await.is_allowed(args)
     }
+
+    /// Bucket-level pre-check: whether the caller described by `args` may see `args.bucket` at all,
+    /// i.e. is allowed any of [`BUCKET_ACCESS_ACTIONS`] on it.
+    pub async fn is_bucket_allowed(&self, args: &Args<'_>) -> bool {
+        for action in BUCKET_ACCESS_ACTIONS {
+            let bucket_args = Args {
+                action,
+                object: "",
+                ..args.clone()
+            };
+            if self.is_allowed(&bucket_args).await {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Explain the decision [`IamSys::is_allowed`] takes for `args`.
+    ///
+    /// The decision itself comes from `is_allowed`, the sources list what every policy attached to
+    /// the account (directly, through its groups, its role or its session) says about the request.
+    pub async fn simulate(&self, args: &Args<'_>) -> PolicySimulation {
+        let mut sim = PolicySimulation {
+            allowed: self.is_allowed(args).await,
+            ..Default::default()
+        };
+
+        if args.is_owner {
+            sim.account_type = AccountType::Owner;
+            return sim;
+        }
+
+        if Self::get_policy_plugin_client().await.is_some() {
+            sim.note = "decided by the external policy plugin".to_string();
+            return sim;
+        }
+
+        let (is_temp, parent_user) = self.is_temp_user(args.account).await.unwrap_or_default();
+        let (is_svc, parent_user) = if is_temp {
+            (false, parent_user)
+        } else {
+            self.is_service_account(args.account).await.unwrap_or_default()
+        };
+
+        sim.account_type = if is_temp {
+            AccountType::Sts
+        } else if is_svc {
+            AccountType::ServiceAccount
+        } else {
+            AccountType::User
+        };
+
+        let policy_user = if is_temp || is_svc {
+            parent_user.as_str()
+        } else {
+            args.account
+        };
+        let eval_args = Args {
+            account: if is_svc { parent_user.as_str() } else { args.account },
+            ..args.clone()
+        };
+
+        if (is_temp || is_svc) && get_global_action_cred().is_some_and(|cred| cred.access_key == parent_user) {
+            sim.note = format!("parent user {parent_user} is the root account");
+        } else if let Some(role_arn) = args.get_role_arn() {
+            let names = ARN::parse(role_arn)
+                .ok()
+                .and_then(|arn| self.roles_map.get(&arn).cloned())
+                .unwrap_or_default();
+            let source = self
+                .evaluate_source(format!("role:{role_arn}"), MappedPolicy::new(&names).to_slice(), &eval_args)
+                .await;
+            sim.sources.push(source);
+        } else if is_temp && policy_user.starts_with(OPENID_PARENT_PREFIX) {
+            let (names, _) = args.get_policies(POLICYNAME);
+            let mut names: Vec<String> = names.into_iter().collect();
+            names.sort();
+            let source = self
+                .evaluate_source(format!("web-identity:{policy_user}"), names, &eval_args)
+                .await;
+            sim.sources.push(source);
+        } else {
+            let names = self
+                .store
+                .get_mapped_policy(policy_user, false)
+                .await
+                .map(|p| p.to_slice())
+                .unwrap_or_default();
+            let source = self.evaluate_source(format!("user:{policy_user}"), names, &eval_args).await;
+            sim.sources.push(source);
+
+            let mut groups: BTreeSet<String> = self.get_user_groups(policy_user).into_iter().collect();
+            groups.extend(args.groups.iter().flatten().cloned());
+            for group in groups.iter() {
+                let names = self
+                    .store
+                    .get_mapped_policy(group, true)
+                    .await
+                    .map(|p| p.to_slice())
+                    .unwrap_or_default();
+                let source = self.evaluate_source(format!("group:{group}"), names, &eval_args).await;
+                sim.sources.push(source);
+            }
+        }
+
+        let inherited = is_svc
+            && args
+                .claims
+                .get(&iam_policy_claim_name_sa())
+                .and_then(|v| v.as_str())
+                .is_some_and(|v| v == INHERITED_POLICY_TYPE);
+        if !inherited
+            && let Some(session_policy) = args
+                .claims
+                .get(SESSION_POLICY_NAME_EXTRACTED)
+                .and_then(|v| v.as_str())
+                .and_then(|v| Policy::parse_config(v.as_bytes()).ok())
+        {
+            let session_args = Args {
+                is_owner: false,
+                ..args.clone()
+            };
+            sim.sources.push(PolicySourceEvaluation {
+                source: if is_svc { "service-account" } else { "session" }.to_string(),
+                policies: Vec::new(),
+                evaluation: session_policy.evaluate(&session_args),
+            });
+        }
+
+        sim
+    }
+
+    async fn evaluate_source(&self, source: String, policies: Vec<String>, args: &Args<'_>) -> PolicySourceEvaluation {
+        let policy = if policies.is_empty() {
+            Policy::default()
+        } else {
+            self.store.merge_policies(&policies.join(",")).await.1
+        };
+
+        PolicySourceEvaluation {
+            source,
+            policies,
+            evaluation: policy.evaluate(args),
+        }
+    }
+}
+
+/// Kind of account a policy simulation was run for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AccountType {
+    Owner,
+    #[default]
+    User,
+    Sts,
+    ServiceAccount,
+}
+
+/// What one policy source attached to an account says about a request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicySourceEvaluation {
+    /// `user:<name>`, `group:<name>`, `role:<arn>`, `session` or `service-account`.
+    pub source: String,
+    /// Names of the policies mapped to the source, empty for inline policies.
+    pub policies: Vec<String>,
+    #[serde(flatten)]
+    pub evaluation: Evaluation<Statement>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicySimulation {
+    pub allowed: bool,
+    pub account_type: AccountType,
+    pub sources: Vec<PolicySourceEvaluation>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub note: String,
 }
 
 fn is_allowed_by_session_policy(args: &Args<'_>) -> (bool, bool) {
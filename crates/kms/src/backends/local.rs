@@ -58,6 +58,17 @@ struct StoredMasterKey {
     encrypted_key_material: Vec<u8>,
     /// Nonce used for encryption
     nonce: Vec<u8>,
+    /// Key material of the versions retired by rotations, kept to unwrap the data keys they wrapped
+    #[serde(default)]
+    previous_versions: Vec<StoredKeyVersion>,
+}
+
+/// Key material of a retired master key version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredKeyVersion {
+    version: u32,
+    encrypted_key_material: Vec<u8>,
+    nonce: Vec<u8>,
 }
 
 /// Data key envelope stored with each data key generation
@@ -70,6 +81,9 @@ struct DataKeyEnvelope {
     nonce: Vec<u8>,
     encryption_context: HashMap<String, String>,
     created_at: chrono::DateTime<chrono::Utc>,
+    /// Version of the master key that wrapped the data key, the current one when absent
+    #[serde(default)]
+    master_key_version: Option<u32>,
 }
 
 impl LocalKmsClient {
@@ -174,6 +188,21 @@ impl LocalKmsClient {
             (key_material.to_vec(), Vec::new())
         };
 
+        // A rotation retires the current version, its material stays for the data keys it wrapped
+        let mut previous_versions = Vec::new();
+        if let Ok(content) = fs::read(&key_path).await
+            && let Ok(existing) = serde_json::from_slice::<StoredMasterKey>(&content)
+        {
+            previous_versions = existing.previous_versions;
+            if existing.version < master_key.version {
+                previous_versions.push(StoredKeyVersion {
+                    version: existing.version,
+                    encrypted_key_material: existing.encrypted_key_material,
+                    nonce: existing.nonce,
+                });
+            }
+        }
+
         let stored_key = StoredMasterKey {
             key_id: master_key.key_id.clone(),
             version: master_key.version,
@@ -187,6 +216,7 @@ impl LocalKmsClient {
             created_by: master_key.created_by.clone(),
             encrypted_key_material,
             nonce,
+            previous_versions,
         };
 
         let content = serde_json::to_vec_pretty(&stored_key)?;
@@ -216,8 +246,9 @@ impl LocalKmsClient {
         key_material
     }
 
-    /// Get the actual key material for a master key
-    async fn get_key_material(&self, key_id: &str) -> Result<Vec<u8>> {
+    /// Get the actual key material of a master key version, the current one when `version` is None,
+    /// along with the version
+    async fn get_key_material(&self, key_id: &str, version: Option<u32>) -> Result<(Vec<u8>, u32)> {
         let key_path = self.master_key_path(key_id);
 
         if !key_path.exists() {
@@ -227,28 +258,41 @@ impl LocalKmsClient {
         let content = fs::read(&key_path).await?;
         let stored_key: StoredMasterKey = serde_json::from_slice(&content)?;
 
+        let (version, encrypted_key_material, stored_nonce) = match version {
+            Some(version) if version != stored_key.version => {
+                let retired = stored_key
+                    .previous_versions
+                    .into_iter()
+                    .find(|v| v.version == version)
+                    .ok_or_else(|| KmsError::key_not_found(format!("{key_id} version {version}")))?;
+                (retired.version, retired.encrypted_key_material, retired.nonce)
+            }
+            _ => (stored_key.version, stored_key.encrypted_key_material, stored_key.nonce),
+        };
+
         // Decrypt key material if master cipher is available
         let key_material = if let Some(ref cipher) = self.master_cipher {
-            if stored_key.nonce.len() != 12 {
+            if stored_nonce.len() != 12 {
                 return Err(KmsError::cryptographic_error("nonce", "Invalid nonce length"));
             }
             let mut nonce_array = [0u8; 12];
-            nonce_array.copy_from_slice(&stored_key.nonce);
+            nonce_array.copy_from_slice(&stored_nonce);
             let nonce = Nonce::from(nonce_array);
             cipher
-                .decrypt(&nonce, stored_key.encrypted_key_material.as_ref())
+                .decrypt(&nonce, encrypted_key_material.as_ref())
                 .map_err(|e| KmsError::cryptographic_error("decrypt", e.to_string()))?
         } else {
-            stored_key.encrypted_key_material
+            encrypted_key_material
         };
 
-        Ok(key_material)
+        Ok((key_material, version))
     }
 
-    /// Encrypt data using a master key
-    async fn encrypt_with_master_key(&self, key_id: &str, plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+    /// Encrypt data using the current version of a master key, returns the ciphertext, the nonce and
+    /// the version used
+    async fn encrypt_with_master_key(&self, key_id: &str, plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>, u32)> {
         // Load the actual master key material
-        let key_material = self.get_key_material(key_id).await?;
+        let (key_material, version) = self.get_key_material(key_id, None).await?;
         let key = Key::<Aes256Gcm>::try_from(key_material.as_slice())
             .map_err(|_| KmsError::cryptographic_error("key", "Invalid key length"))?;
         let cipher = Aes256Gcm::new(&key);
@@ -262,16 +306,22 @@ impl LocalKmsClient {
             .encrypt(&nonce, plaintext)
             .map_err(|e| KmsError::cryptographic_error("encrypt", e.to_string()))?;
 
-        Ok((ciphertext, nonce_bytes.to_vec()))
+        Ok((ciphertext, nonce_bytes.to_vec(), version))
     }
 
-    /// Decrypt data using a master key
-    async fn decrypt_with_master_key(&self, key_id: &str, ciphertext: &[u8], nonce: &[u8]) -> Result<Vec<u8>> {
+    /// Decrypt data using a master key version, the current one when `version` is None
+    async fn decrypt_with_master_key(
+        &self,
+        key_id: &str,
+        version: Option<u32>,
+        ciphertext: &[u8],
+        nonce: &[u8],
+    ) -> Result<Vec<u8>> {
         if nonce.len() != 12 {
             return Err(KmsError::cryptographic_error("nonce", "Invalid nonce length"));
         }
         // Load the actual master key material
-        let key_material = self.get_key_material(key_id).await?;
+        let (key_material, _) = self.get_key_material(key_id, version).await?;
         let key = Key::<Aes256Gcm>::try_from(key_material.as_slice())
             .map_err(|_| KmsError::cryptographic_error("key", "Invalid key length"))?;
         let cipher = Aes256Gcm::new(&key);
@@ -307,7 +357,7 @@ impl KmsClient for LocalKmsClient {
         rand::rng().fill(&mut plaintext_key[..]);
 
         // Encrypt the data key with the master key
-        let (encrypted_key, nonce) = self.encrypt_with_master_key(&request.master_key_id, &plaintext_key).await?;
+        let (encrypted_key, nonce, version) = self.encrypt_with_master_key(&request.master_key_id, &plaintext_key).await?;
 
         // Create data key envelope
         let envelope = DataKeyEnvelope {
@@ -318,12 +368,13 @@ impl KmsClient for LocalKmsClient {
             nonce,
             encryption_context: request.encryption_context.clone(),
             created_at: chrono::Utc::now(),
+            master_key_version: Some(version),
         };
 
         // Serialize the envelope as the ciphertext
         let ciphertext = serde_json::to_vec(&envelope)?;
 
-        let data_key = DataKey::new(envelope.key_id, 1, Some(plaintext_key), ciphertext, request.key_spec.clone());
+        let data_key = DataKey::new(envelope.key_id, version, Some(plaintext_key), ciphertext, request.key_spec.clone());
 
         info!("Generated data key for master key: {}", request.master_key_id);
         Ok(data_key)
@@ -341,12 +392,24 @@ impl KmsClient for LocalKmsClient {
             )));
         }
 
-        let (ciphertext, _nonce) = self.encrypt_with_master_key(&request.key_id, &request.plaintext).await?;
+        let (encrypted_key, nonce, version) = self.encrypt_with_master_key(&request.key_id, &request.plaintext).await?;
+
+        // Wrap the result in the same envelope as data keys, so decrypt() can take it back
+        let envelope = DataKeyEnvelope {
+            key_id: uuid::Uuid::new_v4().to_string(),
+            master_key_id: request.key_id.clone(),
+            key_spec: key_info.algorithm.clone(),
+            encrypted_key,
+            nonce,
+            encryption_context: request.encryption_context.clone(),
+            created_at: chrono::Utc::now(),
+            master_key_version: Some(version),
+        };
 
         Ok(EncryptResponse {
-            ciphertext,
+            ciphertext: serde_json::to_vec(&envelope)?,
             key_id: request.key_id.clone(),
-            key_version: key_info.version,
+            key_version: version,
             algorithm: key_info.algorithm,
         })
     }
@@ -374,7 +437,12 @@ impl KmsClient for LocalKmsClient {
 
         // Decrypt the data key
         let plaintext = self
-            .decrypt_with_master_key(&envelope.master_key_id, &envelope.encrypted_key, &envelope.nonce)
+            .decrypt_with_master_key(
+                &envelope.master_key_id,
+                envelope.master_key_version,
+                &envelope.encrypted_key,
+                &envelope.nonce,
+            )
             .await?;
 
         info!("Successfully decrypted data");
@@ -707,6 +775,7 @@ impl KmsBackend for LocalKmsBackend {
             key_id: request.key_id,
             plaintext_key: data_key.plaintext.clone().unwrap_or_default(),
             ciphertext_blob: data_key.ciphertext.clone(),
+            key_version: data_key.version,
         })
     }
 
@@ -888,6 +957,11 @@ impl KmsBackend for LocalKmsBackend {
         })
     }
 
+    async fn rotate_key(&self, key_id: &str) -> Result<u32> {
+        let master_key = self.client.rotate_key(key_id, None).await?;
+        Ok(master_key.version)
+    }
+
     async fn health_check(&self) -> Result<bool> {
         self.client.health_check().await.map(|_| true)
     }
@@ -995,8 +1069,47 @@ mod tests {
         let encrypt_response = client.encrypt(&encrypt_request, None).await.expect("Failed to encrypt");
         assert!(!encrypt_response.ciphertext.is_empty());
         assert_eq!(encrypt_response.key_id, key_id);
+        assert_eq!(encrypt_response.key_version, 1);
 
-        // Note: Direct decryption of encrypt() results is not implemented in this simple version
-        // In a real implementation, encrypt() would create a different envelope format
+        // Decrypt
+        let decrypted = client
+            .decrypt(&DecryptRequest::new(encrypt_response.ciphertext), None)
+            .await
+            .expect("Failed to decrypt");
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[tokio::test]
+    async fn test_rotated_key_keeps_previous_versions() {
+        let (client, _temp_dir) = create_test_client().await;
+
+        let key_id = "test-key";
+        client
+            .create_key(key_id, "AES_256", None)
+            .await
+            .expect("Failed to create key");
+
+        let request = GenerateKeyRequest::new(key_id.to_string(), "AES_256".to_string());
+        let old_data_key = client
+            .generate_data_key(&request, None)
+            .await
+            .expect("Failed to generate data key");
+        assert_eq!(old_data_key.version, 1);
+
+        let rotated = client.rotate_key(key_id, None).await.expect("Failed to rotate key");
+        assert_eq!(rotated.version, 2);
+
+        // Data keys wrapped by the retired version still unwrap
+        let decrypted = client
+            .decrypt(&DecryptRequest::new(old_data_key.ciphertext.clone()), None)
+            .await
+            .expect("Failed to decrypt");
+        assert_eq!(Some(decrypted), old_data_key.plaintext.clone());
+
+        let new_data_key = client
+            .generate_data_key(&request, None)
+            .await
+            .expect("Failed to generate data key");
+        assert_eq!(new_data_key.version, 2);
     }
 }
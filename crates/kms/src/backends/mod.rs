@@ -179,6 +179,9 @@ pub trait KmsBackend: Send + Sync {
     /// Cancel key deletion
     async fn cancel_key_deletion(&self, request: CancelKeyDeletionRequest) -> Result<CancelKeyDeletionResponse>;
 
+    /// Rotate a key to a new version, returns the new version
+    async fn rotate_key(&self, key_id: &str) -> Result<u32>;
+
     /// Health check
     async fn health_check(&self) -> Result<bool>;
 }
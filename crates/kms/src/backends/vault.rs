@@ -605,6 +605,7 @@ impl KmsBackend for VaultKmsBackend {
             key_id: request.key_id,
             plaintext_key: data_key.plaintext.clone().unwrap_or_default(),
             ciphertext_blob: data_key.ciphertext.clone(),
+            key_version: data_key.version,
         })
     }
 
@@ -723,6 +724,11 @@ impl KmsBackend for VaultKmsBackend {
         })
     }
 
+    async fn rotate_key(&self, key_id: &str) -> Result<u32> {
+        let master_key = self.client.rotate_key(key_id, None).await?;
+        Ok(master_key.version)
+    }
+
     async fn health_check(&self) -> Result<bool> {
         self.client.health_check().await.map(|_| true)
     }
@@ -24,6 +24,8 @@ pub struct CachedDataKey {
     pub plaintext: Vec<u8>,
     pub ciphertext: Vec<u8>,
     pub key_spec: KeySpec,
+    /// Version of the master key that wrapped the data key
+    pub key_version: u32,
 }
 
 /// KMS cache for storing frequently accessed keys and metadata
@@ -95,12 +97,14 @@ impl KmsCache {
     /// * `key_id` - The ID of the key to store the data key for
     /// * `plaintext` - The plaintext data key bytes
     /// * `ciphertext` - The ciphertext data key bytes
+    /// * `key_version` - The version of the master key that wrapped the data key
     ///
-    pub async fn put_data_key(&mut self, key_id: &str, plaintext: &[u8], ciphertext: &[u8]) {
+    pub async fn put_data_key(&mut self, key_id: &str, plaintext: &[u8], ciphertext: &[u8], key_version: u32) {
         let cached_key = CachedDataKey {
             plaintext: plaintext.to_vec(),
             ciphertext: ciphertext.to_vec(),
             key_spec: KeySpec::Aes256, // Default to AES-256
+            key_version,
         };
         self.data_key_cache.insert(key_id.to_string(), cached_key).await;
         self.data_key_cache.run_pending_tasks().await;
@@ -218,7 +222,7 @@ mod tests {
         // Test data key caching
         let plaintext = vec![1, 2, 3, 4];
         let ciphertext = vec![5, 6, 7, 8];
-        cache.put_data_key("test-key-1", &plaintext, &ciphertext).await;
+        cache.put_data_key("test-key-1", &plaintext, &ciphertext, 1).await;
 
         let cached_data_key = cache.get_data_key("test-key-1").await;
         assert!(cached_data_key.is_some());
@@ -226,6 +230,7 @@ mod tests {
         assert_eq!(cached_data_key.plaintext, plaintext);
         assert_eq!(cached_data_key.ciphertext, ciphertext);
         assert_eq!(cached_data_key.key_spec, KeySpec::Aes256);
+        assert_eq!(cached_data_key.key_version, 1);
 
         // Test cache info
         let info = cache.info_for_tests();
@@ -291,7 +296,7 @@ mod tests {
         };
 
         cache.put_key_metadata("contains-test", &metadata).await;
-        cache.put_data_key("contains-test", &[1, 2, 3], &[4, 5, 6]).await;
+        cache.put_data_key("contains-test", &[1, 2, 3], &[4, 5, 6], 1).await;
 
         assert!(cache.contains_key_metadata_for_tests("contains-test"));
         assert!(cache.contains_data_key_for_tests("contains-test"));
@@ -26,6 +26,10 @@ use tokio::io::{AsyncRead, AsyncReadExt};
 use tracing::{debug, info};
 use zeroize::Zeroize;
 
+/// Header recording the version of the KMS key that wrapped the data key of an object.
+/// Objects written before it existed carry none and were wrapped by version 1.
+pub const ENCRYPTION_KEY_VERSION_HEADER: &str = "x-rustfs-encryption-key-version";
+
 /// Data key for object encryption
 /// SECURITY: This struct automatically zeros sensitive key material when dropped
 #[derive(Debug, Clone)]
@@ -161,13 +165,13 @@ impl ObjectEncryptionService {
     /// * `context` - ObjectEncryptionContext with bucket and object key
     ///
     /// # Returns
-    /// Tuple with DataKey and encrypted key blob
+    /// Tuple with DataKey, encrypted key blob and the version of the KMS key that wrapped it
     ///
     pub async fn create_data_key(
         &self,
         kms_key_id: &Option<String>,
         context: &ObjectEncryptionContext,
-    ) -> Result<(DataKey, Vec<u8>)> {
+    ) -> Result<(DataKey, Vec<u8>, u32)> {
         // Determine the KMS key ID to use
         let actual_key_id = kms_key_id
             .as_ref()
@@ -201,7 +205,7 @@ impl ObjectEncryptionService {
             nonce,
         };
 
-        Ok((data_key, data_key_response.ciphertext_blob))
+        Ok((data_key, data_key_response.ciphertext_blob, data_key_response.key_version))
     }
 
     /// Decrypt a data encryption key
@@ -233,6 +237,41 @@ impl ObjectEncryptionService {
         Ok(data_key)
     }
 
+    /// Re-wrap an encrypted data key with the current version of a KMS key
+    ///
+    /// The data key is unwrapped and wrapped again without ever leaving the service, so the object
+    /// data it encrypts stays untouched.
+    ///
+    /// # Arguments
+    /// * `encrypted_key` - Encrypted data key blob
+    /// * `kms_key_id` - KMS key to wrap the data key with
+    ///
+    /// # Returns
+    /// Tuple with the new encrypted key blob and the version of the KMS key that wrapped it
+    ///
+    pub async fn rewrap_data_key(&self, encrypted_key: &[u8], kms_key_id: &str) -> Result<(Vec<u8>, u32)> {
+        let plaintext = self
+            .kms_manager
+            .decrypt(DecryptRequest {
+                ciphertext: encrypted_key.to_vec(),
+                encryption_context: HashMap::new(),
+                grant_tokens: Vec::new(),
+            })
+            .await?
+            .plaintext;
+
+        let response = self
+            .kms_manager
+            .encrypt(EncryptRequest {
+                key_id: kms_key_id.to_string(),
+                plaintext,
+                encryption_context: HashMap::new(),
+                grant_tokens: Vec::new(),
+            })
+            .await?;
+        Ok((response.ciphertext, response.key_version))
+    }
+
     /// Encrypt object data using server-side encryption
     ///
     /// # Arguments
@@ -335,7 +374,7 @@ impl ObjectEncryptionService {
         let metadata = EncryptionMetadata {
             algorithm: algorithm.as_str().to_string(),
             key_id: actual_key_id.to_string(),
-            key_version: 1, // Default to version 1 for now
+            key_version: data_key.key_version,
             iv,
             tag: Some(tag),
             encryption_context: context,
@@ -631,6 +670,10 @@ impl ObjectEncryptionService {
             serde_json::to_string(&metadata.encryption_context).unwrap_or_default(),
         );
 
+        if metadata.key_id != "sse-c" {
+            headers.insert(ENCRYPTION_KEY_VERSION_HEADER.to_string(), metadata.key_version.to_string());
+        }
+
         headers
     }
 
@@ -691,7 +734,10 @@ impl ObjectEncryptionService {
         Ok(EncryptionMetadata {
             algorithm,
             key_id,
-            key_version: 1, // Default for parsing
+            key_version: headers
+                .get(ENCRYPTION_KEY_VERSION_HEADER)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1),
             iv,
             tag,
             encryption_context,
@@ -805,7 +851,7 @@ mod tests {
         let metadata = EncryptionMetadata {
             algorithm: "AES256".to_string(),
             key_id: "test-key".to_string(),
-            key_version: 1,
+            key_version: 2,
             iv: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
             tag: Some(vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]),
             encryption_context: HashMap::from([("bucket".to_string(), "test-bucket".to_string())]),
@@ -825,6 +871,49 @@ mod tests {
         assert_eq!(parsed_metadata.key_id, metadata.key_id);
         assert_eq!(parsed_metadata.iv, metadata.iv);
         assert_eq!(parsed_metadata.tag, metadata.tag);
+        assert_eq!(parsed_metadata.key_version, 2);
+
+        // Objects written before the version was recorded were wrapped by version 1
+        let mut legacy = headers.clone();
+        legacy.remove(ENCRYPTION_KEY_VERSION_HEADER);
+        let parsed_metadata = service.headers_to_metadata(&legacy).expect("Failed to parse headers");
+        assert_eq!(parsed_metadata.key_version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rewrap_data_key() {
+        let (service, _temp_dir) = create_test_service().await;
+
+        for key_name in ["key-a", "key-b"] {
+            service
+                .kms_manager
+                .create_key(CreateKeyRequest {
+                    key_name: Some(key_name.to_string()),
+                    ..Default::default()
+                })
+                .await
+                .expect("Failed to create key");
+        }
+
+        let context = ObjectEncryptionContext::new("test-bucket".to_string(), "test-object".to_string());
+        let (data_key, encrypted_key, version) = service
+            .create_data_key(&Some("key-a".to_string()), &context)
+            .await
+            .expect("Failed to create data key");
+        assert_eq!(version, 1);
+
+        let (rewrapped, version) = service
+            .rewrap_data_key(&encrypted_key, "key-b")
+            .await
+            .expect("Failed to rewrap data key");
+        assert_eq!(version, 1);
+        assert_ne!(rewrapped, encrypted_key);
+
+        let unwrapped = service
+            .decrypt_data_key(&rewrapped, &context)
+            .await
+            .expect("Failed to decrypt rewrapped data key");
+        assert_eq!(unwrapped.plaintext_key, data_key.plaintext_key);
     }
 
     #[tokio::test]
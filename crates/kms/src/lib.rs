@@ -74,7 +74,7 @@ pub use api_types::{
 };
 pub use config::*;
 pub use encryption::ObjectEncryptionService;
-pub use encryption::service::DataKey;
+pub use encryption::service::{DataKey, ENCRYPTION_KEY_VERSION_HEADER};
 pub use error::{KmsError, Result};
 pub use manager::KmsManager;
 pub use service_manager::{
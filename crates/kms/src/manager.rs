@@ -80,6 +80,7 @@ impl KmsManager {
                         key_id: request.key_id.clone(),
                         plaintext_key: cached_key.plaintext.clone(),
                         ciphertext_blob: cached_key.ciphertext.clone(),
+                        key_version: cached_key.key_version,
                     });
                 }
             }
@@ -92,7 +93,7 @@ impl KmsManager {
         if self.config.enable_cache {
             let mut cache = self.cache.write().await;
             cache
-                .put_data_key(&response.key_id, &response.plaintext_key, &response.ciphertext_blob)
+                .put_data_key(&response.key_id, &response.plaintext_key, &response.ciphertext_blob, response.key_version)
                 .await;
         }
 
@@ -175,6 +176,22 @@ impl KmsManager {
         Ok(response)
     }
 
+    /// Rotate a key to a new version, returns the new version
+    ///
+    /// Data keys generated from now on are wrapped by the new version; those wrapped by earlier
+    /// versions keep unwrapping until they are re-wrapped.
+    pub async fn rotate_key(&self, key_id: &str) -> Result<u32> {
+        let version = self.backend.rotate_key(key_id).await?;
+
+        // Cached data keys were wrapped by the previous version
+        if self.config.enable_cache {
+            let mut cache = self.cache.write().await;
+            cache.remove_data_key(key_id).await;
+        }
+
+        Ok(version)
+    }
+
     /// Perform health check on the KMS backend
     pub async fn health_check(&self) -> Result<bool> {
         self.backend.health_check().await
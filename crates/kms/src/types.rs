@@ -826,6 +826,9 @@ pub struct GenerateDataKeyResponse {
     pub plaintext_key: Vec<u8>,
     /// Encrypted data key
     pub ciphertext_blob: Vec<u8>,
+    /// Version of the master key that wrapped the data key
+    #[serde(default)]
+    pub key_version: u32,
 }
 
 impl EncryptionAlgorithm {
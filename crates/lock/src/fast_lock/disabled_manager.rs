@@ -118,6 +118,11 @@ impl DisabledLockManager {
         None
     }
 
+    /// Always returns an empty list - no locks exist
+    pub fn list_locks(&self) -> Vec<ObjectLockInfo> {
+        Vec::new()
+    }
+
     /// Returns empty metrics
     pub fn get_metrics(&self) -> AggregatedMetrics {
         AggregatedMetrics::empty()
@@ -207,6 +212,10 @@ impl LockManager for DisabledLockManager {
         self.get_lock_info(key)
     }
 
+    fn list_locks(&self) -> Vec<ObjectLockInfo> {
+        self.list_locks()
+    }
+
     fn get_metrics(&self) -> AggregatedMetrics {
         self.get_metrics()
     }
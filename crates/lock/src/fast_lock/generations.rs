@@ -0,0 +1,82 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Write generations of objects, for reads that skip the lock.
+//!
+//! Every exclusive lock bumps the generation of its object when taken and again when released.
+//! A reader that notes the generation before reading and finds it unchanged afterwards saw no
+//! write start or finish in between. Objects share a fixed number of counters, a write to another
+//! object mapped to the same counter only makes the reader fall back to the locked path.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters shared by all objects, must be a power of two.
+const GENERATION_SLOTS: usize = 16384;
+
+#[derive(Debug)]
+pub struct WriteGenerations {
+    slots: Box<[AtomicU64]>,
+}
+
+impl Default for WriteGenerations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WriteGenerations {
+    pub fn new() -> Self {
+        Self {
+            slots: (0..GENERATION_SLOTS).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    fn slot(&self, bucket: &str, object: &str) -> &AtomicU64 {
+        let mut hasher = DefaultHasher::new();
+        bucket.hash(&mut hasher);
+        object.hash(&mut hasher);
+        &self.slots[hasher.finish() as usize & (GENERATION_SLOTS - 1)]
+    }
+
+    /// Generation of `bucket/object`, whatever its version.
+    pub fn current(&self, bucket: &str, object: &str) -> u64 {
+        self.slot(bucket, object).load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn bump(&self, bucket: &str, object: &str) {
+        self.slot(bucket, object).fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bump_changes_only_own_generation() {
+        let generations = WriteGenerations::new();
+        let before = generations.current("bucket", "object");
+
+        generations.bump("bucket", "object");
+        assert_ne!(generations.current("bucket", "object"), before);
+
+        // Objects mapped to other counters are not affected
+        let others = (0..64)
+            .map(|i| format!("other-{i}"))
+            .filter(|name| generations.current("bucket", name) == 0)
+            .count();
+        assert!(others > 0);
+    }
+}
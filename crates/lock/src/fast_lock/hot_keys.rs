@@ -0,0 +1,182 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tracking of the objects read most often.
+//!
+//! Every shared lock counts as a read of its object. An object read at least `threshold` times
+//! within one window is hot until a window passes with fewer reads. Tracking is off while the
+//! threshold is 0.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::fast_lock::types::ObjectKey;
+
+/// Objects tracked at most, reads of other objects are not counted until the oldest windows expire.
+const MAX_TRACKED_KEYS: usize = 4096;
+
+#[derive(Debug, Clone, Copy)]
+struct ReadWindow {
+    started: Instant,
+    reads: u32,
+    // Reads of the previous window, so an object stays hot while its current window fills up.
+    previous: u32,
+}
+
+#[derive(Debug)]
+pub struct HotKeyTracker {
+    threshold: u32,
+    window: Duration,
+    windows: Mutex<HashMap<ObjectKey, ReadWindow>>,
+}
+
+impl HotKeyTracker {
+    pub fn new(threshold: u32, window: Duration) -> Self {
+        Self {
+            threshold,
+            window,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.threshold > 0 && !self.window.is_zero()
+    }
+
+    /// Count a read of `key`, the version is ignored.
+    pub fn record(&self, key: &ObjectKey) {
+        self.record_at(key, Instant::now());
+    }
+
+    fn record_at(&self, key: &ObjectKey, now: Instant) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut windows = self.windows.lock();
+        if let Some(window) = windows.get_mut(&key.as_latest()) {
+            self.roll(window, now);
+            window.reads = window.reads.saturating_add(1);
+            return;
+        }
+
+        if windows.len() >= MAX_TRACKED_KEYS {
+            let expiry = self.window * 2;
+            windows.retain(|_, window| now.duration_since(window.started) < expiry);
+            if windows.len() >= MAX_TRACKED_KEYS {
+                return;
+            }
+        }
+        windows.insert(
+            key.as_latest(),
+            ReadWindow {
+                started: now,
+                reads: 1,
+                previous: 0,
+            },
+        );
+    }
+
+    fn roll(&self, window: &mut ReadWindow, now: Instant) {
+        let elapsed = now.duration_since(window.started);
+        if elapsed < self.window {
+            return;
+        }
+        window.previous = if elapsed < self.window * 2 { window.reads } else { 0 };
+        window.reads = 0;
+        window.started = now;
+    }
+
+    /// Whether `key` was read at least `threshold` times within the current or the last window.
+    pub fn is_hot(&self, key: &ObjectKey) -> bool {
+        self.is_hot_at(key, Instant::now())
+    }
+
+    fn is_hot_at(&self, key: &ObjectKey, now: Instant) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+
+        let mut windows = self.windows.lock();
+        match windows.get_mut(&key.as_latest()) {
+            Some(window) => {
+                self.roll(window, now);
+                window.reads.max(window.previous) >= self.threshold
+            }
+            None => false,
+        }
+    }
+
+    /// Hot objects with their reads in the busier of the current and the last window, most read first.
+    pub fn hot_keys(&self) -> Vec<(ObjectKey, u32)> {
+        if !self.is_enabled() {
+            return Vec::new();
+        }
+
+        let now = Instant::now();
+        let mut windows = self.windows.lock();
+        let mut hot: Vec<_> = windows
+            .iter_mut()
+            .filter_map(|(key, window)| {
+                self.roll(window, now);
+                let reads = window.reads.max(window.previous);
+                (reads >= self.threshold).then(|| (key.clone(), reads))
+            })
+            .collect();
+        hot.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        hot
+    }
+}
+
+impl Default for HotKeyTracker {
+    fn default() -> Self {
+        Self::new(0, Duration::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hot_key_window() {
+        let tracker = HotKeyTracker::new(3, Duration::from_secs(10));
+        let hot = ObjectKey::new("bucket", "hot");
+        let cold = ObjectKey::new("bucket", "cold");
+
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            tracker.record_at(&hot, now);
+        }
+        tracker.record_at(&ObjectKey::with_version("bucket", "cold", "v1"), now);
+        assert!(tracker.is_hot_at(&hot, now));
+        assert!(!tracker.is_hot_at(&cold, now));
+        assert_eq!(tracker.hot_keys(), vec![(hot.clone(), 3)]);
+
+        // Still hot through the next window, cold once a whole window passed without reads
+        assert!(tracker.is_hot_at(&hot, now + Duration::from_secs(11)));
+        assert!(!tracker.is_hot_at(&hot, now + Duration::from_secs(21)));
+    }
+
+    #[test]
+    fn test_disabled_tracker() {
+        let tracker = HotKeyTracker::default();
+        let key = ObjectKey::new("bucket", "object");
+        tracker.record(&key);
+        assert!(!tracker.is_hot(&key));
+        assert!(tracker.hot_keys().is_empty());
+    }
+}
@@ -17,11 +17,13 @@ use tokio::sync::RwLock;
 use tokio::time::{Instant, interval};
 
 use crate::fast_lock::{
+    generations::WriteGenerations,
     guard::FastLockGuard,
+    hot_keys::HotKeyTracker,
     manager_trait::LockManager,
     metrics::{AggregatedMetrics, GlobalMetrics},
     shard::LockShard,
-    types::{BatchLockRequest, BatchLockResult, LockConfig, LockResult, ObjectKey, ObjectLockInfo, ObjectLockRequest},
+    types::{BatchLockRequest, BatchLockResult, LockConfig, LockMode, LockResult, ObjectKey, ObjectLockInfo, ObjectLockRequest},
 };
 
 /// High-performance object lock manager
@@ -31,6 +33,8 @@ pub struct FastObjectLockManager {
     shard_mask: usize,
     config: LockConfig,
     metrics: Arc<GlobalMetrics>,
+    hot_keys: Arc<HotKeyTracker>,
+    generations: Arc<WriteGenerations>,
     cleanup_handle: RwLock<Option<tokio::task::JoinHandle<()>>>,
 }
 
@@ -45,15 +49,21 @@ impl FastObjectLockManager {
         let shard_count = config.shard_count;
         assert!(shard_count.is_power_of_two(), "Shard count must be power of 2");
 
-        let shards: Vec<Arc<LockShard>> = (0..shard_count).map(|i| Arc::new(LockShard::new(i))).collect();
+        let generations = Arc::new(WriteGenerations::new());
+        let shards: Vec<Arc<LockShard>> = (0..shard_count)
+            .map(|i| Arc::new(LockShard::with_generations(i, generations.clone())))
+            .collect();
 
         let metrics = Arc::new(GlobalMetrics::new(shard_count));
+        let hot_keys = Arc::new(HotKeyTracker::new(config.hot_key_threshold, config.hot_key_window));
 
         let manager = Self {
             shards,
             shard_mask: shard_count - 1,
             config,
             metrics,
+            hot_keys,
+            generations,
             cleanup_handle: RwLock::new(None),
         };
 
@@ -64,6 +74,10 @@ impl FastObjectLockManager {
 
     /// Acquire object lock
     pub async fn acquire_lock(&self, request: ObjectLockRequest) -> Result<FastLockGuard, LockResult> {
+        if request.mode == LockMode::Shared {
+            self.hot_keys.record(&request.key);
+        }
+
         let shard = self.get_shard(&request.key);
         match shard.acquire_lock(&request).await {
             Ok(()) => {
@@ -311,6 +325,11 @@ impl FastObjectLockManager {
         shard.get_lock_info(key)
     }
 
+    /// Get lock information of every currently held lock
+    pub fn list_locks(&self) -> Vec<ObjectLockInfo> {
+        self.shards.iter().flat_map(|shard| shard.list_locks()).collect()
+    }
+
     /// Get aggregated metrics
     pub fn get_metrics(&self) -> crate::fast_lock::metrics::AggregatedMetrics {
         let shard_metrics: Vec<_> = self.shards.iter().map(|shard| shard.metrics().snapshot()).collect();
@@ -318,6 +337,27 @@ impl FastObjectLockManager {
         self.metrics.aggregate_shard_metrics(&shard_metrics)
     }
 
+    /// Snapshot to validate a read of `bucket/object` made without its lock, `None` while the
+    /// object is being written.
+    pub fn read_snapshot(&self, bucket: &str, object: &str) -> Option<u64> {
+        let generation = self.generations.current(bucket, object);
+        let key = ObjectKey::new(bucket, object);
+        if self.get_shard(&key).is_write_locked(&key) {
+            return None;
+        }
+        Some(generation)
+    }
+
+    /// Whether no write of `bucket/object` started or ended since `snapshot` was taken.
+    pub fn validate_read(&self, bucket: &str, object: &str, snapshot: u64) -> bool {
+        self.generations.current(bucket, object) == snapshot
+    }
+
+    /// Tracker of the objects read most often through this manager
+    pub fn hot_keys(&self) -> &HotKeyTracker {
+        &self.hot_keys
+    }
+
     /// Get total number of active locks across all shards
     pub fn total_lock_count(&self) -> usize {
         self.shards.iter().map(|shard| shard.lock_count()).sum()
@@ -429,6 +469,7 @@ impl Clone for FastObjectLockManager {
             shard_mask: self.shard_mask,
             config: self.config.clone(),
             metrics: self.metrics.clone(),
+            hot_keys: self.hot_keys.clone(),
             cleanup_handle: RwLock::new(None), // Don't clone the cleanup task
         }
     }
@@ -486,6 +527,10 @@ impl LockManager for FastObjectLockManager {
         self.get_lock_info(key)
     }
 
+    fn list_locks(&self) -> Vec<ObjectLockInfo> {
+        self.list_locks()
+    }
+
     fn get_metrics(&self) -> AggregatedMetrics {
         self.get_metrics()
     }
@@ -518,6 +563,7 @@ impl LockManager for FastObjectLockManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fast_lock::types::LockMode;
     use tokio::time::Duration;
 
     #[tokio::test]
@@ -548,6 +594,52 @@ mod tests {
         drop(write_guard);
     }
 
+    #[tokio::test]
+    async fn test_read_snapshot_detects_writes() {
+        let manager = FastObjectLockManager::new();
+
+        let snapshot = manager.read_snapshot("bucket", "object").unwrap();
+        assert!(manager.validate_read("bucket", "object", snapshot));
+
+        // Readers do not invalidate each other
+        drop(manager.acquire_read_lock("bucket", "object", "reader").await.unwrap());
+        assert!(manager.validate_read("bucket", "object", snapshot));
+
+        let write_guard = manager.acquire_write_lock("bucket", "object", "writer").await.unwrap();
+        assert!(!manager.validate_read("bucket", "object", snapshot));
+        assert!(manager.read_snapshot("bucket", "object").is_none());
+
+        drop(write_guard);
+        let snapshot = manager.read_snapshot("bucket", "object").unwrap();
+        assert!(manager.validate_read("bucket", "object", snapshot));
+    }
+
+    #[tokio::test]
+    async fn test_manager_list_locks() {
+        let manager = FastObjectLockManager::new();
+        assert!(manager.list_locks().is_empty());
+
+        let write_guard = manager
+            .acquire_write_lock("bucket", "object1", "owner1")
+            .await
+            .expect("Failed to acquire write lock");
+        let read_guard = manager
+            .acquire_read_lock("bucket", "object2", "owner2")
+            .await
+            .expect("Failed to acquire read lock");
+
+        let mut locks = manager.list_locks();
+        locks.sort_by(|a, b| a.key.object.cmp(&b.key.object));
+        assert_eq!(locks.len(), 2);
+        assert_eq!(&*locks[0].owner, "owner1");
+        assert_eq!(locks[0].mode, LockMode::Exclusive);
+        assert_eq!(locks[1].mode, LockMode::Shared);
+
+        drop(write_guard);
+        drop(read_guard);
+        assert!(manager.list_locks().is_empty());
+    }
+
     #[tokio::test]
     async fn test_manager_contention() {
         let manager = Arc::new(FastObjectLockManager::new());
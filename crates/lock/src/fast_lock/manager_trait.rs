@@ -70,6 +70,9 @@ pub trait LockManager: Send + Sync {
     /// Get lock information for monitoring
     fn get_lock_info(&self, key: &ObjectKey) -> Option<ObjectLockInfo>;
 
+    /// Get lock information of every currently held lock
+    fn list_locks(&self) -> Vec<ObjectLockInfo>;
+
     /// Get aggregated metrics
     fn get_metrics(&self) -> AggregatedMetrics;
 
@@ -25,7 +25,9 @@
 //! 5. **Auto Cleanup** - Access-time based automatic lock reclamation
 
 pub mod disabled_manager;
+pub mod generations;
 pub mod guard;
+pub mod hot_keys;
 pub mod integration_example;
 pub mod integration_test;
 pub mod manager;
@@ -42,7 +44,9 @@ pub mod types;
 
 // Re-export main types
 pub use disabled_manager::DisabledLockManager;
+pub use generations::WriteGenerations;
 pub use guard::FastLockGuard;
+pub use hot_keys::HotKeyTracker;
 pub use manager::FastObjectLockManager;
 pub use manager_trait::LockManager;
 pub use types::*;
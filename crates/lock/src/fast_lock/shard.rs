@@ -19,6 +19,7 @@ use std::time::{Duration, Instant, SystemTime};
 use tokio::time::timeout;
 
 use crate::fast_lock::{
+    generations::WriteGenerations,
     metrics::ShardMetrics,
     object_pool::ObjectStatePool,
     state::ObjectLockState,
@@ -39,16 +40,23 @@ pub struct LockShard {
     _shard_id: usize,
     /// Active guard IDs to prevent cleanup of locks with live guards
     active_guards: parking_lot::Mutex<HashSet<u64>>,
+    /// Write generations, shared by all shards of a manager
+    generations: Arc<WriteGenerations>,
 }
 
 impl LockShard {
     pub fn new(shard_id: usize) -> Self {
+        Self::with_generations(shard_id, Arc::new(WriteGenerations::new()))
+    }
+
+    pub fn with_generations(shard_id: usize, generations: Arc<WriteGenerations>) -> Self {
         Self {
             objects: RwLock::new(HashMap::new()),
             object_pool: ObjectStatePool::new(),
             metrics: ShardMetrics::new(),
             _shard_id: shard_id,
             active_guards: parking_lot::Mutex::new(HashSet::new()),
+            generations,
         }
     }
 
@@ -57,13 +65,26 @@ impl LockShard {
         let start_time = Instant::now();
 
         // Try fast path first
-        if let Some(_state) = self.try_fast_path(request) {
+        let result = if let Some(_state) = self.try_fast_path(request) {
             self.metrics.record_fast_path_success();
-            return Ok(());
+            Ok(())
+        } else {
+            // Slow path with waiting
+            self.acquire_lock_slow_path(request, start_time).await
+        };
+
+        if result.is_ok() && request.mode == LockMode::Exclusive {
+            self.generations.bump(&request.key.bucket, &request.key.object);
         }
+        result
+    }
 
-        // Slow path with waiting
-        self.acquire_lock_slow_path(request, start_time).await
+    /// Whether `key` is held exclusively right now.
+    pub fn is_write_locked(&self, key: &ObjectKey) -> bool {
+        self.objects
+            .read()
+            .get(key)
+            .is_some_and(|state| state.current_mode() == Some(LockMode::Exclusive))
     }
 
     /// Try fast path only (without fallback to slow path)
@@ -221,6 +242,9 @@ impl LockShard {
 
                 if result {
                     self.metrics.record_release();
+                    if mode == LockMode::Exclusive {
+                        self.generations.bump(&key.bucket, &key.object);
+                    }
 
                     // Check if cleanup is needed
                     should_cleanup = !state.is_locked() && !state.atomic_state.has_waiters();
@@ -295,6 +319,9 @@ impl LockShard {
 
                 if result {
                     self.metrics.record_release();
+                    if mode == LockMode::Exclusive {
+                        self.generations.bump(&key.bucket, &key.object);
+                    }
                     should_cleanup = !state.is_locked() && !state.atomic_state.has_waiters();
                 } else {
                     should_cleanup = false;
@@ -475,6 +502,19 @@ impl LockShard {
         None
     }
 
+    /// Get lock information of every currently held lock
+    pub fn list_locks(&self) -> Vec<crate::fast_lock::types::ObjectLockInfo> {
+        let keys: Vec<ObjectKey> = {
+            let objects = self.objects.read();
+            objects
+                .iter()
+                .filter(|(_, state)| state.is_locked())
+                .map(|(key, _)| key.clone())
+                .collect()
+        };
+        keys.iter().filter_map(|key| self.get_lock_info(key)).collect()
+    }
+
     /// Get current load factor of the shard
     pub fn current_load_factor(&self) -> f64 {
         let objects = self.objects.read();
@@ -275,6 +275,9 @@ pub struct LockConfig {
     pub cleanup_interval: Duration,
     pub max_idle_time: Duration,
     pub enable_metrics: bool,
+    /// Shared locks on one object within `hot_key_window` making it hot, 0 disables hot key tracking.
+    pub hot_key_threshold: u32,
+    pub hot_key_window: Duration,
 }
 
 impl Default for LockConfig {
@@ -286,6 +289,8 @@ impl Default for LockConfig {
             cleanup_interval: crate::fast_lock::CLEANUP_INTERVAL,
             max_idle_time: Duration::from_secs(300), // 5 minutes
             enable_metrics: true,
+            hot_key_threshold: 0,
+            hot_key_window: Duration::from_secs(10),
         }
     }
 }
@@ -42,8 +42,8 @@ pub use crate::{
     error::{LockError, Result},
     // Fast Lock System exports
     fast_lock::{
-        BatchLockRequest, BatchLockResult, DisabledLockManager, FastLockGuard, FastObjectLockManager, LockManager, LockMode,
-        LockResult, ObjectKey, ObjectLockInfo, ObjectLockRequest, metrics::AggregatedMetrics,
+        BatchLockRequest, BatchLockResult, DisabledLockManager, FastLockGuard, FastObjectLockManager, HotKeyTracker, LockConfig,
+        LockManager, LockMode, LockResult, ObjectKey, ObjectLockInfo, ObjectLockRequest, metrics::AggregatedMetrics,
     },
     guard::LockGuard,
     // Main components
@@ -198,6 +198,13 @@ impl fast_lock::LockManager for GlobalLockManager {
         }
     }
 
+    fn list_locks(&self) -> Vec<fast_lock::ObjectLockInfo> {
+        match self {
+            Self::Enabled(manager) => manager.list_locks(),
+            Self::Disabled(manager) => manager.list_locks(),
+        }
+    }
+
     fn get_metrics(&self) -> AggregatedMetrics {
         match self {
             Self::Enabled(manager) => manager.get_metrics(),
@@ -93,6 +93,32 @@ pub struct Disk {
     pub pool_index: i32,
     pub set_index: i32,
     pub disk_index: i32,
+    /// SMART health of the device, only reported by the node serving the drive
+    #[serde(default)]
+    pub smart: Option<DriveSmartHealth>,
+}
+
+/// SMART health of the device behind a drive, as last collected by the node serving it.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct DriveSmartHealth {
+    pub device: String,
+    pub model: String,
+    pub serial: String,
+    /// The overall self-assessment of the device, `None` when it reports none
+    pub passed: Option<bool>,
+    pub temperature: Option<i64>,
+    pub power_on_hours: Option<u64>,
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    pub uncorrectable_sectors: Option<u64>,
+    /// NVMe media and data integrity errors
+    pub media_errors: Option<u64>,
+    /// Why the drive is expected to fail, empty while it looks healthy
+    pub warnings: Vec<String>,
+    /// Why the last collection failed, the counters are then from the collection before
+    pub error: String,
+    pub collected: Option<OffsetDateTime>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -248,6 +274,9 @@ pub struct Services {
 pub struct Buckets {
     pub count: u64,
     pub error: Option<String>,
+    /// Cost-allocation tags keyed by bucket name, for buckets that have any
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, HashMap<String, String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -472,6 +501,7 @@ mod tests {
             pool_index: 0,
             set_index: 1,
             disk_index: 2,
+            smart: None,
         };
 
         assert_eq!(disk.endpoint, "http://localhost:9000");
@@ -870,6 +900,7 @@ mod tests {
         let buckets = Buckets {
             count: 10,
             error: Some("Access denied".to_string()),
+            ..Default::default()
         };
 
         assert_eq!(buckets.count, 10);
@@ -1010,7 +1041,11 @@ mod tests {
             region: Some("us-east-1".to_string()),
             sqs_arn: Some(vec!["arn:aws:sqs:us-east-1:123456789012:test-queue".to_string()]),
             deployment_id: Some("deployment-123".to_string()),
-            buckets: Some(Buckets { count: 5, error: None }),
+            buckets: Some(Buckets {
+                count: 5,
+                error: None,
+                ..Default::default()
+            }),
             objects: Some(Objects {
                 count: 1000,
                 error: None,
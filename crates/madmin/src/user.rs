@@ -69,6 +69,16 @@ pub struct UserAuthInfo {
     pub auth_server_user_id: Option<String>,
 }
 
+/// When and from where an access key last authenticated a request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessKeyLastUsed {
+    #[serde(rename = "time", with = "time::serde::rfc3339")]
+    pub time: OffsetDateTime,
+
+    #[serde(rename = "sourceIP")]
+    pub source_ip: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct UserInfo {
     #[serde(rename = "userAuthInfo", skip_serializing_if = "Option::is_none")]
@@ -88,6 +98,9 @@ pub struct UserInfo {
 
     #[serde(rename = "updatedAt")]
     pub updated_at: Option<OffsetDateTime>,
+
+    #[serde(rename = "lastUsed", default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<AccessKeyLastUsed>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -124,6 +137,9 @@ pub struct ServiceAccountInfo {
 
     #[serde(rename = "expiration", with = "time::serde::rfc3339::option")]
     pub expiration: Option<OffsetDateTime>,
+
+    #[serde(rename = "lastUsed", default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<AccessKeyLastUsed>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -186,6 +202,9 @@ pub struct Credentials<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "time::serde::rfc3339::option")]
     pub expiration: Option<OffsetDateTime>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<AccessKeyLastUsed>,
 }
 
 #[derive(Serialize)]
@@ -338,6 +357,15 @@ impl<'de> Deserialize<'de> for SRSessionPolicy {
     }
 }
 
+/// Go encodes nil slices and maps as null, read those as empty.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 /// SRSvcAccCreate - create operation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SRSvcAccCreate {
@@ -349,20 +377,25 @@ pub struct SRSvcAccCreate {
     #[serde(rename = "secretKey")]
     pub secret_key: String,
 
+    #[serde(default, deserialize_with = "null_as_default")]
     pub groups: Vec<String>,
 
+    #[serde(default, deserialize_with = "null_as_default")]
     pub claims: HashMap<String, serde_json::Value>,
 
-    #[serde(rename = "sessionPolicy")]
+    #[serde(rename = "sessionPolicy", default)]
     pub session_policy: SRSessionPolicy,
 
     pub status: String,
 
+    #[serde(default, deserialize_with = "null_as_default")]
     pub name: String,
 
+    #[serde(default, deserialize_with = "null_as_default")]
     pub description: String,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// MinIO writes the Unix epoch for accounts that never expire
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "time::serde::rfc3339::option")]
     pub expiration: Option<OffsetDateTime>,
 
     #[serde(rename = "apiVersion", skip_serializing_if = "Option::is_none")]
@@ -457,6 +490,46 @@ pub struct IAMErrEntity {
     pub error: String,
 }
 
+/// IAMPolicyMapping - the policies mapped to a user or group in an IAM export,
+/// their names joined by commas
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct IAMPolicyMapping {
+    #[serde(default)]
+    pub version: i64,
+
+    #[serde(alias = "policies")]
+    pub policy: String,
+
+    #[serde(
+        rename = "updatedAt",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "time::serde::rfc3339::option"
+    )]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+/// IAMGroupInfo - a group in an IAM export
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct IAMGroupInfo {
+    #[serde(default)]
+    pub version: i64,
+
+    #[serde(default)]
+    pub status: String,
+
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub members: Vec<String>,
+
+    #[serde(
+        rename = "updatedAt",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "time::serde::rfc3339::option"
+    )]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
 /// IAMErrPolicyEntity - represents an errored policy entity with error details
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IAMErrPolicyEntity {
@@ -580,6 +653,7 @@ mod tests {
             status: AccountStatus::Enabled,
             member_of: Some(vec!["group1".to_string(), "group2".to_string()]),
             updated_at: Some(now),
+            last_used: None,
         };
 
         assert!(user_info.auth_info.is_some());
@@ -614,6 +688,7 @@ mod tests {
             name: Some("test-service".to_string()),
             description: Some("Test service account".to_string()),
             expiration: Some(now),
+            last_used: None,
         };
 
         assert_eq!(service_account.parent_user, "admin");
@@ -636,6 +711,7 @@ mod tests {
                     name: Some("service1".to_string()),
                     description: None,
                     expiration: None,
+                    last_used: None,
                 },
                 ServiceAccountInfo {
                     parent_user: "user2".to_string(),
@@ -645,6 +721,7 @@ mod tests {
                     name: Some("service2".to_string()),
                     description: Some("Second service".to_string()),
                     expiration: None,
+                    last_used: None,
                 },
             ],
         };
@@ -778,6 +855,7 @@ mod tests {
             name: Some("test-service".to_string()),
             description: Some("Test service account".to_string()),
             expiration: Some(now),
+            last_used: None,
         };
 
         assert_eq!(resp.parent_user, "admin");
@@ -913,6 +991,7 @@ mod tests {
             status: AccountStatus::Enabled,
             member_of: Some(vec!["group1".to_string()]),
             updated_at: None,
+            last_used: None,
         };
 
         let json = serde_json::to_string(&user_info).unwrap();
@@ -937,6 +1016,7 @@ mod tests {
             name: None,
             description: None,
             expiration: None,
+            last_used: None,
         };
 
         // Test that all structures can be formatted with Debug
@@ -986,4 +1066,26 @@ mod tests {
 
         assert!(long_req.validate().is_ok());
     }
+
+    #[test]
+    fn test_minio_iam_export_entries() {
+        let mapping: IAMPolicyMapping =
+            serde_json::from_str(r#"{"version":1,"policy":"readwrite,diagnostics","updatedAt":"2024-05-01T10:00:00Z"}"#).unwrap();
+        assert_eq!(mapping.policy, "readwrite,diagnostics");
+        assert!(mapping.updated_at.is_some());
+
+        let group: IAMGroupInfo =
+            serde_json::from_str(r#"{"version":1,"status":"disabled","members":null,"updatedAt":"0001-01-01T00:00:00Z"}"#)
+                .unwrap();
+        assert_eq!(group.status, "disabled");
+        assert!(group.members.is_empty());
+
+        let svc: SRSvcAccCreate = serde_json::from_str(
+            r#"{"parent":"alice","accessKey":"AKEXAMPLE","secretKey":"secret123","groups":null,"claims":null,
+                "sessionPolicy":null,"status":"on","name":"","description":"","expiration":"1970-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert!(svc.groups.is_empty() && svc.claims.is_empty() && svc.session_policy.is_null());
+        assert_eq!(svc.expiration.map(|t| t.unix_timestamp()), Some(0));
+    }
 }
@@ -14,23 +14,47 @@
 
 use chrono::{DateTime, Utc};
 use hashbrown::HashMap;
-use rustfs_targets::EventName;
+use rustfs_config::notify::{DEFAULT_NOTIFY_EXTENDED_METADATA, ENV_NOTIFY_EXTENDED_METADATA};
+use rustfs_targets::{EventName, record_event_name};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
 use url::form_urlencoded;
 
+/// Version of the event record layout, following the AWS S3 event message structure.
+pub const EVENT_VERSION: &str = "2.1";
+/// Version of the `s3` section of an event record.
+pub const S3_SCHEMA_VERSION: &str = "1.0";
+/// Version of the `rustfs` extension block, bumped whenever its layout changes incompatibly.
+pub const EXTENSION_SCHEMA_VERSION: &str = "1.0";
+
+/// Request elements copied into `responseElements`, as in S3 event records.
+const RESPONSE_ELEMENT_KEYS: [&str; 2] = ["x-amz-request-id", "x-amz-id-2"];
+/// Prefix of the elements carrying the bucket summary of [`EventName::ScannerCycleComplete`], copied as they are.
+pub const SCANNER_ELEMENT_PREFIX: &str = "x-rustfs-scanner-";
+/// Prefix of the elements carrying the run of [`EventName::BackupFailed`], copied as they are.
+pub const BACKUP_ELEMENT_PREFIX: &str = "x-rustfs-backup-";
+/// Prefix of the elements carrying the drive of [`EventName::DriveHealthDegraded`], copied as they are.
+pub const DRIVE_ELEMENT_PREFIX: &str = "x-rustfs-drive-";
+
+// Field aliases keep events queued by earlier versions, which used snake_case keys, readable.
+
 /// Represents the identity of the user who triggered the event
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Identity {
     /// The principal ID of the user
+    #[serde(alias = "principal_id")]
     pub principal_id: String,
 }
 
 /// Represents the bucket that the object is in
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Bucket {
     /// The name of the bucket
     pub name: String,
     /// The owner identity of the bucket
+    #[serde(alias = "owner_identity")]
     pub owner_identity: Identity,
     /// The Amazon Resource Name (ARN) of the bucket
     pub arn: String,
@@ -38,35 +62,33 @@ pub struct Bucket {
 
 /// Represents the object that the event occurred on
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct Object {
-    /// The key (name) of the object
+    /// The key (name) of the object, URL encoded
     pub key: String,
     /// The size of the object in bytes
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub size: Option<i64>,
     /// The entity tag (ETag) of the object
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "eTag", default, skip_serializing_if = "Option::is_none")]
     pub etag: Option<String>,
-    /// The content type of the object
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub content_type: Option<String>,
-    /// User-defined metadata associated with the object
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub user_metadata: Option<HashMap<String, String>>,
     /// The version ID of the object (if versioning is enabled)
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub version_id: Option<String>,
-    /// A unique identifier for the event
+    /// A string that orders events for the same key and identifies the event, so consumers can
+    /// drop redeliveries of an event they have already seen
     pub sequencer: String,
 }
 
 /// Metadata about the event
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Metadata {
     /// The schema version of the event
     #[serde(rename = "s3SchemaVersion")]
     pub schema_version: String,
     /// The ID of the configuration that triggered the event
+    #[serde(alias = "configuration_id")]
     pub configuration_id: String,
     /// Information about the bucket
     pub bucket: Bucket,
@@ -75,18 +97,57 @@ pub struct Metadata {
 }
 
 /// Information about the source of the event
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Source {
     /// The host where the event originated
     pub host: String,
     /// The port on the host
     pub port: String,
     /// The user agent that caused the event
-    #[serde(rename = "userAgent")]
     pub user_agent: String,
 }
 
-/// Represents a storage event
+/// RustFS specific details that are not part of the S3 event record
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Extension {
+    /// The layout version of this block, see [`EXTENSION_SCHEMA_VERSION`]
+    pub schema_version: String,
+    /// The content type of the object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// User-defined metadata associated with the object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_metadata: Option<HashMap<String, String>>,
+    /// Information about the source of the event
+    pub source: Source,
+}
+
+/// `eventTime` is written with millisecond precision, e.g. `2024-01-01T00:00:00.000Z`.
+mod event_time {
+    use chrono::{DateTime, SecondsFormat, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(time: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&time.to_rfc3339_opts(SecondsFormat::Millis, true))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+        DateTime::<Utc>::deserialize(deserializer)
+    }
+}
+
+static EVENT_SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+/// Fixed width hex of the object modification time followed by a per process counter, so
+/// sequencers of the same key sort by modification time and no two events share one.
+fn sequencer(mod_time_nanos: i64) -> String {
+    let seq = EVENT_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    format!("{:016X}{seq:08X}", mod_time_nanos.max(0))
+}
+
+/// Represents a storage event, laid out as an S3 event notification record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Event {
@@ -97,8 +158,10 @@ pub struct Event {
     /// The AWS region where the event occurred
     pub aws_region: String,
     /// The time when the event occurred
+    #[serde(with = "event_time")]
     pub event_time: DateTime<Utc>,
-    /// The name of the event
+    /// The name of the event, e.g. `ObjectCreated:Put`
+    #[serde(with = "record_event_name")]
     pub event_name: EventName,
     /// The identity of the user who triggered the event
     pub user_identity: Identity,
@@ -108,8 +171,9 @@ pub struct Event {
     pub response_elements: HashMap<String, String>,
     /// Metadata about the event
     pub s3: Metadata,
-    /// Information about the source of the event
-    pub source: Source,
+    /// RustFS extension, omitted when `RUSTFS_NOTIFY_EXTENDED_METADATA` is off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rustfs: Option<Extension>,
 }
 
 impl Event {
@@ -134,8 +198,14 @@ impl Event {
         user_metadata.insert("x-rustfs-object-version-id".to_string(), "1".to_string());
         user_metadata.insert("x-request-time".to_string(), Utc::now().to_rfc3339());
 
+        let mut request_parameters = HashMap::new();
+        request_parameters.insert("sourceIPAddress".to_string(), "127.0.0.1".to_string());
+
+        let mut response_elements = HashMap::new();
+        initialize_response_elements(&mut response_elements, &RESPONSE_ELEMENT_KEYS);
+
         Event {
-            event_version: "2.1".to_string(),
+            event_version: EVENT_VERSION.to_string(),
             event_source: "rustfs:s3".to_string(),
             aws_region: "us-east-1".to_string(),
             event_time: Utc::now(),
@@ -143,33 +213,36 @@ impl Event {
             user_identity: Identity {
                 principal_id: "rustfs".to_string(),
             },
-            request_parameters: HashMap::new(),
-            response_elements: HashMap::new(),
+            request_parameters,
+            response_elements,
             s3: Metadata {
-                schema_version: "1.0".to_string(),
+                schema_version: S3_SCHEMA_VERSION.to_string(),
                 configuration_id: "test-config".to_string(),
                 bucket: Bucket {
                     name: bucket.to_string(),
                     owner_identity: Identity {
                         principal_id: "rustfs".to_string(),
                     },
-                    arn: format!("arn:rustfs:s3:::{bucket}"),
+                    arn: format!("arn:aws:s3:::{bucket}"),
                 },
                 object: Object {
                     key: key.to_string(),
                     size: Some(1024),
                     etag: Some("etag123".to_string()),
-                    content_type: Some("application/octet-stream".to_string()),
-                    user_metadata: Some(user_metadata),
                     version_id: Some("1".to_string()),
                     sequencer: "0055AED6DCD90281E5".to_string(),
                 },
             },
-            source: Source {
-                host: "127.0.0.1".to_string(),
-                port: "9000".to_string(),
-                user_agent: "RustFS (linux; amd64) rustfs-rs/0.1".to_string(),
-            },
+            rustfs: Some(Extension {
+                schema_version: EXTENSION_SCHEMA_VERSION.to_string(),
+                content_type: Some("application/octet-stream".to_string()),
+                user_metadata: Some(user_metadata),
+                source: Source {
+                    host: "127.0.0.1".to_string(),
+                    port: "9000".to_string(),
+                    user_agent: "RustFS (linux; amd64) rustfs-rs/0.1".to_string(),
+                },
+            }),
         }
     }
     /// Return event mask
@@ -178,26 +251,48 @@ impl Event {
     }
 
     pub fn new(args: EventArgs) -> Self {
-        let event_time = Utc::now().naive_local();
-        let unique_id = match args.object.mod_time {
-            Some(t) => format!("{:X}", t.unix_timestamp_nanos()),
-            None => format!("{:X}", event_time.and_utc().timestamp_nanos_opt().unwrap_or(0)),
-        };
+        let extended = rustfs_utils::get_env_bool(ENV_NOTIFY_EXTENDED_METADATA, DEFAULT_NOTIFY_EXTENDED_METADATA);
+        Self::with_extension(args, extended)
+    }
 
-        let mut resp_elements = args.resp_elements.clone();
-        initialize_response_elements(&mut resp_elements, &["x-amz-request-id", "x-amz-id-2"]);
+    /// Build the event record, attaching the `rustfs` extension block when `extended` is set.
+    pub fn with_extension(args: EventArgs, extended: bool) -> Self {
+        let event_time = Utc::now();
+        let unique_id = sequencer(match args.object.mod_time {
+            Some(t) => t.unix_timestamp_nanos() as i64,
+            None => event_time.timestamp_nanos_opt().unwrap_or(0),
+        });
+
+        let mut response_elements: HashMap<String, String> = RESPONSE_ELEMENT_KEYS
+            .iter()
+            .filter_map(|key| args.resp_elements.get(*key).map(|v| (key.to_string(), v.clone())))
+            .collect();
+        response_elements.extend(
+            args.resp_elements
+                .iter()
+                .filter(|(key, _)| {
+                    [SCANNER_ELEMENT_PREFIX, BACKUP_ELEMENT_PREFIX, DRIVE_ELEMENT_PREFIX]
+                        .iter()
+                        .any(|prefix| key.starts_with(prefix))
+                })
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+        initialize_response_elements(&mut response_elements, &RESPONSE_ELEMENT_KEYS);
 
         // URL encoding of object keys
         let key_name = form_urlencoded::byte_serialize(args.object.name.as_bytes()).collect::<String>();
-        let principal_id = args.req_params.get("principalId").unwrap_or(&String::new()).to_string();
+        let principal_id = args.req_params.get("principalId").cloned().unwrap_or_default();
 
         let version_id = match args.object.version_id {
             Some(id) => Some(id.to_string()),
-            None => Some(args.version_id.clone()),
+            None => Some(args.version_id.clone()).filter(|id| !id.is_empty()),
         };
 
+        let mut request_parameters = HashMap::new();
+        request_parameters.insert("sourceIPAddress".to_string(), source_ip_address(&args.req_params));
+
         let mut s3_metadata = Metadata {
-            schema_version: "1.0".to_string(),
+            schema_version: S3_SCHEMA_VERSION.to_string(),
             configuration_id: "Config".to_string(), // or from args
             bucket: Bucket {
                 name: args.bucket_name.clone(),
@@ -219,10 +314,20 @@ impl Event {
             EventName::ObjectRemovedDelete | EventName::ObjectRemovedDeleteMarkerCreated
         );
 
+        let mut extension = Extension {
+            schema_version: EXTENSION_SCHEMA_VERSION.to_string(),
+            source: Source {
+                host: args.host,
+                port: "".to_string(),
+                user_agent: args.user_agent,
+            },
+            ..Default::default()
+        };
+
         if !is_removed_event {
             s3_metadata.object.size = Some(args.object.size);
             s3_metadata.object.etag = args.object.etag.clone();
-            s3_metadata.object.content_type = args.object.content_type.clone();
+            extension.content_type = args.object.content_type.clone();
             // Filter out internal reserved metadata
             let mut user_metadata = HashMap::new();
             for (k, v) in args.object.user_defined.iter() {
@@ -230,24 +335,25 @@ impl Event {
                     user_metadata.insert(k.clone(), v.clone());
                 }
             }
-            s3_metadata.object.user_metadata = Some(user_metadata);
+            extension.user_metadata = Some(user_metadata);
         }
 
         Self {
-            event_version: "2.1".to_string(),
+            event_version: EVENT_VERSION.to_string(),
             event_source: "rustfs:s3".to_string(),
-            aws_region: args.req_params.get("region").cloned().unwrap_or_default(),
-            event_time: event_time.and_utc(),
+            aws_region: args
+                .req_params
+                .get("region")
+                .cloned()
+                .or_else(rustfs_ecstore::global::get_global_region)
+                .unwrap_or_default(),
+            event_time,
             event_name: args.event_name,
             user_identity: Identity { principal_id },
-            request_parameters: args.req_params,
-            response_elements: resp_elements,
+            request_parameters,
+            response_elements,
             s3: s3_metadata,
-            source: Source {
-                host: args.host,
-                port: "".to_string(),
-                user_agent: args.user_agent,
-            },
+            rustfs: extended.then_some(extension),
         }
     }
 }
@@ -258,6 +364,19 @@ fn initialize_response_elements(elements: &mut HashMap<String, String>, keys: &[
     }
 }
 
+/// The client address of the request, taken from the proxy headers when present.
+fn source_ip_address(req_params: &HashMap<String, String>) -> String {
+    if let Some(ip) = req_params.get("sourceIPAddress") {
+        return ip.clone();
+    }
+    if let Some(forwarded) = req_params.get("x-forwarded-for")
+        && let Some(ip) = forwarded.split(',').map(str::trim).find(|ip| !ip.is_empty())
+    {
+        return ip.to_string();
+    }
+    req_params.get("x-real-ip").cloned().unwrap_or_default()
+}
+
 #[derive(Debug, Clone)]
 pub struct EventArgs {
     pub event_name: EventName,
@@ -393,3 +512,111 @@ impl EventArgsBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustfs_targets::TargetLog;
+
+    fn args(event_name: EventName) -> EventArgs {
+        let object = rustfs_ecstore::store_api::ObjectInfo {
+            name: "dir/a b.txt".to_string(),
+            size: 42,
+            etag: Some("abc".to_string()),
+            content_type: Some("text/plain".to_string()),
+            ..Default::default()
+        };
+        EventArgsBuilder::new(event_name, "bucket", object)
+            .req_param("authorization", "secret")
+            .req_param("x-forwarded-for", "10.0.0.1, 10.0.0.2")
+            .resp_element("x-amz-request-id", "req-1")
+            .resp_element("content-length", "0")
+            .host("node1")
+            .build()
+    }
+
+    #[test]
+    fn test_event_record_matches_s3_schema() {
+        let event = Event::with_extension(args(EventName::ObjectCreatedPut), false);
+        let value = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(value["eventVersion"], "2.1");
+        assert_eq!(value["eventName"], "ObjectCreated:Put");
+        assert!(value["eventTime"].as_str().unwrap().ends_with('Z'));
+        assert_eq!(value["userIdentity"]["principalId"], "");
+        assert_eq!(value["requestParameters"], serde_json::json!({"sourceIPAddress": "10.0.0.1"}));
+        assert_eq!(
+            value["responseElements"],
+            serde_json::json!({"x-amz-request-id": "req-1", "x-amz-id-2": ""})
+        );
+        assert_eq!(value["s3"]["s3SchemaVersion"], "1.0");
+        assert_eq!(value["s3"]["bucket"]["ownerIdentity"]["principalId"], "");
+        assert_eq!(value["s3"]["object"]["key"], "dir%2Fa+b.txt");
+        assert_eq!(value["s3"]["object"]["eTag"], "abc");
+        assert_eq!(value["s3"]["object"]["size"], 42);
+        assert!(value["s3"]["object"].get("versionId").is_none());
+        assert!(value.get("rustfs").is_none());
+    }
+
+    #[test]
+    fn test_event_extension_is_optional() {
+        let event = Event::with_extension(args(EventName::ObjectCreatedPut), true);
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["rustfs"]["schemaVersion"], EXTENSION_SCHEMA_VERSION);
+        assert_eq!(value["rustfs"]["contentType"], "text/plain");
+        assert_eq!(value["rustfs"]["source"]["host"], "node1");
+
+        let decoded: Event = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.event_name, EventName::ObjectCreatedPut);
+    }
+
+    #[test]
+    fn test_scanner_cycle_elements_are_kept() {
+        let args = EventArgsBuilder::new(EventName::ScannerCycleComplete, "bucket", Default::default())
+            .resp_element("x-rustfs-scanner-objects-delta", "-3")
+            .resp_element("x-rustfs-other", "dropped")
+            .build();
+        let value = serde_json::to_value(Event::with_extension(args, false)).unwrap();
+
+        assert_eq!(value["eventName"], "Scanner:CycleComplete");
+        assert_eq!(value["responseElements"]["x-rustfs-scanner-objects-delta"], "-3");
+        assert!(value["responseElements"].get("x-rustfs-other").is_none());
+    }
+
+    #[test]
+    fn test_target_log_wraps_records() {
+        let event = Event::new_test_event("bucket", "key", EventName::ObjectRemovedDelete);
+        let log = TargetLog {
+            event_name: event.event_name,
+            key: "bucket/key".to_string(),
+            records: vec![event],
+        };
+        let value = serde_json::to_value(&log).unwrap();
+        assert_eq!(value["EventName"], "s3:ObjectRemoved:Delete");
+        assert_eq!(value["Key"], "bucket/key");
+        assert_eq!(value["Records"][0]["eventName"], "ObjectRemoved:Delete");
+    }
+
+    #[test]
+    fn test_sequencer_is_unique_and_ordered() {
+        let a = Event::with_extension(args(EventName::ObjectCreatedPut), false)
+            .s3
+            .object
+            .sequencer;
+        let b = Event::with_extension(args(EventName::ObjectCreatedPut), false)
+            .s3
+            .object
+            .sequencer;
+        assert_ne!(a, b);
+        assert_eq!(a.len(), 24);
+        assert!(sequencer(1) < sequencer(0x10));
+    }
+
+    #[test]
+    fn test_event_name_accepts_legacy_payloads() {
+        assert_eq!(EventName::from_payload("ObjectCreatedPut").unwrap(), EventName::ObjectCreatedPut);
+        assert_eq!(EventName::from_payload("ObjectCreated:Put").unwrap(), EventName::ObjectCreatedPut);
+        assert_eq!(EventName::from_payload("s3:ObjectCreated:Put").unwrap(), EventName::ObjectCreatedPut);
+        assert!(EventName::from_payload("Nope").is_err());
+    }
+}
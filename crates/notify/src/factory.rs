@@ -16,17 +16,33 @@ use crate::Event;
 use async_trait::async_trait;
 use hashbrown::HashSet;
 use rumqttc::QoS;
-use rustfs_config::notify::{ENV_NOTIFY_MQTT_KEYS, ENV_NOTIFY_WEBHOOK_KEYS, NOTIFY_MQTT_KEYS, NOTIFY_WEBHOOK_KEYS};
+use rustfs_config::notify::{
+    ENV_NOTIFY_ELASTICSEARCH_KEYS, ENV_NOTIFY_MQTT_KEYS, ENV_NOTIFY_MYSQL_KEYS, ENV_NOTIFY_POSTGRES_KEYS,
+    ENV_NOTIFY_WEBHOOK_KEYS, NOTIFY_ELASTICSEARCH_KEYS, NOTIFY_MQTT_KEYS, NOTIFY_MYSQL_KEYS, NOTIFY_POSTGRES_KEYS,
+    NOTIFY_WEBHOOK_KEYS,
+};
 use rustfs_config::{
-    DEFAULT_DIR, DEFAULT_LIMIT, MQTT_BROKER, MQTT_KEEP_ALIVE_INTERVAL, MQTT_PASSWORD, MQTT_QOS, MQTT_QUEUE_DIR, MQTT_QUEUE_LIMIT,
-    MQTT_RECONNECT_INTERVAL, MQTT_TOPIC, MQTT_USERNAME, WEBHOOK_AUTH_TOKEN, WEBHOOK_CLIENT_CERT, WEBHOOK_CLIENT_KEY,
-    WEBHOOK_ENDPOINT, WEBHOOK_QUEUE_DIR, WEBHOOK_QUEUE_LIMIT,
+    DEFAULT_DIR, DEFAULT_ELASTICSEARCH_BATCH_SIZE, DEFAULT_ELASTICSEARCH_MAX_RETRY, DEFAULT_LIMIT, DEFAULT_SQL_BATCH_SIZE,
+    DEFAULT_SQL_MAX_OPEN_CONNECTIONS, ELASTICSEARCH_BATCH_SIZE, ELASTICSEARCH_FORMAT, ELASTICSEARCH_INCLUDE_METADATA,
+    ELASTICSEARCH_INDEX, ELASTICSEARCH_MAX_RETRY, ELASTICSEARCH_PASSWORD, ELASTICSEARCH_QUEUE_DIR, ELASTICSEARCH_QUEUE_LIMIT,
+    ELASTICSEARCH_URL, ELASTICSEARCH_USERNAME, MQTT_BROKER, MQTT_KEEP_ALIVE_INTERVAL, MQTT_PASSWORD, MQTT_QOS, MQTT_QUEUE_DIR,
+    MQTT_QUEUE_LIMIT, MQTT_RECONNECT_INTERVAL, MQTT_TOPIC, MQTT_USERNAME, MYSQL_BATCH_SIZE, MYSQL_DSN_STRING, MYSQL_FORMAT,
+    MYSQL_MAX_OPEN_CONNECTIONS, MYSQL_QUEUE_DIR, MYSQL_QUEUE_LIMIT, MYSQL_TABLE, POSTGRES_BATCH_SIZE, POSTGRES_CONNECTION_STRING,
+    POSTGRES_FORMAT, POSTGRES_MAX_OPEN_CONNECTIONS, POSTGRES_QUEUE_DIR, POSTGRES_QUEUE_LIMIT, POSTGRES_TABLE, WEBHOOK_AUTH_TOKEN,
+    WEBHOOK_CLIENT_CERT, WEBHOOK_CLIENT_KEY, WEBHOOK_ENDPOINT, WEBHOOK_QUEUE_DIR, WEBHOOK_QUEUE_LIMIT,
 };
 use rustfs_ecstore::config::KVS;
 use rustfs_targets::{
     Target,
     error::TargetError,
-    target::{mqtt::MQTTArgs, webhook::WebhookArgs},
+    target::{
+        EventFormat,
+        elasticsearch::{ElasticsearchArgs, ElasticsearchTarget},
+        mqtt::MQTTArgs,
+        parse_bool,
+        sql::{SqlArgs, SqlDialect, SqlTarget},
+        webhook::WebhookArgs,
+    },
 };
 use std::time::Duration;
 use tracing::{debug, warn};
@@ -221,3 +237,172 @@ impl TargetFactory for MQTTTargetFactory {
         ENV_NOTIFY_MQTT_KEYS.iter().map(|s| s.to_string()).collect()
     }
 }
+
+/// Configuration keys of a SQL target type, which only differ in the name of the connection string.
+struct SqlKeys {
+    connection_string: &'static str,
+    table: &'static str,
+    format: &'static str,
+    max_open_connections: &'static str,
+    batch_size: &'static str,
+    queue_dir: &'static str,
+    queue_limit: &'static str,
+}
+
+const POSTGRES_SQL_KEYS: SqlKeys = SqlKeys {
+    connection_string: POSTGRES_CONNECTION_STRING,
+    table: POSTGRES_TABLE,
+    format: POSTGRES_FORMAT,
+    max_open_connections: POSTGRES_MAX_OPEN_CONNECTIONS,
+    batch_size: POSTGRES_BATCH_SIZE,
+    queue_dir: POSTGRES_QUEUE_DIR,
+    queue_limit: POSTGRES_QUEUE_LIMIT,
+};
+
+const MYSQL_SQL_KEYS: SqlKeys = SqlKeys {
+    connection_string: MYSQL_DSN_STRING,
+    table: MYSQL_TABLE,
+    format: MYSQL_FORMAT,
+    max_open_connections: MYSQL_MAX_OPEN_CONNECTIONS,
+    batch_size: MYSQL_BATCH_SIZE,
+    queue_dir: MYSQL_QUEUE_DIR,
+    queue_limit: MYSQL_QUEUE_LIMIT,
+};
+
+fn sql_args(dialect: SqlDialect, keys: &SqlKeys, config: &KVS) -> Result<SqlArgs, TargetError> {
+    let connection_string = config
+        .lookup(keys.connection_string)
+        .ok_or_else(|| TargetError::Configuration(format!("Missing {}", keys.connection_string)))?;
+    let table = config
+        .lookup(keys.table)
+        .ok_or_else(|| TargetError::Configuration(format!("Missing {}", keys.table)))?;
+
+    Ok(SqlArgs {
+        enable: true, // Assumed enabled.
+        dialect,
+        connection_string,
+        table,
+        format: EventFormat::parse(&config.lookup(keys.format).unwrap_or_default())?,
+        max_open_connections: config
+            .lookup(keys.max_open_connections)
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_SQL_MAX_OPEN_CONNECTIONS),
+        batch_size: config
+            .lookup(keys.batch_size)
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_SQL_BATCH_SIZE),
+        queue_dir: config.lookup(keys.queue_dir).unwrap_or(DEFAULT_DIR.to_string()),
+        queue_limit: config
+            .lookup(keys.queue_limit)
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_LIMIT),
+        target_type: rustfs_targets::target::TargetType::NotifyEvent,
+    })
+}
+
+/// Factory for creating PostgreSQL targets
+pub struct PostgresTargetFactory;
+
+#[async_trait]
+impl TargetFactory for PostgresTargetFactory {
+    async fn create_target(&self, id: String, config: &KVS) -> Result<Box<dyn Target<Event> + Send + Sync>, TargetError> {
+        let args = sql_args(SqlDialect::Postgres, &POSTGRES_SQL_KEYS, config)?;
+        Ok(Box::new(SqlTarget::new(id, args)?))
+    }
+
+    fn validate_config(&self, _id: &str, config: &KVS) -> Result<(), TargetError> {
+        sql_args(SqlDialect::Postgres, &POSTGRES_SQL_KEYS, config)?.validate()
+    }
+
+    fn get_valid_fields(&self) -> HashSet<String> {
+        NOTIFY_POSTGRES_KEYS.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn get_valid_env_fields(&self) -> HashSet<String> {
+        ENV_NOTIFY_POSTGRES_KEYS.iter().map(|s| s.to_string()).collect()
+    }
+}
+
+/// Factory for creating MySQL targets
+pub struct MySQLTargetFactory;
+
+#[async_trait]
+impl TargetFactory for MySQLTargetFactory {
+    async fn create_target(&self, id: String, config: &KVS) -> Result<Box<dyn Target<Event> + Send + Sync>, TargetError> {
+        let args = sql_args(SqlDialect::MySql, &MYSQL_SQL_KEYS, config)?;
+        Ok(Box::new(SqlTarget::new(id, args)?))
+    }
+
+    fn validate_config(&self, _id: &str, config: &KVS) -> Result<(), TargetError> {
+        sql_args(SqlDialect::MySql, &MYSQL_SQL_KEYS, config)?.validate()
+    }
+
+    fn get_valid_fields(&self) -> HashSet<String> {
+        NOTIFY_MYSQL_KEYS.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn get_valid_env_fields(&self) -> HashSet<String> {
+        ENV_NOTIFY_MYSQL_KEYS.iter().map(|s| s.to_string()).collect()
+    }
+}
+
+fn elasticsearch_args(config: &KVS) -> Result<ElasticsearchArgs, TargetError> {
+    let url = config
+        .lookup(ELASTICSEARCH_URL)
+        .ok_or_else(|| TargetError::Configuration("Missing Elasticsearch url".to_string()))?;
+    let url = Url::parse(url.trim())
+        .map_err(|e| TargetError::Configuration(format!("Invalid Elasticsearch URL: {e} (value: '{url}')")))?;
+    let index = config
+        .lookup(ELASTICSEARCH_INDEX)
+        .ok_or_else(|| TargetError::Configuration("Missing Elasticsearch index".to_string()))?;
+
+    Ok(ElasticsearchArgs {
+        enable: true, // Assumed enabled.
+        url,
+        index,
+        format: EventFormat::parse(&config.lookup(ELASTICSEARCH_FORMAT).unwrap_or_default())?,
+        username: config.lookup(ELASTICSEARCH_USERNAME).unwrap_or_default(),
+        password: config.lookup(ELASTICSEARCH_PASSWORD).unwrap_or_default(),
+        include_metadata: match config.lookup(ELASTICSEARCH_INCLUDE_METADATA) {
+            Some(v) if !v.is_empty() => parse_bool(&v)?,
+            _ => false,
+        },
+        batch_size: config
+            .lookup(ELASTICSEARCH_BATCH_SIZE)
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_ELASTICSEARCH_BATCH_SIZE),
+        max_retry: config
+            .lookup(ELASTICSEARCH_MAX_RETRY)
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_ELASTICSEARCH_MAX_RETRY),
+        queue_dir: config.lookup(ELASTICSEARCH_QUEUE_DIR).unwrap_or(DEFAULT_DIR.to_string()),
+        queue_limit: config
+            .lookup(ELASTICSEARCH_QUEUE_LIMIT)
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_LIMIT),
+        target_type: rustfs_targets::target::TargetType::NotifyEvent,
+    })
+}
+
+/// Factory for creating Elasticsearch and OpenSearch targets
+pub struct ElasticsearchTargetFactory;
+
+#[async_trait]
+impl TargetFactory for ElasticsearchTargetFactory {
+    async fn create_target(&self, id: String, config: &KVS) -> Result<Box<dyn Target<Event> + Send + Sync>, TargetError> {
+        let args = elasticsearch_args(config)?;
+        Ok(Box::new(ElasticsearchTarget::new(id, args)?))
+    }
+
+    fn validate_config(&self, _id: &str, config: &KVS) -> Result<(), TargetError> {
+        elasticsearch_args(config)?.validate()
+    }
+
+    fn get_valid_fields(&self) -> HashSet<String> {
+        NOTIFY_ELASTICSEARCH_KEYS.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn get_valid_env_fields(&self) -> HashSet<String> {
+        ENV_NOTIFY_ELASTICSEARCH_KEYS.iter().map(|s| s.to_string()).collect()
+    }
+}
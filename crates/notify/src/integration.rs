@@ -13,17 +13,29 @@
 // limitations under the License.
 
 use crate::{
-    Event, error::NotificationError, notifier::EventNotifier, registry::TargetRegistry, rules::BucketNotificationConfig, stream,
+    Event,
+    error::NotificationError,
+    journal::{EventJournal, JournalLag},
+    notifier::EventNotifier,
+    registry::TargetRegistry,
+    rules::BucketNotificationConfig,
+    stream,
 };
+use chrono::{DateTime, Utc};
 use hashbrown::HashMap;
+use rustfs_config::notify::{
+    DEFAULT_NOTIFY_JOURNAL_DIR, DEFAULT_NOTIFY_JOURNAL_RETENTION_HOURS, ENV_NOTIFY_JOURNAL_DIR,
+    ENV_NOTIFY_JOURNAL_RETENTION_HOURS,
+};
 use rustfs_ecstore::config::{Config, KVS};
 use rustfs_targets::EventName;
 use rustfs_targets::arn::TargetID;
 use rustfs_targets::store::{Key, Store};
 use rustfs_targets::target::EntityTarget;
 use rustfs_targets::{StoreError, Target};
+use serde::Serialize;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::{RwLock, Semaphore, mpsc};
 use tracing::{debug, error, info, warn};
@@ -89,6 +101,21 @@ impl NotificationMetrics {
     }
 }
 
+/// How long a journaled event may stay unaccepted before it is dispatched again.
+const REDELIVERY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Delivery lag and backlog of one target on this node
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetLag {
+    #[serde(flatten)]
+    pub journal: JournalLag,
+    /// Events waiting in the target's queue store
+    pub queued: usize,
+    /// Age of the oldest event in the queue store
+    pub oldest_queued_secs: Option<u64>,
+}
+
 /// The notification system that integrates all components
 pub struct NotificationSystem {
     /// The event notifier
@@ -103,13 +130,22 @@ pub struct NotificationSystem {
     concurrency_limiter: Arc<Semaphore>,
     /// Monitoring indicators
     metrics: Arc<NotificationMetrics>,
+    /// Whether the journal redelivery loop runs
+    redelivery_started: AtomicBool,
 }
 
 impl NotificationSystem {
     /// Creates a new NotificationSystem
     pub fn new(config: Config) -> Self {
+        let journal_dir = rustfs_utils::get_env_str(ENV_NOTIFY_JOURNAL_DIR, DEFAULT_NOTIFY_JOURNAL_DIR);
+        let journal = (!journal_dir.is_empty()).then(|| {
+            let retention_hours =
+                rustfs_utils::get_env_u64(ENV_NOTIFY_JOURNAL_RETENTION_HOURS, DEFAULT_NOTIFY_JOURNAL_RETENTION_HOURS);
+            Arc::new(EventJournal::new(journal_dir, Duration::from_secs(retention_hours * 3600)))
+        });
+
         NotificationSystem {
-            notifier: Arc::new(EventNotifier::new()),
+            notifier: Arc::new(EventNotifier::with_journal(journal)),
             registry: Arc::new(TargetRegistry::new()),
             config: Arc::new(RwLock::new(config)),
             stream_cancellers: Arc::new(RwLock::new(HashMap::new())),
@@ -120,6 +156,7 @@ impl NotificationSystem {
                     .unwrap_or(20),
             )), // Limit the maximum number of concurrent processing events to 20
             metrics: Arc::new(NotificationMetrics::new()),
+            redelivery_started: AtomicBool::new(false),
         }
     }
 
@@ -177,10 +214,66 @@ impl NotificationSystem {
         *self.stream_cancellers.write().await = cancellers;
         // Initialize the bucket target
         self.notifier.init_bucket_targets(targets).await?;
+        self.start_redelivery();
         info!("Notification system initialized");
         Ok(())
     }
 
+    /// Dispatches journaled events left over from a crash right away, and events no target
+    /// accepted periodically after that.
+    fn start_redelivery(&self) {
+        if self.notifier.journal().is_none() || self.redelivery_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let notifier = self.notifier.clone();
+        tokio::spawn(async move {
+            notifier.redeliver_pending(Duration::ZERO).await;
+            let mut interval = tokio::time::interval(REDELIVERY_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                notifier.redeliver_pending(REDELIVERY_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Delivery lag and backlog of every active target, by target ID.
+    pub async fn target_lag(&self) -> HashMap<String, TargetLag> {
+        let journal = self.notifier.journal();
+        let target_list = self.notifier.target_list();
+        let target_list = target_list.read().await;
+
+        let mut lag = HashMap::new();
+        for target_id in target_list.keys() {
+            let mut target_lag = TargetLag {
+                journal: journal.as_ref().map(|j| j.lag(&target_id)).unwrap_or_default(),
+                ..Default::default()
+            };
+            if let Some(target) = target_list.get(&target_id)
+                && let Some(store) = target.store()
+            {
+                target_lag.queued = store.len();
+                target_lag.oldest_queued_secs = store.oldest_entry_age().map(|age| age.as_secs());
+            }
+            lag.insert(target_id.to_string(), target_lag);
+        }
+        lag
+    }
+
+    /// Dispatches the events journaled for `target_id` within `[start, end]` again.
+    pub async fn replay_events(
+        &self,
+        target_id: &TargetID,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<usize, NotificationError> {
+        if start > end {
+            return Err(NotificationError::Configuration("replay start must not be after end".to_string()));
+        }
+        self.notifier.replay(target_id, start, end).await
+    }
+
     /// Gets a list of Targets for all currently active (initialized).
     ///
     /// # Return
@@ -0,0 +1,374 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Write-ahead journal of the events dispatched to each target.
+//!
+//! Every event is appended to the journal of its target before it is handed over, and an ack
+//! record follows once the target accepted it. Events without an ack are dispatched again after
+//! a crash or a failed delivery, so delivery is at least once; consumers drop duplicates by the
+//! event sequencer. Acked events stay in the journal for the retention period and can be
+//! replayed by time range.
+//!
+//! Each target has its own directory with one append-only segment per hour, holding one JSON
+//! record per line.
+
+use crate::Event;
+use chrono::{DateTime, Utc};
+use hashbrown::HashMap;
+use rustfs_targets::arn::TargetID;
+use rustfs_targets::target::EntityTarget;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const SEGMENT_EXT: &str = "journal";
+const SEGMENT_SECS: i64 = 3600;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum JournalRecord {
+    Event {
+        seq: u64,
+        time: DateTime<Utc>,
+        event: Box<EntityTarget<Event>>,
+    },
+    Ack {
+        seq: u64,
+    },
+}
+
+/// Delivery state of one target, as seen by its journal.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalLag {
+    /// Events not yet accepted by the target.
+    pub pending: usize,
+    /// Age of the oldest pending event.
+    pub oldest_pending_secs: Option<u64>,
+    /// Events accepted by the target since the node started.
+    pub delivered: u64,
+    pub last_delivered: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct TargetJournal {
+    next_seq: u64,
+    /// Pending sequence numbers and the time they were journaled.
+    pending: BTreeMap<u64, DateTime<Utc>>,
+    delivered: u64,
+    last_delivered: Option<DateTime<Utc>>,
+}
+
+/// Journals of all targets of this node.
+pub struct EventJournal {
+    dir: PathBuf,
+    retention: Duration,
+    targets: Mutex<HashMap<String, TargetJournal>>,
+}
+
+impl EventJournal {
+    pub fn new(dir: impl Into<PathBuf>, retention: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            retention,
+            targets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn target_dir(&self, target_id: &TargetID) -> PathBuf {
+        self.dir.join(target_id.to_id_string().replace(['/', '\\'], "_"))
+    }
+
+    fn segment_path(dir: &Path, time: DateTime<Utc>) -> PathBuf {
+        dir.join(format!("{:012}.{SEGMENT_EXT}", time.timestamp().div_euclid(SEGMENT_SECS)))
+    }
+
+    /// Segments of a target, oldest first, with the hour they start at.
+    fn segments(dir: &Path) -> Vec<(i64, PathBuf)> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut segments: Vec<(i64, PathBuf)> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()?.to_str()? != SEGMENT_EXT {
+                    return None;
+                }
+                let hour = path.file_stem()?.to_str()?.parse().ok()?;
+                Some((hour, path))
+            })
+            .collect();
+        segments.sort();
+        segments
+    }
+
+    fn read_segment(path: &Path) -> Vec<JournalRecord> {
+        let Ok(file) = fs::File::open(path) else {
+            return Vec::new();
+        };
+        // A torn last line from a crash is skipped, everything before it is intact.
+        BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect()
+    }
+
+    fn append_record(dir: &Path, time: DateTime<Utc>, record: &JournalRecord) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let mut line = serde_json::to_vec(record).map_err(io::Error::other)?;
+        line.push(b'\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(Self::segment_path(dir, time))?;
+        file.write_all(&line)?;
+        file.sync_data()
+    }
+
+    /// Rebuild the in-memory state of a target from its segments on first use.
+    fn load<'a>(&self, targets: &'a mut HashMap<String, TargetJournal>, target_id: &TargetID) -> &'a mut TargetJournal {
+        targets.entry(target_id.to_id_string()).or_insert_with(|| {
+            let mut journal = TargetJournal::default();
+            for (_, path) in Self::segments(&self.target_dir(target_id)) {
+                for record in Self::read_segment(&path) {
+                    match record {
+                        JournalRecord::Event { seq, time, .. } => {
+                            journal.pending.insert(seq, time);
+                            journal.next_seq = journal.next_seq.max(seq + 1);
+                        }
+                        JournalRecord::Ack { seq } => {
+                            journal.pending.remove(&seq);
+                        }
+                    }
+                }
+            }
+            journal
+        })
+    }
+
+    /// Journal `event` for `target_id` before it is dispatched, returning its sequence number.
+    pub fn append(&self, target_id: &TargetID, event: &EntityTarget<Event>) -> io::Result<u64> {
+        let mut targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+        let journal = self.load(&mut targets, target_id);
+
+        let seq = journal.next_seq;
+        let time = Utc::now();
+        let record = JournalRecord::Event {
+            seq,
+            time,
+            event: Box::new(event.clone()),
+        };
+        Self::append_record(&self.target_dir(target_id), time, &record)?;
+
+        journal.next_seq += 1;
+        journal.pending.insert(seq, time);
+        Ok(seq)
+    }
+
+    /// Record that the target accepted the event `seq`.
+    pub fn ack(&self, target_id: &TargetID, seq: u64) -> io::Result<()> {
+        let mut targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+        let journal = self.load(&mut targets, target_id);
+        if journal.pending.remove(&seq).is_none() {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        journal.delivered += 1;
+        journal.last_delivered = Some(now);
+        Self::append_record(&self.target_dir(target_id), now, &JournalRecord::Ack { seq })
+    }
+
+    /// Events of `target_id` journaled at least `min_age` ago and not acked yet, oldest first.
+    pub fn pending_events(&self, target_id: &TargetID, min_age: Duration) -> Vec<(u64, EntityTarget<Event>)> {
+        let Some(cutoff) = chrono::Duration::from_std(min_age)
+            .ok()
+            .and_then(|age| Utc::now().checked_sub_signed(age))
+        else {
+            return Vec::new();
+        };
+        let pending: BTreeMap<u64, DateTime<Utc>> = {
+            let mut targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+            let journal = self.load(&mut targets, target_id);
+            journal
+                .pending
+                .iter()
+                .filter(|(_, time)| **time <= cutoff)
+                .map(|(s, t)| (*s, *t))
+                .collect()
+        };
+        let Some(oldest) = pending.values().min() else {
+            return Vec::new();
+        };
+        let first_hour = oldest.timestamp().div_euclid(SEGMENT_SECS);
+
+        let mut events = Vec::with_capacity(pending.len());
+        for (hour, path) in Self::segments(&self.target_dir(target_id)) {
+            if hour < first_hour {
+                continue;
+            }
+            for record in Self::read_segment(&path) {
+                if let JournalRecord::Event { seq, event, .. } = record
+                    && pending.contains_key(&seq)
+                {
+                    events.push((seq, *event));
+                }
+            }
+        }
+        events
+    }
+
+    /// Events of `target_id` journaled within `[start, end]`, delivered or not, oldest first.
+    pub fn events_between(&self, target_id: &TargetID, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<EntityTarget<Event>> {
+        let (first_hour, last_hour) = (start.timestamp().div_euclid(SEGMENT_SECS), end.timestamp().div_euclid(SEGMENT_SECS));
+
+        let mut events = Vec::new();
+        for (hour, path) in Self::segments(&self.target_dir(target_id)) {
+            if hour < first_hour || hour > last_hour {
+                continue;
+            }
+            for record in Self::read_segment(&path) {
+                if let JournalRecord::Event { time, event, .. } = record
+                    && time >= start
+                    && time <= end
+                {
+                    events.push(*event);
+                }
+            }
+        }
+        events
+    }
+
+    pub fn lag(&self, target_id: &TargetID) -> JournalLag {
+        let mut targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+        let journal = self.load(&mut targets, target_id);
+        let now = Utc::now();
+        JournalLag {
+            pending: journal.pending.len(),
+            oldest_pending_secs: journal
+                .pending
+                .values()
+                .min()
+                .map(|time| (now - *time).num_seconds().max(0) as u64),
+            delivered: journal.delivered,
+            last_delivered: journal.last_delivered,
+        }
+    }
+
+    /// Remove segments past the retention period that hold no pending event.
+    pub fn prune(&self, target_id: &TargetID) {
+        let oldest_pending_hour = {
+            let mut targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+            let journal = self.load(&mut targets, target_id);
+            journal
+                .pending
+                .values()
+                .min()
+                .map(|time| time.timestamp().div_euclid(SEGMENT_SECS))
+        };
+        let retention_secs = i64::try_from(self.retention.as_secs()).unwrap_or(i64::MAX);
+        let keep_from = Utc::now()
+            .timestamp()
+            .saturating_sub(retention_secs)
+            .div_euclid(SEGMENT_SECS)
+            .min(oldest_pending_hour.unwrap_or(i64::MAX));
+
+        for (hour, path) in Self::segments(&self.target_dir(target_id)) {
+            if hour >= keep_from {
+                break;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => debug!("removed journal segment {}", path.display()),
+                Err(err) => warn!("remove journal segment {} failed: {}", path.display(), err),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustfs_targets::EventName;
+
+    fn entity(key: &str) -> EntityTarget<Event> {
+        EntityTarget {
+            object_name: key.to_string(),
+            bucket_name: "bucket".to_string(),
+            event_name: EventName::ObjectCreatedPut,
+            data: Event::new_test_event("bucket", key, EventName::ObjectCreatedPut),
+        }
+    }
+
+    #[test]
+    fn test_journal_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = TargetID::new("1".to_string(), "webhook".to_string());
+
+        let journal = EventJournal::new(dir.path(), Duration::from_secs(3600));
+        let first = journal.append(&target, &entity("a")).unwrap();
+        let second = journal.append(&target, &entity("b")).unwrap();
+        journal.ack(&target, first).unwrap();
+        assert_eq!(journal.lag(&target).pending, 1);
+        assert_eq!(journal.lag(&target).delivered, 1);
+
+        let reopened = EventJournal::new(dir.path(), Duration::from_secs(3600));
+        let pending = reopened.pending_events(&target, Duration::ZERO);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].0, second);
+        assert_eq!(pending[0].1.object_name, "b");
+        assert_eq!(reopened.append(&target, &entity("c")).unwrap(), second + 1);
+
+        let now = Utc::now();
+        let replayed = reopened.events_between(&target, now - chrono::Duration::hours(1), now);
+        assert_eq!(replayed.iter().map(|e| e.object_name.as_str()).collect::<Vec<_>>(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_prune_keeps_pending_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = TargetID::new("1".to_string(), "webhook".to_string());
+        let journal = EventJournal::new(dir.path(), Duration::ZERO);
+
+        let old = Utc::now() - chrono::Duration::hours(3);
+        let tdir = journal.target_dir(&target);
+        EventJournal::append_record(
+            &tdir,
+            old,
+            &JournalRecord::Event {
+                seq: 0,
+                time: old,
+                event: Box::new(entity("old")),
+            },
+        )
+        .unwrap();
+
+        journal.prune(&target);
+        assert_eq!(EventJournal::segments(&tdir).len(), 1);
+
+        journal.ack(&target, 0).unwrap();
+        journal.prune(&target);
+        assert!(journal.pending_events(&target, Duration::ZERO).is_empty());
+        assert!(
+            EventJournal::segments(&tdir)
+                .iter()
+                .all(|(hour, _)| *hour > old.timestamp() / SEGMENT_SECS)
+        );
+    }
+}
@@ -23,13 +23,14 @@ mod event;
 pub mod factory;
 mod global;
 pub mod integration;
+pub mod journal;
 pub mod notifier;
 pub mod registry;
 pub mod rules;
 pub mod stream;
 
 pub use error::{LifecycleError, NotificationError};
-pub use event::{Event, EventArgs, EventArgsBuilder};
+pub use event::{BACKUP_ELEMENT_PREFIX, DRIVE_ELEMENT_PREFIX, Event, EventArgs, EventArgsBuilder, SCANNER_ELEMENT_PREFIX};
 pub use global::{initialize, is_notification_system_initialized, notification_system, notifier_global};
-pub use integration::NotificationSystem;
+pub use integration::{NotificationSystem, TargetLag};
 pub use rules::BucketNotificationConfig;
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{error::NotificationError, event::Event, rules::RulesMap};
+use crate::{error::NotificationError, event::Event, journal::EventJournal, rules::RulesMap};
 use hashbrown::HashMap;
 use rustfs_targets::EventName;
 use rustfs_targets::Target;
@@ -20,6 +20,7 @@ use rustfs_targets::arn::TargetID;
 use rustfs_targets::target::EntityTarget;
 use starshard::AsyncShardedHashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument, warn};
 
@@ -27,6 +28,8 @@ use tracing::{debug, error, info, instrument, warn};
 pub struct EventNotifier {
     target_list: Arc<RwLock<TargetList>>,
     bucket_rules_map: Arc<AsyncShardedHashMap<String, RulesMap, rustc_hash::FxBuildHasher>>,
+    /// Write-ahead journal of dispatched events, None when journaling is off
+    journal: Option<Arc<EventJournal>>,
 }
 
 impl Default for EventNotifier {
@@ -38,12 +41,23 @@ impl Default for EventNotifier {
 impl EventNotifier {
     /// Creates a new EventNotifier
     pub fn new() -> Self {
+        Self::with_journal(None)
+    }
+
+    /// Creates a new EventNotifier that journals every event before dispatching it
+    pub fn with_journal(journal: Option<Arc<EventJournal>>) -> Self {
         EventNotifier {
             target_list: Arc::new(RwLock::new(TargetList::new())),
             bucket_rules_map: Arc::new(AsyncShardedHashMap::new(0)),
+            journal,
         }
     }
 
+    /// Returns the event journal, if journaling is on
+    pub fn journal(&self) -> Option<Arc<EventJournal>> {
+        self.journal.clone()
+    }
+
     /// Returns a reference to the target list
     /// This method provides access to the target list for external use.
     ///
@@ -160,11 +174,28 @@ impl EventNotifier {
                             event_name,
                             data: event_clone.clone().as_ref().clone(),
                         });
+                        // Journal before handing over, so the event is dispatched again if this node
+                        // crashes or the target rejects it
+                        let journaled =
+                            self.journal
+                                .as_ref()
+                                .and_then(|journal| match journal.append(&target_id, &entity_target) {
+                                    Ok(seq) => Some((journal.clone(), target_id.clone(), seq)),
+                                    Err(e) => {
+                                        error!("Failed to journal event for target {}: {}", target_name_for_task, e);
+                                        None
+                                    }
+                                });
                         let handle = tokio::spawn(async move {
                             if let Err(e) = cloned_target_for_task.save(entity_target.clone()).await {
                                 error!("Failed to send event to target {}: {}", target_name_for_task, e);
                             } else {
                                 debug!("Successfully saved event to target {}", target_name_for_task);
+                                if let Some((journal, target_id, seq)) = journaled
+                                    && let Err(e) = journal.ack(&target_id, seq)
+                                {
+                                    warn!("Failed to ack journaled event for target {}: {}", target_name_for_task, e);
+                                }
                             }
                         });
                         handles.push(handle);
@@ -187,6 +218,71 @@ impl EventNotifier {
         }
     }
 
+    /// Dispatches the journaled events that no target accepted within `min_age` again, and
+    /// prunes the journal segments past their retention.
+    pub async fn redeliver_pending(&self, min_age: Duration) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+
+        let targets: Vec<_> = {
+            let target_list_guard = self.target_list.read().await;
+            target_list_guard
+                .keys()
+                .into_iter()
+                .filter_map(|id| target_list_guard.get(&id).map(|target| (id, target)))
+                .collect()
+        };
+
+        for (target_id, target) in targets {
+            let pending = journal.pending_events(&target_id, min_age);
+            if !pending.is_empty() {
+                info!("Redelivering {} journaled events to target {}", pending.len(), target_id);
+            }
+            for (seq, event) in pending {
+                match target.save(Arc::new(event)).await {
+                    Ok(()) => {
+                        if let Err(e) = journal.ack(&target_id, seq) {
+                            warn!("Failed to ack journaled event for target {}: {}", target_id, e);
+                        }
+                    }
+                    Err(e) => {
+                        // Keep the rest for the next round, the target is most likely still down
+                        warn!("Redelivery to target {} failed: {}", target_id, e);
+                        break;
+                    }
+                }
+            }
+            journal.prune(&target_id);
+        }
+    }
+
+    /// Dispatches the events journaled for `target_id` within `[start, end]` again, returning how
+    /// many were accepted.
+    pub async fn replay(
+        &self,
+        target_id: &TargetID,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<usize, NotificationError> {
+        let Some(journal) = &self.journal else {
+            return Err(NotificationError::Configuration("event journal is not enabled".to_string()));
+        };
+        let Some(target) = self.target_list.read().await.get(target_id) else {
+            return Err(NotificationError::TargetNotFound(target_id.clone()));
+        };
+
+        let events = journal.events_between(target_id, start, end);
+        let total = events.len();
+        let mut replayed = 0;
+        for event in events {
+            target.save(Arc::new(event)).await?;
+            replayed += 1;
+        }
+        info!("Replayed {}/{} journaled events to target {}", replayed, total, target_id);
+        Ok(replayed)
+    }
+
     /// Initializes the targets for buckets
     #[instrument(skip(self, targets_to_init))]
     pub async fn init_bucket_targets(
@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use crate::Event;
-use crate::factory::{MQTTTargetFactory, TargetFactory, WebhookTargetFactory};
+use crate::factory::{
+    ElasticsearchTargetFactory, MQTTTargetFactory, MySQLTargetFactory, PostgresTargetFactory, TargetFactory, WebhookTargetFactory,
+};
 use futures::stream::{FuturesUnordered, StreamExt};
 use hashbrown::{HashMap, HashSet};
 use rustfs_config::{DEFAULT_DELIMITER, ENABLE_KEY, ENV_PREFIX, notify::NOTIFY_ROUTE_PREFIX};
@@ -42,6 +44,9 @@ impl TargetRegistry {
         // Register built-in factories
         registry.register(ChannelTargetType::Webhook.as_str(), Box::new(WebhookTargetFactory));
         registry.register(ChannelTargetType::Mqtt.as_str(), Box::new(MQTTTargetFactory));
+        registry.register(ChannelTargetType::Postgres.as_str(), Box::new(PostgresTargetFactory));
+        registry.register(ChannelTargetType::Mysql.as_str(), Box::new(MySQLTargetFactory));
+        registry.register(ChannelTargetType::Elasticsearch.as_str(), Box::new(ElasticsearchTargetFactory));
 
         registry
     }
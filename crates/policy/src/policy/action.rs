@@ -363,6 +363,8 @@ pub enum AdminAction {
     ListServiceAccountsAdminAction,
     #[strum(serialize = "admin:ListTemporaryAccounts")]
     ListTemporaryAccountsAdminAction,
+    #[strum(serialize = "admin:RevokeTemporaryAccounts")]
+    RevokeTemporaryAccountsAdminAction,
     #[strum(serialize = "admin:AddUserToGroup")]
     AddUserToGroupAdminAction,
     #[strum(serialize = "admin:RemoveUserFromGroup")]
@@ -466,6 +468,7 @@ impl AdminAction {
                 | AdminAction::RemoveServiceAccountAdminAction
                 | AdminAction::ListServiceAccountsAdminAction
                 | AdminAction::ListTemporaryAccountsAdminAction
+                | AdminAction::RevokeTemporaryAccountsAdminAction
                 | AdminAction::AddUserToGroupAdminAction
                 | AdminAction::RemoveUserFromGroupAdminAction
                 | AdminAction::GetGroupAdminAction
@@ -148,6 +148,9 @@ pub enum S3KeyName {
     #[strum(serialize = "s3:x-amz-server-side-encryption-customer-algorithm")]
     S3XAmzServerSideEncryptionCustomerAlgorithm,
 
+    #[strum(serialize = "s3:x-amz-server-side-encryption-aws-kms-key-id")]
+    S3XAmzServerSideEncryptionAwsKmsKeyId,
+
     #[strum(serialize = "s3:signatureversion")]
     S3SignatureVersion,
 
@@ -327,6 +330,10 @@ mod tests {
     use test_case::test_case;
 
     #[test_case("s3:x-amz-copy-source", KeyName::S3(S3KeyName::S3XAmzCopySource))]
+    #[test_case(
+        "s3:x-amz-server-side-encryption-aws-kms-key-id",
+        KeyName::S3(S3KeyName::S3XAmzServerSideEncryptionAwsKmsKeyId)
+    )]
     #[test_case("aws:SecureTransport", KeyName::Aws(AwsKeyName::AWSSecureTransport))]
     #[test_case("jwt:sub", KeyName::Jwt(JwtKeyName::JWTSub))]
     #[test_case("ldap:user", KeyName::Ldap(LdapKeyName::User))]
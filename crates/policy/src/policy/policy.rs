@@ -12,7 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{Effect, Error as IamError, ID, Statement, action::Action, statement::BPStatement};
+use super::{
+    Effect, Error as IamError, ID, Statement,
+    action::{Action, S3Action},
+    statement::BPStatement,
+};
 use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -21,6 +25,12 @@ use std::collections::{HashMap, HashSet};
 /// https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_policies_elements_version.html
 pub const DEFAULT_VERSION: &str = "2012-10-17";
 
+/// Actions any of which makes a bucket visible to a caller, e.g. in ListBuckets.
+pub const BUCKET_ACCESS_ACTIONS: [Action; 2] = [
+    Action::S3Action(S3Action::ListBucketAction),
+    Action::S3Action(S3Action::GetBucketLocationAction),
+];
+
 /// check the data is Validator
 pub trait Validator {
     type Error;
@@ -51,6 +61,17 @@ impl Args<'_> {
     }
 }
 
+/// Outcome of evaluating a request against a policy, together with the statements that matched it.
+#[derive(Serialize, Clone, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Evaluation<S> {
+    pub allowed: bool,
+    /// Deny statements matching the request, any of them denies it.
+    pub denied_by: Vec<S>,
+    /// Allow statements matching the request.
+    pub allowed_by: Vec<S>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Default, Debug)]
 pub struct Policy {
     #[serde(default, rename = "ID")]
@@ -82,6 +103,41 @@ impl Policy {
         false
     }
 
+    /// Same decision as [`Policy::is_allowed`], also reporting every statement that matched.
+    pub fn evaluate(&self, args: &Args) -> Evaluation<Statement> {
+        let denied_by: Vec<Statement> = self
+            .statements
+            .iter()
+            .filter(|s| matches!(s.effect, Effect::Deny) && !s.is_allowed(args))
+            .cloned()
+            .collect();
+        let allowed_by: Vec<Statement> = self
+            .statements
+            .iter()
+            .filter(|s| matches!(s.effect, Effect::Allow) && s.is_allowed(args))
+            .cloned()
+            .collect();
+
+        Evaluation {
+            allowed: denied_by.is_empty() && (args.deny_only || args.is_owner || !allowed_by.is_empty()),
+            denied_by,
+            allowed_by,
+        }
+    }
+
+    /// Bucket-level pre-check: whether `args` is allowed any of [`BUCKET_ACCESS_ACTIONS`] on `args.bucket`.
+    ///
+    /// The action and object carried by `args` are ignored.
+    pub fn is_bucket_allowed(&self, args: &Args) -> bool {
+        BUCKET_ACCESS_ACTIONS.iter().any(|action| {
+            self.is_allowed(&Args {
+                action: *action,
+                object: "",
+                ..args.clone()
+            })
+        })
+    }
+
     pub fn match_resource(&self, resource: &str) -> bool {
         for statement in self.statements.iter() {
             if statement.resources.match_resource(resource) {
@@ -207,6 +263,28 @@ impl BucketPolicy {
 
         false
     }
+
+    /// Same decision as [`BucketPolicy::is_allowed`], also reporting every statement that matched.
+    pub fn evaluate(&self, args: &BucketPolicyArgs) -> Evaluation<BPStatement> {
+        let denied_by: Vec<BPStatement> = self
+            .statements
+            .iter()
+            .filter(|s| matches!(s.effect, Effect::Deny) && !s.is_allowed(args))
+            .cloned()
+            .collect();
+        let allowed_by: Vec<BPStatement> = self
+            .statements
+            .iter()
+            .filter(|s| matches!(s.effect, Effect::Allow) && s.is_allowed(args))
+            .cloned()
+            .collect();
+
+        Evaluation {
+            allowed: denied_by.is_empty() && (args.is_owner || !allowed_by.is_empty()),
+            denied_by,
+            allowed_by,
+        }
+    }
 }
 
 impl Validator for BucketPolicy {
@@ -525,4 +603,115 @@ mod test {
         // assert_eq!(p, p2);
         Ok(())
     }
+
+    #[test]
+    fn test_is_bucket_allowed() -> Result<()> {
+        let data = r#"
+{
+  "Version": "2012-10-17",
+  "Statement": [
+    {
+      "Effect": "Allow",
+      "Action": ["s3:ListBucket"],
+      "Resource": ["arn:aws:s3:::visible"]
+    },
+    {
+      "Effect": "Allow",
+      "Action": ["s3:GetObject"],
+      "Resource": ["arn:aws:s3:::objects-only/*"]
+    },
+    {
+      "Effect": "Allow",
+      "Action": ["s3:*"],
+      "Resource": ["arn:aws:s3:::denied", "arn:aws:s3:::denied/*"]
+    },
+    {
+      "Effect": "Deny",
+      "Action": ["s3:ListBucket", "s3:GetBucketLocation"],
+      "Resource": ["arn:aws:s3:::denied"]
+    }
+  ]
+}
+"#;
+
+        let p = Policy::parse_config(data.as_bytes())?;
+        let groups = None;
+        let conditions = HashMap::new();
+        let claims = HashMap::new();
+        let args = |bucket: &'static str| Args {
+            account: "user",
+            groups: &groups,
+            action: Action::S3Action(S3Action::GetObjectAction),
+            bucket,
+            conditions: &conditions,
+            is_owner: false,
+            object: "some/key",
+            claims: &claims,
+            deny_only: false,
+        };
+
+        assert!(p.is_bucket_allowed(&args("visible")));
+        assert!(!p.is_bucket_allowed(&args("objects-only")));
+        assert!(!p.is_bucket_allowed(&args("denied")));
+        assert!(!p.is_bucket_allowed(&args("other")));
+        Ok(())
+    }
+    #[test]
+    fn test_evaluate_reports_matched_statements() -> Result<()> {
+        let data = r#"
+{
+  "Version": "2012-10-17",
+  "Statement": [
+    {
+      "Sid": "read",
+      "Effect": "Allow",
+      "Action": ["s3:GetObject"],
+      "Resource": ["arn:aws:s3:::data/*"]
+    },
+    {
+      "Sid": "secrets",
+      "Effect": "Deny",
+      "Action": ["s3:*"],
+      "Resource": ["arn:aws:s3:::data/secret/*"]
+    }
+  ]
+}
+"#;
+
+        let p = Policy::parse_config(data.as_bytes())?;
+        let groups = None;
+        let conditions = HashMap::new();
+        let claims = HashMap::new();
+        let args = |object: &'static str| Args {
+            account: "user",
+            groups: &groups,
+            action: Action::S3Action(S3Action::GetObjectAction),
+            bucket: "data",
+            conditions: &conditions,
+            is_owner: false,
+            object,
+            claims: &claims,
+            deny_only: false,
+        };
+
+        let eval = p.evaluate(&args("public/a.txt"));
+        assert!(eval.allowed);
+        assert_eq!(eval.allowed, p.is_allowed(&args("public/a.txt")));
+        assert_eq!(eval.allowed_by.len(), 1);
+        assert!(eval.denied_by.is_empty());
+
+        let eval = p.evaluate(&args("secret/a.txt"));
+        assert!(!eval.allowed);
+        assert_eq!(eval.allowed, p.is_allowed(&args("secret/a.txt")));
+        assert_eq!(eval.allowed_by.len(), 1);
+        assert_eq!(eval.denied_by.len(), 1);
+
+        let eval = p.evaluate(&Args {
+            bucket: "other",
+            ..args("a.txt")
+        });
+        assert!(!eval.allowed);
+        assert!(eval.allowed_by.is_empty() && eval.denied_by.is_empty());
+        Ok(())
+    }
 }
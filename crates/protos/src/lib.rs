@@ -60,6 +60,10 @@ pub async fn node_service_time_out_client(
         channel,
         Box::new(move |mut req: Request<()>| {
             req.metadata_mut().insert("authorization", token.clone());
+            // Propagate the deadline of the request being served so the peer gives up together with us
+            if let Some(remaining) = rustfs_common::deadline::remaining() {
+                req.set_timeout(remaining);
+            }
             Ok(req)
         }),
     ))
@@ -71,3 +71,8 @@ pub struct InvalidChecksum;
 pub fn is_checksum_mismatch(err: &(dyn std::error::Error + 'static)) -> bool {
     err.downcast_ref::<ChecksumMismatch>().is_some()
 }
+
+/// Check if an error is a Content-MD5 mismatch
+pub fn is_bad_digest(err: &(dyn std::error::Error + 'static)) -> bool {
+    err.downcast_ref::<BadDigest>().is_some()
+}
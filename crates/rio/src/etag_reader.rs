@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use crate::compress_index::{Index, TryGetIndex};
-use crate::{EtagResolvable, HashReaderDetector, HashReaderMut, Reader};
+use crate::{BadDigest, EtagResolvable, HashReaderDetector, HashReaderMut, Reader};
 use md5::{Digest, Md5};
 use pin_project_lite::pin_project;
 use std::pin::Pin;
@@ -66,7 +66,13 @@ impl AsyncRead for EtagReader {
                     let etag_hex = hex_simd::encode_to_string(etag, hex_simd::AsciiCase::Lower);
                     if *checksum != etag_hex {
                         error!("Checksum mismatch, expected={:?}, actual={:?}", checksum, etag_hex);
-                        return Poll::Ready(Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Checksum mismatch")));
+                        return Poll::Ready(Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            BadDigest {
+                                expected_md5: checksum.clone(),
+                                calculated_md5: etag_hex,
+                            },
+                        )));
                     }
                 }
             }
@@ -243,5 +249,6 @@ mod tests {
         // Verification failed, should return InvalidData error
         let err = etag_reader.read_to_end(&mut buf).await.unwrap_err();
         assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.get_ref().is_some_and(|e| crate::is_bad_digest(e)));
     }
 }
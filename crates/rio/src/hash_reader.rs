@@ -152,7 +152,12 @@ pin_project! {
         content_hasher: Option<Box<dyn ChecksumHasher>>,
         content_sha256: Option<String>,
         content_sha256_hasher: Option<Sha256Hasher>,
+        // SHA-256 of the data read, computed on request regardless of the client's headers
+        digest_hasher: Option<Sha256Hasher>,
+        digest: Option<String>,
         checksum_on_finish: bool,
+        // Resolve the ETag from the wrapped readers even though this reader computes no MD5
+        inner_etag: bool,
 
         trailer_s3s: Option<TrailingHeaders>,
 
@@ -224,7 +229,10 @@ impl HashReader {
                 content_sha256_hasher,
                 content_hash,
                 content_hasher,
+                digest_hasher: None,
+                digest: None,
                 checksum_on_finish: false,
+                inner_etag: false,
                 trailer_s3s: existing_hash_reader.get_trailer().cloned(),
             });
         }
@@ -251,11 +259,36 @@ impl HashReader {
             content_hasher: None,
             content_sha256: sha256hex.clone(),
             content_sha256_hasher: sha256hex.clone().map(|_| Sha256Hasher::new()),
+            digest_hasher: None,
+            digest: None,
             checksum_on_finish: false,
+            inner_etag: false,
             trailer_s3s: None,
         })
     }
 
+    /// Wrap a reader that transforms the output of another `HashReader`, such as compression or
+    /// encryption, keeping the ETag of the untransformed data instead of hashing the transformed bytes.
+    pub fn new_with_inner_etag(inner: Box<dyn Reader>, actual_size: i64) -> Self {
+        Self {
+            inner,
+            size: -1,
+            checksum: None,
+            actual_size,
+            diskable_md5: true,
+            bytes_read: 0,
+            content_hash: None,
+            content_hasher: None,
+            content_sha256: None,
+            content_sha256_hasher: None,
+            digest_hasher: None,
+            digest: None,
+            checksum_on_finish: false,
+            inner_etag: true,
+            trailer_s3s: None,
+        }
+    }
+
     pub fn into_inner(self) -> Box<dyn Reader> {
         self.inner
     }
@@ -282,6 +315,17 @@ impl HashReader {
         self.actual_size
     }
 
+    /// Compute the hex SHA-256 of the data read, available from `digest` once the reader is drained.
+    pub fn compute_digest(&mut self) {
+        if self.bytes_read == 0 {
+            self.digest_hasher = Some(Sha256Hasher::new());
+        }
+    }
+
+    pub fn digest(&self) -> Option<&str> {
+        self.digest.as_deref()
+    }
+
     pub fn add_checksum_from_s3s(
         &mut self,
         headers: &HeaderMap,
@@ -457,6 +501,12 @@ impl AsyncRead for HashReader {
                         }
                     }
 
+                    if let Some(hasher) = this.digest_hasher {
+                        if let Err(e) = hasher.write_all(data) {
+                            return Poll::Ready(Err(std::io::Error::other(e)));
+                        }
+                    }
+
                     // Update content hasher
                     if let Some(hasher) = this.content_hasher {
                         if let Err(e) = hasher.write_all(data) {
@@ -466,6 +516,10 @@ impl AsyncRead for HashReader {
                 }
 
                 if filled == 0 && !*this.checksum_on_finish {
+                    if let Some(hasher) = this.digest_hasher {
+                        *this.digest = Some(hex_simd::encode_to_string(hasher.finalize(), hex_simd::AsciiCase::Lower));
+                    }
+
                     // check SHA256
                     if let (Some(hasher), Some(expected_sha256)) = (this.content_sha256_hasher, this.content_sha256) {
                         let sha256 = hex_simd::encode_to_string(hasher.finalize(), hex_simd::AsciiCase::Lower);
@@ -524,7 +578,7 @@ impl AsyncRead for HashReader {
 
 impl EtagResolvable for HashReader {
     fn try_resolve_etag(&mut self) -> Option<String> {
-        if self.diskable_md5 {
+        if self.diskable_md5 && !self.inner_etag {
             return None;
         }
         if let Some(etag) = self.inner.try_resolve_etag() {
@@ -605,6 +659,22 @@ mod tests {
         assert_eq!(buf, data);
     }
 
+    #[tokio::test]
+    async fn test_hashreader_digest() {
+        let data = b"hello world";
+        let reader = Box::new(WarpReader::new(Cursor::new(&data[..])));
+        let mut hash_reader = HashReader::new(reader, data.len() as i64, data.len() as i64, None, None, false).unwrap();
+        hash_reader.compute_digest();
+        assert!(hash_reader.digest().is_none());
+
+        let mut buf = Vec::new();
+        hash_reader.read_to_end(&mut buf).await.unwrap();
+        assert_eq!(
+            hash_reader.digest(),
+            Some("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9")
+        );
+    }
+
     #[tokio::test]
     async fn test_hashreader_diskable_md5() {
         let data = b"no etag";
@@ -619,6 +689,27 @@ mod tests {
         assert_eq!(buf, data);
     }
 
+    #[tokio::test]
+    async fn test_hashreader_inner_etag() {
+        use crate::CompressReader;
+        use md5::{Digest, Md5};
+        use rustfs_utils::compress::CompressionAlgorithm;
+
+        let data = b"inner etag survives compression ".repeat(64);
+        let expected = faster_hex::hex_string(Md5::digest(&data).as_slice());
+
+        let reader = Box::new(WarpReader::new(BufReader::new(Cursor::new(data.clone()))));
+        let plain = HashReader::new(reader, data.len() as i64, data.len() as i64, None, None, false).unwrap();
+        let compressed = Box::new(CompressReader::new(plain, CompressionAlgorithm::default()));
+
+        let mut hash_reader = HashReader::new_with_inner_etag(compressed, data.len() as i64);
+        let mut buf = Vec::new();
+        hash_reader.read_to_end(&mut buf).await.unwrap();
+
+        assert_ne!(buf, data);
+        assert_eq!(hash_reader.try_resolve_etag(), Some(expected));
+    }
+
     #[tokio::test]
     async fn test_hashreader_new_logic() {
         let data = b"test data";
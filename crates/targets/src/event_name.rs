@@ -31,7 +31,7 @@ impl std::error::Error for ParseEventNameError {}
 /// Based on AWS S3 event type and includes RustFS extension.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
 pub enum EventName {
-    // Single event type (values are 1-32 for compatible mask logic)
+    // Single event type (values are 1-35 for compatible mask logic)
     ObjectAccessedGet = 1,
     ObjectAccessedGetRetention = 2,
     ObjectAccessedGetLegalHold = 3,
@@ -64,6 +64,9 @@ pub enum EventName {
     ScannerLargeVersions = 30,               // ObjectLargeVersions corresponding to Go
     ScannerBigPrefix = 31,                   // PrefixManyFolders corresponding to Go
     LifecycleDelMarkerExpirationDelete = 32, // ILMDelMarkerExpirationDelete corresponding to Go
+    ScannerCycleComplete = 33,               // A scanner cycle finished a bucket, RustFS extension
+    BackupFailed = 34,                       // A scheduled bucket backup run failed, RustFS extension
+    DriveHealthDegraded = 35,                // SMART counters of a drive went up, RustFS extension
 
     // Compound "All" event type (no sequential value for mask)
     ObjectAccessedAll,
@@ -78,7 +81,7 @@ pub enum EventName {
 }
 
 // Single event type sequential array for Everything.expand()
-const SINGLE_EVENT_NAMES_IN_ORDER: [EventName; 32] = [
+const SINGLE_EVENT_NAMES_IN_ORDER: [EventName; 35] = [
     EventName::ObjectAccessedGet,
     EventName::ObjectAccessedGetRetention,
     EventName::ObjectAccessedGetLegalHold,
@@ -111,9 +114,12 @@ const SINGLE_EVENT_NAMES_IN_ORDER: [EventName; 32] = [
     EventName::ScannerLargeVersions,
     EventName::ScannerBigPrefix,
     EventName::LifecycleDelMarkerExpirationDelete,
+    EventName::ScannerCycleComplete,
+    EventName::BackupFailed,
+    EventName::DriveHealthDegraded,
 ];
 
-const LAST_SINGLE_TYPE_VALUE: u32 = EventName::LifecycleDelMarkerExpirationDelete as u32;
+const LAST_SINGLE_TYPE_VALUE: u32 = EventName::DriveHealthDegraded as u32;
 
 impl EventName {
     /// The parsed string is EventName.
@@ -157,6 +163,9 @@ impl EventName {
             "s3:Scanner:ManyVersions" => Ok(EventName::ScannerManyVersions),
             "s3:Scanner:LargeVersions" => Ok(EventName::ScannerLargeVersions),
             "s3:Scanner:BigPrefix" => Ok(EventName::ScannerBigPrefix),
+            "s3:Scanner:CycleComplete" => Ok(EventName::ScannerCycleComplete),
+            "s3:Backup:Failed" => Ok(EventName::BackupFailed),
+            "s3:Drive:HealthDegraded" => Ok(EventName::DriveHealthDegraded),
             // ObjectScannerAll and Everything cannot be parsed from strings, because the Go version also does not define their string representation.
             _ => Err(ParseEventNameError(s.to_string())),
         }
@@ -203,6 +212,9 @@ impl EventName {
             EventName::ScannerManyVersions => "s3:Scanner:ManyVersions",
             EventName::ScannerLargeVersions => "s3:Scanner:LargeVersions",
             EventName::ScannerBigPrefix => "s3:Scanner:BigPrefix",
+            EventName::ScannerCycleComplete => "s3:Scanner:CycleComplete",
+            EventName::BackupFailed => "s3:Backup:Failed",
+            EventName::DriveHealthDegraded => "s3:Drive:HealthDegraded",
             // Go's String() returns "" for ObjectScannerAll and Everything
             EventName::ObjectScannerAll => "s3:Scanner:*", // Follow the pattern in Go Expand
             EventName::Everything => "",                   // Go String() returns "" to unprocessed
@@ -249,6 +261,7 @@ impl EventName {
                 EventName::ScannerManyVersions,
                 EventName::ScannerLargeVersions,
                 EventName::ScannerBigPrefix,
+                EventName::ScannerCycleComplete,
             ],
             EventName::Everything => {
                 // New
@@ -289,3 +302,58 @@ impl From<&str> for EventName {
         EventName::parse(event_str).unwrap_or_else(|e| panic!("{}", e))
     }
 }
+
+impl EventName {
+    /// Returns the event name as used in the `eventName` field of S3 event records,
+    /// e.g. `ObjectCreated:Put`.
+    pub fn record_name(&self) -> &'static str {
+        self.as_str().trim_start_matches("s3:")
+    }
+
+    /// Parse an event name from a notification payload.
+    ///
+    /// Accepts the `s3:` prefixed form, the record form without prefix, and the variant name
+    /// written by earlier versions into queued events.
+    pub fn from_payload(s: &str) -> Result<Self, ParseEventNameError> {
+        use serde::de::IntoDeserializer;
+
+        if let Ok(name) = EventName::parse(s) {
+            return Ok(name);
+        }
+        if let Ok(name) = EventName::parse(&format!("s3:{s}")) {
+            return Ok(name);
+        }
+        let de: serde::de::value::StrDeserializer<'_, serde::de::value::Error> = s.into_deserializer();
+        EventName::deserialize(de).map_err(|_| ParseEventNameError(s.to_string()))
+    }
+}
+
+/// Serialize an [`EventName`] in its `s3:` prefixed form, as in `s3:ObjectCreated:Put`.
+pub mod s3_event_name {
+    use super::EventName;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(name: &EventName, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(name.as_str())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<EventName, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        EventName::from_payload(&s).map_err(D::Error::custom)
+    }
+}
+
+/// Serialize an [`EventName`] in the S3 event record form, as in `ObjectCreated:Put`.
+pub mod record_event_name {
+    use super::EventName;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(name: &EventName, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(name.record_name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<EventName, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        EventName::from_payload(&s).map_err(D::Error::custom)
+    }
+}
@@ -21,14 +21,19 @@ pub mod target;
 
 pub use check::check_mqtt_broker_available;
 pub use error::{StoreError, TargetError};
-pub use event_name::EventName;
+pub use event_name::{EventName, record_event_name, s3_event_name};
 use serde::{Deserialize, Serialize};
 pub use target::Target;
 
 /// Represents a log of events for sending to targets
+///
+/// Serialized as `{"EventName": "s3:ObjectCreated:Put", "Key": "bucket/object", "Records": [...]}`,
+/// so consumers of S3 event notifications can read `Records` unchanged.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
 pub struct TargetLog<E> {
     /// The event name
+    #[serde(with = "s3_event_name")]
     pub event_name: EventName,
     /// The object key
     pub key: String,
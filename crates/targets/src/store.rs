@@ -22,7 +22,7 @@ use std::{
     collections::HashMap,
     marker::PhantomData,
     path::PathBuf,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use tracing::{debug, warn};
 use uuid::Uuid;
@@ -160,6 +160,11 @@ where
     /// Returns true if the store is empty
     fn is_empty(&self) -> bool;
 
+    /// Returns how long the oldest item has been waiting, if the store tracks it
+    fn oldest_entry_age(&self) -> Option<Duration> {
+        None
+    }
+
     /// Clones the store into a boxed trait object
     fn boxed_clone(&self) -> Box<dyn Store<T, Error = Self::Error, Key = Self::Key> + Send + Sync>;
 }
@@ -488,6 +493,13 @@ where
         self.len() == 0
     }
 
+    fn oldest_entry_age(&self) -> Option<Duration> {
+        let entries = self.entries.read().ok()?;
+        let oldest = *entries.values().min()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos() as i64;
+        Some(Duration::from_nanos(now.saturating_sub(oldest).max(0) as u64))
+    }
+
     fn boxed_clone(&self) -> Box<dyn Store<T, Error = Self::Error, Key = Self::Key> + Send + Sync> {
         Box::new(self.clone()) as Box<dyn Store<T, Error = Self::Error, Key = Self::Key> + Send + Sync>
     }
@@ -0,0 +1,844 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Elasticsearch and OpenSearch notification target.
+//!
+//! Events are written with the bulk API into an index named by a template, where `{bucket}` is
+//! replaced by the bucket of the event and `{date}` by its day as `YYYY.MM.DD`. As with the SQL
+//! targets, the `namespace` format keeps one document per object, with the key as id, and deletes
+//! it when the object is removed, while the `access` format adds a document per event.
+//!
+//! Documents carry the event record under `Records` next to `bucket`, `key`, `eventName` and
+//! `eventTime`, and optionally the user metadata and tags of the object under `metadata` and
+//! `tags`, so the namespace can be searched by any of them.
+
+use crate::target::{ChannelTargetType, EntityTarget, EventFormat, TargetType, is_object_removal};
+use crate::{
+    StoreError, Target,
+    arn::TargetID,
+    error::TargetError,
+    store::{Key, Store},
+};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode, Url};
+use rustfs_config::notify::STORE_EXTENSION;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, instrument, warn};
+
+/// Placeholder of the index template replaced by the bucket of the event.
+pub const INDEX_BUCKET_PLACEHOLDER: &str = "{bucket}";
+/// Placeholder of the index template replaced by the day of the event, `YYYY.MM.DD`.
+pub const INDEX_DATE_PLACEHOLDER: &str = "{date}";
+
+/// How long a partial batch waits before it is written.
+const BATCH_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+const BASE_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Prefix of the user metadata keys of an object.
+const USER_METADATA_PREFIX: &str = "x-amz-meta-";
+
+/// Metadata key holding the tags of an object, URL encoded.
+const TAGGING_KEY: &str = "x-amz-tagging";
+
+/// Arguments for configuring an Elasticsearch target
+#[derive(Debug, Clone)]
+pub struct ElasticsearchArgs {
+    /// Whether the target is enabled
+    pub enable: bool,
+    /// The URL of the cluster
+    pub url: Url,
+    /// Template of the index name, see [`INDEX_BUCKET_PLACEHOLDER`] and [`INDEX_DATE_PLACEHOLDER`]
+    pub index: String,
+    /// The layout of the index
+    pub format: EventFormat,
+    /// The user for basic authentication
+    pub username: String,
+    /// The password for basic authentication
+    pub password: String,
+    /// Whether documents carry the user metadata and tags of the object
+    pub include_metadata: bool,
+    /// Number of events written in one bulk request. Only applies without a queue directory,
+    /// whose events are delivered one at a time.
+    pub batch_size: usize,
+    /// How many times failed operations of a bulk request are retried
+    pub max_retry: u32,
+    /// The directory to store events in case of failure
+    pub queue_dir: String,
+    /// The maximum number of events to store
+    pub queue_limit: u64,
+    /// the target type
+    pub target_type: TargetType,
+}
+
+impl ElasticsearchArgs {
+    /// ElasticsearchArgs verification method
+    pub fn validate(&self) -> Result<(), TargetError> {
+        if !self.enable {
+            return Ok(());
+        }
+
+        if !matches!(self.url.scheme(), "http" | "https") {
+            return Err(TargetError::Configuration(format!(
+                "unsupported Elasticsearch URL scheme '{}'",
+                self.url.scheme()
+            )));
+        }
+
+        validate_index_template(&self.index)?;
+        if self.format == EventFormat::Namespace && self.index.contains(INDEX_DATE_PLACEHOLDER) {
+            return Err(TargetError::Configuration(format!(
+                "the index of the namespace format cannot contain {INDEX_DATE_PLACEHOLDER}, objects would have a document per day"
+            )));
+        }
+
+        if self.username.is_empty() != self.password.is_empty() {
+            return Err(TargetError::Configuration(
+                "username and password must be specified as a pair".to_string(),
+            ));
+        }
+
+        if self.batch_size == 0 {
+            return Err(TargetError::Configuration("batch_size must be at least 1".to_string()));
+        }
+
+        if !self.queue_dir.is_empty() && !std::path::Path::new(&self.queue_dir).is_absolute() {
+            return Err(TargetError::Configuration("elasticsearch queueDir path should be absolute".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks the index names `template` renders to are valid.
+fn validate_index_template(template: &str) -> Result<(), TargetError> {
+    let name = render_index(template, "bucket", "2024.01.01");
+    let valid = !name.is_empty()
+        && name.len() <= 255
+        && name != "."
+        && name != ".."
+        && !name.starts_with(['-', '_', '+'])
+        && name == name.to_lowercase()
+        && !name.contains(['\\', '/', '*', '?', '"', '<', '>', '|', ' ', ',', '#', ':']);
+    if !valid {
+        return Err(TargetError::Configuration(format!("invalid index name template '{template}'")));
+    }
+    Ok(())
+}
+
+fn render_index(template: &str, bucket: &str, date: &str) -> String {
+    template
+        .replace(INDEX_BUCKET_PLACEHOLDER, bucket)
+        .replace(INDEX_DATE_PLACEHOLDER, date)
+}
+
+/// The day of an RFC 3339 time as `YYYY.MM.DD`.
+fn index_date(event_time: Option<&str>) -> String {
+    if let Some(day) = event_time
+        .and_then(|t| t.get(..10))
+        .filter(|d| d.as_bytes().get(4) == Some(&b'-'))
+    {
+        return day.replace('-', ".");
+    }
+
+    // Days since the epoch to a civil date, from Howard Hinnant's algorithms
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or(0) as i64;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}.{month:02}.{day:02}")
+}
+
+/// The user metadata and tags of the object of an event record.
+fn object_metadata(record: &Value) -> (Map<String, Value>, Map<String, Value>) {
+    let mut metadata = Map::new();
+    let mut tags = Map::new();
+    let Some(user_metadata) = record.pointer("/rustfs/userMetadata").and_then(Value::as_object) else {
+        return (metadata, tags);
+    };
+
+    for (key, value) in user_metadata {
+        let Some(value) = value.as_str() else {
+            continue;
+        };
+        let lower = key.to_lowercase();
+        if lower == TAGGING_KEY {
+            for (tag, tag_value) in url::form_urlencoded::parse(value.as_bytes()) {
+                tags.insert(tag.into_owned(), Value::String(tag_value.into_owned()));
+            }
+        } else if let Some(name) = lower.strip_prefix(USER_METADATA_PREFIX) {
+            metadata.insert(name.to_string(), Value::String(value.to_string()));
+        }
+    }
+    (metadata, tags)
+}
+
+/// An operation of a bulk request.
+#[derive(Debug, Clone, PartialEq)]
+enum BulkOp {
+    Index {
+        index: String,
+        id: Option<String>,
+        document: Value,
+    },
+    Delete {
+        index: String,
+        id: String,
+    },
+}
+
+impl BulkOp {
+    fn from_event<E>(args: &ElasticsearchArgs, event: &EntityTarget<E>) -> Result<Self, TargetError>
+    where
+        E: Send + Sync + 'static + Clone + Serialize,
+    {
+        let record = serde_json::to_value(&event.data)
+            .map_err(|e| TargetError::Serialization(format!("Failed to serialize event: {e}")))?;
+        let object_name = urlencoding::decode(&event.object_name)
+            .map_err(|e| TargetError::Encoding(format!("Failed to decode object key: {e}")))?;
+        let key = format!("{}/{}", event.bucket_name, object_name);
+        let event_time = record.get("eventTime").and_then(Value::as_str).map(str::to_string);
+        let index = render_index(&args.index, &event.bucket_name, &index_date(event_time.as_deref()));
+
+        if args.format == EventFormat::Namespace && is_object_removal(event.event_name) {
+            return Ok(BulkOp::Delete { index, id: key });
+        }
+
+        let mut document = json!({
+            "bucket": event.bucket_name,
+            "key": object_name,
+            "eventName": event.event_name.as_str(),
+            "eventTime": event_time,
+        });
+        if args.include_metadata {
+            let (metadata, tags) = object_metadata(&record);
+            document["metadata"] = Value::Object(metadata);
+            document["tags"] = Value::Object(tags);
+        }
+        document["Records"] = json!([record]);
+
+        let id = (args.format == EventFormat::Namespace).then_some(key);
+        Ok(BulkOp::Index { index, id, document })
+    }
+
+    /// Appends the NDJSON lines of the operation to `body`.
+    fn write_to(&self, body: &mut String) -> Result<(), TargetError> {
+        let lines = match self {
+            BulkOp::Index {
+                index,
+                id: Some(id),
+                document,
+            } => vec![json!({ "index": { "_index": index, "_id": id } }), document.clone()],
+            BulkOp::Index {
+                index,
+                id: None,
+                document,
+            } => vec![json!({ "index": { "_index": index } }), document.clone()],
+            BulkOp::Delete { index, id } => vec![json!({ "delete": { "_index": index, "_id": id } })],
+        };
+        for line in lines {
+            let line = serde_json::to_string(&line)
+                .map_err(|e| TargetError::Serialization(format!("Failed to serialize bulk operation: {e}")))?;
+            body.push_str(&line);
+            body.push('\n');
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkResponse {
+    errors: bool,
+    #[serde(default)]
+    items: Vec<HashMap<String, BulkItem>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkItem {
+    status: u16,
+    #[serde(default)]
+    error: Option<Value>,
+}
+
+/// What became of an operation of a bulk request.
+#[derive(Debug, PartialEq, Eq)]
+enum ItemOutcome {
+    Done,
+    Retry,
+    Failed(String),
+}
+
+impl BulkItem {
+    fn outcome(&self, op: &BulkOp) -> ItemOutcome {
+        match self.status {
+            200..=299 => ItemOutcome::Done,
+            // The document of a removed object may never have been indexed
+            404 if matches!(op, BulkOp::Delete { .. }) => ItemOutcome::Done,
+            429 | 500..=599 => ItemOutcome::Retry,
+            status => ItemOutcome::Failed(format!(
+                "status {status}: {}",
+                self.error.as_ref().map(Value::to_string).unwrap_or_default()
+            )),
+        }
+    }
+}
+
+fn retry_delay(attempt: u32) -> Duration {
+    BASE_RETRY_DELAY.saturating_mul(1u32 << attempt.min(16)).min(MAX_RETRY_DELAY)
+}
+
+/// Sends bulk requests of an Elasticsearch target, shared with its batch flusher.
+#[derive(Clone)]
+struct BulkWriter {
+    id: TargetID,
+    args: ElasticsearchArgs,
+    http_client: Client,
+}
+
+impl BulkWriter {
+    fn request(&self, method: reqwest::Method, path: &str) -> Result<reqwest::RequestBuilder, TargetError> {
+        let url = self.args.url.join(path)?;
+        let mut builder = self.http_client.request(method, url);
+        if !self.args.username.is_empty() {
+            builder = builder.basic_auth(&self.args.username, Some(&self.args.password));
+        }
+        Ok(builder)
+    }
+
+    /// Sends `ops` once; returns the operations to try again.
+    async fn send_once(&self, ops: Vec<BulkOp>) -> Result<Vec<BulkOp>, TargetError> {
+        let mut body = String::new();
+        for op in &ops {
+            op.write_to(&mut body)?;
+        }
+
+        let resp = self
+            .request(reqwest::Method::POST, "_bulk")?
+            .header("Content-Type", "application/x-ndjson")
+            .body(body)
+            .send()
+            .await;
+        let resp = match resp {
+            Ok(resp) => resp,
+            Err(e) if e.is_timeout() || e.is_connect() => {
+                debug!("Bulk request to {} failed: {}", self.id, e);
+                return Ok(ops);
+            }
+            Err(e) => return Err(TargetError::Request(format!("Failed to send bulk request: {e}"))),
+        };
+
+        let status = resp.status();
+        if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            debug!("Bulk request to {} returned {}", self.id, status);
+            return Ok(ops);
+        }
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(TargetError::Authentication(format!(
+                "{} returned '{}', please check the username and password",
+                self.args.url, status
+            )));
+        }
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(TargetError::Request(format!("{} returned '{}': {}", self.args.url, status, text)));
+        }
+
+        let bulk: BulkResponse = resp
+            .json()
+            .await
+            .map_err(|e| TargetError::Request(format!("Failed to parse bulk response: {e}")))?;
+        if !bulk.errors {
+            return Ok(Vec::new());
+        }
+
+        let mut retry = Vec::new();
+        let mut failed = Vec::new();
+        for (op, item) in ops.into_iter().zip(bulk.items.iter()) {
+            let outcome = item
+                .values()
+                .next()
+                .map_or_else(|| ItemOutcome::Failed("missing item".to_string()), |item| item.outcome(&op));
+            match outcome {
+                ItemOutcome::Done => {}
+                ItemOutcome::Retry => retry.push(op),
+                ItemOutcome::Failed(reason) => failed.push(reason),
+            }
+        }
+        if !failed.is_empty() {
+            return Err(TargetError::Request(format!(
+                "{} bulk operations failed, first: {}",
+                failed.len(),
+                failed[0]
+            )));
+        }
+        Ok(retry)
+    }
+
+    /// Writes the operations of `events` in one bulk request, retrying the failed ones.
+    async fn write<E>(&self, events: &[EntityTarget<E>]) -> Result<(), TargetError>
+    where
+        E: Send + Sync + 'static + Clone + Serialize,
+    {
+        let mut ops = events
+            .iter()
+            .map(|event| BulkOp::from_event(&self.args, event))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut attempt = 0;
+        loop {
+            ops = self.send_once(ops).await?;
+            if ops.is_empty() {
+                debug!("Events written to Elasticsearch target: {}", self.id);
+                return Ok(());
+            }
+            if attempt >= self.args.max_retry {
+                return Err(TargetError::Timeout(format!(
+                    "{} bulk operations still failing after {} attempts",
+                    ops.len(),
+                    attempt + 1
+                )));
+            }
+            tokio::time::sleep(retry_delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Writes the buffered events, if any.
+    async fn flush<E>(&self, batch: &Mutex<Vec<EntityTarget<E>>>) -> Result<(), TargetError>
+    where
+        E: Send + Sync + 'static + Clone + Serialize,
+    {
+        let events = std::mem::take(&mut *batch.lock().await);
+        if events.is_empty() {
+            return Ok(());
+        }
+        self.write(&events).await
+    }
+}
+
+/// A target that indexes events into Elasticsearch or OpenSearch
+pub struct ElasticsearchTarget<E>
+where
+    E: Send + Sync + 'static + Clone + Serialize + DeserializeOwned,
+{
+    id: TargetID,
+    args: ElasticsearchArgs,
+    writer: BulkWriter,
+    store: Option<Box<dyn Store<EntityTarget<E>, Error = StoreError, Key = Key> + Send + Sync>>,
+    initialized: AtomicBool,
+    /// Events waiting to be written together, when batching without a queue directory
+    batch: Arc<Mutex<Vec<EntityTarget<E>>>>,
+    flusher_started: Arc<AtomicBool>,
+    closed: Arc<AtomicBool>,
+}
+
+impl<E> ElasticsearchTarget<E>
+where
+    E: Send + Sync + 'static + Clone + Serialize + DeserializeOwned,
+{
+    /// Clones the ElasticsearchTarget, creating a new instance sharing its client and pending batch
+    pub fn clone_box(&self) -> Box<dyn Target<E> + Send + Sync> {
+        Box::new(ElasticsearchTarget {
+            id: self.id.clone(),
+            args: self.args.clone(),
+            writer: self.writer.clone(),
+            store: self.store.as_ref().map(|s| s.boxed_clone()),
+            initialized: AtomicBool::new(self.initialized.load(Ordering::SeqCst)),
+            batch: Arc::clone(&self.batch),
+            flusher_started: Arc::clone(&self.flusher_started),
+            closed: Arc::clone(&self.closed),
+        })
+    }
+
+    /// Creates a new ElasticsearchTarget
+    #[instrument(skip(args), fields(target_id = %id))]
+    pub fn new(id: String, args: ElasticsearchArgs) -> Result<Self, TargetError> {
+        args.validate()?;
+        let target_id = TargetID::new(id, ChannelTargetType::Elasticsearch.as_str().to_string());
+
+        let http_client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .user_agent(rustfs_utils::get_user_agent(rustfs_utils::ServiceType::Basis))
+            .build()
+            .map_err(|e| TargetError::Configuration(format!("Failed to build HTTP client: {e}")))?;
+
+        let queue_store = if !args.queue_dir.is_empty() {
+            let queue_dir = PathBuf::from(&args.queue_dir).join(format!(
+                "rustfs-{}-{}",
+                ChannelTargetType::Elasticsearch.as_str(),
+                target_id.id
+            ));
+
+            let extension = match args.target_type {
+                TargetType::AuditLog => rustfs_config::audit::AUDIT_STORE_EXTENSION,
+                TargetType::NotifyEvent => STORE_EXTENSION,
+            };
+
+            let store = crate::store::QueueStore::<EntityTarget<E>>::new(queue_dir, args.queue_limit, extension);
+
+            if let Err(e) = store.open() {
+                error!("Failed to open store for Elasticsearch target {}: {}", target_id.id, e);
+                return Err(TargetError::Storage(format!("{e}")));
+            }
+
+            Some(Box::new(store) as Box<dyn Store<EntityTarget<E>, Error = StoreError, Key = Key> + Send + Sync>)
+        } else {
+            None
+        };
+
+        let writer = BulkWriter {
+            id: target_id.clone(),
+            args: args.clone(),
+            http_client,
+        };
+
+        info!(target_id = %target_id.id, "Elasticsearch target created");
+        Ok(ElasticsearchTarget {
+            id: target_id,
+            args,
+            writer,
+            store: queue_store,
+            initialized: AtomicBool::new(false),
+            batch: Arc::new(Mutex::new(Vec::new())),
+            flusher_started: Arc::new(AtomicBool::new(false)),
+            closed: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    fn batching(&self) -> bool {
+        self.store.is_none() && self.args.batch_size > 1
+    }
+
+    async fn init(&self) -> Result<(), TargetError> {
+        if !self.initialized.load(Ordering::SeqCst) {
+            match self.is_active().await {
+                Ok(true) => {}
+                Ok(false) => return Err(TargetError::NotConnected),
+                Err(e) => {
+                    error!("Failed to check if Elasticsearch target {} is active: {}", self.id, e);
+                    return Err(e);
+                }
+            }
+            self.initialized.store(true, Ordering::SeqCst);
+            info!("Elasticsearch target {} initialized", self.id);
+        }
+
+        if self.batching() && !self.flusher_started.swap(true, Ordering::SeqCst) {
+            self.start_flusher();
+        }
+        Ok(())
+    }
+
+    /// Writes partial batches in the background until the target is closed or dropped.
+    fn start_flusher(&self) {
+        let id = self.id.clone();
+        let writer = self.writer.clone();
+        let batch = Arc::downgrade(&self.batch);
+        let closed = Arc::clone(&self.closed);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(BATCH_FLUSH_INTERVAL);
+            loop {
+                ticker.tick().await;
+                if closed.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Some(batch) = batch.upgrade() else {
+                    break;
+                };
+                if let Err(e) = writer.flush(&batch).await {
+                    error!("Failed to write event batch to Elasticsearch target {}: {}", id, e);
+                }
+            }
+            debug!("Elasticsearch target {} batch flusher stopped", id);
+        });
+    }
+}
+
+#[async_trait]
+impl<E> Target<E> for ElasticsearchTarget<E>
+where
+    E: Send + Sync + 'static + Clone + Serialize + DeserializeOwned,
+{
+    fn id(&self) -> TargetID {
+        self.id.clone()
+    }
+
+    async fn is_active(&self) -> Result<bool, TargetError> {
+        let resp = self
+            .writer
+            .request(reqwest::Method::GET, "")?
+            .timeout(CONNECT_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_timeout() {
+                    TargetError::Timeout("Connection timed out".to_string())
+                } else if e.is_connect() {
+                    TargetError::NotConnected
+                } else {
+                    TargetError::Network(format!("Connection failed: {e}"))
+                }
+            })?;
+
+        match resp.status() {
+            status if status.is_success() => Ok(true),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(TargetError::Authentication(format!(
+                "{} returned '{}', please check the username and password",
+                self.args.url,
+                resp.status()
+            ))),
+            status => {
+                debug!("Elasticsearch target {} returned {}", self.id, status);
+                Ok(false)
+            }
+        }
+    }
+
+    async fn save(&self, event: Arc<EntityTarget<E>>) -> Result<(), TargetError> {
+        if let Some(store) = &self.store {
+            store
+                .put(event)
+                .map_err(|e| TargetError::Storage(format!("Failed to save event to store: {e}")))?;
+            debug!("Event saved to store for target: {}", self.id);
+            return Ok(());
+        }
+
+        if let Err(e) = self.init().await {
+            error!("Failed to initialize Elasticsearch target {}: {}", self.id.id, e);
+            return Err(TargetError::NotConnected);
+        }
+
+        if !self.batching() {
+            return self.writer.write(std::slice::from_ref(event.as_ref())).await;
+        }
+
+        let full = {
+            let mut batch = self.batch.lock().await;
+            batch.push(event.as_ref().clone());
+            batch.len() >= self.args.batch_size
+        };
+        if full {
+            self.writer.flush(&self.batch).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_from_store(&self, key: Key) -> Result<(), TargetError> {
+        if let Err(e) = self.init().await {
+            error!("Failed to initialize Elasticsearch target {}: {}", self.id.id, e);
+            return Err(TargetError::NotConnected);
+        }
+
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| TargetError::Configuration("No store configured".to_string()))?;
+
+        let events = match store.get_multiple(&key) {
+            Ok(events) => events,
+            Err(StoreError::NotFound) => return Ok(()),
+            Err(e) => {
+                return Err(TargetError::Storage(format!("Failed to get event from store: {e}")));
+            }
+        };
+
+        self.writer.write(&events).await?;
+
+        match store.del(&key) {
+            Ok(_) => debug!("Event deleted from store for target: {}, key:{}", self.id, key),
+            Err(e) => {
+                error!("Failed to delete event from store: {}", e);
+                return Err(TargetError::Storage(format!("Failed to delete event from store: {e}")));
+            }
+        }
+        Ok(())
+    }
+
+    async fn close(&self) -> Result<(), TargetError> {
+        self.closed.store(true, Ordering::SeqCst);
+        if let Err(e) = self.writer.flush(&self.batch).await {
+            warn!("Failed to write pending events of Elasticsearch target {} on close: {}", self.id, e);
+        }
+        info!("Elasticsearch target closed: {}", self.id);
+        Ok(())
+    }
+
+    fn store(&self) -> Option<&(dyn Store<EntityTarget<E>, Error = StoreError, Key = Key> + Send + Sync)> {
+        self.store.as_deref()
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Target<E> + Send + Sync> {
+        self.clone_box()
+    }
+
+    async fn init(&self) -> Result<(), TargetError> {
+        if !self.is_enabled() {
+            debug!("Elasticsearch target {} is disabled, skipping initialization", self.id);
+            return Ok(());
+        }
+
+        ElasticsearchTarget::init(self).await
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.args.enable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventName;
+
+    fn args(index: &str, format: EventFormat) -> ElasticsearchArgs {
+        ElasticsearchArgs {
+            enable: true,
+            url: Url::parse("http://localhost:9200").unwrap(),
+            index: index.to_string(),
+            format,
+            username: String::new(),
+            password: String::new(),
+            include_metadata: true,
+            batch_size: 1,
+            max_retry: 3,
+            queue_dir: String::new(),
+            queue_limit: 0,
+            target_type: TargetType::NotifyEvent,
+        }
+    }
+
+    fn event(event_name: EventName) -> EntityTarget<Value> {
+        EntityTarget {
+            object_name: "photos%2Fa.jpg".to_string(),
+            bucket_name: "bucket".to_string(),
+            event_name,
+            data: json!({
+                "eventTime": "2024-01-02T03:04:05.678Z",
+                "rustfs": {
+                    "userMetadata": {
+                        "X-Amz-Meta-Camera": "x100",
+                        "X-Amz-Tagging": "project=alpha&team=media",
+                        "content-type": "image/jpeg",
+                    }
+                }
+            }),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(args("rustfs-{bucket}", EventFormat::Namespace).validate().is_ok());
+        assert!(args("logs-{bucket}-{date}", EventFormat::Access).validate().is_ok());
+        assert!(args("logs-{date}", EventFormat::Namespace).validate().is_err());
+        assert!(args("Events", EventFormat::Access).validate().is_err());
+        assert!(args("_events", EventFormat::Access).validate().is_err());
+        assert!(args("", EventFormat::Access).validate().is_err());
+
+        let mut half_auth = args("events", EventFormat::Access);
+        half_auth.username = "elastic".to_string();
+        assert!(half_auth.validate().is_err());
+    }
+
+    #[test]
+    fn test_bulk_ops() {
+        let namespace = args("rustfs-{bucket}", EventFormat::Namespace);
+        let BulkOp::Index { index, id, document } = BulkOp::from_event(&namespace, &event(EventName::ObjectCreatedPut)).unwrap()
+        else {
+            panic!("expected an index operation");
+        };
+        assert_eq!(index, "rustfs-bucket");
+        assert_eq!(id.as_deref(), Some("bucket/photos/a.jpg"));
+        assert_eq!(document["key"], "photos/a.jpg");
+        assert_eq!(document["eventName"], "s3:ObjectCreated:Put");
+        assert_eq!(document["metadata"], json!({ "camera": "x100" }));
+        assert_eq!(document["tags"], json!({ "project": "alpha", "team": "media" }));
+
+        assert_eq!(
+            BulkOp::from_event(&namespace, &event(EventName::ObjectRemovedDelete)).unwrap(),
+            BulkOp::Delete {
+                index: "rustfs-bucket".to_string(),
+                id: "bucket/photos/a.jpg".to_string()
+            }
+        );
+
+        let mut access = args("logs-{date}", EventFormat::Access);
+        access.include_metadata = false;
+        let op = BulkOp::from_event(&access, &event(EventName::ObjectRemovedDelete)).unwrap();
+        let BulkOp::Index { index, id, document } = &op else {
+            panic!("expected an index operation");
+        };
+        assert_eq!(index, "logs-2024.01.02");
+        assert!(id.is_none());
+        assert!(document.get("metadata").is_none());
+
+        let mut body = String::new();
+        op.write_to(&mut body).unwrap();
+        let lines: Vec<&str> = body.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], r#"{"index":{"_index":"logs-2024.01.02"}}"#);
+    }
+
+    #[test]
+    fn test_item_outcome() {
+        let delete = BulkOp::Delete {
+            index: "i".to_string(),
+            id: "k".to_string(),
+        };
+        let item = |status| BulkItem { status, error: None };
+        assert_eq!(item(201).outcome(&delete), ItemOutcome::Done);
+        assert_eq!(item(404).outcome(&delete), ItemOutcome::Done);
+        assert_eq!(item(429).outcome(&delete), ItemOutcome::Retry);
+        assert_eq!(item(503).outcome(&delete), ItemOutcome::Retry);
+        assert!(matches!(item(400).outcome(&delete), ItemOutcome::Failed(_)));
+
+        assert_eq!(retry_delay(0), BASE_RETRY_DELAY);
+        assert_eq!(retry_delay(10), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_index_date() {
+        assert_eq!(index_date(Some("2024-01-02T03:04:05.678Z")), "2024.01.02");
+        let today = index_date(None);
+        assert_eq!(today.len(), 10);
+        assert_eq!(today.as_bytes()[4], b'.');
+    }
+}
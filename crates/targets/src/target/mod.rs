@@ -0,0 +1,203 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::arn::TargetID;
+use crate::store::{Key, Store};
+use crate::{EventName, StoreError, TargetError};
+use async_trait::async_trait;
+use rustfs_config::{TARGET_FORMAT_ACCESS, TARGET_FORMAT_NAMESPACE};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+pub mod elasticsearch;
+pub mod mqtt;
+pub mod sql;
+pub mod webhook;
+
+/// Trait for notification targets
+#[async_trait]
+pub trait Target<E>: Send + Sync + 'static
+where
+    E: Send + Sync + 'static + Clone + Serialize + DeserializeOwned,
+{
+    /// Returns the ID of the target
+    fn id(&self) -> TargetID;
+
+    /// Returns the name of the target
+    fn name(&self) -> String {
+        self.id().to_string()
+    }
+
+    /// Checks if the target is active and reachable
+    async fn is_active(&self) -> Result<bool, TargetError>;
+
+    /// Saves an event (either sends it immediately or stores it for later)
+    async fn save(&self, event: Arc<EntityTarget<E>>) -> Result<(), TargetError>;
+
+    /// Sends an event from the store
+    async fn send_from_store(&self, key: Key) -> Result<(), TargetError>;
+
+    /// Closes the target and releases resources
+    async fn close(&self) -> Result<(), TargetError>;
+
+    /// Returns the store associated with the target (if any)
+    fn store(&self) -> Option<&(dyn Store<EntityTarget<E>, Error = StoreError, Key = Key> + Send + Sync)>;
+
+    /// Returns the type of the target
+    fn clone_dyn(&self) -> Box<dyn Target<E> + Send + Sync>;
+
+    /// Initialize the target, such as establishing a connection, etc.
+    async fn init(&self) -> Result<(), TargetError> {
+        // The default implementation is empty
+        Ok(())
+    }
+
+    /// Check if the target is enabled
+    fn is_enabled(&self) -> bool;
+}
+
+#[derive(Debug, Serialize, Clone, Deserialize)]
+pub struct EntityTarget<E>
+where
+    E: Send + Sync + 'static + Clone + Serialize,
+{
+    pub object_name: String,
+    pub bucket_name: String,
+    pub event_name: EventName,
+    pub data: E,
+}
+
+/// The `ChannelTargetType` enum represents the different types of channel Target
+/// used in the notification system.
+///
+/// It includes:
+/// - `Webhook`: Represents a webhook target for sending notifications via HTTP requests.
+/// - `Kafka`: Represents a Kafka target for sending notifications to a Kafka topic.
+/// - `Mqtt`: Represents an MQTT target for sending notifications via MQTT protocol.
+/// - `Postgres`: Represents a PostgreSQL target writing notifications to a table.
+/// - `Mysql`: Represents a MySQL target writing notifications to a table.
+/// - `Elasticsearch`: Represents an Elasticsearch or OpenSearch target indexing notifications.
+///
+/// Each variant has an associated string representation that can be used for serialization
+/// or logging purposes.
+/// The `as_str` method returns the string representation of the target type,
+/// and the `Display` implementation allows for easy formatting of the target type as a string.
+///
+/// example usage:
+/// ```rust
+/// use rustfs_targets::target::ChannelTargetType;
+///
+/// let target_type = ChannelTargetType::Webhook;
+/// assert_eq!(target_type.as_str(), "webhook");
+/// println!("Target type: {}", target_type);
+/// ```
+///
+/// example output:
+/// Target type: webhook
+pub enum ChannelTargetType {
+    Webhook,
+    Kafka,
+    Mqtt,
+    Postgres,
+    Mysql,
+    Elasticsearch,
+}
+
+impl ChannelTargetType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChannelTargetType::Webhook => "webhook",
+            ChannelTargetType::Kafka => "kafka",
+            ChannelTargetType::Mqtt => "mqtt",
+            ChannelTargetType::Postgres => "postgres",
+            ChannelTargetType::Mysql => "mysql",
+            ChannelTargetType::Elasticsearch => "elasticsearch",
+        }
+    }
+}
+
+impl std::fmt::Display for ChannelTargetType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChannelTargetType::Webhook => write!(f, "webhook"),
+            ChannelTargetType::Kafka => write!(f, "kafka"),
+            ChannelTargetType::Mqtt => write!(f, "mqtt"),
+            ChannelTargetType::Postgres => write!(f, "postgres"),
+            ChannelTargetType::Mysql => write!(f, "mysql"),
+            ChannelTargetType::Elasticsearch => write!(f, "elasticsearch"),
+        }
+    }
+}
+
+/// The layout of the events a target keeps, in a table or an index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventFormat {
+    Namespace,
+    Access,
+}
+
+impl EventFormat {
+    pub fn parse(value: &str) -> Result<Self, TargetError> {
+        match value.trim().to_lowercase().as_str() {
+            "" | TARGET_FORMAT_NAMESPACE => Ok(EventFormat::Namespace),
+            TARGET_FORMAT_ACCESS => Ok(EventFormat::Access),
+            other => Err(TargetError::Configuration(format!(
+                "unknown format '{other}', expected '{TARGET_FORMAT_NAMESPACE}' or '{TARGET_FORMAT_ACCESS}'"
+            ))),
+        }
+    }
+}
+
+/// Whether `event_name` removes the current object, so a `namespace` layout drops its entry.
+pub(crate) fn is_object_removal(event_name: EventName) -> bool {
+    matches!(
+        event_name,
+        EventName::ObjectRemovedDelete | EventName::ObjectRemovedDeleteMarkerCreated | EventName::ObjectRemovedDeleteAllVersions
+    )
+}
+
+pub fn parse_bool(value: &str) -> Result<bool, TargetError> {
+    match value.to_lowercase().as_str() {
+        "true" | "on" | "yes" | "1" => Ok(true),
+        "false" | "off" | "no" | "0" => Ok(false),
+        _ => Err(TargetError::ParseError(format!("Unable to parse boolean: {value}"))),
+    }
+}
+
+/// `TargetType` enum represents the type of target in the notification system.
+#[derive(Debug, Clone)]
+pub enum TargetType {
+    AuditLog,
+    NotifyEvent,
+}
+
+impl TargetType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TargetType::AuditLog => "audit_log",
+            TargetType::NotifyEvent => "notify_event",
+        }
+    }
+}
+
+impl std::fmt::Display for TargetType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TargetType::AuditLog => write!(f, "audit_log"),
+            TargetType::NotifyEvent => write!(f, "notify_event"),
+        }
+    }
+}
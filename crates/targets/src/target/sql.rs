@@ -0,0 +1,800 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! PostgreSQL and MySQL notification targets.
+//!
+//! Events are written to a table created on first use, in one of two layouts compatible with the
+//! MinIO `notify_postgres` and `notify_mysql` targets:
+//! - `namespace`: one row per object keyed by `bucket/object`, upserted on every event and deleted
+//!   when the object is removed, so the table mirrors the current objects;
+//! - `access`: one row per event with its time, so the table is a log of the bucket activity.
+//!
+//! Both store the event as `{"Records": [event]}` in a JSON column.
+
+use crate::target::{ChannelTargetType, EntityTarget, EventFormat, TargetType, is_object_removal};
+use crate::{
+    StoreError, Target,
+    arn::TargetID,
+    error::TargetError,
+    store::{Key, Store},
+};
+use async_trait::async_trait;
+use rustfs_config::notify::STORE_EXTENSION;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sqlx::any::{AnyPool, AnyPoolOptions};
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, instrument, warn};
+
+/// How long a partial batch waits before it is written.
+const BATCH_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The database a SQL target writes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    Postgres,
+    MySql,
+}
+
+impl SqlDialect {
+    fn target_type(&self) -> ChannelTargetType {
+        match self {
+            SqlDialect::Postgres => ChannelTargetType::Postgres,
+            SqlDialect::MySql => ChannelTargetType::Mysql,
+        }
+    }
+
+    /// URL of the database, from either a URL or the connection string format MinIO accepts: a
+    /// libpq `key=value` string for PostgreSQL and a Go driver DSN for MySQL.
+    pub fn connection_url(&self, connection_string: &str) -> Result<String, TargetError> {
+        let connection_string = connection_string.trim();
+        if connection_string.is_empty() {
+            return Err(TargetError::Configuration("connection string is empty".to_string()));
+        }
+        match self {
+            SqlDialect::Postgres
+                if connection_string.starts_with("postgres://") || connection_string.starts_with("postgresql://") =>
+            {
+                Ok(connection_string.to_string())
+            }
+            SqlDialect::Postgres => postgres_url(connection_string),
+            SqlDialect::MySql if connection_string.starts_with("mysql://") => Ok(connection_string.to_string()),
+            SqlDialect::MySql => mysql_url(connection_string),
+        }
+    }
+
+    fn create_table_sql(&self, format: EventFormat, table: &str) -> String {
+        match (self, format) {
+            (SqlDialect::Postgres, EventFormat::Namespace) => {
+                format!("CREATE TABLE IF NOT EXISTS {table} (key VARCHAR PRIMARY KEY, value JSONB)")
+            }
+            (SqlDialect::Postgres, EventFormat::Access) => {
+                format!("CREATE TABLE IF NOT EXISTS {table} (event_time TIMESTAMP WITH TIME ZONE NOT NULL, event_data JSONB)")
+            }
+            // Keys can be longer than an index allows, so rows are keyed by the hash of the key
+            (SqlDialect::MySql, EventFormat::Namespace) => format!(
+                "CREATE TABLE IF NOT EXISTS {table} (key_name VARCHAR(3072) NOT NULL, \
+                 key_hash CHAR(64) GENERATED ALWAYS AS (SHA2(key_name, 256)) STORED NOT NULL PRIMARY KEY, value JSON) \
+                 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_bin ROW_FORMAT = DYNAMIC"
+            ),
+            (SqlDialect::MySql, EventFormat::Access) => format!(
+                "CREATE TABLE IF NOT EXISTS {table} (event_time DATETIME(3) NOT NULL, event_data JSON) ROW_FORMAT = DYNAMIC"
+            ),
+        }
+    }
+
+    fn upsert_sql(&self, table: &str) -> String {
+        match self {
+            SqlDialect::Postgres => format!(
+                "INSERT INTO {table} (key, value) VALUES ($1, CAST($2 AS JSONB)) ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value"
+            ),
+            SqlDialect::MySql => {
+                format!(
+                    "INSERT INTO {table} (key_name, value) VALUES (?, CAST(? AS JSON)) ON DUPLICATE KEY UPDATE value = VALUES(value)"
+                )
+            }
+        }
+    }
+
+    fn delete_sql(&self, table: &str) -> String {
+        match self {
+            SqlDialect::Postgres => format!("DELETE FROM {table} WHERE key = $1"),
+            SqlDialect::MySql => format!("DELETE FROM {table} WHERE key_hash = SHA2(?, 256)"),
+        }
+    }
+
+    fn append_sql(&self, table: &str) -> String {
+        match self {
+            SqlDialect::Postgres => format!(
+                "INSERT INTO {table} (event_time, event_data) \
+                 VALUES (COALESCE(CAST($1 AS TIMESTAMPTZ), CURRENT_TIMESTAMP), CAST($2 AS JSONB))"
+            ),
+            SqlDialect::MySql => format!(
+                "INSERT INTO {table} (event_time, event_data) VALUES (COALESCE(?, CURRENT_TIMESTAMP(3)), CAST(? AS JSON))"
+            ),
+        }
+    }
+
+    /// The event time as the database parses it; MySQL does not read RFC 3339 times.
+    fn event_time(&self, event_time: &str) -> Option<String> {
+        match self {
+            SqlDialect::Postgres => Some(event_time.to_string()),
+            SqlDialect::MySql => event_time.strip_suffix('Z').map(|t| t.replacen('T', " ", 1)),
+        }
+    }
+}
+
+/// Converts a libpq `key=value` connection string to a URL.
+fn postgres_url(connection_string: &str) -> Result<String, TargetError> {
+    let mut host = "localhost".to_string();
+    let mut port = "5432".to_string();
+    let mut user = String::new();
+    let mut password = String::new();
+    let mut dbname = String::new();
+    let mut params = Vec::new();
+
+    for pair in split_libpq(connection_string) {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| TargetError::Configuration(format!("invalid connection string parameter '{pair}'")))?;
+        let value = value.trim_matches('\'').to_string();
+        match key.trim() {
+            "host" => host = value,
+            "port" => port = value,
+            "user" => user = value,
+            "password" => password = value,
+            "dbname" => dbname = value,
+            key => params.push(format!("{key}={}", urlencoding::encode(&value))),
+        }
+    }
+
+    let credentials = match (user.is_empty(), password.is_empty()) {
+        (true, _) => String::new(),
+        (false, true) => format!("{}@", urlencoding::encode(&user)),
+        (false, false) => format!("{}:{}@", urlencoding::encode(&user), urlencoding::encode(&password)),
+    };
+    let mut url = format!("postgres://{credentials}{host}:{port}/{dbname}");
+    if !params.is_empty() {
+        url.push('?');
+        url.push_str(&params.join("&"));
+    }
+    Ok(url)
+}
+
+/// Splits a libpq connection string on whitespace outside single quotes.
+fn split_libpq(connection_string: &str) -> Vec<String> {
+    let mut pairs = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in connection_string.chars() {
+        match c {
+            '\'' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    pairs.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        pairs.push(current);
+    }
+    pairs
+}
+
+/// Converts a Go MySQL driver DSN, `user:password@tcp(host:port)/dbname?params`, to a URL.
+fn mysql_url(dsn: &str) -> Result<String, TargetError> {
+    let (dsn, query) = dsn.split_once('?').unwrap_or((dsn, ""));
+    let (prefix, dbname) = dsn
+        .rsplit_once('/')
+        .ok_or_else(|| TargetError::Configuration("MySQL DSN has no database name".to_string()))?;
+    let (credentials, address) = prefix.rsplit_once('@').unwrap_or(("", prefix));
+
+    let address = match address {
+        "" => "localhost:3306",
+        address => address
+            .strip_prefix("tcp(")
+            .and_then(|a| a.strip_suffix(')'))
+            .ok_or_else(|| {
+                TargetError::Configuration(format!("unsupported MySQL DSN address '{address}', expected tcp(host:port)"))
+            })?,
+    };
+
+    let credentials = match credentials.split_once(':') {
+        _ if credentials.is_empty() => String::new(),
+        Some((user, password)) => format!("{}:{}@", urlencoding::encode(user), urlencoding::encode(password)),
+        None => format!("{}@", urlencoding::encode(credentials)),
+    };
+
+    let mut url = format!("mysql://{credentials}{address}/{dbname}");
+    // Go driver parameters do not apply to this driver, except whether TLS is required
+    if query
+        .split('&')
+        .any(|param| matches!(param, "tls=true" | "tls=skip-verify" | "tls=preferred"))
+    {
+        url.push_str("?ssl-mode=REQUIRED");
+    }
+    Ok(url)
+}
+
+/// Whether `table` is a plain or schema-qualified identifier, as it is spliced into statements.
+fn is_valid_table_name(table: &str) -> bool {
+    let parts: Vec<&str> = table.split('.').collect();
+    parts.len() <= 2
+        && parts.iter().all(|part| {
+            let mut chars = part.chars();
+            chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+/// Arguments for configuring a SQL target
+#[derive(Debug, Clone)]
+pub struct SqlArgs {
+    /// Whether the target is enabled
+    pub enable: bool,
+    /// The database written to
+    pub dialect: SqlDialect,
+    /// URL or native connection string of the database
+    pub connection_string: String,
+    /// The table events are written to, created when missing
+    pub table: String,
+    /// The layout of the table
+    pub format: EventFormat,
+    /// The maximum number of connections to the database
+    pub max_open_connections: u32,
+    /// Number of events written in one transaction. Only applies without a queue directory, whose
+    /// events are delivered one at a time.
+    pub batch_size: usize,
+    /// The directory to store events in case of failure
+    pub queue_dir: String,
+    /// The maximum number of events to store
+    pub queue_limit: u64,
+    /// the target type
+    pub target_type: TargetType,
+}
+
+impl SqlArgs {
+    /// SqlArgs verification method
+    pub fn validate(&self) -> Result<(), TargetError> {
+        if !self.enable {
+            return Ok(());
+        }
+
+        self.dialect.connection_url(&self.connection_string)?;
+
+        if !is_valid_table_name(&self.table) {
+            return Err(TargetError::Configuration(format!("invalid table name '{}'", self.table)));
+        }
+
+        if self.max_open_connections == 0 {
+            return Err(TargetError::Configuration("max_open_connections must be at least 1".to_string()));
+        }
+
+        if self.batch_size == 0 {
+            return Err(TargetError::Configuration("batch_size must be at least 1".to_string()));
+        }
+
+        if !self.queue_dir.is_empty() && !std::path::Path::new(&self.queue_dir).is_absolute() {
+            return Err(TargetError::Configuration(format!(
+                "{} queueDir path should be absolute",
+                self.dialect.target_type()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// A row change made for an event.
+#[derive(Debug, PartialEq, Eq)]
+enum SqlRow {
+    Upsert { key: String, value: String },
+    Delete { key: String },
+    Append { event_time: Option<String>, data: String },
+}
+
+fn sql_error(e: sqlx::Error) -> TargetError {
+    match e {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed => TargetError::NotConnected,
+        e => TargetError::Request(format!("SQL statement failed: {e}")),
+    }
+}
+
+/// The row change `event` makes to a table in `format`.
+fn sql_row<E>(dialect: SqlDialect, format: EventFormat, event: &EntityTarget<E>) -> Result<SqlRow, TargetError>
+where
+    E: Send + Sync + 'static + Clone + Serialize,
+{
+    let record =
+        serde_json::to_value(&event.data).map_err(|e| TargetError::Serialization(format!("Failed to serialize event: {e}")))?;
+
+    match format {
+        EventFormat::Namespace => {
+            let object_name = urlencoding::decode(&event.object_name)
+                .map_err(|e| TargetError::Encoding(format!("Failed to decode object key: {e}")))?;
+            let key = format!("{}/{}", event.bucket_name, object_name);
+            if is_object_removal(event.event_name) {
+                return Ok(SqlRow::Delete { key });
+            }
+            let value = serde_json::json!({ "Records": [record] }).to_string();
+            Ok(SqlRow::Upsert { key, value })
+        }
+        EventFormat::Access => {
+            let event_time = record
+                .get("eventTime")
+                .and_then(|t| t.as_str())
+                .and_then(|t| dialect.event_time(t));
+            let data = serde_json::json!({ "Records": [record] }).to_string();
+            Ok(SqlRow::Append { event_time, data })
+        }
+    }
+}
+
+/// Writes rows of a SQL target, shared with its batch flusher.
+#[derive(Clone)]
+struct SqlWriter {
+    pool: AnyPool,
+    dialect: SqlDialect,
+    format: EventFormat,
+    table: String,
+}
+
+impl SqlWriter {
+    async fn create_table(&self) -> Result<(), TargetError> {
+        sqlx::query(&self.dialect.create_table_sql(self.format, &self.table))
+            .execute(&self.pool)
+            .await
+            .map_err(sql_error)?;
+        Ok(())
+    }
+
+    /// Writes the rows of `events` in one transaction.
+    async fn write<E>(&self, events: &[EntityTarget<E>]) -> Result<(), TargetError>
+    where
+        E: Send + Sync + 'static + Clone + Serialize,
+    {
+        let rows = events
+            .iter()
+            .map(|event| sql_row(self.dialect, self.format, event))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut tx = self.pool.begin().await.map_err(sql_error)?;
+        for row in rows {
+            let result = match row {
+                SqlRow::Upsert { key, value } => {
+                    sqlx::query(&self.dialect.upsert_sql(&self.table))
+                        .bind(key)
+                        .bind(value)
+                        .execute(&mut *tx)
+                        .await
+                }
+                SqlRow::Delete { key } => {
+                    sqlx::query(&self.dialect.delete_sql(&self.table))
+                        .bind(key)
+                        .execute(&mut *tx)
+                        .await
+                }
+                SqlRow::Append { event_time, data } => {
+                    sqlx::query(&self.dialect.append_sql(&self.table))
+                        .bind(event_time)
+                        .bind(data)
+                        .execute(&mut *tx)
+                        .await
+                }
+            };
+            result.map_err(sql_error)?;
+        }
+        tx.commit().await.map_err(sql_error)
+    }
+
+    /// Writes the buffered events, if any.
+    async fn flush<E>(&self, batch: &Mutex<Vec<EntityTarget<E>>>) -> Result<(), TargetError>
+    where
+        E: Send + Sync + 'static + Clone + Serialize,
+    {
+        let events = std::mem::take(&mut *batch.lock().await);
+        if events.is_empty() {
+            return Ok(());
+        }
+        self.write(&events).await
+    }
+}
+
+/// A target that writes events to a PostgreSQL or MySQL table
+pub struct SqlTarget<E>
+where
+    E: Send + Sync + 'static + Clone + Serialize + DeserializeOwned,
+{
+    id: TargetID,
+    args: SqlArgs,
+    writer: SqlWriter,
+    store: Option<Box<dyn Store<EntityTarget<E>, Error = StoreError, Key = Key> + Send + Sync>>,
+    initialized: AtomicBool,
+    /// Events waiting to be written together, when batching without a queue directory
+    batch: Arc<Mutex<Vec<EntityTarget<E>>>>,
+    flusher_started: Arc<AtomicBool>,
+    closed: Arc<AtomicBool>,
+}
+
+impl<E> SqlTarget<E>
+where
+    E: Send + Sync + 'static + Clone + Serialize + DeserializeOwned,
+{
+    /// Clones the SqlTarget, creating a new instance sharing its pool and pending batch
+    pub fn clone_box(&self) -> Box<dyn Target<E> + Send + Sync> {
+        Box::new(SqlTarget {
+            id: self.id.clone(),
+            args: self.args.clone(),
+            writer: self.writer.clone(),
+            store: self.store.as_ref().map(|s| s.boxed_clone()),
+            initialized: AtomicBool::new(self.initialized.load(Ordering::SeqCst)),
+            batch: Arc::clone(&self.batch),
+            flusher_started: Arc::clone(&self.flusher_started),
+            closed: Arc::clone(&self.closed),
+        })
+    }
+
+    /// Creates a new SqlTarget; connections are opened on first use
+    #[instrument(skip(args), fields(target_id = %id))]
+    pub fn new(id: String, args: SqlArgs) -> Result<Self, TargetError> {
+        args.validate()?;
+        let target_type = args.dialect.target_type();
+        let target_id = TargetID::new(id, target_type.as_str().to_string());
+
+        sqlx::any::install_default_drivers();
+        let url = args.dialect.connection_url(&args.connection_string)?;
+        let pool = AnyPoolOptions::new()
+            .max_connections(args.max_open_connections)
+            .acquire_timeout(CONNECT_TIMEOUT)
+            .connect_lazy(&url)
+            .map_err(|e| TargetError::Configuration(format!("Invalid connection string: {e}")))?;
+
+        let queue_store = if !args.queue_dir.is_empty() {
+            let queue_dir = PathBuf::from(&args.queue_dir).join(format!("rustfs-{}-{}", target_type.as_str(), target_id.id));
+
+            let extension = match args.target_type {
+                TargetType::AuditLog => rustfs_config::audit::AUDIT_STORE_EXTENSION,
+                TargetType::NotifyEvent => STORE_EXTENSION,
+            };
+
+            let store = crate::store::QueueStore::<EntityTarget<E>>::new(queue_dir, args.queue_limit, extension);
+
+            if let Err(e) = store.open() {
+                error!("Failed to open store for {} target {}: {}", target_type, target_id.id, e);
+                return Err(TargetError::Storage(format!("{e}")));
+            }
+
+            Some(Box::new(store) as Box<dyn Store<EntityTarget<E>, Error = StoreError, Key = Key> + Send + Sync>)
+        } else {
+            None
+        };
+
+        let writer = SqlWriter {
+            pool,
+            dialect: args.dialect,
+            format: args.format,
+            table: args.table.clone(),
+        };
+
+        info!(target_id = %target_id.id, "{} target created", target_type);
+        Ok(SqlTarget {
+            id: target_id,
+            args,
+            writer,
+            store: queue_store,
+            initialized: AtomicBool::new(false),
+            batch: Arc::new(Mutex::new(Vec::new())),
+            flusher_started: Arc::new(AtomicBool::new(false)),
+            closed: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    fn batching(&self) -> bool {
+        self.store.is_none() && self.args.batch_size > 1
+    }
+
+    async fn init(&self) -> Result<(), TargetError> {
+        if !self.initialized.load(Ordering::SeqCst) {
+            match self.is_active().await {
+                Ok(true) => {}
+                Ok(false) => return Err(TargetError::NotConnected),
+                Err(e) => {
+                    error!("Failed to check if SQL target {} is active: {}", self.id, e);
+                    return Err(e);
+                }
+            }
+            self.writer.create_table().await?;
+            self.initialized.store(true, Ordering::SeqCst);
+            info!("SQL target {} initialized, table {}", self.id, self.args.table);
+        }
+
+        if self.batching() && !self.flusher_started.swap(true, Ordering::SeqCst) {
+            self.start_flusher();
+        }
+        Ok(())
+    }
+
+    /// Writes partial batches in the background until the target is closed or dropped.
+    fn start_flusher(&self) {
+        let id = self.id.clone();
+        let writer = self.writer.clone();
+        let batch = Arc::downgrade(&self.batch);
+        let closed = Arc::clone(&self.closed);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(BATCH_FLUSH_INTERVAL);
+            loop {
+                ticker.tick().await;
+                if closed.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Some(batch) = batch.upgrade() else {
+                    break;
+                };
+                if let Err(e) = writer.flush(&batch).await {
+                    error!("Failed to write event batch to SQL target {}: {}", id, e);
+                }
+            }
+            debug!("SQL target {} batch flusher stopped", id);
+        });
+    }
+}
+
+#[async_trait]
+impl<E> Target<E> for SqlTarget<E>
+where
+    E: Send + Sync + 'static + Clone + Serialize + DeserializeOwned,
+{
+    fn id(&self) -> TargetID {
+        self.id.clone()
+    }
+
+    async fn is_active(&self) -> Result<bool, TargetError> {
+        match tokio::time::timeout(CONNECT_TIMEOUT, sqlx::query("SELECT 1").execute(&self.writer.pool)).await {
+            Ok(Ok(_)) => Ok(true),
+            Ok(Err(e)) => {
+                debug!("Connection to SQL target {} failed: {}", self.id, e);
+                Err(sql_error(e))
+            }
+            Err(_) => Err(TargetError::Timeout("Connection timed out".to_string())),
+        }
+    }
+
+    async fn save(&self, event: Arc<EntityTarget<E>>) -> Result<(), TargetError> {
+        if let Some(store) = &self.store {
+            store
+                .put(event)
+                .map_err(|e| TargetError::Storage(format!("Failed to save event to store: {e}")))?;
+            debug!("Event saved to store for target: {}", self.id);
+            return Ok(());
+        }
+
+        if let Err(e) = self.init().await {
+            error!("Failed to initialize SQL target {}: {}", self.id.id, e);
+            return Err(TargetError::NotConnected);
+        }
+
+        if !self.batching() {
+            return self.writer.write(std::slice::from_ref(event.as_ref())).await;
+        }
+
+        let full = {
+            let mut batch = self.batch.lock().await;
+            batch.push(event.as_ref().clone());
+            batch.len() >= self.args.batch_size
+        };
+        if full {
+            self.writer.flush(&self.batch).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_from_store(&self, key: Key) -> Result<(), TargetError> {
+        if let Err(e) = self.init().await {
+            error!("Failed to initialize SQL target {}: {}", self.id.id, e);
+            return Err(TargetError::NotConnected);
+        }
+
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| TargetError::Configuration("No store configured".to_string()))?;
+
+        let events = match store.get_multiple(&key) {
+            Ok(events) => events,
+            Err(StoreError::NotFound) => return Ok(()),
+            Err(e) => {
+                return Err(TargetError::Storage(format!("Failed to get event from store: {e}")));
+            }
+        };
+
+        self.writer.write(&events).await?;
+
+        match store.del(&key) {
+            Ok(_) => debug!("Event deleted from store for target: {}, key:{}", self.id, key),
+            Err(e) => {
+                error!("Failed to delete event from store: {}", e);
+                return Err(TargetError::Storage(format!("Failed to delete event from store: {e}")));
+            }
+        }
+        Ok(())
+    }
+
+    async fn close(&self) -> Result<(), TargetError> {
+        self.closed.store(true, Ordering::SeqCst);
+        if let Err(e) = self.writer.flush(&self.batch).await {
+            warn!("Failed to write pending events of SQL target {} on close: {}", self.id, e);
+        }
+        self.writer.pool.close().await;
+        info!("SQL target closed: {}", self.id);
+        Ok(())
+    }
+
+    fn store(&self) -> Option<&(dyn Store<EntityTarget<E>, Error = StoreError, Key = Key> + Send + Sync)> {
+        self.store.as_deref()
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Target<E> + Send + Sync> {
+        self.clone_box()
+    }
+
+    async fn init(&self) -> Result<(), TargetError> {
+        if !self.is_enabled() {
+            debug!("SQL target {} is disabled, skipping initialization", self.id);
+            return Ok(());
+        }
+
+        SqlTarget::init(self).await
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.args.enable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventName;
+
+    fn args(dialect: SqlDialect, connection_string: &str, table: &str) -> SqlArgs {
+        SqlArgs {
+            enable: true,
+            dialect,
+            connection_string: connection_string.to_string(),
+            table: table.to_string(),
+            format: EventFormat::Namespace,
+            max_open_connections: 2,
+            batch_size: 1,
+            queue_dir: String::new(),
+            queue_limit: 0,
+            target_type: TargetType::NotifyEvent,
+        }
+    }
+
+    #[test]
+    fn test_connection_url() {
+        let pg = SqlDialect::Postgres;
+        assert_eq!(
+            pg.connection_url("postgres://u:p@db:5432/events").unwrap(),
+            "postgres://u:p@db:5432/events"
+        );
+        assert_eq!(
+            pg.connection_url("host=db port=5433 user=rustfs password='p w' dbname=events sslmode=disable")
+                .unwrap(),
+            "postgres://rustfs:p%20w@db:5433/events?sslmode=disable"
+        );
+        assert!(pg.connection_url("host").is_err());
+
+        let my = SqlDialect::MySql;
+        assert_eq!(my.connection_url("mysql://u:p@db:3306/events").unwrap(), "mysql://u:p@db:3306/events");
+        assert_eq!(
+            my.connection_url("rustfs:secret@tcp(db:3306)/events?tls=true").unwrap(),
+            "mysql://rustfs:secret@db:3306/events?ssl-mode=REQUIRED"
+        );
+        assert_eq!(my.connection_url("/events").unwrap(), "mysql://localhost:3306/events");
+        assert!(my.connection_url("rustfs@unix(/tmp/mysql.sock)/events").is_err());
+        assert!(my.connection_url("").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(
+            args(SqlDialect::Postgres, "postgres://db/events", "rustfs_events")
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            args(SqlDialect::Postgres, "postgres://db/events", "public.events")
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            args(SqlDialect::MySql, "mysql://db/events", "events; DROP TABLE x")
+                .validate()
+                .is_err()
+        );
+        assert!(args(SqlDialect::MySql, "mysql://db/events", "1events").validate().is_err());
+        assert!(args(SqlDialect::MySql, "mysql://db/events", "").validate().is_err());
+
+        let mut relative = args(SqlDialect::MySql, "mysql://db/events", "events");
+        relative.queue_dir = "queue".to_string();
+        assert!(relative.validate().is_err());
+
+        assert_eq!(EventFormat::parse("Access").unwrap(), EventFormat::Access);
+        assert_eq!(EventFormat::parse("").unwrap(), EventFormat::Namespace);
+        assert!(EventFormat::parse("json").is_err());
+    }
+
+    #[test]
+    fn test_rows() {
+        let event = |event_name| EntityTarget {
+            object_name: "photos%2Fa.jpg".to_string(),
+            bucket_name: "bucket".to_string(),
+            event_name,
+            data: serde_json::json!({ "eventTime": "2024-01-02T03:04:05.678Z" }),
+        };
+
+        assert_eq!(
+            sql_row(SqlDialect::MySql, EventFormat::Namespace, &event(EventName::ObjectCreatedPut)).unwrap(),
+            SqlRow::Upsert {
+                key: "bucket/photos/a.jpg".to_string(),
+                value: r#"{"Records":[{"eventTime":"2024-01-02T03:04:05.678Z"}]}"#.to_string(),
+            }
+        );
+        assert_eq!(
+            sql_row(SqlDialect::MySql, EventFormat::Namespace, &event(EventName::ObjectRemovedDelete)).unwrap(),
+            SqlRow::Delete {
+                key: "bucket/photos/a.jpg".to_string()
+            }
+        );
+
+        match sql_row(SqlDialect::MySql, EventFormat::Access, &event(EventName::ObjectRemovedDelete)).unwrap() {
+            SqlRow::Append { event_time, .. } => assert_eq!(event_time.as_deref(), Some("2024-01-02 03:04:05.678")),
+            row => panic!("unexpected row {row:?}"),
+        }
+    }
+
+    #[test]
+    fn test_statements() {
+        assert_eq!(
+            SqlDialect::Postgres.upsert_sql("events"),
+            "INSERT INTO events (key, value) VALUES ($1, CAST($2 AS JSONB)) ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value"
+        );
+        assert_eq!(SqlDialect::MySql.delete_sql("events"), "DELETE FROM events WHERE key_hash = SHA2(?, 256)");
+        assert!(
+            SqlDialect::MySql
+                .create_table_sql(EventFormat::Namespace, "events")
+                .contains("key_hash CHAR(64) GENERATED ALWAYS AS (SHA2(key_name, 256))")
+        );
+        assert_eq!(
+            SqlDialect::Postgres.event_time("2024-01-02T03:04:05Z").as_deref(),
+            Some("2024-01-02T03:04:05Z")
+        );
+        assert_eq!(SqlDialect::MySql.event_time("2024-01-02T03:04:05+01:00"), None);
+    }
+}
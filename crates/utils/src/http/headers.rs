@@ -164,6 +164,10 @@ pub const RUSTFS_DATA_MOVE: &str = "X-Rustfs-Internal-data-mov";
 
 pub const RUSTFS_FORCE_DELETE: &str = "X-Rustfs-Force-Delete";
 pub const RUSTFS_INCLUDE_DELETED: &str = "X-Rustfs-Include-Deleted";
+/// Consistency of a listing, `strict` or `eventual`, overriding the configuration of the bucket
+pub const RUSTFS_LIST_CONSISTENCY: &str = "X-Rustfs-List-Consistency";
+/// Erasure block size of an object in bytes, reported by GetObjectAttributes
+pub const RUSTFS_ERASURE_BLOCK_SIZE: &str = "X-Rustfs-Erasure-Block-Size";
 
 pub const RUSTFS_REPLICATION_RESET_STATUS: &str = "X-Rustfs-Replication-Reset-Status";
 pub const RUSTFS_REPLICATION_ACTUAL_OBJECT_SIZE: &str = "X-Rustfs-Replication-Actual-Object-Size";
@@ -332,6 +332,20 @@ where
     })
 }
 
+/// Stream `data` in slices of at most `chunk_size` bytes, sharing its memory instead of copying it.
+pub fn bytes_chunks<E>(data: Bytes, chunk_size: usize) -> impl Stream<Item = Result<Bytes, E>> + Send + 'static
+where
+    E: Send + 'static,
+{
+    let chunk_size = chunk_size.max(1);
+    let len = data.len();
+    futures::stream::iter(
+        (0..len)
+            .step_by(chunk_size)
+            .map(move |start| Ok(data.slice(start..len.min(start + chunk_size)))),
+    )
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -676,4 +690,24 @@ mod test {
         };
         assert_eq!(host_zero_port.to_string(), "example.com:0");
     }
+
+    #[tokio::test]
+    async fn test_bytes_chunks() {
+        let data = Bytes::from_static(b"0123456789");
+        let chunks: Vec<Bytes> = bytes_chunks::<IoError>(data.clone(), 4)
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        assert_eq!(
+            chunks,
+            vec![
+                Bytes::from_static(b"0123"),
+                Bytes::from_static(b"4567"),
+                Bytes::from_static(b"89")
+            ]
+        );
+        assert_eq!(chunks[1].as_ptr(), data[4..].as_ptr());
+
+        assert_eq!(bytes_chunks::<IoError>(Bytes::new(), 4).count().await, 0);
+    }
 }
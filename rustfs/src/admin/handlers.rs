@@ -28,10 +28,12 @@ use rustfs_ecstore::admin_server_info::get_server_info;
 use rustfs_ecstore::bucket::bucket_target_sys::BucketTargetSys;
 use rustfs_ecstore::bucket::metadata::BUCKET_TARGETS_FILE;
 use rustfs_ecstore::bucket::metadata_sys;
+use rustfs_ecstore::bucket::replication::GLOBAL_REPLICATION_DRIFT;
 use rustfs_ecstore::bucket::target::BucketTarget;
 use rustfs_ecstore::bucket::versioning_sys::BucketVersioningSys;
 use rustfs_ecstore::data_usage::{
-    aggregate_local_snapshots, compute_bucket_usage, load_data_usage_from_backend, store_data_usage_in_backend,
+    aggregate_local_snapshots, attach_bucket_tags, compute_bucket_usage, load_data_usage_from_backend,
+    store_data_usage_in_backend,
 };
 use rustfs_ecstore::error::StorageError;
 use rustfs_ecstore::global::get_global_action_cred;
@@ -73,20 +75,56 @@ use tracing::{error, info, warn};
 use url::Host;
 // use url::UrlQuery;
 
+pub mod bandwidth;
+pub mod bucket_backup;
 pub mod bucket_meta;
+pub mod bucket_purge;
+pub mod compat;
+pub mod compose;
+pub mod config_backup;
+pub mod dedup;
+#[cfg(debug_assertions)]
+pub mod disk_faults;
+pub mod disk_replacement;
+pub mod erasure;
 pub mod event;
+pub mod event_backfill;
+pub mod federation;
 pub mod group;
+pub mod health;
+pub mod integrity;
+pub mod key_rotation;
 pub mod kms;
 pub mod kms_dynamic;
 pub mod kms_keys;
+pub mod kms_scope;
+pub mod listing;
+pub mod maintenance;
+pub mod marker_cleanup;
+pub mod metadata_search;
+pub mod mode;
+pub mod naming;
+pub mod object_metadata;
+pub mod object_repair;
+pub mod orphaned_data;
 pub mod policies;
 pub mod pools;
+pub mod presign;
 pub mod profile;
 pub mod rebalance;
+pub mod request_log;
+pub mod rule_eval;
 pub mod service_account;
+pub mod snapshot;
+pub mod speedtest;
 pub mod sts;
+pub mod sts_session;
 pub mod tier;
+pub mod top;
 pub mod trace;
+pub mod transform;
+pub mod trash;
+pub mod usage;
 pub mod user;
 
 #[allow(dead_code)]
@@ -510,6 +548,9 @@ impl Operation for DataUsageInfoHandler {
             info.total_used_capacity = info.total_capacity - info.total_free_capacity;
         }
 
+        // Export cost-allocation tags so usage can be grouped per team/project
+        attach_bucket_tags(&mut info).await;
+
         let data = serde_json::to_vec(&info)
             .map_err(|_e| S3Error::with_message(S3ErrorCode::InternalError, "parse DataUsageInfo failed"))?;
 
@@ -957,6 +998,39 @@ impl Operation for GetReplicationMetricsHandler {
     }
 }
 
+/// Replica drift found by the scanner's replication checker on this node, by target ARN.
+pub struct GetReplicationDriftHandler {}
+#[async_trait::async_trait]
+impl Operation for GetReplicationDriftHandler {
+    // GET <endpoint>/<admin-API>/replication-drift
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let Some(input_cred) = &req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        validate_admin_request(
+            &req.headers,
+            &cred,
+            owner,
+            false,
+            vec![Action::AdminAction(AdminAction::ServerInfoAdminAction)],
+        )
+        .await?;
+
+        let stats = GLOBAL_REPLICATION_DRIFT.stats();
+        let data = serde_json::to_vec(&stats)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal drift stats failed: {e}")))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
+
 pub struct SetRemoteTargetHandler {}
 #[async_trait::async_trait]
 impl Operation for SetRemoteTargetHandler {
@@ -1480,6 +1554,7 @@ mod tests {
             no_lock: true,
             pool: Some(1),
             set: Some(0),
+            rewrite: false,
         };
 
         let encoded = serde_urlencoded::to_string(opts).unwrap();
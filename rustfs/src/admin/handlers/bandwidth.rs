@@ -0,0 +1,120 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::bandwidth::{BandwidthLimits, GLOBAL_BANDWIDTH_SYS, TrafficClass, TrafficStats};
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use std::collections::BTreeMap;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+/// New limits in bytes per second, 0 for no limit. Classes left out keep their limit.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BandwidthQuery {
+    pub heal: Option<u64>,
+    pub rebalance: Option<u64>,
+    pub decommission: Option<u64>,
+    pub replication: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct BandwidthStatus {
+    limits: BandwidthLimits,
+    /// Traffic of this node since it started.
+    stats: BTreeMap<&'static str, TrafficStats>,
+}
+
+async fn check_bandwidth_request(req: &S3Request<Body>, action: AdminAction) -> S3Result<()> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(action)]).await
+}
+
+fn status_response() -> S3Result<S3Response<(StatusCode, Body)>> {
+    let status = BandwidthStatus {
+        limits: GLOBAL_BANDWIDTH_SYS.limits(),
+        stats: TrafficClass::ALL
+            .iter()
+            .map(|class| (class.as_str(), GLOBAL_BANDWIDTH_SYS.stats(*class)))
+            .collect(),
+    };
+    let data = serde_json::to_vec(&status)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal bandwidth status failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct GetBandwidth {}
+
+#[async_trait::async_trait]
+impl Operation for GetBandwidth {
+    // GET <endpoint>/<admin-API>/bandwidth
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        check_bandwidth_request(&req, AdminAction::BandwidthMonitorAction).await?;
+
+        status_response()
+    }
+}
+
+pub struct SetBandwidth {}
+
+#[async_trait::async_trait]
+impl Operation for SetBandwidth {
+    // PUT <endpoint>/<admin-API>/bandwidth?heal=10485760&replication=0
+    //
+    // The limits apply to every node, peers pick them up within the refresh interval.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        check_bandwidth_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+
+        let query: BandwidthQuery = match req.uri.query() {
+            Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+            None => BandwidthQuery::default(),
+        };
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let current = GLOBAL_BANDWIDTH_SYS.limits();
+        let limits = BandwidthLimits {
+            heal: query.heal.unwrap_or(current.heal),
+            rebalance: query.rebalance.unwrap_or(current.rebalance),
+            decommission: query.decommission.unwrap_or(current.decommission),
+            replication: query.replication.unwrap_or(current.replication),
+        };
+        GLOBAL_BANDWIDTH_SYS.set(store, limits).await.map_err(ApiError::from)?;
+
+        status_response()
+    }
+}
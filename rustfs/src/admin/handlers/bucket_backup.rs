@@ -0,0 +1,250 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::error::StorageError;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store::ECStore;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+    storage::{
+        bucket_backup::{BackupRun, BackupSchedule, GLOBAL_BUCKET_BACKUP_SYS},
+        cron::CronSchedule,
+    },
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BucketBackupQuery {
+    /// Schedule to report on, run, cancel or delete
+    pub id: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupScheduleStatus {
+    #[serde(flatten)]
+    pub schedule: BackupSchedule,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub next_run: Option<OffsetDateTime>,
+    /// The runs of the schedule, oldest first, only when a single schedule is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history: Option<Vec<BackupRun>>,
+}
+
+impl BackupScheduleStatus {
+    fn new(schedule: BackupSchedule, history: Option<Vec<BackupRun>>) -> Self {
+        let next_run = CronSchedule::parse(&schedule.cron)
+            .ok()
+            .filter(|_| schedule.enabled)
+            .and_then(|cron| cron.next_after(OffsetDateTime::now_utc()));
+        Self {
+            schedule,
+            next_run,
+            history,
+        }
+    }
+}
+
+async fn check_backup_request(req: &S3Request<Body>, actions: &[AdminAction]) -> S3Result<(Arc<ECStore>, BucketBackupQuery)> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    let actions = actions.iter().map(|action| Action::AdminAction(*action)).collect();
+    validate_admin_request(&req.headers, &cred, owner, false, actions).await?;
+
+    let query = match req.uri.query() {
+        Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+        None => BucketBackupQuery::default(),
+    };
+
+    let Some(store) = new_object_layer_fn() else {
+        return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+    };
+
+    Ok((store, query))
+}
+
+fn require_id(query: &BucketBackupQuery) -> S3Result<&str> {
+    if query.id.is_empty() {
+        return Err(s3_error!(InvalidArgument, "id is required"));
+    }
+    Ok(&query.id)
+}
+
+fn json_response<T: Serialize>(status: StatusCode, value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal bucket backup failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((status, Body::from(data)), header))
+}
+
+pub struct SetBucketBackup {}
+
+#[async_trait::async_trait]
+impl Operation for SetBucketBackup {
+    // PUT <endpoint>/<admin-API>/bucket-backup[?id=scheduleid]
+    //
+    // The body is a JSON schedule: {"bucket", "prefix", "arn", "cron", "enabled"}.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let (store, query) = check_backup_request(&req, &[AdminAction::StartBatchJobAction]).await?;
+
+        let mut input = req.input;
+        let body = match input.store_all_unlimited().await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+
+        let mut schedule: BackupSchedule = serde_json::from_slice(&body)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, format!("unmarshal body err {e}")))?;
+        if !query.id.is_empty() {
+            schedule.id = query.id;
+        }
+        if schedule.bucket.is_empty() || schedule.arn.is_empty() || schedule.cron.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket, arn and cron are required"));
+        }
+
+        let schedule = match GLOBAL_BUCKET_BACKUP_SYS.put_schedule(store, schedule).await {
+            Ok(schedule) => schedule,
+            Err(StorageError::Io(e)) => return Err(S3Error::with_message(S3ErrorCode::InvalidArgument, e.to_string())),
+            Err(e) => return Err(ApiError::from(e).into()),
+        };
+
+        json_response(StatusCode::OK, &BackupScheduleStatus::new(schedule, None))
+    }
+}
+
+pub struct GetBucketBackup {}
+
+#[async_trait::async_trait]
+impl Operation for GetBucketBackup {
+    // GET <endpoint>/<admin-API>/bucket-backup[?id=scheduleid]
+    //
+    // Lists the schedules, or returns one schedule with the history of its runs.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let (store, query) =
+            check_backup_request(&req, &[AdminAction::ListBatchJobsAction, AdminAction::DescribeBatchJobAction]).await?;
+
+        if query.id.is_empty() {
+            let schedules = GLOBAL_BUCKET_BACKUP_SYS.list_schedules(store).await.map_err(ApiError::from)?;
+            let statuses: Vec<BackupScheduleStatus> = schedules
+                .into_iter()
+                .map(|schedule| BackupScheduleStatus::new(schedule, None))
+                .collect();
+            return json_response(StatusCode::OK, &statuses);
+        }
+
+        let Some(schedule) = GLOBAL_BUCKET_BACKUP_SYS
+            .get_schedule(store.clone(), &query.id)
+            .await
+            .map_err(ApiError::from)?
+        else {
+            return Err(s3_error!(NoSuchKey, "no bucket backup schedule {}", query.id));
+        };
+        let history = GLOBAL_BUCKET_BACKUP_SYS
+            .history(store, &query.id)
+            .await
+            .map_err(ApiError::from)?;
+
+        json_response(StatusCode::OK, &BackupScheduleStatus::new(schedule, Some(history)))
+    }
+}
+
+pub struct RemoveBucketBackup {}
+
+#[async_trait::async_trait]
+impl Operation for RemoveBucketBackup {
+    // DELETE <endpoint>/<admin-API>/bucket-backup?id=scheduleid
+    //
+    // Objects already copied are left on the remote target.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let (store, query) = check_backup_request(&req, &[AdminAction::CancelBatchJobAction]).await?;
+        let id = require_id(&query)?;
+
+        if !GLOBAL_BUCKET_BACKUP_SYS
+            .delete_schedule(store, id)
+            .await
+            .map_err(ApiError::from)?
+        {
+            return Err(s3_error!(NoSuchKey, "no bucket backup schedule {}", id));
+        }
+
+        Ok(S3Response::new((StatusCode::OK, Body::empty())))
+    }
+}
+
+pub struct StartBucketBackupRun {}
+
+#[async_trait::async_trait]
+impl Operation for StartBucketBackupRun {
+    // POST <endpoint>/<admin-API>/bucket-backup/run?id=scheduleid
+    //
+    // Runs the schedule now, unless a run of it is in progress.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let (store, query) = check_backup_request(&req, &[AdminAction::StartBatchJobAction]).await?;
+        let id = require_id(&query)?;
+
+        match GLOBAL_BUCKET_BACKUP_SYS.run_now(store, id).await {
+            Ok(Some(run)) => json_response(StatusCode::ACCEPTED, &run),
+            Ok(None) => Err(s3_error!(InvalidRequest, "a run of bucket backup schedule {} is in progress", id)),
+            Err(StorageError::ConfigNotFound) => Err(s3_error!(NoSuchKey, "no bucket backup schedule {}", id)),
+            Err(e) => Err(ApiError::from(e).into()),
+        }
+    }
+}
+
+pub struct CancelBucketBackupRun {}
+
+#[async_trait::async_trait]
+impl Operation for CancelBucketBackupRun {
+    // DELETE <endpoint>/<admin-API>/bucket-backup/run?id=scheduleid
+    //
+    // Only runs on the node receiving the request can be cancelled.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let (_store, query) = check_backup_request(&req, &[AdminAction::CancelBatchJobAction]).await?;
+        let id = require_id(&query)?;
+
+        if !GLOBAL_BUCKET_BACKUP_SYS.cancel(id) {
+            return Err(s3_error!(NoSuchKey, "no run of bucket backup schedule {} on this node", id));
+        }
+
+        Ok(S3Response::new((StatusCode::ACCEPTED, Body::empty())))
+    }
+}
@@ -12,31 +12,43 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{
-    collections::HashMap,
-    io::{Cursor, Read as _, Write as _},
-};
+use std::io::{Cursor, Read as _, Write as _};
 
 use crate::{
     admin::{auth::validate_admin_request, router::Operation},
     auth::{check_key_valid, get_session_token},
+    error::ApiError,
 };
 
 use http::{HeaderMap, StatusCode};
 use matchit::Params;
 use rustfs_ecstore::{
     StorageAPI,
+    bucket::object_lock::ObjectLockApi,
     bucket::{
+        dedup::BucketDedup,
+        erasure::BucketErasure,
+        integrity::BucketIntegrity,
+        kms_scope::BucketKmsScope,
+        listing::BucketListing,
         metadata::{
-            BUCKET_LIFECYCLE_CONFIG, BUCKET_NOTIFICATION_CONFIG, BUCKET_POLICY_CONFIG, BUCKET_QUOTA_CONFIG_FILE,
-            BUCKET_REPLICATION_CONFIG, BUCKET_SSECONFIG, BUCKET_TAGGING_CONFIG, BUCKET_TARGETS_FILE, BUCKET_VERSIONING_CONFIG,
-            BucketMetadata, OBJECT_LOCK_CONFIG,
+            BUCKET_DEDUP_CONFIG, BUCKET_ERASURE_CONFIG, BUCKET_INTEGRITY_CONFIG, BUCKET_KMS_SCOPE_CONFIG,
+            BUCKET_LIFECYCLE_CONFIG, BUCKET_LISTING_CONFIG, BUCKET_MODE_CONFIG, BUCKET_NAMING_CONFIG, BUCKET_NOTIFICATION_CONFIG,
+            BUCKET_PLACEMENT_CONFIG, BUCKET_POLICY_CONFIG, BUCKET_QUOTA_CONFIG_FILE, BUCKET_REPLICATION_CONFIG, BUCKET_SSECONFIG,
+            BUCKET_TAGGING_CONFIG, BUCKET_TARGETS_FILE, BUCKET_TRANSFORM_CONFIG, BUCKET_TRASH_CONFIG, BUCKET_VERSIONING_CONFIG,
+            OBJECT_LOCK_CONFIG,
         },
+        metadata_history::{MetadataChange, load_history},
         metadata_sys,
+        mode::BucketMode,
+        naming::BucketNaming,
+        placement::BucketPlacement,
         quota::BucketQuota,
         target::BucketTargets,
+        transform::BucketTransform,
+        trash::BucketTrash,
     },
-    error::StorageError,
+    error::{StorageError, is_err_bucket_not_found},
     new_object_layer_fn,
     store_api::BucketOptions,
 };
@@ -58,9 +70,8 @@ use s3s::{
     header::{CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE},
     s3_error,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_urlencoded::from_bytes;
-use time::OffsetDateTime;
 use tracing::warn;
 use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
 
@@ -130,6 +141,16 @@ impl Operation for ExportBucketMetadata {
             BUCKET_VERSIONING_CONFIG,
             BUCKET_REPLICATION_CONFIG,
             BUCKET_TARGETS_FILE,
+            BUCKET_PLACEMENT_CONFIG,
+            BUCKET_TRANSFORM_CONFIG,
+            BUCKET_TRASH_CONFIG,
+            BUCKET_NAMING_CONFIG,
+            BUCKET_LISTING_CONFIG,
+            BUCKET_ERASURE_CONFIG,
+            BUCKET_MODE_CONFIG,
+            BUCKET_DEDUP_CONFIG,
+            BUCKET_INTEGRITY_CONFIG,
+            BUCKET_KMS_SCOPE_CONFIG,
         ];
 
         for bucket in buckets {
@@ -339,6 +360,216 @@ impl Operation for ExportBucketMetadata {
                             .write_all(&config_json)
                             .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
                     }
+                    BUCKET_PLACEMENT_CONFIG => {
+                        let config: BucketPlacement = match metadata_sys::get_placement_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_json = config
+                            .marshal()
+                            .map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_json)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
+                    BUCKET_TRANSFORM_CONFIG => {
+                        let config: BucketTransform = match metadata_sys::get_transform_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_json = config
+                            .marshal()
+                            .map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_json)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
+                    BUCKET_TRASH_CONFIG => {
+                        let config: BucketTrash = match metadata_sys::get_trash_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_json = config
+                            .marshal()
+                            .map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_json)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
+                    BUCKET_NAMING_CONFIG => {
+                        let config: BucketNaming = match metadata_sys::get_naming_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_json = config
+                            .marshal()
+                            .map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_json)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
+                    BUCKET_LISTING_CONFIG => {
+                        let config: BucketListing = match metadata_sys::get_listing_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_json = config
+                            .marshal()
+                            .map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_json)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
+                    BUCKET_ERASURE_CONFIG => {
+                        let config: BucketErasure = match metadata_sys::get_erasure_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_json = config
+                            .marshal()
+                            .map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_json)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
+                    BUCKET_MODE_CONFIG => {
+                        let config: BucketMode = match metadata_sys::get_mode_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_json = config
+                            .marshal()
+                            .map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_json)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
+                    BUCKET_DEDUP_CONFIG => {
+                        let config: BucketDedup = match metadata_sys::get_dedup_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_json = config
+                            .marshal()
+                            .map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_json)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
+                    BUCKET_INTEGRITY_CONFIG => {
+                        let config: BucketIntegrity = match metadata_sys::get_integrity_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_json = config
+                            .marshal()
+                            .map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_json)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
+                    BUCKET_KMS_SCOPE_CONFIG => {
+                        let config: BucketKmsScope = match metadata_sys::get_kms_scope_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_json = config
+                            .marshal()
+                            .map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_json)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
                     _ => {}
                 }
             }
@@ -355,18 +586,137 @@ impl Operation for ExportBucketMetadata {
     }
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportConflict {
+    /// Keep the configuration the bucket already has
+    Skip,
+    /// Replace it with the imported one
+    #[default]
+    Overwrite,
+}
+
 #[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct ImportBucketMetadataQuery {
-    #[allow(dead_code)]
+    /// Only import the configuration of this bucket
     pub bucket: String,
+    pub conflict: ImportConflict,
+    /// Report what the import would change without changing anything
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ImportAction {
+    Added,
+    Overwritten,
+    Skipped,
+    Unchanged,
+    Invalid,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+struct ImportConfigResult {
+    config: String,
+    action: ImportAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ImportBucketResult {
+    bucket: String,
+    created: bool,
+    configs: Vec<ImportConfigResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportBucketMetadataReport {
+    dry_run: bool,
+    buckets: Vec<ImportBucketResult>,
+}
+
+/// Check that `content` parses as the configuration `conf_name`.
+fn validate_bucket_config(conf_name: &str, content: &[u8]) -> Result<(), String> {
+    match conf_name {
+        BUCKET_POLICY_CONFIG => {
+            let config: BucketPolicy = serde_json::from_slice(content).map_err(|e| e.to_string())?;
+            if config.version.is_empty() {
+                return Err("policy has no version".to_string());
+            }
+            Ok(())
+        }
+        BUCKET_NOTIFICATION_CONFIG => deserialize::<s3s::dto::NotificationConfiguration>(content)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        BUCKET_LIFECYCLE_CONFIG => deserialize::<BucketLifecycleConfiguration>(content)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        BUCKET_SSECONFIG => deserialize::<ServerSideEncryptionConfiguration>(content)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        BUCKET_TAGGING_CONFIG => deserialize::<Tagging>(content).map(|_| ()).map_err(|e| e.to_string()),
+        BUCKET_QUOTA_CONFIG_FILE => serde_json::from_slice::<BucketQuota>(content)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        OBJECT_LOCK_CONFIG => deserialize::<ObjectLockConfiguration>(content)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        BUCKET_VERSIONING_CONFIG => deserialize::<VersioningConfiguration>(content)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        BUCKET_REPLICATION_CONFIG => deserialize::<ReplicationConfiguration>(content)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        BUCKET_TARGETS_FILE => serde_json::from_slice::<BucketTargets>(content)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        BUCKET_PLACEMENT_CONFIG => BucketPlacement::unmarshal(content).map(|_| ()).map_err(|e| e.to_string()),
+        BUCKET_TRANSFORM_CONFIG => BucketTransform::unmarshal(content)
+            .and_then(|cfg| cfg.validate())
+            .map_err(|e| e.to_string()),
+        BUCKET_TRASH_CONFIG => BucketTrash::unmarshal(content)
+            .and_then(|cfg| cfg.validate())
+            .map_err(|e| e.to_string()),
+        BUCKET_NAMING_CONFIG => BucketNaming::unmarshal(content)
+            .and_then(|cfg| cfg.validate())
+            .map_err(|e| e.to_string()),
+        BUCKET_LISTING_CONFIG => BucketListing::unmarshal(content).map(|_| ()).map_err(|e| e.to_string()),
+        BUCKET_ERASURE_CONFIG => BucketErasure::unmarshal(content)
+            .and_then(|cfg| cfg.validate())
+            .map_err(|e| e.to_string()),
+        BUCKET_MODE_CONFIG => BucketMode::unmarshal(content).map(|_| ()).map_err(|e| e.to_string()),
+        BUCKET_DEDUP_CONFIG => BucketDedup::unmarshal(content)
+            .and_then(|cfg| cfg.validate())
+            .map_err(|e| e.to_string()),
+        BUCKET_INTEGRITY_CONFIG => BucketIntegrity::unmarshal(content).map(|_| ()).map_err(|e| e.to_string()),
+        BUCKET_KMS_SCOPE_CONFIG => BucketKmsScope::unmarshal(content)
+            .and_then(|cfg| cfg.validate())
+            .map_err(|e| e.to_string()),
+        _ => Err("unknown bucket configuration".to_string()),
+    }
+}
+
+/// Whether the object lock configuration among `configs` enables object lock, which a bucket
+/// only gets when it is created.
+fn wants_object_lock(configs: &[(String, Vec<u8>)]) -> bool {
+    configs.iter().any(|(conf, content)| {
+        conf == OBJECT_LOCK_CONFIG && deserialize::<ObjectLockConfiguration>(content).is_ok_and(|cfg| cfg.enabled())
+    })
 }
 
 pub struct ImportBucketMetadata {}
 
 #[async_trait::async_trait]
 impl Operation for ImportBucketMetadata {
+    // PUT <endpoint>/<admin-API>/import-bucket-metadata[?bucket=mybucket][&conflict=skip|overwrite][&dryRun=true]
     async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
-        let _query = {
+        let query = {
             if let Some(query) = req.uri.query() {
                 let input: ImportBucketMetadataQuery =
                     from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
@@ -403,220 +753,205 @@ impl Operation for ImportBucketMetadata {
 
         let mut zip_reader = ZipArchive::new(Cursor::new(body)).map_err(|e| s3_error!(InternalError, "get body failed: {e}"))?;
 
-        // First pass: read all file contents into memory
-        let mut file_contents = Vec::new();
+        // Read the configurations of each bucket, keeping the order of the bundle
+        let mut buckets: Vec<(String, Vec<(String, Vec<u8>)>)> = Vec::new();
         for i in 0..zip_reader.len() {
             let mut file = zip_reader
                 .by_index(i)
                 .map_err(|e| s3_error!(InternalError, "get file failed: {e}"))?;
             let file_path = file.name().to_string();
 
-            let mut content = Vec::new();
-            file.read_to_end(&mut content)
-                .map_err(|e| s3_error!(InternalError, "read file failed: {e}"))?;
-
-            file_contents.push((file_path, content));
-        }
-
-        // Extract bucket names
-        let mut bucket_names = Vec::new();
-        for (file_path, _) in &file_contents {
             let file_path_split = file_path.split(SLASH_SEPARATOR).collect::<Vec<&str>>();
-
             if file_path_split.len() < 2 {
                 warn!("file path is invalid: {}", file_path);
                 continue;
             }
-
-            let bucket_name = file_path_split[0].to_string();
-            if !bucket_names.contains(&bucket_name) {
-                bucket_names.push(bucket_name);
+            let (bucket_name, conf_name) = (file_path_split[0], file_path_split[1]);
+            if !query.bucket.is_empty() && bucket_name != query.bucket {
+                continue;
             }
-        }
 
-        // Get existing bucket metadata
-        let mut bucket_metadatas: HashMap<String, BucketMetadata> = HashMap::new();
-        for bucket_name in bucket_names {
-            match metadata_sys::get_config_from_disk(&bucket_name).await {
-                Ok(res) => {
-                    bucket_metadatas.insert(bucket_name, res);
-                }
-                Err(e) => {
-                    if e == StorageError::ConfigNotFound {
-                        warn!("bucket metadata not found: {e}");
-                        continue;
-                    }
-                    warn!("get bucket metadata failed: {e}");
-                    continue;
-                }
-            };
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)
+                .map_err(|e| s3_error!(InternalError, "read file failed: {e}"))?;
+
+            match buckets.iter_mut().find(|(name, _)| name == bucket_name) {
+                Some((_, configs)) => configs.push((conf_name.to_string(), content)),
+                None => buckets.push((bucket_name.to_string(), vec![(conf_name.to_string(), content)])),
+            }
         }
 
         let Some(store) = new_object_layer_fn() else {
             return Err(s3_error!(InvalidRequest, "object store not init"));
         };
 
-        let update_at = OffsetDateTime::now_utc();
-
-        // Second pass: process file contents
-        for (file_path, content) in file_contents {
-            let file_path_split = file_path.split(SLASH_SEPARATOR).collect::<Vec<&str>>();
+        let mut report = ImportBucketMetadataReport {
+            dry_run: query.dry_run,
+            buckets: Vec::with_capacity(buckets.len()),
+        };
 
-            if file_path_split.len() < 2 {
-                warn!("file path is invalid: {}", file_path);
-                continue;
-            }
+        for (bucket_name, configs) in buckets {
+            let mut result = ImportBucketResult {
+                bucket: bucket_name.clone(),
+                ..Default::default()
+            };
 
-            let bucket_name = file_path_split[0];
-            let conf_name = file_path_split[1];
-
-            // create bucket if not exists
-            if !bucket_metadatas.contains_key(bucket_name) {
-                if let Err(e) = store
-                    .make_bucket(
-                        bucket_name,
-                        &MakeBucketOptions {
-                            force_create: true,
-                            ..Default::default()
-                        },
-                    )
-                    .await
-                {
-                    warn!("create bucket failed: {e}");
+            let exists = match store.get_bucket_info(&bucket_name, &BucketOptions::default()).await {
+                Ok(_) => true,
+                Err(e) if is_err_bucket_not_found(&e) => false,
+                Err(e) => {
+                    result.error = Some(e.to_string());
+                    report.buckets.push(result);
                     continue;
                 }
+            };
 
-                let metadata = metadata_sys::get(bucket_name).await.unwrap_or_default();
-
-                bucket_metadatas.insert(bucket_name.to_string(), (*metadata).clone());
-            }
-
-            match conf_name {
-                BUCKET_POLICY_CONFIG => {
-                    let config: BucketPolicy = match serde_json::from_slice(&content) {
-                        Ok(config) => config,
-                        Err(e) => {
-                            warn!("deserialize config failed: {e}");
-                            continue;
-                        }
+            if !exists {
+                result.created = true;
+                if !query.dry_run {
+                    let lock_enabled = wants_object_lock(&configs);
+                    let opts = MakeBucketOptions {
+                        force_create: true,
+                        lock_enabled,
+                        versioning_enabled: lock_enabled,
+                        ..Default::default()
                     };
-
-                    if config.version.is_empty() {
+                    if let Err(e) = store.make_bucket(&bucket_name, &opts).await {
+                        warn!("create bucket failed: {e}");
+                        result.error = Some(e.to_string());
+                        report.buckets.push(result);
                         continue;
                     }
-
-                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
-                    metadata.policy_config_json = content;
-                    metadata.policy_config_updated_at = update_at;
-                }
-                BUCKET_NOTIFICATION_CONFIG => {
-                    if let Err(e) = deserialize::<s3s::dto::NotificationConfiguration>(&content) {
-                        warn!("deserialize config failed: {e}");
-                        continue;
-                    }
-
-                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
-                    metadata.notification_config_xml = content;
-                    metadata.notification_config_updated_at = update_at;
                 }
+            }
 
-                BUCKET_LIFECYCLE_CONFIG => {
-                    if let Err(e) = deserialize::<BucketLifecycleConfiguration>(&content) {
-                        warn!("deserialize config failed: {e}");
-                        continue;
-                    }
+            let current = if exists {
+                metadata_sys::get(&bucket_name).await.ok()
+            } else {
+                None
+            };
 
-                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
-                    metadata.lifecycle_config_xml = content;
-                    metadata.lifecycle_config_updated_at = update_at;
+            for (conf_name, content) in configs {
+                if let Err(e) = validate_bucket_config(&conf_name, &content) {
+                    warn!("bucket {} config {} is invalid: {}", bucket_name, conf_name, e);
+                    result.configs.push(ImportConfigResult {
+                        config: conf_name,
+                        action: ImportAction::Invalid,
+                        error: Some(e),
+                    });
+                    continue;
                 }
 
-                BUCKET_SSECONFIG => {
-                    if let Err(e) = deserialize::<ServerSideEncryptionConfiguration>(&content) {
-                        warn!("deserialize config failed: {e}");
-                        continue;
-                    }
-
-                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
-                    metadata.encryption_config_xml = content;
-                    metadata.encryption_config_updated_at = update_at;
+                let existing = current
+                    .as_ref()
+                    .and_then(|m| m.config_data(&conf_name).ok())
+                    .filter(|data| !data.is_empty());
+                let mut action = match existing {
+                    None => ImportAction::Added,
+                    Some(data) if data == content.as_slice() => ImportAction::Unchanged,
+                    Some(_) if query.conflict == ImportConflict::Skip => ImportAction::Skipped,
+                    Some(_) => ImportAction::Overwritten,
+                };
+
+                let mut error = None;
+                if !query.dry_run
+                    && matches!(action, ImportAction::Added | ImportAction::Overwritten)
+                    && let Err(e) = metadata_sys::update(&bucket_name, &conf_name, content).await
+                {
+                    warn!("import bucket {} config {} failed: {}", bucket_name, conf_name, e);
+                    action = ImportAction::Failed;
+                    error = Some(e.to_string());
                 }
 
-                BUCKET_TAGGING_CONFIG => {
-                    if let Err(e) = deserialize::<Tagging>(&content) {
-                        warn!("deserialize config failed: {e}");
-                        continue;
-                    }
-
-                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
-                    metadata.tagging_config_xml = content;
-                    metadata.tagging_config_updated_at = update_at;
-                }
+                result.configs.push(ImportConfigResult {
+                    config: conf_name,
+                    action,
+                    error,
+                });
+            }
 
-                BUCKET_QUOTA_CONFIG_FILE => {
-                    if let Err(e) = serde_json::from_slice::<BucketQuota>(&content) {
-                        warn!("deserialize config failed: {e}");
-                        continue;
-                    }
+            report.buckets.push(result);
+        }
 
-                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
-                    metadata.quota_config_json = content;
-                    metadata.quota_config_updated_at = update_at;
-                }
+        // TODO: site replication notify
 
-                OBJECT_LOCK_CONFIG => {
-                    if let Err(e) = deserialize::<ObjectLockConfiguration>(&content) {
-                        warn!("deserialize config failed: {e}");
-                        continue;
-                    }
+        let data = serde_json::to_vec(&report).map_err(|e| s3_error!(InternalError, "marshal import report failed: {e}"))?;
 
-                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
-                    metadata.object_lock_config_xml = content;
-                    metadata.object_lock_config_updated_at = update_at;
-                }
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
 
-                BUCKET_VERSIONING_CONFIG => {
-                    if let Err(e) = deserialize::<VersioningConfiguration>(&content) {
-                        warn!("deserialize config failed: {e}");
-                        continue;
-                    }
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BucketMetadataHistoryQuery {
+    pub bucket: String,
+}
 
-                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
-                    metadata.versioning_config_xml = content;
-                    metadata.versioning_config_updated_at = update_at;
-                }
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BucketMetadataHistoryResponse {
+    bucket: String,
+    revision: u64,
+    etag: String,
+    changes: Vec<MetadataChange>,
+}
 
-                BUCKET_REPLICATION_CONFIG => {
-                    if let Err(e) = deserialize::<ReplicationConfiguration>(&content) {
-                        warn!("deserialize config failed: {e}");
-                        continue;
-                    }
+pub struct GetBucketMetadataHistory {}
 
-                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
-                    metadata.replication_config_xml = content;
-                    metadata.replication_config_updated_at = update_at;
-                }
+#[async_trait::async_trait]
+impl Operation for GetBucketMetadataHistory {
+    // GET <endpoint>/<admin-API>/bucket-metadata-history?bucket=mybucket
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let Some(input_cred) = &req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
 
-                BUCKET_TARGETS_FILE => {
-                    if let Err(e) = serde_json::from_slice::<BucketTargets>(&content) {
-                        warn!("deserialize config failed: {e}");
-                        continue;
-                    }
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
 
-                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
-                    metadata.bucket_targets_config_json = content;
-                    metadata.bucket_targets_config_updated_at = update_at;
-                }
+        validate_admin_request(
+            &req.headers,
+            &cred,
+            owner,
+            false,
+            vec![Action::AdminAction(AdminAction::ExportBucketMetadataAction)],
+        )
+        .await?;
 
-                _ => {}
-            }
+        let query: BucketMetadataHistoryQuery = match req.uri.query() {
+            Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+            None => BucketMetadataHistoryQuery::default(),
+        };
+        if query.bucket.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket is required"));
         }
 
-        // TODO: site replication notify
+        let Some(store) = new_object_layer_fn() else {
+            return Err(s3_error!(InternalError, "Not init"));
+        };
+
+        store
+            .get_bucket_info(&query.bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        let metadata = metadata_sys::get_config_from_disk(&query.bucket)
+            .await
+            .map_err(ApiError::from)?;
+        let history = load_history(store, &query.bucket).await.map_err(ApiError::from)?;
+
+        let resp = BucketMetadataHistoryResponse {
+            bucket: query.bucket,
+            revision: metadata.revision,
+            etag: metadata.etag(),
+            changes: history.changes,
+        };
+        let data = serde_json::to_vec(&resp).map_err(|e| s3_error!(InternalError, "marshal history failed: {e}"))?;
 
         let mut header = HeaderMap::new();
         header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
-        header.insert(CONTENT_LENGTH, "0".parse().unwrap());
-        Ok(S3Response::with_headers((StatusCode::OK, Body::empty()), header))
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
     }
 }
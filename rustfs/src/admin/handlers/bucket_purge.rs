@@ -0,0 +1,115 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::bucket::purge::GLOBAL_BUCKET_PURGE_SYS;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_policy::policy::action::{Action, AdminAction, S3Action};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BucketPurgeQuery {
+    pub bucket: String,
+}
+
+async fn check_purge_request(req: &S3Request<Body>, action: Action) -> S3Result<BucketPurgeQuery> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![action]).await?;
+
+    let query = {
+        if let Some(query) = req.uri.query() {
+            let input: BucketPurgeQuery =
+                from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+            input
+        } else {
+            BucketPurgeQuery::default()
+        }
+    };
+
+    Ok(query)
+}
+
+fn json_response<T: Serialize>(status: StatusCode, value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal purge status failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((status, Body::from(data)), header))
+}
+
+pub struct ForceDeleteBucket {}
+
+#[async_trait::async_trait]
+impl Operation for ForceDeleteBucket {
+    // POST <endpoint>/<admin-API>/force-delete-bucket?bucket=mybucket
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle ForceDeleteBucket");
+
+        let query = check_purge_request(&req, Action::S3Action(S3Action::ForceDeleteBucketAction)).await?;
+        if query.bucket.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket is required"));
+        }
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let status = GLOBAL_BUCKET_PURGE_SYS
+            .start(store, &query.bucket)
+            .await
+            .map_err(ApiError::from)?;
+
+        json_response(StatusCode::ACCEPTED, &status)
+    }
+}
+
+pub struct BucketPurgeStatus {}
+
+#[async_trait::async_trait]
+impl Operation for BucketPurgeStatus {
+    // GET <endpoint>/<admin-API>/bucket-purge-status[?bucket=mybucket]
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = check_purge_request(&req, Action::AdminAction(AdminAction::ServerInfoAdminAction)).await?;
+
+        if query.bucket.is_empty() {
+            return json_response(StatusCode::OK, &GLOBAL_BUCKET_PURGE_SYS.list());
+        }
+
+        match GLOBAL_BUCKET_PURGE_SYS.status(&query.bucket) {
+            Some(status) => json_response(StatusCode::OK, &status),
+            None => Err(s3_error!(NoSuchKey, "no purge in progress for bucket {}", query.bucket)),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::compat::{CompatConfig, DirectoryMarkers, GLOBAL_COMPAT_SYS};
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CompatQuery {
+    /// Unchanged when not given.
+    pub signature_v2: Option<bool>,
+    pub s3a_magic_committer: Option<bool>,
+    /// `keep` or `delete`.
+    pub directory_markers: Option<DirectoryMarkers>,
+}
+
+async fn check_compat_request(req: &S3Request<Body>, action: AdminAction) -> S3Result<()> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(action)]).await
+}
+
+fn config_response(config: &CompatConfig) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(config)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal compat config failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct GetCompat {}
+
+#[async_trait::async_trait]
+impl Operation for GetCompat {
+    // GET <endpoint>/<admin-API>/compat
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        check_compat_request(&req, AdminAction::ServerInfoAdminAction).await?;
+
+        config_response(&GLOBAL_COMPAT_SYS.config())
+    }
+}
+
+pub struct SetCompat {}
+
+#[async_trait::async_trait]
+impl Operation for SetCompat {
+    // POST <endpoint>/<admin-API>/compat?signatureV2=true&s3aMagicCommitter=true&directoryMarkers=delete
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle SetCompat");
+
+        check_compat_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+
+        let query = {
+            if let Some(query) = req.uri.query() {
+                let input: CompatQuery =
+                    from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+                input
+            } else {
+                CompatQuery::default()
+            }
+        };
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let mut config = GLOBAL_COMPAT_SYS.config();
+        if let Some(signature_v2) = query.signature_v2 {
+            config.signature_v2 = signature_v2;
+        }
+        if let Some(s3a_magic_committer) = query.s3a_magic_committer {
+            config.s3a_magic_committer = s3a_magic_committer;
+        }
+        if let Some(directory_markers) = query.directory_markers {
+            config.directory_markers = directory_markers;
+        }
+
+        let config = GLOBAL_COMPAT_SYS.set(store, config).await.map_err(ApiError::from)?;
+
+        config_response(&config)
+    }
+}
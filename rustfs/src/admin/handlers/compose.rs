@@ -0,0 +1,134 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::compose::{ComposeSource, compose_object};
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_policy::policy::Args;
+use rustfs_policy::policy::action::{Action, S3Action};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::warn;
+
+use crate::{
+    admin::router::Operation,
+    auth::{check_key_valid, get_condition_values, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ComposeObjectRequest {
+    pub bucket: String,
+    pub object: String,
+    pub sources: Vec<ComposeSource>,
+    /// Metadata of the composed object, `content-type` and `x-amz-meta-*` keys
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposeObjectResponse {
+    pub bucket: String,
+    pub object: String,
+    pub etag: Option<String>,
+    pub size: i64,
+    pub version_id: Option<String>,
+}
+
+pub struct ComposeObject {}
+
+#[async_trait::async_trait]
+impl Operation for ComposeObject {
+    // POST <endpoint>/<admin-API>/compose-object
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let Some(input_cred) = &req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        let mut input = req.input;
+        let body = match input.store_all_unlimited().await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+
+        let request: ComposeObjectRequest = serde_json::from_slice(&body)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, format!("unmarshal body err {e}")))?;
+        if request.bucket.is_empty() || request.object.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket and object are required"));
+        }
+
+        // The caller needs to be allowed to read every source and to write the destination
+        let Ok(iam_store) = rustfs_iam::get() else {
+            return Err(s3_error!(InternalError, "iam not init"));
+        };
+        let conditions = get_condition_values(&req.headers, &cred, None, None);
+        let claims = cred.claims.clone().unwrap_or_default();
+        let checks = request
+            .sources
+            .iter()
+            .map(|s| (S3Action::GetObjectAction, s.object.as_str()))
+            .chain(std::iter::once((S3Action::PutObjectAction, request.object.as_str())));
+        for (action, object) in checks {
+            let allowed = iam_store
+                .is_allowed(&Args {
+                    account: &cred.access_key,
+                    groups: &cred.groups,
+                    action: Action::S3Action(action),
+                    conditions: &conditions,
+                    is_owner: owner,
+                    claims: &claims,
+                    deny_only: false,
+                    bucket: &request.bucket,
+                    object,
+                })
+                .await;
+            if !allowed {
+                return Err(s3_error!(AccessDenied, "Access Denied"));
+            }
+        }
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let info = compose_object(store, &request.bucket, &request.object, &request.sources, request.metadata)
+            .await
+            .map_err(ApiError::from)?;
+
+        let response = ComposeObjectResponse {
+            bucket: info.bucket,
+            object: info.name,
+            etag: info.etag,
+            size: info.size,
+            version_id: info.version_id.map(|v| v.to_string()),
+        };
+        let data = serde_json::to_vec(&response)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal compose result err {e}")))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::config::backup::{create_backup, list_backups, restore_backup};
+use rustfs_ecstore::error::StorageError;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store::ECStore;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RestoreConfigBackupQuery {
+    /// Restore the last backup taken at or before this time, RFC 3339.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub time: Option<OffsetDateTime>,
+}
+
+/// Backups hold IAM credentials, so every operation on them needs the config update permission.
+async fn check_backup_request(req: &S3Request<Body>) -> S3Result<Arc<ECStore>> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(
+        &req.headers,
+        &cred,
+        owner,
+        false,
+        vec![Action::AdminAction(AdminAction::ConfigUpdateAdminAction)],
+    )
+    .await?;
+
+    let Some(store) = new_object_layer_fn() else {
+        return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+    };
+    Ok(store)
+}
+
+fn json_response<T: Serialize>(value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal config backup failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct ListConfigBackups {}
+
+#[async_trait::async_trait]
+impl Operation for ListConfigBackups {
+    // GET <endpoint>/<admin-API>/config-backups
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let store = check_backup_request(&req).await?;
+
+        let backups = list_backups(store).await.map_err(ApiError::from)?;
+        json_response(&backups)
+    }
+}
+
+pub struct CreateConfigBackup {}
+
+#[async_trait::async_trait]
+impl Operation for CreateConfigBackup {
+    // POST <endpoint>/<admin-API>/config-backups
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let store = check_backup_request(&req).await?;
+
+        match create_backup(store, OffsetDateTime::now_utc())
+            .await
+            .map_err(ApiError::from)?
+        {
+            Some(backup) => json_response(&backup),
+            None => Err(s3_error!(SlowDown, "a config backup was taken within the same second")),
+        }
+    }
+}
+
+pub struct RestoreConfigBackup {}
+
+#[async_trait::async_trait]
+impl Operation for RestoreConfigBackup {
+    // POST <endpoint>/<admin-API>/config-backups/restore?time=2024-05-01T00:00:00Z
+    //
+    // Objects created after the backup are kept. The state before the restore is backed up first,
+    // its id is part of the report.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let store = check_backup_request(&req).await?;
+
+        let query: RestoreConfigBackupQuery = match req.uri.query() {
+            Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+            None => RestoreConfigBackupQuery::default(),
+        };
+        let Some(at) = query.time else {
+            return Err(s3_error!(InvalidArgument, "time is required"));
+        };
+
+        warn!("restoring config backup taken at or before {}", at);
+        let report = restore_backup(store, at).await.map_err(|err| match err {
+            StorageError::ConfigNotFound => s3_error!(NoSuchKey, "no config backup taken at or before {}", at),
+            err => ApiError::from(err).into(),
+        })?;
+        json_response(&report)
+    }
+}
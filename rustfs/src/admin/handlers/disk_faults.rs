@@ -0,0 +1,130 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Disk fault injection rules of the node answering the request. Debug builds only.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::disk::fault::{FaultRule, GLOBAL_DISK_FAULTS};
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+};
+
+#[derive(Debug, Default, Deserialize)]
+struct DiskFaultQuery {
+    id: Option<u64>,
+}
+
+async fn check_fault_request(req: &S3Request<Body>) -> S3Result<()> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(
+        &req.headers,
+        &cred,
+        owner,
+        false,
+        vec![Action::AdminAction(AdminAction::ConfigUpdateAdminAction)],
+    )
+    .await
+}
+
+fn json_response<T: serde::Serialize>(value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal response err {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct ListDiskFaults {}
+
+#[async_trait::async_trait]
+impl Operation for ListDiskFaults {
+    // GET <endpoint>/<admin-API>/debug/disk-faults
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        check_fault_request(&req).await?;
+
+        json_response(&GLOBAL_DISK_FAULTS.list())
+    }
+}
+
+pub struct AddDiskFault {}
+
+#[async_trait::async_trait]
+impl Operation for AddDiskFault {
+    // PUT <endpoint>/<admin-API>/debug/disk-faults
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        check_fault_request(&req).await?;
+
+        let mut input = req.input;
+        let body = match input.store_all_unlimited().await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+
+        let rule: FaultRule = serde_json::from_slice(&body)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, format!("unmarshal body err {e}")))?;
+
+        warn!("disk fault injection enabled: {:?}", rule);
+        let id = GLOBAL_DISK_FAULTS.add(rule);
+
+        json_response(&serde_json::json!({ "id": id }))
+    }
+}
+
+pub struct RemoveDiskFault {}
+
+#[async_trait::async_trait]
+impl Operation for RemoveDiskFault {
+    // DELETE <endpoint>/<admin-API>/debug/disk-faults?id=xxx, every rule without id
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        check_fault_request(&req).await?;
+
+        let query: DiskFaultQuery = match req.uri.query() {
+            Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+            None => DiskFaultQuery::default(),
+        };
+
+        match query.id {
+            Some(id) => {
+                if !GLOBAL_DISK_FAULTS.remove(id) {
+                    return Err(s3_error!(InvalidArgument, "no disk fault rule {}", id));
+                }
+            }
+            None => GLOBAL_DISK_FAULTS.clear(),
+        }
+
+        Ok(S3Response::new((StatusCode::OK, Body::empty())))
+    }
+}
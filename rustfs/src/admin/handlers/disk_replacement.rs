@@ -0,0 +1,143 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::disk_replacement::{DiskReplacementState, GLOBAL_DISK_REPLACEMENT_SYS};
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DiskReplacementQuery {
+    /// Endpoint of the drive, as listed by the server info.
+    pub disk: String,
+    pub reason: String,
+}
+
+async fn check_disk_replacement_request(req: &S3Request<Body>, action: AdminAction) -> S3Result<()> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(action)]).await
+}
+
+fn parse_query(req: &S3Request<Body>) -> S3Result<DiskReplacementQuery> {
+    let query: DiskReplacementQuery = match req.uri.query() {
+        Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+        None => DiskReplacementQuery::default(),
+    };
+    if query.disk.is_empty() {
+        return Err(s3_error!(InvalidArgument, "disk is required"));
+    }
+    Ok(query)
+}
+
+fn status_response(state: DiskReplacementState) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(&state)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal disk replacement status failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct GetDiskReplacement {}
+
+#[async_trait::async_trait]
+impl Operation for GetDiskReplacement {
+    // GET <endpoint>/<admin-API>/disk-replacement
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        check_disk_replacement_request(&req, AdminAction::ServerInfoAdminAction).await?;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        // Steps are moved forward by the node serving the drive, read what it persisted
+        if let Err(err) = GLOBAL_DISK_REPLACEMENT_SYS.load(store).await {
+            warn!("load disk replacement state failed: {:?}", err);
+        }
+
+        status_response(GLOBAL_DISK_REPLACEMENT_SYS.state())
+    }
+}
+
+pub struct MarkDiskReplacement {}
+
+#[async_trait::async_trait]
+impl Operation for MarkDiskReplacement {
+    // POST <endpoint>/<admin-API>/disk-replacement?disk=<endpoint>&reason=...
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle MarkDiskReplacement");
+
+        check_disk_replacement_request(&req, AdminAction::HealAdminAction).await?;
+        let query = parse_query(&req)?;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        GLOBAL_DISK_REPLACEMENT_SYS
+            .mark(store, &query.disk, &query.reason)
+            .await
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidRequest, e.to_string()))?;
+
+        status_response(GLOBAL_DISK_REPLACEMENT_SYS.state())
+    }
+}
+
+pub struct ClearDiskReplacement {}
+
+#[async_trait::async_trait]
+impl Operation for ClearDiskReplacement {
+    // DELETE <endpoint>/<admin-API>/disk-replacement?disk=<endpoint>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle ClearDiskReplacement");
+
+        check_disk_replacement_request(&req, AdminAction::HealAdminAction).await?;
+        let query = parse_query(&req)?;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let cleared = GLOBAL_DISK_REPLACEMENT_SYS
+            .clear(store, &query.disk)
+            .await
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
+        if !cleared {
+            return Err(s3_error!(InvalidArgument, "drive {} is not being replaced", query.disk));
+        }
+
+        status_response(GLOBAL_DISK_REPLACEMENT_SYS.state())
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::bucket::erasure::BucketErasure;
+use rustfs_ecstore::bucket::metadata::BUCKET_ERASURE_CONFIG;
+use rustfs_ecstore::bucket::metadata_sys;
+use rustfs_ecstore::error::StorageError;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store_api::{BucketOptions, StorageAPI};
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BucketErasureQuery {
+    pub bucket: String,
+}
+
+/// Authorize an admin erasure request and return the bucket it targets.
+async fn check_erasure_request(req: &S3Request<Body>) -> S3Result<String> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(
+        &req.headers,
+        &cred,
+        owner,
+        false,
+        vec![Action::AdminAction(AdminAction::ConfigUpdateAdminAction)],
+    )
+    .await?;
+
+    let query = {
+        if let Some(query) = req.uri.query() {
+            let input: BucketErasureQuery =
+                from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+            input
+        } else {
+            BucketErasureQuery::default()
+        }
+    };
+
+    if query.bucket.is_empty() {
+        return Err(s3_error!(InvalidArgument, "bucket is required"));
+    }
+
+    let Some(store) = new_object_layer_fn() else {
+        return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+    };
+
+    store
+        .get_bucket_info(&query.bucket, &BucketOptions::default())
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(query.bucket)
+}
+
+pub struct GetBucketErasure {}
+
+#[async_trait::async_trait]
+impl Operation for GetBucketErasure {
+    // GET <endpoint>/<admin-API>/bucket-erasure?bucket=mybucket
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle GetBucketErasure");
+
+        let bucket = check_erasure_request(&req).await?;
+
+        let cfg = match metadata_sys::get_erasure_config(&bucket).await {
+            Ok((cfg, _)) => cfg,
+            Err(StorageError::ConfigNotFound) => BucketErasure::default(),
+            Err(e) => return Err(ApiError::from(e).into()),
+        };
+
+        let data = cfg.marshal().map_err(ApiError::from)?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
+
+pub struct SetBucketErasure {}
+
+#[async_trait::async_trait]
+impl Operation for SetBucketErasure {
+    // PUT <endpoint>/<admin-API>/bucket-erasure?bucket=mybucket
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle SetBucketErasure");
+
+        let bucket = check_erasure_request(&req).await?;
+
+        let mut input = req.input;
+        let body = match input.store_all_unlimited().await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+
+        let cfg = BucketErasure::unmarshal(&body)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, format!("unmarshal body err {e}")))?;
+        cfg.validate()
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, e.to_string()))?;
+
+        let data = cfg.marshal().map_err(ApiError::from)?;
+        metadata_sys::update(&bucket, BUCKET_ERASURE_CONFIG, data)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::default())))
+    }
+}
+
+pub struct RemoveBucketErasure {}
+
+#[async_trait::async_trait]
+impl Operation for RemoveBucketErasure {
+    // DELETE <endpoint>/<admin-API>/bucket-erasure?bucket=mybucket
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle RemoveBucketErasure");
+
+        let bucket = check_erasure_request(&req).await?;
+
+        metadata_sys::delete(&bucket, BUCKET_ERASURE_CONFIG)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::default())))
+    }
+}
@@ -14,14 +14,21 @@
 
 use crate::admin::router::Operation;
 use crate::auth::{check_key_valid, get_session_token};
+use chrono::{DateTime, Utc};
 use http::{HeaderMap, StatusCode};
 use matchit::Params;
-use rustfs_config::notify::{NOTIFY_MQTT_SUB_SYS, NOTIFY_WEBHOOK_SUB_SYS};
+use rustfs_config::notify::{
+    NOTIFY_ES_SUB_SYS, NOTIFY_MQTT_SUB_SYS, NOTIFY_MY_SQL_SUB_SYS, NOTIFY_POSTGRES_SUB_SYS, NOTIFY_ROUTE_PREFIX,
+    NOTIFY_WEBHOOK_SUB_SYS,
+};
 use rustfs_config::{ENABLE_KEY, EnableState};
+use rustfs_notify::NotificationError;
+use rustfs_targets::arn::TargetID;
 use rustfs_targets::check_mqtt_broker_available;
 use s3s::header::CONTENT_LENGTH;
 use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::future::Future;
 use std::io::{Error, ErrorKind};
 use std::net::SocketAddr;
@@ -149,6 +156,9 @@ impl Operation for NotificationTarget {
         let allowed_keys: std::collections::HashSet<&str> = match target_type {
             NOTIFY_WEBHOOK_SUB_SYS => rustfs_config::notify::NOTIFY_WEBHOOK_KEYS.iter().cloned().collect(),
             NOTIFY_MQTT_SUB_SYS => rustfs_config::notify::NOTIFY_MQTT_KEYS.iter().cloned().collect(),
+            NOTIFY_POSTGRES_SUB_SYS => rustfs_config::notify::NOTIFY_POSTGRES_KEYS.iter().cloned().collect(),
+            NOTIFY_MY_SQL_SUB_SYS => rustfs_config::notify::NOTIFY_MYSQL_KEYS.iter().cloned().collect(),
+            NOTIFY_ES_SUB_SYS => rustfs_config::notify::NOTIFY_ELASTICSEARCH_KEYS.iter().cloned().collect(),
             _ => unreachable!(),
         };
 
@@ -257,6 +267,33 @@ impl Operation for NotificationTarget {
             }
         }
 
+        if target_type == NOTIFY_POSTGRES_SUB_SYS || target_type == NOTIFY_MY_SQL_SUB_SYS {
+            let required_keys = if target_type == NOTIFY_POSTGRES_SUB_SYS {
+                [rustfs_config::POSTGRES_CONNECTION_STRING, rustfs_config::POSTGRES_TABLE]
+            } else {
+                [rustfs_config::MYSQL_DSN_STRING, rustfs_config::MYSQL_TABLE]
+            };
+            for required in required_keys {
+                if !kvs_vec.iter().any(|kv| kv.key == required && !kv.value.is_empty()) {
+                    return Err(s3_error!(InvalidArgument, "{} is required", required));
+                }
+            }
+            if let Some(queue_dir) = queue_dir_val {
+                validate_queue_dir(&queue_dir).await?;
+            }
+        }
+
+        if target_type == NOTIFY_ES_SUB_SYS {
+            for required in [rustfs_config::ELASTICSEARCH_URL, rustfs_config::ELASTICSEARCH_INDEX] {
+                if !kvs_vec.iter().any(|kv| kv.key == required && !kv.value.is_empty()) {
+                    return Err(s3_error!(InvalidArgument, "{} is required", required));
+                }
+            }
+            if let Some(queue_dir) = queue_dir_val {
+                validate_queue_dir(&queue_dir).await?;
+            }
+        }
+
         // 3. Add ENABLE_KEY
         kvs_vec.push(rustfs_ecstore::config::KV {
             key: ENABLE_KEY.to_string(),
@@ -422,6 +459,98 @@ impl Operation for RemoveNotificationTarget {
     }
 }
 
+/// Get the delivery lag and backlog of every active notification target on this node
+pub struct NotificationTargetLag {}
+#[async_trait::async_trait]
+impl Operation for NotificationTargetLag {
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        // 1. Permission verification
+        let Some(input_cred) = &req.credentials else {
+            return Err(s3_error!(InvalidRequest, "credentials not found"));
+        };
+        let (_cred, _owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        // 2. Get notification system instance
+        let Some(ns) = rustfs_notify::notification_system() else {
+            return Err(s3_error!(InternalError, "notification system not initialized"));
+        };
+
+        // 3. Serialize and return the lag of each target
+        let lag = ns.target_lag().await;
+        let data = serde_json::to_vec(&lag)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("failed to serialize target lag: {e}")))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        if let Some(v) = req.headers.get("x-request-id") {
+            header.insert("x-request-id", v.clone());
+        }
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplayResponse {
+    replayed: usize,
+}
+
+/// Dispatch the events journaled for a notification target within a time range again
+pub struct ReplayNotificationTarget {}
+#[async_trait::async_trait]
+impl Operation for ReplayNotificationTarget {
+    async fn call(&self, req: S3Request<Body>, params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        // 1. Analyze path and query parameters
+        let (target_type, target_name) = extract_target_params(&params)?;
+        let query: HashMap<String, String> = req
+            .uri
+            .query()
+            .map(|q| url::form_urlencoded::parse(q.as_bytes()).into_owned().collect())
+            .unwrap_or_default();
+        let parse_time = |key: &str| -> S3Result<DateTime<Utc>> {
+            let value = query
+                .get(key)
+                .ok_or_else(|| s3_error!(InvalidArgument, "missing required parameter: '{}'", key))?;
+            DateTime::parse_from_rfc3339(value)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|e| s3_error!(InvalidArgument, "invalid '{}': {}", key, e))
+        };
+        let start = parse_time("start")?;
+        let end = parse_time("end")?;
+
+        // 2. Permission verification
+        let Some(input_cred) = &req.credentials else {
+            return Err(s3_error!(InvalidRequest, "credentials not found"));
+        };
+        let (_cred, _owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        // 3. Get notification system instance
+        let Some(ns) = rustfs_notify::notification_system() else {
+            return Err(s3_error!(InternalError, "notification system not initialized"));
+        };
+
+        // 4. Replay the journaled events
+        let target_id = TargetID::new(target_name.to_string(), target_type.trim_start_matches(NOTIFY_ROUTE_PREFIX).to_string());
+        info!("Replaying events of target {} from {} to {}", target_id, start, end);
+        let replayed = ns.replay_events(&target_id, start, end).await.map_err(|e| match e {
+            NotificationError::TargetNotFound(_) => s3_error!(InvalidArgument, "{}", e),
+            NotificationError::Configuration(_) => s3_error!(InvalidRequest, "{}", e),
+            _ => S3Error::with_message(S3ErrorCode::InternalError, format!("failed to replay events: {e}")),
+        })?;
+
+        let data = serde_json::to_vec(&ReplayResponse { replayed })
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("failed to serialize response: {e}")))?;
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        if let Some(v) = req.headers.get("x-request-id") {
+            header.insert("x-request-id", v.clone());
+        }
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
+
 fn extract_param<'a>(params: &'a Params<'_, '_>, key: &str) -> S3Result<&'a str> {
     params
         .get(key)
@@ -430,7 +559,15 @@ fn extract_param<'a>(params: &'a Params<'_, '_>, key: &str) -> S3Result<&'a str>
 
 fn extract_target_params<'a>(params: &'a Params<'_, '_>) -> S3Result<(&'a str, &'a str)> {
     let target_type = extract_param(params, "target_type")?;
-    if target_type != NOTIFY_WEBHOOK_SUB_SYS && target_type != NOTIFY_MQTT_SUB_SYS {
+    if ![
+        NOTIFY_WEBHOOK_SUB_SYS,
+        NOTIFY_MQTT_SUB_SYS,
+        NOTIFY_POSTGRES_SUB_SYS,
+        NOTIFY_MY_SQL_SUB_SYS,
+        NOTIFY_ES_SUB_SYS,
+    ]
+    .contains(&target_type)
+    {
         return Err(s3_error!(InvalidArgument, "unsupported target type: '{}'", target_type));
     }
 
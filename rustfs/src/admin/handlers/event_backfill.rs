@@ -0,0 +1,184 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use rustfs_targets::EventName;
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+    storage::event_backfill::{EventBackfillRequest, GLOBAL_EVENT_BACKFILL_SYS},
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EventBackfillQuery {
+    pub bucket: String,
+    pub prefix: String,
+    /// RFC 3339 time, only objects modified at or after it
+    pub since: String,
+    /// RFC 3339 time, only objects modified before it
+    pub until: String,
+    /// Job to report on or cancel
+    pub id: String,
+}
+
+async fn check_backfill_request(req: &S3Request<Body>, actions: &[AdminAction]) -> S3Result<EventBackfillQuery> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    let actions = actions.iter().map(|action| Action::AdminAction(*action)).collect();
+    validate_admin_request(&req.headers, &cred, owner, false, actions).await?;
+
+    match req.uri.query() {
+        Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed")),
+        None => Ok(EventBackfillQuery::default()),
+    }
+}
+
+fn parse_time(key: &str, value: &str) -> S3Result<Option<OffsetDateTime>> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    OffsetDateTime::parse(value, &Rfc3339)
+        .map(Some)
+        .map_err(|_e| s3_error!(InvalidArgument, "{} must be an RFC 3339 time", key))
+}
+
+fn json_response<T: Serialize>(status: StatusCode, value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal event backfill job failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((status, Body::from(data)), header))
+}
+
+pub struct StartEventBackfill {}
+
+#[async_trait::async_trait]
+impl Operation for StartEventBackfill {
+    // POST <endpoint>/<admin-API>/event-backfill?bucket=mybucket[&prefix=logs/][&since=<RFC3339>][&until=<RFC3339>]
+    //
+    // Sends an s3:ObjectCreated:Put event for every current object matching the filter.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = check_backfill_request(&req, &[AdminAction::StartBatchJobAction]).await?;
+        if query.bucket.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket is required"));
+        }
+
+        let since = parse_time("since", &query.since)?;
+        let until = parse_time("until", &query.until)?;
+        if let (Some(since), Some(until)) = (since, until)
+            && since >= until
+        {
+            return Err(s3_error!(InvalidArgument, "since must be before until"));
+        }
+
+        let Some(ns) = rustfs_notify::notification_system() else {
+            return Err(s3_error!(InternalError, "notification system not initialized"));
+        };
+        if !ns.has_subscriber(&query.bucket, &EventName::ObjectCreatedPut).await {
+            return Err(s3_error!(
+                InvalidRequest,
+                "bucket {} has no subscriber for s3:ObjectCreated:Put events",
+                query.bucket
+            ));
+        }
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let job = GLOBAL_EVENT_BACKFILL_SYS
+            .start(
+                store,
+                EventBackfillRequest {
+                    bucket: query.bucket,
+                    prefix: query.prefix,
+                    since,
+                    until,
+                },
+            )
+            .await
+            .map_err(ApiError::from)?;
+
+        json_response(StatusCode::ACCEPTED, &job)
+    }
+}
+
+pub struct EventBackfillStatus {}
+
+#[async_trait::async_trait]
+impl Operation for EventBackfillStatus {
+    // GET <endpoint>/<admin-API>/event-backfill[?id=jobid]
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query =
+            check_backfill_request(&req, &[AdminAction::ListBatchJobsAction, AdminAction::DescribeBatchJobAction]).await?;
+
+        if query.id.is_empty() {
+            return json_response(StatusCode::OK, &GLOBAL_EVENT_BACKFILL_SYS.list());
+        }
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        match GLOBAL_EVENT_BACKFILL_SYS
+            .status(store, &query.id)
+            .await
+            .map_err(ApiError::from)?
+        {
+            Some(job) => json_response(StatusCode::OK, &job),
+            None => Err(s3_error!(NoSuchKey, "no event backfill job {}", query.id)),
+        }
+    }
+}
+
+pub struct CancelEventBackfill {}
+
+#[async_trait::async_trait]
+impl Operation for CancelEventBackfill {
+    // DELETE <endpoint>/<admin-API>/event-backfill?id=jobid
+    //
+    // Only jobs running on the node receiving the request can be cancelled.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = check_backfill_request(&req, &[AdminAction::CancelBatchJobAction]).await?;
+        if query.id.is_empty() {
+            return Err(s3_error!(InvalidArgument, "id is required"));
+        }
+
+        if !GLOBAL_EVENT_BACKFILL_SYS.cancel(&query.id) {
+            return Err(s3_error!(NoSuchKey, "no event backfill job {} running on this node", query.id));
+        }
+
+        Ok(S3Response::new((StatusCode::ACCEPTED, Body::empty())))
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::federation::{BucketRoute, FederationConfig, GLOBAL_FEDERATION_SYS};
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BucketRouteQuery {
+    pub bucket: String,
+    /// `scheme://host[:port]` of the cluster owning the bucket.
+    pub endpoint: String,
+    pub region: Option<String>,
+}
+
+async fn check_federation_request(req: &S3Request<Body>, action: AdminAction) -> S3Result<BucketRouteQuery> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(action)]).await?;
+
+    let query = {
+        if let Some(query) = req.uri.query() {
+            let input: BucketRouteQuery =
+                from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+            input
+        } else {
+            BucketRouteQuery::default()
+        }
+    };
+
+    Ok(query)
+}
+
+fn config_response(config: &FederationConfig) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(config)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal bucket routes failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct ListBucketRoutes {}
+
+#[async_trait::async_trait]
+impl Operation for ListBucketRoutes {
+    // GET <endpoint>/<admin-API>/federation/routes
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        check_federation_request(&req, AdminAction::ServerInfoAdminAction).await?;
+
+        config_response(&GLOBAL_FEDERATION_SYS.config())
+    }
+}
+
+pub struct SetBucketRoute {}
+
+#[async_trait::async_trait]
+impl Operation for SetBucketRoute {
+    // PUT <endpoint>/<admin-API>/federation/route?bucket=photos&endpoint=https://cluster-b:9000[&region=eu-west-1]
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle SetBucketRoute");
+
+        let query = check_federation_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+        if query.bucket.is_empty() || query.endpoint.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket and endpoint are required"));
+        }
+
+        let route = BucketRoute::new(&query.endpoint, query.region)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, e.to_string()))?;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let config = GLOBAL_FEDERATION_SYS
+            .set_route(store, &query.bucket, route)
+            .await
+            .map_err(ApiError::from)?;
+
+        config_response(&config)
+    }
+}
+
+pub struct RemoveBucketRoute {}
+
+#[async_trait::async_trait]
+impl Operation for RemoveBucketRoute {
+    // DELETE <endpoint>/<admin-API>/federation/route?bucket=photos
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle RemoveBucketRoute");
+
+        let query = check_federation_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+        if query.bucket.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket is required"));
+        }
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        if !GLOBAL_FEDERATION_SYS
+            .remove_route(store, &query.bucket)
+            .await
+            .map_err(ApiError::from)?
+        {
+            return Err(s3_error!(InvalidArgument, "bucket {} has no route", query.bucket));
+        }
+
+        config_response(&GLOBAL_FEDERATION_SYS.config())
+    }
+}
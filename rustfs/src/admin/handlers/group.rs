@@ -15,7 +15,7 @@
 use http::{HeaderMap, StatusCode};
 use matchit::Params;
 use rustfs_ecstore::global::get_global_action_cred;
-use rustfs_iam::error::{is_err_no_such_group, is_err_no_such_user};
+use rustfs_iam::error::{Error as IamError, is_err_no_such_group, is_err_no_such_user};
 use rustfs_madmin::GroupAddRemove;
 use rustfs_policy::policy::action::{Action, AdminAction};
 use s3s::{
@@ -23,19 +23,41 @@ use s3s::{
     header::{CONTENT_LENGTH, CONTENT_TYPE},
     s3_error,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_urlencoded::from_bytes;
 use tracing::warn;
 
 use crate::{
-    admin::{auth::validate_admin_request, router::Operation, utils::has_space_be},
+    admin::{
+        auth::validate_admin_request,
+        router::Operation,
+        utils::{PageQuery, has_space_be},
+    },
     auth::{check_key_valid, get_session_token},
 };
 
 #[derive(Debug, Deserialize, Default)]
+#[serde(default)]
 pub struct GroupQuery {
     pub group: String,
     pub status: Option<String>,
+    /// Delete the group even if it still has members.
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GroupsPage {
+    groups: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_marker: Option<String>,
+}
+
+fn parse_group_query(req: &S3Request<Body>) -> S3Result<GroupQuery> {
+    match req.uri.query() {
+        Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed")),
+        None => Ok(GroupQuery::default()),
+    }
 }
 
 pub struct ListGroups {}
@@ -62,12 +84,24 @@ impl Operation for ListGroups {
 
         let Ok(iam_store) = rustfs_iam::get() else { return Err(s3_error!(InternalError, "iam not init")) };
 
+        let page_query: PageQuery = match req.uri.query() {
+            Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+            None => PageQuery::default(),
+        };
+
         let groups = iam_store.list_groups_load().await.map_err(|e| {
             warn!("list groups failed, e: {:?}", e);
             S3Error::with_message(S3ErrorCode::InternalError, e.to_string())
         })?;
 
-        let body = serde_json::to_vec(&groups).map_err(|e| s3_error!(InternalError, "marshal body failed, e: {:?}", e))?;
+        // Unpaged requests keep returning the bare list for existing clients.
+        let body = if page_query.is_paged() {
+            let (groups, next_marker) = page_query.apply(groups);
+            serde_json::to_vec(&GroupsPage { groups, next_marker })
+        } else {
+            serde_json::to_vec(&groups)
+        }
+        .map_err(|e| s3_error!(InternalError, "marshal body failed, e: {:?}", e))?;
 
         let mut header = HeaderMap::new();
         header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
@@ -290,3 +324,101 @@ impl Operation for UpdateGroupMembers {
         Ok(S3Response::with_headers((StatusCode::OK, Body::empty()), header))
     }
 }
+
+pub struct CreateGroup {}
+#[async_trait::async_trait]
+impl Operation for CreateGroup {
+    // PUT <endpoint>/<admin-API>/v3/group?group=xxx
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle CreateGroup");
+
+        let Some(input_cred) = &req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        validate_admin_request(
+            &req.headers,
+            &cred,
+            owner,
+            false,
+            vec![Action::AdminAction(AdminAction::AddUserToGroupAdminAction)],
+        )
+        .await?;
+
+        let query = parse_group_query(&req)?;
+        if query.group.is_empty() || has_space_be(&query.group) {
+            return Err(s3_error!(InvalidArgument, "invalid group name"));
+        }
+
+        let Ok(iam_store) = rustfs_iam::get() else { return Err(s3_error!(InternalError, "iam not init")) };
+
+        match iam_store.get_group_description(&query.group).await {
+            Ok(_) => return Err(s3_error!(InvalidRequest, "group {} already exists", query.group)),
+            Err(e) if !is_err_no_such_group(&e) => {
+                return Err(S3Error::with_message(S3ErrorCode::InternalError, e.to_string()));
+            }
+            Err(_) => {}
+        }
+
+        iam_store.add_users_to_group(&query.group, Vec::new()).await.map_err(|e| {
+            warn!("create group failed, e: {:?}", e);
+            S3Error::with_message(S3ErrorCode::InternalError, e.to_string())
+        })?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        header.insert(CONTENT_LENGTH, "0".parse().unwrap());
+        Ok(S3Response::with_headers((StatusCode::OK, Body::empty()), header))
+    }
+}
+
+pub struct DeleteGroup {}
+#[async_trait::async_trait]
+impl Operation for DeleteGroup {
+    // DELETE <endpoint>/<admin-API>/v3/group?group=xxx[&force=true]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle DeleteGroup");
+
+        let Some(input_cred) = &req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        validate_admin_request(
+            &req.headers,
+            &cred,
+            owner,
+            false,
+            vec![Action::AdminAction(AdminAction::RemoveUserFromGroupAdminAction)],
+        )
+        .await?;
+
+        let query = parse_group_query(&req)?;
+        if query.group.is_empty() {
+            return Err(s3_error!(InvalidArgument, "group is required"));
+        }
+
+        let Ok(iam_store) = rustfs_iam::get() else { return Err(s3_error!(InternalError, "iam not init")) };
+
+        iam_store.delete_group(&query.group, query.force).await.map_err(|e| {
+            warn!("delete group failed, e: {:?}", e);
+            if is_err_no_such_group(&e) {
+                S3Error::with_message(S3ErrorCode::NoSuchKey, e.to_string())
+            } else if matches!(e, IamError::GroupNotEmpty) {
+                S3Error::with_message(S3ErrorCode::InvalidRequest, "group is not empty, use force=true to delete it".to_string())
+            } else {
+                S3Error::with_message(S3ErrorCode::InternalError, e.to_string())
+            }
+        })?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        header.insert(CONTENT_LENGTH, "0".parse().unwrap());
+        Ok(S3Response::with_headers((StatusCode::OK, Body::empty()), header))
+    }
+}
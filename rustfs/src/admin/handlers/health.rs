@@ -0,0 +1,133 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unauthenticated probes for orchestrators and load balancers.
+//!
+//! - `/health/live`: the process is up and serving requests.
+//! - `/health/ready`: the object layer is initialized, the erasure sets this node serves have
+//!   read and write quorum, the IAM store is available, and the node is not in maintenance mode.
+//!   A node in maintenance answers 503 with `draining: true` so load balancers stop sending it
+//!   new requests while the ones in flight complete.
+//! - `/health/cluster`: every erasure set has write quorum; with `?maintenance=true` the drives
+//!   of this node are counted as offline, so a node is only drained when the cluster survives it.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::health::{HealthOptions, HealthResult};
+use rustfs_ecstore::new_object_layer_fn;
+use s3s::{Body, S3Request, S3Response, S3Result, header::CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+
+use crate::admin::router::Operation;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ClusterHealthQuery {
+    pub maintenance: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ReadinessReport {
+    ready: bool,
+    object_layer: bool,
+    iam: bool,
+    #[serde(flatten)]
+    health: HealthResult,
+}
+
+fn json_response<T: Serialize>(status: StatusCode, value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value).unwrap_or_else(|_| b"{}".to_vec());
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((status, Body::from(data)), header))
+}
+
+pub struct LivenessHandler {}
+
+#[async_trait::async_trait]
+impl Operation for LivenessHandler {
+    // GET /health/live
+    async fn call(&self, _req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        Ok(S3Response::new((StatusCode::OK, Body::empty())))
+    }
+}
+
+pub struct ReadinessHandler {}
+
+#[async_trait::async_trait]
+impl Operation for ReadinessHandler {
+    // GET /health/ready
+    async fn call(&self, _req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let mut report = ReadinessReport {
+            iam: rustfs_iam::get().is_ok(),
+            ..Default::default()
+        };
+
+        if let Some(store) = new_object_layer_fn() {
+            report.object_layer = true;
+            report.health = store
+                .health(HealthOptions {
+                    local_only: true,
+                    ..Default::default()
+                })
+                .await;
+        }
+
+        report.ready =
+            report.object_layer && report.iam && report.health.healthy && report.health.healthy_read && !report.health.draining;
+
+        let status = if report.ready {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+        json_response(status, &report)
+    }
+}
+
+pub struct ClusterHealthHandler {}
+
+#[async_trait::async_trait]
+impl Operation for ClusterHealthHandler {
+    // GET /health/cluster?maintenance=true
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query: ClusterHealthQuery = req
+            .uri
+            .query()
+            .and_then(|query| from_bytes(query.as_bytes()).ok())
+            .unwrap_or_default();
+
+        let Some(store) = new_object_layer_fn() else {
+            return Ok(S3Response::new((StatusCode::SERVICE_UNAVAILABLE, Body::empty())));
+        };
+
+        let health = store
+            .health(HealthOptions {
+                maintenance: query.maintenance,
+                ..Default::default()
+            })
+            .await;
+
+        let status = match (health.healthy, query.maintenance) {
+            (true, _) => StatusCode::OK,
+            // Taking this node down would cost quorum
+            (false, true) => StatusCode::PRECONDITION_FAILED,
+            (false, false) => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        json_response(status, &health)
+    }
+}
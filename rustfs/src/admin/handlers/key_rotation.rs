@@ -0,0 +1,197 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_kms::get_global_kms_service_manager;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+    storage::key_rotation::{GLOBAL_KEY_ROTATION_SYS, KeyRotationRequest},
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct KeyRotationQuery {
+    pub bucket: String,
+    pub prefix: String,
+    pub key_id: String,
+    pub source_version: Option<u32>,
+    pub target_key_id: String,
+    /// Job to report on or cancel
+    pub id: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RotateKeyResponse {
+    key_id: String,
+    version: u32,
+}
+
+async fn check_rotation_request(req: &S3Request<Body>, actions: &[AdminAction]) -> S3Result<KeyRotationQuery> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    let actions = actions.iter().map(|action| Action::AdminAction(*action)).collect();
+    validate_admin_request(&req.headers, &cred, owner, false, actions).await?;
+
+    match req.uri.query() {
+        Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed")),
+        None => Ok(KeyRotationQuery::default()),
+    }
+}
+
+fn json_response<T: Serialize>(status: StatusCode, value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal key rotation job failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((status, Body::from(data)), header))
+}
+
+pub struct RotateKmsKey {}
+
+#[async_trait::async_trait]
+impl Operation for RotateKmsKey {
+    // POST <endpoint>/<admin-API>/kms/keys/rotate?keyId=mykey
+    //
+    // Data keys wrapped by earlier versions keep working until a key rotation job re-wraps them.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = check_rotation_request(&req, &[AdminAction::KMSCreateKeyAdminAction]).await?;
+        if query.key_id.is_empty() {
+            return Err(s3_error!(InvalidArgument, "keyId is required"));
+        }
+
+        let Some(service_manager) = get_global_kms_service_manager() else {
+            return Err(s3_error!(InternalError, "KMS service manager not initialized"));
+        };
+        let Some(manager) = service_manager.get_manager().await else {
+            return Err(s3_error!(InternalError, "KMS service not running"));
+        };
+
+        let version = manager.rotate_key(&query.key_id).await.map_err(|e| {
+            warn!("rotate KMS key {} failed: {:?}", query.key_id, e);
+            S3Error::with_message(S3ErrorCode::InternalError, format!("rotate key failed: {e}"))
+        })?;
+
+        json_response(
+            StatusCode::OK,
+            &RotateKeyResponse {
+                key_id: query.key_id,
+                version,
+            },
+        )
+    }
+}
+
+pub struct StartKeyRotation {}
+
+#[async_trait::async_trait]
+impl Operation for StartKeyRotation {
+    // POST <endpoint>/<admin-API>/kms/key-rotation?keyId=mykey[&sourceVersion=1][&targetKeyId=otherkey][&bucket=mybucket][&prefix=logs/]
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = check_rotation_request(&req, &[AdminAction::StartBatchJobAction]).await?;
+        if query.key_id.is_empty() {
+            return Err(s3_error!(InvalidArgument, "keyId is required"));
+        }
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let job = GLOBAL_KEY_ROTATION_SYS
+            .start(
+                store,
+                KeyRotationRequest {
+                    bucket: query.bucket,
+                    prefix: query.prefix,
+                    key_id: query.key_id,
+                    source_version: query.source_version,
+                    target_key_id: query.target_key_id,
+                },
+            )
+            .await
+            .map_err(ApiError::from)?;
+
+        json_response(StatusCode::ACCEPTED, &job)
+    }
+}
+
+pub struct KeyRotationStatus {}
+
+#[async_trait::async_trait]
+impl Operation for KeyRotationStatus {
+    // GET <endpoint>/<admin-API>/kms/key-rotation[?id=jobid]
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query =
+            check_rotation_request(&req, &[AdminAction::ListBatchJobsAction, AdminAction::DescribeBatchJobAction]).await?;
+
+        if query.id.is_empty() {
+            return json_response(StatusCode::OK, &GLOBAL_KEY_ROTATION_SYS.list());
+        }
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        match GLOBAL_KEY_ROTATION_SYS
+            .status(store, &query.id)
+            .await
+            .map_err(ApiError::from)?
+        {
+            Some(job) => json_response(StatusCode::OK, &job),
+            None => Err(s3_error!(NoSuchKey, "no key rotation job {}", query.id)),
+        }
+    }
+}
+
+pub struct CancelKeyRotation {}
+
+#[async_trait::async_trait]
+impl Operation for CancelKeyRotation {
+    // DELETE <endpoint>/<admin-API>/kms/key-rotation?id=jobid
+    //
+    // Only jobs running on the node receiving the request can be cancelled.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = check_rotation_request(&req, &[AdminAction::CancelBatchJobAction]).await?;
+        if query.id.is_empty() {
+            return Err(s3_error!(InvalidArgument, "id is required"));
+        }
+
+        if !GLOBAL_KEY_ROTATION_SYS.cancel(&query.id) {
+            return Err(s3_error!(NoSuchKey, "no key rotation job {} running on this node", query.id));
+        }
+
+        Ok(S3Response::new((StatusCode::ACCEPTED, Body::empty())))
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::bucket::kms_scope::BucketKmsScope;
+use rustfs_ecstore::bucket::metadata::BUCKET_KMS_SCOPE_CONFIG;
+use rustfs_ecstore::bucket::metadata_sys;
+use rustfs_ecstore::error::StorageError;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store_api::{BucketOptions, StorageAPI};
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BucketKmsScopeQuery {
+    pub bucket: String,
+}
+
+/// Authorize an admin KMS scope request and return the bucket it targets.
+async fn check_kms_scope_request(req: &S3Request<Body>) -> S3Result<String> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(
+        &req.headers,
+        &cred,
+        owner,
+        false,
+        vec![Action::AdminAction(AdminAction::ConfigUpdateAdminAction)],
+    )
+    .await?;
+
+    let query = {
+        if let Some(query) = req.uri.query() {
+            let input: BucketKmsScopeQuery =
+                from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+            input
+        } else {
+            BucketKmsScopeQuery::default()
+        }
+    };
+
+    if query.bucket.is_empty() {
+        return Err(s3_error!(InvalidArgument, "bucket is required"));
+    }
+
+    let Some(store) = new_object_layer_fn() else {
+        return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+    };
+
+    store
+        .get_bucket_info(&query.bucket, &BucketOptions::default())
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(query.bucket)
+}
+
+pub struct GetBucketKmsScope {}
+
+#[async_trait::async_trait]
+impl Operation for GetBucketKmsScope {
+    // GET <endpoint>/<admin-API>/bucket-kms-scope?bucket=mybucket
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle GetBucketKmsScope");
+
+        let bucket = check_kms_scope_request(&req).await?;
+
+        let cfg = match metadata_sys::get_kms_scope_config(&bucket).await {
+            Ok((cfg, _)) => cfg,
+            // An empty key id means the bucket is not bound to a key
+            Err(StorageError::ConfigNotFound) => BucketKmsScope::default(),
+            Err(e) => return Err(ApiError::from(e).into()),
+        };
+
+        let data = cfg.marshal().map_err(ApiError::from)?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
+
+pub struct SetBucketKmsScope {}
+
+#[async_trait::async_trait]
+impl Operation for SetBucketKmsScope {
+    // PUT <endpoint>/<admin-API>/bucket-kms-scope?bucket=mybucket
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle SetBucketKmsScope");
+
+        let bucket = check_kms_scope_request(&req).await?;
+
+        let mut input = req.input;
+        let body = match input.store_all_unlimited().await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+
+        let cfg = BucketKmsScope::unmarshal(&body)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, format!("unmarshal body err {e}")))?;
+        cfg.validate()
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, e.to_string()))?;
+
+        let data = cfg.marshal().map_err(ApiError::from)?;
+        metadata_sys::update(&bucket, BUCKET_KMS_SCOPE_CONFIG, data)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::default())))
+    }
+}
+
+pub struct RemoveBucketKmsScope {}
+
+#[async_trait::async_trait]
+impl Operation for RemoveBucketKmsScope {
+    // DELETE <endpoint>/<admin-API>/bucket-kms-scope?bucket=mybucket
+    // Unbinds the bucket, its writes may use any KMS key again.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle RemoveBucketKmsScope");
+
+        let bucket = check_kms_scope_request(&req).await?;
+
+        metadata_sys::delete(&bucket, BUCKET_KMS_SCOPE_CONFIG)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::default())))
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::maintenance::{GLOBAL_MAINTENANCE_SYS, MaintenanceState};
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct MaintenanceQuery {
+    /// `host:port` of the node to change, defaults to the node serving the request.
+    pub node: String,
+    pub enable: bool,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+struct MaintenanceStatus {
+    /// This node is in maintenance mode.
+    draining: bool,
+    #[serde(flatten)]
+    state: MaintenanceState,
+}
+
+async fn check_maintenance_request(req: &S3Request<Body>, action: AdminAction) -> S3Result<()> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(action)]).await
+}
+
+fn status_response(state: MaintenanceState) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let status = MaintenanceStatus {
+        draining: GLOBAL_MAINTENANCE_SYS.is_local_node_in_maintenance(),
+        state,
+    };
+    let data = serde_json::to_vec(&status)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal maintenance status failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct GetMaintenance {}
+
+#[async_trait::async_trait]
+impl Operation for GetMaintenance {
+    // GET <endpoint>/<admin-API>/maintenance
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        check_maintenance_request(&req, AdminAction::ServerInfoAdminAction).await?;
+
+        status_response(GLOBAL_MAINTENANCE_SYS.state())
+    }
+}
+
+pub struct SetMaintenance {}
+
+#[async_trait::async_trait]
+impl Operation for SetMaintenance {
+    // POST <endpoint>/<admin-API>/maintenance?enable=true&node=host:port&reason=...
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle SetMaintenance");
+
+        check_maintenance_request(&req, AdminAction::ServiceRestartAdminAction).await?;
+
+        let query = {
+            if let Some(query) = req.uri.query() {
+                let input: MaintenanceQuery =
+                    from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+                input
+            } else {
+                MaintenanceQuery::default()
+            }
+        };
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let nodes: Vec<String> = if query.node.is_empty() {
+            GLOBAL_MAINTENANCE_SYS.local_nodes().into_iter().collect()
+        } else {
+            vec![query.node]
+        };
+
+        if nodes.is_empty() {
+            return Err(s3_error!(InvalidArgument, "node is required"));
+        }
+
+        let mut state = MaintenanceState::default();
+        for node in nodes {
+            state = GLOBAL_MAINTENANCE_SYS
+                .set(store.clone(), &node, query.enable, &query.reason)
+                .await
+                .map_err(ApiError::from)?;
+        }
+
+        status_response(state)
+    }
+}
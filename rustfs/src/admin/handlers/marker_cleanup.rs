@@ -0,0 +1,143 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::bucket::marker_cleanup::{GLOBAL_MARKER_CLEANUP_SYS, MarkerCleanupRequest};
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_madmin::utils::parse_duration;
+use rustfs_policy::policy::action::{Action, AdminAction, S3Action};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MarkerCleanupQuery {
+    pub bucket: String,
+    pub prefix: String,
+    /// Minimum age of the markers to remove, such as `720h`
+    pub older_than: String,
+    pub dry_run: bool,
+    /// Job to report on
+    pub id: String,
+}
+
+async fn check_cleanup_request(req: &S3Request<Body>, action: Action) -> S3Result<MarkerCleanupQuery> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![action]).await?;
+
+    let query = {
+        if let Some(query) = req.uri.query() {
+            let input: MarkerCleanupQuery =
+                from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+            input
+        } else {
+            MarkerCleanupQuery::default()
+        }
+    };
+
+    Ok(query)
+}
+
+fn json_response<T: Serialize>(status: StatusCode, value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal cleanup job failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((status, Body::from(data)), header))
+}
+
+pub struct StartMarkerCleanup {}
+
+#[async_trait::async_trait]
+impl Operation for StartMarkerCleanup {
+    // POST <endpoint>/<admin-API>/delete-marker-cleanup?bucket=mybucket[&prefix=logs/][&olderThan=720h][&dryRun=true]
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = check_cleanup_request(&req, Action::S3Action(S3Action::DeleteObjectVersionAction)).await?;
+        if query.bucket.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket is required"));
+        }
+
+        let older_than_secs = if query.older_than.is_empty() {
+            None
+        } else {
+            let age = parse_duration(&query.older_than)
+                .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, format!("invalid olderThan: {e}")))?;
+            Some(age.as_secs())
+        };
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let job = GLOBAL_MARKER_CLEANUP_SYS
+            .start(
+                store,
+                MarkerCleanupRequest {
+                    bucket: query.bucket,
+                    prefix: query.prefix,
+                    older_than_secs,
+                    dry_run: query.dry_run,
+                },
+            )
+            .await
+            .map_err(ApiError::from)?;
+
+        json_response(StatusCode::ACCEPTED, &job)
+    }
+}
+
+pub struct MarkerCleanupStatus {}
+
+#[async_trait::async_trait]
+impl Operation for MarkerCleanupStatus {
+    // GET <endpoint>/<admin-API>/delete-marker-cleanup[?id=jobid]
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = check_cleanup_request(&req, Action::AdminAction(AdminAction::ServerInfoAdminAction)).await?;
+
+        if query.id.is_empty() {
+            return json_response(StatusCode::OK, &GLOBAL_MARKER_CLEANUP_SYS.list());
+        }
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        match GLOBAL_MARKER_CLEANUP_SYS
+            .status(store, &query.id)
+            .await
+            .map_err(ApiError::from)?
+        {
+            Some(job) => json_response(StatusCode::OK, &job),
+            None => Err(s3_error!(NoSuchKey, "no delete marker cleanup job {}", query.id)),
+        }
+    }
+}
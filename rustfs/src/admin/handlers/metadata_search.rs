@@ -0,0 +1,96 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Search of the objects of a bucket by user metadata and tags, answered from the metadata index
+//! of the node receiving the request.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::metadata_index::{MetadataQuery, get_metadata_index};
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store_api::{BucketOptions, StorageAPI};
+use rustfs_policy::policy::action::{Action, S3Action};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+const DEFAULT_MAX_KEYS: usize = 1000;
+const MAX_KEYS_LIMIT: usize = 10_000;
+
+pub struct SearchObjectMetadata {}
+
+#[async_trait::async_trait]
+impl Operation for SearchObjectMetadata {
+    // POST <endpoint>/<admin-API>/metadata-search
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let Some(input_cred) = &req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        validate_admin_request(&req.headers, &cred, owner, false, vec![Action::S3Action(S3Action::ListBucketAction)]).await?;
+
+        let mut input = req.input;
+        let body = match input.store_all_unlimited().await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+
+        let mut query: MetadataQuery = serde_json::from_slice(&body)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, format!("unmarshal body err {e}")))?;
+        if query.bucket.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket is required"));
+        }
+        query.max_keys = match query.max_keys {
+            0 => DEFAULT_MAX_KEYS,
+            n => n.min(MAX_KEYS_LIMIT),
+        };
+
+        let Some(index) = get_metadata_index() else {
+            return Err(s3_error!(NotImplemented, "the metadata index is not enabled on this node"));
+        };
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+        store
+            .get_bucket_info(&query.bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        let result = tokio::task::spawn_blocking(move || index.query(&query))
+            .await
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("metadata search failed: {e}")))?
+            .map_err(ApiError::from)?;
+
+        let data = serde_json::to_vec(&result)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal search result err {e}")))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::bucket::versioning_sys::BucketVersioningSys;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store_api::{ObjectOptions, StorageAPI};
+use rustfs_filemeta::{is_replaceable_metadata, metadata_revision};
+use rustfs_policy::policy::Args;
+use rustfs_policy::policy::action::{Action, S3Action};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::warn;
+
+use crate::{
+    admin::router::Operation,
+    auth::{check_key_valid, get_condition_values, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PutObjectMetadataRequest {
+    pub bucket: String,
+    pub object: String,
+    /// Version to update, the latest when empty.
+    pub version_id: String,
+    /// New user metadata, `content-type` and the other content headers and `x-amz-meta-*` keys.
+    /// It replaces all user metadata of the version.
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PutObjectMetadataResponse {
+    pub bucket: String,
+    pub object: String,
+    pub version_id: Option<String>,
+    pub etag: Option<String>,
+    /// Number of in-place metadata updates of the version, this one included.
+    pub metadata_revision: u64,
+}
+
+/// Content headers the metadata of an object may carry besides its `x-amz-meta-*` keys.
+const CONTENT_HEADERS: &[&str] = &[
+    "content-type",
+    "cache-control",
+    "content-language",
+    "content-encoding",
+    "content-disposition",
+    "expires",
+];
+
+/// User metadata as it is stored: lowercase keys, user defined keys without their prefix.
+fn normalize_metadata(metadata: HashMap<String, String>) -> S3Result<HashMap<String, String>> {
+    let mut normalized = HashMap::with_capacity(metadata.len());
+    for (key, value) in metadata {
+        let key = key.to_lowercase();
+        let stored = match key.strip_prefix("x-amz-meta-") {
+            Some(user_key) => user_key.to_string(),
+            None if CONTENT_HEADERS.contains(&key.as_str()) => key.clone(),
+            None => return Err(s3_error!(InvalidArgument, "metadata key {} cannot be set", key)),
+        };
+        // A user key must not shadow what the server keeps with the object
+        if stored.is_empty() || !is_replaceable_metadata(&stored) {
+            return Err(s3_error!(InvalidArgument, "metadata key {} cannot be set", key));
+        }
+        normalized.insert(stored, value);
+    }
+    Ok(normalized)
+}
+
+pub struct PutObjectMetadata {}
+
+#[async_trait::async_trait]
+impl Operation for PutObjectMetadata {
+    // PUT <endpoint>/<admin-API>/object-metadata
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let Some(input_cred) = &req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        let mut input = req.input;
+        let body = match input.store_all_unlimited().await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+
+        let request: PutObjectMetadataRequest = serde_json::from_slice(&body)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, format!("unmarshal body err {e}")))?;
+        if request.bucket.is_empty() || request.object.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket and object are required"));
+        }
+        let metadata = normalize_metadata(request.metadata)?;
+
+        let Ok(iam_store) = rustfs_iam::get() else {
+            return Err(s3_error!(InternalError, "iam not init"));
+        };
+        let conditions = get_condition_values(&req.headers, &cred, None, None);
+        let claims = cred.claims.clone().unwrap_or_default();
+        let allowed = iam_store
+            .is_allowed(&Args {
+                account: &cred.access_key,
+                groups: &cred.groups,
+                action: Action::S3Action(S3Action::PutObjectAction),
+                conditions: &conditions,
+                is_owner: owner,
+                claims: &claims,
+                deny_only: false,
+                bucket: &request.bucket,
+                object: &request.object,
+            })
+            .await;
+        if !allowed {
+            return Err(s3_error!(AccessDenied, "Access Denied"));
+        }
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let opts = ObjectOptions {
+            version_id: (!request.version_id.is_empty()).then_some(request.version_id),
+            versioned: BucketVersioningSys::prefix_enabled(&request.bucket, &request.object).await,
+            version_suspended: BucketVersioningSys::prefix_suspended(&request.bucket, &request.object).await,
+            eval_metadata: Some(metadata),
+            replace_metadata: true,
+            ..Default::default()
+        };
+        let info = store
+            .put_object_metadata(&request.bucket, &request.object, &opts)
+            .await
+            .map_err(ApiError::from)?;
+
+        let response = PutObjectMetadataResponse {
+            metadata_revision: metadata_revision(&info.user_defined),
+            bucket: info.bucket,
+            object: info.name,
+            version_id: info.version_id.map(|v| v.to_string()),
+            etag: info.etag,
+        };
+        let data = serde_json::to_vec(&response)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal object metadata err {e}")))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_metadata() {
+        let metadata = HashMap::from([
+            ("Content-Type".to_string(), "application/json".to_string()),
+            ("X-Amz-Meta-Project".to_string(), "alpha".to_string()),
+        ]);
+        let normalized = normalize_metadata(metadata).unwrap();
+        assert_eq!(normalized.get("content-type").map(String::as_str), Some("application/json"));
+        assert_eq!(normalized.get("project").map(String::as_str), Some("alpha"));
+
+        for key in [
+            "x-amz-storage-class",
+            "x-rustfs-internal-inline-data",
+            "etag",
+            "project",
+            "x-amz-meta-",
+            "x-amz-meta-etag",
+        ] {
+            let metadata = HashMap::from([(key.to_string(), "v".to_string())]);
+            assert!(normalize_metadata(metadata).is_err(), "{key}");
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_common::heal_channel::{HealOpts, HealScanMode};
+use rustfs_ecstore::error::{is_err_object_not_found, is_err_version_not_found};
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store_api::StorageAPI;
+use rustfs_madmin::heal_commands::HealResultItem;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RepairObjectQuery {
+    pub bucket: String,
+    pub object: String,
+    /// Version to repair, the latest when empty.
+    pub version_id: String,
+    /// Only verify the shards and report what a repair would do.
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct RepairObjectResponse {
+    #[serde(flatten)]
+    result: HealResultItem,
+    /// Why the repair stopped short, the drive states tell how far it went.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+async fn check_repair_request(req: &S3Request<Body>) -> S3Result<RepairObjectQuery> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(AdminAction::HealAdminAction)]).await?;
+
+    match req.uri.query() {
+        Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed")),
+        None => Ok(RepairObjectQuery::default()),
+    }
+}
+
+pub struct RepairObject {}
+
+#[async_trait::async_trait]
+impl Operation for RepairObject {
+    // POST <endpoint>/<admin-API>/repair-object?bucket=mybucket&object=myobject[&versionId=vid][&dryRun=true]
+    //
+    // Verifies every shard of the version and rewrites all of them, healthy ones included, from
+    // the shards that pass verification. The report lists the state of each drive before and after.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = check_repair_request(&req).await?;
+        if query.bucket.is_empty() || query.object.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket and object are required"));
+        }
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let opts = HealOpts {
+            dry_run: query.dry_run,
+            scan_mode: HealScanMode::Deep,
+            rewrite: true,
+            ..Default::default()
+        };
+        let (result, err) = store
+            .heal_object(&query.bucket, &query.object, &query.version_id, &opts)
+            .await
+            .map_err(ApiError::from)?;
+
+        if let Some(err) = &err {
+            if is_err_version_not_found(err) && !query.version_id.is_empty() {
+                return Err(s3_error!(
+                    NoSuchVersion,
+                    "no version {} of {}/{}",
+                    query.version_id,
+                    query.bucket,
+                    query.object
+                ));
+            }
+            if is_err_object_not_found(err) || is_err_version_not_found(err) {
+                return Err(s3_error!(NoSuchKey, "no object {}/{}", query.bucket, query.object));
+            }
+            warn!("repair of {}/{} ({}) failed: {}", query.bucket, query.object, query.version_id, err);
+        }
+
+        let data = serde_json::to_vec(&RepairObjectResponse {
+            result,
+            error: err.map(|e| e.to_string()),
+        })
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal repair result failed: {e}")))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
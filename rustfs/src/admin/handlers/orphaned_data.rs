@@ -0,0 +1,107 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::orphaned_data::{OrphanedDataReport, last_report, scan_orphaned_data};
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct OrphanedDataQuery {
+    /// Walk the drives now instead of reporting the last deep scan.
+    pub refresh: bool,
+}
+
+async fn check_orphaned_data_request(req: &S3Request<Body>) -> S3Result<OrphanedDataQuery> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(AdminAction::HealAdminAction)]).await?;
+
+    match req.uri.query() {
+        Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed")),
+        None => Ok(OrphanedDataQuery::default()),
+    }
+}
+
+fn report_response(report: &OrphanedDataReport) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(report)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal orphaned data report failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct GetOrphanedData {}
+
+#[async_trait::async_trait]
+impl Operation for GetOrphanedData {
+    // GET <endpoint>/<admin-API>/orphaned-data[?refresh=true]
+    //
+    // Reports the local drives of the node receiving the request.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = check_orphaned_data_request(&req).await?;
+
+        let report = match last_report() {
+            Some(report) if !query.refresh => report,
+            _ => {
+                let Some(store) = new_object_layer_fn() else {
+                    return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+                };
+                scan_orphaned_data(store, false).await.map_err(ApiError::from)?
+            }
+        };
+
+        report_response(&report)
+    }
+}
+
+pub struct ReclaimOrphanedData {}
+
+#[async_trait::async_trait]
+impl Operation for ReclaimOrphanedData {
+    // POST <endpoint>/<admin-API>/orphaned-data/reclaim
+    //
+    // Walks the local drives of the node receiving the request and removes the orphaned data
+    // older than the configured age, returning what was found.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        check_orphaned_data_request(&req).await?;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let report = scan_orphaned_data(store, true).await.map_err(ApiError::from)?;
+        report_response(&report)
+    }
+}
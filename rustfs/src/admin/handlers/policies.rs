@@ -14,23 +14,27 @@
 
 use crate::{
     admin::{auth::validate_admin_request, router::Operation, utils::has_space_be},
-    auth::{check_key_valid, get_session_token},
+    auth::{check_key_valid, get_condition_values, get_session_token},
 };
 use http::{HeaderMap, StatusCode};
 use matchit::Params;
+use rustfs_ecstore::bucket::policy_sys::PolicySys;
 use rustfs_ecstore::global::get_global_action_cred;
 use rustfs_iam::error::is_err_no_such_user;
 use rustfs_iam::store::MappedPolicy;
+use rustfs_iam::sys::PolicySimulation;
+use rustfs_policy::auth::Credentials;
 use rustfs_policy::policy::{
-    Policy,
+    Args, BucketPolicyArgs, Evaluation, Policy,
     action::{Action, AdminAction},
+    statement::BPStatement,
 };
 use s3s::{
     Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result,
     header::{CONTENT_LENGTH, CONTENT_TYPE},
     s3_error,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_urlencoded::from_bytes;
 use std::collections::HashMap;
 use tracing::warn;
@@ -374,3 +378,187 @@ impl Operation for SetPolicyForUserOrGroup {
         Ok(S3Response::with_headers((StatusCode::OK, Body::empty()), header))
     }
 }
+
+const S3_RESOURCE_ARN_PREFIX: &str = "arn:aws:s3:::";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SimulatePolicyReq {
+    /// Access key to evaluate the request for, empty for an anonymous request.
+    pub access_key: String,
+    pub action: String,
+    pub bucket: String,
+    pub object: String,
+    /// `arn:aws:s3:::bucket/object`, an alternative to `bucket` and `object`.
+    pub resource: String,
+    /// Condition keys added to, or overriding, the ones the server derives for the account.
+    pub conditions: HashMap<String, Vec<String>>,
+}
+
+impl SimulatePolicyReq {
+    fn bucket_object(&self) -> (String, String) {
+        match self.resource.strip_prefix(S3_RESOURCE_ARN_PREFIX) {
+            Some(resource) => match resource.split_once('/') {
+                Some((bucket, object)) => (bucket.to_string(), object.to_string()),
+                None => (resource.to_string(), String::new()),
+            },
+            None => (self.bucket.clone(), self.object.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulatePolicyResp {
+    allowed: bool,
+    /// Policy set the server enforces for this account: `iam` for signed requests, `bucket-policy` for anonymous ones.
+    decided_by: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iam: Option<PolicySimulation>,
+    /// Evaluation of the bucket policy, reported for reference when the request is signed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bucket_policy: Option<Evaluation<BPStatement>>,
+}
+
+pub struct SimulatePolicy {}
+#[async_trait::async_trait]
+impl Operation for SimulatePolicy {
+    // POST <endpoint>/<admin-API>/v3/simulate-policy
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let Some(input_cred) = req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        validate_admin_request(
+            &req.headers,
+            &cred,
+            owner,
+            false,
+            vec![Action::AdminAction(AdminAction::GetPolicyAdminAction)],
+        )
+        .await?;
+
+        let mut input = req.input;
+        let body = match input.store_all_unlimited().await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+
+        let sim_req: SimulatePolicyReq =
+            serde_json::from_slice(&body).map_err(|e| s3_error!(InvalidArgument, "invalid simulate request: {e}"))?;
+
+        let action = Action::try_from(sim_req.action.as_str())
+            .map_err(|_e| s3_error!(InvalidArgument, "invalid action: {}", sim_req.action))?;
+        let (bucket, object) = sim_req.bucket_object();
+
+        let bucket_policy = if bucket.is_empty() {
+            None
+        } else {
+            Some(PolicySys::get(&bucket).await.unwrap_or_default())
+        };
+
+        let resp = if sim_req.access_key.is_empty() {
+            let mut conditions = get_condition_values(&HeaderMap::new(), &Credentials::default(), None, None);
+            conditions.extend(sim_req.conditions);
+
+            let evaluation = bucket_policy.map(|policy| {
+                policy.evaluate(&BucketPolicyArgs {
+                    account: "",
+                    groups: &None,
+                    action,
+                    bucket: &bucket,
+                    conditions: &conditions,
+                    is_owner: false,
+                    object: &object,
+                })
+            });
+
+            SimulatePolicyResp {
+                allowed: evaluation.as_ref().is_some_and(|e| e.allowed),
+                decided_by: "bucket-policy",
+                iam: None,
+                bucket_policy: evaluation,
+            }
+        } else {
+            let Ok(iam_store) = rustfs_iam::get() else { return Err(s3_error!(InternalError, "iam not init")) };
+
+            // Resolve the account the way a signed request from it would be, including its session claims.
+            let session_token = match iam_store.get_user(&sim_req.access_key).await {
+                Some(u) if u.credentials.is_temp() && !u.credentials.is_service_account() => u.credentials.session_token,
+                _ => String::new(),
+            };
+            let (account, is_owner) = check_key_valid(&session_token, &sim_req.access_key).await?;
+
+            let mut conditions = get_condition_values(&HeaderMap::new(), &account, None, None);
+            conditions.extend(sim_req.conditions);
+
+            let default_claims = HashMap::new();
+            let claims = account.claims.as_ref().unwrap_or(&default_claims);
+
+            let simulation = iam_store
+                .simulate(&Args {
+                    account: &account.access_key,
+                    groups: &account.groups,
+                    action,
+                    bucket: &bucket,
+                    conditions: &conditions,
+                    is_owner,
+                    object: &object,
+                    claims,
+                    deny_only: false,
+                })
+                .await;
+
+            let evaluation = bucket_policy.map(|policy| {
+                policy.evaluate(&BucketPolicyArgs {
+                    account: &account.access_key,
+                    groups: &account.groups,
+                    action,
+                    bucket: &bucket,
+                    conditions: &conditions,
+                    is_owner,
+                    object: &object,
+                })
+            });
+
+            SimulatePolicyResp {
+                allowed: simulation.allowed,
+                decided_by: "iam",
+                iam: Some(simulation),
+                bucket_policy: evaluation,
+            }
+        };
+
+        let data = serde_json::to_vec(&resp).map_err(|e| s3_error!(InternalError, "marshal simulation failed: {e}"))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulate_req_resource() {
+        let req: SimulatePolicyReq =
+            serde_json::from_str(r#"{"accessKey":"alice","action":"s3:GetObject","resource":"arn:aws:s3:::photos/2024/a.jpg"}"#)
+                .unwrap();
+        assert_eq!(req.bucket_object(), ("photos".to_string(), "2024/a.jpg".to_string()));
+
+        let req: SimulatePolicyReq =
+            serde_json::from_str(r#"{"action":"s3:ListBucket","resource":"arn:aws:s3:::photos"}"#).unwrap();
+        assert_eq!(req.bucket_object(), ("photos".to_string(), String::new()));
+
+        let req: SimulatePolicyReq = serde_json::from_str(r#"{"action":"s3:GetObject","bucket":"b","object":"k"}"#).unwrap();
+        assert_eq!(req.bucket_object(), ("b".to_string(), "k".to_string()));
+    }
+}
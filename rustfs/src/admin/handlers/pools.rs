@@ -14,6 +14,11 @@
 
 use http::{HeaderMap, StatusCode};
 use matchit::Params;
+use rustfs_ecstore::bucket::metadata::BUCKET_PLACEMENT_CONFIG;
+use rustfs_ecstore::bucket::metadata_sys;
+use rustfs_ecstore::bucket::placement::BucketPlacement;
+use rustfs_ecstore::capacity_plan::{CapacityPlanRequest, existing_pools, simulate};
+use rustfs_ecstore::pools::PoolClass;
 use rustfs_ecstore::{GLOBAL_Endpoints, new_object_layer_fn};
 use rustfs_policy::policy::action::{Action, AdminAction};
 use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
@@ -172,6 +177,68 @@ impl Operation for StatusPool {
     }
 }
 
+pub struct PlanPools {}
+
+#[async_trait::async_trait]
+impl Operation for PlanPools {
+    // POST <endpoint>/<admin-API>/pools/plan
+    //
+    // The body proposes pools to add and pools to decommission:
+    // {"pools": [{"nodes", "drivesPerNode", "driveCapacity", "parity", "setDriveCount"}], "decommission": [0], "ignoreExisting"}
+    // Nothing is changed, the response is the capacity and failure tolerance the cluster would have.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let Some(input_cred) = req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        validate_admin_request(
+            &req.headers,
+            &cred,
+            owner,
+            false,
+            vec![
+                Action::AdminAction(AdminAction::ServerInfoAdminAction),
+                Action::AdminAction(AdminAction::DecommissionAdminAction),
+            ],
+        )
+        .await?;
+
+        let mut input = req.input;
+        let body = match input.store_all_unlimited().await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+        let request: CapacityPlanRequest = serde_json::from_slice(&body)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, format!("unmarshal body err {e}")))?;
+
+        let existing = if request.ignore_existing {
+            Vec::new()
+        } else {
+            let Some(store) = new_object_layer_fn() else {
+                return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+            };
+            existing_pools(&store).await.map_err(ApiError::from)?
+        };
+        let plan =
+            simulate(existing, &request).map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, e.to_string()))?;
+
+        let data = serde_json::to_vec(&plan)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal capacity plan failed: {e}")))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
+
 pub struct StartDecommission {}
 
 #[async_trait::async_trait]
@@ -338,3 +405,171 @@ impl Operation for CancelDecommission {
         Ok(S3Response::new((StatusCode::OK, Body::default())))
     }
 }
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct SetPoolClassQuery {
+    pub pool: String,
+    #[serde(rename = "by-id")]
+    pub by_id: String,
+    pub class: String,
+}
+
+pub struct SetPoolClass {}
+
+#[async_trait::async_trait]
+impl Operation for SetPoolClass {
+    // POST <endpoint>/<admin-API>/pools/class?pool=http://server{1...4}/disk{1...4}&class=cold
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle SetPoolClass");
+
+        let Some(input_cred) = req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        validate_admin_request(
+            &req.headers,
+            &cred,
+            owner,
+            false,
+            vec![Action::AdminAction(AdminAction::DecommissionAdminAction)],
+        )
+        .await?;
+
+        let Some(endpoints) = GLOBAL_Endpoints.get() else {
+            return Err(s3_error!(NotImplemented));
+        };
+
+        if endpoints.legacy() {
+            return Err(s3_error!(NotImplemented));
+        }
+
+        let query = {
+            if let Some(query) = req.uri.query() {
+                let input: SetPoolClassQuery =
+                    from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get body failed"))?;
+                input
+            } else {
+                SetPoolClassQuery::default()
+            }
+        };
+
+        let class: PoolClass = query
+            .class
+            .parse()
+            .map_err(|_e| s3_error!(InvalidArgument, "invalid pool class {}", &query.class))?;
+
+        let is_byid = query.by_id.as_str() == "true";
+
+        let has_idx = {
+            if is_byid {
+                let a = query.pool.parse::<usize>().unwrap_or_default();
+                if a < endpoints.as_ref().len() { Some(a) } else { None }
+            } else {
+                endpoints.get_pool_idx(&query.pool)
+            }
+        };
+
+        let Some(idx) = has_idx else {
+            warn!("specified pool {} not found, please specify a valid pool", &query.pool);
+            return Err(s3_error!(InvalidArgument));
+        };
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store.set_pool_class(idx, class).await.map_err(ApiError::from)?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::default())))
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct MigrateBucketQuery {
+    pub bucket: String,
+    pub class: String,
+}
+
+pub struct MigrateBucketPoolClass {}
+
+#[async_trait::async_trait]
+impl Operation for MigrateBucketPoolClass {
+    // POST <endpoint>/<admin-API>/pools/migrate-bucket?bucket=mybucket&class=cold
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle MigrateBucketPoolClass");
+
+        let Some(input_cred) = req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        validate_admin_request(
+            &req.headers,
+            &cred,
+            owner,
+            false,
+            vec![Action::AdminAction(AdminAction::DecommissionAdminAction)],
+        )
+        .await?;
+
+        let Some(endpoints) = GLOBAL_Endpoints.get() else {
+            return Err(s3_error!(NotImplemented));
+        };
+
+        if endpoints.legacy() {
+            return Err(s3_error!(NotImplemented));
+        }
+
+        let query = {
+            if let Some(query) = req.uri.query() {
+                let input: MigrateBucketQuery =
+                    from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get body failed"))?;
+                input
+            } else {
+                MigrateBucketQuery::default()
+            }
+        };
+
+        if query.bucket.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket is required"));
+        }
+
+        let class: PoolClass = query
+            .class
+            .parse()
+            .map_err(|_e| s3_error!(InvalidArgument, "invalid pool class {}", &query.class))?;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        if store.is_decommission_running().await {
+            return Err(S3Error::with_message(
+                S3ErrorCode::InvalidRequest,
+                "DecommissionAlreadyRunning".to_string(),
+            ));
+        }
+
+        // Route new writes first so objects moved below are not placed back on the old pools
+        let data = BucketPlacement::new(class).marshal().map_err(ApiError::from)?;
+        metadata_sys::update(&query.bucket, BUCKET_PLACEMENT_CONFIG, data)
+            .await
+            .map_err(ApiError::from)?;
+
+        store
+            .migrate_bucket_pool_class(CancellationToken::new(), &query.bucket, class)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::default())))
+    }
+}
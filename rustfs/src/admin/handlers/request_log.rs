@@ -0,0 +1,79 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lookup of a recent request of the node answering the request by its `x-amz-request-id`.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    server::GLOBAL_REQUEST_LOG,
+};
+
+#[derive(Debug, Default, Deserialize)]
+struct RequestLookupQuery {
+    #[serde(default)]
+    id: String,
+}
+
+pub struct LookupRequest {}
+
+#[async_trait::async_trait]
+impl Operation for LookupRequest {
+    // GET <endpoint>/<admin-API>/request?id=xxx
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let Some(input_cred) = &req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        validate_admin_request(
+            &req.headers,
+            &cred,
+            owner,
+            false,
+            vec![Action::AdminAction(AdminAction::TraceAdminAction)],
+        )
+        .await?;
+
+        let query: RequestLookupQuery = match req.uri.query() {
+            Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+            None => RequestLookupQuery::default(),
+        };
+        if query.id.is_empty() {
+            return Err(s3_error!(InvalidArgument, "request id is required"));
+        }
+
+        let Some(record) = GLOBAL_REQUEST_LOG.get(&query.id) else {
+            return Err(s3_error!(NoSuchKey, "request {} is not known to this node", query.id));
+        };
+
+        let data = serde_json::to_vec(&record)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal request record err {e}")))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::bucket::metadata_sys;
+use rustfs_ecstore::bucket::rule_eval::{ObjectRuleEvaluation, evaluate_lifecycle, evaluate_replication};
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store_api::{BucketOptions, ObjectInfo, ObjectOptions, StorageAPI};
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::dto::{BucketLifecycleConfiguration, DefaultRetention, ReplicationConfiguration};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+const DEFAULT_SAMPLE_KEYS: i32 = 100;
+const MAX_SAMPLE_KEYS: i32 = 1000;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RuleEvaluationQuery {
+    pub bucket: String,
+    /// Object to evaluate, a sample of `prefix` when empty.
+    pub object: String,
+    pub version_id: String,
+    pub prefix: String,
+    pub max_keys: Option<i32>,
+    /// RFC 3339 time to evaluate lifecycle rules at, now when empty.
+    pub at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RuleEvaluationResponse {
+    bucket: String,
+    #[serde(with = "time::serde::rfc3339")]
+    at: OffsetDateTime,
+    objects: Vec<ObjectRuleEvaluation>,
+    /// More objects match the prefix than were sampled.
+    is_truncated: bool,
+}
+
+pub struct EvaluateBucketRules {}
+
+#[async_trait::async_trait]
+impl Operation for EvaluateBucketRules {
+    // GET <endpoint>/<admin-API>/bucket-rules/evaluate?bucket=mybucket&object=key
+    // GET <endpoint>/<admin-API>/bucket-rules/evaluate?bucket=mybucket&prefix=logs/&maxKeys=100&at=2026-01-01T00:00:00Z
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle EvaluateBucketRules");
+
+        let Some(input_cred) = &req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        validate_admin_request(
+            &req.headers,
+            &cred,
+            owner,
+            false,
+            vec![Action::AdminAction(AdminAction::ServerInfoAdminAction)],
+        )
+        .await?;
+
+        let query: RuleEvaluationQuery = match req.uri.query() {
+            Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+            None => RuleEvaluationQuery::default(),
+        };
+        if query.bucket.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket is required"));
+        }
+
+        let at = if query.at.is_empty() {
+            OffsetDateTime::now_utc()
+        } else {
+            OffsetDateTime::parse(&query.at, &Rfc3339).map_err(|_e| s3_error!(InvalidArgument, "at must be an RFC 3339 time"))?
+        };
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&query.bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        let lifecycle = metadata_sys::get_lifecycle_config(&query.bucket)
+            .await
+            .ok()
+            .map(|(cfg, _)| cfg);
+        let replication = metadata_sys::get_replication_config(&query.bucket).await.ok();
+        let retention = metadata_sys::get_object_lock_config(&query.bucket)
+            .await
+            .ok()
+            .and_then(|(cfg, _)| cfg.rule.and_then(|rule| rule.default_retention));
+
+        let (infos, is_truncated) = if !query.object.is_empty() {
+            let opts = ObjectOptions {
+                version_id: (!query.version_id.is_empty()).then(|| query.version_id.clone()),
+                ..Default::default()
+            };
+            let info = store
+                .get_object_info(&query.bucket, &query.object, &opts)
+                .await
+                .map_err(ApiError::from)?;
+            (vec![info], false)
+        } else {
+            let max_keys = query.max_keys.unwrap_or(DEFAULT_SAMPLE_KEYS).clamp(1, MAX_SAMPLE_KEYS);
+            let listed = store
+                .clone()
+                .list_objects_v2(&query.bucket, &query.prefix, None, None, max_keys, false, None, false, None)
+                .await
+                .map_err(ApiError::from)?;
+            (listed.objects, listed.is_truncated)
+        };
+
+        let mut objects = Vec::with_capacity(infos.len());
+        for info in infos.iter() {
+            objects.push(evaluate_object(lifecycle.as_ref(), retention.clone(), replication.clone(), info, at).await);
+        }
+
+        let data = serde_json::to_vec(&RuleEvaluationResponse {
+            bucket: query.bucket,
+            at,
+            objects,
+            is_truncated,
+        })
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal rule evaluation failed: {e}")))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
+
+async fn evaluate_object(
+    lifecycle: Option<&BucketLifecycleConfiguration>,
+    retention: Option<DefaultRetention>,
+    replication: Option<(ReplicationConfiguration, OffsetDateTime)>,
+    info: &ObjectInfo,
+    at: OffsetDateTime,
+) -> ObjectRuleEvaluation {
+    let replication_eval = replication.as_ref().map(|(cfg, _)| evaluate_replication(cfg, info));
+    let lifecycle_eval = match lifecycle {
+        Some(lc) => Some(evaluate_lifecycle(lc, retention, replication, info, at).await),
+        None => None,
+    };
+
+    ObjectRuleEvaluation {
+        object: info.name.clone(),
+        version_id: info.version_id.map(|v| v.to_string()).unwrap_or_default(),
+        is_latest: info.is_latest,
+        delete_marker: info.delete_marker,
+        lifecycle: lifecycle_eval,
+        replication: replication_eval,
+    }
+}
@@ -20,6 +20,7 @@ use hyper::StatusCode;
 use matchit::Params;
 use rustfs_ecstore::global::get_global_action_cred;
 use rustfs_iam::error::is_err_no_such_service_account;
+use rustfs_iam::last_used::GLOBAL_KEY_USAGE;
 use rustfs_iam::sys::{NewServiceAccountOpts, UpdateServiceAccountOpts};
 use rustfs_madmin::{
     AddServiceAccountReq, AddServiceAccountResp, Credentials, InfoServiceAccountResp, ListServiceAccountsResp,
@@ -414,6 +415,7 @@ impl Operation for InfoServiceAccount {
             name: svc_account.name,
             description: svc_account.description,
             expiration: svc_account.expiration,
+            last_used: GLOBAL_KEY_USAGE.last_used(&access_key),
             policy,
         };
 
@@ -513,10 +515,11 @@ impl Operation for ListServiceAccount {
                 parent_user: sa.parent_user.clone(),
                 account_status: sa.status.clone(),
                 implied_policy: sa.is_implied_policy(), // or set according to your logic
-                access_key: sa.access_key,
+                access_key: sa.access_key.clone(),
                 name: sa.name,
                 description: sa.description,
                 expiration: sa.expiration,
+                last_used: GLOBAL_KEY_USAGE.last_used(&sa.access_key),
             })
             .collect();
 
@@ -0,0 +1,195 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::bucket::snapshot::{create_snapshot, delete_snapshot, diff_snapshots, get_snapshot, list_snapshots};
+use rustfs_ecstore::error::StorageError;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store::ECStore;
+use rustfs_ecstore::store_api::{BucketOptions, StorageAPI};
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BucketSnapshotQuery {
+    pub bucket: String,
+    pub name: String,
+    /// Older snapshot of a diff.
+    pub from: String,
+    /// Newer snapshot of a diff.
+    pub to: String,
+}
+
+/// Authorize an admin snapshot request and return its query, with the target bucket checked to exist.
+async fn check_snapshot_request(req: &S3Request<Body>, action: AdminAction) -> S3Result<(Arc<ECStore>, BucketSnapshotQuery)> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(action)]).await?;
+
+    let query = {
+        if let Some(query) = req.uri.query() {
+            let input: BucketSnapshotQuery =
+                from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+            input
+        } else {
+            BucketSnapshotQuery::default()
+        }
+    };
+
+    if query.bucket.is_empty() {
+        return Err(s3_error!(InvalidArgument, "bucket is required"));
+    }
+
+    let Some(store) = new_object_layer_fn() else {
+        return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+    };
+
+    store
+        .get_bucket_info(&query.bucket, &BucketOptions::default())
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok((store, query))
+}
+
+fn snapshot_error(err: StorageError, name: &str) -> S3Error {
+    match err {
+        StorageError::ConfigNotFound => s3_error!(InvalidArgument, "snapshot {} does not exist", name),
+        e => ApiError::from(e).into(),
+    }
+}
+
+fn json_response<T: Serialize>(value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal snapshot failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct CreateBucketSnapshot {}
+
+#[async_trait::async_trait]
+impl Operation for CreateBucketSnapshot {
+    // POST <endpoint>/<admin-API>/bucket-snapshot?bucket=mybucket&name=nightly
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle CreateBucketSnapshot");
+
+        let (store, query) = check_snapshot_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+
+        let info = create_snapshot(store, &query.bucket, &query.name)
+            .await
+            .map_err(|e| snapshot_error(e, &query.name))?;
+
+        json_response(&info)
+    }
+}
+
+pub struct GetBucketSnapshot {}
+
+#[async_trait::async_trait]
+impl Operation for GetBucketSnapshot {
+    // GET <endpoint>/<admin-API>/bucket-snapshot?bucket=mybucket&name=nightly
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle GetBucketSnapshot");
+
+        let (store, query) = check_snapshot_request(&req, AdminAction::ServerInfoAdminAction).await?;
+
+        let manifest = get_snapshot(store, &query.bucket, &query.name)
+            .await
+            .map_err(|e| snapshot_error(e, &query.name))?;
+
+        json_response(&manifest)
+    }
+}
+
+pub struct DeleteBucketSnapshot {}
+
+#[async_trait::async_trait]
+impl Operation for DeleteBucketSnapshot {
+    // DELETE <endpoint>/<admin-API>/bucket-snapshot?bucket=mybucket&name=nightly
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle DeleteBucketSnapshot");
+
+        let (store, query) = check_snapshot_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+
+        delete_snapshot(store, &query.bucket, &query.name)
+            .await
+            .map_err(|e| snapshot_error(e, &query.name))?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::default())))
+    }
+}
+
+pub struct ListBucketSnapshots {}
+
+#[async_trait::async_trait]
+impl Operation for ListBucketSnapshots {
+    // GET <endpoint>/<admin-API>/list-bucket-snapshots?bucket=mybucket
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle ListBucketSnapshots");
+
+        let (store, query) = check_snapshot_request(&req, AdminAction::ServerInfoAdminAction).await?;
+
+        let snapshots = list_snapshots(store, &query.bucket).await.map_err(ApiError::from)?;
+
+        json_response(&snapshots)
+    }
+}
+
+pub struct DiffBucketSnapshots {}
+
+#[async_trait::async_trait]
+impl Operation for DiffBucketSnapshots {
+    // GET <endpoint>/<admin-API>/bucket-snapshot-diff?bucket=mybucket&from=monday&to=tuesday
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle DiffBucketSnapshots");
+
+        let (store, query) = check_snapshot_request(&req, AdminAction::ServerInfoAdminAction).await?;
+
+        if query.from.is_empty() || query.to.is_empty() {
+            return Err(s3_error!(InvalidArgument, "from and to are required"));
+        }
+
+        let diff = diff_snapshots(store, &query.bucket, &query.from, &query.to)
+            .await
+            .map_err(|e| snapshot_error(e, &format!("{} or {}", query.from, query.to)))?;
+
+        json_response(&diff)
+    }
+}
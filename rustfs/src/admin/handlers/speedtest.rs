@@ -0,0 +1,142 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::speedtest::{DrivePerfOptions, ObjectPerfOptions, drive_perf, net_perf, object_perf};
+use rustfs_ecstore::store::ECStore;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_urlencoded::from_bytes;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct NetSpeedtestQuery {
+    /// Bytes sent to and received from every peer.
+    pub size: u64,
+}
+
+impl Default for NetSpeedtestQuery {
+    fn default() -> Self {
+        Self { size: 64 << 20 }
+    }
+}
+
+/// Authorize a speed test request and return its options parsed from the query.
+async fn check_speedtest_request<T: DeserializeOwned + Default>(req: &S3Request<Body>) -> S3Result<(Arc<ECStore>, T)> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(
+        &req.headers,
+        &cred,
+        owner,
+        false,
+        vec![Action::AdminAction(AdminAction::HealthInfoAdminAction)],
+    )
+    .await?;
+
+    let opts = {
+        if let Some(query) = req.uri.query() {
+            let input: T = from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+            input
+        } else {
+            T::default()
+        }
+    };
+
+    let Some(store) = new_object_layer_fn() else {
+        return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+    };
+
+    Ok((store, opts))
+}
+
+fn speedtest_error(err: rustfs_ecstore::error::Error) -> S3Error {
+    S3Error::with_message(S3ErrorCode::InvalidRequest, format!("speed test failed: {err}"))
+}
+
+fn json_response<T: Serialize>(value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal speed test result failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct DriveSpeedtest {}
+
+#[async_trait::async_trait]
+impl Operation for DriveSpeedtest {
+    // POST <endpoint>/<admin-API>/speedtest/drive?fileSize=268435456&blockSize=4194304&randomReads=256&serial=false
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle DriveSpeedtest");
+
+        let (store, opts) = check_speedtest_request::<DrivePerfOptions>(&req).await?;
+
+        let results = drive_perf(&store, opts).await.map_err(speedtest_error)?;
+
+        json_response(&results)
+    }
+}
+
+pub struct NetSpeedtest {}
+
+#[async_trait::async_trait]
+impl Operation for NetSpeedtest {
+    // POST <endpoint>/<admin-API>/speedtest/net?size=67108864
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle NetSpeedtest");
+
+        let (store, query) = check_speedtest_request::<NetSpeedtestQuery>(&req).await?;
+
+        let results = net_perf(&store, query.size).await.map_err(speedtest_error)?;
+
+        json_response(&results)
+    }
+}
+
+pub struct ObjectSpeedtest {}
+
+#[async_trait::async_trait]
+impl Operation for ObjectSpeedtest {
+    // POST <endpoint>/<admin-API>/speedtest/object?size=67108864&concurrency=32&duration=10
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle ObjectSpeedtest");
+
+        let (store, opts) = check_speedtest_request::<ObjectPerfOptions>(&req).await?;
+
+        let result = object_perf(store, opts).await.map_err(speedtest_error)?;
+
+        json_response(&result)
+    }
+}
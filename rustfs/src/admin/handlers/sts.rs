@@ -19,7 +19,10 @@ use crate::{
 use http::StatusCode;
 use matchit::Params;
 use rustfs_ecstore::bucket::utils::serialize;
-use rustfs_iam::{manager::get_token_signing_key, sys::SESSION_POLICY_NAME};
+use rustfs_iam::{
+    manager::get_token_signing_key,
+    sys::{POLICYNAME, SESSION_POLICY_NAME},
+};
 use rustfs_policy::{auth::get_new_credentials_with_metadata, policy::Policy};
 use s3s::{
     Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result,
@@ -34,6 +37,7 @@ use time::{Duration, OffsetDateTime};
 use tracing::{error, info, warn};
 
 const ASSUME_ROLE_ACTION: &str = "AssumeRole";
+const ASSUME_ROLE_WITH_WEB_IDENTITY_ACTION: &str = "AssumeRoleWithWebIdentity";
 const ASSUME_ROLE_VERSION: &str = "2011-06-15";
 
 #[derive(Deserialize, Debug, Default)]
@@ -46,6 +50,7 @@ pub struct AssumeRoleRequest {
     pub role_session_name: String,
     pub policy: String,
     pub external_id: String,
+    pub web_identity_token: String,
 }
 
 pub struct AssumeRoleHandle {}
@@ -54,6 +59,22 @@ impl Operation for AssumeRoleHandle {
     async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
         warn!("handle AssumeRoleHandle");
 
+        let mut input = req.input;
+
+        let bytes = match input.store_all_unlimited().await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+
+        let body: AssumeRoleRequest = from_bytes(&bytes).map_err(|_e| s3_error!(InvalidRequest, "get body failed"))?;
+
+        if body.action.as_str() == ASSUME_ROLE_WITH_WEB_IDENTITY_ACTION {
+            return assume_role_with_web_identity(body).await;
+        }
+
         let Some(user) = req.credentials else { return Err(s3_error!(InvalidRequest, "get cred failed")) };
 
         let session_token = get_session_token(&req.uri, &req.headers);
@@ -69,18 +90,6 @@ impl Operation for AssumeRoleHandle {
             return Err(s3_error!(InvalidRequest, "AccessDenied"));
         }
 
-        let mut input = req.input;
-
-        let bytes = match input.store_all_unlimited().await {
-            Ok(b) => b,
-            Err(e) => {
-                warn!("get body failed, e: {:?}", e);
-                return Err(s3_error!(InvalidRequest, "get body failed"));
-            }
-        };
-
-        let body: AssumeRoleRequest = from_bytes(&bytes).map_err(|_e| s3_error!(InvalidRequest, "get body failed"))?;
-
         if body.action.as_str() != ASSUME_ROLE_ACTION {
             return Err(s3_error!(InvalidArgument, "not support action"));
         }
@@ -142,25 +151,108 @@ impl Operation for AssumeRoleHandle {
 
         // TODO: globalSiteReplicationSys
 
-        let resp = AssumeRoleOutput {
-            credentials: Some(Credentials {
-                access_key_id: new_cred.access_key,
-                expiration: Timestamp::from(
-                    new_cred
-                        .expiration
-                        .unwrap_or(OffsetDateTime::now_utc().saturating_add(Duration::seconds(3600))),
-                ),
-                secret_access_key: new_cred.secret_key,
-                session_token: new_cred.session_token,
-            }),
-            ..Default::default()
-        };
+        credentials_response(new_cred)
+    }
+}
+
+fn credentials_response(new_cred: rustfs_policy::auth::Credentials) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let resp = AssumeRoleOutput {
+        credentials: Some(Credentials {
+            access_key_id: new_cred.access_key,
+            expiration: Timestamp::from(
+                new_cred
+                    .expiration
+                    .unwrap_or(OffsetDateTime::now_utc().saturating_add(Duration::seconds(3600))),
+            ),
+            secret_access_key: new_cred.secret_key,
+            session_token: new_cred.session_token,
+        }),
+        ..Default::default()
+    };
+
+    // getAssumeRoleCredentials
+    let output = serialize::<AssumeRoleOutput>(&resp).unwrap();
+
+    Ok(S3Response::new((StatusCode::OK, Body::from(output))))
+}
+
+/// Issue temporary credentials for an ID token of a configured OpenID provider.
+async fn assume_role_with_web_identity(body: AssumeRoleRequest) -> S3Result<S3Response<(StatusCode, Body)>> {
+    if body.version.as_str() != ASSUME_ROLE_VERSION {
+        return Err(s3_error!(InvalidArgument, "not support version"));
+    }
+
+    if body.web_identity_token.is_empty() {
+        return Err(s3_error!(InvalidArgument, "WebIdentityToken is required"));
+    }
 
-        // getAssumeRoleCredentials
-        let output = serialize::<AssumeRoleOutput>(&resp).unwrap();
+    let Ok(iam_store) = rustfs_iam::get() else {
+        return Err(s3_error!(InvalidRequest, "iam not init"));
+    };
 
-        Ok(S3Response::new((StatusCode::OK, Body::from(output))))
+    let openid = iam_store.openid();
+    if !openid.enabled() {
+        return Err(s3_error!(InvalidRequest, "no openid provider is configured"));
     }
+
+    let provider = openid
+        .provider_for(&body.role_arn, &body.web_identity_token)
+        .await
+        .map_err(|e| s3_error!(AccessDenied, "{}", e))?;
+
+    let id_claims = provider.validate(&body.web_identity_token).await.map_err(|e| {
+        warn!("AssumeRoleWithWebIdentity invalid token for provider {}: {}", provider.config().name, e);
+        s3_error!(AccessDenied, "invalid web identity token")
+    })?;
+
+    let policies = provider
+        .policies(&id_claims)
+        .map_err(|_e| s3_error!(AccessDenied, "no policy is mapped to the web identity"))?;
+
+    let parent_user = provider.parent_user(&id_claims);
+
+    let mut claims = HashMap::new();
+    populate_session_policy(&mut claims, &body.policy)?;
+
+    let exp = if body.duration_seconds > 0 {
+        body.duration_seconds
+    } else {
+        3600
+    };
+    claims.insert(
+        "exp".to_string(),
+        Value::Number(serde_json::Number::from(OffsetDateTime::now_utc().unix_timestamp() + exp as i64)),
+    );
+    claims.insert("parent".to_string(), Value::String(parent_user.clone()));
+    claims.insert(POLICYNAME.to_string(), Value::String(policies.join(",")));
+    if let Some(arn) = provider.config().role_arn() {
+        claims.insert("roleArn".to_string(), Value::String(arn.to_string()));
+    }
+    for name in ["sub", "iss"] {
+        if let Some(v) = id_claims.get(name) {
+            claims.insert(name.to_string(), v.clone());
+        }
+    }
+
+    let Some(secret) = get_token_signing_key() else {
+        return Err(s3_error!(InvalidArgument, "global active sk not init"));
+    };
+
+    let mut new_cred = get_new_credentials_with_metadata(&claims, &secret)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("get new cred failed {e}")))?;
+
+    new_cred.parent_user = parent_user;
+
+    if let Err(_err) = iam_store.set_temp_user(&new_cred.access_key, &new_cred, None).await {
+        return Err(s3_error!(InternalError, "set_temp_user failed"));
+    }
+
+    info!(
+        "AssumeRoleWithWebIdentity issued credentials for {} with policies {:?}",
+        new_cred.parent_user, policies
+    );
+
+    credentials_response(new_cred)
 }
 
 pub fn populate_session_policy(claims: &mut HashMap<String, Value>, policy: &str) -> S3Result<()> {
@@ -0,0 +1,152 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_iam::error::Error as IamError;
+use rustfs_iam::last_used::GLOBAL_KEY_USAGE;
+use rustfs_madmin::AccessKeyLastUsed;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use time::OffsetDateTime;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StsSessionQuery {
+    /// Parent user of the sessions, all users when empty.
+    pub user: String,
+    pub access_key: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StsSessionInfo {
+    access_key: String,
+    parent_user: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    expiration: Option<OffsetDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_used: Option<AccessKeyLastUsed>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListStsSessionsResp {
+    sessions: Vec<StsSessionInfo>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RevokeStsSessionsResp {
+    revoked: Vec<String>,
+}
+
+async fn check_sts_session_request(req: &S3Request<Body>, action: AdminAction) -> S3Result<StsSessionQuery> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(action)]).await?;
+
+    let query = {
+        if let Some(query) = req.uri.query() {
+            let input: StsSessionQuery =
+                from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+            input
+        } else {
+            StsSessionQuery::default()
+        }
+    };
+
+    Ok(query)
+}
+
+fn json_response<T: Serialize>(value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal sts sessions failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct ListStsSessions {}
+
+#[async_trait::async_trait]
+impl Operation for ListStsSessions {
+    // GET <endpoint>/<admin-API>/list-sts-sessions[?user=alice]
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = check_sts_session_request(&req, AdminAction::ListTemporaryAccountsAdminAction).await?;
+
+        let Ok(iam_store) = rustfs_iam::get() else {
+            return Err(s3_error!(InvalidRequest, "iam not init"));
+        };
+
+        let sessions = iam_store
+            .list_sts_sessions(&query.user)
+            .into_iter()
+            .map(|c| StsSessionInfo {
+                last_used: GLOBAL_KEY_USAGE.last_used(&c.access_key),
+                access_key: c.access_key,
+                parent_user: c.parent_user,
+                expiration: c.expiration,
+            })
+            .collect();
+
+        json_response(&ListStsSessionsResp { sessions })
+    }
+}
+
+pub struct RevokeStsSessions {}
+
+#[async_trait::async_trait]
+impl Operation for RevokeStsSessions {
+    // POST <endpoint>/<admin-API>/revoke-sts-sessions?user=alice[&accessKey=xxx]
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle RevokeStsSessions");
+
+        let query = check_sts_session_request(&req, AdminAction::RevokeTemporaryAccountsAdminAction).await?;
+        if query.user.is_empty() && query.access_key.is_empty() {
+            return Err(s3_error!(InvalidArgument, "user or accessKey is required"));
+        }
+
+        let Ok(iam_store) = rustfs_iam::get() else {
+            return Err(s3_error!(InvalidRequest, "iam not init"));
+        };
+
+        let revoked = iam_store
+            .revoke_sts_sessions(&query.user, &query.access_key)
+            .await
+            .map_err(|e| match e {
+                IamError::NoSuchTempAccount(ak) => s3_error!(InvalidArgument, "temp account '{}' does not exist", ak),
+                e => S3Error::with_message(S3ErrorCode::InternalError, format!("revoke sts sessions failed: {e}")),
+            })?;
+
+        json_response(&RevokeStsSessionsResp { revoked })
+    }
+}
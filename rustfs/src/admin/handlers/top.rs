@@ -0,0 +1,108 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::top::{DEFAULT_TOP_COUNT, MAX_TOP_COUNT, top_apis, top_locks};
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct TopQuery {
+    pub count: usize,
+}
+
+impl Default for TopQuery {
+    fn default() -> Self {
+        Self {
+            count: DEFAULT_TOP_COUNT,
+        }
+    }
+}
+
+/// Authorize a top view request and return the number of entries asked for.
+async fn check_top_request(req: &S3Request<Body>, action: AdminAction) -> S3Result<usize> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(action)]).await?;
+
+    let query = {
+        if let Some(query) = req.uri.query() {
+            let input: TopQuery = from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+            input
+        } else {
+            TopQuery::default()
+        }
+    };
+
+    if query.count == 0 || query.count > MAX_TOP_COUNT {
+        return Err(s3_error!(InvalidArgument, "count must be between 1 and {}", MAX_TOP_COUNT));
+    }
+
+    Ok(query.count)
+}
+
+fn json_response<T: Serialize>(value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal top view failed: {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct TopLocksHandler {}
+
+#[async_trait::async_trait]
+impl Operation for TopLocksHandler {
+    // GET <endpoint>/<admin-API>/top/locks?count=10
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle TopLocksHandler");
+
+        let count = check_top_request(&req, AdminAction::TopLocksAdminAction).await?;
+
+        json_response(&top_locks(count).await)
+    }
+}
+
+pub struct TopApisHandler {}
+
+#[async_trait::async_trait]
+impl Operation for TopApisHandler {
+    // GET <endpoint>/<admin-API>/top/apis?count=10
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle TopApisHandler");
+
+        let count = check_top_request(&req, AdminAction::ServerInfoAdminAction).await?;
+
+        json_response(&top_apis(count).await)
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::bucket::metadata::BUCKET_TRASH_CONFIG;
+use rustfs_ecstore::bucket::metadata_sys;
+use rustfs_ecstore::bucket::trash::{BucketTrash, list_trash, restore_from_trash};
+use rustfs_ecstore::error::StorageError;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store::ECStore;
+use rustfs_ecstore::store_api::{BucketOptions, StorageAPI};
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BucketTrashQuery {
+    pub bucket: String,
+    /// Only list trashed objects whose key starts with this prefix.
+    pub prefix: String,
+    /// Trash entry to restore.
+    pub id: String,
+    /// Replace an object that exists at the original key.
+    pub overwrite: bool,
+}
+
+/// Authorize an admin trash request and return its query, with the target bucket checked to exist.
+async fn check_trash_request(req: &S3Request<Body>, action: AdminAction) -> S3Result<(Arc<ECStore>, BucketTrashQuery)> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(action)]).await?;
+
+    let query = {
+        if let Some(query) = req.uri.query() {
+            let input: BucketTrashQuery =
+                from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+            input
+        } else {
+            BucketTrashQuery::default()
+        }
+    };
+
+    if query.bucket.is_empty() {
+        return Err(s3_error!(InvalidArgument, "bucket is required"));
+    }
+
+    let Some(store) = new_object_layer_fn() else {
+        return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+    };
+
+    store
+        .get_bucket_info(&query.bucket, &BucketOptions::default())
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok((store, query))
+}
+
+fn json_response(data: Vec<u8>) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct GetBucketTrash {}
+
+#[async_trait::async_trait]
+impl Operation for GetBucketTrash {
+    // GET <endpoint>/<admin-API>/bucket-trash?bucket=mybucket
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle GetBucketTrash");
+
+        let (_, query) = check_trash_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+
+        let cfg = match metadata_sys::get_trash_config(&query.bucket).await {
+            Ok((cfg, _)) => cfg,
+            Err(StorageError::ConfigNotFound) => BucketTrash::default(),
+            Err(e) => return Err(ApiError::from(e).into()),
+        };
+
+        json_response(cfg.marshal().map_err(ApiError::from)?)
+    }
+}
+
+pub struct SetBucketTrash {}
+
+#[async_trait::async_trait]
+impl Operation for SetBucketTrash {
+    // PUT <endpoint>/<admin-API>/bucket-trash?bucket=mybucket
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle SetBucketTrash");
+
+        let (_, query) = check_trash_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+
+        let mut input = req.input;
+        let body = match input.store_all_unlimited().await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+
+        let cfg = BucketTrash::unmarshal(&body)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, format!("unmarshal body err {e}")))?;
+        cfg.validate()
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, e.to_string()))?;
+
+        let data = cfg.marshal().map_err(ApiError::from)?;
+        metadata_sys::update(&query.bucket, BUCKET_TRASH_CONFIG, data)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::default())))
+    }
+}
+
+pub struct RemoveBucketTrash {}
+
+#[async_trait::async_trait]
+impl Operation for RemoveBucketTrash {
+    // DELETE <endpoint>/<admin-API>/bucket-trash?bucket=mybucket
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle RemoveBucketTrash");
+
+        let (_, query) = check_trash_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+
+        metadata_sys::delete(&query.bucket, BUCKET_TRASH_CONFIG)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::default())))
+    }
+}
+
+pub struct ListTrash {}
+
+#[async_trait::async_trait]
+impl Operation for ListTrash {
+    // GET <endpoint>/<admin-API>/list-trash?bucket=mybucket&prefix=photos/
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle ListTrash");
+
+        let (store, query) = check_trash_request(&req, AdminAction::ServerInfoAdminAction).await?;
+
+        let entries = list_trash(store, &query.bucket, &query.prefix)
+            .await
+            .map_err(ApiError::from)?;
+
+        let data = serde_json::to_vec(&entries)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal trash entries failed: {e}")))?;
+
+        json_response(data)
+    }
+}
+
+pub struct RestoreTrash {}
+
+#[async_trait::async_trait]
+impl Operation for RestoreTrash {
+    // POST <endpoint>/<admin-API>/restore-trash?bucket=mybucket&id=<trash-id>&overwrite=false
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        warn!("handle RestoreTrash");
+
+        let (store, query) = check_trash_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+
+        if query.id.is_empty() {
+            return Err(s3_error!(InvalidArgument, "id is required"));
+        }
+
+        let entry = restore_from_trash(store, &query.bucket, &query.id, query.overwrite)
+            .await
+            .map_err(ApiError::from)?;
+
+        let data = serde_json::to_vec(&entry)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal trash entry failed: {e}")))?;
+
+        json_response(data)
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::data_usage::query_usage;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store_api::{BucketOptions, StorageAPI};
+use rustfs_policy::policy::Args;
+use rustfs_policy::policy::action::{Action, AdminAction, S3Action};
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{auth::validate_admin_request, router::Operation},
+    auth::{check_key_valid, get_condition_values, get_session_token},
+    error::ApiError,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BucketUsageQuery {
+    pub bucket: String,
+    pub prefix: String,
+    /// Compute the usage by listing instead of reporting the last collected one.
+    pub refresh: bool,
+}
+
+pub struct GetBucketUsage {}
+
+#[async_trait::async_trait]
+impl Operation for GetBucketUsage {
+    // GET <endpoint>/<admin-API>/bucket-usage?bucket=mybucket&prefix=logs/&refresh=true
+    //
+    // Allowed to administrators and to users who may list the bucket.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let Some(input_cred) = &req.credentials else {
+            return Err(s3_error!(InvalidRequest, "get cred failed"));
+        };
+
+        let (cred, owner) =
+            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+        let query: BucketUsageQuery = match req.uri.query() {
+            Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+            None => BucketUsageQuery::default(),
+        };
+        if query.bucket.is_empty() {
+            return Err(s3_error!(InvalidArgument, "bucket is required"));
+        }
+
+        let is_admin = validate_admin_request(
+            &req.headers,
+            &cred,
+            owner,
+            false,
+            vec![Action::AdminAction(AdminAction::DataUsageInfoAdminAction)],
+        )
+        .await
+        .is_ok();
+        if !is_admin {
+            let Ok(iam_store) = rustfs_iam::get() else {
+                return Err(s3_error!(InternalError, "iam not init"));
+            };
+            let conditions = get_condition_values(&req.headers, &cred, None, None);
+            let claims = cred.claims.clone().unwrap_or_default();
+            let allowed = iam_store
+                .is_allowed(&Args {
+                    account: &cred.access_key,
+                    groups: &cred.groups,
+                    action: Action::S3Action(S3Action::ListBucketAction),
+                    conditions: &conditions,
+                    is_owner: owner,
+                    claims: &claims,
+                    deny_only: false,
+                    bucket: &query.bucket,
+                    object: &query.prefix,
+                })
+                .await;
+            if !allowed {
+                return Err(s3_error!(AccessDenied, "Access Denied"));
+            }
+        }
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&query.bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        let report = query_usage(store, &query.bucket, &query.prefix, query.refresh)
+            .await
+            .map_err(|e| {
+                warn!("query usage of {}/{} failed: {:?}", query.bucket, query.prefix, e);
+                ApiError::from(e)
+            })?;
+
+        let data = serde_json::to_vec(&report)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal usage failed: {e}")))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
@@ -13,7 +13,11 @@
 // limitations under the License.
 
 use crate::{
-    admin::{auth::validate_admin_request, router::Operation, utils::has_space_be},
+    admin::{
+        auth::validate_admin_request,
+        router::Operation,
+        utils::{PageQuery, has_space_be},
+    },
     auth::{check_key_valid, get_session_token},
 };
 use http::{HeaderMap, StatusCode};
@@ -24,7 +28,8 @@ use rustfs_iam::{
     sys::NewServiceAccountOpts,
 };
 use rustfs_madmin::{
-    AccountStatus, AddOrUpdateUserReq, IAMEntities, IAMErrEntities, IAMErrEntity, IAMErrPolicyEntity,
+    AccountStatus, AddOrUpdateUserReq, IAMEntities, IAMErrEntities, IAMErrEntity, IAMErrPolicyEntity, IAMGroupInfo,
+    IAMPolicyMapping,
     user::{ImportIAMResult, SRSessionPolicy, SRSvcAccCreate},
 };
 use rustfs_policy::policy::action::{Action, AdminAction};
@@ -34,7 +39,7 @@ use s3s::{
     header::{CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE},
     s3_error,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_urlencoded::from_bytes;
 use std::io::{Read as _, Write};
 use std::{collections::HashMap, io::Cursor, str::from_utf8};
@@ -198,9 +203,20 @@ impl Operation for SetUserStatus {
 }
 
 #[derive(Debug, Deserialize, Default)]
+#[serde(default)]
 pub struct BucketQuery {
     #[serde(rename = "bucket")]
     pub bucket: String,
+    pub marker: String,
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UsersPage {
+    users: HashMap<String, rustfs_madmin::UserInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_marker: Option<String>,
 }
 pub struct ListUsers {}
 #[async_trait::async_trait]
@@ -250,8 +266,23 @@ impl Operation for ListUsers {
             }
         };
 
-        let data = serde_json::to_vec(&users)
-            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal users err {e}")))?;
+        // Unpaged requests keep returning the bare map for existing clients.
+        let page = PageQuery {
+            marker: query.marker,
+            limit: query.limit,
+        };
+        let data = if page.is_paged() {
+            let mut users = users;
+            let (names, next_marker) = page.apply(users.keys().cloned().collect());
+            let users = names
+                .into_iter()
+                .filter_map(|name| users.remove(&name).map(|info| (name, info)))
+                .collect();
+            serde_json::to_vec(&UsersPage { users, next_marker })
+        } else {
+            serde_json::to_vec(&users)
+        }
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal users err {e}")))?;
 
         let mut header = HeaderMap::new();
         header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
@@ -494,6 +525,7 @@ impl Operation for ExportIam {
                         .load_groups(&mut groups)
                         .await
                         .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
+                    let groups: HashMap<String, IAMGroupInfo> = groups.into_iter().map(|(k, v)| (k, v.into())).collect();
 
                     let json_str = serde_json::to_vec(&groups)
                         .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
@@ -566,6 +598,8 @@ impl Operation for ExportIam {
                         .await
                         .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
 
+                    let user_policy_mappings: HashMap<String, IAMPolicyMapping> =
+                        user_policy_mappings.into_iter().map(|(k, v)| (k, v.into())).collect();
                     let json_str = serde_json::to_vec(&user_policy_mappings)
                         .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
                     zip_writer
@@ -582,6 +616,8 @@ impl Operation for ExportIam {
                         .await
                         .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
 
+                    let group_policy_mappings: HashMap<String, IAMPolicyMapping> =
+                        group_policy_mappings.into_iter().map(|(k, v)| (k, v.into())).collect();
                     let json_str = serde_json::to_vec(&group_policy_mappings)
                         .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
                     zip_writer
@@ -597,6 +633,8 @@ impl Operation for ExportIam {
                         .load_mapped_policies(UserType::Sts, false, &mut sts_user_policy_mappings)
                         .await
                         .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
+                    let sts_user_policy_mappings: HashMap<String, IAMPolicyMapping> =
+                        sts_user_policy_mappings.into_iter().map(|(k, v)| (k, v.into())).collect();
                     let json_str = serde_json::to_vec(&sts_user_policy_mappings)
                         .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
                     zip_writer
@@ -632,7 +670,7 @@ impl Operation for ImportIam {
         let (cred, owner) =
             check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
 
-        validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(AdminAction::ExportIAMAction)])
+        validate_admin_request(&req.headers, &cred, owner, false, vec![Action::AdminAction(AdminAction::ImportIAMAction)])
             .await?;
 
         let mut input = req.input;
@@ -753,16 +791,23 @@ impl Operation for ImportIam {
             };
 
             if let Some(file_content) = file_content {
-                let groups: HashMap<String, GroupInfo> = serde_json::from_slice(&file_content)
+                let groups: HashMap<String, IAMGroupInfo> = serde_json::from_slice(&file_content)
                     .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
                 for (group_name, group_info) in groups {
-                    if let Err(e) = iam_store.get_group_description(&group_name).await {
-                        if matches!(e, rustfs_iam::error::Error::NoSuchGroup(_)) || has_space_be(&group_name) {
-                            return Err(s3_error!(InvalidArgument, "group not found or has space be"));
-                        }
+                    if let Err(e) = iam_store.get_group_description(&group_name).await
+                        && !matches!(e, rustfs_iam::error::Error::NoSuchGroup(_))
+                    {
+                        return Err(s3_error!(InternalError, "get group failed, name: {group_name}, err: {e}"));
+                    }
+                    if has_space_be(&group_name) {
+                        return Err(s3_error!(InvalidArgument, "has space be {group_name}"));
                     }
 
-                    if let Err(e) = iam_store.add_users_to_group(&group_name, group_info.members.clone()).await {
+                    let mut res = iam_store.add_users_to_group(&group_name, group_info.members.clone()).await;
+                    if res.is_ok() && group_info.status == "disabled" {
+                        res = iam_store.set_group_status(&group_name, false).await;
+                    }
+                    if let Err(e) = res {
                         failed.groups.push(IAMErrEntity {
                             name: group_name.clone(),
                             error: e.to_string(),
@@ -808,6 +853,16 @@ impl Operation for ImportIam {
                         return Err(s3_error!(InvalidArgument, "has space be {ak}"));
                     }
 
+                    // Accounts that never expire are exported with the Unix epoch or without an expiration
+                    let Some(expiration) = req.expiration.filter(|t| t.unix_timestamp() > 0) else {
+                        failed.service_accounts.push(IAMErrEntity {
+                            name: ak.clone(),
+                            error: "service accounts without an expiration are not supported, set one before importing"
+                                .to_string(),
+                        });
+                        continue;
+                    };
+
                     let mut update = true;
 
                     if let Err(e) = iam_store.get_service_account(&req.access_key).await {
@@ -832,7 +887,7 @@ impl Operation for ImportIam {
                         secret_key: req.secret_key,
                         name: Some(req.name),
                         description: Some(req.description),
-                        expiration: req.expiration,
+                        expiration: Some(expiration),
                         allow_site_replicator_account: false,
                         claims: Some(req.claims),
                     };
@@ -866,7 +921,7 @@ impl Operation for ImportIam {
             };
 
             if let Some(file_content) = file_content {
-                let user_policy_mappings: HashMap<String, MappedPolicy> = serde_json::from_slice(&file_content)
+                let user_policy_mappings: HashMap<String, IAMPolicyMapping> = serde_json::from_slice(&file_content)
                     .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
                 for (user_name, policies) in user_policy_mappings {
                     let has_temp = match iam_store.is_temp_user(&user_name).await {
@@ -884,18 +939,18 @@ impl Operation for ImportIam {
                     }
 
                     if let Err(e) = iam_store
-                        .policy_db_set(&user_name, UserType::Reg, false, &policies.policies)
+                        .policy_db_set(&user_name, UserType::Reg, false, &policies.policy)
                         .await
                     {
                         failed.user_policies.push(IAMErrPolicyEntity {
                             name: user_name.clone(),
                             error: e.to_string(),
-                            policies: policies.policies.split(',').map(|s| s.to_string()).collect(),
+                            policies: policies.policy.split(',').map(|s| s.to_string()).collect(),
                         });
                     } else {
                         added.user_policies.push(HashMap::from([(
                             user_name.clone(),
-                            policies.policies.split(',').map(|s| s.to_string()).collect(),
+                            policies.policy.split(',').map(|s| s.to_string()).collect(),
                         )]));
                     }
                 }
@@ -917,7 +972,7 @@ impl Operation for ImportIam {
             };
 
             if let Some(file_content) = file_content {
-                let group_policy_mappings: HashMap<String, MappedPolicy> = serde_json::from_slice(&file_content)
+                let group_policy_mappings: HashMap<String, IAMPolicyMapping> = serde_json::from_slice(&file_content)
                     .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
                 for (group_name, policies) in group_policy_mappings {
                     if skipped.groups.contains(&group_name) {
@@ -925,18 +980,18 @@ impl Operation for ImportIam {
                     }
 
                     if let Err(e) = iam_store
-                        .policy_db_set(&group_name, UserType::None, true, &policies.policies)
+                        .policy_db_set(&group_name, UserType::None, true, &policies.policy)
                         .await
                     {
                         failed.group_policies.push(IAMErrPolicyEntity {
                             name: group_name.clone(),
                             error: e.to_string(),
-                            policies: policies.policies.split(',').map(|s| s.to_string()).collect(),
+                            policies: policies.policy.split(',').map(|s| s.to_string()).collect(),
                         });
                     } else {
                         added.group_policies.push(HashMap::from([(
                             group_name.clone(),
-                            policies.policies.split(',').map(|s| s.to_string()).collect(),
+                            policies.policy.split(',').map(|s| s.to_string()).collect(),
                         )]));
                     }
                 }
@@ -958,7 +1013,7 @@ impl Operation for ImportIam {
             };
 
             if let Some(file_content) = file_content {
-                let sts_user_policy_mappings: HashMap<String, MappedPolicy> = serde_json::from_slice(&file_content)
+                let sts_user_policy_mappings: HashMap<String, IAMPolicyMapping> = serde_json::from_slice(&file_content)
                     .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
                 for (user_name, policies) in sts_user_policy_mappings {
                     if skipped.users.contains(&user_name) {
@@ -980,18 +1035,18 @@ impl Operation for ImportIam {
                     }
 
                     if let Err(e) = iam_store
-                        .policy_db_set(&user_name, UserType::Sts, false, &policies.policies)
+                        .policy_db_set(&user_name, UserType::Sts, false, &policies.policy)
                         .await
                     {
                         failed.sts_policies.push(IAMErrPolicyEntity {
                             name: user_name.clone(),
                             error: e.to_string(),
-                            policies: policies.policies.split(',').map(|s| s.to_string()).collect(),
+                            policies: policies.policy.split(',').map(|s| s.to_string()).collect(),
                         });
                     } else {
                         added.sts_policies.push(HashMap::from([(
                             user_name.clone(),
-                            policies.policies.split(',').map(|s| s.to_string()).collect(),
+                            policies.policy.split(',').map(|s| s.to_string()).collect(),
                         )]));
                     }
                 }
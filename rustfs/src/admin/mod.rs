@@ -16,28 +16,34 @@ mod auth;
 pub mod console;
 pub mod handlers;
 pub mod router;
-mod rpc;
+pub(crate) mod rpc;
 pub mod utils;
 
 #[cfg(test)]
 mod console_test;
 
 use handlers::{
-    GetReplicationMetricsHandler, HealthCheckHandler, ListRemoteTargetHandler, RemoveRemoteTargetHandler, SetRemoteTargetHandler,
-    bucket_meta,
-    event::{ListNotificationTargets, ListTargetsArns, NotificationTarget, RemoveNotificationTarget},
-    group, kms, kms_dynamic, kms_keys, policies, pools,
+    GetReplicationDriftHandler, GetReplicationMetricsHandler, HealthCheckHandler, ListRemoteTargetHandler,
+    RemoveRemoteTargetHandler, SetRemoteTargetHandler, bandwidth, bucket_backup, bucket_meta, bucket_purge, compat, compose,
+    config_backup, dedup, disk_replacement, erasure,
+    event::{
+        ListNotificationTargets, ListTargetsArns, NotificationTarget, NotificationTargetLag, RemoveNotificationTarget,
+        ReplayNotificationTarget,
+    },
+    event_backfill, federation, group, health, integrity, key_rotation, kms, kms_dynamic, kms_keys, kms_scope, listing,
+    maintenance, marker_cleanup, metadata_search, mode, naming, object_metadata, object_repair, orphaned_data, policies, pools,
+    presign,
     profile::{TriggerProfileCPU, TriggerProfileMemory},
-    rebalance,
+    rebalance, request_log, rule_eval,
     service_account::{AddServiceAccount, DeleteServiceAccount, InfoServiceAccount, ListServiceAccount, UpdateServiceAccount},
-    sts, tier, user,
+    snapshot, speedtest, sts, sts_session, tier, top, transform, trash, usage, user,
 };
 use hyper::Method;
 use router::{AdminOperation, S3Router};
 use rpc::register_rpc_route;
 use s3s::route::S3Route;
 
-const ADMIN_PREFIX: &str = "/rustfs/admin";
+pub(crate) const ADMIN_PREFIX: &str = "/rustfs/admin";
 // const ADMIN_PREFIX: &str = "/minio/admin";
 
 pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route> {
@@ -45,6 +51,9 @@ pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route>
 
     // Health check endpoint for monitoring and orchestration
     r.insert(Method::GET, "/health", AdminOperation(&HealthCheckHandler {}))?;
+    r.insert(Method::GET, "/health/live", AdminOperation(&health::LivenessHandler {}))?;
+    r.insert(Method::GET, "/health/ready", AdminOperation(&health::ReadinessHandler {}))?;
+    r.insert(Method::GET, "/health/cluster", AdminOperation(&health::ClusterHealthHandler {}))?;
     r.insert(Method::GET, "/profile/cpu", AdminOperation(&TriggerProfileCPU {}))?;
     r.insert(Method::GET, "/profile/memory", AdminOperation(&TriggerProfileMemory {}))?;
 
@@ -87,6 +96,11 @@ pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route>
         format!("{}{}", ADMIN_PREFIX, "/v3/datausageinfo").as_str(),
         AdminOperation(&handlers::DataUsageInfoHandler {}),
     )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-usage").as_str(),
+        AdminOperation(&usage::GetBucketUsage {}),
+    )?;
     r.insert(
         Method::GET,
         format!("{}{}", ADMIN_PREFIX, "/v3/metrics").as_str(),
@@ -117,6 +131,388 @@ pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route>
         format!("{}{}", ADMIN_PREFIX, "/v3/pools/cancel").as_str(),
         AdminOperation(&pools::CancelDecommission {}),
     )?;
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/pools/class").as_str(),
+        AdminOperation(&pools::SetPoolClass {}),
+    )?;
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/pools/migrate-bucket").as_str(),
+        AdminOperation(&pools::MigrateBucketPoolClass {}),
+    )?;
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/pools/plan").as_str(),
+        AdminOperation(&pools::PlanPools {}),
+    )?;
+
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/force-delete-bucket").as_str(),
+        AdminOperation(&bucket_purge::ForceDeleteBucket {}),
+    )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-purge-status").as_str(),
+        AdminOperation(&bucket_purge::BucketPurgeStatus {}),
+    )?;
+
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/delete-marker-cleanup").as_str(),
+        AdminOperation(&marker_cleanup::StartMarkerCleanup {}),
+    )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/delete-marker-cleanup").as_str(),
+        AdminOperation(&marker_cleanup::MarkerCleanupStatus {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/orphaned-data").as_str(),
+        AdminOperation(&orphaned_data::GetOrphanedData {}),
+    )?;
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/orphaned-data/reclaim").as_str(),
+        AdminOperation(&orphaned_data::ReclaimOrphanedData {}),
+    )?;
+
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/repair-object").as_str(),
+        AdminOperation(&object_repair::RepairObject {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/maintenance").as_str(),
+        AdminOperation(&maintenance::GetMaintenance {}),
+    )?;
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/maintenance").as_str(),
+        AdminOperation(&maintenance::SetMaintenance {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bandwidth").as_str(),
+        AdminOperation(&bandwidth::GetBandwidth {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bandwidth").as_str(),
+        AdminOperation(&bandwidth::SetBandwidth {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/config-backups").as_str(),
+        AdminOperation(&config_backup::ListConfigBackups {}),
+    )?;
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/config-backups").as_str(),
+        AdminOperation(&config_backup::CreateConfigBackup {}),
+    )?;
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/config-backups/restore").as_str(),
+        AdminOperation(&config_backup::RestoreConfigBackup {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/disk-replacement").as_str(),
+        AdminOperation(&disk_replacement::GetDiskReplacement {}),
+    )?;
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/disk-replacement").as_str(),
+        AdminOperation(&disk_replacement::MarkDiskReplacement {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/disk-replacement").as_str(),
+        AdminOperation(&disk_replacement::ClearDiskReplacement {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/compat").as_str(),
+        AdminOperation(&compat::GetCompat {}),
+    )?;
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/compat").as_str(),
+        AdminOperation(&compat::SetCompat {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/federation/routes").as_str(),
+        AdminOperation(&federation::ListBucketRoutes {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/federation/route").as_str(),
+        AdminOperation(&federation::SetBucketRoute {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/federation/route").as_str(),
+        AdminOperation(&federation::RemoveBucketRoute {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/presign-limits").as_str(),
+        AdminOperation(&presign::GetPresignLimits {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/presign-limits").as_str(),
+        AdminOperation(&presign::SetPresignLimits {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-transform").as_str(),
+        AdminOperation(&transform::GetBucketTransform {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-transform").as_str(),
+        AdminOperation(&transform::SetBucketTransform {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-transform").as_str(),
+        AdminOperation(&transform::RemoveBucketTransform {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-trash").as_str(),
+        AdminOperation(&trash::GetBucketTrash {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-trash").as_str(),
+        AdminOperation(&trash::SetBucketTrash {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-trash").as_str(),
+        AdminOperation(&trash::RemoveBucketTrash {}),
+    )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/list-trash").as_str(),
+        AdminOperation(&trash::ListTrash {}),
+    )?;
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/restore-trash").as_str(),
+        AdminOperation(&trash::RestoreTrash {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-naming").as_str(),
+        AdminOperation(&naming::GetBucketNaming {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-naming").as_str(),
+        AdminOperation(&naming::SetBucketNaming {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-naming").as_str(),
+        AdminOperation(&naming::RemoveBucketNaming {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-erasure").as_str(),
+        AdminOperation(&erasure::GetBucketErasure {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-erasure").as_str(),
+        AdminOperation(&erasure::SetBucketErasure {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-erasure").as_str(),
+        AdminOperation(&erasure::RemoveBucketErasure {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-dedup").as_str(),
+        AdminOperation(&dedup::GetBucketDedup {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-dedup").as_str(),
+        AdminOperation(&dedup::SetBucketDedup {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-dedup").as_str(),
+        AdminOperation(&dedup::RemoveBucketDedup {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-integrity").as_str(),
+        AdminOperation(&integrity::GetBucketIntegrity {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-integrity").as_str(),
+        AdminOperation(&integrity::SetBucketIntegrity {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-integrity").as_str(),
+        AdminOperation(&integrity::RemoveBucketIntegrity {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-kms-scope").as_str(),
+        AdminOperation(&kms_scope::GetBucketKmsScope {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-kms-scope").as_str(),
+        AdminOperation(&kms_scope::SetBucketKmsScope {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-kms-scope").as_str(),
+        AdminOperation(&kms_scope::RemoveBucketKmsScope {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-mode").as_str(),
+        AdminOperation(&mode::GetBucketMode {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-mode").as_str(),
+        AdminOperation(&mode::SetBucketMode {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-mode").as_str(),
+        AdminOperation(&mode::RemoveBucketMode {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-rules/evaluate").as_str(),
+        AdminOperation(&rule_eval::EvaluateBucketRules {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-listing").as_str(),
+        AdminOperation(&listing::GetBucketListing {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-listing").as_str(),
+        AdminOperation(&listing::SetBucketListing {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-listing").as_str(),
+        AdminOperation(&listing::RemoveBucketListing {}),
+    )?;
+
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-snapshot").as_str(),
+        AdminOperation(&snapshot::CreateBucketSnapshot {}),
+    )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-snapshot").as_str(),
+        AdminOperation(&snapshot::GetBucketSnapshot {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-snapshot").as_str(),
+        AdminOperation(&snapshot::DeleteBucketSnapshot {}),
+    )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/list-bucket-snapshots").as_str(),
+        AdminOperation(&snapshot::ListBucketSnapshots {}),
+    )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-snapshot-diff").as_str(),
+        AdminOperation(&snapshot::DiffBucketSnapshots {}),
+    )?;
+
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/speedtest/drive").as_str(),
+        AdminOperation(&speedtest::DriveSpeedtest {}),
+    )?;
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/speedtest/net").as_str(),
+        AdminOperation(&speedtest::NetSpeedtest {}),
+    )?;
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/speedtest/object").as_str(),
+        AdminOperation(&speedtest::ObjectSpeedtest {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/top/locks").as_str(),
+        AdminOperation(&top::TopLocksHandler {}),
+    )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/top/apis").as_str(),
+        AdminOperation(&top::TopApisHandler {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/request").as_str(),
+        AdminOperation(&request_log::LookupRequest {}),
+    )?;
+
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/metadata-search").as_str(),
+        AdminOperation(&metadata_search::SearchObjectMetadata {}),
+    )?;
+
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/compose-object").as_str(),
+        AdminOperation(&compose::ComposeObject {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/object-metadata").as_str(),
+        AdminOperation(&object_metadata::PutObjectMetadata {}),
+    )?;
 
     r.insert(
         Method::POST,
@@ -197,6 +593,12 @@ pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route>
         AdminOperation(&bucket_meta::ImportBucketMetadata {}),
     )?;
 
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-metadata-history").as_str(),
+        AdminOperation(&bucket_meta::GetBucketMetadataHistory {}),
+    )?;
+
     r.insert(
         Method::GET,
         format!("{}{}", ADMIN_PREFIX, "/v3/list-remote-targets").as_str(),
@@ -209,6 +611,12 @@ pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route>
         AdminOperation(&GetReplicationMetricsHandler {}),
     )?;
 
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/replication-drift").as_str(),
+        AdminOperation(&GetReplicationDriftHandler {}),
+    )?;
+
     r.insert(
         Method::PUT,
         format!("{}{}", ADMIN_PREFIX, "/v3/set-remote-target").as_str(),
@@ -221,6 +629,27 @@ pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route>
         AdminOperation(&RemoveRemoteTargetHandler {}),
     )?;
 
+    #[cfg(debug_assertions)]
+    {
+        use handlers::disk_faults::{AddDiskFault, ListDiskFaults, RemoveDiskFault};
+
+        r.insert(
+            Method::GET,
+            format!("{}{}", ADMIN_PREFIX, "/debug/disk-faults").as_str(),
+            AdminOperation(&ListDiskFaults {}),
+        )?;
+        r.insert(
+            Method::PUT,
+            format!("{}{}", ADMIN_PREFIX, "/debug/disk-faults").as_str(),
+            AdminOperation(&AddDiskFault {}),
+        )?;
+        r.insert(
+            Method::DELETE,
+            format!("{}{}", ADMIN_PREFIX, "/debug/disk-faults").as_str(),
+            AdminOperation(&RemoveDiskFault {}),
+        )?;
+    }
+
     // Performance profiling endpoints (available on all platforms, with platform-specific responses)
     #[cfg(not(target_os = "windows"))]
     r.insert(
@@ -341,6 +770,31 @@ pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route>
         AdminOperation(&kms_keys::DescribeKmsKeyHandler {}),
     )?;
 
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/kms/keys/rotate").as_str(),
+        AdminOperation(&key_rotation::RotateKmsKey {}),
+    )?;
+
+    // Re-wrap object data keys after a key rotation
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/kms/key-rotation").as_str(),
+        AdminOperation(&key_rotation::StartKeyRotation {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/kms/key-rotation").as_str(),
+        AdminOperation(&key_rotation::KeyRotationStatus {}),
+    )?;
+
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/kms/key-rotation").as_str(),
+        AdminOperation(&key_rotation::CancelKeyRotation {}),
+    )?;
+
     Ok(r)
 }
 
@@ -401,6 +855,20 @@ fn register_user_route(r: &mut S3Router<AdminOperation>) -> std::io::Result<()>
         AdminOperation(&group::GetGroup {}),
     )?;
 
+    // ?group=xxx
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/group").as_str(),
+        AdminOperation(&group::CreateGroup {}),
+    )?;
+
+    // ?group=xxx&force=true
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/group").as_str(),
+        AdminOperation(&group::DeleteGroup {}),
+    )?;
+
     // ?group=xxx&status=xxx
     r.insert(
         Method::PUT,
@@ -449,6 +917,20 @@ fn register_user_route(r: &mut S3Router<AdminOperation>) -> std::io::Result<()>
         AdminOperation(&AddServiceAccount {}),
     )?;
 
+    // STS sessions
+    // ?[user=xxx]
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/list-sts-sessions").as_str(),
+        AdminOperation(&sts_session::ListStsSessions {}),
+    )?;
+    // ?user=xxx[&accessKey=xxx]
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/revoke-sts-sessions").as_str(),
+        AdminOperation(&sts_session::RevokeStsSessions {}),
+    )?;
+
     r.insert(
         Method::GET,
         format!("{}{}", ADMIN_PREFIX, "/v3/export-iam").as_str(),
@@ -496,6 +978,13 @@ fn register_user_route(r: &mut S3Router<AdminOperation>) -> std::io::Result<()>
         AdminOperation(&policies::SetPolicyForUserOrGroup {}),
     )?;
 
+    // simulate-policy, JSON body {accessKey, action, bucket, object | resource, conditions}
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/simulate-policy").as_str(),
+        AdminOperation(&policies::SimulatePolicy {}),
+    )?;
+
     r.insert(
         Method::GET,
         format!("{}{}", ADMIN_PREFIX, "/v3/target/list").as_str(),
@@ -519,6 +1008,70 @@ fn register_user_route(r: &mut S3Router<AdminOperation>) -> std::io::Result<()>
         AdminOperation(&RemoveNotificationTarget {}),
     )?;
 
+    // Delivery lag and backlog of each notification target
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/target/lag").as_str(),
+        AdminOperation(&NotificationTargetLag {}),
+    )?;
+
+    // Replay the journaled events of a target, target/{target_type}/{target_name}/replay?start=<RFC3339>&end=<RFC3339>
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/target/{target_type}/{target_name}/replay").as_str(),
+        AdminOperation(&ReplayNotificationTarget {}),
+    )?;
+
+    // Send ObjectCreated events for the existing objects of a bucket
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/event-backfill").as_str(),
+        AdminOperation(&event_backfill::StartEventBackfill {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/event-backfill").as_str(),
+        AdminOperation(&event_backfill::EventBackfillStatus {}),
+    )?;
+
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/event-backfill").as_str(),
+        AdminOperation(&event_backfill::CancelEventBackfill {}),
+    )?;
+
+    // Scheduled backups of buckets to a remote target
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-backup").as_str(),
+        AdminOperation(&bucket_backup::SetBucketBackup {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-backup").as_str(),
+        AdminOperation(&bucket_backup::GetBucketBackup {}),
+    )?;
+
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-backup").as_str(),
+        AdminOperation(&bucket_backup::RemoveBucketBackup {}),
+    )?;
+
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-backup/run").as_str(),
+        AdminOperation(&bucket_backup::StartBucketBackupRun {}),
+    )?;
+
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/bucket-backup/run").as_str(),
+        AdminOperation(&bucket_backup::CancelBucketBackupRun {}),
+    )?;
+
     // arns list
     r.insert(
         Method::GET,
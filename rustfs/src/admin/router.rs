@@ -34,6 +34,20 @@ use s3s::s3_error;
 use tower::Service;
 use tracing::error;
 
+/// GET endpoints served without authentication: health probes and profiling.
+const PUBLIC_PATHS: &[&str] = &[
+    "/health",
+    "/health/live",
+    "/health/ready",
+    "/health/cluster",
+    "/profile/cpu",
+    "/profile/memory",
+];
+
+fn is_public_path(path: &str) -> bool {
+    PUBLIC_PATHS.contains(&path)
+}
+
 pub struct S3Router<T> {
     router: Router<T>,
     console_enabled: bool,
@@ -85,7 +99,7 @@ where
 {
     fn is_match(&self, method: &Method, uri: &Uri, headers: &HeaderMap, _: &mut Extensions) -> bool {
         let path = uri.path();
-        if method == Method::GET && (path == "/health" || path == "/profile/cpu" || path == "/profile/memory") {
+        if method == Method::GET && is_public_path(path) {
             return true;
         }
 
@@ -105,7 +119,7 @@ where
     async fn check_access(&self, req: &mut S3Request<Body>) -> S3Result<()> {
         // Allow unauthenticated access to health check
         let path = req.uri.path();
-        if req.method == Method::GET && (path == "/health" || path == "/profile/cpu" || path == "/profile/memory") {
+        if req.method == Method::GET && is_public_path(path) {
             return Ok(());
         }
         // Allow unauthenticated access to console static files if console is enabled
@@ -125,6 +139,11 @@ where
             return Ok(());
         }
 
+        // STS requests are authenticated by the handler, AssumeRoleWithWebIdentity carries no signature
+        if req.method == Method::POST && path == "/" {
+            return Ok(());
+        }
+
         // For non-RPC admin requests, check credentials
         match req.credentials {
             Some(_) => Ok(()),
@@ -21,8 +21,11 @@ use hyper::Method;
 use matchit::Params;
 use rustfs_ecstore::disk::DiskAPI;
 use rustfs_ecstore::disk::WalkDirOptions;
+use rustfs_ecstore::hot_objects::GLOBAL_HOT_OBJECTS;
 use rustfs_ecstore::set_disk::DEFAULT_READ_BUFFER_SIZE;
+use rustfs_ecstore::speedtest::{DrivePerfOptions, MAX_SPEEDTEST_OBJECT_SIZE, local_drive_perf, net_perf_payload};
 use rustfs_ecstore::store::find_local_disk;
+use rustfs_ecstore::top::{MAX_TOP_COUNT, local_top_apis, local_top_locks};
 use rustfs_utils::net::bytes_stream;
 use s3s::Body;
 use s3s::S3Request;
@@ -68,6 +71,66 @@ pub fn register_rpc_route(r: &mut S3Router<AdminOperation>) -> std::io::Result<(
         AdminOperation(&WalkDir {}),
     )?;
 
+    r.insert(
+        Method::GET,
+        format!("{}{}", RPC_PREFIX, "/drive_perf").as_str(),
+        AdminOperation(&DrivePerf {}),
+    )?;
+
+    r.insert(
+        Method::HEAD,
+        format!("{}{}", RPC_PREFIX, "/drive_perf").as_str(),
+        AdminOperation(&DrivePerf {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", RPC_PREFIX, "/net_perf").as_str(),
+        AdminOperation(&NetPerf {}),
+    )?;
+
+    r.insert(
+        Method::HEAD,
+        format!("{}{}", RPC_PREFIX, "/net_perf").as_str(),
+        AdminOperation(&NetPerf {}),
+    )?;
+
+    r.insert(
+        Method::PUT,
+        format!("{}{}", RPC_PREFIX, "/net_perf").as_str(),
+        AdminOperation(&NetPerf {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", RPC_PREFIX, "/top_locks").as_str(),
+        AdminOperation(&TopLocks {}),
+    )?;
+
+    r.insert(
+        Method::HEAD,
+        format!("{}{}", RPC_PREFIX, "/top_locks").as_str(),
+        AdminOperation(&TopLocks {}),
+    )?;
+
+    r.insert(
+        Method::GET,
+        format!("{}{}", RPC_PREFIX, "/top_apis").as_str(),
+        AdminOperation(&TopApis {}),
+    )?;
+
+    r.insert(
+        Method::HEAD,
+        format!("{}{}", RPC_PREFIX, "/top_apis").as_str(),
+        AdminOperation(&TopApis {}),
+    )?;
+
+    r.insert(
+        Method::POST,
+        format!("{}{}", RPC_PREFIX, "/hot_object_invalidate").as_str(),
+        AdminOperation(&HotObjectInvalidate {}),
+    )?;
+
     Ok(())
 }
 
@@ -218,3 +281,161 @@ impl Operation for PutFile {
         Ok(S3Response::new((StatusCode::OK, Body::empty())))
     }
 }
+
+// /rustfs/rpc/drive_perf?disk={}&file_size={}&block_size={}&random_reads={}
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct DrivePerfQuery {
+    disk: String,
+    file_size: u64,
+    block_size: u64,
+    random_reads: u64,
+}
+pub struct DrivePerf {}
+#[async_trait::async_trait]
+impl Operation for DrivePerf {
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        if req.method == Method::HEAD {
+            return Ok(S3Response::new((StatusCode::OK, Body::empty())));
+        }
+        let query = {
+            if let Some(query) = req.uri.query() {
+                let input: DrivePerfQuery =
+                    from_bytes(query.as_bytes()).map_err(|e| s3_error!(InvalidArgument, "get query failed1 {:?}", e))?;
+                input
+            } else {
+                DrivePerfQuery::default()
+            }
+        };
+
+        let opts = DrivePerfOptions {
+            file_size: query.file_size,
+            block_size: query.block_size,
+            random_reads: query.random_reads,
+            serial: false,
+        };
+        opts.validate().map_err(|e| s3_error!(InvalidArgument, "{}", e))?;
+
+        let Some(disk) = find_local_disk(&query.disk).await else {
+            return Err(s3_error!(InvalidArgument, "disk not found"));
+        };
+
+        let result = local_drive_perf(&disk, &opts).await;
+        let data = serde_json::to_vec(&result).map_err(|e| s3_error!(InternalError, "marshal result err {}", e))?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::from(data))))
+    }
+}
+
+// /rustfs/rpc/net_perf?size={}
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct NetPerfQuery {
+    size: u64,
+}
+pub struct NetPerf {}
+#[async_trait::async_trait]
+impl Operation for NetPerf {
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        if req.method == Method::HEAD {
+            return Ok(S3Response::new((StatusCode::OK, Body::empty())));
+        }
+
+        if req.method == Method::PUT {
+            let mut body = req.input;
+            while let Some(item) = body.next().await {
+                item.map_err(|e| s3_error!(InternalError, "body stream err {}", e))?;
+            }
+            return Ok(S3Response::new((StatusCode::OK, Body::empty())));
+        }
+
+        let query = {
+            if let Some(query) = req.uri.query() {
+                let input: NetPerfQuery =
+                    from_bytes(query.as_bytes()).map_err(|e| s3_error!(InvalidArgument, "get query failed1 {:?}", e))?;
+                input
+            } else {
+                NetPerfQuery::default()
+            }
+        };
+
+        if query.size > MAX_SPEEDTEST_OBJECT_SIZE {
+            return Err(s3_error!(InvalidArgument, "size too large"));
+        }
+
+        Ok(S3Response::new((
+            StatusCode::OK,
+            Body::from(StreamingBlob::wrap(net_perf_payload(query.size))),
+        )))
+    }
+}
+
+// /rustfs/rpc/top_locks?count={}
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct TopQuery {
+    count: usize,
+}
+
+fn parse_top_query(req: &S3Request<Body>) -> S3Result<usize> {
+    let query = {
+        if let Some(query) = req.uri.query() {
+            let input: TopQuery =
+                from_bytes(query.as_bytes()).map_err(|e| s3_error!(InvalidArgument, "get query failed1 {:?}", e))?;
+            input
+        } else {
+            TopQuery::default()
+        }
+    };
+    Ok(query.count.min(MAX_TOP_COUNT))
+}
+
+pub struct TopLocks {}
+#[async_trait::async_trait]
+impl Operation for TopLocks {
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        if req.method == Method::HEAD {
+            return Ok(S3Response::new((StatusCode::OK, Body::empty())));
+        }
+        let count = parse_top_query(&req)?;
+
+        let locks = local_top_locks(count).await;
+        let data = serde_json::to_vec(&locks).map_err(|e| s3_error!(InternalError, "marshal locks err {}", e))?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::from(data))))
+    }
+}
+
+// /rustfs/rpc/top_apis?count={}
+pub struct TopApis {}
+#[async_trait::async_trait]
+impl Operation for TopApis {
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        if req.method == Method::HEAD {
+            return Ok(S3Response::new((StatusCode::OK, Body::empty())));
+        }
+        let count = parse_top_query(&req)?;
+
+        let apis = local_top_apis(count).await;
+        let data = serde_json::to_vec(&apis).map_err(|e| s3_error!(InternalError, "marshal apis err {}", e))?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::from(data))))
+    }
+}
+
+// /rustfs/rpc/hot_object_invalidate?bucket={}&object={}
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct HotObjectInvalidateQuery {
+    bucket: String,
+    #[serde(default)]
+    object: String,
+}
+pub struct HotObjectInvalidate {}
+#[async_trait::async_trait]
+impl Operation for HotObjectInvalidate {
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query: HotObjectInvalidateQuery = from_bytes(req.uri.query().unwrap_or_default().as_bytes())
+            .map_err(|e| s3_error!(InvalidArgument, "get query failed1 {:?}", e))?;
+
+        GLOBAL_HOT_OBJECTS.invalidate(&query.bucket, &query.object).await;
+
+        Ok(S3Response::new((StatusCode::OK, Body::empty())))
+    }
+}
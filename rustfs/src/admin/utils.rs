@@ -15,3 +15,67 @@
 pub(crate) fn has_space_be(s: &str) -> bool {
     s.trim().len() != s.len()
 }
+
+/// Pagination parameters accepted by admin list APIs, `?marker=<last name>&limit=<n>`.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub(crate) struct PageQuery {
+    pub marker: String,
+    pub limit: usize,
+}
+
+impl PageQuery {
+    /// Whether the caller asked for a page rather than the whole listing.
+    pub(crate) fn is_paged(&self) -> bool {
+        !self.marker.is_empty() || self.limit > 0
+    }
+
+    /// Sort `names` and keep those after the marker, at most `limit` of them when set.
+    ///
+    /// Returns the page and, when more names remain, the marker to request the next page with.
+    pub(crate) fn apply(&self, mut names: Vec<String>) -> (Vec<String>, Option<String>) {
+        names.sort();
+        names.dedup();
+        names.retain(|name| self.marker.is_empty() || name.as_str() > self.marker.as_str());
+
+        if self.limit == 0 || names.len() <= self.limit {
+            return (names, None);
+        }
+
+        names.truncate(self.limit);
+        let next_marker = names.last().cloned();
+        (names, next_marker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_page_query_apply() {
+        let all = names(&["dev", "admin", "ops", "qa"]);
+
+        let query = PageQuery::default();
+        assert!(!query.is_paged());
+        assert_eq!(query.apply(all.clone()), (names(&["admin", "dev", "ops", "qa"]), None));
+
+        let query = PageQuery {
+            marker: String::new(),
+            limit: 2,
+        };
+        let (page, next) = query.apply(all.clone());
+        assert_eq!(page, names(&["admin", "dev"]));
+        assert_eq!(next.as_deref(), Some("dev"));
+
+        let query = PageQuery {
+            marker: "dev".to_string(),
+            limit: 2,
+        };
+        assert_eq!(query.apply(all), (names(&["ops", "qa"]), None));
+    }
+}
@@ -206,6 +206,8 @@ impl From<StorageError> for ApiError {
             StorageError::PrefixAccessDenied(_, _) => S3ErrorCode::AccessDenied,
             StorageError::InvalidUploadIDKeyCombination(_, _) => S3ErrorCode::InvalidArgument,
             StorageError::ObjectNameTooLong(_, _) => S3ErrorCode::InvalidArgument,
+            StorageError::InvalidObjectName(_, _, _) => S3ErrorCode::Custom("InvalidObjectName".into()),
+            StorageError::BucketModeViolation(_, _, _) => S3ErrorCode::AccessDenied,
             StorageError::ObjectNamePrefixAsSlash(_, _) => S3ErrorCode::InvalidArgument,
             StorageError::ObjectNotFound(_, _) => S3ErrorCode::NoSuchKey,
             StorageError::ConfigNotFound => S3ErrorCode::NoSuchKey,
@@ -221,10 +223,14 @@ impl From<StorageError> for ApiError {
             StorageError::EntityTooSmall(_, _, _) => S3ErrorCode::EntityTooSmall,
             StorageError::PreconditionFailed => S3ErrorCode::PreconditionFailed,
             StorageError::InvalidRangeSpec(_) => S3ErrorCode::InvalidRange,
+            StorageError::Io(e) if e.get_ref().is_some_and(|e| rustfs_rio::is_bad_digest(e)) => S3ErrorCode::BadDigest,
             _ => S3ErrorCode::InternalError,
         };
 
-        let message = if code == S3ErrorCode::InternalError {
+        // Name and mode violations explain which rule of the bucket was broken
+        let message = if code == S3ErrorCode::InternalError
+            || matches!(err, StorageError::InvalidObjectName(..) | StorageError::BucketModeViolation(..))
+        {
             err.to_string()
         } else {
             ApiError::error_code_to_message(&code)
@@ -370,6 +376,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_api_error_from_bad_digest() {
+        let io_error = IoError::new(
+            ErrorKind::InvalidData,
+            rustfs_rio::BadDigest {
+                expected_md5: "a".into(),
+                calculated_md5: "b".into(),
+            },
+        );
+        let api_error: ApiError = StorageError::from(io_error).into();
+        assert_eq!(api_error.code, S3ErrorCode::BadDigest);
+
+        let api_error: ApiError = StorageError::from(IoError::new(ErrorKind::InvalidData, "other")).into();
+        assert_eq!(api_error.code, S3ErrorCode::InternalError);
+    }
+
     #[test]
     fn test_api_error_from_iam_error() {
         let iam_error = rustfs_iam::error::Error::other("IAM test error");
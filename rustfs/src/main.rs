@@ -27,9 +27,10 @@ mod version;
 
 // Ensure the correct path for parse_license is imported
 use crate::server::{
-    SHUTDOWN_TIMEOUT, ServiceState, ServiceStateManager, ShutdownSignal, init_event_notifier, shutdown_event_notifier,
-    start_audit_system, start_http_server, stop_audit_system, wait_for_shutdown,
+    SHUTDOWN_TIMEOUT, ServiceState, ServiceStateManager, ShutdownSignal, init_auth_chain, init_event_notifier,
+    shutdown_event_notifier, start_audit_system, start_http_server, stop_audit_system, wait_for_shutdown,
 };
+use crate::storage::bucket_backup::init_bucket_backup;
 use crate::storage::ecfs::{process_lambda_configurations, process_queue_configurations, process_topic_configurations};
 use chrono::Datelike;
 use clap::Parser;
@@ -38,14 +39,27 @@ use rustfs_ahm::{
     Scanner, create_ahm_services_cancel_token, heal::storage::ECStoreHealStorage, init_heal_manager,
     scanner::data_scanner::ScannerConfig, shutdown_ahm_services,
 };
-use rustfs_common::globals::set_global_addr;
+use rustfs_common::globals::{GLOBAL_Local_Node_Name, set_global_addr};
 use rustfs_config::DEFAULT_UPDATE_CHECK;
 use rustfs_config::ENV_UPDATE_CHECK;
+use rustfs_ecstore::bandwidth::init_bandwidth_sys;
+use rustfs_ecstore::bucket::dedup::init_dedup_sys;
 use rustfs_ecstore::bucket::metadata_sys;
 use rustfs_ecstore::bucket::metadata_sys::init_bucket_metadata_sys;
+use rustfs_ecstore::bucket::purge::init_bucket_purge_sys;
 use rustfs_ecstore::bucket::replication::{GLOBAL_REPLICATION_POOL, init_background_replication};
+use rustfs_ecstore::compat::init_compat_sys;
 use rustfs_ecstore::config as ecconfig;
 use rustfs_ecstore::config::GLOBAL_CONFIG_SYS;
+use rustfs_ecstore::config::backup::init_config_backup;
+use rustfs_ecstore::config::change_log::init_config_log;
+use rustfs_ecstore::disk_replacement::init_disk_replacement_sys;
+use rustfs_ecstore::dns_discovery::start_dns_discovery_watch;
+use rustfs_ecstore::drive_health::init_drive_health;
+use rustfs_ecstore::federation::init_federation_sys;
+use rustfs_ecstore::maintenance::init_maintenance_sys;
+use rustfs_ecstore::metadata_index::init_metadata_index;
+use rustfs_ecstore::presign::init_presign_sys;
 use rustfs_ecstore::store_api::BucketOptions;
 use rustfs_ecstore::{
     StorageAPI,
@@ -58,6 +72,7 @@ use rustfs_ecstore::{
     update_erasure_type,
 };
 use rustfs_iam::init_iam_sys;
+use rustfs_iam::last_used::init_key_usage_tracker;
 use rustfs_notify::notifier_global;
 use rustfs_obs::{init_obs, set_global_guard};
 use rustfs_targets::arn::TargetID;
@@ -240,6 +255,8 @@ async fn run(opt: config::Opt) -> Result<()> {
 
     let ctx = CancellationToken::new();
 
+    start_dns_discovery_watch(opt.volumes.clone(), endpoint_pools.clone(), ctx.clone());
+
     // init store
     let store = ECStore::new(server_addr, endpoint_pools.clone(), ctx.clone())
         .await
@@ -250,6 +267,8 @@ async fn run(opt: config::Opt) -> Result<()> {
     ecconfig::init();
     // config system configuration
     GLOBAL_CONFIG_SYS.init(store.clone()).await?;
+    // authenticators ahead of the signature check, as configured
+    init_auth_chain().map_err(Error::other)?;
 
     // init  replication_pool
     init_background_replication(store.clone()).await;
@@ -284,8 +303,36 @@ async fn run(opt: config::Opt) -> Result<()> {
 
     init_bucket_metadata_sys(store.clone(), buckets.clone()).await;
 
+    init_config_log(store.clone(), ctx.clone()).await;
+
+    init_config_backup(store.clone(), ctx.clone()).await;
+
     init_iam_sys(store.clone()).await.map_err(Error::other)?;
 
+    init_key_usage_tracker(store.clone(), GLOBAL_Local_Node_Name.read().await.clone(), ctx.clone()).await;
+
+    init_maintenance_sys(store.clone(), ctx.clone()).await;
+
+    init_bandwidth_sys(store.clone(), ctx.clone()).await;
+
+    init_disk_replacement_sys(store.clone(), ctx.clone()).await;
+
+    init_drive_health(ctx.clone()).await;
+
+    init_compat_sys(store.clone(), ctx.clone()).await;
+
+    init_federation_sys(store.clone(), ctx.clone()).await;
+
+    init_presign_sys(store.clone(), ctx.clone()).await;
+
+    init_bucket_purge_sys(store.clone(), ctx.clone()).await;
+
+    init_dedup_sys(store.clone(), ctx.clone()).await;
+
+    init_bucket_backup(store.clone(), ctx.clone()).await;
+
+    init_metadata_index(ctx.clone());
+
     add_bucket_notification_configuration(buckets.clone()).await;
 
     // Initialize the global notification system
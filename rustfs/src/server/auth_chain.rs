@@ -0,0 +1,353 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Chain of authenticators S3 requests go through before the AWS signature check.
+//!
+//! The `order` of the `auth_chain` config sub-system names the steps. Each step either
+//! establishes the access key the request acts as, denies the request, or passes it on to the next
+//! one. A request no step authenticated is checked by `sigv4` as usual, so the chain only adds
+//! ways in. Steps are registered by name with [`register_authenticator`] before the chain is built.
+
+use crate::admin::{ADMIN_PREFIX, console::CONSOLE_PREFIX, rpc::RPC_PREFIX};
+use http::request::Parts;
+use http::{HeaderValue, Request as HttpRequest, Response, StatusCode};
+use rustfs_config::{AUTH_CHAIN_HMAC_TOKEN, AUTH_CHAIN_SIGV4};
+use rustfs_ecstore::config::GLOBAL_SERVER_CONFIG;
+use rustfs_ecstore::config::auth_chain::lookup_order;
+use rustfs_ecstore::global::get_global_action_cred;
+use rustfs_utils::hmac_sha256;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::task::{Context, Poll};
+use time::OffsetDateTime;
+use tower::{Layer, Service};
+use tracing::{debug, info};
+
+/// Identity a step of the chain established, used by the access check in place of signature credentials.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainIdentity {
+    pub access_key: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthOutcome {
+    /// Not for this step, ask the next one.
+    Pass,
+    /// The request acts as this access key.
+    Authenticated(String),
+    /// The request carried credentials of this step that are not valid.
+    Denied(String),
+}
+
+/// One step of the authentication chain. A step may remove the headers it consumed from `parts`.
+#[async_trait::async_trait]
+pub trait Authenticator: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn authenticate(&self, parts: &mut Parts) -> AuthOutcome;
+}
+
+type AuthenticatorRef = Arc<dyn Authenticator>;
+
+static AUTHENTICATORS: LazyLock<RwLock<HashMap<String, AuthenticatorRef>>> = LazyLock::new(|| {
+    let builtin: AuthenticatorRef = Arc::new(HmacTokenAuthenticator);
+    RwLock::new(HashMap::from([(builtin.name().to_owned(), builtin)]))
+});
+
+static AUTH_CHAIN: LazyLock<RwLock<Arc<Vec<AuthenticatorRef>>>> = LazyLock::new(Default::default);
+
+/// Make `authenticator` available to the chain under its name, replacing one of the same name.
+#[allow(dead_code)]
+pub fn register_authenticator(authenticator: AuthenticatorRef) {
+    let name = authenticator.name().to_owned();
+    AUTHENTICATORS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name, authenticator);
+}
+
+fn build_chain(order: &[String]) -> Result<Vec<AuthenticatorRef>, String> {
+    let registered = AUTHENTICATORS.read().unwrap_or_else(|e| e.into_inner());
+    let mut chain = Vec::with_capacity(order.len());
+    for (i, name) in order.iter().enumerate() {
+        if name == AUTH_CHAIN_SIGV4 {
+            if i + 1 != order.len() {
+                return Err(format!("{AUTH_CHAIN_SIGV4} must be the last step of the authentication chain"));
+            }
+            break;
+        }
+        let Some(authenticator) = registered.get(name) else {
+            return Err(format!("unknown authenticator {name}"));
+        };
+        if chain.iter().any(|a: &AuthenticatorRef| a.name() == name) {
+            return Err(format!("authenticator {name} is named twice"));
+        }
+        chain.push(authenticator.clone());
+    }
+    Ok(chain)
+}
+
+/// Build the chain from the `auth_chain` config, to be called once the server config is loaded.
+pub fn init_auth_chain() -> Result<(), String> {
+    let order = GLOBAL_SERVER_CONFIG.get().map(lookup_order).unwrap_or_default();
+    let chain = build_chain(&order)?;
+    info!(
+        "authentication chain: {}",
+        chain
+            .iter()
+            .map(|a| a.name())
+            .chain([AUTH_CHAIN_SIGV4])
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    *AUTH_CHAIN.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(chain);
+    Ok(())
+}
+
+fn current_chain() -> Arc<Vec<AuthenticatorRef>> {
+    AUTH_CHAIN.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Run `parts` through `chain`, recording the identity of the first step that authenticates it.
+async fn authenticate(chain: &[AuthenticatorRef], parts: &mut Parts) -> Result<(), String> {
+    // Only the chain decides who a request is, never the client
+    parts.extensions.remove::<ChainIdentity>();
+
+    for authenticator in chain.iter() {
+        match authenticator.authenticate(parts).await {
+            AuthOutcome::Pass => continue,
+            AuthOutcome::Authenticated(access_key) => {
+                debug!("request authenticated by {} as {}", authenticator.name(), access_key);
+                parts.extensions.insert(ChainIdentity { access_key });
+                return Ok(());
+            }
+            AuthOutcome::Denied(reason) => return Err(reason),
+        }
+    }
+    Ok(())
+}
+
+fn access_denied(reason: &str) -> Response<s3s::Body> {
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Error><Code>AccessDenied</Code><Message>{}</Message></Error>",
+        reason.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+    );
+    let mut res = Response::new(s3s::Body::from(body));
+    *res.status_mut() = StatusCode::FORBIDDEN;
+    res.headers_mut()
+        .insert(http::header::CONTENT_TYPE, HeaderValue::from_static("application/xml"));
+    res
+}
+
+/// Runs S3 requests through the authentication chain
+#[derive(Clone)]
+pub struct AuthChainLayer;
+
+impl<S> Layer<S> for AuthChainLayer {
+    type Service = AuthChainService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthChainService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthChainService<S> {
+    inner: S,
+}
+
+impl<S, B> Service<HttpRequest<B>> for AuthChainService<S>
+where
+    S: Service<HttpRequest<B>, Response = Response<s3s::Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+    B: Send + 'static,
+{
+    type Response = Response<s3s::Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest<B>) -> Self::Future {
+        let path = req.uri().path();
+        let chain = current_chain();
+        if chain.is_empty() || path.starts_with(ADMIN_PREFIX) || path.starts_with(RPC_PREFIX) || path.starts_with(CONSOLE_PREFIX)
+        {
+            return Box::pin(self.inner.call(req));
+        }
+
+        // The ready service handles this request, a clone takes its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            let (mut parts, body) = req.into_parts();
+            if let Err(reason) = authenticate(&chain, &mut parts).await {
+                return Ok(access_denied(&reason));
+            }
+            inner.call(HttpRequest::from_parts(parts, body)).await
+        })
+    }
+}
+
+/// Header of the tokens accepted by [`HmacTokenAuthenticator`].
+pub const HMAC_TOKEN_HEADER: &str = "x-rustfs-auth";
+
+const HMAC_TOKEN_SCHEME: &str = "RUSTFS-HMAC-SHA256";
+
+/// Accepts `x-rustfs-auth: RUSTFS-HMAC-SHA256 Credential=<access key>,Expires=<unix time>,Signature=<hex>`,
+/// the signature being the HMAC-SHA256, keyed by the secret key, of the method, the path and query,
+/// and the expiry, separated by newlines. The body is not signed, the token suits internal clients
+/// that cannot compute AWS signatures.
+pub struct HmacTokenAuthenticator;
+
+fn hmac_token_string_to_sign(parts: &Parts, expires: i64) -> String {
+    let path_and_query = parts.uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/");
+    format!("{}\n{}\n{}", parts.method, path_and_query, expires)
+}
+
+fn parse_hmac_token(value: &str) -> Option<(&str, i64, &str)> {
+    let fields = value.strip_prefix(HMAC_TOKEN_SCHEME)?.trim();
+    let (mut credential, mut expires, mut signature) = (None, None, None);
+    for field in fields.split(',') {
+        match field.trim().split_once('=')? {
+            ("Credential", v) => credential = Some(v),
+            ("Expires", v) => expires = v.parse().ok(),
+            ("Signature", v) => signature = Some(v),
+            _ => return None,
+        }
+    }
+    Some((credential?, expires?, signature?))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn secret_key_of(access_key: &str) -> Option<String> {
+    if let Some(cred) = get_global_action_cred()
+        && cred.access_key == access_key
+    {
+        return Some(cred.secret_key);
+    }
+    let iam = rustfs_iam::get().ok()?;
+    iam.get_user(access_key).await.map(|u| u.credentials.secret_key)
+}
+
+#[async_trait::async_trait]
+impl Authenticator for HmacTokenAuthenticator {
+    fn name(&self) -> &str {
+        AUTH_CHAIN_HMAC_TOKEN
+    }
+
+    async fn authenticate(&self, parts: &mut Parts) -> AuthOutcome {
+        let Some(value) = parts.headers.remove(HMAC_TOKEN_HEADER) else {
+            return AuthOutcome::Pass;
+        };
+        let Some((access_key, expires, signature)) = value.to_str().ok().and_then(parse_hmac_token) else {
+            return AuthOutcome::Denied("malformed authentication token".to_owned());
+        };
+        if expires < OffsetDateTime::now_utc().unix_timestamp() {
+            return AuthOutcome::Denied("authentication token expired".to_owned());
+        }
+        let Some(secret_key) = secret_key_of(access_key).await else {
+            return AuthOutcome::Denied("unknown access key".to_owned());
+        };
+
+        let expected = hmac_sha256(secret_key, hmac_token_string_to_sign(parts, expires));
+        let expected = hex_simd::encode_to_string(expected, hex_simd::AsciiCase::Lower);
+        if !constant_time_eq(expected.as_bytes(), signature.as_bytes()) {
+            return AuthOutcome::Denied("authentication token signature does not match".to_owned());
+        }
+        AuthOutcome::Authenticated(access_key.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(&'static str, AuthOutcome);
+
+    #[async_trait::async_trait]
+    impl Authenticator for Fixed {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        async fn authenticate(&self, _parts: &mut Parts) -> AuthOutcome {
+            self.1.clone()
+        }
+    }
+
+    fn parts() -> Parts {
+        HttpRequest::get("/bucket/key?versionId=1").body(()).unwrap().into_parts().0
+    }
+
+    #[tokio::test]
+    async fn test_chain_order() {
+        let pass: AuthenticatorRef = Arc::new(Fixed("pass", AuthOutcome::Pass));
+        let user: AuthenticatorRef = Arc::new(Fixed("user", AuthOutcome::Authenticated("user".to_owned())));
+        let deny: AuthenticatorRef = Arc::new(Fixed("deny", AuthOutcome::Denied("no".to_owned())));
+
+        let mut p = parts();
+        authenticate(&[pass.clone(), user.clone(), deny.clone()], &mut p)
+            .await
+            .unwrap();
+        assert_eq!(p.extensions.get::<ChainIdentity>().unwrap().access_key, "user");
+
+        let mut p = parts();
+        assert!(authenticate(&[deny, user], &mut p).await.is_err());
+
+        // Nothing authenticated, left to the signature check
+        let mut p = parts();
+        p.extensions.insert(ChainIdentity {
+            access_key: "forged".to_owned(),
+        });
+        authenticate(&[pass], &mut p).await.unwrap();
+        assert!(p.extensions.get::<ChainIdentity>().is_none());
+    }
+
+    #[test]
+    fn test_build_chain() {
+        let order = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        assert!(build_chain(&order(&["sigv4"])).unwrap().is_empty());
+        let chain = build_chain(&order(&["hmac_token", "sigv4"])).unwrap();
+        assert_eq!(chain.iter().map(|a| a.name()).collect::<Vec<_>>(), vec!["hmac_token"]);
+        assert_eq!(build_chain(&order(&["hmac_token"])).unwrap().len(), 1);
+        assert!(build_chain(&order(&["sigv4", "hmac_token"])).is_err());
+        assert!(build_chain(&order(&["missing"])).is_err());
+        assert!(build_chain(&order(&["hmac_token", "hmac_token"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_hmac_token() {
+        assert_eq!(
+            parse_hmac_token("RUSTFS-HMAC-SHA256 Credential=ak,Expires=100,Signature=ab"),
+            Some(("ak", 100, "ab"))
+        );
+        assert_eq!(
+            parse_hmac_token("RUSTFS-HMAC-SHA256 Credential=ak, Expires=100, Signature=ab"),
+            Some(("ak", 100, "ab"))
+        );
+        assert!(parse_hmac_token("RUSTFS-HMAC-SHA256 Credential=ak,Signature=ab").is_none());
+        assert!(parse_hmac_token("AWS4-HMAC-SHA256 Credential=ak,Expires=100,Signature=ab").is_none());
+        assert_eq!(hmac_token_string_to_sign(&parts(), 100), "GET\n/bucket/key?versionId=1\n100".to_owned());
+    }
+}
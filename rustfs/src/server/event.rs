@@ -12,9 +12,17 @@
 //  See the License for the specific language governing permissions and
 //  limitations under the License.
 
+use rustfs_common::scanner_events::{BucketCycleSummary, subscribe_bucket_cycles};
 use rustfs_config::DEFAULT_DELIMITER;
 use rustfs_ecstore::config::GLOBAL_SERVER_CONFIG;
-use tracing::{error, info, instrument, warn};
+use rustfs_ecstore::drive_health::{DriveHealthAlert, subscribe_drive_health_alerts};
+use rustfs_ecstore::global::GLOBAL_LocalNodeName;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store_api::{BucketOptions, ObjectInfo, StorageAPI};
+use rustfs_notify::{DRIVE_ELEMENT_PREFIX, EventArgs, EventArgsBuilder, SCANNER_ELEMENT_PREFIX, notifier_global};
+use rustfs_targets::EventName;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info, instrument, warn};
 
 /// Shuts down the event notifier system gracefully
 pub(crate) async fn shutdown_event_notifier() {
@@ -87,5 +95,111 @@ pub(crate) async fn init_event_notifier() {
             target: "rustfs::main::init_event_notifier",
             "Event notifier system initialized successfully."
         );
+        forward_scanner_events();
+        forward_drive_health_alerts();
     }
 }
+
+/// Turns the buckets finished by the scanner into `s3:Scanner:CycleComplete` events.
+fn forward_scanner_events() {
+    let mut cycles = subscribe_bucket_cycles();
+    tokio::spawn(async move {
+        loop {
+            match cycles.recv().await {
+                Ok(summary) => notifier_global::notify(scanner_cycle_event(&summary)).await,
+                Err(RecvError::Lagged(skipped)) => debug!("Skipped {} scanner cycle events", skipped),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+fn scanner_cycle_event(summary: &BucketCycleSummary) -> EventArgs {
+    let object = ObjectInfo {
+        bucket: summary.bucket.clone(),
+        size: summary.size as i64,
+        ..Default::default()
+    };
+    let elements = [
+        ("cycle", summary.cycle.to_string()),
+        ("objects", summary.objects_count.to_string()),
+        ("versions", summary.versions_count.to_string()),
+        ("delete-markers", summary.delete_markers_count.to_string()),
+        ("size", summary.size.to_string()),
+        ("objects-delta", summary.objects_delta.to_string()),
+        ("versions-delta", summary.versions_delta.to_string()),
+        ("size-delta", summary.size_delta.to_string()),
+        ("heals-queued", summary.heals_queued.to_string()),
+        ("changed", summary.has_changes().to_string()),
+    ];
+
+    elements
+        .into_iter()
+        .fold(
+            EventArgsBuilder::new(EventName::ScannerCycleComplete, summary.bucket.clone(), object),
+            |builder, (name, value)| builder.resp_element(format!("{SCANNER_ELEMENT_PREFIX}{name}"), value),
+        )
+        .host(GLOBAL_LocalNodeName.to_string())
+        .user_agent("Internal: [Scanner]")
+        .build()
+}
+
+/// Turns the alerts of the local drives into `s3:Drive:HealthDegraded` events. A drive belongs to
+/// no bucket, so the event goes to every bucket subscribed to it.
+fn forward_drive_health_alerts() {
+    let mut alerts = subscribe_drive_health_alerts();
+    tokio::spawn(async move {
+        loop {
+            match alerts.recv().await {
+                Ok(alert) => {
+                    let Some(store) = new_object_layer_fn() else {
+                        continue;
+                    };
+                    let buckets = match store.list_bucket(&BucketOptions::default()).await {
+                        Ok(buckets) => buckets,
+                        Err(e) => {
+                            warn!("list buckets for drive health alert failed: {:?}", e);
+                            continue;
+                        }
+                    };
+                    for bucket in buckets {
+                        notifier_global::notify(drive_health_event(&bucket.name, &alert)).await;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => debug!("Skipped {} drive health alerts", skipped),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+fn drive_health_event(bucket: &str, alert: &DriveHealthAlert) -> EventArgs {
+    let object = ObjectInfo {
+        bucket: bucket.to_string(),
+        ..Default::default()
+    };
+    let health = &alert.health;
+    let counter = |value: Option<u64>| value.map(|v| v.to_string()).unwrap_or_default();
+    let elements = [
+        ("endpoint", alert.endpoint.clone()),
+        ("device", health.device.clone()),
+        ("model", health.model.clone()),
+        ("serial", health.serial.clone()),
+        ("passed", health.passed.map(|passed| passed.to_string()).unwrap_or_default()),
+        ("reallocated-sectors", counter(health.reallocated_sectors)),
+        ("pending-sectors", counter(health.pending_sectors)),
+        ("uncorrectable-sectors", counter(health.uncorrectable_sectors)),
+        ("media-errors", counter(health.media_errors)),
+        ("reasons", alert.reasons.join("; ")),
+    ];
+
+    elements
+        .into_iter()
+        .fold(
+            EventArgsBuilder::new(EventName::DriveHealthDegraded, bucket.to_string(), object),
+            |builder, (name, value)| builder.resp_element(format!("{DRIVE_ELEMENT_PREFIX}{name}"), value),
+        )
+        .host(GLOBAL_LocalNodeName.to_string())
+        .user_agent("Internal: [DriveHealth]")
+        .build()
+}
@@ -16,7 +16,11 @@
 use crate::admin;
 use crate::auth::IAMAuth;
 use crate::config;
-use crate::server::{ServiceState, ServiceStateManager, hybrid::hybrid, layer::RedirectLayer};
+use crate::server::{
+    ServiceState, ServiceStateManager, auth_chain::AuthChainLayer, hybrid::hybrid, layer::ApiTimeoutLayer,
+    layer::BucketRegionLayer, layer::ConnectionStatsLayer, layer::FederationRedirectLayer, layer::ProtocolStatsLayer,
+    layer::RedirectLayer, layer::RequestIdLayer, layer::S3ErrorBodyLayer, layer::UploadGuardLayer,
+};
 use crate::storage;
 use crate::storage::tonic_service::make_server;
 use bytes::Bytes;
@@ -28,9 +32,18 @@ use hyper_util::{
     service::TowerToHyperService,
 };
 use metrics::{counter, histogram};
-use rustfs_config::{DEFAULT_ACCESS_KEY, DEFAULT_SECRET_KEY, MI_B, RUSTFS_TLS_CERT, RUSTFS_TLS_KEY};
+use rustfs_config::{
+    DEFAULT_ACCESS_KEY, DEFAULT_HTTP2_ADAPTIVE_WINDOW, DEFAULT_HTTP2_CONNECTION_WINDOW_SIZE,
+    DEFAULT_HTTP2_MAX_CONCURRENT_STREAMS, DEFAULT_HTTP2_MAX_FRAME_SIZE, DEFAULT_HTTP2_MAX_SEND_BUF_SIZE,
+    DEFAULT_HTTP2_STREAM_WINDOW_SIZE, DEFAULT_HTTP3_ADDRESS, DEFAULT_SECRET_KEY, ENV_HTTP2_ADAPTIVE_WINDOW,
+    ENV_HTTP2_CONNECTION_WINDOW_SIZE, ENV_HTTP2_MAX_CONCURRENT_STREAMS, ENV_HTTP2_MAX_FRAME_SIZE, ENV_HTTP2_MAX_SEND_BUF_SIZE,
+    ENV_HTTP2_STREAM_WINDOW_SIZE, ENV_HTTP3_ADDRESS, MI_B, RUSTFS_TLS_CERT, RUSTFS_TLS_KEY,
+};
+use rustfs_ecstore::request_io::RequestIo;
 use rustfs_protos::proto_gen::node_service::node_service_server::NodeServiceServer;
+use rustfs_utils::http::AMZ_REQUEST_ID;
 use rustfs_utils::net::parse_and_resolve_address;
+use rustfs_utils::{get_env_bool, get_env_str, get_env_u32, get_env_usize};
 use rustls::ServerConfig;
 use s3s::{host::MultiDomain, service::S3Service, service::S3ServiceBuilder};
 use socket2::SockRef;
@@ -41,7 +54,7 @@ use std::time::Duration;
 use tokio::net::{TcpListener, TcpStream};
 use tokio_rustls::TlsAcceptor;
 use tonic::{Request, Status, metadata::MetadataValue};
-use tower::ServiceBuilder;
+use tower::{Layer, ServiceBuilder};
 use tower_http::catch_panic::CatchPanicLayer;
 use tower_http::compression::CompressionLayer;
 use tower_http::cors::{AllowOrigin, Any, CorsLayer};
@@ -153,8 +166,8 @@ pub async fn start_http_server(
             local_addr.ip()
         }
     };
-    let tls_acceptor = setup_tls_acceptor(opt.tls_path.as_deref().unwrap_or_default()).await?;
-    let tls_enabled = tls_acceptor.is_some();
+    let tls_config = load_tls_config(opt.tls_path.as_deref().unwrap_or_default()).await?;
+    let tls_enabled = tls_config.is_some();
     let protocol = if tls_enabled { "https" } else { "http" };
     // Detailed endpoint information (showing all API endpoints)
     let api_endpoints = format!("{protocol}://{local_ip}:{server_port}");
@@ -188,6 +201,9 @@ pub async fn start_http_server(
         info!(target: "rustfs::main::startup", "To enable the console, restart the server with --console-enable and a valid --console-address.");
     }
 
+    // Domains of virtual-hosted-style requests, with and without the server port
+    let mut server_domains = Vec::new();
+
     // Setup S3 service
     // This project uses the S3S library to implement S3 services
     let s3_service = {
@@ -216,16 +232,36 @@ pub async fn start_http_server(
             }
 
             info!("virtual-hosted-style requests are enabled use domain_name {:?}", &domain_sets);
+            server_domains.extend(domain_sets.iter().cloned());
             b.set_host(MultiDomain::new(domain_sets).map_err(Error::other)?);
         }
 
         b.build()
     };
+    let server_domains: Arc<[String]> = server_domains.into();
 
     // Create shutdown channel
     let (shutdown_tx, mut shutdown_rx) = tokio::sync::broadcast::channel(1);
     let shutdown_tx_clone = shutdown_tx.clone();
 
+    let http3_address = get_env_str(ENV_HTTP3_ADDRESS, DEFAULT_HTTP3_ADDRESS);
+    if !http3_address.is_empty() {
+        match &tls_config {
+            #[cfg(feature = "http3")]
+            Some(tls_config) => {
+                let http3_addr = parse_and_resolve_address(http3_address.as_str()).map_err(Error::other)?;
+                super::http3::start_http3_server(http3_addr, tls_config.clone(), s3_service.clone(), shutdown_tx.subscribe())?;
+            }
+            #[cfg(not(feature = "http3"))]
+            Some(_) => warn!(
+                "{} is set but this build has no HTTP/3 support, enable the `http3` feature",
+                ENV_HTTP3_ADDRESS
+            ),
+            None => warn!("{} is set but HTTP/3 needs TLS, not starting the HTTP/3 listener", ENV_HTTP3_ADDRESS),
+        }
+    }
+    let tls_acceptor = tls_config.map(|config| TlsAcceptor::from(Arc::new(config)));
+
     // Capture CORS configuration for the server loop
     let cors_allowed_origins = get_cors_allowed_origins();
     let cors_allowed_origins = if cors_allowed_origins.is_empty() {
@@ -248,7 +284,7 @@ pub async fn start_http_server(
             (sigterm_inner, sigint_inner)
         };
 
-        let http_server = Arc::new(ConnBuilder::new(TokioExecutor::new()));
+        let http_server = Arc::new(http_conn_builder());
         let mut ctrl_c = std::pin::pin!(tokio::signal::ctrl_c());
         let graceful = Arc::new(GracefulShutdown::new());
         debug!("graceful initiated");
@@ -332,6 +368,7 @@ pub async fn start_http_server(
                 s3_service.clone(),
                 graceful.clone(),
                 cors_layer.clone(),
+                server_domains.clone(),
                 is_console,
             );
         }
@@ -360,9 +397,33 @@ pub async fn start_http_server(
     Ok(shutdown_tx)
 }
 
-/// Sets up the TLS acceptor if certificates are available.
+/// Builds the connection builder serving HTTP/1 and HTTP/2, with the HTTP/2 flow control sized
+/// for streaming large object bodies.
+fn http_conn_builder() -> ConnBuilder<TokioExecutor> {
+    // Limits of the HTTP/2 specification, out of range values make the h2 builder panic
+    const MIN_FRAME_SIZE: u32 = 16 * 1024;
+    const MAX_FRAME_SIZE: u32 = (1 << 24) - 1;
+    const MAX_WINDOW_SIZE: u32 = (1 << 31) - 1;
+
+    let mut builder = ConnBuilder::new(TokioExecutor::new());
+    builder
+        .http2()
+        .initial_stream_window_size(
+            get_env_u32(ENV_HTTP2_STREAM_WINDOW_SIZE, DEFAULT_HTTP2_STREAM_WINDOW_SIZE).min(MAX_WINDOW_SIZE),
+        )
+        .initial_connection_window_size(
+            get_env_u32(ENV_HTTP2_CONNECTION_WINDOW_SIZE, DEFAULT_HTTP2_CONNECTION_WINDOW_SIZE).min(MAX_WINDOW_SIZE),
+        )
+        .adaptive_window(get_env_bool(ENV_HTTP2_ADAPTIVE_WINDOW, DEFAULT_HTTP2_ADAPTIVE_WINDOW))
+        .max_concurrent_streams(get_env_u32(ENV_HTTP2_MAX_CONCURRENT_STREAMS, DEFAULT_HTTP2_MAX_CONCURRENT_STREAMS))
+        .max_frame_size(get_env_u32(ENV_HTTP2_MAX_FRAME_SIZE, DEFAULT_HTTP2_MAX_FRAME_SIZE).clamp(MIN_FRAME_SIZE, MAX_FRAME_SIZE))
+        .max_send_buf_size(get_env_usize(ENV_HTTP2_MAX_SEND_BUF_SIZE, DEFAULT_HTTP2_MAX_SEND_BUF_SIZE));
+    builder
+}
+
+/// Loads the TLS configuration if certificates are available.
 #[instrument(skip(tls_path))]
-async fn setup_tls_acceptor(tls_path: &str) -> Result<Option<TlsAcceptor>> {
+async fn load_tls_config(tls_path: &str) -> Result<Option<ServerConfig>> {
     if tls_path.is_empty() || tokio::fs::metadata(tls_path).await.is_err() {
         debug!("TLS path is not provided or does not exist, starting with HTTP");
         return Ok(None);
@@ -394,7 +455,7 @@ async fn setup_tls_acceptor(tls_path: &str) -> Result<Option<TlsAcceptor>> {
                 server_config.key_log = Arc::new(rustls::KeyLogFile::new());
             }
 
-            return Ok(Some(TlsAcceptor::from(Arc::new(server_config))));
+            return Ok(Some(server_config));
         }
     }
 
@@ -419,7 +480,7 @@ async fn setup_tls_acceptor(tls_path: &str) -> Result<Option<TlsAcceptor>> {
             server_config.key_log = Arc::new(rustls::KeyLogFile::new());
         }
 
-        return Ok(Some(TlsAcceptor::from(Arc::new(server_config))));
+        return Ok(Some(server_config));
     }
 
     debug!("No valid TLS certificates found in the directory, starting with HTTP");
@@ -441,16 +502,31 @@ fn process_connection(
     s3_service: S3Service,
     graceful: Arc<GracefulShutdown>,
     cors_layer: CorsLayer,
+    server_domains: Arc<[String]>,
     is_console: bool,
 ) {
     tokio::spawn(async move {
         // Build services inside each connected task to avoid passing complex service types across tasks,
         // It also ensures that each connection has an independent service instance.
         let rpc_service = NodeServiceServer::with_interceptor(make_server(), check_auth);
-        let service = hybrid(s3_service, rpc_service);
+        let service = hybrid(
+            BucketRegionLayer.layer(
+                FederationRedirectLayer::new(server_domains)
+                    .layer(S3ErrorBodyLayer.layer(UploadGuardLayer.layer(AuthChainLayer.layer(s3_service)))),
+            ),
+            rpc_service,
+        );
+
+        let peer_addr = socket
+            .peer_addr()
+            .ok()
+            .map_or_else(|| "unknown".to_string(), |addr| addr.to_string());
 
         let hybrid_service = ServiceBuilder::new()
             .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+            .layer(RequestIdLayer)
+            .layer(ConnectionStatsLayer::new(peer_addr.clone()))
+            .layer(ProtocolStatsLayer::default())
             .layer(CatchPanicLayer::new())
             .layer(
                 TraceLayer::new_for_http()
@@ -460,13 +536,26 @@ fn process_connection(
                             .get(http::header::HeaderName::from_static("x-request-id"))
                             .and_then(|v| v.to_str().ok())
                             .unwrap_or("unknown");
+                        let request_id = request
+                            .headers()
+                            .get(AMZ_REQUEST_ID)
+                            .and_then(|v| v.to_str().ok())
+                            .unwrap_or_default();
                         let span = tracing::info_span!("http-request",
                             trace_id = %trace_id,
+                            request_id = %request_id,
                             status_code = tracing::field::Empty,
                             method = %request.method(),
                             uri = %request.uri(),
                             version = ?request.version(),
+                            disk_read_bytes = tracing::field::Empty,
+                            disk_written_bytes = tracing::field::Empty,
+                            disk_io = tracing::field::Empty,
+                            memory_reserved = tracing::field::Empty,
                         );
+                        if let Some(io) = request.extensions().get::<Arc<RequestIo>>() {
+                            io.attach_span(span.clone());
+                        }
                         for (header_name, header_value) in request.headers() {
                             if header_name == "user-agent" || header_name == "content-type" || header_name == "content-length" {
                                 span.record(header_name.as_str(), header_value.to_str().unwrap_or("invalid"));
@@ -510,6 +599,7 @@ fn process_connection(
             // Compress responses
             .layer(CompressionLayer::new())
             .option_layer(if is_console { Some(RedirectLayer) } else { None })
+            .layer(ApiTimeoutLayer)
             .service(service);
 
         let hybrid_service = TowerToHyperService::new(hybrid_service);
@@ -517,10 +607,6 @@ fn process_connection(
         // Decide whether to handle HTTPS or HTTP connections based on the existence of TLS Acceptor
         if let Some(acceptor) = tls_acceptor {
             debug!("TLS handshake start");
-            let peer_addr = socket
-                .peer_addr()
-                .ok()
-                .map_or_else(|| "unknown".to_string(), |addr| addr.to_string());
             match acceptor.accept(socket).await {
                 Ok(tls_socket) => {
                     debug!("TLS handshake successful");
@@ -0,0 +1,159 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Experimental HTTP/3 listener.
+//!
+//! Serves the S3 API over QUIC next to the TCP listener, with the certificates of the TCP listener.
+//! Internode RPC and the console stay on the TCP listener.
+
+use crate::server::layer::ProtocolStats;
+use bytes::{Buf, Bytes};
+use h3::server::{RequestResolver, RequestStream};
+use http_body::Body as _;
+use rustls::ServerConfig;
+use s3s::{Body, dto::StreamingBlob, service::S3Service};
+use std::io::{Error, Result};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, info, warn};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+// Request body chunks buffered ahead of the handler
+const REQUEST_BODY_CHUNKS: usize = 8;
+
+/// Starts the HTTP/3 listener on `addr`, it stops on the first message of `shutdown_rx`.
+pub(crate) fn start_http3_server(
+    addr: SocketAddr,
+    mut tls_config: ServerConfig,
+    s3_service: S3Service,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) -> Result<()> {
+    tls_config.alpn_protocols = vec![b"h3".to_vec()];
+    let crypto = quinn::crypto::rustls::QuicServerConfig::try_from(tls_config).map_err(Error::other)?;
+    let endpoint = quinn::Endpoint::server(quinn::ServerConfig::with_crypto(Arc::new(crypto)), addr)?;
+    info!("Experimental HTTP/3 listener available at: udp://{}", endpoint.local_addr()?);
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                incoming = endpoint.accept() => match incoming {
+                    Some(incoming) => {
+                        tokio::spawn(serve_connection(incoming, s3_service.clone()));
+                    }
+                    None => break,
+                },
+                _ = shutdown_rx.recv() => {
+                    info!("Shutdown signal received by the HTTP/3 listener");
+                    break;
+                }
+            }
+        }
+
+        endpoint.close(0u32.into(), b"shutdown");
+        endpoint.wait_idle().await;
+    });
+
+    Ok(())
+}
+
+async fn serve_connection(incoming: quinn::Incoming, s3_service: S3Service) {
+    let conn = match incoming.await {
+        Ok(conn) => conn,
+        Err(err) => {
+            debug!("QUIC handshake failed: {}", err);
+            return;
+        }
+    };
+    let peer_addr = conn.remote_address();
+
+    let mut h3_conn = match h3::server::Connection::<_, Bytes>::new(h3_quinn::Connection::new(conn)).await {
+        Ok(h3_conn) => h3_conn,
+        Err(err) => {
+            warn!(peer_addr = %peer_addr, "HTTP/3 connection setup failed: {}", err);
+            return;
+        }
+    };
+
+    let stats = Arc::new(ProtocolStats::default());
+    loop {
+        match h3_conn.accept().await {
+            Ok(Some(resolver)) => {
+                let s3_service = s3_service.clone();
+                let stats = stats.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = serve_request(resolver, s3_service, &stats).await {
+                        debug!(peer_addr = %peer_addr, "HTTP/3 request failed: {}", err);
+                    }
+                });
+            }
+            Ok(None) => break,
+            Err(err) => {
+                debug!(peer_addr = %peer_addr, "HTTP/3 connection closed: {}", err);
+                break;
+            }
+        }
+    }
+}
+
+async fn serve_request(
+    resolver: RequestResolver<h3_quinn::Connection, Bytes>,
+    s3_service: S3Service,
+    stats: &ProtocolStats,
+) -> std::result::Result<(), BoxError> {
+    let (req, stream) = resolver.resolve_request().await?;
+    stats.record_request(req.version());
+
+    let (mut send, recv) = stream.split();
+    let req = req.map(|()| Body::from(StreamingBlob::wrap(request_body(recv))));
+
+    let (parts, body) = s3_service.call(req).await?.into_parts();
+    send.send_response(http::Response::from_parts(parts, ())).await?;
+
+    let mut body = std::pin::pin!(body);
+    while let Some(frame) = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await {
+        match frame?.into_data() {
+            Ok(data) => send.send_data(data).await?,
+            Err(frame) => {
+                if let Ok(trailers) = frame.into_trailers() {
+                    send.send_trailers(trailers).await?;
+                }
+            }
+        }
+    }
+
+    send.finish().await?;
+    Ok(())
+}
+
+/// The request body as a stream of chunks, read by a task of its own and ending at the first error.
+fn request_body(mut recv: RequestStream<h3_quinn::RecvStream, Bytes>) -> ReceiverStream<Result<Bytes>> {
+    let (tx, rx) = mpsc::channel(REQUEST_BODY_CHUNKS);
+    tokio::spawn(async move {
+        loop {
+            let chunk = match recv.recv_data().await {
+                Ok(Some(mut data)) => Ok(data.copy_to_bytes(data.remaining())),
+                Ok(None) => break,
+                Err(err) => Err(Error::other(err)),
+            };
+            let failed = chunk.is_err();
+            if tx.send(chunk).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+    ReceiverStream::new(rx)
+}
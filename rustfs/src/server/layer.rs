@@ -12,14 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::admin::{ADMIN_PREFIX, console::CONSOLE_PREFIX, rpc::RPC_PREFIX};
 use crate::server::hybrid::HybridBody;
-use http::{Request as HttpRequest, Response, StatusCode};
+use crate::server::request_id::{GLOBAL_REQUEST_LOG, HOST_ID, RequestId, RequestRecord, extend_error_body, new_request_id};
+use crate::storage::payload::ConnectionStats;
+use bytes::Bytes;
+use http::{HeaderMap, HeaderValue, Method, Request as HttpRequest, Response, StatusCode, Version};
 use hyper::body::Incoming;
+use metrics::{counter, gauge};
+use rustfs_common::deadline;
+use rustfs_config::{
+    DEFAULT_API_TIMEOUT_ADMIN, DEFAULT_API_TIMEOUT_LIST, DEFAULT_API_TIMEOUT_READ, DEFAULT_API_TIMEOUT_WRITE,
+    ENV_API_TIMEOUT_ADMIN, ENV_API_TIMEOUT_LIST, ENV_API_TIMEOUT_READ, ENV_API_TIMEOUT_WRITE,
+};
+use rustfs_ecstore::federation::GLOBAL_FEDERATION_SYS;
+use rustfs_ecstore::request_io::{self, RequestIo};
+use rustfs_utils::get_env_u64;
+use rustfs_utils::http::{AMZ_BUCKET_REGION, AMZ_REQUEST_HOST_ID, AMZ_REQUEST_ID};
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::{Arc, LazyLock, OnceLock};
 use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
 use tower::{Layer, Service};
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Redirect layer that redirects browser requests to the console
 #[derive(Clone)]
@@ -89,3 +106,916 @@ where
         Box::pin(async move { inner.call(req).await.map_err(Into::into) })
     }
 }
+
+/// Attaches the payload accounting of the current connection to every request served on it
+#[derive(Clone)]
+pub struct ConnectionStatsLayer {
+    stats: Arc<ConnectionStats>,
+}
+
+impl ConnectionStatsLayer {
+    pub fn new(peer: String) -> Self {
+        Self {
+            stats: Arc::new(ConnectionStats::new(peer)),
+        }
+    }
+}
+
+impl<S> Layer<S> for ConnectionStatsLayer {
+    type Service = ConnectionStatsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConnectionStatsService {
+            inner,
+            stats: self.stats.clone(),
+        }
+    }
+}
+
+/// Service implementation for per-connection payload accounting
+#[derive(Clone)]
+pub struct ConnectionStatsService<S> {
+    inner: S,
+    stats: Arc<ConnectionStats>,
+}
+
+impl<S, B> Service<HttpRequest<B>> for ConnectionStatsService<S>
+where
+    S: Service<HttpRequest<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: HttpRequest<B>) -> Self::Future {
+        req.extensions_mut().insert(self.stats.clone());
+        self.inner.call(req)
+    }
+}
+
+/// Assigns the request id of every S3 request, records the request in the request log, attributes
+/// the disk IO of the request to it and returns the request id and host id in the response headers.
+/// Internode gRPC calls are passed through.
+#[derive(Clone)]
+pub struct RequestIdLayer;
+
+impl<S> Layer<S> for RequestIdLayer {
+    type Service = RequestIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestIdService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct RequestIdService<S> {
+    inner: S,
+}
+
+impl<S, B, ResBody> Service<HttpRequest<B>> for RequestIdService<S>
+where
+    S: Service<HttpRequest<B>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+    ResBody: 'static,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: HttpRequest<B>) -> Self::Future {
+        let is_grpc = req
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .is_some_and(|v| v.as_bytes().starts_with(b"application/grpc"));
+        if is_grpc {
+            return Box::pin(self.inner.call(req));
+        }
+
+        let request_id = new_request_id();
+        let request_id_value = HeaderValue::from_str(&request_id.0).ok();
+        // Overrides any id sent by the client, the audit entry picks the id up from the request
+        if let Some(value) = &request_id_value {
+            req.headers_mut().insert(AMZ_REQUEST_ID, value.clone());
+        }
+        let trace_id = req
+            .headers()
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let io = RequestIo::new();
+        GLOBAL_REQUEST_LOG.start(
+            RequestRecord::new(&request_id, trace_id, req.method().to_string(), req.uri().path().to_string())
+                .with_io_stats(io.stats()),
+        );
+        req.extensions_mut().insert(request_id.clone());
+        // The trace layer records the disk IO of the request on its span
+        req.extensions_mut().insert(io.clone());
+
+        let start = Instant::now();
+        let future = request_io::scope(io, self.inner.call(req));
+        Box::pin(async move {
+            let mut res = future.await?;
+            GLOBAL_REQUEST_LOG.finish(&request_id.0, res.status().as_u16(), start.elapsed());
+
+            if let Some(value) = request_id_value {
+                res.headers_mut().insert(AMZ_REQUEST_ID, value);
+            }
+            if let Ok(value) = HeaderValue::from_str(&HOST_ID) {
+                res.headers_mut().insert(AMZ_REQUEST_HOST_ID, value);
+            }
+            Ok(res)
+        })
+    }
+}
+
+// Error documents are small, larger bodies are passed through untouched
+const MAX_ERROR_BODY: u64 = 64 * 1024;
+
+/// Puts the request id and host id into the body of S3 error responses
+#[derive(Clone)]
+pub struct S3ErrorBodyLayer;
+
+impl<S> Layer<S> for S3ErrorBodyLayer {
+    type Service = S3ErrorBodyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        S3ErrorBodyService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct S3ErrorBodyService<S> {
+    inner: S,
+}
+
+impl<S, B> Service<HttpRequest<B>> for S3ErrorBodyService<S>
+where
+    S: Service<HttpRequest<B>, Response = Response<s3s::Body>>,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+{
+    type Response = Response<s3s::Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest<B>) -> Self::Future {
+        let request_id = req.extensions().get::<RequestId>().cloned();
+        let future = self.inner.call(req);
+        Box::pin(async move {
+            let res = future.await?;
+            match request_id {
+                Some(request_id) if is_error_document(&res) => Ok(extend_error_response(res, &request_id).await),
+                _ => Ok(res),
+            }
+        })
+    }
+}
+
+fn is_error_document(res: &Response<s3s::Body>) -> bool {
+    (res.status().is_client_error() || res.status().is_server_error())
+        && res
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("xml"))
+        && http_body::Body::size_hint(res.body())
+            .upper()
+            .is_some_and(|len| len > 0 && len <= MAX_ERROR_BODY)
+}
+
+async fn extend_error_response(res: Response<s3s::Body>, request_id: &RequestId) -> Response<s3s::Body> {
+    let (mut parts, mut body) = res.into_parts();
+    let data = match body.store_all_unlimited().await {
+        Ok(data) => data,
+        Err(err) => {
+            warn!("Failed to read S3 error body: {}", err);
+            parts.headers.remove(http::header::CONTENT_LENGTH);
+            return Response::from_parts(parts, s3s::Body::empty());
+        }
+    };
+
+    let data = match std::str::from_utf8(&data)
+        .ok()
+        .and_then(|body| extend_error_body(body, &request_id.0, &HOST_ID))
+    {
+        Some(extended) => Bytes::from(extended),
+        None => data,
+    };
+    parts
+        .headers
+        .insert(http::header::CONTENT_LENGTH, HeaderValue::from(data.len()));
+
+    Response::from_parts(parts, s3s::Body::from(data))
+}
+
+/// Adds the configured region to S3 responses, errors included, so SDKs can follow a wrong-region redirect
+#[derive(Clone)]
+pub struct BucketRegionLayer;
+
+impl<S> Layer<S> for BucketRegionLayer {
+    type Service = BucketRegionService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BucketRegionService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct BucketRegionService<S> {
+    inner: S,
+}
+
+impl<S, B, ResBody> Service<HttpRequest<B>> for BucketRegionService<S>
+where
+    S: Service<HttpRequest<B>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+    ResBody: 'static,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest<B>) -> Self::Future {
+        let region = rustfs_ecstore::global::get_global_region().and_then(|region| HeaderValue::from_str(&region).ok());
+        let future = self.inner.call(req);
+        Box::pin(async move {
+            let mut res = future.await?;
+            if let Some(region) = region {
+                set_bucket_region(res.headers_mut(), region);
+            }
+            Ok(res)
+        })
+    }
+}
+
+/// Keeps a region set by the handler, HeadBucket reports the default region even when none is configured.
+fn set_bucket_region(headers: &mut HeaderMap, region: HeaderValue) {
+    if !headers.contains_key(AMZ_BUCKET_REGION) {
+        headers.insert(AMZ_BUCKET_REGION, region);
+    }
+}
+
+/// Sends requests for buckets routed to another cluster of a federation there with a 307
+#[derive(Clone)]
+pub struct FederationRedirectLayer {
+    domains: Arc<[String]>,
+}
+
+impl FederationRedirectLayer {
+    /// `domains` are the server domains of virtual-hosted-style requests.
+    pub fn new(domains: Arc<[String]>) -> Self {
+        Self { domains }
+    }
+}
+
+impl<S> Layer<S> for FederationRedirectLayer {
+    type Service = FederationRedirectService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FederationRedirectService {
+            inner,
+            domains: self.domains.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FederationRedirectService<S> {
+    inner: S,
+    domains: Arc<[String]>,
+}
+
+impl<S, B> Service<HttpRequest<B>> for FederationRedirectService<S>
+where
+    S: Service<HttpRequest<B>, Response = Response<s3s::Body>>,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+{
+    type Response = Response<s3s::Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest<B>) -> Self::Future {
+        if GLOBAL_FEDERATION_SYS.has_routes()
+            && let Some(res) = federation_redirect(&req, &self.domains)
+        {
+            return Box::pin(async move { Ok(res) });
+        }
+
+        let future = self.inner.call(req);
+        Box::pin(future)
+    }
+}
+
+fn federation_redirect<B>(req: &HttpRequest<B>, domains: &[String]) -> Option<Response<s3s::Body>> {
+    let path = req.uri().path();
+    if path.starts_with(ADMIN_PREFIX) || path.starts_with(RPC_PREFIX) || path.starts_with(CONSOLE_PREFIX) {
+        return None;
+    }
+
+    let host = req
+        .headers()
+        .get(http::header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| req.uri().authority().map(|a| a.as_str()));
+    let (bucket, virtual_hosted) = request_bucket(host, path, domains)?;
+    let route = GLOBAL_FEDERATION_SYS.route(bucket)?;
+    // The route points at this cluster when it is reached through the same address
+    if host.is_some_and(|host| host.eq_ignore_ascii_case(route.authority())) {
+        return None;
+    }
+
+    let mut location = route.endpoint.clone();
+    if virtual_hosted {
+        location.push('/');
+        location.push_str(bucket);
+    }
+    location.push_str(path);
+    if let Some(query) = req.uri().query() {
+        location.push('?');
+        location.push_str(query);
+    }
+    debug!(bucket, %location, "redirecting request to the cluster owning the bucket");
+
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Error><Code>TemporaryRedirect</Code>\
+         <Message>Please re-send this request to the specified temporary endpoint.</Message>\
+         <Bucket>{}</Bucket><Endpoint>{}</Endpoint></Error>",
+        bucket,
+        route.authority()
+    );
+    let mut builder = Response::builder()
+        .status(StatusCode::TEMPORARY_REDIRECT)
+        .header(http::header::LOCATION, location)
+        .header(http::header::CONTENT_TYPE, "application/xml");
+    if let Some(region) = route.region.as_deref() {
+        builder = builder.header(AMZ_BUCKET_REGION, region);
+    }
+    builder
+        .body(s3s::Body::from(body))
+        .inspect_err(|err| warn!("Failed to build federation redirect: {}", err))
+        .ok()
+}
+
+/// Bucket addressed by a request, and whether it was addressed through the host.
+fn request_bucket<'a>(host: Option<&'a str>, path: &'a str, domains: &[String]) -> Option<(&'a str, bool)> {
+    if let Some(host) = host {
+        for domain in domains {
+            if let Some(bucket) = host
+                .strip_suffix(domain.as_str())
+                .and_then(|prefix| prefix.strip_suffix('.'))
+                .filter(|bucket| !bucket.is_empty())
+            {
+                return Some((bucket, true));
+            }
+        }
+    }
+
+    let bucket = path.trim_start_matches('/').split('/').next().unwrap_or_default();
+    (!bucket.is_empty()).then_some((bucket, false))
+}
+
+/// Largest object a single PUT may upload, the largest object a multipart upload may assemble.
+const MAX_OBJECT_SIZE: u64 = 5 * 1024 * 1024 * 1024 * 1024;
+/// Largest part of a multipart upload.
+const MAX_PART_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+
+/// Why an upload is refused from its headers alone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadRejection {
+    pub status: StatusCode,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl UploadRejection {
+    fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn into_response(self) -> Response<s3s::Body> {
+        let body = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Error><Code>{}</Code><Message>{}</Message></Error>",
+            self.code,
+            self.message.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+        );
+        let mut res = Response::new(s3s::Body::from(body));
+        *res.status_mut() = self.status;
+        res.headers_mut()
+            .insert(http::header::CONTENT_TYPE, HeaderValue::from_static("application/xml"));
+        // The body was never read, the connection cannot carry another request
+        res.headers_mut()
+            .insert(http::header::CONNECTION, HeaderValue::from_static("close"));
+        res
+    }
+}
+
+/// Length of the object data an upload declares, the decoded length of aws-chunked bodies.
+fn declared_upload_size(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(rustfs_utils::http::headers::AMZ_DECODED_CONTENT_LENGTH)
+        .or_else(|| headers.get(http::header::CONTENT_LENGTH))
+        .and_then(|v| atoi::atoi::<u64>(v.as_bytes()))
+}
+
+/// Check the headers of an S3 request for what makes its body useless to send: an expectation
+/// other than `100-continue`, a byte range to PUT, which S3 has no notion of, or more data than
+/// an object or a part may hold.
+pub fn check_upload_headers(method: &Method, query: Option<&str>, headers: &HeaderMap) -> Result<(), UploadRejection> {
+    if let Some(expect) = headers.get(http::header::EXPECT)
+        && !expect.as_bytes().eq_ignore_ascii_case(b"100-continue")
+    {
+        return Err(UploadRejection::new(
+            StatusCode::EXPECTATION_FAILED,
+            "ExpectationFailed",
+            "Only the 100-continue expectation is supported.",
+        ));
+    }
+
+    if *method != Method::PUT {
+        return Ok(());
+    }
+
+    if headers.contains_key(http::header::CONTENT_RANGE) {
+        return Err(UploadRejection::new(
+            StatusCode::NOT_IMPLEMENTED,
+            "NotImplemented",
+            "Writing a byte range of an object is not supported, upload the whole object or use a multipart upload.",
+        ));
+    }
+
+    let is_part = query.is_some_and(|q| {
+        q.split('&')
+            .any(|kv| kv.split('=').next().is_some_and(|k| k.eq_ignore_ascii_case("uploadId")))
+    });
+    let (limit, what) = if is_part {
+        (MAX_PART_SIZE, "part")
+    } else {
+        (MAX_OBJECT_SIZE, "object")
+    };
+    if declared_upload_size(headers).is_some_and(|size| size > limit) {
+        return Err(UploadRejection::new(
+            StatusCode::BAD_REQUEST,
+            "EntityTooLarge",
+            format!("Your proposed upload exceeds the maximum allowed {what} size of {limit} bytes."),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Refuses S3 uploads from their headers before anything reads the body.
+///
+/// HTTP/1 clients sending `Expect: 100-continue` hold the body back until the server answers
+/// `100 Continue`, which it does when the body is first read. The body is read only once the
+/// request is authenticated, authorized and accepted by its operation, so requests refused here or
+/// by any later check are answered without the body ever being transferred.
+#[derive(Clone)]
+pub struct UploadGuardLayer;
+
+impl<S> Layer<S> for UploadGuardLayer {
+    type Service = UploadGuardService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        UploadGuardService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct UploadGuardService<S> {
+    inner: S,
+}
+
+impl<S, B> Service<HttpRequest<B>> for UploadGuardService<S>
+where
+    S: Service<HttpRequest<B>, Response = Response<s3s::Body>>,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+{
+    type Response = Response<s3s::Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest<B>) -> Self::Future {
+        let path = req.uri().path();
+        if !(path.starts_with(ADMIN_PREFIX) || path.starts_with(RPC_PREFIX) || path.starts_with(CONSOLE_PREFIX))
+            && let Err(rejection) = check_upload_headers(req.method(), req.uri().query(), req.headers())
+        {
+            debug!("refusing {} {} before its body: {}", req.method(), path, rejection.message);
+            counter!("rustfs_api_uploads_refused_total", "code" => rejection.code).increment(1);
+            return Box::pin(async move { Ok(rejection.into_response()) });
+        }
+        Box::pin(self.inner.call(req))
+    }
+}
+
+/// HTTP protocol a connection speaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpProtocol {
+    Http1,
+    Http2,
+    Http3,
+}
+
+impl HttpProtocol {
+    pub fn from_version(version: Version) -> Self {
+        match version {
+            Version::HTTP_2 => Self::Http2,
+            Version::HTTP_3 => Self::Http3,
+            _ => Self::Http1,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Http1 => "http/1.1",
+            Self::Http2 => "h2",
+            Self::Http3 => "h3",
+        }
+    }
+}
+
+/// Connection and request counts of one connection by protocol.
+///
+/// A connection is counted under the protocol of its first request, the protocol a cleartext
+/// connection speaks is only known once it is parsed. It stays open until the last service
+/// holding it is dropped.
+#[derive(Debug, Default)]
+pub struct ProtocolStats {
+    protocol: OnceLock<HttpProtocol>,
+}
+
+impl ProtocolStats {
+    pub fn record_request(&self, version: Version) {
+        let protocol = HttpProtocol::from_version(version);
+        if self.protocol.set(protocol).is_ok() {
+            counter!("rustfs_http_connections_total", "protocol" => protocol.as_str()).increment(1);
+            gauge!("rustfs_http_connections_open", "protocol" => protocol.as_str()).increment(1.0);
+        }
+        counter!("rustfs_http_requests_total", "protocol" => protocol.as_str()).increment(1);
+    }
+
+    pub fn protocol(&self) -> Option<HttpProtocol> {
+        self.protocol.get().copied()
+    }
+}
+
+impl Drop for ProtocolStats {
+    fn drop(&mut self) {
+        if let Some(protocol) = self.protocol.get() {
+            gauge!("rustfs_http_connections_open", "protocol" => protocol.as_str()).decrement(1.0);
+        }
+    }
+}
+
+/// Counts the connection and its requests under the HTTP protocol they use
+#[derive(Clone, Default)]
+pub struct ProtocolStatsLayer {
+    stats: Arc<ProtocolStats>,
+}
+
+impl<S> Layer<S> for ProtocolStatsLayer {
+    type Service = ProtocolStatsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ProtocolStatsService {
+            inner,
+            stats: self.stats.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ProtocolStatsService<S> {
+    inner: S,
+    stats: Arc<ProtocolStats>,
+}
+
+impl<S, B> Service<HttpRequest<B>> for ProtocolStatsService<S>
+where
+    S: Service<HttpRequest<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest<B>) -> Self::Future {
+        self.stats.record_request(req.version());
+        self.inner.call(req)
+    }
+}
+
+/// Class of API a request belongs to, each class has its own timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiClass {
+    List,
+    Read,
+    Write,
+    Admin,
+    /// Internode gRPC call, bounded by the deadline the caller sent along
+    Internode,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ApiTimeouts {
+    list: Option<Duration>,
+    read: Option<Duration>,
+    write: Option<Duration>,
+    admin: Option<Duration>,
+}
+
+impl ApiTimeouts {
+    fn from_env() -> Self {
+        let secs = |key, default| Some(get_env_u64(key, default)).filter(|s| *s > 0).map(Duration::from_secs);
+        Self {
+            list: secs(ENV_API_TIMEOUT_LIST, DEFAULT_API_TIMEOUT_LIST),
+            read: secs(ENV_API_TIMEOUT_READ, DEFAULT_API_TIMEOUT_READ),
+            write: secs(ENV_API_TIMEOUT_WRITE, DEFAULT_API_TIMEOUT_WRITE),
+            admin: secs(ENV_API_TIMEOUT_ADMIN, DEFAULT_API_TIMEOUT_ADMIN),
+        }
+    }
+}
+
+static API_TIMEOUTS: LazyLock<ApiTimeouts> = LazyLock::new(ApiTimeouts::from_env);
+
+fn is_grpc(req: &HttpRequest<Incoming>) -> bool {
+    req.version() == http::Version::HTTP_2
+        && req
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .is_some_and(|v| v.as_bytes().starts_with(b"application/grpc"))
+}
+
+/// Classify a REST request, `None` for traffic that is never bounded (console, internode streams).
+pub fn classify_request(method: &Method, path: &str) -> Option<ApiClass> {
+    if path.starts_with(RPC_PREFIX) || path.starts_with(CONSOLE_PREFIX) {
+        return None;
+    }
+    if path.starts_with(ADMIN_PREFIX) {
+        return Some(ApiClass::Admin);
+    }
+
+    match *method {
+        Method::GET | Method::HEAD => {
+            // Service and bucket level GETs are listings, object GETs carry a key after the bucket
+            let trimmed = path.trim_matches('/');
+            if *method == Method::GET && !trimmed.contains('/') {
+                Some(ApiClass::List)
+            } else {
+                Some(ApiClass::Read)
+            }
+        }
+        _ => Some(ApiClass::Write),
+    }
+}
+
+/// Parse the `grpc-timeout` header: an integer followed by one of the units `H M S m u n`.
+pub fn parse_grpc_timeout(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get("grpc-timeout")?.to_str().ok()?;
+    if value.len() < 2 || value.len() > 9 {
+        return None;
+    }
+    let (amount, unit) = value.split_at(value.len() - 1);
+    let amount: u64 = amount.parse().ok()?;
+    match unit {
+        "H" => Some(Duration::from_secs(amount * 3600)),
+        "M" => Some(Duration::from_secs(amount * 60)),
+        "S" => Some(Duration::from_secs(amount)),
+        "m" => Some(Duration::from_millis(amount)),
+        "u" => Some(Duration::from_micros(amount)),
+        "n" => Some(Duration::from_nanos(amount)),
+        _ => None,
+    }
+}
+
+/// Enforces the per class API timeouts and publishes the request deadline to disk calls and
+/// internode RPCs issued while serving the request.
+#[derive(Clone)]
+pub struct ApiTimeoutLayer;
+
+impl<S> Layer<S> for ApiTimeoutLayer {
+    type Service = ApiTimeoutService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiTimeoutService { inner }
+    }
+}
+
+/// Service implementation for API timeouts
+#[derive(Clone)]
+pub struct ApiTimeoutService<S> {
+    inner: S,
+}
+
+impl<S, RestBody, GrpcBody> Service<HttpRequest<Incoming>> for ApiTimeoutService<S>
+where
+    S: Service<HttpRequest<Incoming>, Response = Response<HybridBody<RestBody, GrpcBody>>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    RestBody: Default + Send + 'static,
+    GrpcBody: Send + 'static,
+{
+    type Response = Response<HybridBody<RestBody, GrpcBody>>;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: HttpRequest<Incoming>) -> Self::Future {
+        let (class, timeout) = if is_grpc(&req) {
+            (Some(ApiClass::Internode), parse_grpc_timeout(req.headers()))
+        } else {
+            let timeouts = *API_TIMEOUTS;
+            let class = classify_request(req.method(), req.uri().path());
+            let timeout = match class {
+                Some(ApiClass::List) => timeouts.list,
+                Some(ApiClass::Read) => timeouts.read,
+                Some(ApiClass::Write) => timeouts.write,
+                Some(ApiClass::Admin) => timeouts.admin,
+                Some(ApiClass::Internode) | None => None,
+            };
+            (class, timeout)
+        };
+
+        let mut inner = self.inner.clone();
+        let Some(timeout) = timeout else {
+            return Box::pin(async move { inner.call(req).await.map_err(Into::into) });
+        };
+
+        let path = req.uri().path().to_owned();
+        let deadline = Instant::now() + timeout;
+        Box::pin(async move {
+            let served = deadline::scope(Some(deadline), tokio::time::timeout_at(deadline, inner.call(req))).await;
+            match served {
+                Ok(res) => res.map_err(Into::into),
+                Err(_) => {
+                    warn!("request {} of class {:?} timed out after {:?}", path, class, timeout);
+                    counter!("rustfs_api_requests_timeout_total").increment(1);
+                    let builder = if class == Some(ApiClass::Internode) {
+                        // Trailers-only gRPC response with status DEADLINE_EXCEEDED
+                        Response::builder()
+                            .status(StatusCode::OK)
+                            .header(http::header::CONTENT_TYPE, "application/grpc")
+                            .header("grpc-status", "4")
+                            .header("grpc-message", "deadline exceeded")
+                    } else {
+                        Response::builder().status(StatusCode::SERVICE_UNAVAILABLE)
+                    };
+                    let res = builder
+                        .body(HybridBody::Rest {
+                            rest_body: RestBody::default(),
+                        })
+                        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
+                    Ok(res)
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn test_classify_request() {
+        assert_eq!(classify_request(&Method::GET, "/"), Some(ApiClass::List));
+        assert_eq!(classify_request(&Method::GET, "/bucket/"), Some(ApiClass::List));
+        assert_eq!(classify_request(&Method::GET, "/bucket/dir/key"), Some(ApiClass::Read));
+        assert_eq!(classify_request(&Method::HEAD, "/bucket"), Some(ApiClass::Read));
+        assert_eq!(classify_request(&Method::PUT, "/bucket/key"), Some(ApiClass::Write));
+        assert_eq!(classify_request(&Method::DELETE, "/bucket"), Some(ApiClass::Write));
+        assert_eq!(classify_request(&Method::GET, "/rustfs/admin/v3/info"), Some(ApiClass::Admin));
+        assert_eq!(classify_request(&Method::GET, "/rustfs/rpc/read_file_stream"), None);
+        assert_eq!(classify_request(&Method::GET, "/rustfs/console/index.html"), None);
+    }
+
+    #[test]
+    fn test_request_bucket() {
+        let domains = vec!["s3.example.com".to_string(), "s3.example.com:9000".to_string()];
+        assert_eq!(
+            request_bucket(Some("photos.s3.example.com:9000"), "/2024/a.jpg", &domains),
+            Some(("photos", true))
+        );
+        assert_eq!(request_bucket(Some("s3.example.com"), "/photos/a.jpg", &domains), Some(("photos", false)));
+        assert_eq!(request_bucket(Some("10.0.0.1:9000"), "/photos", &domains), Some(("photos", false)));
+        assert_eq!(request_bucket(None, "/", &domains), None);
+    }
+
+    #[test]
+    fn test_set_bucket_region() {
+        let mut headers = HeaderMap::new();
+        set_bucket_region(&mut headers, HeaderValue::from_static("eu-west-1"));
+        assert_eq!(headers.get("x-amz-bucket-region").unwrap(), "eu-west-1");
+
+        set_bucket_region(&mut headers, HeaderValue::from_static("us-east-1"));
+        assert_eq!(headers.get("x-amz-bucket-region").unwrap(), "eu-west-1");
+    }
+
+    #[test]
+    fn test_parse_grpc_timeout() {
+        let timeout = |v: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("grpc-timeout", HeaderValue::from_static(v));
+            parse_grpc_timeout(&headers)
+        };
+
+        assert_eq!(timeout("5S"), Some(Duration::from_secs(5)));
+        assert_eq!(timeout("1500m"), Some(Duration::from_millis(1500)));
+        assert_eq!(timeout("2M"), Some(Duration::from_secs(120)));
+        assert_eq!(timeout("10x"), None);
+        assert_eq!(timeout("S"), None);
+        assert_eq!(parse_grpc_timeout(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_check_upload_headers() {
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            let mut headers = HeaderMap::new();
+            for (k, v) in pairs {
+                headers.insert(*k, HeaderValue::from_static(v));
+            }
+            headers
+        };
+        let code =
+            |method: Method, query: Option<&str>, h: HeaderMap| check_upload_headers(&method, query, &h).map_err(|r| r.code);
+
+        assert_eq!(
+            code(Method::PUT, None, headers(&[("expect", "100-Continue"), ("content-length", "10")])),
+            Ok(())
+        );
+        assert_eq!(code(Method::GET, None, headers(&[("expect", "200-ok")])), Err("ExpectationFailed"));
+        assert_eq!(
+            code(Method::PUT, None, headers(&[("content-range", "bytes 0-9/100")])),
+            Err("NotImplemented")
+        );
+
+        // Parts are limited to 5 GiB, whole objects to 5 TiB
+        let six_gib = headers(&[("content-length", "6442450944")]);
+        assert_eq!(code(Method::PUT, None, six_gib.clone()), Ok(()));
+        assert_eq!(code(Method::PUT, Some("partNumber=1&uploadId=abc"), six_gib), Err("EntityTooLarge"));
+        assert_eq!(
+            code(Method::PUT, None, headers(&[("content-length", "5497558138881")])),
+            Err("EntityTooLarge")
+        );
+        // The decoded length of aws-chunked bodies counts
+        assert_eq!(
+            code(
+                Method::PUT,
+                Some("uploadId=abc&partNumber=2"),
+                headers(&[("content-length", "100"), ("x-amz-decoded-content-length", "6442450944")])
+            ),
+            Err("EntityTooLarge")
+        );
+        assert_eq!(code(Method::POST, None, headers(&[("content-length", "5497558138881")])), Ok(()));
+    }
+
+    #[test]
+    fn test_protocol_stats() {
+        assert_eq!(HttpProtocol::from_version(Version::HTTP_10), HttpProtocol::Http1);
+        assert_eq!(HttpProtocol::from_version(Version::HTTP_2).as_str(), "h2");
+
+        let stats = ProtocolStats::default();
+        assert_eq!(stats.protocol(), None);
+        stats.record_request(Version::HTTP_2);
+        stats.record_request(Version::HTTP_11);
+        assert_eq!(stats.protocol(), Some(HttpProtocol::Http2));
+    }
+}
@@ -13,17 +13,23 @@
 // limitations under the License.
 
 mod audit;
+mod auth_chain;
 mod http;
+#[cfg(feature = "http3")]
+mod http3;
 mod hybrid;
 mod layer;
+mod request_id;
 mod service_state;
 
 mod event;
 mod runtime;
 
 pub(crate) use audit::{start_audit_system, stop_audit_system};
+pub(crate) use auth_chain::{ChainIdentity, init_auth_chain};
 pub(crate) use event::{init_event_notifier, shutdown_event_notifier};
 pub(crate) use http::start_http_server;
+pub(crate) use request_id::GLOBAL_REQUEST_LOG;
 pub(crate) use runtime::get_tokio_runtime_builder;
 pub(crate) use service_state::SHUTDOWN_TIMEOUT;
 pub(crate) use service_state::ServiceState;
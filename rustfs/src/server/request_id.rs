@@ -0,0 +1,255 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Request ids of S3 requests.
+//!
+//! Every request gets an `x-amz-request-id` unique on the node, and every response carries it
+//! along with the `x-amz-id-2` host id of the node, in its headers and in the body of S3 errors.
+//! The request id is part of the tracing span and of the audit entry of the request. The last
+//! requests are kept with their audit entry and the disk IO they caused, so a request id a client
+//! reports can be looked up.
+
+use rustfs_audit::entity::AuditEntry;
+use rustfs_config::{DEFAULT_REQUEST_LOG_SIZE, ENV_REQUEST_LOG_SIZE};
+use rustfs_ecstore::global::get_global_endpoints;
+use rustfs_ecstore::request_io::{RequestIoSnapshot, RequestIoStats};
+use rustfs_utils::{crypto::hex_sha256, get_env_usize};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use time::OffsetDateTime;
+
+pub static GLOBAL_REQUEST_LOG: LazyLock<RequestLog> =
+    LazyLock::new(|| RequestLog::new(get_env_usize(ENV_REQUEST_LOG_SIZE, DEFAULT_REQUEST_LOG_SIZE)));
+
+/// Host id of the node, derived from its node name so it stays the same across restarts.
+pub static HOST_ID: LazyLock<String> = LazyLock::new(|| {
+    let mut node = get_global_endpoints().peers().1;
+    if node.is_empty() {
+        node = sysinfo::System::host_name().unwrap_or_default();
+    }
+    hex_sha256(node.as_bytes(), |s| s.to_string())
+});
+
+// Last request id handed out, as nanoseconds since the epoch
+static LAST_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Request id of the request being served, attached to the request extensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// Create a request id, the current time in nanoseconds as upper case hex, bumped past the last
+/// id when the clock did not move.
+pub fn new_request_id() -> RequestId {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos() as u64;
+    let prev = LAST_REQUEST_ID
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| Some(now.max(last + 1)))
+        .unwrap_or_default();
+
+    RequestId(format!("{:016X}", now.max(prev + 1)))
+}
+
+/// Put the request id and host id into an S3 error document, replacing the ones it has.
+/// None when the body is not an error document.
+pub fn extend_error_body(body: &str, request_id: &str, host_id: &str) -> Option<String> {
+    let end = body.rfind("</Error>")?;
+    let head = strip_element(&strip_element(&body[..end], "RequestId"), "HostId");
+
+    Some(format!(
+        "{head}<RequestId>{request_id}</RequestId><HostId>{host_id}</HostId>{}",
+        &body[end..]
+    ))
+}
+
+fn strip_element(s: &str, name: &str) -> String {
+    let empty = format!("<{name}/>");
+    if let Some(start) = s.find(&empty) {
+        return format!("{}{}", &s[..start], &s[start + empty.len()..]);
+    }
+
+    let open = format!("<{name}>");
+    let close = format!("</{name}>");
+    if let Some(start) = s.find(&open)
+        && let Some(len) = s[start..].find(&close)
+    {
+        return format!("{}{}", &s[..start], &s[start + len + close.len()..]);
+    }
+
+    s.to_string()
+}
+
+/// What is known about a request served by this node.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestRecord {
+    pub request_id: String,
+    pub host_id: String,
+    /// `x-request-id` of the tracing span of the request
+    pub trace_id: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub time: OffsetDateTime,
+    pub method: String,
+    pub path: String,
+    pub status: Option<u16>,
+    pub duration_ms: Option<u64>,
+    pub audit: Option<AuditEntry>,
+    /// Bytes read and written per disk and memory reserved, as of the lookup
+    pub io: Option<RequestIoSnapshot>,
+    #[serde(skip)]
+    io_stats: Option<Arc<RequestIoStats>>,
+}
+
+impl RequestRecord {
+    pub fn new(request_id: &RequestId, trace_id: Option<String>, method: String, path: String) -> Self {
+        Self {
+            request_id: request_id.0.clone(),
+            host_id: HOST_ID.clone(),
+            trace_id,
+            time: OffsetDateTime::now_utc(),
+            method,
+            path,
+            status: None,
+            duration_ms: None,
+            audit: None,
+            io: None,
+            io_stats: None,
+        }
+    }
+
+    pub fn with_io_stats(mut self, stats: Arc<RequestIoStats>) -> Self {
+        self.io_stats = Some(stats);
+        self
+    }
+}
+
+/// The last requests served by this node by request id, the oldest are dropped first.
+pub struct RequestLog {
+    capacity: usize,
+    inner: Mutex<RequestLogInner>,
+}
+
+#[derive(Default)]
+struct RequestLogInner {
+    records: HashMap<String, RequestRecord>,
+    order: VecDeque<String>,
+}
+
+impl RequestLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(RequestLogInner::default()),
+        }
+    }
+
+    pub fn start(&self, record: RequestRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        while inner.order.len() >= self.capacity {
+            if let Some(oldest) = inner.order.pop_front() {
+                inner.records.remove(&oldest);
+            }
+        }
+        inner.order.push_back(record.request_id.clone());
+        inner.records.insert(record.request_id.clone(), record);
+    }
+
+    pub fn finish(&self, request_id: &str, status: u16, duration: Duration) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(record) = inner.records.get_mut(request_id) {
+            record.status = Some(status);
+            record.duration_ms = Some(duration.as_millis() as u64);
+        }
+    }
+
+    /// Attach the audit entry of a request, found by the request id it carries.
+    pub fn attach_audit(&self, entry: &AuditEntry) {
+        let Some(request_id) = entry.request_id.as_deref() else {
+            return;
+        };
+
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(record) = inner.records.get_mut(request_id) {
+            record.audit = Some(entry.clone());
+        }
+    }
+
+    pub fn get(&self, request_id: &str) -> Option<RequestRecord> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let mut record = inner.records.get(request_id).cloned()?;
+        record.io = record.io_stats.as_ref().map(|stats| stats.snapshot());
+        Some(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_request_id_unique() {
+        let ids: Vec<RequestId> = (0..1000).map(|_| new_request_id()).collect();
+        assert!(ids.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(ids[0].0.len(), 16);
+    }
+
+    #[test]
+    fn test_extend_error_body() {
+        let body = "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Error><Code>NoSuchKey</Code><Message>missing</Message></Error>";
+        assert_eq!(
+            extend_error_body(body, "ID", "HOST").unwrap(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Error><Code>NoSuchKey</Code><Message>missing</Message>\
+             <RequestId>ID</RequestId><HostId>HOST</HostId></Error>"
+        );
+
+        let body = "<Error><Code>NoSuchKey</Code><RequestId>old</RequestId><HostId/></Error>";
+        assert_eq!(
+            extend_error_body(body, "ID", "HOST").unwrap(),
+            "<Error><Code>NoSuchKey</Code><RequestId>ID</RequestId><HostId>HOST</HostId></Error>"
+        );
+
+        assert_eq!(extend_error_body("<ListBucketResult/>", "ID", "HOST"), None);
+    }
+
+    #[test]
+    fn test_request_log() {
+        let log = RequestLog::new(2);
+        let ids: Vec<RequestId> = (0..3).map(|_| new_request_id()).collect();
+        for id in &ids {
+            log.start(RequestRecord::new(id, None, "GET".to_string(), "/bucket/key".to_string()));
+        }
+        assert!(log.get(&ids[0].0).is_none());
+
+        log.finish(&ids[2].0, 404, Duration::from_millis(5));
+        log.attach_audit(&AuditEntry {
+            request_id: Some(ids[2].0.clone()),
+            ..Default::default()
+        });
+
+        let record = log.get(&ids[2].0).unwrap();
+        assert_eq!((record.status, record.duration_ms), (Some(404), Some(5)));
+        assert!(record.audit.is_some());
+        assert!(record.io.is_none());
+
+        let id = new_request_id();
+        let io = rustfs_ecstore::request_io::RequestIo::new();
+        log.start(RequestRecord::new(&id, None, "PUT".to_string(), "/bucket/key".to_string()).with_io_stats(io.stats()));
+        assert_eq!(log.get(&id.0).unwrap().io, Some(RequestIoSnapshot::default()));
+    }
+}
@@ -13,16 +13,29 @@
 // limitations under the License.
 
 use super::ecfs::FS;
-use crate::auth::{check_key_valid, get_condition_values, get_session_token};
+use super::payload::ConnectionStats;
+use super::presign::{check_presigned_upload, check_presigned_url, is_presigned_url};
+use crate::auth::{
+    check_key_valid, get_condition_values, get_session_token, is_request_post_policy_signature_v4, is_signature_v2_request,
+};
 use crate::license::license_check;
+use crate::server::ChainIdentity;
+use rustfs_common::perf_monitor::GLOBAL_PERF_MONITOR;
 use rustfs_ecstore::bucket::policy_sys::PolicySys;
+use rustfs_ecstore::bucket::purge::GLOBAL_BUCKET_PURGE_SYS;
+use rustfs_ecstore::compat::GLOBAL_COMPAT_SYS;
+use rustfs_ecstore::presign::{GLOBAL_PRESIGN_SYS, PresignLimits};
 use rustfs_iam::error::Error as IamError;
+use rustfs_iam::last_used::GLOBAL_KEY_USAGE;
 use rustfs_policy::auth;
 use rustfs_policy::policy::action::{Action, S3Action};
 use rustfs_policy::policy::{Args, BucketPolicyArgs};
+use rustfs_utils::http::ip::get_source_ip_raw;
 use s3s::access::{S3Access, S3AccessContext};
+use s3s::path::S3Path;
 use s3s::{S3Error, S3ErrorCode, S3Request, S3Result, dto::*, s3_error};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[allow(dead_code)]
 #[derive(Default, Clone)]
@@ -147,6 +160,39 @@ pub async fn authorize_request<T>(req: &mut S3Request<T>, action: Action) -> S3R
     Err(s3_error!(AccessDenied, "Access Denied"))
 }
 
+/// Bucket-level pre-check for the request's credentials, used to filter ListBuckets.
+///
+/// Returns whether the caller may see `bucket` at all; anonymous requests never can.
+pub async fn is_bucket_accessible<T>(req: &S3Request<T>, bucket: &str) -> bool {
+    let Some(req_info) = req.extensions.get::<ReqInfo>() else {
+        return false;
+    };
+    let Some(cred) = &req_info.cred else {
+        return false;
+    };
+    let Ok(iam_store) = rustfs_iam::get() else {
+        return false;
+    };
+
+    let default_claims = HashMap::new();
+    let claims = cred.claims.as_ref().unwrap_or(&default_claims);
+    let conditions = get_condition_values(&req.headers, cred, None, None);
+
+    iam_store
+        .is_bucket_allowed(&Args {
+            account: &cred.access_key,
+            groups: &cred.groups,
+            action: Action::S3Action(S3Action::ListBucketAction),
+            bucket,
+            conditions: &conditions,
+            is_owner: req_info.is_owner,
+            object: "",
+            claims,
+            deny_only: false,
+        })
+        .await
+}
+
 #[async_trait::async_trait]
 impl S3Access for FS {
     // /// Checks whether the current request has accesses to the resources.
@@ -176,14 +222,47 @@ impl S3Access for FS {
         //     // cx.extensions_mut(),
         // );
 
+        // The request counts as in flight until its extensions are dropped
+        let in_flight = GLOBAL_PERF_MONITOR.start(cx.s3_op().name());
+        cx.extensions_mut().insert(Arc::new(in_flight));
+
+        // Legacy V2 signatures are only honoured once enabled through the admin API
+        if cx.credentials().is_some()
+            && !GLOBAL_COMPAT_SYS.signature_v2_enabled()
+            && is_signature_v2_request(cx.uri(), cx.headers())
+        {
+            return Err(s3_error!(
+                AccessDenied,
+                "AWS Signature Version 2 is disabled, enable the compatibility mode to use it"
+            ));
+        }
+
+        // Presigned URLs may not outlive the cluster limit, whatever they were signed for
+        if cx.credentials().is_some() {
+            check_presigned_url(cx.uri(), &GLOBAL_PRESIGN_SYS.limits())?;
+        }
+
         let (cred, is_owner) = if let Some(input_cred) = cx.credentials() {
             let (cred, is_owner) =
                 check_key_valid(get_session_token(cx.uri(), cx.headers()).unwrap_or_default(), &input_cred.access_key).await?;
             (Some(cred), is_owner)
+        } else if let Some(identity) = cx.extensions_mut().get::<ChainIdentity>().cloned() {
+            // Authenticated by a step of the authentication chain ahead of the signature check
+            let (cred, is_owner) = check_key_valid("", &identity.access_key).await?;
+            (Some(cred), is_owner)
         } else {
             (None, false)
         };
 
+        if let Some(cred) = &cred {
+            let peer = cx
+                .extensions_mut()
+                .get::<Arc<ConnectionStats>>()
+                .map(|stats| stats.peer().to_string())
+                .unwrap_or_default();
+            GLOBAL_KEY_USAGE.record(&cred.access_key, &get_source_ip_raw(cx.headers(), &peer));
+        }
+
         let req_info = ReqInfo {
             cred,
             is_owner,
@@ -194,6 +273,20 @@ impl S3Access for FS {
         let ext = cx.extensions_mut();
         ext.insert(req_info);
 
+        // Force-deleted buckets are gone for clients while their objects are purged
+        let bucket = match cx.s3_path() {
+            S3Path::Bucket { bucket } | S3Path::Object { bucket, .. } => Some(bucket),
+            S3Path::Root => None,
+        };
+        if let Some(bucket) = bucket
+            && GLOBAL_BUCKET_PURGE_SYS.is_purging(bucket)
+        {
+            if cx.s3_op().name() == "CreateBucket" {
+                return Err(s3_error!(OperationAborted, "bucket {} is being deleted", bucket));
+            }
+            return Err(s3_error!(NoSuchBucket, "bucket {} does not exist", bucket));
+        }
+
         // Verify uniformly here? Or verify separately below?
 
         Ok(())
@@ -263,8 +356,13 @@ impl S3Access for FS {
     /// Checks whether the CreateMultipartUpload request has accesses to the resources.
     ///
     /// This method returns `Ok(())` by default.
-    async fn create_multipart_upload(&self, _req: &mut S3Request<CreateMultipartUploadInput>) -> S3Result<()> {
+    async fn create_multipart_upload(&self, req: &mut S3Request<CreateMultipartUploadInput>) -> S3Result<()> {
         license_check().map_err(|er| s3_error!(AccessDenied, "{:?}", er.to_string()))?;
+
+        if is_presigned_url(&req.uri) {
+            let sse_requested = req.input.server_side_encryption.is_some() || req.input.sse_customer_algorithm.is_some();
+            check_presigned_upload(&GLOBAL_PRESIGN_SYS.limits(), None, sse_requested)?;
+        }
         Ok(())
     }
 
@@ -1026,7 +1124,13 @@ impl S3Access for FS {
         req_info.object = Some(req.input.key.clone());
         req_info.version_id = req.input.version_id.clone();
 
-        authorize_request(req, Action::S3Action(S3Action::PutObjectAction)).await
+        authorize_request(req, Action::S3Action(S3Action::PutObjectAction)).await?;
+
+        if is_presigned_url(&req.uri) || is_request_post_policy_signature_v4(&req.headers) {
+            let sse_requested = req.input.server_side_encryption.is_some() || req.input.sse_customer_algorithm.is_some();
+            check_presigned_upload(&GLOBAL_PRESIGN_SYS.limits(), req.input.content_length, sse_requested)?;
+        }
+        Ok(())
     }
 
     /// Checks whether the PutObjectAcl request has accesses to the resources.
@@ -1125,7 +1229,17 @@ impl S3Access for FS {
         req_info.bucket = Some(req.input.bucket.clone());
         req_info.object = Some(req.input.key.clone());
 
-        authorize_request(req, Action::S3Action(S3Action::PutObjectAction)).await
+        authorize_request(req, Action::S3Action(S3Action::PutObjectAction)).await?;
+
+        // Encryption is requested when the upload is created, parts only have their size checked
+        if is_presigned_url(&req.uri) {
+            let limits = PresignLimits {
+                require_sse: false,
+                ..GLOBAL_PRESIGN_SYS.limits()
+            };
+            check_presigned_upload(&limits, req.input.content_length, false)?;
+        }
+        Ok(())
     }
 
     /// Checks whether the UploadPartCopy request has accesses to the resources.
@@ -0,0 +1,879 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Scheduled backups of buckets to a remote S3 target.
+//!
+//! A backup schedule mirrors the current objects of a bucket, optionally below a prefix, to one of
+//! the remote targets of the bucket on a cron schedule. Runs are incremental: objects modified
+//! before the start of the last completed run are skipped, and the others are copied unless the
+//! remote copy carries the ETag of the local object, recorded in its metadata by the run that
+//! copied it. Objects deleted locally are kept on the remote.
+//!
+//! Schedules are kept in the cluster config. Every node runs the schedules, a run is named after
+//! the minute it was due and its record written create-only, so one node takes it and the others
+//! find it taken, as for the backups of the system configuration. The records of the last runs of
+//! a schedule form its history. A run that fails, or fails to copy some objects, sends an
+//! `s3:Backup:Failed` event for the bucket.
+
+use crate::storage::cron::CronSchedule;
+use aws_sdk_s3::error::DisplayErrorContext;
+use aws_sdk_s3::operation::head_object::HeadObjectOutput;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
+use http::HeaderMap;
+use parking_lot::RwLock;
+use rustfs_common::globals::GLOBAL_Local_Node_Name;
+use rustfs_ecstore::bucket::bucket_target_sys::{BucketTargetSys, TargetClient};
+use rustfs_ecstore::config::com::{delete_config, read_config, save_config, save_config_with_opts};
+use rustfs_ecstore::disk::RUSTFS_META_BUCKET;
+use rustfs_ecstore::error::{Error, Result, StorageError};
+use rustfs_ecstore::store::ECStore;
+use rustfs_ecstore::store_api::{
+    BucketOptions, GetObjectReader, HTTPPreconditions, ObjectInfo, ObjectInfoOrErr, ObjectOptions, StorageAPI, WalkOptions,
+};
+use rustfs_notify::{BACKUP_ELEMENT_PREFIX, EventArgs, EventArgsBuilder, notifier_global};
+use rustfs_targets::EventName;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+use time::OffsetDateTime;
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+const SCHEDULE_PREFIX: &str = "config/bucket-backup/schedules";
+const RUN_PREFIX: &str = "config/bucket-backup/runs";
+
+/// Metadata of a remote copy holding the ETag of the object it was copied from.
+pub const BACKUP_ETAG_META: &str = "rustfs-backup-etag";
+/// Metadata of a remote copy holding the modification time of the object it was copied from.
+pub const BACKUP_MTIME_META: &str = "rustfs-backup-mtime";
+
+const USER_METADATA_PREFIX: &str = "x-amz-meta-";
+
+const BACKUP_USER_AGENT: &str = "Internal: [Backup]";
+
+/// How often the schedules are checked for due runs.
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Number of objects listed per batch.
+const BACKUP_BATCH_SIZE: i32 = 1000;
+
+/// Objects larger than this are copied with a multipart upload of parts of this size.
+const BACKUP_PART_SIZE: u64 = 64 << 20;
+
+/// How often the record of a running run is saved.
+const RUN_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A run whose record was not saved for this long is taken as abandoned by its node.
+const STALE_RUN_AGE: time::Duration = time::Duration::minutes(10);
+
+/// Most runs kept in the history of a schedule.
+const MAX_RUN_HISTORY: usize = 50;
+
+pub static GLOBAL_BUCKET_BACKUP_SYS: LazyLock<BucketBackupSys> = LazyLock::new(BucketBackupSys::default);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSchedule {
+    #[serde(default)]
+    pub id: String,
+    pub bucket: String,
+    #[serde(default)]
+    pub prefix: String,
+    /// ARN of the remote target of the bucket the objects are copied to
+    pub arn: String,
+    /// Cron expression of the runs, in UTC
+    pub cron: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupRunState {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRun {
+    /// The minute the run was due, or the second it was requested at, as seconds since the epoch.
+    pub id: String,
+    pub schedule_id: String,
+    pub bucket: String,
+    /// The node running the run.
+    pub node: String,
+    /// Whether the run was requested rather than scheduled.
+    pub manual: bool,
+    pub state: BackupRunState,
+    #[serde(with = "time::serde::rfc3339")]
+    pub started: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated: OffsetDateTime,
+    /// Objects modified before this time were copied by an earlier run.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub since: Option<OffsetDateTime>,
+    pub objects_scanned: u64,
+    pub objects_copied: u64,
+    /// Objects unchanged since the last run.
+    pub objects_skipped: u64,
+    pub objects_failed: u64,
+    pub bytes_transferred: u64,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+impl BackupRun {
+    fn new(schedule: &BackupSchedule, at: OffsetDateTime, node: &str, since: Option<OffsetDateTime>, manual: bool) -> Self {
+        let now = OffsetDateTime::now_utc();
+        Self {
+            id: run_id(at),
+            schedule_id: schedule.id.clone(),
+            bucket: schedule.bucket.clone(),
+            node: node.to_string(),
+            manual,
+            state: BackupRunState::Running,
+            started: now,
+            updated: now,
+            since,
+            objects_scanned: 0,
+            objects_copied: 0,
+            objects_skipped: 0,
+            objects_failed: 0,
+            bytes_transferred: 0,
+            error: String::new(),
+        }
+    }
+
+    /// Whether the object described by `info` may have changed since the last completed run.
+    fn may_have_changed(&self, info: &ObjectInfo) -> bool {
+        match (self.since, info.mod_time) {
+            (Some(since), Some(mod_time)) => mod_time >= since,
+            _ => true,
+        }
+    }
+}
+
+/// Runs are named after the second they were due at, so names sort by time.
+fn run_id(at: OffsetDateTime) -> String {
+    format!("{:020}", at.unix_timestamp().max(0))
+}
+
+fn schedule_path(id: &str) -> String {
+    format!("{SCHEDULE_PREFIX}/{id}.json")
+}
+
+fn run_path(schedule_id: &str, run_id: &str) -> String {
+    format!("{RUN_PREFIX}/{schedule_id}/{run_id}.json")
+}
+
+/// Whether the remote copy described by `head` was copied from an object with `etag`.
+fn is_backed_up(head: &HeadObjectOutput, etag: &str) -> bool {
+    if etag.is_empty() {
+        return false;
+    }
+    if head
+        .metadata()
+        .and_then(|meta| meta.get(BACKUP_ETAG_META))
+        .map(String::as_str)
+        == Some(etag)
+    {
+        return true;
+    }
+    head.e_tag().map(|remote| remote.trim_matches('"')) == Some(etag)
+}
+
+/// The metadata of the remote copy of the object described by `info`.
+fn backup_metadata(info: &ObjectInfo) -> HashMap<String, String> {
+    let mut metadata: HashMap<String, String> = info
+        .user_defined
+        .iter()
+        .filter_map(|(key, value)| {
+            let key = key.to_lowercase();
+            key.strip_prefix(USER_METADATA_PREFIX)
+                .map(|name| (name.to_string(), value.clone()))
+        })
+        .collect();
+    if let Some(etag) = &info.etag {
+        metadata.insert(BACKUP_ETAG_META.to_string(), etag.clone());
+    }
+    if let Some(mod_time) = info.mod_time {
+        metadata.insert(BACKUP_MTIME_META.to_string(), mod_time.unix_timestamp().to_string());
+    }
+    metadata
+}
+
+/// The event sent when `run` of `schedule` failed.
+fn failure_event(schedule: &BackupSchedule, run: &BackupRun) -> EventArgs {
+    let object = ObjectInfo {
+        bucket: schedule.bucket.clone(),
+        name: schedule.prefix.clone(),
+        ..Default::default()
+    };
+    let elements = [
+        ("schedule", schedule.id.clone()),
+        ("run", run.id.clone()),
+        ("arn", schedule.arn.clone()),
+        ("state", format!("{:?}", run.state).to_lowercase()),
+        ("objects-copied", run.objects_copied.to_string()),
+        ("objects-failed", run.objects_failed.to_string()),
+        ("bytes-transferred", run.bytes_transferred.to_string()),
+        ("error", run.error.clone()),
+    ];
+
+    elements
+        .into_iter()
+        .fold(
+            EventArgsBuilder::new(EventName::BackupFailed, schedule.bucket.clone(), object),
+            |builder, (name, value)| builder.resp_element(format!("{BACKUP_ELEMENT_PREFIX}{name}"), value),
+        )
+        .host(run.node.clone())
+        .user_agent(BACKUP_USER_AGENT)
+        .build()
+}
+
+async fn list_config_objects(store: Arc<ECStore>, prefix: &str) -> Result<Vec<String>> {
+    let (tx, mut rx) = mpsc::channel::<ObjectInfoOrErr>(100);
+    let cancel = CancellationToken::new();
+    let walk = {
+        let store = store.clone();
+        let prefix = prefix.to_string();
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            store
+                .walk(cancel, RUSTFS_META_BUCKET, &prefix, tx, WalkOptions::default())
+                .await
+        })
+    };
+
+    let mut names = Vec::new();
+    while let Some(item) = rx.recv().await {
+        if let Some(err) = item.err {
+            cancel.cancel();
+            return Err(err);
+        }
+        if let Some(info) = item.item {
+            names.push(info.name);
+        }
+    }
+
+    match walk.await {
+        Ok(Ok(())) => Ok(names),
+        Ok(Err(err)) => Err(err),
+        Err(err) => Err(Error::other(err)),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BucketBackupSys {
+    /// Runs on this node by schedule, with the token cancelling them.
+    running: RwLock<HashMap<String, (String, CancellationToken)>>,
+}
+
+impl BucketBackupSys {
+    /// Validate and save `schedule`, with a new id when it has none.
+    pub async fn put_schedule(&self, store: Arc<ECStore>, mut schedule: BackupSchedule) -> Result<BackupSchedule> {
+        if schedule.id.is_empty() {
+            schedule.id = Uuid::new_v4().to_string();
+        } else if Uuid::parse_str(&schedule.id).is_err() {
+            return Err(StorageError::other(format!("invalid schedule id '{}'", schedule.id)));
+        }
+        CronSchedule::parse(&schedule.cron).map_err(StorageError::other)?;
+
+        store.get_bucket_info(&schedule.bucket, &BucketOptions::default()).await?;
+        if BucketTargetSys::get()
+            .get_remote_bucket_target_by_arn(&schedule.bucket, &schedule.arn)
+            .await
+            .is_none()
+        {
+            return Err(StorageError::other(format!(
+                "bucket {} has no remote target {}",
+                schedule.bucket, schedule.arn
+            )));
+        }
+
+        let data = serde_json::to_vec(&schedule).map_err(Error::other)?;
+        save_config(store, &schedule_path(&schedule.id), data).await?;
+        info!(
+            id = schedule.id,
+            bucket = schedule.bucket,
+            cron = schedule.cron,
+            "bucket backup schedule saved"
+        );
+        Ok(schedule)
+    }
+
+    pub async fn get_schedule(&self, store: Arc<ECStore>, id: &str) -> Result<Option<BackupSchedule>> {
+        if Uuid::parse_str(id).is_err() {
+            return Ok(None);
+        }
+        match read_config(store, &schedule_path(id)).await {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data).map_err(Error::other)?)),
+            Err(Error::ConfigNotFound) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn list_schedules(&self, store: Arc<ECStore>) -> Result<Vec<BackupSchedule>> {
+        let mut schedules = Vec::new();
+        for name in list_config_objects(store.clone(), &format!("{SCHEDULE_PREFIX}/")).await? {
+            match read_config(store.clone(), &name).await {
+                Ok(data) => match serde_json::from_slice::<BackupSchedule>(&data) {
+                    Ok(schedule) => schedules.push(schedule),
+                    Err(err) => warn!(name, "invalid bucket backup schedule: {:?}", err),
+                },
+                // Deleted while listing
+                Err(Error::ConfigNotFound) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(schedules)
+    }
+
+    /// Delete the schedule with `id` and its history, cancelling its run on this node.
+    pub async fn delete_schedule(&self, store: Arc<ECStore>, id: &str) -> Result<bool> {
+        if self.get_schedule(store.clone(), id).await?.is_none() {
+            return Ok(false);
+        }
+        self.cancel(id);
+        delete_config(store.clone(), &schedule_path(id)).await?;
+        for run in self.history(store.clone(), id).await? {
+            match delete_config(store.clone(), &run_path(id, &run.id)).await {
+                Ok(()) | Err(Error::ConfigNotFound) => {}
+                Err(err) => warn!(id, run = run.id, "delete bucket backup run failed: {:?}", err),
+            }
+        }
+        info!(id, "bucket backup schedule deleted");
+        Ok(true)
+    }
+
+    /// The runs of the schedule with `id`, oldest first.
+    pub async fn history(&self, store: Arc<ECStore>, id: &str) -> Result<Vec<BackupRun>> {
+        let mut names = list_config_objects(store.clone(), &format!("{RUN_PREFIX}/{id}/")).await?;
+        names.sort();
+
+        let mut runs = Vec::with_capacity(names.len());
+        for name in names {
+            match read_config(store.clone(), &name).await {
+                Ok(data) => match serde_json::from_slice::<BackupRun>(&data) {
+                    Ok(run) => runs.push(run),
+                    Err(err) => warn!(name, "invalid bucket backup run: {:?}", err),
+                },
+                Err(Error::ConfigNotFound) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(runs)
+    }
+
+    /// Start a run of the schedule with `id` now.
+    pub async fn run_now(&'static self, store: Arc<ECStore>, id: &str) -> Result<Option<BackupRun>> {
+        let Some(schedule) = self.get_schedule(store.clone(), id).await? else {
+            return Err(Error::ConfigNotFound);
+        };
+        self.trigger(store, schedule, OffsetDateTime::now_utc(), true).await
+    }
+
+    /// Cancel the run of the schedule with `id`; returns false when none runs on this node.
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.running.read().get(id) {
+            Some((_, cancel)) => {
+                cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Claim the run of `schedule` due at `at` and start it in the background. None when another
+    /// node took it, or the previous run of the schedule is still running.
+    async fn trigger(
+        &'static self,
+        store: Arc<ECStore>,
+        schedule: BackupSchedule,
+        at: OffsetDateTime,
+        manual: bool,
+    ) -> Result<Option<BackupRun>> {
+        if self.running.read().contains_key(&schedule.id) {
+            return Ok(None);
+        }
+
+        let history = self.history(store.clone(), &schedule.id).await?;
+        let now = OffsetDateTime::now_utc();
+        if let Some(last) = history.last()
+            && last.state == BackupRunState::Running
+            && now - last.updated < STALE_RUN_AGE
+        {
+            info!(
+                id = schedule.id,
+                run = last.id,
+                node = last.node,
+                "bucket backup still running, run skipped"
+            );
+            return Ok(None);
+        }
+        let since = history
+            .iter()
+            .rev()
+            .find(|run| run.state == BackupRunState::Completed)
+            .map(|run| run.started);
+
+        let node = GLOBAL_Local_Node_Name.read().await.clone();
+        let run = BackupRun::new(&schedule, at, &node, since, manual);
+        let data = serde_json::to_vec(&run).map_err(Error::other)?;
+        let opts = ObjectOptions {
+            http_preconditions: Some(HTTPPreconditions {
+                if_none_match: Some("*".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        match save_config_with_opts(store.clone(), &run_path(&schedule.id, &run.id), data, &opts).await {
+            Ok(()) => {}
+            Err(StorageError::PreconditionFailed) => return Ok(None),
+            Err(err) => return Err(err),
+        }
+
+        let cancel = CancellationToken::new();
+        self.running
+            .write()
+            .insert(schedule.id.clone(), (run.id.clone(), cancel.clone()));
+        info!(id = schedule.id, run = run.id, bucket = schedule.bucket, "bucket backup started");
+
+        let status = run.clone();
+        tokio::spawn(async move {
+            let id = schedule.id.clone();
+            let run = self.run(store.clone(), &schedule, run, cancel).await;
+            if run.state == BackupRunState::Failed {
+                error!(id, run = run.id, "bucket backup failed: {}", run.error);
+                notifier_global::notify(failure_event(&schedule, &run)).await;
+            }
+            save_run(store.clone(), &run).await;
+            self.running.write().remove(&id);
+            self.prune(store, &id).await;
+        });
+
+        Ok(Some(status))
+    }
+
+    /// Delete the oldest runs of the schedule with `id` to keep [`MAX_RUN_HISTORY`].
+    async fn prune(&self, store: Arc<ECStore>, id: &str) {
+        let history = match self.history(store.clone(), id).await {
+            Ok(history) => history,
+            Err(err) => {
+                warn!(id, "list bucket backup runs failed: {:?}", err);
+                return;
+            }
+        };
+        let excess = history.len().saturating_sub(MAX_RUN_HISTORY);
+        for run in &history[..excess] {
+            match delete_config(store.clone(), &run_path(id, &run.id)).await {
+                Ok(()) | Err(Error::ConfigNotFound) => {}
+                Err(err) => warn!(id, run = run.id, "delete expired bucket backup run failed: {:?}", err),
+            }
+        }
+    }
+
+    async fn run(
+        &self,
+        store: Arc<ECStore>,
+        schedule: &BackupSchedule,
+        mut run: BackupRun,
+        cancel: CancellationToken,
+    ) -> BackupRun {
+        match self.mirror(store, schedule, &mut run, &cancel).await {
+            Ok(()) if cancel.is_cancelled() => run.state = BackupRunState::Cancelled,
+            Ok(()) if run.objects_failed > 0 => {
+                run.state = BackupRunState::Failed;
+                run.error = format!("{} objects could not be copied, last error: {}", run.objects_failed, run.error);
+            }
+            Ok(()) => run.state = BackupRunState::Completed,
+            Err(err) => {
+                run.state = BackupRunState::Failed;
+                run.error = err.to_string();
+            }
+        }
+        run.updated = OffsetDateTime::now_utc();
+        info!(
+            id = schedule.id,
+            run = run.id,
+            copied = run.objects_copied,
+            skipped = run.objects_skipped,
+            failed = run.objects_failed,
+            bytes = run.bytes_transferred,
+            state = ?run.state,
+            "bucket backup finished"
+        );
+        run
+    }
+
+    async fn mirror(
+        &self,
+        store: Arc<ECStore>,
+        schedule: &BackupSchedule,
+        run: &mut BackupRun,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        let Some(client) = BucketTargetSys::get()
+            .get_remote_target_client(&schedule.bucket, &schedule.arn)
+            .await
+        else {
+            return Err(StorageError::other(format!(
+                "remote target {} of bucket {} is unavailable",
+                schedule.arn, schedule.bucket
+            )));
+        };
+
+        let mut continuation_token = None;
+        let mut last_saved = Instant::now();
+        loop {
+            let listing = store
+                .clone()
+                .list_objects_v2(
+                    &schedule.bucket,
+                    &schedule.prefix,
+                    continuation_token.take(),
+                    None,
+                    BACKUP_BATCH_SIZE,
+                    false,
+                    None,
+                    false,
+                    None,
+                )
+                .await?;
+
+            for info in listing.objects {
+                if cancel.is_cancelled() {
+                    return Ok(());
+                }
+                if info.delete_marker || info.is_dir {
+                    continue;
+                }
+                run.objects_scanned += 1;
+                if !run.may_have_changed(&info) {
+                    run.objects_skipped += 1;
+                    continue;
+                }
+
+                match copy_object(store.clone(), &client, &info).await {
+                    Ok(Some(bytes)) => {
+                        run.objects_copied += 1;
+                        run.bytes_transferred += bytes;
+                    }
+                    Ok(None) => run.objects_skipped += 1,
+                    Err(err) => {
+                        warn!(id = schedule.id, object = info.name, "bucket backup copy failed: {:?}", err);
+                        run.objects_failed += 1;
+                        run.error = format!("{}: {}", info.name, err);
+                    }
+                }
+
+                if last_saved.elapsed() >= RUN_SAVE_INTERVAL {
+                    run.updated = OffsetDateTime::now_utc();
+                    save_run(store.clone(), run).await;
+                    last_saved = Instant::now();
+                }
+            }
+
+            if !listing.is_truncated {
+                return Ok(());
+            }
+            continuation_token = listing.next_continuation_token;
+            if continuation_token.is_none() {
+                return Err(StorageError::other("object listing truncated without a continuation token"));
+            }
+        }
+    }
+}
+
+async fn save_run(store: Arc<ECStore>, run: &BackupRun) {
+    match serde_json::to_vec(run) {
+        Ok(data) => {
+            if let Err(err) = save_config(store, &run_path(&run.schedule_id, &run.id), data).await {
+                warn!(id = run.schedule_id, run = run.id, "save bucket backup run failed: {:?}", err);
+            }
+        }
+        Err(err) => warn!(id = run.schedule_id, run = run.id, "marshal bucket backup run failed: {:?}", err),
+    }
+}
+
+/// Copy the object described by `info` to the remote target, unless its remote copy is current.
+/// Returns the bytes transferred, None when the object was skipped.
+async fn copy_object(store: Arc<ECStore>, client: &TargetClient, info: &ObjectInfo) -> Result<Option<u64>> {
+    let etag = info.etag.clone().unwrap_or_default();
+    if let Ok(head) = client.head_object(&client.bucket, &info.name, None).await
+        && is_backed_up(&head, &etag)
+    {
+        return Ok(None);
+    }
+
+    let opts = ObjectOptions {
+        version_id: info.version_id.map(|v| v.to_string()),
+        ..Default::default()
+    };
+    let mut reader = store
+        .get_object_reader(&info.bucket, &info.name, None, HeaderMap::new(), &opts)
+        .await?;
+    let size = u64::try_from(reader.object_info.get_actual_size()?).unwrap_or_default();
+    let metadata = backup_metadata(&reader.object_info);
+    let content_type = reader.object_info.content_type.clone();
+    let tagging = Some(reader.object_info.user_tags.clone()).filter(|tags| !tags.is_empty());
+
+    if size <= BACKUP_PART_SIZE {
+        let body = reader.read_all().await?;
+        client
+            .client
+            .put_object()
+            .bucket(&client.bucket)
+            .key(&info.name)
+            .content_length(body.len() as i64)
+            .set_content_type(content_type)
+            .set_metadata(Some(metadata))
+            .set_tagging(tagging)
+            .body(ByteStream::from(body))
+            .send()
+            .await
+            .map_err(|e| StorageError::other(format!("put object failed: {}", DisplayErrorContext(&e))))?;
+        return Ok(Some(size));
+    }
+
+    let upload = client
+        .client
+        .create_multipart_upload()
+        .bucket(&client.bucket)
+        .key(&info.name)
+        .set_content_type(content_type)
+        .set_metadata(Some(metadata))
+        .set_tagging(tagging)
+        .send()
+        .await
+        .map_err(|e| StorageError::other(format!("create multipart upload failed: {}", DisplayErrorContext(&e))))?;
+    let upload_id = upload.upload_id().unwrap_or_default().to_string();
+
+    let parts = match upload_parts(client, &info.name, &upload_id, &mut reader).await {
+        Ok(parts) => parts,
+        Err(err) => {
+            if let Err(e) = client
+                .client
+                .abort_multipart_upload()
+                .bucket(&client.bucket)
+                .key(&info.name)
+                .upload_id(&upload_id)
+                .send()
+                .await
+            {
+                warn!(object = info.name, "abort multipart upload failed: {}", DisplayErrorContext(&e));
+            }
+            return Err(err);
+        }
+    };
+
+    client
+        .client
+        .complete_multipart_upload()
+        .bucket(&client.bucket)
+        .key(&info.name)
+        .upload_id(&upload_id)
+        .multipart_upload(CompletedMultipartUpload::builder().set_parts(Some(parts)).build())
+        .send()
+        .await
+        .map_err(|e| StorageError::other(format!("complete multipart upload failed: {}", DisplayErrorContext(&e))))?;
+    Ok(Some(size))
+}
+
+async fn upload_parts(
+    client: &TargetClient,
+    object: &str,
+    upload_id: &str,
+    reader: &mut GetObjectReader,
+) -> Result<Vec<CompletedPart>> {
+    let mut parts = Vec::new();
+    loop {
+        let mut chunk = Vec::with_capacity(BACKUP_PART_SIZE as usize);
+        (&mut reader.stream).take(BACKUP_PART_SIZE).read_to_end(&mut chunk).await?;
+        if chunk.is_empty() {
+            return Ok(parts);
+        }
+
+        let part_number = parts.len() as i32 + 1;
+        let part = client
+            .client
+            .upload_part()
+            .bucket(&client.bucket)
+            .key(object)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .content_length(chunk.len() as i64)
+            .body(ByteStream::from(chunk))
+            .send()
+            .await
+            .map_err(|e| StorageError::other(format!("upload part {part_number} failed: {}", DisplayErrorContext(&e))))?;
+        parts.push(
+            CompletedPart::builder()
+                .part_number(part_number)
+                .set_e_tag(part.e_tag().map(str::to_string))
+                .build(),
+        );
+    }
+}
+
+/// Run the enabled schedules when they are due, checking every [`SCHEDULER_INTERVAL`]. Runs due
+/// while the node was down are not caught up.
+pub async fn init_bucket_backup(store: Arc<ECStore>, cancel: CancellationToken) {
+    tokio::spawn(async move {
+        let mut checked = OffsetDateTime::now_utc();
+        let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = interval.tick() => {
+                    let now = OffsetDateTime::now_utc();
+                    let schedules = match GLOBAL_BUCKET_BACKUP_SYS.list_schedules(store.clone()).await {
+                        Ok(schedules) => schedules,
+                        Err(err) => {
+                            warn!("list bucket backup schedules failed: {:?}", err);
+                            continue;
+                        }
+                    };
+
+                    for schedule in schedules.into_iter().filter(|schedule| schedule.enabled) {
+                        let cron = match CronSchedule::parse(&schedule.cron) {
+                            Ok(cron) => cron,
+                            Err(err) => {
+                                warn!(id = schedule.id, "invalid bucket backup schedule: {}", err);
+                                continue;
+                            }
+                        };
+                        // The last run due since the previous check
+                        let mut due = None;
+                        let mut next = cron.next_after(checked);
+                        while let Some(at) = next.filter(|at| *at <= now) {
+                            due = Some(at);
+                            next = cron.next_after(at);
+                        }
+                        if let Some(at) = due {
+                            let id = schedule.id.clone();
+                            if let Err(err) = GLOBAL_BUCKET_BACKUP_SYS.trigger(store.clone(), schedule, at, false).await {
+                                warn!(id, "start bucket backup failed: {:?}", err);
+                            }
+                        }
+                    }
+                    checked = now;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> BackupSchedule {
+        BackupSchedule {
+            id: "7a0f2f4e-3c1d-4a8e-9b9e-0c6a4f1d2b3c".to_string(),
+            bucket: "photos".to_string(),
+            prefix: "2024/".to_string(),
+            arn: "arn:rustfs:replication::id:backup".to_string(),
+            cron: "0 3 * * *".to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_may_have_changed() {
+        let now = OffsetDateTime::now_utc();
+        let object = |mod_time| ObjectInfo {
+            name: "a".to_string(),
+            mod_time,
+            ..Default::default()
+        };
+
+        let first = BackupRun::new(&schedule(), now, "node1", None, false);
+        assert!(first.may_have_changed(&object(Some(now - time::Duration::days(30)))));
+
+        let next = BackupRun::new(&schedule(), now, "node1", Some(now - time::Duration::days(1)), false);
+        assert!(!next.may_have_changed(&object(Some(now - time::Duration::days(2)))));
+        assert!(next.may_have_changed(&object(Some(now - time::Duration::hours(1)))));
+        assert!(next.may_have_changed(&object(None)));
+    }
+
+    #[test]
+    fn test_is_backed_up() {
+        let copied = HeadObjectOutput::builder()
+            .e_tag("\"multipart-etag-2\"")
+            .metadata(BACKUP_ETAG_META, "abc123")
+            .build();
+        assert!(is_backed_up(&copied, "abc123"));
+        assert!(!is_backed_up(&copied, "def456"));
+
+        let uploaded = HeadObjectOutput::builder().e_tag("\"abc123\"").build();
+        assert!(is_backed_up(&uploaded, "abc123"));
+        assert!(!is_backed_up(&uploaded, ""));
+    }
+
+    #[test]
+    fn test_backup_metadata() {
+        let mod_time = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let info = ObjectInfo {
+            etag: Some("abc123".to_string()),
+            mod_time: Some(mod_time),
+            user_defined: HashMap::from([
+                ("X-Amz-Meta-Camera".to_string(), "x100".to_string()),
+                ("content-type".to_string(), "image/jpeg".to_string()),
+            ]),
+            ..Default::default()
+        };
+
+        let metadata = backup_metadata(&info);
+        assert_eq!(metadata.len(), 3);
+        assert_eq!(metadata.get("camera").map(String::as_str), Some("x100"));
+        assert_eq!(metadata.get(BACKUP_ETAG_META).map(String::as_str), Some("abc123"));
+        assert_eq!(metadata.get(BACKUP_MTIME_META).map(String::as_str), Some("1700000000"));
+    }
+
+    #[test]
+    fn test_failure_event() {
+        let mut run = BackupRun::new(&schedule(), OffsetDateTime::now_utc(), "node1:9000", None, false);
+        run.state = BackupRunState::Failed;
+        run.objects_failed = 2;
+        run.error = "remote target unavailable".to_string();
+
+        let args = failure_event(&schedule(), &run);
+        assert_eq!(args.event_name, EventName::BackupFailed);
+        assert_eq!(args.bucket_name, "photos");
+        assert_eq!(args.user_agent, BACKUP_USER_AGENT);
+        assert_eq!(args.resp_elements.get("x-rustfs-backup-state").map(String::as_str), Some("failed"));
+        assert_eq!(args.resp_elements.get("x-rustfs-backup-objects-failed").map(String::as_str), Some("2"));
+    }
+
+    #[test]
+    fn test_run_ids_sort_by_time() {
+        let early = OffsetDateTime::from_unix_timestamp(999).unwrap();
+        let late = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        assert!(run_id(early) < run_id(late));
+        assert_eq!(run_path("s", &run_id(late)), "config/bucket-backup/runs/s/00000000001700000000.json");
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HTTP conditional request evaluation for reads and copy sources.
+//!
+//! Conditions are evaluated in the order of RFC 7232 section 6, which is also what S3 does:
+//! `If-Match` takes precedence over `If-Unmodified-Since` and `If-None-Match` takes precedence
+//! over `If-Modified-Since`. Dates only carry whole seconds, so modification times are compared
+//! at second granularity.
+
+use s3s::{S3Error, S3ErrorCode, S3Result};
+use time::OffsetDateTime;
+
+/// Conditional headers of a request, or the `x-amz-copy-source-if-*` headers of a copy.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Preconditions<'a> {
+    pub if_match: Option<&'a str>,
+    pub if_none_match: Option<&'a str>,
+    pub if_modified_since: Option<OffsetDateTime>,
+    pub if_unmodified_since: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PreconditionResult {
+    Proceed,
+    NotModified,
+    Failed,
+}
+
+/// Whether `etag` matches an `If-Match`/`If-None-Match` value, which may be `*` or a
+/// comma-separated list of quoted and optionally weak entity tags.
+fn etag_matches(condition: &str, etag: Option<&str>) -> bool {
+    let Some(etag) = etag else {
+        return false;
+    };
+    let etag = etag.trim_matches('"');
+    condition
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/").trim_matches('"') == etag)
+}
+
+fn truncate_to_second(t: OffsetDateTime) -> OffsetDateTime {
+    t.replace_nanosecond(0).unwrap_or(t)
+}
+
+impl Preconditions<'_> {
+    /// Evaluate the conditions against the current `etag` and `mod_time` of the object.
+    pub(crate) fn evaluate(&self, etag: Option<&str>, mod_time: Option<OffsetDateTime>) -> PreconditionResult {
+        let mod_time = mod_time.map(truncate_to_second);
+
+        if let Some(if_match) = self.if_match {
+            if !etag_matches(if_match, etag) {
+                return PreconditionResult::Failed;
+            }
+        } else if let (Some(since), Some(mod_time)) = (self.if_unmodified_since, mod_time)
+            && mod_time > since
+        {
+            return PreconditionResult::Failed;
+        }
+
+        if let Some(if_none_match) = self.if_none_match {
+            if etag_matches(if_none_match, etag) {
+                return PreconditionResult::NotModified;
+            }
+        } else if let (Some(since), Some(mod_time)) = (self.if_modified_since, mod_time)
+            && mod_time <= since
+        {
+            return PreconditionResult::NotModified;
+        }
+
+        PreconditionResult::Proceed
+    }
+
+    /// Check the conditions of a GET or HEAD, answering 304 or 412 as appropriate.
+    pub(crate) fn check_read(&self, etag: Option<&str>, mod_time: Option<OffsetDateTime>) -> S3Result<()> {
+        match self.evaluate(etag, mod_time) {
+            PreconditionResult::Proceed => Ok(()),
+            PreconditionResult::NotModified => Err(S3Error::new(S3ErrorCode::NotModified)),
+            PreconditionResult::Failed => Err(S3Error::new(S3ErrorCode::PreconditionFailed)),
+        }
+    }
+
+    /// Check the conditions on a copy source, where any unmet condition is a 412.
+    pub(crate) fn check_copy_source(&self, etag: Option<&str>, mod_time: Option<OffsetDateTime>) -> S3Result<()> {
+        match self.evaluate(etag, mod_time) {
+            PreconditionResult::Proceed => Ok(()),
+            PreconditionResult::NotModified | PreconditionResult::Failed => Err(S3Error::new(S3ErrorCode::PreconditionFailed)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    const ETAG: &str = "\"abc\"";
+    const MOD_TIME: OffsetDateTime = datetime!(2024-05-01 12:00:00.500 UTC);
+
+    fn eval(p: Preconditions<'_>) -> PreconditionResult {
+        p.evaluate(Some(ETAG), Some(MOD_TIME))
+    }
+
+    #[test]
+    fn test_etag_conditions() {
+        let matching = Preconditions {
+            if_match: Some("\"abc\""),
+            ..Default::default()
+        };
+        assert_eq!(eval(matching), PreconditionResult::Proceed);
+
+        let listed = Preconditions {
+            if_match: Some("\"x\", W/\"abc\""),
+            ..Default::default()
+        };
+        assert_eq!(eval(listed), PreconditionResult::Proceed);
+
+        let mismatch = Preconditions {
+            if_match: Some("\"x\""),
+            ..Default::default()
+        };
+        assert_eq!(eval(mismatch), PreconditionResult::Failed);
+
+        let none_match = Preconditions {
+            if_none_match: Some("*"),
+            ..Default::default()
+        };
+        assert_eq!(eval(none_match), PreconditionResult::NotModified);
+        assert_eq!(none_match.evaluate(None, Some(MOD_TIME)), PreconditionResult::Proceed);
+    }
+
+    #[test]
+    fn test_date_conditions_use_second_granularity() {
+        let same_second = datetime!(2024-05-01 12:00:00 UTC);
+
+        let modified = Preconditions {
+            if_modified_since: Some(same_second),
+            ..Default::default()
+        };
+        assert_eq!(eval(modified), PreconditionResult::NotModified);
+
+        let unmodified = Preconditions {
+            if_unmodified_since: Some(same_second),
+            ..Default::default()
+        };
+        assert_eq!(eval(unmodified), PreconditionResult::Proceed);
+
+        let before = Preconditions {
+            if_unmodified_since: Some(datetime!(2024-05-01 11:59:59 UTC)),
+            ..Default::default()
+        };
+        assert_eq!(eval(before), PreconditionResult::Failed);
+    }
+
+    #[test]
+    fn test_precedence() {
+        // If-Match succeeding overrides a failing If-Unmodified-Since.
+        let p = Preconditions {
+            if_match: Some(ETAG),
+            if_unmodified_since: Some(datetime!(2020-01-01 0:00 UTC)),
+            ..Default::default()
+        };
+        assert_eq!(eval(p), PreconditionResult::Proceed);
+
+        // If-None-Match not matching overrides a not-modified If-Modified-Since.
+        let p = Preconditions {
+            if_none_match: Some("\"other\""),
+            if_modified_since: Some(datetime!(2030-01-01 0:00 UTC)),
+            ..Default::default()
+        };
+        assert_eq!(eval(p), PreconditionResult::Proceed);
+
+        // A failed precondition wins over not-modified.
+        let p = Preconditions {
+            if_match: Some("\"other\""),
+            if_none_match: Some(ETAG),
+            ..Default::default()
+        };
+        assert_eq!(eval(p), PreconditionResult::Failed);
+        assert!(p.check_copy_source(Some(ETAG), Some(MOD_TIME)).is_err());
+    }
+}
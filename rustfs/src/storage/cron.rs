@@ -0,0 +1,214 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cron expressions of scheduled jobs.
+//!
+//! An expression has five fields, minute, hour, day of month, month and day of week, and is
+//! evaluated in UTC. A field is `*` or a comma separated list of values and `a-b` ranges, each
+//! optionally followed by a `/step`. Days of the week run from 0, Sunday, to 6, and 7 is Sunday
+//! too. As in cron, when both the day of month and the day of week are restricted, a day matching
+//! either of them matches.
+
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, Time};
+
+/// How far ahead the next run of an expression is searched, long enough for leap days.
+const MAX_SEARCH_DAYS: i64 = 366 * 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+/// The values of a field as a bit set, and whether the field is restricted.
+fn parse_field(field: &str, name: &str, min: u32, max: u32) -> Result<(u64, bool), String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step '{step}' in the {name} field"))?;
+                if step == 0 {
+                    return Err(format!("the step of the {name} field must be at least 1"));
+                }
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let parse = |value: &str| {
+            value
+                .parse::<u32>()
+                .ok()
+                .filter(|v| (min..=max).contains(v))
+                .ok_or_else(|| format!("invalid value '{value}' in the {name} field, expected {min}-{max}"))
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (parse(start)?, parse(end)?),
+                // `5/15` runs from 5 to the end of the range
+                None if step.is_some() => (parse(range)?, max),
+                None => {
+                    let value = parse(range)?;
+                    (value, value)
+                }
+            },
+        };
+        if start > end {
+            return Err(format!("invalid range '{range}' in the {name} field"));
+        }
+
+        for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok((bits, field != "*"))
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("a cron expression has 5 fields, '{expr}' has {}", fields.len()));
+        };
+
+        let (minutes, _) = parse_field(minute, "minute", 0, 59)?;
+        let (hours, _) = parse_field(hour, "hour", 0, 23)?;
+        let (days, days_restricted) = parse_field(day, "day of month", 1, 31)?;
+        let (months, _) = parse_field(month, "month", 1, 12)?;
+        let (mut weekdays, weekdays_restricted) = parse_field(weekday, "day of week", 0, 7)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+
+        let schedule = Self {
+            minutes,
+            hours,
+            days,
+            months,
+            weekdays,
+            days_restricted,
+            weekdays_restricted,
+        };
+        if schedule.next_after(OffsetDateTime::UNIX_EPOCH).is_none() {
+            return Err(format!("'{expr}' never runs"));
+        }
+        Ok(schedule)
+    }
+
+    fn matches_day(&self, date: time::Date) -> bool {
+        if self.months & (1 << u8::from(date.month())) == 0 {
+            return false;
+        }
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().number_days_from_sunday()) != 0;
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// Whether the schedule runs at the minute of `t`.
+    pub fn matches(&self, t: OffsetDateTime) -> bool {
+        self.matches_day(t.date()) && self.hours & (1 << t.hour()) != 0 && self.minutes & (1 << t.minute()) != 0
+    }
+
+    /// The first minute after `t` the schedule runs at.
+    pub fn next_after(&self, t: OffsetDateTime) -> Option<OffsetDateTime> {
+        let t = t.to_offset(time::UtcOffset::UTC);
+        let start = t.replace_second(0).ok()?.replace_nanosecond(0).ok()? + Duration::minutes(1);
+
+        for offset in 0..MAX_SEARCH_DAYS {
+            let date = start.date().checked_add(Duration::days(offset))?;
+            if !self.matches_day(date) {
+                continue;
+            }
+            let first_hour = if offset == 0 { start.hour() } else { 0 };
+            for hour in first_hour..24 {
+                if self.hours & (1 << hour) == 0 {
+                    continue;
+                }
+                let first_minute = if offset == 0 && hour == start.hour() {
+                    start.minute()
+                } else {
+                    0
+                };
+                if let Some(minute) = (first_minute..60).find(|minute| self.minutes & (1 << minute) != 0) {
+                    let time = Time::from_hms(hour, minute, 0).ok()?;
+                    return Some(PrimitiveDateTime::new(date, time).assume_utc());
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_parse() {
+        assert!(CronSchedule::parse("*/15 2-4 * * 1-5").is_ok());
+        assert!(CronSchedule::parse("0 0 1,15 * 7").is_ok());
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("5-1 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("0 0 30 2 *").is_err());
+    }
+
+    #[test]
+    fn test_next_after() {
+        let quarter = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            quarter.next_after(datetime!(2024-03-10 10:07:30 UTC)),
+            Some(datetime!(2024-03-10 10:15 UTC))
+        );
+        assert_eq!(quarter.next_after(datetime!(2024-03-10 10:15 UTC)), Some(datetime!(2024-03-10 10:30 UTC)));
+        assert_eq!(quarter.next_after(datetime!(2024-12-31 23:50 UTC)), Some(datetime!(2025-01-01 00:00 UTC)));
+
+        // 2024-03-10 is a Sunday
+        let monday = CronSchedule::parse("30 3 * * 1").unwrap();
+        assert_eq!(monday.next_after(datetime!(2024-03-10 12:00 UTC)), Some(datetime!(2024-03-11 03:30 UTC)));
+        assert!(monday.matches(datetime!(2024-03-11 03:30:59 UTC)));
+        assert!(!monday.matches(datetime!(2024-03-12 03:30 UTC)));
+
+        let leap = CronSchedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(leap.next_after(datetime!(2024-03-01 00:00 UTC)), Some(datetime!(2028-02-29 00:00 UTC)));
+    }
+
+    #[test]
+    fn test_day_of_month_or_day_of_week() {
+        // The 1st of the month or any Sunday
+        let schedule = CronSchedule::parse("0 0 1 * 0").unwrap();
+        assert!(schedule.matches(datetime!(2024-03-10 00:00 UTC)));
+        assert!(schedule.matches(datetime!(2024-04-01 00:00 UTC)));
+        assert!(!schedule.matches(datetime!(2024-03-11 00:00 UTC)));
+
+        // Only the restricted field counts when the other is `*`
+        let sundays = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert!(sundays.matches(datetime!(2024-03-10 00:00 UTC)));
+        assert!(!sundays.matches(datetime!(2024-04-01 00:00 UTC)));
+    }
+}
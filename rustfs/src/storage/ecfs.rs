@@ -17,15 +17,19 @@ use crate::config::workload_profiles::{
     RustFSBufferConfig, WorkloadProfile, get_global_buffer_config, is_buffer_profile_enabled,
 };
 use crate::error::ApiError;
+use crate::server::ChainIdentity;
 use crate::storage::entity;
 use crate::storage::helper::OperationHelper;
 use crate::storage::options::{filter_object_metadata, get_content_sha256};
 use crate::storage::{
-    access::{ReqInfo, authorize_request},
+    access::{ReqInfo, authorize_request, is_bucket_accessible},
+    conditional::Preconditions,
     options::{
         copy_dst_opts, copy_src_opts, del_opts, extract_metadata, extract_metadata_from_mime_with_object_name,
         get_complete_multipart_upload_opts, get_opts, parse_copy_source_range, put_opts,
     },
+    payload::{ConnectionStats, LengthEnforcedStream, resolve_payload_size},
+    transform::{TransformRequest, bucket_transformer},
 };
 use base64::{Engine, engine::general_purpose::STANDARD as BASE64_STANDARD};
 use bytes::Bytes;
@@ -38,10 +42,13 @@ use http::{HeaderMap, StatusCode};
 use metrics::counter;
 use rustfs_ecstore::{
     bucket::{
+        integrity::bucket_integrity,
+        kms_scope::{KmsKeyNotAllowed, bucket_kms_scope},
         lifecycle::{
             bucket_lifecycle_ops::{RestoreRequestOps, post_restore_opts, validate_transition_tier},
             lifecycle::{self, Lifecycle, TransitionOptions},
         },
+        listing::ListingConsistency,
         metadata::{
             BUCKET_LIFECYCLE_CONFIG, BUCKET_NOTIFICATION_CONFIG, BUCKET_POLICY_CONFIG, BUCKET_REPLICATION_CONFIG,
             BUCKET_SSECONFIG, BUCKET_TAGGING_CONFIG, BUCKET_VERSIONING_CONFIG, OBJECT_LOCK_CONFIG,
@@ -50,19 +57,26 @@ use rustfs_ecstore::{
         metadata_sys::get_replication_config,
         object_lock::objectlock_sys::BucketObjectLockSys,
         policy_sys::PolicySys,
+        purge::GLOBAL_BUCKET_PURGE_SYS,
         replication::{
             DeletedObjectReplicationInfo, ReplicationConfigurationExt, check_replicate_delete, get_must_replicate_options,
             must_replicate, schedule_replication, schedule_replication_delete,
         },
+        snapshot::{self, SNAPSHOT_HEADER},
         tagging::{decode_tags, encode_tags},
+        trash::{BucketTrash, move_to_trash},
         utils::serialize,
         versioning::VersioningApi,
         versioning_sys::BucketVersioningSys,
     },
     client::object_api_utils::to_s3s_etag,
+    compat::{MULTIPART_HIDDEN_KEY, UPLOAD_VISIBILITY_HEADER},
     compress::{MIN_COMPRESSIBLE_SIZE, is_compressible},
+    config::storageclass,
     disk::{error::DiskError, error_reduce::is_all_buckets_not_found},
     error::{StorageError, is_err_bucket_not_found, is_err_object_not_found, is_err_version_not_found},
+    federation::GLOBAL_FEDERATION_SYS,
+    list_token::{decode_continuation_token, encode_continuation_token},
     new_object_layer_fn,
     set_disk::{DEFAULT_READ_BUFFER_SIZE, MAX_PARTS_COUNT, is_valid_storage_class},
     store_api::{
@@ -108,7 +122,8 @@ use rustfs_targets::{
     arn::{TargetID, TargetIDError},
 };
 use rustfs_utils::{
-    CompressionAlgorithm, extract_req_params_header, extract_resp_elements, get_request_host, get_request_user_agent,
+    CompressionAlgorithm, bytes_chunks, extract_req_params_header, extract_resp_elements, get_request_host,
+    get_request_user_agent,
     http::{
         AMZ_BUCKET_REPLICATION_STATUS, AMZ_CHECKSUM_MODE, AMZ_CHECKSUM_TYPE,
         headers::{
@@ -121,7 +136,6 @@ use rustfs_utils::{
 use rustfs_zip::CompressionFormat;
 use s3s::header::{X_AMZ_RESTORE, X_AMZ_RESTORE_OUTPUT_PATH};
 use s3s::{S3, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, dto::*, s3_error};
-use std::ops::Add;
 use std::{
     collections::HashMap,
     fmt::Debug,
@@ -153,6 +167,15 @@ static RUSTFS_OWNER: LazyLock<Owner> = LazyLock::new(|| Owner {
     id: Some("c19050dbcee97fda828689dda99097a6321af2248fa760517237346e5d9c8a66".to_owned()),
 });
 
+/// Upper bound of `MaxBuckets` accepted by ListBuckets.
+const MAX_LIST_BUCKETS: i32 = 10000;
+
+/// Maximum number of tags in a bucket tag set.
+const MAX_BUCKET_TAGS: usize = 50;
+
+/// Region reported for buckets when the server has no region configured.
+const DEFAULT_BUCKET_REGION: &str = "us-east-1";
+
 /// Calculate adaptive buffer size with workload profile support.
 ///
 /// This enhanced version supports different workload profiles for optimal performance
@@ -295,7 +318,7 @@ async fn create_managed_encryption_material(
         .clone()
         .ok_or_else(|| ApiError::from(StorageError::other("No KMS key available for managed server-side encryption")))?;
 
-    let (data_key, encrypted_data_key) = service
+    let (data_key, encrypted_data_key, key_version) = service
         .create_data_key(&kms_key_candidate, &context)
         .await
         .map_err(|e| ApiError::from(StorageError::other(format!("Failed to create data key: {e}"))))?;
@@ -303,7 +326,7 @@ async fn create_managed_encryption_material(
     let metadata = EncryptionMetadata {
         algorithm: algorithm_str.to_string(),
         key_id: kms_key_to_use.clone(),
-        key_version: 1,
+        key_version,
         iv: data_key.nonce.to_vec(),
         tag: None,
         encryption_context: context.encryption_context.clone(),
@@ -429,12 +452,13 @@ async fn decrypt_multipart_managed_stream(
 }
 
 fn strip_managed_encryption_metadata(metadata: &mut HashMap<String, String>) {
-    const KEYS: [&str; 7] = [
+    const KEYS: [&str; 8] = [
         "x-amz-server-side-encryption",
         "x-amz-server-side-encryption-aws-kms-key-id",
         "x-rustfs-encryption-iv",
         "x-rustfs-encryption-tag",
         "x-rustfs-encryption-key",
+        "x-rustfs-encryption-key-version",
         "x-rustfs-encryption-context",
         "x-rustfs-encryption-original-size",
     ];
@@ -448,6 +472,58 @@ fn is_managed_sse(algorithm: &ServerSideEncryption) -> bool {
     matches!(algorithm.as_str(), "AES256" | "aws:kms")
 }
 
+/// Hex of the digest sent as Content-MD5, InvalidDigest when it is not the base64 of an MD5.
+fn content_md5_hex(content_md5: Option<&str>) -> S3Result<Option<String>> {
+    let Some(base64_md5) = content_md5 else {
+        return Ok(None);
+    };
+    match base64_simd::STANDARD.decode_to_vec(base64_md5.as_bytes()) {
+        Ok(md5) if md5.len() == 16 => Ok(Some(hex_simd::encode_to_string(&md5, hex_simd::AsciiCase::Lower))),
+        _ => Err(s3_error!(InvalidDigest, "The Content-Md5 you specified is not valid.")),
+    }
+}
+
+/// The KMS key of a write to `bucket`, refusing keys other than the one the bucket is bound to.
+async fn scoped_kms_key(
+    bucket: &str,
+    sse: Option<&ServerSideEncryption>,
+    kms_key_id: Option<String>,
+) -> Result<Option<String>, KmsKeyNotAllowed> {
+    let Some(scope) = bucket_kms_scope(bucket).await else {
+        return Ok(kms_key_id);
+    };
+    let sse_kms = sse.is_some_and(|sse| sse.as_str() == ServerSideEncryption::AWS_KMS);
+    scope.resolve(bucket, sse_kms, kms_key_id)
+}
+
+/// Refuse a write naming a KMS key its bucket is not bound to, recording the attempt in the audit log.
+fn kms_key_denied<T: Send + Sync>(helper: OperationHelper, denied: KmsKeyNotAllowed) -> S3Result<S3Response<T>> {
+    warn!(
+        bucket = %denied.bucket,
+        requested = %denied.requested,
+        bound = %denied.bound,
+        "write refused, KMS key not allowed in bucket"
+    );
+    let tags = HashMap::from([
+        ("kmsKeyDenied".to_string(), serde_json::Value::from(denied.requested.clone())),
+        ("kmsKeyBound".to_string(), serde_json::Value::from(denied.bound.clone())),
+    ]);
+    let result = Err(S3Error::with_message(S3ErrorCode::AccessDenied, denied.to_string()));
+    let _ = helper.audit_tags(tags).complete(&result);
+    result
+}
+
+/// Reject a write sending no digest of its payload to a bucket that requires one.
+async fn check_bucket_integrity(bucket: &str, headers: &HeaderMap, content_md5: Option<&str>) -> S3Result<()> {
+    if bucket_integrity(bucket).await.allows(headers, content_md5) {
+        return Ok(());
+    }
+    Err(s3_error!(
+        InvalidRequest,
+        "Bucket {bucket} requires a Content-MD5 or x-amz-checksum header on every write"
+    ))
+}
+
 impl FS {
     pub fn new() -> Self {
         // let store: ECStore = ECStore::new(address, endpoint_pools).await?;
@@ -500,14 +576,8 @@ impl FS {
 
         let ext = ext.to_owned();
 
-        let md5hex = if let Some(base64_md5) = content_md5 {
-            let md5 = base64_simd::STANDARD
-                .decode_to_vec(base64_md5.as_bytes())
-                .map_err(|e| ApiError::from(StorageError::other(format!("Invalid content MD5: {e}"))))?;
-            Some(hex_simd::encode_to_string(&md5, hex_simd::AsciiCase::Lower))
-        } else {
-            None
-        };
+        check_bucket_integrity(&bucket, &req.headers, content_md5.as_deref()).await?;
+        let md5hex = content_md5_hex(content_md5.as_deref())?;
 
         let sha256hex = get_content_sha256(&req.headers);
         let actual_size = size;
@@ -691,6 +761,89 @@ impl FS {
     }
 }
 
+/// Enabled trash configuration of `bucket`, if any.
+async fn bucket_trash(bucket: &str) -> Option<BucketTrash> {
+    metadata_sys::get_trash_config(bucket)
+        .await
+        .ok()
+        .map(|(cfg, _)| cfg)
+        .filter(|cfg| cfg.enabled)
+}
+
+/// Whether a delete with `opts` removes the current data for good instead of adding a delete marker.
+fn deletes_permanently(opts: &ObjectOptions) -> bool {
+    !opts.versioned && !opts.delete_prefix && opts.version_id.as_deref().is_none_or(|v| v == "null")
+}
+
+/// Resolve the version a GET or HEAD reads when it names a snapshot through `SNAPSHOT_HEADER`.
+///
+/// Returns `version_id` unchanged when no snapshot is requested. Objects absent from the
+/// snapshot are reported as missing.
+async fn snapshot_version_id(
+    bucket: &str,
+    key: &str,
+    version_id: Option<String>,
+    headers: &HeaderMap,
+) -> S3Result<Option<String>> {
+    let Some(name) = headers.get(SNAPSHOT_HEADER) else {
+        return Ok(version_id);
+    };
+    let name = name
+        .to_str()
+        .map_err(|_| s3_error!(InvalidArgument, "invalid snapshot name"))?;
+    if version_id.is_some() {
+        return Err(s3_error!(InvalidArgument, "versionId cannot be combined with a snapshot"));
+    }
+
+    let Some(store) = new_object_layer_fn() else {
+        return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+    };
+    let manifest = match snapshot::get_snapshot(store, bucket, name).await {
+        Ok(manifest) => manifest,
+        Err(StorageError::ConfigNotFound) => {
+            return Err(s3_error!(InvalidArgument, "snapshot {} does not exist on bucket {}", name, bucket));
+        }
+        Err(err) => return Err(ApiError::from(err).into()),
+    };
+
+    match manifest.entries.get(key) {
+        Some(entry) => Ok(Some(entry.version_id_or_null())),
+        None => Err(s3_error!(NoSuchKey, "object is not part of snapshot {}", name)),
+    }
+}
+
+/// Reject deleting a version that a snapshot of `bucket` still references.
+async fn check_snapshot_retention(
+    store: Arc<rustfs_ecstore::store::ECStore>,
+    bucket: &str,
+    key: &str,
+    version_id: Option<&str>,
+) -> S3Result<()> {
+    let Some(version_id) = version_id else {
+        return Ok(());
+    };
+    match snapshot::snapshot_referencing(store, bucket, key, version_id).await {
+        Ok(None) => Ok(()),
+        Ok(Some(name)) => Err(s3_error!(AccessDenied, "version is retained by snapshot {}", name)),
+        Err(err) => Err(ApiError::from(err).into()),
+    }
+}
+
+/// Whether single-part uploads report the MD5 of the uploaded data as ETag even when they are
+/// stored compressed or encrypted, as backup tools verifying ETags literally expect.
+fn full_object_etag_enabled() -> bool {
+    rustfs_utils::get_env_bool(rustfs_config::ENV_ETAG_FULL_OBJECT_MD5, rustfs_config::DEFAULT_ETAG_FULL_OBJECT_MD5)
+}
+
+/// Wrap a compressing or encrypting reader. With `full_object_etag` the ETag is resolved from the
+/// untransformed data instead of the bytes written to disk.
+fn transformed_reader(inner: Box<dyn Reader>, actual_size: i64, full_object_etag: bool) -> S3Result<HashReader> {
+    if full_object_etag {
+        return Ok(HashReader::new_with_inner_etag(inner, actual_size));
+    }
+    Ok(HashReader::new(inner, -1, actual_size, None, None, false).map_err(ApiError::from)?)
+}
+
 /// Helper function to get store and validate bucket exists
 async fn get_validated_store(bucket: &str) -> S3Result<Arc<rustfs_ecstore::store::ECStore>> {
     let Some(store) = new_object_layer_fn() else {
@@ -727,7 +880,10 @@ impl S3 for FS {
 
         counter!("rustfs_create_bucket_total").increment(1);
 
-        store
+        // Federated clusters share one namespace, the name is claimed before the bucket exists
+        let registered = GLOBAL_FEDERATION_SYS.register_bucket(&bucket).await.map_err(ApiError::from)?;
+
+        if let Err(err) = store
             .make_bucket(
                 &bucket,
                 &MakeBucketOptions {
@@ -737,7 +893,12 @@ impl S3 for FS {
                 },
             )
             .await
-            .map_err(ApiError::from)?;
+        {
+            if registered && let Err(err) = GLOBAL_FEDERATION_SYS.unregister_bucket(&bucket).await {
+                warn!("release bucket {} in the bucket directory failed: {:?}", bucket, err);
+            }
+            return Err(ApiError::from(err).into());
+        }
 
         let output = CreateBucketOutput::default();
 
@@ -756,6 +917,10 @@ impl S3 for FS {
             key,
             server_side_encryption: requested_sse,
             ssekms_key_id: requested_kms_key_id,
+            copy_source_if_match,
+            copy_source_if_none_match,
+            copy_source_if_modified_since,
+            copy_source_if_unmodified_since,
             ..
         } = req.input.clone();
         let (src_bucket, src_key, version_id) = match copy_source {
@@ -817,6 +982,10 @@ impl S3 for FS {
                 })
             })
         });
+        effective_kms_key_id = match scoped_kms_key(&bucket, effective_sse.as_ref(), effective_kms_key_id).await {
+            Ok(kms_key_id) => kms_key_id,
+            Err(denied) => return kms_key_denied(helper, denied),
+        };
 
         let h = HeaderMap::new();
 
@@ -827,6 +996,14 @@ impl S3 for FS {
 
         let mut src_info = gr.object_info.clone();
 
+        Preconditions {
+            if_match: copy_source_if_match.as_deref(),
+            if_none_match: copy_source_if_none_match.as_deref(),
+            if_modified_since: copy_source_if_modified_since.map(OffsetDateTime::from),
+            if_unmodified_since: copy_source_if_unmodified_since.map(OffsetDateTime::from),
+        }
+        .check_copy_source(src_info.etag.as_deref(), src_info.mod_time)?;
+
         if cp_src_dst_same {
             src_info.metadata_only = true;
         }
@@ -1002,7 +1179,7 @@ impl S3 for FS {
                     "post restore object failed",
                 ));
             }
-            if !obj_info.restore_ongoing && obj_info.restore_expires.unwrap().unix_timestamp() != 0 {
+            if !obj_info.restore_ongoing && obj_info.restore_expires.is_some_and(|expiry| expiry.unix_timestamp() != 0) {
                 _status_code = StatusCode::ACCEPTED;
                 already_restored = true;
             }
@@ -1194,6 +1371,10 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        if let Err(err) = GLOBAL_FEDERATION_SYS.unregister_bucket(&input.bucket).await {
+            warn!("release bucket {} in the bucket directory failed: {:?}", input.bucket, err);
+        }
+
         let result = Ok(S3Response::new(DeleteBucketOutput {}));
         let _ = helper.complete(&result);
         result
@@ -1247,6 +1428,17 @@ impl S3 for FS {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
         };
 
+        check_snapshot_retention(store.clone(), &bucket, &key, opts.version_id.as_deref()).await?;
+
+        if !replica
+            && deletes_permanently(&opts)
+            && let Some(trash) = bucket_trash(&bucket).await
+        {
+            move_to_trash(store.clone(), &bucket, &key, &trash)
+                .await
+                .map_err(ApiError::from)?;
+        }
+
         let obj_info = {
             match store.delete_object(&bucket, &key, opts).await {
                 Ok(obj) => obj,
@@ -1359,6 +1551,7 @@ impl S3 for FS {
 
         let mut object_to_delete = Vec::new();
         let mut object_to_delete_index = HashMap::new();
+        let trash = bucket_trash(&bucket).await;
 
         for (idx, object) in delete.objects.iter().enumerate() {
             // TODO: check auth
@@ -1429,6 +1622,31 @@ impl S3 for FS {
                 }
             }
 
+            if let Err(err) =
+                check_snapshot_retention(store.clone(), &bucket, &object.object_name, opts.version_id.as_deref()).await
+            {
+                delete_results[idx].error = Some(Error {
+                    code: Some("AccessDenied".to_string()),
+                    key: Some(object.object_name.clone()),
+                    message: err.message().map(str::to_string),
+                    version_id: opts.version_id.clone(),
+                });
+                continue;
+            }
+
+            if let Some(trash) = &trash
+                && deletes_permanently(&opts)
+                && let Err(err) = move_to_trash(store.clone(), &bucket, &object.object_name, trash).await
+            {
+                delete_results[idx].error = Some(Error {
+                    code: Some("InternalError".to_string()),
+                    key: Some(object.object_name.clone()),
+                    message: Some(format!("move to trash failed: {err}")),
+                    version_id: None,
+                });
+                continue;
+            }
+
             // TODO: Retention
             object_to_delete_index.insert(object.object_name.clone(), idx);
             object_to_delete.push(object);
@@ -1657,47 +1875,33 @@ impl S3 for FS {
             return Err(s3_error!(InvalidArgument, "range and part_number invalid"));
         }
 
+        let version_id = snapshot_version_id(&bucket, &key, version_id, &req.headers).await?;
         let opts: ObjectOptions = get_opts(&bucket, &key, version_id, part_number, &req.headers)
             .await
             .map_err(ApiError::from)?;
 
         let store = get_validated_store(&bucket).await?;
 
+        let transformer = bucket_transformer(&bucket, &key).await?;
+        if transformer.is_some() && (rs.is_some() || part_number.is_some()) {
+            return Err(s3_error!(NotImplemented, "Range and part GETs are not supported on transformed objects"));
+        }
+
         let reader = store
             .get_object_reader(bucket.as_str(), key.as_str(), rs.clone(), h, &opts)
             .await
             .map_err(ApiError::from)?;
 
         let info = reader.object_info;
+        let plain_body = reader.body;
 
-        if let Some(match_etag) = if_none_match {
-            if info.etag.as_ref().is_some_and(|etag| etag == match_etag.as_str()) {
-                return Err(S3Error::new(S3ErrorCode::NotModified));
-            }
-        }
-
-        if let Some(modified_since) = if_modified_since {
-            // obj_time < givenTime + 1s
-            if info.mod_time.is_some_and(|mod_time| {
-                let give_time: OffsetDateTime = modified_since.into();
-                mod_time < give_time.add(time::Duration::seconds(1))
-            }) {
-                return Err(S3Error::new(S3ErrorCode::NotModified));
-            }
-        }
-
-        if let Some(match_etag) = if_match {
-            if info.etag.as_ref().is_some_and(|etag| etag != match_etag.as_str()) {
-                return Err(S3Error::new(S3ErrorCode::PreconditionFailed));
-            }
-        } else if let Some(unmodified_since) = if_unmodified_since {
-            if info.mod_time.is_some_and(|mod_time| {
-                let give_time: OffsetDateTime = unmodified_since.into();
-                mod_time > give_time.add(time::Duration::seconds(1))
-            }) {
-                return Err(S3Error::new(S3ErrorCode::PreconditionFailed));
-            }
+        Preconditions {
+            if_match: if_match.as_deref(),
+            if_none_match: if_none_match.as_deref(),
+            if_modified_since: if_modified_since.map(OffsetDateTime::from),
+            if_unmodified_since: if_unmodified_since.map(OffsetDateTime::from),
         }
+        .check_read(info.etag.as_deref(), info.mod_time)?;
 
         debug!(object_size = info.size, part_count = info.parts.len(), "GET object metadata snapshot");
         for part in &info.parts {
@@ -1896,6 +2100,9 @@ impl S3 for FS {
         let body = if stored_sse_algorithm.is_some() || managed_encryption_applied {
             info!("Managed SSE: Using unlimited stream for decryption");
             Some(StreamingBlob::wrap(ReaderStream::with_capacity(final_stream, DEFAULT_READ_BUFFER_SIZE)))
+        } else if let Some(data) = plain_body.filter(|data| data.len() as i64 == response_content_length) {
+            // The whole object is already in memory, hand out slices of it instead of copying it
+            Some(StreamingBlob::wrap(bytes_chunks::<std::io::Error>(data, DEFAULT_READ_BUFFER_SIZE)))
         } else {
             Some(StreamingBlob::wrap(bytes_stream(
                 ReaderStream::with_capacity(final_stream, DEFAULT_READ_BUFFER_SIZE),
@@ -1954,7 +2161,7 @@ impl S3 for FS {
             }
         }
 
-        let output = GetObjectOutput {
+        let mut output = GetObjectOutput {
             body,
             content_length: Some(response_content_length),
             last_modified,
@@ -1976,6 +2183,31 @@ impl S3 for FS {
             ..Default::default()
         };
 
+        if let Some(transformer) = transformer
+            && let Some(body) = output.body.take()
+        {
+            let transform_req = TransformRequest {
+                bucket: bucket.clone(),
+                object: key.clone(),
+                version_id: req.input.version_id.clone(),
+                content_type: event_info.content_type.clone(),
+            };
+            let transformed = transformer.transform(&transform_req, body).await?;
+
+            // The payload changed, so size, etag and checksums of the stored object no longer apply
+            output = GetObjectOutput {
+                body: Some(transformed.body),
+                content_length: transformed.content_length,
+                content_type: transformed
+                    .content_type
+                    .and_then(|v| ContentType::from_str(&v).ok())
+                    .or(output.content_type),
+                last_modified: output.last_modified,
+                metadata: output.metadata,
+                ..Default::default()
+            };
+        }
+
         let version_id = req.input.version_id.clone().unwrap_or_default();
         helper = helper.object(event_info).version_id(version_id);
 
@@ -1998,7 +2230,25 @@ impl S3 for FS {
             .map_err(ApiError::from)?;
         // mc cp step 2 GetBucketInfo
 
-        Ok(S3Response::new(HeadBucketOutput::default()))
+        let region = rustfs_ecstore::global::get_global_region();
+        // SDKs sign with the region they guess first and retry with the one from x-amz-bucket-region of the 301,
+        // the header is added to every S3 response once a region is configured.
+        if let (Some(region), Some(signed_region)) = (region.as_deref(), req.region.as_deref())
+            && region != signed_region
+        {
+            return Err(s3_error!(
+                PermanentRedirect,
+                "the bucket is in region {}, the request was signed for {}",
+                region,
+                signed_region
+            ));
+        }
+
+        let mut resp = S3Response::new(HeadBucketOutput::default());
+        if let Ok(value) = http::HeaderValue::from_str(region.as_deref().unwrap_or(DEFAULT_BUCKET_REGION)) {
+            resp.headers.insert(rustfs_utils::http::headers::AMZ_BUCKET_REGION, value);
+        }
+        Ok(resp)
     }
 
     #[instrument(level = "debug", skip(self, req))]
@@ -2043,6 +2293,7 @@ impl S3 for FS {
             return Err(s3_error!(InvalidArgument, "range and part_number invalid"));
         }
 
+        let version_id = snapshot_version_id(&bucket, &key, version_id, &req.headers).await?;
         let opts: ObjectOptions = get_opts(&bucket, &key, version_id, part_number, &req.headers)
             .await
             .map_err(ApiError::from)?;
@@ -2051,36 +2302,27 @@ impl S3 for FS {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
         };
 
-        let info = store.get_object_info(&bucket, &key, &opts).await.map_err(ApiError::from)?;
-
-        if let Some(match_etag) = if_none_match {
-            if info.etag.as_ref().is_some_and(|etag| etag == match_etag.as_str()) {
-                return Err(S3Error::new(S3ErrorCode::NotModified));
-            }
-        }
-
-        if let Some(modified_since) = if_modified_since {
-            // obj_time < givenTime + 1s
-            if info.mod_time.is_some_and(|mod_time| {
-                let give_time: OffsetDateTime = modified_since.into();
-                mod_time < give_time.add(time::Duration::seconds(1))
-            }) {
-                return Err(S3Error::new(S3ErrorCode::NotModified));
+        let info = match store.get_object_info(&bucket, &key, &opts).await {
+            Ok(info) => info,
+            // Directories created by writing objects below them answer HEAD like an explicit marker
+            Err(err)
+                if is_err_object_not_found(&err) && key.ends_with('/') && opts.version_id.is_none() && part_number.is_none() =>
+            {
+                match store.clone().implicit_dir_info(&bucket, &key).await.map_err(ApiError::from)? {
+                    Some(info) => info,
+                    None => return Err(ApiError::from(err).into()),
+                }
             }
-        }
+            Err(err) => return Err(ApiError::from(err).into()),
+        };
 
-        if let Some(match_etag) = if_match {
-            if info.etag.as_ref().is_some_and(|etag| etag != match_etag.as_str()) {
-                return Err(S3Error::new(S3ErrorCode::PreconditionFailed));
-            }
-        } else if let Some(unmodified_since) = if_unmodified_since {
-            if info.mod_time.is_some_and(|mod_time| {
-                let give_time: OffsetDateTime = unmodified_since.into();
-                mod_time > give_time.add(time::Duration::seconds(1))
-            }) {
-                return Err(S3Error::new(S3ErrorCode::PreconditionFailed));
-            }
+        Preconditions {
+            if_match: if_match.as_deref(),
+            if_none_match: if_none_match.as_deref(),
+            if_modified_since: if_modified_since.map(OffsetDateTime::from),
+            if_unmodified_since: if_unmodified_since.map(OffsetDateTime::from),
         }
+        .check_read(info.etag.as_deref(), info.mod_time)?;
 
         let event_info = info.clone();
         let content_type = {
@@ -2106,6 +2348,14 @@ impl S3 for FS {
             ApiError::from(e)
         })?;
 
+        // S3 leaves the storage class out for STANDARD objects
+        let storage_class = info
+            .storage_class
+            .clone()
+            .filter(|sc| sc != storageclass::STANDARD)
+            .map(StorageClass::from);
+        let restore = object_restore_status(&info).map(|status| RestoreStatusOps::to_string(&status));
+
         let metadata_map = info.user_defined.clone();
         let server_side_encryption = metadata_map
             .get("x-amz-server-side-encryption")
@@ -2167,6 +2417,8 @@ impl S3 for FS {
             checksum_sha256,
             checksum_crc64nvme,
             checksum_type,
+            storage_class,
+            restore,
             // metadata: object_metadata,
             ..Default::default()
         };
@@ -2190,44 +2442,74 @@ impl S3 for FS {
 
         let mut req = req;
 
-        if req.credentials.as_ref().is_none_or(|cred| cred.access_key.is_empty()) {
+        if req.credentials.as_ref().is_none_or(|cred| cred.access_key.is_empty())
+            && req.extensions.get::<ChainIdentity>().is_none()
+        {
             return Err(S3Error::with_message(S3ErrorCode::AccessDenied, "Access Denied"));
         }
 
-        let bucket_infos = if let Err(e) = authorize_request(&mut req, Action::S3Action(S3Action::ListAllMyBucketsAction)).await {
-            if e.code() != &S3ErrorCode::AccessDenied {
-                return Err(e);
+        let max_buckets = match req.input.max_buckets {
+            Some(n) if !(1..=MAX_LIST_BUCKETS).contains(&n) => {
+                return Err(S3Error::with_message(S3ErrorCode::InvalidArgument, "Invalid max buckets".to_string()));
             }
+            Some(n) => Some(n as usize),
+            None => None,
+        };
+        let prefix = req.input.prefix.clone().filter(|v| !v.is_empty());
+        let start_after = req
+            .input
+            .continuation_token
+            .as_deref()
+            .filter(|v| !v.is_empty())
+            .map(decode_bucket_continuation_token)
+            .transpose()?;
 
-            let mut list_bucket_infos = store.list_bucket(&BucketOptions::default()).await.map_err(ApiError::from)?;
+        let list_all = match authorize_request(&mut req, Action::S3Action(S3Action::ListAllMyBucketsAction)).await {
+            Ok(()) => true,
+            Err(e) if e.code() == &S3ErrorCode::AccessDenied => false,
+            Err(e) => return Err(e),
+        };
 
-            list_bucket_infos = futures::stream::iter(list_bucket_infos)
-                .filter_map(|info| async {
-                    let mut req_clone = req.clone();
-                    let req_info = req_clone.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
-                    req_info.bucket = Some(info.name.clone());
+        let mut buckets = store.list_bucket(&BucketOptions::default()).await.map_err(ApiError::from)?;
+        // The buckets of the other federated clusters are part of the namespace, requests for them are redirected
+        for (name, created) in GLOBAL_FEDERATION_SYS.remote_buckets() {
+            if !buckets.iter().any(|info| info.name == name) {
+                buckets.push(rustfs_ecstore::store_api::BucketInfo {
+                    name,
+                    created,
+                    ..Default::default()
+                });
+            }
+        }
+        let candidates = bucket_list_window(buckets, prefix.as_deref().unwrap_or_default(), start_after.as_deref());
+
+        // Without ListAllMyBuckets only the buckets the caller can access are shown. Access is
+        // checked lazily so a page never costs more than `max_buckets + 1` policy evaluations.
+        let limit = max_buckets.unwrap_or(usize::MAX);
+        let mut bucket_infos = Vec::new();
+        let mut truncated = false;
+        for info in candidates {
+            if GLOBAL_BUCKET_PURGE_SYS.is_purging(&info.name) {
+                continue;
+            }
+            if !list_all && !is_bucket_accessible(&req, &info.name).await {
+                continue;
+            }
+            if bucket_infos.len() == limit {
+                truncated = true;
+                break;
+            }
+            bucket_infos.push(info);
+        }
 
-                    if authorize_request(&mut req_clone, Action::S3Action(S3Action::ListBucketAction))
-                        .await
-                        .is_ok()
-                        || authorize_request(&mut req_clone, Action::S3Action(S3Action::GetBucketLocationAction))
-                            .await
-                            .is_ok()
-                    {
-                        Some(info)
-                    } else {
-                        None
-                    }
-                })
-                .collect()
-                .await;
+        if !list_all && bucket_infos.is_empty() && start_after.is_none() && prefix.is_none() {
+            return Err(S3Error::with_message(S3ErrorCode::AccessDenied, "Access Denied"));
+        }
 
-            if list_bucket_infos.is_empty() {
-                return Err(S3Error::with_message(S3ErrorCode::AccessDenied, "Access Denied"));
-            }
-            list_bucket_infos
+        let continuation_token = if truncated {
+            bucket_infos.last().map(|v| encode_bucket_continuation_token(&v.name))
         } else {
-            store.list_bucket(&BucketOptions::default()).await.map_err(ApiError::from)?
+            None
         };
 
         let buckets: Vec<Bucket> = bucket_infos
@@ -2242,6 +2524,8 @@ impl S3 for FS {
         let output = ListBucketsOutput {
             buckets: Some(buckets),
             owner: Some(RUSTFS_OWNER.to_owned()),
+            continuation_token,
+            prefix,
             ..Default::default()
         };
         Ok(S3Response::new(output))
@@ -2249,8 +2533,29 @@ impl S3 for FS {
 
     #[instrument(level = "debug", skip(self, req))]
     async fn list_objects(&self, req: S3Request<ListObjectsInput>) -> S3Result<S3Response<ListObjectsOutput>> {
+        // The marker of a v1 listing is the start-after key of a v2 listing, and the next marker
+        // is the key the v2 continuation token resumes after
+        let bucket = req.input.bucket.clone();
+        let prefix = req.input.prefix.clone().unwrap_or_default();
+        let marker = req.input.marker.clone().filter(|v| !v.is_empty());
+        let url_encode = req
+            .input
+            .encoding_type
+            .as_ref()
+            .is_some_and(|v| v.as_str().eq_ignore_ascii_case(EncodingType::URL));
+
         let v2_resp = self.list_objects_v2(req.map_input(Into::into)).await?;
 
+        // Only listings with a delimiter report the next marker, others continue after their last key
+        let next_marker = match (&v2_resp.output.delimiter, &v2_resp.output.next_continuation_token) {
+            (Some(_), Some(token)) => Some(
+                decode_continuation_token(&bucket, &prefix, token)
+                    .map(|cont| list_encode(&cont.marker, url_encode))
+                    .map_err(ApiError::from)?,
+            ),
+            _ => None,
+        };
+
         Ok(v2_resp.map_output(|v2| ListObjectsOutput {
             contents: v2.contents,
             delimiter: v2.delimiter,
@@ -2259,6 +2564,9 @@ impl S3 for FS {
             prefix: v2.prefix,
             max_keys: v2.max_keys,
             common_prefixes: v2.common_prefixes,
+            is_truncated: v2.is_truncated,
+            marker: Some(marker.map(|v| list_encode(&v, url_encode)).unwrap_or_default()),
+            next_marker,
             ..Default::default()
         }))
     }
@@ -2270,6 +2578,7 @@ impl S3 for FS {
             bucket,
             continuation_token,
             delimiter,
+            encoding_type,
             fetch_owner,
             max_keys,
             prefix,
@@ -2283,6 +2592,18 @@ impl S3 for FS {
             return Err(S3Error::with_message(S3ErrorCode::InvalidArgument, "Invalid max keys".to_string()));
         }
 
+        let url_encode = match encoding_type.as_ref().map(|v| v.as_str()) {
+            None => false,
+            Some(v) if v.eq_ignore_ascii_case(EncodingType::URL) => true,
+            Some(_) => {
+                return Err(S3Error::with_message(
+                    S3ErrorCode::InvalidArgument,
+                    "Invalid Encoding Method specified in Request".to_string(),
+                ));
+            }
+        };
+        let fetch_owner = fetch_owner.unwrap_or_default();
+
         let delimiter = delimiter.filter(|v| !v.is_empty());
         let start_after = start_after.filter(|v| !v.is_empty());
 
@@ -2291,15 +2612,12 @@ impl S3 for FS {
         // Save the original encoded continuation_token for response
         let encoded_continuation_token = continuation_token.clone();
 
-        // Decode continuation_token from base64 for internal use
+        // Verify the opaque token and turn it back into the internal listing marker
         let continuation_token = continuation_token
             .map(|token| {
-                base64_simd::STANDARD
-                    .decode_to_vec(token.as_bytes())
+                decode_continuation_token(&bucket, &prefix, &token)
+                    .map(|cont| cont.marker)
                     .map_err(|_| s3_error!(InvalidArgument, "Invalid continuation token"))
-                    .and_then(|bytes| {
-                        String::from_utf8(bytes).map_err(|_| s3_error!(InvalidArgument, "Invalid continuation token"))
-                    })
             })
             .transpose()?;
 
@@ -2310,6 +2628,17 @@ impl S3 for FS {
             .get(rustfs_utils::http::headers::RUSTFS_INCLUDE_DELETED)
             .is_some_and(|v| v.to_str().unwrap_or_default() == "true");
 
+        let consistency = req
+            .headers
+            .get(rustfs_utils::http::headers::RUSTFS_LIST_CONSISTENCY)
+            .map(|v| {
+                v.to_str()
+                    .ok()
+                    .and_then(|v| v.parse::<ListingConsistency>().ok())
+                    .ok_or_else(|| s3_error!(InvalidArgument, "invalid listing consistency, expected 'strict' or 'eventual'"))
+            })
+            .transpose()?;
+
         let object_infos = store
             .list_objects_v2(
                 &bucket,
@@ -2317,9 +2646,10 @@ impl S3 for FS {
                 continuation_token,
                 delimiter.clone(),
                 max_keys,
-                fetch_owner.unwrap_or_default(),
-                start_after,
+                fetch_owner,
+                start_after.clone(),
                 incl_deleted,
+                consistency,
             )
             .await
             .map_err(ApiError::from)?;
@@ -2332,36 +2662,38 @@ impl S3 for FS {
             .filter(|v| !v.name.is_empty())
             .map(|v| {
                 let mut obj = Object {
-                    key: Some(v.name.to_owned()),
+                    key: Some(list_encode(&v.name, url_encode)),
                     last_modified: v.mod_time.map(Timestamp::from),
                     size: Some(v.get_actual_size().unwrap_or_default()),
                     e_tag: v.etag.clone().map(|etag| to_s3s_etag(&etag)),
                     storage_class: v.storage_class.clone().map(ObjectStorageClass::from),
+                    restore_status: object_restore_status(v),
                     ..Default::default()
                 };
 
-                if fetch_owner.is_some_and(|v| v) {
-                    obj.owner = Some(Owner {
-                        display_name: Some("rustfs".to_owned()),
-                        id: Some("v0.1".to_owned()),
-                    });
+                // Objects belong to the account owning the deployment, as reported for buckets
+                if fetch_owner {
+                    obj.owner = Some(RUSTFS_OWNER.to_owned());
                 }
                 obj
             })
             .collect();
 
-        let key_count = objects.len() as i32;
+        let key_count = (objects.len() + object_infos.prefixes.len()) as i32;
 
         let common_prefixes = object_infos
             .prefixes
-            .into_iter()
-            .map(|v| CommonPrefix { prefix: Some(v) })
+            .iter()
+            .map(|v| CommonPrefix {
+                prefix: Some(list_encode(v, url_encode)),
+            })
             .collect();
 
-        // Encode next_continuation_token to base64
         let next_continuation_token = object_infos
             .next_continuation_token
-            .map(|token| base64_simd::STANDARD.encode_to_string(token.as_bytes()));
+            .map(|marker| encode_continuation_token(&bucket, &prefix, &marker, None))
+            .transpose()
+            .map_err(ApiError::from)?;
 
         let output = ListObjectsV2Output {
             is_truncated: Some(object_infos.is_truncated),
@@ -2370,9 +2702,11 @@ impl S3 for FS {
             key_count: Some(key_count),
             max_keys: Some(max_keys),
             contents: Some(objects),
-            delimiter,
+            delimiter: delimiter.map(|v| list_encode(&v, url_encode)),
+            encoding_type,
             name: Some(bucket),
-            prefix: Some(prefix),
+            prefix: Some(list_encode(&prefix, url_encode)),
+            start_after: start_after.map(|v| list_encode(&v, url_encode)),
             common_prefixes: Some(common_prefixes),
             ..Default::default()
         };
@@ -2421,6 +2755,8 @@ impl S3 for FS {
                     version_id: v.version_id.map(|v| v.to_string()),
                     is_latest: Some(v.is_latest),
                     e_tag: v.etag.clone().map(|etag| to_s3s_etag(&etag)),
+                    storage_class: v.storage_class.clone().map(ObjectVersionStorageClass::from),
+                    restore_status: object_restore_status(v),
                     ..Default::default() // TODO: another fields
                 }
             })
@@ -2535,23 +2871,9 @@ impl S3 for FS {
 
         let Some(body) = body else { return Err(s3_error!(IncompleteBody)) };
 
-        let mut size = match content_length {
-            Some(c) => c,
-            None => {
-                if let Some(val) = req.headers.get(AMZ_DECODED_CONTENT_LENGTH) {
-                    match atoi::atoi::<i64>(val.as_bytes()) {
-                        Some(x) => x,
-                        None => return Err(s3_error!(UnexpectedContent)),
-                    }
-                } else {
-                    return Err(s3_error!(UnexpectedContent));
-                }
-            }
-        };
-
-        if size == -1 {
+        let Some(mut size) = resolve_payload_size(&req.headers, content_length)? else {
             return Err(s3_error!(UnexpectedContent));
-        }
+        };
 
         // Apply adaptive buffer sizing based on file size for optimal streaming performance.
         // Uses workload profile configuration (enabled by default) to select appropriate buffer size.
@@ -2559,7 +2881,11 @@ impl S3 for FS {
         let buffer_size = get_buffer_size_opt_in(size);
         let body = tokio::io::BufReader::with_capacity(
             buffer_size,
-            StreamReader::new(body.map(|f| f.map_err(|e| std::io::Error::other(e.to_string())))),
+            StreamReader::new(LengthEnforcedStream::new(
+                body.map(|f| f.map_err(|e| std::io::Error::other(e.to_string()))),
+                size,
+                req.extensions.get::<Arc<ConnectionStats>>().cloned(),
+            )),
         );
 
         // let body = Box::new(StreamReader::new(body.map(|f| f.map_err(|e| std::io::Error::other(e.to_string())))));
@@ -2601,6 +2927,10 @@ impl S3 for FS {
                 })
             })
         });
+        effective_kms_key_id = match scoped_kms_key(&bucket, effective_sse.as_ref(), effective_kms_key_id).await {
+            Ok(kms_key_id) => kms_key_id,
+            Err(denied) => return kms_key_denied(helper, denied),
+        };
 
         let mut metadata = metadata.unwrap_or_default();
 
@@ -2640,14 +2970,8 @@ impl S3 for FS {
 
         let actual_size = size;
 
-        let mut md5hex = if let Some(base64_md5) = content_md5 {
-            let md5 = base64_simd::STANDARD
-                .decode_to_vec(base64_md5.as_bytes())
-                .map_err(|e| ApiError::from(StorageError::other(format!("Invalid content MD5: {e}"))))?;
-            Some(hex_simd::encode_to_string(&md5, hex_simd::AsciiCase::Lower))
-        } else {
-            None
-        };
+        check_bucket_integrity(&bucket, &req.headers, content_md5.as_deref()).await?;
+        let mut md5hex = content_md5_hex(content_md5.as_deref())?;
 
         let mut sha256hex = get_content_sha256(&req.headers);
 
@@ -2672,7 +2996,12 @@ impl S3 for FS {
             sha256hex = None;
         }
 
-        let mut reader = HashReader::new(reader, size, actual_size, md5hex, sha256hex, false).map_err(ApiError::from)?;
+        let full_object_etag = full_object_etag_enabled();
+        let mut reader = if size < 0 {
+            transformed_reader(reader, actual_size, full_object_etag)?
+        } else {
+            HashReader::new(reader, size, actual_size, md5hex, sha256hex, false).map_err(ApiError::from)?
+        };
 
         if size >= 0 {
             if let Err(err) = reader.add_checksum_from_s3s(&req.headers, req.trailing_headers.clone(), false) {
@@ -2721,7 +3050,7 @@ impl S3 for FS {
 
             // Apply encryption
             let encrypt_reader = EncryptReader::new(reader, key_array, nonce);
-            reader = HashReader::new(Box::new(encrypt_reader), -1, actual_size, None, None, false).map_err(ApiError::from)?;
+            reader = transformed_reader(Box::new(encrypt_reader), actual_size, full_object_etag)?;
         }
 
         // Apply managed SSE (SSE-S3 or SSE-KMS) when requested
@@ -2745,8 +3074,7 @@ impl S3 for FS {
                     effective_kms_key_id = Some(kms_key_used.clone());
 
                     let encrypt_reader = EncryptReader::new(reader, key_bytes, nonce);
-                    reader =
-                        HashReader::new(Box::new(encrypt_reader), -1, actual_size, None, None, false).map_err(ApiError::from)?;
+                    reader = transformed_reader(Box::new(encrypt_reader), actual_size, full_object_etag)?;
                 }
             }
         }
@@ -2910,6 +3238,10 @@ impl S3 for FS {
                 })
             })
         });
+        effective_kms_key_id = match scoped_kms_key(&bucket, effective_sse.as_ref(), effective_kms_key_id).await {
+            Ok(kms_key_id) => kms_key_id,
+            Err(denied) => return kms_key_denied(helper, denied),
+        };
 
         // Store effective SSE information in metadata for multipart upload
         if let Some(sse_alg) = &sse_customer_algorithm {
@@ -2950,6 +3282,16 @@ impl S3 for FS {
             );
         }
 
+        if let Some(visibility) = req.headers.get(UPLOAD_VISIBILITY_HEADER) {
+            match visibility.to_str() {
+                Ok("hidden") => {
+                    metadata.insert(MULTIPART_HIDDEN_KEY.to_owned(), "true".to_owned());
+                }
+                Ok("visible") => {}
+                _ => return Err(s3_error!(InvalidArgument, "{UPLOAD_VISIBILITY_HEADER} must be hidden or visible")),
+            }
+        }
+
         let mut opts: ObjectOptions = put_opts(&bucket, &key, version_id, &req.headers, metadata)
             .await
             .map_err(ApiError::from)?;
@@ -3007,41 +3349,21 @@ impl S3 for FS {
             ..
         } = input;
 
+        if part_number < 1 || part_number as usize > MAX_PARTS_COUNT {
+            return Err(s3_error!(
+                InvalidArgument,
+                "Part number must be an integer between 1 and {}",
+                MAX_PARTS_COUNT
+            ));
+        }
         let part_id = part_number as usize;
 
         // let upload_id =
 
-        let mut size = content_length;
+        let mut size = resolve_payload_size(&req.headers, content_length)?;
         let mut body_stream = body.ok_or_else(|| s3_error!(IncompleteBody))?;
 
-        if size.is_none() {
-            if let Some(val) = req.headers.get(AMZ_DECODED_CONTENT_LENGTH) {
-                if let Some(x) = atoi::atoi::<i64>(val.as_bytes()) {
-                    size = Some(x);
-                }
-            }
-
-            if size.is_none() {
-                let mut total = 0i64;
-                let mut buffer = bytes::BytesMut::new();
-                while let Some(chunk) = body_stream.next().await {
-                    let chunk = chunk.map_err(|e| ApiError::from(StorageError::other(e.to_string())))?;
-                    total += chunk.len() as i64;
-                    buffer.extend_from_slice(&chunk);
-                }
-
-                if total <= 0 {
-                    return Err(s3_error!(UnexpectedContent));
-                }
-
-                size = Some(total);
-                let combined = buffer.freeze();
-                let stream = futures::stream::once(async move { Ok::<Bytes, std::io::Error>(combined) });
-                body_stream = StreamingBlob::wrap(stream);
-            }
-        }
-
-        // Get multipart info early to check if managed encryption will be applied
+        // Get multipart info before reading the body, parts of unknown uploads are refused without it
         let Some(store) = new_object_layer_fn() else {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
         };
@@ -3052,6 +3374,25 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        if size.is_none() {
+            let mut total = 0i64;
+            let mut buffer = bytes::BytesMut::new();
+            while let Some(chunk) = body_stream.next().await {
+                let chunk = chunk.map_err(|e| ApiError::from(StorageError::other(e.to_string())))?;
+                total += chunk.len() as i64;
+                buffer.extend_from_slice(&chunk);
+            }
+
+            if total <= 0 {
+                return Err(s3_error!(UnexpectedContent));
+            }
+
+            size = Some(total);
+            let combined = buffer.freeze();
+            let stream = futures::stream::once(async move { Ok::<Bytes, std::io::Error>(combined) });
+            body_stream = StreamingBlob::wrap(stream);
+        }
+
         // Check if managed encryption will be applied
         let will_apply_managed_encryption = decrypt_managed_encryption_key(&bucket, &key, &fi.user_defined)
             .await?
@@ -3071,6 +3412,9 @@ impl S3 for FS {
             if total <= 0 {
                 return Err(s3_error!(UnexpectedContent));
             }
+            if size.is_some_and(|declared| declared != total) {
+                return Err(s3_error!(IncompleteBody, "request body does not match the declared length"));
+            }
 
             size = Some(total);
             let combined = buffer.freeze();
@@ -3086,7 +3430,11 @@ impl S3 for FS {
         let buffer_size = get_buffer_size_opt_in(size);
         let body = tokio::io::BufReader::with_capacity(
             buffer_size,
-            StreamReader::new(body_stream.map(|f| f.map_err(|e| std::io::Error::other(e.to_string())))),
+            StreamReader::new(LengthEnforcedStream::new(
+                body_stream.map(|f| f.map_err(|e| std::io::Error::other(e.to_string()))),
+                size,
+                req.extensions.get::<Arc<ConnectionStats>>().cloned(),
+            )),
         );
 
         // mc cp step 4
@@ -3139,14 +3487,8 @@ impl S3 for FS {
         }
         */
 
-        let mut md5hex = if let Some(base64_md5) = input.content_md5 {
-            let md5 = base64_simd::STANDARD
-                .decode_to_vec(base64_md5.as_bytes())
-                .map_err(|e| ApiError::from(StorageError::other(format!("Invalid content MD5: {e}"))))?;
-            Some(hex_simd::encode_to_string(&md5, hex_simd::AsciiCase::Lower))
-        } else {
-            None
-        };
+        check_bucket_integrity(&bucket, &req.headers, input.content_md5.as_deref()).await?;
+        let mut md5hex = content_md5_hex(input.content_md5.as_deref())?;
 
         let mut sha256hex = get_content_sha256(&req.headers);
 
@@ -3240,6 +3582,8 @@ impl S3 for FS {
             upload_id,
             copy_source_if_match,
             copy_source_if_none_match,
+            copy_source_if_modified_since,
+            copy_source_if_unmodified_since,
             ..
         } = req.input;
 
@@ -3294,27 +3638,13 @@ impl S3 for FS {
 
         let mut src_info = src_reader.object_info;
 
-        // Validate copy conditions (simplified for now)
-        if let Some(if_match) = copy_source_if_match {
-            if let Some(ref etag) = src_info.etag {
-                if etag != &if_match {
-                    return Err(s3_error!(PreconditionFailed));
-                }
-            } else {
-                return Err(s3_error!(PreconditionFailed));
-            }
+        Preconditions {
+            if_match: copy_source_if_match.as_deref(),
+            if_none_match: copy_source_if_none_match.as_deref(),
+            if_modified_since: copy_source_if_modified_since.map(OffsetDateTime::from),
+            if_unmodified_since: copy_source_if_unmodified_since.map(OffsetDateTime::from),
         }
-
-        if let Some(if_none_match) = copy_source_if_none_match {
-            if let Some(ref etag) = src_info.etag {
-                if etag == &if_none_match {
-                    return Err(s3_error!(PreconditionFailed));
-                }
-            }
-        }
-
-        // TODO: Implement proper time comparison for if_modified_since and if_unmodified_since
-        // For now, we'll skip these conditions
+        .check_copy_source(src_info.etag.as_deref(), src_info.mod_time)?;
 
         // Calculate actual range and length
         // Note: These values are used implicitly through the range specification (rs)
@@ -3517,7 +3847,7 @@ impl S3 for FS {
         }
 
         let result = store
-            .list_multipart_uploads(&bucket, &prefix, delimiter, key_marker, upload_id_marker, max_uploads)
+            .list_multipart_uploads(&bucket, &prefix, key_marker, upload_id_marker, delimiter, max_uploads)
             .await
             .map_err(ApiError::from)?;
 
@@ -3527,6 +3857,8 @@ impl S3 for FS {
             delimiter: result.delimiter,
             key_marker: result.key_marker,
             upload_id_marker: result.upload_id_marker,
+            next_key_marker: result.next_key_marker,
+            next_upload_id_marker: result.next_upload_id_marker,
             max_uploads: Some(result.max_uploads as i32),
             is_truncated: Some(result.is_truncated),
             uploads: Some(
@@ -3791,9 +4123,11 @@ impl S3 for FS {
         let Tagging { tag_set } = match metadata_sys::get_tagging_config(&bucket).await {
             Ok((tags, _)) => tags,
             Err(err) => {
+                if err == StorageError::ConfigNotFound {
+                    return Err(S3Error::with_message(S3ErrorCode::NoSuchTagSet, "The TagSet does not exist".to_string()));
+                }
                 warn!("get_tagging_config err {:?}", &err);
-                // TODO: check not found
-                Tagging::default()
+                return Err(ApiError::from(err).into());
             }
         };
 
@@ -3802,6 +4136,7 @@ impl S3 for FS {
 
     #[instrument(level = "debug", skip(self))]
     async fn put_bucket_tagging(&self, req: S3Request<PutBucketTaggingInput>) -> S3Result<S3Response<PutBucketTaggingOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let PutBucketTaggingInput { bucket, tagging, .. } = req.input;
 
         let Some(store) = new_object_layer_fn() else {
@@ -3813,13 +4148,18 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        if tagging.tag_set.len() > MAX_BUCKET_TAGS {
+            return Err(s3_error!(InvalidTag, "Bucket tag count cannot be greater than 50"));
+        }
+        validate_tag_set(&tagging.tag_set)?;
+
         let data = try_!(serialize(&tagging));
 
-        metadata_sys::update(&bucket, BUCKET_TAGGING_CONFIG, data)
+        let res = metadata_sys::update_if_match(&bucket, BUCKET_TAGGING_CONFIG, data, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
 
-        Ok(S3Response::new(Default::default()))
+        Ok(with_metadata_etag(S3Response::new(Default::default()), &res))
     }
 
     #[instrument(level = "debug", skip(self))]
@@ -3827,13 +4167,14 @@ impl S3 for FS {
         &self,
         req: S3Request<DeleteBucketTaggingInput>,
     ) -> S3Result<S3Response<DeleteBucketTaggingOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let DeleteBucketTaggingInput { bucket, .. } = req.input;
 
-        metadata_sys::delete(&bucket, BUCKET_TAGGING_CONFIG)
+        let res = metadata_sys::delete_if_match(&bucket, BUCKET_TAGGING_CONFIG, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
 
-        Ok(S3Response::new(DeleteBucketTaggingOutput {}))
+        Ok(with_metadata_etag(S3Response::new(DeleteBucketTaggingOutput {}), &res))
     }
 
     #[instrument(level = "debug", skip(self, req))]
@@ -3850,35 +4191,7 @@ impl S3 for FS {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
         };
 
-        let mut tag_keys = std::collections::HashSet::with_capacity(tagging.tag_set.len());
-        for tag in &tagging.tag_set {
-            let key = tag
-                .key
-                .as_ref()
-                .filter(|k| !k.is_empty())
-                .ok_or_else(|| s3_error!(InvalidTag, "Tag key cannot be empty"))?;
-
-            if key.len() > 128 {
-                return Err(s3_error!(InvalidTag, "Tag key is too long, maximum allowed length is 128 characters"));
-            }
-
-            let value = tag
-                .value
-                .as_ref()
-                .ok_or_else(|| s3_error!(InvalidTag, "Tag value cannot be null"))?;
-
-            if value.is_empty() {
-                return Err(s3_error!(InvalidTag, "Tag value cannot be empty"));
-            }
-
-            if value.len() > 256 {
-                return Err(s3_error!(InvalidTag, "Tag value is too long, maximum allowed length is 256 characters"));
-            }
-
-            if !tag_keys.insert(key) {
-                return Err(s3_error!(InvalidTag, "Cannot provide multiple Tags with the same key"));
-            }
-        }
+        validate_tag_set(&tagging.tag_set)?;
 
         let tags = encode_tags(tagging.tag_set);
 
@@ -3975,6 +4288,7 @@ impl S3 for FS {
         &self,
         req: S3Request<PutBucketVersioningInput>,
     ) -> S3Result<S3Response<PutBucketVersioningOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let PutBucketVersioningInput {
             bucket,
             versioning_configuration,
@@ -3988,13 +4302,13 @@ impl S3 for FS {
 
         let data = try_!(serialize(&versioning_configuration));
 
-        metadata_sys::update(&bucket, BUCKET_VERSIONING_CONFIG, data)
+        let res = metadata_sys::update_if_match(&bucket, BUCKET_VERSIONING_CONFIG, data, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
 
         // TODO: globalSiteReplicationSys.BucketMetaHook
 
-        Ok(S3Response::new(PutBucketVersioningOutput {}))
+        Ok(with_metadata_etag(S3Response::new(PutBucketVersioningOutput {}), &res))
     }
 
     async fn get_bucket_policy_status(
@@ -4075,6 +4389,7 @@ impl S3 for FS {
     }
 
     async fn put_bucket_policy(&self, req: S3Request<PutBucketPolicyInput>) -> S3Result<S3Response<PutBucketPolicyOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let PutBucketPolicyInput { bucket, policy, .. } = req.input;
 
         let Some(store) = new_object_layer_fn() else {
@@ -4098,17 +4413,18 @@ impl S3 for FS {
 
         let data = serde_json::to_vec(&cfg).map_err(|e| s3_error!(InternalError, "parse policy failed {:?}", e))?;
 
-        metadata_sys::update(&bucket, BUCKET_POLICY_CONFIG, data)
+        let res = metadata_sys::update_if_match(&bucket, BUCKET_POLICY_CONFIG, data, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
 
-        Ok(S3Response::new(PutBucketPolicyOutput {}))
+        Ok(with_metadata_etag(S3Response::new(PutBucketPolicyOutput {}), &res))
     }
 
     async fn delete_bucket_policy(
         &self,
         req: S3Request<DeleteBucketPolicyInput>,
     ) -> S3Result<S3Response<DeleteBucketPolicyOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let DeleteBucketPolicyInput { bucket, .. } = req.input;
 
         let Some(store) = new_object_layer_fn() else {
@@ -4120,11 +4436,11 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
-        metadata_sys::delete(&bucket, BUCKET_POLICY_CONFIG)
+        let res = metadata_sys::delete_if_match(&bucket, BUCKET_POLICY_CONFIG, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
 
-        Ok(S3Response::new(DeleteBucketPolicyOutput {}))
+        Ok(with_metadata_etag(S3Response::new(DeleteBucketPolicyOutput {}), &res))
     }
 
     #[instrument(level = "debug", skip(self))]
@@ -4165,6 +4481,7 @@ impl S3 for FS {
         &self,
         req: S3Request<PutBucketLifecycleConfigurationInput>,
     ) -> S3Result<S3Response<PutBucketLifecycleConfigurationOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let PutBucketLifecycleConfigurationInput {
             bucket,
             lifecycle_configuration,
@@ -4187,11 +4504,14 @@ impl S3 for FS {
         }
 
         let data = try_!(serialize(&input_cfg));
-        metadata_sys::update(&bucket, BUCKET_LIFECYCLE_CONFIG, data)
+        let res = metadata_sys::update_if_match(&bucket, BUCKET_LIFECYCLE_CONFIG, data, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
 
-        Ok(S3Response::new(PutBucketLifecycleConfigurationOutput::default()))
+        Ok(with_metadata_etag(
+            S3Response::new(PutBucketLifecycleConfigurationOutput::default()),
+            &res,
+        ))
     }
 
     #[instrument(level = "debug", skip(self))]
@@ -4199,6 +4519,7 @@ impl S3 for FS {
         &self,
         req: S3Request<DeleteBucketLifecycleInput>,
     ) -> S3Result<S3Response<DeleteBucketLifecycleOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let DeleteBucketLifecycleInput { bucket, .. } = req.input;
 
         let Some(store) = new_object_layer_fn() else {
@@ -4210,11 +4531,11 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
-        metadata_sys::delete(&bucket, BUCKET_LIFECYCLE_CONFIG)
+        let res = metadata_sys::delete_if_match(&bucket, BUCKET_LIFECYCLE_CONFIG, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
 
-        Ok(S3Response::new(DeleteBucketLifecycleOutput::default()))
+        Ok(with_metadata_etag(S3Response::new(DeleteBucketLifecycleOutput::default()), &res))
     }
 
     async fn get_bucket_encryption(
@@ -4252,6 +4573,7 @@ impl S3 for FS {
         &self,
         req: S3Request<PutBucketEncryptionInput>,
     ) -> S3Result<S3Response<PutBucketEncryptionOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let PutBucketEncryptionInput {
             bucket,
             server_side_encryption_configuration,
@@ -4272,16 +4594,17 @@ impl S3 for FS {
         // TODO: check kms
 
         let data = try_!(serialize(&server_side_encryption_configuration));
-        metadata_sys::update(&bucket, BUCKET_SSECONFIG, data)
+        let res = metadata_sys::update_if_match(&bucket, BUCKET_SSECONFIG, data, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
-        Ok(S3Response::new(PutBucketEncryptionOutput::default()))
+        Ok(with_metadata_etag(S3Response::new(PutBucketEncryptionOutput::default()), &res))
     }
 
     async fn delete_bucket_encryption(
         &self,
         req: S3Request<DeleteBucketEncryptionInput>,
     ) -> S3Result<S3Response<DeleteBucketEncryptionOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let DeleteBucketEncryptionInput { bucket, .. } = req.input;
 
         let Some(store) = new_object_layer_fn() else {
@@ -4292,11 +4615,11 @@ impl S3 for FS {
             .get_bucket_info(&bucket, &BucketOptions::default())
             .await
             .map_err(ApiError::from)?;
-        metadata_sys::delete(&bucket, BUCKET_SSECONFIG)
+        let res = metadata_sys::delete_if_match(&bucket, BUCKET_SSECONFIG, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
 
-        Ok(S3Response::new(DeleteBucketEncryptionOutput::default()))
+        Ok(with_metadata_etag(S3Response::new(DeleteBucketEncryptionOutput::default()), &res))
     }
 
     #[instrument(level = "debug", skip(self))]
@@ -4333,6 +4656,7 @@ impl S3 for FS {
         &self,
         req: S3Request<PutObjectLockConfigurationInput>,
     ) -> S3Result<S3Response<PutObjectLockConfigurationOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let PutObjectLockConfigurationInput {
             bucket,
             object_lock_configuration,
@@ -4352,11 +4676,11 @@ impl S3 for FS {
 
         let data = try_!(serialize(&input_cfg));
 
-        metadata_sys::update(&bucket, OBJECT_LOCK_CONFIG, data)
+        let res = metadata_sys::update_if_match(&bucket, OBJECT_LOCK_CONFIG, data, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
 
-        Ok(S3Response::new(PutObjectLockConfigurationOutput::default()))
+        Ok(with_metadata_etag(S3Response::new(PutObjectLockConfigurationOutput::default()), &res))
     }
 
     async fn get_bucket_replication(
@@ -4418,6 +4742,7 @@ impl S3 for FS {
         &self,
         req: S3Request<PutBucketReplicationInput>,
     ) -> S3Result<S3Response<PutBucketReplicationOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let PutBucketReplicationInput {
             bucket,
             replication_configuration,
@@ -4437,17 +4762,18 @@ impl S3 for FS {
         // TODO: check enable, versioning enable
         let data = try_!(serialize(&replication_configuration));
 
-        metadata_sys::update(&bucket, BUCKET_REPLICATION_CONFIG, data)
+        let res = metadata_sys::update_if_match(&bucket, BUCKET_REPLICATION_CONFIG, data, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
 
-        Ok(S3Response::new(PutBucketReplicationOutput::default()))
+        Ok(with_metadata_etag(S3Response::new(PutBucketReplicationOutput::default()), &res))
     }
 
     async fn delete_bucket_replication(
         &self,
         req: S3Request<DeleteBucketReplicationInput>,
     ) -> S3Result<S3Response<DeleteBucketReplicationOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let DeleteBucketReplicationInput { bucket, .. } = req.input;
 
         let Some(store) = new_object_layer_fn() else {
@@ -4458,14 +4784,14 @@ impl S3 for FS {
             .get_bucket_info(&bucket, &BucketOptions::default())
             .await
             .map_err(ApiError::from)?;
-        metadata_sys::delete(&bucket, BUCKET_REPLICATION_CONFIG)
+        let res = metadata_sys::delete_if_match(&bucket, BUCKET_REPLICATION_CONFIG, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
 
         // TODO: remove targets
         error!("delete bucket");
 
-        Ok(S3Response::new(DeleteBucketReplicationOutput::default()))
+        Ok(with_metadata_etag(S3Response::new(DeleteBucketReplicationOutput::default()), &res))
     }
 
     async fn get_bucket_notification_configuration(
@@ -4512,6 +4838,7 @@ impl S3 for FS {
         &self,
         req: S3Request<PutBucketNotificationConfigurationInput>,
     ) -> S3Result<S3Response<PutBucketNotificationConfigurationOutput>> {
+        let if_match = bucket_config_if_match(&req.headers);
         let PutBucketNotificationConfigurationInput {
             bucket,
             notification_configuration,
@@ -4530,7 +4857,7 @@ impl S3 for FS {
 
         //  Persist the new notification configuration
         let data = try_!(serialize(&notification_configuration));
-        metadata_sys::update(&bucket, BUCKET_NOTIFICATION_CONFIG, data)
+        let res = metadata_sys::update_if_match(&bucket, BUCKET_NOTIFICATION_CONFIG, data, if_match.as_deref())
             .await
             .map_err(ApiError::from)?;
 
@@ -4570,7 +4897,7 @@ impl S3 for FS {
             .await
             .map_err(|e| s3_error!(InternalError, "Failed to add rules: {e}"))?;
 
-        Ok(S3Response::new(PutBucketNotificationConfigurationOutput {}))
+        Ok(with_metadata_etag(S3Response::new(PutBucketNotificationConfigurationOutput {}), &res))
     }
 
     async fn get_bucket_acl(&self, req: S3Request<GetBucketAclInput>) -> S3Result<S3Response<GetBucketAclOutput>> {
@@ -4679,18 +5006,21 @@ impl S3 for FS {
         req: S3Request<GetObjectAttributesInput>,
     ) -> S3Result<S3Response<GetObjectAttributesOutput>> {
         let mut helper = OperationHelper::new(&req, EventName::ObjectAccessedAttributes, "s3:GetObjectAttributes");
-        let GetObjectAttributesInput { bucket, key, .. } = req.input.clone();
+        let GetObjectAttributesInput {
+            bucket, key, version_id, ..
+        } = req.input.clone();
 
         let Some(store) = new_object_layer_fn() else {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
         };
 
-        if let Err(e) = store
-            .get_object_reader(&bucket, &key, None, HeaderMap::new(), &ObjectOptions::default())
+        let opts: ObjectOptions = get_opts(&bucket, &key, version_id, None, &req.headers)
             .await
-        {
-            return Err(S3Error::with_message(S3ErrorCode::InternalError, format!("{e}")));
-        }
+            .map_err(ApiError::from)?;
+        let info = match store.get_object_info(&bucket, &key, &opts).await {
+            Ok(info) => info,
+            Err(e) => return Err(S3Error::with_message(S3ErrorCode::InternalError, format!("{e}"))),
+        };
 
         let output = GetObjectAttributesOutput {
             delete_marker: None,
@@ -4707,7 +5037,12 @@ impl S3 for FS {
             })
             .version_id(version_id);
 
-        let result = Ok(S3Response::new(output));
+        let mut resp = S3Response::new(output);
+        if let Ok(name) = http::HeaderName::from_bytes(rustfs_utils::http::headers::RUSTFS_ERASURE_BLOCK_SIZE.as_bytes()) {
+            resp.headers.insert(name, http::HeaderValue::from(info.block_size));
+        }
+
+        let result = Ok(resp);
         let _ = helper.complete(&result);
         result
     }
@@ -5128,6 +5463,106 @@ pub(crate) fn process_lambda_configurations<F>(
     }
 }
 
+/// Validate tag keys and values as S3 does for both object and bucket tagging.
+fn validate_tag_set(tag_set: &[Tag]) -> S3Result<()> {
+    let mut tag_keys = std::collections::HashSet::with_capacity(tag_set.len());
+    for tag in tag_set {
+        let key = tag
+            .key
+            .as_ref()
+            .filter(|k| !k.is_empty())
+            .ok_or_else(|| s3_error!(InvalidTag, "Tag key cannot be empty"))?;
+
+        if key.len() > 128 {
+            return Err(s3_error!(InvalidTag, "Tag key is too long, maximum allowed length is 128 characters"));
+        }
+
+        let value = tag
+            .value
+            .as_ref()
+            .ok_or_else(|| s3_error!(InvalidTag, "Tag value cannot be null"))?;
+
+        if value.is_empty() {
+            return Err(s3_error!(InvalidTag, "Tag value cannot be empty"));
+        }
+
+        if value.len() > 256 {
+            return Err(s3_error!(InvalidTag, "Tag value is too long, maximum allowed length is 256 characters"));
+        }
+
+        if !tag_keys.insert(key) {
+            return Err(s3_error!(InvalidTag, "Cannot provide multiple Tags with the same key"));
+        }
+    }
+
+    Ok(())
+}
+
+/// `If-Match` guarding a bucket configuration update against concurrent changes.
+fn bucket_config_if_match(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(http::header::IF_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Return the bucket metadata ETag so clients can chain conditional configuration updates.
+fn with_metadata_etag<T>(mut resp: S3Response<T>, res: &metadata_sys::BucketConfigUpdate) -> S3Response<T> {
+    if let Ok(etag) = http::HeaderValue::from_str(&res.etag) {
+        resp.headers.insert(http::header::ETAG, etag);
+    }
+    resp
+}
+
+/// Sort buckets by name and keep those matching `prefix` that come after `start_after`.
+fn bucket_list_window(
+    mut infos: Vec<rustfs_ecstore::store_api::BucketInfo>,
+    prefix: &str,
+    start_after: Option<&str>,
+) -> Vec<rustfs_ecstore::store_api::BucketInfo> {
+    infos.retain(|info| info.name.starts_with(prefix) && start_after.is_none_or(|after| info.name.as_str() > after));
+    infos.sort_by(|a, b| a.name.cmp(&b.name));
+    infos
+}
+
+/// A key, prefix or delimiter of a listing response, URL encoded when asked by `encoding-type=url`.
+/// Slashes are kept as they are, like S3 does.
+fn list_encode(value: &str, url_encode: bool) -> String {
+    if url_encode {
+        value.split('/').map(urlencoding::encode).collect::<Vec<_>>().join("/")
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Restore state of a transitioned object, `None` when it was never restored.
+fn object_restore_status(info: &ObjectInfo) -> Option<RestoreStatus> {
+    if info.restore_ongoing {
+        return Some(RestoreStatus {
+            is_restore_in_progress: Some(true),
+            restore_expiry_date: None,
+        });
+    }
+    info.restore_expires.map(|expiry| RestoreStatus {
+        is_restore_in_progress: Some(false),
+        restore_expiry_date: Some(Timestamp::from(expiry)),
+    })
+}
+
+/// ListBuckets continuation tokens carry the last bucket name of the previous page.
+fn encode_bucket_continuation_token(bucket: &str) -> String {
+    base64_simd::STANDARD.encode_to_string(bucket.as_bytes())
+}
+
+fn decode_bucket_continuation_token(token: &str) -> S3Result<String> {
+    base64_simd::STANDARD
+        .decode_to_vec(token.as_bytes())
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(|| s3_error!(InvalidArgument, "Invalid continuation token"))
+}
+
 pub(crate) async fn has_replication_rules(bucket: &str, objects: &[ObjectToDelete]) -> bool {
     let (cfg, _created) = match get_replication_config(bucket).await {
         Ok(replication_config) => replication_config,
@@ -5418,6 +5853,83 @@ mod tests {
         set_buffer_profile_enabled(false);
     }
 
+    #[test]
+    fn test_list_encode() {
+        assert_eq!(list_encode("quux ab/thud", true), "quux%20ab/thud");
+        assert_eq!(list_encode("bar+1/", true), "bar%2B1/");
+        assert_eq!(list_encode("/", true), "/");
+        assert_eq!(list_encode("quux ab/thud", false), "quux ab/thud");
+    }
+
+    #[test]
+    fn test_bucket_list_window() {
+        let infos = ["photos", "archive", "logs-b", "logs-a"]
+            .iter()
+            .map(|name| rustfs_ecstore::store_api::BucketInfo {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        let names = |infos: Vec<rustfs_ecstore::store_api::BucketInfo>| infos.into_iter().map(|v| v.name).collect::<Vec<_>>();
+
+        assert_eq!(
+            names(bucket_list_window(infos.clone(), "", None)),
+            vec!["archive", "logs-a", "logs-b", "photos"]
+        );
+        assert_eq!(names(bucket_list_window(infos.clone(), "logs-", None)), vec!["logs-a", "logs-b"]);
+        assert_eq!(names(bucket_list_window(infos.clone(), "", Some("logs-a"))), vec!["logs-b", "photos"]);
+        assert!(bucket_list_window(infos, "logs-", Some("logs-b")).is_empty());
+    }
+
+    #[test]
+    fn test_validate_tag_set() {
+        let tag = |k: &str, v: &str| Tag {
+            key: Some(k.to_string()),
+            value: Some(v.to_string()),
+        };
+
+        assert!(validate_tag_set(&[tag("team", "storage"), tag("project", "rustfs")]).is_ok());
+        assert!(validate_tag_set(&[tag("", "storage")]).is_err());
+        assert!(validate_tag_set(&[tag("team", "")]).is_err());
+        assert!(validate_tag_set(&[tag(&"k".repeat(129), "v")]).is_err());
+        assert!(validate_tag_set(&[tag("team", &"v".repeat(257))]).is_err());
+        assert!(validate_tag_set(&[tag("team", "a"), tag("team", "b")]).is_err());
+    }
+
+    #[test]
+    fn test_bucket_continuation_token_round_trip() {
+        let token = encode_bucket_continuation_token("my-bucket");
+        assert_eq!(decode_bucket_continuation_token(&token).unwrap(), "my-bucket");
+        assert!(decode_bucket_continuation_token("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_bucket_config_if_match() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bucket_config_if_match(&headers), None);
+
+        headers.insert(http::header::IF_MATCH, http::HeaderValue::from_static(" "));
+        assert_eq!(bucket_config_if_match(&headers), None);
+
+        headers.insert(http::header::IF_MATCH, http::HeaderValue::from_static("\"0000000000000002\""));
+        assert_eq!(bucket_config_if_match(&headers).as_deref(), Some("\"0000000000000002\""));
+    }
+
+    #[test]
+    fn test_content_md5_hex() {
+        assert_eq!(content_md5_hex(None).unwrap(), None);
+        assert_eq!(
+            content_md5_hex(Some("XrY7u+Ae7tCTyyK7j1rNww==")).unwrap().as_deref(),
+            Some("5eb63bbbe01eeed093cb22bb8f5acdc3")
+        );
+
+        for invalid in ["", "not base64!", "aGVsbG8="] {
+            let err = content_md5_hex(Some(invalid)).unwrap_err();
+            assert_eq!(*err.code(), S3ErrorCode::InvalidDigest, "{invalid}");
+        }
+    }
+
     // Note: S3Request structure is complex and requires many fields.
     // For real testing, we would need proper integration test setup.
     // Removing this test as it requires too much S3 infrastructure setup.
@@ -0,0 +1,365 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backfill of ObjectCreated notifications for objects already stored.
+//!
+//! A consumer subscribed to the events of a bucket only learns about objects written after it was
+//! added. A backfill job walks the current objects of the bucket, optionally below a prefix and
+//! within a range of modification times, and sends an `s3:ObjectCreated:Put` event for each of
+//! them through the notification pipeline, so the rules of the bucket route them to their targets
+//! like the events of new writes. The events carry the `x-rustfs-event-backfill` request parameter
+//! naming the job, so consumers can tell them apart. Jobs run in the background on the node that
+//! accepted the request and can be cancelled there; the report of a finished job is saved in the
+//! cluster config.
+
+use parking_lot::RwLock;
+use rustfs_common::globals::GLOBAL_Local_Node_Name;
+use rustfs_ecstore::config::com::{read_config, save_config};
+use rustfs_ecstore::error::{Error, Result, StorageError};
+use rustfs_ecstore::store::ECStore;
+use rustfs_ecstore::store_api::{BucketOptions, ObjectInfo, StorageAPI};
+use rustfs_notify::{EventArgs, EventArgsBuilder, notifier_global};
+use rustfs_targets::EventName;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, LazyLock};
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+const EVENT_BACKFILL_REPORT_PREFIX: &str = "config/event-backfill";
+
+/// Request parameter of the synthesized events, set to the id of the job.
+pub const EVENT_BACKFILL_PARAM: &str = "x-rustfs-event-backfill";
+
+const EVENT_BACKFILL_USER_AGENT: &str = "rustfs-event-backfill";
+
+/// Number of objects listed per batch.
+const BACKFILL_BATCH_SIZE: i32 = 1000;
+
+/// Most finished jobs kept in memory, older ones are only in the saved reports.
+const MAX_FINISHED_JOBS: usize = 100;
+
+pub static GLOBAL_EVENT_BACKFILL_SYS: LazyLock<EventBackfillSys> = LazyLock::new(EventBackfillSys::default);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EventBackfillRequest {
+    pub bucket: String,
+    pub prefix: String,
+    /// Only objects modified at or after this time, when set
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub since: Option<OffsetDateTime>,
+    /// Only objects modified before this time, when set
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub until: Option<OffsetDateTime>,
+}
+
+impl EventBackfillRequest {
+    /// Whether an event is sent for the object described by `info`.
+    fn selects(&self, info: &ObjectInfo) -> bool {
+        if info.delete_marker || info.is_dir {
+            return false;
+        }
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+        let Some(mod_time) = info.mod_time else {
+            return false;
+        };
+        self.since.is_none_or(|since| mod_time >= since) && self.until.is_none_or(|until| mod_time < until)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventBackfillState {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventBackfillJob {
+    pub id: String,
+    pub request: EventBackfillRequest,
+    /// The node running the job.
+    pub node: String,
+    pub state: EventBackfillState,
+    #[serde(with = "time::serde::rfc3339")]
+    pub started: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated: OffsetDateTime,
+    pub objects_scanned: u64,
+    pub events_sent: u64,
+    /// The last object an event was sent for.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub last_object: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+impl EventBackfillJob {
+    fn new(request: EventBackfillRequest, node: &str) -> Self {
+        let now = OffsetDateTime::now_utc();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            request,
+            node: node.to_string(),
+            state: EventBackfillState::Running,
+            started: now,
+            updated: now,
+            objects_scanned: 0,
+            events_sent: 0,
+            last_object: String::new(),
+            error: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct EventBackfillSys {
+    jobs: RwLock<BTreeMap<String, EventBackfillJob>>,
+    cancel_tokens: RwLock<HashMap<String, CancellationToken>>,
+}
+
+impl EventBackfillSys {
+    fn report_path(id: &str) -> String {
+        format!("{EVENT_BACKFILL_REPORT_PREFIX}/{id}.json")
+    }
+
+    /// Start a backfill job in the background. Events nobody subscribes to are dropped by the
+    /// notifier, so callers check the bucket has a subscriber first.
+    pub async fn start(&'static self, store: Arc<ECStore>, request: EventBackfillRequest) -> Result<EventBackfillJob> {
+        if request.bucket.is_empty() {
+            return Err(StorageError::other("a bucket is required"));
+        }
+        store.get_bucket_info(&request.bucket, &BucketOptions::default()).await?;
+
+        let node = GLOBAL_Local_Node_Name.read().await.clone();
+        let job = EventBackfillJob::new(request, &node);
+        let cancel = CancellationToken::new();
+        self.jobs.write().insert(job.id.clone(), job.clone());
+        self.cancel_tokens.write().insert(job.id.clone(), cancel.clone());
+
+        info!(
+            id = job.id,
+            bucket = job.request.bucket,
+            prefix = job.request.prefix,
+            "event backfill started"
+        );
+        let status = job.clone();
+        tokio::spawn(async move {
+            let id = job.id.clone();
+            let job = self.run(store.clone(), job, cancel).await;
+            if job.state == EventBackfillState::Failed {
+                error!(id, "event backfill failed: {}", job.error);
+            }
+            match serde_json::to_vec(&job) {
+                Ok(data) => {
+                    if let Err(err) = save_config(store, &Self::report_path(&id), data).await {
+                        warn!(id, "save event backfill report failed: {:?}", err);
+                    }
+                }
+                Err(err) => warn!(id, "marshal event backfill report failed: {:?}", err),
+            }
+            self.cancel_tokens.write().remove(&id);
+            self.update(job);
+            self.prune();
+        });
+
+        Ok(status)
+    }
+
+    /// Cancel the running job with `id`; returns false when no such job runs on this node.
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.cancel_tokens.read().get(id) {
+            Some(cancel) => {
+                cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The job with `id`, from memory or from its saved report.
+    pub async fn status(&self, store: Arc<ECStore>, id: &str) -> Result<Option<EventBackfillJob>> {
+        if let Some(job) = self.jobs.read().get(id) {
+            return Ok(Some(job.clone()));
+        }
+        if Uuid::parse_str(id).is_err() {
+            return Ok(None);
+        }
+
+        match read_config(store, &Self::report_path(id)).await {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data).map_err(Error::other)?)),
+            Err(Error::ConfigNotFound) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Jobs run or running on this node since it started.
+    pub fn list(&self) -> Vec<EventBackfillJob> {
+        self.jobs.read().values().cloned().collect()
+    }
+
+    fn update(&self, mut job: EventBackfillJob) {
+        job.updated = OffsetDateTime::now_utc();
+        self.jobs.write().insert(job.id.clone(), job);
+    }
+
+    fn prune(&self) {
+        let mut jobs = self.jobs.write();
+        let mut finished: Vec<(OffsetDateTime, String)> = jobs
+            .values()
+            .filter(|job| job.state != EventBackfillState::Running)
+            .map(|job| (job.updated, job.id.clone()))
+            .collect();
+        if finished.len() > MAX_FINISHED_JOBS {
+            finished.sort();
+            for (_, id) in finished.iter().take(finished.len() - MAX_FINISHED_JOBS) {
+                jobs.remove(id);
+            }
+        }
+    }
+
+    async fn run(&self, store: Arc<ECStore>, mut job: EventBackfillJob, cancel: CancellationToken) -> EventBackfillJob {
+        match self.backfill(store, &mut job, &cancel).await {
+            Ok(()) if cancel.is_cancelled() => job.state = EventBackfillState::Cancelled,
+            Ok(()) => job.state = EventBackfillState::Completed,
+            Err(err) => {
+                job.state = EventBackfillState::Failed;
+                job.error = err.to_string();
+            }
+        }
+        info!(
+            id = job.id,
+            scanned = job.objects_scanned,
+            sent = job.events_sent,
+            state = ?job.state,
+            "event backfill finished"
+        );
+        job
+    }
+
+    async fn backfill(&self, store: Arc<ECStore>, job: &mut EventBackfillJob, cancel: &CancellationToken) -> Result<()> {
+        let bucket = job.request.bucket.clone();
+        let mut continuation_token = None;
+
+        loop {
+            let listing = store
+                .clone()
+                .list_objects_v2(
+                    &bucket,
+                    &job.request.prefix,
+                    continuation_token.take(),
+                    None,
+                    BACKFILL_BATCH_SIZE,
+                    false,
+                    None,
+                    false,
+                    None,
+                )
+                .await?;
+
+            job.objects_scanned += listing.objects.len() as u64;
+            for info in listing.objects {
+                if cancel.is_cancelled() {
+                    self.update(job.clone());
+                    return Ok(());
+                }
+                if !job.request.selects(&info) {
+                    continue;
+                }
+
+                job.last_object = info.name.clone();
+                notifier_global::notify(backfill_event(&job.id, &job.node, info)).await;
+                job.events_sent += 1;
+            }
+            self.update(job.clone());
+
+            if !listing.is_truncated {
+                return Ok(());
+            }
+            continuation_token = listing.next_continuation_token;
+            if continuation_token.is_none() {
+                return Err(StorageError::other("object listing truncated without a continuation token"));
+            }
+        }
+    }
+}
+
+/// The ObjectCreated event of an existing object, sent by job `id`.
+fn backfill_event(id: &str, node: &str, info: ObjectInfo) -> EventArgs {
+    let bucket = info.bucket.clone();
+    let version_id = info.version_id.map(|v| v.to_string()).unwrap_or_default();
+    EventArgsBuilder::new(EventName::ObjectCreatedPut, bucket, info)
+        .version_id(version_id)
+        .host(node)
+        .user_agent(EVENT_BACKFILL_USER_AGENT)
+        .req_param(EVENT_BACKFILL_PARAM, id)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+
+    fn object(name: &str, mod_time: Option<OffsetDateTime>) -> ObjectInfo {
+        ObjectInfo {
+            bucket: "bucket".to_string(),
+            name: name.to_string(),
+            mod_time,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_request_selects() {
+        let now = OffsetDateTime::now_utc();
+        let all = EventBackfillRequest {
+            bucket: "bucket".to_string(),
+            ..Default::default()
+        };
+        assert!(all.selects(&object("a", None)));
+        assert!(!all.selects(&ObjectInfo {
+            delete_marker: true,
+            ..object("a", Some(now))
+        }));
+
+        let range = EventBackfillRequest {
+            since: Some(now - Duration::hours(2)),
+            until: Some(now),
+            ..all.clone()
+        };
+        assert!(range.selects(&object("a", Some(now - Duration::hours(1)))));
+        assert!(!range.selects(&object("a", Some(now - Duration::hours(3)))));
+        assert!(!range.selects(&object("a", Some(now))));
+        assert!(!range.selects(&object("a", None)));
+    }
+
+    #[test]
+    fn test_backfill_event() {
+        let args = backfill_event("job-1", "node1:9000", object("photos/a.jpg", None));
+        assert_eq!(args.event_name, EventName::ObjectCreatedPut);
+        assert_eq!(args.bucket_name, "bucket");
+        assert_eq!(args.object.name, "photos/a.jpg");
+        assert_eq!(args.req_params.get(EVENT_BACKFILL_PARAM).map(String::as_str), Some("job-1"));
+        assert_eq!(args.user_agent, EVENT_BACKFILL_USER_AGENT);
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::server::GLOBAL_REQUEST_LOG;
 use http::StatusCode;
 use rustfs_audit::{
     entity::{ApiDetails, ApiDetailsBuilder, AuditEntryBuilder},
@@ -24,6 +25,8 @@ use rustfs_utils::{
     extract_req_params, extract_req_params_header, extract_resp_elements, get_request_host, get_request_user_agent,
 };
 use s3s::{S3Request, S3Response, S3Result};
+use serde_json::Value;
+use std::collections::HashMap;
 use std::future::Future;
 use tokio::runtime::{Builder, Handle};
 
@@ -136,6 +139,14 @@ impl OperationHelper {
         self
     }
 
+    /// Set tags on the audit log entry, such as the reason a request was refused.
+    pub fn audit_tags(mut self, tags: HashMap<String, Value>) -> Self {
+        if let Some(builder) = self.audit_builder.take() {
+            self.audit_builder = Some(builder.tags(tags.into_iter().collect()));
+        }
+        self
+    }
+
     /// Complete operational details from S3 results.
     /// This method should be called immediately before the function returns.
     /// It consumes and prepares auxiliary structures for use during `drop`.
@@ -188,8 +199,10 @@ impl Drop for OperationHelper {
     fn drop(&mut self) {
         // Distribute audit logs
         if let Some(builder) = self.audit_builder.take() {
+            let entry = builder.build();
+            GLOBAL_REQUEST_LOG.attach_audit(&entry);
             spawn_background(async move {
-                AuditLogger::log(builder.build()).await;
+                AuditLogger::log(entry).await;
             });
         }
 
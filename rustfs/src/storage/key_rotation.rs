@@ -0,0 +1,531 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Re-wrapping of object data keys after a KMS key rotation.
+//!
+//! An object encrypted with a KMS-managed key keeps its data key, wrapped by a version of the KMS
+//! key, in its metadata. A key rotation job walks the object versions of a bucket, or of every
+//! bucket, optionally below a prefix, and wraps the data keys wrapped by a given KMS key again with
+//! the current version of that key or with another key. Only the wrapped data key and the recorded
+//! key and version change, in place: the object data is neither read nor re-encrypted. Jobs run in
+//! the background on the node that accepted the request and can be cancelled there; the report of
+//! a finished job is saved in the cluster config.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use parking_lot::RwLock;
+use rustfs_common::globals::GLOBAL_Local_Node_Name;
+use rustfs_ecstore::config::com::{read_config, save_config};
+use rustfs_ecstore::error::{Error, Result, StorageError};
+use rustfs_ecstore::store::ECStore;
+use rustfs_ecstore::store_api::{BucketOptions, ObjectInfo, ObjectOptions, StorageAPI};
+use rustfs_kms::{ENCRYPTION_KEY_VERSION_HEADER, ObjectEncryptionService, get_global_encryption_service};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, LazyLock};
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+const KEY_ROTATION_REPORT_PREFIX: &str = "config/key-rotation";
+
+/// Number of object versions listed per batch.
+const ROTATION_BATCH_SIZE: i32 = 1000;
+
+/// Most finished jobs kept in memory, older ones are only in the saved reports.
+const MAX_FINISHED_JOBS: usize = 100;
+
+const SSE_HEADER: &str = "x-amz-server-side-encryption";
+const SSE_KMS_KEY_ID_HEADER: &str = "x-amz-server-side-encryption-aws-kms-key-id";
+const SSE_C_ALGORITHM_HEADER: &str = "x-amz-server-side-encryption-customer-algorithm";
+const ENCRYPTED_DATA_KEY_HEADER: &str = "x-rustfs-encryption-key";
+
+pub static GLOBAL_KEY_ROTATION_SYS: LazyLock<KeyRotationSys> = LazyLock::new(KeyRotationSys::default);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct KeyRotationRequest {
+    /// Bucket to process, every bucket when empty
+    pub bucket: String,
+    pub prefix: String,
+    /// KMS key whose data keys are re-wrapped
+    pub key_id: String,
+    /// Only re-wrap the data keys wrapped by this version of the key, when set
+    pub source_version: Option<u32>,
+    /// Key to wrap the data keys with, `key_id` when empty
+    pub target_key_id: String,
+}
+
+impl KeyRotationRequest {
+    pub fn target_key_id(&self) -> &str {
+        if self.target_key_id.is_empty() {
+            &self.key_id
+        } else {
+            &self.target_key_id
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyRotationState {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRotationJob {
+    pub id: String,
+    pub request: KeyRotationRequest,
+    /// The node running the job.
+    pub node: String,
+    pub state: KeyRotationState,
+    #[serde(with = "time::serde::rfc3339")]
+    pub started: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated: OffsetDateTime,
+    /// The bucket being processed.
+    pub bucket: String,
+    pub versions_scanned: u64,
+    pub versions_rewrapped: u64,
+    /// Object versions overwritten while the job ran, left alone.
+    pub versions_skipped: u64,
+    pub versions_failed: u64,
+    /// Version of the target key the data keys were last wrapped with.
+    pub target_version: Option<u32>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+impl KeyRotationJob {
+    fn new(request: KeyRotationRequest, node: &str) -> Self {
+        let now = OffsetDateTime::now_utc();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            request,
+            node: node.to_string(),
+            state: KeyRotationState::Running,
+            started: now,
+            updated: now,
+            bucket: String::new(),
+            versions_scanned: 0,
+            versions_rewrapped: 0,
+            versions_skipped: 0,
+            versions_failed: 0,
+            target_version: None,
+            error: String::new(),
+        }
+    }
+
+    /// Whether the data key of an object version with `metadata` must be re-wrapped.
+    ///
+    /// Without a source version every data key of the key is, except those already wrapped by the
+    /// version the job wraps with.
+    fn needs_rewrap(&self, metadata: &HashMap<String, String>) -> bool {
+        let Some((key_id, version)) = wrapping_key(metadata) else {
+            return false;
+        };
+        if key_id != self.request.key_id {
+            return false;
+        }
+
+        match self.request.source_version {
+            Some(source_version) => version == source_version,
+            None => key_id != self.request.target_key_id() || self.target_version != Some(version),
+        }
+    }
+}
+
+/// The KMS key and key version wrapping the data key of an object version, None when the version is
+/// not encrypted with a KMS-managed data key.
+fn wrapping_key(metadata: &HashMap<String, String>) -> Option<(&str, u32)> {
+    if !metadata.contains_key(SSE_HEADER)
+        || !metadata.contains_key(ENCRYPTED_DATA_KEY_HEADER)
+        || metadata.contains_key(SSE_C_ALGORITHM_HEADER)
+    {
+        return None;
+    }
+
+    let key_id = metadata.get(SSE_KMS_KEY_ID_HEADER)?;
+    // Versions written before the key version was recorded were wrapped by version 1
+    let version = metadata
+        .get(ENCRYPTION_KEY_VERSION_HEADER)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1);
+    Some((key_id.as_str(), version))
+}
+
+enum RewrapOutcome {
+    Rewrapped,
+    /// The version changed since it was listed.
+    Skipped,
+}
+
+#[derive(Debug, Default)]
+pub struct KeyRotationSys {
+    jobs: RwLock<BTreeMap<String, KeyRotationJob>>,
+    cancel_tokens: RwLock<HashMap<String, CancellationToken>>,
+}
+
+impl KeyRotationSys {
+    fn report_path(id: &str) -> String {
+        format!("{KEY_ROTATION_REPORT_PREFIX}/{id}.json")
+    }
+
+    /// Start a key rotation job in the background.
+    pub async fn start(&'static self, store: Arc<ECStore>, request: KeyRotationRequest) -> Result<KeyRotationJob> {
+        if request.key_id.is_empty() {
+            return Err(StorageError::other("a KMS key is required"));
+        }
+        if !request.bucket.is_empty() {
+            store.get_bucket_info(&request.bucket, &BucketOptions::default()).await?;
+        }
+        let Some(service) = get_global_encryption_service().await else {
+            return Err(StorageError::other("KMS encryption service is not initialized"));
+        };
+
+        let node = GLOBAL_Local_Node_Name.read().await.clone();
+        let job = KeyRotationJob::new(request, &node);
+        let cancel = CancellationToken::new();
+        self.jobs.write().insert(job.id.clone(), job.clone());
+        self.cancel_tokens.write().insert(job.id.clone(), cancel.clone());
+
+        info!(
+            id = job.id,
+            key_id = job.request.key_id,
+            bucket = job.request.bucket,
+            "key rotation started"
+        );
+        let status = job.clone();
+        tokio::spawn(async move {
+            let id = job.id.clone();
+            let job = self.run(store.clone(), service, job, cancel).await;
+            if job.state == KeyRotationState::Failed {
+                error!(id, "key rotation failed: {}", job.error);
+            }
+            match serde_json::to_vec(&job) {
+                Ok(data) => {
+                    if let Err(err) = save_config(store, &Self::report_path(&id), data).await {
+                        warn!(id, "save key rotation report failed: {:?}", err);
+                    }
+                }
+                Err(err) => warn!(id, "marshal key rotation report failed: {:?}", err),
+            }
+            self.cancel_tokens.write().remove(&id);
+            self.update(job);
+            self.prune();
+        });
+
+        Ok(status)
+    }
+
+    /// Cancel the running job with `id`; returns false when no such job runs on this node.
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.cancel_tokens.read().get(id) {
+            Some(cancel) => {
+                cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The job with `id`, from memory or from its saved report.
+    pub async fn status(&self, store: Arc<ECStore>, id: &str) -> Result<Option<KeyRotationJob>> {
+        if let Some(job) = self.jobs.read().get(id) {
+            return Ok(Some(job.clone()));
+        }
+        if Uuid::parse_str(id).is_err() {
+            return Ok(None);
+        }
+
+        match read_config(store, &Self::report_path(id)).await {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data).map_err(Error::other)?)),
+            Err(Error::ConfigNotFound) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Jobs run or running on this node since it started.
+    pub fn list(&self) -> Vec<KeyRotationJob> {
+        self.jobs.read().values().cloned().collect()
+    }
+
+    fn update(&self, mut job: KeyRotationJob) {
+        job.updated = OffsetDateTime::now_utc();
+        self.jobs.write().insert(job.id.clone(), job);
+    }
+
+    fn prune(&self) {
+        let mut jobs = self.jobs.write();
+        let mut finished: Vec<(OffsetDateTime, String)> = jobs
+            .values()
+            .filter(|job| job.state != KeyRotationState::Running)
+            .map(|job| (job.updated, job.id.clone()))
+            .collect();
+        if finished.len() > MAX_FINISHED_JOBS {
+            finished.sort();
+            for (_, id) in finished.iter().take(finished.len() - MAX_FINISHED_JOBS) {
+                jobs.remove(id);
+            }
+        }
+    }
+
+    async fn run(
+        &self,
+        store: Arc<ECStore>,
+        service: Arc<ObjectEncryptionService>,
+        mut job: KeyRotationJob,
+        cancel: CancellationToken,
+    ) -> KeyRotationJob {
+        match self.rotate(store, &service, &mut job, &cancel).await {
+            Ok(()) if cancel.is_cancelled() => job.state = KeyRotationState::Cancelled,
+            Ok(()) => job.state = KeyRotationState::Completed,
+            Err(err) => {
+                job.state = KeyRotationState::Failed;
+                job.error = err.to_string();
+            }
+        }
+        info!(
+            id = job.id,
+            scanned = job.versions_scanned,
+            rewrapped = job.versions_rewrapped,
+            failed = job.versions_failed,
+            state = ?job.state,
+            "key rotation finished"
+        );
+        job
+    }
+
+    async fn rotate(
+        &self,
+        store: Arc<ECStore>,
+        service: &ObjectEncryptionService,
+        job: &mut KeyRotationJob,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        let buckets = if job.request.bucket.is_empty() {
+            store
+                .list_bucket(&BucketOptions::default())
+                .await?
+                .into_iter()
+                .map(|bucket| bucket.name)
+                .collect()
+        } else {
+            vec![job.request.bucket.clone()]
+        };
+
+        for bucket in buckets {
+            if cancel.is_cancelled() {
+                return Ok(());
+            }
+            job.bucket = bucket.clone();
+            self.rotate_bucket(&store, service, job, &bucket, cancel).await?;
+        }
+        Ok(())
+    }
+
+    async fn rotate_bucket(
+        &self,
+        store: &Arc<ECStore>,
+        service: &ObjectEncryptionService,
+        job: &mut KeyRotationJob,
+        bucket: &str,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        let mut marker = None;
+        let mut version_marker = None;
+
+        loop {
+            let listing = store
+                .clone()
+                .list_object_versions(
+                    bucket,
+                    &job.request.prefix,
+                    marker.take(),
+                    version_marker.take(),
+                    None,
+                    ROTATION_BATCH_SIZE,
+                )
+                .await?;
+
+            job.versions_scanned += listing.objects.len() as u64;
+            for info in listing.objects.iter() {
+                if cancel.is_cancelled() {
+                    self.update(job.clone());
+                    return Ok(());
+                }
+                if info.delete_marker || !job.needs_rewrap(&info.user_defined) {
+                    continue;
+                }
+
+                match rewrap_object(store, service, job.request.target_key_id(), bucket, info).await {
+                    Ok((RewrapOutcome::Rewrapped, version)) => {
+                        job.versions_rewrapped += 1;
+                        job.target_version = Some(version);
+                    }
+                    Ok((RewrapOutcome::Skipped, _)) => job.versions_skipped += 1,
+                    Err(err) => {
+                        job.versions_failed += 1;
+                        warn!(bucket, object = info.name, "re-wrap data key failed: {:?}", err);
+                    }
+                }
+            }
+            self.update(job.clone());
+
+            if !listing.is_truncated || listing.objects.is_empty() {
+                return Ok(());
+            }
+            marker = listing.next_marker;
+            version_marker = listing.next_version_idmarker;
+            if marker.is_none() {
+                return Err(StorageError::other("version listing truncated without a marker"));
+            }
+        }
+    }
+}
+
+/// Wrap the data key of an object version with the current version of `target_key_id`, returns the
+/// key version it was wrapped with.
+async fn rewrap_object(
+    store: &Arc<ECStore>,
+    service: &ObjectEncryptionService,
+    target_key_id: &str,
+    bucket: &str,
+    info: &ObjectInfo,
+) -> Result<(RewrapOutcome, u32)> {
+    let Some(wrapped) = info.user_defined.get(ENCRYPTED_DATA_KEY_HEADER) else {
+        return Ok((RewrapOutcome::Skipped, 0));
+    };
+    let encrypted_key = BASE64
+        .decode(wrapped)
+        .map_err(|e| StorageError::other(format!("invalid encrypted data key: {e}")))?;
+
+    let (rewrapped, version) = service
+        .rewrap_data_key(&encrypted_key, target_key_id)
+        .await
+        .map_err(|e| StorageError::other(format!("re-wrap data key failed: {e}")))?;
+
+    // Null versions carry no version id, the latest one is updated
+    let version_id = info.version_id.filter(|v| !v.is_nil()).map(|v| v.to_string());
+
+    // The version may have been overwritten meanwhile, its new data key must be left alone
+    let current = store
+        .get_object_info(
+            bucket,
+            &info.name,
+            &ObjectOptions {
+                version_id: version_id.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+    if current.user_defined.get(ENCRYPTED_DATA_KEY_HEADER) != Some(wrapped) {
+        return Ok((RewrapOutcome::Skipped, version));
+    }
+
+    let metadata = HashMap::from([
+        (ENCRYPTED_DATA_KEY_HEADER.to_string(), BASE64.encode(rewrapped)),
+        (ENCRYPTION_KEY_VERSION_HEADER.to_string(), version.to_string()),
+        (SSE_KMS_KEY_ID_HEADER.to_string(), target_key_id.to_string()),
+    ]);
+    let opts = ObjectOptions {
+        version_id,
+        mod_time: info.mod_time,
+        eval_metadata: Some(metadata),
+        ..Default::default()
+    };
+    store.put_object_metadata(bucket, &info.name, &opts).await?;
+
+    Ok((RewrapOutcome::Rewrapped, version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encrypted(key_id: &str, version: Option<u32>) -> HashMap<String, String> {
+        let mut metadata = HashMap::from([
+            (SSE_HEADER.to_string(), "aws:kms".to_string()),
+            (SSE_KMS_KEY_ID_HEADER.to_string(), key_id.to_string()),
+            (ENCRYPTED_DATA_KEY_HEADER.to_string(), "d3JhcHBlZA==".to_string()),
+        ]);
+        if let Some(version) = version {
+            metadata.insert(ENCRYPTION_KEY_VERSION_HEADER.to_string(), version.to_string());
+        }
+        metadata
+    }
+
+    fn job(key_id: &str, source_version: Option<u32>, target_key_id: &str) -> KeyRotationJob {
+        KeyRotationJob::new(
+            KeyRotationRequest {
+                key_id: key_id.to_string(),
+                source_version,
+                target_key_id: target_key_id.to_string(),
+                ..Default::default()
+            },
+            "node1:9000",
+        )
+    }
+
+    #[test]
+    fn test_wrapping_key() {
+        assert_eq!(wrapping_key(&encrypted("key-a", None)), Some(("key-a", 1)));
+        assert_eq!(wrapping_key(&encrypted("key-a", Some(3))), Some(("key-a", 3)));
+        assert_eq!(wrapping_key(&HashMap::new()), None);
+
+        let mut sse_c = encrypted("key-a", None);
+        sse_c.insert(SSE_C_ALGORITHM_HEADER.to_string(), "AES256".to_string());
+        assert_eq!(wrapping_key(&sse_c), None);
+    }
+
+    #[test]
+    fn test_needs_rewrap() {
+        let by_version = job("key-a", Some(1), "");
+        assert!(by_version.needs_rewrap(&encrypted("key-a", None)));
+        assert!(!by_version.needs_rewrap(&encrypted("key-a", Some(2))));
+        assert!(!by_version.needs_rewrap(&encrypted("key-b", Some(1))));
+
+        let mut any_version = job("key-a", None, "");
+        assert!(any_version.needs_rewrap(&encrypted("key-a", Some(2))));
+        any_version.target_version = Some(2);
+        assert!(!any_version.needs_rewrap(&encrypted("key-a", Some(2))));
+        assert!(any_version.needs_rewrap(&encrypted("key-a", Some(1))));
+
+        let mut to_other_key = job("key-a", None, "key-b");
+        to_other_key.target_version = Some(2);
+        assert!(to_other_key.needs_rewrap(&encrypted("key-a", Some(2))));
+        assert!(!to_other_key.needs_rewrap(&encrypted("key-b", Some(1))));
+    }
+
+    #[test]
+    fn test_job_round_trip() {
+        let mut job = job("key-a", Some(1), "key-b");
+        job.versions_rewrapped = 5;
+        let data = serde_json::to_vec(&job).unwrap();
+        let text = String::from_utf8(data.clone()).unwrap();
+        assert!(text.contains("\"state\":\"running\""));
+        assert!(text.contains("\"versionsRewrapped\":5"));
+        assert!(text.contains("\"targetKeyId\":\"key-b\""));
+        assert!(!text.contains("\"error\""));
+
+        assert_eq!(serde_json::from_slice::<KeyRotationJob>(&data).unwrap(), job);
+        assert_eq!(job.request.target_key_id(), "key-b");
+        assert_eq!(KeyRotationRequest::default().target_key_id(), "");
+    }
+}
@@ -13,8 +13,16 @@
 // limitations under the License.
 
 pub mod access;
+pub mod bucket_backup;
+pub(crate) mod conditional;
+pub(crate) mod cron;
 pub mod ecfs;
 pub(crate) mod entity;
+pub mod event_backfill;
 pub(crate) mod helper;
+pub mod key_rotation;
 pub mod options;
+pub(crate) mod payload;
+pub(crate) mod presign;
 pub mod tonic_service;
+pub mod transform;
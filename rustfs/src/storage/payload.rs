@@ -0,0 +1,273 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Payload length handling for object uploads.
+//!
+//! Uploads may arrive with `UNSIGNED-PAYLOAD` or as aws-chunked streams (including
+//! `STREAMING-UNSIGNED-PAYLOAD-TRAILER`), for example when a CDN in front of a presigned PUT
+//! strips the payload signature. Without a signed payload the declared lengths are the only
+//! guard against truncated or padded bodies, so they are validated up front and enforced
+//! while the body is read. Received bytes are also accounted per connection.
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use http::HeaderMap;
+use metrics::counter;
+use rustfs_utils::http::headers::AMZ_DECODED_CONTENT_LENGTH;
+use s3s::{S3Result, s3_error};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use tracing::debug;
+
+const AMZ_CONTENT_SHA256: &str = "x-amz-content-sha256";
+const STREAMING_PAYLOAD_PREFIX: &str = "STREAMING-";
+
+/// Bytes of request payload received over a single client connection.
+///
+/// One instance is created per accepted connection and attached to every request served on it.
+#[derive(Debug)]
+pub struct ConnectionStats {
+    peer: String,
+    payload_bytes: AtomicU64,
+    payloads: AtomicU64,
+}
+
+impl ConnectionStats {
+    pub fn new(peer: String) -> Self {
+        Self {
+            peer,
+            payload_bytes: AtomicU64::new(0),
+            payloads: AtomicU64::new(0),
+        }
+    }
+
+    /// Remote address of the connection
+    pub fn peer(&self) -> &str {
+        &self.peer
+    }
+
+    pub fn payload_bytes(&self) -> u64 {
+        self.payload_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn payloads(&self) -> u64 {
+        self.payloads.load(Ordering::Relaxed)
+    }
+
+    fn record(&self, bytes: u64) {
+        self.payload_bytes.fetch_add(bytes, Ordering::Relaxed);
+        self.payloads.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Drop for ConnectionStats {
+    fn drop(&mut self) {
+        let payloads = self.payloads();
+        if payloads > 0 {
+            debug!(
+                "connection {} closed after receiving {} bytes in {} payloads",
+                self.peer,
+                self.payload_bytes(),
+                payloads
+            );
+        }
+    }
+}
+
+/// Whether the body is aws-chunked encoded, signed or not.
+fn is_streaming_payload(headers: &HeaderMap) -> bool {
+    headers
+        .get(AMZ_CONTENT_SHA256)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with(STREAMING_PAYLOAD_PREFIX))
+}
+
+/// Resolve the decoded length of an upload body from `Content-Length` and
+/// `X-Amz-Decoded-Content-Length`.
+///
+/// Streaming payloads must declare the decoded length, which cannot exceed the length on the
+/// wire. For other payloads both headers must agree when both are present. `None` is returned
+/// when the request declares no length at all.
+pub(crate) fn resolve_payload_size(headers: &HeaderMap, content_length: Option<i64>) -> S3Result<Option<i64>> {
+    let decoded = match headers.get(AMZ_DECODED_CONTENT_LENGTH) {
+        Some(v) => match atoi::atoi::<i64>(v.as_bytes()) {
+            Some(x) if x >= 0 => Some(x),
+            _ => return Err(s3_error!(InvalidArgument, "invalid X-Amz-Decoded-Content-Length")),
+        },
+        None => None,
+    };
+
+    if content_length.is_some_and(|c| c < 0) {
+        return Err(s3_error!(InvalidArgument, "invalid Content-Length"));
+    }
+
+    if is_streaming_payload(headers) {
+        let Some(decoded) = decoded else {
+            return Err(s3_error!(
+                MissingContentLength,
+                "X-Amz-Decoded-Content-Length is required for streaming uploads"
+            ));
+        };
+        if content_length.is_some_and(|c| c < decoded) {
+            return Err(s3_error!(InvalidArgument, "X-Amz-Decoded-Content-Length exceeds Content-Length"));
+        }
+        return Ok(Some(decoded));
+    }
+
+    match (content_length, decoded) {
+        (Some(c), Some(d)) if c != d => {
+            Err(s3_error!(InvalidArgument, "Content-Length does not match X-Amz-Decoded-Content-Length"))
+        }
+        (Some(c), _) => Ok(Some(c)),
+        (None, d) => Ok(d),
+    }
+}
+
+/// Body stream that fails once it yields more or fewer bytes than declared.
+pub(crate) struct LengthEnforcedStream<S> {
+    inner: S,
+    expected: u64,
+    received: u64,
+    stats: Option<Arc<ConnectionStats>>,
+    done: bool,
+}
+
+impl<S> LengthEnforcedStream<S> {
+    pub(crate) fn new(inner: S, expected: i64, stats: Option<Arc<ConnectionStats>>) -> Self {
+        Self {
+            inner,
+            expected: expected.max(0) as u64,
+            received: 0,
+            stats,
+            done: false,
+        }
+    }
+
+    fn finish(&mut self) {
+        self.done = true;
+        counter!("rustfs_api_payload_received_bytes_total").increment(self.received);
+        if let Some(stats) = &self.stats {
+            stats.record(self.received);
+        }
+    }
+}
+
+impl<S> Stream for LengthEnforcedStream<S>
+where
+    S: Stream<Item = std::io::Result<Bytes>> + Unpin,
+{
+    type Item = std::io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        match self.inner.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(chunk))) => {
+                self.received += chunk.len() as u64;
+                if self.received > self.expected {
+                    let err = format!("request body exceeds the declared length of {} bytes", self.expected);
+                    self.finish();
+                    return Poll::Ready(Some(Err(std::io::Error::new(std::io::ErrorKind::InvalidData, err))));
+                }
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Poll::Ready(Some(Err(err))) => {
+                self.finish();
+                Poll::Ready(Some(Err(err)))
+            }
+            Poll::Ready(None) => {
+                let short = self.received < self.expected;
+                let err = format!("request body ended after {} of the declared {} bytes", self.received, self.expected);
+                self.finish();
+                if short {
+                    return Poll::Ready(Some(Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, err))));
+                }
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+    use s3s::S3ErrorCode;
+
+    fn headers(sha256: Option<&'static str>, decoded: Option<&'static str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(v) = sha256 {
+            headers.insert(AMZ_CONTENT_SHA256, HeaderValue::from_static(v));
+        }
+        if let Some(v) = decoded {
+            headers.insert(AMZ_DECODED_CONTENT_LENGTH, HeaderValue::from_static(v));
+        }
+        headers
+    }
+
+    #[test]
+    fn test_resolve_payload_size() {
+        let unsigned = headers(Some("UNSIGNED-PAYLOAD"), None);
+        assert_eq!(resolve_payload_size(&unsigned, Some(10)).unwrap(), Some(10));
+        assert_eq!(resolve_payload_size(&unsigned, None).unwrap(), None);
+        assert!(resolve_payload_size(&unsigned, Some(-1)).is_err());
+
+        let mismatch = headers(Some("UNSIGNED-PAYLOAD"), Some("5"));
+        let err = resolve_payload_size(&mismatch, Some(10)).unwrap_err();
+        assert_eq!(*err.code(), S3ErrorCode::InvalidArgument);
+
+        let trailer = headers(Some("STREAMING-UNSIGNED-PAYLOAD-TRAILER"), Some("10"));
+        assert_eq!(resolve_payload_size(&trailer, Some(64)).unwrap(), Some(10));
+        assert_eq!(resolve_payload_size(&trailer, None).unwrap(), Some(10));
+        assert!(resolve_payload_size(&trailer, Some(5)).is_err());
+
+        let missing = headers(Some("STREAMING-UNSIGNED-PAYLOAD-TRAILER"), None);
+        let err = resolve_payload_size(&missing, Some(64)).unwrap_err();
+        assert_eq!(*err.code(), S3ErrorCode::MissingContentLength);
+
+        assert!(resolve_payload_size(&headers(None, Some("abc")), None).is_err());
+    }
+
+    async fn drain(chunks: Vec<&'static [u8]>, expected: i64, stats: Arc<ConnectionStats>) -> std::io::Result<usize> {
+        let inner = futures::stream::iter(chunks.into_iter().map(|c| Ok(Bytes::from_static(c))));
+        let mut stream = LengthEnforcedStream::new(inner, expected, Some(stats));
+        let mut total = 0;
+        while let Some(chunk) = stream.next().await {
+            total += chunk?.len();
+        }
+        Ok(total)
+    }
+
+    #[tokio::test]
+    async fn test_length_enforced_stream() {
+        let stats = Arc::new(ConnectionStats::new("127.0.0.1:9000".to_string()));
+
+        assert_eq!(drain(vec![b"abc", b"de"], 5, stats.clone()).await.unwrap(), 5);
+        assert_eq!(stats.payload_bytes(), 5);
+
+        let err = drain(vec![b"abc", b"def"], 5, stats.clone()).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        let err = drain(vec![b"abc"], 5, stats.clone()).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+        assert_eq!(stats.payloads(), 3);
+        assert_eq!(stats.payload_bytes(), 14);
+    }
+}
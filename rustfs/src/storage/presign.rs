@@ -0,0 +1,132 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Enforcement of the cluster presign limits on presigned URLs and POST policy uploads.
+//!
+//! Signatures are verified before these checks run, the limits only narrow what a valid signature
+//! may do. The validity of a V4 URL is the `X-Amz-Expires` it was signed with, a V2 URL only
+//! carries its expiry time so the time left is checked instead.
+
+use http::Uri;
+use rustfs_ecstore::presign::PresignLimits;
+use s3s::{S3Result, s3_error};
+use std::collections::HashMap;
+use time::OffsetDateTime;
+
+fn query_params(uri: &Uri) -> HashMap<String, String> {
+    uri.query()
+        .map(|query| serde_urlencoded::from_str(query).unwrap_or_default())
+        .unwrap_or_default()
+}
+
+/// Seconds the presigned URL `uri` is valid for, None when it is not a presigned URL.
+fn presigned_validity(uri: &Uri, now: OffsetDateTime) -> Option<u64> {
+    let params = query_params(uri);
+    if params.contains_key("X-Amz-Signature") {
+        return Some(params.get("X-Amz-Expires").and_then(|v| v.parse().ok()).unwrap_or(0));
+    }
+    if params.contains_key("AWSAccessKeyId") && params.contains_key("Signature") {
+        let expires: i64 = params.get("Expires").and_then(|v| v.parse().ok()).unwrap_or(0);
+        return Some(expires.saturating_sub(now.unix_timestamp()).max(0) as u64);
+    }
+    None
+}
+
+pub(crate) fn is_presigned_url(uri: &Uri) -> bool {
+    presigned_validity(uri, OffsetDateTime::now_utc()).is_some()
+}
+
+/// Reject presigned URLs valid for longer than the limits allow.
+pub(crate) fn check_presigned_url(uri: &Uri, limits: &PresignLimits) -> S3Result<()> {
+    let max = limits.effective_max_expiry_secs();
+    match presigned_validity(uri, OffsetDateTime::now_utc()) {
+        Some(validity) if validity > max => Err(s3_error!(
+            AccessDenied,
+            "presigned URL is valid for {validity} seconds, at most {max} are allowed"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Reject presigned or POST policy uploads larger than allowed or without server side encryption
+/// when it is required.
+pub(crate) fn check_presigned_upload(limits: &PresignLimits, content_length: Option<i64>, sse_requested: bool) -> S3Result<()> {
+    if limits.max_content_length > 0
+        && let Some(length) = content_length
+        && length.max(0) as u64 > limits.max_content_length
+    {
+        return Err(s3_error!(
+            EntityTooLarge,
+            "presigned uploads are limited to {} bytes",
+            limits.max_content_length
+        ));
+    }
+
+    if limits.require_sse && !sse_requested {
+        return Err(s3_error!(AccessDenied, "presigned uploads must request server side encryption"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s3s::S3ErrorCode;
+
+    #[test]
+    fn test_presigned_validity() {
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+        let v4: Uri = "/bucket/key?X-Amz-Algorithm=AWS4-HMAC-SHA256&X-Amz-Expires=7200&X-Amz-Signature=abc"
+            .parse()
+            .unwrap();
+        assert_eq!(presigned_validity(&v4, now), Some(7200));
+
+        let v2: Uri = "/bucket/key?AWSAccessKeyId=ak&Expires=1700000600&Signature=abc"
+            .parse()
+            .unwrap();
+        assert_eq!(presigned_validity(&v2, now), Some(600));
+
+        let plain: Uri = "/bucket/key?versionId=1".parse().unwrap();
+        assert_eq!(presigned_validity(&plain, now), None);
+
+        let limits = PresignLimits {
+            max_expiry_secs: 3600,
+            ..Default::default()
+        };
+        assert!(check_presigned_url(&v4, &limits).is_err());
+        assert!(check_presigned_url(&plain, &limits).is_ok());
+    }
+
+    #[test]
+    fn test_check_presigned_upload() {
+        let limits = PresignLimits {
+            max_expiry_secs: 0,
+            max_content_length: 1024,
+            require_sse: true,
+        };
+        assert!(check_presigned_upload(&limits, Some(1024), true).is_ok());
+        assert!(check_presigned_upload(&limits, None, true).is_ok());
+        assert_eq!(
+            *check_presigned_upload(&limits, Some(1025), true).unwrap_err().code(),
+            S3ErrorCode::EntityTooLarge
+        );
+        assert_eq!(
+            *check_presigned_upload(&limits, Some(10), false).unwrap_err().code(),
+            S3ErrorCode::AccessDenied
+        );
+        assert!(check_presigned_upload(&PresignLimits::default(), Some(i64::MAX), false).is_ok());
+    }
+}
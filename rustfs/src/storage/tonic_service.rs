@@ -24,6 +24,7 @@ use rustfs_ecstore::{
         DeleteOptions, DiskAPI, DiskInfoOptions, DiskStore, FileInfoVersions, ReadMultipleReq, ReadOptions, UpdateMetadataOpts,
         error::DiskError,
     },
+    list_cache::{GLOBAL_LIST_CACHE, ListCacheFetch, ListCacheUpdate},
     metrics_realtime::{CollectMetricsOpts, MetricType, collect_local_metrics},
     new_object_layer_fn,
     rpc::{LocalPeerS3Client, PeerS3Client},
@@ -1283,6 +1284,7 @@ impl Node for NodeService {
                         return Ok(Response::new(DeleteVersionsResponse {
                             success: false,
                             errors: Vec::new(),
+                            disk_errors: Vec::new(),
                             error: Some(DiskError::other(format!("decode FileInfoVersions failed: {err}")).into()),
                         }));
                     }
@@ -1294,30 +1296,37 @@ impl Node for NodeService {
                     return Ok(Response::new(DeleteVersionsResponse {
                         success: false,
                         errors: Vec::new(),
+                        disk_errors: Vec::new(),
                         error: Some(DiskError::other(format!("decode DeleteOptions failed: {err}")).into()),
                     }));
                 }
             };
 
-            let errors = disk
-                .delete_versions(&request.volume, versions, opts)
-                .await
-                .into_iter()
+            let results = disk.delete_versions(&request.volume, versions, opts).await;
+            let errors = results
+                .iter()
                 .map(|error| match error {
                     Some(e) => e.to_string(),
                     None => "".to_string(),
                 })
                 .collect();
+            // Code 0 stands for no error
+            let disk_errors = results
+                .into_iter()
+                .map(|error| error.map(Into::into).unwrap_or_default())
+                .collect();
 
             Ok(Response::new(DeleteVersionsResponse {
                 success: true,
                 errors,
                 error: None,
+                disk_errors,
             }))
         } else {
             Ok(Response::new(DeleteVersionsResponse {
                 success: false,
                 errors: Vec::new(),
+                disk_errors: Vec::new(),
                 error: Some(DiskError::other("can not find disk".to_string()).into()),
             }))
         }
@@ -2188,18 +2197,55 @@ impl Node for NodeService {
         todo!()
     }
 
+    type GetMetacacheListingStream = ResponseStream<GetMetacacheListingResponse>;
     async fn get_metacache_listing(
         &self,
-        _request: Request<GetMetacacheListingRequest>,
-    ) -> Result<Response<GetMetacacheListingResponse>, Status> {
-        todo!()
+        request: Request<GetMetacacheListingRequest>,
+    ) -> Result<Response<Self::GetMetacacheListingStream>, Status> {
+        let request = request.into_inner();
+        let chunks = ListCacheFetch::unmarshal(&request.opts).and_then(|fetch| GLOBAL_LIST_CACHE.serve(&fetch));
+
+        let responses: Vec<Result<GetMetacacheListingResponse, Status>> = match chunks {
+            Ok(chunks) => chunks
+                .into_iter()
+                .map(|metacache| {
+                    Ok(GetMetacacheListingResponse {
+                        success: true,
+                        metacache,
+                        error_info: None,
+                    })
+                })
+                .collect(),
+            Err(err) => vec![Ok(GetMetacacheListingResponse {
+                success: false,
+                metacache: Bytes::new(),
+                error_info: Some(err.to_string()),
+            })],
+        };
+
+        Ok(Response::new(Box::pin(tokio_stream::iter(responses))))
     }
 
     async fn update_metacache_listing(
         &self,
-        _request: Request<UpdateMetacacheListingRequest>,
+        request: Request<UpdateMetacacheListingRequest>,
     ) -> Result<Response<UpdateMetacacheListingResponse>, Status> {
-        todo!()
+        let request = request.into_inner();
+        match ListCacheUpdate::unmarshal(&request.metacache) {
+            Ok(update) => {
+                GLOBAL_LIST_CACHE.apply(&update);
+                Ok(Response::new(UpdateMetacacheListingResponse {
+                    success: true,
+                    metacache: Bytes::new(),
+                    error_info: None,
+                }))
+            }
+            Err(err) => Ok(Response::new(UpdateMetacacheListingResponse {
+                success: false,
+                metacache: Bytes::new(),
+                error_info: Some(err.to_string()),
+            })),
+        }
     }
 
     async fn reload_pool_meta(
@@ -0,0 +1,198 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! GetObject transformation pipeline.
+//!
+//! When a bucket routes a key through a transformer, the object body is streamed into it and the
+//! transformer's output is returned to the client instead. New transformer kinds plug in by
+//! implementing [`GetTransformer`] and extending [`build_transformer`].
+
+use crate::error::ApiError;
+use bytes::Bytes;
+use rustfs_ecstore::bucket::metadata_sys;
+use rustfs_ecstore::bucket::transform::{Transformer, TransformerKind};
+use rustfs_ecstore::error::StorageError;
+use s3s::dto::StreamingBlob;
+use s3s::{S3Error, S3ErrorCode, S3Result, s3_error};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::warn;
+
+pub const TRANSFORM_BUCKET_HEADER: &str = "x-rustfs-transform-bucket";
+pub const TRANSFORM_OBJECT_HEADER: &str = "x-rustfs-transform-object";
+pub const TRANSFORM_VERSION_ID_HEADER: &str = "x-rustfs-transform-version-id";
+
+/// Number of response chunks buffered between a transformer and the client.
+const TRANSFORM_CHANNEL_SIZE: usize = 8;
+
+static TRANSFORM_HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
+/// The object being transformed.
+#[derive(Debug, Clone, Default)]
+pub struct TransformRequest {
+    pub bucket: String,
+    pub object: String,
+    pub version_id: Option<String>,
+    pub content_type: Option<String>,
+}
+
+/// A transformed object body along with the headers the transformer decided on.
+pub struct TransformedObject {
+    pub body: StreamingBlob,
+    pub content_type: Option<String>,
+    pub content_length: Option<i64>,
+}
+
+#[async_trait::async_trait]
+pub trait GetTransformer: Send + Sync {
+    async fn transform(&self, req: &TransformRequest, body: StreamingBlob) -> S3Result<TransformedObject>;
+}
+
+/// Look up the transformer GetObject responses for `object` must go through, if any.
+pub async fn bucket_transformer(bucket: &str, object: &str) -> S3Result<Option<Box<dyn GetTransformer>>> {
+    let cfg = match metadata_sys::get_transform_config(bucket).await {
+        Ok((cfg, _)) => cfg,
+        Err(StorageError::ConfigNotFound) => return Ok(None),
+        Err(err) => return Err(ApiError::from(err).into()),
+    };
+
+    cfg.transformer_for(object).map(build_transformer).transpose()
+}
+
+pub fn build_transformer(transformer: &Transformer) -> S3Result<Box<dyn GetTransformer>> {
+    match &transformer.kind {
+        TransformerKind::Http { endpoint } => Ok(Box::new(HttpTransformer::new(endpoint.clone(), transformer.timeout()))),
+        TransformerKind::Wasm { .. } => Err(s3_error!(NotImplemented, "WASM transformers are not supported by this server")),
+    }
+}
+
+/// POSTs the object to an external endpoint and streams back the response body.
+///
+/// The timeout covers the whole exchange, including streaming the transformed body.
+pub struct HttpTransformer {
+    endpoint: String,
+    timeout: Duration,
+}
+
+impl HttpTransformer {
+    pub fn new(endpoint: String, timeout: Duration) -> Self {
+        Self { endpoint, timeout }
+    }
+}
+
+#[async_trait::async_trait]
+impl GetTransformer for HttpTransformer {
+    async fn transform(&self, req: &TransformRequest, body: StreamingBlob) -> S3Result<TransformedObject> {
+        let mut request = TRANSFORM_HTTP_CLIENT
+            .post(&self.endpoint)
+            .timeout(self.timeout)
+            .header(TRANSFORM_BUCKET_HEADER, &req.bucket)
+            .header(TRANSFORM_OBJECT_HEADER, &req.object)
+            .body(reqwest::Body::wrap_stream(body));
+        if let Some(version_id) = &req.version_id {
+            request = request.header(TRANSFORM_VERSION_ID_HEADER, version_id);
+        }
+        if let Some(content_type) = &req.content_type {
+            request = request.header(http::header::CONTENT_TYPE, content_type);
+        }
+
+        let mut resp = request.send().await.map_err(|e| {
+            warn!("transformer {} request failed: {}", self.endpoint, e);
+            if e.is_timeout() {
+                S3Error::with_message(S3ErrorCode::ServiceUnavailable, "Object transformation timed out".to_string())
+            } else {
+                S3Error::with_message(S3ErrorCode::InternalError, "Object transformation failed".to_string())
+            }
+        })?;
+
+        if !resp.status().is_success() {
+            warn!("transformer {} returned status {}", self.endpoint, resp.status());
+            return Err(S3Error::with_message(
+                S3ErrorCode::InternalError,
+                format!("Object transformation failed with status {}", resp.status().as_u16()),
+            ));
+        }
+
+        let content_type = resp
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+        let content_length = resp.content_length().map(|v| v as i64);
+
+        let (tx, rx) = mpsc::channel::<std::io::Result<Bytes>>(TRANSFORM_CHANNEL_SIZE);
+        let endpoint = self.endpoint.clone();
+        tokio::spawn(async move {
+            loop {
+                let item = match resp.chunk().await {
+                    Ok(Some(chunk)) => Ok(chunk),
+                    Ok(None) => break,
+                    Err(e) => {
+                        warn!("transformer {} body stream failed: {}", endpoint, e);
+                        Err(std::io::Error::other(e))
+                    }
+                };
+                let failed = item.is_err();
+                // The client went away
+                if tx.send(item).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(TransformedObject {
+            body: StreamingBlob::wrap(ReceiverStream::new(rx)),
+            content_type,
+            content_length,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustfs_ecstore::bucket::transform::DEFAULT_TRANSFORM_TIMEOUT;
+
+    #[test]
+    fn test_build_transformer() {
+        let http = Transformer {
+            name: "redact".to_string(),
+            kind: TransformerKind::Http {
+                endpoint: "http://127.0.0.1:1/redact".to_string(),
+            },
+            timeout_secs: None,
+        };
+        assert!(build_transformer(&http).is_ok());
+
+        let wasm = Transformer {
+            name: "csv".to_string(),
+            kind: TransformerKind::Wasm {
+                module: "csv.wasm".to_string(),
+            },
+            timeout_secs: None,
+        };
+        assert!(build_transformer(&wasm).is_err());
+        assert_eq!(wasm.timeout(), DEFAULT_TRANSFORM_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_http_transformer_unreachable_endpoint() {
+        let transformer = HttpTransformer::new("http://127.0.0.1:1/redact".to_string(), Duration::from_secs(1));
+        let body = StreamingBlob::wrap(futures::stream::once(async { Ok::<_, std::io::Error>(Bytes::from_static(b"secret")) }));
+        let err = transformer.transform(&TransformRequest::default(), body).await.err().unwrap();
+        assert!(matches!(err.code(), S3ErrorCode::InternalError | S3ErrorCode::ServiceUnavailable));
+    }
+}
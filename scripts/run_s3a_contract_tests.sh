@@ -0,0 +1,72 @@
+#!/bin/bash -e
+# Copyright 2024 RustFS Team
+#
+# Licensed under the Apache License, Version 2.0 (the "License");
+# you may not use this file except in compliance with the License.
+# You may obtain a copy of the License at
+#
+#     http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing, software
+# distributed under the License is distributed on an "AS IS" BASIS,
+# WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+# See the License for the specific language governing permissions and
+# limitations under the License.
+
+# Run the Hadoop S3A filesystem contract and magic committer tests against a local RustFS.
+#
+# Usage: run_s3a_contract_tests.sh <rustfs binary> <hadoop source checkout> [test pattern]
+#
+# Needs curl >= 7.75 (for --aws-sigv4), the aws CLI, maven and a JDK able to build Hadoop.
+
+BIN=$1
+HADOOP_SRC=$2
+TESTS=${3:-"ITestS3AContract*,ITestMagicCommitProtocol,ITestS3ACommitterMRJob"}
+
+if [ -z "$BIN" ] || [ -z "$HADOOP_SRC" ]; then
+    echo "usage: $0 <rustfs binary> <hadoop source checkout> [test pattern]"
+    exit 1
+fi
+
+ENDPOINT=http://127.0.0.1:9000
+BUCKET=s3a-contract
+VOLUME=$(mktemp -d /tmp/rustfs-s3a.XXXXXX)
+
+export AWS_ACCESS_KEY_ID=rustfsadmin
+export AWS_SECRET_ACCESS_KEY=rustfsadmin
+export AWS_REGION=us-east-1
+
+"$BIN" "$VOLUME" > /tmp/rustfs-s3a.log 2>&1 &
+RUSTFS_PID=$!
+trap 'kill $RUSTFS_PID; rm -rf "$VOLUME"' EXIT
+
+until curl -sf "$ENDPOINT/health" > /dev/null; do
+    sleep 1
+done
+
+# S3A lists pending uploads by prefix and runs with the delete marker policy by default.
+curl -sf -X POST --aws-sigv4 "aws:amz:$AWS_REGION:s3" --user "$AWS_ACCESS_KEY_ID:$AWS_SECRET_ACCESS_KEY" \
+    "$ENDPOINT/rustfs/admin/v3/compat?s3aMagicCommitter=true&directoryMarkers=delete"
+echo
+
+aws --endpoint-url "$ENDPOINT" s3 mb "s3://$BUCKET"
+
+cat > "$HADOOP_SRC/hadoop-tools/hadoop-aws/src/test/resources/auth-keys.xml" <<EOF
+<configuration>
+  <property><name>test.fs.s3a.name</name><value>s3a://$BUCKET/</value></property>
+  <property><name>fs.contract.test.fs.s3a</name><value>s3a://$BUCKET/</value></property>
+  <property><name>fs.s3a.endpoint</name><value>$ENDPOINT</value></property>
+  <property><name>fs.s3a.endpoint.region</name><value>$AWS_REGION</value></property>
+  <property><name>fs.s3a.path.style.access</name><value>true</value></property>
+  <property><name>fs.s3a.connection.ssl.enabled</name><value>false</value></property>
+  <property><name>fs.s3a.access.key</name><value>$AWS_ACCESS_KEY_ID</value></property>
+  <property><name>fs.s3a.secret.key</name><value>$AWS_SECRET_ACCESS_KEY</value></property>
+  <property><name>fs.s3a.committer.magic.enabled</name><value>true</value></property>
+  <property><name>fs.s3a.directory.marker.retention</name><value>delete</value></property>
+  <property><name>test.fs.s3a.encryption.enabled</name><value>false</value></property>
+  <property><name>test.fs.s3a.sts.enabled</name><value>false</value></property>
+</configuration>
+EOF
+
+cd "$HADOOP_SRC/hadoop-tools/hadoop-aws"
+mvn -q verify -Dtest=none -Dit.test="$TESTS" -Dparallel-tests -DtestsThreadCount=4